@@ -0,0 +1,499 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::jsr_url;
+use super::npm_registry_url;
+use crate::cache;
+use crate::util::path::get_atomic_file_path;
+
+const CREDENTIALS_FILE_NAME: &str = "credentials.json";
+
+/// The kind of registry a token was stored for, which decides how the token
+/// is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistryKind {
+  Jsr,
+  Npm,
+  Https,
+}
+
+impl RegistryKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      RegistryKind::Jsr => "jsr",
+      RegistryKind::Npm => "npm",
+      RegistryKind::Https => "https",
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredCredential {
+  pub kind: RegistryKind,
+  /// The token, when it is not stored in the system keychain.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub token: Option<String>,
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub keychain: bool,
+}
+
+/// The tokens stored with `deno auth login`, keyed by host (and port).
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialsFile {
+  #[serde(default)]
+  pub hosts: BTreeMap<String, StoredCredential>,
+}
+
+impl CredentialsFile {
+  /// The location of the credentials file, which lives next to the default
+  /// install root rather than in `DENO_DIR` so that `deno clean` keeps it.
+  pub fn default_path() -> Option<PathBuf> {
+    cache::home_dir().map(|home| home.join(".deno").join(CREDENTIALS_FILE_NAME))
+  }
+
+  pub fn read(path: &Path) -> Result<Self, AnyError> {
+    let text = match std::fs::read_to_string(path) {
+      Ok(text) => text,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+        return Ok(Self::default());
+      }
+      Err(err) => {
+        return Err(err).with_context(|| {
+          format!("Failed reading credentials file '{}'", path.display())
+        });
+      }
+    };
+    serde_json::from_str(&text).with_context(|| {
+      format!("Failed parsing credentials file '{}'", path.display())
+    })
+  }
+
+  /// Writes the file atomically. It is created readable only by the user,
+  /// so that the tokens are never readable by others.
+  pub fn write(&self, path: &Path) -> Result<(), AnyError> {
+    if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    let mut text = serde_json::to_string_pretty(self)?;
+    text.push('\n');
+    let temp_path = get_atomic_file_path(path);
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::OpenOptionsExt;
+      options.mode(0o600);
+    }
+    let result = options
+      .open(&temp_path)
+      .and_then(|mut file| file.write_all(text.as_bytes()))
+      .and_then(|_| std::fs::rename(&temp_path, path));
+    if result.is_err() {
+      let _ = std::fs::remove_file(&temp_path);
+    }
+    result.with_context(|| {
+      format!("Failed writing credentials file '{}'", path.display())
+    })
+  }
+}
+
+/// The tokens stored with `deno auth login`. The credentials file is read on
+/// the first lookup and the keychain is only queried for the hosts that are
+/// requested, because each query spawns a subprocess.
+#[derive(Debug, Default)]
+struct StoredCredentials {
+  file: CredentialsFile,
+  keychain_tokens: Mutex<HashMap<String, Option<String>>>,
+}
+
+impl StoredCredentials {
+  /// Reads the credentials file. Failures are only logged because a broken
+  /// credentials file shouldn't prevent running programs that don't need it.
+  fn read() -> Self {
+    let Some(path) = CredentialsFile::default_path() else {
+      return Self::default();
+    };
+    match CredentialsFile::read(&path) {
+      Ok(file) => Self {
+        file,
+        keychain_tokens: Default::default(),
+      },
+      Err(err) => {
+        log::warn!("{:#}", err);
+        Self::default()
+      }
+    }
+  }
+
+  fn token_for_url(
+    &self,
+    url: &Url,
+    kind: impl Fn(RegistryKind) -> bool,
+  ) -> Option<String> {
+    // never send a stored token in plain text
+    if url.scheme() != "https" {
+      return None;
+    }
+    let host = url_host(url)?;
+    let credential = self.file.hosts.get(&host)?;
+    if !kind(credential.kind) {
+      return None;
+    }
+    if !credential.keychain {
+      return credential.token.clone();
+    }
+    self
+      .keychain_tokens
+      .lock()
+      .entry(host)
+      .or_insert_with_key(|host| match keychain::get(host) {
+        Ok(token) => token,
+        Err(err) => {
+          log::debug!(
+            "Failed reading token for {} from keychain: {:#}",
+            host,
+            err
+          );
+          None
+        }
+      })
+      .clone()
+  }
+}
+
+/// Gets the token stored with `deno auth login` for the host of an https url,
+/// when it was stored for a matching kind of registry. Nothing is read until
+/// the first request that could be authorized with a stored token.
+pub fn stored_token_for_url(
+  url: &Url,
+  kind: impl Fn(RegistryKind) -> bool,
+) -> Option<String> {
+  static STORED_CREDENTIALS: once_cell::sync::OnceCell<StoredCredentials> =
+    once_cell::sync::OnceCell::new();
+  STORED_CREDENTIALS
+    .get_or_init(StoredCredentials::read)
+    .token_for_url(url, kind)
+}
+
+/// Resolves the registry argument of `deno auth` to the host the token is
+/// stored for. Accepts `jsr`, `npm`, `npm:<registry url>`, or the url or
+/// hostname of a module host.
+pub fn resolve_registry(
+  text: &str,
+) -> Result<(String, RegistryKind), AnyError> {
+  let (url, kind) = match text {
+    "jsr" => (jsr_url().clone(), RegistryKind::Jsr),
+    "npm" => (npm_registry_url().clone(), RegistryKind::Npm),
+    _ => {
+      if let Some(text) = text.strip_prefix("npm:") {
+        (parse_registry_url(text)?, RegistryKind::Npm)
+      } else {
+        (parse_registry_url(text)?, RegistryKind::Https)
+      }
+    }
+  };
+  Ok((
+    url_host(&url).with_context(|| format!("Invalid registry: {text}"))?,
+    kind,
+  ))
+}
+
+fn parse_registry_url(text: &str) -> Result<Url, AnyError> {
+  let url = if text.contains("://") {
+    Url::parse(text)
+  } else {
+    Url::parse(&format!("https://{text}"))
+  }
+  .with_context(|| format!("Invalid registry: {text}"))?;
+  if !matches!(url.scheme(), "http" | "https") {
+    bail!("Invalid registry: {text}. Only http and https urls are supported.");
+  }
+  Ok(url)
+}
+
+fn url_host(url: &Url) -> Option<String> {
+  let host = url.host_str()?.to_lowercase();
+  Some(match url.port() {
+    Some(port) => format!("{host}:{port}"),
+    None => host,
+  })
+}
+
+/// Stores tokens in the system keychain by shelling out to the platform's
+/// credential tool, `security` on macOS and `secret-tool` (libsecret) on
+/// Linux.
+pub mod keychain {
+  use std::io::Write;
+  use std::process::Command;
+  use std::process::Stdio;
+
+  use deno_core::anyhow::bail;
+  use deno_core::error::AnyError;
+
+  const SERVICE: &str = "deno";
+
+  /// Whether the platform's credential tool can be used.
+  pub fn is_available() -> bool {
+    let program = if cfg!(target_os = "macos") {
+      "security"
+    } else if cfg!(target_os = "linux") {
+      "secret-tool"
+    } else {
+      return false;
+    };
+    Command::new(program)
+      .arg("--help")
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      .status()
+      .is_ok()
+  }
+
+  /// Stores the token. It is passed on stdin so that it never shows up in
+  /// the arguments of a process, which can be read by other users.
+  pub fn set(host: &str, token: &str) -> Result<(), AnyError> {
+    if cfg!(target_os = "macos") {
+      // `security -i` reads the commands from stdin
+      let command = format!(
+        "add-generic-password -U -s {} -a {} -w {}\n",
+        quote(SERVICE),
+        quote(host),
+        quote(token)
+      );
+      run(Command::new("security").arg("-i"), Some(&command))?;
+    } else {
+      run(
+        Command::new("secret-tool").args([
+          "store",
+          "--label",
+          &format!("Deno ({host})"),
+          "service",
+          SERVICE,
+          "host",
+          host,
+        ]),
+        Some(token),
+      )?;
+    }
+    Ok(())
+  }
+
+  pub fn get(host: &str) -> Result<Option<String>, AnyError> {
+    let output = if cfg!(target_os = "macos") {
+      Command::new("security")
+        .args(["find-generic-password", "-s", SERVICE, "-a", host, "-w"])
+        .stderr(Stdio::null())
+        .output()?
+    } else if cfg!(target_os = "linux") {
+      Command::new("secret-tool")
+        .args(["lookup", "service", SERVICE, "host", host])
+        .stderr(Stdio::null())
+        .output()?
+    } else {
+      return Ok(None);
+    };
+    if !output.status.success() {
+      return Ok(None);
+    }
+    let token = String::from_utf8(output.stdout)?.trim_end().to_string();
+    Ok((!token.is_empty()).then_some(token))
+  }
+
+  pub fn delete(host: &str) -> Result<(), AnyError> {
+    if cfg!(target_os = "macos") {
+      run(
+        Command::new("security").args([
+          "delete-generic-password",
+          "-s",
+          SERVICE,
+          "-a",
+          host,
+        ]),
+        None,
+      )
+    } else {
+      run(
+        Command::new("secret-tool")
+          .args(["clear", "service", SERVICE, "host", host]),
+        None,
+      )
+    }
+  }
+
+  /// Quotes an argument of a command of `security -i`.
+  fn quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+  }
+
+  fn run(command: &mut Command, stdin: Option<&str>) -> Result<(), AnyError> {
+    let mut child = command
+      .stdin(Stdio::piped())
+      .stdout(Stdio::null())
+      .stderr(Stdio::piped())
+      .spawn()?;
+    if let Some(stdin) = stdin {
+      child.stdin.take().unwrap().write_all(stdin.as_bytes())?;
+    }
+    drop(child.stdin.take());
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+      bail!(
+        "Keychain command failed: {}",
+        String::from_utf8_lossy(&output.stderr).trim()
+      );
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use test_util::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn test_resolve_registry() {
+    assert_eq!(
+      resolve_registry("jsr").unwrap(),
+      ("jsr.io".to_string(), RegistryKind::Jsr)
+    );
+    assert_eq!(
+      resolve_registry("npm").unwrap(),
+      ("registry.npmjs.org".to_string(), RegistryKind::Npm)
+    );
+    assert_eq!(
+      resolve_registry("npm:https://npm.pkg.github.com/").unwrap(),
+      ("npm.pkg.github.com".to_string(), RegistryKind::Npm)
+    );
+    assert_eq!(
+      resolve_registry("Example.com:8080").unwrap(),
+      ("example.com:8080".to_string(), RegistryKind::Https)
+    );
+    assert_eq!(
+      resolve_registry("https://deno.land/x/").unwrap(),
+      ("deno.land".to_string(), RegistryKind::Https)
+    );
+    assert!(resolve_registry("ftp://example.com").is_err());
+  }
+
+  #[test]
+  fn test_credentials_file_roundtrip() {
+    let temp_dir = TempDir::new();
+    let path = temp_dir
+      .path()
+      .join("nested")
+      .join(CREDENTIALS_FILE_NAME)
+      .to_path_buf();
+    assert_eq!(
+      CredentialsFile::read(&path).unwrap(),
+      CredentialsFile::default()
+    );
+    let mut file = CredentialsFile::default();
+    file.hosts.insert(
+      "jsr.io".to_string(),
+      StoredCredential {
+        kind: RegistryKind::Jsr,
+        token: Some("abc".to_string()),
+        keychain: false,
+      },
+    );
+    file.hosts.insert(
+      "registry.npmjs.org".to_string(),
+      StoredCredential {
+        kind: RegistryKind::Npm,
+        token: None,
+        keychain: true,
+      },
+    );
+    file.write(&path).unwrap();
+    assert_eq!(
+      std::fs::read_to_string(&path).unwrap(),
+      r#"{
+  "hosts": {
+    "jsr.io": {
+      "kind": "jsr",
+      "token": "abc"
+    },
+    "registry.npmjs.org": {
+      "kind": "npm",
+      "keychain": true
+    }
+  }
+}
+"#
+    );
+    assert_eq!(CredentialsFile::read(&path).unwrap(), file);
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+      assert_eq!(mode & 0o777, 0o600);
+    }
+    // no temporary files are left behind
+    assert_eq!(
+      std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+      1
+    );
+  }
+
+  #[test]
+  fn test_stored_token_for_url() {
+    let mut file = CredentialsFile::default();
+    file.hosts.insert(
+      "registry.npmjs.org".to_string(),
+      StoredCredential {
+        kind: RegistryKind::Npm,
+        token: Some("npm-secret".to_string()),
+        keychain: false,
+      },
+    );
+    file.hosts.insert(
+      "localhost:5545".to_string(),
+      StoredCredential {
+        kind: RegistryKind::Https,
+        token: Some("https-secret".to_string()),
+        keychain: false,
+      },
+    );
+    let credentials = StoredCredentials {
+      file,
+      keychain_tokens: Default::default(),
+    };
+    let is_npm = |kind| kind == RegistryKind::Npm;
+    let is_not_npm = |kind| kind != RegistryKind::Npm;
+    let url = Url::parse("https://registry.npmjs.org/chalk").unwrap();
+    assert_eq!(
+      credentials.token_for_url(&url, is_npm).as_deref(),
+      Some("npm-secret")
+    );
+    // tokens for other kinds of registries aren't used
+    assert_eq!(credentials.token_for_url(&url, is_not_npm), None);
+    let url = Url::parse("https://localhost:5545/mod.ts").unwrap();
+    assert_eq!(
+      credentials.token_for_url(&url, is_not_npm).as_deref(),
+      Some("https-secret")
+    );
+    // the port is part of the host
+    let url = Url::parse("https://localhost/mod.ts").unwrap();
+    assert_eq!(credentials.token_for_url(&url, is_not_npm), None);
+    // tokens are never sent over plain http
+    let url = Url::parse("http://localhost:5545/mod.ts").unwrap();
+    assert_eq!(credentials.token_for_url(&url, is_not_npm), None);
+  }
+}
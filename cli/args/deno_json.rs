@@ -1,12 +1,29 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
 
+use deno_config::deno_json::ConfigFile;
 use deno_config::deno_json::TsConfigForEmit;
+use deno_config::workspace::SpecifiedImportMap;
+use deno_config::workspace::Workspace;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
 use deno_core::serde_json;
+use deno_core::url::Url;
 use deno_semver::jsr::JsrDepPackageReq;
 use deno_semver::jsr::JsrPackageReqReference;
 use deno_semver::npm::NpmPackageReqReference;
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+
+use super::FmtFileOptions;
+use super::LintSpellingOptions;
+use super::LintTodoOptions;
 
 #[cfg(test)] // happens to only be used by the tests at the moment
 pub struct DenoConfigFsAdapter<'a>(
@@ -64,15 +81,235 @@ impl<'a> deno_config::fs::DenoConfigFs for DenoConfigFsAdapter<'a> {
   }
 }
 
+/// The fields of a deno.json which are read by the CLI but are not known to
+/// `deno_config`. They are described by the config file schema like the
+/// others.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CliConfigFields {
+  pub fmt_file: Option<FmtFileOptions>,
+  pub lint_spelling: Option<LintSpellingOptions>,
+  pub lint_todo: Option<LintTodoOptions>,
+  pub vendor_npm: Option<bool>,
+  pub import_maps: Option<Vec<String>>,
+  pub redact_env: Option<Vec<String>>,
+  pub dev_imports: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// The fields of a config file keyed by its path, along with the
+/// modification time and length of the file they were parsed from.
+type CachedCliConfigFields =
+  (Option<std::time::SystemTime>, u64, Arc<CliConfigFields>);
+
+static CLI_CONFIG_FIELDS: Lazy<Mutex<HashMap<PathBuf, CachedCliConfigFields>>> =
+  Lazy::new(Default::default);
+
+impl CliConfigFields {
+  /// Reads the fields of a config file. `ConfigFile::json` only keeps the
+  /// fields `deno_config` knows, so the file is parsed once more, but only
+  /// once for every change of the file.
+  pub fn read(deno_json: &ConfigFile) -> Result<Arc<Self>, AnyError> {
+    let Ok(path) = deno_json.specifier.to_file_path() else {
+      return Ok(Default::default());
+    };
+    let metadata = std::fs::metadata(&path)
+      .with_context(|| format!("Failed reading '{}'", path.display()))?;
+    let modified = metadata.modified().ok();
+    let len = metadata.len();
+    let mut fields_by_path = CLI_CONFIG_FIELDS.lock();
+    if let Some((cached_modified, cached_len, fields)) =
+      fields_by_path.get(&path)
+    {
+      if *cached_modified == modified && *cached_len == len {
+        return Ok(fields.clone());
+      }
+    }
+    let text = std::fs::read_to_string(&path)
+      .with_context(|| format!("Failed reading '{}'", path.display()))?;
+    let value = jsonc_parser::parse_to_serde_value(&text, &Default::default())
+      .with_context(|| format!("Failed parsing '{}'", path.display()))?;
+    let fields = match value {
+      Some(serde_json::Value::Object(values)) => {
+        Arc::new(Self::from_values(values, &deno_json.specifier)?)
+      }
+      _ => Default::default(),
+    };
+    fields_by_path.insert(path, (modified, len, fields.clone()));
+    Ok(fields)
+  }
+
+  fn from_values(
+    mut values: serde_json::Map<String, serde_json::Value>,
+    specifier: &Url,
+  ) -> Result<Self, AnyError> {
+    Ok(Self {
+      fmt_file: parse_field(&mut values, "fmtFile", specifier)?,
+      lint_spelling: parse_field(&mut values, "lintSpelling", specifier)?,
+      lint_todo: parse_field(&mut values, "lintTodo", specifier)?,
+      vendor_npm: parse_field(&mut values, "vendorNpm", specifier)?,
+      import_maps: parse_field(&mut values, "importMaps", specifier)?,
+      redact_env: parse_field(&mut values, "redactEnv", specifier)?,
+      dev_imports: parse_field(&mut values, "devImports", specifier)?,
+    })
+  }
+}
+
+fn parse_field<T: DeserializeOwned>(
+  values: &mut serde_json::Map<String, serde_json::Value>,
+  key: &str,
+  specifier: &Url,
+) -> Result<Option<T>, AnyError> {
+  values
+    .remove(key)
+    .map(|value| {
+      serde_json::from_value(value).with_context(|| {
+        format!("Failed to parse \"{key}\" configuration in {specifier}")
+      })
+    })
+    .transpose()
+}
+
 pub fn deno_json_deps(
   config: &deno_config::deno_json::ConfigFile,
 ) -> HashSet<JsrDepPackageReq> {
+  // errors are surfaced when creating the resolver
+  let dev_imports = deno_json_dev_imports(config)
+    .ok()
+    .flatten()
+    .map(serde_json::Value::Object);
   let values = imports_values(config.json.imports.as_ref())
     .into_iter()
+    .chain(imports_values(dev_imports.as_ref()))
     .chain(scope_values(config.json.scopes.as_ref()));
   values_to_set(values)
 }
 
+/// The "devImports" of a deno.json, which are dependencies only needed
+/// during development that get merged into "imports" unless running with
+/// `--production`.
+pub fn deno_json_dev_imports(
+  deno_json: &deno_config::deno_json::ConfigFile,
+) -> Result<Option<serde_json::Map<String, serde_json::Value>>, AnyError> {
+  Ok(CliConfigFields::read(deno_json)?.dev_imports.clone())
+}
+
+/// An import map for the whole workspace with the "devImports" of every
+/// deno.json merged into its "imports", where regular imports take
+/// precedence. Like the resolver of the workspace does for their "imports",
+/// the members get a scope for their directory. `None` when no deno.json of
+/// the workspace has "devImports".
+pub fn workspace_dev_import_map(
+  workspace: &Workspace,
+) -> Result<Option<SpecifiedImportMap>, AnyError> {
+  let Some(root_deno_json) = workspace.root_deno_json() else {
+    return Ok(None);
+  };
+  let mut has_dev_imports = false;
+  let mut imports = serde_json::Map::new();
+  let mut scopes = serde_json::Map::new();
+  for (dir_url, folder) in workspace.config_folders() {
+    let Some(deno_json) = &folder.deno_json else {
+      continue;
+    };
+    let dev_imports = deno_json_dev_imports(deno_json)?;
+    has_dev_imports |= dev_imports.is_some();
+    let mut folder_imports = match &deno_json.json.imports {
+      Some(serde_json::Value::Object(imports)) => imports.clone(),
+      _ => Default::default(),
+    };
+    for (key, value) in dev_imports.into_iter().flatten() {
+      folder_imports.entry(key).or_insert(value);
+    }
+    let folder_scopes = match &deno_json.json.scopes {
+      Some(serde_json::Value::Object(scopes)) => scopes.clone(),
+      _ => Default::default(),
+    };
+    if deno_json.specifier == root_deno_json.specifier {
+      imports = folder_imports;
+      scopes.extend(folder_scopes);
+      continue;
+    }
+    // the import map is based at the root deno.json, so the relative
+    // addresses of the members are made absolute
+    if !folder_imports.is_empty() {
+      scopes.insert(
+        dir_url.to_string(),
+        serde_json::Value::Object(absolute_specifier_map(
+          folder_imports,
+          &deno_json.specifier,
+        )),
+      );
+    }
+    for (scope, value) in folder_scopes {
+      let serde_json::Value::Object(specifier_map) = value else {
+        continue;
+      };
+      scopes
+        .entry(absolute_address(&scope, &deno_json.specifier))
+        .or_insert_with(|| {
+          serde_json::Value::Object(absolute_specifier_map(
+            specifier_map,
+            &deno_json.specifier,
+          ))
+        });
+    }
+  }
+  if !has_dev_imports {
+    return Ok(None);
+  }
+  let mut value = serde_json::Map::new();
+  value.insert("imports".to_string(), serde_json::Value::Object(imports));
+  if !scopes.is_empty() {
+    value.insert("scopes".to_string(), serde_json::Value::Object(scopes));
+  }
+  Ok(Some(SpecifiedImportMap {
+    base_url: root_deno_json.specifier.clone(),
+    value: serde_json::Value::Object(value),
+  }))
+}
+
+/// The config files of the workspace which have "devImports".
+pub fn deno_jsons_with_dev_imports(workspace: &Workspace) -> Vec<Url> {
+  workspace
+    .config_folders()
+    .values()
+    .filter_map(|folder| folder.deno_json.as_ref())
+    .filter(|deno_json| {
+      deno_json_dev_imports(deno_json).is_ok_and(|d| d.is_some())
+    })
+    .map(|deno_json| deno_json.specifier.clone())
+    .collect()
+}
+
+fn absolute_address(address: &str, base: &Url) -> String {
+  if address.starts_with("./")
+    || address.starts_with("../")
+    || address.starts_with('/')
+  {
+    if let Ok(url) = base.join(address) {
+      return url.to_string();
+    }
+  }
+  address.to_string()
+}
+
+fn absolute_specifier_map(
+  specifier_map: serde_json::Map<String, serde_json::Value>,
+  base: &Url,
+) -> serde_json::Map<String, serde_json::Value> {
+  specifier_map
+    .into_iter()
+    .map(|(key, value)| {
+      let value = match value {
+        serde_json::Value::String(address) => {
+          serde_json::Value::String(absolute_address(&address, base))
+        }
+        value => value,
+      };
+      (absolute_address(&key, base), value)
+    })
+    .collect()
+}
+
 fn imports_values(value: Option<&serde_json::Value>) -> Vec<&String> {
   let Some(obj) = value.and_then(|v| v.as_object()) else {
     return Vec::new();
@@ -107,6 +344,147 @@ fn values_to_set<'a>(
   entries
 }
 
+/// Expands the `${VAR}` and `${VAR:-default}` references to environment
+/// variables in a value of a config file. The default is used when the
+/// variable is unset or empty.
+pub fn expand_config_env_vars(
+  text: &str,
+  get_var: impl Fn(&str) -> Option<String>,
+) -> Result<String, AnyError> {
+  let mut output = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find("${") {
+    output.push_str(&rest[..start]);
+    let reference = &rest[start + 2..];
+    let Some(end) = reference.find('}') else {
+      bail!("Unterminated environment variable reference in \"{text}\"");
+    };
+    output.push_str(&resolve_env_var_reference(
+      &reference[..end],
+      text,
+      &get_var,
+    )?);
+    rest = &reference[end + 1..];
+  }
+  output.push_str(rest);
+  Ok(output)
+}
+
+/// Replaces the `${VAR}` and `${VAR:-default}` references in the command of a
+/// task with references to generated environment variables holding their
+/// values, so the values are never parsed as shell syntax. Returns the command
+/// along with the environment variables to set for it.
+pub fn expand_task_env_vars(
+  script: &str,
+  get_var: impl Fn(&str) -> Option<String>,
+) -> Result<(String, Vec<(String, String)>), AnyError> {
+  let mut output = String::with_capacity(script.len());
+  let mut env_vars = Vec::new();
+  // the quote the text is in
+  let mut quote = None;
+  let mut chars = script.char_indices().peekable();
+  while let Some((index, c)) = chars.next() {
+    if script[index..].starts_with("${") {
+      let reference = &script[index + 2..];
+      let Some(end) = reference.find('}') else {
+        bail!("Unterminated environment variable reference in \"{script}\"");
+      };
+      let value =
+        resolve_env_var_reference(&reference[..end], script, &get_var)?;
+      let name = format!("DENO_CONFIG_ENV_{}", env_vars.len());
+      // close the quote the reference is in and reopen it afterwards, so the
+      // value is always a double quoted part of the word
+      match quote {
+        Some(quote) => output.push_str(&format!("{quote}\"${name}\"{quote}")),
+        None => output.push_str(&format!("\"${name}\"")),
+      }
+      env_vars.push((name, value));
+      while chars
+        .next_if(|(next, _)| *next <= index + 2 + end)
+        .is_some()
+      {}
+      continue;
+    }
+    output.push(c);
+    match (quote, c) {
+      (Some(open), c) if open == c => quote = None,
+      (None, '\'' | '"') => quote = Some(c),
+      // keep escaped characters, like quotes, as they are
+      (None | Some('"'), '\\') => {
+        if let Some((_, next)) = chars.next() {
+          output.push(next);
+        }
+      }
+      _ => {}
+    }
+  }
+  Ok((output, env_vars))
+}
+
+/// Resolves the value of the `VAR` or `VAR:-default` inside a `${...}`
+/// reference in the text. The default is used when the variable is unset or
+/// empty.
+fn resolve_env_var_reference(
+  reference: &str,
+  text: &str,
+  get_var: &impl Fn(&str) -> Option<String>,
+) -> Result<String, AnyError> {
+  let (name, default) = match reference.split_once(":-") {
+    Some((name, default)) => (name, Some(default)),
+    None => (reference, None),
+  };
+  let is_valid_name = name
+    .chars()
+    .next()
+    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+  if !is_valid_name {
+    bail!("Invalid environment variable name \"{name}\" in \"{text}\"");
+  }
+  match (get_var(name).filter(|value| !value.is_empty()), default) {
+    (Some(value), _) => Ok(value),
+    (None, Some(default)) => Ok(default.to_string()),
+    (None, None) => bail!(
+      "Environment variable \"{name}\" used in the config file is not set. Set it or provide a default with \"${{{name}:-default}}\""
+    ),
+  }
+}
+
+/// Expands the environment variables in the values of the `"imports"` and
+/// `"scopes"` of an import map. Returns whether any value changed.
+pub fn expand_import_map_env_vars(
+  import_map: &mut serde_json::Value,
+) -> Result<bool, AnyError> {
+  fn expand_imports(
+    imports: Option<&mut serde_json::Value>,
+  ) -> Result<bool, AnyError> {
+    let Some(serde_json::Value::Object(imports)) = imports else {
+      return Ok(false);
+    };
+    let mut changed = false;
+    for value in imports.values_mut() {
+      if let serde_json::Value::String(text) = value {
+        let expanded =
+          expand_config_env_vars(text, |name| std::env::var(name).ok())?;
+        if expanded != *text {
+          *text = expanded;
+          changed = true;
+        }
+      }
+    }
+    Ok(changed)
+  }
+
+  let mut changed = expand_imports(import_map.get_mut("imports"))?;
+  if let Some(serde_json::Value::Object(scopes)) = import_map.get_mut("scopes")
+  {
+    for imports in scopes.values_mut() {
+      changed |= expand_imports(Some(imports))?;
+    }
+  }
+  Ok(changed)
+}
+
 pub fn check_warn_tsconfig(ts_config: &TsConfigForEmit) {
   if let Some(ignored_options) = &ts_config.maybe_ignored_options {
     log::warn!("{}", ignored_options);
@@ -121,3 +499,93 @@ pub fn check_warn_tsconfig(ts_config: &TsConfigForEmit) {
     );
   }
 }
+
+#[cfg(test)]
+mod test {
+  use deno_core::serde_json::json;
+
+  use super::*;
+
+  fn expand(text: &str) -> Result<String, AnyError> {
+    expand_config_env_vars(text, |name| match name {
+      "REGISTRY" => Some("https://registry.example.com".to_string()),
+      "EMPTY" => Some(String::new()),
+      _ => None,
+    })
+  }
+
+  #[test]
+  fn expands_env_vars() {
+    assert_eq!(
+      expand("${REGISTRY}/std/").unwrap(),
+      "https://registry.example.com/std/"
+    );
+    assert_eq!(expand("${MISSING:-./vendor}/").unwrap(), "./vendor/");
+    assert_eq!(expand("${EMPTY:-dev}").unwrap(), "dev");
+    assert_eq!(expand("${MISSING:-}").unwrap(), "");
+    assert_eq!(
+      expand("deno run $HOME main.ts").unwrap(),
+      "deno run $HOME main.ts"
+    );
+    assert!(expand("${MISSING}")
+      .unwrap_err()
+      .to_string()
+      .contains("\"MISSING\" used in the config file is not set"));
+    assert!(expand("${REGISTRY").is_err());
+    assert!(expand("${1ABC}").is_err());
+  }
+
+  #[test]
+  fn expands_task_env_vars() {
+    let get_var = |name: &str| match name {
+      "NAME" => Some("Deno; rm -rf $(pwd)".to_string()),
+      _ => None,
+    };
+    let (script, env_vars) =
+      expand_task_env_vars("echo ${GREETING:-Hello} ${NAME}!", get_var)
+        .unwrap();
+    assert_eq!(
+      script,
+      "echo \"$DENO_CONFIG_ENV_0\" \"$DENO_CONFIG_ENV_1\"!"
+    );
+    assert_eq!(
+      env_vars,
+      vec![
+        ("DENO_CONFIG_ENV_0".to_string(), "Hello".to_string()),
+        (
+          "DENO_CONFIG_ENV_1".to_string(),
+          "Deno; rm -rf $(pwd)".to_string()
+        ),
+      ]
+    );
+
+    let (script, _) = expand_task_env_vars(
+      "echo \"a ${NAME}b\" 'c ${NAME}' \\\" ${NAME}",
+      get_var,
+    )
+    .unwrap();
+    assert_eq!(
+      script,
+      concat!(
+        "echo \"a \"\"$DENO_CONFIG_ENV_0\"\"b\" ",
+        "'c '\"$DENO_CONFIG_ENV_1\"'' \\\" \"$DENO_CONFIG_ENV_2\""
+      )
+    );
+
+    assert!(expand_task_env_vars("echo ${NAME", get_var).is_err());
+    assert!(expand_task_env_vars("echo ${MISSING}", get_var).is_err());
+  }
+
+  #[test]
+  fn expands_import_map_env_vars() {
+    let mut import_map = json!({
+      "imports": { "@std/": "${DENO_TEST_UNSET_VAR:-https://jsr.io/}@std/" },
+      "scopes": { "./legacy/": { "a": "./a.ts" } },
+    });
+    assert!(expand_import_map_env_vars(&mut import_map).unwrap());
+    assert_eq!(import_map["imports"]["@std/"], "https://jsr.io/@std/");
+
+    let mut import_map = json!({ "imports": { "a": "./a.ts" } });
+    assert!(!expand_import_map_env_vars(&mut import_map).unwrap());
+  }
+}
@@ -6,11 +6,13 @@ use std::env;
 use std::ffi::OsString;
 use std::net::SocketAddr;
 use std::num::NonZeroU32;
+use std::num::NonZeroU64;
 use std::num::NonZeroU8;
 use std::num::NonZeroUsize;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 use clap::builder::styling::AnsiColor;
 use clap::builder::FalseyValueParser;
@@ -84,29 +86,218 @@ impl FileFlags {
   }
 }
 
+/// The workspace folders whose configuration files are updated by
+/// `deno add` and `deno remove`.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum WorkspaceMemberTarget {
+  /// The configuration files of the current directory.
+  #[default]
+  Current,
+  /// The member with this package name or path relative to the workspace
+  /// root.
+  Member(String),
+  AllMembers,
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct AddFlags {
   pub packages: Vec<String>,
   pub dev: bool,
+  pub target: WorkspaceMemberTarget,
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct RemoveFlags {
   pub packages: Vec<String>,
+  pub target: WorkspaceMemberTarget,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CompatFlags {
+  pub paths: Vec<String>,
+  pub json: bool,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WhyFlags {
+  pub package: String,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum AuditSeverity {
+  Low,
+  Moderate,
+  High,
+  Critical,
+}
+
+impl AuditSeverity {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Low => "low",
+      Self::Moderate => "moderate",
+      Self::High => "high",
+      Self::Critical => "critical",
+    }
+  }
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuditFlags {
+  pub json: bool,
+  /// Fail on vulnerabilities of this severity or higher. Fails on any
+  /// vulnerability when not provided.
+  pub fail_on: Option<AuditSeverity>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConfigFlags {
+  /// Validate the config file, and the ones of the workspace members, against
+  /// the schema.
+  Check { json: bool },
+  /// Print the value of a field, like `compilerOptions.strict`.
+  Get { key: String },
+  /// Set the value of a field, keeping the comments and the formatting of
+  /// the file. The value is a string unless `json` is set.
+  Set {
+    key: String,
+    value: String,
+    json: bool,
+  },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LockFlags {
+  /// Print an import map pinning the packages and redirects of the lockfile.
+  Export { output: Option<String> },
+  /// Remove the entries which aren't used by the module graph of the files or
+  /// the dependencies of the configuration files.
+  Prune { files: Vec<String> },
+  /// Fail when the lockfile is out of date with the module graph of the files.
+  Verify { files: Vec<String> },
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AuthFlags {
+  Login(AuthLoginFlags),
+  Logout { registry: String },
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuthLoginFlags {
+  pub registry: String,
+  /// Read from stdin when not provided.
+  pub token: Option<String>,
+  pub no_keychain: bool,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct OutdatedFlags {
+  pub filters: Vec<String>,
+  pub json: bool,
+  pub update: bool,
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct BenchFlags {
   pub files: FileFlags,
   pub filter: Option<String>,
-  pub json: bool,
+  pub reporter: BenchReporterConfig,
+  /// File the report of the machine-readable reporters is written to instead
+  /// of stdout.
+  pub output: Option<String>,
+  pub export_csv: Option<String>,
   pub no_run: bool,
   pub watch: Option<WatchFlags>,
+  pub engine: BenchEngineOptions,
+}
+
+/// Tuning of the benchmark engine, so that results can be reproduced across
+/// machines.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchEngineOptions {
+  /// Minimum number of iterations run before measuring.
+  pub warmup: u64,
+  /// Minimum number of measured samples, even past `max_time_ms`.
+  pub min_samples: u64,
+  /// Time budget for measuring a single bench.
+  pub max_time_ms: u64,
+  pub outliers: BenchOutliers,
+}
+
+impl Default for BenchEngineOptions {
+  fn default() -> Self {
+    Self {
+      warmup: 20,
+      min_samples: 10,
+      max_time_ms: 500,
+      outliers: BenchOutliers::Keep,
+    }
+  }
+}
+
+/// How samples which are far off the others are treated.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BenchOutliers {
+  #[default]
+  Keep,
+  /// Drop the samples outside of 1.5 interquartile ranges of the quartiles.
+  Iqr,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BenchReporterConfig {
+  #[default]
+  Pretty,
+  Json,
+  Junit,
+  Csv,
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BundleFormat {
+  #[default]
+  Esm,
+  Iife,
+}
+
+/// The runtime which the bundle is for.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BundlePlatform {
+  /// npm and `node:` specifiers are left as imports.
+  #[default]
+  Deno,
+  /// The ES modules of npm packages are bundled too.
+  Browser,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BundleSourceMap {
+  Inline,
+  External,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BundleFlags {
+  pub entrypoints: Vec<String>,
+  pub output: Option<String>,
+  pub outdir: Option<String>,
+  pub format: BundleFormat,
+  pub platform: BundlePlatform,
+  pub source_map: Option<BundleSourceMap>,
+  /// Specifiers to leave as imports in the output rather than bundling.
+  pub external: Vec<String>,
+  pub minify: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CacheFlags {
   pub files: Vec<String>,
+  /// Only report what would be downloaded.
+  pub dry_run: bool,
+  pub json: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -114,6 +305,10 @@ pub struct CheckFlags {
   pub files: Vec<String>,
   pub doc: bool,
   pub doc_only: bool,
+  /// Number of threads to type check independent parts of the graph on.
+  pub check_jobs: Option<NonZeroUsize>,
+  /// Only type check the modules which are impacted by these changed files.
+  pub affected_by: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -125,6 +320,27 @@ pub struct CompileFlags {
   pub no_terminal: bool,
   pub icon: Option<String>,
   pub include: Vec<String>,
+  pub no_licenses: bool,
+  /// Fail when a remote module has no integrity in the lockfile or is
+  /// imported by a specifier whose content can change.
+  pub require_lock_integrity: bool,
+  pub watch: Option<WatchFlags>,
+  /// Run the executable after each build of `--watch`.
+  pub watch_run: bool,
+  /// Platform installer package to create for the executable.
+  pub installer: Option<CompileInstaller>,
+  pub installer_version: Option<String>,
+  /// License file included in the installer package.
+  pub installer_license: Option<String>,
+  /// Make builds from the same inputs byte-identical.
+  pub reproducible: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompileInstaller {
+  Deb,
+  Pkg,
+  Msi,
 }
 
 impl CompileFlags {
@@ -150,6 +366,11 @@ pub enum CoverageType {
   Html,
 }
 
+/// The modules which `deno coverage` reports on by default: local modules
+/// which aren't tests.
+pub static DEFAULT_COVERAGE_INCLUDE: &str = r"^file:";
+pub static DEFAULT_COVERAGE_EXCLUDE: &str = r"test\.(js|mjs|ts|jsx|tsx)$";
+
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 pub struct CoverageFlags {
   pub files: FileFlags,
@@ -157,6 +378,14 @@ pub struct CoverageFlags {
   pub include: Vec<String>,
   pub exclude: Vec<String>,
   pub r#type: CoverageType,
+  pub changed_since: Option<String>,
+  pub threshold: Option<u8>,
+  /// JSON lines file which the summary of each run is appended to.
+  pub history: Option<String>,
+  /// Number of previous runs of the history to compare the coverage with.
+  pub trend: Option<usize>,
+  /// Report the modules of the project which were never loaded as uncovered.
+  pub include_untested: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -189,12 +418,28 @@ pub struct DocFlags {
   pub html: Option<DocHtmlFlag>,
   pub source_files: DocSourceFileFlag,
   pub filter: Option<String>,
+  /// Only document the symbols with a `@deprecated` tag.
+  pub only_deprecated: bool,
+  /// Only document the symbols with a `@since` tag of this version or later.
+  pub min_since: Option<String>,
+  /// Output an OpenAPI document for the routes of a `deno serve` entrypoint
+  /// instead of the documentation nodes.
+  pub openapi: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum EvalInputMode {
+  Json,
+  Text,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EvalFlags {
   pub print: bool,
-  pub code: String,
+  /// Snippets which are evaluated in order as a single module.
+  pub code: Vec<String>,
+  /// How stdin is read and exposed to the snippets as `$stdin`.
+  pub input: Option<EvalInputMode>,
 }
 
 #[derive(Clone, Default, Debug, Eq, PartialEq)]
@@ -209,6 +454,11 @@ pub struct FmtFlags {
   pub no_semicolons: Option<bool>,
   pub watch: Option<WatchFlags>,
   pub unstable_component: bool,
+  pub line_ending: Option<String>,
+  pub bom: Option<String>,
+  pub skip_code_blocks: Option<Vec<String>>,
+  /// File to which the result of `--check` is written as JSON.
+  pub output_file: Option<String>,
 }
 
 impl FmtFlags {
@@ -223,12 +473,51 @@ pub struct InitFlags {
   pub dir: Option<String>,
   pub lib: bool,
   pub serve: bool,
+  /// Scaffold a package.json based project which uses a node_modules
+  /// directory managed by npm or another package manager.
+  pub npm_compat: bool,
+  /// A built-in template other than the script, lib and serve layouts, a git
+  /// repository url or a path to a local template directory.
+  pub template: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CleanFlags {
+  /// Only remove the remote modules whose url matches this prefix or glob.
+  pub remote_match: Option<String>,
+  pub npm: bool,
+  pub remote: bool,
+  pub codegen: bool,
+  /// Only remove cache entries which were downloaded or written before this
+  /// long ago. When an entry was last used is not tracked.
+  pub older_than: Option<Duration>,
+  pub dry_run: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct InfoFlags {
   pub json: bool,
   pub file: Option<String>,
+  pub http_cache: bool,
+  pub cache_usage: bool,
+  /// Output the module graph as a diagram instead of a tree.
+  pub graph: Option<InfoGraphFormat>,
+  /// Depth from the root module after which dependencies are left out of
+  /// the diagram.
+  pub graph_depth: Option<usize>,
+  /// Show npm packages as single nodes without their dependencies.
+  pub collapse_npm: bool,
+  /// Report the imports and dependencies of the configuration files which
+  /// no module of the workspace imports.
+  pub unused: bool,
+  /// Remove the entries reported by `unused`.
+  pub fix: bool,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InfoGraphFormat {
+  Dot,
+  Mermaid,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -238,6 +527,14 @@ pub struct InstallFlagsGlobal {
   pub name: Option<String>,
   pub root: Option<String>,
   pub force: bool,
+  /// Environment variables passed through by the launcher. When set, all
+  /// other variables are removed from the environment of the executable.
+  pub env_allowlist: Option<Vec<String>>,
+  /// Working directory the launcher switches to before running.
+  pub cwd: Option<String>,
+  /// Also generate a definition which runs the executable as a user service
+  /// of the service manager of the platform.
+  pub service: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -268,12 +565,22 @@ pub struct JupyterFlags {
   pub install: bool,
   pub kernel: bool,
   pub conn_file: Option<String>,
+  /// Name of the kernelspec directory, defaults to `deno`.
+  pub name: Option<String>,
+  /// Name shown in the Jupyter UI, defaults to `Deno`.
+  pub display_name: Option<String>,
+  /// Extra arguments passed to `deno jupyter --kernel` by the kernelspec.
+  pub kernel_args: Vec<String>,
+  /// Directory the outputs too large to send inline are saved to.
+  pub assets_dir: Option<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct UninstallFlagsGlobal {
   pub name: String,
   pub root: Option<String>,
+  /// Also stop the service of the executable and remove its definition.
+  pub service: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -287,6 +594,16 @@ pub struct UninstallFlags {
   pub kind: UninstallKind,
 }
 
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct LspFlags {
+  /// Print the memory status of the running language servers and exit.
+  pub status: bool,
+  /// Print the configuration the language server resolves as JSON and exit.
+  pub diagnose: bool,
+  /// A JSON file with the editor settings to resolve with `--diagnose`.
+  pub settings: Option<String>,
+}
+
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct LintFlags {
   pub files: FileFlags,
@@ -298,6 +615,9 @@ pub struct LintFlags {
   pub json: bool,
   pub compact: bool,
   pub watch: Option<WatchFlags>,
+  /// File to which the diagnostics are written as JSON, in addition to the
+  /// selected reporter.
+  pub output_file: Option<String>,
 }
 
 impl LintFlags {
@@ -312,6 +632,14 @@ pub struct ReplFlags {
   pub eval_files: Option<Vec<String>>,
   pub eval: Option<String>,
   pub is_default_command: bool,
+  /// File to which the code evaluated in the session is written on exit.
+  pub save_session: Option<String>,
+  /// Offer to import npm and JSR packages when an undefined identifier is
+  /// referenced.
+  pub auto_import: bool,
+  /// Address on which to serve a JSON-RPC protocol for evaluating and
+  /// inspecting code, instead of reading lines from the terminal.
+  pub repl_server: Option<SocketAddr>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
@@ -319,6 +647,28 @@ pub struct RunFlags {
   pub script: String,
   pub watch: Option<WatchFlagsWithPaths>,
   pub bare: bool,
+  /// Run the script with its own temporary directory, which is removed when
+  /// it exits.
+  pub ephemeral_tmp: bool,
+  pub restart_on_failure: Option<RestartOnFailureFlags>,
+  /// Procfile with the programs to run as sibling processes instead of the
+  /// script, which is empty then.
+  pub procfile: Option<String>,
+  /// Programs run as sibling processes of the script, each given with a
+  /// `--sibling` flag as the script followed by its arguments.
+  pub siblings: Vec<Vec<String>>,
+  /// Show the status of the sibling processes in a panel below their output.
+  pub ui: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RestartOnFailureFlags {
+  /// The number of consecutive restarts before giving up, unlimited when not
+  /// set.
+  pub max_retries: Option<u32>,
+  /// The delay before the first restart, which is doubled for each
+  /// consecutive failure.
+  pub backoff: Duration,
 }
 
 impl RunFlags {
@@ -328,9 +678,19 @@ impl RunFlags {
       script,
       watch: None,
       bare: false,
+      ephemeral_tmp: false,
+      restart_on_failure: None,
+      procfile: None,
+      siblings: vec![],
+      ui: false,
     }
   }
 
+  /// Whether several programs are run as sibling processes.
+  pub fn has_siblings(&self) -> bool {
+    self.procfile.is_some() || !self.siblings.is_empty()
+  }
+
   pub fn is_stdin(&self) -> bool {
     self.script == "-"
   }
@@ -343,6 +703,34 @@ pub struct ServeFlags {
   pub port: u16,
   pub host: String,
   pub worker_count: Option<usize>,
+  /// Shadow backend that a copy of the incoming requests is sent to.
+  pub mirror: Option<String>,
+  /// Percentage of the incoming requests that are mirrored.
+  pub mirror_percent: u8,
+  /// Requests with a body beyond this many concurrent ones are rejected.
+  pub max_concurrent_uploads: Option<usize>,
+  /// Bytes of a request body buffered ahead of the handler.
+  pub upload_high_water_mark: Option<usize>,
+  /// Path on which the upload metrics are served as JSON.
+  pub upload_metrics_path: Option<String>,
+  /// Seconds without requests after which the server exits.
+  pub idle_shutdown: Option<u64>,
+  /// Certificate chain file to serve HTTPS with.
+  pub cert: Option<String>,
+  /// Private key file of the certificate.
+  pub key: Option<String>,
+  /// CA certificates file which client certificates are verified against.
+  pub tls_client_ca: Option<String>,
+  pub tls_client_auth: TlsClientAuth,
+}
+
+/// Whether `deno serve --tls-client-ca` accepts clients without a
+/// certificate.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TlsClientAuth {
+  #[default]
+  Require,
+  Optional,
 }
 
 impl ServeFlags {
@@ -354,6 +742,16 @@ impl ServeFlags {
       port,
       host: host.to_owned(),
       worker_count: None,
+      mirror: None,
+      mirror_percent: 100,
+      max_concurrent_uploads: None,
+      upload_high_water_mark: None,
+      upload_metrics_path: None,
+      idle_shutdown: None,
+      cert: None,
+      key: None,
+      tls_client_ca: None,
+      tls_client_auth: TlsClientAuth::Require,
     }
   }
 }
@@ -363,6 +761,13 @@ pub struct WatchFlags {
   pub hmr: bool,
   pub no_clear_screen: bool,
   pub exclude: Vec<String>,
+  /// How long to wait for more file changes before restarting.
+  pub debounce: Option<Duration>,
+  /// Poll the file system at this interval instead of relying on native
+  /// file system events.
+  pub poll_interval: Option<Duration>,
+  /// Shell command which is run before restarting.
+  pub exec: Option<String>,
 }
 
 #[derive(Clone, Default, Debug, Eq, PartialEq)]
@@ -371,6 +776,21 @@ pub struct WatchFlagsWithPaths {
   pub paths: Vec<String>,
   pub no_clear_screen: bool,
   pub exclude: Vec<String>,
+  pub debounce: Option<Duration>,
+  pub poll_interval: Option<Duration>,
+  pub exec: Option<String>,
+}
+
+/// The options of the file watcher shared by the watch-capable subcommands.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WatchOptions {
+  /// How long to wait for more file changes before restarting.
+  pub debounce: Option<Duration>,
+  /// Poll the file system at this interval instead of relying on native
+  /// file system events.
+  pub poll_interval: Option<Duration>,
+  /// Shell command which is run before restarting.
+  pub exec: Option<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -387,6 +807,14 @@ pub enum TestReporterConfig {
   Dot,
   Junit,
   Tap,
+  Json,
+}
+
+/// A reporter which writes to a file, from `--reporter=NAME:PATH`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TestReporterOutput {
+  pub reporter: TestReporterConfig,
+  pub path: String,
 }
 
 #[derive(Clone, Debug, Default, Eq, PartialEq)]
@@ -394,6 +822,8 @@ pub struct TestFlags {
   pub doc: bool,
   pub no_run: bool,
   pub coverage_dir: Option<String>,
+  /// Coverage reports generated from the coverage directory after the run.
+  pub coverage_report: Vec<CoverageType>,
   pub clean: bool,
   pub fail_fast: Option<NonZeroUsize>,
   pub files: FileFlags,
@@ -403,9 +833,31 @@ pub struct TestFlags {
   pub concurrent_jobs: Option<NonZeroUsize>,
   pub trace_leaks: bool,
   pub watch: Option<WatchFlagsWithPaths>,
+  /// Re-run all tests on changes instead of only the affected ones.
+  pub watch_all: bool,
   pub reporter: TestReporterConfig,
   pub junit_path: Option<String>,
+  /// Reporters which write to files in addition to `reporter`.
+  pub reporter_outputs: Vec<TestReporterOutput>,
   pub hide_stacktraces: bool,
+  /// Export a span per test to the OTLP collector configured by the
+  /// `OTEL_*` environment variables.
+  pub otel: bool,
+  /// Module run once before all the tests, which can share data with them.
+  pub setup: Option<String>,
+  /// Module run once after all the tests.
+  pub teardown: Option<String>,
+  pub update_snapshots: bool,
+  /// Fail the tests which run for longer than this.
+  pub timeout: Option<Duration>,
+  /// Mark the tests which run for longer than this as slow.
+  pub slow_threshold: Option<Duration>,
+  /// Directory in which the tests get their `t.artifactsDir`.
+  pub artifacts_dir: Option<String>,
+  /// Only run the tests which have one of these tags.
+  pub tags: Vec<String>,
+  /// Don't run the tests which have one of these tags.
+  pub skip_tags: Vec<String>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -417,6 +869,10 @@ pub struct UpgradeFlags {
   pub version: Option<String>,
   pub output: Option<String>,
   pub version_or_hash_or_channel: Option<String>,
+  /// The GitHub repository, as `owner/name`, of a fork to upgrade from.
+  pub repo: Option<String>,
+  /// The base URL of a mirror of dl.deno.land to upgrade from.
+  pub url: Option<Url>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -426,6 +882,14 @@ pub struct PublishFlags {
   pub allow_slow_types: bool,
   pub allow_dirty: bool,
   pub no_provenance: bool,
+  pub provenance_file: Option<String>,
+  pub registry: Option<Url>,
+  pub set_version: Option<String>,
+  pub tag: bool,
+  pub changed_since: Option<String>,
+  /// Print the changes of the files and exports since the previously
+  /// published version on a dry run.
+  pub diff_previous: bool,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -437,13 +901,16 @@ pub struct HelpFlags {
 pub enum DenoSubcommand {
   Add(AddFlags),
   Remove(RemoveFlags),
+  Auth(AuthFlags),
   Bench(BenchFlags),
-  Bundle,
+  Bundle(BundleFlags),
   Cache(CacheFlags),
   Check(CheckFlags),
-  Clean,
+  Clean(CleanFlags),
+  Compat(CompatFlags),
   Compile(CompileFlags),
   Completions(CompletionsFlags),
+  Config(ConfigFlags),
   Coverage(CoverageFlags),
   Doc(DocFlags),
   Eval(EvalFlags),
@@ -454,8 +921,10 @@ pub enum DenoSubcommand {
   JSONReference(JSONReferenceFlags),
   Jupyter(JupyterFlags),
   Uninstall(UninstallFlags),
-  Lsp,
+  Lsp(LspFlags),
   Lint(LintFlags),
+  Lock(LockFlags),
+  Outdated(OutdatedFlags),
   Repl(ReplFlags),
   Run(RunFlags),
   Serve(ServeFlags),
@@ -465,6 +934,8 @@ pub enum DenoSubcommand {
   Upgrade(UpgradeFlags),
   Vendor,
   Publish(PublishFlags),
+  Why(WhyFlags),
+  Audit(AuditFlags),
   Help(HelpFlags),
 }
 
@@ -481,7 +952,7 @@ impl DenoSubcommand {
         | Self::Jupyter(_)
         | Self::Repl(_)
         | Self::Bench(_)
-        | Self::Lsp
+        | Self::Lsp(_)
     )
   }
 }
@@ -492,10 +963,25 @@ impl Default for DenoSubcommand {
       eval_files: None,
       eval: None,
       is_default_command: true,
+      save_session: None,
+      auto_import: false,
+      repl_server: None,
     })
   }
 }
 
+/// How the progress of downloads is displayed, selected with `--progress`.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum ProgressMode {
+  /// A progress bar when stderr is a terminal, otherwise a line per download.
+  #[default]
+  Auto,
+  /// A line per download, which is readable in the logs of a CI.
+  Plain,
+  /// No progress output.
+  None,
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub enum TypeCheckMode {
   /// Type-check all modules.
@@ -597,14 +1083,34 @@ pub struct Flags {
   pub subcommand: DenoSubcommand,
 
   pub frozen_lockfile: Option<bool>,
+  /// Leave out the dev dependencies of the configuration files.
+  pub production: bool,
   pub ca_stores: Option<Vec<String>>,
   pub ca_data: Option<CaData>,
   pub cache_blocklist: Vec<String>,
   pub cached_only: bool,
+  /// Like `cached_only`, but also disables all other network access of the
+  /// CLI itself, such as the upgrade check.
+  pub offline: bool,
+  pub progress: ProgressMode,
+  /// Expand the environment variables referenced by the tasks and the import
+  /// map of the config file.
+  pub allow_env_in_config: bool,
   pub type_check_mode: TypeCheckMode,
   pub config_flag: ConfigFlag,
   pub node_modules_dir: Option<NodeModulesDirMode>,
+  /// Whether the files of the packages in the `node_modules` folder are hard
+  /// linked from a content addressed store, with `--node-modules-dir=linked`.
+  pub node_modules_linked: bool,
+  /// Additional conditions for the "exports" and "imports" of package.json
+  /// files, like `development` or `react-server`.
+  pub node_conditions: Vec<String>,
+  /// The `.npmrc` file passed with `--npmrc`, which is used instead of
+  /// discovering one.
+  pub npmrc: Option<String>,
   pub vendor: Option<bool>,
+  /// Whether to also store npm packages in the vendor folder.
+  pub vendor_npm: Option<bool>,
   pub enable_op_summary_metrics: bool,
   pub enable_testing_features: bool,
   pub ext: Option<String>,
@@ -612,18 +1118,30 @@ pub struct Flags {
   pub internal: InternalFlags,
   pub ignore: Vec<String>,
   pub import_map_path: Option<String>,
-  pub env_file: Option<String>,
+  /// The import maps of the additional `--import-map` flags, which are merged
+  /// over the first one in order.
+  pub import_map_overrides: Vec<String>,
+  /// The environment files, where the variables of the later files override
+  /// the ones of the earlier files.
+  pub env_file: Option<Vec<String>>,
   pub inspect_brk: Option<SocketAddr>,
   pub inspect_wait: Option<SocketAddr>,
+  /// The `host:port` of a debugger which the inspector connects to, instead
+  /// of listening for connections.
+  pub inspect_connect: Option<String>,
   pub inspect: Option<SocketAddr>,
   pub location: Option<Url>,
   pub lock: Option<String>,
   pub log_level: Option<Level>,
   pub no_remote: bool,
+  /// Type check in the current process instead of the check daemon.
+  pub no_daemon: bool,
   pub no_lock: bool,
   pub no_npm: bool,
   pub reload: bool,
   pub seed: Option<u64>,
+  /// The environment variables whose values are masked in the output.
+  pub redact_env: Vec<String>,
   pub strace_ops: Option<Vec<String>>,
   pub unstable_config: UnstableConfig,
   pub unsafely_ignore_certificate_errors: Option<Vec<String>>,
@@ -1131,6 +1649,43 @@ impl Flags {
       Ok(PathOrPatternSet::default())
     }
   }
+
+  /// Resolves the options of the file watcher which don't affect what is
+  /// watched.
+  pub fn resolve_watch_options(&self) -> WatchOptions {
+    match &self.subcommand {
+      DenoSubcommand::Run(RunFlags {
+        watch: Some(watch), ..
+      })
+      | DenoSubcommand::Serve(ServeFlags {
+        watch: Some(watch), ..
+      })
+      | DenoSubcommand::Test(TestFlags {
+        watch: Some(watch), ..
+      }) => WatchOptions {
+        debounce: watch.debounce,
+        poll_interval: watch.poll_interval,
+        exec: watch.exec.clone(),
+      },
+      DenoSubcommand::Bench(BenchFlags {
+        watch: Some(watch), ..
+      })
+      | DenoSubcommand::Compile(CompileFlags {
+        watch: Some(watch), ..
+      })
+      | DenoSubcommand::Lint(LintFlags {
+        watch: Some(watch), ..
+      })
+      | DenoSubcommand::Fmt(FmtFlags {
+        watch: Some(watch), ..
+      }) => WatchOptions {
+        debounce: watch.debounce,
+        poll_interval: watch.poll_interval,
+        exec: watch.exec.clone(),
+      },
+      _ => WatchOptions::default(),
+    }
+  }
 }
 
 static ENV_VARIABLES_HELP: &str = cstr!(
@@ -1140,12 +1695,27 @@ static ENV_VARIABLES_HELP: &str = cstr!(
   <g>DENO_AUTH_TOKENS</>      A semi-colon separated list of bearer tokens and hostnames
                         to use when fetching remote modules from private repositories
                          <p(245)>(e.g. "abcde12345@deno.land;54321edcba@github.com")</>
+                        Prefer <c>deno auth login</> to store tokens instead
+  <g>DENO_CACHE_BACKEND</>    Share the emitted code with other machines through an HTTPS
+                        store which supports GET and PUT, with the cache directory
+                        as a local overlay. Only the emit cache is shared
+                         <p(245)>(e.g. "remote:https://cache.example.com/deno")</>
+  <g>DENO_CACHE_BACKEND_SECRET</> The secret which signs the entries of the DENO_CACHE_BACKEND
+                        store, shared by the machines using it
   <g>DENO_CERT</>             Load certificate authorities from PEM encoded file
+  <g>DENO_CHECK_DAEMON</>     Set to "1" to type check in a background process which keeps
+                        the state of the project warm for deno check and deno test
   <g>DENO_DIR</>              Set the cache directory
+  <g>DENO_INSPECT_BRK</>      Same as --inspect-brk, for commands which don't
+                        pass the flag themselves, like tasks
   <g>DENO_INSTALL_ROOT</>     Set deno install's output directory
                          <p(245)>(defaults to $HOME/.deno/bin)</>
+  <g>DENO_MAX_CONCURRENT_DOWNLOADS</> The number of remote modules downloaded at the
+                        same time <p(245)>(defaults to 64)</>
   <g>DENO_NO_PACKAGE_JSON</>  Disables auto-resolution of package.json
   <g>DENO_NO_UPDATE_CHECK</>  Set to disable checking if a newer Deno version is available
+  <g>DENO_VERSION_MANAGER</>  Set to "1" to run the Deno version pinned by the project
+                        in a .deno-version file or the "deno.version" config field
   <g>DENO_TLS_CA_STORE</>     Comma-separated list of order dependent certificate stores.
                         Possible values: "system", "mozilla".
                          <p(245)>(defaults to "mozilla")</>
@@ -1181,12 +1751,21 @@ static DENO_HELP: &str = cstr!(
     <g>install</>      Install script as an executable
     <g>uninstall</>    Uninstall a script previously installed with deno install
     <g>remove</>       Remove dependencies from the configuration file
+    <g>outdated</>     Find dependencies which have newer versions available
+    <g>why</>          Explain why a package is in the dependency graph
+                  <p(245)>deno why npm:chalk  |  deno why @std/fmt</>
+    <g>audit</>        Check the dependencies in the lockfile for known vulnerabilities
+    <g>auth</>         Store tokens for private registries and module hosts
+                  <p(245)>deno auth login npm:https://npm.pkg.github.com</>
 
   <y>Tooling:</>
     <g>bench</>        Run benchmarks
                   <p(245)>deno bench bench.ts</>
+    <g>bundle</>       Bundle a module and its dependencies into a single file
+                  <p(245)>deno bundle main.ts -o dist/main.js</>
     <g>check</>        Type-check the dependencies
     <g>clean</>        Remove the cache directory
+    <g>compat</>       Report the Node APIs used by a project which Deno doesn't support yet
     <g>compile</>      Compile the script into a self contained executable
                   <p(245)>deno compile main.ts  |  deno compile --target=x86_64-unknown-linux-gnu</>
     <g>coverage</>     Print coverage reports
@@ -1243,6 +1822,17 @@ pub fn flags_from_vec(args: Vec<OsString>) -> clap::error::Result<Flags> {
 
   let mut flags = Flags::default();
 
+  flags.offline = matches.get_flag("offline");
+  flags.allow_env_in_config = matches.get_flag("allow-env-in-config");
+  if let Some(progress) = matches.get_one::<String>("progress") {
+    flags.progress = match progress.as_str() {
+      "auto" => ProgressMode::Auto,
+      "plain" => ProgressMode::Plain,
+      "none" => ProgressMode::None,
+      _ => unreachable!(),
+    };
+  }
+
   if matches.get_flag("quiet") {
     flags.log_level = Some(Level::Error);
   } else if let Some(log_level) = matches.get_one::<String>("log-level") {
@@ -1344,13 +1934,16 @@ pub fn flags_from_vec(args: Vec<OsString>) -> clap::error::Result<Flags> {
     match subcommand.as_str() {
       "add" => add_parse(&mut flags, &mut m),
       "remove" => remove_parse(&mut flags, &mut m),
+      "auth" => auth_parse(&mut flags, &mut m),
       "bench" => bench_parse(&mut flags, &mut m)?,
-      "bundle" => bundle_parse(&mut flags, &mut m),
+      "bundle" => bundle_parse(&mut flags, &mut m)?,
       "cache" => cache_parse(&mut flags, &mut m)?,
       "check" => check_parse(&mut flags, &mut m)?,
       "clean" => clean_parse(&mut flags, &mut m),
+      "compat" => compat_parse(&mut flags, &mut m),
       "compile" => compile_parse(&mut flags, &mut m)?,
       "completions" => completions_parse(&mut flags, &mut m, app),
+      "config" => config_parse(&mut flags, &mut m),
       "coverage" => coverage_parse(&mut flags, &mut m)?,
       "doc" => doc_parse(&mut flags, &mut m)?,
       "eval" => eval_parse(&mut flags, &mut m)?,
@@ -1359,9 +1952,13 @@ pub fn flags_from_vec(args: Vec<OsString>) -> clap::error::Result<Flags> {
       "info" => info_parse(&mut flags, &mut m)?,
       "install" => install_parse(&mut flags, &mut m)?,
       "json_reference" => json_reference_parse(&mut flags, &mut m, app),
-      "jupyter" => jupyter_parse(&mut flags, &mut m),
+      "jupyter" => jupyter_parse(&mut flags, &mut m)?,
       "lint" => lint_parse(&mut flags, &mut m)?,
+      "lock" => lock_parse(&mut flags, &mut m),
       "lsp" => lsp_parse(&mut flags, &mut m),
+      "outdated" => outdated_parse(&mut flags, &mut m),
+      "why" => why_parse(&mut flags, &mut m),
+      "audit" => audit_parse(&mut flags, &mut m),
       "repl" => repl_parse(&mut flags, &mut m)?,
       "run" => run_parse(&mut flags, &mut m, app, false)?,
       "serve" => serve_parse(&mut flags, &mut m, app)?,
@@ -1393,6 +1990,9 @@ pub fn flags_from_vec(args: Vec<OsString>) -> clap::error::Result<Flags> {
           eval_files: None,
           eval: None,
           is_default_command: true,
+          save_session: None,
+          auto_import: false,
+          repl_server: None,
         },
       )
     }
@@ -1581,19 +2181,45 @@ pub fn clap_root() -> Command {
         .action(ArgAction::SetTrue)
         .global(true),
     )
+    .arg(
+      Arg::new("offline")
+        .long("offline")
+        .help("Never access the network to fetch modules, npm packages or registry metadata, or to check for upgrades. Fails naming the specifier that is not cached")
+        .action(ArgAction::SetTrue)
+        .global(true),
+    )
+    .arg(
+      Arg::new("progress")
+        .long("progress")
+        .help("How to display the progress of downloads: a progress bar in a terminal (auto), a line per download (plain) or nothing (none)")
+        .value_name("MODE")
+        .value_parser(["auto", "plain", "none"])
+        .require_equals(true)
+        .global(true),
+    )
+    .arg(
+      Arg::new("allow-env-in-config")
+        .long("allow-env-in-config")
+        .help("Expand ${VAR} and ${VAR:-default} references to environment variables in the tasks and the import map of the config file")
+        .action(ArgAction::SetTrue)
+        .global(true),
+    )
     .subcommand(run_subcommand())
     .subcommand(serve_subcommand())
     .defer(|cmd| {
       let cmd = cmd
         .subcommand(add_subcommand())
         .subcommand(remove_subcommand())
+        .subcommand(auth_subcommand())
         .subcommand(bench_subcommand())
         .subcommand(bundle_subcommand())
         .subcommand(cache_subcommand())
         .subcommand(check_subcommand())
         .subcommand(clean_subcommand())
+        .subcommand(compat_subcommand())
         .subcommand(compile_subcommand())
         .subcommand(completions_subcommand())
+        .subcommand(config_subcommand())
         .subcommand(coverage_subcommand())
         .subcommand(doc_subcommand())
         .subcommand(eval_subcommand())
@@ -1606,6 +2232,10 @@ pub fn clap_root() -> Command {
         .subcommand(uninstall_subcommand())
         .subcommand(lsp_subcommand())
         .subcommand(lint_subcommand())
+        .subcommand(outdated_subcommand())
+        .subcommand(why_subcommand())
+        .subcommand(lock_subcommand())
+        .subcommand(audit_subcommand())
         .subcommand(publish_subcommand())
         .subcommand(repl_subcommand())
         .subcommand(task_subcommand())
@@ -1649,10 +2279,27 @@ fn add_dev_arg() -> Arg {
     .long("dev")
     .short('D')
     .help("Add as a dev dependency")
-    .long_help("Add the package as a dev dependency. Note: This only applies when adding to a `package.json` file.")
+    .long_help("Add the package as a dev dependency. It is written to \"devImports\" of a `deno.json` file or to \"devDependencies\" of a `package.json` file.")
     .action(ArgAction::SetTrue)
 }
 
+fn workspace_member_args(cmd: Command) -> Command {
+  cmd
+    .arg(
+      Arg::new("member")
+        .long("member")
+        .value_name("NAME")
+        .help("Update the configuration file of the workspace member with this package name or path instead of the current directory"),
+    )
+    .arg(
+      Arg::new("all-members")
+        .long("all-members")
+        .help("Update the configuration files of all workspace members")
+        .action(ArgAction::SetTrue)
+        .conflicts_with("member"),
+    )
+}
+
 fn add_subcommand() -> Command {
   command(
     "add",
@@ -1661,20 +2308,26 @@ fn add_subcommand() -> Command {
   <p(245)>deno add @std/path</>
 
 You can add multiple dependencies at once:
-  <p(245)>deno add @std/path @std/assert</>"
+  <p(245)>deno add @std/path @std/assert</>
+
+Add a dependency to a member of the workspace:
+  <p(245)>deno add --member @scope/server @std/path</>"
     ),
     UnstableArgsConfig::None,
   )
   .defer(|cmd| {
-    cmd
-      .arg(
-        Arg::new("packages")
-          .help("List of packages to add")
-          .required_unless_present("help")
-          .num_args(1..)
-          .action(ArgAction::Append),
-      )
-      .arg(add_dev_arg())
+    workspace_member_args(
+      cmd
+        .arg(
+          Arg::new("packages")
+            .help("List of packages to add")
+            .required_unless_present("help")
+            .num_args(1..)
+            .action(ArgAction::Append),
+        )
+        .arg(add_dev_arg())
+        .arg(npmrc_arg()),
+    )
   })
 }
 
@@ -1687,4114 +2340,8944 @@ fn remove_subcommand() -> Command {
 
 You can remove multiple dependencies at once:
   <p(245)>deno remove @std/path @std/assert</>
+
+Remove a dependency from all members of the workspace:
+  <p(245)>deno remove --all-members @std/path</>
 "
     ),
     UnstableArgsConfig::None,
   )
   .defer(|cmd| {
-    cmd.arg(
-      Arg::new("packages")
-        .help("List of packages to remove")
-        .required_unless_present("help")
-        .num_args(1..)
-        .action(ArgAction::Append),
+    workspace_member_args(
+      cmd.arg(
+        Arg::new("packages")
+          .help("List of packages to remove")
+          .required_unless_present("help")
+          .num_args(1..)
+          .action(ArgAction::Append),
+      ),
     )
   })
 }
 
-fn bench_subcommand() -> Command {
+fn auth_subcommand() -> Command {
   command(
-    "bench",
-    cstr!("Run benchmarks using Deno's built-in bench tool.
+    "auth",
+    cstr!(
+      "Store tokens for private registries and module hosts.
+  <p(245)>deno auth login jsr</>
+  <p(245)>deno auth login npm</>
+  <p(245)>deno auth login npm:https://npm.pkg.github.com</>
+  <p(245)>deno auth login git.example.com</>
 
-Evaluate the given files, run all benches declared with 'Deno.bench()' and report results to standard output:
-  <p(245)>deno bench src/fetch_bench.ts src/signal_bench.ts</>
+The token is read from standard input unless <c>--token</> is passed. It is stored in the system
+keychain where available, and otherwise in <c>$HOME/.deno/credentials.json</>.
 
-If you specify a directory instead of a file, the path is expanded to all contained files matching the glob <c>{*_,*.,}bench.{js,mjs,ts,mts,jsx,tsx}</>:
-  <p(245)>deno bench src/</>
+Tokens in <c>DENO_AUTH_TOKENS</> and <c>.npmrc</> files take precedence over the stored ones."
+    ),
+    UnstableArgsConfig::None,
+  )
+  .subcommand_required(true)
+  .defer(|cmd| {
+    let registry_arg = Arg::new("registry")
+      .help("jsr, npm, npm:<registry url>, or the host of remote modules")
+      .required(true);
+    cmd
+      .subcommand(
+        Command::new("login")
+          .about("Store a token for a registry")
+          .arg(registry_arg.clone())
+          .arg(
+            Arg::new("token")
+              .long("token")
+              .value_name("TOKEN")
+              .help("The token to store, instead of reading it from standard input"),
+          )
+          .arg(
+            Arg::new("no-keychain")
+              .long("no-keychain")
+              .help("Store the token in the credentials file even if a system keychain is available")
+              .action(ArgAction::SetTrue),
+          ),
+      )
+      .subcommand(
+        Command::new("logout")
+          .about("Remove the stored token for a registry")
+          .arg(registry_arg),
+      )
+  })
+}
 
-<y>Read more:</> <c>https://docs.deno.com/go/bench</>"),
-    UnstableArgsConfig::ResolutionAndRuntime,
+fn audit_subcommand() -> Command {
+  command(
+    "audit",
+    cstr!(
+      "Check the npm and JSR packages in the lockfile against the OSV vulnerability database.
+  <p(245)>deno audit</>
+
+Only fail for vulnerabilities of a certain severity or higher:
+  <p(245)>deno audit --fail-on=high</>
+
+Vulnerabilities without a severity count as high ones for <c>--fail-on</>.
+The dependencies are read from the lockfile, so they need to be installed first.
+The OSV API can be changed with the <c>DENO_OSV_API_URL</> environment variable, like for a mirror."
+    ),
+    UnstableArgsConfig::None,
   )
   .defer(|cmd| {
-    runtime_args(cmd, true, false)
-      .arg(check_arg(true))
+    cmd
       .arg(
         Arg::new("json")
           .long("json")
-          .action(ArgAction::SetTrue)
-          .help("UNSTABLE: Output benchmark result in JSON format"),
-      )
-      .arg(
-        Arg::new("ignore")
-          .long("ignore")
-          .num_args(1..)
-          .action(ArgAction::Append)
-          .require_equals(true)
-          .help("Ignore files"),
+          .help("Output the vulnerabilities as JSON")
+          .action(ArgAction::SetTrue),
       )
       .arg(
-        Arg::new("filter")
-          .long("filter")
-          .allow_hyphen_values(true)
-          .help(
-          "Run benchmarks with this string or regexp pattern in the bench name",
-        ),
+        Arg::new("fail-on")
+          .long("fail-on")
+          .help("Only exit with an error for vulnerabilities of this severity or higher")
+          .value_parser(["low", "moderate", "high", "critical"])
+          .require_equals(true),
       )
+  })
+}
+
+fn compat_subcommand() -> Command {
+  command(
+    "compat",
+    cstr!(
+      "Report the Node built-in modules and APIs used by a Node project or npm package which Deno doesn't support yet.
+  <p(245)>deno compat</>
+  <p(245)>deno compat node_modules/express</>
+
+The files are scanned statically without running them, and <c>node_modules</> directories within the paths are skipped."
+    ),
+    UnstableArgsConfig::None,
+  )
+  .defer(|cmd| {
+    cmd
       .arg(
-        Arg::new("files")
-          .help("List of file names to run")
-          .num_args(..)
-          .action(ArgAction::Append),
+        Arg::new("paths")
+          .help("Directories or files to scan, defaulting to the current directory")
+          .num_args(1..)
+          .action(ArgAction::Append)
+          .value_hint(ValueHint::AnyPath),
       )
       .arg(
-        Arg::new("no-run")
-          .long("no-run")
-          .help("Cache bench modules, but don't run benchmarks")
+        Arg::new("json")
+          .long("json")
+          .help("Output the report as JSON")
           .action(ArgAction::SetTrue),
       )
-      .arg(watch_arg(false))
-      .arg(watch_exclude_arg())
-      .arg(no_clear_screen_arg())
-      .arg(script_arg().last(true))
-      .arg(env_file_arg())
-      .arg(executable_ext_arg())
   })
 }
 
-fn bundle_subcommand() -> Command {
-  command("bundle",  "⚠️ `deno bundle` was removed in Deno 2.
-
-See the Deno 1.x to 2.x Migration Guide for migration instructions: https://docs.deno.com/runtime/manual/advanced/migrate_deprecations", UnstableArgsConfig::ResolutionOnly)
-    .hide(true)
-}
-
-fn cache_subcommand() -> Command {
+fn config_subcommand() -> Command {
   command(
-    "cache",
-    cstr!("Cache and compile remote dependencies.
-
-Download and compile a module with all of its static dependencies and save them in the local cache, without running any code:
-  <p(245)>deno cache jsr:@std/http/file-server</>
-
-Future runs of this module will trigger no downloads or compilation unless --reload is specified
-
-<y>Read more:</> <c>https://docs.deno.com/go/cache</>"),
-    UnstableArgsConfig::ResolutionOnly,
-)
-  .hide(true)
+    "config",
+    cstr!(
+      "Manage the configuration file.
+
+Validate deno.json against the schema, reporting unknown fields, invalid values and deprecated
+fields along with their replacements:
+  <p(245)>deno config check</>
+  <p(245)>deno config check --config=deno.jsonc --json</>
+
+Read or change a field, keeping the comments and the formatting of the file. Values are set as
+strings, unless they're parsed as JSON with --json:
+  <p(245)>deno config get compilerOptions.strict</>
+  <p(245)>deno config set --json compilerOptions.strict true</>
+  <p(245)>deno config set tasks.dev \"deno run --watch main.ts\"</>
+  <p(245)>deno config set imports.'\"@std/assert\"' jsr:@std/assert@1</>"
+    ),
+    UnstableArgsConfig::None,
+  )
+  .subcommand_required(true)
   .defer(|cmd| {
-    compile_args(cmd)
-      .arg(check_arg(false))
-      .arg(
-        Arg::new("file")
-          .num_args(1..)
-          .required_unless_present("help")
-          .value_hint(ValueHint::FilePath),
+    let key_arg = Arg::new("key")
+      .help("The dotted path of the field, quoting the fields which contain dots")
+      .required(true);
+    cmd
+      .subcommand(
+        Command::new("check")
+          .about("Validate the configuration file and the ones of the workspace members")
+          .arg(config_arg())
+          .arg(no_config_arg())
+          .arg(
+            Arg::new("json")
+              .long("json")
+              .help("Output the problems as JSON")
+              .action(ArgAction::SetTrue),
+          ),
+      )
+      .subcommand(
+        Command::new("get")
+          .about("Print the value of a field of the configuration file")
+          .arg(config_arg())
+          .arg(no_config_arg())
+          .arg(key_arg.clone()),
+      )
+      .subcommand(
+        Command::new("set")
+          .about("Set the value of a field of the configuration file, creating deno.json when there's none")
+          .arg(config_arg())
+          .arg(no_config_arg())
+          .arg(key_arg)
+          .arg(
+            Arg::new("value")
+              .help("The value, which is set as a string unless --json is given")
+              .required(true),
+          )
+          .arg(
+            Arg::new("json")
+              .long("json")
+              .help("Parse the value as JSON, e.g. to set a boolean, number, array or object")
+              .action(ArgAction::SetTrue),
+          ),
       )
-      .arg(frozen_lockfile_arg())
-      .arg(allow_scripts_arg())
-      .arg(allow_import_arg())
   })
 }
 
-fn clean_subcommand() -> Command {
+fn lock_subcommand() -> Command {
   command(
-    "clean",
-    cstr!("Remove the cache directory (<c>$DENO_DIR</>)"),
+    "lock",
+    cstr!(
+      "Manage the lockfile.
+
+Export an import map which pins the versions of the packages in the lockfile:
+  <p(245)>deno lock export --import-map --output=import_map.json</>
+
+Remove the entries which aren't used by the module graph of the files, defaulting to the files
+of the current directory, or by the dependencies of the configuration files:
+  <p(245)>deno lock prune</>
+
+Check that the lockfile is up to date without changing it, for example on CI:
+  <p(245)>deno lock verify main.ts</>"
+    ),
     UnstableArgsConfig::None,
   )
+  .subcommand_required(true)
+  .defer(|cmd| {
+    let with_lock_args = |cmd: Command| {
+      cmd
+        .arg(config_arg())
+        .arg(no_config_arg())
+        .arg(lock_arg())
+    };
+    let files_arg = Arg::new("files")
+      .help("The files of the module graph, defaulting to the files of the current directory")
+      .num_args(0..)
+      .action(ArgAction::Append)
+      .value_hint(ValueHint::FilePath);
+    cmd
+      .subcommand(with_lock_args(
+        Command::new("export")
+          .about("Print an import map pinning the packages and redirects of the lockfile")
+          .arg(
+            Arg::new("import-map")
+              .long("import-map")
+              .help("Export the lockfile as an import map, which is currently the only format")
+              .required(true)
+              .action(ArgAction::SetTrue),
+          )
+          .arg(
+            Arg::new("output")
+              .long("output")
+              .short('o')
+              .value_name("FILE")
+              .help("Write the import map to a file instead of standard output")
+              .value_hint(ValueHint::FilePath),
+          ),
+      ))
+      .subcommand(with_lock_args(
+        Command::new("prune")
+          .about("Remove the unused entries of the lockfile")
+          .arg(files_arg.clone()),
+      ))
+      .subcommand(with_lock_args(
+        Command::new("verify")
+          .about("Fail when the lockfile is out of date, without changing it")
+          .arg(files_arg),
+      ))
+  })
 }
 
-fn check_subcommand() -> Command {
-  command("check",
-      cstr!("Download and type-check without execution.
-
-  <p(245)>deno check jsr:@std/http/file-server</>
+fn why_subcommand() -> Command {
+  command(
+    "why",
+    cstr!(
+      "Explain why a package is in the dependency graph by listing the chains of dependencies
+from the configuration files that lead to it.
+  <p(245)>deno why npm:chalk</>
+  <p(245)>deno why @std/fmt</>
 
-Unless --reload is specified, this command will not re-download already cached dependencies
+A version requirement narrows the results down to the matching versions:
+  <p(245)>deno why npm:chalk@^4</>
 
-<y>Read more:</> <c>https://docs.deno.com/go/check</>"),
-          UnstableArgsConfig::ResolutionAndRuntime
-    )
-    .defer(|cmd| {
-      compile_args_without_check_args(cmd)
-        .arg(
-          Arg::new("all")
-            .long("all")
-            .help("Type-check all code, including remote modules and npm packages")
-            .action(ArgAction::SetTrue)
-            .conflicts_with("no-remote")
-        )
-        .arg(
-          // past alias for --all
-          Arg::new("remote")
-            .long("remote")
-            .help("Type-check all modules, including remote ones")
-            .action(ArgAction::SetTrue)
-            .conflicts_with("no-remote")
-            .hide(true)
-        )
-        .arg(
-          Arg::new("doc")
-            .long("doc")
-            .help("Type-check code blocks in JSDoc as well as actual code")
-            .action(ArgAction::SetTrue)
-        )
-        .arg(
-          Arg::new("doc-only")
-          .long("doc-only")
-          .help("Type-check code blocks in JSDoc and Markdown only")
-            .action(ArgAction::SetTrue)
-            .conflicts_with("doc")
-        )
-        .arg(
-          Arg::new("file")
-            .num_args(1..)
-            .required_unless_present("help")
-            .value_hint(ValueHint::FilePath),
-        )
-        .arg(allow_import_arg())
-      }
+The dependency graph is read from the lockfile, so dependencies need to be installed first."
+    ),
+    UnstableArgsConfig::None,
+  )
+  .defer(|cmd| {
+    cmd.arg(
+      Arg::new("package")
+        .help("The package to explain, optionally prefixed with jsr: or npm:")
+        .required(true),
     )
+  })
 }
 
-fn compile_subcommand() -> Command {
+fn outdated_subcommand() -> Command {
   command(
-    "compile",
-    cstr!("Compiles the given script into a self contained executable.
+    "outdated",
+    cstr!(
+      "Find dependencies in the configuration files which have newer versions available.
+  <p(245)>deno outdated</>
 
-  <p(245)>deno compile --allow-read --allow-net jsr:@std/http/file-server</>
-  <p(245)>deno compile --output file_server jsr:@std/http/file-server</>
+Only check specific packages:
+  <p(245)>deno outdated @std/path npm:chalk</>
 
-Any flags specified which affect runtime behavior will be applied to the resulting binary.
+Update the configuration files to the latest versions:
+  <p(245)>deno outdated --update</>
 
-This allows distribution of a Deno application to systems that do not have Deno installed.
-Under the hood, it bundles a slimmed down version of the Deno runtime along with your
-JavaScript or TypeScript code.
+The current version is the one in the lockfile, the wanted version is the newest one matching
+the version requirement and the latest version is the newest one in the registry."
+    ),
+    UnstableArgsConfig::None,
+  )
+  .defer(|cmd| {
+    cmd
+      .arg(
+        Arg::new("filters")
+          .help("Only check these packages")
+          .num_args(0..)
+          .action(ArgAction::Append),
+      )
+      .arg(
+        Arg::new("json")
+          .long("json")
+          .help("Output the outdated dependencies as JSON")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("update")
+          .long("update")
+          .short('u')
+          .help("Update the version requirements in the configuration files to the latest versions")
+          .action(ArgAction::SetTrue),
+      )
+  })
+}
 
-Cross-compiling to different target architectures is supported using the <c>--target</> flag.
-On the first invocation with deno will download the proper binary and cache it in <c>$DENO_DIR</>.
+fn bench_subcommand() -> Command {
+  command(
+    "bench",
+    cstr!("Run benchmarks using Deno's built-in bench tool.
 
-<y>Read more:</> <c>https://docs.deno.com/go/compile</>
-"),
+Evaluate the given files, run all benches declared with 'Deno.bench()' and report results to standard output:
+  <p(245)>deno bench src/fetch_bench.ts src/signal_bench.ts</>
+
+If you specify a directory instead of a file, the path is expanded to all contained files matching the glob <c>{*_,*.,}bench.{js,mjs,ts,mts,jsx,tsx}</>:
+  <p(245)>deno bench src/</>
+
+<y>Read more:</> <c>https://docs.deno.com/go/bench</>"),
     UnstableArgsConfig::ResolutionAndRuntime,
   )
   .defer(|cmd| {
     runtime_args(cmd, true, false)
       .arg(check_arg(true))
       .arg(
-        Arg::new("include")
-          .long("include")
-          .help(
-            cstr!("Includes an additional module in the compiled executable's module graph.
-  <p(245)>Use this flag if a dynamically imported module or a web worker main module
-  fails to load in the executable. This flag can be passed multiple times,
-  to include multiple additional modules.</>",
-          ))
-          .action(ArgAction::Append)
-          .value_hint(ValueHint::FilePath)
-          .help_heading(COMPILE_HEADING),
+        Arg::new("json")
+          .long("json")
+          .action(ArgAction::SetTrue)
+          .conflicts_with("reporter")
+          .help("UNSTABLE: Output benchmark result in JSON format. Alias of --reporter=json"),
+      )
+      .arg(
+        Arg::new("reporter")
+          .long("reporter")
+          .help("Select reporter to use. Default to 'pretty'")
+          .value_parser(["pretty", "json", "junit", "csv"]),
       )
       .arg(
         Arg::new("output")
           .long("output")
-          .short('o')
-          .value_parser(value_parser!(String))
-          .help(cstr!("Output file <p(245)>(defaults to $PWD/<<inferred-name>>)</>"))
+          .require_equals(true)
+          .value_name("FILE")
           .value_hint(ValueHint::FilePath)
-          .help_heading(COMPILE_HEADING),
+          .help("Write the report to FILE instead of stdout. Not supported by the pretty reporter"),
       )
       .arg(
-        Arg::new("target")
-          .long("target")
-          .help("Target OS architecture")
-          .value_parser([
-            "x86_64-unknown-linux-gnu",
-            "aarch64-unknown-linux-gnu",
-            "x86_64-pc-windows-msvc",
-            "x86_64-apple-darwin",
-            "aarch64-apple-darwin",
-          ])
-          .help_heading(COMPILE_HEADING),
+        Arg::new("export-csv")
+          .long("export-csv")
+          .require_equals(true)
+          .value_name("FILE")
+          .value_hint(ValueHint::FilePath)
+          .help("Write raw iteration samples of every benchmark to a CSV file"),
       )
       .arg(
-        Arg::new("no-terminal")
-          .long("no-terminal")
-          .help("Hide terminal on Windows")
-          .action(ArgAction::SetTrue)
-          .help_heading(COMPILE_HEADING),
+        Arg::new("warmup")
+          .long("warmup")
+          .require_equals(true)
+          .value_name("N")
+          .value_parser(value_parser!(u64).range(1..))
+          .help("Minimum number of iterations run before measuring a benchmark. Defaults to 20"),
       )
       .arg(
-        Arg::new("icon")
-          .long("icon")
-          .help("Set the icon of the executable on Windows (.ico)")
-          .value_parser(value_parser!(String))
-          .help_heading(COMPILE_HEADING),
+        Arg::new("min-samples")
+          .long("min-samples")
+          .require_equals(true)
+          .value_name("N")
+          .value_parser(value_parser!(u64).range(1..))
+          .help("Minimum number of samples measured for a benchmark, even past --max-time. Defaults to 10"),
       )
-      .arg(executable_ext_arg())
-      .arg(env_file_arg())
       .arg(
-        script_arg()
-          .required_unless_present("help")
-          .trailing_var_arg(true),
+        Arg::new("max-time")
+          .long("max-time")
+          .require_equals(true)
+          .value_name("MS")
+          .value_parser(value_parser!(u64))
+          .help("Time budget in milliseconds for measuring a benchmark. Defaults to 500"),
+      )
+      .arg(
+        Arg::new("outliers")
+          .long("outliers")
+          .require_equals(true)
+          .value_name("STRATEGY")
+          .value_parser(["keep", "iqr"])
+          .help(cstr!("How samples far off the others are treated. Defaults to 'keep'
+  <p(245)>iqr drops the samples outside of 1.5 interquartile ranges of the quartiles</>")),
+      )
+      .arg(
+        Arg::new("ignore")
+          .long("ignore")
+          .num_args(1..)
+          .action(ArgAction::Append)
+          .require_equals(true)
+          .help("Ignore files"),
+      )
+      .arg(
+        Arg::new("filter")
+          .long("filter")
+          .allow_hyphen_values(true)
+          .help(
+          "Run benchmarks with this string or regexp pattern in the bench name",
+        ),
+      )
+      .arg(
+        Arg::new("files")
+          .help("List of file names to run")
+          .num_args(..)
+          .action(ArgAction::Append),
+      )
+      .arg(
+        Arg::new("no-run")
+          .long("no-run")
+          .help("Cache bench modules, but don't run benchmarks")
+          .action(ArgAction::SetTrue),
       )
+      .arg(watch_arg(false))
+      .arg(watch_exclude_arg())
+      .arg(watch_debounce_arg())
+      .arg(watch_poll_interval_arg())
+      .arg(watch_exec_arg())
+      .arg(no_clear_screen_arg())
+      .arg(script_arg().last(true))
+      .arg(env_file_arg())
+      .arg(executable_ext_arg())
   })
 }
 
-fn completions_subcommand() -> Command {
+fn bundle_subcommand() -> Command {
   command(
-    "completions",
-    cstr!(
-      "Output shell completion script to standard output.
+    "bundle",
+    cstr!("Bundle a module and its dependencies into a single JavaScript file.
 
-  <p(245)>deno completions bash > /usr/local/etc/bash_completion.d/deno.bash</>
-  <p(245)>source /usr/local/etc/bash_completion.d/deno.bash</>"
-    ),
-    UnstableArgsConfig::None,
+  <p(245)>deno bundle main.ts --output dist/main.js</>
+  <p(245)>deno bundle --outdir dist --format iife --source-map=external main.ts worker.ts</>
+
+Remote modules, JSR packages and specifiers mapped by the import map are bundled, while npm and
+node: specifiers are left as imports. Unused exports of the bundled modules are removed.
+
+Bundle for browsers, which also bundles the ES modules of npm packages:
+  <p(245)>deno bundle --platform=browser --outdir dist --minify main.tsx</>
+
+Each module which is only imported dynamically is emitted to its own chunk next to the bundle.
+
+When neither --output nor --outdir is provided, the bundle is written to stdout."),
+    UnstableArgsConfig::ResolutionOnly,
   )
   .defer(|cmd| {
-    cmd.disable_help_subcommand(true).arg(
-      Arg::new("shell")
-        .value_parser(["bash", "fish", "powershell", "zsh", "fig"])
-        .required_unless_present("help"),
-    )
+    compile_args(cmd)
+      .arg(check_arg(false))
+      .arg(
+        Arg::new("entrypoints")
+          .num_args(1..)
+          .required_unless_present("help")
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(
+        Arg::new("output")
+          .long("output")
+          .short('o')
+          .value_name("FILE")
+          .help("Write the bundle of a single entrypoint to this file")
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(
+        Arg::new("outdir")
+          .long("outdir")
+          .value_name("DIR")
+          .help("Write the bundles of the entrypoints to this directory")
+          .conflicts_with("output")
+          .value_hint(ValueHint::DirPath),
+      )
+      .arg(
+        Arg::new("format")
+          .long("format")
+          .value_name("FORMAT")
+          .help("The module format of the bundle")
+          .value_parser(["esm", "iife"])
+          .default_value("esm"),
+      )
+      .arg(
+        Arg::new("platform")
+          .long("platform")
+          .value_name("PLATFORM")
+          .help("The runtime of the bundle. For browsers, the npm packages are bundled and node: specifiers are errors")
+          .value_parser(["browser", "deno"])
+          .default_value("deno"),
+      )
+      .arg(
+        Arg::new("source-map")
+          .long("source-map")
+          .value_name("KIND")
+          .help("Generate a source map, inline in the bundle or as a separate .map file")
+          .num_args(0..=1)
+          .require_equals(true)
+          .default_missing_value("external")
+          .value_parser(["inline", "external"]),
+      )
+      .arg(
+        Arg::new("external")
+          .long("external")
+          .value_name("SPECIFIER")
+          .help(cstr!("Leave the imports of this specifier as is instead of bundling it
+  <p(245)>A trailing * matches any specifier with the prefix, ex. --external=npm:* </>"))
+          .action(ArgAction::Append),
+      )
+      .arg(
+        Arg::new("minify")
+          .long("minify")
+          .help("Emit the bundle without whitespace")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(allow_import_arg())
   })
 }
 
-fn coverage_subcommand() -> Command {
+fn cache_subcommand() -> Command {
   command(
-    "coverage",
-    cstr!("Print coverage reports from coverage profiles.
-
-Collect a coverage profile with deno test:
-  <p(245)>deno test --coverage=cov_profile</>
-
-Print a report to stdout:
-  <p(245)>deno coverage cov_profile</>
+    "cache",
+    cstr!("Cache and compile remote dependencies.
 
-Include urls that start with the file schema and exclude files ending with <c>test.ts</> and <c>test.js</>,
-for an url to match it must match the include pattern and not match the exclude pattern:
-  <p(245)>deno coverage --include=\"^file:\" --exclude=\"test\\.(ts|js)\" cov_profile</>
+Download and compile a module with all of its static dependencies and save them in the local cache, without running any code:
+  <p(245)>deno cache jsr:@std/http/file-server</>
 
-Write a report using the lcov format:
-  <p(245)>deno coverage --lcov --output=cov.lcov cov_profile/</>
+Future runs of this module will trigger no downloads or compilation unless --reload is specified
 
-Generate html reports from lcov:
-  <p(245)>genhtml -o html_cov cov.lcov</>
+Report the remote modules and the packages of the module graph which would be downloaded, without accessing the network:
+  <p(245)>deno cache --dry-run --json main.ts</>
 
-<y>Read more:</> <c>https://docs.deno.com/go/coverage</>"),
-    UnstableArgsConfig::None,
-  )
+<y>Read more:</> <c>https://docs.deno.com/go/cache</>"),
+    UnstableArgsConfig::ResolutionOnly,
+)
+  .hide(true)
   .defer(|cmd| {
-    cmd
+    compile_args(cmd)
+      .arg(check_arg(false))
       .arg(
-        Arg::new("ignore")
-          .long("ignore")
+        Arg::new("file")
           .num_args(1..)
-          .action(ArgAction::Append)
-          .require_equals(true)
-          .help("Ignore coverage files")
-          .value_hint(ValueHint::AnyPath),
+          .required_unless_present("help")
+          .value_hint(ValueHint::FilePath),
       )
+      .arg(frozen_lockfile_arg())
+      .arg(allow_scripts_arg())
+      .arg(allow_import_arg())
       .arg(
-        Arg::new("include")
-          .long("include")
-          .num_args(1..)
-          .action(ArgAction::Append)
-          .value_name("regex")
-          .require_equals(true)
-          .default_value(r"^file:")
-          .help("Include source files in the report"),
+        Arg::new("dry-run")
+          .long("dry-run")
+          .help("Report what would be downloaded without accessing the network")
+          .action(ArgAction::SetTrue),
       )
       .arg(
-        Arg::new("exclude")
-          .long("exclude")
-          .num_args(1..)
-          .action(ArgAction::Append)
-          .value_name("regex")
-          .require_equals(true)
-          .default_value(r"test\.(js|mjs|ts|jsx|tsx)$")
-          .help("Exclude source files from the report"),
+        Arg::new("json")
+          .long("json")
+          .help("Output the report of --dry-run as JSON")
+          .requires("dry-run")
+          .action(ArgAction::SetTrue),
       )
+  })
+}
+
+fn clean_subcommand() -> Command {
+  command(
+    "clean",
+    cstr!("Remove the cache directory (<c>$DENO_DIR</>)
+
+Only remove the cached remote modules matching a url prefix or glob:
+  <p(245)>deno clean --remote-match https://deno.land/std@</>
+  <p(245)>deno clean --remote-match 'https://esm.sh/*react*'</>
+
+Use <c>deno info --http-cache</> to list the cached remote modules, and <c>deno info --cache-usage</>
+to see how much space each of the caches takes.
+
+Only remove some of the caches:
+  <p(245)>deno clean --npm --codegen</>
+
+Only remove what was downloaded or emitted a while ago, and see what would be removed first:
+  <p(245)>deno clean --older-than=30d --dry-run</>
+
+The age of an entry is counted from when it was cached, not from when it was last used,
+so packages still in use are removed too once they are old enough."),
+    UnstableArgsConfig::None,
+  )
+  .defer(|cmd| {
+    cmd
       .arg(
-        Arg::new("lcov")
-          .long("lcov")
-          .help("Output coverage report in lcov format")
-          .action(ArgAction::SetTrue),
+        Arg::new("remote-match")
+          .long("remote-match")
+          .value_name("PATTERN")
+          .help("Only remove the remote modules whose url starts with the prefix or matches the glob"),
       )
       .arg(
-        Arg::new("output")
-          .requires("lcov")
-          .long("output")
-          .value_parser(value_parser!(String))
-          .help(
-            cstr!("Exports the coverage report in lcov format to the given file.
-  <p(245)>If no --output arg is specified then the report is written to stdout.</>",
-          ))
-          .require_equals(true)
-          .value_hint(ValueHint::FilePath),
+        Arg::new("npm")
+          .long("npm")
+          .help("Remove the npm package cache")
+          .action(ArgAction::SetTrue),
       )
       .arg(
-        Arg::new("html")
-          .long("html")
-          .help("Output coverage report in HTML format in the given directory")
+        Arg::new("remote")
+          .long("remote")
+          .help("Remove the remote module cache")
           .action(ArgAction::SetTrue),
       )
       .arg(
-        Arg::new("detailed")
-          .long("detailed")
-          .help("Output coverage report in detailed format in the terminal")
+        Arg::new("codegen")
+          .long("codegen")
+          .help("Remove the cache of emitted JavaScript")
           .action(ArgAction::SetTrue),
       )
       .arg(
-        Arg::new("files")
-          .num_args(0..)
-          .action(ArgAction::Append)
-          .value_hint(ValueHint::AnyPath),
+        Arg::new("older-than")
+          .long("older-than")
+          .value_name("DURATION")
+          .help("Only remove entries cached longer ago than this, e.g. 30d, 12h or 2w. Entries still in use are not kept")
+          .value_parser(parse_duration)
+          .require_equals(true),
+      )
+      .arg(
+        Arg::new("dry-run")
+          .long("dry-run")
+          .help("Report what would be removed without removing anything")
+          .action(ArgAction::SetTrue),
       )
   })
 }
 
-fn doc_subcommand() -> Command {
-  command("doc",
-      cstr!("Show documentation for a module.
-
-Output documentation to standard output:
-    <p(245)>deno doc ./path/to/module.ts</>
-
-Output documentation in HTML format:
-    <p(245)>deno doc --html --name=\"My library\" ./path/to/module.ts</>
+/// Parses durations like `90s`, `30m`, `12h`, `30d` or `2w`.
+fn parse_duration(text: &str) -> Result<Duration, String> {
+  let index = text.find(|c: char| !c.is_ascii_digit()).ok_or_else(|| {
+    "missing unit, expected one of s, m, h, d or w".to_string()
+  })?;
+  let (value, unit) = text.split_at(index);
+  let value = value
+    .parse::<u64>()
+    .map_err(|_| format!("invalid duration: {text}"))?;
+  let seconds = match unit {
+    "s" => 1,
+    "m" => 60,
+    "h" => 60 * 60,
+    "d" => 24 * 60 * 60,
+    "w" => 7 * 24 * 60 * 60,
+    _ => {
+      return Err(format!(
+        "invalid unit '{unit}', expected one of s, m, h, d or w"
+      ))
+    }
+  };
+  Ok(Duration::from_secs(value * seconds))
+}
 
-Lint a module for documentation diagnostics:
-    <p(245)>deno doc --lint ./path/to/module.ts</>
+fn check_subcommand() -> Command {
+  command("check",
+      cstr!("Download and type-check without execution.
 
-Target a specific symbol:
-    <p(245)>deno doc ./path/to/module.ts MyClass.someField</>
+  <p(245)>deno check jsr:@std/http/file-server</>
 
-Show documentation for runtime built-ins:
-    <p(245)>deno doc</>
-    <p(245)>deno doc --filter Deno.Listener</>
+Unless --reload is specified, this command will not re-download already cached dependencies
 
-<y>Read more:</> <c>https://docs.deno.com/go/doc</>"),
-          UnstableArgsConfig::ResolutionOnly
+<y>Read more:</> <c>https://docs.deno.com/go/check</>"),
+          UnstableArgsConfig::ResolutionAndRuntime
     )
     .defer(|cmd| {
-      cmd
-        .arg(import_map_arg())
-        .arg(reload_arg())
-        .arg(lock_arg())
-        .arg(no_lock_arg())
-        .arg(no_npm_arg())
-        .arg(no_remote_arg())
-        .arg(allow_import_arg())
+      compile_args_without_check_args(cmd)
         .arg(
-          Arg::new("json")
-            .long("json")
-            .help("Output documentation in JSON format")
+          Arg::new("all")
+            .long("all")
+            .help("Type-check all code, including remote modules and npm packages")
             .action(ArgAction::SetTrue)
-            .help_heading(DOC_HEADING),
+            .conflicts_with("no-remote")
         )
         .arg(
-          Arg::new("html")
-            .long("html")
-            .help("Output documentation in HTML format")
+          // past alias for --all
+          Arg::new("remote")
+            .long("remote")
+            .help("Type-check all modules, including remote ones")
             .action(ArgAction::SetTrue)
-            .display_order(1000)
-            .conflicts_with("json").help_heading(DOC_HEADING)
-        )
-        .arg(
-          Arg::new("name")
-            .long("name")
-            .help("The name that will be used in the docs (ie for breadcrumbs)")
-            .action(ArgAction::Set)
-            .require_equals(true).help_heading(DOC_HEADING)
-        )
-        .arg(
-          Arg::new("category-docs")
-            .long("category-docs")
-            .help("Path to a JSON file keyed by category and an optional value of a markdown doc")
-            .requires("html")
-            .action(ArgAction::Set)
-            .require_equals(true).help_heading(DOC_HEADING)
+            .conflicts_with("no-remote")
+            .hide(true)
         )
         .arg(
-          Arg::new("symbol-redirect-map")
-            .long("symbol-redirect-map")
-            .help("Path to a JSON file keyed by file, with an inner map of symbol to an external link")
-            .requires("html")
-            .action(ArgAction::Set)
-            .require_equals(true).help_heading(DOC_HEADING)
+          Arg::new("doc")
+            .long("doc")
+            .help("Type-check code blocks in JSDoc as well as actual code")
+            .action(ArgAction::SetTrue)
         )
         .arg(
-          Arg::new("strip-trailing-html")
-            .long("strip-trailing-html")
-            .help("Remove trailing .html from various links. Will still generate files with a .html extension")
-            .requires("html")
-            .action(ArgAction::SetTrue).help_heading(DOC_HEADING)
+          Arg::new("doc-only")
+          .long("doc-only")
+          .help("Type-check code blocks in JSDoc and Markdown only")
+            .action(ArgAction::SetTrue)
+            .conflicts_with("doc")
         )
         .arg(
-          Arg::new("default-symbol-map")
-            .long("default-symbol-map")
-            .help("Uses the provided mapping of default name to wanted name for usage blocks")
-            .requires("html")
-            .action(ArgAction::Set)
-            .require_equals(true).help_heading(DOC_HEADING)
+          Arg::new("check-jobs")
+            .long("check-jobs")
+            .value_name("N")
+            .help("Type-check the parts of the module graph that don't share local modules on up to N threads. The graph isn't split when a module declares globals")
+            .value_parser(value_parser!(NonZeroUsize))
         )
         .arg(
-          Arg::new("output")
-            .long("output")
-            .help("Directory for HTML documentation output")
-            .action(ArgAction::Set)
+          Arg::new("affected-by")
+            .long("affected-by")
+            .value_name("FILES")
             .require_equals(true)
-            .value_hint(ValueHint::DirPath)
-            .value_parser(value_parser!(String)).help_heading(DOC_HEADING)
-        )
-        .arg(
-          Arg::new("private")
-            .long("private")
-            .help("Output private documentation")
-            .action(ArgAction::SetTrue).help_heading(DOC_HEADING),
+            .num_args(1..)
+            .use_value_delimiter(true)
+            .action(ArgAction::Append)
+            .help("Only type-check the changed files and the modules importing them, e.g. --affected-by=src/a.ts,src/b.ts")
+            .long_help("Only type-check the changed files and the modules importing them directly or indirectly, for a fast type-check before committing. The files to check default to the current directory when this is provided")
+            .value_hint(ValueHint::FilePath)
         )
         .arg(
-          Arg::new("filter")
-            .long("filter")
-            .help("Dot separated path to symbol")
-            .conflicts_with("json")
-            .conflicts_with("lint")
-            .conflicts_with("html").help_heading(DOC_HEADING),
-        )
-        .arg(
-          Arg::new("lint")
-            .long("lint")
-            .help("Output documentation diagnostics.")
-            .action(ArgAction::SetTrue).help_heading(DOC_HEADING),
-        )
-        // TODO(nayeemrmn): Make `--builtin` a proper option. Blocked by
-        // https://github.com/clap-rs/clap/issues/1794. Currently `--builtin` is
-        // just a possible value of `source_file` so leading hyphens must be
-        // enabled.
-        .allow_hyphen_values(true)
-        .arg(
-          Arg::new("source_file")
+          Arg::new("file")
             .num_args(1..)
-            .action(ArgAction::Append)
-            .value_hint(ValueHint::FilePath)
-            .required_if_eq_any([("html", "true"), ("lint", "true")]),
+            .required_unless_present_any(["help", "affected-by"])
+            .value_hint(ValueHint::FilePath),
         )
-    })
+        .arg(no_daemon_arg())
+        .arg(allow_import_arg())
+      }
+    )
 }
 
-fn eval_subcommand() -> Command {
+fn compile_subcommand() -> Command {
   command(
-    "eval",
-    cstr!(
-      "Evaluate JavaScript from the command line.
-  <p(245)>deno eval \"console.log('hello world')\"</>
+    "compile",
+    cstr!("Compiles the given script into a self contained executable.
 
-To evaluate as TypeScript:
-  <p(245)>deno eval --ext=ts \"const v: string = 'hello'; console.log(v)\"</>
+  <p(245)>deno compile --allow-read --allow-net jsr:@std/http/file-server</>
+  <p(245)>deno compile --output file_server jsr:@std/http/file-server</>
 
-This command has implicit access to all permissions.
+Any flags specified which affect runtime behavior will be applied to the resulting binary.
 
-<y>Read more:</> <c>https://docs.deno.com/go/eval</>"
-    ),
+This allows distribution of a Deno application to systems that do not have Deno installed.
+Under the hood, it bundles a slimmed down version of the Deno runtime along with your
+JavaScript or TypeScript code.
+
+Cross-compiling to different target architectures is supported using the <c>--target</> flag.
+On the first invocation with deno will download the proper binary and cache it in <c>$DENO_DIR</>.
+
+The licenses of the npm packages included in the executable are embedded as well, and printed
+when it is run with <c>--licenses</> as the only argument.
+
+<y>Read more:</> <c>https://docs.deno.com/go/compile</>
+"),
     UnstableArgsConfig::ResolutionAndRuntime,
   )
   .defer(|cmd| {
-    runtime_args(cmd, false, true)
-      .arg(check_arg(false))
-      .arg(executable_ext_arg())
-      .arg(
-        Arg::new("print")
-          .long("print")
-          .short('p')
-          .help("print result to stdout")
-          .action(ArgAction::SetTrue),
-      )
+    runtime_args(cmd, true, false)
+      .arg(check_arg(true))
       .arg(
-        Arg::new("code_arg")
-          .num_args(1..)
+        Arg::new("include")
+          .long("include")
+          .help(
+            cstr!("Includes an additional module in the compiled executable's module graph.
+  <p(245)>Use this flag if a dynamically imported module or a web worker main module
+  fails to load in the executable. This flag can be passed multiple times,
+  to include multiple additional modules.</>",
+          ))
           .action(ArgAction::Append)
-          .help("Code to evaluate")
-          .value_name("CODE_ARG")
-          .required_unless_present("help"),
+          .value_hint(ValueHint::FilePath)
+          .help_heading(COMPILE_HEADING),
       )
-      .arg(env_file_arg())
-  })
-}
-
-fn fmt_subcommand() -> Command {
-  command(
-    "fmt",
-    cstr!("Auto-format various file types.
-  <p(245)>deno fmt myfile1.ts myfile2.ts</>
-
-Supported file types are:
-  <p(245)>JavaScript, TypeScript, Markdown, JSON(C) and Jupyter Notebooks</>
-
-Supported file types which are behind corresponding unstable flags (see formatting options):
-  <p(245)>HTML, CSS, SCSS, SASS, LESS, YAML, Svelte, Vue, Astro and Angular</>
-
-Format stdin and write to stdout:
-  <p(245)>cat file.ts | deno fmt -</>
-
-Check if the files are formatted:
-  <p(245)>deno fmt --check</>
-
-Ignore formatting code by preceding it with an ignore comment:
-  <p(245)>// deno-fmt-ignore</>
-
-Ignore formatting a file by adding an ignore comment at the top of the file:
-  <p(245)>// deno-fmt-ignore-file</>
-
-<y>Read more:</> <c>https://docs.deno.com/go/fmt</>"),
-    UnstableArgsConfig::None,
-  )
-  .defer(|cmd| {
-    cmd
-      .arg(config_arg())
-      .arg(no_config_arg())
       .arg(
-         Arg::new("check")
-          .long("check")
-          .help("Check if the source files are formatted")
-          .num_args(0)
-          .help_heading(FMT_HEADING),
+        Arg::new("output")
+          .long("output")
+          .short('o')
+          .value_parser(value_parser!(String))
+          .help(cstr!("Output file <p(245)>(defaults to $PWD/<<inferred-name>>)</>"))
+          .value_hint(ValueHint::FilePath)
+          .help_heading(COMPILE_HEADING),
       )
       .arg(
-        Arg::new("ext")
-          .long("ext")
-          .help("Set content type of the supplied file")
+        Arg::new("target")
+          .long("target")
+          .help("Target OS architecture")
           .value_parser([
-            "ts", "tsx", "js", "jsx", "md", "json", "jsonc", "css", "scss",
-            "sass", "less", "html", "svelte", "vue", "astro", "yml", "yaml",
-            "ipynb",
+            "x86_64-unknown-linux-gnu",
+            "aarch64-unknown-linux-gnu",
+            "x86_64-pc-windows-msvc",
+            "x86_64-apple-darwin",
+            "aarch64-apple-darwin",
           ])
-          .help_heading(FMT_HEADING),
+          .help_heading(COMPILE_HEADING),
       )
       .arg(
-        Arg::new("ignore")
-          .long("ignore")
-          .num_args(1..)
-          .action(ArgAction::Append)
-          .require_equals(true)
-          .help("Ignore formatting particular source files")
-          .value_hint(ValueHint::AnyPath)
-          .help_heading(FMT_HEADING),
+        Arg::new("no-terminal")
+          .long("no-terminal")
+          .help("Hide terminal on Windows")
+          .action(ArgAction::SetTrue)
+          .help_heading(COMPILE_HEADING),
       )
       .arg(
-        Arg::new("files")
-          .num_args(1..)
-          .action(ArgAction::Append)
-          .value_hint(ValueHint::AnyPath),
+        Arg::new("icon")
+          .long("icon")
+          .help("Set the icon of the executable on Windows (.ico)")
+          .value_parser(value_parser!(String))
+          .help_heading(COMPILE_HEADING),
       )
-      .arg(watch_arg(false))
-      .arg(watch_exclude_arg())
-      .arg(no_clear_screen_arg())
       .arg(
-        Arg::new("use-tabs")
-          .long("use-tabs")
-          .alias("options-use-tabs")
-          .num_args(0..=1)
-          .value_parser(value_parser!(bool))
-          .default_missing_value("true")
-          .require_equals(true)
-          .help(
-          cstr!(  "Use tabs instead of spaces for indentation <p(245)>[default: false]</>"),
-          )
-          .help_heading(FMT_HEADING),
+        Arg::new("no-licenses")
+          .long("no-licenses")
+          .help("Don't embed the licenses of npm packages, which the executable prints when run with --licenses")
+          .action(ArgAction::SetTrue)
+          .help_heading(COMPILE_HEADING),
       )
       .arg(
-        Arg::new("line-width")
-          .long("line-width")
-          .alias("options-line-width")
-          .help(cstr!("Define maximum line width <p(245)>[default: 80]</>"))
-          .value_parser(value_parser!(NonZeroU32))
-          .help_heading(FMT_HEADING),
+        Arg::new("require-lock-integrity")
+          .long("require-lock-integrity")
+          .help("Fail if a remote module has no integrity in the lockfile or is imported by a mutable specifier, like an unversioned URL or a branch")
+          .action(ArgAction::SetTrue)
+          .conflicts_with("no-lock")
+          .help_heading(COMPILE_HEADING),
       )
       .arg(
-        Arg::new("indent-width")
-          .long("indent-width")
-          .alias("options-indent-width")
-          .help(cstr!("Define indentation width <p(245)>[default: 2]</>"))
-          .value_parser(value_parser!(NonZeroU8))
-          .help_heading(FMT_HEADING),
+        Arg::new("reproducible")
+          .long("reproducible")
+          .help(cstr!("Make builds from the same inputs byte-identical, by normalizing the order of the embedded modules and metadata and the timestamp of the executable
+  <p(245)>The timestamp is taken from the SOURCE_DATE_EPOCH environment variable, or the Unix epoch.</>"))
+          .action(ArgAction::SetTrue)
+          .help_heading(COMPILE_HEADING),
       )
       .arg(
-        Arg::new("single-quote")
-          .long("single-quote")
-          .alias("options-single-quote")
-          .num_args(0..=1)
-          .value_parser(value_parser!(bool))
-          .default_missing_value("true")
+        Arg::new("installer")
+          .long("installer")
           .require_equals(true)
-          .help(cstr!("Use single quotes <p(245)>[default: false]</>"))
-          .help_heading(FMT_HEADING),
+          .value_parser(["deb", "pkg", "msi"])
+          .conflicts_with("watch")
+          .help(cstr!("Also create an installer package which puts the executable on the PATH
+  <p(245)>deb is created for Linux targets, pkg requires pkgbuild on macOS and msi requires the WiX Toolset v4 CLI.</>"))
+          .help_heading(COMPILE_HEADING),
       )
       .arg(
-        Arg::new("prose-wrap")
-          .long("prose-wrap")
-          .alias("options-prose-wrap")
-          .value_parser(["always", "never", "preserve"])
-          .help(cstr!("Define how prose should be wrapped <p(245)>[default: always]</>"))
-          .help_heading(FMT_HEADING),
+        Arg::new("installer-version")
+          .long("installer-version")
+          .require_equals(true)
+          .requires("installer")
+          .value_name("VERSION")
+          .help("Version of the installer package [default: the version in the config file or 0.1.0]")
+          .help_heading(COMPILE_HEADING),
       )
       .arg(
-        Arg::new("no-semicolons")
-          .long("no-semicolons")
-          .alias("options-no-semicolons")
-          .num_args(0..=1)
-          .value_parser(value_parser!(bool))
-          .default_missing_value("true")
+        Arg::new("installer-license")
+          .long("installer-license")
           .require_equals(true)
-          .help(
-           cstr!("Don't use semicolons except where necessary <p(245)>[default: false]</>"),
-          )
-          .help_heading(FMT_HEADING),
+          .requires("installer")
+          .value_name("FILE")
+          .value_hint(ValueHint::FilePath)
+          .help("License file installed next to the executable by the installer package")
+          .help_heading(COMPILE_HEADING),
       )
       .arg(
-        Arg::new("unstable-css")
-          .long("unstable-css")
-          .help("Enable formatting CSS, SCSS, Sass and Less files")
-          .value_parser(FalseyValueParser::new())
-          .action(ArgAction::SetTrue)
-          .help_heading(FMT_HEADING)
-          .hide(true),
+        watch_arg(false)
+          .help("Watch for file changes and rebuild the executable automatically.")
       )
+      .arg(watch_exclude_arg())
+      .arg(watch_debounce_arg())
+      .arg(watch_poll_interval_arg())
+      .arg(watch_exec_arg())
       .arg(
-        Arg::new("unstable-html")
-          .long("unstable-html")
-          .help("Enable formatting HTML files")
-          .value_parser(FalseyValueParser::new())
+        Arg::new("watch-run")
+          .requires("watch")
+          .long("watch-run")
+          .help("Run the executable after each build, with the arguments passed after the script. The previous run is stopped on file changes")
           .action(ArgAction::SetTrue)
-          .help_heading(FMT_HEADING)
-          .hide(true),
+          .help_heading(FILE_WATCHING_HEADING),
       )
+      .arg(no_clear_screen_arg())
+      .arg(executable_ext_arg())
+      .arg(env_file_arg())
       .arg(
-        Arg::new("unstable-component")
-          .long("unstable-component")
-          .help("Enable formatting Svelte, Vue, Astro and Angular files")
-          .value_parser(FalseyValueParser::new())
-          .action(ArgAction::SetTrue)
-          .help_heading(FMT_HEADING),
-      )
-      .arg(
-        Arg::new("unstable-yaml")
-          .long("unstable-yaml")
-          .help("Enable formatting YAML files")
-          .value_parser(FalseyValueParser::new())
-          .action(ArgAction::SetTrue)
-          .help_heading(FMT_HEADING)
-          .hide(true),
+        script_arg()
+          .required_unless_present("help")
+          .trailing_var_arg(true),
       )
   })
 }
 
-fn init_subcommand() -> Command {
-  command("init", "scaffolds a basic Deno project with a script, test, and configuration file", UnstableArgsConfig::None).defer(
-    |cmd| {
-      cmd
-        .arg(Arg::new("dir").value_hint(ValueHint::DirPath))
-        .arg(
-          Arg::new("lib")
-            .long("lib")
-            .help("Generate an example library project")
-            .action(ArgAction::SetTrue),
-        )
-        .arg(
-          Arg::new("serve")
-            .long("serve")
-            .help("Generate an example project for `deno serve`")
-            .conflicts_with("lib")
-            .action(ArgAction::SetTrue),
-        )
-    },
+fn completions_subcommand() -> Command {
+  command(
+    "completions",
+    cstr!(
+      "Output shell completion script to standard output.
+
+  <p(245)>deno completions bash > /usr/local/etc/bash_completion.d/deno.bash</>
+  <p(245)>source /usr/local/etc/bash_completion.d/deno.bash</>"
+    ),
+    UnstableArgsConfig::None,
   )
+  .defer(|cmd| {
+    cmd.disable_help_subcommand(true).arg(
+      Arg::new("shell")
+        .value_parser(["bash", "fish", "powershell", "zsh", "fig"])
+        .required_unless_present("help"),
+    )
+  })
 }
 
-fn info_subcommand() -> Command {
-  command("info",
-      cstr!("Show information about a module or the cache directories.
+fn coverage_subcommand() -> Command {
+  command(
+    "coverage",
+    cstr!("Print coverage reports from coverage profiles.
 
-Get information about a module:
-  <p(245)>deno info jsr:@std/http/file-server</>
+Collect a coverage profile with deno test:
+  <p(245)>deno test --coverage=cov_profile</>
 
-The following information is shown:
-  local: Local path of the file
-  type: JavaScript, TypeScript, or JSON
-  emit: Local path of compiled source code (TypeScript only)
-  dependencies: Dependency tree of the source file
+Print a report to stdout:
+  <p(245)>deno coverage cov_profile</>
 
-<y>Read more:</> <c>https://docs.deno.com/go/info</>"),
-          UnstableArgsConfig::ResolutionOnly
-    )
-    .defer(|cmd| cmd
-      .arg(Arg::new("file").value_hint(ValueHint::FilePath))
-      .arg(reload_arg().requires("file"))
-      .arg(ca_file_arg())
-      .arg(unsafely_ignore_certificate_errors_arg())
+Include urls that start with the file schema and exclude files ending with <c>test.ts</> and <c>test.js</>,
+for an url to match it must match the include pattern and not match the exclude pattern:
+  <p(245)>deno coverage --include=\"^file:\" --exclude=\"test\\.(ts|js)\" cov_profile</>
+
+Write a report using the lcov format:
+  <p(245)>deno coverage --lcov --output=cov.lcov cov_profile/</>
+
+Generate html reports from lcov:
+  <p(245)>genhtml -o html_cov cov.lcov</>
+
+Only report lines changed since a git ref and fail if less than 90% of them are covered:
+  <p(245)>deno coverage --changed-since=main --threshold=90 cov_profile</>
+
+Record the coverage of each run and show how it changed in each directory over the last runs:
+  <p(245)>deno coverage --history=coverage_history.jsonl --trend cov_profile</>
+
+<y>Read more:</> <c>https://docs.deno.com/go/coverage</>"),
+    UnstableArgsConfig::None,
+  )
+  .defer(|cmd| {
+    cmd
       .arg(
-        location_arg()
-          .conflicts_with("file")
-          .help(cstr!("Show files used for origin bound APIs like the Web Storage API when running a script with <c>--location=<<HREF>></>"))
+        Arg::new("ignore")
+          .long("ignore")
+          .num_args(1..)
+          .action(ArgAction::Append)
+          .require_equals(true)
+          .help("Ignore coverage files")
+          .value_hint(ValueHint::AnyPath),
       )
-      .arg(no_check_arg().hide(true)) // TODO(lucacasonato): remove for 2.0
-      .arg(no_config_arg())
-      .arg(no_remote_arg())
-      .arg(no_npm_arg())
-      .arg(lock_arg())
-      .arg(no_lock_arg())
-      .arg(config_arg())
-      .arg(import_map_arg())
-      .arg(node_modules_dir_arg())
-      .arg(vendor_arg())
       .arg(
-        Arg::new("json")
-          .long("json")
-          .help("UNSTABLE: Outputs the information in JSON format")
+        Arg::new("include")
+          .long("include")
+          .num_args(1..)
+          .action(ArgAction::Append)
+          .value_name("regex")
+          .require_equals(true)
+          .default_value(DEFAULT_COVERAGE_INCLUDE)
+          .help("Include source files in the report"),
+      )
+      .arg(
+        Arg::new("exclude")
+          .long("exclude")
+          .num_args(1..)
+          .action(ArgAction::Append)
+          .value_name("regex")
+          .require_equals(true)
+          .default_value(DEFAULT_COVERAGE_EXCLUDE)
+          .help("Exclude source files from the report"),
+      )
+      .arg(
+        Arg::new("lcov")
+          .long("lcov")
+          .help("Output coverage report in lcov format")
           .action(ArgAction::SetTrue),
-      ))
-      .arg(allow_import_arg())
+      )
+      .arg(
+        Arg::new("output")
+          .requires("lcov")
+          .long("output")
+          .value_parser(value_parser!(String))
+          .help(
+            cstr!("Exports the coverage report in lcov format to the given file.
+  <p(245)>If no --output arg is specified then the report is written to stdout.</>",
+          ))
+          .require_equals(true)
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(
+        Arg::new("html")
+          .long("html")
+          .help("Output coverage report in HTML format in the given directory")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("detailed")
+          .long("detailed")
+          .help("Output coverage report in detailed format in the terminal")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("changed-since")
+          .long("changed-since")
+          .value_name("REF")
+          .require_equals(true)
+          .help("Only report lines added or modified since the given git ref"),
+      )
+      .arg(
+        Arg::new("threshold")
+          .long("threshold")
+          .value_name("PERCENT")
+          .require_equals(true)
+          .value_parser(value_parser!(u8).range(0..=100))
+          .help("Fail if the line coverage of the report is below this percentage"),
+      )
+      .arg(
+        Arg::new("history")
+          .long("history")
+          .value_name("FILE")
+          .require_equals(true)
+          .help("Append the line coverage of each directory to this JSON lines file")
+          .value_hint(ValueHint::FilePath),
+      )
+      .arg(
+        Arg::new("trend")
+          .long("trend")
+          .value_name("RUNS")
+          .num_args(0..=1)
+          .require_equals(true)
+          .default_missing_value("5")
+          .value_parser(value_parser!(NonZeroUsize))
+          .requires("history")
+          .help(cstr!("Print the change in coverage of each directory compared to this many previous runs of the history <p(245)>[default: 5]</>")),
+      )
+      .arg(
+        Arg::new("include-untested")
+          .long("include-untested")
+          .help("Report the modules of the project which no test loaded as 0% covered, instead of leaving them out")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("files")
+          .num_args(0..)
+          .action(ArgAction::Append)
+          .value_hint(ValueHint::AnyPath),
+      )
+  })
 }
 
-fn install_subcommand() -> Command {
-  command("install", cstr!("Installs dependencies either in the local project or globally to a bin directory.
-
-<g>Local installation</>
-
-Add dependencies to the local project's configuration (<p(245)>deno.json / package.json</>) and installs them
-in the package cache. If no dependency is specified, installs all dependencies listed in the config file.
-If the <p(245)>--entrypoint</> flag is passed, installs the dependencies of the specified entrypoint(s).
-
-  <p(245)>deno install</>
-  <p(245)>deno install @std/bytes</>
-  <p(245)>deno install npm:chalk</>
-  <p(245)>deno install --entrypoint entry1.ts entry2.ts</>
-
-<g>Global installation</>
+fn doc_subcommand() -> Command {
+  command("doc",
+      cstr!("Show documentation for a module.
 
-If the <bold>--global</> flag is set, installs a script as an executable in the installation root's bin directory.
+Output documentation to standard output:
+    <p(245)>deno doc ./path/to/module.ts</>
 
-  <p(245)>deno install --global --allow-net --allow-read jsr:@std/http/file-server</>
-  <p(245)>deno install -g https://examples.deno.land/color-logging.ts</>
+Output documentation in HTML format:
+    <p(245)>deno doc --html --name=\"My library\" ./path/to/module.ts</>
 
-To change the executable name, use <c>-n</>/<c>--name</>:
-  <p(245)>deno install -g --allow-net --allow-read -n serve jsr:@std/http/file-server</>
+Output an OpenAPI document for the routes of a deno serve entrypoint:
+    <p(245)>deno doc --json --openapi ./main.ts</>
 
-The executable name is inferred by default:
-  - Attempt to take the file stem of the URL path. The above example would
-    become <p(245)>file_server</>.
-  - If the file stem is something generic like <p(245)>main</>, <p(245)>mod</>, <p(245)>index</> or <p(245)>cli</>,
-    and the path has no parent, take the file name of the parent path. Otherwise
-    settle with the generic name.
-  - If the resulting name has an <p(245)>@...</> suffix, strip it.
+Lint a module for documentation diagnostics:
+    <p(245)>deno doc --lint ./path/to/module.ts</>
 
-To change the installation root, use <c>--root</>:
-  <p(245)>deno install -g --allow-net --allow-read --root /usr/local jsr:@std/http/file-server</>
+Target a specific symbol:
+    <p(245)>deno doc ./path/to/module.ts MyClass.someField</>
 
-The installation root is determined, in order of precedence:
-  - <p(245)>--root</> option
-  - <p(245)>DENO_INSTALL_ROOT</> environment variable
-  - <p(245)>$HOME/.deno</>
+Show documentation for runtime built-ins:
+    <p(245)>deno doc</>
+    <p(245)>deno doc --filter Deno.Listener</>
 
-These must be added to the path manually if required."), UnstableArgsConfig::ResolutionAndRuntime)
-    .visible_alias("i")
+<y>Read more:</> <c>https://docs.deno.com/go/doc</>"),
+          UnstableArgsConfig::ResolutionOnly
+    )
     .defer(|cmd| {
-      permission_args(runtime_args(cmd, false, true), Some("global"))
-        .arg(check_arg(true))
-        .arg(allow_scripts_arg())
+      cmd
+        .arg(import_map_arg())
+        .arg(reload_arg())
+        .arg(lock_arg())
+        .arg(no_lock_arg())
+        .arg(no_npm_arg())
+        .arg(no_remote_arg())
+        .arg(allow_import_arg())
         .arg(
-          Arg::new("cmd")
-            .required_if_eq("global", "true")
-            .required_if_eq("entrypoint", "true")
-            .num_args(1..)
-            .value_hint(ValueHint::FilePath),
+          Arg::new("json")
+            .long("json")
+            .help("Output documentation in JSON format")
+            .action(ArgAction::SetTrue)
+            .help_heading(DOC_HEADING),
         )
         .arg(
-          Arg::new("name")
+          Arg::new("html")
+            .long("html")
+            .help("Output documentation in HTML format")
+            .action(ArgAction::SetTrue)
+            .display_order(1000)
+            .conflicts_with("json").help_heading(DOC_HEADING)
+        )
+        .arg(
+          Arg::new("name")
             .long("name")
-            .short('n')
-            .requires("global")
-            .help("Executable file name"),
+            .help("The name that will be used in the docs (ie for breadcrumbs)")
+            .action(ArgAction::Set)
+            .require_equals(true).help_heading(DOC_HEADING)
         )
         .arg(
-          Arg::new("root")
-            .long("root")
-            .requires("global")
-            .help("Installation root")
-            .value_hint(ValueHint::DirPath),
+          Arg::new("category-docs")
+            .long("category-docs")
+            .help("Path to a JSON file keyed by category and an optional value of a markdown doc")
+            .requires("html")
+            .action(ArgAction::Set)
+            .require_equals(true).help_heading(DOC_HEADING)
         )
         .arg(
-          Arg::new("force")
-            .long("force")
-            .requires("global")
-            .short('f')
-            .help("Forcefully overwrite existing installation")
-            .action(ArgAction::SetTrue),
+          Arg::new("symbol-redirect-map")
+            .long("symbol-redirect-map")
+            .help("Path to a JSON file keyed by file, with an inner map of symbol to an external link")
+            .requires("html")
+            .action(ArgAction::Set)
+            .require_equals(true).help_heading(DOC_HEADING)
         )
         .arg(
-          Arg::new("global")
-            .long("global")
-            .short('g')
-            .help("Install a package or script as a globally available executable")
-            .action(ArgAction::SetTrue),
+          Arg::new("strip-trailing-html")
+            .long("strip-trailing-html")
+            .help("Remove trailing .html from various links. Will still generate files with a .html extension")
+            .requires("html")
+            .action(ArgAction::SetTrue).help_heading(DOC_HEADING)
         )
         .arg(
-          Arg::new("entrypoint")
-            .long("entrypoint")
-            .short('e')
-            .conflicts_with("global")
+          Arg::new("default-symbol-map")
+            .long("default-symbol-map")
+            .help("Uses the provided mapping of default name to wanted name for usage blocks")
+            .requires("html")
+            .action(ArgAction::Set)
+            .require_equals(true).help_heading(DOC_HEADING)
+        )
+        .arg(
+          Arg::new("output")
+            .long("output")
+            .help("Directory for HTML documentation output")
+            .action(ArgAction::Set)
+            .require_equals(true)
+            .value_hint(ValueHint::DirPath)
+            .value_parser(value_parser!(String)).help_heading(DOC_HEADING)
+        )
+        .arg(
+          Arg::new("private")
+            .long("private")
+            .help("Output private documentation")
+            .action(ArgAction::SetTrue).help_heading(DOC_HEADING),
+        )
+        .arg(
+          Arg::new("filter")
+            .long("filter")
+            .help("Dot separated path to symbol")
+            .conflicts_with("json")
+            .conflicts_with("lint")
+            .conflicts_with("html").help_heading(DOC_HEADING),
+        )
+        .arg(
+          Arg::new("lint")
+            .long("lint")
+            .help("Output documentation diagnostics.")
+            .action(ArgAction::SetTrue).help_heading(DOC_HEADING),
+        )
+        .arg(
+          Arg::new("only-deprecated")
+            .long("only-deprecated")
+            .help("Only document the symbols marked with a @deprecated JSDoc tag")
+            .conflicts_with("lint")
+            .action(ArgAction::SetTrue).help_heading(DOC_HEADING),
+        )
+        .arg(
+          Arg::new("min-since")
+            .long("min-since")
+            .value_name("VERSION")
+            .require_equals(true)
+            .help("Only document the symbols with a @since JSDoc tag of VERSION or later")
+            .conflicts_with("lint")
+            .help_heading(DOC_HEADING),
+        )
+        .arg(
+          Arg::new("openapi")
+            .long("openapi")
+            .help("Output an OpenAPI 3.1 document for the routes of a deno serve entrypoint")
+            .requires("json")
+            .conflicts_with("lint")
+            .conflicts_with("only-deprecated")
+            .conflicts_with("min-since")
             .action(ArgAction::SetTrue)
-            .help("Install dependents of the specified entrypoint(s)"),
+            .help_heading(DOC_HEADING),
+        )
+        // TODO(nayeemrmn): Make `--builtin` a proper option. Blocked by
+        // https://github.com/clap-rs/clap/issues/1794. Currently `--builtin` is
+        // just a possible value of `source_file` so leading hyphens must be
+        // enabled.
+        .allow_hyphen_values(true)
+        .arg(
+          Arg::new("source_file")
+            .num_args(1..)
+            .action(ArgAction::Append)
+            .value_hint(ValueHint::FilePath)
+            .required_if_eq_any([("html", "true"), ("lint", "true")]),
         )
-        .arg(env_file_arg())
-        .arg(add_dev_arg().conflicts_with("entrypoint").conflicts_with("global"))
     })
 }
 
-fn json_reference_subcommand() -> Command {
-  Command::new("json_reference").hide(true)
-}
+fn eval_subcommand() -> Command {
+  command(
+    "eval",
+    cstr!(
+      "Evaluate JavaScript from the command line.
+  <p(245)>deno eval \"console.log('hello world')\"</>
 
-fn jupyter_subcommand() -> Command {
-  command("jupyter", "Deno kernel for Jupyter notebooks", UnstableArgsConfig::ResolutionAndRuntime)
-    .arg(
-      Arg::new("install")
-        .long("install")
-        .help("Installs kernelspec, requires 'jupyter' command to be available.")
-        .conflicts_with("kernel")
-        .action(ArgAction::SetTrue)
-    )
-    .arg(
-      Arg::new("kernel")
-        .long("kernel")
-        .help("Start the kernel")
-        .conflicts_with("install")
-        .requires("conn")
-        .action(ArgAction::SetTrue)
-    )
-    .arg(
-      Arg::new("conn")
-        .long("conn")
-        .help("Path to JSON file describing connection parameters, provided by Jupyter")
-        .value_parser(value_parser!(String))
-        .value_hint(ValueHint::FilePath)
-        .conflicts_with("install"))
-}
+To evaluate as TypeScript:
+  <p(245)>deno eval --ext=ts \"const v: string = 'hello'; console.log(v)\"</>
 
-fn uninstall_subcommand() -> Command {
-  command(
-    "uninstall",
-    cstr!("Uninstalls a dependency or an executable script in the installation root's bin directory.
-  <p(245)>deno uninstall @std/dotenv chalk</>
-  <p(245)>deno uninstall --global file_server</>
+To evaluate multiple snippets in order:
+  <p(245)>deno eval -e \"const a = 1\" -e \"console.log(a + 1)\"</>
 
-To change the installation root, use <c>--root</> flag:
-  <p(245)>deno uninstall --global --root /usr/local serve</>
+To expose stdin parsed as JSON to the snippets as <c>$stdin</>:
+  <p(245)>echo '{\"a\": 1}' | deno eval -p --input=json '$stdin.a'</>
 
-The installation root is determined, in order of precedence:
-  - <p(245)>--root</> option
-  - <p(245)>DENO_INSTALL_ROOT</> environment variable
-  - <p(245)>$HOME/.deno</>"),
-    UnstableArgsConfig::None,
+This command has implicit access to all permissions.
+
+<y>Read more:</> <c>https://docs.deno.com/go/eval</>"
+    ),
+    UnstableArgsConfig::ResolutionAndRuntime,
   )
   .defer(|cmd| {
-    cmd
-      .arg(Arg::new("name-or-package").required_unless_present("help"))
+    runtime_args(cmd, false, true)
+      .arg(check_arg(false))
+      .arg(executable_ext_arg())
       .arg(
-        Arg::new("root")
-          .long("root")
-          .help("Installation root")
-          .requires("global")
-          .value_hint(ValueHint::DirPath),
+        Arg::new("print")
+          .long("print")
+          .short('p')
+          .help("print result to stdout")
+          .action(ArgAction::SetTrue),
       )
       .arg(
-        Arg::new("global")
-          .long("global")
-          .short('g')
-          .help("Remove globally installed package or module")
-          .action(ArgAction::SetTrue),
+        Arg::new("eval")
+          .long("eval")
+          .short('e')
+          .action(ArgAction::Append)
+          .value_name("CODE")
+          .help("Code snippet to evaluate, can be repeated. When used, all positional arguments are passed to the script"),
       )
       .arg(
-        Arg::new("additional-packages")
-          .help("List of additional packages to remove")
-          .conflicts_with("global")
+        Arg::new("input")
+          .long("input")
+          .require_equals(true)
+          .value_parser(["json", "text"])
+          .help("Read stdin and expose it as $stdin, either parsed as JSON or as text"),
+      )
+      .arg(
+        Arg::new("code_arg")
           .num_args(1..)
           .action(ArgAction::Append)
+          .help("Code to evaluate")
+          .value_name("CODE_ARG")
+          .required_unless_present_any(["help", "eval"]),
       )
+      .arg(env_file_arg())
   })
 }
 
-fn lsp_subcommand() -> Command {
-  Command::new("lsp").about(
-    "The 'deno lsp' subcommand provides a way for code editors and IDEs to interact with Deno
-using the Language Server Protocol. Usually humans do not use this subcommand directly.
-For example, 'deno lsp' can provide IDEs with go-to-definition support and automatic code formatting.
-
-How to connect various editors and IDEs to 'deno lsp': https://docs.deno.com/go/lsp",
-  )
-}
-
-fn lint_subcommand() -> Command {
+fn fmt_subcommand() -> Command {
   command(
-    "lint",
-    cstr!("Lint JavaScript/TypeScript source code.
+    "fmt",
+    cstr!("Auto-format various file types.
+  <p(245)>deno fmt myfile1.ts myfile2.ts</>
 
-  <p(245)>deno lint</>
-  <p(245)>deno lint myfile1.ts myfile2.js</>
+Supported file types are:
+  <p(245)>JavaScript, TypeScript, Markdown, JSON(C) and Jupyter Notebooks</>
 
-Print result as JSON:
-  <p(245)>deno lint --json</>
+Supported file types which are behind corresponding unstable flags (see formatting options):
+  <p(245)>HTML, CSS, SCSS, SASS, LESS, YAML, Svelte, Vue, Astro and Angular</>
 
-Read from stdin:
-  <p(245)>cat file.ts | deno lint -</>
-  <p(245)>cat file.ts | deno lint --json -</>
+Format stdin and write to stdout:
+  <p(245)>cat file.ts | deno fmt -</>
 
-List available rules:
-  <p(245)>deno lint --rules</>
+Check if the files are formatted:
+  <p(245)>deno fmt --check</>
 
-To ignore specific diagnostics, you can write an ignore comment on the preceding line with a rule name (or multiple):
-  <p(245)>// deno-lint-ignore no-explicit-any</>
-  <p(245)>// deno-lint-ignore require-await no-empty</>
+Ignore formatting code by preceding it with an ignore comment:
+  <p(245)>// deno-fmt-ignore</>
 
-To ignore linting on an entire file, you can add an ignore comment at the top of the file:
-  <p(245)>// deno-lint-ignore-file</>
+Ignore formatting a file by adding an ignore comment at the top of the file:
+  <p(245)>// deno-fmt-ignore-file</>
 
-<y>Read more:</> <c>https://docs.deno.com/go/lint</>
-"),
-    UnstableArgsConfig::ResolutionOnly,
+<y>Read more:</> <c>https://docs.deno.com/go/fmt</>"),
+    UnstableArgsConfig::None,
   )
   .defer(|cmd| {
     cmd
+      .arg(config_arg())
+      .arg(no_config_arg())
       .arg(
-        Arg::new("fix")
-          .long("fix")
-          .help("Fix any linting errors for rules that support it")
-          .action(ArgAction::SetTrue)
-          .help_heading(LINT_HEADING),
+         Arg::new("check")
+          .long("check")
+          .help("Check if the source files are formatted")
+          .num_args(0)
+          .help_heading(FMT_HEADING),
       )
       .arg(
-            Arg::new("ext")
-                .long("ext")
-                .require_equals(true)
-                .value_name("EXT")
-                .help("Specify the file extension to lint when reading from stdin.\
-  For example, use `jsx` to lint JSX files or `tsx` for TSX files.\
-  This argument is necessary because stdin input does not automatically infer the file type.\
-  Example usage: `cat file.jsx | deno lint - --ext=jsx`."),
-        )
-        .arg(
-        Arg::new("rules")
-          .long("rules")
-          .help("List available rules")
-          .action(ArgAction::SetTrue)
-          .help_heading(LINT_HEADING),
+        Arg::new("output-file")
+          .long("output-file")
+          .value_name("PATH")
+          .value_hint(ValueHint::FilePath)
+          .require_equals(true)
+          .requires("check")
+          .help("Write the files which aren't formatted, with their diffs, to PATH as JSON")
+          .help_heading(FMT_HEADING),
       )
       .arg(
-        Arg::new("rules-tags")
-          .long("rules-tags")
+        Arg::new("ext")
+          .long("ext")
+          .help("Set content type of the supplied file")
+          .value_parser([
+            "ts", "tsx", "js", "jsx", "md", "json", "jsonc", "css", "scss",
+            "sass", "less", "html", "svelte", "vue", "astro", "yml", "yaml",
+            "ipynb",
+          ])
+          .help_heading(FMT_HEADING),
+      )
+      .arg(
+        Arg::new("ignore")
+          .long("ignore")
+          .num_args(1..)
+          .action(ArgAction::Append)
           .require_equals(true)
+          .help("Ignore formatting particular source files")
+          .value_hint(ValueHint::AnyPath)
+          .help_heading(FMT_HEADING),
+      )
+      .arg(
+        Arg::new("files")
           .num_args(1..)
           .action(ArgAction::Append)
-          .use_value_delimiter(true)
-          .help("Use set of rules with a tag")
-          .help_heading(LINT_HEADING),
+          .value_hint(ValueHint::AnyPath),
       )
+      .arg(watch_arg(false))
+      .arg(watch_exclude_arg())
+      .arg(watch_debounce_arg())
+      .arg(watch_poll_interval_arg())
+      .arg(watch_exec_arg())
+      .arg(no_clear_screen_arg())
       .arg(
-        Arg::new("rules-include")
-          .long("rules-include")
+        Arg::new("use-tabs")
+          .long("use-tabs")
+          .alias("options-use-tabs")
+          .num_args(0..=1)
+          .value_parser(value_parser!(bool))
+          .default_missing_value("true")
           .require_equals(true)
-          .num_args(1..)
-          .use_value_delimiter(true)
-          .conflicts_with("rules")
-          .help("Include lint rules")
-          .help_heading(LINT_HEADING),
+          .help(
+          cstr!(  "Use tabs instead of spaces for indentation <p(245)>[default: false]</>"),
+          )
+          .help_heading(FMT_HEADING),
       )
       .arg(
-        Arg::new("rules-exclude")
-          .long("rules-exclude")
+        Arg::new("line-width")
+          .long("line-width")
+          .alias("options-line-width")
+          .help(cstr!("Define maximum line width <p(245)>[default: 80]</>"))
+          .value_parser(value_parser!(NonZeroU32))
+          .help_heading(FMT_HEADING),
+      )
+      .arg(
+        Arg::new("indent-width")
+          .long("indent-width")
+          .alias("options-indent-width")
+          .help(cstr!("Define indentation width <p(245)>[default: 2]</>"))
+          .value_parser(value_parser!(NonZeroU8))
+          .help_heading(FMT_HEADING),
+      )
+      .arg(
+        Arg::new("single-quote")
+          .long("single-quote")
+          .alias("options-single-quote")
+          .num_args(0..=1)
+          .value_parser(value_parser!(bool))
+          .default_missing_value("true")
           .require_equals(true)
-          .num_args(1..)
-          .use_value_delimiter(true)
-          .conflicts_with("rules")
-          .help("Exclude lint rules")
-          .help_heading(LINT_HEADING),
+          .help(cstr!("Use single quotes <p(245)>[default: false]</>"))
+          .help_heading(FMT_HEADING),
       )
-      .arg(no_config_arg())
-      .arg(config_arg())
       .arg(
-        Arg::new("ignore")
-          .long("ignore")
+        Arg::new("prose-wrap")
+          .long("prose-wrap")
+          .alias("options-prose-wrap")
+          .value_parser(["always", "never", "preserve"])
+          .help(cstr!("Define how prose should be wrapped <p(245)>[default: always]</>"))
+          .help_heading(FMT_HEADING),
+      )
+      .arg(
+        Arg::new("no-semicolons")
+          .long("no-semicolons")
+          .alias("options-no-semicolons")
+          .num_args(0..=1)
+          .value_parser(value_parser!(bool))
+          .default_missing_value("true")
+          .require_equals(true)
+          .help(
+           cstr!("Don't use semicolons except where necessary <p(245)>[default: false]</>"),
+          )
+          .help_heading(FMT_HEADING),
+      )
+      .arg(
+        Arg::new("line-ending")
+          .long("line-ending")
+          .require_equals(true)
+          .value_parser(["lf", "crlf", "auto"])
+          .help(cstr!("Line endings of formatted files, where auto keeps the line endings of each file <p(245)>[default: lf]</>"))
+          .help_heading(FMT_HEADING),
+      )
+      .arg(
+        Arg::new("bom")
+          .long("bom")
+          .require_equals(true)
+          .value_parser(["preserve", "strip"])
+          .help(cstr!("Whether to keep or remove the byte order mark of files <p(245)>[default: preserve]</>"))
+          .help_heading(FMT_HEADING),
+      )
+      .arg(
+        Arg::new("skip-code-blocks")
+          .long("skip-code-blocks")
+          .value_name("LANGUAGES")
+          .require_equals(true)
           .num_args(1..)
+          .use_value_delimiter(true)
           .action(ArgAction::Append)
-          .require_equals(true)
-          .help("Ignore linting particular source files")
-          .value_hint(ValueHint::AnyPath)
-          .help_heading(LINT_HEADING),
+          .help("Languages of the code blocks in Markdown files to leave unformatted, e.g. --skip-code-blocks=json,yaml")
+          .help_heading(FMT_HEADING),
       )
       .arg(
-        Arg::new("json")
-          .long("json")
-          .help("Output lint result in JSON format")
+        Arg::new("unstable-css")
+          .long("unstable-css")
+          .help("Enable formatting CSS, SCSS, Sass and Less files")
+          .value_parser(FalseyValueParser::new())
           .action(ArgAction::SetTrue)
-          .help_heading(LINT_HEADING),
+          .help_heading(FMT_HEADING)
+          .hide(true),
       )
       .arg(
-        Arg::new("compact")
-          .long("compact")
-          .help("Output lint result in compact format")
+        Arg::new("unstable-html")
+          .long("unstable-html")
+          .help("Enable formatting HTML files")
+          .value_parser(FalseyValueParser::new())
           .action(ArgAction::SetTrue)
-          .conflicts_with("json")
-          .help_heading(LINT_HEADING),
+          .help_heading(FMT_HEADING)
+          .hide(true),
       )
       .arg(
-        Arg::new("files")
-          .num_args(1..)
-          .action(ArgAction::Append)
-          .value_hint(ValueHint::AnyPath),
+        Arg::new("unstable-component")
+          .long("unstable-component")
+          .help("Enable formatting Svelte, Vue, Astro and Angular files")
+          .value_parser(FalseyValueParser::new())
+          .action(ArgAction::SetTrue)
+          .help_heading(FMT_HEADING),
+      )
+      .arg(
+        Arg::new("unstable-yaml")
+          .long("unstable-yaml")
+          .help("Enable formatting YAML files")
+          .value_parser(FalseyValueParser::new())
+          .action(ArgAction::SetTrue)
+          .help_heading(FMT_HEADING)
+          .hide(true),
       )
-      .arg(watch_arg(false))
-      .arg(watch_exclude_arg())
-      .arg(no_clear_screen_arg())
   })
 }
 
-fn repl_subcommand() -> Command {
-  command("repl", cstr!(
-    "Starts a read-eval-print-loop, which lets you interactively build up program state in the global context.
-It is especially useful for quick prototyping and checking snippets of code.
+fn init_subcommand() -> Command {
+  command("init", "scaffolds a basic Deno project with a script, test, and configuration file", UnstableArgsConfig::None).defer(
+    |cmd| {
+      cmd
+        .arg(Arg::new("dir").value_hint(ValueHint::DirPath))
+        .arg(
+          Arg::new("lib")
+            .long("lib")
+            .help("Generate an example library project")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("serve")
+            .long("serve")
+            .help("Generate an example project for `deno serve`")
+            .conflicts_with("lib")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("template")
+            .long("template")
+            .value_name("NAME_OR_URL")
+            .help(cstr!("Scaffold the project from a template: <p(245)>script</>, <p(245)>lib</>, <p(245)>serve</>, <p(245)>fresh</>, a git repository url or a local directory"))
+            .conflicts_with_all(["lib", "serve"])
+            .value_hint(ValueHint::Url),
+        )
+        .arg(
+          Arg::new("npm-compat")
+            .long("npm-compat")
+            .help("Generate a project with a package.json and a node_modules directory, for migrating from Node.js")
+            .conflicts_with_all(["lib", "serve", "template"])
+            .action(ArgAction::SetTrue),
+        )
+    },
+  )
+}
 
-TypeScript is supported, however it is not type-checked, only transpiled."
-  ), UnstableArgsConfig::ResolutionAndRuntime)
-    .defer(|cmd| runtime_args(cmd, true, true)
-      .arg(check_arg(false))
+fn info_subcommand() -> Command {
+  command("info",
+      cstr!("Show information about a module or the cache directories.
+
+Get information about a module:
+  <p(245)>deno info jsr:@std/http/file-server</>
+
+Output the module graph as a Graphviz or Mermaid diagram:
+  <p(245)>deno info --graph=dot main.ts | dot -Tsvg > graph.svg</>
+  <p(245)>deno info --graph=mermaid --depth=2 --collapse-npm main.ts</>
+
+The following information is shown:
+  local: Local path of the file
+  type: JavaScript, TypeScript, or JSON
+  emit: Local path of compiled source code (TypeScript only)
+  dependencies: Dependency tree of the source file
+
+<y>Read more:</> <c>https://docs.deno.com/go/info</>"),
+          UnstableArgsConfig::ResolutionOnly
+    )
+    .defer(|cmd| cmd
+      .arg(Arg::new("file").value_hint(ValueHint::FilePath))
+      .arg(reload_arg().requires("file"))
+      .arg(ca_file_arg())
+      .arg(unsafely_ignore_certificate_errors_arg())
       .arg(
-        Arg::new("eval-file")
-          .long("eval-file")
-          .num_args(1..)
-          .action(ArgAction::Append)
-          .require_equals(true)
-          .help("Evaluates the provided file(s) as scripts when the REPL starts. Accepts file paths and URLs")
-          .value_hint(ValueHint::AnyPath),
+        location_arg()
+          .conflicts_with("file")
+          .help(cstr!("Show files used for origin bound APIs like the Web Storage API when running a script with <c>--location=<<HREF>></>"))
       )
-      .arg(
-        Arg::new("eval")
-          .long("eval")
-          .help("Evaluates the provided code when the REPL starts")
-          .value_name("code"),
+      .arg(no_check_arg().hide(true)) // TODO(lucacasonato): remove for 2.0
+      .arg(no_config_arg())
+      .arg(no_remote_arg())
+      .arg(no_npm_arg())
+      .arg(lock_arg())
+      .arg(no_lock_arg())
+      .arg(npmrc_arg())
+      .arg(config_arg())
+      .arg(import_map_arg())
+      .arg(node_modules_dir_arg())
+      .arg(vendor_arg())
+      .arg(vendor_npm_arg())
+      .arg(
+        Arg::new("json")
+          .long("json")
+          .help("UNSTABLE: Outputs the information in JSON format")
+          .action(ArgAction::SetTrue),
       )
-      .after_help(cstr!("<y>Environment variables:</>
-  <g>DENO_REPL_HISTORY</>  Set REPL history file path. History file is disabled when the value is empty.
-                       <p(245)>[default: $DENO_DIR/deno_history.txt]</>"))
-    )
-    .arg(env_file_arg())
-    .arg(
-      Arg::new("args")
-        .num_args(0..)
-        .action(ArgAction::Append)
-        .value_name("ARGS")
-        .last(true)
-    )
+      .arg(
+        Arg::new("http-cache")
+          .long("http-cache")
+          .conflicts_with_all(["file", "location"])
+          .help("List the remote modules in the cache with their age, size and headers")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("cache-usage")
+          .long("cache-usage")
+          .visible_alias("storage")
+          .conflicts_with_all(["file", "location", "http-cache"])
+          .help("Report the disk usage of the caches, the largest cached npm packages and the growth since the last report")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("graph")
+          .long("graph")
+          .require_equals(true)
+          .value_name("FORMAT")
+          .value_parser(["dot", "mermaid"])
+          .requires("file")
+          .conflicts_with("json")
+          .help("Output the module graph as a Graphviz DOT or Mermaid diagram"),
+      )
+      .arg(
+        Arg::new("depth")
+          .long("depth")
+          .require_equals(true)
+          .value_name("DEPTH")
+          .value_parser(value_parser!(usize))
+          .requires("graph")
+          .help("Leave out the dependencies deeper than DEPTH from the root module of the diagram"),
+      )
+      .arg(
+        Arg::new("collapse-npm")
+          .long("collapse-npm")
+          .requires("graph")
+          .help("Show npm packages as single nodes without their dependencies in the diagram")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("unused")
+          .long("unused")
+          .conflicts_with_all(["file", "location", "http-cache", "cache-usage"])
+          .help("Report the imports of the deno.json and the dependencies of the package.json which no module of the workspace imports")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("fix")
+          .long("fix")
+          .requires("unused")
+          .help("Remove the unused imports and dependencies from the configuration files")
+          .action(ArgAction::SetTrue),
+      ))
+      .arg(allow_import_arg())
 }
 
-fn run_args(command: Command, top_level: bool) -> Command {
-  runtime_args(command, true, true)
-    .arg(check_arg(false))
-    .arg(watch_arg(true))
-    .arg(hmr_arg(true))
-    .arg(watch_exclude_arg())
-    .arg(no_clear_screen_arg())
-    .arg(executable_ext_arg())
-    .arg(if top_level {
-      script_arg().trailing_var_arg(true).hide(true)
-    } else {
-      script_arg().trailing_var_arg(true)
-    })
-    .arg(env_file_arg())
-    .arg(no_code_cache_arg())
-}
+fn install_subcommand() -> Command {
+  command("install", cstr!("Installs dependencies either in the local project or globally to a bin directory.
 
-fn run_subcommand() -> Command {
-  run_args(command("run", cstr!("Run a JavaScript or TypeScript program, or a task or script.
+<g>Local installation</>
 
-By default all programs are run in sandbox without access to disk, network or ability to spawn subprocesses.
-  <p(245)>deno run https://examples.deno.land/hello-world.ts</>
+Add dependencies to the local project's configuration (<p(245)>deno.json / package.json</>) and installs them
+in the package cache. If no dependency is specified, installs all dependencies listed in the config file.
+If the <p(245)>--entrypoint</> flag is passed, installs the dependencies of the specified entrypoint(s).
 
-Grant permission to read from disk and listen to network:
-  <p(245)>deno run --allow-read --allow-net jsr:@std/http/file-server</>
+  <p(245)>deno install</>
+  <p(245)>deno install @std/bytes</>
+  <p(245)>deno install npm:chalk</>
+  <p(245)>deno install --entrypoint entry1.ts entry2.ts</>
 
-Grant permission to read allow-listed files from disk:
-  <p(245)>deno run --allow-read=/etc jsr:@std/http/file-server</>
+<g>Global installation</>
 
-Grant all permissions:
-  <p(245)>deno run -A jsr:@std/http/file-server</>
+If the <bold>--global</> flag is set, installs a script as an executable in the installation root's bin directory.
 
-Specifying the filename '-' to read the file from stdin.
-  <p(245)>curl https://examples.deno.land/hello-world.ts | deno run -</>
+  <p(245)>deno install --global --allow-net --allow-read jsr:@std/http/file-server</>
+  <p(245)>deno install -g https://examples.deno.land/color-logging.ts</>
 
-<y>Read more:</> <c>https://docs.deno.com/go/run</>"), UnstableArgsConfig::ResolutionAndRuntime), false)
+To change the executable name, use <c>-n</>/<c>--name</>:
+  <p(245)>deno install -g --allow-net --allow-read -n serve jsr:@std/http/file-server</>
+
+The executable name is inferred by default:
+  - Attempt to take the file stem of the URL path. The above example would
+    become <p(245)>file_server</>.
+  - If the file stem is something generic like <p(245)>main</>, <p(245)>mod</>, <p(245)>index</> or <p(245)>cli</>,
+    and the path has no parent, take the file name of the parent path. Otherwise
+    settle with the generic name.
+  - If the resulting name has an <p(245)>@...</> suffix, strip it.
+
+To change the installation root, use <c>--root</>:
+  <p(245)>deno install -g --allow-net --allow-read --root /usr/local jsr:@std/http/file-server</>
+
+The installation root is determined, in order of precedence:
+  - <p(245)>--root</> option
+  - <p(245)>DENO_INSTALL_ROOT</> environment variable
+  - <p(245)>$HOME/.deno</>
+
+These must be added to the path manually if required."), UnstableArgsConfig::ResolutionAndRuntime)
+    .visible_alias("i")
+    .defer(|cmd| {
+      permission_args(runtime_args(cmd, false, true), Some("global"))
+        .arg(check_arg(true))
+        .arg(allow_scripts_arg())
+        .arg(
+          Arg::new("cmd")
+            .required_if_eq("global", "true")
+            .required_if_eq("entrypoint", "true")
+            .num_args(1..)
+            .value_hint(ValueHint::FilePath),
+        )
+        .arg(
+          Arg::new("name")
+            .long("name")
+            .short('n')
+            .requires("global")
+            .help("Executable file name"),
+        )
+        .arg(
+          Arg::new("root")
+            .long("root")
+            .requires("global")
+            .help("Installation root")
+            .value_hint(ValueHint::DirPath),
+        )
+        .arg(
+          Arg::new("force")
+            .long("force")
+            .requires("global")
+            .short('f')
+            .help("Forcefully overwrite existing installation")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("env-allow")
+            .long("env-allow")
+            .num_args(0..)
+            .use_value_delimiter(true)
+            .require_equals(true)
+            .value_name("VARIABLE_NAME")
+            .value_parser(env_var_name_validator)
+            .requires("global")
+            .help("Only pass these environment variables to the executable, all others are removed")
+            .long_help("Only pass these environment variables to the executable. All other variables are removed, except for the ones Deno itself needs to run, like PATH, HOME and DENO_DIR."),
+        )
+        .arg(
+          Arg::new("cwd")
+            .long("cwd")
+            .requires("global")
+            .value_name("DIR")
+            .help("Directory the executable is run in")
+            .value_hint(ValueHint::DirPath),
+        )
+        .arg(
+          Arg::new("service")
+            .long("service")
+            .requires("global")
+            .help("Also run the executable as a user service (a Task Scheduler task on Windows), restarted when it fails and with its output written to a log file")
+            .long_help(cstr!("Also generate a definition which runs the executable as a user service, restarted when it fails
+and with its output written to <p(245)>logs/NAME.log</> in the installation root:
+  - Linux: a systemd unit in <p(245)>~/.config/systemd/user</>, which is stopped when the user logs out
+    unless lingering is enabled with <p(245)>loginctl enable-linger</>
+  - macOS: a launchd agent in <p(245)>~/Library/LaunchAgents</>
+  - Windows: a Task Scheduler task run at logon rather than a Windows service, in <p(245)>services</> in the installation root
+The commands to start the service are printed afterwards."))
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("global")
+            .long("global")
+            .short('g')
+            .help("Install a package or script as a globally available executable")
+            .action(ArgAction::SetTrue),
+        )
+        .arg(
+          Arg::new("entrypoint")
+            .long("entrypoint")
+            .short('e')
+            .conflicts_with("global")
+            .action(ArgAction::SetTrue)
+            .help("Install dependents of the specified entrypoint(s)"),
+        )
+        .arg(env_file_arg())
+        .arg(add_dev_arg().conflicts_with("entrypoint").conflicts_with("global"))
+        .arg(
+          Arg::new("production")
+            .long("production")
+            .conflicts_with_all(["global", "entrypoint", "cmd"])
+            .action(ArgAction::SetTrue)
+            .help("Don't install dev dependencies")
+            .long_help("Don't install the dev dependencies, which are the \"devImports\" of a `deno.json` file and the \"devDependencies\" of a `package.json` file."),
+        )
+    })
 }
 
-fn serve_host_validator(host: &str) -> Result<String, String> {
-  if Url::parse(&format!("internal://{host}:9999")).is_ok() {
-    Ok(host.to_owned())
+fn env_var_name_validator(name: &str) -> Result<String, String> {
+  let mut chars = name.chars();
+  let valid = chars
+    .next()
+    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+  if valid {
+    Ok(name.to_string())
   } else {
-    Err(format!("Bad serve host: {host}"))
+    Err(format!("Invalid environment variable name: {name}"))
   }
 }
 
-fn serve_subcommand() -> Command {
-  runtime_args(command("serve", cstr!("Run a server defined in a main module
-
-The serve command uses the default exports of the main module to determine which servers to start.
-
-Start a server defined in server.ts:
-  <p(245)>deno serve server.ts</>
+fn json_reference_subcommand() -> Command {
+  Command::new("json_reference").hide(true)
+}
 
-Start a server defined in server.ts, watching for changes and running on port 5050:
-  <p(245)>deno serve --watch --port 5050 server.ts</>
+fn jupyter_kernel_name_validator(name: &str) -> Result<String, String> {
+  // https://jupyter-client.readthedocs.io/en/stable/kernels.html#kernel-specs
+  if !name.is_empty()
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+  {
+    Ok(name.to_lowercase())
+  } else {
+    Err(format!(
+      "Invalid kernel name '{name}': only ASCII letters, numbers, '.', '_' and '-' are allowed"
+    ))
+  }
+}
 
-<y>Read more:</> <c>https://docs.deno.com/go/serve</>"), UnstableArgsConfig::ResolutionAndRuntime), true, true)
+fn jupyter_subcommand() -> Command {
+  command("jupyter", "Deno kernel for Jupyter notebooks", UnstableArgsConfig::ResolutionAndRuntime)
     .arg(
-      Arg::new("port")
-        .long("port")
-        .help(cstr!("The TCP port to serve on. Pass 0 to pick a random free port <p(245)>[default: 8000]</>"))
-        .value_parser(value_parser!(u16)),
+      Arg::new("install")
+        .long("install")
+        .help("Installs kernelspec, requires 'jupyter' command to be available.")
+        .conflicts_with("kernel")
+        .action(ArgAction::SetTrue)
     )
     .arg(
-      Arg::new("host")
-        .long("host")
-        .help("The TCP address to serve on, defaulting to 0.0.0.0 (all interfaces)")
-        .value_parser(serve_host_validator),
+      Arg::new("kernel")
+        .long("kernel")
+        .help("Start the kernel")
+        .conflicts_with("install")
+        .requires("conn")
+        .action(ArgAction::SetTrue)
     )
     .arg(
-      parallel_arg("multiple server workers")
-    )
-    .arg(check_arg(false))
-    .arg(watch_arg(true))
-    .arg(hmr_arg(true))
-    .arg(watch_exclude_arg())
-    .arg(no_clear_screen_arg())
-    .arg(executable_ext_arg())
+      Arg::new("conn")
+        .long("conn")
+        .help("Path to JSON file describing connection parameters, provided by Jupyter")
+        .value_parser(value_parser!(String))
+        .value_hint(ValueHint::FilePath)
+        .conflicts_with("install"))
     .arg(
-      script_arg()
-        .required_unless_present_any(["help", "v8-flags"])
-        .trailing_var_arg(true),
-    )
-    .arg(env_file_arg())
-    .arg(no_code_cache_arg())
+      Arg::new("name")
+        .long("name")
+        .short('n')
+        .help("Name of the kernelspec to install. Defaults to 'deno'")
+        .value_parser(jupyter_kernel_name_validator)
+        .requires("install"))
+    .arg(
+      Arg::new("display-name")
+        .long("display-name")
+        .help("Name of the kernel shown in the Jupyter UI. Defaults to 'Deno'")
+        .requires("install"))
+    .arg(
+      Arg::new("kernel-arg")
+        .long("kernel-arg")
+        .help(cstr!("Argument passed to the kernel when it is started, can be repeated
+  <p(245)>deno jupyter --install --name=deno-sandboxed --kernel-arg=--allow-net=example.com</>"))
+        .action(ArgAction::Append)
+        .allow_hyphen_values(true)
+        .requires("install"))
+    .arg(
+      Arg::new("assets-dir")
+        .long("assets-dir")
+        .value_name("DIR")
+        .help(cstr!("Directory to save outputs that are too large to send to the notebook, which reference the files instead
+  <p(245)>deno jupyter --install --kernel-arg=--assets-dir=outputs</>"))
+        .value_hint(ValueHint::DirPath)
+        .requires("kernel"))
+    .defer(|cmd| {
+      let cmd = compile_args_without_check_args(cmd);
+      permission_args(cmd, Some("kernel"))
+    })
 }
 
-fn task_subcommand() -> Command {
+fn uninstall_subcommand() -> Command {
   command(
-    "task",
-    cstr!(
-      "Run a task defined in the configuration file.
-  <p(245)>deno task build</>
+    "uninstall",
+    cstr!("Uninstalls a dependency or an executable script in the installation root's bin directory.
+  <p(245)>deno uninstall @std/dotenv chalk</>
+  <p(245)>deno uninstall --global file_server</>
 
-List all available tasks:
-  <p(245)>deno task</>"
-    ),
-    UnstableArgsConfig::ResolutionAndRuntime,
+To change the installation root, use <c>--root</> flag:
+  <p(245)>deno uninstall --global --root /usr/local serve</>
+
+The installation root is determined, in order of precedence:
+  - <p(245)>--root</> option
+  - <p(245)>DENO_INSTALL_ROOT</> environment variable
+  - <p(245)>$HOME/.deno</>"),
+    UnstableArgsConfig::None,
   )
   .defer(|cmd| {
     cmd
-      .allow_external_subcommands(true)
-      .subcommand_value_name("TASK")
-      .arg(config_arg())
+      .arg(Arg::new("name-or-package").required_unless_present("help"))
       .arg(
-        Arg::new("cwd")
-          .long("cwd")
-          .value_name("DIR")
-          .help("Specify the directory to run the task in")
+        Arg::new("root")
+          .long("root")
+          .help("Installation root")
+          .requires("global")
           .value_hint(ValueHint::DirPath),
       )
-      .arg(node_modules_dir_arg())
+      .arg(
+        Arg::new("global")
+          .long("global")
+          .short('g')
+          .help("Remove globally installed package or module")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("service")
+          .long("service")
+          .help("Also stop the service generated by deno install --service and remove its definition")
+          .requires("global")
+          .action(ArgAction::SetTrue),
+      )
+      .arg(
+        Arg::new("additional-packages")
+          .help("List of additional packages to remove")
+          .conflicts_with("global")
+          .num_args(1..)
+          .action(ArgAction::Append)
+      )
   })
 }
 
-fn test_subcommand() -> Command {
-  command("test",
-      cstr!("Run tests using Deno's built-in test runner.
+fn lsp_subcommand() -> Command {
+  Command::new("lsp").about(
+    "The 'deno lsp' subcommand provides a way for code editors and IDEs to interact with Deno
+using the Language Server Protocol. Usually humans do not use this subcommand directly.
+For example, 'deno lsp' can provide IDEs with go-to-definition support and automatic code formatting.
 
-Evaluate the given modules, run all tests declared with <bold>Deno.</><y>test()</> and report results to standard output:
-  <p(245)>deno test src/fetch_test.ts src/signal_test.ts</>
+How to connect various editors and IDEs to 'deno lsp': https://docs.deno.com/go/lsp",
+  )
+  .arg(
+    Arg::new("status")
+      .long("status")
+      .help("Print the memory usage of running language servers and exit")
+      .action(ArgAction::SetTrue),
+  )
+  .arg(
+    Arg::new("diagnose")
+      .long("diagnose")
+      .help("Print the resolved settings, config files, registries and cache paths as JSON and exit")
+      .conflicts_with("status")
+      .action(ArgAction::SetTrue),
+  )
+  .arg(
+    Arg::new("settings")
+      .long("settings")
+      .value_name("FILE")
+      .help("Read the editor settings to diagnose from a JSON file")
+      .requires("diagnose")
+      .value_hint(ValueHint::FilePath),
+  )
+}
 
-Directory arguments are expanded to all contained files matching the glob <c>{*_,*.,}test.{js,mjs,ts,mts,jsx,tsx}</>
-or <c>**/__tests__/**</>:
- <p(245)>deno test src/</>
+fn lint_subcommand() -> Command {
+  command(
+    "lint",
+    cstr!("Lint JavaScript/TypeScript source code.
 
-<y>Read more:</> <c>https://docs.deno.com/go/test</>"),
-          UnstableArgsConfig::ResolutionAndRuntime
-    )
-    .defer(|cmd|
-      runtime_args(cmd, true, true)
-      .arg(check_arg(true))
-      .arg(
-        Arg::new("ignore")
-          .long("ignore")
-          .num_args(1..)
-          .action(ArgAction::Append)
-          .require_equals(true)
-          .help("Ignore files")
-          .value_hint(ValueHint::AnyPath),
-      )
+  <p(245)>deno lint</>
+  <p(245)>deno lint myfile1.ts myfile2.js</>
+
+Print result as JSON:
+  <p(245)>deno lint --json</>
+
+Read from stdin:
+  <p(245)>cat file.ts | deno lint -</>
+  <p(245)>cat file.ts | deno lint --json -</>
+
+List available rules:
+  <p(245)>deno lint --rules</>
+  <p(245)>deno lint --rules --json</>
+
+To ignore specific diagnostics, you can write an ignore comment on the preceding line with a rule name (or multiple):
+  <p(245)>// deno-lint-ignore no-explicit-any</>
+  <p(245)>// deno-lint-ignore require-await no-empty</>
+
+To ignore linting on an entire file, you can add an ignore comment at the top of the file:
+  <p(245)>// deno-lint-ignore-file</>
+
+<y>Read more:</> <c>https://docs.deno.com/go/lint</>
+"),
+    UnstableArgsConfig::ResolutionOnly,
+  )
+  .defer(|cmd| {
+    cmd
       .arg(
-        Arg::new("no-run")
-          .long("no-run")
-          .help("Cache test modules, but don't run tests")
+        Arg::new("fix")
+          .long("fix")
+          .help("Fix any linting errors for rules that support it")
           .action(ArgAction::SetTrue)
-          .help_heading(TEST_HEADING),
+          .help_heading(LINT_HEADING),
       )
       .arg(
-        Arg::new("trace-leaks")
-          .long("trace-leaks")
-          .help("Enable tracing of leaks. Useful when debugging leaking ops in test, but impacts test execution time")
+            Arg::new("ext")
+                .long("ext")
+                .require_equals(true)
+                .value_name("EXT")
+                .help("Specify the file extension to lint when reading from stdin.\
+  For example, use `jsx` to lint JSX files or `tsx` for TSX files.\
+  This argument is necessary because stdin input does not automatically infer the file type.\
+  Example usage: `cat file.jsx | deno lint - --ext=jsx`."),
+        )
+        .arg(
+        Arg::new("rules")
+          .long("rules")
+          .help("List available rules")
           .action(ArgAction::SetTrue)
-          .help_heading(TEST_HEADING),
+          .help_heading(LINT_HEADING),
       )
       .arg(
-        Arg::new("doc")
-          .long("doc")
-          .help("Evaluate code blocks in JSDoc and Markdown")
-          .action(ArgAction::SetTrue)
-          .help_heading(TEST_HEADING),
+        Arg::new("rules-tags")
+          .long("rules-tags")
+          .require_equals(true)
+          .num_args(1..)
+          .action(ArgAction::Append)
+          .use_value_delimiter(true)
+          .help("Use set of rules with a tag")
+          .help_heading(LINT_HEADING),
       )
       .arg(
-        Arg::new("fail-fast")
-          .long("fail-fast")
-          .alias("failfast")
-          .help("Stop after N errors. Defaults to stopping after first failure")
-          .num_args(0..=1)
+        Arg::new("rules-include")
+          .long("rules-include")
           .require_equals(true)
-          .value_name("N")
-          .value_parser(value_parser!(NonZeroUsize))
-          .help_heading(TEST_HEADING))
-      .arg(
-        Arg::new("permit-no-files")
-          .long("permit-no-files")
-          .help("Don't return an error code if no test files were found")
-          .action(ArgAction::SetTrue)
-          .help_heading(TEST_HEADING),
+          .num_args(1..)
+          .use_value_delimiter(true)
+          .conflicts_with("rules")
+          .help("Include lint rules")
+          .help_heading(LINT_HEADING),
       )
       .arg(
-        Arg::new("filter")
-          .allow_hyphen_values(true)
-          .long("filter")
-          .help("Run tests with this string or regexp pattern in the test name")
-          .help_heading(TEST_HEADING),
+        Arg::new("rules-exclude")
+          .long("rules-exclude")
+          .require_equals(true)
+          .num_args(1..)
+          .use_value_delimiter(true)
+          .conflicts_with("rules")
+          .help("Exclude lint rules")
+          .help_heading(LINT_HEADING),
       )
+      .arg(no_config_arg())
+      .arg(config_arg())
       .arg(
-        Arg::new("shuffle")
-          .long("shuffle")
-          .value_name("NUMBER")
-          .help("Shuffle the order in which the tests are run")
-          .num_args(0..=1)
+        Arg::new("ignore")
+          .long("ignore")
+          .num_args(1..)
+          .action(ArgAction::Append)
           .require_equals(true)
-          .value_parser(value_parser!(u64))
-          .help_heading(TEST_HEADING),
+          .help("Ignore linting particular source files")
+          .value_hint(ValueHint::AnyPath)
+          .help_heading(LINT_HEADING),
       )
       .arg(
-        Arg::new("coverage")
-          .long("coverage")
-          .value_name("DIR")
-          .num_args(0..=1)
-          .require_equals(true)
-          .default_missing_value("coverage")
-          .conflicts_with("inspect")
-          .conflicts_with("inspect-wait")
-          .conflicts_with("inspect-brk")
-          .help("Collect coverage profile data into DIR. If DIR is not specified, it uses 'coverage/'")
-          .help_heading(TEST_HEADING),
+        Arg::new("json")
+          .long("json")
+          .help("Output lint result in JSON format")
+          .action(ArgAction::SetTrue)
+          .help_heading(LINT_HEADING),
       )
       .arg(
-        Arg::new("clean")
-          .long("clean")
-          .help(cstr!("Empty the temporary coverage profile data directory before running tests.
-  <p(245)>Note: running multiple `deno test --clean` calls in series or parallel for the same coverage directory may cause race conditions.</>"))
+        Arg::new("compact")
+          .long("compact")
+          .help("Output lint result in compact format")
           .action(ArgAction::SetTrue)
-          .help_heading(TEST_HEADING),
+          .conflicts_with("json")
+          .help_heading(LINT_HEADING),
       )
       .arg(
-        parallel_arg("test modules")
+        Arg::new("output-file")
+          .long("output-file")
+          .value_name("PATH")
+          .value_hint(ValueHint::FilePath)
+          .require_equals(true)
+          .help("Also write the diagnostics to PATH in the format of --json")
+          .help_heading(LINT_HEADING),
       )
       .arg(
         Arg::new("files")
-          .help("List of file names to run")
-          .num_args(0..)
+          .num_args(1..)
           .action(ArgAction::Append)
           .value_hint(ValueHint::AnyPath),
       )
-      .arg(
-        watch_arg(true)
-          .conflicts_with("no-run")
-          .conflicts_with("coverage"),
-      )
+      .arg(watch_arg(false))
       .arg(watch_exclude_arg())
+      .arg(watch_debounce_arg())
+      .arg(watch_poll_interval_arg())
+      .arg(watch_exec_arg())
       .arg(no_clear_screen_arg())
-      .arg(script_arg().last(true))
+  })
+}
+
+fn repl_subcommand() -> Command {
+  command("repl", cstr!(
+    "Starts a read-eval-print-loop, which lets you interactively build up program state in the global context.
+It is especially useful for quick prototyping and checking snippets of code.
+
+TypeScript is supported, however it is not type-checked, only transpiled."
+  ), UnstableArgsConfig::ResolutionAndRuntime)
+    .defer(|cmd| runtime_args(cmd, true, true)
+      .arg(check_arg(false))
       .arg(
-        Arg::new("junit-path")
-          .long("junit-path")
-          .value_name("PATH")
-          .value_hint(ValueHint::FilePath)
-          .help("Write a JUnit XML test report to PATH. Use '-' to write to stdout which is the default when PATH is not provided")
-          .help_heading(TEST_HEADING)
+        Arg::new("eval-file")
+          .long("eval-file")
+          .num_args(1..)
+          .action(ArgAction::Append)
+          .require_equals(true)
+          .help("Evaluates the provided file(s) as scripts when the REPL starts. Accepts file paths and URLs")
+          .value_hint(ValueHint::AnyPath),
       )
       .arg(
-        Arg::new("reporter")
-          .long("reporter")
-          .help("Select reporter to use. Default to 'pretty'")
-          .value_parser(["pretty", "dot", "junit", "tap"])
-          .help_heading(TEST_HEADING)
+        Arg::new("eval")
+          .long("eval")
+          .help("Evaluates the provided code when the REPL starts")
+          .value_name("code"),
       )
       .arg(
-        Arg::new("hide-stacktraces")
-          .long("hide-stacktraces")
-          .help("Hide stack traces for errors in failure test results.")
-          .action(ArgAction::SetTrue)
+        Arg::new("auto-import")
+          .long("auto-import")
+          .help("Offer to import an npm or JSR package when an undefined identifier is referenced")
+          .action(ArgAction::SetTrue),
       )
-      .arg(env_file_arg())
-      .arg(executable_ext_arg())
+      .arg(
+        Arg::new("repl-server")
+          .long("repl-server")
+          .require_equals(true)
+          .value_name("HOST_AND_PORT")
+          .help("Serve a JSON-RPC protocol to evaluate code, complete and inspect objects on the given loopback address, for building GUI frontends.
+Clients authenticate with the token printed on startup, or the one set with DENO_REPL_SERVER_TOKEN")
+          .value_parser(repl_server_addr_parser),
+      )
+      .arg(
+        Arg::new("save-session")
+          .long("save-session")
+          .require_equals(true)
+          .value_name("FILE")
+          .help("Writes the code evaluated during the session to the provided file when the REPL exits.
+Saved sessions can be restored with the .load command")
+          .value_hint(ValueHint::FilePath),
+      )
+      .after_help(cstr!("<y>Environment variables:</>
+  <g>DENO_REPL_HISTORY</>  Set REPL history file path. History file is disabled when the value is empty.
+                       <p(245)>[default: $DENO_DIR/deno_history.txt]</>"))
+    )
+    .arg(env_file_arg())
+    .arg(
+      Arg::new("args")
+        .num_args(0..)
+        .action(ArgAction::Append)
+        .value_name("ARGS")
+        .last(true)
     )
 }
 
-fn parallel_arg(descr: &str) -> Arg {
-  Arg::new("parallel")
-    .long("parallel")
-    .help(format!("Run {descr} in parallel. Parallelism defaults to the number of available CPUs or the value of the DENO_JOBS environment variable"))
-    .action(ArgAction::SetTrue)
+fn run_args(command: Command, top_level: bool) -> Command {
+  runtime_args(command, true, true)
+    .arg(check_arg(false))
+    .arg(watch_arg(true))
+    .arg(hmr_arg(true))
+    .arg(watch_exclude_arg())
+    .arg(watch_debounce_arg())
+    .arg(watch_poll_interval_arg())
+    .arg(watch_exec_arg())
+    .arg(no_clear_screen_arg())
+    .arg(executable_ext_arg())
+    .arg(
+      Arg::new("ephemeral-tmp")
+        .long("ephemeral-tmp")
+        .help("Give the program its own temporary directory that is readable and writable, and removed when the program exits")
+        .action(ArgAction::SetTrue)
+    )
+    .arg(
+      Arg::new("restart-on-failure")
+        .long("restart-on-failure")
+        .visible_alias("restart-on-crash")
+        .num_args(0..=1)
+        .require_equals(true)
+        .value_name("MAX_RETRIES")
+        .value_parser(value_parser!(u32))
+        .help(cstr!("Restart the program when it exits with a non-zero code or crashes, up to the given number of consecutive times
+  <p(245)>Restarts without a limit when no number is given.</>"))
+        .conflicts_with_all(["watch", "hmr"])
+    )
+    .arg(
+      Arg::new("procfile")
+        .long("procfile")
+        .value_name("FILE")
+        .require_equals(true)
+        .value_hint(ValueHint::FilePath)
+        .conflicts_with("script_arg")
+        .help(cstr!("Run the programs of a Procfile as sibling processes with prefixed output
+  <p(245)>Each line is a name followed by a colon, the script and its arguments, e.g. 'web: server.ts --port=8000'.</>"))
+    )
+    .arg(
+      Arg::new("sibling")
+        .long("sibling")
+        .value_name("SCRIPT")
+        .action(ArgAction::Append)
+        .require_equals(true)
+        .conflicts_with("procfile")
+        .help(cstr!("Run another program as a sibling process of the script with prefixed output, which can be repeated
+  <p(245)>The value is the script followed by its arguments separated by whitespace, e.g. 'deno run -A --sibling=\"worker.ts --queue=jobs\" server.ts'.</>"))
+    )
+    .arg(
+      Arg::new("ui")
+        .long("ui")
+        .action(ArgAction::SetTrue)
+        .help("Show the status, restart count and last output line of each process in a panel below their output when running several programs with --sibling or --procfile. The output of the processes stays interleaved with their name as a prefix")
+    )
+    .arg(
+      Arg::new("restart-backoff")
+        .long("restart-backoff")
+        .requires("restart-on-failure")
+        .help("Wait for the given number of milliseconds before restarting, doubled for each consecutive failure [default: 1000]")
+        .value_name("MS")
+        .require_equals(true)
+        .value_parser(value_parser!(u64))
+    )
+    .arg(if top_level {
+      script_arg().trailing_var_arg(true).hide(true)
+    } else {
+      script_arg().trailing_var_arg(true)
+    })
+    .arg(env_file_arg())
+    .arg(no_code_cache_arg())
 }
 
-fn types_subcommand() -> Command {
-  command(
-    "types",
-    cstr!(
-      "Print runtime TypeScript declarations.
+fn run_subcommand() -> Command {
+  run_args(command("run", cstr!("Run a JavaScript or TypeScript program, or a task or script.
 
-  <p(245)>deno types > lib.deno.d.ts</>
+By default all programs are run in sandbox without access to disk, network or ability to spawn subprocesses.
+  <p(245)>deno run https://examples.deno.land/hello-world.ts</>
 
-The declaration file could be saved and used for typing information."
-    ),
-    UnstableArgsConfig::None,
-  )
+Grant permission to read from disk and listen to network:
+  <p(245)>deno run --allow-read --allow-net jsr:@std/http/file-server</>
+
+Grant permission to read allow-listed files from disk:
+  <p(245)>deno run --allow-read=/etc jsr:@std/http/file-server</>
+
+Grant all permissions:
+  <p(245)>deno run -A jsr:@std/http/file-server</>
+
+Specifying the filename '-' to read the file from stdin.
+  <p(245)>curl https://examples.deno.land/hello-world.ts | deno run -</>
+
+<y>Read more:</> <c>https://docs.deno.com/go/run</>"), UnstableArgsConfig::ResolutionAndRuntime), false)
 }
 
-pub static UPGRADE_USAGE: &str = cstr!(
-  "<g>Latest</>
-  <bold>deno upgrade</>
+fn repl_server_addr_parser(addr: &str) -> Result<SocketAddr, String> {
+  let addr = addr
+    .parse::<SocketAddr>()
+    .map_err(|err| format!("Bad REPL server address: {err}"))?;
+  if addr.ip().is_loopback() {
+    Ok(addr)
+  } else {
+    Err(format!(
+      "The REPL server can only listen on a loopback address, got {}",
+      addr.ip()
+    ))
+  }
+}
 
-<g>Specific version</>
-  <bold>deno upgrade</> <p(245)>1.45.0</>
-  <bold>deno upgrade</> <p(245)>1.46.0-rc.1</>
-  <bold>deno upgrade</> <p(245)>9bc2dd29ad6ba334fd57a20114e367d3c04763d4</>
+fn serve_host_validator(host: &str) -> Result<String, String> {
+  if Url::parse(&format!("internal://{host}:9999")).is_ok() {
+    Ok(host.to_owned())
+  } else {
+    Err(format!("Bad serve host: {host}"))
+  }
+}
 
-<g>Channel</>
-  <bold>deno upgrade</> <p(245)>stable</>
-  <bold>deno upgrade</> <p(245)>rc</>
-  <bold>deno upgrade</> <p(245)>canary</>"
-);
+fn serve_mirror_validator(url: &str) -> Result<String, String> {
+  match Url::parse(url) {
+    Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(url.into()),
+    _ => Err(format!("Bad mirror URL: {url}")),
+  }
+}
 
-fn upgrade_subcommand() -> Command {
-  command(
-    "upgrade",
-    color_print::cformat!("Upgrade deno executable to the given version.
+fn serve_metrics_path_validator(path: &str) -> Result<String, String> {
+  if path.starts_with('/') {
+    Ok(path.to_string())
+  } else {
+    Err(format!(
+      "Bad metrics path: {path}. It must start with a slash"
+    ))
+  }
+}
 
-{}
+fn serve_subcommand() -> Command {
+  runtime_args(command("serve", cstr!("Run a server defined in a main module
 
-The version is downloaded from <p(245)>https://dl.deno.land</> and is used to replace the current executable.
+The serve command uses the default exports of the main module to determine which servers to start.
 
-If you want to not replace the current Deno executable but instead download an update to a
-different location, use the <c>--output</> flag:
-  <p(245)>deno upgrade --output $HOME/my_deno</>
+Start a server defined in server.ts:
+  <p(245)>deno serve server.ts</>
 
-<y>Read more:</> <c>https://docs.deno.com/go/upgrade</>", UPGRADE_USAGE),
-    UnstableArgsConfig::None,
+Start a server defined in server.ts, watching for changes and running on port 5050:
+  <p(245)>deno serve --watch --port 5050 server.ts</>
+
+<y>Read more:</> <c>https://docs.deno.com/go/serve</>"), UnstableArgsConfig::ResolutionAndRuntime), true, true)
+    .arg(
+      Arg::new("port")
+        .long("port")
+        .help(cstr!("The TCP port to serve on. Pass 0 to pick a random free port <p(245)>[default: 8000]</>"))
+        .value_parser(value_parser!(u16)),
+    )
+    .arg(
+      Arg::new("host")
+        .long("host")
+        .help("The TCP address to serve on, defaulting to 0.0.0.0 (all interfaces)")
+        .value_parser(serve_host_validator),
+    )
+    .arg(
+      parallel_arg("multiple server workers")
+    )
+    .arg(
+      Arg::new("mirror")
+        .long("mirror")
+        .value_name("URL")
+        .help("Asynchronously send a copy of incoming requests to a shadow backend. Its responses are ignored")
+        .value_parser(serve_mirror_validator)
+        .value_hint(ValueHint::Url),
+    )
+    .arg(
+      Arg::new("mirror-percent")
+        .long("mirror-percent")
+        .value_name("N")
+        .help(cstr!("Percentage of incoming requests to mirror <p(245)>[default: 100]</>"))
+        .value_parser(value_parser!(u8).range(0..=100))
+        .requires("mirror"),
+    )
+    .arg(
+      Arg::new("max-concurrent-uploads")
+        .long("max-concurrent-uploads")
+        .value_name("N")
+        .help("Respond with 503 to requests with a body while N others are being received")
+        .value_parser(value_parser!(NonZeroUsize)),
+    )
+    .arg(
+      Arg::new("upload-high-water-mark")
+        .long("upload-high-water-mark")
+        .value_name("BYTES")
+        .help("Maximum number of bytes of a request body to buffer before the handler reads them")
+        .value_parser(value_parser!(usize)),
+    )
+    .arg(
+      Arg::new("upload-metrics-path")
+        .long("upload-metrics-path")
+        .value_name("PATH")
+        .help("Serve the number of active, rejected and backpressured uploads as JSON on this path")
+        .value_parser(serve_metrics_path_validator),
+    )
+    .arg(
+      Arg::new("idle-shutdown")
+        .long("idle-shutdown")
+        .value_name("SECONDS")
+        .help("Exit with code 75 after no requests were handled for this many seconds, so that a supervisor can start the server again on the next connection")
+        .value_parser(value_parser!(NonZeroU64))
+        .conflicts_with("parallel"),
+    )
+    .arg(
+      Arg::new("cert")
+        .long("cert")
+        .value_name("FILE")
+        .help("Serve HTTPS with the PEM encoded certificate chain in this file")
+        .requires("key")
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("key")
+        .long("key")
+        .value_name("FILE")
+        .help("PEM encoded private key of the certificate passed to --cert")
+        .requires("cert")
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("tls-client-ca")
+        .long("tls-client-ca")
+        .value_name("FILE")
+        .help("Verify the certificates of the clients against the PEM encoded CA certificates in this file. The verified certificate is available to handlers as `info.clientCertificate`")
+        .requires("cert")
+        .value_hint(ValueHint::FilePath),
+    )
+    .arg(
+      Arg::new("tls-client-auth")
+        .long("tls-client-auth")
+        .value_name("MODE")
+        .help(cstr!("Whether clients must present a certificate, or connections without one are accepted as well <p(245)>[default: require]</>"))
+        .value_parser(["require", "optional"])
+        .requires("tls-client-ca"),
+    )
+    .arg(check_arg(false))
+    .arg(watch_arg(true))
+    .arg(hmr_arg(true))
+    .arg(watch_exclude_arg())
+    .arg(watch_debounce_arg())
+    .arg(watch_poll_interval_arg())
+    .arg(watch_exec_arg())
+    .arg(no_clear_screen_arg())
+    .arg(executable_ext_arg())
+    .arg(
+      script_arg()
+        .required_unless_present_any(["help", "v8-flags"])
+        .trailing_var_arg(true),
+    )
+    .arg(env_file_arg())
+    .arg(no_code_cache_arg())
+}
+
+fn task_subcommand() -> Command {
+  command(
+    "task",
+    cstr!(
+      "Run a task defined in the configuration file.
+  <p(245)>deno task build</>
+
+List all available tasks:
+  <p(245)>deno task</>"
+    ),
+    UnstableArgsConfig::ResolutionAndRuntime,
   )
-  .hide(cfg!(not(feature = "upgrade")))
   .defer(|cmd| {
     cmd
+      .allow_external_subcommands(true)
+      .subcommand_value_name("TASK")
+      .arg(config_arg())
       .arg(
-        Arg::new("version")
-          .long("version")
-          .help("The version to upgrade to")
-          .help_heading(UPGRADE_HEADING)// NOTE(bartlomieju): pre-v1.46 compat
-          .hide(true),
-      )
-      .arg(
-        Arg::new("output")
-          .long("output")
-          .help("The path to output the updated version to")
-          .value_parser(value_parser!(String))
-          .value_hint(ValueHint::FilePath)
-          .help_heading(UPGRADE_HEADING),
-      )
-      .arg(
-        Arg::new("dry-run")
-          .long("dry-run")
-          .help("Perform all checks without replacing old exe")
-          .action(ArgAction::SetTrue)
-          .help_heading(UPGRADE_HEADING),
+        Arg::new("cwd")
+          .long("cwd")
+          .value_name("DIR")
+          .help("Specify the directory to run the task in")
+          .value_hint(ValueHint::DirPath),
       )
-      .arg(
-        Arg::new("force")
-          .long("force")
-          .short('f')
-          .help("Replace current exe even if not out-of-date")
+      .arg(node_modules_dir_arg())
+  })
+}
+
+fn test_subcommand() -> Command {
+  command("test",
+      cstr!("Run tests using Deno's built-in test runner.
+
+Evaluate the given modules, run all tests declared with <bold>Deno.</><y>test()</> and report results to standard output:
+  <p(245)>deno test src/fetch_test.ts src/signal_test.ts</>
+
+Directory arguments are expanded to all contained files matching the glob <c>{*_,*.,}test.{js,mjs,ts,mts,jsx,tsx}</>
+or <c>**/__tests__/**</>:
+ <p(245)>deno test src/</>
+
+<y>Read more:</> <c>https://docs.deno.com/go/test</>"),
+          UnstableArgsConfig::ResolutionAndRuntime
+    )
+    .defer(|cmd|
+      runtime_args(cmd, true, true)
+      .arg(check_arg(true))
+      .arg(no_daemon_arg())
+      .arg(
+        Arg::new("ignore")
+          .long("ignore")
+          .num_args(1..)
+          .action(ArgAction::Append)
+          .require_equals(true)
+          .help("Ignore files")
+          .value_hint(ValueHint::AnyPath),
+      )
+      .arg(
+        Arg::new("no-run")
+          .long("no-run")
+          .help("Cache test modules, but don't run tests")
           .action(ArgAction::SetTrue)
-          .help_heading(UPGRADE_HEADING),
+          .help_heading(TEST_HEADING),
       )
       .arg(
-        Arg::new("canary")
-          .long("canary")
-          .help("Upgrade to canary builds")
+        Arg::new("trace-leaks")
+          .long("trace-leaks")
+          .help("Enable tracing of leaks. Useful when debugging leaking ops in test, but impacts test execution time")
           .action(ArgAction::SetTrue)
-          .help_heading(UPGRADE_HEADING)// NOTE(bartlomieju): pre-v1.46 compat
-          .hide(true),
+          .help_heading(TEST_HEADING),
       )
       .arg(
-        Arg::new("release-candidate")
-          .long("rc")
-          .help("Upgrade to a release candidate")
-          .conflicts_with_all(["canary", "version"])
+        Arg::new("doc")
+          .long("doc")
+          .help("Evaluate code blocks in JSDoc and Markdown")
           .action(ArgAction::SetTrue)
-          .help_heading(UPGRADE_HEADING)
-          // NOTE(bartlomieju): pre-v1.46 compat
-          .hide(true),
+          .help_heading(TEST_HEADING),
       )
       .arg(
-        Arg::new("version-or-hash-or-channel")
-          .help(cstr!("Version <p(245)>(v1.46.0)</>, channel <p(245)>(rc, canary)</> or commit hash <p(245)>(9bc2dd29ad6ba334fd57a20114e367d3c04763d4)</>"))
-          .value_name("VERSION")
-          .action(ArgAction::Append)
-          .trailing_var_arg(true),
+        Arg::new("fail-fast")
+          .long("fail-fast")
+          .alias("failfast")
+          .help("Stop after N errors. Defaults to stopping after first failure")
+          .num_args(0..=1)
+          .require_equals(true)
+          .value_name("N")
+          .value_parser(value_parser!(NonZeroUsize))
+          .help_heading(TEST_HEADING))
+      .arg(
+        Arg::new("permit-no-files")
+          .long("permit-no-files")
+          .help("Don't return an error code if no test files were found")
+          .action(ArgAction::SetTrue)
+          .help_heading(TEST_HEADING),
       )
-      .arg(ca_file_arg())
-      .arg(unsafely_ignore_certificate_errors_arg())
-  })
-}
-
-fn vendor_subcommand() -> Command {
-  command("vendor",
-      "⚠️ `deno vendor` was removed in Deno 2.
-
-See the Deno 1.x to 2.x Migration Guide for migration instructions: https://docs.deno.com/runtime/manual/advanced/migrate_deprecations",
-      UnstableArgsConfig::ResolutionOnly
-    )
-    .hide(true)
-}
-
-fn publish_subcommand() -> Command {
-  command("publish", "Publish the current working directory's package or workspace to JSR", UnstableArgsConfig::ResolutionOnly)
-    .defer(|cmd| {
-      cmd
       .arg(
-        Arg::new("token")
-          .long("token")
-          .help("The API token to use when publishing. If unset, interactive authentication is be used")
-          .help_heading(PUBLISH_HEADING)
+        Arg::new("timeout")
+          .long("timeout")
+          .value_name("MS")
+          .require_equals(true)
+          .help("Fail the tests which run for longer than the given number of milliseconds, cancelling the rest of their module")
+          .value_parser(value_parser!(u64).range(1..))
+          .help_heading(TEST_HEADING),
       )
-        .arg(config_arg())
-        .arg(no_config_arg())
-        .arg(
-          Arg::new("dry-run")
-            .long("dry-run")
-            .help("Prepare the package for publishing performing all checks and validations without uploading")
-            .action(ArgAction::SetTrue)
-          .help_heading(PUBLISH_HEADING),
-        )
-        .arg(
-          Arg::new("allow-slow-types")
-            .long("allow-slow-types")
-            .help("Allow publishing with slow types")
-            .action(ArgAction::SetTrue)
-          .help_heading(PUBLISH_HEADING),
-        )
-        .arg(
-          Arg::new("allow-dirty")
-            .long("allow-dirty")
-            .help("Allow publishing if the repository has uncommitted changed")
-            .action(ArgAction::SetTrue)
-          .help_heading(PUBLISH_HEADING),
-        ).arg(
-        Arg::new("no-provenance")
-          .long("no-provenance")
-          .help(cstr!("Disable provenance attestation.
-  <p(245)>Enabled by default on Github actions, publicly links the package to where it was built and published from.</>"))
+      .arg(
+        Arg::new("slow-threshold")
+          .long("slow-threshold")
+          .value_name("MS")
+          .require_equals(true)
+          .help("Mark the tests which run for longer than the given number of milliseconds as slow")
+          .value_parser(value_parser!(u64))
+          .help_heading(TEST_HEADING),
+      )
+      .arg(
+        Arg::new("artifacts-dir")
+          .long("artifacts-dir")
+          .value_name("DIR")
+          .value_hint(ValueHint::DirPath)
+          .help("Directory for the screenshots, logs and other files which tests write to t.artifactsDir, namespaced by module and test name")
+          .help_heading(TEST_HEADING),
+      )
+      .arg(
+        Arg::new("update-snapshots")
+          .long("update-snapshots")
+          .help("Update the snapshots of snapshot assertions instead of comparing against them. Snapshot libraries read it with Deno.test.updateSnapshots")
           .action(ArgAction::SetTrue)
-        .help_heading(PUBLISH_HEADING)
+          .help_heading(TEST_HEADING),
       )
-        .arg(check_arg(/* type checks by default */ true))
-        .arg(no_check_arg())
-    })
-}
-
-fn compile_args(app: Command) -> Command {
-  compile_args_without_check_args(app.arg(no_check_arg()))
-}
-
-fn compile_args_without_check_args(app: Command) -> Command {
-  app
-    .arg(import_map_arg())
-    .arg(no_remote_arg())
-    .arg(no_npm_arg())
-    .arg(node_modules_dir_arg())
-    .arg(vendor_arg())
-    .arg(config_arg())
-    .arg(no_config_arg())
-    .arg(reload_arg())
-    .arg(lock_arg())
-    .arg(no_lock_arg())
-    .arg(ca_file_arg())
-    .arg(unsafely_ignore_certificate_errors_arg())
-}
-
-fn permission_args(app: Command, requires: Option<&'static str>) -> Command {
-  app
-    .after_help(cstr!(r#"<y>Permission options:</>
-<y>Docs</>: <c>https://docs.deno.com/go/permissions</>
-
-  <g>-A, --allow-all</>                          Allow all permissions.
-  <g>--no-prompt</>                              Always throw if required permission wasn't passed.
-                                             <p(245)>Can also be set via the DENO_NO_PROMPT environment variable.</>
-  <g>-R, --allow-read[=<<PATH>...]</>             Allow file system read access. Optionally specify allowed paths.
-                                             <p(245)>--allow-read  |  --allow-read="/etc,/var/log.txt"</>
-  <g>-W, --allow-write[=<<PATH>...]</>            Allow file system write access. Optionally specify allowed paths.
-                                             <p(245)>--allow-write  |  --allow-write="/etc,/var/log.txt"</>
-  <g>-I, --allow-import[=<<IP_OR_HOSTNAME>...]</> Allow importing from remote hosts. Optionally specify allowed IP addresses and host names, with ports as necessary.
-                                            Default value: <p(245)>deno.land:443,jsr.io:443,esm.sh:443,cdn.jsdelivr.net:443,raw.githubusercontent.com:443,user.githubusercontent.com:443</>
-                                             <p(245)>--allow-import  |  --allow-import="example.com,github.com"</>
-  <g>-N, --allow-net[=<<IP_OR_HOSTNAME>...]</>    Allow network access. Optionally specify allowed IP addresses and host names, with ports as necessary.
-                                             <p(245)>--allow-net  |  --allow-net="localhost:8080,deno.land"</>
-  <g>-E, --allow-env[=<<VARIABLE_NAME>...]</>     Allow access to environment variables. Optionally specify accessible environment variables.
-                                             <p(245)>--allow-env  |  --allow-env="PORT,HOME,PATH"</>
-  <g>-S, --allow-sys[=<<API_NAME>...]</>          Allow access to OS information. Optionally allow specific APIs by function name.
-                                             <p(245)>--allow-sys  |  --allow-sys="systemMemoryInfo,osRelease"</>
-      <g>--allow-run[=<<PROGRAM_NAME>...]</>      Allow running subprocesses. Optionally specify allowed runnable program names.
-                                             <p(245)>--allow-run  |  --allow-run="whoami,ps"</>
-      <g>--allow-ffi[=<<PATH>...]</>              (Unstable) Allow loading dynamic libraries. Optionally specify allowed directories or files.
-                                             <p(245)>--allow-ffi  |  --allow-ffi="./libfoo.so"</>
-  <g>    --deny-read[=<<PATH>...]</>              Deny file system read access. Optionally specify denied paths.
-                                             <p(245)>--deny-read  |  --deny-read="/etc,/var/log.txt"</>
-  <g>    --deny-write[=<<PATH>...]</>             Deny file system write access. Optionally specify denied paths.
-                                             <p(245)>--deny-write  |  --deny-write="/etc,/var/log.txt"</>
-  <g>    --deny-net[=<<IP_OR_HOSTNAME>...]</>     Deny network access. Optionally specify defined IP addresses and host names, with ports as necessary.
-                                             <p(245)>--deny-net  |  --deny-net="localhost:8080,deno.land"</>
-  <g>    --deny-env[=<<VARIABLE_NAME>...]</>      Deny access to environment variables. Optionally specify inacessible environment variables.
-                                             <p(245)>--deny-env  |  --deny-env="PORT,HOME,PATH"</>
-  <g>    --deny-sys[=<<API_NAME>...]</>           Deny access to OS information. Optionally deny specific APIs by function name.
-                                             <p(245)>--deny-sys  |  --deny-sys="systemMemoryInfo,osRelease"</>
-      <g>--deny-run[=<<PROGRAM_NAME>...]</>       Deny running subprocesses. Optionally specify denied runnable program names.
-                                             <p(245)>--deny-run  |  --deny-run="whoami,ps"</>
-      <g>--deny-ffi[=<<PATH>...]</>               (Unstable) Deny loading dynamic libraries. Optionally specify denied directories or files.
-                                             <p(245)>--deny-ffi  |  --deny-ffi="./libfoo.so"</>
-"#))
-    .arg(
-      {
-        let mut arg = allow_all_arg().hide(true);
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("allow-read")
-          .long("allow-read")
-          .short('R')
-          .num_args(0..)
-          .action(ArgAction::Append)
+      .arg(
+        Arg::new("setup")
+          .long("setup")
+          .value_name("MODULE")
           .require_equals(true)
-          .value_name("PATH")
-          .help("Allow file system read access. Optionally specify allowed paths")
-          .value_hint(ValueHint::AnyPath)
-          .hide(true);
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("deny-read")
-          .long("deny-read")
-          .num_args(0..)
+          .value_hint(ValueHint::FilePath)
+          .help("Run the default export of MODULE before the tests of each test module, in the same worker. The value it resolves to can be read in tests with Deno.test.getSetupData()")
+          .help_heading(TEST_HEADING),
+      )
+      .arg(
+        Arg::new("teardown")
+          .long("teardown")
+          .value_name("MODULE")
+          .require_equals(true)
+          .value_hint(ValueHint::FilePath)
+          .help("Run the default export of MODULE after the tests of each test module, in the same worker, passing it the setup data. Its errors are reported with the results of the module")
+          .help_heading(TEST_HEADING),
+      )
+      .arg(
+        Arg::new("filter")
+          .allow_hyphen_values(true)
+          .long("filter")
+          .help("Run tests with this string or regexp pattern in the test name")
+          .help_heading(TEST_HEADING),
+      )
+      .arg(
+        Arg::new("tags")
+          .long("tags")
+          .value_name("TAGS")
           .action(ArgAction::Append)
+          .value_delimiter(',')
           .require_equals(true)
-          .value_name("PATH")
-          .help("Deny file system read access. Optionally specify denied paths")
-          .value_hint(ValueHint::AnyPath)
-          .hide(true);
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("allow-write")
-          .long("allow-write")
-          .short('W')
-          .num_args(0..)
-          .action(ArgAction::Append)
-          .require_equals(true)
-          .value_name("PATH")
-          .help("Allow file system write access. Optionally specify allowed paths")
-          .value_hint(ValueHint::AnyPath)
-          .hide(true);
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("deny-write")
-          .long("deny-write")
-          .num_args(0..)
+          .help(cstr!("Only run the tests which have at least one of these tags
+  <p(245)>Tags are set with the `tags` option of Deno.test, e.g. --tags=integration,e2e</>"))
+          .help_heading(TEST_HEADING),
+      )
+      .arg(
+        Arg::new("skip-tags")
+          .long("skip-tags")
+          .value_name("TAGS")
           .action(ArgAction::Append)
+          .value_delimiter(',')
           .require_equals(true)
-          .value_name("PATH")
-          .help("Deny file system write access. Optionally specify denied paths")
-          .value_hint(ValueHint::AnyPath)
-          .hide(true);
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("allow-net")
-          .long("allow-net")
-          .short('N')
-          .num_args(0..)
-          .use_value_delimiter(true)
-          .require_equals(true)
-          .value_name("IP_OR_HOSTNAME")
-          .help("Allow network access. Optionally specify allowed IP addresses and host names, with ports as necessary")
-          .value_parser(flags_net::validator)
-          .hide(true)
-          ;
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("deny-net")
-          .long("deny-net")
-          .num_args(0..)
-          .use_value_delimiter(true)
-          .require_equals(true)
-          .value_name("IP_OR_HOSTNAME")
-          .help("Deny network access. Optionally specify denied IP addresses and host names, with ports as necessary")
-          .value_parser(flags_net::validator)
-          .hide(true)
-          ;
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("allow-env")
-          .long("allow-env")
-          .short('E')
-          .num_args(0..)
-          .use_value_delimiter(true)
-          .require_equals(true)
-          .value_name("VARIABLE_NAME")
-          .help("Allow access to system environment information. Optionally specify accessible environment variables")
-          .value_parser(|key: &str| {
-            if key.is_empty() || key.contains(&['=', '\0'] as &[char]) {
-              return Err(format!("invalid key \"{key}\""));
-            }
-
-            Ok(if cfg!(windows) {
-              key.to_uppercase()
-            } else {
-              key.to_string()
-            })
-          })
-          .hide(true)
-          ;
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("deny-env")
-          .long("deny-env")
-          .num_args(0..)
-          .use_value_delimiter(true)
-          .require_equals(true)
-          .value_name("VARIABLE_NAME")
-          .help("Deny access to system environment information. Optionally specify accessible environment variables")
-          .value_parser(|key: &str| {
-            if key.is_empty() || key.contains(&['=', '\0'] as &[char]) {
-              return Err(format!("invalid key \"{key}\""));
-            }
-
-            Ok(if cfg!(windows) {
-              key.to_uppercase()
-            } else {
-              key.to_string()
-            })
-          })
-          .hide(true)
-          ;
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("allow-sys")
-          .long("allow-sys")
-          .short('S')
-          .num_args(0..)
-          .use_value_delimiter(true)
+          .help("Don't run the tests which have any of these tags, even if they match --tags")
+          .help_heading(TEST_HEADING),
+      )
+      .arg(
+        Arg::new("shuffle")
+          .long("shuffle")
+          .value_name("NUMBER")
+          .help("Shuffle the order in which the tests are run")
+          .num_args(0..=1)
           .require_equals(true)
-          .value_name("API_NAME")
-          .help("Allow access to OS information. Optionally allow specific APIs by function name")
-          .value_parser(|key: &str| SysDescriptor::parse(key.to_string()).map(|s| s.into_string()))
-          .hide(true)
-          ;
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("deny-sys")
-          .long("deny-sys")
-          .num_args(0..)
-          .use_value_delimiter(true)
+          .value_parser(value_parser!(u64))
+          .help_heading(TEST_HEADING),
+      )
+      .arg(
+        Arg::new("coverage")
+          .long("coverage")
+          .value_name("DIR")
+          .num_args(0..=1)
           .require_equals(true)
-          .value_name("API_NAME")
-          .help("Deny access to OS information. Optionally deny specific APIs by function name")
-          .value_parser(|key: &str| SysDescriptor::parse(key.to_string()).map(|s| s.into_string()))
-          .hide(true)
-          ;
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("allow-run")
-          .long("allow-run")
-          .num_args(0..)
-          .use_value_delimiter(true)
+          .default_missing_value("coverage")
+          .conflicts_with("inspect")
+          .conflicts_with("inspect-wait")
+          .conflicts_with("inspect-brk")
+          .conflicts_with("inspect-connect")
+          .help("Collect coverage profile data into DIR. If DIR is not specified, it uses 'coverage/'")
+          .help_heading(TEST_HEADING),
+      )
+      .arg(
+        Arg::new("coverage-report")
+          .long("coverage-report")
           .require_equals(true)
-          .value_name("PROGRAM_NAME")
-          .help("Allow running subprocesses. Optionally specify allowed runnable program names")
-          .hide(true)
-          ;
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("deny-run")
-          .long("deny-run")
-          .num_args(0..)
+          .num_args(1..)
+          .action(ArgAction::Append)
           .use_value_delimiter(true)
-          .require_equals(true)
-          .value_name("PROGRAM_NAME")
-          .help("Deny running subprocesses. Optionally specify denied runnable program names")
-          .hide(true)
-          ;
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("allow-ffi")
-          .long("allow-ffi")
+          .value_parser(["summary", "detailed", "lcov", "html"])
+          .conflicts_with("inspect")
+          .conflicts_with("inspect-wait")
+          .conflicts_with("inspect-brk")
+          .conflicts_with("inspect-connect")
+          .help(cstr!("Print or write coverage reports after the tests have run. Implies --coverage.
+  <p(245)>The lcov report is written to 'lcov.info' and the HTML report to 'html/' in the coverage directory.</>"))
+          .help_heading(TEST_HEADING),
+      )
+      .arg(
+        Arg::new("clean")
+          .long("clean")
+          .help(cstr!("Empty the temporary coverage profile data directory before running tests.
+  <p(245)>Note: running multiple `deno test --clean` calls in series or parallel for the same coverage directory may cause race conditions.</>"))
+          .action(ArgAction::SetTrue)
+          .help_heading(TEST_HEADING),
+      )
+      .arg(
+        parallel_arg("test modules")
+      )
+      .arg(
+        Arg::new("files")
+          .help("List of file names to run")
           .num_args(0..)
           .action(ArgAction::Append)
-          .require_equals(true)
+          .value_hint(ValueHint::AnyPath),
+      )
+      .arg(
+        watch_arg(true)
+          .conflicts_with("no-run")
+          .conflicts_with("coverage")
+          .conflicts_with("coverage-report"),
+      )
+      .arg(watch_exclude_arg())
+      .arg(watch_debounce_arg())
+      .arg(watch_poll_interval_arg())
+      .arg(watch_exec_arg())
+      .arg(
+        Arg::new("watch-all")
+          .requires("watch")
+          .long("watch-all")
+          .help("Re-run all tests on file changes, instead of only the test modules which depend on the changed files")
+          .action(ArgAction::SetTrue)
+          .help_heading(FILE_WATCHING_HEADING),
+      )
+      .arg(no_clear_screen_arg())
+      .arg(script_arg().last(true))
+      .arg(
+        Arg::new("junit-path")
+          .long("junit-path")
           .value_name("PATH")
-          .help("(Unstable) Allow loading dynamic libraries. Optionally specify allowed directories or files")
-          .value_hint(ValueHint::AnyPath)
-          .hide(true);
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("deny-ffi")
-          .long("deny-ffi")
-          .num_args(0..)
+          .value_hint(ValueHint::FilePath)
+          .help("Write a JUnit XML test report to PATH. Use '-' to write to stdout which is the default when PATH is not provided")
+          .help_heading(TEST_HEADING)
+      )
+      .arg(
+        Arg::new("reporter")
+          .long("reporter")
+          .help(cstr!("Select reporter to use. Default to 'pretty'. Can be passed multiple times, with NAME:PATH writing to a file
+  <p(245)>pretty, dot, junit, tap or json. Only junit and json can write to a file, e.g. --reporter=pretty --reporter=junit:report.xml --reporter=json:events.ndjson</>"))
+          .value_name("NAME[:PATH]")
+          .value_parser(parse_test_reporter)
           .action(ArgAction::Append)
-          .require_equals(true)
-          .value_name("PATH")
-          .help("(Unstable) Deny loading dynamic libraries. Optionally specify denied directories or files")
-          .value_hint(ValueHint::AnyPath)
-          .hide(true);
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("allow-hrtime")
-          .long("allow-hrtime")
+          .help_heading(TEST_HEADING)
+      )
+      .arg(
+        Arg::new("hide-stacktraces")
+          .long("hide-stacktraces")
+          .help("Hide stack traces for errors in failure test results.")
           .action(ArgAction::SetTrue)
-          .help("REMOVED in Deno 2.0")
-          .hide(true);
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = Arg::new("deny-hrtime")
-          .long("deny-hrtime")
+      )
+      .arg(
+        Arg::new("otel")
+          .long("otel")
+          .help(cstr!("Export a span per test file, test and step to an OpenTelemetry collector over OTLP/HTTP.
+  <p(245)>The collector is configured with OTEL_EXPORTER_OTLP_TRACES_ENDPOINT or OTEL_EXPORTER_OTLP_ENDPOINT and defaults to http://localhost:4318</>"))
           .action(ArgAction::SetTrue)
-          .help("REMOVED in Deno 2.0")
-          .hide(true);
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
+          .help_heading(TEST_HEADING)
+      )
+      .arg(env_file_arg())
+      .arg(executable_ext_arg())
     )
-    .arg(
-      {
-        let mut arg = Arg::new("no-prompt")
-          .long("no-prompt")
+}
+
+fn parse_test_reporter(
+  text: &str,
+) -> Result<(TestReporterConfig, Option<String>), String> {
+  let (name, path) = match text.split_once(':') {
+    Some((name, path)) => (name, Some(path)),
+    None => (text, None),
+  };
+  let reporter = match name {
+    "pretty" => TestReporterConfig::Pretty,
+    "dot" => TestReporterConfig::Dot,
+    "junit" => TestReporterConfig::Junit,
+    "tap" => TestReporterConfig::Tap,
+    "json" => TestReporterConfig::Json,
+    _ => {
+      return Err(format!(
+        "invalid reporter '{name}', expected one of pretty, dot, junit, tap or json"
+      ))
+    }
+  };
+  match path {
+    Some("") => Err(format!("missing path after '{name}:'")),
+    Some(_)
+      if !matches!(
+        reporter,
+        TestReporterConfig::Junit | TestReporterConfig::Json
+      ) =>
+    {
+      Err(format!(
+        "the {name} reporter can only write to stdout, only junit and json can write to a file"
+      ))
+    }
+    path => Ok((reporter, path.map(|path| path.to_string()))),
+  }
+}
+
+fn parallel_arg(descr: &str) -> Arg {
+  Arg::new("parallel")
+    .long("parallel")
+    .help(format!("Run {descr} in parallel. Parallelism defaults to the number of available CPUs or the value of the DENO_JOBS environment variable"))
+    .action(ArgAction::SetTrue)
+}
+
+fn types_subcommand() -> Command {
+  command(
+    "types",
+    cstr!(
+      "Print runtime TypeScript declarations.
+
+  <p(245)>deno types > lib.deno.d.ts</>
+
+The declaration file could be saved and used for typing information."
+    ),
+    UnstableArgsConfig::None,
+  )
+}
+
+pub static UPGRADE_USAGE: &str = cstr!(
+  "<g>Latest</>
+  <bold>deno upgrade</>
+
+<g>Specific version</>
+  <bold>deno upgrade</> <p(245)>1.45.0</>
+  <bold>deno upgrade</> <p(245)>1.46.0-rc.1</>
+  <bold>deno upgrade</> <p(245)>9bc2dd29ad6ba334fd57a20114e367d3c04763d4</>
+
+<g>Channel</>
+  <bold>deno upgrade</> <p(245)>stable</>
+  <bold>deno upgrade</> <p(245)>rc</>
+  <bold>deno upgrade</> <p(245)>canary</>"
+);
+
+fn upgrade_subcommand() -> Command {
+  command(
+    "upgrade",
+    color_print::cformat!("Upgrade deno executable to the given version.
+
+{}
+
+The version is downloaded from <p(245)>https://dl.deno.land</> and is used to replace the current executable.
+
+If you want to not replace the current Deno executable but instead download an update to a
+different location, use the <c>--output</> flag:
+  <p(245)>deno upgrade --output $HOME/my_deno</>
+
+To upgrade from the releases of a fork on GitHub or from a mirror of <p(245)>https://dl.deno.land</>, use the
+<c>--repo</> or <c>--url</> flags. The SHA-256 checksums of the executables must be published next to the
+archives as <p(245)>deno-<<target>>.sha256sum</> files, like the official releases do:
+  <p(245)>deno upgrade --url https://deno-mirror.example.com</>
+
+<y>Read more:</> <c>https://docs.deno.com/go/upgrade</>", UPGRADE_USAGE),
+    UnstableArgsConfig::None,
+  )
+  .hide(cfg!(not(feature = "upgrade")))
+  .defer(|cmd| {
+    cmd
+      .arg(
+        Arg::new("version")
+          .long("version")
+          .help("The version to upgrade to")
+          .help_heading(UPGRADE_HEADING)// NOTE(bartlomieju): pre-v1.46 compat
+          .hide(true),
+      )
+      .arg(
+        Arg::new("output")
+          .long("output")
+          .help("The path to output the updated version to")
+          .value_parser(value_parser!(String))
+          .value_hint(ValueHint::FilePath)
+          .help_heading(UPGRADE_HEADING),
+      )
+      .arg(
+        Arg::new("dry-run")
+          .long("dry-run")
+          .help("Perform all checks without replacing old exe")
           .action(ArgAction::SetTrue)
-          .hide(true)
-          .help("Always throw if required permission wasn't passed");
-        if let Some(requires) = requires {
-          arg = arg.requires(requires)
-        }
-        arg
-      }
-    )
-    .arg(
-      {
-        let mut arg = allow_import_arg().hide(true);
-        if let Some(requires) = requires {
-          // allow this for install --global
-          if requires != "global" {
-            arg = arg.requires(requires)
-          }
-        }
-        arg
-      }
+          .help_heading(UPGRADE_HEADING),
+      )
+      .arg(
+        Arg::new("force")
+          .long("force")
+          .short('f')
+          .help("Replace current exe even if not out-of-date")
+          .action(ArgAction::SetTrue)
+          .help_heading(UPGRADE_HEADING),
+      )
+      .arg(
+        Arg::new("canary")
+          .long("canary")
+          .help("Upgrade to canary builds")
+          .action(ArgAction::SetTrue)
+          .help_heading(UPGRADE_HEADING)// NOTE(bartlomieju): pre-v1.46 compat
+          .hide(true),
+      )
+      .arg(
+        Arg::new("release-candidate")
+          .long("rc")
+          .help("Upgrade to a release candidate")
+          .conflicts_with_all(["canary", "version"])
+          .action(ArgAction::SetTrue)
+          .help_heading(UPGRADE_HEADING)
+          // NOTE(bartlomieju): pre-v1.46 compat
+          .hide(true),
+      )
+      .arg(
+        Arg::new("repo")
+          .long("repo")
+          .value_name("OWNER/NAME")
+          .help("Upgrade from the releases of a fork on GitHub")
+          .value_parser(upgrade_repo_parser)
+          .help_heading(UPGRADE_HEADING),
+      )
+      .arg(
+        Arg::new("url")
+          .long("url")
+          .value_name("URL")
+          .help("Upgrade from a mirror of https://dl.deno.land")
+          .value_parser(upgrade_url_parser)
+          .value_hint(ValueHint::Url)
+          .conflicts_with("repo")
+          .help_heading(UPGRADE_HEADING),
+      )
+      .arg(
+        Arg::new("version-or-hash-or-channel")
+          .help(cstr!("Version <p(245)>(v1.46.0)</>, channel <p(245)>(rc, canary)</> or commit hash <p(245)>(9bc2dd29ad6ba334fd57a20114e367d3c04763d4)</>"))
+          .value_name("VERSION")
+          .action(ArgAction::Append)
+          .trailing_var_arg(true),
+      )
+      .arg(ca_file_arg())
+      .arg(unsafely_ignore_certificate_errors_arg())
+  })
+}
+
+fn upgrade_repo_parser(repo: &str) -> Result<String, String> {
+  let is_valid_part = |part: &str| {
+    !part.is_empty()
+      && part
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+  };
+  match repo.split_once('/') {
+    Some((owner, name)) if is_valid_part(owner) && is_valid_part(name) => {
+      Ok(repo.to_string())
+    }
+    _ => Err(format!("Bad repository: {repo}. Expected OWNER/NAME")),
+  }
+}
+
+fn upgrade_url_parser(url: &str) -> Result<Url, String> {
+  match Url::parse(url.trim_end_matches('/')) {
+    Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(url),
+    _ => Err(format!("Bad upgrade URL: {url}")),
+  }
+}
+
+fn vendor_subcommand() -> Command {
+  command("vendor",
+      "⚠️ `deno vendor` was removed in Deno 2.
+
+See the Deno 1.x to 2.x Migration Guide for migration instructions: https://docs.deno.com/runtime/manual/advanced/migrate_deprecations",
+      UnstableArgsConfig::ResolutionOnly
     )
+    .hide(true)
 }
 
-fn allow_all_arg() -> Arg {
-  Arg::new("allow-all")
-    .short('A')
-    .long("allow-all")
-    .conflicts_with("allow-read")
-    .conflicts_with("allow-write")
-    .conflicts_with("allow-net")
-    .conflicts_with("allow-env")
-    .conflicts_with("allow-run")
-    .conflicts_with("allow-sys")
-    .conflicts_with("allow-ffi")
-    .conflicts_with("allow-import")
-    .action(ArgAction::SetTrue)
-    .help("Allow all permissions")
+fn publish_subcommand() -> Command {
+  command("publish", "Publish the current working directory's package or workspace to JSR", UnstableArgsConfig::ResolutionOnly)
+    .defer(|cmd| {
+      cmd
+      .arg(
+        Arg::new("token")
+          .long("token")
+          .help("The API token to use when publishing. If unset, interactive authentication is be used")
+          .help_heading(PUBLISH_HEADING)
+      )
+        .arg(config_arg())
+        .arg(no_config_arg())
+        .arg(
+          Arg::new("dry-run")
+            .long("dry-run")
+            .help("Prepare the package for publishing performing all checks and validations without uploading")
+            .action(ArgAction::SetTrue)
+          .help_heading(PUBLISH_HEADING),
+        )
+        .arg(
+          Arg::new("allow-slow-types")
+            .long("allow-slow-types")
+            .help("Allow publishing with slow types")
+            .action(ArgAction::SetTrue)
+          .help_heading(PUBLISH_HEADING),
+        )
+        .arg(
+          Arg::new("allow-dirty")
+            .long("allow-dirty")
+            .help("Allow publishing if the repository has uncommitted changed")
+            .action(ArgAction::SetTrue)
+          .help_heading(PUBLISH_HEADING),
+        ).arg(
+        Arg::new("no-provenance")
+          .long("no-provenance")
+          .help(cstr!("Disable provenance attestation.
+  <p(245)>Enabled by default on Github actions, publicly links the package to where it was built and published from.</>"))
+          .action(ArgAction::SetTrue)
+        .help_heading(PUBLISH_HEADING)
+      )
+        .arg(
+          Arg::new("provenance-file")
+            .long("provenance-file")
+            .value_name("FILE")
+            .help(cstr!("Submit the provenance attestation of a Sigstore bundle file instead of generating one.
+  <p(245)>For publishing with provenance from CI systems other than Github actions.</>"))
+            .conflicts_with("no-provenance")
+            .value_hint(ValueHint::FilePath)
+            .help_heading(PUBLISH_HEADING),
+        )
+        .arg(
+          Arg::new("registry")
+            .long("registry")
+            .value_name("URL")
+            .help("The URL of the JSR compatible registry to publish to. Defaults to https://jsr.io/")
+            .value_parser(publish_registry_parser)
+            .value_hint(ValueHint::Url)
+            .help_heading(PUBLISH_HEADING),
+        )
+        .arg(
+          Arg::new("set-version")
+            .long("set-version")
+            .value_name("VERSION")
+            .help(cstr!("Update the version of the packages in their config files before publishing.
+  <p(245)>Either an exact version or patch, minor or major to increment the current version.</>"))
+            .value_parser(publish_set_version_parser)
+            .help_heading(PUBLISH_HEADING),
+        )
+        .arg(
+          Arg::new("tag")
+            .long("tag")
+            .help(cstr!("Commit the updated config files and create a git tag of the new version.
+  <p(245)>The tag is v<<VERSION>, or <<NAME>@<<VERSION> for each package of a workspace.</>"))
+            .requires("set-version")
+            .action(ArgAction::SetTrue)
+            .help_heading(PUBLISH_HEADING),
+        )
+        .arg(
+          Arg::new("changed-since")
+            .long("changed-since")
+            .value_name("GIT_REF")
+            .help(cstr!("Only publish the workspace members with changes since the git ref, and the members depending on them.
+  <p(245)>Versions which are already published are skipped.</>"))
+            .help_heading(PUBLISH_HEADING),
+        )
+        .arg(
+          Arg::new("diff-previous")
+            .long("diff-previous")
+            .help(cstr!("Print the files and exports that change since the previously published version.
+  <p(245)>Requires --dry-run. Helps catching accidentally included files and removed exports.</>"))
+            .requires("dry-run")
+            .action(ArgAction::SetTrue)
+            .help_heading(PUBLISH_HEADING),
+        )
+        .arg(check_arg(/* type checks by default */ true))
+        .arg(no_check_arg())
+    })
+}
+
+fn publish_registry_parser(url: &str) -> Result<Url, String> {
+  // ensure there is a trailing slash for the directory
+  let url = format!("{}/", url.trim_end_matches('/'));
+  match Url::parse(&url) {
+    Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(url),
+    _ => Err(format!("Bad registry URL: {url}")),
+  }
+}
+
+fn publish_set_version_parser(version: &str) -> Result<String, String> {
+  match version {
+    "patch" | "minor" | "major" => Ok(version.to_string()),
+    _ => deno_semver::Version::parse_standard(version)
+      .map(|_| version.to_string())
+      .map_err(|_| {
+        format!(
+          "Bad version: {version}. Expected a version, patch, minor or major"
+        )
+      }),
+  }
+}
+
+fn compile_args(app: Command) -> Command {
+  compile_args_without_check_args(app.arg(no_check_arg()))
+}
+
+fn compile_args_without_check_args(app: Command) -> Command {
+  app
+    .arg(import_map_arg())
+    .arg(no_remote_arg())
+    .arg(no_npm_arg())
+    .arg(node_modules_dir_arg())
+    .arg(conditions_arg())
+    .arg(vendor_arg())
+    .arg(vendor_npm_arg())
+    .arg(config_arg())
+    .arg(no_config_arg())
+    .arg(reload_arg())
+    .arg(lock_arg())
+    .arg(no_lock_arg())
+    .arg(npmrc_arg())
+    .arg(ca_file_arg())
+    .arg(unsafely_ignore_certificate_errors_arg())
+}
+
+fn permission_args(app: Command, requires: Option<&'static str>) -> Command {
+  app
+    .after_help(cstr!(r#"<y>Permission options:</>
+<y>Docs</>: <c>https://docs.deno.com/go/permissions</>
+
+  <g>-A, --allow-all</>                          Allow all permissions.
+  <g>--no-prompt</>                              Always throw if required permission wasn't passed.
+                                             <p(245)>Can also be set via the DENO_NO_PROMPT environment variable.</>
+  <g>-R, --allow-read[=<<PATH>...]</>             Allow file system read access. Optionally specify allowed paths.
+                                             <p(245)>--allow-read  |  --allow-read="/etc,/var/log.txt"</>
+  <g>-W, --allow-write[=<<PATH>...]</>            Allow file system write access. Optionally specify allowed paths.
+                                             <p(245)>--allow-write  |  --allow-write="/etc,/var/log.txt"</>
+  <g>-I, --allow-import[=<<IP_OR_HOSTNAME>...]</> Allow importing from remote hosts. Optionally specify allowed IP addresses and host names, with ports as necessary.
+                                            Default value: <p(245)>deno.land:443,jsr.io:443,esm.sh:443,cdn.jsdelivr.net:443,raw.githubusercontent.com:443,user.githubusercontent.com:443</>
+                                             <p(245)>--allow-import  |  --allow-import="example.com,github.com"</>
+  <g>-N, --allow-net[=<<IP_OR_HOSTNAME>...]</>    Allow network access. Optionally specify allowed IP addresses and host names, with ports as necessary.
+                                             <p(245)>--allow-net  |  --allow-net="localhost:8080,deno.land"</>
+  <g>-E, --allow-env[=<<VARIABLE_NAME>...]</>     Allow access to environment variables. Optionally specify accessible environment variables.
+                                             <p(245)>--allow-env  |  --allow-env="PORT,HOME,PATH"</>
+  <g>-S, --allow-sys[=<<API_NAME>...]</>          Allow access to OS information. Optionally allow specific APIs by function name.
+                                             <p(245)>--allow-sys  |  --allow-sys="systemMemoryInfo,osRelease"</>
+      <g>--allow-run[=<<PROGRAM_NAME>...]</>      Allow running subprocesses. Optionally specify allowed runnable program names.
+                                             <p(245)>--allow-run  |  --allow-run="whoami,ps"</>
+      <g>--allow-ffi[=<<PATH>...]</>              (Unstable) Allow loading dynamic libraries. Optionally specify allowed directories or files.
+                                             <p(245)>--allow-ffi  |  --allow-ffi="./libfoo.so"</>
+  <g>    --deny-read[=<<PATH>...]</>              Deny file system read access. Optionally specify denied paths.
+                                             <p(245)>--deny-read  |  --deny-read="/etc,/var/log.txt"</>
+  <g>    --deny-write[=<<PATH>...]</>             Deny file system write access. Optionally specify denied paths.
+                                             <p(245)>--deny-write  |  --deny-write="/etc,/var/log.txt"</>
+  <g>    --deny-net[=<<IP_OR_HOSTNAME>...]</>     Deny network access. Optionally specify defined IP addresses and host names, with ports as necessary.
+                                             <p(245)>--deny-net  |  --deny-net="localhost:8080,deno.land"</>
+  <g>    --deny-env[=<<VARIABLE_NAME>...]</>      Deny access to environment variables. Optionally specify inacessible environment variables.
+                                             <p(245)>--deny-env  |  --deny-env="PORT,HOME,PATH"</>
+  <g>    --deny-sys[=<<API_NAME>...]</>           Deny access to OS information. Optionally deny specific APIs by function name.
+                                             <p(245)>--deny-sys  |  --deny-sys="systemMemoryInfo,osRelease"</>
+      <g>--deny-run[=<<PROGRAM_NAME>...]</>       Deny running subprocesses. Optionally specify denied runnable program names.
+                                             <p(245)>--deny-run  |  --deny-run="whoami,ps"</>
+      <g>--deny-ffi[=<<PATH>...]</>               (Unstable) Deny loading dynamic libraries. Optionally specify denied directories or files.
+                                             <p(245)>--deny-ffi  |  --deny-ffi="./libfoo.so"</>
+"#))
+    .arg(
+      {
+        let mut arg = allow_all_arg().hide(true);
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("allow-read")
+          .long("allow-read")
+          .short('R')
+          .num_args(0..)
+          .action(ArgAction::Append)
+          .require_equals(true)
+          .value_name("PATH")
+          .help("Allow file system read access. Optionally specify allowed paths")
+          .value_hint(ValueHint::AnyPath)
+          .hide(true);
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("deny-read")
+          .long("deny-read")
+          .num_args(0..)
+          .action(ArgAction::Append)
+          .require_equals(true)
+          .value_name("PATH")
+          .help("Deny file system read access. Optionally specify denied paths")
+          .value_hint(ValueHint::AnyPath)
+          .hide(true);
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("allow-write")
+          .long("allow-write")
+          .short('W')
+          .num_args(0..)
+          .action(ArgAction::Append)
+          .require_equals(true)
+          .value_name("PATH")
+          .help("Allow file system write access. Optionally specify allowed paths")
+          .value_hint(ValueHint::AnyPath)
+          .hide(true);
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("deny-write")
+          .long("deny-write")
+          .num_args(0..)
+          .action(ArgAction::Append)
+          .require_equals(true)
+          .value_name("PATH")
+          .help("Deny file system write access. Optionally specify denied paths")
+          .value_hint(ValueHint::AnyPath)
+          .hide(true);
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("allow-net")
+          .long("allow-net")
+          .short('N')
+          .num_args(0..)
+          .use_value_delimiter(true)
+          .require_equals(true)
+          .value_name("IP_OR_HOSTNAME")
+          .help("Allow network access. Optionally specify allowed IP addresses and host names, with ports as necessary")
+          .value_parser(flags_net::validator)
+          .hide(true)
+          ;
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("deny-net")
+          .long("deny-net")
+          .num_args(0..)
+          .use_value_delimiter(true)
+          .require_equals(true)
+          .value_name("IP_OR_HOSTNAME")
+          .help("Deny network access. Optionally specify denied IP addresses and host names, with ports as necessary")
+          .value_parser(flags_net::validator)
+          .hide(true)
+          ;
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("allow-env")
+          .long("allow-env")
+          .short('E')
+          .num_args(0..)
+          .use_value_delimiter(true)
+          .require_equals(true)
+          .value_name("VARIABLE_NAME")
+          .help("Allow access to system environment information. Optionally specify accessible environment variables")
+          .value_parser(|key: &str| {
+            if key.is_empty() || key.contains(&['=', '\0'] as &[char]) {
+              return Err(format!("invalid key \"{key}\""));
+            }
+
+            Ok(if cfg!(windows) {
+              key.to_uppercase()
+            } else {
+              key.to_string()
+            })
+          })
+          .hide(true)
+          ;
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("deny-env")
+          .long("deny-env")
+          .num_args(0..)
+          .use_value_delimiter(true)
+          .require_equals(true)
+          .value_name("VARIABLE_NAME")
+          .help("Deny access to system environment information. Optionally specify accessible environment variables")
+          .value_parser(|key: &str| {
+            if key.is_empty() || key.contains(&['=', '\0'] as &[char]) {
+              return Err(format!("invalid key \"{key}\""));
+            }
+
+            Ok(if cfg!(windows) {
+              key.to_uppercase()
+            } else {
+              key.to_string()
+            })
+          })
+          .hide(true)
+          ;
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("allow-sys")
+          .long("allow-sys")
+          .short('S')
+          .num_args(0..)
+          .use_value_delimiter(true)
+          .require_equals(true)
+          .value_name("API_NAME")
+          .help("Allow access to OS information. Optionally allow specific APIs by function name")
+          .value_parser(|key: &str| SysDescriptor::parse(key.to_string()).map(|s| s.into_string()))
+          .hide(true)
+          ;
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("deny-sys")
+          .long("deny-sys")
+          .num_args(0..)
+          .use_value_delimiter(true)
+          .require_equals(true)
+          .value_name("API_NAME")
+          .help("Deny access to OS information. Optionally deny specific APIs by function name")
+          .value_parser(|key: &str| SysDescriptor::parse(key.to_string()).map(|s| s.into_string()))
+          .hide(true)
+          ;
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("allow-run")
+          .long("allow-run")
+          .num_args(0..)
+          .use_value_delimiter(true)
+          .require_equals(true)
+          .value_name("PROGRAM_NAME")
+          .help("Allow running subprocesses. Optionally specify allowed runnable program names")
+          .hide(true)
+          ;
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("deny-run")
+          .long("deny-run")
+          .num_args(0..)
+          .use_value_delimiter(true)
+          .require_equals(true)
+          .value_name("PROGRAM_NAME")
+          .help("Deny running subprocesses. Optionally specify denied runnable program names")
+          .hide(true)
+          ;
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("allow-ffi")
+          .long("allow-ffi")
+          .num_args(0..)
+          .action(ArgAction::Append)
+          .require_equals(true)
+          .value_name("PATH")
+          .help("(Unstable) Allow loading dynamic libraries. Optionally specify allowed directories or files")
+          .value_hint(ValueHint::AnyPath)
+          .hide(true);
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("deny-ffi")
+          .long("deny-ffi")
+          .num_args(0..)
+          .action(ArgAction::Append)
+          .require_equals(true)
+          .value_name("PATH")
+          .help("(Unstable) Deny loading dynamic libraries. Optionally specify denied directories or files")
+          .value_hint(ValueHint::AnyPath)
+          .hide(true);
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("allow-hrtime")
+          .long("allow-hrtime")
+          .action(ArgAction::SetTrue)
+          .help("REMOVED in Deno 2.0")
+          .hide(true);
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("deny-hrtime")
+          .long("deny-hrtime")
+          .action(ArgAction::SetTrue)
+          .help("REMOVED in Deno 2.0")
+          .hide(true);
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = Arg::new("no-prompt")
+          .long("no-prompt")
+          .action(ArgAction::SetTrue)
+          .hide(true)
+          .help("Always throw if required permission wasn't passed");
+        if let Some(requires) = requires {
+          arg = arg.requires(requires)
+        }
+        arg
+      }
+    )
+    .arg(
+      {
+        let mut arg = allow_import_arg().hide(true);
+        if let Some(requires) = requires {
+          // allow this for install --global
+          if requires != "global" {
+            arg = arg.requires(requires)
+          }
+        }
+        arg
+      }
+    )
+}
+
+fn allow_all_arg() -> Arg {
+  Arg::new("allow-all")
+    .short('A')
+    .long("allow-all")
+    .conflicts_with("allow-read")
+    .conflicts_with("allow-write")
+    .conflicts_with("allow-net")
+    .conflicts_with("allow-env")
+    .conflicts_with("allow-run")
+    .conflicts_with("allow-sys")
+    .conflicts_with("allow-ffi")
+    .conflicts_with("allow-import")
+    .action(ArgAction::SetTrue)
+    .help("Allow all permissions")
+}
+
+fn runtime_args(
+  app: Command,
+  include_perms: bool,
+  include_inspector: bool,
+) -> Command {
+  let app = compile_args(app);
+  let app = if include_perms {
+    permission_args(app, None)
+  } else {
+    app
+  };
+  let app = if include_inspector {
+    inspect_args(app)
+  } else {
+    app
+  };
+  app
+    .arg(frozen_lockfile_arg())
+    .arg(cached_only_arg())
+    .arg(location_arg())
+    .arg(v8_flags_arg())
+    .arg(seed_arg())
+    .arg(redact_env_arg())
+    .arg(enable_testing_features_arg())
+    .arg(strace_ops_arg())
+}
+
+fn allow_import_arg() -> Arg {
+  Arg::new("allow-import")
+    .long("allow-import")
+    .short('I')
+    .num_args(0..)
+    .use_value_delimiter(true)
+    .require_equals(true)
+    .value_name("IP_OR_HOSTNAME")
+    .help(cstr!(
+      "Allow importing from remote hosts. Optionally specify allowed IP addresses and host names, with ports as necessary. Default value: <p(245)>deno.land:443,jsr.io:443,esm.sh:443,cdn.jsdelivr.net:443,raw.githubusercontent.com:443,user.githubusercontent.com:443</>"
+    ))
+    .value_parser(flags_net::validator)
+}
+
+fn inspect_args(app: Command) -> Command {
+  app
+    .arg(
+      Arg::new("inspect")
+        .long("inspect")
+        .value_name("HOST_AND_PORT")
+        .default_missing_value("127.0.0.1:9229")
+        .help(cstr!("Activate inspector on host:port <p(245)>[default: 127.0.0.1:9229]</>"))
+        .num_args(0..=1)
+        .require_equals(true)
+        .value_parser(value_parser!(SocketAddr))
+        .help_heading(DEBUGGING_HEADING),
+    )
+    .arg(
+      Arg::new("inspect-brk")
+        .long("inspect-brk")
+        .value_name("HOST_AND_PORT")
+        .default_missing_value("127.0.0.1:9229")
+        .env("DENO_INSPECT_BRK")
+        .help(
+          "Activate inspector on host:port, wait for debugger to connect and break at the start of user script",
+        )
+        .num_args(0..=1)
+        .require_equals(true)
+        .value_parser(value_parser!(SocketAddr))
+        .help_heading(DEBUGGING_HEADING),
+    )
+    .arg(
+      Arg::new("inspect-wait")
+        .long("inspect-wait")
+        .value_name("HOST_AND_PORT")
+        .default_missing_value("127.0.0.1:9229")
+        .help(
+          "Activate inspector on host:port and wait for debugger to connect before running user code",
+        )
+        .num_args(0..=1)
+        .require_equals(true)
+        .value_parser(value_parser!(SocketAddr))
+        .help_heading(DEBUGGING_HEADING),
+    )
+    .arg(
+      Arg::new("inspect-connect")
+        .long("inspect-connect")
+        .value_name("HOST_AND_PORT")
+        .help(cstr!(
+          "Connect the inspector to a debugger listening on host:port and wait for the session before running user code
+  <p(245)>For debugging in containers and networks where the inspector can't accept connections</>"
+        ))
+        .require_equals(true)
+        .value_parser(inspect_connect_parser)
+        .conflicts_with_all(["inspect", "inspect-brk", "inspect-wait"])
+        .help_heading(DEBUGGING_HEADING),
+    )
+}
+
+/// Validates the `host:port` of `--inspect-connect`, where the host can also
+/// be a name, like the one of the machine running a container.
+fn inspect_connect_parser(value: &str) -> Result<String, String> {
+  match value.rsplit_once(':') {
+    Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {
+      Ok(value.to_string())
+    }
+    _ => Err(format!("Expected HOST:PORT, got \"{value}\"")),
+  }
+}
+
+fn import_map_arg() -> Arg {
+  Arg::new("import-map")
+    .long("import-map")
+    .alias("importmap")
+    .value_name("FILE")
+    .help(cstr!(
+      "Load import map file from local file or remote URL.
+  Can be specified multiple times to layer import maps, where the entries of the later maps take precedence
+  <p(245)>Docs: https://docs.deno.com/runtime/manual/basics/import_maps</>",
+    ))
+    .action(ArgAction::Append)
+    .value_hint(ValueHint::FilePath)
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+fn env_file_arg() -> Arg {
+  Arg::new("env-file")
+    .long("env-file")
+    .alias("env")
+    .value_name("FILE")
+    .help(cstr!(
+      "Load environment variables from local file
+  <p(245)>Can be passed multiple times, later definitions override earlier ones.
+  Values can reference other variables with ${VAR}.
+  Existing process environment variables are not overwritten.</>"
+    ))
+    .value_hint(ValueHint::FilePath)
+    .default_missing_value(".env")
+    .require_equals(true)
+    .num_args(0..=1)
+    .action(ArgAction::Append)
+}
+
+fn reload_arg() -> Arg {
+  Arg::new("reload")
+    .short('r')
+    .num_args(0..)
+    .action(ArgAction::Append)
+    .require_equals(true)
+    .long("reload")
+    .value_name("CACHE_BLOCKLIST")
+    .help(
+      cstr!("Reload source code cache (recompile TypeScript)
+  <p(245)>no value                                                 Reload everything
+  jsr:@std/http/file-server,jsr:@std/assert/assert-equals  Reloads specific modules
+  npm:                                                     Reload all npm modules
+  npm:chalk                                                Reload specific npm module</>",
+    ))
+    .value_hint(ValueHint::FilePath)
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+fn ca_file_arg() -> Arg {
+  Arg::new("cert")
+    .long("cert")
+    .value_name("FILE")
+    .help("Load certificate authority from PEM encoded file")
+    .value_hint(ValueHint::FilePath)
+}
+
+fn cached_only_arg() -> Arg {
+  Arg::new("cached-only")
+    .long("cached-only")
+    .action(ArgAction::SetTrue)
+    .help("Require that remote dependencies are already cached")
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+fn frozen_lockfile_arg() -> Arg {
+  Arg::new("frozen")
+    .long("frozen")
+    .alias("frozen-lockfile")
+    .value_parser(value_parser!(bool))
+    .value_name("BOOLEAN")
+    .num_args(0..=1)
+    .require_equals(true)
+    .default_missing_value("true")
+    .help("Error out if lockfile is out of date")
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+/// Used for subcommands that operate on executable scripts only.
+/// `deno fmt` has its own `--ext` arg because its possible values differ.
+/// If --ext is not provided and the script doesn't have a file extension,
+/// deno_graph::parse_module() defaults to js.
+fn executable_ext_arg() -> Arg {
+  Arg::new("ext")
+    .long("ext")
+    .help("Set content type of the supplied file")
+    .value_parser(["ts", "tsx", "js", "jsx"])
+}
+
+fn location_arg() -> Arg {
+  Arg::new("location")
+    .long("location")
+    .value_name("HREF")
+    .value_parser(|href: &str| -> Result<Url, String> {
+      let url = Url::parse(href);
+      if url.is_err() {
+        return Err("Failed to parse URL".to_string());
+      }
+      let mut url = url.unwrap();
+      if !["http", "https"].contains(&url.scheme()) {
+        return Err("Expected protocol \"http\" or \"https\"".to_string());
+      }
+      url.set_username("").unwrap();
+      url.set_password(None).unwrap();
+      Ok(url)
+    })
+    .help(cstr!(
+      "Value of <p(245)>globalThis.location</> used by some web APIs"
+    ))
+    .value_hint(ValueHint::Url)
+}
+
+fn enable_testing_features_arg() -> Arg {
+  Arg::new("enable-testing-features-do-not-use")
+    .long("enable-testing-features-do-not-use")
+    .help("INTERNAL: Enable internal features used during integration testing")
+    .action(ArgAction::SetTrue)
+    .hide(true)
+}
+
+fn strace_ops_arg() -> Arg {
+  Arg::new("strace-ops")
+    .long("strace-ops")
+    .num_args(0..)
+    .use_value_delimiter(true)
+    .require_equals(true)
+    .value_name("OPS")
+    .help("Trace low-level op calls")
+    .hide(true)
+}
+
+fn v8_flags_arg() -> Arg {
+  Arg::new("v8-flags")
+    .long("v8-flags")
+    .num_args(..)
+    .use_value_delimiter(true)
+    .require_equals(true)
+    .value_name("V8_FLAGS")
+    .help( cstr!("To see a list of all available flags use --v8-flags=--help
+  <p(245)>Flags can also be set via the DENO_V8_FLAGS environment variable.
+  Any flags set with this flag are appended after the DENO_V8_FLAGS environment variable</>"))
+}
+
+fn redact_env_arg() -> Arg {
+  Arg::new("redact-env")
+    .long("redact-env")
+    .num_args(1..)
+    .use_value_delimiter(true)
+    .require_equals(true)
+    .action(ArgAction::Append)
+    .value_name("VARIABLE_NAME")
+    .help(cstr!(
+      "Mask the values of environment variables in the console, error and log output.
+  <p(245)>Adds to the \"redactEnv\" variables of the config file.
+  Output written with Deno.stdout.write(), Deno.stderr.write() or by subprocesses is not masked.</>"
+    ))
+}
+
+fn seed_arg() -> Arg {
+  Arg::new("seed")
+    .long("seed")
+    .value_name("NUMBER")
+    .help("Set the random number generator seed")
+    .value_parser(value_parser!(u64))
+}
+
+fn hmr_arg(takes_files: bool) -> Arg {
+  let arg = Arg::new("hmr")
+    .long("watch-hmr")
+    // NOTE(bartlomieju): compatibility with Deno pre-1.46
+    .alias("unstable-hmr")
+    .help("Watch for file changes and hot replace modules")
+    .conflicts_with("watch")
+    .help_heading(FILE_WATCHING_HEADING);
+
+  if takes_files {
+    arg
+      .value_name("FILES")
+      .num_args(0..)
+      .action(ArgAction::Append)
+      .require_equals(true)
+      .help(
+        cstr!(
+        "Watch for file changes and restart process automatically.
+  <p(245)>Local files from entry point module graph are watched by default.
+  Additional paths might be watched by passing them as arguments to this flag.</>"),
+      )
+      .value_hint(ValueHint::AnyPath)
+  } else {
+    arg.action(ArgAction::SetTrue).help(cstr!(
+      "Watch for file changes and restart process automatically.
+  <p(245)>Only local files from entry point module graph are watched.</>"
+    ))
+  }
+}
+
+fn watch_arg(takes_files: bool) -> Arg {
+  let arg = Arg::new("watch")
+    .long("watch")
+    .help_heading(FILE_WATCHING_HEADING);
+
+  if takes_files {
+    arg
+      .value_name("FILES")
+      .num_args(0..)
+      .action(ArgAction::Append)
+      .require_equals(true)
+      .help(
+        cstr!(
+        "Watch for file changes and restart process automatically.
+  <p(245)>Local files from entry point module graph are watched by default.
+  Additional paths might be watched by passing them as arguments to this flag.</>"),
+      )
+      .value_hint(ValueHint::AnyPath)
+  } else {
+    arg.action(ArgAction::SetTrue).help(cstr!(
+      "Watch for file changes and restart process automatically.
+  <p(245)>Only local files from entry point module graph are watched.</>"
+    ))
+  }
+}
+
+fn no_clear_screen_arg() -> Arg {
+  Arg::new("no-clear-screen")
+    .requires("watch")
+    .long("no-clear-screen")
+    .action(ArgAction::SetTrue)
+    .help("Do not clear terminal screen when under watch mode")
+    .help_heading(FILE_WATCHING_HEADING)
+}
+
+fn no_code_cache_arg() -> Arg {
+  Arg::new("no-code-cache")
+    .long("no-code-cache")
+    .help("Disable V8 code cache feature")
+    .action(ArgAction::SetTrue)
+}
+
+fn watch_debounce_arg() -> Arg {
+  Arg::new("watch-debounce")
+    .requires("watch")
+    .long("watch-debounce")
+    .help("Wait for the given number of milliseconds without file changes before restarting [default: 200]")
+    .value_name("MS")
+    .require_equals(true)
+    .value_parser(value_parser!(u64))
+    .help_heading(FILE_WATCHING_HEADING)
+}
+
+fn watch_poll_interval_arg() -> Arg {
+  Arg::new("watch-poll-interval")
+    .requires("watch")
+    .long("watch-poll-interval")
+    .help("Poll the file system for changes every given number of milliseconds instead of relying on native file system events")
+    .value_name("MS")
+    .require_equals(true)
+    .value_parser(value_parser!(u64).range(1..))
+    .help_heading(FILE_WATCHING_HEADING)
+}
+
+fn watch_exec_arg() -> Arg {
+  Arg::new("watch-exec")
+    .requires("watch")
+    .long("watch-exec")
+    .help("Run a shell command before restarting on file changes, e.g. to regenerate code. Failures are reported but don't prevent the restart")
+    .value_name("COMMAND")
+    .require_equals(true)
+    .help_heading(FILE_WATCHING_HEADING)
+}
+
+fn watch_exclude_arg() -> Arg {
+  Arg::new("watch-exclude")
+    .long("watch-exclude")
+    .help("Exclude provided files/patterns from watch mode")
+    .value_name("FILES")
+    .num_args(0..)
+    .action(ArgAction::Append)
+    .require_equals(true)
+    .value_hint(ValueHint::AnyPath)
+    .help_heading(FILE_WATCHING_HEADING)
+}
+
+fn no_check_arg() -> Arg {
+  Arg::new("no-check")
+    .num_args(0..=1)
+    .require_equals(true)
+    .value_name("NO_CHECK_TYPE")
+    .long("no-check")
+    .help("Skip type-checking. If the value of \"remote\" is supplied, diagnostic errors from remote modules will be ignored")
+    .help_heading(TYPE_CHECKING_HEADING)
+}
+
+fn check_arg(checks_local_by_default: bool) -> Arg {
+  let arg = Arg::new("check")
+    .conflicts_with("no-check")
+    .long("check")
+    .num_args(0..=1)
+    .require_equals(true)
+    .value_name("CHECK_TYPE")
+    .help_heading(TYPE_CHECKING_HEADING);
+
+  if checks_local_by_default {
+    arg.help(
+      cstr!("Set type-checking behavior. This subcommand type-checks local modules by default, so adding --check is redundant
+  <p(245)>If the value of \"all\" is supplied, remote modules will be included.
+  Alternatively, the 'deno check' subcommand can be used</>",
+    ))
+  } else {
+    arg.help(cstr!(
+      "Enable type-checking. This subcommand does not type-check by default
+  <p(245)>If the value of \"all\" is supplied, remote modules will be included.
+  Alternatively, the 'deno check' subcommand can be used</>"
+    ))
+  }
+}
+
+fn no_daemon_arg() -> Arg {
+  Arg::new("no-daemon")
+    .long("no-daemon")
+    .action(ArgAction::SetTrue)
+    .help("Type-check in this process instead of the background check daemon enabled by DENO_CHECK_DAEMON=1")
+    .long_help("Type-check in this process instead of the background check daemon enabled by DENO_CHECK_DAEMON=1. The daemon is started on the first type-check in an interactive terminal and keeps the state of the project warm for the following ones. It exits after being idle for 10 minutes")
+    .help_heading(TYPE_CHECKING_HEADING)
+}
+
+fn script_arg() -> Arg {
+  Arg::new("script_arg")
+    .num_args(0..)
+    .action(ArgAction::Append)
+    // NOTE: these defaults are provided
+    // so `deno run --v8-flags=--help` works
+    // without specifying file to run.
+    .default_value_ifs([
+      ("v8-flags", "--help", Some("_")),
+      ("v8-flags", "-help", Some("_")),
+    ])
+    .help("Script arg")
+    .value_name("SCRIPT_ARG")
+    .value_hint(ValueHint::FilePath)
+}
+
+fn lock_arg() -> Arg {
+  Arg::new("lock")
+    .long("lock")
+    .value_name("FILE")
+    .default_missing_value("./deno.lock")
+    .help("Check the specified lock file. (If value is not provided, defaults to \"./deno.lock\")")
+    .num_args(0..=1)
+    .value_parser(value_parser!(String))
+    .value_hint(ValueHint::FilePath)
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+fn npmrc_arg() -> Arg {
+  Arg::new("npmrc")
+    .long("npmrc")
+    .value_name("FILE")
+    .help("Use the registries and credentials of the specified .npmrc file instead of discovering one. The always-auth option is not supported")
+    .value_hint(ValueHint::FilePath)
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+fn no_lock_arg() -> Arg {
+  Arg::new("no-lock")
+    .long("no-lock")
+    .action(ArgAction::SetTrue)
+    .help("Disable auto discovery of the lock file")
+    .conflicts_with("lock")
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+fn config_arg() -> Arg {
+  Arg::new("config")
+    .short('c')
+    .long("config")
+    .value_name("FILE")
+    .help(cstr!("Configure different aspects of deno including TypeScript, linting, and code formatting
+  <p(245)>Typically the configuration file will be called `deno.json` or `deno.jsonc` and
+  automatically detected; in that case this flag is not necessary.
+  Docs: https://docs.deno.com/go/config</>"))
+    .value_hint(ValueHint::FilePath)
+}
+
+fn no_config_arg() -> Arg {
+  Arg::new("no-config")
+    .long("no-config")
+    .action(ArgAction::SetTrue)
+    .help("Disable automatic loading of the configuration file")
+    .conflicts_with("config")
+}
+
+fn no_remote_arg() -> Arg {
+  Arg::new("no-remote")
+    .long("no-remote")
+    .action(ArgAction::SetTrue)
+    .help("Do not resolve remote modules")
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+fn no_npm_arg() -> Arg {
+  Arg::new("no-npm")
+    .long("no-npm")
+    .action(ArgAction::SetTrue)
+    .help("Do not resolve npm modules")
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+fn conditions_arg() -> Arg {
+  Arg::new("conditions")
+    .long("conditions")
+    .value_name("CONDITIONS")
+    .action(ArgAction::Append)
+    .value_delimiter(',')
+    .help(cstr!("Additional conditions for resolving the \"exports\" and \"imports\" of npm packages
+  <p(245)>--conditions=development,react-server</>"))
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+fn conditions_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(conditions) = matches.remove_many::<String>("conditions") {
+    flags.node_conditions = conditions.collect();
+  }
+}
+
+/// The value of `--node-modules-dir`, where `linked` is the `auto` mode with
+/// the files of the packages hard linked from a content addressed store.
+#[derive(Debug, Clone, Copy)]
+enum NodeModulesDirArg {
+  Mode(NodeModulesDirMode),
+  Linked,
+}
+
+fn node_modules_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let value = matches.remove_one::<NodeModulesDirArg>("node-modules-dir");
+  match value {
+    Some(NodeModulesDirArg::Mode(mode)) => {
+      flags.node_modules_dir = Some(mode);
+    }
+    Some(NodeModulesDirArg::Linked) => {
+      flags.node_modules_dir = Some(NodeModulesDirMode::Auto);
+      flags.node_modules_linked = true;
+    }
+    None => {}
+  }
+}
+
+fn node_modules_dir_arg() -> Arg {
+  fn parse_node_modules_dir_mode(s: &str) -> Result<NodeModulesDirArg, String> {
+    match s {
+      "auto" | "true" => Ok(NodeModulesDirArg::Mode(NodeModulesDirMode::Auto)),
+      "manual" => Ok(NodeModulesDirArg::Mode(NodeModulesDirMode::Manual)),
+      "none" | "false" => Ok(NodeModulesDirArg::Mode(NodeModulesDirMode::None)),
+      "linked" => Ok(NodeModulesDirArg::Linked),
+      _ => Err(format!(
+        "Invalid value '{}': expected \"auto\", \"manual\", \"linked\" or \"none\"",
+        s
+      )),
+    }
+  }
+
+  Arg::new("node-modules-dir")
+    .long("node-modules-dir")
+    .num_args(0..=1)
+    .default_missing_value("auto")
+    .value_parser(clap::builder::ValueParser::new(parse_node_modules_dir_mode))
+    .value_name("MODE")
+    .require_equals(true)
+    .help(cstr!("Sets the node modules management mode for npm packages
+  <p(245)>With <c>linked</>, the files of the packages are hard linked from a content addressed store, so identical files are stored once. The files are read-only since they are shared by all projects, and a modified file of the store is restored before it is linked again.</>
+  <p(245)>On macOS, where the files are already cloned copy-on-write, <c>linked</> behaves like <c>auto</>.</>"))
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+fn vendor_arg() -> Arg {
+  Arg::new("vendor")
+    .long("vendor")
+    .num_args(0..=1)
+    .value_parser(value_parser!(bool))
+    .default_missing_value("true")
+    .require_equals(true)
+    .help("Toggles local vendor folder usage for remote modules and a node_modules folder for npm packages")
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+fn vendor_npm_arg() -> Arg {
+  Arg::new("vendor-npm")
+    .long("vendor-npm")
+    .num_args(0..=1)
+    .value_parser(value_parser!(bool))
+    .default_missing_value("true")
+    .require_equals(true)
+    .help(cstr!("Toggles storing the contents of npm packages along with their integrity in the vendor folder, so they can be committed for offline use.
+  <p(245)>Enables the vendor folder unless <c>--vendor=false</> is provided.</>"))
+    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+}
+
+fn unsafely_ignore_certificate_errors_arg() -> Arg {
+  Arg::new("unsafely-ignore-certificate-errors")
+    .hide(true)
+    .long("unsafely-ignore-certificate-errors")
+    .num_args(0..)
+    .use_value_delimiter(true)
+    .require_equals(true)
+    .value_name("HOSTNAMES")
+    .help("DANGER: Disables verification of TLS certificates")
+    .value_parser(flags_net::validator)
+}
+
+fn allow_scripts_arg() -> Arg {
+  Arg::new("allow-scripts")
+    .long("allow-scripts")
+    .num_args(0..)
+    .action(ArgAction::Append)
+    .require_equals(true)
+    .value_name("PACKAGE")
+    .value_parser(parse_packages_allowed_scripts)
+    .help(cstr!("Allow running npm lifecycle scripts for the given packages
+  <p(245)>Note: Scripts will only be executed when using a node_modules directory (`--node-modules-dir`)</>"))
+}
+
+enum UnstableArgsConfig {
+  // for backwards-compatability
+  None,
+  ResolutionOnly,
+  ResolutionAndRuntime,
+}
+
+struct UnstableArgsIter {
+  idx: usize,
+  cfg: UnstableArgsConfig,
+}
+
+impl Iterator for UnstableArgsIter {
+  type Item = Arg;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let arg = if self.idx == 0 {
+      Arg::new("unstable")
+        .long("unstable")
+        .help(cstr!("Enable all unstable features and APIs. Instead of using this flag, consider enabling individual unstable features
+  <p(245)>To view the list of individual unstable feature flags, run this command again with --help=unstable</>"))
+        .action(ArgAction::SetTrue)
+        .hide(matches!(self.cfg, UnstableArgsConfig::None))
+    } else if self.idx == 1 {
+      Arg::new("unstable-bare-node-builtins")
+        .long("unstable-bare-node-builtins")
+        .help("Enable unstable bare node builtins feature")
+        .env("DENO_UNSTABLE_BARE_NODE_BUILTINS")
+        .value_parser(FalseyValueParser::new())
+        .action(ArgAction::SetTrue)
+        .hide(true)
+        .long_help(match self.cfg {
+          UnstableArgsConfig::None => None,
+          UnstableArgsConfig::ResolutionOnly
+          | UnstableArgsConfig::ResolutionAndRuntime => Some("true"),
+        })
+        .help_heading(UNSTABLE_HEADING)
+    } else if self.idx == 2 {
+      Arg::new("unstable-byonm")
+        .long("unstable-byonm")
+        .value_parser(FalseyValueParser::new())
+        .action(ArgAction::SetTrue)
+        .hide(true)
+        .help_heading(UNSTABLE_HEADING)
+    } else if self.idx == 3 {
+      Arg::new("unstable-sloppy-imports")
+      .long("unstable-sloppy-imports")
+      .help("Enable unstable resolving of specifiers by extension probing, .js to .ts, and directory probing")
+      .env("DENO_UNSTABLE_SLOPPY_IMPORTS")
+      .value_parser(FalseyValueParser::new())
+      .action(ArgAction::SetTrue)
+      .hide(true)
+      .long_help(match self.cfg {
+        UnstableArgsConfig::None => None,
+        UnstableArgsConfig::ResolutionOnly | UnstableArgsConfig::ResolutionAndRuntime => Some("true")
+      })
+      .help_heading(UNSTABLE_HEADING)
+    } else if self.idx > 3 {
+      let granular_flag = crate::UNSTABLE_GRANULAR_FLAGS.get(self.idx - 4)?;
+      Arg::new(format!("unstable-{}", granular_flag.name))
+        .long(format!("unstable-{}", granular_flag.name))
+        .help(granular_flag.help_text)
+        .action(ArgAction::SetTrue)
+        .hide(true)
+        .help_heading(UNSTABLE_HEADING)
+        // we don't render long help, so using it here as a sort of metadata
+        .long_help(if granular_flag.show_in_help {
+          match self.cfg {
+            UnstableArgsConfig::None | UnstableArgsConfig::ResolutionOnly => {
+              None
+            }
+            UnstableArgsConfig::ResolutionAndRuntime => Some("true"),
+          }
+        } else {
+          None
+        })
+    } else {
+      return None;
+    };
+    self.idx += 1;
+    Some(arg.display_order(self.idx + 1000))
+  }
+}
+
+fn unstable_args(cfg: UnstableArgsConfig) -> impl IntoIterator<Item = Arg> {
+  UnstableArgsIter { idx: 0, cfg }
+}
+
+fn allow_scripts_arg_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  let Some(parts) = matches.remove_many::<String>("allow-scripts") else {
+    return Ok(());
+  };
+  if parts.len() == 0 {
+    flags.allow_scripts = PackagesAllowedScripts::All;
+  } else {
+    flags.allow_scripts = PackagesAllowedScripts::Some(
+      parts
+        .flat_map(flat_escape_split_commas)
+        .collect::<Result<_, _>>()?,
+    );
+  }
+  Ok(())
+}
+
+fn add_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let mut add_flags = add_parse_inner(matches, None);
+  add_flags.target = workspace_member_target_parse(matches);
+  npmrc_arg_parse(flags, matches);
+  flags.subcommand = DenoSubcommand::Add(add_flags);
+}
+
+fn add_parse_inner(
+  matches: &mut ArgMatches,
+  packages: Option<clap::parser::Values<String>>,
+) -> AddFlags {
+  let packages = packages
+    .unwrap_or_else(|| matches.remove_many::<String>("packages").unwrap())
+    .collect();
+  let dev = matches.get_flag("dev");
+  AddFlags {
+    packages,
+    dev,
+    target: WorkspaceMemberTarget::Current,
+  }
+}
+
+fn remove_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.subcommand = DenoSubcommand::Remove(RemoveFlags {
+    packages: matches.remove_many::<String>("packages").unwrap().collect(),
+    target: workspace_member_target_parse(matches),
+  });
+}
+
+fn workspace_member_target_parse(
+  matches: &mut ArgMatches,
+) -> WorkspaceMemberTarget {
+  if let Some(member) = matches.remove_one::<String>("member") {
+    WorkspaceMemberTarget::Member(member)
+  } else if matches.get_flag("all-members") {
+    WorkspaceMemberTarget::AllMembers
+  } else {
+    WorkspaceMemberTarget::Current
+  }
+}
+
+fn auth_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let (subcommand, mut matches) = matches.remove_subcommand().unwrap();
+  let registry = matches.remove_one::<String>("registry").unwrap();
+  flags.subcommand = DenoSubcommand::Auth(match subcommand.as_str() {
+    "login" => AuthFlags::Login(AuthLoginFlags {
+      registry,
+      token: matches.remove_one::<String>("token"),
+      no_keychain: matches.get_flag("no-keychain"),
+    }),
+    "logout" => AuthFlags::Logout { registry },
+    _ => unreachable!(),
+  });
+}
+
+fn compat_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.subcommand = DenoSubcommand::Compat(CompatFlags {
+    paths: matches
+      .remove_many::<String>("paths")
+      .map(|paths| paths.collect())
+      .unwrap_or_default(),
+    json: matches.get_flag("json"),
+  });
+}
+
+fn config_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let (subcommand, mut matches) = matches.remove_subcommand().unwrap();
+  config_args_parse(flags, &mut matches);
+  let config_flags = match subcommand.as_str() {
+    "check" => ConfigFlags::Check {
+      json: matches.get_flag("json"),
+    },
+    "get" => ConfigFlags::Get {
+      key: matches.remove_one::<String>("key").unwrap(),
+    },
+    "set" => ConfigFlags::Set {
+      key: matches.remove_one::<String>("key").unwrap(),
+      value: matches.remove_one::<String>("value").unwrap(),
+      json: matches.get_flag("json"),
+    },
+    _ => unreachable!(),
+  };
+  flags.subcommand = DenoSubcommand::Config(config_flags);
+}
+
+fn lock_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let (subcommand, mut matches) = matches.remove_subcommand().unwrap();
+  config_args_parse(flags, &mut matches);
+  lock_arg_parse(flags, &mut matches);
+  let lock_flags = match subcommand.as_str() {
+    "export" => LockFlags::Export {
+      output: matches.remove_one::<String>("output"),
+    },
+    subcommand => {
+      let files = match matches.remove_many::<String>("files") {
+        Some(files) => files.collect(),
+        None => vec![".".to_string()],
+      };
+      match subcommand {
+        "prune" => LockFlags::Prune { files },
+        "verify" => LockFlags::Verify { files },
+        _ => unreachable!(),
+      }
+    }
+  };
+  flags.subcommand = DenoSubcommand::Lock(lock_flags);
+}
+
+fn why_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.subcommand = DenoSubcommand::Why(WhyFlags {
+    package: matches.remove_one::<String>("package").unwrap(),
+  });
+}
+
+fn audit_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let fail_on = matches.remove_one::<String>("fail-on").map(|severity| {
+    match severity.as_str() {
+      "low" => AuditSeverity::Low,
+      "moderate" => AuditSeverity::Moderate,
+      "high" => AuditSeverity::High,
+      "critical" => AuditSeverity::Critical,
+      _ => unreachable!(),
+    }
+  });
+  flags.subcommand = DenoSubcommand::Audit(AuditFlags {
+    json: matches.get_flag("json"),
+    fail_on,
+  });
+}
+
+fn outdated_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.subcommand = DenoSubcommand::Outdated(OutdatedFlags {
+    filters: match matches.remove_many::<String>("filters") {
+      Some(f) => f.collect(),
+      None => vec![],
+    },
+    json: matches.get_flag("json"),
+    update: matches.get_flag("update"),
+  });
+}
+
+fn bench_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  flags.type_check_mode = TypeCheckMode::Local;
+
+  runtime_args_parse(flags, matches, true, false)?;
+  ext_arg_parse(flags, matches);
+
+  // NOTE: `deno bench` always uses `--no-prompt`, tests shouldn't ever do
+  // interactive prompts, unless done by user code
+  flags.permissions.no_prompt = true;
+
+  let reporter = if matches.get_flag("json") {
+    BenchReporterConfig::Json
+  } else if let Some(reporter) = matches.remove_one::<String>("reporter") {
+    match reporter.as_str() {
+      "pretty" => BenchReporterConfig::Pretty,
+      "json" => BenchReporterConfig::Json,
+      "junit" => BenchReporterConfig::Junit,
+      "csv" => BenchReporterConfig::Csv,
+      _ => unreachable!(),
+    }
+  } else {
+    BenchReporterConfig::Pretty
+  };
+  let output = matches.remove_one::<String>("output");
+  let export_csv = matches.remove_one::<String>("export-csv");
+
+  let ignore = match matches.remove_many::<String>("ignore") {
+    Some(f) => f
+      .flat_map(flat_escape_split_commas)
+      .collect::<Result<_, _>>()?,
+    None => vec![],
+  };
+
+  let filter = matches.remove_one::<String>("filter");
+
+  if matches.contains_id("script_arg") {
+    flags
+      .argv
+      .extend(matches.remove_many::<String>("script_arg").unwrap());
+  }
+
+  let include = if let Some(files) = matches.remove_many::<String>("files") {
+    files.collect()
+  } else {
+    Vec::new()
+  };
+
+  let no_run = matches.get_flag("no-run");
+
+  let defaults = BenchEngineOptions::default();
+  let engine = BenchEngineOptions {
+    warmup: matches
+      .remove_one::<u64>("warmup")
+      .unwrap_or(defaults.warmup),
+    min_samples: matches
+      .remove_one::<u64>("min-samples")
+      .unwrap_or(defaults.min_samples),
+    max_time_ms: matches
+      .remove_one::<u64>("max-time")
+      .unwrap_or(defaults.max_time_ms),
+    outliers: match matches.remove_one::<String>("outliers").as_deref() {
+      Some("iqr") => BenchOutliers::Iqr,
+      Some("keep") | None => BenchOutliers::Keep,
+      _ => unreachable!(),
+    },
+  };
+
+  flags.subcommand = DenoSubcommand::Bench(BenchFlags {
+    files: FileFlags { include, ignore },
+    filter,
+    reporter,
+    output,
+    export_csv,
+    no_run,
+    watch: watch_arg_parse(matches)?,
+    engine,
+  });
+
+  Ok(())
+}
+
+fn bundle_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  compile_args_parse(flags, matches)?;
+  check_arg_parse(flags, matches);
+  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionOnly);
+  allow_import_parse(flags, matches);
+  let format = match matches.remove_one::<String>("format").as_deref() {
+    Some("iife") => BundleFormat::Iife,
+    _ => BundleFormat::Esm,
+  };
+  let platform = match matches.remove_one::<String>("platform").as_deref() {
+    Some("browser") => BundlePlatform::Browser,
+    _ => BundlePlatform::Deno,
+  };
+  let source_map = match matches.remove_one::<String>("source-map").as_deref() {
+    Some("inline") => Some(BundleSourceMap::Inline),
+    Some("external") => Some(BundleSourceMap::External),
+    Some(_) => unreachable!(),
+    None => None,
+  };
+  flags.subcommand = DenoSubcommand::Bundle(BundleFlags {
+    entrypoints: matches
+      .remove_many::<String>("entrypoints")
+      .unwrap()
+      .collect(),
+    output: matches.remove_one::<String>("output"),
+    outdir: matches.remove_one::<String>("outdir"),
+    format,
+    platform,
+    source_map,
+    external: matches
+      .remove_many::<String>("external")
+      .map(|e| e.collect())
+      .unwrap_or_default(),
+    minify: matches.get_flag("minify"),
+  });
+  Ok(())
+}
+
+fn cache_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  compile_args_parse(flags, matches)?;
+  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionOnly);
+  frozen_lockfile_arg_parse(flags, matches);
+  allow_scripts_arg_parse(flags, matches)?;
+  allow_import_parse(flags, matches);
+  let files = matches.remove_many::<String>("file").unwrap().collect();
+  flags.subcommand = DenoSubcommand::Cache(CacheFlags {
+    files,
+    dry_run: matches.get_flag("dry-run"),
+    json: matches.get_flag("json"),
+  });
+  Ok(())
+}
+
+fn check_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  flags.type_check_mode = TypeCheckMode::Local;
+  compile_args_without_check_parse(flags, matches)?;
+  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionAndRuntime);
+  let affected_by = matches
+    .remove_many::<String>("affected-by")
+    .map(|files| files.collect());
+  let files = match matches.remove_many::<String>("file") {
+    Some(files) => files.collect(),
+    None => vec![".".to_string()],
+  };
+  if matches.get_flag("all") || matches.get_flag("remote") {
+    flags.type_check_mode = TypeCheckMode::All;
+  }
+  flags.subcommand = DenoSubcommand::Check(CheckFlags {
+    files,
+    doc: matches.get_flag("doc"),
+    doc_only: matches.get_flag("doc-only"),
+    check_jobs: matches.remove_one::<NonZeroUsize>("check-jobs"),
+    affected_by,
+  });
+  no_daemon_arg_parse(flags, matches);
+  allow_import_parse(flags, matches);
+  Ok(())
+}
+
+fn clean_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.subcommand = DenoSubcommand::Clean(CleanFlags {
+    remote_match: matches.remove_one::<String>("remote-match"),
+    npm: matches.get_flag("npm"),
+    remote: matches.get_flag("remote"),
+    codegen: matches.get_flag("codegen"),
+    older_than: matches.remove_one::<Duration>("older-than"),
+    dry_run: matches.get_flag("dry-run"),
+  });
+}
+
+fn compile_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  flags.type_check_mode = TypeCheckMode::Local;
+  runtime_args_parse(flags, matches, true, false)?;
+
+  let mut script = matches.remove_many::<String>("script_arg").unwrap();
+  let source_file = script.next().unwrap();
+  let args = script.collect();
+  let output = matches.remove_one::<String>("output");
+  let target = matches.remove_one::<String>("target");
+  let icon = matches.remove_one::<String>("icon");
+  let no_terminal = matches.get_flag("no-terminal");
+  let no_licenses = matches.get_flag("no-licenses");
+  let require_lock_integrity = matches.get_flag("require-lock-integrity");
+  let include = match matches.remove_many::<String>("include") {
+    Some(f) => f.collect(),
+    None => vec![],
+  };
+  ext_arg_parse(flags, matches);
+
+  flags.subcommand = DenoSubcommand::Compile(CompileFlags {
+    source_file,
+    output,
+    args,
+    target,
+    no_terminal,
+    icon,
+    include,
+    no_licenses,
+    require_lock_integrity,
+    watch: watch_arg_parse(matches)?,
+    watch_run: matches.get_flag("watch-run"),
+    installer: matches.remove_one::<String>("installer").map(|installer| {
+      match installer.as_str() {
+        "deb" => CompileInstaller::Deb,
+        "pkg" => CompileInstaller::Pkg,
+        "msi" => CompileInstaller::Msi,
+        _ => unreachable!(),
+      }
+    }),
+    installer_version: matches.remove_one::<String>("installer-version"),
+    installer_license: matches.remove_one::<String>("installer-license"),
+    reproducible: matches.get_flag("reproducible"),
+  });
+
+  Ok(())
+}
+
+fn completions_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+  mut app: Command,
+) {
+  use clap_complete::generate;
+  use clap_complete::shells::Bash;
+  use clap_complete::shells::Fish;
+  use clap_complete::shells::PowerShell;
+  use clap_complete::shells::Zsh;
+  use clap_complete_fig::Fig;
+
+  let mut buf: Vec<u8> = vec![];
+  let name = "deno";
+
+  match matches.get_one::<String>("shell").unwrap().as_str() {
+    "bash" => generate(Bash, &mut app, name, &mut buf),
+    "fish" => generate(Fish, &mut app, name, &mut buf),
+    "powershell" => generate(PowerShell, &mut app, name, &mut buf),
+    "zsh" => generate(Zsh, &mut app, name, &mut buf),
+    "fig" => generate(Fig, &mut app, name, &mut buf),
+    _ => unreachable!(),
+  }
+
+  flags.subcommand = DenoSubcommand::Completions(CompletionsFlags {
+    buf: buf.into_boxed_slice(),
+  });
+}
+
+fn coverage_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  let files = match matches.remove_many::<String>("files") {
+    Some(f) => f.collect(),
+    None => vec!["coverage".to_string()], // default
+  };
+  let ignore = match matches.remove_many::<String>("ignore") {
+    Some(f) => f
+      .flat_map(flat_escape_split_commas)
+      .collect::<Result<Vec<_>, _>>()?,
+    None => vec![],
+  };
+  let include = match matches.remove_many::<String>("include") {
+    Some(f) => f.collect(),
+    None => vec![],
+  };
+  let exclude = match matches.remove_many::<String>("exclude") {
+    Some(f) => f.collect(),
+    None => vec![],
+  };
+  let r#type = if matches.get_flag("lcov") {
+    CoverageType::Lcov
+  } else if matches.get_flag("html") {
+    CoverageType::Html
+  } else if matches.get_flag("detailed") {
+    CoverageType::Detailed
+  } else {
+    CoverageType::Summary
+  };
+  let output = matches.remove_one::<String>("output");
+  let changed_since = matches.remove_one::<String>("changed-since");
+  let threshold = matches.remove_one::<u8>("threshold");
+  let history = matches.remove_one::<String>("history");
+  let trend = matches
+    .remove_one::<NonZeroUsize>("trend")
+    .map(|runs| runs.get());
+  flags.subcommand = DenoSubcommand::Coverage(CoverageFlags {
+    files: FileFlags {
+      include: files,
+      ignore,
+    },
+    output,
+    include,
+    exclude,
+    r#type,
+    changed_since,
+    threshold,
+    history,
+    trend,
+    include_untested: matches.get_flag("include-untested"),
+  });
+  Ok(())
+}
+
+fn doc_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionOnly);
+  import_map_arg_parse(flags, matches);
+  reload_arg_parse(flags, matches)?;
+  lock_arg_parse(flags, matches);
+  no_lock_arg_parse(flags, matches);
+  no_npm_arg_parse(flags, matches);
+  no_remote_arg_parse(flags, matches);
+  allow_import_parse(flags, matches);
+
+  let source_files_val = matches.remove_many::<String>("source_file");
+  let source_files = if let Some(val) = source_files_val {
+    let vals: Vec<String> = val.collect();
+
+    if vals.len() == 1 {
+      if vals[0] == "--builtin" {
+        DocSourceFileFlag::Builtin
+      } else {
+        DocSourceFileFlag::Paths(vec![vals[0].to_string()])
+      }
+    } else {
+      DocSourceFileFlag::Paths(
+        vals.into_iter().filter(|v| v != "--builtin").collect(),
+      )
+    }
+  } else {
+    DocSourceFileFlag::Builtin
+  };
+  let private = matches.get_flag("private");
+  let lint = matches.get_flag("lint");
+  let json = matches.get_flag("json");
+  let filter = matches.remove_one::<String>("filter");
+  let html = if matches.get_flag("html") {
+    let name = matches.remove_one::<String>("name");
+    let category_docs_path = matches.remove_one::<String>("category-docs");
+    let symbol_redirect_map_path =
+      matches.remove_one::<String>("symbol-redirect-map");
+    let strip_trailing_html = matches.get_flag("strip-trailing-html");
+    let default_symbol_map_path =
+      matches.remove_one::<String>("default-symbol-map");
+    let output = matches
+      .remove_one::<String>("output")
+      .unwrap_or(String::from("./docs/"));
+    Some(DocHtmlFlag {
+      name,
+      category_docs_path,
+      symbol_redirect_map_path,
+      default_symbol_map_path,
+      strip_trailing_html,
+      output,
+    })
+  } else {
+    None
+  };
+
+  flags.subcommand = DenoSubcommand::Doc(DocFlags {
+    source_files,
+    json,
+    lint,
+    html,
+    filter,
+    private,
+    only_deprecated: matches.get_flag("only-deprecated"),
+    min_since: matches.remove_one::<String>("min-since"),
+    openapi: matches.get_flag("openapi"),
+  });
+  Ok(())
+}
+
+fn eval_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  runtime_args_parse(flags, matches, false, true)?;
+  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionAndRuntime);
+  flags.allow_all();
+
+  ext_arg_parse(flags, matches);
+
+  let print = matches.get_flag("print");
+  let input =
+    matches
+      .remove_one::<String>("input")
+      .map(|input| match input.as_str() {
+        "json" => EvalInputMode::Json,
+        "text" => EvalInputMode::Text,
+        _ => unreachable!(),
+      });
+  let mut code = match matches.remove_many::<String>("eval") {
+    Some(snippets) => snippets.collect(),
+    None => vec![],
+  };
+  let mut code_args = matches
+    .remove_many::<String>("code_arg")
+    .into_iter()
+    .flatten();
+  if code.is_empty() {
+    code.push(code_args.next().unwrap());
+  }
+  flags.argv.extend(code_args);
+
+  flags.subcommand = DenoSubcommand::Eval(EvalFlags { print, code, input });
+  Ok(())
+}
+
+fn fmt_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  config_args_parse(flags, matches);
+  ext_arg_parse(flags, matches);
+
+  let include = match matches.remove_many::<String>("files") {
+    Some(f) => f.collect(),
+    None => vec![],
+  };
+  let ignore = match matches.remove_many::<String>("ignore") {
+    Some(f) => f
+      .flat_map(flat_escape_split_commas)
+      .collect::<Result<Vec<_>, _>>()?,
+    None => vec![],
+  };
+
+  let use_tabs = matches.remove_one::<bool>("use-tabs");
+  let line_width = matches.remove_one::<NonZeroU32>("line-width");
+  let indent_width = matches.remove_one::<NonZeroU8>("indent-width");
+  let single_quote = matches.remove_one::<bool>("single-quote");
+  let prose_wrap = matches.remove_one::<String>("prose-wrap");
+  let no_semicolons = matches.remove_one::<bool>("no-semicolons");
+  let unstable_component = matches.get_flag("unstable-component");
+  let line_ending = matches.remove_one::<String>("line-ending");
+  let bom = matches.remove_one::<String>("bom");
+  let skip_code_blocks = matches
+    .remove_many::<String>("skip-code-blocks")
+    .map(|languages| languages.collect());
+
+  flags.subcommand = DenoSubcommand::Fmt(FmtFlags {
+    check: matches.get_flag("check"),
+    files: FileFlags { include, ignore },
+    use_tabs,
+    line_width,
+    indent_width,
+    single_quote,
+    prose_wrap,
+    no_semicolons,
+    watch: watch_arg_parse(matches)?,
+    unstable_component,
+    line_ending,
+    bom,
+    skip_code_blocks,
+    output_file: matches.remove_one::<String>("output-file"),
+  });
+  Ok(())
+}
+
+fn init_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let mut lib = matches.get_flag("lib");
+  let mut serve = matches.get_flag("serve");
+  let template = match matches.remove_one::<String>("template") {
+    Some(template) => match template.as_str() {
+      "script" => None,
+      "lib" => {
+        lib = true;
+        None
+      }
+      "serve" => {
+        serve = true;
+        None
+      }
+      _ => Some(template),
+    },
+    None => None,
+  };
+  flags.subcommand = DenoSubcommand::Init(InitFlags {
+    dir: matches.remove_one::<String>("dir"),
+    lib,
+    serve,
+    npm_compat: matches.get_flag("npm-compat"),
+    template,
+  });
+}
+
+fn info_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionOnly);
+  reload_arg_parse(flags, matches)?;
+  config_args_parse(flags, matches);
+  import_map_arg_parse(flags, matches);
+  location_arg_parse(flags, matches);
+  ca_file_arg_parse(flags, matches);
+  unsafely_ignore_certificate_errors_parse(flags, matches);
+  node_modules_and_vendor_dir_arg_parse(flags, matches);
+  lock_args_parse(flags, matches);
+  npmrc_arg_parse(flags, matches);
+  no_remote_arg_parse(flags, matches);
+  no_npm_arg_parse(flags, matches);
+  allow_import_parse(flags, matches);
+  let json = matches.get_flag("json");
+  flags.subcommand = DenoSubcommand::Info(InfoFlags {
+    file: matches.remove_one::<String>("file"),
+    json,
+    http_cache: matches.get_flag("http-cache"),
+    cache_usage: matches.get_flag("cache-usage"),
+    graph: matches.remove_one::<String>("graph").map(|graph| {
+      match graph.as_str() {
+        "dot" => InfoGraphFormat::Dot,
+        "mermaid" => InfoGraphFormat::Mermaid,
+        _ => unreachable!(),
+      }
+    }),
+    graph_depth: matches.remove_one::<usize>("depth"),
+    collapse_npm: matches.get_flag("collapse-npm"),
+    unused: matches.get_flag("unused"),
+    fix: matches.get_flag("fix"),
+  });
+
+  Ok(())
+}
+
+fn install_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  runtime_args_parse(flags, matches, true, true)?;
+
+  let global = matches.get_flag("global");
+  if global {
+    let root = matches.remove_one::<String>("root");
+    let force = matches.get_flag("force");
+    let name = matches.remove_one::<String>("name");
+    let env_allowlist = matches
+      .remove_many::<String>("env-allow")
+      .map(|vars| vars.collect());
+    let cwd = matches.remove_one::<String>("cwd");
+    let service = matches.get_flag("service");
+    let mut cmd_values =
+      matches.remove_many::<String>("cmd").unwrap_or_default();
+
+    let module_url = cmd_values.next().unwrap();
+    let args = cmd_values.collect();
+
+    flags.subcommand = DenoSubcommand::Install(InstallFlags {
+      kind: InstallKind::Global(InstallFlagsGlobal {
+        name,
+        module_url,
+        args,
+        root,
+        force,
+        env_allowlist,
+        cwd,
+        service,
+      }),
+    });
+
+    return Ok(());
+  }
+
+  // allow scripts only applies to local install
+  allow_scripts_arg_parse(flags, matches)?;
+  flags.production = matches.get_flag("production");
+  if matches.get_flag("entrypoint") {
+    let entrypoints = matches.remove_many::<String>("cmd").unwrap_or_default();
+    flags.subcommand = DenoSubcommand::Install(InstallFlags {
+      kind: InstallKind::Local(InstallFlagsLocal::Entrypoints(
+        entrypoints.collect(),
+      )),
+    });
+  } else if let Some(add_files) = matches
+    .remove_many("cmd")
+    .map(|packages| add_parse_inner(matches, Some(packages)))
+  {
+    flags.subcommand = DenoSubcommand::Install(InstallFlags {
+      kind: InstallKind::Local(InstallFlagsLocal::Add(add_files)),
+    })
+  } else {
+    flags.subcommand = DenoSubcommand::Install(InstallFlags {
+      kind: InstallKind::Local(InstallFlagsLocal::TopLevel),
+    });
+  }
+  Ok(())
+}
+
+fn json_reference_parse(
+  flags: &mut Flags,
+  _matches: &mut ArgMatches,
+  mut app: Command,
+) {
+  use deno_core::serde_json::json;
+
+  app.build();
+
+  fn serialize_command(
+    mut command: Command,
+    top_level: bool,
+  ) -> deno_core::serde_json::Value {
+    let args = command
+      .get_arguments()
+      .filter(|arg| {
+        !arg.is_hide_set()
+          && if top_level {
+            arg.is_global_set()
+          } else {
+            !arg.is_global_set()
+          }
+      })
+      .map(|arg| {
+        let name = arg.get_id().as_str();
+        let short = arg.get_short();
+        let long = arg.get_long();
+        let required = arg.is_required_set();
+        let help = arg.get_help().map(|help| help.ansi().to_string());
+        let help_heading = arg
+          .get_help_heading()
+          .map(|help_heading| help_heading.to_string());
+        let usage = arg.to_string();
+
+        json!({
+          "name": name,
+          "short": short,
+          "long": long,
+          "required": required,
+          "help": help,
+          "help_heading": help_heading,
+          "usage": usage,
+        })
+      })
+      .collect::<Vec<_>>();
+
+    let name = command.get_name().to_string();
+    let about = command.get_about().map(|about| about.ansi().to_string());
+    let usage = command.render_usage().ansi().to_string();
+
+    let subcommands = command
+      .get_subcommands()
+      .map(|command| {
+        serialize_command(
+          if command
+            .get_arguments()
+            .any(|arg| arg.get_id().as_str() == "unstable")
+          {
+            enable_unstable(command.clone())
+          } else {
+            command.clone()
+          },
+          false,
+        )
+      })
+      .collect::<Vec<_>>();
+
+    json!({
+      "name": name,
+      "about": about,
+      "args": args,
+      "subcommands": subcommands,
+      "usage": usage,
+    })
+  }
+
+  flags.subcommand = DenoSubcommand::JSONReference(JSONReferenceFlags {
+    json: serialize_command(app, true),
+  })
+}
+
+fn jupyter_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionAndRuntime);
+
+  compile_args_without_check_parse(flags, matches)?;
+  permission_args_parse(flags, matches)?;
+
+  let conn_file = matches.remove_one::<String>("conn");
+  let kernel = matches.get_flag("kernel");
+  let install = matches.get_flag("install");
+  let name = matches.remove_one::<String>("name");
+  let display_name = matches.remove_one::<String>("display-name");
+  let kernel_args = matches
+    .remove_many::<String>("kernel-arg")
+    .map(|args| args.collect())
+    .unwrap_or_default();
+  let assets_dir = matches.remove_one::<String>("assets-dir");
+
+  flags.subcommand = DenoSubcommand::Jupyter(JupyterFlags {
+    install,
+    kernel,
+    conn_file,
+    name,
+    display_name,
+    kernel_args,
+    assets_dir,
+  });
+  Ok(())
+}
+
+fn uninstall_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let name = matches.remove_one::<String>("name-or-package").unwrap();
+
+  let kind = if matches.get_flag("global") {
+    let root = matches.remove_one::<String>("root");
+    let service = matches.get_flag("service");
+    UninstallKind::Global(UninstallFlagsGlobal {
+      name,
+      root,
+      service,
+    })
+  } else {
+    let packages: Vec<_> = vec![name]
+      .into_iter()
+      .chain(
+        matches
+          .remove_many::<String>("additional-packages")
+          .unwrap_or_default(),
+      )
+      .collect();
+    UninstallKind::Local(RemoveFlags {
+      packages,
+      target: WorkspaceMemberTarget::Current,
+    })
+  };
+
+  flags.subcommand = DenoSubcommand::Uninstall(UninstallFlags { kind });
+}
+
+fn lsp_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  let status = matches.get_flag("status");
+  let diagnose = matches.get_flag("diagnose");
+  let settings = matches.remove_one::<String>("settings");
+  flags.subcommand = DenoSubcommand::Lsp(LspFlags {
+    status,
+    diagnose,
+    settings,
+  });
+}
+
+fn lint_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionOnly);
+  ext_arg_parse(flags, matches);
+  config_args_parse(flags, matches);
+
+  let files = match matches.remove_many::<String>("files") {
+    Some(f) => f.collect(),
+    None => vec![],
+  };
+  let ignore = match matches.remove_many::<String>("ignore") {
+    Some(f) => f
+      .flat_map(flat_escape_split_commas)
+      .collect::<Result<Vec<_>, _>>()?,
+    None => vec![],
+  };
+  let fix = matches.get_flag("fix");
+  let rules = matches.get_flag("rules");
+  let maybe_rules_tags = matches
+    .remove_many::<String>("rules-tags")
+    .map(|f| f.collect());
+
+  let maybe_rules_include = matches
+    .remove_many::<String>("rules-include")
+    .map(|f| f.collect());
+
+  let maybe_rules_exclude = matches
+    .remove_many::<String>("rules-exclude")
+    .map(|f| f.collect());
+
+  let json = matches.get_flag("json");
+  let compact = matches.get_flag("compact");
+
+  flags.subcommand = DenoSubcommand::Lint(LintFlags {
+    files: FileFlags {
+      include: files,
+      ignore,
+    },
+    fix,
+    rules,
+    maybe_rules_tags,
+    maybe_rules_include,
+    maybe_rules_exclude,
+    json,
+    compact,
+    watch: watch_arg_parse(matches)?,
+    output_file: matches.remove_one::<String>("output-file"),
+  });
+  Ok(())
+}
+
+fn repl_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  runtime_args_parse(flags, matches, true, true)?;
+  unsafely_ignore_certificate_errors_parse(flags, matches);
+
+  let eval_files = matches
+    .remove_many::<String>("eval-file")
+    .map(|values| {
+      values
+        .flat_map(flat_escape_split_commas)
+        .collect::<Result<Vec<_>, _>>()
+    })
+    .transpose()?;
+
+  if let Some(args) = matches.remove_many::<String>("args") {
+    flags.argv.extend(args);
+  }
+
+  handle_repl_flags(
+    flags,
+    ReplFlags {
+      eval_files,
+      eval: matches.remove_one::<String>("eval"),
+      is_default_command: false,
+      save_session: matches.remove_one::<String>("save-session"),
+      auto_import: matches.get_flag("auto-import"),
+      repl_server: matches.remove_one::<SocketAddr>("repl-server"),
+    },
+  );
+  Ok(())
+}
+
+fn restart_on_failure_parse(
+  matches: &mut ArgMatches,
+) -> Option<RestartOnFailureFlags> {
+  if !matches.contains_id("restart-on-failure") {
+    return None;
+  }
+  Some(RestartOnFailureFlags {
+    max_retries: matches.remove_one::<u32>("restart-on-failure"),
+    backoff: Duration::from_millis(
+      matches.remove_one::<u64>("restart-backoff").unwrap_or(1000),
+    ),
+  })
+}
+
+fn run_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+  mut app: Command,
+  bare: bool,
+) -> clap::error::Result<()> {
+  runtime_args_parse(flags, matches, true, true)?;
+  ext_arg_parse(flags, matches);
+
+  flags.code_cache_enabled = !matches.get_flag("no-code-cache");
+
+  if let Some(mut script_arg) = matches.remove_many::<String>("script_arg") {
+    let script = script_arg.next().unwrap();
+    flags.argv.extend(script_arg);
+    let siblings = matches
+      .remove_many::<String>("sibling")
+      .into_iter()
+      .flatten()
+      .map(|sibling| {
+        sibling
+          .split_whitespace()
+          .map(|part| part.to_string())
+          .collect::<Vec<_>>()
+      })
+      .collect::<Vec<_>>();
+    if siblings.iter().any(|sibling| sibling.is_empty()) {
+      return Err(app.error(
+        clap::error::ErrorKind::InvalidValue,
+        "--sibling must be given a script to run",
+      ));
+    }
+    flags.subcommand = DenoSubcommand::Run(RunFlags {
+      script,
+      watch: watch_arg_parse_with_paths(matches)?,
+      bare,
+      ephemeral_tmp: matches.get_flag("ephemeral-tmp"),
+      restart_on_failure: restart_on_failure_parse(matches),
+      procfile: None,
+      siblings,
+      ui: matches.get_flag("ui"),
+    });
+  } else if let Some(procfile) = matches.remove_one::<String>("procfile") {
+    flags.subcommand = DenoSubcommand::Run(RunFlags {
+      script: String::new(),
+      watch: watch_arg_parse_with_paths(matches)?,
+      bare,
+      ephemeral_tmp: matches.get_flag("ephemeral-tmp"),
+      restart_on_failure: restart_on_failure_parse(matches),
+      procfile: Some(procfile),
+      siblings: vec![],
+      ui: matches.get_flag("ui"),
+    });
+  } else if bare {
+    return Err(app.override_usage("deno [OPTIONS] [COMMAND] [SCRIPT_ARG]...").error(
+      clap::error::ErrorKind::MissingRequiredArgument,
+      "[SCRIPT_ARG] may only be omitted with --v8-flags=--help, else to use the repl with arguments, please use the `deno repl` subcommand",
+    ));
+  } else {
+    return Err(app.find_subcommand_mut("run").unwrap().error(
+      clap::error::ErrorKind::MissingRequiredArgument,
+      "[SCRIPT_ARG] may only be omitted with --v8-flags=--help",
+    ));
+  }
+
+  Ok(())
 }
 
-fn runtime_args(
+fn serve_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
   app: Command,
-  include_perms: bool,
-  include_inspector: bool,
-) -> Command {
-  let app = compile_args(app);
-  let app = if include_perms {
-    permission_args(app, None)
-  } else {
-    app
-  };
-  let app = if include_inspector {
-    inspect_args(app)
+) -> clap::error::Result<()> {
+  // deno serve implies --allow-net=host:port
+  let port = matches.remove_one::<u16>("port").unwrap_or(8000);
+  let host = matches
+    .remove_one::<String>("host")
+    .unwrap_or_else(|| "0.0.0.0".to_owned());
+
+  let worker_count = parallel_arg_parse(matches).map(|v| v.get());
+  let mirror = matches.remove_one::<String>("mirror");
+  let mirror_percent =
+    matches.remove_one::<u8>("mirror-percent").unwrap_or(100);
+  let max_concurrent_uploads = matches
+    .remove_one::<NonZeroUsize>("max-concurrent-uploads")
+    .map(|n| n.get());
+  let upload_high_water_mark =
+    matches.remove_one::<usize>("upload-high-water-mark");
+  let upload_metrics_path = matches.remove_one::<String>("upload-metrics-path");
+  let idle_shutdown = matches
+    .remove_one::<NonZeroU64>("idle-shutdown")
+    .map(|n| n.get());
+  let cert = matches.remove_one::<String>("cert");
+  let key = matches.remove_one::<String>("key");
+  let tls_client_ca = matches.remove_one::<String>("tls-client-ca");
+  let tls_client_auth =
+    match matches.remove_one::<String>("tls-client-auth").as_deref() {
+      Some("optional") => TlsClientAuth::Optional,
+      _ => TlsClientAuth::Require,
+    };
+
+  runtime_args_parse(flags, matches, true, true)?;
+  // If the user didn't pass --allow-net, add this port to the network
+  // allowlist. If the host is 0.0.0.0, we add :{port} and allow the same network perms
+  // as if it was passed to --allow-net directly.
+  let mut allowed = vec![if host == "0.0.0.0" {
+    format!(":{port}")
   } else {
-    app
+    format!("{host}:{port}")
+  }];
+  // The mirrored requests are sent to the shadow backend, so allow it too.
+  if let Some(mirror) = mirror.as_ref().and_then(|m| Url::parse(m).ok()) {
+    if let (Some(mirror_host), Some(mirror_port)) =
+      (mirror.host_str(), mirror.port_or_known_default())
+    {
+      allowed.push(format!("{mirror_host}:{mirror_port}"));
+    }
+  }
+  let allowed = flags_net::parse(allowed)?;
+  match &mut flags.permissions.allow_net {
+    None if !flags.permissions.allow_all => {
+      flags.permissions.allow_net = Some(allowed)
+    }
+    None => {}
+    Some(v) => {
+      if !v.is_empty() {
+        v.extend(allowed);
+      }
+    }
+  }
+  flags.code_cache_enabled = !matches.get_flag("no-code-cache");
+
+  let mut script_arg =
+    matches.remove_many::<String>("script_arg").ok_or_else(|| {
+      let mut app = app;
+      let subcommand = &mut app.find_subcommand_mut("serve").unwrap();
+      subcommand.error(
+        clap::error::ErrorKind::MissingRequiredArgument,
+        "[SCRIPT_ARG] may only be omitted with --v8-flags=--help",
+      )
+    })?;
+
+  let script = script_arg.next().unwrap();
+  flags.argv.extend(script_arg);
+
+  ext_arg_parse(flags, matches);
+
+  flags.subcommand = DenoSubcommand::Serve(ServeFlags {
+    script,
+    watch: watch_arg_parse_with_paths(matches)?,
+    port,
+    host,
+    worker_count,
+    mirror,
+    mirror_percent,
+    max_concurrent_uploads,
+    upload_high_water_mark,
+    upload_metrics_path,
+    idle_shutdown,
+    cert,
+    key,
+    tls_client_ca,
+    tls_client_auth,
+  });
+
+  Ok(())
+}
+
+fn task_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.config_flag = matches
+    .remove_one::<String>("config")
+    .map(ConfigFlag::Path)
+    .unwrap_or(ConfigFlag::Discover);
+
+  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionAndRuntime);
+  node_modules_arg_parse(flags, matches);
+
+  let mut task_flags = TaskFlags {
+    cwd: matches.remove_one::<String>("cwd"),
+    task: None,
+    is_run: false,
   };
-  app
-    .arg(frozen_lockfile_arg())
-    .arg(cached_only_arg())
-    .arg(location_arg())
-    .arg(v8_flags_arg())
-    .arg(seed_arg())
-    .arg(enable_testing_features_arg())
-    .arg(strace_ops_arg())
+
+  if let Some((task, mut matches)) = matches.remove_subcommand() {
+    task_flags.task = Some(task);
+
+    flags.argv.extend(
+      matches
+        .remove_many::<std::ffi::OsString>("")
+        .into_iter()
+        .flatten()
+        .filter_map(|arg| arg.into_string().ok()),
+    );
+  }
+
+  flags.subcommand = DenoSubcommand::Task(task_flags);
 }
 
-fn allow_import_arg() -> Arg {
-  Arg::new("allow-import")
-    .long("allow-import")
-    .short('I')
-    .num_args(0..)
-    .use_value_delimiter(true)
-    .require_equals(true)
-    .value_name("IP_OR_HOSTNAME")
-    .help(cstr!(
-      "Allow importing from remote hosts. Optionally specify allowed IP addresses and host names, with ports as necessary. Default value: <p(245)>deno.land:443,jsr.io:443,esm.sh:443,cdn.jsdelivr.net:443,raw.githubusercontent.com:443,user.githubusercontent.com:443</>"
-    ))
-    .value_parser(flags_net::validator)
+fn parallel_arg_parse(matches: &mut ArgMatches) -> Option<NonZeroUsize> {
+  if matches.get_flag("parallel") {
+    if let Ok(value) = env::var("DENO_JOBS") {
+      value.parse::<NonZeroUsize>().ok()
+    } else {
+      std::thread::available_parallelism().ok()
+    }
+  } else {
+    None
+  }
 }
 
-fn inspect_args(app: Command) -> Command {
-  app
-    .arg(
-      Arg::new("inspect")
-        .long("inspect")
-        .value_name("HOST_AND_PORT")
-        .default_missing_value("127.0.0.1:9229")
-        .help(cstr!("Activate inspector on host:port <p(245)>[default: 127.0.0.1:9229]</>"))
-        .num_args(0..=1)
-        .require_equals(true)
-        .value_parser(value_parser!(SocketAddr))
-        .help_heading(DEBUGGING_HEADING),
-    )
-    .arg(
-      Arg::new("inspect-brk")
-        .long("inspect-brk")
-        .value_name("HOST_AND_PORT")
-        .default_missing_value("127.0.0.1:9229")
-        .help(
-          "Activate inspector on host:port, wait for debugger to connect and break at the start of user script",
-        )
-        .num_args(0..=1)
-        .require_equals(true)
-        .value_parser(value_parser!(SocketAddr))
-        .help_heading(DEBUGGING_HEADING),
+fn test_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  flags.type_check_mode = TypeCheckMode::Local;
+  runtime_args_parse(flags, matches, true, true)?;
+  ext_arg_parse(flags, matches);
+  no_daemon_arg_parse(flags, matches);
+
+  // NOTE: `deno test` always uses `--no-prompt`, tests shouldn't ever do
+  // interactive prompts, unless done by user code
+  flags.permissions.no_prompt = true;
+
+  let ignore = match matches.remove_many::<String>("ignore") {
+    Some(f) => f
+      .flat_map(flat_escape_split_commas)
+      .collect::<Result<_, _>>()?,
+    None => vec![],
+  };
+
+  let no_run = matches.get_flag("no-run");
+  let trace_leaks = matches.get_flag("trace-leaks");
+  let doc = matches.get_flag("doc");
+  #[allow(clippy::print_stderr)]
+  let permit_no_files = matches.get_flag("permit-no-files");
+  let filter = matches.remove_one::<String>("filter");
+  let clean = matches.get_flag("clean");
+
+  let fail_fast = if matches.contains_id("fail-fast") {
+    Some(
+      matches
+        .remove_one::<NonZeroUsize>("fail-fast")
+        .unwrap_or_else(|| NonZeroUsize::new(1).unwrap()),
     )
-    .arg(
-      Arg::new("inspect-wait")
-        .long("inspect-wait")
-        .value_name("HOST_AND_PORT")
-        .default_missing_value("127.0.0.1:9229")
-        .help(
-          "Activate inspector on host:port and wait for debugger to connect before running user code",
-        )
-        .num_args(0..=1)
-        .require_equals(true)
-        .value_parser(value_parser!(SocketAddr))
-        .help_heading(DEBUGGING_HEADING),
+  } else {
+    None
+  };
+
+  let shuffle = if matches.contains_id("shuffle") {
+    Some(
+      matches
+        .remove_one::<u64>("shuffle")
+        .unwrap_or_else(rand::random),
     )
-}
+  } else {
+    None
+  };
+
+  if let Some(script_arg) = matches.remove_many::<String>("script_arg") {
+    flags.argv.extend(script_arg);
+  }
+
+  let concurrent_jobs = parallel_arg_parse(matches);
+
+  let include = if let Some(files) = matches.remove_many::<String>("files") {
+    files.collect()
+  } else {
+    Vec::new()
+  };
+
+  let junit_path = matches.remove_one::<String>("junit-path");
+
+  let mut reporter = None;
+  let mut reporter_outputs = Vec::new();
+  for (config, path) in matches
+    .remove_many::<(TestReporterConfig, Option<String>)>("reporter")
+    .into_iter()
+    .flatten()
+  {
+    match path {
+      Some(path) => reporter_outputs.push(TestReporterOutput {
+        reporter: config,
+        path,
+      }),
+      None if reporter.is_some() => {
+        return Err(clap::Error::raw(
+          clap::error::ErrorKind::ArgumentConflict,
+          "Only one --reporter can write to stdout, pass NAME:PATH to write the others to files\n",
+        ));
+      }
+      None => reporter = Some(config),
+    }
+  }
+  let reporter = reporter.unwrap_or_default();
+
+  if matches!(
+    reporter,
+    TestReporterConfig::Dot
+      | TestReporterConfig::Tap
+      | TestReporterConfig::Json
+  ) {
+    flags.log_level = Some(Level::Error);
+  }
 
-fn import_map_arg() -> Arg {
-  Arg::new("import-map")
-    .long("import-map")
-    .alias("importmap")
-    .value_name("FILE")
-    .help(cstr!(
-      "Load import map file from local file or remote URL
-  <p(245)>Docs: https://docs.deno.com/runtime/manual/basics/import_maps</>",
-    ))
-    .value_hint(ValueHint::FilePath)
-    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
-}
+  let hide_stacktraces = matches.get_flag("hide-stacktraces");
 
-fn env_file_arg() -> Arg {
-  Arg::new("env-file")
-    .long("env-file")
-    .alias("env")
-    .value_name("FILE")
-    .help(cstr!(
-      "Load environment variables from local file
-  <p(245)>Only the first environment variable with a given key is used.
-  Existing process environment variables are not overwritten.</>"
-    ))
-    .value_hint(ValueHint::FilePath)
-    .default_missing_value(".env")
-    .require_equals(true)
-    .num_args(0..=1)
-}
+  let coverage_report = match matches.remove_many::<String>("coverage-report") {
+    Some(reports) => reports
+      .map(|report| match report.as_str() {
+        "summary" => CoverageType::Summary,
+        "detailed" => CoverageType::Detailed,
+        "lcov" => CoverageType::Lcov,
+        "html" => CoverageType::Html,
+        _ => unreachable!(),
+      })
+      .collect(),
+    None => vec![],
+  };
+  let mut coverage_dir = matches.remove_one::<String>("coverage");
+  if !coverage_report.is_empty() && coverage_dir.is_none() {
+    coverage_dir = Some("coverage".to_string());
+  }
 
-fn reload_arg() -> Arg {
-  Arg::new("reload")
-    .short('r')
-    .num_args(0..)
-    .action(ArgAction::Append)
-    .require_equals(true)
-    .long("reload")
-    .value_name("CACHE_BLOCKLIST")
-    .help(
-      cstr!("Reload source code cache (recompile TypeScript)
-  <p(245)>no value                                                 Reload everything
-  jsr:@std/http/file-server,jsr:@std/assert/assert-equals  Reloads specific modules
-  npm:                                                     Reload all npm modules
-  npm:chalk                                                Reload specific npm module</>",
-    ))
-    .value_hint(ValueHint::FilePath)
-    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+  flags.subcommand = DenoSubcommand::Test(TestFlags {
+    no_run,
+    doc,
+    coverage_dir,
+    coverage_report,
+    clean,
+    fail_fast,
+    files: FileFlags { include, ignore },
+    filter,
+    shuffle,
+    permit_no_files,
+    concurrent_jobs,
+    trace_leaks,
+    watch: watch_arg_parse_with_paths(matches)?,
+    watch_all: matches.get_flag("watch-all"),
+    reporter,
+    junit_path,
+    reporter_outputs,
+    hide_stacktraces,
+    otel: matches.get_flag("otel"),
+    setup: matches.remove_one::<String>("setup"),
+    teardown: matches.remove_one::<String>("teardown"),
+    update_snapshots: matches.get_flag("update-snapshots"),
+    timeout: matches
+      .remove_one::<u64>("timeout")
+      .map(Duration::from_millis),
+    slow_threshold: matches
+      .remove_one::<u64>("slow-threshold")
+      .map(Duration::from_millis),
+    artifacts_dir: matches.remove_one::<String>("artifacts-dir"),
+    tags: matches
+      .remove_many::<String>("tags")
+      .map(|tags| tags.collect())
+      .unwrap_or_default(),
+    skip_tags: matches
+      .remove_many::<String>("skip-tags")
+      .map(|tags| tags.collect())
+      .unwrap_or_default(),
+  });
+  Ok(())
 }
 
-fn ca_file_arg() -> Arg {
-  Arg::new("cert")
-    .long("cert")
-    .value_name("FILE")
-    .help("Load certificate authority from PEM encoded file")
-    .value_hint(ValueHint::FilePath)
+fn types_parse(flags: &mut Flags, _matches: &mut ArgMatches) {
+  flags.subcommand = DenoSubcommand::Types;
 }
 
-fn cached_only_arg() -> Arg {
-  Arg::new("cached-only")
-    .long("cached-only")
-    .action(ArgAction::SetTrue)
-    .help("Require that remote dependencies are already cached")
-    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
-}
+fn upgrade_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  ca_file_arg_parse(flags, matches);
+  unsafely_ignore_certificate_errors_parse(flags, matches);
 
-fn frozen_lockfile_arg() -> Arg {
-  Arg::new("frozen")
-    .long("frozen")
-    .alias("frozen-lockfile")
-    .value_parser(value_parser!(bool))
-    .value_name("BOOLEAN")
-    .num_args(0..=1)
-    .require_equals(true)
-    .default_missing_value("true")
-    .help("Error out if lockfile is out of date")
-    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
+  let dry_run = matches.get_flag("dry-run");
+  let force = matches.get_flag("force");
+  let canary = matches.get_flag("canary");
+  let release_candidate = matches.get_flag("release-candidate");
+  let version = matches.remove_one::<String>("version");
+  let output = matches.remove_one::<String>("output");
+  let version_or_hash_or_channel =
+    matches.remove_one::<String>("version-or-hash-or-channel");
+  let repo = matches.remove_one::<String>("repo");
+  let url = matches.remove_one::<Url>("url");
+  flags.subcommand = DenoSubcommand::Upgrade(UpgradeFlags {
+    dry_run,
+    force,
+    release_candidate,
+    canary,
+    version,
+    output,
+    version_or_hash_or_channel,
+    repo,
+    url,
+  });
 }
 
-/// Used for subcommands that operate on executable scripts only.
-/// `deno fmt` has its own `--ext` arg because its possible values differ.
-/// If --ext is not provided and the script doesn't have a file extension,
-/// deno_graph::parse_module() defaults to js.
-fn executable_ext_arg() -> Arg {
-  Arg::new("ext")
-    .long("ext")
-    .help("Set content type of the supplied file")
-    .value_parser(["ts", "tsx", "js", "jsx"])
+fn vendor_parse(flags: &mut Flags, _matches: &mut ArgMatches) {
+  flags.subcommand = DenoSubcommand::Vendor
 }
 
-fn location_arg() -> Arg {
-  Arg::new("location")
-    .long("location")
-    .value_name("HREF")
-    .value_parser(|href: &str| -> Result<Url, String> {
-      let url = Url::parse(href);
-      if url.is_err() {
-        return Err("Failed to parse URL".to_string());
-      }
-      let mut url = url.unwrap();
-      if !["http", "https"].contains(&url.scheme()) {
-        return Err("Expected protocol \"http\" or \"https\"".to_string());
-      }
-      url.set_username("").unwrap();
-      url.set_password(None).unwrap();
-      Ok(url)
-    })
-    .help(cstr!(
-      "Value of <p(245)>globalThis.location</> used by some web APIs"
-    ))
-    .value_hint(ValueHint::Url)
-}
+fn publish_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.type_check_mode = TypeCheckMode::Local; // local by default
+  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionOnly);
+  no_check_arg_parse(flags, matches);
+  check_arg_parse(flags, matches);
+  config_args_parse(flags, matches);
 
-fn enable_testing_features_arg() -> Arg {
-  Arg::new("enable-testing-features-do-not-use")
-    .long("enable-testing-features-do-not-use")
-    .help("INTERNAL: Enable internal features used during integration testing")
-    .action(ArgAction::SetTrue)
-    .hide(true)
+  flags.subcommand = DenoSubcommand::Publish(PublishFlags {
+    token: matches.remove_one("token"),
+    dry_run: matches.get_flag("dry-run"),
+    allow_slow_types: matches.get_flag("allow-slow-types"),
+    allow_dirty: matches.get_flag("allow-dirty"),
+    no_provenance: matches.get_flag("no-provenance"),
+    provenance_file: matches.remove_one("provenance-file"),
+    registry: matches.remove_one("registry"),
+    set_version: matches.remove_one("set-version"),
+    tag: matches.get_flag("tag"),
+    changed_since: matches.remove_one("changed-since"),
+    diff_previous: matches.get_flag("diff-previous"),
+  });
 }
 
-fn strace_ops_arg() -> Arg {
-  Arg::new("strace-ops")
-    .long("strace-ops")
-    .num_args(0..)
-    .use_value_delimiter(true)
-    .require_equals(true)
-    .value_name("OPS")
-    .help("Trace low-level op calls")
-    .hide(true)
+fn compile_args_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  compile_args_without_check_parse(flags, matches)?;
+  no_check_arg_parse(flags, matches);
+  check_arg_parse(flags, matches);
+  Ok(())
 }
 
-fn v8_flags_arg() -> Arg {
-  Arg::new("v8-flags")
-    .long("v8-flags")
-    .num_args(..)
-    .use_value_delimiter(true)
-    .require_equals(true)
-    .value_name("V8_FLAGS")
-    .help( cstr!("To see a list of all available flags use --v8-flags=--help
-  <p(245)>Flags can also be set via the DENO_V8_FLAGS environment variable.
-  Any flags set with this flag are appended after the DENO_V8_FLAGS environment variable</>"))
+fn compile_args_without_check_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  import_map_arg_parse(flags, matches);
+  no_remote_arg_parse(flags, matches);
+  no_npm_arg_parse(flags, matches);
+  node_modules_and_vendor_dir_arg_parse(flags, matches);
+  conditions_arg_parse(flags, matches);
+  config_args_parse(flags, matches);
+  reload_arg_parse(flags, matches)?;
+  lock_args_parse(flags, matches);
+  npmrc_arg_parse(flags, matches);
+  ca_file_arg_parse(flags, matches);
+  unsafely_ignore_certificate_errors_parse(flags, matches);
+  Ok(())
 }
 
-fn seed_arg() -> Arg {
-  Arg::new("seed")
-    .long("seed")
-    .value_name("NUMBER")
-    .help("Set the random number generator seed")
-    .value_parser(value_parser!(u64))
-}
+fn escape_and_split_commas(s: String) -> Result<Vec<String>, clap::Error> {
+  let mut result = vec![];
+  let mut current = String::new();
+  let mut chars = s.chars();
 
-fn hmr_arg(takes_files: bool) -> Arg {
-  let arg = Arg::new("hmr")
-    .long("watch-hmr")
-    // NOTE(bartlomieju): compatibility with Deno pre-1.46
-    .alias("unstable-hmr")
-    .help("Watch for file changes and hot replace modules")
-    .conflicts_with("watch")
-    .help_heading(FILE_WATCHING_HEADING);
+  while let Some(c) = chars.next() {
+    if c == ',' {
+      if let Some(next) = chars.next() {
+        if next == ',' {
+          current.push(',');
+        } else {
+          if current.is_empty() {
+            return Err(
+              std::io::Error::new(
+                std::io::ErrorKind::Other,
+                String::from("Empty values are not allowed"),
+              )
+              .into(),
+            );
+          }
+
+          result.push(current.clone());
+          current.clear();
+          current.push(next);
+        }
+      } else {
+        return Err(
+          std::io::Error::new(
+            std::io::ErrorKind::Other,
+            String::from("Empty values are not allowed"),
+          )
+          .into(),
+        );
+      }
+    } else {
+      current.push(c);
+    }
+  }
 
-  if takes_files {
-    arg
-      .value_name("FILES")
-      .num_args(0..)
-      .action(ArgAction::Append)
-      .require_equals(true)
-      .help(
-        cstr!(
-        "Watch for file changes and restart process automatically.
-  <p(245)>Local files from entry point module graph are watched by default.
-  Additional paths might be watched by passing them as arguments to this flag.</>"),
+  if current.is_empty() {
+    return Err(
+      std::io::Error::new(
+        std::io::ErrorKind::Other,
+        String::from("Empty values are not allowed"),
       )
-      .value_hint(ValueHint::AnyPath)
-  } else {
-    arg.action(ArgAction::SetTrue).help(cstr!(
-      "Watch for file changes and restart process automatically.
-  <p(245)>Only local files from entry point module graph are watched.</>"
-    ))
+      .into(),
+    );
   }
-}
 
-fn watch_arg(takes_files: bool) -> Arg {
-  let arg = Arg::new("watch")
-    .long("watch")
-    .help_heading(FILE_WATCHING_HEADING);
+  result.push(current);
 
-  if takes_files {
-    arg
-      .value_name("FILES")
-      .num_args(0..)
-      .action(ArgAction::Append)
-      .require_equals(true)
-      .help(
-        cstr!(
-        "Watch for file changes and restart process automatically.
-  <p(245)>Local files from entry point module graph are watched by default.
-  Additional paths might be watched by passing them as arguments to this flag.</>"),
-      )
-      .value_hint(ValueHint::AnyPath)
-  } else {
-    arg.action(ArgAction::SetTrue).help(cstr!(
-      "Watch for file changes and restart process automatically.
-  <p(245)>Only local files from entry point module graph are watched.</>"
-    ))
-  }
+  Ok(result)
 }
 
-fn no_clear_screen_arg() -> Arg {
-  Arg::new("no-clear-screen")
-    .requires("watch")
-    .long("no-clear-screen")
-    .action(ArgAction::SetTrue)
-    .help("Do not clear terminal screen when under watch mode")
-    .help_heading(FILE_WATCHING_HEADING)
+fn flat_escape_split_commas(str: String) -> Vec<Result<String, clap::Error>> {
+  match escape_and_split_commas(str) {
+    Ok(vec) => vec.into_iter().map(Ok).collect::<Vec<_>>(),
+    Err(e) => vec![Err(e)],
+  }
 }
 
-fn no_code_cache_arg() -> Arg {
-  Arg::new("no-code-cache")
-    .long("no-code-cache")
-    .help("Disable V8 code cache feature")
-    .action(ArgAction::SetTrue)
-}
+fn permission_args_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) -> clap::error::Result<()> {
+  if let Some(read_wl) = matches.remove_many::<String>("allow-read") {
+    let read_wl = read_wl
+      .flat_map(flat_escape_split_commas)
+      .collect::<Result<Vec<_>, _>>()?;
+    flags.permissions.allow_read = Some(read_wl);
+  }
 
-fn watch_exclude_arg() -> Arg {
-  Arg::new("watch-exclude")
-    .long("watch-exclude")
-    .help("Exclude provided files/patterns from watch mode")
-    .value_name("FILES")
-    .num_args(0..)
-    .action(ArgAction::Append)
-    .require_equals(true)
-    .value_hint(ValueHint::AnyPath)
-    .help_heading(FILE_WATCHING_HEADING)
-}
+  if let Some(read_wl) = matches.remove_many::<String>("deny-read") {
+    let read_wl = read_wl
+      .flat_map(flat_escape_split_commas)
+      .collect::<Result<Vec<_>, _>>()?;
+    flags.permissions.deny_read = Some(read_wl);
+  }
 
-fn no_check_arg() -> Arg {
-  Arg::new("no-check")
-    .num_args(0..=1)
-    .require_equals(true)
-    .value_name("NO_CHECK_TYPE")
-    .long("no-check")
-    .help("Skip type-checking. If the value of \"remote\" is supplied, diagnostic errors from remote modules will be ignored")
-    .help_heading(TYPE_CHECKING_HEADING)
-}
+  if let Some(write_wl) = matches.remove_many::<String>("allow-write") {
+    let write_wl = write_wl
+      .flat_map(flat_escape_split_commas)
+      .collect::<Result<Vec<_>, _>>()?;
+    flags.permissions.allow_write = Some(write_wl);
+  }
 
-fn check_arg(checks_local_by_default: bool) -> Arg {
-  let arg = Arg::new("check")
-    .conflicts_with("no-check")
-    .long("check")
-    .num_args(0..=1)
-    .require_equals(true)
-    .value_name("CHECK_TYPE")
-    .help_heading(TYPE_CHECKING_HEADING);
+  if let Some(write_wl) = matches.remove_many::<String>("deny-write") {
+    let write_wl = write_wl
+      .flat_map(flat_escape_split_commas)
+      .collect::<Result<Vec<_>, _>>()?;
+    flags.permissions.deny_write = Some(write_wl);
+  }
 
-  if checks_local_by_default {
-    arg.help(
-      cstr!("Set type-checking behavior. This subcommand type-checks local modules by default, so adding --check is redundant
-  <p(245)>If the value of \"all\" is supplied, remote modules will be included.
-  Alternatively, the 'deno check' subcommand can be used</>",
-    ))
-  } else {
-    arg.help(cstr!(
-      "Enable type-checking. This subcommand does not type-check by default
-  <p(245)>If the value of \"all\" is supplied, remote modules will be included.
-  Alternatively, the 'deno check' subcommand can be used</>"
-    ))
+  if let Some(net_wl) = matches.remove_many::<String>("allow-net") {
+    let net_allowlist = flags_net::parse(net_wl.collect())?;
+    flags.permissions.allow_net = Some(net_allowlist);
   }
-}
 
-fn script_arg() -> Arg {
-  Arg::new("script_arg")
-    .num_args(0..)
-    .action(ArgAction::Append)
-    // NOTE: these defaults are provided
-    // so `deno run --v8-flags=--help` works
-    // without specifying file to run.
-    .default_value_ifs([
-      ("v8-flags", "--help", Some("_")),
-      ("v8-flags", "-help", Some("_")),
-    ])
-    .help("Script arg")
-    .value_name("SCRIPT_ARG")
-    .value_hint(ValueHint::FilePath)
-}
+  if let Some(net_wl) = matches.remove_many::<String>("deny-net") {
+    let net_denylist = flags_net::parse(net_wl.collect())?;
+    flags.permissions.deny_net = Some(net_denylist);
+  }
 
-fn lock_arg() -> Arg {
-  Arg::new("lock")
-    .long("lock")
-    .value_name("FILE")
-    .default_missing_value("./deno.lock")
-    .help("Check the specified lock file. (If value is not provided, defaults to \"./deno.lock\")")
-    .num_args(0..=1)
-    .value_parser(value_parser!(String))
-    .value_hint(ValueHint::FilePath)
-    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
-}
+  if let Some(env_wl) = matches.remove_many::<String>("allow-env") {
+    flags.permissions.allow_env = Some(env_wl.collect());
+    debug!("env allowlist: {:#?}", &flags.permissions.allow_env);
+  }
 
-fn no_lock_arg() -> Arg {
-  Arg::new("no-lock")
-    .long("no-lock")
-    .action(ArgAction::SetTrue)
-    .help("Disable auto discovery of the lock file")
-    .conflicts_with("lock")
-    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
-}
+  if let Some(env_wl) = matches.remove_many::<String>("deny-env") {
+    flags.permissions.deny_env = Some(env_wl.collect());
+    debug!("env denylist: {:#?}", &flags.permissions.deny_env);
+  }
 
-fn config_arg() -> Arg {
-  Arg::new("config")
-    .short('c')
-    .long("config")
-    .value_name("FILE")
-    .help(cstr!("Configure different aspects of deno including TypeScript, linting, and code formatting
-  <p(245)>Typically the configuration file will be called `deno.json` or `deno.jsonc` and
-  automatically detected; in that case this flag is not necessary.
-  Docs: https://docs.deno.com/go/config</>"))
-    .value_hint(ValueHint::FilePath)
-}
+  if let Some(run_wl) = matches.remove_many::<String>("allow-run") {
+    flags.permissions.allow_run = Some(run_wl.collect());
+    debug!("run allowlist: {:#?}", &flags.permissions.allow_run);
+  }
 
-fn no_config_arg() -> Arg {
-  Arg::new("no-config")
-    .long("no-config")
-    .action(ArgAction::SetTrue)
-    .help("Disable automatic loading of the configuration file")
-    .conflicts_with("config")
-}
+  if let Some(run_wl) = matches.remove_many::<String>("deny-run") {
+    flags.permissions.deny_run = Some(run_wl.collect());
+    debug!("run denylist: {:#?}", &flags.permissions.deny_run);
+  }
 
-fn no_remote_arg() -> Arg {
-  Arg::new("no-remote")
-    .long("no-remote")
-    .action(ArgAction::SetTrue)
-    .help("Do not resolve remote modules")
-    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
-}
+  if let Some(sys_wl) = matches.remove_many::<String>("allow-sys") {
+    flags.permissions.allow_sys = Some(sys_wl.collect());
+    debug!("sys info allowlist: {:#?}", &flags.permissions.allow_sys);
+  }
 
-fn no_npm_arg() -> Arg {
-  Arg::new("no-npm")
-    .long("no-npm")
-    .action(ArgAction::SetTrue)
-    .help("Do not resolve npm modules")
-    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
-}
+  if let Some(sys_wl) = matches.remove_many::<String>("deny-sys") {
+    flags.permissions.deny_sys = Some(sys_wl.collect());
+    debug!("sys info denylist: {:#?}", &flags.permissions.deny_sys);
+  }
 
-fn node_modules_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  let value = matches.remove_one::<NodeModulesDirMode>("node-modules-dir");
-  if let Some(mode) = value {
-    flags.node_modules_dir = Some(mode);
+  if let Some(ffi_wl) = matches.remove_many::<String>("allow-ffi") {
+    let ffi_wl = ffi_wl
+      .flat_map(flat_escape_split_commas)
+      .collect::<Result<Vec<_>, _>>()?;
+    flags.permissions.allow_ffi = Some(ffi_wl);
+    debug!("ffi allowlist: {:#?}", &flags.permissions.allow_ffi);
   }
-}
 
-fn node_modules_dir_arg() -> Arg {
-  fn parse_node_modules_dir_mode(
-    s: &str,
-  ) -> Result<NodeModulesDirMode, String> {
-    match s {
-      "auto" | "true" => Ok(NodeModulesDirMode::Auto),
-      "manual" => Ok(NodeModulesDirMode::Manual),
-      "none" | "false" => Ok(NodeModulesDirMode::None),
-      _ => Err(format!(
-        "Invalid value '{}': expected \"auto\", \"manual\" or \"none\"",
-        s
-      )),
+  if let Some(ffi_wl) = matches.remove_many::<String>("deny-ffi") {
+    let ffi_wl = ffi_wl
+      .flat_map(flat_escape_split_commas)
+      .collect::<Result<Vec<_>, _>>()?;
+    flags.permissions.deny_ffi = Some(ffi_wl);
+    debug!("ffi denylist: {:#?}", &flags.permissions.deny_ffi);
+  }
+
+  if matches.get_flag("allow-hrtime") || matches.get_flag("deny-hrtime") {
+    // use eprintln instead of log::warn because logging hasn't been initialized yet
+    #[allow(clippy::print_stderr)]
+    {
+      eprintln!(
+        "{} `allow-hrtime` and `deny-hrtime` have been removed in Deno 2, as high resolution time is now always allowed",
+        deno_runtime::colors::yellow("Warning")
+      );
     }
   }
 
-  Arg::new("node-modules-dir")
-    .long("node-modules-dir")
-    .num_args(0..=1)
-    .default_missing_value("auto")
-    .value_parser(clap::builder::ValueParser::new(parse_node_modules_dir_mode))
-    .value_name("MODE")
-    .require_equals(true)
-    .help("Sets the node modules management mode for npm packages")
-    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
-}
-
-fn vendor_arg() -> Arg {
-  Arg::new("vendor")
-    .long("vendor")
-    .num_args(0..=1)
-    .value_parser(value_parser!(bool))
-    .default_missing_value("true")
-    .require_equals(true)
-    .help("Toggles local vendor folder usage for remote modules and a node_modules folder for npm packages")
-    .help_heading(DEPENDENCY_MANAGEMENT_HEADING)
-}
-
-fn unsafely_ignore_certificate_errors_arg() -> Arg {
-  Arg::new("unsafely-ignore-certificate-errors")
-    .hide(true)
-    .long("unsafely-ignore-certificate-errors")
-    .num_args(0..)
-    .use_value_delimiter(true)
-    .require_equals(true)
-    .value_name("HOSTNAMES")
-    .help("DANGER: Disables verification of TLS certificates")
-    .value_parser(flags_net::validator)
-}
+  if matches.get_flag("allow-all") {
+    flags.allow_all();
+  }
 
-fn allow_scripts_arg() -> Arg {
-  Arg::new("allow-scripts")
-    .long("allow-scripts")
-    .num_args(0..)
-    .action(ArgAction::Append)
-    .require_equals(true)
-    .value_name("PACKAGE")
-    .value_parser(parse_packages_allowed_scripts)
-    .help(cstr!("Allow running npm lifecycle scripts for the given packages
-  <p(245)>Note: Scripts will only be executed when using a node_modules directory (`--node-modules-dir`)</>"))
-}
+  allow_import_parse(flags, matches);
 
-enum UnstableArgsConfig {
-  // for backwards-compatability
-  None,
-  ResolutionOnly,
-  ResolutionAndRuntime,
-}
+  if matches.get_flag("no-prompt") {
+    flags.permissions.no_prompt = true;
+  }
 
-struct UnstableArgsIter {
-  idx: usize,
-  cfg: UnstableArgsConfig,
+  Ok(())
 }
 
-impl Iterator for UnstableArgsIter {
-  type Item = Arg;
-
-  fn next(&mut self) -> Option<Self::Item> {
-    let arg = if self.idx == 0 {
-      Arg::new("unstable")
-        .long("unstable")
-        .help(cstr!("Enable all unstable features and APIs. Instead of using this flag, consider enabling individual unstable features
-  <p(245)>To view the list of individual unstable feature flags, run this command again with --help=unstable</>"))
-        .action(ArgAction::SetTrue)
-        .hide(matches!(self.cfg, UnstableArgsConfig::None))
-    } else if self.idx == 1 {
-      Arg::new("unstable-bare-node-builtins")
-        .long("unstable-bare-node-builtins")
-        .help("Enable unstable bare node builtins feature")
-        .env("DENO_UNSTABLE_BARE_NODE_BUILTINS")
-        .value_parser(FalseyValueParser::new())
-        .action(ArgAction::SetTrue)
-        .hide(true)
-        .long_help(match self.cfg {
-          UnstableArgsConfig::None => None,
-          UnstableArgsConfig::ResolutionOnly
-          | UnstableArgsConfig::ResolutionAndRuntime => Some("true"),
-        })
-        .help_heading(UNSTABLE_HEADING)
-    } else if self.idx == 2 {
-      Arg::new("unstable-byonm")
-        .long("unstable-byonm")
-        .value_parser(FalseyValueParser::new())
-        .action(ArgAction::SetTrue)
-        .hide(true)
-        .help_heading(UNSTABLE_HEADING)
-    } else if self.idx == 3 {
-      Arg::new("unstable-sloppy-imports")
-      .long("unstable-sloppy-imports")
-      .help("Enable unstable resolving of specifiers by extension probing, .js to .ts, and directory probing")
-      .env("DENO_UNSTABLE_SLOPPY_IMPORTS")
-      .value_parser(FalseyValueParser::new())
-      .action(ArgAction::SetTrue)
-      .hide(true)
-      .long_help(match self.cfg {
-        UnstableArgsConfig::None => None,
-        UnstableArgsConfig::ResolutionOnly | UnstableArgsConfig::ResolutionAndRuntime => Some("true")
-      })
-      .help_heading(UNSTABLE_HEADING)
-    } else if self.idx > 3 {
-      let granular_flag = crate::UNSTABLE_GRANULAR_FLAGS.get(self.idx - 4)?;
-      Arg::new(format!("unstable-{}", granular_flag.name))
-        .long(format!("unstable-{}", granular_flag.name))
-        .help(granular_flag.help_text)
-        .action(ArgAction::SetTrue)
-        .hide(true)
-        .help_heading(UNSTABLE_HEADING)
-        // we don't render long help, so using it here as a sort of metadata
-        .long_help(if granular_flag.show_in_help {
-          match self.cfg {
-            UnstableArgsConfig::None | UnstableArgsConfig::ResolutionOnly => {
-              None
-            }
-            UnstableArgsConfig::ResolutionAndRuntime => Some("true"),
-          }
-        } else {
-          None
-        })
-    } else {
-      return None;
-    };
-    self.idx += 1;
-    Some(arg.display_order(self.idx + 1000))
+fn allow_import_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(imports_wl) = matches.remove_many::<String>("allow-import") {
+    let imports_allowlist = flags_net::parse(imports_wl.collect()).unwrap();
+    flags.permissions.allow_import = Some(imports_allowlist);
   }
 }
 
-fn unstable_args(cfg: UnstableArgsConfig) -> impl IntoIterator<Item = Arg> {
-  UnstableArgsIter { idx: 0, cfg }
+fn unsafely_ignore_certificate_errors_parse(
+  flags: &mut Flags,
+  matches: &mut ArgMatches,
+) {
+  if let Some(ic_wl) =
+    matches.remove_many::<String>("unsafely-ignore-certificate-errors")
+  {
+    let ic_allowlist = flags_net::parse(ic_wl.collect()).unwrap();
+    flags.unsafely_ignore_certificate_errors = Some(ic_allowlist);
+  }
 }
 
-fn allow_scripts_arg_parse(
+fn runtime_args_parse(
   flags: &mut Flags,
   matches: &mut ArgMatches,
+  include_perms: bool,
+  include_inspector: bool,
 ) -> clap::error::Result<()> {
-  let Some(parts) = matches.remove_many::<String>("allow-scripts") else {
-    return Ok(());
-  };
-  if parts.len() == 0 {
-    flags.allow_scripts = PackagesAllowedScripts::All;
-  } else {
-    flags.allow_scripts = PackagesAllowedScripts::Some(
-      parts
-        .flat_map(flat_escape_split_commas)
-        .collect::<Result<_, _>>()?,
-    );
+  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionAndRuntime);
+  compile_args_parse(flags, matches)?;
+  cached_only_arg_parse(flags, matches);
+  frozen_lockfile_arg_parse(flags, matches);
+  if include_perms {
+    permission_args_parse(flags, matches)?;
+  }
+  if include_inspector {
+    inspect_arg_parse(flags, matches);
   }
+  location_arg_parse(flags, matches);
+  v8_flags_arg_parse(flags, matches);
+  seed_arg_parse(flags, matches);
+  redact_env_arg_parse(flags, matches);
+  enable_testing_features_arg_parse(flags, matches);
+  env_file_arg_parse(flags, matches);
+  strace_ops_parse(flags, matches);
   Ok(())
 }
 
-fn add_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.subcommand = DenoSubcommand::Add(add_parse_inner(matches, None));
+fn inspect_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.inspect = matches.remove_one::<SocketAddr>("inspect");
+  flags.inspect_brk = matches.remove_one::<SocketAddr>("inspect-brk");
+  flags.inspect_wait = matches.remove_one::<SocketAddr>("inspect-wait");
+  flags.inspect_connect = matches.remove_one::<String>("inspect-connect");
 }
 
-fn add_parse_inner(
-  matches: &mut ArgMatches,
-  packages: Option<clap::parser::Values<String>>,
-) -> AddFlags {
-  let packages = packages
-    .unwrap_or_else(|| matches.remove_many::<String>("packages").unwrap())
-    .collect();
-  let dev = matches.get_flag("dev");
-  AddFlags { packages, dev }
+fn import_map_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(mut paths) = matches.remove_many::<String>("import-map") {
+    flags.import_map_path = paths.next();
+    flags.import_map_overrides = paths.collect();
+  }
 }
 
-fn remove_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.subcommand = DenoSubcommand::Remove(RemoveFlags {
-    packages: matches.remove_many::<String>("packages").unwrap().collect(),
-  });
+fn env_file_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.env_file = matches
+    .remove_many::<String>("env-file")
+    .map(|files| files.collect());
 }
 
-fn bench_parse(
+fn reload_arg_parse(
   flags: &mut Flags,
   matches: &mut ArgMatches,
 ) -> clap::error::Result<()> {
-  flags.type_check_mode = TypeCheckMode::Local;
-
-  runtime_args_parse(flags, matches, true, false)?;
-  ext_arg_parse(flags, matches);
-
-  // NOTE: `deno bench` always uses `--no-prompt`, tests shouldn't ever do
-  // interactive prompts, unless done by user code
-  flags.permissions.no_prompt = true;
-
-  let json = matches.get_flag("json");
-
-  let ignore = match matches.remove_many::<String>("ignore") {
-    Some(f) => f
+  if let Some(cache_bl) = matches.remove_many::<String>("reload") {
+    let raw_cache_blocklist: Vec<String> = cache_bl
       .flat_map(flat_escape_split_commas)
-      .collect::<Result<_, _>>()?,
-    None => vec![],
-  };
-
-  let filter = matches.remove_one::<String>("filter");
-
-  if matches.contains_id("script_arg") {
-    flags
-      .argv
-      .extend(matches.remove_many::<String>("script_arg").unwrap());
+      .map(|s| s.and_then(reload_arg_validate))
+      .collect::<Result<Vec<_>, _>>()?;
+    if raw_cache_blocklist.is_empty() {
+      flags.reload = true;
+    } else {
+      flags.cache_blocklist = resolve_urls(raw_cache_blocklist);
+      debug!("cache blocklist: {:#?}", &flags.cache_blocklist);
+      flags.reload = false;
+    }
   }
 
-  let include = if let Some(files) = matches.remove_many::<String>("files") {
-    files.collect()
-  } else {
-    Vec::new()
-  };
-
-  let no_run = matches.get_flag("no-run");
-
-  flags.subcommand = DenoSubcommand::Bench(BenchFlags {
-    files: FileFlags { include, ignore },
-    filter,
-    json,
-    no_run,
-    watch: watch_arg_parse(matches)?,
-  });
-
   Ok(())
 }
 
-fn bundle_parse(flags: &mut Flags, _matches: &mut ArgMatches) {
-  flags.subcommand = DenoSubcommand::Bundle;
+fn ca_file_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.ca_data = matches.remove_one::<String>("cert").map(CaData::File);
 }
 
-fn cache_parse(
+fn enable_testing_features_arg_parse(
   flags: &mut Flags,
   matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  compile_args_parse(flags, matches)?;
-  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionOnly);
-  frozen_lockfile_arg_parse(flags, matches);
-  allow_scripts_arg_parse(flags, matches)?;
-  allow_import_parse(flags, matches);
-  let files = matches.remove_many::<String>("file").unwrap().collect();
-  flags.subcommand = DenoSubcommand::Cache(CacheFlags { files });
-  Ok(())
+) {
+  if matches.get_flag("enable-testing-features-do-not-use") {
+    flags.enable_testing_features = true
+  }
 }
 
-fn check_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  flags.type_check_mode = TypeCheckMode::Local;
-  compile_args_without_check_parse(flags, matches)?;
-  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionAndRuntime);
-  let files = matches.remove_many::<String>("file").unwrap().collect();
-  if matches.get_flag("all") || matches.get_flag("remote") {
-    flags.type_check_mode = TypeCheckMode::All;
+fn strace_ops_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(patterns) = matches.remove_many::<String>("strace-ops") {
+    flags.strace_ops = Some(patterns.collect());
   }
-  flags.subcommand = DenoSubcommand::Check(CheckFlags {
-    files,
-    doc: matches.get_flag("doc"),
-    doc_only: matches.get_flag("doc-only"),
-  });
-  allow_import_parse(flags, matches);
-  Ok(())
 }
 
-fn clean_parse(flags: &mut Flags, _matches: &mut ArgMatches) {
-  flags.subcommand = DenoSubcommand::Clean;
+fn cached_only_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if matches.get_flag("cached-only") {
+    flags.cached_only = true;
+  }
 }
 
-fn compile_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  flags.type_check_mode = TypeCheckMode::Local;
-  runtime_args_parse(flags, matches, true, false)?;
+fn frozen_lockfile_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(&v) = matches.get_one::<bool>("frozen") {
+    flags.frozen_lockfile = Some(v);
+  }
+}
 
-  let mut script = matches.remove_many::<String>("script_arg").unwrap();
-  let source_file = script.next().unwrap();
-  let args = script.collect();
-  let output = matches.remove_one::<String>("output");
-  let target = matches.remove_one::<String>("target");
-  let icon = matches.remove_one::<String>("icon");
-  let no_terminal = matches.get_flag("no-terminal");
-  let include = match matches.remove_many::<String>("include") {
-    Some(f) => f.collect(),
-    None => vec![],
-  };
-  ext_arg_parse(flags, matches);
+fn ext_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.ext = matches.remove_one::<String>("ext");
+}
 
-  flags.subcommand = DenoSubcommand::Compile(CompileFlags {
-    source_file,
-    output,
-    args,
-    target,
-    no_terminal,
-    icon,
-    include,
-  });
+fn location_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.location = matches.remove_one::<Url>("location");
+}
 
-  Ok(())
+fn v8_flags_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(v8_flags) = matches.remove_many::<String>("v8-flags") {
+    flags.v8_flags = v8_flags.collect();
+  }
 }
 
-fn completions_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-  mut app: Command,
-) {
-  use clap_complete::generate;
-  use clap_complete::shells::Bash;
-  use clap_complete::shells::Fish;
-  use clap_complete::shells::PowerShell;
-  use clap_complete::shells::Zsh;
-  use clap_complete_fig::Fig;
+fn redact_env_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(names) = matches.remove_many::<String>("redact-env") {
+    flags.redact_env = names.collect();
+  }
+}
 
-  let mut buf: Vec<u8> = vec![];
-  let name = "deno";
+fn seed_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(seed) = matches.remove_one::<u64>("seed") {
+    flags.seed = Some(seed);
 
-  match matches.get_one::<String>("shell").unwrap().as_str() {
-    "bash" => generate(Bash, &mut app, name, &mut buf),
-    "fish" => generate(Fish, &mut app, name, &mut buf),
-    "powershell" => generate(PowerShell, &mut app, name, &mut buf),
-    "zsh" => generate(Zsh, &mut app, name, &mut buf),
-    "fig" => generate(Fig, &mut app, name, &mut buf),
-    _ => unreachable!(),
+    flags.v8_flags.push(format!("--random-seed={seed}"));
   }
+}
 
-  flags.subcommand = DenoSubcommand::Completions(CompletionsFlags {
-    buf: buf.into_boxed_slice(),
-  });
+fn no_check_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(cache_type) = matches.get_one::<String>("no-check") {
+    match cache_type.as_str() {
+      "remote" => flags.type_check_mode = TypeCheckMode::Local,
+      _ => debug!(
+        "invalid value for 'no-check' of '{}' using default",
+        cache_type
+      ),
+    }
+  } else if matches.contains_id("no-check") {
+    flags.type_check_mode = TypeCheckMode::None;
+  }
 }
 
-fn coverage_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  let files = match matches.remove_many::<String>("files") {
-    Some(f) => f.collect(),
-    None => vec!["coverage".to_string()], // default
-  };
-  let ignore = match matches.remove_many::<String>("ignore") {
-    Some(f) => f
-      .flat_map(flat_escape_split_commas)
-      .collect::<Result<Vec<_>, _>>()?,
-    None => vec![],
-  };
-  let include = match matches.remove_many::<String>("include") {
-    Some(f) => f.collect(),
-    None => vec![],
-  };
-  let exclude = match matches.remove_many::<String>("exclude") {
-    Some(f) => f.collect(),
-    None => vec![],
-  };
-  let r#type = if matches.get_flag("lcov") {
-    CoverageType::Lcov
-  } else if matches.get_flag("html") {
-    CoverageType::Html
-  } else if matches.get_flag("detailed") {
-    CoverageType::Detailed
-  } else {
-    CoverageType::Summary
-  };
-  let output = matches.remove_one::<String>("output");
-  flags.subcommand = DenoSubcommand::Coverage(CoverageFlags {
-    files: FileFlags {
-      include: files,
-      ignore,
-    },
-    output,
-    include,
-    exclude,
-    r#type,
-  });
-  Ok(())
+fn check_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if let Some(cache_type) = matches.get_one::<String>("check") {
+    match cache_type.as_str() {
+      "all" => flags.type_check_mode = TypeCheckMode::All,
+      _ => debug!(
+        "invalid value for 'check' of '{}' using default",
+        cache_type
+      ),
+    }
+  } else if matches.contains_id("check") {
+    flags.type_check_mode = TypeCheckMode::Local;
+  }
 }
 
-fn doc_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionOnly);
-  import_map_arg_parse(flags, matches);
-  reload_arg_parse(flags, matches)?;
+fn lock_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
   lock_arg_parse(flags, matches);
   no_lock_arg_parse(flags, matches);
-  no_npm_arg_parse(flags, matches);
-  no_remote_arg_parse(flags, matches);
-  allow_import_parse(flags, matches);
+}
 
-  let source_files_val = matches.remove_many::<String>("source_file");
-  let source_files = if let Some(val) = source_files_val {
-    let vals: Vec<String> = val.collect();
+fn npmrc_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.npmrc = matches.remove_one::<String>("npmrc");
+}
 
-    if vals.len() == 1 {
-      if vals[0] == "--builtin" {
-        DocSourceFileFlag::Builtin
-      } else {
-        DocSourceFileFlag::Paths(vec![vals[0].to_string()])
-      }
-    } else {
-      DocSourceFileFlag::Paths(
-        vals.into_iter().filter(|v| v != "--builtin").collect(),
-      )
-    }
-  } else {
-    DocSourceFileFlag::Builtin
-  };
-  let private = matches.get_flag("private");
-  let lint = matches.get_flag("lint");
-  let json = matches.get_flag("json");
-  let filter = matches.remove_one::<String>("filter");
-  let html = if matches.get_flag("html") {
-    let name = matches.remove_one::<String>("name");
-    let category_docs_path = matches.remove_one::<String>("category-docs");
-    let symbol_redirect_map_path =
-      matches.remove_one::<String>("symbol-redirect-map");
-    let strip_trailing_html = matches.get_flag("strip-trailing-html");
-    let default_symbol_map_path =
-      matches.remove_one::<String>("default-symbol-map");
-    let output = matches
-      .remove_one::<String>("output")
-      .unwrap_or(String::from("./docs/"));
-    Some(DocHtmlFlag {
-      name,
-      category_docs_path,
-      symbol_redirect_map_path,
-      default_symbol_map_path,
-      strip_trailing_html,
-      output,
-    })
-  } else {
-    None
-  };
+fn lock_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if matches.contains_id("lock") {
+    let lockfile = matches.remove_one::<String>("lock").unwrap();
+    flags.lock = Some(lockfile);
+  }
+}
 
-  flags.subcommand = DenoSubcommand::Doc(DocFlags {
-    source_files,
-    json,
-    lint,
-    html,
-    filter,
-    private,
-  });
-  Ok(())
+fn no_lock_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
+  if matches.get_flag("no-lock") {
+    flags.no_lock = true;
+  }
+}
+
+fn config_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  flags.config_flag = if matches.get_flag("no-config") {
+    ConfigFlag::Disabled
+  } else if let Some(config) = matches.remove_one::<String>("config") {
+    ConfigFlag::Path(config)
+  } else {
+    ConfigFlag::Discover
+  };
 }
 
-fn eval_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  runtime_args_parse(flags, matches, false, true)?;
-  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionAndRuntime);
-  flags.allow_all();
-
-  ext_arg_parse(flags, matches);
+fn no_remote_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if matches.get_flag("no-remote") {
+    flags.no_remote = true;
+  }
+}
 
-  let print = matches.get_flag("print");
-  let mut code_args = matches.remove_many::<String>("code_arg").unwrap();
-  let code = code_args.next().unwrap();
-  flags.argv.extend(code_args);
+fn no_daemon_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if matches.get_flag("no-daemon") {
+    flags.no_daemon = true;
+  }
+}
 
-  flags.subcommand = DenoSubcommand::Eval(EvalFlags { print, code });
-  Ok(())
+fn no_npm_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
+  if matches.get_flag("no-npm") {
+    flags.no_npm = true;
+  }
 }
 
-fn fmt_parse(
+fn node_modules_and_vendor_dir_arg_parse(
   flags: &mut Flags,
   matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  config_args_parse(flags, matches);
-  ext_arg_parse(flags, matches);
+) {
+  node_modules_arg_parse(flags, matches);
+  flags.vendor = matches.remove_one::<bool>("vendor");
+  flags.vendor_npm = matches.remove_one::<bool>("vendor-npm");
+}
 
-  let include = match matches.remove_many::<String>("files") {
-    Some(f) => f.collect(),
-    None => vec![],
-  };
-  let ignore = match matches.remove_many::<String>("ignore") {
-    Some(f) => f
-      .flat_map(flat_escape_split_commas)
-      .collect::<Result<Vec<_>, _>>()?,
-    None => vec![],
-  };
+fn reload_arg_validate(urlstr: String) -> Result<String, clap::Error> {
+  if urlstr.is_empty() {
+    return Err(
+      std::io::Error::new(
+        std::io::ErrorKind::Other,
+        String::from("Missing url. Check for extra commas."),
+      )
+      .into(),
+    );
+  }
+  match Url::from_str(&urlstr) {
+    Ok(_) => Ok(urlstr),
+    Err(e) => {
+      Err(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()).into())
+    }
+  }
+}
 
-  let use_tabs = matches.remove_one::<bool>("use-tabs");
-  let line_width = matches.remove_one::<NonZeroU32>("line-width");
-  let indent_width = matches.remove_one::<NonZeroU8>("indent-width");
-  let single_quote = matches.remove_one::<bool>("single-quote");
-  let prose_wrap = matches.remove_one::<String>("prose-wrap");
-  let no_semicolons = matches.remove_one::<bool>("no-semicolons");
-  let unstable_component = matches.get_flag("unstable-component");
+fn watch_arg_parse(
+  matches: &mut ArgMatches,
+) -> clap::error::Result<Option<WatchFlags>> {
+  if matches.get_flag("watch") {
+    Ok(Some(WatchFlags {
+      hmr: false,
+      no_clear_screen: matches.get_flag("no-clear-screen"),
+      exclude: matches
+        .remove_many::<String>("watch-exclude")
+        .map(|f| {
+          f.flat_map(flat_escape_split_commas)
+            .collect::<Result<_, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default(),
+      debounce: watch_debounce_parse(matches),
+      poll_interval: watch_poll_interval_parse(matches),
+      exec: matches.remove_one::<String>("watch-exec"),
+    }))
+  } else {
+    Ok(None)
+  }
+}
 
-  flags.subcommand = DenoSubcommand::Fmt(FmtFlags {
-    check: matches.get_flag("check"),
-    files: FileFlags { include, ignore },
-    use_tabs,
-    line_width,
-    indent_width,
-    single_quote,
-    prose_wrap,
-    no_semicolons,
-    watch: watch_arg_parse(matches)?,
-    unstable_component,
-  });
-  Ok(())
+fn watch_debounce_parse(matches: &mut ArgMatches) -> Option<Duration> {
+  matches
+    .remove_one::<u64>("watch-debounce")
+    .map(Duration::from_millis)
 }
 
-fn init_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.subcommand = DenoSubcommand::Init(InitFlags {
-    dir: matches.remove_one::<String>("dir"),
-    lib: matches.get_flag("lib"),
-    serve: matches.get_flag("serve"),
-  });
+fn watch_poll_interval_parse(matches: &mut ArgMatches) -> Option<Duration> {
+  matches
+    .remove_one::<u64>("watch-poll-interval")
+    .map(Duration::from_millis)
 }
 
-fn info_parse(
-  flags: &mut Flags,
+fn watch_arg_parse_with_paths(
   matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionOnly);
-  reload_arg_parse(flags, matches)?;
-  config_args_parse(flags, matches);
-  import_map_arg_parse(flags, matches);
-  location_arg_parse(flags, matches);
-  ca_file_arg_parse(flags, matches);
-  unsafely_ignore_certificate_errors_parse(flags, matches);
-  node_modules_and_vendor_dir_arg_parse(flags, matches);
-  lock_args_parse(flags, matches);
-  no_remote_arg_parse(flags, matches);
-  no_npm_arg_parse(flags, matches);
-  allow_import_parse(flags, matches);
-  let json = matches.get_flag("json");
-  flags.subcommand = DenoSubcommand::Info(InfoFlags {
-    file: matches.remove_one::<String>("file"),
-    json,
-  });
+) -> clap::error::Result<Option<WatchFlagsWithPaths>> {
+  if let Some(paths) = matches.remove_many::<String>("watch") {
+    return Ok(Some(WatchFlagsWithPaths {
+      paths: paths
+        .flat_map(flat_escape_split_commas)
+        .collect::<Result<Vec<_>, _>>()?,
+      hmr: false,
+      no_clear_screen: matches.get_flag("no-clear-screen"),
+      exclude: matches
+        .remove_many::<String>("watch-exclude")
+        .map(|f| {
+          f.flat_map(flat_escape_split_commas)
+            .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .unwrap_or_default(),
+      debounce: watch_debounce_parse(matches),
+      poll_interval: watch_poll_interval_parse(matches),
+      exec: matches.remove_one::<String>("watch-exec"),
+    }));
+  }
 
-  Ok(())
+  if matches.try_contains_id("hmr").is_ok() {
+    return matches
+      .remove_many::<String>("hmr")
+      .map(|paths| {
+        Ok(WatchFlagsWithPaths {
+          paths: paths
+            .flat_map(flat_escape_split_commas)
+            .collect::<Result<Vec<_>, _>>()?,
+          hmr: true,
+          no_clear_screen: matches.get_flag("no-clear-screen"),
+          exclude: matches
+            .remove_many::<String>("watch-exclude")
+            .map(|f| {
+              f.flat_map(flat_escape_split_commas)
+                .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default(),
+          debounce: watch_debounce_parse(matches),
+          poll_interval: watch_poll_interval_parse(matches),
+          exec: matches.remove_one::<String>("watch-exec"),
+        })
+      })
+      .transpose();
+  }
+
+  Ok(None)
 }
 
-fn install_parse(
+fn unstable_args_parse(
   flags: &mut Flags,
   matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  runtime_args_parse(flags, matches, true, true)?;
+  cfg: UnstableArgsConfig,
+) {
+  // TODO(bartlomieju): remove in Deno 2.5
+  if matches.get_flag("unstable") {
+    flags.unstable_config.legacy_flag_enabled = true;
+  }
 
-  let global = matches.get_flag("global");
-  if global {
-    let root = matches.remove_one::<String>("root");
-    let force = matches.get_flag("force");
-    let name = matches.remove_one::<String>("name");
-    let mut cmd_values =
-      matches.remove_many::<String>("cmd").unwrap_or_default();
+  flags.unstable_config.bare_node_builtins =
+    matches.get_flag("unstable-bare-node-builtins");
+  flags.unstable_config.sloppy_imports =
+    matches.get_flag("unstable-sloppy-imports");
+
+  if matches!(cfg, UnstableArgsConfig::ResolutionAndRuntime) {
+    for granular_flag in crate::UNSTABLE_GRANULAR_FLAGS {
+      if matches.get_flag(&format!("unstable-{}", granular_flag.name)) {
+        flags
+          .unstable_config
+          .features
+          .push(granular_flag.name.to_string());
+      }
+    }
+  }
+}
+
+// TODO(ry) move this to utility module and add test.
+/// Strips fragment part of URL. Panics on bad URL.
+pub fn resolve_urls(urls: Vec<String>) -> Vec<String> {
+  let mut out: Vec<String> = vec![];
+  for urlstr in urls.iter() {
+    if let Ok(mut url) = Url::from_str(urlstr) {
+      url.set_fragment(None);
+      let mut full_url = String::from(url.as_str());
+      if full_url.len() > 1 && full_url.ends_with('/') {
+        full_url.pop();
+      }
+      out.push(full_url);
+    } else {
+      panic!("Bad Url: {urlstr}");
+    }
+  }
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  /// Creates vector of strings, Vec<String>
+  macro_rules! svec {
+    ($($x:expr),* $(,)?) => (vec![$($x.to_string().into()),*]);
+  }
+
+  #[test]
+  fn global_flags() {
+    #[rustfmt::skip]
+    let r = flags_from_vec(svec!["deno", "--log-level", "debug", "--quiet", "run", "script.ts"]);
+
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string()
+        )),
+        log_level: Some(Level::Error),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+    #[rustfmt::skip]
+    let r2 = flags_from_vec(svec!["deno", "run", "--log-level", "debug", "--quiet", "script.ts"]);
+    let flags2 = r2.unwrap();
+    assert_eq!(flags2, flags);
+  }
 
-    let module_url = cmd_values.next().unwrap();
-    let args = cmd_values.collect();
+  #[test]
+  fn upgrade() {
+    let r = flags_from_vec(svec!["deno", "upgrade", "--dry-run", "--force"]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: true,
+          dry_run: true,
+          canary: false,
+          release_candidate: false,
+          version: None,
+          output: None,
+          version_or_hash_or_channel: None,
+          repo: None,
+          url: None,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
 
-    flags.subcommand = DenoSubcommand::Install(InstallFlags {
-      kind: InstallKind::Global(InstallFlagsGlobal {
-        name,
-        module_url,
-        args,
-        root,
-        force,
-      }),
-    });
+  #[test]
+  fn upgrade_with_output_flag() {
+    let r = flags_from_vec(svec!["deno", "upgrade", "--output", "example.txt"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          canary: false,
+          release_candidate: false,
+          version: None,
+          output: Some(String::from("example.txt")),
+          version_or_hash_or_channel: None,
+          repo: None,
+          url: None,
+        }),
+        ..Flags::default()
+      }
+    );
+  }
 
-    return Ok(());
+  #[test]
+  fn version() {
+    let r = flags_from_vec(svec!["deno", "--version"]);
+    assert_eq!(
+      r.unwrap_err().kind(),
+      clap::error::ErrorKind::DisplayVersion
+    );
+    let r = flags_from_vec(svec!["deno", "-V"]);
+    assert_eq!(
+      r.unwrap_err().kind(),
+      clap::error::ErrorKind::DisplayVersion
+    );
   }
 
-  // allow scripts only applies to local install
-  allow_scripts_arg_parse(flags, matches)?;
-  if matches.get_flag("entrypoint") {
-    let entrypoints = matches.remove_many::<String>("cmd").unwrap_or_default();
-    flags.subcommand = DenoSubcommand::Install(InstallFlags {
-      kind: InstallKind::Local(InstallFlagsLocal::Entrypoints(
-        entrypoints.collect(),
-      )),
-    });
-  } else if let Some(add_files) = matches
-    .remove_many("cmd")
-    .map(|packages| add_parse_inner(matches, Some(packages)))
-  {
-    flags.subcommand = DenoSubcommand::Install(InstallFlags {
-      kind: InstallKind::Local(InstallFlagsLocal::Add(add_files)),
-    })
-  } else {
-    flags.subcommand = DenoSubcommand::Install(InstallFlags {
-      kind: InstallKind::Local(InstallFlagsLocal::TopLevel),
-    });
+  #[test]
+  fn run_reload() {
+    let r = flags_from_vec(svec!["deno", "run", "-r", "script.ts"]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string()
+        )),
+        reload: true,
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
   }
-  Ok(())
-}
 
-fn json_reference_parse(
-  flags: &mut Flags,
-  _matches: &mut ArgMatches,
-  mut app: Command,
-) {
-  use deno_core::serde_json::json;
+  #[test]
+  fn run_ephemeral_tmp() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--ephemeral-tmp", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: None,
+          bare: false,
+          ephemeral_tmp: true,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
 
-  app.build();
+  #[test]
+  fn run_restart_on_failure() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--restart-on-failure=5",
+      "--restart-backoff=500",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: None,
+          bare: false,
+          ephemeral_tmp: false,
+          restart_on_failure: Some(RestartOnFailureFlags {
+            max_retries: Some(5),
+            backoff: Duration::from_millis(500),
+          }),
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  fn serialize_command(
-    mut command: Command,
-    top_level: bool,
-  ) -> deno_core::serde_json::Value {
-    let args = command
-      .get_arguments()
-      .filter(|arg| {
-        !arg.is_hide_set()
-          && if top_level {
-            arg.is_global_set()
-          } else {
-            !arg.is_global_set()
-          }
-      })
-      .map(|arg| {
-        let name = arg.get_id().as_str();
-        let short = arg.get_short();
-        let long = arg.get_long();
-        let required = arg.is_required_set();
-        let help = arg.get_help().map(|help| help.ansi().to_string());
-        let help_heading = arg
-          .get_help_heading()
-          .map(|help_heading| help_heading.to_string());
-        let usage = arg.to_string();
+    let r =
+      flags_from_vec(svec!["deno", "run", "--restart-on-failure", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: None,
+          bare: false,
+          ephemeral_tmp: false,
+          restart_on_failure: Some(RestartOnFailureFlags {
+            max_retries: None,
+            backoff: Duration::from_millis(1000),
+          }),
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-        json!({
-          "name": name,
-          "short": short,
-          "long": long,
-          "required": required,
-          "help": help,
-          "help_heading": help_heading,
-          "usage": usage,
-        })
-      })
-      .collect::<Vec<_>>();
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--restart-on-failure",
+      "--watch",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--restart-backoff=500",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
 
-    let name = command.get_name().to_string();
-    let about = command.get_about().map(|about| about.ansi().to_string());
-    let usage = command.render_usage().ansi().to_string();
+  #[test]
+  fn run_restart_on_crash() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--restart-on-crash=3", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: None,
+          bare: false,
+          ephemeral_tmp: false,
+          restart_on_failure: Some(RestartOnFailureFlags {
+            max_retries: Some(3),
+            backoff: Duration::from_millis(1000),
+          }),
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-    let subcommands = command
-      .get_subcommands()
-      .map(|command| {
-        serialize_command(
-          if command
-            .get_arguments()
-            .any(|arg| arg.get_id().as_str() == "unstable")
-          {
-            enable_unstable(command.clone())
-          } else {
-            command.clone()
-          },
-          false,
-        )
-      })
-      .collect::<Vec<_>>();
+    // the limit is the value of the flag
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--restart-on-failure",
+      "--max-restarts=3",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn run_siblings() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-net",
+      "--sibling=worker.ts",
+      "--sibling=jobs.ts --queue  default",
+      "server.ts",
+      "--port=8000",
+      "--sibling=not-a-sibling.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          siblings: vec![
+            svec!["worker.ts"],
+            svec!["jobs.ts", "--queue", "default"]
+          ],
+          ..RunFlags::new_default("server.ts".to_string())
+        }),
+        argv: svec!["--port=8000", "--sibling=not-a-sibling.ts"],
+        permissions: PermissionFlags {
+          allow_net: Some(vec![]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-    json!({
-      "name": name,
-      "about": about,
-      "args": args,
-      "subcommands": subcommands,
-      "usage": usage,
-    })
-  }
+    // the arguments of the script are left alone
+    let r = flags_from_vec(svec!["deno", "run", "server.ts", "--and", "x.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "server.ts".to_string()
+        )),
+        argv: svec!["--and", "x.ts"],
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  flags.subcommand = DenoSubcommand::JSONReference(JSONReferenceFlags {
-    json: serialize_command(app, true),
-  })
-}
+    let r = flags_from_vec(svec!["deno", "run", "--sibling= ", "server.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--sibling=worker.ts",
+      "--procfile=Procfile"
+    ]);
+    assert!(r.is_err());
+  }
 
-fn jupyter_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionAndRuntime);
+  #[test]
+  fn run_procfile() {
+    let r = flags_from_vec(svec!["deno", "run", "-A", "--procfile=Procfile"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          procfile: Some("Procfile".to_string()),
+          ..RunFlags::new_default(String::new())
+        }),
+        permissions: PermissionFlags {
+          allow_all: true,
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  let conn_file = matches.remove_one::<String>("conn");
-  let kernel = matches.get_flag("kernel");
-  let install = matches.get_flag("install");
+    let r =
+      flags_from_vec(svec!["deno", "run", "--procfile=Procfile", "script.ts"]);
+    assert!(r.is_err());
 
-  flags.subcommand = DenoSubcommand::Jupyter(JupyterFlags {
-    install,
-    kernel,
-    conn_file,
-  });
-}
+    let r = flags_from_vec(svec!["deno", "run", "--ui", "--procfile=Procfile"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          procfile: Some("Procfile".to_string()),
+          ui: true,
+          ..RunFlags::new_default(String::new())
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
 
-fn uninstall_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  let name = matches.remove_one::<String>("name-or-package").unwrap();
+  #[test]
+  fn run_watch() {
+    let r = flags_from_vec(svec!["deno", "run", "--watch", "script.ts"]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![],
+            no_clear_screen: false,
+            exclude: vec![],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          }),
+          bare: false,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  let kind = if matches.get_flag("global") {
-    let root = matches.remove_one::<String>("root");
-    UninstallKind::Global(UninstallFlagsGlobal { name, root })
-  } else {
-    let packages: Vec<_> = vec![name]
-      .into_iter()
-      .chain(
-        matches
-          .remove_many::<String>("additional-packages")
-          .unwrap_or_default(),
-      )
-      .collect();
-    UninstallKind::Local(RemoveFlags { packages })
-  };
+    let r = flags_from_vec(svec![
+      "deno",
+      "--watch",
+      "--no-clear-screen",
+      "script.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![],
+            no_clear_screen: true,
+            exclude: vec![],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          }),
+          bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  flags.subcommand = DenoSubcommand::Uninstall(UninstallFlags { kind });
-}
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch-hmr",
+      "--no-clear-screen",
+      "script.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: true,
+            paths: vec![],
+            no_clear_screen: true,
+            exclude: vec![],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          }),
+          bare: false,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-fn lsp_parse(flags: &mut Flags, _matches: &mut ArgMatches) {
-  flags.subcommand = DenoSubcommand::Lsp;
-}
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--unstable-hmr",
+      "--no-clear-screen",
+      "script.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: true,
+            paths: vec![],
+            no_clear_screen: true,
+            exclude: vec![],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          }),
+          bare: false,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-fn lint_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionOnly);
-  ext_arg_parse(flags, matches);
-  config_args_parse(flags, matches);
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch-hmr=foo.txt",
+      "--no-clear-screen",
+      "script.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: true,
+            paths: vec![String::from("foo.txt")],
+            no_clear_screen: true,
+            exclude: vec![],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          }),
+          bare: false,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  let files = match matches.remove_many::<String>("files") {
-    Some(f) => f.collect(),
-    None => vec![],
-  };
-  let ignore = match matches.remove_many::<String>("ignore") {
-    Some(f) => f
-      .flat_map(flat_escape_split_commas)
-      .collect::<Result<Vec<_>, _>>()?,
-    None => vec![],
-  };
-  let fix = matches.get_flag("fix");
-  let rules = matches.get_flag("rules");
-  let maybe_rules_tags = matches
-    .remove_many::<String>("rules-tags")
-    .map(|f| f.collect());
+    let r =
+      flags_from_vec(svec!["deno", "run", "--hmr", "--watch", "script.ts"]);
+    assert!(r.is_err());
+  }
 
-  let maybe_rules_include = matches
-    .remove_many::<String>("rules-include")
-    .map(|f| f.collect());
+  #[test]
+  fn run_watch_with_external() {
+    let r = flags_from_vec(svec!["deno", "--watch=file1,file2", "script.ts"]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![String::from("file1"), String::from("file2")],
+            no_clear_screen: false,
+            exclude: vec![],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          }),
+          bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
 
-  let maybe_rules_exclude = matches
-    .remove_many::<String>("rules-exclude")
-    .map(|f| f.collect());
+  #[test]
+  fn run_watch_with_no_clear_screen() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch",
+      "--no-clear-screen",
+      "script.ts"
+    ]);
 
-  let json = matches.get_flag("json");
-  let compact = matches.get_flag("compact");
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![],
+            no_clear_screen: true,
+            exclude: vec![],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          }),
+          bare: false,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
 
-  flags.subcommand = DenoSubcommand::Lint(LintFlags {
-    files: FileFlags {
-      include: files,
-      ignore,
-    },
-    fix,
-    rules,
-    maybe_rules_tags,
-    maybe_rules_include,
-    maybe_rules_exclude,
-    json,
-    compact,
-    watch: watch_arg_parse(matches)?,
-  });
-  Ok(())
-}
+  #[test]
+  fn run_watch_with_debounce_and_poll_interval() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch",
+      "--watch-debounce=500",
+      "--watch-poll-interval=1000",
+      "script.ts"
+    ]);
 
-fn repl_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  runtime_args_parse(flags, matches, true, true)?;
-  unsafely_ignore_certificate_errors_parse(flags, matches);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![],
+            no_clear_screen: false,
+            exclude: vec![],
+            debounce: Some(Duration::from_millis(500)),
+            poll_interval: Some(Duration::from_secs(1)),
+            exec: None,
+          }),
+          bare: false,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+    assert_eq!(
+      flags.resolve_watch_options(),
+      WatchOptions {
+        debounce: Some(Duration::from_millis(500)),
+        poll_interval: Some(Duration::from_secs(1)),
+        exec: None,
+      }
+    );
 
-  let eval_files = matches
-    .remove_many::<String>("eval-file")
-    .map(|values| {
-      values
-        .flat_map(flat_escape_split_commas)
-        .collect::<Result<Vec<_>, _>>()
-    })
-    .transpose()?;
+    let r =
+      flags_from_vec(svec!["deno", "run", "--watch-debounce=500", "script.ts"]);
+    assert!(r.is_err());
 
-  if let Some(args) = matches.remove_many::<String>("args") {
-    flags.argv.extend(args);
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--watch",
+      "--watch-poll-interval=0"
+    ]);
+    assert!(r.is_err());
   }
 
-  handle_repl_flags(
-    flags,
-    ReplFlags {
-      eval_files,
-      eval: matches.remove_one::<String>("eval"),
-      is_default_command: false,
-    },
-  );
-  Ok(())
-}
+  #[test]
+  fn run_watch_with_exec() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch",
+      "--watch-exec=deno task codegen",
+      "script.ts"
+    ]);
 
-fn run_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-  mut app: Command,
-  bare: bool,
-) -> clap::error::Result<()> {
-  runtime_args_parse(flags, matches, true, true)?;
-  ext_arg_parse(flags, matches);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags.resolve_watch_options(),
+      WatchOptions {
+        debounce: None,
+        poll_interval: None,
+        exec: Some("deno task codegen".to_string()),
+      }
+    );
 
-  flags.code_cache_enabled = !matches.get_flag("no-code-cache");
+    let r = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "--watch",
+      "--watch-exec=echo changed"
+    ]);
+    assert_eq!(
+      r.unwrap().resolve_watch_options().exec,
+      Some("echo changed".to_string())
+    );
 
-  if let Some(mut script_arg) = matches.remove_many::<String>("script_arg") {
-    let script = script_arg.next().unwrap();
-    flags.argv.extend(script_arg);
-    flags.subcommand = DenoSubcommand::Run(RunFlags {
-      script,
-      watch: watch_arg_parse_with_paths(matches)?,
-      bare,
-    });
-  } else if bare {
-    return Err(app.override_usage("deno [OPTIONS] [COMMAND] [SCRIPT_ARG]...").error(
-      clap::error::ErrorKind::MissingRequiredArgument,
-      "[SCRIPT_ARG] may only be omitted with --v8-flags=--help, else to use the repl with arguments, please use the `deno repl` subcommand",
-    ));
-  } else {
-    return Err(app.find_subcommand_mut("run").unwrap().error(
-      clap::error::ErrorKind::MissingRequiredArgument,
-      "[SCRIPT_ARG] may only be omitted with --v8-flags=--help",
-    ));
+    let r =
+      flags_from_vec(svec!["deno", "run", "--watch-exec=echo", "script.ts"]);
+    assert!(r.is_err());
   }
 
-  Ok(())
-}
-
-fn serve_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-  app: Command,
-) -> clap::error::Result<()> {
-  // deno serve implies --allow-net=host:port
-  let port = matches.remove_one::<u16>("port").unwrap_or(8000);
-  let host = matches
-    .remove_one::<String>("host")
-    .unwrap_or_else(|| "0.0.0.0".to_owned());
-
-  let worker_count = parallel_arg_parse(matches).map(|v| v.get());
+  #[test]
+  fn run_watch_with_excluded_paths() {
+    let r = flags_from_vec(svec!(
+      "deno",
+      "--watch",
+      "--watch-exclude=foo",
+      "script.ts"
+    ));
 
-  runtime_args_parse(flags, matches, true, true)?;
-  // If the user didn't pass --allow-net, add this port to the network
-  // allowlist. If the host is 0.0.0.0, we add :{port} and allow the same network perms
-  // as if it was passed to --allow-net directly.
-  let allowed = flags_net::parse(vec![if host == "0.0.0.0" {
-    format!(":{port}")
-  } else {
-    format!("{host}:{port}")
-  }])?;
-  match &mut flags.permissions.allow_net {
-    None if !flags.permissions.allow_all => {
-      flags.permissions.allow_net = Some(allowed)
-    }
-    None => {}
-    Some(v) => {
-      if !v.is_empty() {
-        v.extend(allowed);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![],
+            no_clear_screen: false,
+            exclude: vec![String::from("foo")],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          }),
+          bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
       }
-    }
-  }
-  flags.code_cache_enabled = !matches.get_flag("no-code-cache");
+    );
 
-  let mut script_arg =
-    matches.remove_many::<String>("script_arg").ok_or_else(|| {
-      let mut app = app;
-      let subcommand = &mut app.find_subcommand_mut("serve").unwrap();
-      subcommand.error(
-        clap::error::ErrorKind::MissingRequiredArgument,
-        "[SCRIPT_ARG] may only be omitted with --v8-flags=--help",
-      )
-    })?;
+    let r = flags_from_vec(svec!(
+      "deno",
+      "run",
+      "--watch=foo",
+      "--watch-exclude=bar",
+      "script.ts"
+    ));
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![String::from("foo")],
+            no_clear_screen: false,
+            exclude: vec![String::from("bar")],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          }),
+          bare: false,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  let script = script_arg.next().unwrap();
-  flags.argv.extend(script_arg);
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--watch",
+      "--watch-exclude=foo,bar",
+      "script.ts"
+    ]);
 
-  ext_arg_parse(flags, matches);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![],
+            no_clear_screen: false,
+            exclude: vec![String::from("foo"), String::from("bar")],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          }),
+          bare: false,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  flags.subcommand = DenoSubcommand::Serve(ServeFlags {
-    script,
-    watch: watch_arg_parse_with_paths(matches)?,
-    port,
-    host,
-    worker_count,
-  });
+    let r = flags_from_vec(svec![
+      "deno",
+      "--watch=foo,bar",
+      "--watch-exclude=baz,qux",
+      "script.ts"
+    ]);
 
-  Ok(())
-}
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![String::from("foo"), String::from("bar")],
+            no_clear_screen: false,
+            exclude: vec![String::from("baz"), String::from("qux"),],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          }),
+          bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
 
-fn task_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.config_flag = matches
-    .remove_one::<String>("config")
-    .map(ConfigFlag::Path)
-    .unwrap_or(ConfigFlag::Discover);
+  #[test]
+  fn run_reload_allow_write() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "-r", "--allow-write", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        reload: true,
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string()
+        )),
+        permissions: PermissionFlags {
+          allow_write: Some(vec![]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
 
-  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionAndRuntime);
-  node_modules_arg_parse(flags, matches);
+  #[test]
+  fn run_v8_flags() {
+    let r = flags_from_vec(svec!["deno", "run", "--v8-flags=--help"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default("_".to_string())),
+        v8_flags: svec!["--help"],
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  let mut task_flags = TaskFlags {
-    cwd: matches.remove_one::<String>("cwd"),
-    task: None,
-    is_run: false,
-  };
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--v8-flags=--expose-gc,--gc-stats=1",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        v8_flags: svec!["--expose-gc", "--gc-stats=1"],
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  if let Some((task, mut matches)) = matches.remove_subcommand() {
-    task_flags.task = Some(task);
+    let r = flags_from_vec(svec!["deno", "run", "--v8-flags=--expose-gc"]);
+    assert!(r.is_err());
+  }
 
-    flags.argv.extend(
-      matches
-        .remove_many::<std::ffi::OsString>("")
-        .into_iter()
-        .flatten()
-        .filter_map(|arg| arg.into_string().ok()),
+  #[test]
+  fn serve_flags() {
+    let r = flags_from_vec(svec!["deno", "serve", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(ServeFlags::new_default(
+          "main.ts".to_string(),
+          8000,
+          "0.0.0.0"
+        )),
+        permissions: PermissionFlags {
+          allow_net: Some(vec![
+            "0.0.0.0:8000".to_string(),
+            "127.0.0.1:8000".to_string(),
+            "localhost:8000".to_string()
+          ]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+    let r = flags_from_vec(svec!["deno", "serve", "--port", "5000", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(ServeFlags::new_default(
+          "main.ts".to_string(),
+          5000,
+          "0.0.0.0"
+        )),
+        permissions: PermissionFlags {
+          allow_net: Some(vec![
+            "0.0.0.0:5000".to_string(),
+            "127.0.0.1:5000".to_string(),
+            "localhost:5000".to_string()
+          ]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--port",
+      "5000",
+      "--allow-net=example.com",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(ServeFlags::new_default(
+          "main.ts".to_string(),
+          5000,
+          "0.0.0.0"
+        )),
+        permissions: PermissionFlags {
+          allow_net: Some(vec![
+            "example.com".to_string(),
+            "0.0.0.0:5000".to_string(),
+            "127.0.0.1:5000".to_string(),
+            "localhost:5000".to_string()
+          ]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--port",
+      "5000",
+      "--allow-net",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(ServeFlags::new_default(
+          "main.ts".to_string(),
+          5000,
+          "0.0.0.0"
+        )),
+        permissions: PermissionFlags {
+          allow_net: Some(vec![]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--port",
+      "5000",
+      "--host",
+      "example.com",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(ServeFlags::new_default(
+          "main.ts".to_string(),
+          5000,
+          "example.com"
+        )),
+        permissions: PermissionFlags {
+          allow_net: Some(vec!["example.com:5000".to_owned()]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
     );
-  }
-
-  flags.subcommand = DenoSubcommand::Task(task_flags);
-}
 
-fn parallel_arg_parse(matches: &mut ArgMatches) -> Option<NonZeroUsize> {
-  if matches.get_flag("parallel") {
-    if let Ok(value) = env::var("DENO_JOBS") {
-      value.parse::<NonZeroUsize>().ok()
-    } else {
-      std::thread::available_parallelism().ok()
-    }
-  } else {
-    None
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--port",
+      "0",
+      "--host",
+      "example.com",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(ServeFlags::new_default(
+          "main.ts".to_string(),
+          0,
+          "example.com"
+        )),
+        permissions: PermissionFlags {
+          allow_net: Some(vec!["example.com:0".to_owned()]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
   }
-}
 
-fn test_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  flags.type_check_mode = TypeCheckMode::Local;
-  runtime_args_parse(flags, matches, true, true)?;
-  ext_arg_parse(flags, matches);
+  #[test]
+  fn serve_mirror_flags() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--mirror",
+      "http://localhost:9000",
+      "--mirror-percent",
+      "10",
+      "main.ts"
+    ]);
+    let mut serve_flags =
+      ServeFlags::new_default("main.ts".to_string(), 8000, "0.0.0.0");
+    serve_flags.mirror = Some("http://localhost:9000/".to_string());
+    serve_flags.mirror_percent = 10;
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(serve_flags),
+        permissions: PermissionFlags {
+          allow_net: Some(vec![
+            "0.0.0.0:8000".to_string(),
+            "127.0.0.1:8000".to_string(),
+            "localhost:8000".to_string(),
+            "localhost:9000".to_string()
+          ]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  // NOTE: `deno test` always uses `--no-prompt`, tests shouldn't ever do
-  // interactive prompts, unless done by user code
-  flags.permissions.no_prompt = true;
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--mirror-percent",
+      "10",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
 
-  let ignore = match matches.remove_many::<String>("ignore") {
-    Some(f) => f
-      .flat_map(flat_escape_split_commas)
-      .collect::<Result<_, _>>()?,
-    None => vec![],
-  };
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--mirror",
+      "http://localhost:9000",
+      "--mirror-percent",
+      "101",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
 
-  let no_run = matches.get_flag("no-run");
-  let trace_leaks = matches.get_flag("trace-leaks");
-  let doc = matches.get_flag("doc");
-  #[allow(clippy::print_stderr)]
-  let permit_no_files = matches.get_flag("permit-no-files");
-  let filter = matches.remove_one::<String>("filter");
-  let clean = matches.get_flag("clean");
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--mirror",
+      "file:///backend",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
+  }
 
-  let fail_fast = if matches.contains_id("fail-fast") {
-    Some(
-      matches
-        .remove_one::<NonZeroUsize>("fail-fast")
-        .unwrap_or_else(|| NonZeroUsize::new(1).unwrap()),
-    )
-  } else {
-    None
-  };
+  #[test]
+  fn serve_upload_flags() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--max-concurrent-uploads",
+      "4",
+      "--upload-high-water-mark",
+      "65536",
+      "--upload-metrics-path",
+      "/_metrics",
+      "main.ts"
+    ]);
+    let mut serve_flags =
+      ServeFlags::new_default("main.ts".to_string(), 8000, "0.0.0.0");
+    serve_flags.max_concurrent_uploads = Some(4);
+    serve_flags.upload_high_water_mark = Some(65536);
+    serve_flags.upload_metrics_path = Some("/_metrics".to_string());
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(serve_flags),
+        permissions: PermissionFlags {
+          allow_net: Some(vec![
+            "0.0.0.0:8000".to_string(),
+            "127.0.0.1:8000".to_string(),
+            "localhost:8000".to_string()
+          ]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  let shuffle = if matches.contains_id("shuffle") {
-    Some(
-      matches
-        .remove_one::<u64>("shuffle")
-        .unwrap_or_else(rand::random),
-    )
-  } else {
-    None
-  };
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--max-concurrent-uploads",
+      "0",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
 
-  if let Some(script_arg) = matches.remove_many::<String>("script_arg") {
-    flags.argv.extend(script_arg);
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--upload-metrics-path",
+      "metrics",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
   }
 
-  let concurrent_jobs = parallel_arg_parse(matches);
+  #[test]
+  fn serve_idle_shutdown_flags() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--idle-shutdown",
+      "300",
+      "main.ts"
+    ]);
+    let mut serve_flags =
+      ServeFlags::new_default("main.ts".to_string(), 8000, "0.0.0.0");
+    serve_flags.idle_shutdown = Some(300);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(serve_flags),
+        permissions: PermissionFlags {
+          allow_net: Some(vec![
+            "0.0.0.0:8000".to_string(),
+            "127.0.0.1:8000".to_string(),
+            "localhost:8000".to_string()
+          ]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
 
-  let include = if let Some(files) = matches.remove_many::<String>("files") {
-    files.collect()
-  } else {
-    Vec::new()
-  };
+    let r =
+      flags_from_vec(svec!["deno", "serve", "--idle-shutdown", "0", "main.ts"]);
+    assert!(r.is_err());
 
-  let junit_path = matches.remove_one::<String>("junit-path");
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--idle-shutdown",
+      "300",
+      "--parallel",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
+  }
 
-  let reporter =
-    if let Some(reporter) = matches.remove_one::<String>("reporter") {
-      match reporter.as_str() {
-        "pretty" => TestReporterConfig::Pretty,
-        "junit" => TestReporterConfig::Junit,
-        "dot" => TestReporterConfig::Dot,
-        "tap" => TestReporterConfig::Tap,
-        _ => unreachable!(),
+  #[test]
+  fn serve_tls_flags() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--cert",
+      "cert.pem",
+      "--key",
+      "key.pem",
+      "--tls-client-ca",
+      "ca.pem",
+      "--tls-client-auth",
+      "optional",
+      "main.ts"
+    ]);
+    let mut serve_flags =
+      ServeFlags::new_default("main.ts".to_string(), 8000, "0.0.0.0");
+    serve_flags.cert = Some("cert.pem".to_string());
+    serve_flags.key = Some("key.pem".to_string());
+    serve_flags.tls_client_ca = Some("ca.pem".to_string());
+    serve_flags.tls_client_auth = TlsClientAuth::Optional;
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Serve(serve_flags),
+        permissions: PermissionFlags {
+          allow_net: Some(vec![
+            "0.0.0.0:8000".to_string(),
+            "127.0.0.1:8000".to_string(),
+            "localhost:8000".to_string()
+          ]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
       }
-    } else {
-      TestReporterConfig::Pretty
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--cert",
+      "cert.pem",
+      "--key",
+      "key.pem",
+      "--tls-client-ca",
+      "ca.pem",
+      "main.ts"
+    ]);
+    let DenoSubcommand::Serve(serve_flags) = r.unwrap().subcommand else {
+      unreachable!()
     };
+    assert_eq!(serve_flags.tls_client_auth, TlsClientAuth::Require);
 
-  if matches!(reporter, TestReporterConfig::Dot | TestReporterConfig::Tap) {
-    flags.log_level = Some(Level::Error);
-  }
+    let r =
+      flags_from_vec(svec!["deno", "serve", "--cert", "cert.pem", "main.ts"]);
+    assert!(r.is_err());
 
-  let hide_stacktraces = matches.get_flag("hide-stacktraces");
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--tls-client-ca",
+      "ca.pem",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
 
-  flags.subcommand = DenoSubcommand::Test(TestFlags {
-    no_run,
-    doc,
-    coverage_dir: matches.remove_one::<String>("coverage"),
-    clean,
-    fail_fast,
-    files: FileFlags { include, ignore },
-    filter,
-    shuffle,
-    permit_no_files,
-    concurrent_jobs,
-    trace_leaks,
-    watch: watch_arg_parse_with_paths(matches)?,
-    reporter,
-    junit_path,
-    hide_stacktraces,
-  });
-  Ok(())
-}
+    let r = flags_from_vec(svec![
+      "deno",
+      "serve",
+      "--cert",
+      "cert.pem",
+      "--key",
+      "key.pem",
+      "--tls-client-auth",
+      "optional",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
+  }
 
-fn types_parse(flags: &mut Flags, _matches: &mut ArgMatches) {
-  flags.subcommand = DenoSubcommand::Types;
-}
+  #[test]
+  fn has_permission() {
+    let r = flags_from_vec(svec!["deno", "--allow-read", "x.ts"]);
+    assert_eq!(r.unwrap().has_permission(), true);
 
-fn upgrade_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  ca_file_arg_parse(flags, matches);
-  unsafely_ignore_certificate_errors_parse(flags, matches);
+    let r = flags_from_vec(svec!["deno", "run", "--deny-read", "x.ts"]);
+    assert_eq!(r.unwrap().has_permission(), true);
 
-  let dry_run = matches.get_flag("dry-run");
-  let force = matches.get_flag("force");
-  let canary = matches.get_flag("canary");
-  let release_candidate = matches.get_flag("release-candidate");
-  let version = matches.remove_one::<String>("version");
-  let output = matches.remove_one::<String>("output");
-  let version_or_hash_or_channel =
-    matches.remove_one::<String>("version-or-hash-or-channel");
-  flags.subcommand = DenoSubcommand::Upgrade(UpgradeFlags {
-    dry_run,
-    force,
-    release_candidate,
-    canary,
-    version,
-    output,
-    version_or_hash_or_channel,
-  });
-}
+    let r = flags_from_vec(svec!["deno", "run", "x.ts"]);
+    assert_eq!(r.unwrap().has_permission(), false);
+  }
 
-fn vendor_parse(flags: &mut Flags, _matches: &mut ArgMatches) {
-  flags.subcommand = DenoSubcommand::Vendor
-}
+  #[test]
+  fn has_permission_in_argv() {
+    let r = flags_from_vec(svec!["deno", "run", "x.ts", "--allow-read"]);
+    assert_eq!(r.unwrap().has_permission_in_argv(), true);
 
-fn publish_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.type_check_mode = TypeCheckMode::Local; // local by default
-  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionOnly);
-  no_check_arg_parse(flags, matches);
-  check_arg_parse(flags, matches);
-  config_args_parse(flags, matches);
+    let r = flags_from_vec(svec!["deno", "x.ts", "--deny-read"]);
+    assert_eq!(r.unwrap().has_permission_in_argv(), true);
 
-  flags.subcommand = DenoSubcommand::Publish(PublishFlags {
-    token: matches.remove_one("token"),
-    dry_run: matches.get_flag("dry-run"),
-    allow_slow_types: matches.get_flag("allow-slow-types"),
-    allow_dirty: matches.get_flag("allow-dirty"),
-    no_provenance: matches.get_flag("no-provenance"),
-  });
-}
+    let r = flags_from_vec(svec!["deno", "run", "x.ts"]);
+    assert_eq!(r.unwrap().has_permission_in_argv(), false);
+  }
 
-fn compile_args_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  compile_args_without_check_parse(flags, matches)?;
-  no_check_arg_parse(flags, matches);
-  check_arg_parse(flags, matches);
-  Ok(())
-}
+  #[test]
+  fn script_args() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-net",
+      "gist.ts",
+      "--title",
+      "X"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "gist.ts".to_string()
+        )),
+        argv: svec!["--title", "X"],
+        permissions: PermissionFlags {
+          allow_net: Some(vec![]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
 
-fn compile_args_without_check_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  import_map_arg_parse(flags, matches);
-  no_remote_arg_parse(flags, matches);
-  no_npm_arg_parse(flags, matches);
-  node_modules_and_vendor_dir_arg_parse(flags, matches);
-  config_args_parse(flags, matches);
-  reload_arg_parse(flags, matches)?;
-  lock_args_parse(flags, matches);
-  ca_file_arg_parse(flags, matches);
-  unsafely_ignore_certificate_errors_parse(flags, matches);
-  Ok(())
-}
+  #[test]
+  fn allow_all() {
+    let r = flags_from_vec(svec!["deno", "run", "--allow-all", "gist.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "gist.ts".to_string()
+        )),
+        permissions: PermissionFlags {
+          allow_all: true,
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
 
-fn escape_and_split_commas(s: String) -> Result<Vec<String>, clap::Error> {
-  let mut result = vec![];
-  let mut current = String::new();
-  let mut chars = s.chars();
+  #[test]
+  fn allow_read() {
+    let r = flags_from_vec(svec!["deno", "run", "--allow-read", "gist.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "gist.ts".to_string()
+        )),
+        permissions: PermissionFlags {
+          allow_read: Some(vec![]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
 
-  while let Some(c) = chars.next() {
-    if c == ',' {
-      if let Some(next) = chars.next() {
-        if next == ',' {
-          current.push(',');
-        } else {
-          if current.is_empty() {
-            return Err(
-              std::io::Error::new(
-                std::io::ErrorKind::Other,
-                String::from("Empty values are not allowed"),
-              )
-              .into(),
-            );
-          }
+  #[test]
+  fn short_permission_flags() {
+    let r = flags_from_vec(svec!["deno", "run", "-RNESWI", "gist.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "gist.ts".to_string()
+        )),
+        permissions: PermissionFlags {
+          allow_read: Some(vec![]),
+          allow_write: Some(vec![]),
+          allow_env: Some(vec![]),
+          allow_import: Some(vec![]),
+          allow_net: Some(vec![]),
+          allow_sys: Some(vec![]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
 
-          result.push(current.clone());
-          current.clear();
-          current.push(next);
-        }
-      } else {
-        return Err(
-          std::io::Error::new(
-            std::io::ErrorKind::Other,
-            String::from("Empty values are not allowed"),
-          )
-          .into(),
-        );
+  #[test]
+  fn deny_read() {
+    let r = flags_from_vec(svec!["deno", "--deny-read", "gist.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "gist.ts".to_string(),
+          watch: None,
+          bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        permissions: PermissionFlags {
+          deny_read: Some(vec![]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
       }
-    } else {
-      current.push(c);
-    }
+    );
   }
 
-  if current.is_empty() {
-    return Err(
-      std::io::Error::new(
-        std::io::ErrorKind::Other,
-        String::from("Empty values are not allowed"),
-      )
-      .into(),
+  #[test]
+  fn double_hyphen() {
+    // notice that flags passed after double dash will not
+    // be parsed to Flags but instead forwarded to
+    // script args as Deno.args
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-write",
+      "script.ts",
+      "--",
+      "-D",
+      "--allow-net"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        argv: svec!["--", "-D", "--allow-net"],
+        permissions: PermissionFlags {
+          allow_write: Some(vec![]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
     );
   }
 
-  result.push(current);
-
-  Ok(result)
-}
-
-fn flat_escape_split_commas(str: String) -> Vec<Result<String, clap::Error>> {
-  match escape_and_split_commas(str) {
-    Ok(vec) => vec.into_iter().map(Ok).collect::<Vec<_>>(),
-    Err(e) => vec![Err(e)],
-  }
-}
-
-fn permission_args_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  if let Some(read_wl) = matches.remove_many::<String>("allow-read") {
-    let read_wl = read_wl
-      .flat_map(flat_escape_split_commas)
-      .collect::<Result<Vec<_>, _>>()?;
-    flags.permissions.allow_read = Some(read_wl);
-  }
-
-  if let Some(read_wl) = matches.remove_many::<String>("deny-read") {
-    let read_wl = read_wl
-      .flat_map(flat_escape_split_commas)
-      .collect::<Result<Vec<_>, _>>()?;
-    flags.permissions.deny_read = Some(read_wl);
-  }
+  #[test]
+  fn fmt() {
+    let r = flags_from_vec(svec!["deno", "fmt", "script_1.ts", "script_2.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          files: FileFlags {
+            include: vec!["script_1.ts".to_string(), "script_2.ts".to_string()],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          unstable_component: false,
+          watch: Default::default(),
+          line_ending: None,
+          bom: None,
+          skip_code_blocks: None,
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-  if let Some(write_wl) = matches.remove_many::<String>("allow-write") {
-    let write_wl = write_wl
-      .flat_map(flat_escape_split_commas)
-      .collect::<Result<Vec<_>, _>>()?;
-    flags.permissions.allow_write = Some(write_wl);
-  }
+    let r = flags_from_vec(svec!["deno", "fmt", "--check"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: true,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          unstable_component: false,
+          watch: Default::default(),
+          line_ending: None,
+          bom: None,
+          skip_code_blocks: None,
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-  if let Some(write_wl) = matches.remove_many::<String>("deny-write") {
-    let write_wl = write_wl
-      .flat_map(flat_escape_split_commas)
-      .collect::<Result<Vec<_>, _>>()?;
-    flags.permissions.deny_write = Some(write_wl);
-  }
+    let r = flags_from_vec(svec!["deno", "fmt"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          unstable_component: false,
+          watch: Default::default(),
+          line_ending: None,
+          bom: None,
+          skip_code_blocks: None,
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-  if let Some(net_wl) = matches.remove_many::<String>("allow-net") {
-    let net_allowlist = flags_net::parse(net_wl.collect())?;
-    flags.permissions.allow_net = Some(net_allowlist);
-  }
+    let r = flags_from_vec(svec!["deno", "fmt", "--watch"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          unstable_component: false,
+          watch: Some(Default::default()),
+          line_ending: None,
+          bom: None,
+          skip_code_blocks: None,
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-  if let Some(net_wl) = matches.remove_many::<String>("deny-net") {
-    let net_denylist = flags_net::parse(net_wl.collect())?;
-    flags.permissions.deny_net = Some(net_denylist);
-  }
+    let r = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "--watch",
+      "--no-clear-screen",
+      "--unstable-css",
+      "--unstable-html",
+      "--unstable-component",
+      "--unstable-yaml"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          unstable_component: true,
+          watch: Some(WatchFlags {
+            line_ending: None,
+            bom: None,
+            hmr: false,
+            no_clear_screen: true,
+            exclude: vec![],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          })
+          skip_code_blocks: None,
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-  if let Some(env_wl) = matches.remove_many::<String>("allow-env") {
-    flags.permissions.allow_env = Some(env_wl.collect());
-    debug!("env allowlist: {:#?}", &flags.permissions.allow_env);
-  }
+    let r = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "--check",
+      "--watch",
+      "foo.ts",
+      "--ignore=bar.js"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: true,
+          files: FileFlags {
+            include: vec!["foo.ts".to_string()],
+            ignore: vec!["bar.js".to_string()],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          unstable_component: false,
+          watch: Some(Default::default()),
+          line_ending: None,
+          bom: None,
+          skip_code_blocks: None,
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-  if let Some(env_wl) = matches.remove_many::<String>("deny-env") {
-    flags.permissions.deny_env = Some(env_wl.collect());
-    debug!("env denylist: {:#?}", &flags.permissions.deny_env);
-  }
+    let r = flags_from_vec(svec!["deno", "fmt", "--config", "deno.jsonc"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          unstable_component: false,
+          watch: Default::default(),
+          line_ending: None,
+          bom: None,
+          skip_code_blocks: None,
+          output_file: None,
+        }),
+        config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
+        ..Flags::default()
+      }
+    );
 
-  if let Some(run_wl) = matches.remove_many::<String>("allow-run") {
-    flags.permissions.allow_run = Some(run_wl.collect());
-    debug!("run allowlist: {:#?}", &flags.permissions.allow_run);
-  }
+    let r = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "--config",
+      "deno.jsonc",
+      "--watch",
+      "foo.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          files: FileFlags {
+            include: vec!["foo.ts".to_string()],
+            ignore: vec![],
+          },
+          use_tabs: None,
+          line_width: None,
+          indent_width: None,
+          single_quote: None,
+          prose_wrap: None,
+          no_semicolons: None,
+          unstable_component: false,
+          watch: Some(Default::default()),
+          line_ending: None,
+          bom: None,
+          skip_code_blocks: None,
+          output_file: None,
+        }),
+        config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
+        ..Flags::default()
+      }
+    );
 
-  if let Some(run_wl) = matches.remove_many::<String>("deny-run") {
-    flags.permissions.deny_run = Some(run_wl.collect());
-    debug!("run denylist: {:#?}", &flags.permissions.deny_run);
-  }
+    let r = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "--use-tabs",
+      "--line-width",
+      "60",
+      "--indent-width",
+      "4",
+      "--single-quote",
+      "--prose-wrap",
+      "never",
+      "--no-semicolons",
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: Some(true),
+          line_width: Some(NonZeroU32::new(60).unwrap()),
+          indent_width: Some(NonZeroU8::new(4).unwrap()),
+          single_quote: Some(true),
+          prose_wrap: Some("never".to_string()),
+          no_semicolons: Some(true),
+          unstable_component: false,
+          watch: Default::default(),
+          line_ending: None,
+          bom: None,
+          skip_code_blocks: None,
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-  if let Some(sys_wl) = matches.remove_many::<String>("allow-sys") {
-    flags.permissions.allow_sys = Some(sys_wl.collect());
-    debug!("sys info allowlist: {:#?}", &flags.permissions.allow_sys);
+    // try providing =false to the booleans
+    let r = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "--use-tabs=false",
+      "--single-quote=false",
+      "--no-semicolons=false",
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: false,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          use_tabs: Some(false),
+          line_width: None,
+          indent_width: None,
+          single_quote: Some(false),
+          prose_wrap: None,
+          no_semicolons: Some(false),
+          unstable_component: false,
+          watch: Default::default(),
+          line_ending: None,
+          bom: None,
+          skip_code_blocks: None,
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
   }
 
-  if let Some(sys_wl) = matches.remove_many::<String>("deny-sys") {
-    flags.permissions.deny_sys = Some(sys_wl.collect());
-    debug!("sys info denylist: {:#?}", &flags.permissions.deny_sys);
-  }
+  #[test]
+  fn fmt_line_ending_and_bom() {
+    let r =
+      flags_from_vec(svec!["deno", "fmt", "--line-ending=crlf", "--bom=strip"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          line_ending: Some("crlf".to_string()),
+          bom: Some("strip".to_string()),
+          ..FmtFlags::default()
+        }),
+        ..Flags::default()
+      }
+    );
 
-  if let Some(ffi_wl) = matches.remove_many::<String>("allow-ffi") {
-    let ffi_wl = ffi_wl
-      .flat_map(flat_escape_split_commas)
-      .collect::<Result<Vec<_>, _>>()?;
-    flags.permissions.allow_ffi = Some(ffi_wl);
-    debug!("ffi allowlist: {:#?}", &flags.permissions.allow_ffi);
+    let r = flags_from_vec(svec!["deno", "fmt", "--line-ending=cr"]);
+    assert!(r.is_err());
   }
 
-  if let Some(ffi_wl) = matches.remove_many::<String>("deny-ffi") {
-    let ffi_wl = ffi_wl
-      .flat_map(flat_escape_split_commas)
-      .collect::<Result<Vec<_>, _>>()?;
-    flags.permissions.deny_ffi = Some(ffi_wl);
-    debug!("ffi denylist: {:#?}", &flags.permissions.deny_ffi);
+  #[test]
+  fn fmt_skip_code_blocks() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "--skip-code-blocks=json,yaml",
+      "--skip-code-blocks=css"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          skip_code_blocks: Some(svec!["json", "yaml", "css"]),
+          ..FmtFlags::default()
+        }),
+        ..Flags::default()
+      }
+    );
   }
 
-  if matches.get_flag("allow-hrtime") || matches.get_flag("deny-hrtime") {
-    // use eprintln instead of log::warn because logging hasn't been initialized yet
-    #[allow(clippy::print_stderr)]
-    {
-      eprintln!(
-        "{} `allow-hrtime` and `deny-hrtime` have been removed in Deno 2, as high resolution time is now always allowed",
-        deno_runtime::colors::yellow("Warning")
-      );
-    }
-  }
+  #[test]
+  fn fmt_and_lint_output_file() {
+    let r =
+      flags_from_vec(svec!["deno", "fmt", "--check", "--output-file=fmt.json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Fmt(FmtFlags {
+          check: true,
+          output_file: Some("fmt.json".to_string()),
+          ..FmtFlags::default()
+        }),
+        ..Flags::default()
+      }
+    );
+    let r = flags_from_vec(svec!["deno", "fmt", "--output-file=fmt.json"]);
+    assert!(r.is_err());
 
-  if matches.get_flag("allow-all") {
-    flags.allow_all();
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--compact",
+      "--output-file=lint.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          compact: true,
+          output_file: Some("lint.json".to_string()),
+          ..LintFlags::default()
+        }),
+        ..Flags::default()
+      }
+    );
   }
 
-  allow_import_parse(flags, matches);
-
-  if matches.get_flag("no-prompt") {
-    flags.permissions.no_prompt = true;
-  }
+  #[test]
+  fn lsp() {
+    let r = flags_from_vec(svec!["deno", "lsp"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lsp(LspFlags::default()),
+        ..Flags::default()
+      }
+    );
 
-  Ok(())
-}
+    let r = flags_from_vec(svec!["deno", "lsp", "--status"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lsp(LspFlags {
+          status: true,
+          ..Default::default()
+        }),
+        ..Flags::default()
+      }
+    );
 
-fn allow_import_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  if let Some(imports_wl) = matches.remove_many::<String>("allow-import") {
-    let imports_allowlist = flags_net::parse(imports_wl.collect()).unwrap();
-    flags.permissions.allow_import = Some(imports_allowlist);
-  }
-}
+    let r = flags_from_vec(svec![
+      "deno",
+      "lsp",
+      "--diagnose",
+      "--settings",
+      "settings.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lsp(LspFlags {
+          status: false,
+          diagnose: true,
+          settings: Some("settings.json".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
 
-fn unsafely_ignore_certificate_errors_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) {
-  if let Some(ic_wl) =
-    matches.remove_many::<String>("unsafely-ignore-certificate-errors")
-  {
-    let ic_allowlist = flags_net::parse(ic_wl.collect()).unwrap();
-    flags.unsafely_ignore_certificate_errors = Some(ic_allowlist);
+    let r = flags_from_vec(svec!["deno", "lsp", "--settings", "settings.json"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "lsp", "--status", "--diagnose"]);
+    assert!(r.is_err());
   }
-}
 
-fn runtime_args_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-  include_perms: bool,
-  include_inspector: bool,
-) -> clap::error::Result<()> {
-  unstable_args_parse(flags, matches, UnstableArgsConfig::ResolutionAndRuntime);
-  compile_args_parse(flags, matches)?;
-  cached_only_arg_parse(flags, matches);
-  frozen_lockfile_arg_parse(flags, matches);
-  if include_perms {
-    permission_args_parse(flags, matches)?;
-  }
-  if include_inspector {
-    inspect_arg_parse(flags, matches);
-  }
-  location_arg_parse(flags, matches);
-  v8_flags_arg_parse(flags, matches);
-  seed_arg_parse(flags, matches);
-  enable_testing_features_arg_parse(flags, matches);
-  env_file_arg_parse(flags, matches);
-  strace_ops_parse(flags, matches);
-  Ok(())
-}
+  #[test]
+  fn lint() {
+    let r = flags_from_vec(svec!["deno", "lint", "script_1.ts", "script_2.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec!["script_1.ts".to_string(), "script_2.ts".to_string(),],
+            ignore: vec![],
+          },
+          fix: false,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          watch: Default::default(),
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-fn inspect_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.inspect = matches.remove_one::<SocketAddr>("inspect");
-  flags.inspect_brk = matches.remove_one::<SocketAddr>("inspect-brk");
-  flags.inspect_wait = matches.remove_one::<SocketAddr>("inspect-wait");
-}
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--watch",
+      "script_1.ts",
+      "script_2.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec!["script_1.ts".to_string(), "script_2.ts".to_string()],
+            ignore: vec![],
+          },
+          fix: false,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          watch: Some(Default::default()),
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-fn import_map_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.import_map_path = matches.remove_one::<String>("import-map");
-}
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--watch",
+      "--no-clear-screen",
+      "script_1.ts",
+      "script_2.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec!["script_1.ts".to_string(), "script_2.ts".to_string()],
+            ignore: vec![],
+          },
+          fix: false,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          watch: Some(WatchFlags {
+            hmr: false,
+            no_clear_screen: true,
+            exclude: vec![],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
+          }),
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-fn env_file_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.env_file = matches.remove_one::<String>("env-file");
-}
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--fix",
+      "--ignore=script_1.ts,script_2.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![],
+            ignore: vec!["script_1.ts".to_string(), "script_2.ts".to_string()],
+          },
+          fix: true,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          watch: Default::default(),
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-fn reload_arg_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) -> clap::error::Result<()> {
-  if let Some(cache_bl) = matches.remove_many::<String>("reload") {
-    let raw_cache_blocklist: Vec<String> = cache_bl
-      .flat_map(flat_escape_split_commas)
-      .map(|s| s.and_then(reload_arg_validate))
-      .collect::<Result<Vec<_>, _>>()?;
-    if raw_cache_blocklist.is_empty() {
-      flags.reload = true;
-    } else {
-      flags.cache_blocklist = resolve_urls(raw_cache_blocklist);
-      debug!("cache blocklist: {:#?}", &flags.cache_blocklist);
-      flags.reload = false;
-    }
-  }
+    let r = flags_from_vec(svec!["deno", "lint", "--rules"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          fix: false,
+          rules: true,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          watch: Default::default(),
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-  Ok(())
-}
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--rules",
+      "--rules-tags=recommended"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          fix: false,
+          rules: true,
+          maybe_rules_tags: Some(svec!["recommended"]),
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: false,
+          watch: Default::default(),
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-fn ca_file_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.ca_data = matches.remove_one::<String>("cert").map(CaData::File);
-}
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--rules-tags=",
+      "--rules-include=ban-untagged-todo,no-undef",
+      "--rules-exclude=no-const-assign"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          fix: false,
+          rules: false,
+          maybe_rules_tags: Some(svec![""]),
+          maybe_rules_include: Some(svec!["ban-untagged-todo", "no-undef"]),
+          maybe_rules_exclude: Some(svec!["no-const-assign"]),
+          json: false,
+          compact: false,
+          watch: Default::default(),
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-fn enable_testing_features_arg_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) {
-  if matches.get_flag("enable-testing-features-do-not-use") {
-    flags.enable_testing_features = true
-  }
-}
+    let r = flags_from_vec(svec!["deno", "lint", "--json", "script_1.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec!["script_1.ts".to_string()],
+            ignore: vec![],
+          },
+          fix: false,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: true,
+          compact: false,
+          watch: Default::default(),
+          output_file: None,
+        }),
+        ..Flags::default()
+      }
+    );
 
-fn strace_ops_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  if let Some(patterns) = matches.remove_many::<String>("strace-ops") {
-    flags.strace_ops = Some(patterns.collect());
-  }
-}
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--config",
+      "Deno.jsonc",
+      "--json",
+      "script_1.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec!["script_1.ts".to_string()],
+            ignore: vec![],
+          },
+          fix: false,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: true,
+          compact: false,
+          watch: Default::default(),
+          output_file: None,
+        }),
+        config_flag: ConfigFlag::Path("Deno.jsonc".to_string()),
+        ..Flags::default()
+      }
+    );
 
-fn cached_only_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  if matches.get_flag("cached-only") {
-    flags.cached_only = true;
+    let r = flags_from_vec(svec![
+      "deno",
+      "lint",
+      "--config",
+      "Deno.jsonc",
+      "--compact",
+      "script_1.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Lint(LintFlags {
+          files: FileFlags {
+            include: vec!["script_1.ts".to_string()],
+            ignore: vec![],
+          },
+          fix: false,
+          rules: false,
+          maybe_rules_tags: None,
+          maybe_rules_include: None,
+          maybe_rules_exclude: None,
+          json: false,
+          compact: true,
+          watch: Default::default(),
+          output_file: None,
+        }),
+        config_flag: ConfigFlag::Path("Deno.jsonc".to_string()),
+        ..Flags::default()
+      }
+    );
   }
-}
 
-fn frozen_lockfile_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  if let Some(&v) = matches.get_one::<bool>("frozen") {
-    flags.frozen_lockfile = Some(v);
+  #[test]
+  fn types() {
+    let r = flags_from_vec(svec!["deno", "types"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Types,
+        ..Flags::default()
+      }
+    );
   }
-}
 
-fn ext_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.ext = matches.remove_one::<String>("ext");
-}
+  #[test]
+  fn bundle() {
+    let r = flags_from_vec(svec!["deno", "bundle", "main.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bundle(BundleFlags {
+          entrypoints: svec!["main.ts"],
+          ..Default::default()
+        }),
+        ..Flags::default()
+      }
+    );
 
-fn location_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.location = matches.remove_one::<Url>("location");
-}
+    let r = flags_from_vec(svec![
+      "deno",
+      "bundle",
+      "--outdir",
+      "dist",
+      "--format",
+      "iife",
+      "--platform",
+      "browser",
+      "--source-map",
+      "--external=npm:*",
+      "--minify",
+      "--check",
+      "main.ts",
+      "worker.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bundle(BundleFlags {
+          entrypoints: svec!["main.ts", "worker.ts"],
+          output: None,
+          outdir: Some("dist".to_string()),
+          format: BundleFormat::Iife,
+          platform: BundlePlatform::Browser,
+          source_map: Some(BundleSourceMap::External),
+          external: svec!["npm:*"],
+          minify: true,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
 
-fn v8_flags_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  if let Some(v8_flags) = matches.remove_many::<String>("v8-flags") {
-    flags.v8_flags = v8_flags.collect();
-  }
-}
+    let r = flags_from_vec(svec![
+      "deno",
+      "bundle",
+      "--source-map=inline",
+      "-o",
+      "out.js",
+      "main.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Bundle(BundleFlags {
+          entrypoints: svec!["main.ts"],
+          output: Some("out.js".to_string()),
+          source_map: Some(BundleSourceMap::Inline),
+          ..Default::default()
+        }),
+        ..Flags::default()
+      }
+    );
 
-fn seed_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  if let Some(seed) = matches.remove_one::<u64>("seed") {
-    flags.seed = Some(seed);
+    let r = flags_from_vec(svec![
+      "deno", "bundle", "-o", "out.js", "--outdir", "dist", "main.ts"
+    ]);
+    assert!(r.is_err());
 
-    flags.v8_flags.push(format!("--random-seed={seed}"));
+    let r =
+      flags_from_vec(svec!["deno", "bundle", "--platform=node", "main.ts"]);
+    assert!(r.is_err());
   }
-}
 
-fn no_check_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  if let Some(cache_type) = matches.get_one::<String>("no-check") {
-    match cache_type.as_str() {
-      "remote" => flags.type_check_mode = TypeCheckMode::Local,
-      _ => debug!(
-        "invalid value for 'no-check' of '{}' using default",
-        cache_type
-      ),
-    }
-  } else if matches.contains_id("no-check") {
-    flags.type_check_mode = TypeCheckMode::None;
+  #[test]
+  fn cache() {
+    let r = flags_from_vec(svec!["deno", "cache", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts"],
+          dry_run: false,
+          json: false,
+        }),
+        ..Flags::default()
+      }
+    );
   }
-}
 
-fn check_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  if let Some(cache_type) = matches.get_one::<String>("check") {
-    match cache_type.as_str() {
-      "all" => flags.type_check_mode = TypeCheckMode::All,
-      _ => debug!(
-        "invalid value for 'check' of '{}' using default",
-        cache_type
-      ),
-    }
-  } else if matches.contains_id("check") {
-    flags.type_check_mode = TypeCheckMode::Local;
+  #[test]
+  fn cache_dry_run() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "cache",
+      "--dry-run",
+      "--json",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts"],
+          dry_run: true,
+          json: true,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    // --json is only supported for the report of --dry-run
+    let r = flags_from_vec(svec!["deno", "cache", "--json", "script.ts"]);
+    assert!(r.is_err());
   }
-}
 
-fn lock_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  lock_arg_parse(flags, matches);
-  no_lock_arg_parse(flags, matches);
-}
+  #[test]
+  fn check() {
+    let r = flags_from_vec(svec!["deno", "check", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["script.ts"],
+          doc: false,
+          doc_only: false,
+          check_jobs: None,
+          affected_by: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
 
-fn lock_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  if matches.contains_id("lock") {
-    let lockfile = matches.remove_one::<String>("lock").unwrap();
-    flags.lock = Some(lockfile);
-  }
-}
+    let r = flags_from_vec(svec!["deno", "check", "--doc", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["script.ts"],
+          doc: true,
+          doc_only: false,
+          check_jobs: None,
+          affected_by: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
 
-fn no_lock_arg_parse(flags: &mut Flags, matches: &ArgMatches) {
-  if matches.get_flag("no-lock") {
-    flags.no_lock = true;
-  }
-}
+    let r = flags_from_vec(svec!["deno", "check", "--doc-only", "markdown.md"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["markdown.md"],
+          doc: false,
+          doc_only: true,
+          check_jobs: None,
+          affected_by: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
 
-fn config_args_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  flags.config_flag = if matches.get_flag("no-config") {
-    ConfigFlag::Disabled
-  } else if let Some(config) = matches.remove_one::<String>("config") {
-    ConfigFlag::Path(config)
-  } else {
-    ConfigFlag::Discover
-  };
-}
+    let r =
+      flags_from_vec(svec!["deno", "check", "--check-jobs", "4", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["script.ts"],
+          doc: false,
+          doc_only: false,
+          check_jobs: NonZeroUsize::new(4),
+          affected_by: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+    let r =
+      flags_from_vec(svec!["deno", "check", "--check-jobs", "0", "script.ts"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "check", "--no-daemon", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["script.ts"],
+          doc: false,
+          doc_only: false,
+          check_jobs: None,
+          affected_by: None,
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        no_daemon: true,
+        ..Flags::default()
+      }
+    );
 
-fn no_remote_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  if matches.get_flag("no-remote") {
-    flags.no_remote = true;
-  }
-}
+    let r =
+      flags_from_vec(svec!["deno", "check", "--affected-by=src/a.ts,src/b.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Check(CheckFlags {
+          files: svec!["."],
+          doc: false,
+          doc_only: false,
+          check_jobs: None,
+          affected_by: Some(svec!["src/a.ts", "src/b.ts"]),
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
 
-fn no_npm_arg_parse(flags: &mut Flags, matches: &mut ArgMatches) {
-  if matches.get_flag("no-npm") {
-    flags.no_npm = true;
-  }
-}
+    // `--doc` and `--doc-only` are mutually exclusive
+    let r = flags_from_vec(svec![
+      "deno",
+      "check",
+      "--doc",
+      "--doc-only",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap_err().kind(),
+      clap::error::ErrorKind::ArgumentConflict
+    );
 
-fn node_modules_and_vendor_dir_arg_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-) {
-  node_modules_arg_parse(flags, matches);
-  flags.vendor = matches.remove_one::<bool>("vendor");
-}
+    for all_flag in ["--remote", "--all"] {
+      let r = flags_from_vec(svec!["deno", "check", all_flag, "script.ts"]);
+      assert_eq!(
+        r.unwrap(),
+        Flags {
+          subcommand: DenoSubcommand::Check(CheckFlags {
+            files: svec!["script.ts"],
+            doc: false,
+            doc_only: false,
+            check_jobs: None,
+            affected_by: None,
+          }),
+          type_check_mode: TypeCheckMode::All,
+          ..Flags::default()
+        }
+      );
 
-fn reload_arg_validate(urlstr: String) -> Result<String, clap::Error> {
-  if urlstr.is_empty() {
-    return Err(
-      std::io::Error::new(
-        std::io::ErrorKind::Other,
-        String::from("Missing url. Check for extra commas."),
-      )
-      .into(),
-    );
-  }
-  match Url::from_str(&urlstr) {
-    Ok(_) => Ok(urlstr),
-    Err(e) => {
-      Err(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()).into())
+      let r = flags_from_vec(svec![
+        "deno",
+        "check",
+        all_flag,
+        "--no-remote",
+        "script.ts"
+      ]);
+      assert_eq!(
+        r.unwrap_err().kind(),
+        clap::error::ErrorKind::ArgumentConflict
+      );
     }
   }
-}
 
-fn watch_arg_parse(
-  matches: &mut ArgMatches,
-) -> clap::error::Result<Option<WatchFlags>> {
-  if matches.get_flag("watch") {
-    Ok(Some(WatchFlags {
-      hmr: false,
-      no_clear_screen: matches.get_flag("no-clear-screen"),
-      exclude: matches
-        .remove_many::<String>("watch-exclude")
-        .map(|f| {
-          f.flat_map(flat_escape_split_commas)
-            .collect::<Result<_, _>>()
-        })
-        .transpose()?
-        .unwrap_or_default(),
-    }))
-  } else {
-    Ok(None)
-  }
-}
+  #[test]
+  fn clean() {
+    let r = flags_from_vec(svec!["deno", "clean"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Clean(CleanFlags::default()),
+        ..Flags::default()
+      }
+    );
 
-fn watch_arg_parse_with_paths(
-  matches: &mut ArgMatches,
-) -> clap::error::Result<Option<WatchFlagsWithPaths>> {
-  if let Some(paths) = matches.remove_many::<String>("watch") {
-    return Ok(Some(WatchFlagsWithPaths {
-      paths: paths
-        .flat_map(flat_escape_split_commas)
-        .collect::<Result<Vec<_>, _>>()?,
-      hmr: false,
-      no_clear_screen: matches.get_flag("no-clear-screen"),
-      exclude: matches
-        .remove_many::<String>("watch-exclude")
-        .map(|f| {
-          f.flat_map(flat_escape_split_commas)
-            .collect::<Result<Vec<_>, _>>()
-        })
-        .transpose()?
-        .unwrap_or_default(),
-    }));
-  }
+    let r = flags_from_vec(svec![
+      "deno",
+      "clean",
+      "--remote-match",
+      "https://deno.land/std@"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Clean(CleanFlags {
+          remote_match: Some("https://deno.land/std@".to_string()),
+          ..Default::default()
+        }),
+        ..Flags::default()
+      }
+    );
 
-  if matches.try_contains_id("hmr").is_ok() {
-    return matches
-      .remove_many::<String>("hmr")
-      .map(|paths| {
-        Ok(WatchFlagsWithPaths {
-          paths: paths
-            .flat_map(flat_escape_split_commas)
-            .collect::<Result<Vec<_>, _>>()?,
-          hmr: true,
-          no_clear_screen: matches.get_flag("no-clear-screen"),
-          exclude: matches
-            .remove_many::<String>("watch-exclude")
-            .map(|f| {
-              f.flat_map(flat_escape_split_commas)
-                .collect::<Result<Vec<_>, _>>()
-            })
-            .transpose()?
-            .unwrap_or_default(),
-        })
-      })
-      .transpose();
-  }
+    let r = flags_from_vec(svec![
+      "deno",
+      "clean",
+      "--npm",
+      "--codegen",
+      "--older-than=30d",
+      "--dry-run"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Clean(CleanFlags {
+          npm: true,
+          codegen: true,
+          older_than: Some(Duration::from_secs(30 * 24 * 60 * 60)),
+          dry_run: true,
+          ..Default::default()
+        }),
+        ..Flags::default()
+      }
+    );
 
-  Ok(None)
-}
+    let r = flags_from_vec(svec!["deno", "clean", "--older-than=12h"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Clean(CleanFlags {
+          older_than: Some(Duration::from_secs(12 * 60 * 60)),
+          ..Default::default()
+        }),
+        ..Flags::default()
+      }
+    );
 
-fn unstable_args_parse(
-  flags: &mut Flags,
-  matches: &mut ArgMatches,
-  cfg: UnstableArgsConfig,
-) {
-  // TODO(bartlomieju): remove in Deno 2.5
-  if matches.get_flag("unstable") {
-    flags.unstable_config.legacy_flag_enabled = true;
+    let r = flags_from_vec(svec!["deno", "clean", "--older-than=30"]);
+    r.unwrap_err();
+    let r = flags_from_vec(svec!["deno", "clean", "--older-than=3y"]);
+    r.unwrap_err();
   }
 
-  flags.unstable_config.bare_node_builtins =
-    matches.get_flag("unstable-bare-node-builtins");
-  flags.unstable_config.sloppy_imports =
-    matches.get_flag("unstable-sloppy-imports");
-
-  if matches!(cfg, UnstableArgsConfig::ResolutionAndRuntime) {
-    for granular_flag in crate::UNSTABLE_GRANULAR_FLAGS {
-      if matches.get_flag(&format!("unstable-{}", granular_flag.name)) {
-        flags
-          .unstable_config
-          .features
-          .push(granular_flag.name.to_string());
+  #[test]
+  fn info_unused() {
+    let r = flags_from_vec(svec!["deno", "info", "--unused", "--fix"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: None,
+          http_cache: false,
+          cache_usage: false,
+          graph: None,
+          graph_depth: None,
+          collapse_npm: false,
+          unused: true,
+          fix: true,
+        }),
+        ..Flags::default()
       }
-    }
-  }
-}
+    );
 
-// TODO(ry) move this to utility module and add test.
-/// Strips fragment part of URL. Panics on bad URL.
-pub fn resolve_urls(urls: Vec<String>) -> Vec<String> {
-  let mut out: Vec<String> = vec![];
-  for urlstr in urls.iter() {
-    if let Ok(mut url) = Url::from_str(urlstr) {
-      url.set_fragment(None);
-      let mut full_url = String::from(url.as_str());
-      if full_url.len() > 1 && full_url.ends_with('/') {
-        full_url.pop();
-      }
-      out.push(full_url);
-    } else {
-      panic!("Bad Url: {urlstr}");
-    }
+    let r = flags_from_vec(svec!["deno", "info", "--fix"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "info", "--unused", "script.ts"]);
+    assert!(r.is_err());
   }
-  out
-}
 
-#[cfg(test)]
-mod tests {
-  use super::*;
-  use pretty_assertions::assert_eq;
+  #[test]
+  fn info_http_cache() {
+    let r = flags_from_vec(svec!["deno", "info", "--http-cache", "--json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          file: None,
+          http_cache: true,
+          cache_usage: false,
+          graph: None,
+          graph_depth: None,
+          collapse_npm: false,
+          unused: false,
+          fix: false,
+        }),
+        ..Flags::default()
+      }
+    );
 
-  /// Creates vector of strings, Vec<String>
-  macro_rules! svec {
-    ($($x:expr),* $(,)?) => (vec![$($x.to_string().into()),*]);
+    let r = flags_from_vec(svec!["deno", "info", "--http-cache", "script.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn global_flags() {
-    #[rustfmt::skip]
-    let r = flags_from_vec(svec!["deno", "--log-level", "debug", "--quiet", "run", "script.ts"]);
+  fn info_cache_usage() {
+    let r = flags_from_vec(svec!["deno", "info", "--cache-usage"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: None,
+          http_cache: false,
+          cache_usage: true,
+          graph: None,
+          graph_depth: None,
+          collapse_npm: false,
+          unused: false,
+          fix: false,
+        }),
+        ..Flags::default()
+      }
+    );
 
-    let flags = r.unwrap();
+    let r =
+      flags_from_vec(svec!["deno", "info", "--cache-usage", "--http-cache"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "info", "--storage", "--json"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string()
-        )),
-        log_level: Some(Level::Error),
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          file: None,
+          http_cache: false,
+          cache_usage: true,
+          graph: None,
+          graph_depth: None,
+          collapse_npm: false,
+          unused: false,
+          fix: false,
+        }),
         ..Flags::default()
       }
     );
-    #[rustfmt::skip]
-    let r2 = flags_from_vec(svec!["deno", "run", "--log-level", "debug", "--quiet", "script.ts"]);
-    let flags2 = r2.unwrap();
-    assert_eq!(flags2, flags);
   }
 
   #[test]
-  fn upgrade() {
-    let r = flags_from_vec(svec!["deno", "upgrade", "--dry-run", "--force"]);
-    let flags = r.unwrap();
+  fn info_graph() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "info",
+      "--graph=mermaid",
+      "--depth=2",
+      "--collapse-npm",
+      "script.ts"
+    ]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
-          force: true,
-          dry_run: true,
-          canary: false,
-          release_candidate: false,
-          version: None,
-          output: None,
-          version_or_hash_or_channel: None,
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: Some("script.ts".to_string()),
+          http_cache: false,
+          cache_usage: false,
+          graph: Some(InfoGraphFormat::Mermaid),
+          graph_depth: Some(2),
+          collapse_npm: true,
+          unused: false,
+          fix: false,
         }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "info", "--graph=dot"]);
+    assert!(r.is_err());
+    let r =
+      flags_from_vec(svec!["deno", "info", "--graph=dot", "--json", "a.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "info", "--depth=1", "a.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn upgrade_with_output_flag() {
-    let r = flags_from_vec(svec!["deno", "upgrade", "--output", "example.txt"]);
+  fn info() {
+    let r = flags_from_vec(svec!["deno", "info", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
-          force: false,
-          dry_run: false,
-          canary: false,
-          release_candidate: false,
-          version: None,
-          output: Some(String::from("example.txt")),
-          version_or_hash_or_channel: None,
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: Some("script.ts".to_string()),
+          http_cache: false,
+          cache_usage: false,
+          graph: None,
+          graph_depth: None,
+          collapse_npm: false,
+          unused: false,
+          fix: false,
         }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn version() {
-    let r = flags_from_vec(svec!["deno", "--version"]);
+    let r = flags_from_vec(svec!["deno", "info", "--reload", "script.ts"]);
     assert_eq!(
-      r.unwrap_err().kind(),
-      clap::error::ErrorKind::DisplayVersion
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: Some("script.ts".to_string()),
+          http_cache: false,
+          cache_usage: false,
+          graph: None,
+          graph_depth: None,
+          collapse_npm: false,
+          unused: false,
+          fix: false,
+        }),
+        reload: true,
+        ..Flags::default()
+      }
     );
-    let r = flags_from_vec(svec!["deno", "-V"]);
+
+    let r = flags_from_vec(svec!["deno", "info", "--json", "script.ts"]);
     assert_eq!(
-      r.unwrap_err().kind(),
-      clap::error::ErrorKind::DisplayVersion
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          file: Some("script.ts".to_string()),
+          http_cache: false,
+          cache_usage: false,
+          graph: None,
+          graph_depth: None,
+          collapse_npm: false,
+          unused: false,
+          fix: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: None,
+          http_cache: false,
+          cache_usage: false,
+          graph: None,
+          graph_depth: None,
+          collapse_npm: false,
+          unused: false,
+          fix: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "info", "--json"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: true,
+          file: None,
+          http_cache: false,
+          cache_usage: false,
+          graph: None,
+          graph_depth: None,
+          collapse_npm: false,
+          unused: false,
+          fix: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "info",
+      "--no-npm",
+      "--no-remote",
+      "--config",
+      "tsconfig.json"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: None,
+          http_cache: false,
+          cache_usage: false,
+          graph: None,
+          graph_depth: None,
+          collapse_npm: false,
+          unused: false,
+          fix: false,
+        }),
+        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
+        no_npm: true,
+        no_remote: true,
+        ..Flags::default()
+      }
     );
   }
 
   #[test]
-  fn run_reload() {
-    let r = flags_from_vec(svec!["deno", "run", "-r", "script.ts"]);
-    let flags = r.unwrap();
+  fn tsconfig() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "-c", "tsconfig.json", "script.ts"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string()
+          "script.ts".to_string(),
         )),
-        reload: true,
+        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -5802,290 +11285,385 @@ mod tests {
   }
 
   #[test]
-  fn run_watch() {
-    let r = flags_from_vec(svec!["deno", "run", "--watch", "script.ts"]);
-    let flags = r.unwrap();
+  fn eval() {
+    let r = flags_from_vec(svec!["deno", "eval", "'console.log(\"hello\")'"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![],
-            no_clear_screen: false,
-            exclude: vec![],
-          }),
-          bare: false,
+        subcommand: DenoSubcommand::Eval(EvalFlags {
+          print: false,
+          code: vec!["'console.log(\"hello\")'".to_string()],
+          input: None,
         }),
-        code_cache_enabled: true,
+        permissions: PermissionFlags {
+          allow_all: true,
+          ..Default::default()
+        },
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn eval_p() {
+    let r = flags_from_vec(svec!["deno", "eval", "-p", "1+2"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Eval(EvalFlags {
+          print: true,
+          code: vec!["1+2".to_string()],
+          input: None,
+        }),
+        permissions: PermissionFlags {
+          allow_all: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn eval_typescript() {
     let r = flags_from_vec(svec![
       "deno",
-      "--watch",
-      "--no-clear-screen",
-      "script.ts"
+      "eval",
+      "--ext=ts",
+      "'console.log(\"hello\")'"
     ]);
-    let flags = r.unwrap();
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![],
-            no_clear_screen: true,
-            exclude: vec![],
-          }),
-          bare: true,
+        subcommand: DenoSubcommand::Eval(EvalFlags {
+          print: false,
+          code: vec!["'console.log(\"hello\")'".to_string()],
+          input: None,
         }),
-        code_cache_enabled: true,
+        permissions: PermissionFlags {
+          allow_all: true,
+          ..Default::default()
+        },
+        ext: Some("ts".to_string()),
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn eval_with_flags() {
+    #[rustfmt::skip]
+    let r = flags_from_vec(svec!["deno", "eval", "--import-map", "import_map.json", "--no-remote", "--config", "tsconfig.json", "--no-check", "--reload", "--lock", "lock.json", "--cert", "example.crt", "--cached-only", "--location", "https:foo", "--v8-flags=--help", "--seed", "1", "--inspect=127.0.0.1:9229", "--env=.example.env", "42"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Eval(EvalFlags {
+          print: false,
+          code: vec!["42".to_string()],
+          input: None,
+        }),
+        import_map_path: Some("import_map.json".to_string()),
+        no_remote: true,
+        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
+        type_check_mode: TypeCheckMode::None,
+        reload: true,
+        lock: Some(String::from("lock.json")),
+        ca_data: Some(CaData::File("example.crt".to_string())),
+        cached_only: true,
+        location: Some(Url::parse("https://foo/").unwrap()),
+        v8_flags: svec!["--help", "--random-seed=1"],
+        seed: Some(1),
+        inspect: Some("127.0.0.1:9229".parse().unwrap()),
+        permissions: PermissionFlags {
+          allow_all: true,
+          ..Default::default()
+        },
+        env_file: Some(vec![".example.env".to_owned()]),
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn eval_args() {
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "--watch-hmr",
-      "--no-clear-screen",
-      "script.ts"
+      "eval",
+      "console.log(Deno.args)",
+      "arg1",
+      "arg2"
     ]);
-    let flags = r.unwrap();
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: Some(WatchFlagsWithPaths {
-            hmr: true,
-            paths: vec![],
-            no_clear_screen: true,
-            exclude: vec![],
-          }),
-          bare: false,
+        subcommand: DenoSubcommand::Eval(EvalFlags {
+          print: false,
+          code: vec!["console.log(Deno.args)".to_string()],
+          input: None,
         }),
-        code_cache_enabled: true,
+        argv: svec!["arg1", "arg2"],
+        permissions: PermissionFlags {
+          allow_all: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn eval_snippets_with_input() {
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "--unstable-hmr",
-      "--no-clear-screen",
-      "script.ts"
+      "eval",
+      "-p",
+      "--input=json",
+      "-e",
+      "const { a } = $stdin",
+      "-e",
+      "a + Deno.args[0]",
+      "arg1"
     ]);
-    let flags = r.unwrap();
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: Some(WatchFlagsWithPaths {
-            hmr: true,
-            paths: vec![],
-            no_clear_screen: true,
-            exclude: vec![],
-          }),
-          bare: false,
+        subcommand: DenoSubcommand::Eval(EvalFlags {
+          print: true,
+          code: svec!["const { a } = $stdin", "a + Deno.args[0]"],
+          input: Some(EvalInputMode::Json),
         }),
-        code_cache_enabled: true,
+        argv: svec!["arg1"],
+        permissions: PermissionFlags {
+          allow_all: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--watch-hmr=foo.txt",
-      "--no-clear-screen",
-      "script.ts"
-    ]);
-    let flags = r.unwrap();
+    let r = flags_from_vec(svec!["deno", "eval", "--input=yaml", "1"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn repl() {
+    let r = flags_from_vec(svec!["deno"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: Some(WatchFlagsWithPaths {
-            hmr: true,
-            paths: vec![String::from("foo.txt")],
-            no_clear_screen: true,
-            exclude: vec![],
-          }),
-          bare: false,
+        subcommand: DenoSubcommand::Repl(ReplFlags {
+          eval_files: None,
+          eval: None,
+          is_default_command: true,
+          save_session: None,
+          auto_import: false,
+          repl_server: None,
         }),
-        code_cache_enabled: true,
+        unsafely_ignore_certificate_errors: None,
+        permissions: PermissionFlags {
+          allow_all: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn repl_strace_ops() {
+    // Lightly test this undocumented flag
+    let r = flags_from_vec(svec!["deno", "repl", "--strace-ops"]);
+    assert_eq!(r.unwrap().strace_ops, Some(vec![]));
     let r =
-      flags_from_vec(svec!["deno", "run", "--hmr", "--watch", "script.ts"]);
-    assert!(r.is_err());
+      flags_from_vec(svec!["deno", "repl", "--strace-ops=http,websocket"]);
+    assert_eq!(
+      r.unwrap().strace_ops,
+      Some(vec!["http".to_string(), "websocket".to_string()])
+    );
   }
 
   #[test]
-  fn run_watch_with_external() {
-    let r = flags_from_vec(svec!["deno", "--watch=file1,file2", "script.ts"]);
-    let flags = r.unwrap();
+  fn repl_with_flags() {
+    #[rustfmt::skip]
+    let r = flags_from_vec(svec!["deno", "repl", "-A", "--import-map", "import_map.json", "--no-remote", "--config", "tsconfig.json", "--no-check", "--reload", "--lock", "lock.json", "--cert", "example.crt", "--cached-only", "--location", "https:foo", "--v8-flags=--help", "--seed", "1", "--inspect=127.0.0.1:9229", "--unsafely-ignore-certificate-errors", "--env=.example.env"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![String::from("file1"), String::from("file2")],
-            no_clear_screen: false,
-            exclude: vec![],
-          }),
-          bare: true,
+        subcommand: DenoSubcommand::Repl(ReplFlags {
+          eval_files: None,
+          eval: None,
+          is_default_command: false,
+          save_session: None,
+          auto_import: false,
+          repl_server: None,
         }),
-        code_cache_enabled: true,
+        import_map_path: Some("import_map.json".to_string()),
+        no_remote: true,
+        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
+        type_check_mode: TypeCheckMode::None,
+        reload: true,
+        lock: Some(String::from("lock.json")),
+        ca_data: Some(CaData::File("example.crt".to_string())),
+        cached_only: true,
+        location: Some(Url::parse("https://foo/").unwrap()),
+        v8_flags: svec!["--help", "--random-seed=1"],
+        seed: Some(1),
+        inspect: Some("127.0.0.1:9229".parse().unwrap()),
+        permissions: PermissionFlags {
+          allow_all: true,
+          ..Default::default()
+        },
+        env_file: Some(vec![".example.env".to_owned()]),
+        unsafely_ignore_certificate_errors: Some(vec![]),
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn run_watch_with_no_clear_screen() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--watch",
-      "--no-clear-screen",
-      "script.ts"
-    ]);
-
-    let flags = r.unwrap();
+  fn repl_with_eval_flag() {
+    #[rustfmt::skip]
+    let r = flags_from_vec(svec!["deno", "repl", "--allow-write", "--eval", "console.log('hello');"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![],
-            no_clear_screen: true,
-            exclude: vec![],
-          }),
-          bare: false,
+        subcommand: DenoSubcommand::Repl(ReplFlags {
+          eval_files: None,
+          eval: Some("console.log('hello');".to_string()),
+          is_default_command: false,
+          save_session: None,
+          auto_import: false,
+          repl_server: None,
         }),
-        code_cache_enabled: true,
+        permissions: PermissionFlags {
+          allow_write: Some(vec![]),
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::None,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn run_watch_with_excluded_paths() {
-    let r = flags_from_vec(svec!(
-      "deno",
-      "--watch",
-      "--watch-exclude=foo",
-      "script.ts"
-    ));
+  fn repl_with_eval_file_flag() {
+    #[rustfmt::skip]
+    let r = flags_from_vec(svec!["deno", "repl", "--eval-file=./a.js,./b.ts,https://examples.deno.land/hello-world.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Repl(ReplFlags {
+          eval_files: Some(vec![
+            "./a.js".to_string(),
+            "./b.ts".to_string(),
+            "https://examples.deno.land/hello-world.ts".to_string()
+          ]),
+          eval: None,
+          is_default_command: false,
+          save_session: None,
+          auto_import: false,
+          repl_server: None,
+        }),
+        type_check_mode: TypeCheckMode::None,
+        ..Flags::default()
+      }
+    );
+  }
 
-    let flags = r.unwrap();
+  #[test]
+  fn repl_with_save_session_flag() {
+    let r = flags_from_vec(svec!["deno", "repl", "--save-session=session.ts"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![],
-            no_clear_screen: false,
-            exclude: vec![String::from("foo")],
-          }),
-          bare: true,
+        subcommand: DenoSubcommand::Repl(ReplFlags {
+          eval_files: None,
+          eval: None,
+          is_default_command: false,
+          save_session: Some("session.ts".to_string()),
+          auto_import: false,
+          repl_server: None,
         }),
-        code_cache_enabled: true,
+        type_check_mode: TypeCheckMode::None,
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec!(
-      "deno",
-      "run",
-      "--watch=foo",
-      "--watch-exclude=bar",
-      "script.ts"
-    ));
-    let flags = r.unwrap();
+  #[test]
+  fn repl_with_repl_server_flag() {
+    let r =
+      flags_from_vec(svec!["deno", "repl", "--repl-server=127.0.0.1:9000"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![String::from("foo")],
-            no_clear_screen: false,
-            exclude: vec![String::from("bar")],
-          }),
-          bare: false,
+        subcommand: DenoSubcommand::Repl(ReplFlags {
+          eval_files: None,
+          eval: None,
+          is_default_command: false,
+          save_session: None,
+          auto_import: false,
+          repl_server: Some("127.0.0.1:9000".parse().unwrap()),
         }),
-        code_cache_enabled: true,
+        type_check_mode: TypeCheckMode::None,
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--watch",
-      "--watch-exclude=foo,bar",
-      "script.ts"
-    ]);
+    let r = flags_from_vec(svec!["deno", "repl", "--repl-server=9000"]);
+    assert!(r.is_err());
 
-    let flags = r.unwrap();
+    let r = flags_from_vec(svec!["deno", "repl", "--repl-server=0.0.0.0:9000"]);
+    assert!(r.is_err());
+    let r =
+      flags_from_vec(svec!["deno", "repl", "--repl-server=192.168.1.2:9000"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "repl", "--repl-server=[::1]:9000"]);
+    assert!(r.is_ok());
+  }
+
+  #[test]
+  fn repl_with_auto_import_flag() {
+    let r = flags_from_vec(svec!["deno", "repl", "--auto-import"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![],
-            no_clear_screen: false,
-            exclude: vec![String::from("foo"), String::from("bar")],
-          }),
-          bare: false,
+        subcommand: DenoSubcommand::Repl(ReplFlags {
+          eval_files: None,
+          eval: None,
+          is_default_command: false,
+          save_session: None,
+          auto_import: true,
+          repl_server: None,
         }),
-        code_cache_enabled: true,
+        type_check_mode: TypeCheckMode::None,
         ..Flags::default()
       }
     );
+  }
+
+  #[test]
+  fn allow_read_allowlist() {
+    use test_util::TempDir;
+    let temp_dir_guard = TempDir::new();
+    let temp_dir = temp_dir_guard.path().to_string();
 
     let r = flags_from_vec(svec![
       "deno",
-      "--watch=foo,bar",
-      "--watch-exclude=baz,qux",
+      "run",
+      format!("--allow-read=.,{}", temp_dir),
       "script.ts"
     ]);
-
-    let flags = r.unwrap();
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![String::from("foo"), String::from("bar")],
-            no_clear_screen: false,
-            exclude: vec![String::from("baz"), String::from("qux"),],
-          }),
-          bare: true,
-        }),
+        permissions: PermissionFlags {
+          allow_read: Some(vec![String::from("."), temp_dir]),
+          ..Default::default()
+        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -6093,20 +11671,27 @@ mod tests {
   }
 
   #[test]
-  fn run_reload_allow_write() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "-r", "--allow-write", "script.ts"]);
+  fn deny_read_denylist() {
+    use test_util::TempDir;
+    let temp_dir_guard = TempDir::new();
+    let temp_dir = temp_dir_guard.path().to_string();
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      format!("--deny-read=.,{}", temp_dir),
+      "script.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        reload: true,
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string()
-        )),
         permissions: PermissionFlags {
-          allow_write: Some(vec![]),
+          deny_read: Some(vec![String::from("."), temp_dir]),
           ..Default::default()
         },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -6114,182 +11699,187 @@ mod tests {
   }
 
   #[test]
-  fn run_v8_flags() {
-    let r = flags_from_vec(svec!["deno", "run", "--v8-flags=--help"]);
+  fn allow_write_allowlist() {
+    use test_util::TempDir;
+    let temp_dir_guard = TempDir::new();
+    let temp_dir = temp_dir_guard.path().to_string();
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      format!("--allow-write=.,{}", temp_dir),
+      "script.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default("_".to_string())),
-        v8_flags: svec!["--help"],
+        permissions: PermissionFlags {
+          allow_write: Some(vec![String::from("."), temp_dir]),
+          ..Default::default()
+        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
+  }
+
+  #[test]
+  fn deny_write_denylist() {
+    use test_util::TempDir;
+    let temp_dir_guard = TempDir::new();
+    let temp_dir = temp_dir_guard.path().to_string();
 
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--v8-flags=--expose-gc,--gc-stats=1",
+      format!("--deny-write=.,{}", temp_dir),
       "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
+        permissions: PermissionFlags {
+          deny_write: Some(vec![String::from("."), temp_dir]),
+          ..Default::default()
+        },
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        v8_flags: svec!["--expose-gc", "--gc-stats=1"],
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
-
-    let r = flags_from_vec(svec!["deno", "run", "--v8-flags=--expose-gc"]);
-    assert!(r.is_err());
   }
 
   #[test]
-  fn serve_flags() {
-    let r = flags_from_vec(svec!["deno", "serve", "main.ts"]);
+  fn allow_net_allowlist() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-net=127.0.0.1",
+      "script.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Serve(ServeFlags::new_default(
-          "main.ts".to_string(),
-          8000,
-          "0.0.0.0"
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
         )),
         permissions: PermissionFlags {
-          allow_net: Some(vec![
-            "0.0.0.0:8000".to_string(),
-            "127.0.0.1:8000".to_string(),
-            "localhost:8000".to_string()
-          ]),
+          allow_net: Some(svec!["127.0.0.1"]),
           ..Default::default()
         },
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
-    let r = flags_from_vec(svec!["deno", "serve", "--port", "5000", "main.ts"]);
+  }
+
+  #[test]
+  fn deny_net_denylist() {
+    let r = flags_from_vec(svec!["deno", "--deny-net=127.0.0.1", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Serve(ServeFlags::new_default(
-          "main.ts".to_string(),
-          5000,
-          "0.0.0.0"
-        )),
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: None,
+          bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
         permissions: PermissionFlags {
-          allow_net: Some(vec![
-            "0.0.0.0:5000".to_string(),
-            "127.0.0.1:5000".to_string(),
-            "localhost:5000".to_string()
-          ]),
+          deny_net: Some(svec!["127.0.0.1"]),
           ..Default::default()
         },
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
-    let r = flags_from_vec(svec![
-      "deno",
-      "serve",
-      "--port",
-      "5000",
-      "--allow-net=example.com",
-      "main.ts"
-    ]);
+  }
+
+  #[test]
+  fn allow_env_allowlist() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--allow-env=HOME", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Serve(ServeFlags::new_default(
-          "main.ts".to_string(),
-          5000,
-          "0.0.0.0"
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
         )),
         permissions: PermissionFlags {
-          allow_net: Some(vec![
-            "example.com".to_string(),
-            "0.0.0.0:5000".to_string(),
-            "127.0.0.1:5000".to_string(),
-            "localhost:5000".to_string()
-          ]),
+          allow_env: Some(svec!["HOME"]),
           ..Default::default()
         },
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
-    let r = flags_from_vec(svec![
-      "deno",
-      "serve",
-      "--port",
-      "5000",
-      "--allow-net",
-      "main.ts"
-    ]);
+  }
+
+  #[test]
+  fn deny_env_denylist() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--deny-env=HOME", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Serve(ServeFlags::new_default(
-          "main.ts".to_string(),
-          5000,
-          "0.0.0.0"
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
         )),
         permissions: PermissionFlags {
-          allow_net: Some(vec![]),
+          deny_env: Some(svec!["HOME"]),
           ..Default::default()
         },
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
+  }
+
+  #[test]
+  fn allow_env_allowlist_multiple() {
     let r = flags_from_vec(svec![
       "deno",
-      "serve",
-      "--port",
-      "5000",
-      "--host",
-      "example.com",
-      "main.ts"
+      "run",
+      "--allow-env=HOME,PATH",
+      "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Serve(ServeFlags::new_default(
-          "main.ts".to_string(),
-          5000,
-          "example.com"
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
         )),
         permissions: PermissionFlags {
-          allow_net: Some(vec!["example.com:5000".to_owned()]),
+          allow_env: Some(svec!["HOME", "PATH"]),
           ..Default::default()
         },
         code_cache_enabled: true,
         ..Flags::default()
       }
-    );
-
-    let r = flags_from_vec(svec![
-      "deno",
-      "serve",
-      "--port",
-      "0",
-      "--host",
-      "example.com",
-      "main.ts"
-    ]);
+    );
+  }
+
+  #[test]
+  fn deny_env_denylist_multiple() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--deny-env=HOME,PATH", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Serve(ServeFlags::new_default(
-          "main.ts".to_string(),
-          0,
-          "example.com"
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
         )),
         permissions: PermissionFlags {
-          allow_net: Some(vec!["example.com:0".to_owned()]),
+          deny_env: Some(svec!["HOME", "PATH"]),
           ..Default::default()
         },
         code_cache_enabled: true,
@@ -6299,48 +11889,40 @@ mod tests {
   }
 
   #[test]
-  fn has_permission() {
-    let r = flags_from_vec(svec!["deno", "--allow-read", "x.ts"]);
-    assert_eq!(r.unwrap().has_permission(), true);
-
-    let r = flags_from_vec(svec!["deno", "run", "--deny-read", "x.ts"]);
-    assert_eq!(r.unwrap().has_permission(), true);
-
-    let r = flags_from_vec(svec!["deno", "run", "x.ts"]);
-    assert_eq!(r.unwrap().has_permission(), false);
+  fn allow_env_allowlist_validator() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--allow-env=HOME", "script.ts"]);
+    assert!(r.is_ok());
+    let r = flags_from_vec(svec!["deno", "--allow-env=H=ME", "script.ts"]);
+    assert!(r.is_err());
+    let r =
+      flags_from_vec(svec!["deno", "run", "--allow-env=H\0ME", "script.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn has_permission_in_argv() {
-    let r = flags_from_vec(svec!["deno", "run", "x.ts", "--allow-read"]);
-    assert_eq!(r.unwrap().has_permission_in_argv(), true);
-
-    let r = flags_from_vec(svec!["deno", "x.ts", "--deny-read"]);
-    assert_eq!(r.unwrap().has_permission_in_argv(), true);
-
-    let r = flags_from_vec(svec!["deno", "run", "x.ts"]);
-    assert_eq!(r.unwrap().has_permission_in_argv(), false);
+  fn deny_env_denylist_validator() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--deny-env=HOME", "script.ts"]);
+    assert!(r.is_ok());
+    let r =
+      flags_from_vec(svec!["deno", "run", "--deny-env=H=ME", "script.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "--deny-env=H\0ME", "script.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn script_args() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--allow-net",
-      "gist.ts",
-      "--title",
-      "X"
-    ]);
+  fn allow_sys() {
+    let r = flags_from_vec(svec!["deno", "run", "--allow-sys", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "gist.ts".to_string()
+          "script.ts".to_string(),
         )),
-        argv: svec!["--title", "X"],
         permissions: PermissionFlags {
-          allow_net: Some(vec![]),
+          allow_sys: Some(vec![]),
           ..Default::default()
         },
         code_cache_enabled: true,
@@ -6350,16 +11932,16 @@ mod tests {
   }
 
   #[test]
-  fn allow_all() {
-    let r = flags_from_vec(svec!["deno", "run", "--allow-all", "gist.ts"]);
+  fn deny_sys() {
+    let r = flags_from_vec(svec!["deno", "run", "--deny-sys", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "gist.ts".to_string()
+          "script.ts".to_string(),
         )),
         permissions: PermissionFlags {
-          allow_all: true,
+          deny_sys: Some(vec![]),
           ..Default::default()
         },
         code_cache_enabled: true,
@@ -6369,16 +11951,17 @@ mod tests {
   }
 
   #[test]
-  fn allow_read() {
-    let r = flags_from_vec(svec!["deno", "run", "--allow-read", "gist.ts"]);
+  fn allow_sys_allowlist() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--allow-sys=hostname", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "gist.ts".to_string()
+          "script.ts".to_string(),
         )),
         permissions: PermissionFlags {
-          allow_read: Some(vec![]),
+          allow_sys: Some(svec!["hostname"]),
           ..Default::default()
         },
         code_cache_enabled: true,
@@ -6388,21 +11971,47 @@ mod tests {
   }
 
   #[test]
-  fn short_permission_flags() {
-    let r = flags_from_vec(svec!["deno", "run", "-RNESWI", "gist.ts"]);
+  fn deny_sys_denylist() {
+    let r = flags_from_vec(svec!["deno", "--deny-sys=hostname", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: None,
+          bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
+        }),
+        permissions: PermissionFlags {
+          deny_sys: Some(svec!["hostname"]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn allow_sys_allowlist_multiple() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-sys=hostname,osRelease",
+      "script.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "gist.ts".to_string()
+          "script.ts".to_string(),
         )),
         permissions: PermissionFlags {
-          allow_read: Some(vec![]),
-          allow_write: Some(vec![]),
-          allow_env: Some(vec![]),
-          allow_import: Some(vec![]),
-          allow_net: Some(vec![]),
-          allow_sys: Some(vec![]),
+          allow_sys: Some(svec!["hostname", "osRelease"]),
           ..Default::default()
         },
         code_cache_enabled: true,
@@ -6412,18 +12021,21 @@ mod tests {
   }
 
   #[test]
-  fn deny_read() {
-    let r = flags_from_vec(svec!["deno", "--deny-read", "gist.ts"]);
+  fn deny_sys_denylist_multiple() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--deny-sys=hostname,osRelease",
+      "script.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "gist.ts".to_string(),
-          watch: None,
-          bare: true,
-        }),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
         permissions: PermissionFlags {
-          deny_read: Some(vec![]),
+          deny_sys: Some(svec!["hostname", "osRelease"]),
           ..Default::default()
         },
         code_cache_enabled: true,
@@ -6433,18 +12045,123 @@ mod tests {
   }
 
   #[test]
-  fn double_hyphen() {
-    // notice that flags passed after double dash will not
-    // be parsed to Flags but instead forwarded to
-    // script args as Deno.args
+  fn allow_sys_allowlist_validator() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--allow-sys=hostname", "script.ts"]);
+    assert!(r.is_ok());
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-sys=hostname,osRelease",
+      "script.ts"
+    ]);
+    assert!(r.is_ok());
+    let r =
+      flags_from_vec(svec!["deno", "run", "--allow-sys=foo", "script.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-sys=hostname,foo",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn deny_sys_denylist_validator() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--deny-sys=hostname", "script.ts"]);
+    assert!(r.is_ok());
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--deny-sys=hostname,osRelease",
+      "script.ts"
+    ]);
+    assert!(r.is_ok());
+    let r = flags_from_vec(svec!["deno", "run", "--deny-sys=foo", "script.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--deny-sys=hostname,foo",
+      "script.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn reload_validator() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--reload=http://deno.land/",
+      "script.ts"
+    ]);
+    assert!(r.is_ok(), "should accept valid urls");
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--reload=http://deno.land/a,http://deno.land/b",
+      "script.ts"
+    ]);
+    assert!(r.is_ok(), "should accept accept multiple valid urls");
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--reload=./relativeurl/",
+      "script.ts"
+    ]);
+    assert!(r.is_err(), "Should reject relative urls that start with ./");
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--reload=relativeurl/",
+      "script.ts"
+    ]);
+    assert!(r.is_err(), "Should reject relative urls");
+
+    let r =
+      flags_from_vec(svec!["deno", "run", "--reload=/absolute", "script.ts"]);
+    assert!(r.is_err(), "Should reject absolute urls");
+
+    let r = flags_from_vec(svec!["deno", "--reload=/", "script.ts"]);
+    assert!(r.is_err(), "Should reject absolute root url");
+
+    let r = flags_from_vec(svec!["deno", "run", "--reload=", "script.ts"]);
+    assert!(r.is_err(), "Should reject when nothing is provided");
+
+    let r = flags_from_vec(svec!["deno", "run", "--reload=,", "script.ts"]);
+    assert!(r.is_err(), "Should reject when a single comma is provided");
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--reload=,http://deno.land/a",
+      "script.ts"
+    ]);
+    assert!(r.is_err(), "Should reject a leading comma");
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--reload=http://deno.land/a,",
+      "script.ts"
+    ]);
+    assert!(r.is_err(), "Should reject a trailing comma");
+  }
+
+  #[test]
+  fn run_import_map() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--allow-write",
-      "script.ts",
-      "--",
-      "-D",
-      "--allow-net"
+      "--import-map=import_map.json",
+      "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
@@ -6452,11 +12169,7 @@ mod tests {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        argv: svec!["--", "-D", "--allow-net"],
-        permissions: PermissionFlags {
-          allow_write: Some(vec![]),
-          ..Default::default()
-        },
+        import_map_path: Some("import_map.json".to_owned()),
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -6464,728 +12177,581 @@ mod tests {
   }
 
   #[test]
-  fn fmt() {
-    let r = flags_from_vec(svec!["deno", "fmt", "script_1.ts", "script_2.ts"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Fmt(FmtFlags {
-          check: false,
-          files: FileFlags {
-            include: vec!["script_1.ts".to_string(), "script_2.ts".to_string()],
-            ignore: vec![],
-          },
-          use_tabs: None,
-          line_width: None,
-          indent_width: None,
-          single_quote: None,
-          prose_wrap: None,
-          no_semicolons: None,
-          unstable_component: false,
-          watch: Default::default(),
-        }),
-        ..Flags::default()
-      }
-    );
-
-    let r = flags_from_vec(svec!["deno", "fmt", "--check"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Fmt(FmtFlags {
-          check: true,
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          use_tabs: None,
-          line_width: None,
-          indent_width: None,
-          single_quote: None,
-          prose_wrap: None,
-          no_semicolons: None,
-          unstable_component: false,
-          watch: Default::default(),
-        }),
-        ..Flags::default()
-      }
-    );
-
-    let r = flags_from_vec(svec!["deno", "fmt"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Fmt(FmtFlags {
-          check: false,
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          use_tabs: None,
-          line_width: None,
-          indent_width: None,
-          single_quote: None,
-          prose_wrap: None,
-          no_semicolons: None,
-          unstable_component: false,
-          watch: Default::default(),
-        }),
-        ..Flags::default()
-      }
-    );
-
-    let r = flags_from_vec(svec!["deno", "fmt", "--watch"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Fmt(FmtFlags {
-          check: false,
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          use_tabs: None,
-          line_width: None,
-          indent_width: None,
-          single_quote: None,
-          prose_wrap: None,
-          no_semicolons: None,
-          unstable_component: false,
-          watch: Some(Default::default()),
-        }),
-        ..Flags::default()
-      }
-    );
-
+  fn run_multiple_import_maps() {
     let r = flags_from_vec(svec![
       "deno",
-      "fmt",
-      "--watch",
-      "--no-clear-screen",
-      "--unstable-css",
-      "--unstable-html",
-      "--unstable-component",
-      "--unstable-yaml"
+      "run",
+      "--import-map=shared.json",
+      "--import-map",
+      "project.json",
+      "--import-map=local.json",
+      "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Fmt(FmtFlags {
-          check: false,
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          use_tabs: None,
-          line_width: None,
-          indent_width: None,
-          single_quote: None,
-          prose_wrap: None,
-          no_semicolons: None,
-          unstable_component: true,
-          watch: Some(WatchFlags {
-            hmr: false,
-            no_clear_screen: true,
-            exclude: vec![],
-          })
-        }),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        import_map_path: Some("shared.json".to_owned()),
+        import_map_overrides: svec!["project.json", "local.json"],
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn info_import_map() {
     let r = flags_from_vec(svec![
       "deno",
-      "fmt",
-      "--check",
-      "--watch",
-      "foo.ts",
-      "--ignore=bar.js"
+      "info",
+      "--import-map=import_map.json",
+      "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Fmt(FmtFlags {
-          check: true,
-          files: FileFlags {
-            include: vec!["foo.ts".to_string()],
-            ignore: vec!["bar.js".to_string()],
-          },
-          use_tabs: None,
-          line_width: None,
-          indent_width: None,
-          single_quote: None,
-          prose_wrap: None,
-          no_semicolons: None,
-          unstable_component: false,
-          watch: Some(Default::default()),
-        }),
-        ..Flags::default()
-      }
-    );
-
-    let r = flags_from_vec(svec!["deno", "fmt", "--config", "deno.jsonc"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Fmt(FmtFlags {
-          check: false,
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          use_tabs: None,
-          line_width: None,
-          indent_width: None,
-          single_quote: None,
-          prose_wrap: None,
-          no_semicolons: None,
-          unstable_component: false,
-          watch: Default::default(),
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          file: Some("script.ts".to_string()),
+          json: false,
+          http_cache: false,
+          cache_usage: false,
+          graph: None,
+          graph_depth: None,
+          collapse_npm: false,
+          unused: false,
+          fix: false,
         }),
-        config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
+        import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn cache_import_map() {
     let r = flags_from_vec(svec![
       "deno",
-      "fmt",
-      "--config",
-      "deno.jsonc",
-      "--watch",
-      "foo.ts"
+      "cache",
+      "--import-map=import_map.json",
+      "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Fmt(FmtFlags {
-          check: false,
-          files: FileFlags {
-            include: vec!["foo.ts".to_string()],
-            ignore: vec![],
-          },
-          use_tabs: None,
-          line_width: None,
-          indent_width: None,
-          single_quote: None,
-          prose_wrap: None,
-          no_semicolons: None,
-          unstable_component: false,
-          watch: Some(Default::default()),
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts"],
+          dry_run: false,
+          json: false,
         }),
-        config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
+        import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn doc_import_map() {
     let r = flags_from_vec(svec![
       "deno",
-      "fmt",
-      "--use-tabs",
-      "--line-width",
-      "60",
-      "--indent-width",
-      "4",
-      "--single-quote",
-      "--prose-wrap",
-      "never",
-      "--no-semicolons",
-    ]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Fmt(FmtFlags {
-          check: false,
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          use_tabs: Some(true),
-          line_width: Some(NonZeroU32::new(60).unwrap()),
-          indent_width: Some(NonZeroU8::new(4).unwrap()),
-          single_quote: Some(true),
-          prose_wrap: Some("never".to_string()),
-          no_semicolons: Some(true),
-          unstable_component: false,
-          watch: Default::default(),
+      "doc",
+      "--import-map=import_map.json",
+      "script.ts"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          source_files: DocSourceFileFlag::Paths(vec!["script.ts".to_owned()]),
+          private: false,
+          json: false,
+          html: None,
+          lint: false,
+          filter: None,
+          only_deprecated: false,
+          min_since: None,
+          openapi: false,
         }),
+        import_map_path: Some("import_map.json".to_owned()),
         ..Flags::default()
       }
     );
+  }
 
-    // try providing =false to the booleans
-    let r = flags_from_vec(svec![
-      "deno",
-      "fmt",
-      "--use-tabs=false",
-      "--single-quote=false",
-      "--no-semicolons=false",
-    ]);
+  #[test]
+  fn run_env_default() {
+    let r = flags_from_vec(svec!["deno", "run", "--env", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Fmt(FmtFlags {
-          check: false,
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          use_tabs: Some(false),
-          line_width: None,
-          indent_width: None,
-          single_quote: Some(false),
-          prose_wrap: None,
-          no_semicolons: Some(false),
-          unstable_component: false,
-          watch: Default::default(),
-        }),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        env_file: Some(vec![".env".to_owned()]),
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn lint() {
-    let r = flags_from_vec(svec!["deno", "lint", "script_1.ts", "script_2.ts"]);
+  fn run_env_file_default() {
+    let r = flags_from_vec(svec!["deno", "run", "--env-file", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Lint(LintFlags {
-          files: FileFlags {
-            include: vec!["script_1.ts".to_string(), "script_2.ts".to_string(),],
-            ignore: vec![],
-          },
-          fix: false,
-          rules: false,
-          maybe_rules_tags: None,
-          maybe_rules_include: None,
-          maybe_rules_exclude: None,
-          json: false,
-          compact: false,
-          watch: Default::default(),
-        }),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        env_file: Some(vec![".env".to_owned()]),
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "lint",
-      "--watch",
-      "script_1.ts",
-      "script_2.ts"
-    ]);
+  #[test]
+  fn run_no_code_cache() {
+    let r = flags_from_vec(svec!["deno", "--no-code-cache", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Lint(LintFlags {
-          files: FileFlags {
-            include: vec!["script_1.ts".to_string(), "script_2.ts".to_string()],
-            ignore: vec![],
-          },
-          fix: false,
-          rules: false,
-          maybe_rules_tags: None,
-          maybe_rules_include: None,
-          maybe_rules_exclude: None,
-          json: false,
-          compact: false,
-          watch: Some(Default::default()),
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: None,
+          bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
         }),
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "lint",
-      "--watch",
-      "--no-clear-screen",
-      "script_1.ts",
-      "script_2.ts"
-    ]);
+  #[test]
+  fn run_env_defined() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--env=.another_env", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Lint(LintFlags {
-          files: FileFlags {
-            include: vec!["script_1.ts".to_string(), "script_2.ts".to_string()],
-            ignore: vec![],
-          },
-          fix: false,
-          rules: false,
-          maybe_rules_tags: None,
-          maybe_rules_include: None,
-          maybe_rules_exclude: None,
-          json: false,
-          compact: false,
-          watch: Some(WatchFlags {
-            hmr: false,
-            no_clear_screen: true,
-            exclude: vec![],
-          }),
-        }),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        env_file: Some(vec![".another_env".to_owned()]),
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn run_env_file_defined() {
     let r = flags_from_vec(svec![
       "deno",
-      "lint",
-      "--fix",
-      "--ignore=script_1.ts,script_2.ts"
+      "run",
+      "--env-file=.another_env",
+      "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Lint(LintFlags {
-          files: FileFlags {
-            include: vec![],
-            ignore: vec!["script_1.ts".to_string(), "script_2.ts".to_string()],
-          },
-          fix: true,
-          rules: false,
-          maybe_rules_tags: None,
-          maybe_rules_include: None,
-          maybe_rules_exclude: None,
-          json: false,
-          compact: false,
-          watch: Default::default(),
-        }),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        env_file: Some(vec![".another_env".to_owned()]),
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec!["deno", "lint", "--rules"]);
+  #[test]
+  fn run_env_file_multiple() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--env-file",
+      "--env-file=.env.local",
+      "script.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Lint(LintFlags {
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          fix: false,
-          rules: true,
-          maybe_rules_tags: None,
-          maybe_rules_include: None,
-          maybe_rules_exclude: None,
-          json: false,
-          compact: false,
-          watch: Default::default(),
-        }),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        env_file: Some(svec![".env", ".env.local"]),
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn run_redact_env() {
     let r = flags_from_vec(svec![
       "deno",
-      "lint",
-      "--rules",
-      "--rules-tags=recommended"
+      "run",
+      "--redact-env=API_TOKEN,DB_PASSWORD",
+      "--redact-env=NPM_TOKEN",
+      "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Lint(LintFlags {
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          fix: false,
-          rules: true,
-          maybe_rules_tags: Some(svec!["recommended"]),
-          maybe_rules_include: None,
-          maybe_rules_exclude: None,
-          json: false,
-          compact: false,
-          watch: Default::default(),
-        }),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        redact_env: svec!["API_TOKEN", "DB_PASSWORD", "NPM_TOKEN"],
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "lint",
-      "--rules-tags=",
-      "--rules-include=ban-untagged-todo,no-undef",
-      "--rules-exclude=no-const-assign"
-    ]);
+  #[test]
+  fn cache_multiple() {
+    let r =
+      flags_from_vec(svec!["deno", "cache", "script.ts", "script_two.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Lint(LintFlags {
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          fix: false,
-          rules: false,
-          maybe_rules_tags: Some(svec![""]),
-          maybe_rules_include: Some(svec!["ban-untagged-todo", "no-undef"]),
-          maybe_rules_exclude: Some(svec!["no-const-assign"]),
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts", "script_two.ts"],
+          dry_run: false,
           json: false,
-          compact: false,
-          watch: Default::default(),
         }),
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec!["deno", "lint", "--json", "script_1.ts"]);
+  #[test]
+  fn run_seed() {
+    let r = flags_from_vec(svec!["deno", "run", "--seed", "250", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Lint(LintFlags {
-          files: FileFlags {
-            include: vec!["script_1.ts".to_string()],
-            ignore: vec![],
-          },
-          fix: false,
-          rules: false,
-          maybe_rules_tags: None,
-          maybe_rules_include: None,
-          maybe_rules_exclude: None,
-          json: true,
-          compact: false,
-          watch: Default::default(),
-        }),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        seed: Some(250_u64),
+        v8_flags: svec!["--random-seed=250"],
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn run_seed_with_v8_flags() {
     let r = flags_from_vec(svec![
       "deno",
-      "lint",
-      "--config",
-      "Deno.jsonc",
-      "--json",
-      "script_1.ts"
+      "run",
+      "--seed",
+      "250",
+      "--v8-flags=--expose-gc",
+      "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Lint(LintFlags {
-          files: FileFlags {
-            include: vec!["script_1.ts".to_string()],
-            ignore: vec![],
-          },
-          fix: false,
-          rules: false,
-          maybe_rules_tags: None,
-          maybe_rules_include: None,
-          maybe_rules_exclude: None,
-          json: true,
-          compact: false,
-          watch: Default::default(),
-        }),
-        config_flag: ConfigFlag::Path("Deno.jsonc".to_string()),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        seed: Some(250_u64),
+        v8_flags: svec!["--expose-gc", "--random-seed=250"],
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn install() {
     let r = flags_from_vec(svec![
       "deno",
-      "lint",
-      "--config",
-      "Deno.jsonc",
-      "--compact",
-      "script_1.ts"
+      "install",
+      "-g",
+      "jsr:@std/http/file-server"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Lint(LintFlags {
-          files: FileFlags {
-            include: vec!["script_1.ts".to_string()],
-            ignore: vec![],
-          },
-          fix: false,
-          rules: false,
-          maybe_rules_tags: None,
-          maybe_rules_include: None,
-          maybe_rules_exclude: None,
-          json: false,
-          compact: true,
-          watch: Default::default(),
+        subcommand: DenoSubcommand::Install(InstallFlags {
+          kind: InstallKind::Global(InstallFlagsGlobal {
+            name: None,
+            module_url: "jsr:@std/http/file-server".to_string(),
+            args: vec![],
+            root: None,
+            service: false,
+            force: false,
+            env_allowlist: None,
+            cwd: None,
+            service: false,
+          }),
         }),
-        config_flag: ConfigFlag::Path("Deno.jsonc".to_string()),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn types() {
-    let r = flags_from_vec(svec!["deno", "types"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "install",
+      "-g",
+      "jsr:@std/http/file-server"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Types,
+        subcommand: DenoSubcommand::Install(InstallFlags {
+          kind: InstallKind::Global(InstallFlagsGlobal {
+            name: None,
+            module_url: "jsr:@std/http/file-server".to_string(),
+            args: vec![],
+            root: None,
+            force: false,
+            env_allowlist: None,
+            cwd: None,
+            service: false,
+          }),
+        }),
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn cache() {
-    let r = flags_from_vec(svec!["deno", "cache", "script.ts"]);
+  fn install_with_flags() {
+    #[rustfmt::skip]
+    let r = flags_from_vec(svec!["deno", "install", "--global", "--import-map", "import_map.json", "--no-remote", "--config", "tsconfig.json", "--no-check", "--unsafely-ignore-certificate-errors", "--reload", "--lock", "lock.json", "--cert", "example.crt", "--cached-only", "--allow-read", "--allow-net", "--v8-flags=--help", "--seed", "1", "--inspect=127.0.0.1:9229", "--name", "file_server", "--root", "/foo", "--force", "--env=.example.env", "jsr:@std/http/file-server", "foo", "bar"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Cache(CacheFlags {
-          files: svec!["script.ts"],
+        subcommand: DenoSubcommand::Install(InstallFlags {
+          kind: InstallKind::Global(InstallFlagsGlobal {
+            name: Some("file_server".to_string()),
+            module_url: "jsr:@std/http/file-server".to_string(),
+            args: svec!["foo", "bar"],
+            root: Some("/foo".to_string()),
+            force: true,
+            env_allowlist: None,
+            cwd: None,
+            service: false,
+          }),
         }),
+        import_map_path: Some("import_map.json".to_string()),
+        no_remote: true,
+        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
+        type_check_mode: TypeCheckMode::None,
+        reload: true,
+        lock: Some(String::from("lock.json")),
+        ca_data: Some(CaData::File("example.crt".to_string())),
+        cached_only: true,
+        v8_flags: svec!["--help", "--random-seed=1"],
+        seed: Some(1),
+        inspect: Some("127.0.0.1:9229".parse().unwrap()),
+        unsafely_ignore_certificate_errors: Some(vec![]),
+        permissions: PermissionFlags {
+          allow_net: Some(vec![]),
+          allow_read: Some(vec![]),
+          ..Default::default()
+        },
+        env_file: Some(vec![".example.env".to_owned()]),
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn check() {
-    let r = flags_from_vec(svec!["deno", "check", "script.ts"]);
+  fn install_production() {
+    let r = flags_from_vec(svec!["deno", "install", "--production"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Check(CheckFlags {
-          files: svec!["script.ts"],
-          doc: false,
-          doc_only: false,
+        subcommand: DenoSubcommand::Install(InstallFlags {
+          kind: InstallKind::Local(InstallFlagsLocal::TopLevel),
         }),
-        type_check_mode: TypeCheckMode::Local,
+        production: true,
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "check", "--doc", "script.ts"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Check(CheckFlags {
-          files: svec!["script.ts"],
-          doc: true,
-          doc_only: false,
-        }),
-        type_check_mode: TypeCheckMode::Local,
-        ..Flags::default()
-      }
-    );
+    let r =
+      flags_from_vec(svec!["deno", "install", "--production", "npm:chalk"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "install",
+      "-g",
+      "--production",
+      "jsr:@std/http/file-server"
+    ]);
+    assert!(r.is_err());
+  }
 
-    let r = flags_from_vec(svec!["deno", "check", "--doc-only", "markdown.md"]);
+  #[test]
+  fn install_with_env_allow_and_cwd() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "install",
+      "-g",
+      "--env-allow=GITHUB_TOKEN,NO_COLOR",
+      "--cwd",
+      "/tmp/tool",
+      "jsr:@std/http/file-server"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Check(CheckFlags {
-          files: svec!["markdown.md"],
-          doc: false,
-          doc_only: true,
+        subcommand: DenoSubcommand::Install(InstallFlags {
+          kind: InstallKind::Global(InstallFlagsGlobal {
+            name: None,
+            module_url: "jsr:@std/http/file-server".to_string(),
+            args: vec![],
+            root: None,
+            force: false,
+            env_allowlist: Some(svec!["GITHUB_TOKEN", "NO_COLOR"]),
+            cwd: Some("/tmp/tool".to_string()),
+            service: false,
+          }),
         }),
-        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
 
-    // `--doc` and `--doc-only` are mutually exclusive
     let r = flags_from_vec(svec![
       "deno",
-      "check",
-      "--doc",
-      "--doc-only",
-      "script.ts"
+      "install",
+      "-g",
+      "--env-allow",
+      "jsr:@std/http/file-server"
     ]);
     assert_eq!(
-      r.unwrap_err().kind(),
-      clap::error::ErrorKind::ArgumentConflict
+      r.unwrap().subcommand,
+      DenoSubcommand::Install(InstallFlags {
+        kind: InstallKind::Global(InstallFlagsGlobal {
+          name: None,
+          module_url: "jsr:@std/http/file-server".to_string(),
+          args: vec![],
+          root: None,
+          force: false,
+          env_allowlist: Some(vec![]),
+          cwd: None,
+          service: false,
+        }),
+      })
     );
 
-    for all_flag in ["--remote", "--all"] {
-      let r = flags_from_vec(svec!["deno", "check", all_flag, "script.ts"]);
-      assert_eq!(
-        r.unwrap(),
-        Flags {
-          subcommand: DenoSubcommand::Check(CheckFlags {
-            files: svec!["script.ts"],
-            doc: false,
-            doc_only: false,
-          }),
-          type_check_mode: TypeCheckMode::All,
-          ..Flags::default()
-        }
-      );
+    let r = flags_from_vec(svec![
+      "deno",
+      "install",
+      "-g",
+      "--env-allow=FOO;rm",
+      "jsr:@std/http/file-server"
+    ]);
+    assert!(r.is_err());
 
-      let r = flags_from_vec(svec![
-        "deno",
-        "check",
-        all_flag,
-        "--no-remote",
-        "script.ts"
-      ]);
-      assert_eq!(
-        r.unwrap_err().kind(),
-        clap::error::ErrorKind::ArgumentConflict
-      );
-    }
+    let r = flags_from_vec(svec!["deno", "install", "--env-allow=FOO"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn info() {
-    let r = flags_from_vec(svec!["deno", "info", "script.ts"]);
+  fn install_and_uninstall_service() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "install",
+      "-g",
+      "--allow-net",
+      "--service",
+      "-n",
+      "serve",
+      "jsr:@std/http/file-server"
+    ]);
     assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
-          json: false,
-          file: Some("script.ts".to_string()),
+      r.unwrap().subcommand,
+      DenoSubcommand::Install(InstallFlags {
+        kind: InstallKind::Global(InstallFlagsGlobal {
+          name: Some("serve".to_string()),
+          module_url: "jsr:@std/http/file-server".to_string(),
+          args: vec![],
+          root: None,
+          force: false,
+          env_allowlist: None,
+          cwd: None,
+          service: true,
         }),
-        ..Flags::default()
-      }
+      })
     );
 
-    let r = flags_from_vec(svec!["deno", "info", "--reload", "script.ts"]);
+    let r =
+      flags_from_vec(svec!["deno", "uninstall", "-g", "--service", "serve"]);
     assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
-          json: false,
-          file: Some("script.ts".to_string()),
+      r.unwrap().subcommand,
+      DenoSubcommand::Uninstall(UninstallFlags {
+        kind: UninstallKind::Global(UninstallFlagsGlobal {
+          name: "serve".to_string(),
+          root: None,
+          service: true,
         }),
-        reload: true,
-        ..Flags::default()
-      }
+      })
     );
 
-    let r = flags_from_vec(svec!["deno", "info", "--json", "script.ts"]);
+    let r = flags_from_vec(svec!["deno", "install", "--service", "main.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "uninstall", "--service", "serve"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn uninstall() {
+    let r = flags_from_vec(svec!["deno", "uninstall"]);
+    assert!(r.is_err(),);
+
+    let r = flags_from_vec(svec!["deno", "uninstall", "@std/load"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
-          json: true,
-          file: Some("script.ts".to_string()),
+        subcommand: DenoSubcommand::Uninstall(UninstallFlags {
+          kind: UninstallKind::Local(RemoveFlags {
+            packages: vec!["@std/load".to_string()],
+            target: WorkspaceMemberTarget::Current,
+          }),
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "info"]);
+    let r =
+      flags_from_vec(svec!["deno", "uninstall", "file_server", "@std/load"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
-          json: false,
-          file: None
+        subcommand: DenoSubcommand::Uninstall(UninstallFlags {
+          kind: UninstallKind::Local(RemoveFlags {
+            packages: vec!["file_server".to_string(), "@std/load".to_string()],
+            target: WorkspaceMemberTarget::Current,
+          }),
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "info", "--json"]);
+    let r = flags_from_vec(svec!["deno", "uninstall", "-g", "file_server"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
-          json: true,
-          file: None
+        subcommand: DenoSubcommand::Uninstall(UninstallFlags {
+          kind: UninstallKind::Global(UninstallFlagsGlobal {
+            name: "file_server".to_string(),
+            root: None,
+            service: false,
+          }),
         }),
         ..Flags::default()
       }
@@ -7193,38 +12759,44 @@ mod tests {
 
     let r = flags_from_vec(svec![
       "deno",
-      "info",
-      "--no-npm",
-      "--no-remote",
-      "--config",
-      "tsconfig.json"
+      "uninstall",
+      "-g",
+      "--root",
+      "/user/foo/bar",
+      "file_server"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
-          json: false,
-          file: None
+        subcommand: DenoSubcommand::Uninstall(UninstallFlags {
+          kind: UninstallKind::Global(UninstallFlagsGlobal {
+            name: "file_server".to_string(),
+            root: Some("/user/foo/bar".to_string()),
+            service: false,
+          }),
         }),
-        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
-        no_npm: true,
-        no_remote: true,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn tsconfig() {
+  fn uninstall_with_help_flag() {
+    let r = flags_from_vec(svec!["deno", "uninstall", "--help"]);
+    assert!(r.is_ok());
+  }
+
+  #[test]
+  fn log_level() {
     let r =
-      flags_from_vec(svec!["deno", "run", "-c", "tsconfig.json", "script.ts"]);
+      flags_from_vec(svec!["deno", "run", "--log-level=debug", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
+        log_level: Some(Level::Debug),
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -7232,234 +12804,191 @@ mod tests {
   }
 
   #[test]
-  fn eval() {
-    let r = flags_from_vec(svec!["deno", "eval", "'console.log(\"hello\")'"]);
+  fn quiet() {
+    let r = flags_from_vec(svec!["deno", "-q", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Eval(EvalFlags {
-          print: false,
-          code: "'console.log(\"hello\")'".to_string(),
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: None,
+          bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
         }),
-        permissions: PermissionFlags {
-          allow_all: true,
-          ..Default::default()
-        },
+        log_level: Some(Level::Error),
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn eval_p() {
-    let r = flags_from_vec(svec!["deno", "eval", "-p", "1+2"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Eval(EvalFlags {
-          print: true,
-          code: "1+2".to_string(),
-        }),
-        permissions: PermissionFlags {
-          allow_all: true,
-          ..Default::default()
-        },
-        ..Flags::default()
+  fn completions() {
+    let r = flags_from_vec(svec!["deno", "completions", "zsh"]).unwrap();
+
+    match r.subcommand {
+      DenoSubcommand::Completions(CompletionsFlags { buf }) => {
+        assert!(!buf.is_empty())
       }
-    );
+      _ => unreachable!(),
+    }
   }
 
   #[test]
-  fn eval_typescript() {
+  fn run_with_args() {
     let r = flags_from_vec(svec![
       "deno",
-      "eval",
-      "--ext=ts",
-      "'console.log(\"hello\")'"
+      "run",
+      "script.ts",
+      "--allow-read",
+      "--allow-net"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Eval(EvalFlags {
-          print: false,
-          code: "'console.log(\"hello\")'".to_string(),
-        }),
-        permissions: PermissionFlags {
-          allow_all: true,
-          ..Default::default()
-        },
-        ext: Some("ts".to_string()),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        argv: svec!["--allow-read", "--allow-net"],
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
-
-  #[test]
-  fn eval_with_flags() {
-    #[rustfmt::skip]
-    let r = flags_from_vec(svec!["deno", "eval", "--import-map", "import_map.json", "--no-remote", "--config", "tsconfig.json", "--no-check", "--reload", "--lock", "lock.json", "--cert", "example.crt", "--cached-only", "--location", "https:foo", "--v8-flags=--help", "--seed", "1", "--inspect=127.0.0.1:9229", "--env=.example.env", "42"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--location",
+      "https:foo",
+      "--allow-read",
+      "script.ts",
+      "--allow-net",
+      "-r",
+      "--help",
+      "--foo",
+      "bar"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Eval(EvalFlags {
-          print: false,
-          code: "42".to_string(),
-        }),
-        import_map_path: Some("import_map.json".to_string()),
-        no_remote: true,
-        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
-        type_check_mode: TypeCheckMode::None,
-        reload: true,
-        lock: Some(String::from("lock.json")),
-        ca_data: Some(CaData::File("example.crt".to_string())),
-        cached_only: true,
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
         location: Some(Url::parse("https://foo/").unwrap()),
-        v8_flags: svec!["--help", "--random-seed=1"],
-        seed: Some(1),
-        inspect: Some("127.0.0.1:9229".parse().unwrap()),
         permissions: PermissionFlags {
-          allow_all: true,
+          allow_read: Some(vec![]),
           ..Default::default()
         },
-        env_file: Some(".example.env".to_owned()),
+        argv: svec!["--allow-net", "-r", "--help", "--foo", "bar"],
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn eval_args() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "eval",
-      "console.log(Deno.args)",
-      "arg1",
-      "arg2"
-    ]);
+    let r = flags_from_vec(svec!["deno", "run", "script.ts", "foo", "bar"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Eval(EvalFlags {
-          print: false,
-          code: "console.log(Deno.args)".to_string(),
-        }),
-        argv: svec!["arg1", "arg2"],
-        permissions: PermissionFlags {
-          allow_all: true,
-          ..Default::default()
-        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        argv: svec!["foo", "bar"],
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
-
-  #[test]
-  fn repl() {
-    let r = flags_from_vec(svec!["deno"]);
+    let r = flags_from_vec(svec!["deno", "run", "script.ts", "-"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Repl(ReplFlags {
-          eval_files: None,
-          eval: None,
-          is_default_command: true,
-        }),
-        unsafely_ignore_certificate_errors: None,
-        permissions: PermissionFlags {
-          allow_all: true,
-          ..Default::default()
-        },
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        argv: svec!["-"],
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn repl_strace_ops() {
-    // Lightly test this undocumented flag
-    let r = flags_from_vec(svec!["deno", "repl", "--strace-ops"]);
-    assert_eq!(r.unwrap().strace_ops, Some(vec![]));
     let r =
-      flags_from_vec(svec!["deno", "repl", "--strace-ops=http,websocket"]);
-    assert_eq!(
-      r.unwrap().strace_ops,
-      Some(vec!["http".to_string(), "websocket".to_string()])
-    );
-  }
-
-  #[test]
-  fn repl_with_flags() {
-    #[rustfmt::skip]
-    let r = flags_from_vec(svec!["deno", "repl", "-A", "--import-map", "import_map.json", "--no-remote", "--config", "tsconfig.json", "--no-check", "--reload", "--lock", "lock.json", "--cert", "example.crt", "--cached-only", "--location", "https:foo", "--v8-flags=--help", "--seed", "1", "--inspect=127.0.0.1:9229", "--unsafely-ignore-certificate-errors", "--env=.example.env"]);
+      flags_from_vec(svec!["deno", "run", "script.ts", "-", "foo", "bar"]);
     assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Repl(ReplFlags {
-          eval_files: None,
-          eval: None,
-          is_default_command: false,
-        }),
-        import_map_path: Some("import_map.json".to_string()),
-        no_remote: true,
-        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
-        type_check_mode: TypeCheckMode::None,
-        reload: true,
-        lock: Some(String::from("lock.json")),
-        ca_data: Some(CaData::File("example.crt".to_string())),
-        cached_only: true,
-        location: Some(Url::parse("https://foo/").unwrap()),
-        v8_flags: svec!["--help", "--random-seed=1"],
-        seed: Some(1),
-        inspect: Some("127.0.0.1:9229".parse().unwrap()),
-        permissions: PermissionFlags {
-          allow_all: true,
-          ..Default::default()
-        },
-        env_file: Some(".example.env".to_owned()),
-        unsafely_ignore_certificate_errors: Some(vec![]),
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        argv: svec!["-", "foo", "bar"],
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn repl_with_eval_flag() {
-    #[rustfmt::skip]
-    let r = flags_from_vec(svec!["deno", "repl", "--allow-write", "--eval", "console.log('hello');"]);
+  fn no_check() {
+    let r = flags_from_vec(svec!["deno", "--no-check", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Repl(ReplFlags {
-          eval_files: None,
-          eval: Some("console.log('hello');".to_string()),
-          is_default_command: false,
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "script.ts".to_string(),
+          watch: None,
+          bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
         }),
-        permissions: PermissionFlags {
-          allow_write: Some(vec![]),
-          ..Default::default()
-        },
         type_check_mode: TypeCheckMode::None,
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn repl_with_eval_file_flag() {
-    #[rustfmt::skip]
-    let r = flags_from_vec(svec!["deno", "repl", "--eval-file=./a.js,./b.ts,https://examples.deno.land/hello-world.ts"]);
+  fn no_check_remote() {
+    let r =
+      flags_from_vec(svec!["deno", "run", "--no-check=remote", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        type_check_mode: TypeCheckMode::Local,
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn repl_with_unsafely_ignore_certificate_errors() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "repl",
+      "--eval",
+      "console.log('hello');",
+      "--unsafely-ignore-certificate-errors"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Repl(ReplFlags {
-          eval_files: Some(vec![
-            "./a.js".to_string(),
-            "./b.ts".to_string(),
-            "https://examples.deno.land/hello-world.ts".to_string()
-          ]),
-          eval: None,
+          eval_files: None,
+          eval: Some("console.log('hello');".to_string()),
           is_default_command: false,
+          save_session: None,
+          auto_import: false,
+          repl_server: None,
         }),
+        unsafely_ignore_certificate_errors: Some(vec![]),
         type_check_mode: TypeCheckMode::None,
         ..Flags::default()
       }
@@ -7467,27 +12996,20 @@ mod tests {
   }
 
   #[test]
-  fn allow_read_allowlist() {
-    use test_util::TempDir;
-    let temp_dir_guard = TempDir::new();
-    let temp_dir = temp_dir_guard.path().to_string();
-
+  fn run_with_unsafely_ignore_certificate_errors() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      format!("--allow-read=.,{}", temp_dir),
+      "--unsafely-ignore-certificate-errors",
       "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        permissions: PermissionFlags {
-          allow_read: Some(vec![String::from("."), temp_dir]),
-          ..Default::default()
-        },
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
+        unsafely_ignore_certificate_errors: Some(vec![]),
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -7495,27 +13017,27 @@ mod tests {
   }
 
   #[test]
-  fn deny_read_denylist() {
-    use test_util::TempDir;
-    let temp_dir_guard = TempDir::new();
-    let temp_dir = temp_dir_guard.path().to_string();
-
+  fn run_with_unsafely_treat_insecure_origin_as_secure_with_ipv6_address() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      format!("--deny-read=.,{}", temp_dir),
+      "--unsafely-ignore-certificate-errors=deno.land,localhost,[::],127.0.0.1,[::1],1.2.3.4",
       "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        permissions: PermissionFlags {
-          deny_read: Some(vec![String::from("."), temp_dir]),
-          ..Default::default()
-        },
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
+        unsafely_ignore_certificate_errors: Some(svec![
+          "deno.land",
+          "localhost",
+          "[::]",
+          "127.0.0.1",
+          "[::1]",
+          "1.2.3.4"
+        ]),
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -7523,27 +13045,46 @@ mod tests {
   }
 
   #[test]
-  fn allow_write_allowlist() {
-    use test_util::TempDir;
-    let temp_dir_guard = TempDir::new();
-    let temp_dir = temp_dir_guard.path().to_string();
-
+  fn repl_with_unsafely_treat_insecure_origin_as_secure_with_ipv6_address() {
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      format!("--allow-write=.,{}", temp_dir),
-      "script.ts"
-    ]);
+      "repl",
+      "--unsafely-ignore-certificate-errors=deno.land,localhost,[::],127.0.0.1,[::1],1.2.3.4"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Repl(ReplFlags {
+          eval_files: None,
+          eval: None,
+          is_default_command: false,
+          save_session: None,
+          auto_import: false,
+          repl_server: None,
+        }),
+        unsafely_ignore_certificate_errors: Some(svec![
+          "deno.land",
+          "localhost",
+          "[::]",
+          "127.0.0.1",
+          "[::1]",
+          "1.2.3.4"
+        ]),
+        type_check_mode: TypeCheckMode::None,
+        ..Flags::default()
+      }
+    );
+  }
+
+  #[test]
+  fn no_remote() {
+    let r = flags_from_vec(svec!["deno", "run", "--no-remote", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        permissions: PermissionFlags {
-          allow_write: Some(vec![String::from("."), temp_dir]),
-          ..Default::default()
-        },
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
+        no_remote: true,
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -7551,39 +13092,58 @@ mod tests {
   }
 
   #[test]
-  fn deny_write_denylist() {
-    use test_util::TempDir;
-    let temp_dir_guard = TempDir::new();
-    let temp_dir = temp_dir_guard.path().to_string();
+  fn no_npm() {
+    let r = flags_from_vec(svec!["deno", "run", "--no-npm", "script.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        no_npm: true,
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
+  }
 
+  #[test]
+  fn npmrc() {
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      format!("--deny-write=.,{}", temp_dir),
+      "cache",
+      "--npmrc",
+      "ci/.npmrc",
       "script.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        permissions: PermissionFlags {
-          deny_write: Some(vec![String::from("."), temp_dir]),
-          ..Default::default()
-        },
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts"],
+          dry_run: false,
+          json: false,
+        }),
+        npmrc: Some("ci/.npmrc".to_string()),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "info", "--npmrc=.npmrc"]);
+    assert_eq!(r.unwrap().npmrc, Some(".npmrc".to_string()));
+
+    let r = flags_from_vec(svec!["deno", "add", "--npmrc=.npmrc", "npm:chalk"]);
+    assert_eq!(r.unwrap().npmrc, Some(".npmrc".to_string()));
   }
 
   #[test]
-  fn allow_net_allowlist() {
+  fn node_conditions() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--allow-net=127.0.0.1",
+      "--conditions=development,react-server",
+      "--conditions",
+      "custom",
       "script.ts"
     ]);
     assert_eq!(
@@ -7592,10 +13152,7 @@ mod tests {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        permissions: PermissionFlags {
-          allow_net: Some(svec!["127.0.0.1"]),
-          ..Default::default()
-        },
+        node_conditions: svec!["development", "react-server", "custom"],
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -7603,8 +13160,8 @@ mod tests {
   }
 
   #[test]
-  fn deny_net_denylist() {
-    let r = flags_from_vec(svec!["deno", "--deny-net=127.0.0.1", "script.ts"]);
+  fn local_npm() {
+    let r = flags_from_vec(svec!["deno", "--node-modules-dir", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
@@ -7612,11 +13169,13 @@ mod tests {
           script: "script.ts".to_string(),
           watch: None,
           bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
         }),
-        permissions: PermissionFlags {
-          deny_net: Some(svec!["127.0.0.1"]),
-          ..Default::default()
-        },
+        node_modules_dir: Some(NodeModulesDirMode::Auto),
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -7624,39 +13183,28 @@ mod tests {
   }
 
   #[test]
-  fn allow_env_allowlist() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--allow-env=HOME", "script.ts"]);
+  fn vendor_flag() {
+    let r = flags_from_vec(svec!["deno", "run", "--vendor", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        permissions: PermissionFlags {
-          allow_env: Some(svec!["HOME"]),
-          ..Default::default()
-        },
+        vendor: Some(true),
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn deny_env_denylist() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--deny-env=HOME", "script.ts"]);
+    let r = flags_from_vec(svec!["deno", "run", "--vendor=false", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        permissions: PermissionFlags {
-          deny_env: Some(svec!["HOME"]),
-          ..Default::default()
-        },
+        vendor: Some(false),
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -7664,43 +13212,35 @@ mod tests {
   }
 
   #[test]
-  fn allow_env_allowlist_multiple() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--allow-env=HOME,PATH",
-      "script.ts"
-    ]);
+  fn vendor_npm_flag() {
+    let r = flags_from_vec(svec!["deno", "run", "--vendor-npm", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        permissions: PermissionFlags {
-          allow_env: Some(svec!["HOME", "PATH"]),
-          ..Default::default()
-        },
+        vendor_npm: Some(true),
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn deny_env_denylist_multiple() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--deny-env=HOME,PATH", "script.ts"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--vendor=false",
+      "--vendor-npm=false",
+      "script.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        permissions: PermissionFlags {
-          deny_env: Some(svec!["HOME", "PATH"]),
-          ..Default::default()
-        },
+        vendor: Some(false),
+        vendor_npm: Some(false),
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -7708,81 +13248,76 @@ mod tests {
   }
 
   #[test]
-  fn allow_env_allowlist_validator() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--allow-env=HOME", "script.ts"]);
-    assert!(r.is_ok());
-    let r = flags_from_vec(svec!["deno", "--allow-env=H=ME", "script.ts"]);
-    assert!(r.is_err());
+  fn allow_env_in_config() {
     let r =
-      flags_from_vec(svec!["deno", "run", "--allow-env=H\0ME", "script.ts"]);
-    assert!(r.is_err());
-  }
+      flags_from_vec(svec!["deno", "task", "--allow-env-in-config", "build"]);
+    let flags = r.unwrap();
+    assert!(flags.allow_env_in_config);
+    assert!(matches!(flags.subcommand, DenoSubcommand::Task(_)));
 
-  #[test]
-  fn deny_env_denylist_validator() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--deny-env=HOME", "script.ts"]);
-    assert!(r.is_ok());
-    let r =
-      flags_from_vec(svec!["deno", "run", "--deny-env=H=ME", "script.ts"]);
-    assert!(r.is_err());
-    let r = flags_from_vec(svec!["deno", "--deny-env=H\0ME", "script.ts"]);
-    assert!(r.is_err());
+    let r =
+      flags_from_vec(svec!["deno", "--allow-env-in-config", "run", "main.ts"]);
+    assert!(r.unwrap().allow_env_in_config);
+
+    let r = flags_from_vec(svec!["deno", "run", "main.ts"]);
+    assert!(!r.unwrap().allow_env_in_config);
   }
 
   #[test]
-  fn allow_sys() {
-    let r = flags_from_vec(svec!["deno", "run", "--allow-sys", "script.ts"]);
+  fn offline() {
+    let r = flags_from_vec(svec!["deno", "run", "--offline", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        permissions: PermissionFlags {
-          allow_sys: Some(vec![]),
-          ..Default::default()
-        },
+        offline: true,
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
+
+    // it's a global flag
+    let r = flags_from_vec(svec!["deno", "--offline", "cache", "script.ts"]);
+    assert!(r.unwrap().offline);
   }
 
   #[test]
-  fn deny_sys() {
-    let r = flags_from_vec(svec!["deno", "run", "--deny-sys", "script.ts"]);
+  fn progress() {
+    let r =
+      flags_from_vec(svec!["deno", "cache", "--progress=plain", "main.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        permissions: PermissionFlags {
-          deny_sys: Some(vec![]),
-          ..Default::default()
-        },
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["main.ts"],
+          dry_run: false,
+          json: false,
+        }),
+        progress: ProgressMode::Plain,
         ..Flags::default()
       }
     );
+
+    let r =
+      flags_from_vec(svec!["deno", "--progress=none", "check", "main.ts"]);
+    assert_eq!(r.unwrap().progress, ProgressMode::None);
+
+    let r = flags_from_vec(svec!["deno", "run", "--progress=bar", "main.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn allow_sys_allowlist() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--allow-sys=hostname", "script.ts"]);
+  fn cached_only() {
+    let r = flags_from_vec(svec!["deno", "run", "--cached-only", "script.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        permissions: PermissionFlags {
-          allow_sys: Some(svec!["hostname"]),
-          ..Default::default()
-        },
+        cached_only: true,
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -7790,18 +13325,29 @@ mod tests {
   }
 
   #[test]
-  fn deny_sys_denylist() {
-    let r = flags_from_vec(svec!["deno", "--deny-sys=hostname", "script.ts"]);
+  fn allow_net_allowlist_with_ports() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--allow-net=deno.land,:8000,:4545",
+      "script.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: None,
-          bare: true,
-        }),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
         permissions: PermissionFlags {
-          deny_sys: Some(svec!["hostname"]),
+          allow_net: Some(svec![
+            "deno.land",
+            "0.0.0.0:8000",
+            "127.0.0.1:8000",
+            "localhost:8000",
+            "0.0.0.0:4545",
+            "127.0.0.1:4545",
+            "localhost:4545"
+          ]),
           ..Default::default()
         },
         code_cache_enabled: true,
@@ -7811,11 +13357,11 @@ mod tests {
   }
 
   #[test]
-  fn allow_sys_allowlist_multiple() {
+  fn deny_net_denylist_with_ports() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--allow-sys=hostname,osRelease",
+      "--deny-net=deno.land,:8000,:4545",
       "script.ts"
     ]);
     assert_eq!(
@@ -7825,7 +13371,15 @@ mod tests {
           "script.ts".to_string(),
         )),
         permissions: PermissionFlags {
-          allow_sys: Some(svec!["hostname", "osRelease"]),
+          deny_net: Some(svec![
+            "deno.land",
+            "0.0.0.0:8000",
+            "127.0.0.1:8000",
+            "localhost:8000",
+            "0.0.0.0:4545",
+            "127.0.0.1:4545",
+            "localhost:4545"
+          ]),
           ..Default::default()
         },
         code_cache_enabled: true,
@@ -7835,11 +13389,11 @@ mod tests {
   }
 
   #[test]
-  fn deny_sys_denylist_multiple() {
+  fn allow_net_allowlist_with_ipv6_address() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--deny-sys=hostname,osRelease",
+      "--allow-net=deno.land,deno.land:80,[::],127.0.0.1,[::1],1.2.3.4:5678,:5678,[::1]:8080",
       "script.ts"
     ]);
     assert_eq!(
@@ -7849,7 +13403,18 @@ mod tests {
           "script.ts".to_string(),
         )),
         permissions: PermissionFlags {
-          deny_sys: Some(svec!["hostname", "osRelease"]),
+          allow_net: Some(svec![
+            "deno.land",
+            "deno.land:80",
+            "[::]",
+            "127.0.0.1",
+            "[::1]",
+            "1.2.3.4:5678",
+            "0.0.0.0:5678",
+            "127.0.0.1:5678",
+            "localhost:5678",
+            "[::1]:8080"
+          ]),
           ..Default::default()
         },
         code_cache_enabled: true,
@@ -7859,122 +13424,140 @@ mod tests {
   }
 
   #[test]
-  fn allow_sys_allowlist_validator() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--allow-sys=hostname", "script.ts"]);
-    assert!(r.is_ok());
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--allow-sys=hostname,osRelease",
-      "script.ts"
-    ]);
-    assert!(r.is_ok());
-    let r =
-      flags_from_vec(svec!["deno", "run", "--allow-sys=foo", "script.ts"]);
-    assert!(r.is_err());
+  fn deny_net_denylist_with_ipv6_address() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--allow-sys=hostname,foo",
+      "--deny-net=deno.land,deno.land:80,[::],127.0.0.1,[::1],1.2.3.4:5678,:5678,[::1]:8080",
       "script.ts"
     ]);
-    assert!(r.is_err());
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        permissions: PermissionFlags {
+          deny_net: Some(svec![
+            "deno.land",
+            "deno.land:80",
+            "[::]",
+            "127.0.0.1",
+            "[::1]",
+            "1.2.3.4:5678",
+            "0.0.0.0:5678",
+            "127.0.0.1:5678",
+            "localhost:5678",
+            "[::1]:8080"
+          ]),
+          ..Default::default()
+        },
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
   }
 
   #[test]
-  fn deny_sys_denylist_validator() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--deny-sys=hostname", "script.ts"]);
-    assert!(r.is_ok());
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--deny-sys=hostname,osRelease",
-      "script.ts"
-    ]);
-    assert!(r.is_ok());
-    let r = flags_from_vec(svec!["deno", "run", "--deny-sys=foo", "script.ts"]);
-    assert!(r.is_err());
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--deny-sys=hostname,foo",
-      "script.ts"
-    ]);
-    assert!(r.is_err());
+  fn test_no_colon_in_value_name() {
+    let app =
+      runtime_args(Command::new("test_inspect_completion_value"), true, true);
+    let inspect_args = app
+      .get_arguments()
+      .filter(|arg| arg.get_id() == "inspect")
+      .collect::<Vec<_>>();
+    // The value_name cannot have a : otherwise it breaks shell completions for zsh.
+    let value_name = "HOST_AND_PORT";
+    let arg = inspect_args
+      .iter()
+      .any(|v| v.get_value_names().unwrap() == [value_name]);
+
+    assert_eq!(arg, true);
   }
 
   #[test]
-  fn reload_validator() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--reload=http://deno.land/",
-      "script.ts"
-    ]);
-    assert!(r.is_ok(), "should accept valid urls");
-
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--reload=http://deno.land/a,http://deno.land/b",
-      "script.ts"
-    ]);
-    assert!(r.is_ok(), "should accept accept multiple valid urls");
-
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--reload=./relativeurl/",
-      "script.ts"
-    ]);
-    assert!(r.is_err(), "Should reject relative urls that start with ./");
-
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--reload=relativeurl/",
-      "script.ts"
-    ]);
-    assert!(r.is_err(), "Should reject relative urls");
-
-    let r =
-      flags_from_vec(svec!["deno", "run", "--reload=/absolute", "script.ts"]);
-    assert!(r.is_err(), "Should reject absolute urls");
-
-    let r = flags_from_vec(svec!["deno", "--reload=/", "script.ts"]);
-    assert!(r.is_err(), "Should reject absolute root url");
-
-    let r = flags_from_vec(svec!["deno", "run", "--reload=", "script.ts"]);
-    assert!(r.is_err(), "Should reject when nothing is provided");
-
-    let r = flags_from_vec(svec!["deno", "run", "--reload=,", "script.ts"]);
-    assert!(r.is_err(), "Should reject when a single comma is provided");
-
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--reload=,http://deno.land/a",
-      "script.ts"
-    ]);
-    assert!(r.is_err(), "Should reject a leading comma");
+  fn test_with_flags() {
+    #[rustfmt::skip]
+    let r = flags_from_vec(svec!["deno", "test", "--no-npm", "--no-remote", "--no-daemon", "--trace-leaks", "--no-run", "--filter", "- foo", "--coverage=cov", "--clean", "--location", "https:foo", "--allow-net", "--permit-no-files", "dir1/", "dir2/", "--", "arg1", "arg2"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: true,
+          doc: false,
+          fail_fast: None,
+          filter: Some("- foo".to_string()),
+          permit_no_files: true,
+          files: FileFlags {
+            include: vec!["dir1/".to_string(), "dir2/".to_string()],
+            ignore: vec![],
+          },
+          shuffle: None,
+          concurrent_jobs: None,
+          trace_leaks: true,
+          coverage_dir: Some("cov".to_string()),
+          coverage_report: vec![],
+          clean: true,
+          watch: Default::default(),
+          reporter: Default::default(),
+          junit_path: None,
+          reporter_outputs: vec![],
+          hide_stacktraces: false,
+          otel: false,
+          watch_all: false,
+          setup: None,
+          teardown: None,
+          update_snapshots: false,
+          timeout: None,
+          slow_threshold: None,
+          artifacts_dir: None,
+          tags: vec![],
+          skip_tags: vec![],
+        }),
+        no_npm: true,
+        no_remote: true,
+        no_daemon: true,
+        location: Some(Url::parse("https://foo/").unwrap()),
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          allow_net: Some(vec![]),
+          ..Default::default()
+        },
+        argv: svec!["arg1", "arg2"],
+        ..Flags::default()
+      }
+    );
+  }
 
+  #[test]
+  fn run_with_cafile() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--reload=http://deno.land/a,",
+      "--cert",
+      "example.crt",
       "script.ts"
     ]);
-    assert!(r.is_err(), "Should reject a trailing comma");
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        ca_data: Some(CaData::File("example.crt".to_owned())),
+        code_cache_enabled: true,
+        ..Flags::default()
+      }
+    );
   }
 
   #[test]
-  fn run_import_map() {
+  fn run_with_enable_testing_features() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--import-map=import_map.json",
+      "--enable-testing-features-do-not-use",
       "script.ts"
     ]);
     assert_eq!(
@@ -7983,7 +13566,7 @@ mod tests {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
           "script.ts".to_string(),
         )),
-        import_map_path: Some("import_map.json".to_owned()),
+        enable_testing_features: true,
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -7991,869 +13574,1412 @@ mod tests {
   }
 
   #[test]
-  fn info_import_map() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "info",
-      "--import-map=import_map.json",
-      "script.ts"
-    ]);
+  fn test_with_fail_fast() {
+    let r = flags_from_vec(svec!["deno", "test", "--fail-fast=3"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
-          file: Some("script.ts".to_string()),
-          json: false,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: Some(NonZeroUsize::new(3).unwrap()),
+          filter: None,
+          permit_no_files: false,
+          shuffle: None,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          concurrent_jobs: None,
+          trace_leaks: false,
+          coverage_dir: None,
+          coverage_report: vec![],
+          clean: false,
+          watch: Default::default(),
+          reporter: Default::default(),
+          junit_path: None,
+          reporter_outputs: vec![],
+          hide_stacktraces: false,
+          otel: false,
+          watch_all: false,
+          setup: None,
+          teardown: None,
+          update_snapshots: false,
+          timeout: None,
+          slow_threshold: None,
+          artifacts_dir: None,
+          tags: vec![],
+          skip_tags: vec![],
         }),
-        import_map_path: Some("import_map.json".to_owned()),
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "test", "--fail-fast=0"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn cache_import_map() {
+  fn test_with_enable_testing_features() {
     let r = flags_from_vec(svec![
       "deno",
-      "cache",
-      "--import-map=import_map.json",
-      "script.ts"
+      "test",
+      "--enable-testing-features-do-not-use"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Cache(CacheFlags {
-          files: svec!["script.ts"],
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          permit_no_files: false,
+          shuffle: None,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          concurrent_jobs: None,
+          trace_leaks: false,
+          coverage_dir: None,
+          coverage_report: vec![],
+          clean: false,
+          watch: Default::default(),
+          reporter: Default::default(),
+          junit_path: None,
+          reporter_outputs: vec![],
+          hide_stacktraces: false,
+          otel: false,
+          watch_all: false,
+          setup: None,
+          teardown: None,
+          update_snapshots: false,
+          timeout: None,
+          slow_threshold: None,
+          artifacts_dir: None,
+          tags: vec![],
+          skip_tags: vec![],
         }),
-        import_map_path: Some("import_map.json".to_owned()),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        enable_testing_features: true,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn doc_import_map() {
+  fn test_reporter() {
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=pretty"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          reporter: TestReporterConfig::Pretty,
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=dot"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          reporter: TestReporterConfig::Dot,
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        log_level: Some(Level::Error),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=junit"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          reporter: TestReporterConfig::Junit,
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=tap"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Test(TestFlags {
+          reporter: TestReporterConfig::Tap,
+          ..Default::default()
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        log_level: Some(Level::Error),
+        ..Flags::default()
+      }
+    );
+
     let r = flags_from_vec(svec![
       "deno",
-      "doc",
-      "--import-map=import_map.json",
-      "script.ts"
+      "test",
+      "--reporter=dot",
+      "--junit-path=report.xml"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          source_files: DocSourceFileFlag::Paths(vec!["script.ts".to_owned()]),
-          private: false,
-          json: false,
-          html: None,
-          lint: false,
-          filter: None,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          reporter: TestReporterConfig::Dot,
+          junit_path: Some("report.xml".to_string()),
+          ..Default::default()
         }),
-        import_map_path: Some("import_map.json".to_owned()),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
+        log_level: Some(Level::Error),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "test", "--junit-path"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn run_env_default() {
-    let r = flags_from_vec(svec!["deno", "run", "--env", "script.ts"]);
+  fn test_multiple_reporters() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--reporter=pretty",
+      "--reporter=junit:report.xml",
+      "--reporter=json:events.ndjson"
+    ]);
+    let flags = r.unwrap();
+    match flags.subcommand {
+      DenoSubcommand::Test(test_flags) => {
+        assert_eq!(test_flags.reporter, TestReporterConfig::Pretty);
+        assert_eq!(
+          test_flags.reporter_outputs,
+          vec![
+            TestReporterOutput {
+              reporter: TestReporterConfig::Junit,
+              path: "report.xml".to_string(),
+            },
+            TestReporterOutput {
+              reporter: TestReporterConfig::Json,
+              path: "events.ndjson".to_string(),
+            },
+          ]
+        );
+      }
+      _ => unreachable!(),
+    }
+    assert_eq!(flags.log_level, None);
+
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=json"]);
+    let flags = r.unwrap();
+    assert_eq!(flags.log_level, Some(Level::Error));
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--reporter=pretty",
+      "--reporter=dot"
+    ]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=tap:out.tap"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "test", "--reporter=junit:"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_shuffle() {
+    let r = flags_from_vec(svec!["deno", "test", "--shuffle=1"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        env_file: Some(".env".to_owned()),
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          permit_no_files: false,
+          shuffle: Some(1),
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          concurrent_jobs: None,
+          trace_leaks: false,
+          coverage_dir: None,
+          coverage_report: vec![],
+          clean: false,
+          watch: Default::default(),
+          reporter: Default::default(),
+          junit_path: None,
+          reporter_outputs: vec![],
+          hide_stacktraces: false,
+          otel: false,
+          watch_all: false,
+          setup: None,
+          teardown: None,
+          update_snapshots: false,
+          timeout: None,
+          slow_threshold: None,
+          artifacts_dir: None,
+          tags: vec![],
+          skip_tags: vec![],
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn run_env_file_default() {
-    let r = flags_from_vec(svec!["deno", "run", "--env-file", "script.ts"]);
+  fn test_watch() {
+    let r = flags_from_vec(svec!["deno", "test", "--watch"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        env_file: Some(".env".to_owned()),
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          permit_no_files: false,
+          shuffle: None,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          concurrent_jobs: None,
+          trace_leaks: false,
+          coverage_dir: None,
+          coverage_report: vec![],
+          clean: false,
+          watch: Some(Default::default()),
+          reporter: Default::default(),
+          junit_path: None,
+          reporter_outputs: vec![],
+          hide_stacktraces: false,
+          otel: false,
+          watch_all: false,
+          setup: None,
+          teardown: None,
+          update_snapshots: false,
+          timeout: None,
+          slow_threshold: None,
+          artifacts_dir: None,
+          tags: vec![],
+          skip_tags: vec![],
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn run_no_code_cache() {
-    let r = flags_from_vec(svec!["deno", "--no-code-cache", "script.ts"]);
+  fn test_watch_all() {
+    let r = flags_from_vec(svec!["deno", "test", "--watch", "--watch-all"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: None,
-          bare: true,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          permit_no_files: false,
+          shuffle: None,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          concurrent_jobs: None,
+          trace_leaks: false,
+          coverage_dir: None,
+          coverage_report: vec![],
+          clean: false,
+          watch: Some(Default::default()),
+          reporter: Default::default(),
+          junit_path: None,
+          reporter_outputs: vec![],
+          hide_stacktraces: false,
+          otel: false,
+          watch_all: true,
+          setup: None,
+          teardown: None,
+          update_snapshots: false,
+          timeout: None,
+          slow_threshold: None,
+          artifacts_dir: None,
+          tags: vec![],
+          skip_tags: vec![],
         }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "test", "--watch-all"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn run_env_defined() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--env=.another_env", "script.ts"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        env_file: Some(".another_env".to_owned()),
-        code_cache_enabled: true,
-        ..Flags::default()
+  fn test_timeout_and_slow_threshold() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--timeout=5000",
+      "--slow-threshold=500"
+    ]);
+    let flags = r.unwrap();
+    match flags.subcommand {
+      DenoSubcommand::Test(test_flags) => {
+        assert_eq!(test_flags.timeout, Some(Duration::from_millis(5000)));
+        assert_eq!(test_flags.slow_threshold, Some(Duration::from_millis(500)));
       }
-    );
+      _ => unreachable!(),
+    }
+
+    let r = flags_from_vec(svec!["deno", "test", "--timeout=0"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn run_env_file_defined() {
+  fn test_artifacts_dir() {
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "--env-file=.another_env",
-      "script.ts"
+      "test",
+      "--artifacts-dir",
+      "test-results/artifacts"
     ]);
+    let DenoSubcommand::Test(test_flags) = r.unwrap().subcommand else {
+      unreachable!()
+    };
     assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        env_file: Some(".another_env".to_owned()),
-        code_cache_enabled: true,
-        ..Flags::default()
-      }
+      test_flags.artifacts_dir,
+      Some("test-results/artifacts".to_string())
     );
   }
 
   #[test]
-  fn cache_multiple() {
-    let r =
-      flags_from_vec(svec!["deno", "cache", "script.ts", "script_two.ts"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Cache(CacheFlags {
-          files: svec!["script.ts", "script_two.ts"],
-        }),
-        ..Flags::default()
-      }
-    );
+  fn test_tags() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--tags=integration,e2e",
+      "--skip-tags=slow",
+      "--tags=db"
+    ]);
+    let DenoSubcommand::Test(test_flags) = r.unwrap().subcommand else {
+      unreachable!()
+    };
+    assert_eq!(test_flags.tags, svec!["integration", "e2e", "db"]);
+    assert_eq!(test_flags.skip_tags, svec!["slow"]);
   }
 
   #[test]
-  fn run_seed() {
-    let r = flags_from_vec(svec!["deno", "run", "--seed", "250", "script.ts"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        seed: Some(250_u64),
-        v8_flags: svec!["--random-seed=250"],
-        code_cache_enabled: true,
-        ..Flags::default()
+  fn test_update_snapshots() {
+    let r = flags_from_vec(svec!["deno", "test", "--update-snapshots"]);
+    let flags = r.unwrap();
+    match flags.subcommand {
+      DenoSubcommand::Test(test_flags) => {
+        assert!(test_flags.update_snapshots);
       }
-    );
+      _ => unreachable!(),
+    }
   }
 
   #[test]
-  fn run_seed_with_v8_flags() {
+  fn test_setup_teardown() {
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "--seed",
-      "250",
-      "--v8-flags=--expose-gc",
-      "script.ts"
+      "test",
+      "--setup=./setup.ts",
+      "--teardown=./teardown.ts"
     ]);
+    let flags = r.unwrap();
+    match flags.subcommand {
+      DenoSubcommand::Test(test_flags) => {
+        assert_eq!(test_flags.setup, Some("./setup.ts".to_string()));
+        assert_eq!(test_flags.teardown, Some("./teardown.ts".to_string()));
+      }
+      _ => unreachable!(),
+    }
+  }
+
+  #[test]
+  fn test_watch_explicit_cwd() {
+    let r = flags_from_vec(svec!["deno", "test", "--watch", "./"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        seed: Some(250_u64),
-        v8_flags: svec!["--expose-gc", "--random-seed=250"],
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          permit_no_files: false,
+          shuffle: None,
+          files: FileFlags {
+            include: vec!["./".to_string()],
+            ignore: vec![],
+          },
+          concurrent_jobs: None,
+          trace_leaks: false,
+          coverage_dir: None,
+          coverage_report: vec![],
+          clean: false,
+          watch: Some(Default::default()),
+          reporter: Default::default(),
+          junit_path: None,
+          reporter_outputs: vec![],
+          hide_stacktraces: false,
+          otel: false,
+          watch_all: false,
+          setup: None,
+          teardown: None,
+          update_snapshots: false,
+          timeout: None,
+          slow_threshold: None,
+          artifacts_dir: None,
+          tags: vec![],
+          skip_tags: vec![],
+        }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn install() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "install",
-      "-g",
-      "jsr:@std/http/file-server"
-    ]);
+  fn test_watch_with_no_clear_screen() {
+    let r =
+      flags_from_vec(svec!["deno", "test", "--watch", "--no-clear-screen"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Install(InstallFlags {
-          kind: InstallKind::Global(InstallFlagsGlobal {
-            name: None,
-            module_url: "jsr:@std/http/file-server".to_string(),
-            args: vec![],
-            root: None,
-            force: false,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          no_run: false,
+          doc: false,
+          fail_fast: None,
+          filter: None,
+          permit_no_files: false,
+          shuffle: None,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          concurrent_jobs: None,
+          trace_leaks: false,
+          coverage_dir: None,
+          coverage_report: vec![],
+          clean: false,
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            no_clear_screen: true,
+            exclude: vec![],
+            paths: vec![],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
           }),
+          reporter: Default::default(),
+          junit_path: None,
+          reporter_outputs: vec![],
+          hide_stacktraces: false,
+          otel: false,
+          watch_all: false,
+          setup: None,
+          teardown: None,
+          update_snapshots: false,
+          timeout: None,
+          slow_threshold: None,
+          artifacts_dir: None,
+          tags: vec![],
+          skip_tags: vec![],
         }),
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "install",
-      "-g",
-      "jsr:@std/http/file-server"
-    ]);
+  #[test]
+  fn test_watch_with_paths() {
+    let r = flags_from_vec(svec!("deno", "test", "--watch=foo"));
+
+    let flags = r.unwrap();
     assert_eq!(
-      r.unwrap(),
+      flags,
       Flags {
-        subcommand: DenoSubcommand::Install(InstallFlags {
-          kind: InstallKind::Global(InstallFlagsGlobal {
-            name: None,
-            module_url: "jsr:@std/http/file-server".to_string(),
-            args: vec![],
-            root: None,
-            force: false,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![String::from("foo")],
+            no_clear_screen: false,
+            exclude: vec![],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
           }),
+          ..TestFlags::default()
         }),
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn install_with_flags() {
-    #[rustfmt::skip]
-    let r = flags_from_vec(svec!["deno", "install", "--global", "--import-map", "import_map.json", "--no-remote", "--config", "tsconfig.json", "--no-check", "--unsafely-ignore-certificate-errors", "--reload", "--lock", "lock.json", "--cert", "example.crt", "--cached-only", "--allow-read", "--allow-net", "--v8-flags=--help", "--seed", "1", "--inspect=127.0.0.1:9229", "--name", "file_server", "--root", "/foo", "--force", "--env=.example.env", "jsr:@std/http/file-server", "foo", "bar"]);
+    let r = flags_from_vec(svec!["deno", "test", "--watch=foo,bar"]);
+
+    let flags = r.unwrap();
     assert_eq!(
-      r.unwrap(),
+      flags,
       Flags {
-        subcommand: DenoSubcommand::Install(InstallFlags {
-          kind: InstallKind::Global(InstallFlagsGlobal {
-            name: Some("file_server".to_string()),
-            module_url: "jsr:@std/http/file-server".to_string(),
-            args: svec!["foo", "bar"],
-            root: Some("/foo".to_string()),
-            force: true,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![String::from("foo"), String::from("bar")],
+            no_clear_screen: false,
+            exclude: vec![],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
           }),
+          ..TestFlags::default()
         }),
-        import_map_path: Some("import_map.json".to_string()),
-        no_remote: true,
-        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
-        type_check_mode: TypeCheckMode::None,
-        reload: true,
-        lock: Some(String::from("lock.json")),
-        ca_data: Some(CaData::File("example.crt".to_string())),
-        cached_only: true,
-        v8_flags: svec!["--help", "--random-seed=1"],
-        seed: Some(1),
-        inspect: Some("127.0.0.1:9229".parse().unwrap()),
-        unsafely_ignore_certificate_errors: Some(vec![]),
+        type_check_mode: TypeCheckMode::Local,
         permissions: PermissionFlags {
-          allow_net: Some(vec![]),
-          allow_read: Some(vec![]),
+          no_prompt: true,
           ..Default::default()
         },
-        env_file: Some(".example.env".to_owned()),
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn uninstall() {
-    let r = flags_from_vec(svec!["deno", "uninstall"]);
-    assert!(r.is_err(),);
+  fn test_watch_with_excluded_paths() {
+    let r =
+      flags_from_vec(svec!("deno", "test", "--watch", "--watch-exclude=foo",));
 
-    let r = flags_from_vec(svec!["deno", "uninstall", "@std/load"]);
+    let flags = r.unwrap();
     assert_eq!(
-      r.unwrap(),
+      flags,
       Flags {
-        subcommand: DenoSubcommand::Uninstall(UninstallFlags {
-          kind: UninstallKind::Local(RemoveFlags {
-            packages: vec!["@std/load".to_string()],
+        subcommand: DenoSubcommand::Test(TestFlags {
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![],
+            no_clear_screen: false,
+            exclude: vec![String::from("foo")],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
           }),
+          ..TestFlags::default()
         }),
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
 
-    let r =
-      flags_from_vec(svec!["deno", "uninstall", "file_server", "@std/load"]);
+    let r = flags_from_vec(svec!(
+      "deno",
+      "test",
+      "--watch=foo",
+      "--watch-exclude=bar",
+    ));
+    let flags = r.unwrap();
     assert_eq!(
-      r.unwrap(),
+      flags,
       Flags {
-        subcommand: DenoSubcommand::Uninstall(UninstallFlags {
-          kind: UninstallKind::Local(RemoveFlags {
-            packages: vec!["file_server".to_string(), "@std/load".to_string()],
+        subcommand: DenoSubcommand::Test(TestFlags {
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![String::from("foo")],
+            no_clear_screen: false,
+            exclude: vec![String::from("bar")],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
           }),
+          ..TestFlags::default()
         }),
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "uninstall", "-g", "file_server"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--watch",
+      "--watch-exclude=foo,bar",
+    ]);
+
+    let flags = r.unwrap();
     assert_eq!(
-      r.unwrap(),
+      flags,
       Flags {
-        subcommand: DenoSubcommand::Uninstall(UninstallFlags {
-          kind: UninstallKind::Global(UninstallFlagsGlobal {
-            name: "file_server".to_string(),
-            root: None,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![],
+            no_clear_screen: false,
+            exclude: vec![String::from("foo"), String::from("bar")],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
           }),
+          ..TestFlags::default()
         }),
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
 
     let r = flags_from_vec(svec![
       "deno",
-      "uninstall",
-      "-g",
-      "--root",
-      "/user/foo/bar",
-      "file_server"
+      "test",
+      "--watch=foo,bar",
+      "--watch-exclude=baz,qux",
     ]);
+
+    let flags = r.unwrap();
     assert_eq!(
-      r.unwrap(),
+      flags,
       Flags {
-        subcommand: DenoSubcommand::Uninstall(UninstallFlags {
-          kind: UninstallKind::Global(UninstallFlagsGlobal {
-            name: "file_server".to_string(),
-            root: Some("/user/foo/bar".to_string()),
+        subcommand: DenoSubcommand::Test(TestFlags {
+          watch: Some(WatchFlagsWithPaths {
+            hmr: false,
+            paths: vec![String::from("foo"), String::from("bar")],
+            no_clear_screen: false,
+            exclude: vec![String::from("baz"), String::from("qux"),],
+            debounce: None,
+            poll_interval: None,
+            exec: None,
           }),
+          ..TestFlags::default()
         }),
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn uninstall_with_help_flag() {
-    let r = flags_from_vec(svec!["deno", "uninstall", "--help"]);
-    assert!(r.is_ok());
-  }
-
-  #[test]
-  fn log_level() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--log-level=debug", "script.ts"]);
+  fn test_coverage_default_dir() {
+    let r = flags_from_vec(svec!["deno", "test", "--coverage"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        log_level: Some(Level::Debug),
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          coverage_dir: Some("coverage".to_string()),
+          ..TestFlags::default()
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn quiet() {
-    let r = flags_from_vec(svec!["deno", "-q", "script.ts"]);
+  fn test_coverage_report() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "test",
+      "--coverage-report=html,lcov",
+      "--coverage-report=summary"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: None,
-          bare: true,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          coverage_dir: Some("coverage".to_string()),
+          coverage_report: vec![
+            CoverageType::Html,
+            CoverageType::Lcov,
+            CoverageType::Summary
+          ],
+          ..TestFlags::default()
         }),
-        log_level: Some(Level::Error),
-        code_cache_enabled: true,
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
-  }
-
-  #[test]
-  fn completions() {
-    let r = flags_from_vec(svec!["deno", "completions", "zsh"]).unwrap();
-
-    match r.subcommand {
-      DenoSubcommand::Completions(CompletionsFlags { buf }) => {
-        assert!(!buf.is_empty())
-      }
-      _ => unreachable!(),
-    }
-  }
 
-  #[test]
-  fn run_with_args() {
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "script.ts",
-      "--allow-read",
-      "--allow-net"
+      "test",
+      "--coverage=cov",
+      "--coverage-report=detailed"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        argv: svec!["--allow-read", "--allow-net"],
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          coverage_dir: Some("cov".to_string()),
+          coverage_report: vec![CoverageType::Detailed],
+          ..TestFlags::default()
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--location",
-      "https:foo",
-      "--allow-read",
-      "script.ts",
-      "--allow-net",
-      "-r",
-      "--help",
-      "--foo",
-      "bar"
-    ]);
+
+    let r = flags_from_vec(svec!["deno", "test", "--coverage-report=json"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn test_hide_stacktraces() {
+    let r = flags_from_vec(svec!["deno", "test", "--hide-stacktraces"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        location: Some(Url::parse("https://foo/").unwrap()),
+        subcommand: DenoSubcommand::Test(TestFlags {
+          hide_stacktraces: true,
+          ..TestFlags::default()
+        }),
+        type_check_mode: TypeCheckMode::Local,
         permissions: PermissionFlags {
-          allow_read: Some(vec![]),
+          no_prompt: true,
           ..Default::default()
         },
-        argv: svec!["--allow-net", "-r", "--help", "--foo", "bar"],
-        code_cache_enabled: true,
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec!["deno", "run", "script.ts", "foo", "bar"]);
+  #[test]
+  fn test_otel() {
+    let r = flags_from_vec(svec!["deno", "test", "--otel"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        argv: svec!["foo", "bar"],
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Test(TestFlags {
+          otel: true,
+          ..TestFlags::default()
+        }),
+        type_check_mode: TypeCheckMode::Local,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
-    let r = flags_from_vec(svec!["deno", "run", "script.ts", "-"]);
+  }
+
+  #[test]
+  fn upgrade_with_ca_file() {
+    let r = flags_from_vec(svec!["deno", "upgrade", "--cert", "example.crt"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        argv: svec!["-"],
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          canary: false,
+          release_candidate: false,
+          version: None,
+          output: None,
+          version_or_hash_or_channel: None,
+          repo: None,
+          url: None,
+        }),
+        ca_data: Some(CaData::File("example.crt".to_owned())),
         ..Flags::default()
       }
     );
+  }
 
-    let r =
-      flags_from_vec(svec!["deno", "run", "script.ts", "-", "foo", "bar"]);
+  #[test]
+  fn upgrade_release_candidate() {
+    let r = flags_from_vec(svec!["deno", "upgrade", "--rc"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        argv: svec!["-", "foo", "bar"],
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          canary: false,
+          release_candidate: true,
+          version: None,
+          output: None,
+          version_or_hash_or_channel: None,
+          repo: None,
+          url: None,
+        }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "upgrade", "--rc", "--canary"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "upgrade", "--rc", "--version"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn no_check() {
-    let r = flags_from_vec(svec!["deno", "--no-check", "script.ts"]);
+  fn upgrade_custom_source() {
+    let r = flags_from_vec(svec!["deno", "upgrade", "--repo", "acme/deno"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: None,
-          bare: true,
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          canary: false,
+          release_candidate: false,
+          version: None,
+          output: None,
+          version_or_hash_or_channel: None,
+          repo: Some("acme/deno".to_string()),
+          url: None,
         }),
-        type_check_mode: TypeCheckMode::None,
-        code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn no_check_remote() {
-    let r =
-      flags_from_vec(svec!["deno", "run", "--no-check=remote", "script.ts"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "upgrade",
+      "--url",
+      "https://deno.example.com/mirror/",
+      "2.0.0"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        type_check_mode: TypeCheckMode::Local,
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
+          force: false,
+          dry_run: false,
+          canary: false,
+          release_candidate: false,
+          version: None,
+          output: None,
+          version_or_hash_or_channel: Some("2.0.0".to_string()),
+          repo: None,
+          url: Some(Url::parse("https://deno.example.com/mirror").unwrap()),
+        }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "upgrade", "--repo", "deno"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "upgrade", "--url", "ftp://mirror"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "upgrade",
+      "--repo",
+      "acme/deno",
+      "--url",
+      "https://deno.example.com"
+    ]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn repl_with_unsafely_ignore_certificate_errors() {
+  fn cache_with_cafile() {
     let r = flags_from_vec(svec![
       "deno",
-      "repl",
-      "--eval",
-      "console.log('hello');",
-      "--unsafely-ignore-certificate-errors"
+      "cache",
+      "--cert",
+      "example.crt",
+      "script.ts",
+      "script_two.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Repl(ReplFlags {
-          eval_files: None,
-          eval: Some("console.log('hello');".to_string()),
-          is_default_command: false,
+        subcommand: DenoSubcommand::Cache(CacheFlags {
+          files: svec!["script.ts", "script_two.ts"],
+          dry_run: false,
+          json: false,
         }),
-        unsafely_ignore_certificate_errors: Some(vec![]),
-        type_check_mode: TypeCheckMode::None,
+        ca_data: Some(CaData::File("example.crt".to_owned())),
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn run_with_unsafely_ignore_certificate_errors() {
+  fn info_with_cafile() {
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "--unsafely-ignore-certificate-errors",
-      "script.ts"
+      "info",
+      "--cert",
+      "example.crt",
+      "https://example.com"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        unsafely_ignore_certificate_errors: Some(vec![]),
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Info(InfoFlags {
+          json: false,
+          file: Some("https://example.com".to_string()),
+          http_cache: false,
+          cache_usage: false,
+          graph: None,
+          graph_depth: None,
+          collapse_npm: false,
+          unused: false,
+          fix: false,
+        }),
+        ca_data: Some(CaData::File("example.crt".to_owned())),
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn run_with_unsafely_treat_insecure_origin_as_secure_with_ipv6_address() {
+  fn doc() {
+    let r = flags_from_vec(svec!["deno", "doc", "--json", "path/to/module.ts"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: true,
+          html: None,
+          lint: false,
+          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
+          filter: None,
+          only_deprecated: false,
+          min_since: None,
+          openapi: false,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "doc", "--html", "path/to/module.ts"]);
+    assert!(r.is_ok());
+
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "--unsafely-ignore-certificate-errors=deno.land,localhost,[::],127.0.0.1,[::1],1.2.3.4",
-      "script.ts"
+      "doc",
+      "--html",
+      "--name=My library",
+      "path/to/module.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        unsafely_ignore_certificate_errors: Some(svec![
-          "deno.land",
-          "localhost",
-          "[::]",
-          "127.0.0.1",
-          "[::1]",
-          "1.2.3.4"
-        ]),
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          lint: false,
+          html: Some(DocHtmlFlag {
+            name: Some("My library".to_string()),
+            category_docs_path: None,
+            symbol_redirect_map_path: None,
+            default_symbol_map_path: None,
+            strip_trailing_html: false,
+            output: String::from("./docs/"),
+          }),
+          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
+          filter: None,
+          only_deprecated: false,
+          min_since: None,
+          openapi: false,
+        }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn repl_with_unsafely_treat_insecure_origin_as_secure_with_ipv6_address() {
     let r = flags_from_vec(svec![
       "deno",
-      "repl",
-      "--unsafely-ignore-certificate-errors=deno.land,localhost,[::],127.0.0.1,[::1],1.2.3.4"]);
+      "doc",
+      "--html",
+      "--name=My library",
+      "--lint",
+      "--output=./foo",
+      "path/to/module.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Repl(ReplFlags {
-          eval_files: None,
-          eval: None,
-          is_default_command: false,
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: Some(DocHtmlFlag {
+            name: Some("My library".to_string()),
+            category_docs_path: None,
+            symbol_redirect_map_path: None,
+            default_symbol_map_path: None,
+            strip_trailing_html: false,
+            output: String::from("./foo"),
+          }),
+          lint: true,
+          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
+          filter: None,
+          only_deprecated: false,
+          min_since: None,
+          openapi: false,
         }),
-        unsafely_ignore_certificate_errors: Some(svec![
-          "deno.land",
-          "localhost",
-          "[::]",
-          "127.0.0.1",
-          "[::1]",
-          "1.2.3.4"
-        ]),
-        type_check_mode: TypeCheckMode::None,
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn no_remote() {
-    let r = flags_from_vec(svec!["deno", "run", "--no-remote", "script.ts"]);
+    let r =
+      flags_from_vec(svec!["deno", "doc", "--html", "--name=My library",]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--filter",
+      "SomeClass.someField",
+      "path/to/module.ts",
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        no_remote: true,
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: None,
+          lint: false,
+          source_files: DocSourceFileFlag::Paths(vec![
+            "path/to/module.ts".to_string()
+          ]),
+          filter: Some("SomeClass.someField".to_string()),
+          only_deprecated: false,
+          min_since: None,
+          openapi: false,
+        }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn no_npm() {
-    let r = flags_from_vec(svec!["deno", "run", "--no-npm", "script.ts"]);
+    let r = flags_from_vec(svec!["deno", "doc"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        no_npm: true,
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: None,
+          lint: false,
+          source_files: Default::default(),
+          filter: None,
+          only_deprecated: false,
+          min_since: None,
+          openapi: false,
+        }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn local_npm() {
-    let r = flags_from_vec(svec!["deno", "--node-modules-dir", "script.ts"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--filter",
+      "Deno.Listener",
+      "--builtin"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: None,
-          bare: true,
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          lint: false,
+          json: false,
+          html: None,
+          source_files: DocSourceFileFlag::Builtin,
+          filter: Some("Deno.Listener".to_string()),
+          only_deprecated: false,
+          min_since: None,
+          openapi: false,
         }),
-        node_modules_dir: Some(NodeModulesDirMode::Auto),
-        code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn vendor_flag() {
-    let r = flags_from_vec(svec!["deno", "run", "--vendor", "script.ts"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "--no-npm",
+      "--no-remote",
+      "--private",
+      "path/to/module.js"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        vendor: Some(true),
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: true,
+          lint: false,
+          json: false,
+          html: None,
+          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.js"]),
+          filter: None,
+          only_deprecated: false,
+          min_since: None,
+          openapi: false,
+        }),
+        no_npm: true,
+        no_remote: true,
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "run", "--vendor=false", "script.ts"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "path/to/module.js",
+      "path/to/module2.js"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        vendor: Some(false),
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          lint: false,
+          json: false,
+          html: None,
+          source_files: DocSourceFileFlag::Paths(vec![
+            "path/to/module.js".to_string(),
+            "path/to/module2.js".to_string()
+          ]),
+          filter: None,
+          only_deprecated: false,
+          min_since: None,
+          openapi: false,
+        }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn cached_only() {
-    let r = flags_from_vec(svec!["deno", "run", "--cached-only", "script.ts"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "doc",
+      "path/to/module.js",
+      "--builtin",
+      "path/to/module2.js"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        cached_only: true,
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: false,
+          html: None,
+          lint: false,
+          source_files: DocSourceFileFlag::Paths(vec![
+            "path/to/module.js".to_string(),
+            "path/to/module2.js".to_string()
+          ]),
+          filter: None,
+          only_deprecated: false,
+          min_since: None,
+          openapi: false,
+        }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn allow_net_allowlist_with_ports() {
+    let r = flags_from_vec(svec!["deno", "doc", "--lint",]);
+    assert!(r.is_err());
+
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "--allow-net=deno.land,:8000,:4545",
-      "script.ts"
+      "doc",
+      "--lint",
+      "path/to/module.js",
+      "path/to/module2.js"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        permissions: PermissionFlags {
-          allow_net: Some(svec![
-            "deno.land",
-            "0.0.0.0:8000",
-            "127.0.0.1:8000",
-            "localhost:8000",
-            "0.0.0.0:4545",
-            "127.0.0.1:4545",
-            "localhost:4545"
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          lint: true,
+          json: false,
+          html: None,
+          source_files: DocSourceFileFlag::Paths(vec![
+            "path/to/module.js".to_string(),
+            "path/to/module2.js".to_string()
           ]),
-          ..Default::default()
-        },
-        code_cache_enabled: true,
+          filter: None,
+          only_deprecated: false,
+          min_since: None,
+          openapi: false,
+        }),
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn deny_net_denylist_with_ports() {
+  fn doc_only_deprecated_and_min_since() {
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "--deny-net=deno.land,:8000,:4545",
-      "script.ts"
+      "doc",
+      "--json",
+      "--only-deprecated",
+      "--min-since=1.2.0",
+      "path/to/module.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        permissions: PermissionFlags {
-          deny_net: Some(svec![
-            "deno.land",
-            "0.0.0.0:8000",
-            "127.0.0.1:8000",
-            "localhost:8000",
-            "0.0.0.0:4545",
-            "127.0.0.1:4545",
-            "localhost:4545"
-          ]),
-          ..Default::default()
-        },
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: true,
+          html: None,
+          lint: false,
+          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
+          filter: None,
+          only_deprecated: true,
+          min_since: Some("1.2.0".to_string()),
+          openapi: false,
+        }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn allow_net_allowlist_with_ipv6_address() {
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "--allow-net=deno.land,deno.land:80,[::],127.0.0.1,[::1],1.2.3.4:5678,:5678,[::1]:8080",
-      "script.ts"
+      "doc",
+      "--lint",
+      "--only-deprecated",
+      "path/to/module.ts"
     ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn doc_openapi() {
+    let r =
+      flags_from_vec(svec!["deno", "doc", "--json", "--openapi", "main.ts"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        permissions: PermissionFlags {
-          allow_net: Some(svec![
-            "deno.land",
-            "deno.land:80",
-            "[::]",
-            "127.0.0.1",
-            "[::1]",
-            "1.2.3.4:5678",
-            "0.0.0.0:5678",
-            "127.0.0.1:5678",
-            "localhost:5678",
-            "[::1]:8080"
-          ]),
-          ..Default::default()
-        },
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Doc(DocFlags {
+          private: false,
+          json: true,
+          html: None,
+          lint: false,
+          source_files: DocSourceFileFlag::Paths(svec!["main.ts"]),
+          filter: None,
+          only_deprecated: false,
+          min_since: None,
+          openapi: true,
+        }),
         ..Flags::default()
       }
     );
+
+    // the document is JSON
+    let r = flags_from_vec(svec!["deno", "doc", "--openapi", "main.ts"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn deny_net_denylist_with_ipv6_address() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--deny-net=deno.land,deno.land:80,[::],127.0.0.1,[::1],1.2.3.4:5678,:5678,[::1]:8080",
-      "script.ts"
-    ]);
+  fn inspect_default_host() {
+    let r = flags_from_vec(svec!["deno", "run", "--inspect", "foo.js"]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
+          "foo.js".to_string(),
         )),
-        permissions: PermissionFlags {
-          deny_net: Some(svec![
-            "deno.land",
-            "deno.land:80",
-            "[::]",
-            "127.0.0.1",
-            "[::1]",
-            "1.2.3.4:5678",
-            "0.0.0.0:5678",
-            "127.0.0.1:5678",
-            "localhost:5678",
-            "[::1]:8080"
-          ]),
-          ..Default::default()
-        },
+        inspect: Some("127.0.0.1:9229".parse().unwrap()),
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -8861,80 +14987,40 @@ mod tests {
   }
 
   #[test]
-  fn test_no_colon_in_value_name() {
-    let app =
-      runtime_args(Command::new("test_inspect_completion_value"), true, true);
-    let inspect_args = app
-      .get_arguments()
-      .filter(|arg| arg.get_id() == "inspect")
-      .collect::<Vec<_>>();
-    // The value_name cannot have a : otherwise it breaks shell completions for zsh.
-    let value_name = "HOST_AND_PORT";
-    let arg = inspect_args
-      .iter()
-      .any(|v| v.get_value_names().unwrap() == [value_name]);
-
-    assert_eq!(arg, true);
-  }
-
-  #[test]
-  fn test_with_flags() {
-    #[rustfmt::skip]
-    let r = flags_from_vec(svec!["deno", "test", "--no-npm", "--no-remote", "--trace-leaks", "--no-run", "--filter", "- foo", "--coverage=cov", "--clean", "--location", "https:foo", "--allow-net", "--permit-no-files", "dir1/", "dir2/", "--", "arg1", "arg2"]);
+  fn inspect_wait() {
+    let r = flags_from_vec(svec!["deno", "--inspect-wait", "foo.js"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          no_run: true,
-          doc: false,
-          fail_fast: None,
-          filter: Some("- foo".to_string()),
-          permit_no_files: true,
-          files: FileFlags {
-            include: vec!["dir1/".to_string(), "dir2/".to_string()],
-            ignore: vec![],
-          },
-          shuffle: None,
-          concurrent_jobs: None,
-          trace_leaks: true,
-          coverage_dir: Some("cov".to_string()),
-          clean: true,
-          watch: Default::default(),
-          reporter: Default::default(),
-          junit_path: None,
-          hide_stacktraces: false,
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "foo.js".to_string(),
+          watch: None,
+          bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
         }),
-        no_npm: true,
-        no_remote: true,
-        location: Some(Url::parse("https://foo/").unwrap()),
-        type_check_mode: TypeCheckMode::Local,
-        permissions: PermissionFlags {
-          no_prompt: true,
-          allow_net: Some(vec![]),
-          ..Default::default()
-        },
-        argv: svec!["arg1", "arg2"],
+        inspect_wait: Some("127.0.0.1:9229".parse().unwrap()),
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn run_with_cafile() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--cert",
-      "example.crt",
-      "script.ts"
+      "--inspect-wait=127.0.0.1:3567",
+      "foo.js"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
+          "foo.js".to_string(),
         )),
-        ca_data: Some(CaData::File("example.crt".to_owned())),
+        inspect_wait: Some("127.0.0.1:3567".parse().unwrap()),
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -8942,929 +15028,914 @@ mod tests {
   }
 
   #[test]
-  fn run_with_enable_testing_features() {
+  fn inspect_connect() {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--enable-testing-features-do-not-use",
-      "script.ts"
+      "--inspect-connect=host.docker.internal:9229",
+      "foo.js"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
+          "foo.js".to_string(),
         )),
-        enable_testing_features: true,
+        inspect_connect: Some("host.docker.internal:9229".to_string()),
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
-
-  #[test]
-  fn test_with_fail_fast() {
-    let r = flags_from_vec(svec!["deno", "test", "--fail-fast=3"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          no_run: false,
-          doc: false,
-          fail_fast: Some(NonZeroUsize::new(3).unwrap()),
-          filter: None,
-          permit_no_files: false,
-          shuffle: None,
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          concurrent_jobs: None,
-          trace_leaks: false,
-          coverage_dir: None,
-          clean: false,
-          watch: Default::default(),
-          reporter: Default::default(),
-          junit_path: None,
-          hide_stacktraces: false,
-        }),
-        type_check_mode: TypeCheckMode::Local,
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
-        ..Flags::default()
-      }
-    );
 
-    let r = flags_from_vec(svec!["deno", "test", "--fail-fast=0"]);
+    let r =
+      flags_from_vec(svec!["deno", "run", "--inspect-connect=9229", "foo.js"]);
     assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--inspect-connect=127.0.0.1:9229",
+      "--inspect-brk",
+      "foo.js"
+    ]);
+    assert_eq!(r.unwrap_err().kind(), ErrorKind::ArgumentConflict);
   }
 
   #[test]
-  fn test_with_enable_testing_features() {
+  fn compile() {
     let r = flags_from_vec(svec![
       "deno",
-      "test",
-      "--enable-testing-features-do-not-use"
+      "compile",
+      "https://examples.deno.land/color-logging.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          no_run: false,
-          doc: false,
-          fail_fast: None,
-          filter: None,
-          permit_no_files: false,
-          shuffle: None,
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          concurrent_jobs: None,
-          trace_leaks: false,
-          coverage_dir: None,
-          clean: false,
-          watch: Default::default(),
-          reporter: Default::default(),
-          junit_path: None,
-          hide_stacktraces: false,
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "https://examples.deno.land/color-logging.ts"
+            .to_string(),
+          output: None,
+          args: vec![],
+          target: None,
+          no_terminal: false,
+          icon: None,
+          include: vec![],
+          no_licenses: false,
+          require_lock_integrity: false,
+          watch: None,
+          watch_run: false,
+          installer: None,
+          installer_version: None,
+          installer_license: None,
+          reproducible: false,
         }),
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
         type_check_mode: TypeCheckMode::Local,
-        enable_testing_features: true,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn test_reporter() {
-    let r = flags_from_vec(svec!["deno", "test", "--reporter=pretty"]);
+  fn compile_with_flags() {
+    #[rustfmt::skip]
+    let r = flags_from_vec(svec!["deno", "compile", "--import-map", "import_map.json", "--no-remote", "--config", "tsconfig.json", "--no-check", "--unsafely-ignore-certificate-errors", "--reload", "--lock", "lock.json", "--cert", "example.crt", "--cached-only", "--location", "https:foo", "--allow-read", "--allow-net", "--v8-flags=--help", "--seed", "1", "--no-terminal", "--icon", "favicon.ico", "--no-licenses", "--output", "colors", "--env=.example.env", "https://examples.deno.land/color-logging.ts", "foo", "bar", "-p", "8080"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          reporter: TestReporterConfig::Pretty,
-          ..Default::default()
+        subcommand: DenoSubcommand::Compile(CompileFlags {
+          source_file: "https://examples.deno.land/color-logging.ts"
+            .to_string(),
+          output: Some(String::from("colors")),
+          args: svec!["foo", "bar", "-p", "8080"],
+          target: None,
+          no_terminal: true,
+          icon: Some(String::from("favicon.ico")),
+          include: vec![],
+          no_licenses: true,
+          require_lock_integrity: false,
+          watch: None,
+          watch_run: false,
+          installer: None,
+          installer_version: None,
+          installer_license: None,
+          reproducible: false,
         }),
+        import_map_path: Some("import_map.json".to_string()),
+        no_remote: true,
+        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
+        type_check_mode: TypeCheckMode::None,
+        reload: true,
+        lock: Some(String::from("lock.json")),
+        ca_data: Some(CaData::File("example.crt".to_string())),
+        cached_only: true,
+        location: Some(Url::parse("https://foo/").unwrap()),
         permissions: PermissionFlags {
-          no_prompt: true,
+          allow_read: Some(vec![]),
+          allow_net: Some(vec![]),
           ..Default::default()
         },
-        type_check_mode: TypeCheckMode::Local,
+        unsafely_ignore_certificate_errors: Some(vec![]),
+        v8_flags: svec!["--help", "--random-seed=1"],
+        seed: Some(1),
+        env_file: Some(vec![".example.env".to_owned()]),
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec!["deno", "test", "--reporter=dot"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          reporter: TestReporterConfig::Dot,
-          ..Default::default()
-        }),
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
-        type_check_mode: TypeCheckMode::Local,
-        log_level: Some(Level::Error),
-        ..Flags::default()
+  #[test]
+  fn compile_with_watch() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--watch",
+      "--watch-run",
+      "--no-clear-screen",
+      "main.ts",
+      "foo"
+    ]);
+    let flags = r.unwrap();
+    match flags.subcommand {
+      DenoSubcommand::Compile(compile_flags) => {
+        assert!(compile_flags.watch_run);
+        assert!(compile_flags.watch.unwrap().no_clear_screen);
+        assert_eq!(compile_flags.args, svec!["foo"]);
+      }
+      _ => unreachable!(),
+    }
+
+    let r = flags_from_vec(svec!["deno", "compile", "--watch-run", "main.ts"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn compile_require_lock_integrity() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--require-lock-integrity",
+      "main.ts"
+    ]);
+    let flags = r.unwrap();
+    match flags.subcommand {
+      DenoSubcommand::Compile(compile_flags) => {
+        assert!(compile_flags.require_lock_integrity);
+      }
+      _ => unreachable!(),
+    }
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--require-lock-integrity",
+      "--no-lock",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn compile_reproducible() {
+    let r =
+      flags_from_vec(svec!["deno", "compile", "--reproducible", "main.ts"]);
+    let flags = r.unwrap();
+    match flags.subcommand {
+      DenoSubcommand::Compile(compile_flags) => {
+        assert!(compile_flags.reproducible);
       }
-    );
+      _ => unreachable!(),
+    }
+  }
 
-    let r = flags_from_vec(svec!["deno", "test", "--reporter=junit"]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          reporter: TestReporterConfig::Junit,
-          ..Default::default()
-        }),
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
-        type_check_mode: TypeCheckMode::Local,
-        ..Flags::default()
+  #[test]
+  fn compile_installer() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--installer=deb",
+      "--installer-version=1.2.3",
+      "--installer-license=LICENSE",
+      "main.ts"
+    ]);
+    let flags = r.unwrap();
+    match flags.subcommand {
+      DenoSubcommand::Compile(compile_flags) => {
+        assert_eq!(compile_flags.installer, Some(CompileInstaller::Deb));
+        assert_eq!(compile_flags.installer_version.as_deref(), Some("1.2.3"));
+        assert_eq!(compile_flags.installer_license.as_deref(), Some("LICENSE"));
       }
-    );
+      _ => unreachable!(),
+    }
 
-    let r = flags_from_vec(svec!["deno", "test", "--reporter=tap"]);
+    let r =
+      flags_from_vec(svec!["deno", "compile", "--installer=rpm", "main.ts"]);
+    assert!(r.is_err());
+    let r = flags_from_vec(svec![
+      "deno",
+      "compile",
+      "--installer-version=1.2.3",
+      "main.ts"
+    ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn coverage() {
+    let r = flags_from_vec(svec!["deno", "coverage", "foo.json"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          reporter: TestReporterConfig::Tap,
-          ..Default::default()
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: FileFlags {
+            include: vec!["foo.json".to_string()],
+            ignore: vec![],
+          },
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          ..CoverageFlags::default()
         }),
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
-        type_check_mode: TypeCheckMode::Local,
-        log_level: Some(Level::Error),
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn coverage_with_lcov_and_out_file() {
     let r = flags_from_vec(svec![
       "deno",
-      "test",
-      "--reporter=dot",
-      "--junit-path=report.xml"
+      "coverage",
+      "--lcov",
+      "--output=foo.lcov",
+      "foo.json"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          reporter: TestReporterConfig::Dot,
-          junit_path: Some("report.xml".to_string()),
-          ..Default::default()
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
+          files: FileFlags {
+            include: vec!["foo.json".to_string()],
+            ignore: vec![],
+          },
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          r#type: CoverageType::Lcov,
+          output: Some(String::from("foo.lcov")),
+          ..CoverageFlags::default()
         }),
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
-        type_check_mode: TypeCheckMode::Local,
-        log_level: Some(Level::Error),
         ..Flags::default()
       }
     );
-
-    let r = flags_from_vec(svec!["deno", "test", "--junit-path"]);
-    assert!(r.is_err());
   }
 
   #[test]
-  fn test_shuffle() {
-    let r = flags_from_vec(svec!["deno", "test", "--shuffle=1"]);
+  fn coverage_with_default_files() {
+    let r = flags_from_vec(svec!["deno", "coverage",]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          no_run: false,
-          doc: false,
-          fail_fast: None,
-          filter: None,
-          permit_no_files: false,
-          shuffle: Some(1),
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
           files: FileFlags {
-            include: vec![],
+            include: vec!["coverage".to_string()],
             ignore: vec![],
           },
-          concurrent_jobs: None,
-          trace_leaks: false,
-          coverage_dir: None,
-          clean: false,
-          watch: Default::default(),
-          reporter: Default::default(),
-          junit_path: None,
-          hide_stacktraces: false,
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          ..CoverageFlags::default()
         }),
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
-        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn test_watch() {
-    let r = flags_from_vec(svec!["deno", "test", "--watch"]);
+  fn coverage_with_changed_since_and_threshold() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--changed-since=main",
+      "--threshold=90",
+      "foo.json"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          no_run: false,
-          doc: false,
-          fail_fast: None,
-          filter: None,
-          permit_no_files: false,
-          shuffle: None,
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
           files: FileFlags {
-            include: vec![],
+            include: vec!["foo.json".to_string()],
             ignore: vec![],
           },
-          concurrent_jobs: None,
-          trace_leaks: false,
-          coverage_dir: None,
-          clean: false,
-          watch: Some(Default::default()),
-          reporter: Default::default(),
-          junit_path: None,
-          hide_stacktraces: false,
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          changed_since: Some("main".to_string()),
+          threshold: Some(90),
+          ..CoverageFlags::default()
         }),
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
-        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "coverage", "--threshold=101"]);
+    assert!(r.is_err());
   }
+
   #[test]
-  fn test_watch_explicit_cwd() {
-    let r = flags_from_vec(svec!["deno", "test", "--watch", "./"]);
+  fn coverage_with_history_and_trend() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--history=history.jsonl",
+      "--trend",
+      "foo.json"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          no_run: false,
-          doc: false,
-          fail_fast: None,
-          filter: None,
-          permit_no_files: false,
-          shuffle: None,
+        subcommand: DenoSubcommand::Coverage(CoverageFlags {
           files: FileFlags {
-            include: vec!["./".to_string()],
+            include: vec!["foo.json".to_string()],
             ignore: vec![],
           },
-          concurrent_jobs: None,
-          trace_leaks: false,
-          coverage_dir: None,
-          clean: false,
-          watch: Some(Default::default()),
-          reporter: Default::default(),
-          junit_path: None,
-          hide_stacktraces: false,
+          include: vec![r"^file:".to_string()],
+          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+          history: Some("history.jsonl".to_string()),
+          trend: Some(5),
+          ..CoverageFlags::default()
         }),
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
-        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--history=history.jsonl",
+      "--trend=10",
+      "foo.json"
+    ]);
+    assert_eq!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Coverage(CoverageFlags {
+        files: FileFlags {
+          include: vec!["foo.json".to_string()],
+          ignore: vec![],
+        },
+        include: vec![r"^file:".to_string()],
+        exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+        history: Some("history.jsonl".to_string()),
+        trend: Some(10),
+        ..CoverageFlags::default()
+      })
+    );
+
+    // the trend is computed from the history
+    let r = flags_from_vec(svec!["deno", "coverage", "--trend", "foo.json"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn test_watch_with_no_clear_screen() {
-    let r =
-      flags_from_vec(svec!["deno", "test", "--watch", "--no-clear-screen"]);
+  fn coverage_include_untested() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "coverage",
+      "--include-untested",
+      "foo.json"
+    ]);
     assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          no_run: false,
-          doc: false,
-          fail_fast: None,
-          filter: None,
-          permit_no_files: false,
-          shuffle: None,
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          concurrent_jobs: None,
-          trace_leaks: false,
-          coverage_dir: None,
-          clean: false,
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            no_clear_screen: true,
-            exclude: vec![],
-            paths: vec![],
-          }),
-          reporter: Default::default(),
-          junit_path: None,
-          hide_stacktraces: false,
-        }),
-        type_check_mode: TypeCheckMode::Local,
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
+      r.unwrap().subcommand,
+      DenoSubcommand::Coverage(CoverageFlags {
+        files: FileFlags {
+          include: vec!["foo.json".to_string()],
+          ignore: vec![],
         },
-        ..Flags::default()
-      }
+        include: vec![r"^file:".to_string()],
+        exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
+        include_untested: true,
+        ..CoverageFlags::default()
+      })
     );
   }
 
   #[test]
-  fn test_watch_with_paths() {
-    let r = flags_from_vec(svec!("deno", "test", "--watch=foo"));
+  fn location_with_bad_scheme() {
+    #[rustfmt::skip]
+    let r = flags_from_vec(svec!["deno", "run", "--location", "foo:", "mod.ts"]);
+    assert!(r.is_err());
+    assert!(r
+      .unwrap_err()
+      .to_string()
+      .contains("Expected protocol \"http\" or \"https\""));
+  }
 
-    let flags = r.unwrap();
+  #[test]
+  fn test_config_path_args() {
+    let flags = flags_from_vec(svec!["deno", "run", "foo.js"]).unwrap();
+    let cwd = std::env::current_dir().unwrap();
+
+    assert_eq!(flags.config_path_args(&cwd), Some(vec![cwd.clone()]));
+
+    let flags = flags_from_vec(svec!["deno", "run", "sub_dir/foo.js"]).unwrap();
+    let cwd = std::env::current_dir().unwrap();
     assert_eq!(
-      flags,
-      Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![String::from("foo")],
-            no_clear_screen: false,
-            exclude: vec![],
-          }),
-          ..TestFlags::default()
-        }),
-        type_check_mode: TypeCheckMode::Local,
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
-        ..Flags::default()
-      }
+      flags.config_path_args(&cwd),
+      Some(vec![cwd.join("sub_dir").clone()])
     );
 
-    let r = flags_from_vec(svec!["deno", "test", "--watch=foo,bar"]);
+    let flags =
+      flags_from_vec(svec!["deno", "https://example.com/foo.js"]).unwrap();
+    assert_eq!(flags.config_path_args(&cwd), None);
 
-    let flags = r.unwrap();
+    let flags =
+      flags_from_vec(svec!["deno", "lint", "dir/a/a.js", "dir/b/b.js"])
+        .unwrap();
     assert_eq!(
-      flags,
-      Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![String::from("foo"), String::from("bar")],
-            no_clear_screen: false,
-            exclude: vec![],
-          }),
-          ..TestFlags::default()
-        }),
-        type_check_mode: TypeCheckMode::Local,
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
-        ..Flags::default()
-      }
+      flags.config_path_args(&cwd),
+      Some(vec![cwd.join("dir/a/a.js"), cwd.join("dir/b/b.js")])
     );
-  }
 
-  #[test]
-  fn test_watch_with_excluded_paths() {
-    let r =
-      flags_from_vec(svec!("deno", "test", "--watch", "--watch-exclude=foo",));
+    let flags = flags_from_vec(svec!["deno", "lint"]).unwrap();
+    assert_eq!(flags.config_path_args(&cwd), Some(vec![cwd.clone()]));
 
-    let flags = r.unwrap();
+    let flags = flags_from_vec(svec![
+      "deno",
+      "fmt",
+      "dir/a/a.js",
+      "dir/a/a2.js",
+      "dir/b.js"
+    ])
+    .unwrap();
     assert_eq!(
-      flags,
-      Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![],
-            no_clear_screen: false,
-            exclude: vec![String::from("foo")],
-          }),
-          ..TestFlags::default()
-        }),
-        type_check_mode: TypeCheckMode::Local,
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
-        ..Flags::default()
-      }
+      flags.config_path_args(&cwd),
+      Some(vec![
+        cwd.join("dir/a/a.js"),
+        cwd.join("dir/a/a2.js"),
+        cwd.join("dir/b.js")
+      ])
     );
+  }
 
-    let r = flags_from_vec(svec!(
-      "deno",
-      "test",
-      "--watch=foo",
-      "--watch-exclude=bar",
-    ));
-    let flags = r.unwrap();
+  #[test]
+  fn test_no_clear_watch_flag_without_watch_flag() {
+    let r = flags_from_vec(svec!["deno", "run", "--no-clear-screen", "foo.js"]);
+    assert!(r.is_err());
+    let error_message = r.unwrap_err().to_string();
+    assert!(&error_message
+      .contains("error: the following required arguments were not provided:"));
+    assert!(&error_message.contains("--watch[=<FILES>...]"));
+  }
+
+  #[test]
+  fn task_subcommand() {
+    let r = flags_from_vec(svec!["deno", "task", "build", "hello", "world",]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![String::from("foo")],
-            no_clear_screen: false,
-            exclude: vec![String::from("bar")],
-          }),
-          ..TestFlags::default()
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("build".to_string()),
+          is_run: false,
         }),
-        type_check_mode: TypeCheckMode::Local,
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
+        argv: svec!["hello", "world"],
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "test",
-      "--watch",
-      "--watch-exclude=foo,bar",
-    ]);
-
-    let flags = r.unwrap();
+    let r = flags_from_vec(svec!["deno", "task", "build"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![],
-            no_clear_screen: false,
-            exclude: vec![String::from("foo"), String::from("bar")],
-          }),
-          ..TestFlags::default()
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("build".to_string()),
+          is_run: false,
         }),
-        type_check_mode: TypeCheckMode::Local,
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "test",
-      "--watch=foo,bar",
-      "--watch-exclude=baz,qux",
-    ]);
-
-    let flags = r.unwrap();
+    let r = flags_from_vec(svec!["deno", "task", "--cwd", "foo", "build"]);
     assert_eq!(
-      flags,
+      r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          watch: Some(WatchFlagsWithPaths {
-            hmr: false,
-            paths: vec![String::from("foo"), String::from("bar")],
-            no_clear_screen: false,
-            exclude: vec![String::from("baz"), String::from("qux"),],
-          }),
-          ..TestFlags::default()
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: Some("foo".to_string()),
+          task: Some("build".to_string()),
+          is_run: false,
         }),
-        type_check_mode: TypeCheckMode::Local,
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn test_coverage_default_dir() {
-    let r = flags_from_vec(svec!["deno", "test", "--coverage"]);
+  fn task_subcommand_double_hyphen() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "task",
+      "-c",
+      "deno.json",
+      "build",
+      "--",
+      "hello",
+      "world",
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          coverage_dir: Some("coverage".to_string()),
-          ..TestFlags::default()
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("build".to_string()),
+          is_run: false,
         }),
-        type_check_mode: TypeCheckMode::Local,
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
+        argv: svec!["--", "hello", "world"],
+        config_flag: ConfigFlag::Path("deno.json".to_owned()),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn test_hide_stacktraces() {
-    let r = flags_from_vec(svec!["deno", "test", "--hide-stacktraces"]);
+    let r = flags_from_vec(svec![
+      "deno", "task", "--cwd", "foo", "build", "--", "hello", "world"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Test(TestFlags {
-          hide_stacktraces: true,
-          ..TestFlags::default()
-        }),
-        type_check_mode: TypeCheckMode::Local,
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: Some("foo".to_string()),
+          task: Some("build".to_string()),
+          is_run: false,
+        }),
+        argv: svec!["--", "hello", "world"],
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn upgrade_with_ca_file() {
-    let r = flags_from_vec(svec!["deno", "upgrade", "--cert", "example.crt"]);
+  fn task_subcommand_double_hyphen_only() {
+    // edge case, but it should forward
+    let r = flags_from_vec(svec!["deno", "task", "build", "--"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
-          force: false,
-          dry_run: false,
-          canary: false,
-          release_candidate: false,
-          version: None,
-          output: None,
-          version_or_hash_or_channel: None,
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("build".to_string()),
+          is_run: false,
         }),
-        ca_data: Some(CaData::File("example.crt".to_owned())),
+        argv: svec!["--"],
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn upgrade_release_candidate() {
-    let r = flags_from_vec(svec!["deno", "upgrade", "--rc"]);
+  fn task_following_arg() {
+    let r = flags_from_vec(svec!["deno", "task", "build", "-1", "--test"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Upgrade(UpgradeFlags {
-          force: false,
-          dry_run: false,
-          canary: false,
-          release_candidate: true,
-          version: None,
-          output: None,
-          version_or_hash_or_channel: None,
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("build".to_string()),
+          is_run: false,
         }),
+        argv: svec!["-1", "--test"],
         ..Flags::default()
       }
     );
-
-    let r = flags_from_vec(svec!["deno", "upgrade", "--rc", "--canary"]);
-    assert!(r.is_err());
-
-    let r = flags_from_vec(svec!["deno", "upgrade", "--rc", "--version"]);
-    assert!(r.is_err());
   }
 
   #[test]
-  fn cache_with_cafile() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "cache",
-      "--cert",
-      "example.crt",
-      "script.ts",
-      "script_two.ts"
-    ]);
+  fn task_following_double_hyphen_arg() {
+    let r = flags_from_vec(svec!["deno", "task", "build", "--test"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Cache(CacheFlags {
-          files: svec!["script.ts", "script_two.ts"],
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("build".to_string()),
+          is_run: false,
         }),
-        ca_data: Some(CaData::File("example.crt".to_owned())),
+        argv: svec!["--test"],
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn info_with_cafile() {
-    let r = flags_from_vec(svec![
-      "deno",
-      "info",
-      "--cert",
-      "example.crt",
-      "https://example.com"
-    ]);
+  fn task_with_global_flags() {
+    // can fail if the custom parser in task_parse() starts at the wrong index
+    let r = flags_from_vec(svec!["deno", "--quiet", "task", "build"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Info(InfoFlags {
-          json: false,
-          file: Some("https://example.com".to_string()),
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: Some("build".to_string()),
+          is_run: false,
         }),
-        ca_data: Some(CaData::File("example.crt".to_owned())),
+        log_level: Some(log::Level::Error),
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn doc() {
-    let r = flags_from_vec(svec!["deno", "doc", "--json", "path/to/module.ts"]);
+  fn task_subcommand_empty() {
+    let r = flags_from_vec(svec!["deno", "task"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: false,
-          json: true,
-          html: None,
-          lint: false,
-          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
-          filter: None,
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: None,
+          is_run: false,
         }),
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec!["deno", "doc", "--html", "path/to/module.ts"]);
-    assert!(r.is_ok());
-
-    let r = flags_from_vec(svec![
-      "deno",
-      "doc",
-      "--html",
-      "--name=My library",
-      "path/to/module.ts"
-    ]);
+  #[test]
+  fn task_subcommand_config() {
+    let r = flags_from_vec(svec!["deno", "task", "--config", "deno.jsonc"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: false,
-          json: false,
-          lint: false,
-          html: Some(DocHtmlFlag {
-            name: Some("My library".to_string()),
-            category_docs_path: None,
-            symbol_redirect_map_path: None,
-            default_symbol_map_path: None,
-            strip_trailing_html: false,
-            output: String::from("./docs/"),
-          }),
-          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
-          filter: None,
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: None,
+          is_run: false,
         }),
+        config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "doc",
-      "--html",
-      "--name=My library",
-      "--lint",
-      "--output=./foo",
-      "path/to/module.ts"
-    ]);
+  #[test]
+  fn task_subcommand_config_short() {
+    let r = flags_from_vec(svec!["deno", "task", "-c", "deno.jsonc"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: false,
-          json: false,
-          html: Some(DocHtmlFlag {
-            name: Some("My library".to_string()),
-            category_docs_path: None,
-            symbol_redirect_map_path: None,
-            default_symbol_map_path: None,
-            strip_trailing_html: false,
-            output: String::from("./foo"),
-          }),
-          lint: true,
-          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.ts"]),
-          filter: None,
+        subcommand: DenoSubcommand::Task(TaskFlags {
+          cwd: None,
+          task: None,
+          is_run: false,
         }),
+        config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
         ..Flags::default()
       }
     );
+  }
 
-    let r =
-      flags_from_vec(svec!["deno", "doc", "--html", "--name=My library",]);
-    assert!(r.is_err());
+  #[test]
+  fn task_subcommand_noconfig_invalid() {
+    let r = flags_from_vec(svec!["deno", "task", "--no-config"]);
+    assert_eq!(
+      r.unwrap_err().kind(),
+      clap::error::ErrorKind::UnknownArgument
+    );
+  }
 
+  #[test]
+  fn bench_with_flags() {
     let r = flags_from_vec(svec![
       "deno",
-      "doc",
+      "bench",
+      "--json",
+      "--no-npm",
+      "--no-remote",
+      "--no-run",
       "--filter",
-      "SomeClass.someField",
-      "path/to/module.ts",
+      "- foo",
+      "--location",
+      "https:foo",
+      "--allow-net",
+      "dir1/",
+      "dir2/",
+      "--",
+      "arg1",
+      "arg2"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: false,
-          json: false,
-          html: None,
-          lint: false,
-          source_files: DocSourceFileFlag::Paths(vec![
-            "path/to/module.ts".to_string()
-          ]),
-          filter: Some("SomeClass.someField".to_string()),
+        subcommand: DenoSubcommand::Bench(BenchFlags {
+          filter: Some("- foo".to_string()),
+          reporter: BenchReporterConfig::Json,
+          output: None,
+          export_csv: None,
+          no_run: true,
+          files: FileFlags {
+            include: vec!["dir1/".to_string(), "dir2/".to_string()],
+            ignore: vec![],
+          },
+          watch: Default::default(),
+          engine: Default::default(),
         }),
+        no_npm: true,
+        no_remote: true,
+        type_check_mode: TypeCheckMode::Local,
+        location: Some(Url::parse("https://foo/").unwrap()),
+        permissions: PermissionFlags {
+          allow_net: Some(vec![]),
+          no_prompt: true,
+          ..Default::default()
+        },
+        argv: svec!["arg1", "arg2"],
         ..Flags::default()
       }
     );
+  }
 
-    let r = flags_from_vec(svec!["deno", "doc"]);
+  #[test]
+  fn bench_watch() {
+    let r = flags_from_vec(svec!["deno", "bench", "--watch"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: false,
-          json: false,
-          html: None,
-          lint: false,
-          source_files: Default::default(),
+        subcommand: DenoSubcommand::Bench(BenchFlags {
           filter: None,
+          reporter: BenchReporterConfig::Pretty,
+          output: None,
+          export_csv: None,
+          no_run: false,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          watch: Some(Default::default()),
+          engine: Default::default(),
         }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn bench_export_csv() {
     let r = flags_from_vec(svec![
       "deno",
-      "doc",
-      "--filter",
-      "Deno.Listener",
-      "--builtin"
-    ]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: false,
-          lint: false,
-          json: false,
-          html: None,
-          source_files: DocSourceFileFlag::Builtin,
-          filter: Some("Deno.Listener".to_string()),
-        }),
-        ..Flags::default()
-      }
-    );
-
-    let r = flags_from_vec(svec![
-      "deno",
-      "doc",
-      "--no-npm",
-      "--no-remote",
-      "--private",
-      "path/to/module.js"
+      "bench",
+      "--export-csv=samples.csv",
+      "bench.ts"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: true,
-          lint: false,
-          json: false,
-          html: None,
-          source_files: DocSourceFileFlag::Paths(svec!["path/to/module.js"]),
+        subcommand: DenoSubcommand::Bench(BenchFlags {
           filter: None,
+          reporter: BenchReporterConfig::Pretty,
+          output: None,
+          export_csv: Some("samples.csv".to_string()),
+          no_run: false,
+          files: FileFlags {
+            include: vec!["bench.ts".to_string()],
+            ignore: vec![],
+          },
+          watch: None,
+          engine: Default::default(),
         }),
-        no_npm: true,
-        no_remote: true,
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
+  }
 
+  #[test]
+  fn bench_reporter() {
     let r = flags_from_vec(svec![
       "deno",
-      "doc",
-      "path/to/module.js",
-      "path/to/module2.js"
+      "bench",
+      "--reporter=junit",
+      "--output=report.xml",
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: false,
-          lint: false,
-          json: false,
-          html: None,
-          source_files: DocSourceFileFlag::Paths(vec![
-            "path/to/module.js".to_string(),
-            "path/to/module2.js".to_string()
-          ]),
+        subcommand: DenoSubcommand::Bench(BenchFlags {
           filter: None,
+          reporter: BenchReporterConfig::Junit,
+          output: Some("report.xml".to_string()),
+          export_csv: None,
+          no_run: false,
+          files: FileFlags {
+            include: vec![],
+            ignore: vec![],
+          },
+          watch: None,
+          engine: Default::default(),
         }),
+        permissions: PermissionFlags {
+          no_prompt: true,
+          ..Default::default()
+        },
+        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
 
+    let r = flags_from_vec(svec!["deno", "bench", "--reporter=csv"]);
+    assert!(matches!(
+      r.unwrap().subcommand,
+      DenoSubcommand::Bench(BenchFlags {
+        reporter: BenchReporterConfig::Csv,
+        output: None,
+        ..
+      })
+    ));
+
+    let r =
+      flags_from_vec(svec!["deno", "bench", "--json", "--reporter=junit"]);
+    assert_eq!(r.unwrap_err().kind(), ErrorKind::ArgumentConflict);
+  }
+
+  #[test]
+  fn bench_engine_options() {
     let r = flags_from_vec(svec![
       "deno",
-      "doc",
-      "path/to/module.js",
-      "--builtin",
-      "path/to/module2.js"
+      "bench",
+      "--warmup=100",
+      "--min-samples=50",
+      "--max-time=2000",
+      "--outliers=iqr",
     ]);
+    let DenoSubcommand::Bench(bench_flags) = r.unwrap().subcommand else {
+      unreachable!()
+    };
     assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: false,
-          json: false,
-          html: None,
-          lint: false,
-          source_files: DocSourceFileFlag::Paths(vec![
-            "path/to/module.js".to_string(),
-            "path/to/module2.js".to_string()
-          ]),
-          filter: None,
-        }),
-        ..Flags::default()
+      bench_flags.engine,
+      BenchEngineOptions {
+        warmup: 100,
+        min_samples: 50,
+        max_time_ms: 2000,
+        outliers: BenchOutliers::Iqr,
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "doc", "--lint",]);
+    let r = flags_from_vec(svec!["deno", "bench"]);
+    let DenoSubcommand::Bench(bench_flags) = r.unwrap().subcommand else {
+      unreachable!()
+    };
+    assert_eq!(bench_flags.engine, BenchEngineOptions::default());
+
+    let r = flags_from_vec(svec!["deno", "bench", "--min-samples=0"]);
     assert!(r.is_err());
+  }
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "doc",
-      "--lint",
-      "path/to/module.js",
-      "path/to/module2.js"
-    ]);
+  #[test]
+  fn run_with_check() {
+    let r = flags_from_vec(svec!["deno", "run", "--check", "script.ts",]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Doc(DocFlags {
-          private: false,
-          lint: true,
-          json: false,
-          html: None,
-          source_files: DocSourceFileFlag::Paths(vec![
-            "path/to/module.js".to_string(),
-            "path/to/module2.js".to_string()
-          ]),
-          filter: None,
-        }),
+        subcommand: DenoSubcommand::Run(RunFlags::new_default(
+          "script.ts".to_string(),
+        )),
+        type_check_mode: TypeCheckMode::Local,
+        code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn inspect_default_host() {
-    let r = flags_from_vec(svec!["deno", "run", "--inspect", "foo.js"]);
+    let r = flags_from_vec(svec!["deno", "run", "--check=all", "script.ts",]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "foo.js".to_string(),
+          "script.ts".to_string(),
         )),
-        inspect: Some("127.0.0.1:9229".parse().unwrap()),
+        type_check_mode: TypeCheckMode::All,
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn inspect_wait() {
-    let r = flags_from_vec(svec!["deno", "--inspect-wait", "foo.js"]);
+    let r = flags_from_vec(svec!["deno", "--check=foo", "script.ts",]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags {
-          script: "foo.js".to_string(),
+          script: "script.ts".to_string(),
           watch: None,
           bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
         }),
-        inspect_wait: Some("127.0.0.1:9229".parse().unwrap()),
+        type_check_mode: TypeCheckMode::None,
         code_cache_enabled: true,
         ..Flags::default()
       }
@@ -9873,811 +15944,914 @@ mod tests {
     let r = flags_from_vec(svec![
       "deno",
       "run",
-      "--inspect-wait=127.0.0.1:3567",
-      "foo.js"
+      "--no-check",
+      "--check",
+      "script.ts",
     ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn no_config() {
+    let r = flags_from_vec(svec!["deno", "run", "--no-config", "script.ts",]);
     assert_eq!(
       r.unwrap(),
       Flags {
         subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "foo.js".to_string(),
+          "script.ts".to_string(),
         )),
-        inspect_wait: Some("127.0.0.1:3567".parse().unwrap()),
+        config_flag: ConfigFlag::Disabled,
         code_cache_enabled: true,
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn compile() {
     let r = flags_from_vec(svec![
       "deno",
-      "compile",
-      "https://examples.deno.land/color-logging.ts"
+      "run",
+      "--config",
+      "deno.json",
+      "--no-config",
+      "script.ts",
     ]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn init() {
+    let r = flags_from_vec(svec!["deno", "init"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Compile(CompileFlags {
-          source_file: "https://examples.deno.land/color-logging.ts"
-            .to_string(),
-          output: None,
-          args: vec![],
-          target: None,
-          no_terminal: false,
-          icon: None,
-          include: vec![]
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: None,
+          lib: false,
+          serve: false,
+          npm_compat: false,
+          template: None,
         }),
-        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn compile_with_flags() {
-    #[rustfmt::skip]
-    let r = flags_from_vec(svec!["deno", "compile", "--import-map", "import_map.json", "--no-remote", "--config", "tsconfig.json", "--no-check", "--unsafely-ignore-certificate-errors", "--reload", "--lock", "lock.json", "--cert", "example.crt", "--cached-only", "--location", "https:foo", "--allow-read", "--allow-net", "--v8-flags=--help", "--seed", "1", "--no-terminal", "--icon", "favicon.ico", "--output", "colors", "--env=.example.env", "https://examples.deno.land/color-logging.ts", "foo", "bar", "-p", "8080"]);
+    let r = flags_from_vec(svec!["deno", "init", "foo"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Compile(CompileFlags {
-          source_file: "https://examples.deno.land/color-logging.ts"
-            .to_string(),
-          output: Some(String::from("colors")),
-          args: svec!["foo", "bar", "-p", "8080"],
-          target: None,
-          no_terminal: true,
-          icon: Some(String::from("favicon.ico")),
-          include: vec![]
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: Some(String::from("foo")),
+          lib: false,
+          serve: false,
+          npm_compat: false,
+          template: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "init", "--quiet"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: None,
+          lib: false,
+          serve: false,
+          npm_compat: false,
+          template: None,
+        }),
+        log_level: Some(Level::Error),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "init", "--lib"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: None,
+          lib: true,
+          serve: false,
+          npm_compat: false,
+          template: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "init", "--serve"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: None,
+          lib: false,
+          serve: true,
+          npm_compat: false,
+          template: None,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec!["deno", "init", "foo", "--lib"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: Some(String::from("foo")),
+          lib: true,
+          serve: false,
+          npm_compat: false,
+          template: None,
         }),
-        import_map_path: Some("import_map.json".to_string()),
-        no_remote: true,
-        config_flag: ConfigFlag::Path("tsconfig.json".to_owned()),
-        type_check_mode: TypeCheckMode::None,
-        reload: true,
-        lock: Some(String::from("lock.json")),
-        ca_data: Some(CaData::File("example.crt".to_string())),
-        cached_only: true,
-        location: Some(Url::parse("https://foo/").unwrap()),
-        permissions: PermissionFlags {
-          allow_read: Some(vec![]),
-          allow_net: Some(vec![]),
-          ..Default::default()
-        },
-        unsafely_ignore_certificate_errors: Some(vec![]),
-        v8_flags: svec!["--help", "--random-seed=1"],
-        seed: Some(1),
-        env_file: Some(".example.env".to_owned()),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn coverage() {
-    let r = flags_from_vec(svec!["deno", "coverage", "foo.json"]);
+    let r = flags_from_vec(svec!["deno", "init", "--template", "serve"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Coverage(CoverageFlags {
-          files: FileFlags {
-            include: vec!["foo.json".to_string()],
-            ignore: vec![],
-          },
-          include: vec![r"^file:".to_string()],
-          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
-          ..CoverageFlags::default()
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: None,
+          lib: false,
+          serve: true,
+          npm_compat: false,
+          template: None,
         }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn coverage_with_lcov_and_out_file() {
     let r = flags_from_vec(svec![
       "deno",
-      "coverage",
-      "--lcov",
-      "--output=foo.lcov",
-      "foo.json"
+      "init",
+      "foo",
+      "--template",
+      "https://example.com/template.git"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Coverage(CoverageFlags {
-          files: FileFlags {
-            include: vec!["foo.json".to_string()],
-            ignore: vec![],
-          },
-          include: vec![r"^file:".to_string()],
-          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
-          r#type: CoverageType::Lcov,
-          output: Some(String::from("foo.lcov")),
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: Some(String::from("foo")),
+          lib: false,
+          serve: false,
+          npm_compat: false,
+          template: Some(String::from("https://example.com/template.git")),
         }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn coverage_with_default_files() {
-    let r = flags_from_vec(svec!["deno", "coverage",]);
+    let r =
+      flags_from_vec(svec!["deno", "init", "--lib", "--template", "fresh"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "init", "--npm-compat"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Coverage(CoverageFlags {
-          files: FileFlags {
-            include: vec!["coverage".to_string()],
-            ignore: vec![],
-          },
-          include: vec![r"^file:".to_string()],
-          exclude: vec![r"test\.(js|mjs|ts|jsx|tsx)$".to_string()],
-          ..CoverageFlags::default()
+        subcommand: DenoSubcommand::Init(InitFlags {
+          dir: None,
+          lib: false,
+          serve: false,
+          npm_compat: true,
+          template: None,
         }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn location_with_bad_scheme() {
-    #[rustfmt::skip]
-    let r = flags_from_vec(svec!["deno", "run", "--location", "foo:", "mod.ts"]);
+    let r = flags_from_vec(svec!["deno", "init", "--npm-compat", "--serve"]);
     assert!(r.is_err());
-    assert!(r
-      .unwrap_err()
-      .to_string()
-      .contains("Expected protocol \"http\" or \"https\""));
   }
 
   #[test]
-  fn test_config_path_args() {
-    let flags = flags_from_vec(svec!["deno", "run", "foo.js"]).unwrap();
-    let cwd = std::env::current_dir().unwrap();
-
-    assert_eq!(flags.config_path_args(&cwd), Some(vec![cwd.clone()]));
-
-    let flags = flags_from_vec(svec!["deno", "run", "sub_dir/foo.js"]).unwrap();
-    let cwd = std::env::current_dir().unwrap();
+  fn jupyter() {
+    let r = flags_from_vec(svec!["deno", "jupyter"]);
     assert_eq!(
-      flags.config_path_args(&cwd),
-      Some(vec![cwd.join("sub_dir").clone()])
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
+          install: false,
+          kernel: false,
+          conn_file: None,
+          name: None,
+          display_name: None,
+          kernel_args: vec![],
+          assets_dir: None,
+        }),
+        ..Flags::default()
+      }
     );
 
-    let flags =
-      flags_from_vec(svec!["deno", "https://example.com/foo.js"]).unwrap();
-    assert_eq!(flags.config_path_args(&cwd), None);
-
-    let flags =
-      flags_from_vec(svec!["deno", "lint", "dir/a/a.js", "dir/b/b.js"])
-        .unwrap();
+    let r = flags_from_vec(svec!["deno", "jupyter", "--install"]);
     assert_eq!(
-      flags.config_path_args(&cwd),
-      Some(vec![cwd.join("dir/a/a.js"), cwd.join("dir/b/b.js")])
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
+          install: true,
+          kernel: false,
+          conn_file: None,
+          name: None,
+          display_name: None,
+          kernel_args: vec![],
+          assets_dir: None,
+        }),
+        ..Flags::default()
+      }
     );
 
-    let flags = flags_from_vec(svec!["deno", "lint"]).unwrap();
-    assert_eq!(flags.config_path_args(&cwd), Some(vec![cwd.clone()]));
-
-    let flags = flags_from_vec(svec![
+    let r = flags_from_vec(svec![
       "deno",
-      "fmt",
-      "dir/a/a.js",
-      "dir/a/a2.js",
-      "dir/b.js"
-    ])
-    .unwrap();
-    assert_eq!(
-      flags.config_path_args(&cwd),
-      Some(vec![
-        cwd.join("dir/a/a.js"),
-        cwd.join("dir/a/a2.js"),
-        cwd.join("dir/b.js")
-      ])
-    );
-  }
-
-  #[test]
-  fn test_no_clear_watch_flag_without_watch_flag() {
-    let r = flags_from_vec(svec!["deno", "run", "--no-clear-screen", "foo.js"]);
-    assert!(r.is_err());
-    let error_message = r.unwrap_err().to_string();
-    assert!(&error_message
-      .contains("error: the following required arguments were not provided:"));
-    assert!(&error_message.contains("--watch[=<FILES>...]"));
-  }
-
-  #[test]
-  fn task_subcommand() {
-    let r = flags_from_vec(svec!["deno", "task", "build", "hello", "world",]);
+      "jupyter",
+      "--kernel",
+      "--conn",
+      "path/to/conn/file"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Task(TaskFlags {
-          cwd: None,
-          task: Some("build".to_string()),
-          is_run: false,
+        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
+          install: false,
+          kernel: true,
+          conn_file: Some(String::from("path/to/conn/file")),
+          name: None,
+          display_name: None,
+          kernel_args: vec![],
+          assets_dir: None,
         }),
-        argv: svec!["hello", "world"],
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "task", "build"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "jupyter",
+      "--install",
+      "--conn",
+      "path/to/conn/file"
+    ]);
+    r.unwrap_err();
+    let r = flags_from_vec(svec!["deno", "jupyter", "--kernel",]);
+    r.unwrap_err();
+    let r = flags_from_vec(svec!["deno", "jupyter", "--install", "--kernel",]);
+    r.unwrap_err();
+  }
+
+  #[test]
+  fn jupyter_assets_dir() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "jupyter",
+      "--kernel",
+      "--conn",
+      "path/to/conn/file",
+      "--assets-dir=outputs"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Task(TaskFlags {
-          cwd: None,
-          task: Some("build".to_string()),
-          is_run: false,
+        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
+          install: false,
+          kernel: true,
+          conn_file: Some(String::from("path/to/conn/file")),
+          name: None,
+          display_name: None,
+          kernel_args: vec![],
+          assets_dir: Some("outputs".to_string()),
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "task", "--cwd", "foo", "build"]);
+    let r = flags_from_vec(svec!["deno", "jupyter", "--assets-dir=outputs"]);
+    r.unwrap_err();
+  }
+
+  #[test]
+  fn jupyter_install_kernelspec() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "jupyter",
+      "--install",
+      "--name=Deno-Sandboxed",
+      "--display-name",
+      "Deno (sandboxed)",
+      "--kernel-arg=--allow-net=example.com",
+      "--kernel-arg=--config=deno.json",
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Task(TaskFlags {
-          cwd: Some("foo".to_string()),
-          task: Some("build".to_string()),
-          is_run: false,
+        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
+          install: true,
+          kernel: false,
+          conn_file: None,
+          name: Some("deno-sandboxed".to_string()),
+          display_name: Some("Deno (sandboxed)".to_string()),
+          kernel_args: svec!["--allow-net=example.com", "--config=deno.json"],
+          assets_dir: None,
         }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn task_subcommand_double_hyphen() {
     let r = flags_from_vec(svec![
       "deno",
-      "task",
-      "-c",
-      "deno.json",
-      "build",
-      "--",
-      "hello",
-      "world",
+      "jupyter",
+      "--kernel",
+      "--conn",
+      "path/to/conn/file",
+      "--allow-net=example.com",
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Task(TaskFlags {
-          cwd: None,
-          task: Some("build".to_string()),
-          is_run: false,
+        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
+          install: false,
+          kernel: true,
+          conn_file: Some(String::from("path/to/conn/file")),
+          name: None,
+          display_name: None,
+          kernel_args: vec![],
+          assets_dir: None,
         }),
-        argv: svec!["--", "hello", "world"],
-        config_flag: ConfigFlag::Path("deno.json".to_owned()),
+        permissions: PermissionFlags {
+          allow_net: Some(svec!["example.com"]),
+          ..Default::default()
+        },
         ..Flags::default()
       }
     );
 
+    let r = flags_from_vec(svec!["deno", "jupyter", "--name=my kernel"]);
+    r.unwrap_err();
+    let r = flags_from_vec(svec!["deno", "jupyter", "--name=deno-2"]);
+    r.unwrap_err();
+    let r = flags_from_vec(svec!["deno", "jupyter", "--allow-all"]);
+    r.unwrap_err();
+  }
+
+  #[test]
+  fn publish_args() {
     let r = flags_from_vec(svec![
-      "deno", "task", "--cwd", "foo", "build", "--", "hello", "world"
+      "deno",
+      "publish",
+      "--no-provenance",
+      "--dry-run",
+      "--allow-slow-types",
+      "--allow-dirty",
+      "--token=asdf",
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Task(TaskFlags {
-          cwd: Some("foo".to_string()),
-          task: Some("build".to_string()),
-          is_run: false,
+        subcommand: DenoSubcommand::Publish(PublishFlags {
+          token: Some("asdf".to_string()),
+          dry_run: true,
+          allow_slow_types: true,
+          allow_dirty: true,
+          no_provenance: true,
+          provenance_file: None,
+          registry: None,
+          set_version: None,
+          tag: false,
+          changed_since: None,
+          diff_previous: false,
         }),
-        argv: svec!["--", "hello", "world"],
+        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn task_subcommand_double_hyphen_only() {
-    // edge case, but it should forward
-    let r = flags_from_vec(svec!["deno", "task", "build", "--"]);
+  fn publish_registry_and_provenance_file() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "publish",
+      "--registry=https://jsr.example.com/registry",
+      "--provenance-file=bundle.json",
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Task(TaskFlags {
-          cwd: None,
-          task: Some("build".to_string()),
-          is_run: false,
+        subcommand: DenoSubcommand::Publish(PublishFlags {
+          token: None,
+          dry_run: false,
+          allow_slow_types: false,
+          allow_dirty: false,
+          no_provenance: false,
+          provenance_file: Some("bundle.json".to_string()),
+          registry: Some(
+            Url::parse("https://jsr.example.com/registry/").unwrap()
+          ),
+          set_version: None,
+          tag: false,
+          changed_since: None,
+          diff_previous: false,
         }),
-        argv: svec!["--"],
+        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "publish",
+      "--no-provenance",
+      "--provenance-file=bundle.json",
+    ]);
+    r.unwrap_err();
+
+    let r = flags_from_vec(svec!["deno", "publish", "--registry=ftp://jsr"]);
+    r.unwrap_err();
   }
 
   #[test]
-  fn task_following_arg() {
-    let r = flags_from_vec(svec!["deno", "task", "build", "-1", "--test"]);
+  fn publish_set_version() {
+    let r =
+      flags_from_vec(svec!["deno", "publish", "--set-version=minor", "--tag"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Task(TaskFlags {
-          cwd: None,
-          task: Some("build".to_string()),
-          is_run: false,
+        subcommand: DenoSubcommand::Publish(PublishFlags {
+          token: None,
+          dry_run: false,
+          allow_slow_types: false,
+          allow_dirty: false,
+          no_provenance: false,
+          provenance_file: None,
+          registry: None,
+          set_version: Some("minor".to_string()),
+          tag: true,
+          changed_since: None,
+          diff_previous: false,
         }),
-        argv: svec!["-1", "--test"],
+        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
+
+    let r =
+      flags_from_vec(svec!["deno", "publish", "--set-version=1.2.0-rc.1"]);
+    assert!(r.is_ok());
+    let r = flags_from_vec(svec!["deno", "publish", "--set-version=next"]);
+    r.unwrap_err();
+    let r = flags_from_vec(svec!["deno", "publish", "--tag"]);
+    r.unwrap_err();
   }
 
   #[test]
-  fn task_following_double_hyphen_arg() {
-    let r = flags_from_vec(svec!["deno", "task", "build", "--test"]);
+  fn publish_changed_since() {
+    let r = flags_from_vec(svec!["deno", "publish", "--changed-since=v1.0.0"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Task(TaskFlags {
-          cwd: None,
-          task: Some("build".to_string()),
-          is_run: false,
+        subcommand: DenoSubcommand::Publish(PublishFlags {
+          token: None,
+          dry_run: false,
+          allow_slow_types: false,
+          allow_dirty: false,
+          no_provenance: false,
+          provenance_file: None,
+          registry: None,
+          set_version: None,
+          tag: false,
+          changed_since: Some("v1.0.0".to_string()),
+          diff_previous: false,
         }),
-        argv: svec!["--test"],
+        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
   }
 
   #[test]
-  fn task_with_global_flags() {
-    // can fail if the custom parser in task_parse() starts at the wrong index
-    let r = flags_from_vec(svec!["deno", "--quiet", "task", "build"]);
+  fn publish_diff_previous() {
+    let r =
+      flags_from_vec(svec!["deno", "publish", "--dry-run", "--diff-previous"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Task(TaskFlags {
-          cwd: None,
-          task: Some("build".to_string()),
-          is_run: false,
+        subcommand: DenoSubcommand::Publish(PublishFlags {
+          token: None,
+          dry_run: true,
+          allow_slow_types: false,
+          allow_dirty: false,
+          no_provenance: false,
+          provenance_file: None,
+          registry: None,
+          set_version: None,
+          tag: false,
+          changed_since: None,
+          diff_previous: true,
         }),
-        log_level: Some(log::Level::Error),
+        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
+    let r = flags_from_vec(svec!["deno", "publish", "--diff-previous"]);
+    r.unwrap_err();
   }
 
   #[test]
-  fn task_subcommand_empty() {
-    let r = flags_from_vec(svec!["deno", "task"]);
+  fn add_or_install_subcommand() {
+    let r = flags_from_vec(svec!["deno", "add"]);
+    r.unwrap_err();
+    for cmd in ["add", "install"] {
+      let mk_flags = |flags: AddFlags| -> Flags {
+        match cmd {
+          "add" => Flags {
+            subcommand: DenoSubcommand::Add(flags),
+            ..Flags::default()
+          },
+          "install" => Flags {
+            subcommand: DenoSubcommand::Install(InstallFlags {
+              kind: InstallKind::Local(InstallFlagsLocal::Add(flags)),
+            }),
+            ..Flags::default()
+          },
+          _ => unreachable!(),
+        }
+      };
+
+      let r = flags_from_vec(svec!["deno", cmd, "@david/which"]);
+      assert_eq!(
+        r.unwrap(),
+        mk_flags(AddFlags {
+          packages: svec!["@david/which"],
+          dev: false,
+          target: WorkspaceMemberTarget::Current,
+        }) // default is false
+      );
+
+      let r = flags_from_vec(svec!["deno", cmd, "@david/which", "@luca/hello"]);
+      assert_eq!(
+        r.unwrap(),
+        mk_flags(AddFlags {
+          packages: svec!["@david/which", "@luca/hello"],
+          dev: false,
+          target: WorkspaceMemberTarget::Current,
+        })
+      );
+
+      let r = flags_from_vec(svec!["deno", cmd, "--dev", "npm:chalk"]);
+      assert_eq!(
+        r.unwrap(),
+        mk_flags(AddFlags {
+          packages: svec!["npm:chalk"],
+          dev: true,
+          target: WorkspaceMemberTarget::Current,
+        }),
+      );
+    }
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "add",
+      "--member",
+      "@scope/server",
+      "npm:chalk"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Task(TaskFlags {
-          cwd: None,
-          task: None,
-          is_run: false,
+        subcommand: DenoSubcommand::Add(AddFlags {
+          packages: svec!["npm:chalk"],
+          dev: false,
+          target: WorkspaceMemberTarget::Member("@scope/server".to_string()),
         }),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn task_subcommand_config() {
-    let r = flags_from_vec(svec!["deno", "task", "--config", "deno.jsonc"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "add",
+      "--all-members",
+      "--dev",
+      "jsr:@std/assert"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Task(TaskFlags {
-          cwd: None,
-          task: None,
-          is_run: false,
+        subcommand: DenoSubcommand::Add(AddFlags {
+          packages: svec!["jsr:@std/assert"],
+          dev: true,
+          target: WorkspaceMemberTarget::AllMembers,
         }),
-        config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
         ..Flags::default()
       }
     );
+
+    // installing packages always updates the current configuration file
+    let r =
+      flags_from_vec(svec!["deno", "install", "--member", "a", "npm:chalk"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn task_subcommand_config_short() {
-    let r = flags_from_vec(svec!["deno", "task", "-c", "deno.jsonc"]);
+  fn auth_subcommand() {
+    let r = flags_from_vec(svec!["deno", "auth", "login", "npm"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Task(TaskFlags {
-          cwd: None,
-          task: None,
-          is_run: false,
-        }),
-        config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
+        subcommand: DenoSubcommand::Auth(AuthFlags::Login(AuthLoginFlags {
+          registry: "npm".to_string(),
+          ..Default::default()
+        })),
         ..Flags::default()
       }
     );
-  }
-
-  #[test]
-  fn task_subcommand_noconfig_invalid() {
-    let r = flags_from_vec(svec!["deno", "task", "--no-config"]);
-    assert_eq!(
-      r.unwrap_err().kind(),
-      clap::error::ErrorKind::UnknownArgument
-    );
-  }
 
-  #[test]
-  fn bench_with_flags() {
     let r = flags_from_vec(svec![
       "deno",
-      "bench",
-      "--json",
-      "--no-npm",
-      "--no-remote",
-      "--no-run",
-      "--filter",
-      "- foo",
-      "--location",
-      "https:foo",
-      "--allow-net",
-      "dir1/",
-      "dir2/",
-      "--",
-      "arg1",
-      "arg2"
+      "auth",
+      "login",
+      "--token",
+      "abc",
+      "--no-keychain",
+      "git.example.com"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Bench(BenchFlags {
-          filter: Some("- foo".to_string()),
-          json: true,
-          no_run: true,
-          files: FileFlags {
-            include: vec!["dir1/".to_string(), "dir2/".to_string()],
-            ignore: vec![],
-          },
-          watch: Default::default(),
-        }),
-        no_npm: true,
-        no_remote: true,
-        type_check_mode: TypeCheckMode::Local,
-        location: Some(Url::parse("https://foo/").unwrap()),
-        permissions: PermissionFlags {
-          allow_net: Some(vec![]),
-          no_prompt: true,
-          ..Default::default()
-        },
-        argv: svec!["arg1", "arg2"],
+        subcommand: DenoSubcommand::Auth(AuthFlags::Login(AuthLoginFlags {
+          registry: "git.example.com".to_string(),
+          token: Some("abc".to_string()),
+          no_keychain: true,
+        })),
         ..Flags::default()
       }
     );
-  }
 
-  #[test]
-  fn bench_watch() {
-    let r = flags_from_vec(svec!["deno", "bench", "--watch"]);
+    let r = flags_from_vec(svec!["deno", "auth", "logout", "jsr"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Bench(BenchFlags {
-          filter: None,
-          json: false,
-          no_run: false,
-          files: FileFlags {
-            include: vec![],
-            ignore: vec![],
-          },
-          watch: Some(Default::default()),
+        subcommand: DenoSubcommand::Auth(AuthFlags::Logout {
+          registry: "jsr".to_string(),
         }),
-        permissions: PermissionFlags {
-          no_prompt: true,
-          ..Default::default()
-        },
-        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "auth"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn run_with_check() {
-    let r = flags_from_vec(svec!["deno", "run", "--check", "script.ts",]);
-    assert_eq!(
-      r.unwrap(),
-      Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        type_check_mode: TypeCheckMode::Local,
-        code_cache_enabled: true,
-        ..Flags::default()
-      }
-    );
-
-    let r = flags_from_vec(svec!["deno", "run", "--check=all", "script.ts",]);
+  fn audit_subcommand() {
+    let r = flags_from_vec(svec!["deno", "audit"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        type_check_mode: TypeCheckMode::All,
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Audit(AuditFlags::default()),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "--check=foo", "script.ts",]);
+    let r = flags_from_vec(svec!["deno", "audit", "--json", "--fail-on=high"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags {
-          script: "script.ts".to_string(),
-          watch: None,
-          bare: true,
+        subcommand: DenoSubcommand::Audit(AuditFlags {
+          json: true,
+          fail_on: Some(AuditSeverity::High),
         }),
-        type_check_mode: TypeCheckMode::None,
-        code_cache_enabled: true,
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "run",
-      "--no-check",
-      "--check",
-      "script.ts",
-    ]);
-    assert!(r.is_err());
+    let r = flags_from_vec(svec!["deno", "audit", "--fail-on=severe"]);
+    r.unwrap_err();
   }
 
   #[test]
-  fn no_config() {
-    let r = flags_from_vec(svec!["deno", "run", "--no-config", "script.ts",]);
+  fn compat_subcommand() {
+    let r = flags_from_vec(svec!["deno", "compat"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Run(RunFlags::new_default(
-          "script.ts".to_string(),
-        )),
-        config_flag: ConfigFlag::Disabled,
-        code_cache_enabled: true,
+        subcommand: DenoSubcommand::Compat(CompatFlags::default()),
         ..Flags::default()
       }
     );
 
     let r = flags_from_vec(svec![
       "deno",
-      "run",
-      "--config",
-      "deno.json",
-      "--no-config",
-      "script.ts",
+      "compat",
+      "--json",
+      "src",
+      "node_modules/express"
     ]);
-    assert!(r.is_err());
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Compat(CompatFlags {
+          paths: svec!["src", "node_modules/express"],
+          json: true,
+        }),
+        ..Flags::default()
+      }
+    );
   }
 
   #[test]
-  fn init() {
-    let r = flags_from_vec(svec!["deno", "init"]);
+  fn why_subcommand() {
+    let r = flags_from_vec(svec!["deno", "why", "npm:chalk@^4"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Init(InitFlags {
-          dir: None,
-          lib: false,
-          serve: false,
+        subcommand: DenoSubcommand::Why(WhyFlags {
+          package: "npm:chalk@^4".to_string(),
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "init", "foo"]);
+    let r = flags_from_vec(svec!["deno", "why"]);
+    assert!(r.is_err());
+  }
+
+  #[test]
+  fn config_subcommand() {
+    let r = flags_from_vec(svec!["deno", "config", "check"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Init(InitFlags {
-          dir: Some(String::from("foo")),
-          lib: false,
-          serve: false,
-        }),
+        subcommand: DenoSubcommand::Config(ConfigFlags::Check { json: false }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "init", "--quiet"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "config",
+      "check",
+      "--config=deno.jsonc",
+      "--json"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Init(InitFlags {
-          dir: None,
-          lib: false,
-          serve: false,
-        }),
-        log_level: Some(Level::Error),
+        subcommand: DenoSubcommand::Config(ConfigFlags::Check { json: true }),
+        config_flag: ConfigFlag::Path("deno.jsonc".to_string()),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "init", "--lib"]);
+    let r =
+      flags_from_vec(svec!["deno", "config", "get", "compilerOptions.strict"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Init(InitFlags {
-          dir: None,
-          lib: true,
-          serve: false,
+        subcommand: DenoSubcommand::Config(ConfigFlags::Get {
+          key: "compilerOptions.strict".to_string(),
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "init", "--serve"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "config",
+      "set",
+      "tasks.dev",
+      "deno run main.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Init(InitFlags {
-          dir: None,
-          lib: false,
-          serve: true,
+        subcommand: DenoSubcommand::Config(ConfigFlags::Set {
+          key: "tasks.dev".to_string(),
+          value: "deno run main.ts".to_string(),
+          json: false,
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "init", "foo", "--lib"]);
+    let r = flags_from_vec(svec![
+      "deno",
+      "config",
+      "set",
+      "--json",
+      "compilerOptions.strict",
+      "true"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Init(InitFlags {
-          dir: Some(String::from("foo")),
-          lib: true,
-          serve: false,
+        subcommand: DenoSubcommand::Config(ConfigFlags::Set {
+          key: "compilerOptions.strict".to_string(),
+          value: "true".to_string(),
+          json: true,
         }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec!["deno", "config", "set", "tasks.dev"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec!["deno", "config"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn jupyter() {
-    let r = flags_from_vec(svec!["deno", "jupyter"]);
+  fn lock_subcommand() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "lock",
+      "export",
+      "--import-map",
+      "--output=import_map.json"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
-          install: false,
-          kernel: false,
-          conn_file: None,
+        subcommand: DenoSubcommand::Lock(LockFlags::Export {
+          output: Some("import_map.json".to_string()),
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec!["deno", "jupyter", "--install"]);
+    // the format is required
+    let r = flags_from_vec(svec!["deno", "lock", "export"]);
+    assert!(r.is_err());
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "lock",
+      "prune",
+      "--lock=other.lock",
+      "main.ts"
+    ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
-          install: true,
-          kernel: false,
-          conn_file: None,
+        subcommand: DenoSubcommand::Lock(LockFlags::Prune {
+          files: svec!["main.ts"],
         }),
+        lock: Some("other.lock".to_string()),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "jupyter",
-      "--kernel",
-      "--conn",
-      "path/to/conn/file"
-    ]);
+    let r = flags_from_vec(svec!["deno", "lock", "verify"]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Jupyter(JupyterFlags {
-          install: false,
-          kernel: true,
-          conn_file: Some(String::from("path/to/conn/file")),
+        subcommand: DenoSubcommand::Lock(LockFlags::Verify {
+          files: svec!["."],
         }),
         ..Flags::default()
       }
     );
 
-    let r = flags_from_vec(svec![
-      "deno",
-      "jupyter",
-      "--install",
-      "--conn",
-      "path/to/conn/file"
-    ]);
-    r.unwrap_err();
-    let r = flags_from_vec(svec!["deno", "jupyter", "--kernel",]);
-    r.unwrap_err();
-    let r = flags_from_vec(svec!["deno", "jupyter", "--install", "--kernel",]);
-    r.unwrap_err();
+    let r = flags_from_vec(svec!["deno", "lock"]);
+    assert!(r.is_err());
   }
 
   #[test]
-  fn publish_args() {
+  fn outdated_subcommand() {
+    let r = flags_from_vec(svec!["deno", "outdated"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Outdated(OutdatedFlags::default()),
+        ..Flags::default()
+      }
+    );
+
     let r = flags_from_vec(svec![
       "deno",
-      "publish",
-      "--no-provenance",
-      "--dry-run",
-      "--allow-slow-types",
-      "--allow-dirty",
-      "--token=asdf",
+      "outdated",
+      "--json",
+      "--update",
+      "@std/path",
+      "npm:chalk"
     ]);
     assert_eq!(
       r.unwrap(),
       Flags {
-        subcommand: DenoSubcommand::Publish(PublishFlags {
-          token: Some("asdf".to_string()),
-          dry_run: true,
-          allow_slow_types: true,
-          allow_dirty: true,
-          no_provenance: true,
+        subcommand: DenoSubcommand::Outdated(OutdatedFlags {
+          filters: svec!["@std/path", "npm:chalk"],
+          json: true,
+          update: true,
         }),
-        type_check_mode: TypeCheckMode::Local,
         ..Flags::default()
       }
     );
   }
 
-  #[test]
-  fn add_or_install_subcommand() {
-    let r = flags_from_vec(svec!["deno", "add"]);
-    r.unwrap_err();
-    for cmd in ["add", "install"] {
-      let mk_flags = |flags: AddFlags| -> Flags {
-        match cmd {
-          "add" => Flags {
-            subcommand: DenoSubcommand::Add(flags),
-            ..Flags::default()
-          },
-          "install" => Flags {
-            subcommand: DenoSubcommand::Install(InstallFlags {
-              kind: InstallKind::Local(InstallFlagsLocal::Add(flags)),
-            }),
-            ..Flags::default()
-          },
-          _ => unreachable!(),
-        }
-      };
-
-      let r = flags_from_vec(svec!["deno", cmd, "@david/which"]);
-      assert_eq!(
-        r.unwrap(),
-        mk_flags(AddFlags {
-          packages: svec!["@david/which"],
-          dev: false,
-        }) // default is false
-      );
-
-      let r = flags_from_vec(svec!["deno", cmd, "@david/which", "@luca/hello"]);
-      assert_eq!(
-        r.unwrap(),
-        mk_flags(AddFlags {
-          packages: svec!["@david/which", "@luca/hello"],
-          dev: false,
-        })
-      );
-
-      let r = flags_from_vec(svec!["deno", cmd, "--dev", "npm:chalk"]);
-      assert_eq!(
-        r.unwrap(),
-        mk_flags(AddFlags {
-          packages: svec!["npm:chalk"],
-          dev: true,
-        }),
-      );
-    }
-  }
-
   #[test]
   fn remove_subcommand() {
     let r = flags_from_vec(svec!["deno", "remove"]);
@@ -10689,6 +16863,7 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Remove(RemoveFlags {
           packages: svec!["@david/which"],
+          target: WorkspaceMemberTarget::Current,
         }),
         ..Flags::default()
       }
@@ -10701,10 +16876,52 @@ mod tests {
       Flags {
         subcommand: DenoSubcommand::Remove(RemoveFlags {
           packages: svec!["@david/which", "@luca/hello"],
+          target: WorkspaceMemberTarget::Current,
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "remove",
+      "--member",
+      "packages/server",
+      "@david/which"
+    ]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Remove(RemoveFlags {
+          packages: svec!["@david/which"],
+          target: WorkspaceMemberTarget::Member("packages/server".to_string()),
+        }),
+        ..Flags::default()
+      }
+    );
+
+    let r =
+      flags_from_vec(svec!["deno", "remove", "--all-members", "@david/which"]);
+    assert_eq!(
+      r.unwrap(),
+      Flags {
+        subcommand: DenoSubcommand::Remove(RemoveFlags {
+          packages: svec!["@david/which"],
+          target: WorkspaceMemberTarget::AllMembers,
         }),
         ..Flags::default()
       }
     );
+
+    let r = flags_from_vec(svec![
+      "deno",
+      "remove",
+      "--all-members",
+      "--member",
+      "server",
+      "@david/which"
+    ]);
+    assert!(r.is_err());
   }
 
   #[test]
@@ -10765,6 +16982,8 @@ mod tests {
             Flags {
               subcommand: DenoSubcommand::Cache(CacheFlags {
                 files: svec!["script.ts"],
+                dry_run: false,
+                json: false,
               }),
               allow_scripts: value,
               ..Flags::default()
@@ -10792,6 +17011,11 @@ mod tests {
           script: "script.ts".to_string(),
           watch: None,
           bare: true,
+          ephemeral_tmp: false,
+          restart_on_failure: None,
+          procfile: None,
+          siblings: vec![],
+          ui: false,
         }),
         config_flag: ConfigFlag::Disabled,
         code_cache_enabled: true,
@@ -10810,6 +17034,9 @@ mod tests {
           eval_files: None,
           eval: None,
           is_default_command: true,
+          save_session: None,
+          auto_import: false,
+          repl_server: None,
         }),
         log_level: Some(Level::Debug),
         permissions: PermissionFlags {
@@ -10833,6 +17060,9 @@ mod tests {
           eval_files: None,
           eval: None,
           is_default_command: false,
+          save_session: None,
+          auto_import: false,
+          repl_server: None,
         }),
         argv: svec!["foo"],
         ..Flags::default()
@@ -10918,6 +17148,10 @@ mod tests {
           install: false,
           kernel: false,
           conn_file: None,
+          name: None,
+          display_name: None,
+          kernel_args: vec![],
+          assets_dir: None,
         }),
         unstable_config: UnstableConfig {
           bare_node_builtins: true,
@@ -11015,6 +17249,30 @@ mod tests {
     )
   }
 
+  #[test]
+  fn node_modules_dir_linked() {
+    let r = flags_from_vec(svec![
+      "deno",
+      "run",
+      "--node-modules-dir=linked",
+      "./foo.ts"
+    ]);
+    let flags = r.unwrap();
+    assert_eq!(
+      flags,
+      Flags {
+        subcommand: DenoSubcommand::Run(RunFlags {
+          script: "./foo.ts".into(),
+          ..Default::default()
+        }),
+        node_modules_dir: Some(NodeModulesDirMode::Auto),
+        node_modules_linked: true,
+        code_cache_enabled: true,
+        ..Default::default()
+      }
+    )
+  }
+
   #[test]
   fn flag_before_subcommand() {
     let r = flags_from_vec(svec!["deno", "--allow-net", "repl"]);
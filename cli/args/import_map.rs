@@ -1,5 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
 use deno_core::url::Url;
@@ -22,3 +24,193 @@ pub async fn resolve_import_map_value_from_specifier(
     Ok(serde_json::from_str(&file.source)?)
   }
 }
+
+/// Merges import maps in order, where the entries of the later maps take
+/// precedence over the ones of the earlier maps, for multiple `--import-map`
+/// flags or the `"importMaps"` of a config file.
+///
+/// The relative addresses and specifier keys are resolved against the url of
+/// their own map first, so the merged map can use the url of the last map.
+/// The other top-level keys are kept, where the `"integrity"` objects are
+/// merged like `"imports"` and any other key of a later map replaces the one
+/// of the earlier maps.
+pub fn merge_import_map_values(
+  maps: Vec<(Url, serde_json::Value)>,
+) -> Result<(Url, serde_json::Value), AnyError> {
+  if maps.len() <= 1 {
+    return maps
+      .into_iter()
+      .next()
+      .ok_or_else(|| anyhow!("Expected at least one import map to merge."));
+  }
+  let mut merged_base_url = None;
+  let mut imports = serde_json::Map::new();
+  let mut scopes = serde_json::Map::new();
+  let mut integrity = serde_json::Map::new();
+  let mut other_keys = serde_json::Map::new();
+  for (base_url, value) in maps {
+    let serde_json::Value::Object(mut value) = value else {
+      bail!("Import map \"{}\" must be a JSON object.", base_url);
+    };
+    if let Some(serde_json::Value::Object(map_imports)) =
+      value.remove("imports")
+    {
+      merge_specifier_map(&base_url, &mut imports, map_imports);
+    }
+    if let Some(serde_json::Value::Object(map_scopes)) = value.remove("scopes")
+    {
+      for (scope, scope_imports) in map_scopes {
+        let serde_json::Value::Object(scope_imports) = scope_imports else {
+          continue;
+        };
+        let scope = base_url
+          .join(&scope)
+          .map(|url| url.to_string())
+          .unwrap_or(scope);
+        let entry = scopes
+          .entry(scope)
+          .or_insert_with(|| serde_json::Value::Object(Default::default()));
+        if let serde_json::Value::Object(entry) = entry {
+          merge_specifier_map(&base_url, entry, scope_imports);
+        }
+      }
+    }
+    if let Some(serde_json::Value::Object(map_integrity)) =
+      value.remove("integrity")
+    {
+      merge_specifier_map(&base_url, &mut integrity, map_integrity);
+    }
+    other_keys.extend(value);
+    merged_base_url = Some(base_url);
+  }
+  let mut value = serde_json::Map::new();
+  value.insert("imports".to_string(), serde_json::Value::Object(imports));
+  if !scopes.is_empty() {
+    value.insert("scopes".to_string(), serde_json::Value::Object(scopes));
+  }
+  if !integrity.is_empty() {
+    value.insert(
+      "integrity".to_string(),
+      serde_json::Value::Object(integrity),
+    );
+  }
+  value.extend(other_keys);
+  Ok((merged_base_url.unwrap(), serde_json::Value::Object(value)))
+}
+
+fn merge_specifier_map(
+  base_url: &Url,
+  merged: &mut serde_json::Map<String, serde_json::Value>,
+  map: serde_json::Map<String, serde_json::Value>,
+) {
+  for (key, value) in map {
+    let value = match value {
+      serde_json::Value::String(address) => {
+        serde_json::Value::String(resolve_relative(base_url, address))
+      }
+      value => value,
+    };
+    merged.insert(resolve_relative(base_url, key), value);
+  }
+}
+
+/// Resolves a url-like specifier that is relative to the import map, leaving
+/// other specifiers like bare specifiers or urls untouched.
+fn resolve_relative(base_url: &Url, specifier: String) -> String {
+  if specifier.starts_with("./")
+    || specifier.starts_with("../")
+    || specifier.starts_with('/')
+  {
+    match base_url.join(&specifier) {
+      Ok(url) => url.to_string(),
+      Err(_) => specifier,
+    }
+  } else {
+    specifier
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use deno_core::serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_merge_import_map_values() {
+    let base = Url::parse("file:///shared/import_map.json").unwrap();
+    let project = Url::parse("file:///project/import_map.json").unwrap();
+    let (base_url, value) = merge_import_map_values(vec![
+      (
+        base.clone(),
+        json!({
+          "imports": {
+            "@std/path": "jsr:@std/path@1",
+            "utils/": "./utils/",
+            "./polyfill.ts": "./polyfill_impl.ts",
+          },
+          "scopes": {
+            "./vendor/": { "lodash": "npm:lodash@3" },
+          },
+          "integrity": {
+            "./polyfill_impl.ts": "sha384-base",
+          },
+          "$comment": "shared",
+        }),
+      ),
+      (
+        project.clone(),
+        json!({
+          "imports": {
+            "@std/path": "jsr:@std/path@1.0.8",
+            "app/": "./src/",
+          },
+          "scopes": {
+            "/shared/vendor/": { "chalk": "npm:chalk@5" },
+          },
+          "integrity": {
+            "./src/main.ts": "sha384-project",
+          },
+          "$comment": "project",
+        }),
+      ),
+    ])
+    .unwrap();
+    assert_eq!(base_url, project);
+    assert_eq!(
+      value,
+      json!({
+        "imports": {
+          "@std/path": "jsr:@std/path@1.0.8",
+          "utils/": "file:///shared/utils/",
+          "file:///shared/polyfill.ts": "file:///shared/polyfill_impl.ts",
+          "app/": "file:///project/src/",
+        },
+        "scopes": {
+          "file:///shared/vendor/": {
+            "lodash": "npm:lodash@3",
+            "chalk": "npm:chalk@5",
+          },
+        },
+        "integrity": {
+          "file:///shared/polyfill_impl.ts": "sha384-base",
+          "file:///project/src/main.ts": "sha384-project",
+        },
+        "$comment": "project",
+      })
+    );
+
+    // a single map is left as is
+    let (base_url, value) =
+      merge_import_map_values(vec![(base.clone(), json!({ "imports": {} }))])
+        .unwrap();
+    assert_eq!(base_url, base);
+    assert_eq!(value, json!({ "imports": {} }));
+
+    assert!(merge_import_map_values(vec![
+      (base, json!({})),
+      (project, json!([]))
+    ])
+    .is_err());
+  }
+}
@@ -73,6 +73,10 @@ impl CliLockfile {
     self.lockfile.lock().set_workspace_config(options);
   }
 
+  pub fn frozen(&self) -> bool {
+    self.frozen
+  }
+
   pub fn overwrite(&self) -> bool {
     self.lockfile.lock().overwrite
   }
@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+pub mod credentials;
 pub mod deno_json;
 mod flags;
 mod flags_net;
@@ -28,6 +29,7 @@ use deno_npm::resolution::ValidSerializedNpmResolutionSnapshot;
 use deno_npm::NpmSystemInfo;
 use deno_path_util::normalize_path;
 use deno_semver::npm::NpmPackageReqReference;
+use import_map::merge_import_map_values;
 use import_map::resolve_import_map_value_from_specifier;
 
 pub use deno_config::deno_json::BenchConfig;
@@ -42,6 +44,11 @@ pub use deno_config::deno_json::TsConfigType;
 pub use deno_config::deno_json::TsTypeLib;
 pub use deno_config::glob::FilePatterns;
 pub use deno_json::check_warn_tsconfig;
+pub use deno_json::deno_json_dev_imports;
+pub use deno_json::deno_jsons_with_dev_imports;
+pub use deno_json::expand_task_env_vars;
+pub use deno_json::workspace_dev_import_map;
+pub use deno_json::CliConfigFields;
 pub use flags::*;
 pub use lockfile::CliLockfile;
 pub use lockfile::CliLockfileReadFromPathOptions;
@@ -60,14 +67,17 @@ use deno_runtime::deno_tls::rustls::RootCertStore;
 use deno_runtime::deno_tls::rustls_pemfile;
 use deno_runtime::deno_tls::webpki_roots;
 use deno_runtime::inspector_server::InspectorServer;
+use deno_runtime::ServeTlsOptions;
 use deno_terminal::colors;
-use dotenvy::from_filename;
+use dotenvy::from_filename_iter;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 use serde::Serialize;
 use std::borrow::Cow;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::env;
+use std::ffi::OsStr;
 use std::io::BufReader;
 use std::io::Cursor;
 use std::io::Read;
@@ -77,6 +87,7 @@ use std::num::NonZeroUsize;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use thiserror::Error;
 
 use crate::cache;
@@ -217,6 +228,9 @@ pub enum CacheSetting {
   /// Only the cached files should be used.  Any files not in the cache will
   /// error.  This is the equivalent of `--cached-only` in the CLI.
   Only,
+  /// The same as `Only`, but the CLI doesn't access the network for anything
+  /// else either.  This is the equivalent of `--offline` in the CLI.
+  Offline,
   /// No cached source files should be used, and all files should be reloaded.
   /// This is the equivalent of `--reload` in the CLI.
   ReloadAll,
@@ -234,6 +248,19 @@ pub enum CacheSetting {
 }
 
 impl CacheSetting {
+  /// Whether files not in the cache should error instead of being fetched.
+  pub fn is_cache_only(&self) -> bool {
+    matches!(self, CacheSetting::Only | CacheSetting::Offline)
+  }
+
+  /// The flag to mention in errors about files not being in the cache.
+  pub fn cache_only_flag(&self) -> &'static str {
+    match self {
+      CacheSetting::Offline => "--offline",
+      _ => "--cached-only",
+    }
+  }
+
   pub fn should_use_for_npm_package(&self, package_name: &str) -> bool {
     match self {
       CacheSetting::ReloadAll => false,
@@ -254,16 +281,22 @@ impl CacheSetting {
 
 pub struct WorkspaceBenchOptions {
   pub filter: Option<String>,
-  pub json: bool,
+  pub reporter: BenchReporterConfig,
+  pub output: Option<String>,
+  pub export_csv: Option<String>,
   pub no_run: bool,
+  pub engine: BenchEngineOptions,
 }
 
 impl WorkspaceBenchOptions {
   pub fn resolve(bench_flags: &BenchFlags) -> Self {
     Self {
       filter: bench_flags.filter.clone(),
-      json: bench_flags.json,
+      reporter: bench_flags.reporter,
+      output: bench_flags.output.clone(),
+      export_csv: bench_flags.export_csv.clone(),
       no_run: bench_flags.no_run,
+      engine: bench_flags.engine,
     }
   }
 }
@@ -287,10 +320,41 @@ pub struct UnstableFmtOptions {
   pub component: bool,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FmtLineEnding {
+  Lf,
+  Crlf,
+  /// Keeps the line ending used by the first line of each file.
+  Auto,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FmtBom {
+  #[default]
+  Preserve,
+  Strip,
+}
+
+/// Options applied to the text of every formatted file regardless of its
+/// type. These are configured in the `"fmtFile"` object of the config file,
+/// as the `"fmt"` object only accepts options of the formatters.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct FmtFileOptions {
+  /// Leaves the line endings to the formatters when not set.
+  pub line_ending: Option<FmtLineEnding>,
+  pub bom: FmtBom,
+  /// Languages of the code blocks in Markdown files which are left as is.
+  pub skip_code_blocks: Vec<String>,
+}
+
 #[derive(Clone, Debug)]
 pub struct FmtOptions {
   pub options: FmtOptionsConfig,
   pub unstable: UnstableFmtOptions,
+  pub file: FmtFileOptions,
   pub files: FilePatterns,
 }
 
@@ -305,6 +369,7 @@ impl FmtOptions {
     Self {
       options: FmtOptionsConfig::default(),
       unstable: Default::default(),
+      file: Default::default(),
       files: FilePatterns::new_with_base(base),
     }
   }
@@ -312,6 +377,7 @@ impl FmtOptions {
   pub fn resolve(
     fmt_config: FmtConfig,
     unstable: UnstableFmtOptions,
+    file: FmtFileOptions,
     fmt_flags: &FmtFlags,
   ) -> Self {
     Self {
@@ -319,11 +385,162 @@ impl FmtOptions {
       unstable: UnstableFmtOptions {
         component: unstable.component || fmt_flags.unstable_component,
       },
+      file: resolve_fmt_file_options(fmt_flags, file),
       files: fmt_config.files,
     }
   }
 }
 
+fn resolve_fmt_file_options(
+  fmt_flags: &FmtFlags,
+  mut options: FmtFileOptions,
+) -> FmtFileOptions {
+  if let Some(line_ending) = &fmt_flags.line_ending {
+    options.line_ending = Some(match line_ending.as_str() {
+      "lf" => FmtLineEnding::Lf,
+      "crlf" => FmtLineEnding::Crlf,
+      "auto" => FmtLineEnding::Auto,
+      // validators in `flags.rs` makes other values unreachable
+      _ => unreachable!(),
+    });
+  }
+
+  if let Some(bom) = &fmt_flags.bom {
+    options.bom = match bom.as_str() {
+      "preserve" => FmtBom::Preserve,
+      "strip" => FmtBom::Strip,
+      // validators in `flags.rs` makes other values unreachable
+      _ => unreachable!(),
+    };
+  }
+
+  if let Some(skip_code_blocks) = &fmt_flags.skip_code_blocks {
+    options.skip_code_blocks.clone_from(skip_code_blocks);
+  }
+
+  options
+}
+
+/// The environment variables of `--redact-env` and of the `"redactEnv"` of
+/// the config file.
+fn resolve_redact_env(
+  dir: &WorkspaceDirectory,
+  flags: &Flags,
+) -> Result<Vec<String>, AnyError> {
+  let mut names = flags.redact_env.clone();
+  let Some(deno_json) = dir
+    .maybe_deno_json()
+    .or_else(|| dir.workspace.root_deno_json())
+  else {
+    return Ok(names);
+  };
+  let fields = CliConfigFields::read(deno_json)?;
+  names.extend(fields.redact_env.iter().flatten().cloned());
+  Ok(names)
+}
+
+/// Reads the `"fmtFile"` object of the config file of the directory, falling
+/// back to the one of the workspace root.
+fn resolve_config_fmt_file_options(
+  dir: &WorkspaceDirectory,
+) -> Result<FmtFileOptions, AnyError> {
+  let Some(deno_json) = dir
+    .maybe_deno_json()
+    .or_else(|| dir.workspace.root_deno_json())
+  else {
+    return Ok(FmtFileOptions::default());
+  };
+  let fields = CliConfigFields::read(deno_json)?;
+  Ok(fields.fmt_file.clone().unwrap_or_default())
+}
+
+/// Options of the `spelling` lint rule. These are configured in the
+/// `"lintSpelling"` object of the config file, as the `"lint"` object only
+/// accepts the options known to `deno_lint`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct LintSpellingOptions {
+  /// Project specific words which are never reported.
+  pub words: Vec<String>,
+  pub check_strings: bool,
+}
+
+/// Reads the `"lintSpelling"` object of the config file.
+pub fn resolve_config_lint_spelling_options(
+  deno_json: &ConfigFile,
+) -> Result<LintSpellingOptions, AnyError> {
+  let fields = CliConfigFields::read(deno_json)?;
+  Ok(fields.lint_spelling.clone().unwrap_or_default())
+}
+
+/// Options of the `todo-format` lint rule, which are configured in the
+/// `"lintTodo"` object of the config file.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "camelCase")]
+pub struct LintTodoOptions {
+  /// The keywords of the comments to check, like `TODO`.
+  pub keywords: Vec<String>,
+  /// Require an owner, as in `TODO(owner): description`.
+  pub require_owner: bool,
+  /// Regular expression of the issue reference which must be part of the
+  /// comment. Issues aren't required when it's `null`.
+  pub issue_pattern: Option<String>,
+}
+
+impl Default for LintTodoOptions {
+  fn default() -> Self {
+    Self {
+      keywords: vec!["TODO".to_string(), "FIXME".to_string()],
+      require_owner: true,
+      issue_pattern: Some(r"https?://\S+|#\d+".to_string()),
+    }
+  }
+}
+
+/// Reads the `"lintTodo"` object of the config file.
+pub fn resolve_config_lint_todo_options(
+  deno_json: &ConfigFile,
+) -> Result<LintTodoOptions, AnyError> {
+  let fields = CliConfigFields::read(deno_json)?;
+  let Some(options) = fields.lint_todo.clone() else {
+    return Ok(LintTodoOptions::default());
+  };
+  if let Some(issue_pattern) = &options.issue_pattern {
+    regex::Regex::new(issue_pattern).with_context(|| {
+      format!(
+        "Invalid \"lintTodo.issuePattern\" in {}",
+        deno_json.specifier
+      )
+    })?;
+  }
+  Ok(options)
+}
+
+/// Modules which are run in the worker of every test module, before and
+/// after its tests.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TestFixtures {
+  pub setup: Option<ModuleSpecifier>,
+  pub teardown: Option<ModuleSpecifier>,
+}
+
+/// Resolves the fixture modules of the flags relative to the cwd.
+fn resolve_test_fixtures(
+  test_flags: &TestFlags,
+  cwd: &Path,
+) -> Result<TestFixtures, AnyError> {
+  let resolve = |path: &Option<String>| {
+    path
+      .as_ref()
+      .map(|path| resolve_url_or_path(path, cwd))
+      .transpose()
+  };
+  Ok(TestFixtures {
+    setup: resolve(&test_flags.setup)?,
+    teardown: resolve(&test_flags.teardown)?,
+  })
+}
+
 fn resolve_fmt_options(
   fmt_flags: &FmtFlags,
   mut options: FmtOptionsConfig,
@@ -373,7 +590,45 @@ pub struct WorkspaceTestOptions {
   pub trace_leaks: bool,
   pub reporter: TestReporterConfig,
   pub junit_path: Option<String>,
+  pub reporter_outputs: Vec<TestReporterOutput>,
   pub hide_stacktraces: bool,
+  pub otel: Option<TestOtelOptions>,
+  pub update_snapshots: bool,
+  pub timeout: Option<Duration>,
+  pub slow_threshold: Option<Duration>,
+  pub artifacts_dir: Option<PathBuf>,
+  pub tags: Vec<String>,
+  pub skip_tags: Vec<String>,
+}
+
+/// Where to export OpenTelemetry spans of the test run when `--otel` is
+/// passed, resolved from the standard `OTEL_*` environment variables.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TestOtelOptions {
+  pub traces_endpoint: String,
+  pub service_name: String,
+}
+
+impl TestOtelOptions {
+  fn from_env() -> Self {
+    let traces_endpoint = env::var("OTEL_EXPORTER_OTLP_TRACES_ENDPOINT")
+      .ok()
+      .filter(|e| !e.is_empty())
+      .unwrap_or_else(|| {
+        let endpoint = env::var("OTEL_EXPORTER_OTLP_ENDPOINT")
+          .ok()
+          .filter(|e| !e.is_empty())
+          .unwrap_or_else(|| "http://localhost:4318".to_string());
+        format!("{}/v1/traces", endpoint.trim_end_matches('/'))
+      });
+    Self {
+      traces_endpoint,
+      service_name: env::var("OTEL_SERVICE_NAME")
+        .ok()
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| "deno".to_string()),
+    }
+  }
 }
 
 impl WorkspaceTestOptions {
@@ -391,7 +646,15 @@ impl WorkspaceTestOptions {
       trace_leaks: test_flags.trace_leaks,
       reporter: test_flags.reporter,
       junit_path: test_flags.junit_path.clone(),
+      reporter_outputs: test_flags.reporter_outputs.clone(),
       hide_stacktraces: test_flags.hide_stacktraces,
+      otel: test_flags.otel.then(TestOtelOptions::from_env),
+      update_snapshots: test_flags.update_snapshots,
+      timeout: test_flags.timeout,
+      slow_threshold: test_flags.slow_threshold,
+      artifacts_dir: test_flags.artifacts_dir.as_ref().map(PathBuf::from),
+      tags: test_flags.tags.clone(),
+      skip_tags: test_flags.skip_tags.clone(),
     }
   }
 }
@@ -421,6 +684,8 @@ pub enum LintReporterKind {
 #[derive(Clone, Debug)]
 pub struct WorkspaceLintOptions {
   pub reporter_kind: LintReporterKind,
+  /// File to which the diagnostics are also written as JSON.
+  pub output_file: Option<PathBuf>,
 }
 
 impl WorkspaceLintOptions {
@@ -450,6 +715,7 @@ impl WorkspaceLintOptions {
     }
     Ok(Self {
       reporter_kind: maybe_reporter_kind.unwrap_or_default(),
+      output_file: lint_flags.output_file.as_ref().map(PathBuf::from),
     })
   }
 }
@@ -516,11 +782,15 @@ fn resolve_lint_rules_options(
   }
 }
 
+/// Resolves the `.npmrc` of the workspace, where `maybe_npmrc_path` is the
+/// one passed with `--npmrc`.
 pub fn discover_npmrc_from_workspace(
   workspace: &Workspace,
+  maybe_npmrc_path: Option<PathBuf>,
 ) -> Result<(Arc<ResolvedNpmRc>, Option<PathBuf>), AnyError> {
   let root_folder = workspace.root_folder_configs();
   discover_npmrc(
+    maybe_npmrc_path,
     root_folder.pkg_json.as_ref().map(|p| p.path.clone()),
     root_folder.deno_json.as_ref().and_then(|cf| {
       if cf.specifier.scheme() == "file" {
@@ -538,6 +808,7 @@ pub fn discover_npmrc_from_workspace(
 /// In the future we will need to support it in user directory or global directory
 /// as per https://docs.npmjs.com/cli/v10/configuring-npm/npmrc#files.
 fn discover_npmrc(
+  maybe_npmrc_path: Option<PathBuf>,
   maybe_package_json_path: Option<PathBuf>,
   maybe_deno_json_path: Option<PathBuf>,
 ) -> Result<(Arc<ResolvedNpmRc>, Option<PathBuf>), AnyError> {
@@ -575,12 +846,29 @@ fn discover_npmrc(
     let npmrc = NpmRc::parse(&source, &get_env_var).with_context(|| {
       format!("Failed to parse .npmrc at {}", path.display())
     })?;
+    if has_npmrc_always_auth(&source) {
+      log::warn!(
+        "{} \"always-auth\" in {} is not supported, credentials are only sent to the urls under the registry they are set for",
+        colors::yellow("Warning"),
+        path.display()
+      );
+    }
     let resolved = npmrc
       .as_resolved(npm_registry_url())
       .context("Failed to resolve .npmrc options")?;
     Ok(Arc::new(resolved))
   }
 
+  // 0. Use the file passed with `--npmrc`, which must exist
+  if let Some(path) = maybe_npmrc_path {
+    let source =
+      std::fs::read_to_string(&path).map_err(|source| NpmRcLoadError {
+        path: path.clone(),
+        source,
+      })?;
+    return try_to_parse_npmrc(source, &path).map(|r| (r, Some(path)));
+  }
+
   // 1. Try `.npmrc` next to `package.json`
   if let Some(package_json_path) = maybe_package_json_path {
     if let Some(package_json_dir) = package_json_path.parent() {
@@ -624,6 +912,15 @@ fn discover_npmrc(
   Ok((create_default_npmrc(), None))
 }
 
+/// Whether the `.npmrc` sets `always-auth`, globally or for a registry.
+fn has_npmrc_always_auth(source: &str) -> bool {
+  source.lines().any(|line| {
+    line
+      .split_once('=')
+      .is_some_and(|(key, _)| key.trim().ends_with("always-auth"))
+  })
+}
+
 pub fn create_default_npmrc() -> Arc<ResolvedNpmRc> {
   Arc::new(ResolvedNpmRc {
     default_config: deno_npm::npm_rc::RegistryConfigWithUrl {
@@ -836,7 +1133,12 @@ impl CliOptions {
     )
     .with_context(|| "Resolving node_modules folder.")?;
 
-    load_env_variables_from_env_file(flags.env_file.as_ref());
+    load_env_variables_from_env_files(flags.env_file.as_deref());
+    deno_runtime::deno_io::set_redacted_values(
+      resolve_redact_env(&start_dir, &flags)?
+        .iter()
+        .filter_map(|name| std::env::var(name).ok()),
+    );
 
     Ok(Self {
       flags,
@@ -854,10 +1156,23 @@ impl CliOptions {
   pub fn from_flags(flags: Arc<Flags>) -> Result<Self, AnyError> {
     let initial_cwd =
       std::env::current_dir().with_context(|| "Failed getting cwd.")?;
-    let maybe_vendor_override = flags.vendor.map(|v| match v {
-      true => VendorEnablement::Enable { cwd: &initial_cwd },
-      false => VendorEnablement::Disable,
-    });
+    Self::from_flags_with_cwd(flags, initial_cwd)
+  }
+
+  /// Resolves the options as if the process was started in `initial_cwd`,
+  /// which is used by the check daemon to serve other processes.
+  pub fn from_flags_with_cwd(
+    flags: Arc<Flags>,
+    initial_cwd: PathBuf,
+  ) -> Result<Self, AnyError> {
+    // vendoring npm packages requires the vendor folder
+    let maybe_vendor_override = flags
+      .vendor
+      .or(flags.vendor_npm.filter(|vendor_npm| *vendor_npm))
+      .map(|v| match v {
+        true => VendorEnablement::Enable { cwd: &initial_cwd },
+        false => VendorEnablement::Disable,
+      });
     let resolve_workspace_discover_options = || {
       let additional_config_file_names: &'static [&'static str] =
         if matches!(flags.subcommand, DenoSubcommand::Publish(..)) {
@@ -923,7 +1238,10 @@ impl CliOptions {
       log::warn!("{} {}", colors::yellow("Warning"), diagnostic);
     }
 
-    let (npmrc, _) = discover_npmrc_from_workspace(&start_dir.workspace)?;
+    let (npmrc, _) = discover_npmrc_from_workspace(
+      &start_dir.workspace,
+      flags.npmrc.as_ref().map(|path| initial_cwd.join(path)),
+    )?;
 
     let maybe_lock_file = CliLockfile::discover(&flags, &start_dir.workspace)?;
 
@@ -976,7 +1294,9 @@ impl CliOptions {
   }
 
   pub fn cache_setting(&self) -> CacheSetting {
-    if self.flags.cached_only {
+    if self.flags.offline {
+      CacheSetting::Offline
+    } else if self.flags.cached_only {
       CacheSetting::Only
     } else if !self.flags.cache_blocklist.is_empty() {
       CacheSetting::ReloadSome(self.flags.cache_blocklist.clone())
@@ -1050,6 +1370,75 @@ impl CliOptions {
     }
   }
 
+  /// Resolve the import maps which are merged over the specified import map,
+  /// from the additional `--import-map` flags or otherwise the
+  /// `"importMaps"` array of the root config file.
+  pub fn resolve_import_map_override_specifiers(
+    &self,
+  ) -> Result<Vec<ModuleSpecifier>, AnyError> {
+    if self.overrides.import_map_specifier.is_some() {
+      return Ok(Vec::new());
+    }
+    if self.flags.import_map_path.is_some() {
+      return self
+        .flags
+        .import_map_overrides
+        .iter()
+        .map(|path| {
+          deno_core::resolve_url_or_path(path, &self.initial_cwd)
+            .with_context(|| format!("Bad URL (\"{path}\") for import map."))
+        })
+        .collect();
+    }
+    let Some(deno_json) = self.workspace().root_deno_json() else {
+      return Ok(Vec::new());
+    };
+    let fields = CliConfigFields::read(deno_json)?;
+    fields
+      .import_maps
+      .iter()
+      .flatten()
+      .map(|path| {
+        deno_json
+          .specifier
+          .join(path)
+          .with_context(|| format!("Bad URL (\"{path}\") for import map."))
+      })
+      .collect()
+  }
+
+  /// The base layer of the `"importMaps"` of the root config file, which is
+  /// its `"importMap"` or otherwise its `"imports"` and `"scopes"`.
+  async fn config_base_import_map(
+    &self,
+    file_fetcher: &FileFetcher,
+  ) -> Result<Option<(ModuleSpecifier, serde_json::Value)>, AnyError> {
+    let Some(deno_json) = self.workspace().root_deno_json() else {
+      return Ok(None);
+    };
+    if let Some(import_map) = &deno_json.json.import_map {
+      let specifier = deno_json.specifier.join(import_map)?;
+      let value =
+        resolve_import_map_value_from_specifier(&specifier, file_fetcher)
+          .await
+          .with_context(|| {
+            format!("Unable to load '{}' import map", specifier)
+          })?;
+      return Ok(Some((specifier, value)));
+    }
+    let mut value = serde_json::Map::new();
+    if let Some(imports) = &deno_json.json.imports {
+      value.insert("imports".to_string(), imports.clone());
+    }
+    if let Some(scopes) = &deno_json.json.scopes {
+      value.insert("scopes".to_string(), scopes.clone());
+    }
+    Ok(Some((
+      deno_json.specifier.clone(),
+      serde_json::Value::Object(value),
+    )))
+  }
+
   pub async fn create_workspace_resolver(
     &self,
     file_fetcher: &FileFetcher,
@@ -1061,7 +1450,7 @@ impl CliOptions {
       .as_ref()
       .map(|s| s.is_none())
       == Some(true);
-    let cli_arg_specified_import_map = if overrode_no_import_map {
+    let mut cli_arg_specified_import_map = if overrode_no_import_map {
       // use a fake empty import map
       Some(deno_config::workspace::SpecifiedImportMap {
         base_url: self.workspace().root_dir().join("import_map.json").unwrap(),
@@ -1070,22 +1459,70 @@ impl CliOptions {
     } else {
       let maybe_import_map_specifier =
         self.resolve_specified_import_map_specifier()?;
-      match maybe_import_map_specifier {
-        Some(specifier) => {
-          let value =
-            resolve_import_map_value_from_specifier(&specifier, file_fetcher)
-              .await
-              .with_context(|| {
-                format!("Unable to load '{}' import map", specifier)
-              })?;
-          Some(deno_config::workspace::SpecifiedImportMap {
-            base_url: specifier,
-            value,
-          })
+      let override_specifiers =
+        self.resolve_import_map_override_specifiers()?;
+      let mut maps = Vec::with_capacity(override_specifiers.len() + 1);
+      if maybe_import_map_specifier.is_none() && !override_specifiers.is_empty()
+      {
+        // the "importMaps" of the config file are layered over its imports
+        maps.extend(self.config_base_import_map(file_fetcher).await?);
+      }
+      for specifier in maybe_import_map_specifier
+        .into_iter()
+        .chain(override_specifiers)
+      {
+        let value =
+          resolve_import_map_value_from_specifier(&specifier, file_fetcher)
+            .await
+            .with_context(|| {
+              format!("Unable to load '{}' import map", specifier)
+            })?;
+        maps.push((specifier, value));
+      }
+      if maps.is_empty() {
+        if self.production() {
+          None
+        } else {
+          deno_json::workspace_dev_import_map(self.workspace())?
         }
-        None => None,
+      } else {
+        if !self.production() {
+          for specifier in
+            deno_json::deno_jsons_with_dev_imports(self.workspace())
+          {
+            log::warn!(
+              "{} \"devImports\" of {} are ignored, because an import map is specified.",
+              colors::yellow("Warning"),
+              specifier
+            );
+          }
+        }
+        let (base_url, value) = merge_import_map_values(maps)?;
+        Some(deno_config::workspace::SpecifiedImportMap { base_url, value })
       }
     };
+    if self.flags.allow_env_in_config && !overrode_no_import_map {
+      match &mut cli_arg_specified_import_map {
+        Some(import_map) => {
+          deno_json::expand_import_map_env_vars(&mut import_map.value)?;
+        }
+        None => {
+          // only replace the import map of the config file when it
+          // references environment variables
+          if let Some((base_url, mut value)) =
+            self.config_base_import_map(file_fetcher).await?
+          {
+            if deno_json::expand_import_map_env_vars(&mut value)? {
+              cli_arg_specified_import_map =
+                Some(deno_config::workspace::SpecifiedImportMap {
+                  base_url,
+                  value,
+                });
+            }
+          }
+        }
+      }
+    }
     Ok(self.workspace().create_resolver(
       CreateResolverOptions {
         pkg_json_dep_resolution,
@@ -1095,6 +1532,15 @@ impl CliOptions {
     )?)
   }
 
+  /// Whether dev dependencies should be left out.
+  pub fn production(&self) -> bool {
+    self.flags.production
+  }
+
+  pub fn allow_env_in_config(&self) -> bool {
+    self.flags.allow_env_in_config
+  }
+
   pub fn node_ipc_fd(&self) -> Option<i64> {
     let maybe_node_channel_fd = std::env::var("NODE_CHANNEL_FD").ok();
     if let Some(node_channel_fd) = maybe_node_channel_fd {
@@ -1122,8 +1568,87 @@ impl CliOptions {
     }
   }
 
-  pub fn env_file_name(&self) -> Option<&String> {
-    self.flags.env_file.as_ref()
+  pub fn serve_mirror(&self) -> Option<String> {
+    if let DenoSubcommand::Serve(flags) = self.sub_command() {
+      flags.mirror.clone()
+    } else {
+      None
+    }
+  }
+
+  pub fn serve_mirror_percent(&self) -> Option<u8> {
+    if let DenoSubcommand::Serve(flags) = self.sub_command() {
+      Some(flags.mirror_percent)
+    } else {
+      None
+    }
+  }
+
+  /// The number of threads `deno check` type checks on.
+  pub fn check_jobs(&self) -> usize {
+    match self.sub_command() {
+      DenoSubcommand::Check(flags) => {
+        flags.check_jobs.map(|jobs| jobs.get()).unwrap_or(1)
+      }
+      _ => 1,
+    }
+  }
+
+  pub fn serve_max_concurrent_uploads(&self) -> Option<usize> {
+    if let DenoSubcommand::Serve(flags) = self.sub_command() {
+      flags.max_concurrent_uploads
+    } else {
+      None
+    }
+  }
+
+  pub fn serve_upload_high_water_mark(&self) -> Option<usize> {
+    if let DenoSubcommand::Serve(flags) = self.sub_command() {
+      flags.upload_high_water_mark
+    } else {
+      None
+    }
+  }
+
+  pub fn serve_upload_metrics_path(&self) -> Option<String> {
+    if let DenoSubcommand::Serve(flags) = self.sub_command() {
+      flags.upload_metrics_path.clone()
+    } else {
+      None
+    }
+  }
+
+  pub fn serve_idle_shutdown(&self) -> Option<u64> {
+    if let DenoSubcommand::Serve(flags) = self.sub_command() {
+      flags.idle_shutdown
+    } else {
+      None
+    }
+  }
+
+  /// Reads the certificate and key files passed to `deno serve`.
+  pub fn serve_tls(&self) -> Result<Option<ServeTlsOptions>, AnyError> {
+    let DenoSubcommand::Serve(flags) = self.sub_command() else {
+      return Ok(None);
+    };
+    let (Some(cert), Some(key)) = (&flags.cert, &flags.key) else {
+      return Ok(None);
+    };
+    let read = |path: &String| {
+      let path = self.initial_cwd().join(path);
+      std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed reading '{}'", path.display()))
+    };
+    Ok(Some(ServeTlsOptions {
+      cert: read(cert)?,
+      key: read(key)?,
+      client_ca: flags.tls_client_ca.as_ref().map(read).transpose()?,
+      client_auth_optional: flags.tls_client_auth == TlsClientAuth::Optional,
+    }))
+  }
+
+  pub fn env_file_names(&self) -> Option<&[String]> {
+    self.flags.env_file.as_deref()
   }
 
   pub fn resolve_main_module(&self) -> Result<&ModuleSpecifier, AnyError> {
@@ -1225,10 +1750,34 @@ impl CliOptions {
     self.workspace().node_modules_dir().map_err(Into::into)
   }
 
+  /// Whether the files of the packages in the `node_modules` folder are hard
+  /// linked from a content addressed store.
+  pub fn node_modules_linked(&self) -> bool {
+    self.flags.node_modules_linked
+  }
+
   pub fn vendor_dir_path(&self) -> Option<&PathBuf> {
     self.workspace().vendor_dir_path()
   }
 
+  /// The folder of the vendor folder which is used as the npm cache when npm
+  /// packages are vendored with `--vendor-npm` or `"vendorNpm": true`.
+  pub fn npm_vendor_dir_path(&self) -> Result<Option<PathBuf>, AnyError> {
+    let Some(vendor_dir_path) = self.vendor_dir_path() else {
+      return Ok(None);
+    };
+    let vendor_npm = match self.flags.vendor_npm {
+      Some(vendor_npm) => vendor_npm,
+      None => match self.workspace().root_deno_json() {
+        Some(deno_json) => CliConfigFields::read(deno_json)?
+          .vendor_npm
+          .unwrap_or(false),
+        None => false,
+      },
+    };
+    Ok(vendor_npm.then(|| vendor_dir_path.join("npm")))
+  }
+
   pub fn resolve_ts_config_for_emit(
     &self,
     config_type: TsConfigType,
@@ -1239,6 +1788,13 @@ impl CliOptions {
   pub fn resolve_inspector_server(
     &self,
   ) -> Result<Option<InspectorServer>, AnyError> {
+    if let Some(target) = &self.flags.inspect_connect {
+      return Ok(Some(InspectorServer::connect(
+        target,
+        version::DENO_VERSION_INFO.user_agent,
+      )?));
+    }
+
     let maybe_inspect_host = self
       .flags
       .inspect
@@ -1292,12 +1848,20 @@ impl CliOptions {
     let unstable = self.resolve_config_unstable_fmt_options();
     let mut result = Vec::with_capacity(member_configs.len());
     for (ctx, config) in member_configs {
-      let options = FmtOptions::resolve(config, unstable.clone(), fmt_flags);
+      let file = resolve_config_fmt_file_options(&ctx)?;
+      let options =
+        FmtOptions::resolve(config, unstable.clone(), file, fmt_flags);
       result.push((ctx, options));
     }
     Ok(result)
   }
 
+  pub fn resolve_config_fmt_file_options(
+    &self,
+  ) -> Result<FmtFileOptions, AnyError> {
+    resolve_config_fmt_file_options(&self.start_dir)
+  }
+
   pub fn resolve_config_unstable_fmt_options(&self) -> UnstableFmtOptions {
     let workspace = self.workspace();
     UnstableFmtOptions {
@@ -1356,6 +1920,13 @@ impl CliOptions {
     WorkspaceTestOptions::resolve(test_flags)
   }
 
+  pub fn resolve_test_fixtures(
+    &self,
+    test_flags: &TestFlags,
+  ) -> Result<TestFixtures, AnyError> {
+    resolve_test_fixtures(test_flags, self.initial_cwd())
+  }
+
   pub fn resolve_test_options_for_members(
     &self,
     test_flags: &TestFlags,
@@ -1460,6 +2031,7 @@ impl CliOptions {
     self.flags.inspect.is_some()
       || self.flags.inspect_brk.is_some()
       || self.flags.inspect_wait.is_some()
+      || self.flags.inspect_connect.is_some()
   }
 
   pub fn inspect_brk(&self) -> Option<SocketAddr> {
@@ -1470,6 +2042,10 @@ impl CliOptions {
     self.flags.inspect_wait
   }
 
+  pub fn inspect_connect(&self) -> Option<&str> {
+    self.flags.inspect_connect.as_deref()
+  }
+
   pub fn log_level(&self) -> Option<log::Level> {
     self.flags.log_level
   }
@@ -1489,10 +2065,18 @@ impl CliOptions {
     self.flags.no_remote
   }
 
+  pub fn no_daemon(&self) -> bool {
+    self.flags.no_daemon
+  }
+
   pub fn no_npm(&self) -> bool {
     self.flags.no_npm
   }
 
+  pub fn node_conditions(&self) -> &[String] {
+    &self.flags.node_conditions
+  }
+
   pub fn permission_flags(&self) -> &PermissionFlags {
     &self.flags.permissions
   }
@@ -1678,6 +2262,10 @@ impl CliOptions {
     {
       full_paths.push(import_map_path);
     }
+    if let Ok(specifiers) = self.resolve_import_map_override_specifiers() {
+      full_paths
+        .extend(specifiers.iter().filter_map(|s| s.to_file_path().ok()));
+    }
 
     for (_, folder) in self.workspace().config_folders() {
       if let Some(deno_json) = &folder.deno_json {
@@ -1739,6 +2327,7 @@ fn resolve_node_modules_folder(
       .node_modules_dir()?
       .map(|m| m.uses_node_modules_dir())
       .or(flags.vendor)
+      .or(flags.vendor_npm.filter(|vendor_npm| *vendor_npm))
       .or_else(|| root_folder.deno_json.as_ref().and_then(|c| c.json.vendor))
   };
   let path = if use_node_modules_dir == Some(false) {
@@ -1867,23 +2456,51 @@ pub fn config_to_deno_graph_workspace_member(
   })
 }
 
-fn load_env_variables_from_env_file(filename: Option<&String>) {
-  let Some(env_file_name) = filename else {
+/// Loads the environment files in order, so the variables of the later files
+/// override the ones of the earlier files and can reference them. The
+/// variables of the process environment are never overridden.
+fn load_env_variables_from_env_files(filenames: Option<&[String]>) {
+  let Some(env_file_names) = filenames else {
     return;
   };
-  match from_filename(env_file_name) {
-    Ok(_) => (),
-    Err(error) => {
-      match error {
-          dotenvy::Error::LineParse(line, index)=> log::info!("{} Parsing failed within the specified environment file: {} at index: {} of the value: {}",colors::yellow("Warning"), env_file_name, index, line),
-          dotenvy::Error::Io(_)=> log::info!("{} The `--env-file` flag was used, but the environment file specified '{}' was not found.",colors::yellow("Warning"),env_file_name),
-          dotenvy::Error::EnvVar(_)=> log::info!("{} One or more of the environment variables isn't present or not unicode within the specified environment file: {}",colors::yellow("Warning"),env_file_name),
-          _ => log::info!("{} Unknown failure occurred with the specified environment file: {}", colors::yellow("Warning"), env_file_name),
+  let process_env_keys = std::env::vars_os()
+    .map(|(key, _)| key)
+    .collect::<HashSet<_>>();
+  for env_file_name in env_file_names {
+    let items = match from_filename_iter(env_file_name) {
+      Ok(items) => items,
+      Err(error) => {
+        warn_env_file_error(env_file_name, error);
+        continue;
+      }
+    };
+    // the items are read lazily, so the values can reference the variables
+    // which were set before them
+    for item in items {
+      match item {
+        Ok((key, value)) => {
+          if !process_env_keys.contains(OsStr::new(&key)) {
+            std::env::set_var(key, value);
+          }
         }
+        Err(error) => {
+          warn_env_file_error(env_file_name, error);
+          break;
+        }
+      }
     }
   }
 }
 
+fn warn_env_file_error(env_file_name: &str, error: dotenvy::Error) {
+  match error {
+    dotenvy::Error::LineParse(line, index)=> log::info!("{} Parsing failed within the specified environment file: {} at index: {} of the value: {}",colors::yellow("Warning"), env_file_name, index, line),
+    dotenvy::Error::Io(_)=> log::info!("{} The `--env-file` flag was used, but the environment file specified '{}' was not found.",colors::yellow("Warning"),env_file_name),
+    dotenvy::Error::EnvVar(_)=> log::info!("{} One or more of the environment variables isn't present or not unicode within the specified environment file: {}",colors::yellow("Warning"),env_file_name),
+    _ => log::info!("{} Unknown failure occurred with the specified environment file: {}", colors::yellow("Warning"), env_file_name),
+  }
+}
+
 #[cfg(test)]
 mod test {
   use pretty_assertions::assert_eq;
@@ -1946,6 +2563,37 @@ mod test {
     assert_eq!(actual, None);
   }
 
+  #[test]
+  fn cli_config_fields_after_change() {
+    let temp_dir = test_util::TempDir::new();
+    let path = temp_dir.path().join("deno.json");
+    path.write(r#"{ "fmtFile": { "bom": "strip" }, "vendorNpm": true }"#);
+    let config_file = ConfigFile::new(
+      &path.read_to_string(),
+      path.url_file(),
+      &deno_config::deno_json::ConfigParseOptions::default(),
+    )
+    .unwrap();
+    let fields = CliConfigFields::read(&config_file).unwrap();
+    assert_eq!(fields.fmt_file.as_ref().unwrap().bom, FmtBom::Strip);
+    assert_eq!(fields.vendor_npm, Some(true));
+    assert_eq!(fields.lint_todo, None);
+
+    path.write(r#"{ "fmtFile": { "bom": "preserve" } }"#);
+    let fields = CliConfigFields::read(&config_file).unwrap();
+    assert_eq!(fields.fmt_file.as_ref().unwrap().bom, FmtBom::Preserve);
+    assert_eq!(fields.vendor_npm, None);
+
+    // errors name the field
+    path.write(r#"{ "vendorNpm": "yes" }"#);
+    let err = CliConfigFields::read(&config_file).unwrap_err();
+    assert!(
+      format!("{err:#}")
+        .starts_with("Failed to parse \"vendorNpm\" configuration in file:///"),
+      "{err:#}"
+    );
+  }
+
   #[test]
   fn storage_key_resolver_test() {
     let resolver = StorageKeyResolver(None);
@@ -1974,4 +2622,15 @@ mod test {
     let reg_api_url = jsr_api_url();
     assert!(reg_api_url.as_str().ends_with('/'));
   }
+
+  #[test]
+  fn npmrc_always_auth() {
+    assert!(has_npmrc_always_auth("always-auth=true\n"));
+    assert!(has_npmrc_always_auth(
+      "registry=https://npm.example.com/\n//npm.example.com/:always-auth = true"
+    ));
+    assert!(!has_npmrc_always_auth(
+      "//npm.example.com/:_authToken=always-auth"
+    ));
+  }
 }
@@ -5,11 +5,26 @@ use std::sync::Arc;
 
 use deno_config::workspace::Workspace;
 use deno_core::serde_json;
+use deno_package_json::PackageJson;
 use deno_package_json::PackageJsonDepValue;
 use deno_package_json::PackageJsonDepValueParseError;
 use deno_semver::npm::NpmPackageReqReference;
 use deno_semver::package::PackageReq;
 
+use super::deno_json_dev_imports;
+
+fn is_dev_only_dependency(pkg_json: &PackageJson, alias: &str) -> bool {
+  let in_dev_deps = pkg_json
+    .dev_dependencies
+    .as_ref()
+    .is_some_and(|deps| deps.contains_key(alias));
+  let in_deps = pkg_json
+    .dependencies
+    .as_ref()
+    .is_some_and(|deps| deps.contains_key(alias));
+  in_dev_deps && !in_deps
+}
+
 #[derive(Debug)]
 pub struct InstallNpmRemotePkg {
   pub alias: Option<String>,
@@ -35,7 +50,9 @@ impl NpmInstallDepsProvider {
     Self::default()
   }
 
-  pub fn from_workspace(workspace: &Arc<Workspace>) -> Self {
+  /// Collects the npm packages to install from the configuration files,
+  /// leaving out the dev dependencies unless `include_dev` is set.
+  pub fn from_workspace(workspace: &Arc<Workspace>, include_dev: bool) -> Self {
     // todo(dsherret): estimate capacity?
     let mut workspace_pkgs = Vec::new();
     let mut remote_pkgs = Vec::new();
@@ -47,43 +64,56 @@ impl NpmInstallDepsProvider {
       if let Some(deno_json) = &folder.deno_json {
         // don't bother with externally referenced import maps as users
         // should inline their import map to get this behaviour
+        let dev_imports = if include_dev {
+          // errors are surfaced when creating the resolver
+          deno_json_dev_imports(deno_json).ok().flatten()
+        } else {
+          None
+        };
+        let mut imports = Vec::new();
         if let Some(serde_json::Value::Object(obj)) = &deno_json.json.imports {
-          let mut pkg_pkgs = Vec::with_capacity(obj.len());
-          for (_alias, value) in obj {
-            let serde_json::Value::String(specifier) = value else {
-              continue;
-            };
-            let Ok(npm_req_ref) = NpmPackageReqReference::from_str(specifier)
-            else {
-              continue;
-            };
-            let pkg_req = npm_req_ref.into_inner().req;
-            let workspace_pkg = workspace_npm_pkgs
-              .iter()
-              .find(|pkg| pkg.matches_req(&pkg_req));
-
-            if let Some(pkg) = workspace_pkg {
-              workspace_pkgs.push(InstallNpmWorkspacePkg {
-                alias: None,
-                target_dir: pkg.pkg_json.dir_path().to_path_buf(),
-              });
-            } else {
-              pkg_pkgs.push(InstallNpmRemotePkg {
-                alias: None,
-                base_dir: deno_json.dir_path(),
-                req: pkg_req,
-              });
-            }
-          }
+          imports.extend(obj.values());
+        }
+        imports.extend(dev_imports.iter().flat_map(|obj| obj.values()));
 
-          // sort within each package (more like npm resolution)
-          pkg_pkgs.sort_by(|a, b| a.req.cmp(&b.req));
-          remote_pkgs.extend(pkg_pkgs);
+        let mut pkg_pkgs = Vec::with_capacity(imports.len());
+        for value in imports {
+          let serde_json::Value::String(specifier) = value else {
+            continue;
+          };
+          let Ok(npm_req_ref) = NpmPackageReqReference::from_str(specifier)
+          else {
+            continue;
+          };
+          let pkg_req = npm_req_ref.into_inner().req;
+          let workspace_pkg = workspace_npm_pkgs
+            .iter()
+            .find(|pkg| pkg.matches_req(&pkg_req));
+
+          if let Some(pkg) = workspace_pkg {
+            workspace_pkgs.push(InstallNpmWorkspacePkg {
+              alias: None,
+              target_dir: pkg.pkg_json.dir_path().to_path_buf(),
+            });
+          } else {
+            pkg_pkgs.push(InstallNpmRemotePkg {
+              alias: None,
+              base_dir: deno_json.dir_path(),
+              req: pkg_req,
+            });
+          }
         }
+
+        // sort within each package (more like npm resolution)
+        pkg_pkgs.sort_by(|a, b| a.req.cmp(&b.req));
+        remote_pkgs.extend(pkg_pkgs);
       }
 
       if let Some(pkg_json) = &folder.pkg_json {
-        let deps = pkg_json.resolve_local_package_json_deps();
+        let mut deps = pkg_json.resolve_local_package_json_deps();
+        if !include_dev {
+          deps.retain(|alias, _| !is_dev_only_dependency(pkg_json, alias));
+        }
         let mut pkg_pkgs = Vec::with_capacity(deps.len());
         for (alias, dep) in deps {
           let dep = match dep {
@@ -3,6 +3,8 @@
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use deno_core::ModuleSpecifier;
+use deno_npm::npm_rc::RegistryConfig;
+use deno_npm::npm_rc::ResolvedNpmRc;
 use log::debug;
 use log::error;
 use std::borrow::Cow;
@@ -25,6 +27,15 @@ pub struct AuthToken {
   token: AuthTokenData,
 }
 
+impl AuthToken {
+  pub fn bearer(host: AuthDomain, token: String) -> Self {
+    Self {
+      host,
+      token: AuthTokenData::Bearer(token),
+    }
+  }
+}
+
 impl fmt::Display for AuthToken {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match &self.token {
@@ -50,6 +61,9 @@ pub enum AuthDomain {
   IpPort(SocketAddr),
   /// Suffix match, no dot. May include a port.
   Suffix(Cow<'static, str>),
+  /// Exact match of the host and port, with a path starting with the path of
+  /// the url, like the registries of an `.npmrc`.
+  UrlPrefix(ModuleSpecifier),
 }
 
 impl<T: ToString> From<T> for AuthDomain {
@@ -111,6 +125,17 @@ impl AuthDomain {
 
         false
       }
+      Self::UrlPrefix(ref url) => {
+        if url.host_str() != Some(host) || url.port() != specifier.port() {
+          return false;
+        }
+        let prefix = url.path().trim_end_matches('/');
+        let path = specifier.path();
+        path == prefix
+          || path
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('/'))
+      }
     }
   }
 }
@@ -152,6 +177,28 @@ impl AuthTokens {
     Self(tokens)
   }
 
+  /// Appends the credentials of the registries of an `.npmrc`, which are only
+  /// used when none of the tokens already in the set match. Like npm, they
+  /// are only sent to the urls under the registry url.
+  pub fn with_npmrc_tokens(mut self, npmrc: &ResolvedNpmRc) -> Self {
+    let registries =
+      std::iter::once(&npmrc.default_config).chain(npmrc.scopes.values());
+    for registry in registries {
+      let url = &registry.registry_url;
+      if url.host_str().is_none() {
+        continue;
+      }
+      let Some(token) = npmrc_token_data(&registry.config) else {
+        continue;
+      };
+      self.0.push(AuthToken {
+        host: AuthDomain::UrlPrefix(url.clone()),
+        token,
+      });
+    }
+    self
+  }
+
   /// Attempt to match the provided specifier to the tokens in the set.  The
   /// matching occurs from the right of the hostname plus port, irrespective of
   /// scheme.  For example `https://www.deno.land:8080/` would match a token
@@ -168,6 +215,29 @@ impl AuthTokens {
   }
 }
 
+/// Converts the credentials of a registry of an `.npmrc` like
+/// `maybe_auth_header_for_npm_registry` does.
+fn npmrc_token_data(config: &RegistryConfig) -> Option<AuthTokenData> {
+  if let Some(token) = &config.auth_token {
+    return Some(AuthTokenData::Bearer(token.clone()));
+  }
+  if let Some(auth) = &config.auth {
+    let auth = String::from_utf8(BASE64_STANDARD.decode(auth).ok()?).ok()?;
+    let (username, password) = auth.split_once(':')?;
+    return Some(AuthTokenData::Basic {
+      username: username.to_string(),
+      password: password.to_string(),
+    });
+  }
+  match (&config.username, &config.password) {
+    (Some(username), Some(password)) => Some(AuthTokenData::Basic {
+      username: username.clone(),
+      password: password.clone(),
+    }),
+    _ => None,
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -195,6 +265,68 @@ mod tests {
     assert_eq!(auth_tokens.get(&fixture), None);
   }
 
+  #[test]
+  fn test_auth_tokens_npmrc() {
+    let npmrc = deno_npm::npm_rc::NpmRc::parse(
+      r#"
+@private:registry=https://npm.example.com/
+//npm.example.com/:_authToken=private-token
+//registry.npmjs.org/:_auth=dXNlcjpwYXNz
+"#,
+      &|_| None,
+    )
+    .unwrap()
+    .as_resolved(&resolve_url("https://registry.npmjs.org/").unwrap())
+    .unwrap();
+    let auth_tokens = AuthTokens::new(Some("abc123@deno.land".to_string()))
+      .with_npmrc_tokens(&npmrc);
+    let fixture = resolve_url("https://npm.example.com/@private/pkg").unwrap();
+    assert_eq!(
+      auth_tokens.get(&fixture).unwrap().to_string(),
+      "Bearer private-token"
+    );
+    let fixture = resolve_url("https://registry.npmjs.org/chalk").unwrap();
+    assert_eq!(
+      auth_tokens.get(&fixture).unwrap().to_string(),
+      format!("Basic {}", BASE64_STANDARD.encode("user:pass"))
+    );
+    let fixture = resolve_url("https://deno.land/x/mod.ts").unwrap();
+    assert_eq!(
+      auth_tokens.get(&fixture).unwrap().to_string(),
+      "Bearer abc123"
+    );
+  }
+
+  #[test]
+  fn test_auth_tokens_npmrc_registry_path() {
+    let npmrc = deno_npm::npm_rc::NpmRc::parse(
+      r#"
+@private:registry=https://npm.example.com/private/
+//npm.example.com/private/:_authToken=private-token
+"#,
+      &|_| None,
+    )
+    .unwrap()
+    .as_resolved(&resolve_url("https://registry.npmjs.org/").unwrap())
+    .unwrap();
+    let auth_tokens = AuthTokens::new(None).with_npmrc_tokens(&npmrc);
+    let fixture =
+      resolve_url("https://npm.example.com/private/@private%2fpkg").unwrap();
+    assert_eq!(
+      auth_tokens.get(&fixture).unwrap().to_string(),
+      "Bearer private-token"
+    );
+    for url in [
+      "https://npm.example.com/other/pkg",
+      "https://npm.example.com/private-other/pkg",
+      "https://cdn.npm.example.com/private/pkg",
+      "https://npm.example.com:8080/private/pkg",
+      "https://registry.npmjs.org/chalk",
+    ] {
+      assert_eq!(auth_tokens.get(&resolve_url(url).unwrap()), None, "{url}");
+    }
+  }
+
   #[test]
   fn test_auth_tokens_multiple() {
     let auth_tokens =
@@ -0,0 +1,525 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::io::ErrorKind;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::unsync::spawn;
+use deno_core::url::Url;
+use deno_runtime::tokio_util::create_basic_runtime;
+use http::header::AUTHORIZATION;
+use http::HeaderValue;
+use http::StatusCode;
+use http_body_util::BodyExt;
+use once_cell::sync::Lazy;
+use ring::hmac;
+use tokio::sync::mpsc;
+
+use super::CACHE_PERM;
+use crate::auth_tokens::AuthTokens;
+use crate::http_util::HttpClientProvider;
+use crate::util::fs::atomic_write_file_with_retries;
+
+/// The environment variable which selects the backend of the emit cache in
+/// the DENO_DIR, like `remote:https://cache.example.com/`. The other caches
+/// are always local.
+pub const CACHE_BACKEND_ENV_VAR_NAME: &str = "DENO_CACHE_BACKEND";
+/// The environment variable with the secret which signs the entries of a
+/// remote store, shared by the machines using it.
+pub const CACHE_BACKEND_SECRET_ENV_VAR_NAME: &str = "DENO_CACHE_BACKEND_SECRET";
+
+/// A slow store shouldn't be slower than emitting the code again.
+const REMOTE_GET_TIMEOUT: Duration = Duration::from_secs(5);
+const REMOTE_PUT_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long the process waits at exit for the uploads which are still
+/// running.
+const UPLOADS_FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Stores the files of a cache, where the keys are relative paths. The source
+/// hash identifies the content a file was derived from, like the source of an
+/// emitted module.
+pub trait CacheBackend: std::fmt::Debug + Send + Sync {
+  fn get(&self, key: &Path, source_hash: u64) -> std::io::Result<Vec<u8>>;
+  fn set(
+    &self,
+    key: &Path,
+    source_hash: u64,
+    data: &[u8],
+  ) -> std::io::Result<()>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheBackendSetting {
+  /// Only use the files in the DENO_DIR.
+  Local,
+  /// Use a shared HTTPS store with the files in the DENO_DIR as an overlay.
+  Remote { url: Url, secret: String },
+}
+
+impl CacheBackendSetting {
+  pub fn from_env() -> Result<Self, AnyError> {
+    match std::env::var(CACHE_BACKEND_ENV_VAR_NAME) {
+      Ok(value) => Self::parse(
+        &value,
+        std::env::var(CACHE_BACKEND_SECRET_ENV_VAR_NAME).ok(),
+      ),
+      Err(_) => Ok(Self::Local),
+    }
+  }
+
+  fn parse(value: &str, secret: Option<String>) -> Result<Self, AnyError> {
+    if value.is_empty() || value == "local" {
+      return Ok(Self::Local);
+    }
+    let Some(url) = value.strip_prefix("remote:") else {
+      bail!(
+        "Invalid {} \"{}\", expected \"local\" or \"remote:<url>\".",
+        CACHE_BACKEND_ENV_VAR_NAME,
+        value
+      );
+    };
+    let mut url = Url::parse(url).with_context(|| {
+      format!("Invalid URL in {}: {}", CACHE_BACKEND_ENV_VAR_NAME, url)
+    })?;
+    // the emitted code is executed, so it can't be fetched in plain text
+    if url.scheme() != "https" {
+      bail!(
+        "Unsupported scheme of the URL in {}, expected https: {}",
+        CACHE_BACKEND_ENV_VAR_NAME,
+        url
+      );
+    }
+    let Some(secret) = secret.filter(|secret| !secret.is_empty()) else {
+      bail!(
+        "{} has to be set to a secret shared by the machines using the store of {}, which signs its entries.",
+        CACHE_BACKEND_SECRET_ENV_VAR_NAME,
+        CACHE_BACKEND_ENV_VAR_NAME
+      );
+    };
+    // the keys are appended to the path of the url
+    if !url.path().ends_with('/') {
+      url.set_path(&format!("{}/", url.path()));
+    }
+    Ok(Self::Remote { url, secret })
+  }
+}
+
+#[derive(Debug)]
+pub struct LocalCacheBackend {
+  location: PathBuf,
+}
+
+impl LocalCacheBackend {
+  pub fn new(location: PathBuf) -> Self {
+    Self { location }
+  }
+}
+
+impl CacheBackend for LocalCacheBackend {
+  /// The local files are verified by the caches when they're read, so the
+  /// source hash isn't part of the path.
+  fn get(&self, key: &Path, _source_hash: u64) -> std::io::Result<Vec<u8>> {
+    std::fs::read(self.location.join(key))
+  }
+
+  fn set(
+    &self,
+    key: &Path,
+    _source_hash: u64,
+    data: &[u8],
+  ) -> std::io::Result<()> {
+    atomic_write_file_with_retries(&self.location.join(key), data, CACHE_PERM)
+  }
+}
+
+enum RemoteCacheRequest {
+  Get(Url, std::sync::mpsc::Sender<Option<Vec<u8>>>),
+  Put(Url, Vec<u8>, PendingUpload),
+}
+
+/// The number of uploads to the remote stores which haven't finished yet.
+#[derive(Default)]
+struct PendingUploads {
+  count: Mutex<usize>,
+  finished: Condvar,
+}
+
+static PENDING_UPLOADS: Lazy<PendingUploads> = Lazy::new(Default::default);
+
+/// Counts as pending until dropped, which is also when the upload is
+/// cancelled.
+struct PendingUpload;
+
+impl PendingUpload {
+  fn new() -> Self {
+    *PENDING_UPLOADS.count.lock().unwrap() += 1;
+    Self
+  }
+}
+
+impl Drop for PendingUpload {
+  fn drop(&mut self) {
+    *PENDING_UPLOADS.count.lock().unwrap() -= 1;
+    PENDING_UPLOADS.finished.notify_all();
+  }
+}
+
+/// Waits for the uploads to the remote cache stores which are still running,
+/// for at most `UPLOADS_FLUSH_TIMEOUT`, so that they aren't lost when the
+/// process exits.
+pub fn flush_remote_cache_uploads() {
+  let count = PENDING_UPLOADS.count.lock().unwrap();
+  if *count == 0 {
+    return;
+  }
+  let (count, result) = PENDING_UPLOADS
+    .finished
+    .wait_timeout_while(count, UPLOADS_FLUSH_TIMEOUT, |count| *count > 0)
+    .unwrap();
+  if result.timed_out() {
+    log::debug!("Abandoned {} uploads to the remote cache", *count);
+  }
+}
+
+/// Reads the files which aren't in the DENO_DIR from a shared HTTPS store with
+/// `GET <url>/<source hash>/<key>` and uploads new files with `PUT`, so that
+/// machines like the runners of a CI can share a warm cache.
+///
+/// The entries are signed with an HMAC-SHA256 of the secret, and entries
+/// with an invalid signature are treated like missing ones, so only the
+/// machines with the secret can provide the code which gets executed.
+///
+/// Bearer tokens of `DENO_AUTH_TOKENS` for the host of the store are sent.
+/// Failed and timed out requests are treated like files missing from the
+/// store. Uploads run in the background and are awaited at exit with
+/// `flush_remote_cache_uploads`.
+#[derive(Debug)]
+pub struct RemoteCacheBackend {
+  local: LocalCacheBackend,
+  base_url: Url,
+  key: hmac::Key,
+  sender: mpsc::UnboundedSender<RemoteCacheRequest>,
+}
+
+impl RemoteCacheBackend {
+  pub fn new(
+    local: LocalCacheBackend,
+    base_url: Url,
+    secret: &str,
+    http_client_provider: Arc<HttpClientProvider>,
+  ) -> Self {
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    // the caches are used synchronously, so the requests are made on a
+    // separate thread
+    std::thread::spawn(move || {
+      let runtime = create_basic_runtime();
+      runtime.block_on(async move {
+        let auth_tokens =
+          Rc::new(AuthTokens::new(std::env::var("DENO_AUTH_TOKENS").ok()));
+        while let Some(request) = receiver.recv().await {
+          let http_client_provider = http_client_provider.clone();
+          let auth_tokens = auth_tokens.clone();
+          // serve the requests concurrently, so that the reads don't wait
+          // for the uploads
+          spawn(async move {
+            match request {
+              RemoteCacheRequest::Get(url, response_sender) => {
+                let result = tokio::time::timeout(
+                  REMOTE_GET_TIMEOUT,
+                  remote_get(&http_client_provider, &auth_tokens, url.clone()),
+                )
+                .await;
+                let maybe_data = match result {
+                  Ok(Ok(maybe_data)) => maybe_data,
+                  Ok(Err(err)) => {
+                    log::debug!(
+                      "Failed fetching {} from cache: {:#}",
+                      url,
+                      err
+                    );
+                    None
+                  }
+                  Err(_) => {
+                    log::debug!("Timed out fetching {} from cache", url);
+                    None
+                  }
+                };
+                let _ = response_sender.send(maybe_data);
+              }
+              RemoteCacheRequest::Put(url, data, _pending_upload) => {
+                let result = tokio::time::timeout(
+                  REMOTE_PUT_TIMEOUT,
+                  remote_put(
+                    &http_client_provider,
+                    &auth_tokens,
+                    url.clone(),
+                    data,
+                  ),
+                )
+                .await;
+                match result {
+                  Ok(Ok(())) => {}
+                  Ok(Err(err)) => {
+                    log::debug!("Failed uploading {} to cache: {:#}", url, err)
+                  }
+                  Err(_) => {
+                    log::debug!("Timed out uploading {} to cache", url)
+                  }
+                }
+              }
+            }
+          });
+        }
+      });
+    });
+    Self {
+      local,
+      base_url,
+      key: hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes()),
+      sender,
+    }
+  }
+}
+
+impl CacheBackend for RemoteCacheBackend {
+  fn get(&self, key: &Path, source_hash: u64) -> std::io::Result<Vec<u8>> {
+    match self.local.get(key, source_hash) {
+      Err(err) if err.kind() == ErrorKind::NotFound => {}
+      result => return result,
+    }
+    let url = remote_key_url(&self.base_url, key, source_hash)
+      .ok_or_else(|| std::io::Error::from(ErrorKind::NotFound))?;
+    let entry = {
+      let (response_sender, response_receiver) = std::sync::mpsc::channel();
+      self
+        .sender
+        .send(RemoteCacheRequest::Get(url.clone(), response_sender))
+        .ok()
+        .and_then(|_| response_receiver.recv().ok().flatten())
+        .ok_or_else(|| std::io::Error::from(ErrorKind::NotFound))?
+    };
+    let Some(data) = verify_entry(&self.key, &url, entry) else {
+      log::warn!(
+        "{} Ignored the entry of the remote cache {} with an invalid signature",
+        crate::colors::yellow("Warning"),
+        url
+      );
+      return Err(std::io::Error::from(ErrorKind::NotFound));
+    };
+    // keep a copy for the next runs
+    if let Err(err) = self.local.set(key, source_hash, &data) {
+      log::debug!("Failed saving {} to cache: {:#}", key.display(), err);
+    }
+    Ok(data)
+  }
+
+  fn set(
+    &self,
+    key: &Path,
+    source_hash: u64,
+    data: &[u8],
+  ) -> std::io::Result<()> {
+    self.local.set(key, source_hash, data)?;
+    if let Some(url) = remote_key_url(&self.base_url, key, source_hash) {
+      let _ = self.sender.send(RemoteCacheRequest::Put(
+        url.clone(),
+        sign_entry(&self.key, &url, data),
+        PendingUpload::new(),
+      ));
+    }
+    Ok(())
+  }
+}
+
+/// Prefixes the data with a line with the hex encoded signature of the url
+/// and the data, so that entries can't be moved to other keys either.
+fn sign_entry(key: &hmac::Key, url: &Url, data: &[u8]) -> Vec<u8> {
+  let tag = hmac::sign(key, &signed_message(url, data));
+  let mut entry = faster_hex::hex_string(tag.as_ref()).into_bytes();
+  entry.push(b'\n');
+  entry.extend(data);
+  entry
+}
+
+/// Returns the data of the entry when its signature is valid.
+fn verify_entry(
+  key: &hmac::Key,
+  url: &Url,
+  mut entry: Vec<u8>,
+) -> Option<Vec<u8>> {
+  let newline_index = entry.iter().position(|byte| *byte == b'\n')?;
+  let mut tag = vec![0; newline_index / 2];
+  faster_hex::hex_decode(&entry[..newline_index], &mut tag).ok()?;
+  let data = entry.split_off(newline_index + 1);
+  hmac::verify(key, &signed_message(url, &data), &tag).ok()?;
+  Some(data)
+}
+
+fn signed_message(url: &Url, data: &[u8]) -> Vec<u8> {
+  let mut message = Vec::with_capacity(url.path().len() + 1 + data.len());
+  message.extend(url.path().as_bytes());
+  message.push(0);
+  message.extend(data);
+  message
+}
+
+fn remote_key_url(base_url: &Url, key: &Path, source_hash: u64) -> Option<Url> {
+  let mut url = base_url.clone();
+  {
+    let mut segments = url.path_segments_mut().ok()?;
+    segments.pop_if_empty();
+    segments.push(&format!("{source_hash:016x}"));
+    for component in key.components() {
+      match component {
+        Component::Normal(segment) => {
+          segments.push(segment.to_str()?);
+        }
+        _ => return None,
+      }
+    }
+  }
+  Some(url)
+}
+
+async fn remote_get(
+  http_client_provider: &HttpClientProvider,
+  auth_tokens: &AuthTokens,
+  url: Url,
+) -> Result<Option<Vec<u8>>, AnyError> {
+  let mut builder = http_client_provider.get_or_create()?.get(url.clone())?;
+  if let Some(auth_token) = auth_tokens.get(&url) {
+    builder = builder.header(
+      AUTHORIZATION,
+      HeaderValue::from_str(&auth_token.to_string())?,
+    );
+  }
+  let response = builder.send().await?;
+  match response.status() {
+    StatusCode::OK => {
+      let bytes = response.into_body().collect().await?.to_bytes();
+      Ok(Some(bytes.to_vec()))
+    }
+    StatusCode::NOT_FOUND => Ok(None),
+    status => bail!("Unexpected status {}", status),
+  }
+}
+
+async fn remote_put(
+  http_client_provider: &HttpClientProvider,
+  auth_tokens: &AuthTokens,
+  url: Url,
+  data: Vec<u8>,
+) -> Result<(), AnyError> {
+  let body = http_body_util::Full::new(data.into())
+    .map_err(|never| match never {})
+    .boxed();
+  let mut builder = http_client_provider
+    .get_or_create()?
+    .put(url.clone(), body)?;
+  if let Some(auth_token) = auth_tokens.get(&url) {
+    builder = builder.header(
+      AUTHORIZATION,
+      HeaderValue::from_str(&auth_token.to_string())?,
+    );
+  }
+  let response = builder.send().await?;
+  if !response.status().is_success() {
+    bail!("Unexpected status {}", response.status());
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_cache_backend_setting_parse() {
+    let secret = || Some("secret".to_string());
+    assert_eq!(
+      CacheBackendSetting::parse("local", None).unwrap(),
+      CacheBackendSetting::Local
+    );
+    assert_eq!(
+      CacheBackendSetting::parse("", None).unwrap(),
+      CacheBackendSetting::Local
+    );
+    assert_eq!(
+      CacheBackendSetting::parse(
+        "remote:https://cache.example.com/deno",
+        secret()
+      )
+      .unwrap(),
+      CacheBackendSetting::Remote {
+        url: Url::parse("https://cache.example.com/deno/").unwrap(),
+        secret: "secret".to_string(),
+      }
+    );
+    assert!(CacheBackendSetting::parse("s3://bucket", secret()).is_err());
+    assert!(
+      CacheBackendSetting::parse("remote:file:///cache", secret()).is_err()
+    );
+    assert!(CacheBackendSetting::parse(
+      "remote:http://cache.example.com/deno",
+      secret()
+    )
+    .is_err());
+    assert!(CacheBackendSetting::parse(
+      "remote:https://cache.example.com/deno",
+      None
+    )
+    .is_err());
+  }
+
+  #[test]
+  fn test_signed_entries() {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, b"secret");
+    let url =
+      Url::parse("https://cache.example.com/deno/a/main.ts.js").unwrap();
+    let entry = sign_entry(&key, &url, b"console.log(1);");
+    assert_eq!(
+      verify_entry(&key, &url, entry.clone()).unwrap(),
+      b"console.log(1);"
+    );
+
+    // tampered with data
+    let mut tampered = entry.clone();
+    *tampered.last_mut().unwrap() = b'!';
+    assert_eq!(verify_entry(&key, &url, tampered), None);
+    // signed with another secret
+    let other_key = hmac::Key::new(hmac::HMAC_SHA256, b"other");
+    assert_eq!(verify_entry(&other_key, &url, entry.clone()), None);
+    // moved to another key
+    let other_url =
+      Url::parse("https://cache.example.com/deno/b/main.ts.js").unwrap();
+    assert_eq!(verify_entry(&key, &other_url, entry), None);
+    assert_eq!(verify_entry(&key, &url, b"unsigned".to_vec()), None);
+  }
+
+  #[test]
+  fn test_remote_key_url() {
+    let base_url = Url::parse("https://cache.example.com/deno/").unwrap();
+    assert_eq!(
+      remote_key_url(&base_url, Path::new("file/home/main.ts.js"), 0xabc)
+        .unwrap()
+        .as_str(),
+      "https://cache.example.com/deno/0000000000000abc/file/home/main.ts.js"
+    );
+    assert_eq!(
+      remote_key_url(&base_url, Path::new("file/a#b?.ts.js"), 1)
+        .unwrap()
+        .as_str(),
+      "https://cache.example.com/deno/0000000000000001/file/a%23b%3F.ts.js"
+    );
+    assert_eq!(remote_key_url(&base_url, Path::new("../secret"), 1), None);
+  }
+}
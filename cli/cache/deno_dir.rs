@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::url::Url;
 use once_cell::sync::OnceCell;
 
 use super::DiskCache;
@@ -126,6 +127,11 @@ impl DenoDir {
     self.root.join("registries")
   }
 
+  /// Folder where running language servers write their status snapshots.
+  pub fn lsp_status_folder_path(&self) -> PathBuf {
+    self.root.join("lsp_status")
+  }
+
   /// Path to the remote cache folder.
   pub fn remote_folder_path(&self) -> PathBuf {
     self.root.join("remote")
@@ -137,6 +143,21 @@ impl DenoDir {
     self.root.join("location_data")
   }
 
+  /// File where `deno info --cache-usage` records the disk usage of the
+  /// caches, to report their growth on the next invocation.
+  pub fn cache_usage_file_path(&self) -> PathBuf {
+    self.root.join("cache_usage.json")
+  }
+
+  /// File where `deno test` records the duration of every test module of a
+  /// workspace, to run the slowest modules first on the next parallel run.
+  pub fn test_durations_file_path(&self, workspace_root: &Url) -> PathBuf {
+    self.root.join("test_durations").join(format!(
+      "{}.json",
+      crate::util::checksum::gen(&[workspace_root.as_str()])
+    ))
+  }
+
   /// File used for the upgrade checker.
   pub fn upgrade_check_file_path(&self) -> PathBuf {
     self.root.join("latest.txt")
@@ -161,6 +182,11 @@ impl DenoDir {
     }
   }
 
+  /// Folder where the Deno versions pinned by projects are installed.
+  pub fn versions_folder_path(&self) -> PathBuf {
+    self.root.join("versions")
+  }
+
   /// Folder path used for downloading new versions of deno.
   pub fn dl_folder_path(&self) -> PathBuf {
     self.root.join("dl")
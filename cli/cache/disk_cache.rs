@@ -1,22 +1,25 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
-use super::CACHE_PERM;
-use crate::util::fs::atomic_write_file_with_retries;
+use super::backend::CacheBackend;
+use super::backend::LocalCacheBackend;
+use super::backend::RemoteCacheBackend;
+use crate::http_util::HttpClientProvider;
 
 use deno_cache_dir::url_to_filename;
 use deno_core::url::Host;
 use deno_core::url::Url;
 use std::ffi::OsStr;
-use std::fs;
 use std::path::Component;
 use std::path::Path;
 use std::path::PathBuf;
 use std::path::Prefix;
 use std::str;
+use std::sync::Arc;
 
 #[derive(Debug, Clone)]
 pub struct DiskCache {
   pub location: PathBuf,
+  backend: Arc<dyn CacheBackend>,
 }
 
 impl DiskCache {
@@ -25,6 +28,26 @@ impl DiskCache {
     assert!(location.is_absolute());
     Self {
       location: location.to_owned(),
+      backend: Arc::new(LocalCacheBackend::new(location.to_owned())),
+    }
+  }
+
+  /// Uses a shared HTTPS store for the files, which are also kept in the
+  /// location as an overlay.
+  pub fn with_remote_backend(
+    self,
+    url: Url,
+    secret: &str,
+    http_client_provider: Arc<HttpClientProvider>,
+  ) -> Self {
+    Self {
+      backend: Arc::new(RemoteCacheBackend::new(
+        LocalCacheBackend::new(self.location.clone()),
+        url,
+        secret,
+        http_client_provider,
+      )),
+      location: self.location,
     }
   }
 
@@ -113,14 +136,21 @@ impl DiskCache {
     }
   }
 
-  pub fn get(&self, filename: &Path) -> std::io::Result<Vec<u8>> {
-    let path = self.location.join(filename);
-    fs::read(path)
+  pub fn get(
+    &self,
+    filename: &Path,
+    source_hash: u64,
+  ) -> std::io::Result<Vec<u8>> {
+    self.backend.get(filename, source_hash)
   }
 
-  pub fn set(&self, filename: &Path, data: &[u8]) -> std::io::Result<()> {
-    let path = self.location.join(filename);
-    atomic_write_file_with_retries(&path, data, CACHE_PERM)
+  pub fn set(
+    &self,
+    filename: &Path,
+    source_hash: u64,
+    data: &[u8],
+  ) -> std::io::Result<()> {
+    self.backend.set(filename, source_hash, data)
   }
 }
 
@@ -135,8 +165,8 @@ mod tests {
     let sub_dir = temp_dir.path().join("sub_dir");
     let cache = DiskCache::new(&sub_dir.to_path_buf());
     let path = PathBuf::from("foo/bar.txt");
-    cache.set(&path, b"hello").unwrap();
-    assert_eq!(cache.get(&path).unwrap(), b"hello");
+    cache.set(&path, 0, b"hello").unwrap();
+    assert_eq!(cache.get(&path, 0).unwrap(), b"hello");
   }
 
   #[test]
@@ -0,0 +1,150 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DiskUsage {
+  pub files: u64,
+  pub bytes: u64,
+}
+
+impl DiskUsage {
+  /// The number of files and their total size at the path, which may be a
+  /// file or a directory.
+  pub fn of_path(path: &Path) -> Self {
+    walkdir::WalkDir::new(path)
+      .into_iter()
+      .filter_map(|entry| entry.ok())
+      .filter(|entry| entry.file_type().is_file())
+      .fold(Self::default(), |usage, entry| {
+        let bytes = entry.metadata().map(|m| m.len()).unwrap_or(0);
+        usage.add(Self { files: 1, bytes })
+      })
+  }
+
+  pub fn add(self, other: Self) -> Self {
+    Self {
+      files: self.files + other.files,
+      bytes: self.bytes + other.bytes,
+    }
+  }
+
+  pub fn saturating_sub(self, other: Self) -> Self {
+    Self {
+      files: self.files.saturating_sub(other.files),
+      bytes: self.bytes.saturating_sub(other.bytes),
+    }
+  }
+}
+
+/// A package version in the global npm cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NpmCachePackageDir {
+  pub name: String,
+  pub version: String,
+  pub path: PathBuf,
+}
+
+/// Lists the package versions in the global npm cache, which has the layout
+/// `<registry>/<name>/<version>` or `<registry>/<@scope>/<name>/<version>`.
+pub fn npm_cache_package_dirs(
+  npm_dir: &Path,
+) -> Result<Vec<NpmCachePackageDir>, AnyError> {
+  fn read_sub_dirs(dir: &Path) -> Result<Vec<PathBuf>, AnyError> {
+    let mut dirs = Vec::new();
+    let entries = match std::fs::read_dir(dir) {
+      Ok(entries) => entries,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+        return Ok(dirs)
+      }
+      Err(err) => {
+        return Err(err)
+          .with_context(|| format!("Failed reading {}", dir.display()))
+      }
+    };
+    for entry in entries {
+      let entry = entry?;
+      if entry.file_type()?.is_dir() {
+        dirs.push(entry.path());
+      }
+    }
+    dirs.sort();
+    Ok(dirs)
+  }
+
+  fn file_name(path: &Path) -> String {
+    path
+      .file_name()
+      .map(|name| name.to_string_lossy().into_owned())
+      .unwrap_or_default()
+  }
+
+  let mut package_dirs = Vec::new();
+  for registry_dir in read_sub_dirs(npm_dir)? {
+    // skip the content addressed store of `--node-modules-dir=linked`
+    if file_name(&registry_dir).starts_with('.') {
+      continue;
+    }
+    for dir in read_sub_dirs(&registry_dir)? {
+      let dir_name = file_name(&dir);
+      if dir_name.starts_with('@') {
+        for dir in read_sub_dirs(&dir)? {
+          package_dirs.push((format!("{}/{}", dir_name, file_name(&dir)), dir));
+        }
+      } else {
+        package_dirs.push((dir_name, dir));
+      }
+    }
+  }
+
+  let mut version_dirs = Vec::new();
+  for (name, package_dir) in package_dirs {
+    for version_dir in read_sub_dirs(&package_dir)? {
+      version_dirs.push(NpmCachePackageDir {
+        name: name.clone(),
+        version: file_name(&version_dir),
+        path: version_dir,
+      });
+    }
+  }
+  Ok(version_dirs)
+}
+
+#[cfg(test)]
+mod test {
+  use test_util::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn test_npm_cache_package_dirs() {
+    let temp_dir = TempDir::new();
+    let registry_dir = temp_dir.path().join("registry.npmjs.org");
+    registry_dir.join("chalk/5.3.0").create_dir_all();
+    registry_dir
+      .join("chalk/5.3.0/index.js")
+      .write("export {};");
+    registry_dir.join("chalk/registry.json").write("{}");
+    registry_dir.join("@types/node/20.1.0").create_dir_all();
+
+    let dirs = npm_cache_package_dirs(temp_dir.path().as_path()).unwrap();
+    assert_eq!(
+      dirs
+        .iter()
+        .map(|dir| format!("{}@{}", dir.name, dir.version))
+        .collect::<Vec<_>>(),
+      vec!["@types/node@20.1.0", "chalk@5.3.0"]
+    );
+    assert_eq!(
+      DiskUsage::of_path(&dirs[1].path),
+      DiskUsage {
+        files: 1,
+        bytes: 10
+      }
+    );
+  }
+}
@@ -41,7 +41,10 @@ impl EmitCache {
     expected_source_hash: u64,
   ) -> Option<Vec<u8>> {
     let emit_filename = self.get_emit_filename(specifier)?;
-    let bytes = self.disk_cache.get(&emit_filename).ok()?;
+    let bytes = self
+      .disk_cache
+      .get(&emit_filename, expected_source_hash)
+      .ok()?;
     self
       .file_serializer
       .deserialize(bytes, expected_source_hash)
@@ -77,7 +80,9 @@ impl EmitCache {
       .get_emit_filename(specifier)
       .ok_or_else(|| anyhow!("Could not get emit filename."))?;
     let cache_data = self.file_serializer.serialize(code, source_hash);
-    self.disk_cache.set(&emit_filename, &cache_data)?;
+    self
+      .disk_cache
+      .set(&emit_filename, source_hash, &cache_data)?;
 
     Ok(())
   }
@@ -0,0 +1,144 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use serde::Deserialize;
+
+/// The marker `deno_cache_dir` puts in front of the metadata, which is
+/// appended to the content of each file in the global http cache.
+const METADATA_PREFIX: &[u8] = b"\n// denoCacheMetadata=";
+
+#[derive(Debug, Deserialize)]
+struct SerializedMetadata {
+  headers: HashMap<String, String>,
+  url: String,
+  /// Seconds since the unix epoch.
+  time: Option<u64>,
+}
+
+/// A remote module stored in the global http cache.
+#[derive(Debug, Clone)]
+pub struct HttpCacheEntry {
+  pub url: Url,
+  pub path: PathBuf,
+  /// The size of the file, including the metadata.
+  pub size: u64,
+  pub headers: HashMap<String, String>,
+  pub download_time: Option<SystemTime>,
+}
+
+/// Reads the metadata of all the entries in the global http cache, skipping
+/// files that are not in the expected format.
+pub fn read_http_cache_entries(
+  remote_dir: &Path,
+) -> Result<Vec<HttpCacheEntry>, AnyError> {
+  let mut entries = Vec::new();
+  if !remote_dir.exists() {
+    return Ok(entries);
+  }
+  for entry in walkdir::WalkDir::new(remote_dir) {
+    let entry = entry.with_context(|| {
+      format!("Failed reading directory '{}'", remote_dir.display())
+    })?;
+    if !entry.file_type().is_file() {
+      continue;
+    }
+    let Ok(bytes) = std::fs::read(entry.path()) else {
+      continue;
+    };
+    let Some(metadata) = parse_metadata(&bytes) else {
+      continue;
+    };
+    let Ok(url) = Url::parse(&metadata.url) else {
+      continue;
+    };
+    entries.push(HttpCacheEntry {
+      url,
+      path: entry.path().to_path_buf(),
+      size: bytes.len() as u64,
+      headers: metadata.headers,
+      download_time: metadata
+        .time
+        .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs)),
+    });
+  }
+  entries.sort_by(|a, b| a.url.cmp(&b.url));
+  Ok(entries)
+}
+
+fn parse_metadata(bytes: &[u8]) -> Option<SerializedMetadata> {
+  let index = bytes
+    .windows(METADATA_PREFIX.len())
+    .rposition(|window| window == METADATA_PREFIX)?;
+  serde_json::from_slice(&bytes[index + METADATA_PREFIX.len()..]).ok()
+}
+
+/// Matches urls of cache entries either by prefix or, when it contains
+/// wildcards, by a glob.
+#[derive(Debug)]
+pub enum HttpCacheEntryMatcher {
+  Prefix(String),
+  Glob(glob::Pattern),
+}
+
+impl HttpCacheEntryMatcher {
+  pub fn new(pattern: &str) -> Result<Self, AnyError> {
+    if pattern.contains(['*', '?', '[']) {
+      let pattern = glob::Pattern::new(pattern)
+        .with_context(|| format!("Invalid pattern: {pattern}"))?;
+      Ok(Self::Glob(pattern))
+    } else {
+      Ok(Self::Prefix(pattern.to_string()))
+    }
+  }
+
+  pub fn matches(&self, url: &Url) -> bool {
+    match self {
+      Self::Prefix(prefix) => url.as_str().starts_with(prefix.as_str()),
+      Self::Glob(pattern) => pattern.matches(url.as_str()),
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_parse_metadata() {
+    let bytes = b"export const a = 1;\n// denoCacheMetadata={\"headers\":{\"content-type\":\"application/typescript\"},\"url\":\"https://deno.land/x/a.ts\",\"time\":1700000000}";
+    let metadata = parse_metadata(bytes).unwrap();
+    assert_eq!(metadata.url, "https://deno.land/x/a.ts");
+    assert_eq!(metadata.time, Some(1700000000));
+    assert_eq!(
+      metadata.headers.get("content-type").map(|s| s.as_str()),
+      Some("application/typescript")
+    );
+    assert!(parse_metadata(b"export const a = 1;").is_none());
+  }
+
+  #[test]
+  fn test_entry_matcher() {
+    let url = Url::parse("https://deno.land/std@0.200.0/path/mod.ts").unwrap();
+    assert!(HttpCacheEntryMatcher::new("https://deno.land/std@")
+      .unwrap()
+      .matches(&url));
+    assert!(!HttpCacheEntryMatcher::new("https://esm.sh/")
+      .unwrap()
+      .matches(&url));
+    assert!(HttpCacheEntryMatcher::new("https://deno.land/std@*/path/*")
+      .unwrap()
+      .matches(&url));
+    assert!(!HttpCacheEntryMatcher::new("https://deno.land/x/*")
+      .unwrap()
+      .matches(&url));
+  }
+}
@@ -29,6 +29,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::SystemTime;
 
+mod backend;
 mod cache_db;
 mod caches;
 mod check;
@@ -36,13 +37,17 @@ mod code_cache;
 mod common;
 mod deno_dir;
 mod disk_cache;
+mod disk_usage;
 mod emit;
 mod fast_check;
+mod http_cache_entries;
 mod incremental;
 mod module_info;
 mod node;
 mod parsed_source;
 
+pub use backend::flush_remote_cache_uploads;
+pub use backend::CacheBackendSetting;
 pub use cache_db::CacheDBHash;
 pub use caches::Caches;
 pub use check::TypeCheckCache;
@@ -52,8 +57,14 @@ pub use deno_dir::dirs::home_dir;
 pub use deno_dir::DenoDir;
 pub use deno_dir::DenoDirProvider;
 pub use disk_cache::DiskCache;
+pub use disk_usage::npm_cache_package_dirs;
+pub use disk_usage::DiskUsage;
+pub use disk_usage::NpmCachePackageDir;
 pub use emit::EmitCache;
 pub use fast_check::FastCheckCache;
+pub use http_cache_entries::read_http_cache_entries;
+pub use http_cache_entries::HttpCacheEntry;
+pub use http_cache_entries::HttpCacheEntryMatcher;
 pub use incremental::IncrementalCache;
 pub use module_info::ModuleInfoCache;
 pub use node::NodeAnalysisCache;
@@ -307,9 +318,11 @@ impl Loader for FetchCacher {
       let maybe_cache_setting = match options.cache_setting {
         LoaderCacheSetting::Use => None,
         LoaderCacheSetting::Reload => {
-          if matches!(file_fetcher.cache_setting(), CacheSetting::Only) {
+          let cache_setting = file_fetcher.cache_setting();
+          if cache_setting.is_cache_only() {
             return Err(deno_core::anyhow::anyhow!(
-              "Could not resolve version constraint using only cached data. Try running again without --cached-only"
+              "Could not resolve version constraint using only cached data. Try running again without {}",
+              cache_setting.cache_only_flag()
             ));
           }
           Some(CacheSetting::ReloadAll)
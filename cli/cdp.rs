@@ -272,6 +272,7 @@ impl From<&RemoteObject> for CallArgument {
 #[serde(rename_all = "camelCase")]
 pub struct PropertyDescriptor {
   pub name: String,
+  pub value: Option<RemoteObject>,
 }
 
 /// <https://chromedevtools.github.io/devtools-protocol/tot/Runtime/#type-RemoteObjectId>
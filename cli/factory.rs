@@ -9,6 +9,7 @@ use crate::args::Flags;
 use crate::args::NpmInstallDepsProvider;
 use crate::args::StorageKeyResolver;
 use crate::args::TsConfigType;
+use crate::cache::CacheBackendSetting;
 use crate::cache::Caches;
 use crate::cache::CodeCache;
 use crate::cache::DenoDir;
@@ -80,7 +81,7 @@ use once_cell::sync::OnceCell;
 use std::future::Future;
 use std::sync::Arc;
 
-struct CliRootCertStoreProvider {
+pub struct CliRootCertStoreProvider {
   cell: OnceCell<RootCertStore>,
   maybe_root_path: Option<PathBuf>,
   maybe_ca_stores: Option<Vec<String>>,
@@ -190,6 +191,7 @@ struct CliFactoryServices {
   root_permissions_container: Deferred<PermissionsContainer>,
   sloppy_imports_resolver: Deferred<Option<Arc<CliSloppyImportsResolver>>>,
   text_only_progress_bar: Deferred<ProgressBar>,
+  download_progress_bar: Deferred<ProgressBar>,
   type_checker: Deferred<Arc<TypeChecker>>,
   cjs_resolutions: Deferred<Arc<CjsResolutionStore>>,
   cli_node_resolver: Deferred<Arc<CliNodeResolver>>,
@@ -236,6 +238,17 @@ impl CliFactory {
     }
   }
 
+  /// Uses the parsed sources of a previous run of a watcher, which are
+  /// reused for the modules whose source didn't change.
+  pub fn with_parsed_source_cache(
+    mut self,
+    parsed_source_cache: Arc<ParsedSourceCache>,
+  ) -> Self {
+    self.services.parsed_source_cache =
+      Deferred::from_value(parsed_source_cache);
+    self
+  }
+
   pub fn cli_options(&self) -> Result<&Arc<CliOptions>, AnyError> {
     self.services.cli_options.get_or_try_init(|| {
       CliOptions::from_flags(self.flags.clone()).map(Arc::new)
@@ -292,10 +305,22 @@ impl CliFactory {
   }
 
   pub fn text_only_progress_bar(&self) -> &ProgressBar {
-    self
-      .services
-      .text_only_progress_bar
-      .get_or_init(|| ProgressBar::new(ProgressBarStyle::TextOnly))
+    self.services.text_only_progress_bar.get_or_init(|| {
+      ProgressBar::new_with_mode(
+        ProgressBarStyle::TextOnly,
+        self.flags.progress,
+      )
+    })
+  }
+
+  /// The progress bar of the remote modules downloaded for the module graph.
+  pub fn download_progress_bar(&self) -> &ProgressBar {
+    self.services.download_progress_bar.get_or_init(|| {
+      ProgressBar::new_with_mode(
+        ProgressBarStyle::DownloadSummary,
+        self.flags.progress,
+      )
+    })
   }
 
   pub fn global_http_cache(&self) -> Result<&Arc<GlobalHttpCache>, AnyError> {
@@ -326,10 +351,13 @@ impl CliFactory {
 
   pub fn http_client_provider(&self) -> &Arc<HttpClientProvider> {
     self.services.http_client_provider.get_or_init(|| {
-      Arc::new(HttpClientProvider::new(
-        Some(self.root_cert_store_provider().clone()),
-        self.flags.unsafely_ignore_certificate_errors.clone(),
-      ))
+      Arc::new(
+        HttpClientProvider::new(
+          Some(self.root_cert_store_provider().clone()),
+          self.flags.unsafely_ignore_certificate_errors.clone(),
+        )
+        .with_offline(self.flags.offline),
+      )
     })
   }
 
@@ -342,7 +370,7 @@ impl CliFactory {
         !cli_options.no_remote(),
         self.http_client_provider().clone(),
         self.blob_store().clone(),
-        Some(self.text_only_progress_bar().clone()),
+        Some(self.download_progress_bar().clone()),
       )))
     })
   }
@@ -361,7 +389,7 @@ impl CliFactory {
         let fs = self.fs();
         let cli_options = self.cli_options()?;
         // For `deno install` we want to force the managed resolver so it can set up `node_modules/` directory.
-        create_cli_npm_resolver(if cli_options.use_byonm() && !matches!(cli_options.sub_command(), DenoSubcommand::Install(_) | DenoSubcommand::Add(_) | DenoSubcommand::Remove(_)) {
+        create_cli_npm_resolver(if cli_options.use_byonm() && !matches!(cli_options.sub_command(), DenoSubcommand::Install(_) | DenoSubcommand::Add(_) | DenoSubcommand::Remove(_) | DenoSubcommand::Outdated(_)) {
           CliNpmResolverCreateOptions::Byonm(CliByonmNpmResolverCreateOptions {
             fs: CliDenoResolverFs(fs.clone()),
             root_node_modules_dir: Some(match cli_options.node_modules_dir_path() {
@@ -373,6 +401,7 @@ impl CliFactory {
             }),
           })
         } else {
+          let npm_vendor_dir_path = cli_options.npm_vendor_dir_path()?;
           CliNpmResolverCreateOptions::Managed(CliNpmResolverManagedCreateOptions {
             snapshot: match cli_options.resolve_npm_resolution_snapshot()? {
               Some(snapshot) => {
@@ -390,11 +419,16 @@ impl CliFactory {
             maybe_lockfile: cli_options.maybe_lockfile().cloned(),
             fs: fs.clone(),
             http_client_provider: self.http_client_provider().clone(),
-            npm_global_cache_dir: self.deno_dir()?.npm_folder_path(),
+            npm_global_cache_dir: match &npm_vendor_dir_path {
+              Some(npm_vendor_dir_path) => npm_vendor_dir_path.clone(),
+              None => self.deno_dir()?.npm_folder_path(),
+            },
+            vendor_npm: npm_vendor_dir_path.is_some(),
             cache_setting: cli_options.cache_setting(),
             text_only_progress_bar: self.text_only_progress_bar().clone(),
             maybe_node_modules_path: cli_options.node_modules_dir_path().cloned(),
-            npm_install_deps_provider: Arc::new(NpmInstallDepsProvider::from_workspace(cli_options.workspace())),
+            node_modules_linked: cli_options.node_modules_linked(),
+            npm_install_deps_provider: Arc::new(NpmInstallDepsProvider::from_workspace(cli_options.workspace(), !cli_options.production())),
             npm_system_info: cli_options.npm_system_info(),
             npmrc: cli_options.npmrc().clone(),
             lifecycle_scripts: cli_options.lifecycle_scripts_config(),
@@ -497,7 +531,17 @@ impl CliFactory {
 
   pub fn emit_cache(&self) -> Result<&Arc<EmitCache>, AnyError> {
     self.services.emit_cache.get_or_try_init(|| {
-      Ok(Arc::new(EmitCache::new(self.deno_dir()?.gen_cache.clone())))
+      let gen_cache = self.deno_dir()?.gen_cache.clone();
+      let gen_cache = match CacheBackendSetting::from_env()? {
+        CacheBackendSetting::Local => gen_cache,
+        CacheBackendSetting::Remote { url, secret } => gen_cache
+          .with_remote_backend(
+            url,
+            &secret,
+            self.http_client_provider().clone(),
+          ),
+      };
+      Ok(Arc::new(EmitCache::new(gen_cache)))
     })
   }
 
@@ -557,6 +601,7 @@ impl CliFactory {
           Ok(Arc::new(NodeResolver::new(
             DenoFsNodeResolverEnv::new(self.fs().clone()),
             self.npm_resolver().await?.clone().into_npm_resolver(),
+            self.cli_options()?.node_conditions().to_vec(),
           )))
         }
         .boxed_local(),
@@ -875,7 +920,8 @@ impl CliFactory {
       has_node_modules_dir: cli_options.has_node_modules_dir(),
       hmr: cli_options.has_hmr(),
       inspect_brk: cli_options.inspect_brk().is_some(),
-      inspect_wait: cli_options.inspect_wait().is_some(),
+      inspect_wait: cli_options.inspect_wait().is_some()
+        || cli_options.inspect_connect().is_some(),
       strace_ops: cli_options.strace_ops().clone(),
       is_inspecting: cli_options.is_inspecting(),
       is_npm_main: cli_options.is_npm_main(),
@@ -896,6 +942,13 @@ impl CliFactory {
       node_ipc: cli_options.node_ipc_fd(),
       serve_port: cli_options.serve_port(),
       serve_host: cli_options.serve_host(),
+      serve_mirror: cli_options.serve_mirror(),
+      serve_mirror_percent: cli_options.serve_mirror_percent(),
+      serve_max_concurrent_uploads: cli_options.serve_max_concurrent_uploads(),
+      serve_upload_high_water_mark: cli_options.serve_upload_high_water_mark(),
+      serve_upload_metrics_path: cli_options.serve_upload_metrics_path(),
+      serve_idle_shutdown: cli_options.serve_idle_shutdown(),
+      serve_tls: cli_options.serve_tls()?,
     })
   }
 }
@@ -1,6 +1,10 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::credentials::stored_token_for_url;
+use crate::args::credentials::RegistryKind;
 use crate::args::CacheSetting;
+use crate::auth_tokens::AuthDomain;
+use crate::auth_tokens::AuthToken;
 use crate::auth_tokens::AuthTokens;
 use crate::cache::HttpCache;
 use crate::colors;
@@ -17,9 +21,11 @@ use deno_core::error::generic_error;
 use deno_core::error::uri_error;
 use deno_core::error::AnyError;
 use deno_core::parking_lot::Mutex;
+use deno_core::unsync::spawn_blocking;
 use deno_core::url::Url;
 use deno_core::ModuleSpecifier;
 use deno_graph::source::LoaderChecksum;
+use deno_npm::npm_rc::ResolvedNpmRc;
 
 use deno_path_util::url_to_file_path;
 use deno_runtime::deno_permissions::PermissionsContainer;
@@ -32,6 +38,7 @@ use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::SystemTime;
+use tokio::sync::Semaphore;
 
 pub const SUPPORTED_SCHEMES: [&str; 5] =
   ["data", "blob", "file", "http", "https"];
@@ -192,10 +199,36 @@ pub struct FetchNoFollowOptions<'a> {
   pub maybe_checksum: Option<&'a LoaderChecksum>,
 }
 
+/// The number of remote modules which are downloaded at the same time when
+/// `DENO_MAX_CONCURRENT_DOWNLOADS` isn't set.
+const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 64;
+
+/// Reads the number of concurrent downloads of a `DENO_MAX_CONCURRENT_DOWNLOADS`
+/// value, falling back to the default when it's not a positive number.
+fn parse_max_concurrent_downloads(value: Option<&str>) -> usize {
+  let Some(value) = value else {
+    return DEFAULT_MAX_CONCURRENT_DOWNLOADS;
+  };
+  match value.parse::<usize>() {
+    Ok(max) if max > 0 => max,
+    _ => {
+      log::warn!(
+        "{} Ignoring DENO_MAX_CONCURRENT_DOWNLOADS={}, which is not a positive number.",
+        colors::yellow("Warning"),
+        value
+      );
+      DEFAULT_MAX_CONCURRENT_DOWNLOADS
+    }
+  }
+}
+
 /// A structure for resolving, fetching and caching source files.
 #[derive(Debug)]
 pub struct FileFetcher {
   auth_tokens: AuthTokens,
+  /// The registry urls of the `.npmrc`, to which the npm tokens stored with
+  /// `deno auth login` are also sent.
+  npm_registry_urls: Vec<Url>,
   allow_remote: bool,
   memory_files: MemoryFiles,
   cache_setting: CacheSetting,
@@ -204,6 +237,8 @@ pub struct FileFetcher {
   blob_store: Arc<BlobStore>,
   download_log_level: log::Level,
   progress_bar: Option<ProgressBar>,
+  /// Limits the downloads which are in flight at the same time.
+  download_permits: Semaphore,
 }
 
 impl FileFetcher {
@@ -217,6 +252,7 @@ impl FileFetcher {
   ) -> Self {
     Self {
       auth_tokens: AuthTokens::new(env::var("DENO_AUTH_TOKENS").ok()),
+      npm_registry_urls: Vec::new(),
       allow_remote,
       memory_files: Default::default(),
       cache_setting,
@@ -225,6 +261,9 @@ impl FileFetcher {
       blob_store,
       download_log_level: log::Level::Info,
       progress_bar,
+      download_permits: Semaphore::new(parse_max_concurrent_downloads(
+        env::var("DENO_MAX_CONCURRENT_DOWNLOADS").ok().as_deref(),
+      )),
     }
   }
 
@@ -232,6 +271,19 @@ impl FileFetcher {
     &self.cache_setting
   }
 
+  /// Authorizes the requests to the npm registries of the `.npmrc`, which is
+  /// used when fetching package information outside of the npm resolver. The
+  /// credentials are only sent to the urls under the registry urls.
+  pub fn add_npmrc_auth_tokens(&mut self, npmrc: &ResolvedNpmRc) {
+    self.auth_tokens =
+      std::mem::replace(&mut self.auth_tokens, AuthTokens::new(None))
+        .with_npmrc_tokens(npmrc);
+    self.npm_registry_urls = std::iter::once(&npmrc.default_config)
+      .chain(npmrc.scopes.values())
+      .map(|registry| registry.registry_url.clone())
+      .collect();
+  }
+
   /// Sets the log level to use when outputting the download message.
   pub fn set_download_log_level(&mut self, level: log::Level) {
     self.download_log_level = level;
@@ -364,15 +416,18 @@ impl FileFetcher {
       }
     }
 
-    if *cache_setting == CacheSetting::Only {
+    if cache_setting.is_cache_only() {
       return Err(custom_error(
         "NotCached",
         format!(
-          "Specifier not found in cache: \"{specifier}\", --cached-only is specified."
+          "Specifier not found in cache: \"{specifier}\", {} is specified.",
+          cache_setting.cache_only_flag()
         ),
       ));
     }
 
+    // the semaphore is never closed
+    let _permit = self.download_permits.acquire().await.unwrap();
     let mut maybe_progress_guard = None;
     if let Some(pb) = self.progress_bar.as_ref() {
       maybe_progress_guard = Some(pb.update(specifier.as_str()));
@@ -409,7 +464,21 @@ impl FileFetcher {
           .cloned()
           .map(|etag| (cache_entry, etag))
       });
-    let maybe_auth_token = self.auth_tokens.get(specifier);
+    // the tokens stored with `deno auth login` are only used when none of
+    // the configured ones match
+    let maybe_auth_token = self.auth_tokens.get(specifier).or_else(|| {
+      let is_npm_registry = self
+        .npm_registry_urls
+        .iter()
+        .any(|url| specifier.as_str().starts_with(url.as_str()));
+      let token = stored_token_for_url(specifier, |kind| {
+        kind != RegistryKind::Npm || is_npm_registry
+      })?;
+      Some(AuthToken::bearer(
+        AuthDomain::from(specifier.host_str()?),
+        token,
+      ))
+    });
 
     async fn handle_request_or_server_error(
       retried: &mut bool,
@@ -455,7 +524,18 @@ impl FileFetcher {
           Ok(FileOrRedirect::Redirect(redirect_url))
         }
         FetchOnceResult::Code(bytes, headers) => {
-          self.http_cache.set(specifier, headers.clone(), &bytes)?;
+          // write to the cache on a blocking thread so the disk doesn't hold
+          // up the other downloads of the module graph
+          let bytes = spawn_blocking({
+            let http_cache = self.http_cache.clone();
+            let specifier = specifier.clone();
+            let headers = headers.clone();
+            move || {
+              http_cache.set(&specifier, headers, &bytes)?;
+              Ok::<_, AnyError>(bytes)
+            }
+          })
+          .await??;
           if let Some(checksum) = &maybe_checksum {
             checksum.check_source(&bytes)?;
           }
@@ -494,7 +574,7 @@ impl FileFetcher {
   ) -> bool {
     match cache_setting {
       CacheSetting::ReloadAll => false,
-      CacheSetting::Use | CacheSetting::Only => true,
+      CacheSetting::Use | CacheSetting::Only | CacheSetting::Offline => true,
       CacheSetting::RespectHeaders => {
         let Ok(cache_key) = self.http_cache.cache_item_key(specifier) else {
           return false;
@@ -712,6 +792,23 @@ mod tests {
   use deno_runtime::deno_web::InMemoryBlobPart;
   use test_util::TempDir;
 
+  #[test]
+  fn test_parse_max_concurrent_downloads() {
+    assert_eq!(
+      parse_max_concurrent_downloads(None),
+      DEFAULT_MAX_CONCURRENT_DOWNLOADS
+    );
+    assert_eq!(parse_max_concurrent_downloads(Some("128")), 128);
+    assert_eq!(
+      parse_max_concurrent_downloads(Some("0")),
+      DEFAULT_MAX_CONCURRENT_DOWNLOADS
+    );
+    assert_eq!(
+      parse_max_concurrent_downloads(Some("many")),
+      DEFAULT_MAX_CONCURRENT_DOWNLOADS
+    );
+  }
+
   fn setup(
     cache_setting: CacheSetting,
     maybe_temp_dir: Option<TempDir>,
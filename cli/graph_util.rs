@@ -35,6 +35,7 @@ use deno_core::ModuleSpecifier;
 use deno_graph::source::Loader;
 use deno_graph::source::ResolveError;
 use deno_graph::GraphKind;
+use deno_graph::Module;
 use deno_graph::ModuleError;
 use deno_graph::ModuleGraph;
 use deno_graph::ModuleGraphError;
@@ -46,8 +47,12 @@ use deno_runtime::deno_fs::FileSystem;
 use deno_runtime::deno_node;
 use deno_runtime::deno_permissions::PermissionsContainer;
 use deno_semver::jsr::JsrDepPackageReq;
+use deno_semver::jsr::JsrPackageReqReference;
+use deno_semver::npm::NpmPackageReqReference;
 use deno_semver::package::PackageNv;
+use deno_semver::package::PackageReq;
 use import_map::ImportMapError;
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::error::Error;
 use std::ops::Deref;
@@ -571,23 +576,7 @@ impl ModuleGraphBuilder {
     // fill the graph with the information from the lockfile
     let is_first_execution = graph.roots.is_empty();
     if is_first_execution {
-      // populate the information from the lockfile
-      if let Some(lockfile) = &self.lockfile {
-        let lockfile = lockfile.lock();
-        graph.fill_from_lockfile(FillFromLockfileOptions {
-          redirects: lockfile
-            .content
-            .redirects
-            .iter()
-            .map(|(from, to)| (from.as_str(), to.as_str())),
-          package_specifiers: lockfile
-            .content
-            .packages
-            .specifiers
-            .iter()
-            .map(|(dep, id)| (dep, id.as_str())),
-        });
-      }
+      self.fill_graph_from_lockfile(graph);
     }
 
     let initial_redirects_len = graph.redirects.len();
@@ -681,6 +670,62 @@ impl ModuleGraphBuilder {
     Ok(())
   }
 
+  fn fill_graph_from_lockfile(&self, graph: &mut ModuleGraph) {
+    if let Some(lockfile) = &self.lockfile {
+      let lockfile = lockfile.lock();
+      graph.fill_from_lockfile(FillFromLockfileOptions {
+        redirects: lockfile
+          .content
+          .redirects
+          .iter()
+          .map(|(from, to)| (from.as_str(), to.as_str())),
+        package_specifiers: lockfile
+          .content
+          .packages
+          .specifiers
+          .iter()
+          .map(|(dep, id)| (dep, id.as_str())),
+      });
+    }
+  }
+
+  /// Builds a graph without resolving npm packages, installing the
+  /// `node_modules` folder or updating the lockfile, which is used with a
+  /// loader that doesn't access the network for `deno cache --dry-run`.
+  ///
+  /// The versions of the jsr packages are resolved from the lockfile.
+  pub async fn build_graph_without_installing(
+    &self,
+    roots: Vec<ModuleSpecifier>,
+    loader: &mut dyn Loader,
+  ) -> Result<ModuleGraph, AnyError> {
+    let mut graph = ModuleGraph::new(GraphKind::All);
+    self.fill_graph_from_lockfile(&mut graph);
+    let analyzer = self
+      .module_info_cache
+      .as_module_analyzer(&self.parsed_source_cache);
+    graph
+      .build(
+        roots,
+        loader,
+        deno_graph::BuildOptions {
+          imports: self.options.to_compiler_option_types()?,
+          is_dynamic: false,
+          passthrough_jsr_specifiers: false,
+          executor: Default::default(),
+          file_system: &DenoGraphFsAdapter(self.fs.as_ref()),
+          jsr_url_provider: &CliJsrUrlProvider,
+          npm_resolver: None,
+          module_analyzer: &analyzer,
+          reporter: None,
+          resolver: Some(self.resolver.as_graph_resolver()),
+          locker: None,
+        },
+      )
+      .await;
+    Ok(graph)
+  }
+
   /// Creates the default loader used for creating a graph.
   pub fn create_graph_loader(&self) -> cache::FetchCacher {
     self.create_fetch_cacher(self.root_permissions_container.clone())
@@ -738,6 +783,44 @@ impl ModuleGraphBuilder {
   }
 }
 
+/// The npm and jsr package requirements of the dependencies in the graph.
+pub fn collect_graph_package_reqs(
+  graph: &ModuleGraph,
+) -> (Vec<PackageReq>, Vec<PackageReq>) {
+  let mut specifiers = graph.roots.iter().collect::<Vec<_>>();
+  for module in graph.modules() {
+    if let Module::Js(module) = module {
+      for dependency in module.dependencies.values() {
+        specifiers.extend(dependency.maybe_code.maybe_specifier());
+        specifiers.extend(dependency.maybe_type.maybe_specifier());
+      }
+    }
+  }
+  for import in graph.imports.values() {
+    for dependency in import.dependencies.values() {
+      specifiers.extend(dependency.maybe_type.maybe_specifier());
+    }
+  }
+
+  let mut npm_reqs = BTreeMap::new();
+  let mut jsr_reqs = BTreeMap::new();
+  for specifier in specifiers {
+    if let Ok(reference) = NpmPackageReqReference::from_specifier(specifier) {
+      let req = reference.into_inner().req;
+      npm_reqs.insert(req.to_string(), req);
+    } else if let Ok(reference) =
+      JsrPackageReqReference::from_specifier(specifier)
+    {
+      let req = reference.into_inner().req;
+      jsr_reqs.insert(req.to_string(), req);
+    }
+  }
+  (
+    npm_reqs.into_values().collect(),
+    jsr_reqs.into_values().collect(),
+  )
+}
+
 /// Adds more explanatory information to a resolution error.
 pub fn enhanced_resolution_error_message(error: &ResolutionError) -> String {
   let mut message = format_deno_graph_error(error);
@@ -214,6 +214,7 @@ pub struct HttpClientProvider {
   // so we store these Clients keyed by thread id
   // https://github.com/seanmonstar/reqwest/issues/1148#issuecomment-910868788
   clients_by_thread_id: Mutex<HashMap<ThreadId, deno_fetch::Client>>,
+  offline: bool,
 }
 
 impl std::fmt::Debug for HttpClientProvider {
@@ -224,6 +225,14 @@ impl std::fmt::Debug for HttpClientProvider {
   }
 }
 
+impl std::fmt::Debug for HttpClientProvider {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("HttpClientProvider")
+      .field("offline", &self.offline)
+      .finish_non_exhaustive()
+  }
+}
+
 impl HttpClientProvider {
   pub fn new(
     root_cert_store_provider: Option<Arc<dyn RootCertStoreProvider>>,
@@ -236,16 +245,30 @@ impl HttpClientProvider {
       },
       root_cert_store_provider,
       clients_by_thread_id: Default::default(),
+      offline: false,
     }
   }
 
+  /// Makes the created clients refuse to send any request, which is how
+  /// `--offline` is enforced.
+  pub fn with_offline(mut self, offline: bool) -> Self {
+    self.offline = offline;
+    self
+  }
+
+  pub fn is_offline(&self) -> bool {
+    self.offline
+  }
+
   pub fn get_or_create(&self) -> Result<HttpClient, AnyError> {
     use std::collections::hash_map::Entry;
     let thread_id = std::thread::current().id();
     let mut clients = self.clients_by_thread_id.lock();
     let entry = clients.entry(thread_id);
     match entry {
-      Entry::Occupied(entry) => Ok(HttpClient::new(entry.get().clone())),
+      Entry::Occupied(entry) => {
+        Ok(HttpClient::new(entry.get().clone(), self.offline))
+      }
       Entry::Vacant(entry) => {
         let client = create_http_client(
           version::DENO_VERSION_INFO.user_agent,
@@ -258,7 +281,7 @@ impl HttpClientProvider {
           },
         )?;
         entry.insert(client.clone());
-        Ok(HttpClient::new(client))
+        Ok(HttpClient::new(client, self.offline))
       }
     }
   }
@@ -271,6 +294,10 @@ pub struct BadResponseError {
   pub response_text: Option<String>,
 }
 
+#[derive(Debug, Error)]
+#[error("Network access to \"{0}\" is required, but --offline is specified.")]
+pub struct OfflineError(pub String);
+
 #[derive(Debug, Error)]
 pub enum DownloadError {
   #[error(transparent)]
@@ -289,11 +316,14 @@ pub enum DownloadError {
   TooManyRedirects,
   #[error(transparent)]
   BadResponse(#[from] BadResponseError),
+  #[error(transparent)]
+  Offline(#[from] OfflineError),
 }
 
 #[derive(Debug)]
 pub struct HttpClient {
   client: deno_fetch::Client,
+  offline: bool,
   // don't allow sending this across threads because then
   // it might be shared accidentally across tokio runtimes
   // which will cause issues
@@ -304,13 +334,22 @@ pub struct HttpClient {
 impl HttpClient {
   // DO NOT make this public. You should always be creating one of these from
   // the HttpClientProvider
-  fn new(client: deno_fetch::Client) -> Self {
+  fn new(client: deno_fetch::Client, offline: bool) -> Self {
     Self {
       client,
+      offline,
       _unsend_marker: deno_core::unsync::UnsendMarker::default(),
     }
   }
 
+  fn ensure_online(&self, url: &Url) -> Result<(), OfflineError> {
+    if self.offline {
+      Err(OfflineError(url.to_string()))
+    } else {
+      Ok(())
+    }
+  }
+
   pub fn get(&self, url: Url) -> Result<RequestBuilder, http::Error> {
     let body = http_body_util::Empty::new()
       .map_err(|never| match never {})
@@ -319,6 +358,7 @@ impl HttpClient {
     *req.uri_mut() = url.as_str().parse()?;
     Ok(RequestBuilder {
       client: self.client.clone(),
+      offline: self.offline,
       req,
     })
   }
@@ -333,6 +373,22 @@ impl HttpClient {
     *req.uri_mut() = url.as_str().parse()?;
     Ok(RequestBuilder {
       client: self.client.clone(),
+      offline: self.offline,
+      req,
+    })
+  }
+
+  pub fn put(
+    &self,
+    url: Url,
+    body: deno_fetch::ReqBody,
+  ) -> Result<RequestBuilder, http::Error> {
+    let mut req = http::Request::new(body);
+    *req.method_mut() = http::Method::PUT;
+    *req.uri_mut() = url.as_str().parse()?;
+    Ok(RequestBuilder {
+      client: self.client.clone(),
+      offline: self.offline,
       req,
     })
   }
@@ -365,6 +421,7 @@ impl HttpClient {
     &self,
     args: FetchOnceArgs<'a>,
   ) -> Result<FetchOnceResult, AnyError> {
+    self.ensure_online(&args.url)?;
     let body = http_body_util::Empty::new()
       .map_err(|never| match never {})
       .boxed();
@@ -462,6 +519,15 @@ impl HttpClient {
     Ok(String::from_utf8(bytes)?)
   }
 
+  /// Downloads the text at the url, or `None` when it's not found.
+  pub async fn download_text_if_exists(
+    &self,
+    url: Url,
+  ) -> Result<Option<String>, AnyError> {
+    let maybe_bytes = self.download_inner(url, None, None).await?;
+    Ok(maybe_bytes.map(String::from_utf8).transpose()?)
+  }
+
   pub async fn download(&self, url: Url) -> Result<Vec<u8>, AnyError> {
     let maybe_bytes = self.download_inner(url, None, None).await?;
     match maybe_bytes {
@@ -522,6 +588,7 @@ impl HttpClient {
     mut url: Url,
     mut maybe_header: Option<(HeaderName, HeaderValue)>,
   ) -> Result<(http::Response<deno_fetch::ResBody>, Url), DownloadError> {
+    self.ensure_online(&url)?;
     let mut req = self.get(url.clone())?.build();
     if let Some((header_name, header_value)) = maybe_header.as_ref() {
       req.headers_mut().append(header_name, header_value.clone());
@@ -666,6 +733,7 @@ where
 
 pub struct RequestBuilder {
   client: deno_fetch::Client,
+  offline: bool,
   req: http::Request<deno_fetch::ReqBody>,
 }
 
@@ -678,6 +746,9 @@ impl RequestBuilder {
   pub async fn send(
     self,
   ) -> Result<http::Response<deno_fetch::ResBody>, AnyError> {
+    if self.offline {
+      return Err(OfflineError(self.req.uri().to_string()).into());
+    }
     self.client.send(self.req).await.map_err(Into::into)
   }
 
@@ -768,6 +839,7 @@ mod test {
     HttpClient::new(
       create_http_client("test_client", CreateHttpClientOptions::default())
         .unwrap(),
+      false,
     )
   }
 
@@ -958,6 +1030,7 @@ mod test {
         },
       )
       .unwrap(),
+      false,
     );
     let result = client
       .fetch_no_follow(FetchOnceArgs {
@@ -1004,6 +1077,7 @@ mod test {
           CreateHttpClientOptions::default(),
         )
         .unwrap(),
+        false,
       );
 
       let result = client
@@ -1066,6 +1140,7 @@ mod test {
         },
       )
       .unwrap(),
+      false,
     );
 
     let result = client
@@ -1120,6 +1195,7 @@ mod test {
         },
       )
       .unwrap(),
+      false,
     );
     let result = client
       .fetch_no_follow(FetchOnceArgs {
@@ -1161,6 +1237,7 @@ mod test {
         },
       )
       .unwrap(),
+      false,
     );
     let result = client
       .fetch_no_follow(FetchOnceArgs {
@@ -1217,6 +1294,7 @@ mod test {
         },
       )
       .unwrap(),
+      false,
     );
     let result = client
       .fetch_no_follow(FetchOnceArgs {
@@ -1303,4 +1381,28 @@ mod test {
 
     assert!(matches!(result, Ok(FetchOnceResult::RequestError(_))));
   }
+
+  #[tokio::test]
+  async fn offline() {
+    let client = HttpClientProvider::new(None, None)
+      .with_offline(true)
+      .get_or_create()
+      .unwrap();
+    let url = Url::parse("http://127.0.0.1:4545/assets/fixture.json").unwrap();
+    let err = client.download(url.clone()).await.unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Network access to \"http://127.0.0.1:4545/assets/fixture.json\" is required, but --offline is specified."
+    );
+    let result = client
+      .fetch_no_follow(FetchOnceArgs {
+        url,
+        maybe_accept: None,
+        maybe_etag: None,
+        maybe_auth_token: None,
+        maybe_progress_guard: None,
+      })
+      .await;
+    assert!(result.is_err());
+  }
 }
@@ -14,11 +14,16 @@ const {
   op_bench_get_origin,
   op_dispatch_bench_event,
   op_bench_now,
+  op_bench_collect_samples,
+  op_bench_get_engine_options,
 } = core.ops;
 const {
+  ArrayPrototypeFilter,
   ArrayPrototypePush,
+  ArrayPrototypeSlice,
   Error,
   MathCeil,
+  MathFloor,
   SymbolToStringTag,
   TypeError,
 } = primordials;
@@ -199,6 +204,7 @@ function benchStats(
   min,
   max,
   all,
+  samples,
 ) {
   return {
     n,
@@ -211,22 +217,46 @@ function benchStats(
     avg: !highPrecision ? (avg / n) : MathCeil(avg / n),
     highPrecision,
     usedExplicitTimers,
+    samples,
   };
 }
 
-async function benchMeasure(timeBudget, fn, async, context) {
+/**
+ * Drops the samples outside of 1.5 interquartile ranges of the quartiles.
+ * @param all {number[]} sorted samples
+ */
+function rejectOutliers(all) {
+  const q1 = all[MathFloor((all.length - 1) * 0.25)];
+  const q3 = all[MathFloor((all.length - 1) * 0.75)];
+  const iqr = q3 - q1;
+  return ArrayPrototypeFilter(
+    all,
+    (sample) => sample >= q1 - 1.5 * iqr && sample <= q3 + 1.5 * iqr,
+  );
+}
+
+/**
+ * @param engine {{
+ *   warmup: number,
+ *   minSamples: number,
+ *   maxTimeMs: number,
+ *   outliers: "keep" | "iqr",
+ * }}
+ */
+async function benchMeasure(engine, fn, async, context) {
+  const timeBudget = engine.maxTimeMs;
   let n = 0;
   let avg = 0;
   let wavg = 0;
   let usedExplicitTimers = false;
-  const all = [];
+  let all = [];
   let min = Infinity;
   let max = -Infinity;
   const lowPrecisionThresholdInNs = 1e4;
 
   // warmup step
   let c = 0;
-  let iterations = 20;
+  let iterations = engine.warmup;
   let budget = 10 * 1e6;
 
   if (!async) {
@@ -273,7 +303,7 @@ async function benchMeasure(timeBudget, fn, async, context) {
 
   // measure step
   if (wavg > lowPrecisionThresholdInNs) {
-    let iterations = 10;
+    let iterations = engine.minSamples;
     let budget = timeBudget * 1e6;
 
     if (!async) {
@@ -326,7 +356,7 @@ async function benchMeasure(timeBudget, fn, async, context) {
   } else {
     context.start = function start() {};
     context.end = function end() {};
-    let iterations = 10;
+    let iterations = engine.minSamples;
     let budget = timeBudget * 1e6;
 
     if (!async) {
@@ -364,7 +394,17 @@ async function benchMeasure(timeBudget, fn, async, context) {
     }
   }
 
+  // Keep the samples in measurement order before sorting them for percentiles.
+  const samples = op_bench_collect_samples() ? ArrayPrototypeSlice(all) : null;
   all.sort(compareMeasurements);
+  if (engine.outliers === "iqr") {
+    all = rejectOutliers(all);
+    n = all.length;
+    avg = 0;
+    for (let i = 0; i < n; i++) avg += all[i];
+    min = all[0];
+    max = all[n - 1];
+  }
   return benchStats(
     n,
     wavg > lowPrecisionThresholdInNs,
@@ -373,6 +413,7 @@ async function benchMeasure(timeBudget, fn, async, context) {
     min,
     max,
     all,
+    samples,
   );
 }
 
@@ -435,10 +476,9 @@ function wrapBenchmark(desc) {
         });
       }
 
-      const benchTimeInMs = 500;
       const context = createBenchContext(desc);
       const stats = await benchMeasure(
-        benchTimeInMs,
+        op_bench_get_engine_options(),
         fn,
         desc.async,
         context,
@@ -35,11 +35,21 @@
  *  "text/markdown": "# Hello, world!",
  * }, { raw: true });
  * ```
+ *
+ * @example
+ * Displaying the image of a plot.
+ * ```typescript
+ * await display(image(await chart.toPNG(), { width: 640 }));
+ * ```
  */
 import { core, internals } from "ext:core/mod.js";
+import { forgivingBase64Encode } from "ext:deno_web/00_infra.js";
 
 const $display = Symbol.for("Jupyter.display");
 
+/** Metadata of the outputs of displayable objects, like the size of images */
+const displayMetadata = new WeakMap();
+
 /** Escape copied from https://jsr.io/@std/html/0.221.0/entities.ts */
 const rawToEntityEntries = [
   ["&", "&amp;"],
@@ -314,6 +324,85 @@ const html = createTaggedTemplateDisplayable("text/html");
  */
 const svg = createTaggedTemplateDisplayable("image/svg+xml");
 
+/** The magic numbers of the supported binary image formats */
+const imageSignatures = [
+  ["image/png", [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a]],
+  ["image/jpeg", [0xff, 0xd8, 0xff]],
+  ["image/gif", [0x47, 0x49, 0x46, 0x38]],
+];
+
+function detectImageMediaType(bytes) {
+  for (const [mediaType, signature] of imageSignatures) {
+    if (signature.every((byte, i) => bytes[i] === byte)) {
+      return mediaType;
+    }
+  }
+  const decoder = new TextDecoder();
+  if (
+    decoder.decode(bytes.subarray(0, 4)) === "RIFF" &&
+    decoder.decode(bytes.subarray(8, 12)) === "WEBP"
+  ) {
+    return "image/webp";
+  }
+  const start = decoder.decode(bytes.subarray(0, 512)).trimStart();
+  if (start.startsWith("<svg") || start.startsWith("<?xml")) {
+    return "image/svg+xml";
+  }
+  return null;
+}
+
+/**
+ * Show an image, like a plot rendered by a plotting library, in Jupyter
+ * frontends.
+ *
+ * @param data - The path of the image file or its bytes
+ * @param options - The media type, when it can't be detected, and the size
+ * @returns A displayable object
+ */
+function image(data, options = { __proto__: null }) {
+  let bytes;
+  if (typeof data === "string") {
+    bytes = Deno.readFileSync(data);
+  } else if (data instanceof ArrayBuffer) {
+    bytes = new Uint8Array(data);
+  } else if (ArrayBuffer.isView(data)) {
+    bytes = new Uint8Array(data.buffer, data.byteOffset, data.byteLength);
+  } else {
+    throw new TypeError(
+      "Expected the image to be a path, an ArrayBuffer or a typed array",
+    );
+  }
+  const mediaType = options.mediaType ?? detectImageMediaType(bytes);
+  if (mediaType == null) {
+    throw new TypeError(
+      "Unsupported image format, expected PNG, JPEG, GIF, WebP or SVG",
+    );
+  }
+  const value = mediaType === "image/svg+xml"
+    ? new TextDecoder().decode(bytes)
+    : forgivingBase64Encode(bytes);
+  const displayable = makeDisplayable({
+    [mediaType]: value,
+    "text/plain": `<${mediaType} image, ${bytes.byteLength} bytes>`,
+  });
+  const size = { __proto__: null };
+  if (options.width !== undefined) {
+    size.width = options.width;
+  }
+  if (options.height !== undefined) {
+    size.height = options.height;
+  }
+  if (Object.keys(size).length > 0) {
+    displayMetadata.set(displayable, { [mediaType]: size });
+  }
+  return displayable;
+}
+
+function getDisplayMetadata(obj) {
+  return (obj !== null && typeof obj === "object" &&
+    displayMetadata.get(obj)) || {};
+}
+
 function isMediaBundle(obj) {
   if (obj == null || typeof obj !== "object" || Array.isArray(obj)) {
     return false;
@@ -364,7 +453,7 @@ function enableJupyter() {
       await broadcast("execute_result", {
         execution_count: executionCount,
         data,
-        metadata: {},
+        metadata: getDisplayMetadata(result),
       });
     } catch (err) {
       if (err instanceof Error) {
@@ -413,7 +502,7 @@ function enableJupyter() {
     }
     await broadcast(messageType, {
       data: bundle,
-      metadata: {},
+      metadata: getDisplayMetadata(obj),
       transient,
     });
     return;
@@ -465,6 +554,7 @@ function enableJupyter() {
     md,
     html,
     svg,
+    image,
     $display,
   };
 }
@@ -1,6 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
-import { core, primordials } from "ext:core/mod.js";
+import { core, internals, primordials } from "ext:core/mod.js";
 import { escapeName, withPermissions } from "ext:cli/40_test_common.js";
 
 // TODO(mmastrac): We cannot import these from "ext:core/ops" yet
@@ -11,22 +11,33 @@ const {
   op_test_event_step_result_ignored,
   op_test_event_step_result_ok,
   op_test_event_step_wait,
+  op_test_get_artifacts_dir,
   op_test_get_origin,
+  op_test_get_update_snapshots,
+  op_test_wait_for_fail_fast,
 } = core.ops;
 const {
+  ArrayIsArray,
+  ArrayPrototypeEvery,
   ArrayPrototypeFilter,
   ArrayPrototypePush,
   DateNow,
   Error,
   Map,
+  MapPrototypeDelete,
+  MapPrototypeForEach,
   MapPrototypeGet,
   MapPrototypeSet,
+  ObjectDefineProperty,
+  PromisePrototypeThen,
   SafeArrayIterator,
   SymbolToStringTag,
   TypeError,
 } = primordials;
 
 import { setExitHandler } from "ext:runtime/30_os.js";
+import { AbortController } from "ext:deno_web/03_abort_signal.js";
+import { DOMException } from "ext:deno_web/01_dom_exception.js";
 
 // Capture `Deno` global so that users deleting or mangling it, won't
 // have impact on our sanitizers.
@@ -91,6 +102,43 @@ const DenoNs = globalThis.Deno;
 /** @type {Map<number, TestState | TestStepState>} */
 const testStates = new Map();
 
+/**
+ * The abort controllers of the running tests which accessed their signal, by
+ * the id of the root test.
+ * @type {Map<number, AbortController>}
+ */
+const abortControllers = new Map();
+let waitingForFailFast = false;
+let failFastReason = undefined;
+
+/** @param rootId {number} */
+function getTestSignal(rootId) {
+  let controller = MapPrototypeGet(abortControllers, rootId);
+  if (controller === undefined) {
+    controller = new AbortController();
+    if (failFastReason !== undefined) {
+      controller.abort(failFastReason);
+    }
+    MapPrototypeSet(abortControllers, rootId, controller);
+  }
+  if (!waitingForFailFast) {
+    waitingForFailFast = true;
+    const promise = op_test_wait_for_fail_fast();
+    core.unrefOpPromise(promise);
+    PromisePrototypeThen(promise, () => {
+      failFastReason = new DOMException(
+        "The test run was stopped by --fail-fast",
+        "AbortError",
+      );
+      MapPrototypeForEach(
+        abortControllers,
+        (controller) => controller.abort(failFastReason),
+      );
+    });
+  }
+  return controller.signal;
+}
+
 // Wrap test function in additional assertion that makes sure
 // that the test case does not accidentally exit prematurely.
 function assertExit(fn, isTest) {
@@ -140,6 +188,9 @@ function wrapOuter(fn, desc) {
         stepReportResult(childDesc, { failed: "incomplete" }, 0);
       }
       state.completed = true;
+      if (!("parent" in desc)) {
+        MapPrototypeDelete(abortControllers, desc.id);
+      }
     }
   };
 }
@@ -228,6 +279,7 @@ function testInner(
     sanitizeResources: true,
     sanitizeExit: true,
     permissions: null,
+    tags: [],
   };
 
   if (typeof nameOrFnOrOptions === "string") {
@@ -309,6 +361,13 @@ function testInner(
     cachedOrigin = op_test_get_origin();
   }
 
+  if (
+    !ArrayIsArray(testDesc.tags) ||
+    !ArrayPrototypeEvery(testDesc.tags, (tag) => typeof tag === "string")
+  ) {
+    throw new TypeError("The test tags must be an array of strings");
+  }
+
   testDesc.location = core.currentUserCallSite();
   testDesc.fn = wrapTest(testDesc);
   testDesc.name = escapeName(testDesc.name);
@@ -320,6 +379,7 @@ function testInner(
     testDesc.only,
     testDesc.sanitizeOps,
     testDesc.sanitizeResources,
+    testDesc.tags,
     testDesc.location.fileName,
     testDesc.location.lineNumber,
     testDesc.location.columnNumber,
@@ -355,6 +415,43 @@ test.only = function (
   return testInner(nameOrFnOrOptions, optionsOrFn, maybeFn, { only: true });
 };
 
+/** The value which the default export of the `--setup` module resolved to. */
+let setupData;
+
+test.getSetupData = function () {
+  return setupData;
+};
+
+/**
+ * Runs the default export of a `--setup` or `--teardown` module before or
+ * after the tests of the module.
+ * @param {{ default: unknown }} mod
+ * @param {boolean} isSetup
+ */
+async function runTestFixture(mod, isSetup) {
+  if (typeof mod.default !== "function") {
+    throw new TypeError(
+      "The test fixture module must export a default function",
+    );
+  }
+  if (isSetup) {
+    setupData = await mod.default();
+  } else {
+    await mod.default(setupData);
+  }
+}
+
+internals.runTestFixture = runTestFixture;
+
+ObjectDefineProperty(test, "updateSnapshots", {
+  __proto__: null,
+  get() {
+    return op_test_get_update_snapshots();
+  },
+  enumerable: true,
+  configurable: false,
+});
+
 function getFullName(desc) {
   if ("parent" in desc) {
     return `${getFullName(desc.parent)} ... ${desc.name}`;
@@ -362,6 +459,15 @@ function getFullName(desc) {
   return desc.name;
 }
 
+function getNames(desc) {
+  if ("parent" in desc) {
+    const names = getNames(desc.parent);
+    ArrayPrototypePush(names, desc.name);
+    return names;
+  }
+  return [desc.name];
+}
+
 function usesSanitizer(desc) {
   return desc.sanitizeResources || desc.sanitizeOps || desc.sanitizeExit;
 }
@@ -411,6 +517,20 @@ function createTestContext(desc) {
      * File Uri of the test code.
      */
     origin: desc.origin,
+    /**
+     * The directory for the screenshots, logs and other files of the test,
+     * when `--artifacts-dir` is passed. It's created on first access.
+     */
+    get artifactsDir() {
+      return op_test_get_artifacts_dir(desc.id, getNames(desc)) ?? undefined;
+    },
+    /**
+     * Aborted when `--fail-fast` stops the test run while the test is still
+     * running. Steps share the signal of their test.
+     */
+    get signal() {
+      return getTestSignal(rootId);
+    },
     /**
      * @param nameOrFnOrOptions {string | TestStepDefinition | ((t: TestContext) => void | Promise<void>)}
      * @param maybeFn {((t: TestContext) => void | Promise<void>) | undefined}
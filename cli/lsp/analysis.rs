@@ -706,6 +706,29 @@ pub fn ts_changes_to_edit(
   }))
 }
 
+/// Converts a TypeScript code fix into a refactor code action, for fixes of
+/// suggestion diagnostics which are useful to apply from a selection, like
+/// converting a function to an async function.
+pub fn ts_fix_to_refactor_action(
+  specifier: &ModuleSpecifier,
+  action: &tsc::CodeFixAction,
+  kind: lsp::CodeActionKind,
+  language_server: &language_server::Inner,
+) -> Result<lsp::CodeAction, AnyError> {
+  let action = fix_ts_import_action(
+    specifier,
+    action,
+    &language_server.get_ts_response_import_mapper(specifier),
+  )?;
+  let edit = ts_changes_to_edit(&action.changes, language_server)?;
+  Ok(lsp::CodeAction {
+    title: action.description.clone(),
+    kind: Some(kind),
+    edit,
+    ..Default::default()
+  })
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CodeActionData {
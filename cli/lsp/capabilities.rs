@@ -10,6 +10,7 @@ use tower_lsp::lsp_types::*;
 
 use super::refactor::ALL_KNOWN_REFACTOR_ACTION_KINDS;
 use super::semantic_tokens::get_legend;
+use super::tasks;
 
 fn code_action_capabilities(
   client_capabilities: &ClientCapabilities,
@@ -122,6 +123,10 @@ pub fn server_capabilities(
       commands: vec![
         "deno.cache".to_string(),
         "deno.reloadImportRegistries".to_string(),
+        tasks::TASK_LIST_COMMAND.to_string(),
+        tasks::TASK_RUN_COMMAND.to_string(),
+        tasks::TASK_DEBUG_COMMAND.to_string(),
+        tasks::TASK_STOP_COMMAND.to_string(),
       ],
       ..Default::default()
     }),
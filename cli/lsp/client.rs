@@ -92,6 +92,17 @@ impl Client {
     });
   }
 
+  pub fn send_task_run_progress_notification(
+    &self,
+    params: lsp_custom::TaskRunProgressParams,
+  ) {
+    // do on a task in case the caller currently is in the lsp lock
+    let client = self.0.clone();
+    spawn(async move {
+      client.send_task_run_progress_notification(params).await;
+    });
+  }
+
   pub fn send_did_change_deno_configuration_notification(
     &self,
     params: lsp_custom::DidChangeDenoConfigurationNotificationParams,
@@ -169,6 +180,10 @@ trait ClientTrait: Send + Sync {
     params: lsp_custom::DiagnosticBatchNotificationParams,
   );
   async fn send_test_notification(&self, params: TestingNotification);
+  async fn send_task_run_progress_notification(
+    &self,
+    params: lsp_custom::TaskRunProgressParams,
+  );
   async fn send_did_change_deno_configuration_notification(
     &self,
     params: lsp_custom::DidChangeDenoConfigurationNotificationParams,
@@ -249,6 +264,16 @@ impl ClientTrait for TowerClient {
     }
   }
 
+  async fn send_task_run_progress_notification(
+    &self,
+    params: lsp_custom::TaskRunProgressParams,
+  ) {
+    self
+      .0
+      .send_notification::<lsp_custom::TaskRunProgressNotification>(params)
+      .await
+  }
+
   async fn send_did_change_deno_configuration_notification(
     &self,
     params: lsp_custom::DidChangeDenoConfigurationNotificationParams,
@@ -366,6 +391,12 @@ impl ClientTrait for ReplClient {
 
   async fn send_test_notification(&self, _params: TestingNotification) {}
 
+  async fn send_task_run_progress_notification(
+    &self,
+    _params: lsp_custom::TaskRunProgressParams,
+  ) {
+  }
+
   async fn send_did_change_deno_configuration_notification(
     &self,
     _params: lsp_custom::DidChangeDenoConfigurationNotificationParams,
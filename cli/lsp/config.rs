@@ -50,8 +50,10 @@ use std::sync::Arc;
 use tower_lsp::lsp_types as lsp;
 
 use super::logging::lsp_log;
+use crate::args::deno_jsons_with_dev_imports;
 use crate::args::discover_npmrc_from_workspace;
 use crate::args::has_flag_env_var;
+use crate::args::workspace_dev_import_map;
 use crate::args::CliLockfile;
 use crate::args::CliLockfileReadFromPathOptions;
 use crate::args::ConfigFile;
@@ -509,6 +511,34 @@ impl InspectSetting {
   }
 }
 
+/// Which files the language server loads into memory ahead of them being
+/// opened or imported.
+#[derive(
+  Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum DocumentPreloadScope {
+  /// Preload the files of the whole workspace, up to the preload limit.
+  #[default]
+  Workspace,
+  /// Only preload the exports of the workspace's configuration files.
+  Entrypoints,
+}
+
+/// Which files the language server publishes diagnostics for.
+#[derive(
+  Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticsScope {
+  /// Only the files which are open in the editor.
+  #[default]
+  OpenFiles,
+  /// Also the files of the workspace which aren't open, which are type
+  /// checked and linted in the background with a lower priority.
+  Workspace,
+}
+
 /// Deno language server specific settings that are applied to a workspace.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -552,6 +582,10 @@ pub struct WorkspaceSettings {
   #[serde(default)]
   pub code_lens: CodeLensSettings,
 
+  /// Which files diagnostics are published for.
+  #[serde(default)]
+  pub diagnostics_scope: DiagnosticsScope,
+
   /// A flag that indicates if internal debug logging should be made available.
   #[serde(default)]
   pub internal_debug: bool,
@@ -571,6 +605,15 @@ pub struct WorkspaceSettings {
   #[serde(default = "default_document_preload_limit")]
   pub document_preload_limit: usize,
 
+  /// Which files can be preloaded by the language server.
+  #[serde(default)]
+  pub document_preload_scope: DocumentPreloadScope,
+
+  /// When the resident memory of the language server exceeds this amount of
+  /// megabytes, documents which aren't open are evicted from its caches.
+  #[serde(default)]
+  pub memory_budget_mb: Option<u64>,
+
   #[serde(default)]
   pub suggest: DenoCompletionSettings,
 
@@ -610,11 +653,14 @@ impl Default for WorkspaceSettings {
       config: None,
       import_map: None,
       code_lens: Default::default(),
+      diagnostics_scope: Default::default(),
       internal_debug: false,
       internal_inspect: Default::default(),
       log_file: false,
       lint: true,
       document_preload_limit: default_document_preload_limit(),
+      document_preload_scope: Default::default(),
+      memory_budget_mb: None,
       suggest: Default::default(),
       testing: Default::default(),
       tls_certificate: None,
@@ -872,6 +918,7 @@ impl Settings {
     hasher.write_hashable(&unscoped.enable_paths);
     hasher.write_hashable(&unscoped.disable_paths);
     hasher.write_hashable(unscoped.document_preload_limit);
+    hasher.write_hashable(unscoped.document_preload_scope);
     for (folder_uri, settings) in &self.by_workspace_folder {
       hasher.write_hashable(folder_uri);
       hasher.write_hashable(
@@ -1340,7 +1387,7 @@ impl ConfigData {
     }
 
     // todo(dsherret): cache this so we don't load this so many times
-    let npmrc = discover_npmrc_from_workspace(&member_dir.workspace)
+    let npmrc = discover_npmrc_from_workspace(&member_dir.workspace, None)
       .inspect(|(_, path)| {
         if let Some(path) = path {
           lsp_log!("  Resolved .npmrc: \"{}\"", path.display());
@@ -1507,6 +1554,12 @@ impl ConfigData {
         import_map_from_settings = None;
       }
       if let Some(import_map_url) = &import_map_from_settings {
+        for specifier in deno_jsons_with_dev_imports(&member_dir.workspace) {
+          lsp_warn!(
+            "  \"devImports\" of {} are ignored, because an import map is specified in the settings.",
+            specifier
+          );
+        }
         add_watched_file(
           import_map_url.clone(),
           ConfigWatchedFileType::ImportMap,
@@ -1542,7 +1595,10 @@ impl ConfigData {
           }
         }
       } else {
-        None
+        workspace_dev_import_map(&member_dir.workspace).unwrap_or_else(|err| {
+          lsp_warn!("  Failed to resolve \"devImports\": {}", err);
+          None
+        })
       }
     };
     let resolver = member_dir
@@ -1698,6 +1754,24 @@ impl ConfigTree {
       .collect()
   }
 
+  /// Returns the modules listed in the `exports` of each scope's `deno.json`,
+  /// along with the `deno.json` files themselves.
+  pub fn entrypoints(&self) -> IndexSet<ModuleSpecifier> {
+    let mut entrypoints = IndexSet::new();
+    for config_file in self.config_files() {
+      entrypoints.insert(config_file.specifier.clone());
+      let Ok(exports) = config_file.to_exports_config() else {
+        continue;
+      };
+      for key in exports.clone().into_map().into_keys() {
+        if let Ok(Some(specifier)) = exports.get_resolved(&key) {
+          entrypoints.insert(specifier);
+        }
+      }
+    }
+    entrypoints
+  }
+
   pub fn package_jsons(&self) -> Vec<&Arc<PackageJson>> {
     self
       .scopes
@@ -2165,11 +2239,14 @@ mod tests {
           references_all_functions: false,
           test: true,
         },
+        diagnostics_scope: DiagnosticsScope::OpenFiles,
         internal_debug: false,
         internal_inspect: InspectSetting::Bool(false),
         log_file: false,
         lint: true,
         document_preload_limit: 1_000,
+        document_preload_scope: DocumentPreloadScope::Workspace,
+        memory_budget_mb: None,
         suggest: DenoCompletionSettings {
           imports: ImportCompletionSettings {
             auto_discover: true,
@@ -0,0 +1,234 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Prints the configuration which the language server resolves for the
+//! current directory, which is used by `deno lsp --diagnose` to debug editor
+//! setups without starting a server.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use deno_core::serde_json::Map;
+use deno_core::serde_json::Value;
+
+use super::config::WorkspaceSettings;
+use super::registries::ModuleRegistry;
+use crate::args::ConfigFlag;
+use crate::args::Flags;
+use crate::args::LspFlags;
+use crate::factory::CliFactory;
+use crate::version;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Diagnosis {
+  version: String,
+  cwd: PathBuf,
+  settings_file: Option<PathBuf>,
+  settings: WorkspaceSettings,
+  config_files: Vec<PathBuf>,
+  registries: RegistriesDiagnosis,
+  cache: CacheDiagnosis,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RegistriesDiagnosis {
+  auto_discover: bool,
+  hosts: Vec<RegistryHostStatus>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RegistryHostStatus {
+  origin: String,
+  enabled: bool,
+  /// Whether the origin serves a valid import completions configuration.
+  /// Disabled origins aren't checked.
+  supported: Option<bool>,
+  error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheDiagnosis {
+  deno_dir: PathBuf,
+  remote: PathBuf,
+  npm: PathBuf,
+  registries: PathBuf,
+  lsp_status: PathBuf,
+}
+
+/// Prints the resolved settings, the discovered config files, the status of
+/// the import completion registries and the cache paths as JSON.
+#[allow(clippy::print_stdout)]
+pub async fn print_diagnosis(
+  flags: Arc<Flags>,
+  lsp_flags: LspFlags,
+) -> Result<(), AnyError> {
+  let cwd = std::env::current_dir().context("Failed getting cwd")?;
+  let settings_file = lsp_flags.settings.map(|path| cwd.join(path));
+  let settings = match &settings_file {
+    Some(path) => {
+      let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed reading '{}'", path.display()))?;
+      let value =
+        jsonc_parser::parse_to_serde_value(&text, &Default::default())
+          .with_context(|| format!("Failed parsing '{}'", path.display()))?
+          .unwrap_or(Value::Null);
+      parse_settings(value)
+    }
+    None => WorkspaceSettings::default(),
+  };
+
+  // resolve the config files the same way as the language server, where the
+  // settings take precedence over the discovery
+  let mut flags = (*flags).clone();
+  if let Some(config) = &settings.config {
+    flags.config_flag = ConfigFlag::Path(config.clone());
+  }
+  if let Some(import_map) = &settings.import_map {
+    flags.import_map_path = Some(import_map.clone());
+  }
+  if let Some(cache) = &settings.cache {
+    flags.internal.cache_path = Some(cwd.join(cache));
+  }
+  let factory = CliFactory::from_flags(Arc::new(flags));
+  let cli_options = factory.cli_options()?;
+  let config_files = cli_options.watch_paths();
+
+  let deno_dir = factory.deno_dir()?;
+  let module_registry = ModuleRegistry::new(
+    deno_dir.registries_folder_path(),
+    factory.http_client_provider().clone(),
+  );
+  let mut hosts = settings
+    .suggest
+    .imports
+    .hosts
+    .iter()
+    .map(|(origin, enabled)| (origin.clone(), *enabled))
+    .collect::<Vec<_>>();
+  hosts.sort();
+  let mut host_statuses = Vec::with_capacity(hosts.len());
+  for (origin, enabled) in hosts {
+    let (supported, error) = if enabled {
+      match module_registry.check_origin(&origin).await {
+        Ok(()) => (Some(true), None),
+        Err(err) => (Some(false), Some(format!("{err:#}"))),
+      }
+    } else {
+      (None, None)
+    };
+    host_statuses.push(RegistryHostStatus {
+      origin,
+      enabled,
+      supported,
+      error,
+    });
+  }
+
+  let diagnosis = Diagnosis {
+    version: version::DENO_VERSION_INFO.deno.to_string(),
+    cwd,
+    settings_file,
+    registries: RegistriesDiagnosis {
+      auto_discover: settings.suggest.imports.auto_discover,
+      hosts: host_statuses,
+    },
+    settings,
+    config_files,
+    cache: CacheDiagnosis {
+      remote: deno_dir.remote_folder_path(),
+      npm: deno_dir.npm_folder_path(),
+      registries: deno_dir.registries_folder_path(),
+      lsp_status: deno_dir.lsp_status_folder_path(),
+      deno_dir: deno_dir.root.clone(),
+    },
+  };
+  println!("{}", serde_json::to_string_pretty(&diagnosis)?);
+  Ok(())
+}
+
+/// Parses the settings of an editor, which are either grouped under the
+/// `"deno"`, `"javascript"` and `"typescript"` keys or flattened as in the
+/// VS Code `settings.json`, like `"deno.suggest.imports.hosts"`.
+fn parse_settings(value: Value) -> WorkspaceSettings {
+  let mut value = unflatten_settings(value);
+  let mut take = |key: &str| {
+    value
+      .as_object_mut()
+      .and_then(|obj| obj.remove(key))
+      .unwrap_or(Value::Null)
+  };
+  let deno = take("deno");
+  let javascript = take("javascript");
+  let typescript = take("typescript");
+  WorkspaceSettings::from_raw_settings(deno, javascript, typescript)
+}
+
+fn unflatten_settings(value: Value) -> Value {
+  let Value::Object(obj) = value else {
+    return value;
+  };
+  let mut result = Map::new();
+  for (key, value) in obj {
+    let mut parts = key.split('.').collect::<Vec<_>>();
+    let last = parts.pop().unwrap();
+    let mut current = &mut result;
+    for part in parts {
+      let entry = current
+        .entry(part.to_string())
+        .or_insert_with(|| Value::Object(Map::new()));
+      if !entry.is_object() {
+        *entry = Value::Object(Map::new());
+      }
+      current = entry.as_object_mut().unwrap();
+    }
+    match (current.get_mut(last), value) {
+      (Some(Value::Object(existing)), Value::Object(value)) => {
+        existing.extend(value);
+      }
+      (_, value) => {
+        current.insert(last.to_string(), value);
+      }
+    }
+  }
+  result.into()
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use deno_core::serde_json::json;
+
+  #[test]
+  fn test_parse_settings() {
+    let settings = parse_settings(json!({
+      "editor.tabSize": 2,
+      "deno.enable": true,
+      "deno.suggest.imports.hosts": {
+        "https://deno.land": false,
+      },
+      "deno.suggest.imports.autoDiscover": false,
+      "typescript.preferences.quoteStyle": "single",
+    }));
+    assert_eq!(settings.enable, Some(true));
+    assert!(!settings.suggest.imports.auto_discover);
+    assert_eq!(
+      settings.suggest.imports.hosts.get("https://deno.land"),
+      Some(&false)
+    );
+
+    let settings = parse_settings(json!({
+      "deno": { "enable": false, "config": "./deno.jsonc" },
+    }));
+    assert_eq!(settings.enable, Some(false));
+    assert_eq!(settings.config, Some("./deno.jsonc".to_string()));
+
+    assert_eq!(parse_settings(Value::Null), WorkspaceSettings::default());
+  }
+}
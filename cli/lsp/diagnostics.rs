@@ -3,6 +3,7 @@
 use super::analysis;
 use super::client::Client;
 use super::config::Config;
+use super::config::DiagnosticsScope;
 use super::documents;
 use super::documents::Document;
 use super::documents::Documents;
@@ -92,14 +93,18 @@ pub enum DiagnosticSource {
   Deno,
   Lint,
   Ts,
+  /// Lint diagnostics of the files of the workspace which aren't open.
+  WorkspaceLint,
+  /// TypeScript diagnostics of the files of the workspace which aren't open.
+  WorkspaceTs,
 }
 
 impl DiagnosticSource {
   pub fn as_lsp_source(&self) -> &'static str {
     match self {
       Self::Deno => "deno",
-      Self::Lint => "deno-lint",
-      Self::Ts => "deno-ts",
+      Self::Lint | Self::WorkspaceLint => "deno-lint",
+      Self::Ts | Self::WorkspaceTs => "deno-ts",
     }
   }
 }
@@ -446,6 +451,7 @@ impl DiagnosticsServer {
         let mut ts_handle: Option<JoinHandle<()>> = None;
         let mut lint_handle: Option<JoinHandle<()>> = None;
         let mut deps_handle: Option<JoinHandle<()>> = None;
+        let mut workspace_handle: Option<JoinHandle<()>> = None;
         let diagnostics_publisher =
           Arc::new(DiagnosticsPublisher::new(client.clone(), state.clone()));
 
@@ -652,6 +658,79 @@ impl DiagnosticsServer {
                   }
                 }
               }));
+
+              let previous_workspace_handle = workspace_handle.take();
+              workspace_handle = Some(spawn({
+                let performance = performance.clone();
+                let diagnostics_publisher = diagnostics_publisher.clone();
+                let ts_server = ts_server.clone();
+                let token = token.clone();
+                let snapshot = snapshot.clone();
+                let config = snapshot.config.clone();
+                let url_map = url_map.clone();
+                async move {
+                  if let Some(previous_handle) = previous_workspace_handle {
+                    previous_handle.await;
+                  }
+
+                  // Wait longer than the debounce of the open files, so their
+                  // diagnostics are requested first.
+                  const DELAY: Duration = Duration::from_millis(1_000);
+                  tokio::select! {
+                    _ = token.cancelled() => { return; }
+                    _ = tokio::time::sleep(DELAY) => {}
+                  };
+
+                  let mark =
+                    performance.mark("lsp.update_diagnostics_workspace");
+                  // still publish when disabled in order to clear the
+                  // diagnostics of a previous run
+                  let (ts_diagnostics, lint_diagnostics) =
+                    if config.workspace_settings().diagnostics_scope
+                      == DiagnosticsScope::Workspace
+                    {
+                      generate_workspace_diagnostics(
+                        snapshot.clone(),
+                        &ts_server,
+                        token.clone(),
+                      )
+                      .await
+                      .map_err(|err| {
+                        if !token.is_cancelled() {
+                          error!(
+                            "Error generating workspace diagnostics: {}",
+                            err
+                          );
+                          token.cancel();
+                        }
+                      })
+                      .unwrap_or_default()
+                    } else {
+                      Default::default()
+                    };
+
+                  if token.is_cancelled() {
+                    return;
+                  }
+                  for (source, diagnostics) in [
+                    (DiagnosticSource::WorkspaceTs, ts_diagnostics),
+                    (DiagnosticSource::WorkspaceLint, lint_diagnostics),
+                  ] {
+                    diagnostics_publisher
+                      .publish(
+                        source,
+                        diagnostics,
+                        &url_map,
+                        snapshot.documents.as_ref(),
+                        &token,
+                      )
+                      .await;
+                  }
+                  if !token.is_cancelled() {
+                    performance.measure(mark);
+                  }
+                }
+              }));
             }
           }
         }
@@ -796,6 +875,15 @@ fn generate_lint_diagnostics(
   let documents = snapshot
     .documents
     .documents(DocumentsFilter::OpenDiagnosable);
+  generate_documents_lint_diagnostics(snapshot, documents, config, token)
+}
+
+fn generate_documents_lint_diagnostics(
+  snapshot: &language_server::StateSnapshot,
+  documents: Vec<Arc<Document>>,
+  config: &Config,
+  token: CancellationToken,
+) -> DiagnosticVec {
   let config_data_by_scope = config.tree.data_by_scope();
   let mut diagnostics_vec = Vec::new();
   for document in documents {
@@ -890,12 +978,26 @@ async fn generate_ts_diagnostics(
   ts_server: &tsc::TsServer,
   token: CancellationToken,
 ) -> Result<DiagnosticVec, AnyError> {
-  let mut diagnostics_vec = Vec::new();
   let specifiers = snapshot
     .documents
     .documents(DocumentsFilter::OpenDiagnosable)
     .into_iter()
-    .map(|d| d.specifier().clone());
+    .map(|d| d.specifier().clone())
+    .collect();
+  generate_specifiers_ts_diagnostics(
+    snapshot, specifiers, config, ts_server, token,
+  )
+  .await
+}
+
+async fn generate_specifiers_ts_diagnostics(
+  snapshot: Arc<language_server::StateSnapshot>,
+  specifiers: Vec<ModuleSpecifier>,
+  config: &Config,
+  ts_server: &tsc::TsServer,
+  token: CancellationToken,
+) -> Result<DiagnosticVec, AnyError> {
+  let mut diagnostics_vec = Vec::new();
   let (enabled_specifiers, disabled_specifiers) = specifiers
     .into_iter()
     .partition::<Vec<_>, _>(|s| config.specifier_enabled(s));
@@ -945,6 +1047,65 @@ async fn generate_ts_diagnostics(
   Ok(diagnostics_vec)
 }
 
+/// The number of files which are checked at a time in the background, so the
+/// requests for the open files don't have to wait on the whole workspace.
+const WORKSPACE_DIAGNOSTICS_CHUNK_SIZE: usize = 50;
+
+/// Type checks and lints the files of the workspace which aren't open, in
+/// chunks which can be cancelled by a change to the documents. Returns the
+/// TypeScript and the lint diagnostics.
+async fn generate_workspace_diagnostics(
+  snapshot: Arc<language_server::StateSnapshot>,
+  ts_server: &tsc::TsServer,
+  token: CancellationToken,
+) -> Result<(DiagnosticVec, DiagnosticVec), AnyError> {
+  let documents = snapshot
+    .documents
+    .documents(DocumentsFilter::AllDiagnosable)
+    .into_iter()
+    .filter(|document| {
+      !document.is_open()
+        && document.specifier().scheme() == "file"
+        && !snapshot.resolver.in_node_modules(document.specifier())
+    })
+    .collect::<Vec<_>>();
+  let mut ts_diagnostics = Vec::new();
+  let mut lint_diagnostics = Vec::new();
+  for chunk in documents.chunks(WORKSPACE_DIAGNOSTICS_CHUNK_SIZE) {
+    if token.is_cancelled() {
+      break;
+    }
+    lint_diagnostics.extend(
+      spawn_blocking({
+        let snapshot = snapshot.clone();
+        let chunk = chunk.to_vec();
+        let token = token.clone();
+        move || {
+          generate_documents_lint_diagnostics(
+            &snapshot,
+            chunk,
+            &snapshot.config,
+            token,
+          )
+        }
+      })
+      .await
+      .unwrap(),
+    );
+    ts_diagnostics.extend(
+      generate_specifiers_ts_diagnostics(
+        snapshot.clone(),
+        chunk.iter().map(|d| d.specifier().clone()).collect(),
+        &snapshot.config,
+        ts_server,
+        token.clone(),
+      )
+      .await?,
+    );
+  }
+  Ok((ts_diagnostics, lint_diagnostics))
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DiagnosticDataSpecifier {
@@ -23,6 +23,8 @@ use deno_core::futures::future;
 use deno_core::futures::future::Shared;
 use deno_core::futures::FutureExt;
 use deno_core::parking_lot::Mutex;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
 use deno_core::ModuleSpecifier;
 use deno_graph::source::ResolutionMode;
 use deno_graph::Resolution;
@@ -926,6 +928,15 @@ impl FileSystemDocuments {
   }
 }
 
+#[derive(Debug, Default, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentsMemoryUsage {
+  pub open_count: usize,
+  pub open_bytes: usize,
+  pub file_system_count: usize,
+  pub file_system_bytes: usize,
+}
+
 /// Specify the documents to include on a `documents.documents(...)` call.
 #[derive(Debug, Clone, Copy)]
 pub enum DocumentsFilter {
@@ -1055,6 +1066,31 @@ impl Documents {
     self.file_system_docs.set_dirty(true);
   }
 
+  /// Drops all cached file system documents, which will be lazily reloaded
+  /// when they are requested again. Open documents are kept. Returns the
+  /// number of documents which were evicted.
+  pub fn evict_file_system_documents(&self) -> usize {
+    let count = self.file_system_docs.docs.len();
+    self.file_system_docs.docs.clear();
+    self.file_system_docs.set_dirty(true);
+    count
+  }
+
+  /// Returns the number of documents and the total size of their content in
+  /// bytes, split between open and file system documents.
+  pub fn memory_usage(&self) -> DocumentsMemoryUsage {
+    let mut usage = DocumentsMemoryUsage::default();
+    for doc in self.open_docs.values() {
+      usage.open_count += 1;
+      usage.open_bytes += doc.content().len();
+    }
+    for entry in self.file_system_docs.docs.iter() {
+      usage.file_system_count += 1;
+      usage.file_system_bytes += entry.value().content().len();
+    }
+    usage
+  }
+
   pub fn get_file_referrer<'a>(
     &self,
     specifier: &'a ModuleSpecifier,
@@ -24,6 +24,7 @@ use indexmap::IndexSet;
 use log::error;
 use serde::Deserialize;
 use serde_json::from_value;
+use std::borrow::Cow;
 use std::collections::BTreeMap;
 use std::collections::BTreeSet;
 use std::collections::HashMap;
@@ -34,6 +35,7 @@ use std::fmt::Write as _;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc::unbounded_channel;
 use tokio::sync::mpsc::UnboundedReceiver;
 use tokio::sync::mpsc::UnboundedSender;
@@ -44,6 +46,7 @@ use tower_lsp::lsp_types::*;
 
 use super::analysis::fix_ts_import_changes;
 use super::analysis::ts_changes_to_edit;
+use super::analysis::ts_fix_to_refactor_action;
 use super::analysis::CodeActionCollection;
 use super::analysis::CodeActionData;
 use super::analysis::TsResponseImportMapper;
@@ -53,6 +56,7 @@ use super::client::Client;
 use super::code_lens;
 use super::completions;
 use super::config::Config;
+use super::config::DocumentPreloadScope;
 use super::config::UpdateImportsOnFileMoveEnabled;
 use super::config::WorkspaceSettings;
 use super::config::SETTINGS_SECTION;
@@ -72,12 +76,16 @@ use super::logging::lsp_log;
 use super::logging::lsp_warn;
 use super::lsp_custom;
 use super::lsp_custom::TaskDefinition;
+use super::memory;
+use super::memory::MemoryStatus;
 use super::npm::CliNpmSearchApi;
 use super::parent_process_checker;
 use super::performance::Performance;
 use super::refactor;
 use super::registries::ModuleRegistry;
 use super::resolver::LspResolver;
+use super::tasks;
+use super::tasks::LspTaskRunner;
 use super::testing;
 use super::text;
 use super::tsc;
@@ -206,6 +214,14 @@ pub struct Inner {
   jsr_search_api: CliJsrSearchApi,
   /// Handles module registries, which allow discovery of modules
   module_registry: ModuleRegistry,
+  /// Folder where the memory status of the server is written to, read by
+  /// `deno lsp --status`.
+  lsp_status_folder: PathBuf,
+  last_memory_eviction: Option<Instant>,
+  /// The used heap size of tsc as of the last memory budget check.
+  tsc_heap_used_bytes: usize,
+  /// Number of times the caches were evicted due to `deno.memoryBudgetMb`.
+  memory_evictions: usize,
   /// A lazily create "server" for handling test run requests.
   maybe_testing_server: Option<testing::TestServer>,
   pub npm_search_api: CliNpmSearchApi,
@@ -214,6 +230,8 @@ pub struct Inner {
   performance: Arc<Performance>,
   pub resolver: Arc<LspResolver>,
   task_queue: LanguageServerTaskQueue,
+  /// Runs the tasks of the config files for the `deno.task.*` commands.
+  task_runner: LspTaskRunner,
   /// A memoized version of fixable diagnostic codes retrieved from TypeScript.
   ts_fixable_diagnostics: Vec<String>,
   /// An abstraction that handles interactions with TypeScript.
@@ -462,6 +480,7 @@ impl Inner {
       cache.deno_dir().registries_folder_path(),
       http_client_provider.clone(),
     );
+    let lsp_status_folder = cache.deno_dir().lsp_status_folder_path();
     let jsr_search_api =
       CliJsrSearchApi::new(module_registry.file_fetcher.clone());
     let npm_search_api =
@@ -481,6 +500,8 @@ impl Inner {
       panic!("Could not resolve current working directory")
     });
 
+    let task_runner = LspTaskRunner::new(client.clone());
+
     Self {
       assets,
       cache,
@@ -492,8 +513,13 @@ impl Inner {
       http_client_provider,
       initial_cwd: initial_cwd.clone(),
       jsr_search_api,
+      lsp_status_folder,
+      last_memory_eviction: None,
+      tsc_heap_used_bytes: 0,
+      memory_evictions: 0,
       project_version: 0,
       task_queue: Default::default(),
+      task_runner,
       maybe_testing_server: None,
       module_registry,
       npm_search_api,
@@ -1010,11 +1036,18 @@ impl Inner {
   }
 
   async fn refresh_documents_config(&mut self) {
+    let preload_files =
+      match self.config.settings.unscoped.document_preload_scope {
+        DocumentPreloadScope::Workspace => Cow::Borrowed(&self.workspace_files),
+        DocumentPreloadScope::Entrypoints => {
+          Cow::Owned(self.config.tree.entrypoints())
+        }
+      };
     self.documents.update_config(
       &self.config,
       &self.resolver,
       &self.cache,
-      &self.workspace_files,
+      &preload_files,
     );
 
     // refresh the npm specifiers because it might have discovered
@@ -1173,6 +1206,50 @@ impl Inner {
     self.performance.measure(mark);
   }
 
+  fn memory_status(&self) -> MemoryStatus {
+    MemoryStatus {
+      pid: std::process::id(),
+      root_uri: self.config.root_uri().map(|uri| uri.as_str().to_string()),
+      timestamp: memory::now_timestamp(),
+      rss_bytes: deno_runtime::ops::os::rss(),
+      documents: self.documents.memory_usage(),
+      tsc_heap_used_bytes: self.tsc_heap_used_bytes,
+      memory_budget_mb: self.config.settings.unscoped.memory_budget_mb,
+      evictions: self.memory_evictions,
+    }
+  }
+
+  /// Evicts the cached file system documents and the semantic cache of tsc
+  /// when `deno.memoryBudgetMb` is exceeded, and writes the memory status of
+  /// the server for `deno lsp --status`. Called every
+  /// `memory::STATUS_WRITE_INTERVAL`.
+  async fn check_memory_budget(&mut self) {
+    self.tsc_heap_used_bytes = self.ts_server.heap_used(self.snapshot()).await;
+    let status = self.memory_status();
+    let in_cooldown = self
+      .last_memory_eviction
+      .is_some_and(|t| t.elapsed() < memory::EVICTION_COOLDOWN);
+    if status.exceeds_budget() && !in_cooldown {
+      self.last_memory_eviction = Some(Instant::now());
+      let evicted = self.documents.evict_file_system_documents();
+      self.ts_server.cleanup_semantic_cache(self.snapshot()).await;
+      self.memory_evictions += 1;
+      self.project_changed([], false);
+      lsp_log!(
+        "Memory budget of {}MB exceeded ({}MB in use), evicted {} documents.",
+        status.memory_budget_mb.unwrap_or_default(),
+        status.rss_bytes / 1024 / 1024,
+        evicted,
+      );
+    }
+    let status = self.memory_status();
+    if let Err(err) =
+      memory::write_status_file(&self.lsp_status_folder, &status)
+    {
+      lsp_warn!("Unable to write the language server status: {:#}", err);
+    }
+  }
+
   async fn did_change_configuration(
     &mut self,
     params: DidChangeConfigurationParams,
@@ -1411,6 +1488,7 @@ impl Inner {
               document.content(),
               &fmt_options,
               &unstable_options,
+              &[],
               None,
             )
           }
@@ -1759,6 +1837,59 @@ impl Inner {
       refactor_actions
         .extend(refactor_info.to_code_actions(&specifier, &params.range));
     }
+    // TypeScript only offers converting a function to an async function as a
+    // fix of a suggestion diagnostic, so offer it for the selection as well.
+    if refactor::REWRITE_FUNCTION_ASYNC
+      .kind
+      .as_str()
+      .starts_with(only.as_str())
+    {
+      let suggestions = self
+        .diagnostics_server
+        .get_ts_diagnostics(&specifier, asset_or_doc.document_lsp_version());
+      for diagnostic in suggestions.iter().filter(|d| {
+        d.code
+          == Some(NumberOrString::Number(
+            refactor::CONVERT_TO_ASYNC_FUNCTION_CODE,
+          ))
+          && d.range.start <= params.range.start
+          && params.range.start <= d.range.end
+          && !params.context.diagnostics.contains(d)
+      }) {
+        let actions = self
+          .ts_server
+          .get_code_fixes(
+            self.snapshot(),
+            specifier.clone(),
+            line_index.offset_tsc(diagnostic.range.start)?
+              ..line_index.offset_tsc(diagnostic.range.end)?,
+            vec![refactor::CONVERT_TO_ASYNC_FUNCTION_CODE],
+            (&self
+              .config
+              .tree
+              .fmt_config_for_specifier(&specifier)
+              .options)
+              .into(),
+            tsc::UserPreferences::from_config_for_specifier(
+              &self.config,
+              &specifier,
+            ),
+            asset_or_doc.scope().cloned(),
+          )
+          .await;
+        for action in actions.iter().filter(|a| a.commands.is_none()) {
+          match ts_fix_to_refactor_action(
+            &specifier,
+            action,
+            refactor::REWRITE_FUNCTION_ASYNC.kind.clone(),
+            self,
+          ) {
+            Ok(action) => refactor_actions.push(action),
+            Err(err) => lsp_warn!("Unable to convert fix: {:#}", err),
+          }
+        }
+      }
+    }
     all_actions.extend(
       refactor::prune_invalid_actions(refactor_actions, 5)
         .into_iter()
@@ -3039,6 +3170,20 @@ impl tower_lsp::LanguageServer for LanguageServer {
         .await
     } else if params.command == "deno.reloadImportRegistries" {
       self.inner.write().await.reload_import_registries().await
+    } else if params.command == tasks::TASK_LIST_COMMAND {
+      Ok(Some(json!(self.inner.read().await.task_definitions()?)))
+    } else if params.command == tasks::TASK_RUN_COMMAND
+      || params.command == tasks::TASK_DEBUG_COMMAND
+    {
+      let (args,): (tasks::TaskRunArgs,) =
+        serde_json::from_value(json!(params.arguments))
+          .map_err(|err| LspError::invalid_params(err.to_string()))?;
+      let debug = params.command == tasks::TASK_DEBUG_COMMAND;
+      self.inner.read().await.run_task(args, debug)
+    } else if params.command == tasks::TASK_STOP_COMMAND {
+      let (id,): (u32,) = serde_json::from_value(json!(params.arguments))
+        .map_err(|err| LspError::invalid_params(err.to_string()))?;
+      Ok(Some(json!(self.inner.read().await.task_runner.stop(id))))
     } else {
       Ok(None)
     }
@@ -3061,6 +3206,20 @@ impl tower_lsp::LanguageServer for LanguageServer {
     };
     self.init_flag.raise();
 
+    {
+      let ls = self.clone();
+      // checked on an interval as it scans all the documents
+      spawn(async move {
+        loop {
+          tokio::time::sleep(memory::STATUS_WRITE_INTERVAL).await;
+          if ls.shutdown_flag.is_raised() {
+            break;
+          }
+          ls.inner.write().await.check_memory_budget().await;
+        }
+      });
+    }
+
     for registration in registrations {
       if let Err(err) = self
         .client
@@ -3099,6 +3258,10 @@ impl tower_lsp::LanguageServer for LanguageServer {
   }
 
   async fn shutdown(&self) -> LspResult<()> {
+    memory::remove_status_file(
+      &self.inner.read().await.lsp_status_folder,
+      std::process::id(),
+    );
     self.shutdown_flag.raise();
     Ok(())
   }
@@ -3766,6 +3929,30 @@ impl Inner {
     Ok(result)
   }
 
+  fn run_task(
+    &self,
+    args: tasks::TaskRunArgs,
+    debug: bool,
+  ) -> LspResult<Option<Value>> {
+    let definition = self
+      .task_definitions()?
+      .into_iter()
+      .find(|d| d.name == args.name && d.source_uri == args.source_uri)
+      .ok_or_else(|| {
+        LspError::invalid_params(format!("Unknown task \"{}\".", args.name))
+      })?;
+    let inspect = debug
+      .then(|| args.inspect.unwrap_or_else(tasks::default_inspect_address));
+    let id = self.task_runner.run(&definition, inspect).map_err(|err| {
+      error!("Failed running the task \"{}\": {:#}", definition.name, err);
+      LspError::internal_error()
+    })?;
+    Ok(Some(json!({
+      "id": id,
+      "inspect": inspect,
+    })))
+  }
+
   async fn inlay_hint(
     &self,
     params: InlayHintParams,
@@ -3894,6 +4081,9 @@ impl Inner {
       )
       .unwrap();
 
+      contents.push_str("\n## Memory\n\n");
+      contents.push_str(&self.memory_status().to_markdown());
+
       contents
         .push_str("\n## Performance (last 3 000 entries)\n\n|Name|Count|Duration|\n|---|---|---|\n");
       let mut averages = self.performance.averages_as_f64();
@@ -20,6 +20,40 @@ pub struct TaskDefinition {
   pub source_uri: lsp::Uri,
 }
 
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TaskOutputStream {
+  Stdout,
+  Stderr,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TaskRunProgressMessage {
+  Output {
+    text: String,
+    stream: TaskOutputStream,
+  },
+  /// The exit code is `None` if the task was stopped or killed by a signal.
+  #[serde(rename_all = "camelCase")]
+  End { exit_code: Option<i32> },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskRunProgressParams {
+  pub id: u32,
+  pub message: TaskRunProgressMessage,
+}
+
+pub enum TaskRunProgressNotification {}
+
+impl lsp::notification::Notification for TaskRunProgressNotification {
+  type Params = TaskRunProgressParams;
+
+  const METHOD: &'static str = "deno/taskRunProgress";
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct RegistryStateNotificationParams {
   pub origin: String,
@@ -0,0 +1,211 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde::Deserialize;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use super::documents::DocumentsMemoryUsage;
+use super::parent_process_checker::is_process_active;
+use crate::cache::DenoDir;
+use crate::colors;
+use crate::util::display::human_size;
+use crate::util::fs::atomic_write_file_with_retries;
+
+/// The interval in which the memory budget is checked and the status file of
+/// a language server is written.
+pub const STATUS_WRITE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The minimum time between two evictions due to the memory budget. The rss
+/// rarely shrinks after an eviction, so without it the documents would be
+/// evicted again on every check.
+pub const EVICTION_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// Memory used by a running language server, broken down per subsystem.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStatus {
+  pub pid: u32,
+  pub root_uri: Option<String>,
+  /// Unix timestamp in milliseconds of when the status was taken.
+  pub timestamp: u64,
+  pub rss_bytes: usize,
+  pub documents: DocumentsMemoryUsage,
+  pub tsc_heap_used_bytes: usize,
+  pub memory_budget_mb: Option<u64>,
+  /// Number of times the caches were evicted due to the memory budget.
+  pub evictions: usize,
+}
+
+impl MemoryStatus {
+  pub fn exceeds_budget(&self) -> bool {
+    match self.memory_budget_mb {
+      Some(budget_mb) => self.rss_bytes as u64 > budget_mb * 1024 * 1024,
+      None => false,
+    }
+  }
+
+  pub fn to_markdown(&self) -> String {
+    let mut contents = String::new();
+    contents.push_str("|Subsystem|Count|Size|\n|---|---|---|\n");
+    writeln!(
+      contents,
+      "|Process (rss)||{}|",
+      human_size(self.rss_bytes as f64)
+    )
+    .unwrap();
+    writeln!(
+      contents,
+      "|Open documents|{}|{}|",
+      self.documents.open_count,
+      human_size(self.documents.open_bytes as f64)
+    )
+    .unwrap();
+    writeln!(
+      contents,
+      "|File system documents|{}|{}|",
+      self.documents.file_system_count,
+      human_size(self.documents.file_system_bytes as f64)
+    )
+    .unwrap();
+    writeln!(
+      contents,
+      "|TypeScript heap||{}|",
+      human_size(self.tsc_heap_used_bytes as f64)
+    )
+    .unwrap();
+    if let Some(budget_mb) = self.memory_budget_mb {
+      writeln!(
+        contents,
+        "\nMemory budget: {}MB ({} evictions)",
+        budget_mb, self.evictions
+      )
+      .unwrap();
+    }
+    contents
+  }
+}
+
+pub fn now_timestamp() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or(0)
+}
+
+fn status_file_path(folder: &Path, pid: u32) -> PathBuf {
+  folder.join(format!("{}.json", pid))
+}
+
+pub fn write_status_file(
+  folder: &Path,
+  status: &MemoryStatus,
+) -> Result<(), AnyError> {
+  let path = status_file_path(folder, status.pid);
+  let data = serde_json::to_vec_pretty(status)?;
+  atomic_write_file_with_retries(&path, data, 0o644)
+    .with_context(|| format!("Failed writing '{}'", path.display()))
+}
+
+pub fn remove_status_file(folder: &Path, pid: u32) {
+  let _ = std::fs::remove_file(status_file_path(folder, pid));
+}
+
+/// Prints the last reported status of the language servers running on this
+/// machine, which is used by `deno lsp --status`.
+pub fn print_status(deno_dir: &DenoDir) -> Result<(), AnyError> {
+  let folder = deno_dir.lsp_status_folder_path();
+  let mut statuses = Vec::new();
+  if let Ok(entries) = std::fs::read_dir(&folder) {
+    for entry in entries.flatten() {
+      let path = entry.path();
+      if path.extension().and_then(|e| e.to_str()) != Some("json") {
+        continue;
+      }
+      let Ok(text) = std::fs::read_to_string(&path) else {
+        continue;
+      };
+      match serde_json::from_str::<MemoryStatus>(&text) {
+        // the status file of a server which crashed is never removed
+        Ok(status) if !is_process_active(status.pid) => {
+          let _ = std::fs::remove_file(&path);
+        }
+        Ok(status) => statuses.push(status),
+        Err(err) => {
+          log::debug!("Invalid status file {}: {:#}", path.display(), err)
+        }
+      }
+    }
+  }
+  if statuses.is_empty() {
+    log::info!("No running language servers found.");
+    return Ok(());
+  }
+  statuses.sort_by_key(|s| s.pid);
+  let now = now_timestamp();
+  for status in statuses {
+    log::info!(
+      "{} {}",
+      colors::green("Language server"),
+      colors::gray(format!("(pid {})", status.pid))
+    );
+    if let Some(root_uri) = &status.root_uri {
+      log::info!("  workspace:             {}", root_uri);
+    }
+    log::info!(
+      "  updated:               {}s ago",
+      now.saturating_sub(status.timestamp) / 1000
+    );
+    log::info!(
+      "  process (rss):         {}",
+      human_size(status.rss_bytes as f64)
+    );
+    log::info!(
+      "  open documents:        {} ({})",
+      status.documents.open_count,
+      human_size(status.documents.open_bytes as f64)
+    );
+    log::info!(
+      "  file system documents: {} ({})",
+      status.documents.file_system_count,
+      human_size(status.documents.file_system_bytes as f64)
+    );
+    log::info!(
+      "  typescript heap:       {}",
+      human_size(status.tsc_heap_used_bytes as f64)
+    );
+    if let Some(budget_mb) = status.memory_budget_mb {
+      log::info!(
+        "  memory budget:         {}MB ({} evictions)",
+        budget_mb,
+        status.evictions
+      );
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_exceeds_budget() {
+    let mut status = MemoryStatus {
+      rss_bytes: 2 * 1024 * 1024,
+      ..Default::default()
+    };
+    assert!(!status.exceeds_budget());
+    status.memory_budget_mb = Some(2);
+    assert!(!status.exceeds_budget());
+    status.memory_budget_mb = Some(1);
+    assert!(status.exceeds_budget());
+  }
+}
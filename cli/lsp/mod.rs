@@ -7,6 +7,8 @@ use tower_lsp::Server;
 
 use crate::lsp::language_server::LanguageServer;
 use crate::util::sync::AsyncFlag;
+pub use diagnose::print_diagnosis;
+pub use memory::print_status;
 pub use repl::ReplCompletionItem;
 pub use repl::ReplLanguageServer;
 
@@ -19,12 +21,14 @@ mod client;
 mod code_lens;
 mod completions;
 mod config;
+mod diagnose;
 mod diagnostics;
 mod documents;
 mod jsr;
 pub mod language_server;
 mod logging;
 mod lsp_custom;
+mod memory;
 mod npm;
 mod parent_process_checker;
 mod path_to_regex;
@@ -35,6 +39,7 @@ mod repl;
 mod resolver;
 mod search;
 mod semantic_tokens;
+mod tasks;
 mod testing;
 mod text;
 mod tsc;
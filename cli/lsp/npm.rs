@@ -9,6 +9,7 @@ use deno_semver::Version;
 use serde::Deserialize;
 use std::sync::Arc;
 
+use crate::args::create_default_npmrc;
 use crate::args::npm_registry_url;
 use crate::file_fetcher::FileFetcher;
 use crate::npm::NpmFetchResolver;
@@ -25,7 +26,8 @@ pub struct CliNpmSearchApi {
 
 impl CliNpmSearchApi {
   pub fn new(file_fetcher: Arc<FileFetcher>) -> Self {
-    let resolver = NpmFetchResolver::new(file_fetcher.clone());
+    let resolver =
+      NpmFetchResolver::new(file_fetcher.clone(), create_default_npmrc());
     Self {
       file_fetcher,
       resolver,
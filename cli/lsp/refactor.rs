@@ -130,6 +130,48 @@ pub static REWRITE_PROPERTY_GENERATEACCESSORS: Lazy<RefactorCodeActionKind> =
     }),
   });
 
+pub static REWRITE_FUNCTION_RETURN_TYPE: Lazy<RefactorCodeActionKind> =
+  Lazy::new(|| RefactorCodeActionKind {
+    kind: [
+      lsp::CodeActionKind::REFACTOR_REWRITE.as_str(),
+      "function",
+      "returnType",
+    ]
+    .join(".")
+    .into(),
+    matches_callback: Box::new(|tag: &str| {
+      tag.starts_with("Infer function return type")
+    }),
+  });
+
+/// TypeScript only provides this as a fix for a suggestion diagnostic, see
+/// `CONVERT_TO_ASYNC_FUNCTION_CODE`, which we also surface as a refactor.
+pub static REWRITE_FUNCTION_ASYNC: Lazy<RefactorCodeActionKind> =
+  Lazy::new(|| RefactorCodeActionKind {
+    kind: [
+      lsp::CodeActionKind::REFACTOR_REWRITE.as_str(),
+      "function",
+      "async",
+    ]
+    .join(".")
+    .into(),
+    matches_callback: Box::new(|tag: &str| {
+      tag.starts_with("Convert to async function")
+    }),
+  });
+
+pub static INLINE_VARIABLE: Lazy<RefactorCodeActionKind> =
+  Lazy::new(|| RefactorCodeActionKind {
+    kind: [lsp::CodeActionKind::REFACTOR_INLINE.as_str(), "variable"]
+      .join(".")
+      .into(),
+    matches_callback: Box::new(|tag: &str| tag.starts_with("Inline variable")),
+  });
+
+/// The code of the "This may be converted to an async function." suggestion
+/// diagnostic.
+pub const CONVERT_TO_ASYNC_FUNCTION_CODE: i32 = 80006;
+
 pub static ALL_KNOWN_REFACTOR_ACTION_KINDS: Lazy<
   Vec<&'static RefactorCodeActionKind>,
 > = Lazy::new(|| {
@@ -144,6 +186,9 @@ pub static ALL_KNOWN_REFACTOR_ACTION_KINDS: Lazy<
     &REWRITE_ARROW_BRACES,
     &REWRITE_PARAMETERS_TO_DESTRUCTURED,
     &REWRITE_PROPERTY_GENERATEACCESSORS,
+    &REWRITE_FUNCTION_RETURN_TYPE,
+    &REWRITE_FUNCTION_ASYNC,
+    &INLINE_VARIABLE,
   ]
 });
 
@@ -305,11 +305,14 @@ pub fn get_repl_workspace_settings() -> WorkspaceSettings {
     cache_on_save: false,
     import_map: None,
     code_lens: Default::default(),
+    diagnostics_scope: Default::default(),
     internal_debug: false,
     internal_inspect: Default::default(),
     log_file: false,
     lint: false,
     document_preload_limit: 0, // don't pre-load any modules as it's expensive and not useful for the repl
+    document_preload_scope: Default::default(),
+    memory_budget_mb: None,
     tls_certificate: None,
     unsafely_ignore_certificate_errors: None,
     unstable: Default::default(),
@@ -486,6 +486,7 @@ async fn create_npm_resolver(
       maybe_lockfile: None,
       fs: Arc::new(deno_fs::RealFs),
       npm_global_cache_dir: cache.deno_dir().npm_folder_path(),
+      vendor_npm: false,
       // Use an "only" cache setting in order to make the
       // user do an explicit "cache" command and prevent
       // the cache from being filled with lots of packages while
@@ -494,6 +495,7 @@ async fn create_npm_resolver(
       text_only_progress_bar: ProgressBar::new(ProgressBarStyle::TextOnly),
       maybe_node_modules_path: config_data
         .and_then(|d| d.node_modules_dir.clone()),
+      node_modules_linked: false,
       // only used for top level install, so we can ignore this
       npm_install_deps_provider: Arc::new(NpmInstallDepsProvider::empty()),
       npmrc: config_data
@@ -521,6 +523,7 @@ fn create_node_resolver(
   let node_resolver_inner = Arc::new(NodeResolver::new(
     deno_runtime::deno_node::DenoFsNodeResolverEnv::new(fs.clone()),
     npm_resolver.clone().into_npm_resolver(),
+    Vec::new(),
   ));
   Some(Arc::new(CliNodeResolver::new(
     CJS_RESOLUTIONS.clone(),
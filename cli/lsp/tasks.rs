@@ -0,0 +1,164 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Runs the tasks of the config files for editors. The tasks are run by
+//! `deno task` in a subprocess, so they are resolved the same way as on the
+//! command line, and their output is streamed to the client with
+//! `deno/taskRunProgress` notifications.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::process::Stdio;
+use std::sync::atomic::AtomicU32;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use deno_core::anyhow::anyhow;
+use deno_core::error::AnyError;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde::Deserialize;
+use deno_core::unsync::spawn;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::sync::oneshot;
+use tower_lsp::lsp_types as lsp;
+
+use super::client::Client;
+use super::lsp_custom::TaskDefinition;
+use super::lsp_custom::TaskOutputStream;
+use super::lsp_custom::TaskRunProgressMessage;
+use super::lsp_custom::TaskRunProgressParams;
+use super::urls::uri_to_url;
+
+pub const TASK_LIST_COMMAND: &str = "deno.task.list";
+pub const TASK_RUN_COMMAND: &str = "deno.task.run";
+pub const TASK_DEBUG_COMMAND: &str = "deno.task.debug";
+pub const TASK_STOP_COMMAND: &str = "deno.task.stop";
+
+/// The address of the inspector of debugged tasks when none is given.
+const DEFAULT_INSPECT_ADDRESS: &str = "127.0.0.1:9229";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskRunArgs {
+  pub name: String,
+  pub source_uri: lsp::Uri,
+  /// Address of the inspector, only used when debugging.
+  pub inspect: Option<SocketAddr>,
+}
+
+#[derive(Debug)]
+pub struct LspTaskRunner {
+  client: Client,
+  next_id: AtomicU32,
+  /// Senders which stop the running tasks by their id.
+  runs: Arc<Mutex<HashMap<u32, oneshot::Sender<()>>>>,
+}
+
+impl LspTaskRunner {
+  pub fn new(client: Client) -> Self {
+    Self {
+      client,
+      next_id: AtomicU32::new(1),
+      runs: Default::default(),
+    }
+  }
+
+  /// Starts the task and returns the id of the run, which is used by the
+  /// progress notifications and to stop it. When debugging, the deno
+  /// processes of the task wait for a debugger to connect to the inspector.
+  pub fn run(
+    &self,
+    definition: &TaskDefinition,
+    inspect: Option<SocketAddr>,
+  ) -> Result<u32, AnyError> {
+    let config_path = uri_to_url(&definition.source_uri)
+      .to_file_path()
+      .map_err(|_| anyhow!("The task isn't defined in a local file."))?;
+    let cwd = config_path
+      .parent()
+      .ok_or_else(|| anyhow!("The config file has no parent directory."))?;
+    let mut command = tokio::process::Command::new(std::env::current_exe()?);
+    command.arg("task");
+    if config_path
+      .file_name()
+      .is_some_and(|name| name != "package.json")
+    {
+      command.arg("--config").arg(&config_path);
+    }
+    command
+      .arg(&definition.name)
+      .current_dir(cwd)
+      .stdin(Stdio::null())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .kill_on_drop(true);
+    if let Some(inspect) = inspect {
+      command.env("DENO_INSPECT_BRK", inspect.to_string());
+    }
+    let mut child = command.spawn()?;
+
+    let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+    let (stop_sender, stop_receiver) = oneshot::channel();
+    self.runs.lock().insert(id, stop_sender);
+    let runs = self.runs.clone();
+    let client = self.client.clone();
+    spawn(async move {
+      let stdout = child.stdout.take().unwrap();
+      let stderr = child.stderr.take().unwrap();
+      let wait = async {
+        tokio::select! {
+          status = child.wait() => status.ok().and_then(|status| status.code()),
+          _ = stop_receiver => {
+            let _ = child.kill().await;
+            None
+          }
+        }
+      };
+      let (exit_code, _, _) = tokio::join!(
+        wait,
+        forward_output(&client, id, stdout, TaskOutputStream::Stdout),
+        forward_output(&client, id, stderr, TaskOutputStream::Stderr),
+      );
+      runs.lock().remove(&id);
+      client.send_task_run_progress_notification(TaskRunProgressParams {
+        id,
+        message: TaskRunProgressMessage::End { exit_code },
+      });
+    });
+    Ok(id)
+  }
+
+  /// Stops the run with the id, returning `false` if it already ended.
+  pub fn stop(&self, id: u32) -> bool {
+    match self.runs.lock().remove(&id) {
+      Some(stop_sender) => stop_sender.send(()).is_ok(),
+      None => false,
+    }
+  }
+}
+
+pub fn default_inspect_address() -> SocketAddr {
+  DEFAULT_INSPECT_ADDRESS.parse().unwrap()
+}
+
+async fn forward_output(
+  client: &Client,
+  id: u32,
+  mut reader: impl AsyncRead + Unpin,
+  stream: TaskOutputStream,
+) {
+  let mut buf = vec![0; 8 * 1024];
+  loop {
+    let len = match reader.read(&mut buf).await {
+      Ok(0) | Err(_) => return,
+      Ok(len) => len,
+    };
+    client.send_task_run_progress_notification(TaskRunProgressParams {
+      id,
+      message: TaskRunProgressMessage::Output {
+        text: String::from_utf8_lossy(&buf[..len]).into_owned(),
+        stream,
+      },
+    });
+  }
+}
@@ -38,6 +38,7 @@ use std::borrow::Cow;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
@@ -291,6 +292,7 @@ impl TestRun {
         exclude: lsp_filter
           .map(|f| f.exclude.values().map(|t| t.name.clone()).collect())
           .unwrap_or_default(),
+        ..Default::default()
       };
       let token = self.token.clone();
 
@@ -313,6 +315,10 @@ impl TestRun {
               filter,
               shuffle: None,
               trace_leaks: false,
+              fixtures: Default::default(),
+              update_snapshots: false,
+              timeout: None,
+              artifacts_dir: None,
             },
           ))
         }
@@ -365,6 +371,7 @@ impl TestRun {
             test::TestEvent::Slow(id, elapsed) => {
               reporter.report_slow(tests.read().get(&id).unwrap(), elapsed);
             }
+            test::TestEvent::Artifacts(..) => {}
             test::TestEvent::Result(id, result, elapsed) => {
               if tests_with_result.insert(id) {
                 let description = tests.read().get(&id).unwrap().clone();
@@ -380,10 +387,12 @@ impl TestRun {
                   test::TestResult::Cancelled => {
                     summary.failed += 1;
                   }
+                  test::TestResult::Skipped => summary.skipped += 1,
                 }
                 reporter.report_result(&description, &result, elapsed);
               }
             }
+            test::TestEvent::SkippedFile(_) => {}
             test::TestEvent::UncaughtError(origin, error) => {
               reporter.report_uncaught_error(&origin, &error);
               summary.failed += 1;
@@ -630,6 +639,8 @@ impl LspTestReporter {
 
   fn report_slow(&mut self, _desc: &test::TestDescription, _elapsed: u64) {}
 
+  fn report_artifacts(&mut self, _id: usize, _path: &Path) {}
+
   fn report_output(&mut self, output: &[u8]) {
     let test = self
       .current_test
@@ -659,7 +670,7 @@ impl LspTestReporter {
           duration: Some(elapsed as u32),
         })
       }
-      test::TestResult::Ignored => {
+      test::TestResult::Ignored | test::TestResult::Skipped => {
         let desc = self.tests.get(&desc.id).unwrap();
         self.progress(lsp_custom::TestRunProgressMessage::Skipped {
           test: desc.as_test_identifier(&self.tests),
@@ -395,6 +395,18 @@ impl TsServer {
     }
   }
 
+  /// The used heap size of the TypeScript isolate in bytes.
+  pub async fn heap_used(&self, snapshot: Arc<StateSnapshot>) -> usize {
+    let req = TscRequest::GetHeapUsed;
+    self
+      .request::<usize>(snapshot, req, None)
+      .await
+      .map_err(|err| {
+        log::error!("Failed to request to tsserver {}", err);
+      })
+      .unwrap_or_default()
+  }
+
   pub fn start(
     &self,
     inspector_server_addr: Option<String>,
@@ -4458,6 +4470,14 @@ fn op_resolve_inner(
   Ok(specifiers)
 }
 
+#[op2]
+#[number]
+fn op_heap_used(scope: &mut v8::HandleScope) -> usize {
+  let mut heap_stats = v8::HeapStatistics::default();
+  scope.get_heap_statistics(&mut heap_stats);
+  heap_stats.used_heap_size()
+}
+
 #[op2(fast)]
 fn op_respond(
   state: &mut OpState,
@@ -4726,6 +4746,7 @@ fn run_tsc_thread(
 
 deno_core::extension!(deno_tsc,
   ops = [
+    op_heap_used,
     op_is_cancelled,
     op_is_node_file,
     op_load,
@@ -5122,6 +5143,7 @@ pub struct JsNull;
 pub enum TscRequest {
   GetDiagnostics((Vec<String>, usize)),
   GetAssets,
+  GetHeapUsed,
 
   CleanupSemanticCache,
   // https://github.com/denoland/deno/blob/v1.37.1/cli/tsc/dts/typescript.d.ts#L6230
@@ -5327,6 +5349,7 @@ impl TscRequest {
       }
       TscRequest::CleanupSemanticCache => ("cleanupSemanticCache", None),
       TscRequest::GetAssets => ("$getAssets", None),
+      TscRequest::GetHeapUsed => ("$getHeapUsed", None),
     };
 
     Ok(args)
@@ -5372,6 +5395,7 @@ impl TscRequest {
       TscRequest::GetNavigateToItems(_) => "getNavigateToItems",
       TscRequest::ProvideInlayHints(_) => "provideInlayHints",
       TscRequest::GetAssets => "$getAssets",
+      TscRequest::GetHeapUsed => "$getHeapUsed",
     }
   }
 }
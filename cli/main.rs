@@ -41,6 +41,7 @@ use deno_resolver::npm::ByonmResolvePkgFolderFromDenoReqError;
 use deno_runtime::WorkerExecutionMode;
 pub use deno_runtime::UNSTABLE_GRANULAR_FLAGS;
 
+use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
@@ -105,6 +106,21 @@ async fn run_subcommand(flags: Arc<Flags>) -> Result<i32, AnyError> {
     DenoSubcommand::Remove(remove_flags) => spawn_subcommand(async {
       tools::registry::remove(flags, remove_flags).await
     }),
+    DenoSubcommand::Auth(auth_flags) => spawn_subcommand(async {
+      tools::auth::auth(auth_flags)
+    }),
+    DenoSubcommand::Outdated(outdated_flags) => spawn_subcommand(async {
+      tools::registry::outdated(flags, outdated_flags).await
+    }),
+    DenoSubcommand::Why(why_flags) => spawn_subcommand(async {
+      tools::registry::why(flags, why_flags).await
+    }),
+    DenoSubcommand::Lock(lock_flags) => spawn_subcommand(async {
+      tools::registry::lock(flags, lock_flags).await
+    }),
+    DenoSubcommand::Audit(audit_flags) => spawn_subcommand(async {
+      tools::registry::audit(flags, audit_flags).await
+    }),
     DenoSubcommand::Bench(bench_flags) => spawn_subcommand(async {
       if bench_flags.watch.is_some() {
         tools::bench::run_benchmarks_with_watch(flags, bench_flags).await
@@ -112,7 +128,9 @@ async fn run_subcommand(flags: Arc<Flags>) -> Result<i32, AnyError> {
         tools::bench::run_benchmarks(flags, bench_flags).await
       }
     }),
-    DenoSubcommand::Bundle => exit_with_message("⚠️ `deno bundle` was removed in Deno 2.\n\nSee the Deno 1.x to 2.x Migration Guide for migration instructions: https://docs.deno.com/runtime/manual/advanced/migrate_deprecations", 1),
+    DenoSubcommand::Bundle(bundle_flags) => spawn_subcommand(async {
+      tools::bundle::bundle(flags, bundle_flags).await
+    }),
     DenoSubcommand::Doc(doc_flags) => {
       spawn_subcommand(async { tools::doc::doc(flags, doc_flags).await })
     }
@@ -120,16 +138,36 @@ async fn run_subcommand(flags: Arc<Flags>) -> Result<i32, AnyError> {
       tools::run::eval_command(flags, eval_flags).await
     }),
     DenoSubcommand::Cache(cache_flags) => spawn_subcommand(async move {
-      tools::installer::install_from_entrypoints(flags, &cache_flags.files).await
+      if cache_flags.dry_run {
+        tools::cache::cache_dry_run(flags, cache_flags).await
+      } else {
+        tools::installer::install_from_entrypoints(flags, &cache_flags.files)
+          .await
+      }
     }),
     DenoSubcommand::Check(check_flags) => spawn_subcommand(async move {
+      if let Some(socket_path) =
+        tools::check_daemon::daemon_socket_path_from_env()
+      {
+        return tools::check_daemon::run_daemon(socket_path).await;
+      }
       tools::check::check(flags, check_flags).await
     }),
-    DenoSubcommand::Clean => spawn_subcommand(async move {
-      tools::clean::clean()
+    DenoSubcommand::Clean(clean_flags) => spawn_subcommand(async move {
+      tools::clean::clean(clean_flags)
+    }),
+    DenoSubcommand::Compat(compat_flags) => spawn_subcommand(async move {
+      tools::compat::compat(compat_flags)
+    }),
+    DenoSubcommand::Config(config_flags) => spawn_subcommand(async move {
+      tools::config::config(flags, config_flags)
     }),
     DenoSubcommand::Compile(compile_flags) => spawn_subcommand(async {
-      tools::compile::compile(flags, compile_flags).await
+      if compile_flags.watch.is_some() {
+        tools::compile::compile_with_watch(flags, compile_flags).await
+      } else {
+        tools::compile::compile(flags, compile_flags).await
+      }
     }),
     DenoSubcommand::Coverage(coverage_flags) => spawn_subcommand(async {
       tools::coverage::cover_files(flags, coverage_flags).await
@@ -161,7 +199,16 @@ async fn run_subcommand(flags: Arc<Flags>) -> Result<i32, AnyError> {
     DenoSubcommand::Uninstall(uninstall_flags) => spawn_subcommand(async {
       tools::installer::uninstall(flags, uninstall_flags).await
     }),
-    DenoSubcommand::Lsp => spawn_subcommand(async { lsp::start().await }),
+    DenoSubcommand::Lsp(lsp_flags) => spawn_subcommand(async move {
+      if lsp_flags.status {
+        let factory = CliFactory::from_flags(flags);
+        lsp::print_status(factory.deno_dir()?)
+      } else if lsp_flags.diagnose {
+        lsp::print_diagnosis(flags, lsp_flags).await
+      } else {
+        lsp::start().await
+      }
+    }),
     DenoSubcommand::Lint(lint_flags) => spawn_subcommand(async {
       if lint_flags.rules {
         tools::lint::print_rules_list(
@@ -177,6 +224,28 @@ async fn run_subcommand(flags: Arc<Flags>) -> Result<i32, AnyError> {
       spawn_subcommand(async move { tools::repl::run(flags, repl_flags).await })
     }
     DenoSubcommand::Run(run_flags) => spawn_subcommand(async move {
+      if run_flags.has_siblings() {
+        return tools::run::run_siblings(&flags, &run_flags).await;
+      }
+      if run_flags.ui {
+        bail!("--ui requires several programs given with --sibling or --procfile");
+      }
+      if let Some(restart_on_failure) = &run_flags.restart_on_failure {
+        if run_flags.is_stdin() {
+          bail!("--restart-on-failure can't be used with a program read from stdin");
+        }
+        if !tools::run::is_restart_supervised() {
+          return tools::run::supervise_restarts(restart_on_failure).await;
+        }
+      }
+      let flags = if run_flags.ephemeral_tmp {
+        match tools::run::ephemeral_tmp_flags(&flags) {
+          Some(flags) => flags,
+          None => return tools::run::supervise_ephemeral_tmp().await,
+        }
+      } else {
+        flags
+      };
       if run_flags.is_stdin() {
         tools::run::run_from_stdin(flags.clone()).await
       } else {
@@ -341,6 +410,7 @@ fn setup_panic_hook() {
 
 #[allow(clippy::print_stderr)]
 fn exit_with_message(message: &str, code: i32) -> ! {
+  let message = deno_runtime::deno_io::redact(message);
   eprintln!(
     "{}: {}",
     colors::red_bold("error"),
@@ -483,11 +553,18 @@ pub fn main() {
     // NOTE(lucacasonato): due to new PKU feature introduced in V8 11.6 we need to
     // initialize the V8 platform on a parent thread of all threads that will spawn
     // V8 isolates.
+    if let Some(exit_code) =
+      tools::version_manager::maybe_run_pinned_version(&args).await?
+    {
+      return Ok(exit_code);
+    }
     let flags = resolve_flags_and_init(args)?;
     run_subcommand(Arc::new(flags)).await
   };
 
-  match create_and_run_current_thread_with_maybe_metrics(future) {
+  let result = create_and_run_current_thread_with_maybe_metrics(future);
+  cache::flush_remote_cache_uploads();
+  match result {
     Ok(exit_code) => std::process::exit(exit_code),
     Err(err) => exit_for_error(err),
   }
@@ -521,7 +598,7 @@ fn resolve_flags_and_init(
   let default_v8_flags = match flags.subcommand {
     // Using same default as VSCode:
     // https://github.com/microsoft/vscode/blob/48d4ba271686e8072fc6674137415bc80d936bc7/extensions/typescript-language-features/src/configuration/configuration.ts#L213-L214
-    DenoSubcommand::Lsp => vec!["--max-old-space-size=3072".to_string()],
+    DenoSubcommand::Lsp(_) => vec!["--max-old-space-size=3072".to_string()],
     _ => {
       // TODO(bartlomieju): I think this can be removed as it's handled by `deno_core`
       // and its settings.
@@ -4,9 +4,13 @@ use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
 use deno_core::anyhow::bail;
 use deno_core::error::AnyError;
+use deno_core::url::Url;
 use deno_npm::npm_rc::RegistryConfig;
 use http::header;
 
+use crate::args::credentials::stored_token_for_url;
+use crate::args::credentials::RegistryKind;
+
 // TODO(bartlomieju): support more auth methods besides token and basic auth
 pub fn maybe_auth_header_for_npm_registry(
   registry_config: &RegistryConfig,
@@ -52,3 +56,23 @@ pub fn maybe_auth_header_for_npm_registry(
 
   Ok(None)
 }
+
+/// Gets the auth header for a request to a url of a registry, which falls
+/// back to the token stored with `deno auth login` for the registry when the
+/// `.npmrc` doesn't configure any authentication for it.
+pub fn maybe_auth_header_for_npm_url(
+  registry_config: &RegistryConfig,
+  url: &Url,
+) -> Result<Option<(header::HeaderName, header::HeaderValue)>, AnyError> {
+  if let Some(header) = maybe_auth_header_for_npm_registry(registry_config)? {
+    return Ok(Some(header));
+  }
+  let Some(token) = stored_token_for_url(url, |kind| kind == RegistryKind::Npm)
+  else {
+    return Ok(None);
+  };
+  Ok(Some((
+    header::AUTHORIZATION,
+    header::HeaderValue::from_str(&format!("Bearer {}", token))?,
+  )))
+}
@@ -0,0 +1,404 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_terminal::colors;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::util::checksum;
+use crate::util::fs::atomic_write_file_with_retries;
+use crate::util::path::get_atomic_file_path;
+
+/// A pnpm style store of the files of npm packages which is addressed by
+/// their contents, so the identical files of different packages and versions
+/// are stored once and hard linked into the `node_modules` folders.
+///
+/// Since a file of the store is shared by the `node_modules` folders of all
+/// projects, the files are read-only and their contents are verified against
+/// their hash before they are linked again.
+///
+/// Used with `--node-modules-dir=linked`.
+#[derive(Debug)]
+pub struct NpmContentStore {
+  root: PathBuf,
+  warned_copy: AtomicBool,
+}
+
+/// The files of a package folder of the npm cache in the store, so that the
+/// folder is only hashed once.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PackageIndex {
+  /// The folders relative to the package folder, with `/` separators.
+  dirs: Vec<String>,
+  /// The files relative to the package folder with their path in the store.
+  files: BTreeMap<String, PathBuf>,
+}
+
+impl NpmContentStore {
+  pub fn new(root: PathBuf) -> Self {
+    Self {
+      root,
+      warned_copy: AtomicBool::new(false),
+    }
+  }
+
+  /// Hard links the files of a package folder of the npm cache to another
+  /// folder through the store, falling back to copying them with a warning
+  /// when the folders are on different devices.
+  ///
+  /// Note: Does not handle symlinks.
+  pub fn link_dir_recursive(
+    &self,
+    from: &Path,
+    to: &Path,
+  ) -> Result<(), AnyError> {
+    let index_path = self.index_path(from);
+    let index = match self.read_index(&index_path) {
+      Some(index) => index,
+      None => {
+        let mut index = PackageIndex::default();
+        self.add_dir(from, "", &mut index)?;
+        let text = serde_json::to_string(&index)?;
+        atomic_write_file_with_retries(&index_path, text.as_bytes(), 0o644)
+          .with_context(|| format!("Writing {}", index_path.display()))?;
+        index
+      }
+    };
+
+    fs::create_dir_all(to)
+      .with_context(|| format!("Creating {}", to.display()))?;
+    for dir in &index.dirs {
+      let dir = to.join(dir);
+      fs::create_dir_all(&dir)
+        .with_context(|| format!("Creating {}", dir.display()))?;
+    }
+    for (file, store_file) in &index.files {
+      let store_path = self.root.join(store_file);
+      let new_to = to.join(file);
+      let link_result = link_file(&store_path, &new_to);
+      if let Err(err) = link_result {
+        if !self.warned_copy.swap(true, Ordering::Relaxed) {
+          log::warn!(
+            "{} Failed hard linking the files of npm packages from {}, so they are copied instead: {}",
+            colors::yellow("Warning"),
+            self.root.display(),
+            err
+          );
+        }
+        let _ = fs::remove_file(&new_to);
+        fs::copy(&store_path, &new_to).with_context(|| {
+          format!("Copying {} to {}", store_path.display(), new_to.display())
+        })?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// The package folders of the npm cache aren't modified once extracted, so
+  /// their index is keyed by their path.
+  fn index_path(&self, package_dir: &Path) -> PathBuf {
+    let hash = checksum::gen(&[package_dir.to_string_lossy().as_bytes()]);
+    self.root.join("index").join(format!("{hash}.json"))
+  }
+
+  /// Reads the index of a package folder, unless files of it were removed
+  /// from the store or modified in the meantime.
+  fn read_index(&self, index_path: &Path) -> Option<PackageIndex> {
+    let text = fs::read_to_string(index_path).ok()?;
+    let index = serde_json::from_str::<PackageIndex>(&text).ok()?;
+    index
+      .files
+      .values()
+      .all(|store_file| self.is_intact(store_file))
+      .then_some(index)
+  }
+
+  /// Whether a file of the store still has the contents its name is the hash
+  /// of. The files are read-only, but a process can still change their
+  /// permissions and write to them through a `node_modules` folder.
+  fn is_intact(&self, store_file: &Path) -> bool {
+    let Ok(data) = fs::read(self.root.join(store_file)) else {
+      return false;
+    };
+    let executable = store_file.to_string_lossy().ends_with("-exec");
+    store_file_name(&data, executable) == store_file
+  }
+
+  fn add_dir(
+    &self,
+    dir: &Path,
+    relative_dir: &str,
+    index: &mut PackageIndex,
+  ) -> Result<(), AnyError> {
+    let read_dir = fs::read_dir(dir)
+      .with_context(|| format!("Reading {}", dir.display()))?;
+    for entry in read_dir {
+      let entry = entry?;
+      let file_type = entry.file_type()?;
+      let file_name = entry.file_name();
+      let relative_path = if relative_dir.is_empty() {
+        file_name.to_string_lossy().into_owned()
+      } else {
+        format!("{}/{}", relative_dir, file_name.to_string_lossy())
+      };
+      if file_type.is_dir() {
+        self.add_dir(&dir.join(&file_name), &relative_path, index)?;
+        index.dirs.push(relative_path);
+      } else if file_type.is_file() {
+        let store_file = self.ensure_file(&dir.join(&file_name))?;
+        index.files.insert(relative_path, store_file);
+      }
+    }
+    Ok(())
+  }
+
+  /// Adds a file to the store when it isn't in it yet, returning the path of
+  /// the file relative to the store.
+  fn ensure_file(&self, path: &Path) -> Result<PathBuf, AnyError> {
+    let data =
+      fs::read(path).with_context(|| format!("Reading {}", path.display()))?;
+    let executable = is_executable(&fs::metadata(path)?);
+    let store_file = store_file_name(&data, executable);
+    let store_path = self.root.join(&store_file);
+    if store_path.exists() {
+      if self.is_intact(&store_file) {
+        return Ok(store_file);
+      }
+      log::warn!(
+        "{} The file {} of the npm content store was modified, which affects the node_modules folders it is linked into. Restoring it from {}.",
+        colors::yellow("Warning"),
+        store_path.display(),
+        path.display()
+      );
+    }
+    if let Some(parent) = store_path.parent() {
+      fs::create_dir_all(parent)
+        .with_context(|| format!("Creating {}", parent.display()))?;
+    }
+    // the store gets its own copy, so that writing to the files linked into
+    // the `node_modules` folders doesn't modify the npm cache
+    copy_file_atomic(path, &store_path, executable).with_context(|| {
+      format!("Copying {} to {}", path.display(), store_path.display())
+    })?;
+    Ok(store_file)
+  }
+}
+
+/// Copies a file through a temporary file, making the copy read-only. The
+/// rename replaces a modified file of the store. `fs::copy` makes a
+/// copy-on-write clone on the file systems supporting it, like Btrfs, XFS
+/// and APFS.
+fn copy_file_atomic(
+  from: &Path,
+  to: &Path,
+  executable: bool,
+) -> std::io::Result<()> {
+  let temp_path = get_atomic_file_path(to);
+  let result = fs::copy(from, &temp_path)
+    .and_then(|_| set_read_only(&temp_path, executable))
+    .and_then(|_| fs::rename(&temp_path, to));
+  if result.is_err() {
+    let _ = fs::remove_file(&temp_path);
+  }
+  result
+}
+
+/// The path of a file in the store, like `ab/cdef...` for the SHA-256 hash
+/// `abcdef...` of its contents. Executable files are stored separately since
+/// hard links share their permissions.
+fn store_file_name(data: &[u8], executable: bool) -> PathBuf {
+  let hash = checksum::gen(&[data]);
+  let (prefix, rest) = hash.split_at(2);
+  let file_name = if executable {
+    format!("{}-exec", rest)
+  } else {
+    rest.to_string()
+  };
+  Path::new(prefix).join(file_name)
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &fs::Metadata) -> bool {
+  use std::os::unix::fs::PermissionsExt;
+  metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &fs::Metadata) -> bool {
+  false
+}
+
+#[cfg(unix)]
+fn set_read_only(path: &Path, executable: bool) -> std::io::Result<()> {
+  use std::os::unix::fs::PermissionsExt;
+  let mode = if executable { 0o555 } else { 0o444 };
+  fs::set_permissions(path, fs::Permissions::from_mode(mode))
+}
+
+/// Read-only files can't be removed on Windows, which would keep the
+/// `node_modules` folders from being removed, so there the store relies on
+/// verifying the hashes alone.
+#[cfg(not(unix))]
+fn set_read_only(_path: &Path, _executable: bool) -> std::io::Result<()> {
+  Ok(())
+}
+
+fn link_file(from: &Path, to: &Path) -> std::io::Result<()> {
+  match fs::hard_link(from, to) {
+    Ok(()) => Ok(()),
+    Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+      fs::remove_file(to)?;
+      fs::hard_link(from, to)
+    }
+    Err(err) => Err(err),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use test_util::TempDir;
+
+  #[test]
+  fn test_store_file_name() {
+    assert_eq!(
+      store_file_name(b"hello world", false),
+      PathBuf::from("b9")
+        .join("4d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9")
+    );
+    assert_eq!(
+      store_file_name(b"hello world", true),
+      PathBuf::from("b9").join(
+        "4d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9-exec"
+      )
+    );
+  }
+
+  #[test]
+  fn test_link_dir_recursive() {
+    let temp_dir = TempDir::new();
+    temp_dir.create_dir_all("a/lib");
+    temp_dir.create_dir_all("b");
+    temp_dir.write("a/package.json", "{}");
+    temp_dir.write("a/lib/index.js", "export {};");
+    temp_dir.write("b/package.json", "{}");
+    let store =
+      NpmContentStore::new(temp_dir.path().join("store").to_path_buf());
+    store
+      .link_dir_recursive(
+        temp_dir.path().join("a").as_path(),
+        temp_dir.path().join("out/a").as_path(),
+      )
+      .unwrap();
+    store
+      .link_dir_recursive(
+        temp_dir.path().join("b").as_path(),
+        temp_dir.path().join("out/b").as_path(),
+      )
+      .unwrap();
+    assert_eq!(temp_dir.read_to_string("out/a/lib/index.js"), "export {};");
+    assert_eq!(temp_dir.read_to_string("out/b/package.json"), "{}");
+    // the identical package.json files are stored once
+    let store_files = fs::read_dir(temp_dir.path().join("store"))
+      .unwrap()
+      .map(|dir| dir.unwrap().path())
+      .filter(|dir| !dir.ends_with("index"))
+      .flat_map(|dir| fs::read_dir(dir).unwrap())
+      .count();
+    assert_eq!(store_files, 2);
+    // the store has its own copies of the files of the npm cache
+    temp_dir.write("a/lib/index.js", "export const a = 1;");
+    assert_eq!(temp_dir.read_to_string("out/a/lib/index.js"), "export {};");
+  }
+
+  #[test]
+  fn test_link_dir_recursive_with_index() {
+    let temp_dir = TempDir::new();
+    temp_dir.create_dir_all("a/lib");
+    temp_dir.create_dir_all("a/empty");
+    temp_dir.write("a/lib/index.js", "export {};");
+    let store =
+      NpmContentStore::new(temp_dir.path().join("store").to_path_buf());
+    let from = temp_dir.path().join("a");
+    store
+      .link_dir_recursive(
+        from.as_path(),
+        temp_dir.path().join("out/a").as_path(),
+      )
+      .unwrap();
+
+    // the indexed files are linked without reading the package folder again
+    temp_dir.write("a/lib/index.js", "changed");
+    store
+      .link_dir_recursive(
+        from.as_path(),
+        temp_dir.path().join("out/b").as_path(),
+      )
+      .unwrap();
+    assert_eq!(temp_dir.read_to_string("out/b/lib/index.js"), "export {};");
+    assert!(temp_dir.path().join("out/b/empty").is_dir());
+
+    // the package folder is indexed again when its files left the store
+    let index = store.read_index(&store.index_path(from.as_path())).unwrap();
+    for store_file in index.files.values() {
+      fs::remove_file(temp_dir.path().join("store").join(store_file)).unwrap();
+    }
+    store
+      .link_dir_recursive(
+        from.as_path(),
+        temp_dir.path().join("out/c").as_path(),
+      )
+      .unwrap();
+    assert_eq!(temp_dir.read_to_string("out/c/lib/index.js"), "changed");
+  }
+
+  #[test]
+  fn test_link_dir_recursive_restores_modified_files() {
+    let temp_dir = TempDir::new();
+    temp_dir.create_dir_all("a");
+    temp_dir.write("a/index.js", "export {};");
+    let store =
+      NpmContentStore::new(temp_dir.path().join("store").to_path_buf());
+    let from = temp_dir.path().join("a");
+    store
+      .link_dir_recursive(
+        from.as_path(),
+        temp_dir.path().join("out/a").as_path(),
+      )
+      .unwrap();
+    let out_file = temp_dir.path().join("out/a/index.js");
+    #[cfg(unix)]
+    {
+      use std::os::unix::fs::PermissionsExt;
+      let mode = fs::metadata(&out_file).unwrap().permissions().mode();
+      assert_eq!(mode & 0o777, 0o444);
+    }
+
+    // a project modifies the linked file, which is the file of the store
+    let mut permissions = fs::metadata(&out_file).unwrap().permissions();
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+    fs::set_permissions(&out_file, permissions).unwrap();
+    fs::write(&out_file, "modified").unwrap();
+
+    // the next project gets the original contents again
+    store
+      .link_dir_recursive(
+        from.as_path(),
+        temp_dir.path().join("out/b").as_path(),
+      )
+      .unwrap();
+    assert_eq!(temp_dir.read_to_string("out/b/index.js"), "export {};");
+  }
+}
@@ -26,10 +26,12 @@ use crate::cache::CACHE_PERM;
 use crate::util::fs::atomic_write_file_with_retries;
 use crate::util::fs::hard_link_dir_recursive;
 
+mod content_store;
 mod registry_info;
 mod tarball;
 mod tarball_extract;
 
+pub use content_store::NpmContentStore;
 pub use registry_info::RegistryInfoDownloader;
 pub use tarball::TarballCache;
 
@@ -39,6 +41,9 @@ pub struct NpmCache {
   cache_dir: NpmCacheDir,
   cache_setting: CacheSetting,
   npmrc: Arc<ResolvedNpmRc>,
+  /// Whether this is the npm folder of the vendor folder, which records the
+  /// integrity of each package.
+  vendored: bool,
   /// ensures a package is only downloaded once per run
   previously_reloaded_packages: Mutex<HashSet<PackageNv>>,
 }
@@ -48,15 +53,21 @@ impl NpmCache {
     cache_dir: NpmCacheDir,
     cache_setting: CacheSetting,
     npmrc: Arc<ResolvedNpmRc>,
+    vendored: bool,
   ) -> Self {
     Self {
       cache_dir,
       cache_setting,
       previously_reloaded_packages: Default::default(),
       npmrc,
+      vendored,
     }
   }
 
+  pub fn is_vendored(&self) -> bool {
+    self.vendored
+  }
+
   pub fn cache_setting(&self) -> &CacheSetting {
     &self.cache_setting
   }
@@ -156,6 +167,13 @@ impl NpmCache {
     self.cache_dir.root_dir().to_owned()
   }
 
+  /// The content addressed store of the files of the packages, which are
+  /// hard linked into a `node_modules` folder with `--node-modules-dir=linked`
+  /// on platforms other than macOS.
+  pub fn content_store(&self) -> NpmContentStore {
+    NpmContentStore::new(self.root_folder().join(".store"))
+  }
+
   pub fn resolve_package_folder_id_from_specifier(
     &self,
     specifier: &ModuleSpecifier,
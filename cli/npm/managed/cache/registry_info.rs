@@ -16,9 +16,8 @@ use deno_core::url::Url;
 use deno_npm::npm_rc::ResolvedNpmRc;
 use deno_npm::registry::NpmPackageInfo;
 
-use crate::args::CacheSetting;
 use crate::http_util::HttpClientProvider;
-use crate::npm::common::maybe_auth_header_for_npm_registry;
+use crate::npm::common::maybe_auth_header_for_npm_url;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::sync::MultiRuntimeAsyncValueCreator;
 
@@ -94,12 +93,14 @@ impl RegistryInfoDownloader {
     self: &Arc<Self>,
     name: &str,
   ) -> Result<Option<Arc<NpmPackageInfo>>, AnyError> {
-    if *self.cache.cache_setting() == CacheSetting::Only {
+    let cache_setting = self.cache.cache_setting();
+    if cache_setting.is_cache_only() {
       return Err(custom_error(
         "NotCached",
         format!(
-          "An npm specifier not found in cache: \"{name}\", --cached-only is specified."
-        )
+          "An npm specifier not found in cache: \"{name}\", {} is specified.",
+          cache_setting.cache_only_flag()
+        ),
       ));
     }
 
@@ -193,7 +194,7 @@ impl RegistryInfoDownloader {
     let package_url = self.get_package_url(name);
     let registry_config = self.npmrc.get_registry_config(name);
     let maybe_auth_header =
-      match maybe_auth_header_for_npm_registry(registry_config) {
+      match maybe_auth_header_for_npm_url(registry_config, &package_url) {
         Ok(maybe_auth_header) => maybe_auth_header,
         Err(err) => {
           return std::future::ready(Err(Arc::new(err))).boxed_local()
@@ -1,6 +1,8 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use deno_core::anyhow::anyhow;
@@ -14,14 +16,14 @@ use deno_core::parking_lot::Mutex;
 use deno_core::url::Url;
 use deno_npm::npm_rc::ResolvedNpmRc;
 use deno_npm::registry::NpmPackageVersionDistInfo;
+use deno_npm::registry::NpmPackageVersionDistInfoIntegrity;
 use deno_runtime::deno_fs::FileSystem;
 use deno_semver::package::PackageNv;
 use http::StatusCode;
 
-use crate::args::CacheSetting;
 use crate::http_util::DownloadError;
 use crate::http_util::HttpClientProvider;
-use crate::npm::common::maybe_auth_header_for_npm_registry;
+use crate::npm::common::maybe_auth_header_for_npm_url;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::sync::MultiRuntimeAsyncValueCreator;
 
@@ -145,14 +147,25 @@ impl TarballCache {
         tarball_cache.cache.package_folder_for_nv_and_url(&package_nv, registry_url);
       let should_use_cache = tarball_cache.cache.should_use_cache_for_package(&package_nv);
       let package_folder_exists = tarball_cache.fs.exists_sync(&package_folder);
-      if should_use_cache && package_folder_exists {
+      // a vendored package is extracted again when its tarball changed, like
+      // after updating the lockfile
+      let integrity_file_path = tarball_cache
+        .cache
+        .is_vendored()
+        .then(|| integrity_file_path(&package_folder));
+      let is_outdated_vendored_package = package_folder_exists
+        && integrity_file_path.as_ref().is_some_and(|path| {
+          std::fs::read_to_string(path).ok() != Some(integrity_text(&dist))
+        });
+      if should_use_cache && package_folder_exists && !is_outdated_vendored_package {
         return Ok(());
-      } else if tarball_cache.cache.cache_setting() == &CacheSetting::Only {
+      } else if tarball_cache.cache.cache_setting().is_cache_only() {
         return Err(custom_error(
           "NotCached",
           format!(
-            "An npm specifier not found in cache: \"{}\", --cached-only is specified.",
-            &package_nv.name
+            "An npm specifier not found in cache: \"{}\", {} is specified.",
+            &package_nv.name,
+            tarball_cache.cache.cache_setting().cache_only_flag()
           )
         )
         );
@@ -167,7 +180,7 @@ impl TarballCache {
       let tarball_uri = Url::parse(&dist.tarball)?;
       let maybe_registry_config =
         tarball_cache.npmrc.tarball_config(&tarball_uri);
-      let maybe_auth_header = maybe_registry_config.and_then(|c| maybe_auth_header_for_npm_registry(c).ok()?);
+      let maybe_auth_header = maybe_registry_config.and_then(|c| maybe_auth_header_for_npm_url(c, &tarball_uri).ok()?);
 
       let guard = tarball_cache.progress_bar.update(&dist.tarball);
       let result = tarball_cache.http_client_provider
@@ -198,7 +211,15 @@ impl TarballCache {
       };
       match maybe_bytes {
         Some(bytes) => {
-          let extraction_mode = if should_use_cache || !package_folder_exists {
+          if is_outdated_vendored_package {
+            std::fs::remove_dir_all(&package_folder).with_context(|| {
+              format!("Failed removing '{}'", package_folder.display())
+            })?;
+          }
+          let extraction_mode = if should_use_cache
+            || !package_folder_exists
+            || is_outdated_vendored_package
+          {
             TarballExtractionMode::SiblingTempDir
           } else {
             // The user ran with `--reload`, so overwrite the package instead of
@@ -220,7 +241,17 @@ impl TarballCache {
               &dist,
               &package_folder,
               extraction_mode,
-            )
+            )?;
+            if let Some(integrity_file_path) = integrity_file_path {
+              std::fs::write(&integrity_file_path, integrity_text(&dist))
+                .with_context(|| {
+                  format!(
+                    "Failed writing '{}'",
+                    integrity_file_path.display()
+                  )
+                })?;
+            }
+            Ok(())
           })
           .await?
         }
@@ -233,3 +264,41 @@ impl TarballCache {
     .boxed_local()
   }
 }
+
+/// The file next to the folder of a vendored package which holds the
+/// integrity of the tarball it was extracted from (ex. `4.1.0.integrity`).
+fn integrity_file_path(package_folder: &Path) -> PathBuf {
+  let mut file_name = package_folder.file_name().unwrap().to_os_string();
+  file_name.push(".integrity");
+  package_folder.with_file_name(file_name)
+}
+
+fn integrity_text(dist: &NpmPackageVersionDistInfo) -> String {
+  match dist.integrity() {
+    NpmPackageVersionDistInfoIntegrity::Integrity {
+      algorithm,
+      base64_hash,
+    } => format!("{}-{}", algorithm, base64_hash),
+    NpmPackageVersionDistInfoIntegrity::LegacySha1Hex(hex) => {
+      format!("sha1-hex-{}", hex)
+    }
+    NpmPackageVersionDistInfoIntegrity::UnknownIntegrity(integrity) => {
+      integrity.to_string()
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_integrity_file_path() {
+    assert_eq!(
+      integrity_file_path(Path::new(
+        "/vendor/npm/registry.npmjs.org/chalk/4.1.0"
+      )),
+      PathBuf::from("/vendor/npm/registry.npmjs.org/chalk/4.1.0.integrity"),
+    );
+  }
+}
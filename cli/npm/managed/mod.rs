@@ -49,6 +49,7 @@ use self::cache::NpmCache;
 use self::registry::CliNpmRegistryApi;
 use self::resolution::NpmResolution;
 use self::resolvers::create_npm_fs_resolver;
+use self::resolvers::local_packages_with_skipped_scripts;
 use self::resolvers::NpmPackageFsResolver;
 
 use super::CliNpmResolver;
@@ -71,9 +72,14 @@ pub struct CliNpmResolverManagedCreateOptions {
   pub fs: Arc<dyn deno_runtime::deno_fs::FileSystem>,
   pub http_client_provider: Arc<crate::http_util::HttpClientProvider>,
   pub npm_global_cache_dir: PathBuf,
+  /// Whether the npm cache is the npm folder of the vendor folder.
+  pub vendor_npm: bool,
   pub cache_setting: crate::args::CacheSetting,
   pub text_only_progress_bar: crate::util::progress_bar::ProgressBar,
   pub maybe_node_modules_path: Option<PathBuf>,
+  /// Whether the files of the packages in the `node_modules` folder are hard
+  /// linked from a content addressed store.
+  pub node_modules_linked: bool,
   pub npm_system_info: NpmSystemInfo,
   pub npm_install_deps_provider: Arc<NpmInstallDepsProvider>,
   pub npmrc: Arc<ResolvedNpmRc>,
@@ -104,6 +110,7 @@ pub async fn create_managed_npm_resolver_for_lsp(
       options.npm_install_deps_provider,
       options.text_only_progress_bar,
       options.maybe_node_modules_path,
+      options.node_modules_linked,
       options.npm_system_info,
       snapshot,
       options.lifecycle_scripts,
@@ -129,6 +136,7 @@ pub async fn create_managed_npm_resolver(
     options.npm_install_deps_provider,
     options.text_only_progress_bar,
     options.maybe_node_modules_path,
+    options.node_modules_linked,
     options.npm_system_info,
     snapshot,
     options.lifecycle_scripts,
@@ -146,6 +154,7 @@ fn create_inner(
   npm_install_deps_provider: Arc<NpmInstallDepsProvider>,
   text_only_progress_bar: crate::util::progress_bar::ProgressBar,
   node_modules_dir_path: Option<PathBuf>,
+  node_modules_linked: bool,
   npm_system_info: NpmSystemInfo,
   snapshot: Option<ValidSerializedNpmResolutionSnapshot>,
   lifecycle_scripts: LifecycleScriptsConfig,
@@ -170,6 +179,7 @@ fn create_inner(
     resolution.clone(),
     tarball_cache.clone(),
     node_modules_dir_path,
+    node_modules_linked,
     npm_system_info.clone(),
     lifecycle_scripts.clone(),
   );
@@ -183,6 +193,7 @@ fn create_inner(
     resolution,
     tarball_cache,
     text_only_progress_bar,
+    node_modules_linked,
     npm_system_info,
     lifecycle_scripts,
   ))
@@ -197,6 +208,7 @@ fn create_cache(options: &CliNpmResolverManagedCreateOptions) -> Arc<NpmCache> {
     ),
     options.cache_setting.clone(),
     options.npmrc.clone(),
+    options.vendor_npm,
   ))
 }
 
@@ -270,6 +282,7 @@ pub struct ManagedCliNpmResolver {
   resolution: Arc<NpmResolution>,
   tarball_cache: Arc<TarballCache>,
   text_only_progress_bar: ProgressBar,
+  node_modules_linked: bool,
   npm_system_info: NpmSystemInfo,
   top_level_install_flag: AtomicFlag,
   lifecycle_scripts: LifecycleScriptsConfig,
@@ -295,6 +308,7 @@ impl ManagedCliNpmResolver {
     resolution: Arc<NpmResolution>,
     tarball_cache: Arc<TarballCache>,
     text_only_progress_bar: ProgressBar,
+    node_modules_linked: bool,
     npm_system_info: NpmSystemInfo,
     lifecycle_scripts: LifecycleScriptsConfig,
   ) -> Self {
@@ -308,6 +322,7 @@ impl ManagedCliNpmResolver {
       text_only_progress_bar,
       resolution,
       tarball_cache,
+      node_modules_linked,
       npm_system_info,
       top_level_install_flag: Default::default(),
       lifecycle_scripts,
@@ -370,6 +385,20 @@ impl ManagedCliNpmResolver {
     self.resolution.all_system_packages(system_info)
   }
 
+  /// Packages whose lifecycle scripts were not run when they were set up in
+  /// the local node_modules directory, because they were not allowed.
+  pub fn packages_with_skipped_lifecycle_scripts(
+    &self,
+  ) -> Vec<NpmResolutionPackage> {
+    let Some(node_modules_path) = self.fs_resolver.node_modules_path() else {
+      return Vec::new();
+    };
+    local_packages_with_skipped_scripts(
+      node_modules_path,
+      self.all_system_packages(&self.npm_system_info),
+    )
+  }
+
   /// Checks if the provided package req's folder is cached.
   pub fn is_pkg_req_folder_cached(&self, req: &PackageReq) -> bool {
     self
@@ -645,6 +674,7 @@ impl CliNpmResolver for ManagedCliNpmResolver {
         npm_resolution.clone(),
         self.tarball_cache.clone(),
         self.root_node_modules_path().map(ToOwned::to_owned),
+        self.node_modules_linked,
         self.npm_system_info.clone(),
         self.lifecycle_scripts.clone(),
       ),
@@ -655,6 +685,7 @@ impl CliNpmResolver for ManagedCliNpmResolver {
       npm_resolution,
       self.tarball_cache.clone(),
       self.text_only_progress_bar.clone(),
+      self.node_modules_linked,
       self.npm_system_info.clone(),
       self.lifecycle_scripts.clone(),
     ))
@@ -159,7 +159,7 @@ impl CliNpmRegistryApiInner {
     // is disabled or if we're already reloading
     if matches!(
       self.cache.cache_setting(),
-      CacheSetting::Only | CacheSetting::ReloadAll
+      CacheSetting::Only | CacheSetting::Offline | CacheSetting::ReloadAll
     ) {
       return false;
     }
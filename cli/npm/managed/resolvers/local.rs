@@ -70,6 +70,9 @@ pub struct LocalNpmPackageResolver {
   tarball_cache: Arc<TarballCache>,
   root_node_modules_path: PathBuf,
   root_node_modules_url: Url,
+  /// Whether the files of the packages are hard linked from the content
+  /// addressed store instead of the npm cache.
+  linked: bool,
   system_info: NpmSystemInfo,
   registry_read_permission_checker: RegistryReadPermissionChecker,
   lifecycle_scripts: LifecycleScriptsConfig,
@@ -85,6 +88,7 @@ impl LocalNpmPackageResolver {
     resolution: Arc<NpmResolution>,
     tarball_cache: Arc<TarballCache>,
     node_modules_folder: PathBuf,
+    linked: bool,
     system_info: NpmSystemInfo,
     lifecycle_scripts: LifecycleScriptsConfig,
   ) -> Self {
@@ -102,6 +106,7 @@ impl LocalNpmPackageResolver {
       root_node_modules_url: Url::from_directory_path(&node_modules_folder)
         .unwrap(),
       root_node_modules_path: node_modules_folder,
+      linked,
       system_info,
       lifecycle_scripts,
     }
@@ -251,6 +256,7 @@ impl NpmPackageFsResolver for LocalNpmPackageResolver {
       &self.progress_bar,
       &self.tarball_cache,
       &self.root_node_modules_path,
+      self.linked,
       &self.system_info,
       &self.lifecycle_scripts,
     )
@@ -292,6 +298,7 @@ async fn sync_resolution_with_fs(
   progress_bar: &ProgressBar,
   tarball_cache: &Arc<TarballCache>,
   root_node_modules_dir_path: &Path,
+  linked: bool,
   system_info: &NpmSystemInfo,
   lifecycle_scripts: &LifecycleScriptsConfig,
 ) -> Result<(), AnyError> {
@@ -324,10 +331,18 @@ async fn sync_resolution_with_fs(
 
   let pb_clear_guard = progress_bar.clear_guard(); // prevent flickering
 
+  // clonefile already shares the data of the cloned files on macOS, so the
+  // store wouldn't save any space there
+  let content_store = (linked && !cfg!(target_vendor = "apple"))
+    .then(|| Arc::new(cache.content_store()));
+
   // 1. Write all the packages out the .deno directory.
   //
   // Copy (hardlink in future) <global_registry_cache>/<package_id>/ to
   // node_modules/.deno/<package_folder_id_folder_name>/node_modules/<package_name>
+  //
+  // With `--node-modules-dir=linked`, the files are hard linked from the
+  // content addressed store instead.
   let package_partitions =
     snapshot.all_system_packages_partitioned(system_info);
   let mut cache_futures = FuturesUnordered::new();
@@ -397,6 +412,7 @@ async fn sync_resolution_with_fs(
       let bin_entries_to_setup = bin_entries.clone();
       let packages_with_deprecation_warnings =
         packages_with_deprecation_warnings.clone();
+      let content_store = content_store.clone();
 
       cache_futures.push(async move {
         tarball_cache
@@ -414,7 +430,11 @@ async fn sync_resolution_with_fs(
         deno_core::unsync::spawn_blocking({
           let package_path = package_path.clone();
           move || {
-            clone_dir_recursive(&cache_folder, &package_path)?;
+            match &content_store {
+              Some(content_store) => content_store
+                .link_dir_recursive(&cache_folder, &package_path)?,
+              None => clone_dir_recursive(&cache_folder, &package_path)?,
+            }
             // write out a file that indicates this folder has been initialized
             fs::write(initialized_file, tags)?;
 
@@ -723,6 +743,25 @@ async fn sync_resolution_with_fs(
   Ok(())
 }
 
+/// Packages in the local `node_modules` directory which have lifecycle
+/// scripts that were skipped because they were not allowed to run.
+pub fn local_packages_with_skipped_scripts(
+  root_node_modules_dir_path: &Path,
+  packages: Vec<NpmResolutionPackage>,
+) -> Vec<NpmResolutionPackage> {
+  let deno_local_registry_dir = root_node_modules_dir_path.join(".deno");
+  let scripts = LocalLifecycleScripts {
+    deno_local_registry_dir: &deno_local_registry_dir,
+  };
+  packages
+    .into_iter()
+    .filter(|package| {
+      scripts.warned_scripts_file(package).exists()
+        && !scripts.ran_scripts_file(package).exists()
+    })
+    .collect()
+}
+
 /// `node_modules/.deno/<package>/`
 fn local_node_modules_package_folder(
   local_registry_dir: &Path,
@@ -15,6 +15,7 @@ use crate::args::NpmInstallDepsProvider;
 use crate::util::progress_bar::ProgressBar;
 
 pub use self::common::NpmPackageFsResolver;
+pub use self::local::local_packages_with_skipped_scripts;
 
 use self::global::GlobalNpmPackageResolver;
 use self::local::LocalNpmPackageResolver;
@@ -32,6 +33,7 @@ pub fn create_npm_fs_resolver(
   resolution: Arc<NpmResolution>,
   tarball_cache: Arc<TarballCache>,
   maybe_node_modules_path: Option<PathBuf>,
+  node_modules_linked: bool,
   system_info: NpmSystemInfo,
   lifecycle_scripts: LifecycleScriptsConfig,
 ) -> Arc<dyn NpmPackageFsResolver> {
@@ -44,6 +46,7 @@ pub fn create_npm_fs_resolver(
       resolution,
       tarball_cache,
       node_modules_folder,
+      node_modules_linked,
       system_info,
       lifecycle_scripts,
     )),
@@ -16,6 +16,7 @@ use deno_runtime::deno_permissions::PermissionsContainer;
 use tokio::sync::mpsc::UnboundedSender;
 use uuid::Uuid;
 
+use crate::args::BenchEngineOptions;
 use crate::tools::bench::BenchDescription;
 use crate::tools::bench::BenchEvent;
 
@@ -32,16 +33,25 @@ deno_core::extension!(deno_bench,
     op_bench_get_origin,
     op_dispatch_bench_event,
     op_bench_now,
+    op_bench_collect_samples,
+    op_bench_get_engine_options,
   ],
   options = {
     sender: UnboundedSender<BenchEvent>,
+    collect_samples: bool,
+    engine: BenchEngineOptions,
   },
   state = |state, options| {
     state.put(options.sender);
     state.put(BenchContainer::default());
+    state.put(BenchCollectSamples(options.collect_samples));
+    state.put(options.engine);
   },
 );
 
+/// Whether raw iteration samples should be sent back along with the stats.
+struct BenchCollectSamples(bool);
+
 #[op2]
 #[string]
 fn op_bench_get_origin(state: &mut OpState) -> String {
@@ -152,3 +162,14 @@ fn op_bench_now(state: &mut OpState) -> Result<u64, AnyError> {
   let ns_u64 = u64::try_from(ns)?;
   Ok(ns_u64)
 }
+
+#[op2(fast)]
+fn op_bench_collect_samples(state: &mut OpState) -> bool {
+  state.borrow::<BenchCollectSamples>().0
+}
+
+#[op2]
+#[serde]
+fn op_bench_get_engine_options(state: &mut OpState) -> BenchEngineOptions {
+  *state.borrow::<BenchEngineOptions>()
+}
@@ -21,6 +21,7 @@ use deno_core::serde_json;
 use deno_core::OpState;
 use tokio::sync::mpsc;
 
+use crate::tools::jupyter::outputs::OutputSink;
 use crate::tools::jupyter::server::StdinConnectionProxy;
 
 deno_core::extension!(deno_jupyter,
@@ -30,6 +31,7 @@ deno_core::extension!(deno_jupyter,
   ],
   options = {
     sender: mpsc::UnboundedSender<StreamContent>,
+    output_sink: OutputSink,
   },
   middleware = |op| match op.name {
     "op_print" => op_print(),
@@ -37,6 +39,7 @@ deno_core::extension!(deno_jupyter,
   },
   state = |state, options| {
     state.put(options.sender);
+    state.put(options.output_sink);
   },
 );
 
@@ -99,19 +102,32 @@ pub fn op_jupyter_input(
 pub async fn op_jupyter_broadcast(
   state: Rc<RefCell<OpState>>,
   #[string] message_type: String,
-  #[serde] content: serde_json::Value,
+  #[serde] mut content: serde_json::Value,
   #[serde] metadata: serde_json::Value,
   #[serde] buffers: Vec<deno_core::JsBuffer>,
 ) -> Result<(), AnyError> {
-  let (iopub_connection, last_execution_request) = {
+  let (iopub_connection, last_execution_request, output_sink) = {
     let s = state.borrow();
 
     (
       s.borrow::<Arc<Mutex<KernelIoPubConnection>>>().clone(),
       s.borrow::<Arc<Mutex<Option<JupyterMessage>>>>().clone(),
+      s.borrow::<OutputSink>().clone(),
     )
   };
 
+  if matches!(
+    message_type.as_str(),
+    "display_data" | "update_display_data" | "execute_result"
+  ) {
+    if let Some(bundle) = content
+      .get_mut("data")
+      .and_then(|data| data.as_object_mut())
+    {
+      output_sink.process_bundle(bundle)?;
+    }
+  }
+
   let maybe_last_request = last_execution_request.lock().clone();
   if let Some(last_request) = maybe_last_request {
     let content = JupyterMessageContent::from_type_and_content(
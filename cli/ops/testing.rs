@@ -1,5 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use crate::tools::test::FailFastTracker;
+use crate::tools::test::TestArtifactsDir;
 use crate::tools::test::TestContainer;
 use crate::tools::test::TestDescription;
 use crate::tools::test::TestEvent;
@@ -9,6 +11,7 @@ use crate::tools::test::TestLocation;
 use crate::tools::test::TestStepDescription;
 use crate::tools::test::TestStepResult;
 
+use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::type_error;
 use deno_core::error::AnyError;
@@ -18,6 +21,8 @@ use deno_core::ModuleSpecifier;
 use deno_core::OpState;
 use deno_runtime::deno_permissions::ChildPermissionsArg;
 use deno_runtime::deno_permissions::PermissionsContainer;
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use uuid::Uuid;
@@ -29,6 +34,9 @@ deno_core::extension!(deno_test,
     op_register_test,
     op_register_test_step,
     op_test_get_origin,
+    op_test_get_update_snapshots,
+    op_test_get_artifacts_dir,
+    op_test_wait_for_fail_fast,
     op_test_event_step_wait,
     op_test_event_step_result_ok,
     op_test_event_step_result_ignored,
@@ -36,13 +44,23 @@ deno_core::extension!(deno_test,
   ],
   options = {
     sender: TestEventSender,
+    update_snapshots: bool,
+    artifacts_dir: Option<TestArtifactsDir>,
   },
   state = |state, options| {
     state.put(options.sender);
     state.put(TestContainer::default());
+    state.put(UpdateSnapshots(options.update_snapshots));
+    state.put(TestArtifacts(options.artifacts_dir));
   },
 );
 
+/// Whether `--update-snapshots` was passed.
+struct UpdateSnapshots(bool);
+
+/// The `--artifacts-dir`, if passed.
+struct TestArtifacts(Option<TestArtifactsDir>);
+
 #[derive(Clone)]
 struct PermissionsHolder(Uuid, PermissionsContainer);
 
@@ -98,6 +116,7 @@ fn op_register_test(
   only: bool,
   sanitize_ops: bool,
   sanitize_resources: bool,
+  #[serde] tags: Vec<String>,
   #[string] file_name: String,
   #[smi] line_number: u32,
   #[smi] column_number: u32,
@@ -118,6 +137,7 @@ fn op_register_test(
     only,
     sanitize_ops,
     sanitize_resources,
+    tags,
     origin: origin.clone(),
     location: TestLocation {
       file_name,
@@ -137,6 +157,43 @@ fn op_test_get_origin(state: &mut OpState) -> String {
   state.borrow::<ModuleSpecifier>().to_string()
 }
 
+#[op2(fast)]
+fn op_test_get_update_snapshots(state: &mut OpState) -> bool {
+  state.borrow::<UpdateSnapshots>().0
+}
+
+/// Resolves once `--fail-fast` stops the run, so that the tests which are
+/// still running can be aborted through their signal.
+#[op2(async)]
+async fn op_test_wait_for_fail_fast(state: Rc<RefCell<OpState>>) {
+  let tracker = state.borrow().try_borrow::<FailFastTracker>().cloned();
+  match tracker {
+    Some(tracker) => tracker.stopped().await,
+    None => std::future::pending().await,
+  }
+}
+
+/// Creates the artifacts directory of a test or step, whose names are passed
+/// from the root test down.
+#[op2]
+#[string]
+fn op_test_get_artifacts_dir(
+  state: &mut OpState,
+  #[smi] id: usize,
+  #[serde] names: Vec<String>,
+) -> Result<Option<String>, AnyError> {
+  let Some(artifacts_dir) = &state.borrow::<TestArtifacts>().0 else {
+    return Ok(None);
+  };
+  let path = artifacts_dir.path(state.borrow::<ModuleSpecifier>(), &names);
+  std::fs::create_dir_all(&path).with_context(|| {
+    format!("Failed creating artifacts directory '{}'", path.display())
+  })?;
+  let sender = state.borrow_mut::<TestEventSender>();
+  sender.send(TestEvent::Artifacts(id, path.clone())).ok();
+  Ok(Some(path.to_string_lossy().into_owned()))
+}
+
 #[op2(fast)]
 #[smi]
 #[allow(clippy::too_many_arguments)]
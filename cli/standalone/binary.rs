@@ -42,6 +42,11 @@ use log::Level;
 use serde::Deserialize;
 use serde::Serialize;
 
+use super::licenses::print_licenses;
+use super::licenses::read_node_modules_licenses;
+use super::licenses::read_npm_package_license;
+use super::licenses::ThirdPartyLicense;
+use super::licenses::LICENSES_ARG;
 use crate::args::CaData;
 use crate::args::CliOptions;
 use crate::args::CompileFlags;
@@ -118,6 +123,10 @@ pub struct Metadata {
   pub entrypoint_key: String,
   pub node_modules: Option<NodeModules>,
   pub unstable_config: UnstableConfig,
+  pub node_conditions: Vec<String>,
+  /// The licenses of the embedded npm packages, printed when the binary is
+  /// run with `--licenses`. `None` when compiled with `--no-licenses`.
+  pub licenses: Option<Vec<ThirdPartyLicense>>,
 }
 
 pub fn load_npm_vfs(root_dir_path: PathBuf) -> Result<FileBackedVfs, AnyError> {
@@ -263,6 +272,12 @@ pub fn extract_standalone(
       .context("Failed to read metadata from the current executable")?;
 
     let mut metadata: Metadata = serde_json::from_str(&metadata).unwrap();
+    if let Some(licenses) = &metadata.licenses {
+      if cli_args.get(1).is_some_and(|arg| arg == LICENSES_ARG) {
+        print_licenses(licenses);
+        std::process::exit(0);
+      }
+    }
     metadata.argv.reserve(cli_args.len() - 1);
     for arg in cli_args.into_iter().skip(1) {
       metadata.argv.push(arg.into_string().unwrap());
@@ -560,18 +575,30 @@ impl<'a> DenoCompileBinaryWriter<'a> {
       }
     };
 
-    let env_vars_from_env_file = match cli_options.env_file_name() {
-      Some(env_filename) => {
-        log::info!("{} Environment variables from the file \"{}\" were embedded in the generated executable file", crate::colors::yellow("Warning"), env_filename);
-        get_file_env_vars(env_filename.to_string())?
+    let licenses = if compile_flags.no_licenses {
+      None
+    } else {
+      Some(self.collect_licenses()?)
+    };
+
+    let env_vars_from_env_file = match cli_options.env_file_names() {
+      Some(env_filenames) => {
+        let mut env_vars = IndexMap::new();
+        for env_filename in env_filenames {
+          log::info!("{} Environment variables from the file \"{}\" were embedded in the generated executable file", crate::colors::yellow("Warning"), env_filename);
+          // the variables of the later files override the earlier ones
+          env_vars.extend(get_file_env_vars(env_filename.to_string())?);
+        }
+        env_vars
       }
       None => Default::default(),
     };
 
-    let metadata = Metadata {
+    let mut metadata = Metadata {
       argv: compile_flags.args.clone(),
       seed: cli_options.seed(),
       location: cli_options.location_flag().clone(),
+      node_conditions: cli_options.node_conditions().to_vec(),
       permissions: cli_options.permission_flags().clone(),
       v8_flags: cli_options.v8_flags().clone(),
       unsafely_ignore_certificate_errors: cli_options
@@ -619,6 +646,7 @@ impl<'a> DenoCompileBinaryWriter<'a> {
         pkg_json_resolution: self.workspace_resolver.pkg_json_dep_resolution(),
       },
       node_modules,
+      licenses,
       unstable_config: UnstableConfig {
         legacy_flag_enabled: false,
         bare_node_builtins: cli_options.unstable_bare_node_builtins(),
@@ -626,6 +654,13 @@ impl<'a> DenoCompileBinaryWriter<'a> {
         features: cli_options.unstable_features(),
       },
     };
+    if compile_flags.reproducible {
+      // the workspace members are in the order they were discovered in
+      metadata
+        .workspace_resolver
+        .jsr_pkgs
+        .sort_by(|a, b| a.relative_base.cmp(&b.relative_base));
+    }
 
     write_binary_bytes(
       writer,
@@ -638,6 +673,39 @@ impl<'a> DenoCompileBinaryWriter<'a> {
     )
   }
 
+  /// Collects the licenses of the npm packages which are embedded in the
+  /// binary.
+  fn collect_licenses(&self) -> Result<Vec<ThirdPartyLicense>, AnyError> {
+    match self.npm_resolver.as_inner() {
+      InnerCliNpmResolverRef::Managed(npm_resolver) => {
+        let mut licenses = BTreeMap::new();
+        for package in npm_resolver.all_system_packages(&self.npm_system_info) {
+          let nv = &package.id.nv;
+          // packages only differing in their peer dependencies share a license
+          let key = nv.to_string();
+          if licenses.contains_key(&key) {
+            continue;
+          }
+          let folder =
+            npm_resolver.resolve_pkg_folder_from_pkg_id(&package.id)?;
+          let license = read_npm_package_license(
+            &nv.name,
+            &nv.version.to_string(),
+            &folder,
+          );
+          licenses.insert(key, license);
+        }
+        Ok(licenses.into_values().collect())
+      }
+      InnerCliNpmResolverRef::Byonm(resolver) => Ok(
+        resolver
+          .root_node_modules_path()
+          .map(read_node_modules_licenses)
+          .unwrap_or_default(),
+      ),
+    }
+  }
+
   fn build_vfs(
     &self,
     root_path: &Path,
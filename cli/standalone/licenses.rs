@@ -0,0 +1,209 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::serde_json;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// The argument that makes a compiled binary print the licenses of the
+/// embedded third-party packages instead of running.
+pub const LICENSES_ARG: &str = "--licenses";
+
+/// The license of a third-party package embedded in a compiled binary.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ThirdPartyLicense {
+  pub name: String,
+  pub version: String,
+  /// The SPDX expression from the "license" field of the package.json.
+  pub license: Option<String>,
+  /// The contents of the license and notice files of the package.
+  pub texts: Vec<String>,
+}
+
+fn is_license_file_name(name: &str) -> bool {
+  let name = name.to_ascii_uppercase();
+  ["LICENSE", "LICENCE", "COPYING", "NOTICE"]
+    .iter()
+    .any(|prefix| name.starts_with(prefix))
+}
+
+/// Reads the license of the npm package in the folder.
+pub fn read_npm_package_license(
+  name: &str,
+  version: &str,
+  folder: &Path,
+) -> ThirdPartyLicense {
+  #[derive(Deserialize)]
+  struct PackageJsonLicense {
+    license: Option<serde_json::Value>,
+  }
+
+  let license = std::fs::read_to_string(folder.join("package.json"))
+    .ok()
+    .and_then(|text| {
+      serde_json::from_str::<PackageJsonLicense>(&text)
+        .ok()?
+        .license
+    })
+    .and_then(|license| match license {
+      serde_json::Value::String(license) => Some(license),
+      // the deprecated `{ "type": "MIT", "url": "..." }` form
+      serde_json::Value::Object(obj) => {
+        obj.get("type")?.as_str().map(|s| s.to_string())
+      }
+      _ => None,
+    });
+
+  let mut files = std::fs::read_dir(folder)
+    .into_iter()
+    .flatten()
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+    .filter(|entry| is_license_file_name(&entry.file_name().to_string_lossy()))
+    .map(|entry| entry.path())
+    .collect::<Vec<_>>();
+  files.sort(); // determinism
+  let texts = files
+    .iter()
+    .filter_map(|path| std::fs::read_to_string(path).ok())
+    .map(|text| text.trim().to_string())
+    .filter(|text| !text.is_empty())
+    .collect();
+
+  ThirdPartyLicense {
+    name: name.to_string(),
+    version: version.to_string(),
+    license,
+    texts,
+  }
+}
+
+/// Reads the licenses of the packages in the top level of a node_modules
+/// directory.
+pub fn read_node_modules_licenses(
+  node_modules_dir: &Path,
+) -> Vec<ThirdPartyLicense> {
+  #[derive(Deserialize)]
+  struct PackageJsonNameAndVersion {
+    name: Option<String>,
+    version: Option<String>,
+  }
+
+  fn sub_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = std::fs::read_dir(dir)
+      .into_iter()
+      .flatten()
+      .filter_map(|entry| entry.ok())
+      .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+      .map(|entry| entry.path())
+      .collect::<Vec<_>>();
+    dirs.sort(); // determinism
+    dirs
+  }
+
+  let mut package_dirs = Vec::new();
+  for dir in sub_dirs(node_modules_dir) {
+    let Some(file_name) =
+      dir.file_name().map(|n| n.to_string_lossy().into_owned())
+    else {
+      continue;
+    };
+    if file_name.starts_with('@') {
+      package_dirs.extend(sub_dirs(&dir));
+    } else if !file_name.starts_with('.') {
+      package_dirs.push(dir);
+    }
+  }
+
+  package_dirs
+    .into_iter()
+    .filter_map(|dir| {
+      let text = std::fs::read_to_string(dir.join("package.json")).ok()?;
+      let pkg_json =
+        serde_json::from_str::<PackageJsonNameAndVersion>(&text).ok()?;
+      Some(read_npm_package_license(
+        &pkg_json.name?,
+        &pkg_json.version.unwrap_or_default(),
+        &dir,
+      ))
+    })
+    .collect()
+}
+
+#[allow(clippy::print_stdout)]
+pub fn print_licenses(licenses: &[ThirdPartyLicense]) {
+  print!("{}", format_licenses(licenses));
+}
+
+/// Formats the licenses the way they're printed by `<binary> --licenses`.
+pub fn format_licenses(licenses: &[ThirdPartyLicense]) -> String {
+  if licenses.is_empty() {
+    return "This executable does not include any third-party packages.\n"
+      .to_string();
+  }
+  let mut output = String::new();
+  for license in licenses {
+    let separator = "=".repeat(80);
+    output.push_str(&separator);
+    output.push('\n');
+    output.push_str(&format!(
+      "{}@{}{}\n",
+      license.name,
+      license.version,
+      license
+        .license
+        .as_ref()
+        .map(|l| format!(" ({l})"))
+        .unwrap_or_default()
+    ));
+    output.push_str(&separator);
+    output.push('\n');
+    if license.texts.is_empty() {
+      output.push_str("No license file was found in this package.\n");
+    }
+    for text in &license.texts {
+      output.push('\n');
+      output.push_str(text);
+      output.push('\n');
+    }
+    output.push('\n');
+  }
+  output
+}
+
+#[cfg(test)]
+mod test {
+  use test_util::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn test_read_npm_package_license() {
+    let temp_dir = TempDir::new();
+    temp_dir.write(
+      "package.json",
+      r#"{ "name": "chalk", "license": { "type": "MIT" } }"#,
+    );
+    temp_dir.write("license.md", "MIT License\n\nCopyright (c) Sindre\n");
+    temp_dir.write("NOTICE", "Some notice");
+    temp_dir.write("index.js", "export default 1;");
+
+    let license =
+      read_npm_package_license("chalk", "5.3.0", temp_dir.path().as_path());
+    assert_eq!(
+      license,
+      ThirdPartyLicense {
+        name: "chalk".to_string(),
+        version: "5.3.0".to_string(),
+        license: Some("MIT".to_string()),
+        texts: vec![
+          "Some notice".to_string(),
+          "MIT License\n\nCopyright (c) Sindre".to_string(),
+        ],
+      }
+    );
+    assert!(format_licenses(&[license]).contains("chalk@5.3.0 (MIT)\n"));
+  }
+}
@@ -78,6 +78,7 @@ use crate::worker::ModuleLoaderFactory;
 
 pub mod binary;
 mod file_system;
+mod licenses;
 mod virtual_fs;
 
 pub use binary::extract_standalone;
@@ -497,9 +498,11 @@ pub async fn run(
             fs: fs.clone(),
             http_client_provider: http_client_provider.clone(),
             npm_global_cache_dir,
+            vendor_npm: false,
             cache_setting,
             text_only_progress_bar: progress_bar,
             maybe_node_modules_path,
+            node_modules_linked: false,
             npm_system_info: Default::default(),
             npm_install_deps_provider: Arc::new(
               // this is only used for installing packages, which isn't necessary with deno compile
@@ -549,9 +552,11 @@ pub async fn run(
             fs: fs.clone(),
             http_client_provider: http_client_provider.clone(),
             npm_global_cache_dir,
+            vendor_npm: false,
             cache_setting,
             text_only_progress_bar: progress_bar,
             maybe_node_modules_path: None,
+            node_modules_linked: false,
             npm_system_info: Default::default(),
             npm_install_deps_provider: Arc::new(
               // this is only used for installing packages, which isn't necessary with deno compile
@@ -572,6 +577,7 @@ pub async fn run(
   let node_resolver = Arc::new(NodeResolver::new(
     deno_runtime::deno_node::DenoFsNodeResolverEnv::new(fs.clone()),
     npm_resolver.clone().into_npm_resolver(),
+    metadata.node_conditions.clone(),
   ));
   let cjs_resolutions = Arc::new(CjsResolutionStore::default());
   let cache_db = Caches::new(deno_dir_provider.clone());
@@ -738,6 +744,13 @@ pub async fn run(
       node_ipc: None,
       serve_port: None,
       serve_host: None,
+      serve_mirror: None,
+      serve_mirror_percent: None,
+      serve_max_concurrent_uploads: None,
+      serve_upload_high_water_mark: None,
+      serve_upload_metrics_path: None,
+      serve_idle_shutdown: None,
+      serve_tls: None,
     },
   );
 
@@ -0,0 +1,153 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::io::IsTerminal;
+use std::io::Write;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+
+use crate::args::credentials::keychain;
+use crate::args::credentials::resolve_registry;
+use crate::args::credentials::CredentialsFile;
+use crate::args::credentials::StoredCredential;
+use crate::args::AuthFlags;
+use crate::args::AuthLoginFlags;
+use crate::colors;
+
+pub fn auth(auth_flags: AuthFlags) -> Result<(), AnyError> {
+  let Some(path) = CredentialsFile::default_path() else {
+    bail!("Could not determine the home directory to store credentials in.");
+  };
+  let mut file = CredentialsFile::read(&path)?;
+  match auth_flags {
+    AuthFlags::Login(login_flags) => login(&mut file, login_flags)?,
+    AuthFlags::Logout { registry } => logout(&mut file, &registry)?,
+  }
+  file.write(&path)
+}
+
+fn login(
+  file: &mut CredentialsFile,
+  login_flags: AuthLoginFlags,
+) -> Result<(), AnyError> {
+  let (host, kind) = resolve_registry(&login_flags.registry)?;
+  let token = match login_flags.token {
+    Some(token) => token,
+    None => read_token(&host)?,
+  };
+  if token.is_empty() {
+    bail!("No token provided.");
+  }
+
+  let use_keychain = !login_flags.no_keychain && keychain::is_available();
+  let stored_in = if use_keychain {
+    keychain::set(&host, &token)
+      .context("Failed storing the token in the system keychain. Pass --no-keychain to store it in the credentials file instead.")?;
+    "system keychain".to_string()
+  } else {
+    CredentialsFile::default_path()
+      .map(|path| path.display().to_string())
+      .unwrap_or_default()
+  };
+  file.hosts.insert(
+    host.clone(),
+    StoredCredential {
+      kind,
+      token: (!use_keychain).then_some(token),
+      keychain: use_keychain,
+    },
+  );
+  log::info!(
+    "{} {} token for {} {}",
+    colors::green("Stored"),
+    kind.as_str(),
+    host,
+    colors::gray(format!("({stored_in})"))
+  );
+  Ok(())
+}
+
+fn logout(file: &mut CredentialsFile, registry: &str) -> Result<(), AnyError> {
+  let (host, _) = resolve_registry(registry)?;
+  let Some(credential) = file.hosts.remove(&host) else {
+    bail!("No token is stored for {}.", host);
+  };
+  if credential.keychain {
+    if let Err(err) = keychain::delete(&host) {
+      log::warn!(
+        "{} Failed removing the token from the system keychain: {:#}",
+        colors::yellow("Warning"),
+        err
+      );
+    }
+  }
+  log::info!("{} token for {}", colors::green("Removed"), host);
+  Ok(())
+}
+
+#[allow(clippy::print_stderr)]
+fn read_token(host: &str) -> Result<String, AnyError> {
+  let stdin = std::io::stdin();
+  let token = if stdin.is_terminal() {
+    eprint!("Token for {}: ", host);
+    std::io::stderr().flush()?;
+    read_line_without_echo()
+  } else {
+    let mut token = String::new();
+    stdin.read_line(&mut token).map(|_| token)
+  }
+  .context("Failed reading the token from standard input")?;
+  Ok(token.trim().to_string())
+}
+
+/// Reads a line from the terminal without showing what is typed.
+#[cfg(unix)]
+fn read_line_without_echo() -> Result<String, std::io::Error> {
+  use nix::sys::termios;
+  use std::os::fd::AsRawFd;
+
+  let stdin = std::io::stdin();
+  let fd = stdin.as_raw_fd();
+  let original = termios::tcgetattr(fd)?;
+  let mut silent = original.clone();
+  silent.local_flags &= !termios::LocalFlags::ECHO;
+  // still echo the newline, so that the output continues on the next line
+  silent.local_flags |= termios::LocalFlags::ECHONL;
+  termios::tcsetattr(fd, termios::SetArg::TCSANOW, &silent)?;
+  let mut line = String::new();
+  let result = stdin.read_line(&mut line);
+  termios::tcsetattr(fd, termios::SetArg::TCSANOW, &original)?;
+  result.map(|_| line)
+}
+
+/// Reads a line from the terminal without showing what is typed.
+#[cfg(windows)]
+#[allow(clippy::print_stderr)]
+fn read_line_without_echo() -> Result<String, std::io::Error> {
+  use winapi::um::consoleapi::GetConsoleMode;
+  use winapi::um::consoleapi::SetConsoleMode;
+  use winapi::um::processenv::GetStdHandle;
+  use winapi::um::winbase::STD_INPUT_HANDLE;
+  use winapi::um::wincon::ENABLE_ECHO_INPUT;
+
+  // SAFETY: winapi calls on the standard input handle
+  let (handle, original_mode) = unsafe {
+    let handle = GetStdHandle(STD_INPUT_HANDLE);
+    let mut mode = 0;
+    if GetConsoleMode(handle, &mut mode) == 0
+      || SetConsoleMode(handle, mode & !ENABLE_ECHO_INPUT) == 0
+    {
+      return Err(std::io::Error::last_os_error());
+    }
+    (handle, mode)
+  };
+  let mut line = String::new();
+  let result = std::io::stdin().read_line(&mut line);
+  // SAFETY: restores the mode of the handle read above
+  unsafe {
+    SetConsoleMode(handle, original_mode);
+  }
+  eprintln!();
+  result.map(|_| line)
+}
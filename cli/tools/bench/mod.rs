@@ -1,9 +1,10 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use crate::args::BenchEngineOptions;
 use crate::args::BenchFlags;
+use crate::args::BenchReporterConfig;
 use crate::args::Flags;
 use crate::colors;
-use crate::display::write_json_to_stdout;
 use crate::factory::CliFactory;
 use crate::graph_util::has_graph_root_local_dependent_changed;
 use crate::ops;
@@ -16,6 +17,7 @@ use crate::util::path::matches_pattern_or_exact_path;
 use crate::worker::CliMainWorkerFactory;
 
 use deno_config::glob::WalkEntry;
+use deno_core::anyhow::Context;
 use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::error::JsError;
@@ -48,15 +50,21 @@ use tokio::sync::mpsc::UnboundedSender;
 mod mitata;
 mod reporters;
 
+use reporters::write_csv_samples;
 use reporters::BenchReporter;
 use reporters::ConsoleReporter;
+use reporters::CsvReporter;
 use reporters::JsonReporter;
+use reporters::JunitReporter;
 
 #[derive(Debug, Clone)]
 struct BenchSpecifierOptions {
   filter: TestFilter,
-  json: bool,
+  reporter: BenchReporterConfig,
+  output: Option<String>,
+  export_csv: Option<String>,
   log_level: Option<log::Level>,
+  engine: BenchEngineOptions,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
@@ -119,6 +127,10 @@ pub struct BenchStats {
   pub p999: f64,
   pub high_precision: bool,
   pub used_explicit_timers: bool,
+  /// Raw iteration times in measurement order, only collected when
+  /// `--export-csv` is used.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub samples: Option<Vec<f64>>,
 }
 
 impl BenchReport {
@@ -134,12 +146,15 @@ impl BenchReport {
 
 fn create_reporter(
   show_output: bool,
-  json: bool,
+  reporter: BenchReporterConfig,
+  output: Option<String>,
 ) -> Box<dyn BenchReporter + Send> {
-  if json {
-    return Box::new(JsonReporter::new());
+  match reporter {
+    BenchReporterConfig::Pretty => Box::new(ConsoleReporter::new(show_output)),
+    BenchReporterConfig::Json => Box::new(JsonReporter::new(output)),
+    BenchReporterConfig::Junit => Box::new(JunitReporter::new(output)),
+    BenchReporterConfig::Csv => Box::new(CsvReporter::new(output)),
   }
-  Box::new(ConsoleReporter::new(show_output))
 }
 
 /// Run a single specifier as an executable bench module.
@@ -149,6 +164,8 @@ async fn bench_specifier(
   specifier: ModuleSpecifier,
   sender: UnboundedSender<BenchEvent>,
   filter: TestFilter,
+  collect_samples: bool,
+  engine: BenchEngineOptions,
 ) -> Result<(), AnyError> {
   match bench_specifier_inner(
     worker_factory,
@@ -156,6 +173,8 @@ async fn bench_specifier(
     specifier.clone(),
     &sender,
     filter,
+    collect_samples,
+    engine,
   )
   .await
   {
@@ -181,13 +200,19 @@ async fn bench_specifier_inner(
   specifier: ModuleSpecifier,
   sender: &UnboundedSender<BenchEvent>,
   filter: TestFilter,
+  collect_samples: bool,
+  engine: BenchEngineOptions,
 ) -> Result<(), AnyError> {
   let mut worker = worker_factory
     .create_custom_worker(
       WorkerExecutionMode::Bench,
       specifier.clone(),
       permissions_container,
-      vec![ops::bench::deno_bench::init_ops(sender.clone())],
+      vec![ops::bench::deno_bench::init_ops(
+        sender.clone(),
+        collect_samples,
+        engine,
+      )],
       Default::default(),
     )
     .await?;
@@ -288,6 +313,8 @@ async fn bench_specifiers(
         specifier,
         sender,
         options.filter,
+        options.export_csv.is_some(),
+        options.engine,
       );
       create_and_run_current_thread(future)
     })
@@ -301,8 +328,11 @@ async fn bench_specifiers(
     spawn(async move {
       let mut used_only = false;
       let mut report = BenchReport::new();
-      let mut reporter =
-        create_reporter(log_level != Some(Level::Error), options.json);
+      let mut reporter = create_reporter(
+        log_level != Some(Level::Error),
+        options.reporter,
+        options.output.clone(),
+      );
       let mut benches = IndexMap::new();
 
       while let Some(event) = receiver.recv().await {
@@ -351,7 +381,17 @@ async fn bench_specifiers(
         }
       }
 
-      reporter.report_end(&report);
+      reporter.report_end(&report)?;
+
+      if let Some(export_csv) = &options.export_csv {
+        let mut file =
+          std::fs::File::create(export_csv).with_context(|| {
+            format!("Failed to create CSV export file '{}'", export_csv)
+          })?;
+        write_csv_samples(&mut file, &report.measurements).with_context(
+          || format!("Failed to write CSV export file '{}'", export_csv),
+        )?;
+      }
 
       if used_only {
         return Err(generic_error(
@@ -460,8 +500,11 @@ pub async fn run_benchmarks(
     specifiers,
     BenchSpecifierOptions {
       filter: TestFilter::from_flag(&workspace_bench_options.filter),
-      json: workspace_bench_options.json,
+      reporter: workspace_bench_options.reporter,
+      output: workspace_bench_options.output,
+      export_csv: workspace_bench_options.export_csv,
       log_level,
+      engine: workspace_bench_options.engine,
     },
   )
   .await?;
@@ -586,8 +629,11 @@ pub async fn run_benchmarks_with_watch(
           specifiers,
           BenchSpecifierOptions {
             filter: TestFilter::from_flag(&workspace_bench_options.filter),
-            json: workspace_bench_options.json,
+            reporter: workspace_bench_options.reporter,
+            output: workspace_bench_options.output,
+            export_csv: workspace_bench_options.export_csv,
             log_level,
+            engine: workspace_bench_options.engine,
           },
         )
         .await?;
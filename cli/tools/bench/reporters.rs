@@ -1,5 +1,10 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::borrow::Cow;
+use std::io::BufWriter;
+use std::io::Write;
+
+use deno_core::serde_json;
 use serde::Serialize;
 
 use crate::tools::test::TestFailureFormatOptions;
@@ -10,7 +15,7 @@ use super::*;
 pub trait BenchReporter {
   fn report_group_summary(&mut self);
   fn report_plan(&mut self, plan: &BenchPlan);
-  fn report_end(&mut self, report: &BenchReport);
+  fn report_end(&mut self, report: &BenchReport) -> Result<(), AnyError>;
   fn report_register(&mut self, desc: &BenchDescription);
   fn report_wait(&mut self, desc: &BenchDescription);
   fn report_output(&mut self, output: &str);
@@ -20,6 +25,28 @@ pub trait BenchReporter {
 
 const JSON_SCHEMA_VERSION: u8 = 1;
 
+/// Opens the file the report is written to, or stdout when no file is given.
+fn create_report_writer(
+  output: Option<&str>,
+) -> Result<Box<dyn Write>, AnyError> {
+  match output {
+    Some(path) => {
+      let file = crate::util::fs::create_file(Path::new(path))
+        .with_context(|| format!("Failed to create report file '{}'", path))?;
+      Ok(Box::new(BufWriter::new(file)))
+    }
+    None => Ok(Box::new(BufWriter::new(std::io::stdout()))),
+  }
+}
+
+fn runtime_name() -> String {
+  format!(
+    "{} {}",
+    version::DENO_VERSION_INFO.user_agent,
+    env!("TARGET")
+  )
+}
+
 #[derive(Debug, Serialize)]
 struct JsonReporterOutput {
   version: u8,
@@ -32,11 +59,7 @@ impl Default for JsonReporterOutput {
   fn default() -> Self {
     Self {
       version: JSON_SCHEMA_VERSION,
-      runtime: format!(
-        "{} {}",
-        version::DENO_VERSION_INFO.user_agent,
-        env!("TARGET")
-      ),
+      runtime: runtime_name(),
       cpu: mitata::cpu::name(),
       benches: vec![],
     }
@@ -52,26 +75,32 @@ struct JsonReporterBench {
   results: Vec<BenchResult>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct JsonReporter(JsonReporterOutput);
+#[derive(Debug)]
+pub struct JsonReporter {
+  output: Option<String>,
+  report: JsonReporterOutput,
+}
 
 impl JsonReporter {
-  pub fn new() -> Self {
-    Self(Default::default())
+  pub fn new(output: Option<String>) -> Self {
+    Self {
+      output,
+      report: Default::default(),
+    }
   }
 }
 
-#[allow(clippy::print_stdout)]
 impl BenchReporter for JsonReporter {
   fn report_group_summary(&mut self) {}
   #[cold]
   fn report_plan(&mut self, _plan: &BenchPlan) {}
 
-  fn report_end(&mut self, _report: &BenchReport) {
-    match write_json_to_stdout(self) {
-      Ok(_) => (),
-      Err(e) => println!("{}", e),
-    }
+  fn report_end(&mut self, _report: &BenchReport) -> Result<(), AnyError> {
+    let mut writer = create_report_writer(self.output.as_deref())?;
+    serde_json::to_writer_pretty(&mut writer, &self.report)?;
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
   }
 
   fn report_register(&mut self, _desc: &BenchDescription) {}
@@ -85,7 +114,7 @@ impl BenchReporter for JsonReporter {
       return;
     }
 
-    let maybe_bench = self.0.benches.iter_mut().find(|bench| {
+    let maybe_bench = self.report.benches.iter_mut().find(|bench| {
       bench.origin == desc.origin
         && bench.group == desc.group
         && bench.name == desc.name
@@ -95,7 +124,7 @@ impl BenchReporter for JsonReporter {
     if let Some(bench) = maybe_bench {
       bench.results.push(result.clone());
     } else {
-      self.0.benches.push(JsonReporterBench {
+      self.report.benches.push(JsonReporterBench {
         origin: desc.origin.clone(),
         group: desc.group.clone(),
         name: desc.name.clone(),
@@ -108,6 +137,258 @@ impl BenchReporter for JsonReporter {
   fn report_uncaught_error(&mut self, _origin: &str, _error: Box<JsError>) {}
 }
 
+/// Reports every benchmark module as a JUnit test suite and every benchmark
+/// as a test case, with the measured statistics in nanoseconds as attributes.
+pub struct JunitReporter {
+  output: Option<String>,
+  suites: IndexMap<String, quick_junit::TestSuite>,
+}
+
+impl JunitReporter {
+  pub fn new(output: Option<String>) -> Self {
+    Self {
+      output,
+      suites: IndexMap::new(),
+    }
+  }
+
+  fn suite(&mut self, origin: &str) -> &mut quick_junit::TestSuite {
+    self
+      .suites
+      .entry(origin.to_string())
+      .or_insert_with(|| quick_junit::TestSuite::new(origin))
+  }
+}
+
+impl BenchReporter for JunitReporter {
+  fn report_group_summary(&mut self) {}
+  #[cold]
+  fn report_plan(&mut self, _plan: &BenchPlan) {}
+
+  fn report_end(&mut self, _report: &BenchReport) -> Result<(), AnyError> {
+    let mut report = quick_junit::Report::new("deno bench");
+    report.add_test_suites(std::mem::take(&mut self.suites).into_values());
+    let mut writer = create_report_writer(self.output.as_deref())?;
+    report
+      .serialize(&mut writer)
+      .context("Failed to write JUnit report")?;
+    writer.flush()?;
+    Ok(())
+  }
+
+  fn report_register(&mut self, _desc: &BenchDescription) {}
+
+  fn report_wait(&mut self, _desc: &BenchDescription) {}
+
+  fn report_output(&mut self, _output: &str) {}
+
+  fn report_result(&mut self, desc: &BenchDescription, result: &BenchResult) {
+    if desc.warmup {
+      return;
+    }
+
+    let name = match &desc.group {
+      Some(group) => format!("{} > {}", group, desc.name),
+      None => desc.name.clone(),
+    };
+    let case = match result {
+      BenchResult::Ok(stats) => {
+        let mut case = quick_junit::TestCase::new(
+          name,
+          quick_junit::TestCaseStatus::success(),
+        );
+        case.set_time(Duration::from_nanos(stats.avg as u64));
+        for (key, value) in [
+          ("n", stats.n as f64),
+          ("min", stats.min),
+          ("max", stats.max),
+          ("avg", stats.avg),
+          ("p75", stats.p75),
+          ("p99", stats.p99),
+          ("p995", stats.p995),
+          ("p999", stats.p999),
+        ] {
+          case.extra.insert(key.to_string(), value.to_string());
+        }
+        case
+      }
+      BenchResult::Failed(js_error) => quick_junit::TestCase::new(
+        name,
+        quick_junit::TestCaseStatus::NonSuccess {
+          kind: quick_junit::NonSuccessKind::Failure,
+          message: Some(js_error.exception_message.clone()),
+          ty: None,
+          description: Some(format_test_error(
+            js_error,
+            &TestFailureFormatOptions::default(),
+          )),
+          reruns: vec![],
+        },
+      ),
+    };
+    let mut case = case;
+    case.classname = Some(desc.origin.clone());
+    if desc.baseline {
+      case
+        .extra
+        .insert("baseline".to_string(), "true".to_string());
+    }
+    self.suite(&desc.origin).add_test_case(case);
+  }
+
+  fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>) {
+    let mut case = quick_junit::TestCase::new(
+      "uncaught error",
+      quick_junit::TestCaseStatus::NonSuccess {
+        kind: quick_junit::NonSuccessKind::Error,
+        message: Some(error.exception_message.clone()),
+        ty: None,
+        description: Some(format_test_error(
+          &error,
+          &TestFailureFormatOptions::default(),
+        )),
+        reruns: vec![],
+      },
+    );
+    case.classname = Some(origin.to_string());
+    self.suite(origin).add_test_case(case);
+  }
+}
+
+/// Reports one CSV row with the statistics in nanoseconds of every
+/// benchmark. Failed benchmarks have empty statistics and the error message
+/// in the last column.
+pub struct CsvReporter {
+  output: Option<String>,
+  results: Vec<(BenchDescription, BenchResult)>,
+}
+
+impl CsvReporter {
+  pub fn new(output: Option<String>) -> Self {
+    Self {
+      output,
+      results: Vec::new(),
+    }
+  }
+}
+
+impl BenchReporter for CsvReporter {
+  fn report_group_summary(&mut self) {}
+  #[cold]
+  fn report_plan(&mut self, _plan: &BenchPlan) {}
+
+  fn report_end(&mut self, _report: &BenchReport) -> Result<(), AnyError> {
+    let mut writer = create_report_writer(self.output.as_deref())?;
+    write_csv_summary(&mut writer, &self.results)?;
+    writer.flush()?;
+    Ok(())
+  }
+
+  fn report_register(&mut self, _desc: &BenchDescription) {}
+
+  fn report_wait(&mut self, _desc: &BenchDescription) {}
+
+  fn report_output(&mut self, _output: &str) {}
+
+  fn report_result(&mut self, desc: &BenchDescription, result: &BenchResult) {
+    if desc.warmup {
+      return;
+    }
+    self.results.push((desc.clone(), result.clone()));
+  }
+
+  fn report_uncaught_error(&mut self, _origin: &str, _error: Box<JsError>) {}
+}
+
+fn write_csv_summary(
+  writer: &mut impl Write,
+  results: &[(BenchDescription, BenchResult)],
+) -> std::io::Result<()> {
+  let runtime = runtime_name();
+  let cpu = mitata::cpu::name();
+  writeln!(
+    writer,
+    "origin,group,name,baseline,runtime,cpu,n,min,max,avg,p75,p99,p995,p999,error"
+  )?;
+  for (desc, result) in results {
+    let (stats, error) = match result {
+      BenchResult::Ok(stats) => (
+        format!(
+          "{},{},{},{},{},{},{},{}",
+          stats.n,
+          stats.min,
+          stats.max,
+          stats.avg,
+          stats.p75,
+          stats.p99,
+          stats.p995,
+          stats.p999
+        ),
+        "",
+      ),
+      BenchResult::Failed(js_error) => {
+        (",,,,,,,".to_string(), js_error.exception_message.as_str())
+      }
+    };
+    writeln!(
+      writer,
+      "{},{},{},{},{},{},{},{}",
+      csv_escape(&desc.origin),
+      csv_escape(desc.group.as_deref().unwrap_or("")),
+      csv_escape(&desc.name),
+      desc.baseline,
+      csv_escape(&runtime),
+      csv_escape(&cpu),
+      stats,
+      csv_escape(error)
+    )?;
+  }
+  Ok(())
+}
+
+/// Writes the raw iteration samples of every measured benchmark as CSV, one
+/// row per sample, so they can be analyzed with external tools.
+pub fn write_csv_samples(
+  writer: &mut impl std::io::Write,
+  measurements: &[(BenchDescription, BenchStats)],
+) -> std::io::Result<()> {
+  let runtime = runtime_name();
+  let cpu = mitata::cpu::name();
+  writeln!(
+    writer,
+    "origin,group,name,baseline,high_precision,runtime,cpu,sample,time_ns"
+  )?;
+  for (desc, stats) in measurements {
+    let Some(samples) = &stats.samples else {
+      continue;
+    };
+    for (i, sample) in samples.iter().enumerate() {
+      writeln!(
+        writer,
+        "{},{},{},{},{},{},{},{},{}",
+        csv_escape(&desc.origin),
+        csv_escape(desc.group.as_deref().unwrap_or("")),
+        csv_escape(&desc.name),
+        desc.baseline,
+        stats.high_precision,
+        csv_escape(&runtime),
+        csv_escape(&cpu),
+        i,
+        sample
+      )?;
+    }
+  }
+  Ok(())
+}
+
+fn csv_escape(value: &str) -> Cow<str> {
+  if value.contains([',', '"', '\n', '\r']) {
+    Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+  } else {
+    Cow::Borrowed(value)
+  }
+}
+
 pub struct ConsoleReporter {
   name: String,
   show_output: bool,
@@ -301,8 +582,9 @@ impl BenchReporter for ConsoleReporter {
     self.group_measurements.clear();
   }
 
-  fn report_end(&mut self, _: &BenchReport) {
+  fn report_end(&mut self, _: &BenchReport) -> Result<(), AnyError> {
     self.report_group_summary();
+    Ok(())
   }
 
   fn report_uncaught_error(&mut self, _origin: &str, error: Box<JsError>) {
@@ -316,3 +598,54 @@ impl BenchReporter for ConsoleReporter {
     println!();
   }
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_csv_escape() {
+    assert_eq!(csv_escape("foo"), "foo");
+    assert_eq!(csv_escape("foo, bar"), "\"foo, bar\"");
+    assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+    assert_eq!(csv_escape("a\nb"), "\"a\nb\"");
+  }
+
+  #[test]
+  fn test_write_csv_summary() {
+    let desc = BenchDescription {
+      id: 0,
+      name: "parse, fast".to_string(),
+      origin: "file:///bench.ts".to_string(),
+      baseline: true,
+      group: None,
+      ignore: false,
+      only: false,
+      warmup: false,
+    };
+    let stats = BenchStats {
+      n: 10,
+      min: 1.0,
+      max: 3.5,
+      avg: 2.0,
+      p75: 2.5,
+      p99: 3.0,
+      p995: 3.25,
+      p999: 3.5,
+      high_precision: true,
+      used_explicit_timers: false,
+      samples: None,
+    };
+    let mut output = Vec::new();
+    write_csv_summary(&mut output, &[(desc, BenchResult::Ok(stats))]).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(
+      lines[0],
+      "origin,group,name,baseline,runtime,cpu,n,min,max,avg,p75,p99,p995,p999,error"
+    );
+    assert!(lines[1].starts_with("file:///bench.ts,,\"parse, fast\",true,"));
+    assert!(lines[1].ends_with(",10,1,3.5,2,2.5,3,3.25,3.5,"));
+  }
+}
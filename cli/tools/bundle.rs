@@ -0,0 +1,912 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use deno_ast::swc::ast;
+use deno_ast::swc::bundler;
+use deno_ast::swc::codegen;
+use deno_ast::swc::codegen::text_writer::JsWriter;
+use deno_ast::swc::common::FileName;
+use deno_ast::swc::common::Globals;
+use deno_ast::swc::common::SourceMap as SwcSourceMap;
+use deno_ast::swc::common::Span;
+use deno_ast::swc::common::GLOBALS;
+use deno_ast::swc::loader::resolve::Resolution;
+use deno_ast::swc::parser::lexer::Lexer;
+use deno_ast::swc::parser::Parser;
+use deno_ast::swc::parser::StringInput;
+use deno_ast::swc::visit::VisitMut;
+use deno_ast::swc::visit::VisitMutWith;
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::SourceMapOption;
+use deno_ast::TranspileResult;
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::resolve_url_or_path;
+use deno_core::sourcemap;
+use deno_graph::DependencyDescriptor;
+use deno_graph::DynamicArgument;
+use deno_graph::Module;
+use deno_graph::ModuleGraph;
+use deno_graph::ParserModuleAnalyzer;
+use deno_semver::npm::NpmPackageReqReference;
+use node_resolver::NodeResolution;
+use node_resolver::NodeResolutionMode;
+
+use crate::args::BundleFlags;
+use crate::args::BundleFormat;
+use crate::args::BundlePlatform;
+use crate::args::BundleSourceMap;
+use crate::args::Flags;
+use crate::cache::ParsedSourceCache;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::resolver::CliNodeResolver;
+use crate::util::checksum;
+use crate::util::display;
+
+pub async fn bundle(
+  flags: Arc<Flags>,
+  bundle_flags: BundleFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags);
+  let cli_options = factory.cli_options()?;
+  let module_graph_creator = factory.module_graph_creator().await?;
+  let parsed_source_cache = factory.parsed_source_cache();
+
+  let entrypoints = bundle_flags
+    .entrypoints
+    .iter()
+    .map(|entrypoint| {
+      resolve_url_or_path(entrypoint, cli_options.initial_cwd())
+    })
+    .collect::<Result<Vec<_>, _>>()?;
+  if bundle_flags.output.is_some() && entrypoints.len() > 1 {
+    bail!("--output can only be used with a single entrypoint. Use --outdir instead.");
+  }
+  let graph = module_graph_creator
+    .create_graph_and_maybe_check(entrypoints.clone())
+    .await?;
+
+  let npm_modules = match bundle_flags.platform {
+    BundlePlatform::Deno => NpmModules::default(),
+    BundlePlatform::Browser => NpmModules::collect(
+      &graph,
+      factory.cli_node_resolver().await?,
+      parsed_source_cache,
+      &bundle_flags.external,
+    )?,
+  };
+  let plan =
+    BundlePlan::new(&graph, &npm_modules, &entrypoints, &bundle_flags.external);
+  let out_dir = match (&bundle_flags.output, &bundle_flags.outdir) {
+    (Some(output), _) => {
+      let output = cli_options.initial_cwd().join(output);
+      output.parent().map(|p| p.to_path_buf())
+    }
+    (None, Some(outdir)) => Some(cli_options.initial_cwd().join(outdir)),
+    (None, None) => {
+      if entrypoints.len() > 1 {
+        bail!("Bundling multiple entrypoints requires --outdir.");
+      }
+      if plan.has_chunks() {
+        bail!("The dynamically imported modules are emitted to separate chunks, which requires --output or --outdir.");
+      }
+      if bundle_flags.source_map == Some(BundleSourceMap::External) {
+        bail!("An external source map requires --output or --outdir.");
+      }
+      None
+    }
+  };
+
+  let ts_config_for_emit = cli_options
+    .resolve_ts_config_for_emit(deno_config::deno_json::TsConfigType::Emit)?;
+  let (transpile_options, mut emit_options) =
+    crate::args::ts_config_to_transpile_and_emit_options(
+      ts_config_for_emit.ts_config,
+    )?;
+  emit_options.source_map = if bundle_flags.source_map.is_some() {
+    SourceMapOption::Separate
+  } else {
+    SourceMapOption::None
+  };
+  let outputs = bundle_graph(
+    &graph,
+    &npm_modules,
+    parsed_source_cache,
+    &plan,
+    &BundleOptions {
+      transpile_options,
+      emit_options,
+      format: bundle_flags.format,
+      minify: bundle_flags.minify,
+      source_map: bundle_flags.source_map.is_some(),
+    },
+  )?;
+
+  let Some(out_dir) = out_dir else {
+    for output in outputs {
+      print_bundle(&with_source_map(output.code, output.source_map, None));
+    }
+    return Ok(());
+  };
+  std::fs::create_dir_all(&out_dir)
+    .with_context(|| format!("Failed creating {}", out_dir.display()))?;
+  for output in outputs {
+    let file_name = match &bundle_flags.output {
+      Some(output_path) if !plan.is_chunk(&output.specifier) => {
+        Path::new(output_path)
+          .file_name()
+          .map(|name| name.to_string_lossy().into_owned())
+          .unwrap_or(output.file_name)
+      }
+      _ => output.file_name,
+    };
+    let path = out_dir.join(&file_name);
+    let code = match (bundle_flags.source_map, output.source_map) {
+      (Some(BundleSourceMap::External), Some(source_map)) => {
+        let map_file_name = format!("{}.map", file_name);
+        write_file(&out_dir.join(&map_file_name), source_map.as_bytes())?;
+        with_source_map(output.code, None, Some(&map_file_name))
+      }
+      (_, source_map) => with_source_map(output.code, source_map, None),
+    };
+    write_file(&path, code.as_bytes())?;
+    log::info!(
+      "{} {} {}",
+      colors::green("Bundle"),
+      path.display(),
+      colors::gray(format!("({})", display::human_size(code.len() as f64)))
+    );
+  }
+  Ok(())
+}
+
+#[allow(clippy::print_stdout)]
+fn print_bundle(code: &str) {
+  print!("{}", code);
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), AnyError> {
+  std::fs::write(path, bytes)
+    .with_context(|| format!("Failed writing {}", path.display()))
+}
+
+/// Appends the source map, or a reference to the file of the source map, to
+/// the code.
+fn with_source_map(
+  mut code: String,
+  inline_source_map: Option<String>,
+  map_file_name: Option<&str>,
+) -> String {
+  if !code.ends_with('\n') {
+    code.push('\n');
+  }
+  if let Some(source_map) = inline_source_map {
+    code.push_str("//# sourceMappingURL=data:application/json;base64,");
+    code.push_str(&BASE64_STANDARD.encode(source_map));
+    code.push('\n');
+  } else if let Some(map_file_name) = map_file_name {
+    code.push_str(&format!("//# sourceMappingURL={}\n", map_file_name));
+  }
+  code
+}
+
+/// Decides which modules are emitted as separate files and which imports are
+/// kept as is.
+struct BundlePlan {
+  /// The entrypoints followed by the dynamically imported modules.
+  entries: Vec<(ModuleSpecifier, String)>,
+  chunks: HashSet<ModuleSpecifier>,
+  /// The specifiers, as written in the source, of the imports to not bundle.
+  externals: HashSet<String>,
+}
+
+impl BundlePlan {
+  fn new(
+    graph: &ModuleGraph,
+    npm_modules: &NpmModules,
+    entrypoints: &[ModuleSpecifier],
+    external_patterns: &[String],
+  ) -> Self {
+    let mut entries = Vec::new();
+    let mut file_names = HashSet::new();
+    for entrypoint in entrypoints {
+      let entrypoint = graph.resolve(entrypoint).clone();
+      let stem = file_stem(&entrypoint);
+      let mut file_name = format!("{}.js", stem);
+      let mut i = 2;
+      while !file_names.insert(file_name.clone()) {
+        file_name = format!("{}-{}.js", stem, i);
+        i += 1;
+      }
+      entries.push((entrypoint, file_name));
+    }
+
+    let mut chunks = HashSet::new();
+    let mut externals = npm_modules.externals.clone();
+    let mut dynamic_imports = Vec::new();
+    for module in graph.modules() {
+      let Module::Js(module) = module else {
+        continue;
+      };
+      for (text, dependency) in &module.dependencies {
+        let resolved = dependency.get_code().map(|s| graph.resolve(s));
+        let npm_module = resolved.and_then(|s| npm_modules.entries.get(s));
+        let is_external = is_external_match(text, external_patterns)
+          || resolved.is_some_and(|specifier| {
+            is_external_match(specifier.as_str(), external_patterns)
+          })
+          || (npm_module.is_none()
+            && !matches!(
+              resolved.and_then(|s| graph.get(s)),
+              Some(Module::Js(_)) | Some(Module::Json(_))
+            ));
+        if is_external {
+          externals.insert(text.clone());
+        } else if dependency.is_dynamic {
+          dynamic_imports.push(npm_module.or(resolved).unwrap());
+        }
+      }
+    }
+    dynamic_imports.extend(
+      npm_modules
+        .modules
+        .values()
+        .flat_map(|module| module.dependencies.values())
+        .filter(|dependency| dependency.is_dynamic)
+        .map(|dependency| &dependency.specifier),
+    );
+    for resolved in dynamic_imports {
+      if !entries.iter().any(|(entry, _)| entry == resolved)
+        && chunks.insert(resolved.clone())
+      {
+        let file_name = format!(
+          "{}-{}.js",
+          file_stem(resolved),
+          &checksum::gen(&[resolved.as_str()])[..8]
+        );
+        file_names.insert(file_name.clone());
+        entries.push((resolved.clone(), file_name));
+      }
+    }
+    // the rewritten dynamic imports of the chunks
+    for (specifier, file_name) in &entries {
+      if chunks.contains(specifier) {
+        externals.insert(format!("./{}", file_name));
+      }
+    }
+
+    Self {
+      entries,
+      chunks,
+      externals,
+    }
+  }
+
+  fn has_chunks(&self) -> bool {
+    !self.chunks.is_empty()
+  }
+
+  fn is_chunk(&self, specifier: &ModuleSpecifier) -> bool {
+    self.chunks.contains(specifier)
+  }
+
+  fn chunk_file_name(&self, specifier: &ModuleSpecifier) -> Option<&str> {
+    if !self.is_chunk(specifier) {
+      return None;
+    }
+    self
+      .entries
+      .iter()
+      .find(|(entry, _)| entry == specifier)
+      .map(|(_, file_name)| file_name.as_str())
+  }
+}
+
+/// Matches a specifier against the `--external` values, which may end with
+/// a `*` to match any specifier with the prefix.
+fn is_external_match(specifier: &str, patterns: &[String]) -> bool {
+  patterns
+    .iter()
+    .any(|pattern| match pattern.strip_suffix('*') {
+      Some(prefix) => specifier.starts_with(prefix),
+      None => specifier == pattern,
+    })
+}
+
+/// The ES modules of the npm packages which are bundled for browsers. The
+/// module graph only contains the packages, so their modules are resolved
+/// and loaded separately.
+#[derive(Default)]
+struct NpmModules {
+  /// The modules which the npm specifiers of the graph resolve to.
+  entries: HashMap<ModuleSpecifier, ModuleSpecifier>,
+  modules: HashMap<ModuleSpecifier, NpmModule>,
+  /// The specifiers, as written in the modules, of the imports to not bundle.
+  externals: HashSet<String>,
+}
+
+struct NpmModule {
+  source: Arc<str>,
+  media_type: MediaType,
+  dependencies: HashMap<String, NpmDependency>,
+}
+
+struct NpmDependency {
+  specifier: ModuleSpecifier,
+  is_dynamic: bool,
+}
+
+impl NpmModules {
+  /// Resolves the npm specifiers of the graph and loads the modules of the
+  /// packages with their dependencies. Errors for the imports which can't be
+  /// bundled for browsers, unless they are external.
+  fn collect(
+    graph: &ModuleGraph,
+    node_resolver: &CliNodeResolver,
+    parsed_source_cache: &ParsedSourceCache,
+    external_patterns: &[String],
+  ) -> Result<Self, AnyError> {
+    let mut npm_modules = Self::default();
+    let mut pending = Vec::new();
+    for module in graph.modules() {
+      let Module::Js(module) = module else {
+        continue;
+      };
+      for (text, dependency) in &module.dependencies {
+        let Some(specifier) = dependency.get_code() else {
+          continue;
+        };
+        let resolved = graph.resolve(specifier);
+        if is_external_match(text, external_patterns)
+          || is_external_match(resolved.as_str(), external_patterns)
+        {
+          continue;
+        }
+        match graph.get(resolved) {
+          Some(Module::Npm(_)) => {
+            let req_ref = NpmPackageReqReference::from_specifier(specifier)?;
+            let resolution = node_resolver.resolve_req_reference(
+              &req_ref,
+              &module.specifier,
+              NodeResolutionMode::Execution,
+            )?;
+            let npm_module = esm_resolution(text, resolution)?;
+            npm_modules
+              .entries
+              .insert(resolved.clone(), npm_module.clone());
+            pending.push(npm_module);
+          }
+          Some(Module::Node(module)) => {
+            esm_resolution(
+              text,
+              NodeResolution::BuiltIn(module.module_name.clone()),
+            )?;
+          }
+          _ => {}
+        }
+      }
+    }
+
+    while let Some(specifier) = pending.pop() {
+      if npm_modules.modules.contains_key(&specifier) {
+        continue;
+      }
+      let path = specifier
+        .to_file_path()
+        .map_err(|_| anyhow!("Invalid npm module path: {}", specifier))?;
+      let source: Arc<str> = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed reading {}", path.display()))?
+        .into();
+      let media_type = MediaType::from_specifier(&specifier);
+      let parsed_source = parsed_source_cache.remove_or_parse_module(
+        &specifier,
+        source.clone(),
+        media_type,
+      )?;
+      let mut dependencies = HashMap::new();
+      for dependency in
+        ParserModuleAnalyzer::module_info(&parsed_source).dependencies
+      {
+        let (text, is_dynamic) = match dependency {
+          DependencyDescriptor::Static(dependency) => {
+            (dependency.specifier, false)
+          }
+          DependencyDescriptor::Dynamic(dependency) => {
+            match dependency.argument {
+              DynamicArgument::String(text) => (text, true),
+              // left as is, like the bundler does
+              _ => continue,
+            }
+          }
+        };
+        if is_external_match(&text, external_patterns) {
+          npm_modules.externals.insert(text);
+          continue;
+        }
+        let resolution = node_resolver.resolve(
+          &text,
+          &specifier,
+          NodeResolutionMode::Execution,
+        )?;
+        let dependency_specifier = esm_resolution(&text, resolution)?;
+        pending.push(dependency_specifier.clone());
+        dependencies.insert(
+          text,
+          NpmDependency {
+            specifier: dependency_specifier,
+            is_dynamic,
+          },
+        );
+      }
+      npm_modules.modules.insert(
+        specifier,
+        NpmModule {
+          source,
+          media_type,
+          dependencies,
+        },
+      );
+    }
+    Ok(npm_modules)
+  }
+}
+
+/// Gets the ES module of the resolution. CommonJS modules and the built-in
+/// modules of Node.js can't be bundled for browsers.
+fn esm_resolution(
+  text: &str,
+  resolution: NodeResolution,
+) -> Result<ModuleSpecifier, AnyError> {
+  match resolution {
+    NodeResolution::Esm(specifier) => Ok(specifier),
+    NodeResolution::CommonJs(specifier) => bail!(
+      "\"{}\" resolves to the CommonJS module \"{}\", which can't be bundled for browsers. Use --external to leave it as an import.",
+      text,
+      specifier
+    ),
+    NodeResolution::BuiltIn(name) => bail!(
+      "\"{}\" is the Node.js built-in module \"{}\", which isn't available in browsers. Use --external to leave it as an import.",
+      text,
+      name
+    ),
+  }
+}
+
+fn file_stem(specifier: &ModuleSpecifier) -> String {
+  let name = specifier
+    .path()
+    .rsplit('/')
+    .find(|segment| !segment.is_empty())
+    .unwrap_or("bundle");
+  let stem = match name.rsplit_once('.') {
+    Some((stem, _)) if !stem.is_empty() => stem,
+    _ => name,
+  };
+  stem.to_string()
+}
+
+struct BundleOptions {
+  transpile_options: deno_ast::TranspileOptions,
+  emit_options: deno_ast::EmitOptions,
+  format: BundleFormat,
+  minify: bool,
+  source_map: bool,
+}
+
+struct BundleOutput {
+  specifier: ModuleSpecifier,
+  file_name: String,
+  code: String,
+  source_map: Option<String>,
+}
+
+fn bundle_graph(
+  graph: &ModuleGraph,
+  npm_modules: &NpmModules,
+  parsed_source_cache: &ParsedSourceCache,
+  plan: &BundlePlan,
+  options: &BundleOptions,
+) -> Result<Vec<BundleOutput>, AnyError> {
+  let globals = Globals::new();
+  GLOBALS.set(&globals, || {
+    let cm = Rc::new(SwcSourceMap::default());
+    let module_source_maps = Rc::new(RefCell::new(HashMap::new()));
+    let loader = BundleLoader {
+      graph,
+      npm_modules,
+      parsed_source_cache,
+      plan,
+      options,
+      cm: cm.clone(),
+      module_source_maps: module_source_maps.clone(),
+    };
+    let config = bundler::Config {
+      require: false,
+      disable_inliner: false,
+      disable_hygiene: false,
+      disable_fixer: false,
+      disable_dce: false,
+      external_modules: plan
+        .externals
+        .iter()
+        .map(|specifier| specifier.as_str().into())
+        .collect(),
+      module: match options.format {
+        BundleFormat::Esm => bundler::ModuleType::Es,
+        BundleFormat::Iife => bundler::ModuleType::Iife,
+      },
+    };
+    let mut bundler = bundler::Bundler::new(
+      &globals,
+      cm.clone(),
+      loader,
+      BundleResolver { graph, npm_modules },
+      config,
+      Box::new(BundleHook),
+    );
+    let entries = plan
+      .entries
+      .iter()
+      .map(|(specifier, file_name)| {
+        (file_name.clone(), FileName::Url(specifier.clone()))
+      })
+      .collect::<HashMap<_, _>>();
+    let bundles = bundler
+      .bundle(entries)
+      .context("Unable to output during bundling.")?;
+
+    let mut outputs = Vec::with_capacity(bundles.len());
+    for bundle in bundles {
+      let bundler::BundleKind::Named { name } = &bundle.kind else {
+        bail!("An unexpected bundle was produced.");
+      };
+      let Some((specifier, file_name)) =
+        plan.entries.iter().find(|(_, file_name)| file_name == name)
+      else {
+        bail!("An unexpected bundle was produced.");
+      };
+
+      let mut buf = Vec::new();
+      let mut src_map_buf = Vec::new();
+      {
+        let writer = Box::new(JsWriter::new(
+          cm.clone(),
+          "\n",
+          &mut buf,
+          options.source_map.then_some(&mut src_map_buf),
+        ));
+        let mut emitter = codegen::Emitter {
+          cfg: codegen::Config::default().with_minify(options.minify),
+          cm: cm.clone(),
+          comments: None,
+          wr: writer,
+        };
+        emitter
+          .emit_module(&bundle.module)
+          .context("Unable to emit the bundle.")?;
+      }
+      let source_map = if options.source_map {
+        let mut bundle_map_buf = Vec::new();
+        cm.build_source_map(&src_map_buf)
+          .to_writer(&mut bundle_map_buf)?;
+        let bundle_map = sourcemap::SourceMap::from_slice(&bundle_map_buf)?;
+        Some(compose_source_maps(
+          &bundle_map,
+          &module_source_maps.borrow(),
+        )?)
+      } else {
+        None
+      };
+      outputs.push(BundleOutput {
+        specifier: specifier.clone(),
+        file_name: file_name.clone(),
+        code: String::from_utf8(buf)?,
+        source_map,
+      });
+    }
+    // emit the entrypoints first
+    outputs.sort_by_key(|output| plan.is_chunk(&output.specifier));
+    Ok(outputs)
+  })
+}
+
+/// Maps the positions in the bundle, which point to the transpiled
+/// JavaScript of the modules, back to the original sources.
+fn compose_source_maps(
+  bundle_map: &sourcemap::SourceMap,
+  module_source_maps: &HashMap<String, sourcemap::SourceMap>,
+) -> Result<String, AnyError> {
+  let mut builder = sourcemap::SourceMapBuilder::new(None);
+  for token in bundle_map.tokens() {
+    let Some(source) = token.get_source() else {
+      continue;
+    };
+    let original = module_source_maps.get(source).and_then(|map| {
+      map.lookup_token(token.get_src_line(), token.get_src_col())
+    });
+    match original {
+      Some(original) => {
+        let Some(original_source) = original.get_source() else {
+          continue;
+        };
+        builder.add(
+          token.get_dst_line(),
+          token.get_dst_col(),
+          original.get_src_line(),
+          original.get_src_col(),
+          Some(original_source),
+          original.get_name().or(token.get_name()),
+        );
+      }
+      None => {
+        builder.add(
+          token.get_dst_line(),
+          token.get_dst_col(),
+          token.get_src_line(),
+          token.get_src_col(),
+          Some(source),
+          token.get_name(),
+        );
+      }
+    }
+  }
+  let mut buf = Vec::new();
+  builder.into_sourcemap().to_writer(&mut buf)?;
+  Ok(String::from_utf8(buf)?)
+}
+
+struct BundleLoader<'a> {
+  graph: &'a ModuleGraph,
+  npm_modules: &'a NpmModules,
+  parsed_source_cache: &'a ParsedSourceCache,
+  plan: &'a BundlePlan,
+  options: &'a BundleOptions,
+  cm: Rc<SwcSourceMap>,
+  module_source_maps: Rc<RefCell<HashMap<String, sourcemap::SourceMap>>>,
+}
+
+impl bundler::Load for BundleLoader<'_> {
+  fn load(
+    &self,
+    file_name: &FileName,
+  ) -> Result<bundler::ModuleData, AnyError> {
+    let FileName::Url(specifier) = file_name else {
+      unreachable!("An unexpected file name was passed when bundling.");
+    };
+    let (source, media_type) = match self.graph.get(specifier) {
+      Some(Module::Js(module)) => (module.source.clone(), module.media_type),
+      Some(Module::Json(module)) => (
+        format!("export default {};", module.source).into(),
+        MediaType::JavaScript,
+      ),
+      _ => match self.npm_modules.modules.get(specifier) {
+        Some(module) => (module.source.clone(), module.media_type),
+        None => bail!("Module \"{}\" can not be bundled.", specifier),
+      },
+    };
+    let parsed_source = self
+      .parsed_source_cache
+      .remove_or_parse_module(specifier, source, media_type)?;
+    let transpiled = match parsed_source
+      .transpile(&self.options.transpile_options, &self.options.emit_options)?
+    {
+      TranspileResult::Owned(source) | TranspileResult::Cloned(source) => {
+        source
+      }
+    };
+    if let Some(source_map) = &transpiled.source_map {
+      self.module_source_maps.borrow_mut().insert(
+        specifier.to_string(),
+        sourcemap::SourceMap::from_slice(source_map)?,
+      );
+    }
+
+    let source_file = self.cm.new_source_file(
+      file_name.clone().into(),
+      String::from_utf8(transpiled.source)?,
+    );
+    let lexer = Lexer::new(
+      deno_ast::get_syntax(MediaType::JavaScript),
+      deno_ast::ES_VERSION,
+      StringInput::from(&*source_file),
+      None,
+    );
+    let mut module = Parser::new_from(lexer).parse_module().map_err(|err| {
+      anyhow!(
+        "Unable to parse the emitted code of \"{}\": {:?}",
+        specifier,
+        err.kind()
+      )
+    })?;
+    module.visit_mut_with(&mut DynamicImportRewriter {
+      graph: self.graph,
+      npm_modules: self.npm_modules,
+      plan: self.plan,
+      referrer: specifier,
+    });
+    Ok(bundler::ModuleData {
+      fm: source_file,
+      module,
+      helpers: Default::default(),
+    })
+  }
+}
+
+/// Points the dynamic imports of the modules which are emitted to separate
+/// chunks at the file of the chunk.
+struct DynamicImportRewriter<'a> {
+  graph: &'a ModuleGraph,
+  npm_modules: &'a NpmModules,
+  plan: &'a BundlePlan,
+  referrer: &'a ModuleSpecifier,
+}
+
+impl VisitMut for DynamicImportRewriter<'_> {
+  fn visit_mut_call_expr(&mut self, call: &mut ast::CallExpr) {
+    call.visit_mut_children_with(self);
+    if !matches!(call.callee, ast::Callee::Import(_)) {
+      return;
+    }
+    let Some(ast::Expr::Lit(ast::Lit::Str(str))) =
+      call.args.first_mut().map(|arg| &mut *arg.expr)
+    else {
+      return;
+    };
+    let Some(file_name) = resolve_dependency(
+      self.graph,
+      self.npm_modules,
+      &str.value,
+      self.referrer,
+    )
+    .and_then(|specifier| self.plan.chunk_file_name(specifier)) else {
+      return;
+    };
+    *str = ast::Str {
+      span: str.span,
+      value: format!("./{}", file_name).into(),
+      raw: None,
+    };
+  }
+}
+
+/// Resolves an import of a bundled module, where the npm specifiers resolve
+/// to the modules of the packages when they are bundled.
+fn resolve_dependency<'a>(
+  graph: &'a ModuleGraph,
+  npm_modules: &'a NpmModules,
+  specifier: &str,
+  referrer: &ModuleSpecifier,
+) -> Option<&'a ModuleSpecifier> {
+  if let Some(npm_module) = npm_modules.modules.get(referrer) {
+    return npm_module
+      .dependencies
+      .get(specifier)
+      .map(|dependency| &dependency.specifier);
+  }
+  let dependency = graph.get(referrer)?.js()?.dependencies.get(specifier)?;
+  let resolved = graph.resolve(dependency.get_code()?);
+  Some(npm_modules.entries.get(resolved).unwrap_or(resolved))
+}
+
+struct BundleResolver<'a> {
+  graph: &'a ModuleGraph,
+  npm_modules: &'a NpmModules,
+}
+
+impl bundler::Resolve for BundleResolver<'_> {
+  fn resolve(
+    &self,
+    referrer: &FileName,
+    specifier: &str,
+  ) -> Result<Resolution, AnyError> {
+    let FileName::Url(referrer) = referrer else {
+      unreachable!("An unexpected referrer was passed when bundling.");
+    };
+    match resolve_dependency(self.graph, self.npm_modules, specifier, referrer)
+    {
+      Some(specifier) => Ok(Resolution {
+        filename: FileName::Url(specifier.clone()),
+        slug: None,
+      }),
+      None => Err(anyhow!(
+        "Cannot resolve \"{}\" from \"{}\".",
+        specifier,
+        referrer
+      )),
+    }
+  }
+}
+
+/// Provides `import.meta` for the bundled modules, which is the one of the
+/// bundle for the entrypoints.
+struct BundleHook;
+
+impl bundler::Hook for BundleHook {
+  fn get_import_meta_props(
+    &self,
+    span: Span,
+    module_record: &bundler::ModuleRecord,
+  ) -> Result<Vec<ast::KeyValueProp>, AnyError> {
+    let import_meta_prop = |name: &str| {
+      Box::new(ast::Expr::Member(ast::MemberExpr {
+        span,
+        obj: Box::new(ast::Expr::MetaProp(ast::MetaPropExpr {
+          span,
+          kind: ast::MetaPropKind::ImportMeta,
+        })),
+        prop: ast::MemberProp::Ident(ast::IdentName {
+          span,
+          sym: name.into(),
+        }),
+      }))
+    };
+    let url = if module_record.is_entry {
+      import_meta_prop("url")
+    } else {
+      Box::new(ast::Expr::Lit(ast::Lit::Str(ast::Str {
+        span,
+        value: module_record.file_name.to_string().into(),
+        raw: None,
+      })))
+    };
+    let main = if module_record.is_entry {
+      import_meta_prop("main")
+    } else {
+      Box::new(ast::Expr::Lit(ast::Lit::Bool(ast::Bool {
+        span,
+        value: false,
+      })))
+    };
+    Ok(vec![
+      ast::KeyValueProp {
+        key: ast::PropName::Ident(ast::IdentName {
+          span,
+          sym: "url".into(),
+        }),
+        value: url,
+      },
+      ast::KeyValueProp {
+        key: ast::PropName::Ident(ast::IdentName {
+          span,
+          sym: "main".into(),
+        }),
+        value: main,
+      },
+    ])
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_is_external_match() {
+    let patterns = vec!["npm:*".to_string(), "@std/path".to_string()];
+    assert!(is_external_match("npm:chalk@5", &patterns));
+    assert!(is_external_match("@std/path", &patterns));
+    assert!(!is_external_match("@std/path/join", &patterns));
+    assert!(!is_external_match("jsr:@std/fmt", &patterns));
+  }
+
+  #[test]
+  fn test_file_stem() {
+    let stem = |s: &str| file_stem(&ModuleSpecifier::parse(s).unwrap());
+    assert_eq!(stem("file:///project/main.ts"), "main");
+    assert_eq!(stem("file:///project/worker.d.ts"), "worker.d");
+    assert_eq!(stem("https://deno.land/x/mod/"), "mod");
+    assert_eq!(stem("https://example.com/.hidden"), ".hidden");
+  }
+}
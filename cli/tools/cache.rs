@@ -0,0 +1,246 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use deno_core::error::AnyError;
+use deno_core::futures::FutureExt;
+use deno_core::ModuleSpecifier;
+use deno_graph::source::CacheSetting as LoaderCacheSetting;
+use deno_graph::source::LoadFuture;
+use deno_graph::source::LoadOptions;
+use deno_graph::source::LoadResponse;
+use deno_graph::source::Loader;
+use deno_semver::jsr::JsrDepPackageReq;
+use serde::Serialize;
+
+use crate::args::CacheFlags;
+use crate::args::Flags;
+use crate::cache::FetchCacher;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::graph_util::collect_graph_package_reqs;
+use crate::util::display;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteModuleReport {
+  specifier: ModuleSpecifier,
+  cached: bool,
+  /// The size of the module, which is only known when it's cached.
+  size: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PackageReport {
+  req: String,
+  /// The version of the package, which is only known when it's locked.
+  version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DryRunReport {
+  roots: Vec<ModuleSpecifier>,
+  remote: Vec<RemoteModuleReport>,
+  npm: Vec<PackageReport>,
+  jsr: Vec<PackageReport>,
+}
+
+/// Loads the remote modules only from the cache and records them, so the
+/// graph is built without accessing the network.
+struct DryRunLoader {
+  inner: FetchCacher,
+  remote: Rc<RefCell<BTreeMap<ModuleSpecifier, Option<usize>>>>,
+  missing: Rc<RefCell<BTreeSet<ModuleSpecifier>>>,
+}
+
+impl Loader for DryRunLoader {
+  fn load(
+    &self,
+    specifier: &ModuleSpecifier,
+    mut options: LoadOptions,
+  ) -> LoadFuture {
+    if !matches!(specifier.scheme(), "http" | "https") {
+      return self.inner.load(specifier, options);
+    }
+    options.cache_setting = LoaderCacheSetting::Only;
+    let future = self.inner.load(specifier, options);
+    let specifier = specifier.clone();
+    let remote = self.remote.clone();
+    let missing = self.missing.clone();
+    async move {
+      let response = future.await?;
+      match &response {
+        Some(LoadResponse::Module { content, .. }) => {
+          remote.borrow_mut().insert(specifier, Some(content.len()));
+        }
+        Some(_) => {
+          remote.borrow_mut().insert(specifier, None);
+        }
+        None => {
+          missing.borrow_mut().insert(specifier);
+        }
+      }
+      Ok(response)
+    }
+    .boxed_local()
+  }
+}
+
+/// Reports the remote modules and the packages of the module graph which
+/// would be downloaded by `deno cache`, without accessing the network.
+pub async fn cache_dry_run(
+  flags: Arc<Flags>,
+  cache_flags: CacheFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags);
+  let cli_options = factory.cli_options()?;
+  let roots = factory
+    .main_module_graph_container()
+    .await?
+    .collect_specifiers(&cache_flags.files)?;
+  let module_graph_builder = factory.module_graph_builder().await?;
+  let mut loader = DryRunLoader {
+    inner: module_graph_builder.create_graph_loader(),
+    remote: Default::default(),
+    missing: Default::default(),
+  };
+  let graph = module_graph_builder
+    .build_graph_without_installing(roots.clone(), &mut loader)
+    .await?;
+
+  let mut remote = loader
+    .remote
+    .take()
+    .into_iter()
+    .map(|(specifier, size)| RemoteModuleReport {
+      specifier,
+      cached: true,
+      size,
+    })
+    .chain(loader.missing.take().into_iter().map(|specifier| {
+      RemoteModuleReport {
+        specifier,
+        cached: false,
+        size: None,
+      }
+    }))
+    .collect::<Vec<_>>();
+  remote.sort_by(|a, b| a.specifier.cmp(&b.specifier));
+
+  let (npm_reqs, jsr_reqs) = collect_graph_package_reqs(&graph);
+  let npm = {
+    let maybe_lockfile = cli_options.maybe_lockfile().map(|l| l.lock());
+    npm_reqs
+      .into_iter()
+      .map(|req| PackageReport {
+        version: maybe_lockfile.as_ref().and_then(|lockfile| {
+          lockfile
+            .content
+            .packages
+            .specifiers
+            .get(&JsrDepPackageReq::npm(req.clone()))
+            .cloned()
+        }),
+        req: req.to_string(),
+      })
+      .collect::<Vec<_>>()
+  };
+  let jsr = jsr_reqs
+    .into_iter()
+    .map(|req| PackageReport {
+      version: graph
+        .packages
+        .mappings()
+        .get(&req)
+        .map(|nv| nv.version.to_string()),
+      req: req.to_string(),
+    })
+    .collect::<Vec<_>>();
+
+  let report = DryRunReport {
+    roots,
+    remote,
+    npm,
+    jsr,
+  };
+  if cache_flags.json {
+    display::write_json_to_stdout(&report)
+  } else {
+    print_report(&report);
+    Ok(())
+  }
+}
+
+fn print_report(report: &DryRunReport) {
+  let downloads = report
+    .remote
+    .iter()
+    .filter(|module| !module.cached)
+    .collect::<Vec<_>>();
+  if downloads.is_empty() && report.npm.is_empty() && report.jsr.is_empty() {
+    log::info!("Nothing would be downloaded.");
+    return;
+  }
+  for module in downloads {
+    log::info!("{} {}", colors::green("Download"), module.specifier);
+  }
+  for (kind, packages) in [("npm", &report.npm), ("jsr", &report.jsr)] {
+    for package in packages {
+      let version = match &package.version {
+        Some(version) => colors::gray(format!(" ({})", version)).to_string(),
+        None => colors::gray(" (unlocked)").to_string(),
+      };
+      log::info!(
+        "{} {}:{}{}",
+        colors::green("Package"),
+        kind,
+        package.req,
+        version
+      );
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use deno_core::serde_json;
+
+  use super::*;
+
+  #[test]
+  fn test_report_json() {
+    let report = DryRunReport {
+      roots: vec![ModuleSpecifier::parse("file:///main.ts").unwrap()],
+      remote: vec![RemoteModuleReport {
+        specifier: ModuleSpecifier::parse("https://deno.land/x/mod.ts")
+          .unwrap(),
+        cached: false,
+        size: None,
+      }],
+      npm: vec![PackageReport {
+        req: "chalk@5".to_string(),
+        version: Some("5.3.0".to_string()),
+      }],
+      jsr: vec![],
+    };
+    assert_eq!(
+      serde_json::to_value(&report).unwrap(),
+      serde_json::json!({
+        "roots": ["file:///main.ts"],
+        "remote": [{
+          "specifier": "https://deno.land/x/mod.ts",
+          "cached": false,
+          "size": null,
+        }],
+        "npm": [{ "req": "chalk@5", "version": "5.3.0" }],
+        "jsr": [],
+      })
+    );
+  }
+}
@@ -1,5 +1,7 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::sync::Arc;
@@ -7,6 +9,8 @@ use std::sync::Arc;
 use deno_ast::MediaType;
 use deno_ast::ModuleSpecifier;
 use deno_core::error::AnyError;
+use deno_core::resolve_url_or_path;
+use deno_graph::GraphKind;
 use deno_graph::Module;
 use deno_graph::ModuleGraph;
 use deno_runtime::deno_node::NodeResolver;
@@ -30,6 +34,7 @@ use crate::factory::CliFactory;
 use crate::graph_util::BuildFastCheckGraphOptions;
 use crate::graph_util::ModuleGraphBuilder;
 use crate::npm::CliNpmResolver;
+use crate::tools::check_daemon;
 use crate::tsc;
 use crate::tsc::Diagnostics;
 use crate::util::extract;
@@ -43,12 +48,30 @@ pub async fn check(
 
   let main_graph_container = factory.main_module_graph_container().await?;
 
-  let specifiers =
+  let mut specifiers =
     main_graph_container.collect_specifiers(&check_flags.files)?;
   if specifiers.is_empty() {
     log::warn!("{} No matching files found.", colors::yellow("Warning"));
   }
 
+  if let Some(changed_files) = &check_flags.affected_by {
+    let cwd = factory.cli_options()?.initial_cwd();
+    let changed = changed_files
+      .iter()
+      .map(|file| resolve_url_or_path(file, cwd))
+      .collect::<Result<HashSet<_>, _>>()?;
+    let graph = factory
+      .module_graph_creator()
+      .await?
+      .create_graph(GraphKind::TypesOnly, specifiers)
+      .await?;
+    specifiers = affected_specifiers(&graph, &changed);
+    if specifiers.is_empty() {
+      log::info!("No modules are affected by the changed files.");
+      return Ok(());
+    }
+  }
+
   let specifiers_for_typecheck = if check_flags.doc || check_flags.doc_only {
     let file_fetcher = factory.file_fetcher()?;
     let root_permissions = factory.root_permissions_container()?;
@@ -210,15 +233,29 @@ impl TypeChecker {
       );
     }
 
+    // the daemon doesn't build the fast check graph, which is only used when
+    // publishing
+    if !options.build_fast_check_graph
+      && check_daemon::is_enabled(&self.cli_options)
+    {
+      let roots = graph.roots.iter().cloned().collect::<Vec<_>>();
+      match check_daemon::check_in_daemon(
+        &self.cli_options,
+        &roots,
+        options.lib,
+        options.reload,
+      )
+      .await
+      {
+        // the daemon stores the check hash and build info in the shared cache
+        Ok(diagnostics) => return Ok((graph.into(), diagnostics)),
+        Err(err) => {
+          log::debug!("Type checking in this process, because the check daemon failed: {:#}", err);
+        }
+      }
+    }
+
     let check_js = ts_config.get_check_js();
-    // while there might be multiple roots, we can't "merge" the build info, so we
-    // try to retrieve the build info for first root, which is the most common use
-    // case.
-    let maybe_tsbuildinfo = if options.reload {
-      None
-    } else {
-      cache.get_tsbuildinfo(&graph.roots[0])
-    };
     // to make tsc build info work, we need to consistently hash modules, so that
     // tsc can better determine if an emit is still valid or not, so we provide
     // that data here.
@@ -236,44 +273,86 @@ impl TypeChecker {
       )?;
     }
 
-    let root_names = get_tsc_roots(&graph, check_js);
     let graph = Arc::new(graph);
-    let response = tsc::exec(tsc::Request {
-      config: ts_config,
-      debug: self.cli_options.log_level() == Some(log::Level::Debug),
-      graph: graph.clone(),
-      hash_data,
-      maybe_npm: Some(tsc::RequestNpmState {
-        node_resolver: self.node_resolver.clone(),
-        npm_resolver: self.npm_resolver.clone(),
-      }),
-      maybe_tsbuildinfo,
-      root_names,
-      check_mode: type_check_mode,
-    })?;
-
-    let mut diagnostics = response.diagnostics.filter(|d| {
-      if self.is_remote_diagnostic(d) {
-        type_check_mode == TypeCheckMode::All && d.include_when_remote()
-      } else {
-        true
-      }
-    });
+    let partitions =
+      partition_roots(&graph, check_js, self.cli_options.check_jobs());
+    if partitions.len() > 1 {
+      log::debug!(
+        "Type checking {} independent parts of the graph in parallel.",
+        partitions.len()
+      );
+    }
+    let requests = partitions
+      .into_iter()
+      .map(|roots| {
+        // while there might be multiple roots, we can't "merge" the build info,
+        // so we try to retrieve the build info for first root, which is the
+        // most common use case.
+        let maybe_tsbuildinfo = if options.reload {
+          None
+        } else {
+          cache.get_tsbuildinfo(&roots[0])
+        };
+        let request = tsc::Request {
+          config: ts_config.clone(),
+          debug: self.cli_options.log_level() == Some(log::Level::Debug),
+          graph: graph.clone(),
+          hash_data,
+          maybe_npm: Some(tsc::RequestNpmState {
+            node_resolver: self.node_resolver.clone(),
+            npm_resolver: self.npm_resolver.clone(),
+          }),
+          maybe_tsbuildinfo,
+          root_names: get_tsc_roots(&graph, &roots, check_js),
+          check_mode: type_check_mode,
+        };
+        (roots[0].clone(), request)
+      })
+      .collect::<Vec<_>>();
+    let responses = if requests.len() == 1 {
+      let (root, request) = requests.into_iter().next().unwrap();
+      vec![(root, tsc::exec(request)?)]
+    } else {
+      // each request runs in its own isolate, so they can run in parallel
+      std::thread::scope(|scope| {
+        let handles = requests
+          .into_iter()
+          .map(|(root, request)| {
+            (root, scope.spawn(move || tsc::exec(request)))
+          })
+          .collect::<Vec<_>>();
+        handles
+          .into_iter()
+          .map(|(root, handle)| Ok((root, handle.join().unwrap()?)))
+          .collect::<Result<Vec<_>, AnyError>>()
+      })?
+    };
 
-    diagnostics.apply_fast_check_source_maps(&graph);
+    let mut diagnostics = Diagnostics::default();
+    for (root, response) in responses {
+      diagnostics.extend(response.diagnostics.filter(|d| {
+        if self.is_remote_diagnostic(d) {
+          type_check_mode == TypeCheckMode::All && d.include_when_remote()
+        } else {
+          true
+        }
+      }));
 
-    if let Some(tsbuildinfo) = response.maybe_tsbuildinfo {
-      cache.set_tsbuildinfo(&graph.roots[0], &tsbuildinfo);
+      if let Some(tsbuildinfo) = response.maybe_tsbuildinfo {
+        cache.set_tsbuildinfo(&root, &tsbuildinfo);
+      }
+
+      log::debug!("{}", response.stats);
     }
 
+    diagnostics.apply_fast_check_source_maps(&graph);
+
     if diagnostics.is_empty() {
       if let Some(check_hash) = maybe_check_hash {
         cache.add_check_hash(check_hash);
       }
     }
 
-    log::debug!("{}", response.stats);
-
     Ok((graph, diagnostics))
   }
 
@@ -391,6 +470,181 @@ fn get_check_hash(
   }
 }
 
+/// The local modules of the graph whose types could be impacted by the
+/// changed modules, which are the changed modules and the modules importing
+/// them directly or through other modules.
+fn affected_specifiers(
+  graph: &ModuleGraph,
+  changed: &HashSet<ModuleSpecifier>,
+) -> Vec<ModuleSpecifier> {
+  let mut importers: HashMap<&ModuleSpecifier, Vec<&ModuleSpecifier>> =
+    HashMap::new();
+  for module in graph.modules() {
+    let Some(module) = module.js() else {
+      continue;
+    };
+    let types_dependency = module
+      .maybe_types_dependency
+      .as_ref()
+      .and_then(|dep| dep.dependency.maybe_specifier());
+    let dependencies = module.dependencies.values().flat_map(|dep| {
+      [
+        dep.maybe_code.maybe_specifier(),
+        dep.maybe_type.maybe_specifier(),
+      ]
+    });
+    for specifier in types_dependency.into_iter().chain(dependencies.flatten())
+    {
+      importers
+        .entry(graph.resolve(specifier))
+        .or_default()
+        .push(&module.specifier);
+    }
+  }
+
+  let mut affected = HashSet::new();
+  let mut pending = changed
+    .iter()
+    .filter_map(|specifier| graph.get(specifier))
+    .map(|module| module.specifier())
+    .collect::<VecDeque<_>>();
+  while let Some(specifier) = pending.pop_front() {
+    if affected.insert(specifier) {
+      if let Some(importers) = importers.get(specifier) {
+        pending.extend(importers.iter().copied());
+      }
+    }
+  }
+
+  graph
+    .modules()
+    .filter(|module| {
+      module.specifier().scheme() == "file"
+        && module.js().is_some()
+        && affected.contains(module.specifier())
+    })
+    .map(|module| module.specifier().clone())
+    .collect()
+}
+
+/// Splits the roots of the graph into at most `jobs` groups that can be type
+/// checked independently because they don't share any local modules. Remote
+/// and npm dependencies may still be shared, those are checked in each group.
+/// The roots aren't split when a module of the graph declares globals, which
+/// would then only apply to the group it's in.
+fn partition_roots(
+  graph: &ModuleGraph,
+  check_js: bool,
+  jobs: usize,
+) -> Vec<Vec<ModuleSpecifier>> {
+  fn find(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+      parents[index] = parents[parents[index]];
+      index = parents[index];
+    }
+    index
+  }
+
+  let roots = graph.roots.iter().cloned().collect::<Vec<_>>();
+  if jobs <= 1 || roots.len() <= 1 {
+    return vec![roots];
+  }
+  let maybe_global_module = graph.modules().find_map(|module| match module {
+    Module::Js(module) if declares_globals(module) => Some(&module.specifier),
+    _ => None,
+  });
+  if let Some(specifier) = maybe_global_module {
+    log::debug!(
+      "Type checking the roots in one program, because {} declares globals.",
+      specifier
+    );
+    return vec![roots];
+  }
+
+  // union the roots that reach the same local module
+  let mut parents = (0..roots.len()).collect::<Vec<_>>();
+  let mut weights = vec![0; roots.len()];
+  let mut owners = HashMap::new();
+  for (index, root) in roots.iter().enumerate() {
+    let tsc_roots = get_tsc_roots(graph, std::slice::from_ref(root), check_js);
+    weights[index] = tsc_roots.len();
+    for (specifier, _) in tsc_roots {
+      if specifier.scheme() != "file" {
+        continue;
+      }
+      match owners.entry(specifier) {
+        Entry::Vacant(entry) => {
+          entry.insert(index);
+        }
+        Entry::Occupied(entry) => {
+          let owner = find(&mut parents, *entry.get());
+          let current = find(&mut parents, index);
+          parents[current] = owner;
+        }
+      }
+    }
+  }
+
+  let mut components: Vec<(usize, Vec<ModuleSpecifier>)> = Vec::new();
+  let mut component_indexes = HashMap::new();
+  for (index, root) in roots.iter().enumerate() {
+    let parent = find(&mut parents, index);
+    let component_index =
+      *component_indexes.entry(parent).or_insert_with(|| {
+        components.push((0, Vec::new()));
+        components.len() - 1
+      });
+    components[component_index].0 += weights[index];
+    components[component_index].1.push(root.clone());
+  }
+  if components.len() <= 1 {
+    return vec![roots];
+  }
+
+  // hand out the largest components first, each to the group with the least
+  // modules to check so far
+  components.sort_by(|a, b| b.0.cmp(&a.0));
+  let mut groups = vec![(0, Vec::new()); jobs.min(components.len())];
+  for (weight, roots) in components {
+    let group = groups.iter_mut().min_by_key(|(weight, _)| *weight).unwrap();
+    group.0 += weight;
+    group.1.extend(roots);
+  }
+  groups.into_iter().map(|(_, roots)| roots).collect()
+}
+
+/// Whether a module adds to the global scope of the program it's checked in,
+/// by being a script, with `declare global` or by augmenting a module with
+/// `declare module "name"`. Modules which fail to parse are assumed to.
+fn declares_globals(module: &deno_graph::JsModule) -> bool {
+  use deno_ast::swc::ast::Decl;
+  use deno_ast::swc::ast::ModuleItem;
+  use deno_ast::swc::ast::Stmt;
+  use deno_ast::swc::ast::TsModuleName;
+
+  let Ok(parsed_source) = deno_ast::parse_program(deno_ast::ParseParams {
+    specifier: module.specifier.clone(),
+    text: module.source.clone(),
+    media_type: module.media_type,
+    capture_tokens: false,
+    scope_analysis: false,
+    maybe_syntax: None,
+  }) else {
+    return true;
+  };
+  match parsed_source.program_ref().as_module() {
+    Some(program) => program.body.iter().any(|item| {
+      matches!(
+        item,
+        ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(decl)))
+          if decl.global || matches!(decl.id, TsModuleName::Str(_))
+      )
+    }),
+    // the top level declarations of scripts are global
+    None => true,
+  }
+}
+
 /// Transform the graph into root specifiers that we can feed `tsc`. We have to
 /// provide the media type for root modules because `tsc` does not "resolve" the
 /// media type like other modules, as well as a root specifier needs any
@@ -399,6 +653,7 @@ fn get_check_hash(
 /// otherwise they would be ignored if only imported into JavaScript.
 fn get_tsc_roots(
   graph: &ModuleGraph,
+  roots: &[ModuleSpecifier],
   check_js: bool,
 ) -> Vec<(ModuleSpecifier, MediaType)> {
   fn maybe_get_check_entry(
@@ -466,7 +721,7 @@ fn get_tsc_roots(
   }
 
   // then the roots
-  for root in &graph.roots {
+  for root in roots {
     let specifier = graph.resolve(root);
     if seen.insert(specifier.clone()) {
       pending.push_back(specifier);
@@ -603,9 +858,149 @@ fn get_leading_comments(file_text: &str) -> Vec<String> {
 #[cfg(test)]
 mod test {
   use deno_ast::MediaType;
+  use deno_ast::ModuleSpecifier;
+  use deno_graph::source::MemoryLoader;
+  use deno_graph::source::Source;
+  use deno_graph::GraphKind;
+  use deno_graph::ModuleGraph;
 
+  use super::affected_specifiers;
   use super::get_leading_comments;
   use super::has_ts_check;
+  use super::partition_roots;
+
+  #[tokio::test]
+  async fn partition_roots_test() {
+    let module = |specifier: &str, content: &str| {
+      (
+        specifier.to_string(),
+        Source::Module {
+          specifier: specifier.to_string(),
+          content: content.to_string(),
+          maybe_headers: None,
+        },
+      )
+    };
+    let loader = MemoryLoader::new(
+      vec![
+        module("file:///a.ts", "import './shared.ts';"),
+        module("file:///b.ts", "import './shared.ts';"),
+        module("file:///c.ts", "export const c = 1;"),
+        module("file:///shared.ts", "export const shared = 1;"),
+      ],
+      Vec::new(),
+    );
+    let roots = ["file:///a.ts", "file:///b.ts", "file:///c.ts"]
+      .into_iter()
+      .map(|s| ModuleSpecifier::parse(s).unwrap())
+      .collect::<Vec<_>>();
+    let mut graph = ModuleGraph::new(GraphKind::TypesOnly);
+    graph
+      .build(roots.clone(), &loader, Default::default())
+      .await;
+
+    assert_eq!(
+      partition_roots(&graph, false, 4),
+      vec![
+        vec![roots[0].clone(), roots[1].clone()],
+        vec![roots[2].clone()]
+      ]
+    );
+    assert_eq!(partition_roots(&graph, false, 1), vec![roots]);
+  }
+
+  #[tokio::test]
+  async fn partition_roots_global_augmentation_test() {
+    let module = |specifier: &str, content: &str| {
+      (
+        specifier.to_string(),
+        Source::Module {
+          specifier: specifier.to_string(),
+          content: content.to_string(),
+          maybe_headers: None,
+        },
+      )
+    };
+    for globals in [
+      "declare global { var answer: number; }\nexport {};",
+      "declare module \"./b.ts\" { export const answer: number; }\nexport {};",
+      "declare var answer: number;",
+    ] {
+      let loader = MemoryLoader::new(
+        vec![
+          module("file:///a.ts", "import './globals.d.ts';"),
+          module("file:///b.ts", "console.log(answer);\nexport {};"),
+          module("file:///globals.d.ts", globals),
+        ],
+        Vec::new(),
+      );
+      let roots = ["file:///a.ts", "file:///b.ts"]
+        .into_iter()
+        .map(|s| ModuleSpecifier::parse(s).unwrap())
+        .collect::<Vec<_>>();
+      let mut graph = ModuleGraph::new(GraphKind::TypesOnly);
+      graph
+        .build(roots.clone(), &loader, Default::default())
+        .await;
+
+      assert_eq!(partition_roots(&graph, false, 4), vec![roots]);
+    }
+  }
+
+  #[tokio::test]
+  async fn affected_specifiers_test() {
+    let module = |specifier: &str, content: &str| {
+      (
+        specifier.to_string(),
+        Source::Module {
+          specifier: specifier.to_string(),
+          content: content.to_string(),
+          maybe_headers: None,
+        },
+      )
+    };
+    let loader = MemoryLoader::new(
+      vec![
+        module("file:///a.ts", "import './b.ts';"),
+        module("file:///b.ts", "import type { C } from './c.ts';"),
+        module("file:///c.ts", "export type C = string;"),
+        module("file:///d.ts", "import './c.ts';"),
+        module("file:///e.ts", "export const e = 1;"),
+      ],
+      Vec::new(),
+    );
+    let specifier = |s: &str| ModuleSpecifier::parse(s).unwrap();
+    let roots = ["file:///a.ts", "file:///d.ts", "file:///e.ts"]
+      .into_iter()
+      .map(specifier)
+      .collect::<Vec<_>>();
+    let mut graph = ModuleGraph::new(GraphKind::TypesOnly);
+    graph.build(roots, &loader, Default::default()).await;
+
+    let affected = |changed: &[&str]| {
+      let changed = changed.iter().map(|s| specifier(s)).collect();
+      let mut affected = affected_specifiers(&graph, &changed)
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<_>>();
+      affected.sort();
+      affected
+    };
+    assert_eq!(
+      affected(&["file:///b.ts"]),
+      vec!["file:///a.ts", "file:///b.ts"]
+    );
+    assert_eq!(
+      affected(&["file:///c.ts"]),
+      vec![
+        "file:///a.ts",
+        "file:///b.ts",
+        "file:///c.ts",
+        "file:///d.ts"
+      ]
+    );
+    assert!(affected(&["file:///other.ts"]).is_empty());
+  }
 
   #[test]
   fn get_leading_comments_test() {
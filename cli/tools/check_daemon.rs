@@ -0,0 +1,576 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! A long-lived process which type checks the module graphs of `deno check`
+//! and `deno test`, so that repeated invocations reuse the resolved workspace,
+//! npm state and caches of a project instead of starting cold each time.
+//!
+//! The daemon is opt-in with `DENO_CHECK_DAEMON=1`. It's started lazily by the
+//! first type check and listens on a unix socket which is unique per
+//! `DENO_DIR`, Deno version and values of `DAEMON_ENV_VAR_NAMES`, in a
+//! directory only accessible by the current user. The daemon is started with
+//! only those variables set, so it never has to change its own environment to
+//! serve a request. Requests and responses are newline delimited JSON objects
+//! and connections are served one at a time. The daemon exits after being
+//! idle for `IDLE_TIMEOUT` or when its socket is removed.
+
+#![cfg_attr(not(unix), allow(dead_code))]
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::args::CliOptions;
+use crate::args::DenoSubcommand;
+use crate::args::TsTypeLib;
+use crate::tsc::Diagnostics;
+
+/// Hidden environment variable with the socket path for the process which
+/// runs as the check daemon.
+pub(crate) const CHECK_DAEMON_SOCKET_HIDDEN_ENV_VAR_NAME: &str =
+  "DENO_INTERNAL_CHECK_DAEMON_SOCKET";
+
+/// Environment variable to opt into type checking in the check daemon.
+const CHECK_DAEMON_ENV_VAR_NAME: &str = "DENO_CHECK_DAEMON";
+
+const IDLE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+/// How often the idle daemon checks whether its socket still exists.
+const SOCKET_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(5);
+/// The number of projects whose state is kept warm at the same time.
+const MAX_SESSIONS: usize = 8;
+
+/// The environment variables which affect how a project is resolved and
+/// type checked. Some of them are only read once per process, like
+/// `NPM_CONFIG_REGISTRY`, so each combination of values gets its own daemon.
+const DAEMON_ENV_VAR_NAMES: &[&str] = &[
+  "HOME",
+  "PATH",
+  "XDG_CACHE_HOME",
+  "DENO_AUTH_TOKENS",
+  "DENO_CACHE_BACKEND",
+  "DENO_CACHE_BACKEND_SECRET",
+  "DENO_CERT",
+  "DENO_NO_PACKAGE_JSON",
+  "DENO_TLS_CA_STORE",
+  "DENO_UNSTABLE_SLOPPY_IMPORTS",
+  "JSR_URL",
+  "NPM_CONFIG_REGISTRY",
+  "HTTP_PROXY",
+  "http_proxy",
+  "HTTPS_PROXY",
+  "https_proxy",
+  "NO_PROXY",
+  "no_proxy",
+];
+
+static IS_CHECK_DAEMON: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckRequest {
+  cwd: PathBuf,
+  /// The arguments of the process which requests the check, used to resolve
+  /// the same options in the daemon.
+  args: Vec<String>,
+  roots: Vec<String>,
+  lib: String,
+  reload: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum CheckResponse {
+  Diagnostics(Diagnostics),
+  Error(String),
+}
+
+/// Whether the type checks of this process are sent to the check daemon.
+pub fn is_enabled(cli_options: &CliOptions) -> bool {
+  cfg!(unix)
+    && std::env::var(CHECK_DAEMON_ENV_VAR_NAME).is_ok_and(|value| value == "1")
+    && !cli_options.no_daemon()
+    && !IS_CHECK_DAEMON.load(Ordering::Relaxed)
+    && matches!(
+      cli_options.sub_command(),
+      DenoSubcommand::Check(_) | DenoSubcommand::Test(_)
+    )
+    // the daemon resolves the media types without the overwrite
+    && cli_options.ext_flag().is_none()
+    // one-off runs in scripts and CI don't benefit from a warm process
+    && std::io::stderr().is_terminal()
+}
+
+/// Returns the socket path when this process was started as the daemon.
+pub fn daemon_socket_path_from_env() -> Option<PathBuf> {
+  let socket_path = std::env::var_os(CHECK_DAEMON_SOCKET_HIDDEN_ENV_VAR_NAME)?;
+  // remove the env var so that child sub processes won't pick this up
+  std::env::remove_var(CHECK_DAEMON_SOCKET_HIDDEN_ENV_VAR_NAME);
+  Some(PathBuf::from(socket_path))
+}
+
+/// The values of `DAEMON_ENV_VAR_NAMES` in this process, including the ones
+/// loaded from `--env-file`.
+fn daemon_env() -> BTreeMap<&'static str, String> {
+  DAEMON_ENV_VAR_NAMES
+    .iter()
+    .filter_map(|name| Some((*name, std::env::var(name).ok()?)))
+    .collect()
+}
+
+fn lib_name(lib: TsTypeLib) -> &'static str {
+  match lib {
+    TsTypeLib::DenoWindow => "window",
+    TsTypeLib::DenoWorker => "worker",
+  }
+}
+
+fn lib_from_name(name: &str) -> Result<TsTypeLib, AnyError> {
+  match name {
+    "window" => Ok(TsTypeLib::DenoWindow),
+    "worker" => Ok(TsTypeLib::DenoWorker),
+    _ => bail!("Unknown type library '{}'", name),
+  }
+}
+
+/// Roots which only exist in memory, like the code blocks extracted from
+/// documentation, can't be loaded by the daemon.
+fn is_loadable_root(root: &ModuleSpecifier) -> bool {
+  match root.scheme() {
+    "file" => root.to_file_path().is_ok_and(|path| path.is_file()),
+    "http" | "https" | "npm" | "jsr" => true,
+    _ => false,
+  }
+}
+
+#[cfg(unix)]
+pub use unix::check_in_daemon;
+#[cfg(unix)]
+pub use unix::run_daemon;
+
+#[cfg(not(unix))]
+pub async fn check_in_daemon(
+  _cli_options: &CliOptions,
+  _roots: &[ModuleSpecifier],
+  _lib: TsTypeLib,
+  _reload: bool,
+) -> Result<Diagnostics, AnyError> {
+  bail!("The check daemon is only supported on unix")
+}
+
+#[cfg(not(unix))]
+pub async fn run_daemon(_socket_path: PathBuf) -> Result<(), AnyError> {
+  bail!("The check daemon is only supported on unix")
+}
+
+#[cfg(unix)]
+mod unix {
+  use std::os::unix::fs::DirBuilderExt;
+  use std::os::unix::fs::MetadataExt;
+  use std::os::unix::fs::PermissionsExt;
+  use std::os::unix::process::CommandExt;
+  use std::path::Path;
+  use std::process::Stdio;
+  use std::sync::Arc;
+  use std::time::Instant;
+  use std::time::SystemTime;
+
+  use deno_core::anyhow::Context;
+  use deno_core::serde_json;
+  use tokio::io::AsyncBufReadExt;
+  use tokio::io::AsyncWriteExt;
+  use tokio::io::BufReader;
+  use tokio::net::UnixListener;
+  use tokio::net::UnixStream;
+
+  use super::*;
+  use crate::args::flags_from_vec;
+  use crate::cache::DenoDir;
+  use crate::cache::FastInsecureHasher;
+  use crate::factory::CliFactory;
+  use crate::tools::check::CheckOptions;
+
+  /// Unix socket paths are limited to 104 bytes on macOS and 108 on Linux.
+  const MAX_SOCKET_PATH_LEN: usize = 100;
+
+  fn current_uid() -> u32 {
+    // SAFETY: getuid can't fail
+    unsafe { libc::getuid() }
+  }
+
+  /// The socket is kept in the runtime directory of the user when there is
+  /// one, and in the `DENO_DIR` otherwise.
+  fn socket_path(
+    deno_dir: &DenoDir,
+    env: &BTreeMap<&'static str, String>,
+  ) -> Result<PathBuf, AnyError> {
+    let dir = match std::env::var_os("XDG_RUNTIME_DIR") {
+      Some(dir) if !dir.is_empty() => PathBuf::from(dir).join("deno"),
+      _ => deno_dir.root.join("check_daemon"),
+    };
+    let mut hasher = FastInsecureHasher::new_deno_versioned();
+    hasher.write_str(&deno_dir.root.to_string_lossy());
+    for (name, value) in env {
+      hasher.write_str(name).write_str(value);
+    }
+    let hash = hasher.finish();
+    let socket_path = dir.join(format!("check-{:x}.sock", hash));
+    if socket_path.as_os_str().len() > MAX_SOCKET_PATH_LEN {
+      bail!(
+        "The check daemon socket path {} is too long",
+        socket_path.display()
+      );
+    }
+    create_private_dir(&dir)?;
+    Ok(socket_path)
+  }
+
+  /// Creates a directory which only the current user can access, so that
+  /// other users can neither connect to the socket in it nor replace it.
+  pub(super) fn create_private_dir(dir: &Path) -> Result<(), AnyError> {
+    std::fs::DirBuilder::new()
+      .recursive(true)
+      .mode(0o700)
+      .create(dir)
+      .with_context(|| format!("Failed creating {}", dir.display()))?;
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != current_uid() {
+      bail!("{} is not a directory of the current user", dir.display());
+    }
+    // the directory may have existed with other permissions
+    if metadata.permissions().mode() & 0o077 != 0 {
+      std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+  }
+
+  /// Only processes of the current user may talk to each other.
+  fn ensure_same_user(stream: &UnixStream) -> Result<(), AnyError> {
+    let peer_uid = stream.peer_cred()?.uid();
+    if peer_uid != current_uid() {
+      bail!("The check daemon peer belongs to user {}", peer_uid);
+    }
+    Ok(())
+  }
+
+  /// Type checks the graph with the given roots in the check daemon, which
+  /// is started when it's not running yet.
+  pub async fn check_in_daemon(
+    cli_options: &CliOptions,
+    roots: &[ModuleSpecifier],
+    lib: TsTypeLib,
+    reload: bool,
+  ) -> Result<Diagnostics, AnyError> {
+    if let Some(root) = roots.iter().find(|root| !is_loadable_root(root)) {
+      bail!("The check daemon can't load {}", root);
+    }
+    let deno_dir = cli_options.deno_dir_provider.get_or_create()?;
+    let stream = connect_or_start(deno_dir, daemon_env()).await?;
+    let request = CheckRequest {
+      cwd: cli_options.initial_cwd().to_path_buf(),
+      args: std::env::args_os()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect(),
+      roots: roots.iter().map(|root| root.to_string()).collect(),
+      lib: lib_name(lib).to_string(),
+      reload,
+    };
+    let (reader, mut writer) = stream.into_split();
+    let mut text = serde_json::to_string(&request)?;
+    text.push('\n');
+    writer.write_all(text.as_bytes()).await?;
+    let mut line = String::new();
+    BufReader::new(reader).read_line(&mut line).await?;
+    let response = serde_json::from_str::<CheckResponse>(&line)
+      .context("Invalid response from the check daemon")?;
+    match response {
+      CheckResponse::Diagnostics(diagnostics) => Ok(diagnostics),
+      CheckResponse::Error(message) => bail!(message),
+    }
+  }
+
+  async fn connect_or_start(
+    deno_dir: &DenoDir,
+    env: BTreeMap<&'static str, String>,
+  ) -> Result<UnixStream, AnyError> {
+    let socket_path = socket_path(deno_dir, &env)?;
+    if let Ok(stream) = UnixStream::connect(&socket_path).await {
+      ensure_same_user(&stream)?;
+      return Ok(stream);
+    }
+    log::debug!("Starting the check daemon at {}", socket_path.display());
+    std::process::Command::new(std::env::current_exe()?)
+      .args(["check", "--quiet", "--no-daemon", "."])
+      .env_clear()
+      .envs(env)
+      .env(CHECK_DAEMON_SOCKET_HIDDEN_ENV_VAR_NAME, &socket_path)
+      .env("DENO_DIR", &deno_dir.root)
+      .stdin(Stdio::null())
+      .stdout(Stdio::null())
+      .stderr(Stdio::null())
+      // keep the daemon running when the terminal is interrupted
+      .process_group(0)
+      .spawn()
+      .context("Failed starting the check daemon")?;
+    let started = Instant::now();
+    loop {
+      tokio::time::sleep(Duration::from_millis(50)).await;
+      match UnixStream::connect(&socket_path).await {
+        Ok(stream) => {
+          ensure_same_user(&stream)?;
+          return Ok(stream);
+        }
+        Err(err) if started.elapsed() > STARTUP_TIMEOUT => {
+          return Err(err)
+            .context("Timed out waiting for the check daemon to start");
+        }
+        Err(_) => {}
+      }
+    }
+  }
+
+  /// Serves the type checks of other processes until being idle for
+  /// `IDLE_TIMEOUT` or until the socket is removed.
+  pub async fn run_daemon(socket_path: PathBuf) -> Result<(), AnyError> {
+    IS_CHECK_DAEMON.store(true, Ordering::Relaxed);
+    // another daemon was started in the meantime
+    if UnixStream::connect(&socket_path).await.is_ok() {
+      return Ok(());
+    }
+    if let Some(dir) = socket_path.parent() {
+      create_private_dir(dir)?;
+    }
+    let _ = std::fs::remove_file(&socket_path);
+    let listener = UnixListener::bind(&socket_path).with_context(|| {
+      format!("Failed listening on {}", socket_path.display())
+    })?;
+    let mut sessions = Sessions::default();
+    let mut last_request = Instant::now();
+    loop {
+      let Ok(accepted) =
+        tokio::time::timeout(SOCKET_CHECK_INTERVAL, listener.accept()).await
+      else {
+        if !socket_path.exists() {
+          return Ok(());
+        }
+        if last_request.elapsed() >= IDLE_TIMEOUT {
+          break;
+        }
+        continue;
+      };
+      let (stream, _) = accepted?;
+      if let Err(err) = ensure_same_user(&stream) {
+        log::debug!("Rejected a check daemon connection: {:#}", err);
+        continue;
+      }
+      if let Err(err) = serve_connection(&mut sessions, stream).await {
+        log::debug!("Failed serving a check daemon connection: {:#}", err);
+      }
+      last_request = Instant::now();
+    }
+    let _ = std::fs::remove_file(&socket_path);
+    Ok(())
+  }
+
+  async fn serve_connection(
+    sessions: &mut Sessions,
+    stream: UnixStream,
+  ) -> Result<(), AnyError> {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    BufReader::new(reader).read_line(&mut line).await?;
+    let request = serde_json::from_str::<CheckRequest>(&line)?;
+    let response = match sessions.check(request).await {
+      Ok(diagnostics) => CheckResponse::Diagnostics(diagnostics),
+      Err(err) => CheckResponse::Error(format!("{err:#}")),
+    };
+    let mut text = serde_json::to_string(&response)?;
+    text.push('\n');
+    writer.write_all(text.as_bytes()).await?;
+    Ok(())
+  }
+
+  /// The working directory and arguments of the requesting process, which
+  /// together with the environment of the daemon determine the resolved
+  /// options.
+  type SessionKey = (PathBuf, Vec<String>);
+
+  /// The warm state of the projects, keyed by what the options are resolved
+  /// from.
+  #[derive(Default)]
+  struct Sessions {
+    sessions: HashMap<SessionKey, Session>,
+  }
+
+  impl Sessions {
+    async fn check(
+      &mut self,
+      request: CheckRequest,
+    ) -> Result<Diagnostics, AnyError> {
+      let key = (request.cwd, request.args);
+      if self.sessions.get(&key).map_or(true, |s| s.is_stale()) {
+        self.sessions.remove(&key);
+        if self.sessions.len() >= MAX_SESSIONS {
+          let least_recently_used = self
+            .sessions
+            .iter()
+            .min_by_key(|(_, session)| session.last_used)
+            .map(|(key, _)| key.clone())
+            .unwrap();
+          self.sessions.remove(&least_recently_used);
+        }
+        let session = Session::new(&key.0, &key.1)?;
+        self.sessions.insert(key.clone(), session);
+      }
+      let session = self.sessions.get_mut(&key).unwrap();
+      session.last_used = Instant::now();
+      let roots = request
+        .roots
+        .iter()
+        .map(|root| ModuleSpecifier::parse(root))
+        .collect::<Result<Vec<_>, _>>()?;
+      session
+        .check(roots, lib_from_name(&request.lib)?, request.reload)
+        .await
+    }
+  }
+
+  struct Session {
+    factory: CliFactory,
+    /// The config files of the project with their modification times when
+    /// the session was created.
+    config_files: Vec<(PathBuf, Option<SystemTime>)>,
+    last_used: Instant,
+  }
+
+  impl Session {
+    fn new(cwd: &Path, args: &[String]) -> Result<Self, AnyError> {
+      let flags = flags_from_vec(args.iter().map(Into::into).collect())?;
+      let cli_options =
+        CliOptions::from_flags_with_cwd(Arc::new(flags), cwd.to_path_buf())?;
+      let factory = CliFactory::from_cli_options(Arc::new(cli_options));
+      let config_files = factory
+        .cli_options()?
+        .watch_paths()
+        .into_iter()
+        .map(|path| {
+          let modified = modified_time(&path);
+          (path, modified)
+        })
+        .collect();
+      Ok(Self {
+        factory,
+        config_files,
+        last_used: Instant::now(),
+      })
+    }
+
+    /// Whether a config file changed since the session was created, which
+    /// requires resolving the options again.
+    fn is_stale(&self) -> bool {
+      self
+        .config_files
+        .iter()
+        .any(|(path, modified)| modified_time(path) != *modified)
+    }
+
+    async fn check(
+      &self,
+      roots: Vec<ModuleSpecifier>,
+      lib: TsTypeLib,
+      reload: bool,
+    ) -> Result<Diagnostics, AnyError> {
+      let cli_options = self.factory.cli_options()?;
+      let type_check_mode = cli_options.type_check_mode();
+      let graph = self
+        .factory
+        .module_graph_creator()
+        .await?
+        .create_graph(type_check_mode.as_graph_kind(), roots)
+        .await?;
+      let (_, diagnostics) = self
+        .factory
+        .type_checker()
+        .await?
+        .check_diagnostics(
+          graph,
+          CheckOptions {
+            build_fast_check_graph: false,
+            lib,
+            log_ignored_options: false,
+            reload,
+            type_check_mode,
+          },
+        )
+        .await?;
+      Ok(diagnostics)
+    }
+  }
+
+  fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_lib_name() {
+    for lib in [TsTypeLib::DenoWindow, TsTypeLib::DenoWorker] {
+      assert_eq!(lib_from_name(lib_name(lib)).unwrap(), lib);
+    }
+    assert!(lib_from_name("dom").is_err());
+  }
+
+  #[test]
+  fn test_is_loadable_root() {
+    let temp_dir = test_util::TempDir::new();
+    let file = temp_dir.path().join("mod.ts");
+    file.write("export {};");
+    let specifier = ModuleSpecifier::from_file_path(file.as_path()).unwrap();
+    assert!(is_loadable_root(&specifier));
+    let snippet = ModuleSpecifier::from_file_path(
+      temp_dir.path().join("mod.ts$1-3.ts").as_path(),
+    )
+    .unwrap();
+    assert!(!is_loadable_root(&snippet));
+    let stdin = ModuleSpecifier::parse("file:///$deno$stdin.ts").unwrap();
+    assert!(!is_loadable_root(&stdin));
+    let remote = ModuleSpecifier::parse("https://deno.land/x/mod.ts").unwrap();
+    assert!(is_loadable_root(&remote));
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn test_create_private_dir() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = test_util::TempDir::new();
+    let dir = temp_dir.path().join("deno/check_daemon");
+    unix::create_private_dir(dir.as_path()).unwrap();
+    let mode = |path: &std::path::Path| {
+      std::fs::metadata(path).unwrap().permissions().mode() & 0o777
+    };
+    assert_eq!(mode(dir.as_path()), 0o700);
+
+    // loosened permissions are restricted again
+    std::fs::set_permissions(
+      dir.as_path(),
+      std::fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+    unix::create_private_dir(dir.as_path()).unwrap();
+    assert_eq!(mode(dir.as_path()), 0o700);
+  }
+}
@@ -3,8 +3,15 @@
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
 
+use crate::args::CleanFlags;
+use crate::cache::npm_cache_package_dirs;
+use crate::cache::read_http_cache_entries;
 use crate::cache::DenoDir;
+use crate::cache::DiskUsage;
+use crate::cache::HttpCacheEntryMatcher;
 use crate::colors;
 use crate::display;
 use crate::util::progress_bar::ProgressBar;
@@ -27,44 +34,279 @@ impl CleanState {
   }
 }
 
-pub fn clean() -> Result<(), AnyError> {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CacheCategory {
+  Npm,
+  Remote,
+  Codegen,
+}
+
+impl CacheCategory {
+  fn description(&self) -> &'static str {
+    match self {
+      Self::Npm => "npm package cache",
+      Self::Remote => "remote module cache",
+      Self::Codegen => "emitted JavaScript cache",
+    }
+  }
+}
+
+/// The files and directories of a cache category to remove.
+struct CategoryCandidates {
+  category: CacheCategory,
+  paths: Vec<PathBuf>,
+  usage: DiskUsage,
+}
+
+pub fn clean(clean_flags: CleanFlags) -> Result<(), AnyError> {
   let deno_dir = DenoDir::new(None)?;
-  if deno_dir.root.exists() {
-    let no_of_files = walkdir::WalkDir::new(&deno_dir.root).into_iter().count();
-    let progress_bar = ProgressBar::new(ProgressBarStyle::ProgressBars);
-    let progress_guard =
-      progress_bar.update_with_prompt(ProgressMessagePrompt::Cleaning, "");
-
-    let mut state = CleanState {
-      files_removed: 0,
-      dirs_removed: 0,
-      bytes_removed: 0,
-      progress_guard,
-    };
-    state
-      .progress_guard
-      .set_total_size(no_of_files.try_into().unwrap());
+  let mut categories = Vec::new();
+  if clean_flags.npm {
+    categories.push(CacheCategory::Npm);
+  }
+  if clean_flags.remote || clean_flags.remote_match.is_some() {
+    categories.push(CacheCategory::Remote);
+  }
+  if clean_flags.codegen {
+    categories.push(CacheCategory::Codegen);
+  }
+  if categories.is_empty() {
+    if clean_flags.older_than.is_none() {
+      return clean_all(&deno_dir, clean_flags.dry_run);
+    }
+    categories = vec![
+      CacheCategory::Npm,
+      CacheCategory::Remote,
+      CacheCategory::Codegen,
+    ];
+  }
 
-    rm_rf(&mut state, &deno_dir.root)?;
+  let cutoff = clean_flags.older_than.map(|older_than| {
+    SystemTime::now()
+      .checked_sub(older_than)
+      .unwrap_or(SystemTime::UNIX_EPOCH)
+  });
+  let matcher = clean_flags
+    .remote_match
+    .as_deref()
+    .map(HttpCacheEntryMatcher::new)
+    .transpose()?;
+  let candidates = categories
+    .into_iter()
+    .map(|category| {
+      let paths = match category {
+        CacheCategory::Npm => {
+          let npm_dir = deno_dir.npm_folder_path();
+          match cutoff {
+            Some(cutoff) => collect_npm_package_dirs(&npm_dir, cutoff)?,
+            None => existing(npm_dir),
+          }
+        }
+        CacheCategory::Remote => {
+          let remote_dir = deno_dir.remote_folder_path();
+          if matcher.is_none() && cutoff.is_none() {
+            existing(remote_dir)
+          } else {
+            read_http_cache_entries(&remote_dir)?
+              .into_iter()
+              .filter(|entry| {
+                matcher.as_ref().map_or(true, |m| m.matches(&entry.url))
+                  && cutoff.map_or(true, |cutoff| {
+                    entry
+                      .download_time
+                      .or_else(|| modified_time(&entry.path))
+                      .is_some_and(|time| time < cutoff)
+                  })
+              })
+              .map(|entry| entry.path)
+              .collect()
+          }
+        }
+        CacheCategory::Codegen => {
+          let gen_dir = deno_dir.gen_cache.location.clone();
+          match cutoff {
+            Some(cutoff) => collect_files_older_than(&gen_dir, cutoff),
+            None => existing(gen_dir),
+          }
+        }
+      };
+      let usage = paths.iter().fold(DiskUsage::default(), |usage, path| {
+        usage.add(DiskUsage::of_path(path))
+      });
+      Ok(CategoryCandidates {
+        category,
+        paths,
+        usage,
+      })
+    })
+    .collect::<Result<Vec<_>, AnyError>>()?;
 
-    // Drop the guard so that progress bar disappears.
-    drop(state.progress_guard);
+  if clean_flags.dry_run {
+    for candidates in &candidates {
+      log::info!(
+        "{} {} {}",
+        colors::yellow("Would remove"),
+        candidates.category.description(),
+        colors::gray(&format!(
+          "({} files, {})",
+          candidates.usage.files,
+          display::human_size(candidates.usage.bytes as f64)
+        ))
+      );
+    }
+    return Ok(());
+  }
 
+  let progress_bar = ProgressBar::new(ProgressBarStyle::ProgressBars);
+  let progress_guard =
+    progress_bar.update_with_prompt(ProgressMessagePrompt::Cleaning, "");
+  let mut state = CleanState {
+    files_removed: 0,
+    dirs_removed: 0,
+    bytes_removed: 0,
+    progress_guard,
+  };
+  let no_of_entries = candidates
+    .iter()
+    .flat_map(|c| &c.paths)
+    .map(|path| walkdir::WalkDir::new(path).into_iter().count() as u64)
+    .sum();
+  state.progress_guard.set_total_size(no_of_entries);
+  let mut removed = Vec::with_capacity(candidates.len());
+  for candidates in &candidates {
+    let files_before = state.files_removed;
+    let bytes_before = state.bytes_removed;
+    for path in &candidates.paths {
+      log::debug!("Removing {}", path.display());
+      if path.is_dir() {
+        rm_rf(&mut state, path)?;
+      } else {
+        remove_file(&mut state, path, std::fs::metadata(path).ok())?;
+      }
+    }
+    removed.push((
+      candidates.category,
+      state.files_removed - files_before,
+      state.bytes_removed - bytes_before,
+    ));
+  }
+
+  // Drop the guard so that progress bar disappears.
+  drop(state.progress_guard);
+
+  for (category, files, bytes) in removed {
     log::info!(
       "{} {} {}",
       colors::green("Removed"),
+      category.description(),
+      colors::gray(&format!(
+        "({} files, {})",
+        files,
+        display::human_size(bytes as f64)
+      ))
+    );
+  }
+
+  Ok(())
+}
+
+fn clean_all(deno_dir: &DenoDir, dry_run: bool) -> Result<(), AnyError> {
+  if !deno_dir.root.exists() {
+    return Ok(());
+  }
+  if dry_run {
+    let usage = DiskUsage::of_path(&deno_dir.root);
+    log::info!(
+      "{} {} {}",
+      colors::yellow("Would remove"),
       deno_dir.root.display(),
       colors::gray(&format!(
         "({} files, {})",
-        state.files_removed + state.dirs_removed,
-        display::human_size(state.bytes_removed as f64)
+        usage.files,
+        display::human_size(usage.bytes as f64)
       ))
     );
+    return Ok(());
   }
 
+  let no_of_files = walkdir::WalkDir::new(&deno_dir.root).into_iter().count();
+  let progress_bar = ProgressBar::new(ProgressBarStyle::ProgressBars);
+  let progress_guard =
+    progress_bar.update_with_prompt(ProgressMessagePrompt::Cleaning, "");
+
+  let mut state = CleanState {
+    files_removed: 0,
+    dirs_removed: 0,
+    bytes_removed: 0,
+    progress_guard,
+  };
+  state
+    .progress_guard
+    .set_total_size(no_of_files.try_into().unwrap());
+
+  rm_rf(&mut state, &deno_dir.root)?;
+
+  // Drop the guard so that progress bar disappears.
+  drop(state.progress_guard);
+
+  log::info!(
+    "{} {} {}",
+    colors::green("Removed"),
+    deno_dir.root.display(),
+    colors::gray(&format!(
+      "({} files, {})",
+      state.files_removed + state.dirs_removed,
+      display::human_size(state.bytes_removed as f64)
+    ))
+  );
+
   Ok(())
 }
 
+fn existing(path: PathBuf) -> Vec<PathBuf> {
+  if path.exists() {
+    vec![path]
+  } else {
+    vec![]
+  }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+  std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn collect_files_older_than(dir: &Path, cutoff: SystemTime) -> Vec<PathBuf> {
+  walkdir::WalkDir::new(dir)
+    .into_iter()
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| {
+      entry.file_type().is_file()
+        && entry
+          .metadata()
+          .ok()
+          .and_then(|m| m.modified().ok())
+          .is_some_and(|time| time < cutoff)
+    })
+    .map(|entry| entry.into_path())
+    .collect()
+}
+
+/// Collects the directories of the npm package versions which were last
+/// modified before the cutoff. Removing individual files of a package would
+/// leave it in a broken state, so packages are removed as a whole.
+fn collect_npm_package_dirs(
+  npm_dir: &Path,
+  cutoff: SystemTime,
+) -> Result<Vec<PathBuf>, AnyError> {
+  Ok(
+    npm_cache_package_dirs(npm_dir)?
+      .into_iter()
+      .map(|dir| dir.path)
+      .filter(|path| modified_time(path).is_some_and(|time| time < cutoff))
+      .collect(),
+  )
+}
+
 fn rm_rf(state: &mut CleanState, path: &Path) -> Result<(), AnyError> {
   for entry in walkdir::WalkDir::new(path).contents_first(true) {
     let entry = entry?;
@@ -95,3 +337,44 @@ fn remove_file(
     .with_context(|| format!("Failed to remove file: {}", path.display()))?;
   Ok(())
 }
+
+#[cfg(test)]
+mod test {
+  use std::time::Duration;
+
+  use test_util::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn test_collect_npm_package_dirs() {
+    let temp_dir = TempDir::new();
+    let registry_dir = temp_dir.path().join("registry.npmjs.org");
+    registry_dir.join("chalk/5.3.0").create_dir_all();
+    registry_dir.join("chalk").join("registry.json").write("{}");
+    registry_dir.join("@types/node/20.1.0").create_dir_all();
+    registry_dir.join("@types/node/18.0.0").create_dir_all();
+
+    let future = SystemTime::now() + Duration::from_secs(60);
+    let dirs = collect_npm_package_dirs(temp_dir.path().as_path(), future)
+      .unwrap()
+      .into_iter()
+      .map(|dir| {
+        dir
+          .strip_prefix(registry_dir.as_path())
+          .unwrap()
+          .to_string_lossy()
+          .replace('\\', "/")
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(
+      dirs,
+      vec!["@types/node/18.0.0", "@types/node/20.1.0", "chalk/5.3.0"]
+    );
+
+    let past = SystemTime::now() - Duration::from_secs(60 * 60);
+    assert!(collect_npm_package_dirs(temp_dir.path().as_path(), past)
+      .unwrap()
+      .is_empty());
+  }
+}
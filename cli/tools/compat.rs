@@ -0,0 +1,554 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Statically scans a Node project or npm package for the Node built-in
+//! modules and APIs it uses which Deno's Node compatibility layer doesn't
+//! provide, so that a migration can be estimated before running the code.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::SourceRange;
+use deno_ast::SourceRangedForSpanned;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde::Serialize;
+use deno_core::serde_json;
+use deno_runtime::deno_node::is_builtin_node_module;
+
+use crate::args::CompatFlags;
+use crate::colors;
+use crate::util::path::is_script_ext;
+
+/// Node built-in modules which Deno doesn't provide.
+const UNSUPPORTED_MODULES: &[&str] = &[
+  "inspector/promises",
+  "sea",
+  "sqlite",
+  "test/reporters",
+  "trace_events",
+];
+
+/// Exports of the built-in modules which throw a "not implemented" error.
+// NOTE: keep in sync with the `notImplemented` calls in `ext/node/polyfills`
+const UNSUPPORTED_APIS: &[(&str, &str)] = &[
+  ("cluster", "disconnect"),
+  ("cluster", "fork"),
+  ("cluster", "setupPrimary"),
+  ("crypto", "getFipsCrypto"),
+  ("crypto", "publicDecrypt"),
+  ("crypto", "secureHeapUsed"),
+  ("crypto", "setEngine"),
+  ("crypto", "setFipsCrypto"),
+  ("http2", "createSecureServer"),
+  ("http2", "getDefaultSettings"),
+  ("http2", "getPackedSettings"),
+  ("http2", "getUnpackedSettings"),
+  ("inspector", "waitForDebugger"),
+  ("repl", "start"),
+  ("test", "after"),
+  ("test", "afterEach"),
+  ("test", "before"),
+  ("test", "beforeEach"),
+  ("test", "describe"),
+  ("test", "it"),
+  ("test", "run"),
+  ("tls", "createSecurePair"),
+  ("v8", "getHeapCodeStatistics"),
+  ("v8", "getHeapSnapshot"),
+  ("v8", "getHeapSpaceStatistics"),
+  ("v8", "stopCoverage"),
+  ("v8", "takeCoverage"),
+  ("v8", "writeHeapSnapshot"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Finding {
+  /// The built-in module, without the `node:` prefix.
+  module: String,
+  /// The unsupported export of the module, or `None` when the whole module
+  /// is unsupported.
+  api: Option<String>,
+  specifier: ModuleSpecifier,
+  line: usize,
+  column: usize,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CompatReport {
+  files_checked: usize,
+  /// The number of uses of each built-in module.
+  builtin_modules: BTreeMap<String, usize>,
+  findings: Vec<Finding>,
+}
+
+#[allow(clippy::print_stdout)]
+pub fn compat(compat_flags: CompatFlags) -> Result<(), AnyError> {
+  let cwd = std::env::current_dir().context("Failed getting cwd")?;
+  let roots = if compat_flags.paths.is_empty() {
+    vec![cwd]
+  } else {
+    compat_flags.paths.iter().map(|p| cwd.join(p)).collect()
+  };
+
+  let mut report = CompatReport {
+    files_checked: 0,
+    builtin_modules: BTreeMap::new(),
+    findings: Vec::new(),
+  };
+  for path in collect_files(&roots)? {
+    let specifier = ModuleSpecifier::from_file_path(&path).unwrap();
+    let text = std::fs::read_to_string(&path)
+      .with_context(|| format!("Failed reading '{}'", path.display()))?;
+    let usages = match analyze_source(&specifier, text) {
+      Ok(usages) => usages,
+      Err(err) => {
+        log::warn!(
+          "{} Skipping '{}': {:#}",
+          colors::yellow("Warning"),
+          path.display(),
+          err
+        );
+        continue;
+      }
+    };
+    report.files_checked += 1;
+    for usage in usages {
+      if usage.api.is_none() {
+        *report
+          .builtin_modules
+          .entry(usage.module.clone())
+          .or_default() += 1;
+      }
+      if is_unsupported(&usage.module, usage.api.as_deref()) {
+        report.findings.push(usage);
+      }
+    }
+  }
+
+  if compat_flags.json {
+    println!("{}", serde_json::to_string_pretty(&report)?);
+  } else {
+    print_report(&report);
+  }
+  if !report.findings.is_empty() {
+    bail!(
+      "Found {} use{} of Node APIs which aren't supported",
+      report.findings.len(),
+      plural(report.findings.len())
+    );
+  }
+  Ok(())
+}
+
+#[allow(clippy::print_stdout)]
+fn print_report(report: &CompatReport) {
+  if !report.builtin_modules.is_empty() {
+    println!("{}", colors::bold("Node built-in modules:"));
+    for (module, count) in &report.builtin_modules {
+      let status = if UNSUPPORTED_MODULES.contains(&module.as_str()) {
+        colors::red("unsupported").to_string()
+      } else {
+        colors::green("supported").to_string()
+      };
+      println!(
+        "  node:{} {} {}",
+        module,
+        colors::gray(format!("({count} import{})", plural(*count))),
+        status
+      );
+    }
+    println!();
+  }
+  if !report.findings.is_empty() {
+    println!("{}", colors::bold("Unsupported APIs:"));
+    for finding in &report.findings {
+      let name = match &finding.api {
+        Some(api) => format!("node:{} {}", finding.module, api),
+        None => format!("node:{}", finding.module),
+      };
+      println!(
+        "  {}\n    {}",
+        colors::red(name),
+        colors::gray(format!(
+          "at {}:{}:{}",
+          finding.specifier, finding.line, finding.column
+        ))
+      );
+    }
+    println!();
+  }
+  println!(
+    "Checked {} file{}",
+    report.files_checked,
+    plural(report.files_checked)
+  );
+}
+
+fn plural(count: usize) -> &'static str {
+  if count == 1 {
+    ""
+  } else {
+    "s"
+  }
+}
+
+fn is_unsupported(module: &str, api: Option<&str>) -> bool {
+  match api {
+    Some(api) => UNSUPPORTED_APIS.contains(&(module, api)),
+    None => UNSUPPORTED_MODULES.contains(&module),
+  }
+}
+
+/// Collects the script files of the paths. The `node_modules` directories
+/// within the paths are skipped, but a path may point into one to check an
+/// installed package.
+fn collect_files(roots: &[PathBuf]) -> Result<Vec<PathBuf>, AnyError> {
+  let mut files = Vec::new();
+  for root in roots {
+    let entries = walkdir::WalkDir::new(root)
+      .sort_by_file_name()
+      .into_iter()
+      .filter_entry(|entry| {
+        entry.depth() == 0
+          || !matches!(
+            entry.file_name().to_str(),
+            Some("node_modules" | ".git")
+          )
+      });
+    for entry in entries {
+      let entry = entry
+        .with_context(|| format!("Failed reading '{}'", root.display()))?;
+      if entry.file_type().is_file() && is_script_ext(entry.path()) {
+        files.push(entry.into_path());
+      }
+    }
+  }
+  Ok(files)
+}
+
+/// Returns the built-in module of the specifier, without the `node:` prefix.
+fn builtin_module(specifier: &str) -> Option<&str> {
+  if let Some(module) = specifier.strip_prefix("node:") {
+    return Some(module);
+  }
+  // these modules can only be imported with the prefix
+  let prefix_only = ["sea", "sqlite", "test", "test/reporters"];
+  (!prefix_only.contains(&specifier)
+    && (is_builtin_node_module(specifier)
+      || UNSUPPORTED_MODULES.contains(&specifier)))
+  .then_some(specifier)
+}
+
+/// Finds the imports of built-in modules and the uses of their exports.
+fn analyze_source(
+  specifier: &ModuleSpecifier,
+  text: String,
+) -> Result<Vec<Finding>, AnyError> {
+  let parsed_source = deno_ast::parse_program(deno_ast::ParseParams {
+    specifier: specifier.clone(),
+    text: text.into(),
+    media_type: MediaType::from_specifier(specifier),
+    capture_tokens: false,
+    scope_analysis: false,
+    maybe_syntax: None,
+  })?;
+  let mut collector = BuiltinUsageCollector::default();
+  parsed_source.program_ref().visit_with(&mut collector);
+  let text_info = parsed_source.text_info_lazy();
+  Ok(
+    collector
+      .usages
+      .into_iter()
+      .map(|(module, api, range)| {
+        let position = text_info.line_and_column_display(range.start);
+        Finding {
+          module,
+          api,
+          specifier: specifier.clone(),
+          line: position.line_number,
+          column: position.column_number,
+        }
+      })
+      .collect(),
+  )
+}
+
+/// Collects the uses of the built-in modules, which are the imports of the
+/// modules and the uses of their exports through named imports, namespace or
+/// default imports and `require` calls. Bindings aren't scoped, which is
+/// precise enough for an estimate.
+#[derive(Default)]
+struct BuiltinUsageCollector {
+  /// The local names bound to a whole built-in module.
+  bindings: HashMap<String, String>,
+  usages: Vec<(String, Option<String>, SourceRange)>,
+}
+
+impl BuiltinUsageCollector {
+  fn add_module(&mut self, module: &str, range: SourceRange) {
+    self.usages.push((module.to_string(), None, range));
+  }
+
+  fn add_api(&mut self, module: &str, api: &str, range: SourceRange) {
+    self
+      .usages
+      .push((module.to_string(), Some(api.to_string()), range));
+  }
+
+  fn add_object_pattern(&mut self, module: &str, pattern: &ast::ObjectPat) {
+    for prop in &pattern.props {
+      match prop {
+        ast::ObjectPatProp::KeyValue(kv) => {
+          if let Some(name) = prop_name(&kv.key) {
+            self.add_api(module, &name, kv.key.range());
+          }
+        }
+        ast::ObjectPatProp::Assign(assign) => {
+          self.add_api(module, &assign.key.sym, assign.key.range());
+        }
+        ast::ObjectPatProp::Rest(_) => {}
+      }
+    }
+  }
+}
+
+/// Returns the built-in module of a `require("...")` or `import("...")`
+/// call, also when the dynamic import is awaited.
+fn required_module(expr: &ast::Expr) -> Option<(String, SourceRange)> {
+  match expr {
+    ast::Expr::Await(await_expr) => {
+      required_module_call(await_expr.arg.as_call()?)
+    }
+    ast::Expr::Call(call) => required_module_call(call),
+    _ => None,
+  }
+}
+
+fn required_module_call(call: &ast::CallExpr) -> Option<(String, SourceRange)> {
+  let is_require = match &call.callee {
+    ast::Callee::Expr(callee) => {
+      matches!(&**callee, ast::Expr::Ident(ident) if ident.sym == *"require")
+    }
+    ast::Callee::Import(_) => true,
+    ast::Callee::Super(_) => false,
+  };
+  if !is_require || call.args.len() != 1 {
+    return None;
+  }
+  let ast::Expr::Lit(ast::Lit::Str(specifier)) = &*call.args[0].expr else {
+    return None;
+  };
+  let module = builtin_module(&specifier.value)?;
+  Some((module.to_string(), call.range()))
+}
+
+fn prop_name(key: &ast::PropName) -> Option<String> {
+  match key {
+    ast::PropName::Ident(ident) => Some(ident.sym.to_string()),
+    ast::PropName::Str(str) => Some(str.value.to_string()),
+    _ => None,
+  }
+}
+
+impl Visit for BuiltinUsageCollector {
+  fn visit_import_decl(&mut self, import_decl: &ast::ImportDecl) {
+    let Some(module) = builtin_module(&import_decl.src.value) else {
+      return;
+    };
+    self.add_module(module, import_decl.src.range());
+    for specifier in &import_decl.specifiers {
+      match specifier {
+        ast::ImportSpecifier::Named(named) => {
+          let name = match &named.imported {
+            Some(ast::ModuleExportName::Ident(ident)) => ident.sym.to_string(),
+            Some(ast::ModuleExportName::Str(str)) => str.value.to_string(),
+            None => named.local.sym.to_string(),
+          };
+          self.add_api(module, &name, named.range());
+        }
+        ast::ImportSpecifier::Default(default) => {
+          self
+            .bindings
+            .insert(default.local.sym.to_string(), module.to_string());
+        }
+        ast::ImportSpecifier::Namespace(namespace) => {
+          self
+            .bindings
+            .insert(namespace.local.sym.to_string(), module.to_string());
+        }
+      }
+    }
+  }
+
+  fn visit_named_export(&mut self, export: &ast::NamedExport) {
+    let Some(src) = &export.src else {
+      return;
+    };
+    let Some(module) = builtin_module(&src.value) else {
+      return;
+    };
+    self.add_module(module, src.range());
+    for specifier in &export.specifiers {
+      if let ast::ExportSpecifier::Named(named) = specifier {
+        if let ast::ModuleExportName::Ident(ident) = &named.orig {
+          self.add_api(module, &ident.sym, named.range());
+        }
+      }
+    }
+  }
+
+  fn visit_export_all(&mut self, export: &ast::ExportAll) {
+    if let Some(module) = builtin_module(&export.src.value) {
+      self.add_module(module, export.src.range());
+    }
+  }
+
+  fn visit_var_declarator(&mut self, declarator: &ast::VarDeclarator) {
+    if let Some((module, _)) =
+      declarator.init.as_deref().and_then(required_module)
+    {
+      match &declarator.name {
+        ast::Pat::Ident(ident) => {
+          self.bindings.insert(ident.id.sym.to_string(), module);
+        }
+        ast::Pat::Object(pattern) => {
+          self.add_object_pattern(&module, pattern);
+        }
+        _ => {}
+      }
+    }
+    declarator.visit_children_with(self);
+  }
+
+  fn visit_call_expr(&mut self, call: &ast::CallExpr) {
+    if let Some((module, range)) = required_module_call(call) {
+      self.add_module(&module, range);
+    }
+    call.visit_children_with(self);
+  }
+
+  fn visit_member_expr(&mut self, member_expr: &ast::MemberExpr) {
+    let module = match &*member_expr.obj {
+      ast::Expr::Ident(ident) => self.bindings.get(&*ident.sym).cloned(),
+      // `require("v8").getHeapSnapshot`
+      obj => required_module(obj).map(|(module, _)| module),
+    };
+    if let Some(module) = module {
+      let name = match &member_expr.prop {
+        ast::MemberProp::Ident(ident) => Some(ident.sym.to_string()),
+        ast::MemberProp::Computed(computed) => match &*computed.expr {
+          ast::Expr::Lit(ast::Lit::Str(str)) => Some(str.value.to_string()),
+          _ => None,
+        },
+        ast::MemberProp::PrivateName(_) => None,
+      };
+      if let Some(name) = name {
+        self.add_api(&module, &name, member_expr.prop.range());
+      }
+    }
+    member_expr.visit_children_with(self);
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn analyze(text: &str) -> Vec<(String, Option<String>, usize)> {
+    let specifier = ModuleSpecifier::parse("file:///mod.js").unwrap();
+    analyze_source(&specifier, text.to_string())
+      .unwrap()
+      .into_iter()
+      .map(|finding| (finding.module, finding.api, finding.line))
+      .collect()
+  }
+
+  #[test]
+  fn test_analyze_source() {
+    let usages = analyze(
+      r#"import { getHeapSnapshot, cachedDataVersionTag } from "node:v8";
+import * as cluster from "cluster";
+import "./local.js";
+const { fork } = require("child_process");
+const trace = require("trace_events");
+cluster.fork();
+export { createSecurePair } from "tls";
+require("repl").start();
+"#,
+    );
+    assert_eq!(
+      usages,
+      vec![
+        ("v8".to_string(), None, 1),
+        ("v8".to_string(), Some("getHeapSnapshot".to_string()), 1),
+        (
+          "v8".to_string(),
+          Some("cachedDataVersionTag".to_string()),
+          1
+        ),
+        ("cluster".to_string(), None, 2),
+        ("child_process".to_string(), Some("fork".to_string()), 4),
+        ("child_process".to_string(), None, 4),
+        ("trace_events".to_string(), None, 5),
+        ("cluster".to_string(), Some("fork".to_string()), 6),
+        ("tls".to_string(), None, 7),
+        ("tls".to_string(), Some("createSecurePair".to_string()), 7),
+        ("repl".to_string(), Some("start".to_string()), 8),
+        ("repl".to_string(), None, 8),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_builtin_module() {
+    assert_eq!(builtin_module("node:fs"), Some("fs"));
+    assert_eq!(builtin_module("fs/promises"), Some("fs/promises"));
+    assert_eq!(builtin_module("trace_events"), Some("trace_events"));
+    assert_eq!(builtin_module("node:sqlite"), Some("sqlite"));
+    assert_eq!(builtin_module("sqlite"), None);
+    assert_eq!(builtin_module("test"), None);
+    assert_eq!(builtin_module("express"), None);
+  }
+
+  #[test]
+  fn test_is_unsupported() {
+    assert!(is_unsupported("trace_events", None));
+    assert!(!is_unsupported("fs", None));
+    assert!(is_unsupported("v8", Some("getHeapSnapshot")));
+    assert!(!is_unsupported("v8", Some("cachedDataVersionTag")));
+  }
+
+  #[test]
+  fn test_collect_files() {
+    use std::path::Path;
+
+    let temp_dir = test_util::TempDir::new();
+    temp_dir.create_dir_all("pkg/node_modules/dep");
+    temp_dir.write("pkg/index.js", "");
+    temp_dir.write("pkg/README.md", "");
+    temp_dir.write("pkg/node_modules/dep/index.js", "");
+    let files = collect_files(&[temp_dir.path().join("pkg").to_path_buf()])
+      .unwrap()
+      .into_iter()
+      .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+      .collect::<Vec<_>>();
+    assert_eq!(files, vec!["index.js"]);
+
+    let files = collect_files(&[temp_dir
+      .path()
+      .join("pkg/node_modules/dep")
+      .to_path_buf()])
+    .unwrap();
+    assert_eq!(files.len(), 1);
+    assert!(Path::new(&files[0]).ends_with("dep/index.js"));
+  }
+}
@@ -1,11 +1,14 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use crate::args::check_warn_tsconfig;
+use crate::args::jsr_url;
 use crate::args::CompileFlags;
 use crate::args::Flags;
+use crate::cache::ParsedSourceCache;
 use crate::factory::CliFactory;
 use crate::http_util::HttpClientProvider;
 use crate::standalone::is_standalone_binary;
+use crate::util::file_watcher;
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
@@ -13,20 +16,134 @@ use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
 use deno_graph::GraphKind;
+use deno_graph::ModuleGraph;
 use deno_terminal::colors;
 use eszip::EszipRelativeFileBaseUrl;
 use rand::Rng;
+use std::collections::BTreeSet;
+use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
 
 use super::installer::infer_name_from_url;
 
+mod package;
+
 pub async fn compile(
   flags: Arc<Flags>,
   compile_flags: CompileFlags,
 ) -> Result<(), AnyError> {
   let factory = CliFactory::from_flags(flags);
+  let output_path = compile_with_factory(&factory, &compile_flags).await?;
+  if let Some(kind) = compile_flags.installer {
+    let cli_options = factory.cli_options()?;
+    let version = compile_flags
+      .installer_version
+      .clone()
+      .or_else(|| {
+        cli_options
+          .start_dir
+          .maybe_deno_json()
+          .and_then(|config| config.json.version.clone())
+      })
+      .unwrap_or_else(|| "0.1.0".to_string());
+    let target = compile_flags.resolve_target();
+    let license = compile_flags
+      .installer_license
+      .as_ref()
+      .map(|path| cli_options.initial_cwd().join(path));
+    let icon = compile_flags
+      .icon
+      .as_ref()
+      .map(|path| cli_options.initial_cwd().join(path));
+    let package_path = package::create_installer(&package::InstallerOptions {
+      kind,
+      executable: &output_path,
+      target: &target,
+      version: &version,
+      license: license.as_deref(),
+      icon: icon.as_deref(),
+    })?;
+    log::info!("{} {}", colors::green("Package"), package_path.display());
+  }
+  Ok(())
+}
+
+/// Rebuilds the executable when the local modules of its module graph
+/// change, optionally running it after each build with `--watch-run`. The
+/// parsed modules are kept between the builds, so only the changed ones are
+/// parsed again.
+pub async fn compile_with_watch(
+  flags: Arc<Flags>,
+  compile_flags: CompileFlags,
+) -> Result<(), AnyError> {
+  if compile_flags.watch_run && compile_flags.resolve_target() != env!("TARGET")
+  {
+    bail!("--watch-run can't run an executable compiled for another target.");
+  }
+  let parsed_source_cache = Arc::new(ParsedSourceCache::default());
+  file_watcher::watch_func(
+    flags,
+    file_watcher::PrintConfig::new(
+      "Compile",
+      compile_flags
+        .watch
+        .as_ref()
+        .map(|w| !w.no_clear_screen)
+        .unwrap_or(true),
+    ),
+    move |flags, watcher_communicator, changed_paths| {
+      let compile_flags = compile_flags.clone();
+      let parsed_source_cache = parsed_source_cache.clone();
+      Ok(async move {
+        for path in changed_paths.into_iter().flatten() {
+          if let Ok(specifier) = ModuleSpecifier::from_file_path(&path) {
+            parsed_source_cache.free(&specifier);
+          }
+        }
+        let factory = CliFactory::from_flags_for_watcher(
+          flags,
+          watcher_communicator.clone(),
+        )
+        .with_parsed_source_cache(parsed_source_cache);
+        let cli_options = factory.cli_options()?;
+        let _ = watcher_communicator.watch_paths(cli_options.watch_paths());
+        let output_path =
+          compile_with_factory(&factory, &compile_flags).await?;
+        if compile_flags.watch_run {
+          // the arguments are embedded in the executable, so it's run
+          // without any. It's killed when the watcher restarts.
+          let status = tokio::process::Command::new(&output_path)
+            .kill_on_drop(true)
+            .status()
+            .await
+            .with_context(|| {
+              format!("Failed running '{}'", output_path.display())
+            })?;
+          if !status.success() {
+            log::warn!(
+              "{} '{}' exited with {}",
+              colors::yellow("Warning"),
+              output_path.display(),
+              status
+            );
+          }
+        }
+        Ok(())
+      })
+    },
+  )
+  .await
+}
+
+/// Compiles the executable, returning the path it was written to.
+async fn compile_with_factory(
+  factory: &CliFactory,
+  compile_flags: &CompileFlags,
+) -> Result<PathBuf, AnyError> {
   let cli_options = factory.cli_options()?;
   let module_graph_creator = factory.module_graph_creator().await?;
   let parsed_source_cache = factory.parsed_source_cache();
@@ -39,6 +156,11 @@ pub async fn compile(
     for side_module in &compile_flags.include {
       vec.push(resolve_url_or_path(side_module, cli_options.initial_cwd())?);
     }
+    if compile_flags.reproducible {
+      // the included modules are embedded in the order of the roots
+      vec[1..].sort();
+      vec.dedup();
+    }
     vec
   };
 
@@ -56,11 +178,31 @@ pub async fn compile(
 
   let output_path = resolve_compile_executable_output_path(
     http_client,
-    &compile_flags,
+    compile_flags,
     cli_options.initial_cwd(),
   )
   .await?;
 
+  // the graph adds the integrity of new remote modules to the lockfile, so
+  // take the ones it had before
+  let locked_remote_modules = if compile_flags.require_lock_integrity {
+    let Some(lockfile) = cli_options.maybe_lockfile() else {
+      bail!(
+        "--require-lock-integrity requires a lockfile. Add a deno.json or pass --lock."
+      );
+    };
+    Some(
+      lockfile
+        .lock()
+        .remote()
+        .keys()
+        .cloned()
+        .collect::<HashSet<_>>(),
+    )
+  } else {
+    None
+  };
+
   let graph = Arc::try_unwrap(
     module_graph_creator
       .create_graph_and_maybe_check(module_roots.clone())
@@ -77,6 +219,9 @@ pub async fn compile(
   } else {
     graph
   };
+  if let Some(locked_remote_modules) = &locked_remote_modules {
+    verify_lock_integrity(&graph, locked_remote_modules)?;
+  }
 
   let ts_config_for_emit = cli_options
     .resolve_ts_config_for_emit(deno_config::deno_json::TsConfigType::Emit)?;
@@ -136,7 +281,7 @@ pub async fn compile(
       eszip,
       root_dir_url,
       module_specifier,
-      &compile_flags,
+      compile_flags,
       cli_options,
     )
     .await
@@ -157,6 +302,22 @@ pub async fn compile(
     })
   });
 
+  let write_result = write_result.and_then(|_| {
+    if !compile_flags.reproducible {
+      return Ok(());
+    }
+    std::fs::File::options()
+      .write(true)
+      .open(&temp_path)
+      .and_then(|file| file.set_modified(reproducible_mtime()))
+      .with_context(|| {
+        format!(
+          "Setting the modification time of temporary file '{}'",
+          temp_path.display()
+        )
+      })
+  });
+
   let write_result = write_result.and_then(|_| {
     std::fs::rename(&temp_path, &output_path).with_context(|| {
       format!(
@@ -173,7 +334,124 @@ pub async fn compile(
     return Err(err);
   }
 
-  Ok(())
+  Ok(output_path)
+}
+
+/// The modification time of executables built with `--reproducible`, which
+/// follows the `SOURCE_DATE_EPOCH` convention of reproducible builds.
+fn reproducible_mtime() -> SystemTime {
+  let secs = std::env::var("SOURCE_DATE_EPOCH")
+    .ok()
+    .and_then(|value| value.trim().parse::<u64>().ok())
+    .unwrap_or(0);
+  SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+}
+
+/// Verifies for `--require-lock-integrity` that the remote modules embedded
+/// in the executable had an integrity in the lockfile and are imported by
+/// specifiers which always resolve to the same content.
+fn verify_lock_integrity(
+  graph: &ModuleGraph,
+  locked_remote_modules: &HashSet<String>,
+) -> Result<(), AnyError> {
+  // the integrity of jsr modules is verified with their package manifests
+  let is_remote = |specifier: &ModuleSpecifier| {
+    matches!(specifier.scheme(), "http" | "https")
+      && !specifier.as_str().starts_with(jsr_url().as_str())
+  };
+  let mut problems = BTreeSet::new();
+  let mut add_problem = |specifier: &ModuleSpecifier, reason: &str| {
+    problems.insert(format!("  {specifier} {reason}"));
+  };
+  for specifier in graph.redirects.keys().filter(|s| is_remote(s)) {
+    if let Some(reason) = mutable_specifier_reason(specifier) {
+      add_problem(specifier, reason);
+    }
+  }
+  for module in graph.modules() {
+    let specifier = module.specifier();
+    if !is_remote(specifier) {
+      continue;
+    }
+    if !locked_remote_modules.contains(specifier.as_str()) {
+      add_problem(specifier, "has no integrity in the lockfile");
+    }
+    if let Some(reason) = mutable_specifier_reason(specifier) {
+      add_problem(specifier, reason);
+    }
+  }
+  if problems.is_empty() {
+    return Ok(());
+  }
+  bail!(
+    "The executable would embed remote modules which aren't pinned by the lockfile:\n{}\n\nImport them with versioned specifiers and add them to the lockfile with `deno cache` before compiling.",
+    problems.into_iter().collect::<Vec<_>>().join("\n")
+  )
+}
+
+const MUTABLE_REFS: &[&str] = &[
+  "latest", "main", "master", "HEAD", "next", "canary", "dev", "develop",
+  "trunk",
+];
+
+/// Returns why the content of a remote specifier can change between builds,
+/// which is when it references a branch or a tag like `@latest` or doesn't
+/// pin a version on a well-known CDN.
+fn mutable_specifier_reason(
+  specifier: &ModuleSpecifier,
+) -> Option<&'static str> {
+  const BRANCH: &str = "references a branch or tag which can change";
+  const UNVERSIONED: &str = "doesn't pin a version";
+  let segments = specifier
+    .path_segments()
+    .map(|segments| segments.collect::<Vec<_>>())
+    .unwrap_or_default();
+  let has_mutable_version = |segment: &str| {
+    segment.rsplit_once('@').is_some_and(|(name, version)| {
+      !name.is_empty() && MUTABLE_REFS.contains(&version)
+    })
+  };
+  if segments.iter().any(|segment| has_mutable_version(segment)) {
+    return Some(BRANCH);
+  }
+  // whether the name of the package at the start of the segments has a version
+  let is_package_versioned = |segments: &[&str]| {
+    let name = match segments {
+      [scope, name, ..] if scope.starts_with('@') => name,
+      [name, ..] => name,
+      [] => return true,
+    };
+    name.contains('@')
+  };
+  match specifier.host_str()? {
+    "deno.land" => match segments.as_slice() {
+      ["std", ..] => Some(UNVERSIONED),
+      ["x", name, ..] if !name.contains('@') => Some(UNVERSIONED),
+      _ => None,
+    },
+    "raw.githubusercontent.com" => match segments.as_slice() {
+      [_, _, git_ref, ..] if MUTABLE_REFS.contains(git_ref) => Some(BRANCH),
+      _ => None,
+    },
+    "esm.sh" | "unpkg.com" | "cdn.skypack.dev" => {
+      let mut segments = segments.as_slice();
+      // the build version of esm.sh, like `/v135/`
+      if let [first, rest @ ..] = segments {
+        if lazy_regex::regex_is_match!(r"^(v\d+|stable)$", first) {
+          segments = rest;
+        }
+      }
+      (!is_package_versioned(segments)).then_some(UNVERSIONED)
+    }
+    "cdn.jsdelivr.net" => match segments.as_slice() {
+      ["npm", rest @ ..] => {
+        (!is_package_versioned(rest)).then_some(UNVERSIONED)
+      }
+      ["gh", _, repo, ..] if !repo.contains('@') => Some(UNVERSIONED),
+      _ => None,
+    },
+    _ => None,
+  }
 }
 
 /// This function writes out a final binary to specified path. If output path
@@ -365,6 +643,14 @@ mod test {
         no_terminal: false,
         icon: None,
         include: vec![],
+        no_licenses: false,
+        require_lock_integrity: false,
+        watch: None,
+        watch_run: false,
+        installer: None,
+        installer_version: None,
+        installer_license: None,
+        reproducible: false,
       },
       &std::env::current_dir().unwrap(),
     )
@@ -390,6 +676,14 @@ mod test {
         include: vec![],
         icon: None,
         no_terminal: false,
+        no_licenses: false,
+        require_lock_integrity: false,
+        watch: None,
+        watch_run: false,
+        installer: None,
+        installer_version: None,
+        installer_license: None,
+        reproducible: false,
       },
       &std::env::current_dir().unwrap(),
     )
@@ -425,6 +719,42 @@ mod test {
     run_test("my-exe-0.1.2", Some("linux"), "my-exe-0.1.2");
   }
 
+  #[test]
+  fn test_mutable_specifier_reason() {
+    fn reason(specifier: &str) -> Option<&'static str> {
+      mutable_specifier_reason(&ModuleSpecifier::parse(specifier).unwrap())
+    }
+    let unversioned = Some("doesn't pin a version");
+    let branch = Some("references a branch or tag which can change");
+    assert_eq!(reason("https://deno.land/std/path/mod.ts"), unversioned);
+    assert_eq!(reason("https://deno.land/std@0.224.0/path/mod.ts"), None);
+    assert_eq!(reason("https://deno.land/x/oak/mod.ts"), unversioned);
+    assert_eq!(reason("https://deno.land/x/oak@v16.1.0/mod.ts"), None);
+    assert_eq!(
+      reason("https://raw.githubusercontent.com/acme/lib/main/mod.ts"),
+      branch
+    );
+    assert_eq!(
+      reason("https://raw.githubusercontent.com/acme/lib/4f1c2d3/mod.ts"),
+      None
+    );
+    assert_eq!(reason("https://esm.sh/preact"), unversioned);
+    assert_eq!(reason("https://esm.sh/preact@10.19.2"), None);
+    assert_eq!(reason("https://esm.sh/v135/preact@10.19.2/hooks"), None);
+    assert_eq!(reason("https://esm.sh/@preact/signals@1.2.0"), None);
+    assert_eq!(reason("https://esm.sh/@preact/signals"), unversioned);
+    assert_eq!(reason("https://esm.sh/preact@latest"), branch);
+    assert_eq!(
+      reason("https://cdn.jsdelivr.net/npm/lodash-es"),
+      unversioned
+    );
+    assert_eq!(
+      reason("https://cdn.jsdelivr.net/gh/acme/lib@main/x.js"),
+      branch
+    );
+    assert_eq!(reason("https://example.com/src/main/mod.ts"), None);
+  }
+
   #[test]
   fn test_resolve_root_dir_from_specifiers() {
     fn resolve(start: &str, specifiers: &[&str]) -> String {
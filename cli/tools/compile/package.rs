@@ -0,0 +1,452 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use sha2::Digest;
+
+use crate::args::CompileInstaller;
+
+/// What goes into the installer package of a compiled executable.
+pub struct InstallerOptions<'a> {
+  pub kind: CompileInstaller,
+  pub executable: &'a Path,
+  pub target: &'a str,
+  pub version: &'a str,
+  pub license: Option<&'a Path>,
+  /// Icon of the package in the list of installed programs on Windows.
+  pub icon: Option<&'a Path>,
+}
+
+impl InstallerOptions<'_> {
+  fn name(&self) -> String {
+    self
+      .executable
+      .file_stem()
+      .unwrap()
+      .to_string_lossy()
+      .to_string()
+  }
+}
+
+/// Creates the installer package next to the executable, returning its path.
+pub fn create_installer(
+  options: &InstallerOptions,
+) -> Result<PathBuf, AnyError> {
+  match options.kind {
+    CompileInstaller::Deb => create_deb(options),
+    CompileInstaller::Pkg => create_pkg(options),
+    CompileInstaller::Msi => create_msi(options),
+  }
+}
+
+fn create_deb(options: &InstallerOptions) -> Result<PathBuf, AnyError> {
+  if !options.target.contains("-linux-") {
+    bail!(
+      "--installer=deb requires a Linux target, not {}",
+      options.target
+    );
+  }
+  let architecture = deb_architecture(options.target)?;
+  let name = options.name();
+  let package = deb_package_name(&name);
+  let output_path = options.executable.with_file_name(format!(
+    "{}_{}_{}.deb",
+    package, options.version, architecture
+  ));
+
+  let executable = std::fs::read(options.executable)?;
+  let mut data_files =
+    vec![(format!("usr/bin/{name}"), 0o755, executable.as_slice())];
+  let license = options
+    .license
+    .map(std::fs::read)
+    .transpose()
+    .context("Failed reading the license file")?;
+  if let Some(license) = &license {
+    data_files.push((
+      format!("usr/share/doc/{package}/copyright"),
+      0o644,
+      license.as_slice(),
+    ));
+  }
+  let control = deb_control(
+    &package,
+    options.version,
+    architecture,
+    executable.len() as u64,
+  );
+  let control_files = vec![("control".to_string(), 0o644, control.as_bytes())];
+
+  let mut ar = b"!<arch>\n".to_vec();
+  append_ar_entry(&mut ar, "debian-binary", b"2.0\n");
+  append_ar_entry(&mut ar, "control.tar.gz", &tar_gz(&control_files)?);
+  append_ar_entry(&mut ar, "data.tar.gz", &tar_gz(&data_files)?);
+  std::fs::write(&output_path, ar)
+    .with_context(|| format!("Failed writing {}", output_path.display()))?;
+  Ok(output_path)
+}
+
+fn deb_architecture(target: &str) -> Result<&'static str, AnyError> {
+  Ok(match target.split('-').next().unwrap_or_default() {
+    "x86_64" => "amd64",
+    "aarch64" => "arm64",
+    arch => bail!("Unsupported architecture for --installer=deb: {arch}"),
+  })
+}
+
+/// Debian package names are lowercase and can't contain underscores.
+fn deb_package_name(name: &str) -> String {
+  name.to_lowercase().replace('_', "-")
+}
+
+fn deb_control(
+  package: &str,
+  version: &str,
+  architecture: &str,
+  installed_size: u64,
+) -> String {
+  format!(
+    "Package: {package}
+Version: {version}
+Architecture: {architecture}
+Maintainer: {package} maintainers
+Installed-Size: {}
+Section: utils
+Priority: optional
+Description: {package}
+ Compiled with deno compile.
+",
+    installed_size.div_ceil(1024)
+  )
+}
+
+fn tar_gz(files: &[(String, u32, &[u8])]) -> Result<Vec<u8>, AnyError> {
+  let mut builder =
+    tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+  for (path, mode, contents) in files {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(*mode);
+    header.set_uid(0);
+    header.set_gid(0);
+    header.set_mtime(0);
+    header.set_cksum();
+    builder.append_data(&mut header, path, *contents)?;
+  }
+  Ok(builder.into_inner()?.finish()?)
+}
+
+/// Appends a file to an `ar` archive, the container format of deb packages.
+fn append_ar_entry(ar: &mut Vec<u8>, name: &str, contents: &[u8]) {
+  // name, modification time, owner, group, mode, size and end marker
+  let header = format!(
+    "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+    name,
+    0,
+    0,
+    0,
+    "100644",
+    contents.len()
+  );
+  ar.extend_from_slice(header.as_bytes());
+  ar.extend_from_slice(contents);
+  // entries are aligned to two bytes
+  if contents.len() % 2 == 1 {
+    ar.push(b'\n');
+  }
+}
+
+fn create_pkg(options: &InstallerOptions) -> Result<PathBuf, AnyError> {
+  if !options.target.contains("-apple-darwin") {
+    bail!(
+      "--installer=pkg requires a macOS target, not {}",
+      options.target
+    );
+  }
+  let name = options.name();
+  let output_path = options
+    .executable
+    .with_file_name(format!("{}-{}.pkg", name, options.version));
+  let staging_dir = tempfile::tempdir()?;
+  let bin_dir = staging_dir.path().join("usr/local/bin");
+  std::fs::create_dir_all(&bin_dir)?;
+  std::fs::copy(options.executable, bin_dir.join(&name))?;
+  if let Some(license) = options.license {
+    let doc_dir = staging_dir.path().join("usr/local/share/doc").join(&name);
+    std::fs::create_dir_all(&doc_dir)?;
+    std::fs::copy(license, doc_dir.join("LICENSE"))
+      .context("Failed copying the license file")?;
+  }
+  run_packaging_tool(
+    Command::new("pkgbuild")
+      .arg("--root")
+      .arg(staging_dir.path())
+      .arg("--identifier")
+      .arg(format!("land.deno.compiled.{name}"))
+      .arg("--version")
+      .arg(options.version)
+      .arg("--install-location")
+      .arg("/")
+      .arg(&output_path),
+    "pkgbuild, which is only available on macOS",
+  )?;
+  Ok(output_path)
+}
+
+fn create_msi(options: &InstallerOptions) -> Result<PathBuf, AnyError> {
+  if !options.target.contains("-windows-") {
+    bail!(
+      "--installer=msi requires a Windows target, not {}",
+      options.target
+    );
+  }
+  let output_path = options.executable.with_extension("msi");
+  let staging_dir = tempfile::tempdir()?;
+  let wxs_path = staging_dir.path().join("installer.wxs");
+  std::fs::write(&wxs_path, wix_source(options)?)?;
+  run_packaging_tool(
+    Command::new("wix")
+      .arg("build")
+      .arg(&wxs_path)
+      .arg("-arch")
+      .arg(if options.target.starts_with("aarch64") {
+        "arm64"
+      } else {
+        "x64"
+      })
+      .arg("-o")
+      .arg(&output_path),
+    "the WiX Toolset v4 CLI (wix)",
+  )?;
+  Ok(output_path)
+}
+
+/// The WiX source of an MSI package which installs the executable to the
+/// program files and adds its directory to the PATH.
+fn wix_source(options: &InstallerOptions) -> Result<String, AnyError> {
+  let name = options.name();
+  // MSI versions have up to three numeric fields
+  let version = options.version.split(['-', '+']).next().unwrap_or_default();
+  if version.split('.').count() > 3
+    || version.split('.').any(|part| part.parse::<u16>().is_err())
+  {
+    bail!(
+      "--installer=msi requires a numeric version like 1.2.3, not {}",
+      options.version
+    );
+  }
+  let mut license = String::new();
+  let mut license_ref = String::new();
+  if let Some(path) = options.license {
+    let path = std::fs::canonicalize(path)
+      .context("Failed resolving the license file")?;
+    license = format!(
+      r#"
+        <Component Id="License">
+          <File Source="{}" Name="LICENSE" KeyPath="yes" />
+        </Component>"#,
+      escape_xml(&path.to_string_lossy())
+    );
+    license_ref = "\n      <ComponentRef Id=\"License\" />".to_string();
+  }
+  let mut icon = String::new();
+  if let Some(path) = options.icon {
+    let path =
+      std::fs::canonicalize(path).context("Failed resolving the icon file")?;
+    icon = format!(
+      r#"
+    <Icon Id="icon.ico" SourceFile="{}" />
+    <Property Id="ARPPRODUCTICON" Value="icon.ico" />"#,
+      escape_xml(&path.to_string_lossy())
+    );
+  }
+  let executable = std::fs::canonicalize(options.executable)?;
+  Ok(format!(
+    r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- generated by deno compile --installer=msi -->
+<Wix xmlns="http://wixtoolset.org/schemas/v4/wxs">
+  <Package Name="{name}" Manufacturer="{name}" Version="{version}" UpgradeCode="{upgrade_code}" Scope="perMachine">
+    <MajorUpgrade DowngradeErrorMessage="A newer version of {name} is already installed." />
+    <MediaTemplate EmbedCab="yes" />{icon}
+    <StandardDirectory Id="ProgramFiles64Folder">
+      <Directory Id="INSTALLFOLDER" Name="{name}">
+        <Component Id="Executable">
+          <File Source="{executable}" KeyPath="yes" />
+          <Environment Id="Path" Name="PATH" Value="[INSTALLFOLDER]" Permanent="no" Part="last" Action="set" System="yes" />
+        </Component>{license}
+      </Directory>
+    </StandardDirectory>
+    <Feature Id="Main">
+      <ComponentRef Id="Executable" />{license_ref}
+    </Feature>
+  </Package>
+</Wix>
+"#,
+    name = escape_xml(&name),
+    upgrade_code = upgrade_code(&name),
+    executable = escape_xml(&executable.to_string_lossy()),
+  ))
+}
+
+/// A GUID derived from the name, so new versions of the package replace the
+/// installed one.
+fn upgrade_code(name: &str) -> String {
+  let hash = sha2::Sha256::digest(format!("deno-compile-msi:{name}"));
+  let mut bytes = [0u8; 16];
+  bytes.copy_from_slice(&hash[..16]);
+  uuid::Builder::from_bytes(bytes)
+    .with_variant(uuid::Variant::RFC4122)
+    .with_version(uuid::Version::Sha1)
+    .into_uuid()
+    .hyphenated()
+    .to_string()
+    .to_uppercase()
+}
+
+fn escape_xml(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+fn run_packaging_tool(
+  command: &mut Command,
+  tool_description: &str,
+) -> Result<(), AnyError> {
+  let program = command.get_program().to_string_lossy().to_string();
+  let output = match command.output() {
+    Ok(output) => output,
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+      bail!("Creating this installer requires {tool_description}.");
+    }
+    Err(err) => {
+      return Err(err).with_context(|| format!("Failed running {program}"));
+    }
+  };
+  if !output.status.success() {
+    let _ = std::io::stderr().write_all(&output.stderr);
+    bail!("{program} failed with {}", output.status);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use std::io::Read;
+
+  use test_util::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn test_deb_package() {
+    let temp_dir = TempDir::new();
+    let executable = temp_dir.path().join("my_tool").to_path_buf();
+    std::fs::write(&executable, "binary").unwrap();
+    let license = temp_dir.path().join("LICENSE").to_path_buf();
+    std::fs::write(&license, "MIT").unwrap();
+    let output_path = create_installer(&InstallerOptions {
+      kind: CompileInstaller::Deb,
+      executable: &executable,
+      target: "x86_64-unknown-linux-gnu",
+      version: "1.2.3",
+      license: Some(&license),
+      icon: None,
+    })
+    .unwrap();
+    assert_eq!(
+      output_path,
+      temp_dir
+        .path()
+        .join("my-tool_1.2.3_amd64.deb")
+        .to_path_buf()
+    );
+
+    let deb = std::fs::read(&output_path).unwrap();
+    assert!(deb.starts_with(b"!<arch>\ndebian-binary   0 "));
+    // the data archive is the last entry
+    let data_start = deb
+      .windows(b"data.tar.gz".len())
+      .position(|window| window == b"data.tar.gz")
+      .unwrap()
+      + 60;
+    let mut archive =
+      tar::Archive::new(flate2::read::GzDecoder::new(&deb[data_start..]));
+    let mut entries = archive
+      .entries()
+      .unwrap()
+      .map(|entry| {
+        let mut entry = entry.unwrap();
+        let mut contents = String::new();
+        entry.read_to_string(&mut contents).unwrap();
+        (
+          entry.path().unwrap().to_string_lossy().to_string(),
+          contents,
+        )
+      })
+      .collect::<Vec<_>>();
+    entries.sort();
+    assert_eq!(
+      entries,
+      vec![
+        ("usr/bin/my_tool".to_string(), "binary".to_string()),
+        (
+          "usr/share/doc/my-tool/copyright".to_string(),
+          "MIT".to_string()
+        ),
+      ]
+    );
+
+    let err = create_installer(&InstallerOptions {
+      kind: CompileInstaller::Deb,
+      executable: &executable,
+      target: "x86_64-apple-darwin",
+      version: "1.2.3",
+      license: None,
+      icon: None,
+    })
+    .unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "--installer=deb requires a Linux target, not x86_64-apple-darwin"
+    );
+  }
+
+  #[test]
+  fn test_wix_source() {
+    let temp_dir = TempDir::new();
+    let executable = temp_dir.path().join("tool.exe").to_path_buf();
+    std::fs::write(&executable, "binary").unwrap();
+    let options = InstallerOptions {
+      kind: CompileInstaller::Msi,
+      executable: &executable,
+      target: "x86_64-pc-windows-msvc",
+      version: "1.2.3-beta",
+      license: None,
+      icon: None,
+    };
+    let source = wix_source(&options).unwrap();
+    assert!(
+      source.contains(r#"Name="tool" Manufacturer="tool" Version="1.2.3""#)
+    );
+    assert!(source.contains(r#"Value="[INSTALLFOLDER]""#));
+    assert!(source.contains(&upgrade_code("tool")));
+    assert_eq!(upgrade_code("tool").len(), 36);
+
+    let options = InstallerOptions {
+      version: "1.2.3.4",
+      ..options
+    };
+    assert!(wix_source(&options).is_err());
+  }
+}
@@ -0,0 +1,987 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::Value;
+use jsonc_parser::ast;
+use jsonc_parser::common::Ranged;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::args::ConfigFlag;
+use crate::args::ConfigFlags;
+use crate::args::Flags;
+use crate::colors;
+use crate::util::display;
+use crate::util::fs::canonicalize_path;
+
+static CONFIG_FILE_SCHEMA: Lazy<Value> = Lazy::new(|| {
+  serde_json::from_str(include_str!("../schemas/config-file.v1.json")).unwrap()
+});
+
+/// Fields which still work, but have a replacement. The fields which aren't
+/// in the schema anymore are reported here instead of as unknown.
+const DEPRECATED_FIELDS: &[(&str, &str)] = &[
+  ("fmt.options", "move its properties directly into \"fmt\""),
+  ("fmt.files", "use \"fmt.include\" and \"fmt.exclude\""),
+  ("lint.files", "use \"lint.include\" and \"lint.exclude\""),
+  ("test.files", "use \"test.include\" and \"test.exclude\""),
+  ("bench.files", "use \"bench.include\" and \"bench.exclude\""),
+  (
+    "compilerOptions.experimentalDecorators",
+    "use the standard decorators, which don't need an option",
+  ),
+  (
+    "compilerOptions.emitDecoratorMetadata",
+    "use the standard decorators, which don't need an option",
+  ),
+];
+
+/// The unstable features which can be enabled in addition to the granular
+/// flags of the runtime.
+const CLI_UNSTABLE_FEATURES: &[&str] = &[
+  "sloppy-imports",
+  "byonm",
+  "bare-node-builtins",
+  "fmt-component",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigProblemSeverity {
+  Error,
+  Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigProblem {
+  /// The dotted path of the field, like `fmt.lineWidth`.
+  pub path: String,
+  pub code: &'static str,
+  pub severity: ConfigProblemSeverity,
+  pub message: String,
+}
+
+impl ConfigProblem {
+  fn error(path: &str, code: &'static str, message: String) -> Self {
+    Self {
+      path: path.to_string(),
+      code,
+      severity: ConfigProblemSeverity::Error,
+      message,
+    }
+  }
+
+  fn warning(path: &str, code: &'static str, message: String) -> Self {
+    Self {
+      path: path.to_string(),
+      code,
+      severity: ConfigProblemSeverity::Warning,
+      message,
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConfigFileReport {
+  file: PathBuf,
+  problems: Vec<ConfigProblem>,
+}
+
+pub fn config(
+  flags: Arc<Flags>,
+  config_flags: ConfigFlags,
+) -> Result<(), AnyError> {
+  match config_flags {
+    ConfigFlags::Check { json } => check(&flags, json),
+    ConfigFlags::Get { key } => get(&flags, &key),
+    ConfigFlags::Set { key, value, json } => set(&flags, &key, value, json),
+  }
+}
+
+/// The config file of the `--config` flag, or the closest one to the
+/// current directory.
+fn resolve_config_file(
+  flags: &Flags,
+  subcommand: &str,
+) -> Result<Option<PathBuf>, AnyError> {
+  let cwd = std::env::current_dir()?;
+  match &flags.config_flag {
+    ConfigFlag::Path(path) => Ok(Some(cwd.join(path))),
+    ConfigFlag::Discover => Ok(discover_config_file(&cwd)),
+    ConfigFlag::Disabled => {
+      bail!("deno config {subcommand} requires a config file.")
+    }
+  }
+}
+
+fn config_file_not_found() -> AnyError {
+  let cwd = std::env::current_dir().unwrap_or_default();
+  anyhow!(
+    "Could not find a deno.json or deno.jsonc in '{}' or its parents.",
+    cwd.display()
+  )
+}
+
+fn get(flags: &Flags, key: &str) -> Result<(), AnyError> {
+  let file =
+    resolve_config_file(flags, "get")?.ok_or_else(config_file_not_found)?;
+  let text = std::fs::read_to_string(&file)
+    .with_context(|| format!("Failed reading '{}'", file.display()))?;
+  let root = jsonc_parser::parse_to_serde_value(&text, &Default::default())
+    .with_context(|| format!("Failed parsing '{}'", file.display()))?
+    .unwrap_or(Value::Null);
+  let mut value = &root;
+  for segment in parse_key(key)? {
+    let next = match value {
+      Value::Object(object) => object.get(&segment),
+      Value::Array(items) => segment
+        .parse::<usize>()
+        .ok()
+        .and_then(|index| items.get(index)),
+      _ => None,
+    };
+    value = next.with_context(|| {
+      format!("\"{key}\" is not set in '{}'", file.display())
+    })?;
+  }
+  // strings are printed as is, so they can be used by scripts
+  let output = match value {
+    Value::String(text) => text.clone(),
+    value => serde_json::to_string_pretty(value)?,
+  };
+  display::write_to_stdout_ignore_sigpipe(format!("{output}\n").as_bytes())?;
+  Ok(())
+}
+
+fn set(
+  flags: &Flags,
+  key: &str,
+  value: String,
+  json: bool,
+) -> Result<(), AnyError> {
+  let path = parse_key(key)?;
+  // strings are set as they are, so values like `123` or `null` given for a
+  // string field don't change its type
+  let value = if json {
+    serde_json::from_str(&value)
+      .with_context(|| format!("Failed parsing the value as JSON: {value}"))?
+  } else {
+    Value::String(value)
+  };
+  let file = match resolve_config_file(flags, "set")? {
+    Some(file) => file,
+    None => std::env::current_dir()?.join("deno.json"),
+  };
+  let text = match std::fs::read_to_string(&file) {
+    Ok(text) => text,
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+    Err(err) => {
+      return Err(err)
+        .with_context(|| format!("Failed reading '{}'", file.display()))
+    }
+  };
+  let new_text = set_value(&text, &path, &value)
+    .with_context(|| format!("Failed updating '{}'", file.display()))?;
+  std::fs::write(&file, new_text)
+    .with_context(|| format!("Failed writing '{}'", file.display()))?;
+  log::info!(
+    "{} {} in {}",
+    colors::green("Set"),
+    key,
+    colors::gray(file.display())
+  );
+  Ok(())
+}
+
+/// Splits a key like `compilerOptions.lib` into its fields. Fields containing
+/// dots can be quoted, like `imports."./utils.ts"`.
+fn parse_key(key: &str) -> Result<Vec<String>, AnyError> {
+  let mut fields = Vec::new();
+  let mut chars = key.chars().peekable();
+  loop {
+    let mut field = String::new();
+    if chars.peek() == Some(&'"') {
+      chars.next();
+      loop {
+        match chars.next() {
+          Some('"') => break,
+          Some(c) => field.push(c),
+          None => bail!("Unterminated quote in the key \"{key}\"."),
+        }
+      }
+      if !matches!(chars.peek(), None | Some('.')) {
+        bail!("Expected a dot after the quoted field in \"{key}\".");
+      }
+    } else {
+      while let Some(c) = chars.next_if(|c| *c != '.') {
+        field.push(c);
+      }
+      if field.is_empty() {
+        bail!("Invalid key \"{key}\", it contains an empty field.");
+      }
+    }
+    fields.push(field);
+    if chars.next().is_none() {
+      return Ok(fields);
+    }
+  }
+}
+
+/// Sets the value of the field at the path, only changing the text of that
+/// field so the comments and the formatting of the file are kept.
+fn set_value(
+  text: &str,
+  path: &[String],
+  value: &Value,
+) -> Result<String, AnyError> {
+  let text = if text.trim().is_empty() { "{}\n" } else { text };
+  let ast =
+    jsonc_parser::parse_to_ast(text, &Default::default(), &Default::default())?;
+  let Some(ast::Value::Object(root)) = ast.value else {
+    bail!("Expected the config file to contain an object.");
+  };
+  let indent_unit = root
+    .properties
+    .first()
+    .map(|prop| line_indent(text, prop.range.start))
+    .filter(|indent| !indent.is_empty())
+    .unwrap_or("  ");
+
+  let mut object = &root;
+  for (index, field) in path.iter().enumerate() {
+    let prop = object
+      .properties
+      .iter()
+      .find(|prop| prop.name.as_str() == field.as_str());
+    let Some(prop) = prop else {
+      // create the missing objects along with the field
+      let value =
+        path[index + 1..]
+          .iter()
+          .rev()
+          .fold(value.clone(), |value, field| {
+            let mut object = serde_json::Map::new();
+            object.insert(field.clone(), value);
+            Value::Object(object)
+          });
+      return Ok(insert_property(text, object, field, &value, indent_unit));
+    };
+    if index == path.len() - 1 {
+      let range = prop.value.range();
+      let value_text = if is_single_line(text, object) {
+        serde_json::to_string(value)?
+      } else {
+        to_indented_json(
+          value,
+          line_indent(text, prop.range.start),
+          indent_unit,
+        )
+      };
+      return Ok(splice(text, range.start..range.end, &value_text));
+    }
+    match &prop.value {
+      ast::Value::Object(child) => object = child,
+      _ => bail!(
+        "Can't set \"{}\", \"{}\" isn't an object.",
+        path.join("."),
+        path[..=index].join(".")
+      ),
+    }
+  }
+  unreachable!()
+}
+
+fn insert_property(
+  text: &str,
+  object: &ast::Object,
+  field: &str,
+  value: &Value,
+  indent_unit: &str,
+) -> String {
+  let key = serde_json::to_string(field).unwrap();
+  if let Some(last) = object.properties.last() {
+    if is_single_line(text, object) {
+      let value = serde_json::to_string(value).unwrap();
+      return splice(
+        text,
+        last.range.end..last.range.end,
+        &format!(", {key}: {value}"),
+      );
+    }
+    let indent = line_indent(text, last.range.start);
+    let value = to_indented_json(value, indent, indent_unit);
+    // an existing trailing comma ends up after the new property
+    splice(
+      text,
+      last.range.end..last.range.end,
+      &format!(",\n{indent}{key}: {value}"),
+    )
+  } else {
+    let indent = line_indent(text, object.range.start);
+    let inner = (object.range.start + 1)..(object.range.end - 1);
+    // keep the comments of an empty object
+    let range = if text[inner.clone()].trim().is_empty() {
+      inner
+    } else {
+      inner.start..inner.start
+    };
+    let value =
+      to_indented_json(value, &format!("{indent}{indent_unit}"), indent_unit);
+    splice(
+      text,
+      range,
+      &format!("\n{indent}{indent_unit}{key}: {value}\n{indent}"),
+    )
+  }
+}
+
+fn splice(text: &str, range: std::ops::Range<usize>, new_text: &str) -> String {
+  format!("{}{}{}", &text[..range.start], new_text, &text[range.end..])
+}
+
+fn is_single_line(text: &str, object: &ast::Object) -> bool {
+  !object.properties.is_empty()
+    && !text[object.range.start..object.range.end].contains('\n')
+}
+
+/// The whitespace at the start of the line containing the position.
+fn line_indent(text: &str, pos: usize) -> &str {
+  let line_start = text[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+  let line = &text[line_start..];
+  &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Pretty prints the value for a field at the indentation.
+fn to_indented_json(value: &Value, indent: &str, indent_unit: &str) -> String {
+  let mut output = Vec::new();
+  let formatter =
+    serde_json::ser::PrettyFormatter::with_indent(indent_unit.as_bytes());
+  let mut serializer =
+    serde_json::Serializer::with_formatter(&mut output, formatter);
+  value.serialize(&mut serializer).unwrap();
+  String::from_utf8(output)
+    .unwrap()
+    .replace('\n', &format!("\n{indent}"))
+}
+
+fn check(flags: &Flags, json: bool) -> Result<(), AnyError> {
+  let root =
+    resolve_config_file(flags, "check")?.ok_or_else(config_file_not_found)?;
+
+  let mut reports = Vec::new();
+  // the canonical paths of the files which were checked, so that a member
+  // pointing back into the workspace isn't checked again
+  let mut visited = HashSet::from([canonicalize_path(&root)?]);
+  let mut files = vec![root];
+  while let Some(file) = files.pop() {
+    let text = std::fs::read_to_string(&file)
+      .with_context(|| format!("Failed reading '{}'", file.display()))?;
+    let value = jsonc_parser::parse_to_serde_value(&text, &Default::default())
+      .with_context(|| format!("Failed parsing '{}'", file.display()))?
+      .unwrap_or(Value::Null);
+    let mut problems = validate_config(&value);
+    // the members are checked after their workspace
+    let dir = file.parent().unwrap();
+    let mut member_files = Vec::new();
+    for (path, member) in workspace_members(&value) {
+      let Some(member_file) = discover_config_file_in_dir(&dir.join(member))
+      else {
+        continue;
+      };
+      if visited.insert(canonicalize_path(&member_file)?) {
+        member_files.push(member_file);
+      } else {
+        problems.push(ConfigProblem::error(
+          &path,
+          "workspace-cycle",
+          format!(
+            "The member \"{member}\" points to {}, which is already part of the workspace.",
+            member_file.display()
+          ),
+        ));
+      }
+    }
+    files.extend(member_files.into_iter().rev());
+    reports.push(ConfigFileReport { problems, file });
+  }
+
+  let problems = reports.iter().flat_map(|report| &report.problems);
+  let errors = problems
+    .clone()
+    .filter(|p| p.severity == ConfigProblemSeverity::Error)
+    .count();
+  let warnings = problems.count() - errors;
+  if json {
+    display::write_json_to_stdout(&reports)?;
+  } else {
+    for report in &reports {
+      for problem in &report.problems {
+        let severity = match problem.severity {
+          ConfigProblemSeverity::Error => colors::red_bold("error"),
+          ConfigProblemSeverity::Warning => colors::yellow_bold("warning"),
+        };
+        log::error!(
+          "{}[{}]: {}\n    {} {}",
+          severity,
+          problem.code,
+          problem.message,
+          colors::cyan("at"),
+          colors::gray(format!("{} ({})", problem.path, report.file.display())),
+        );
+      }
+    }
+    let files = match reports.len() {
+      1 => "1 config file".to_string(),
+      n => format!("{n} config files"),
+    };
+    if errors == 0 && warnings == 0 {
+      log::info!("Checked {files}");
+    } else if errors == 0 {
+      log::info!("Found {} in {files}", plural(warnings, "warning"));
+    }
+  }
+  if errors > 0 {
+    bail!(
+      "Found {} and {} in the config",
+      plural(errors, "error"),
+      plural(warnings, "warning")
+    );
+  }
+  Ok(())
+}
+
+fn plural(count: usize, word: &str) -> String {
+  if count == 1 {
+    format!("1 {word}")
+  } else {
+    format!("{count} {word}s")
+  }
+}
+
+fn discover_config_file(cwd: &Path) -> Option<PathBuf> {
+  cwd.ancestors().find_map(discover_config_file_in_dir)
+}
+
+fn discover_config_file_in_dir(dir: &Path) -> Option<PathBuf> {
+  ["deno.json", "deno.jsonc"]
+    .into_iter()
+    .map(|name| dir.join(name))
+    .find(|path| path.is_file())
+}
+
+/// The directories of the workspace members along with the paths of their
+/// fields, skipping globs.
+fn workspace_members(value: &Value) -> Vec<(String, &str)> {
+  let (path, members) = match &value["workspace"] {
+    Value::Array(members) => ("workspace", members),
+    Value::Object(workspace) => match workspace.get("members") {
+      Some(Value::Array(members)) => ("workspace.members", members),
+      _ => return Vec::new(),
+    },
+    _ => return Vec::new(),
+  };
+  members
+    .iter()
+    .enumerate()
+    .filter_map(|(index, member)| {
+      Some((format!("{path}[{index}]"), member.as_str()?))
+    })
+    .filter(|(_, member)| !member.contains('*'))
+    .collect()
+}
+
+/// Validates a config file against its schema, also reporting the unknown
+/// fields and the deprecated ones.
+pub fn validate_config(value: &Value) -> Vec<ConfigProblem> {
+  let mut problems = Vec::new();
+  if !value.is_object() {
+    problems.push(ConfigProblem::error(
+      "",
+      "invalid-type",
+      format!("Expected an object, but found {}.", value_type(value)),
+    ));
+    return problems;
+  }
+  validate_value(value, &CONFIG_FILE_SCHEMA, "", &mut problems);
+  validate_unstable_features(value, &mut problems);
+  // the boolean form of "nodeModulesDir" is still accepted
+  if let Some(enabled) = value["nodeModulesDir"].as_bool() {
+    problems.push(ConfigProblem::warning(
+      "nodeModulesDir",
+      "deprecated",
+      if enabled {
+        "\"nodeModulesDir\": true is deprecated, use \"auto\", or \"manual\" with a package.json.".to_string()
+      } else {
+        "\"nodeModulesDir\": false is deprecated, use \"none\".".to_string()
+      },
+    ));
+  }
+  problems
+}
+
+fn validate_value(
+  value: &Value,
+  schema: &Value,
+  path: &str,
+  problems: &mut Vec<ConfigProblem>,
+) {
+  if let Some((_, replacement)) =
+    DEPRECATED_FIELDS.iter().find(|(field, _)| *field == path)
+  {
+    problems.push(ConfigProblem::warning(
+      path,
+      "deprecated",
+      format!("\"{path}\" is deprecated, {replacement}."),
+    ));
+    if schema.is_null() {
+      return;
+    }
+  } else if schema["deprecated"] == Value::Bool(true) {
+    problems.push(ConfigProblem::warning(
+      path,
+      "deprecated",
+      format!("\"{path}\" is deprecated."),
+    ));
+  }
+
+  if let Some(branches) = schema["oneOf"]
+    .as_array()
+    .or_else(|| schema["anyOf"].as_array())
+  {
+    validate_branches(value, branches, path, problems);
+    return;
+  }
+
+  if let Some(expected) = schema.get("type") {
+    let types = match expected {
+      Value::String(expected) => vec![expected.as_str()],
+      Value::Array(expected) => {
+        expected.iter().filter_map(|t| t.as_str()).collect()
+      }
+      _ => vec![],
+    };
+    if !types.is_empty() && !types.iter().any(|t| is_type(value, t)) {
+      problems.push(ConfigProblem::error(
+        path,
+        "invalid-type",
+        format!(
+          "Expected {}, but found {}.",
+          types.join(" or "),
+          value_type(value)
+        ),
+      ));
+      return;
+    }
+  }
+
+  if let Some(allowed) = schema["enum"].as_array() {
+    if !allowed.contains(value) {
+      let allowed_str = allowed
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+      let suggestion = value.as_str().and_then(|value| {
+        suggest(value, allowed.iter().filter_map(|v| v.as_str()))
+      });
+      problems.push(ConfigProblem::error(
+        path,
+        "invalid-value",
+        match suggestion {
+          Some(suggestion) => format!(
+            "Invalid value {value}, did you mean \"{suggestion}\"? Expected one of {allowed_str}."
+          ),
+          None => {
+            format!("Invalid value {value}, expected one of {allowed_str}.")
+          }
+        },
+      ));
+      return;
+    }
+  }
+
+  match value {
+    Value::String(text) => {
+      if let Some(pattern) = schema["pattern"].as_str() {
+        if let Ok(regex) = Regex::new(pattern) {
+          if !regex.is_match(text) {
+            problems.push(ConfigProblem::error(
+              path,
+              "invalid-value",
+              format!("\"{text}\" doesn't match the pattern {pattern}."),
+            ));
+          }
+        }
+      }
+    }
+    Value::Array(items) => {
+      if !schema["items"].is_null() {
+        for (index, item) in items.iter().enumerate() {
+          validate_value(
+            item,
+            &schema["items"],
+            &format!("{path}[{index}]"),
+            problems,
+          );
+        }
+      }
+    }
+    Value::Object(object) => validate_object(object, schema, path, problems),
+    _ => {}
+  }
+}
+
+fn validate_object(
+  object: &serde_json::Map<String, Value>,
+  schema: &Value,
+  path: &str,
+  problems: &mut Vec<ConfigProblem>,
+) {
+  let properties = schema["properties"].as_object();
+  let pattern_properties = schema["patternProperties"].as_object();
+  if let Some(required) = schema["required"].as_array() {
+    for key in required.iter().filter_map(|key| key.as_str()) {
+      if !object.contains_key(key) {
+        problems.push(ConfigProblem::error(
+          path,
+          "missing-field",
+          format!("Missing the required field \"{key}\"."),
+        ));
+      }
+    }
+  }
+  for (key, value) in object {
+    let child_path = if path.is_empty() {
+      key.to_string()
+    } else {
+      format!("{path}.{key}")
+    };
+    if let Some(property) = properties.and_then(|p| p.get(key)) {
+      validate_value(value, property, &child_path, problems);
+      continue;
+    }
+    let pattern_property = pattern_properties.and_then(|patterns| {
+      patterns.iter().find_map(|(pattern, property)| {
+        Regex::new(pattern)
+          .ok()
+          .filter(|regex| regex.is_match(key))
+          .map(|_| property)
+      })
+    });
+    if let Some(property) = pattern_property {
+      validate_value(value, property, &child_path, problems);
+      continue;
+    }
+    match &schema["additionalProperties"] {
+      Value::Bool(true) => {}
+      additional @ Value::Object(_) => {
+        validate_value(value, additional, &child_path, problems);
+      }
+      // objects without fixed fields, like "imports", are free form
+      Value::Null if properties.is_none() => {}
+      // "$schema" is used by editors
+      Value::Null if path.is_empty() && key == "$schema" => {}
+      _ => {
+        if DEPRECATED_FIELDS
+          .iter()
+          .any(|(field, _)| *field == child_path)
+        {
+          validate_value(value, &Value::Null, &child_path, problems);
+          continue;
+        }
+        let suggestion =
+          properties.and_then(|properties| suggest(key, properties.keys()));
+        problems.push(ConfigProblem::error(
+          &child_path,
+          "unknown-field",
+          match suggestion {
+            Some(suggestion) => {
+              format!("Unknown field \"{key}\", did you mean \"{suggestion}\"?")
+            }
+            None => format!("Unknown field \"{key}\"."),
+          },
+        ));
+      }
+    }
+  }
+}
+
+/// Reports the problems of the matching branch, or the ones of the first
+/// branch of the same type when none matches.
+fn validate_branches(
+  value: &Value,
+  branches: &[Value],
+  path: &str,
+  problems: &mut Vec<ConfigProblem>,
+) {
+  let mut closest = None;
+  for branch in branches {
+    let mut branch_problems = Vec::new();
+    validate_value(value, branch, path, &mut branch_problems);
+    let has_errors = branch_problems
+      .iter()
+      .any(|p| p.severity == ConfigProblemSeverity::Error);
+    if !has_errors {
+      problems.extend(branch_problems);
+      return;
+    }
+    let is_type_mismatch = branch_problems
+      .iter()
+      .any(|p| p.code == "invalid-type" && p.path == path);
+    if !is_type_mismatch && closest.is_none() {
+      closest = Some(branch_problems);
+    }
+  }
+  if let Some(closest) = closest {
+    problems.extend(closest);
+    return;
+  }
+  let expected = branches
+    .iter()
+    .flat_map(|branch| match &branch["type"] {
+      Value::String(t) => vec![t.as_str()],
+      Value::Array(types) => types.iter().filter_map(|t| t.as_str()).collect(),
+      _ => vec![],
+    })
+    .collect::<Vec<_>>();
+  problems.push(ConfigProblem::error(
+    path,
+    "invalid-type",
+    format!(
+      "Expected {}, but found {}.",
+      expected.join(" or "),
+      value_type(value)
+    ),
+  ));
+}
+
+fn validate_unstable_features(
+  value: &Value,
+  problems: &mut Vec<ConfigProblem>,
+) {
+  let Some(features) = value["unstable"].as_array() else {
+    return;
+  };
+  let known = crate::UNSTABLE_GRANULAR_FLAGS
+    .iter()
+    .map(|flag| flag.name)
+    .chain(CLI_UNSTABLE_FEATURES.iter().copied())
+    .collect::<Vec<_>>();
+  for (index, feature) in features.iter().enumerate() {
+    let Some(feature) = feature.as_str() else {
+      continue;
+    };
+    if known.contains(&feature) {
+      continue;
+    }
+    let path = format!("unstable[{index}]");
+    problems.push(ConfigProblem::error(
+      &path,
+      "unknown-unstable-feature",
+      match suggest(feature, known.iter()) {
+        Some(suggestion) => format!(
+          "Unknown unstable feature \"{feature}\", did you mean \"{suggestion}\"?"
+        ),
+        None => format!("Unknown unstable feature \"{feature}\"."),
+      },
+    ));
+  }
+}
+
+fn suggest<'a>(
+  text: &str,
+  candidates: impl IntoIterator<Item = impl AsRef<str> + 'a>,
+) -> Option<String> {
+  let max_distance = (text.len() / 3).max(2);
+  candidates
+    .into_iter()
+    .map(|candidate| {
+      let candidate = candidate.as_ref().to_string();
+      (
+        strsim::levenshtein(&text.to_lowercase(), &candidate.to_lowercase()),
+        candidate,
+      )
+    })
+    .filter(|(distance, _)| *distance <= max_distance)
+    .min_by_key(|(distance, _)| *distance)
+    .map(|(_, candidate)| candidate)
+}
+
+fn is_type(value: &Value, expected: &str) -> bool {
+  match expected {
+    "object" => value.is_object(),
+    "array" => value.is_array(),
+    "string" => value.is_string(),
+    "boolean" => value.is_boolean(),
+    "number" => value.is_number(),
+    "integer" => value.is_i64() || value.is_u64(),
+    "null" => value.is_null(),
+    _ => true,
+  }
+}
+
+fn value_type(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "boolean",
+    Value::Number(_) => "number",
+    Value::String(_) => "string",
+    Value::Array(_) => "array",
+    Value::Object(_) => "object",
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use deno_core::serde_json::json;
+
+  use super::*;
+
+  fn problems(value: Value) -> Vec<(String, &'static str)> {
+    validate_config(&value)
+      .into_iter()
+      .map(|problem| (problem.path, problem.code))
+      .collect()
+  }
+
+  #[test]
+  fn valid_config() {
+    assert_eq!(
+      problems(json!({
+        "$schema": "https://deno.land/x/deno/cli/schemas/config-file.v1.json",
+        "imports": { "@std/assert": "jsr:@std/assert@1" },
+        "fmt": { "lineWidth": 100, "include": ["src/"] },
+        "tasks": { "dev": "deno run main.ts" },
+        "lock": false,
+        "nodeModulesDir": "auto",
+        "unstable": ["kv"],
+      })),
+      vec![]
+    );
+  }
+
+  #[test]
+  fn unknown_fields_and_types() {
+    let problems = validate_config(&json!({
+      "fmt": { "lineWidht": 100, "useTabs": "yes" },
+      "lint": { "rules": { "tags": "recommended" } },
+      "unstable": ["kvv"],
+    }));
+    assert_eq!(
+      problems
+        .iter()
+        .map(|p| (p.path.as_str(), p.code))
+        .collect::<Vec<_>>(),
+      vec![
+        ("fmt.lineWidht", "unknown-field"),
+        ("fmt.useTabs", "invalid-type"),
+        ("lint.rules.tags", "invalid-type"),
+        ("unstable[0]", "unknown-unstable-feature"),
+      ]
+    );
+    assert_eq!(
+      problems[0].message,
+      "Unknown field \"lineWidht\", did you mean \"lineWidth\"?"
+    );
+    assert_eq!(problems[1].message, "Expected boolean, but found string.");
+    assert_eq!(
+      problems[3].message,
+      "Unknown unstable feature \"kvv\", did you mean \"kv\"?"
+    );
+  }
+
+  #[test]
+  fn deprecated_fields() {
+    let problems = validate_config(&json!({
+      "fmt": { "files": { "include": ["src/"] } },
+      "nodeModulesDir": false,
+    }));
+    assert_eq!(
+      problems
+        .iter()
+        .map(|p| (p.path.as_str(), p.severity))
+        .collect::<Vec<_>>(),
+      vec![
+        ("fmt.files", ConfigProblemSeverity::Warning),
+        ("nodeModulesDir", ConfigProblemSeverity::Warning),
+      ]
+    );
+    assert_eq!(
+      problems[0].message,
+      "\"fmt.files\" is deprecated, use \"fmt.include\" and \"fmt.exclude\"."
+    );
+  }
+
+  #[test]
+  fn parse_keys() {
+    assert_eq!(
+      parse_key("compilerOptions.strict").unwrap(),
+      vec!["compilerOptions", "strict"]
+    );
+    assert_eq!(
+      parse_key("imports.\"./utils.ts\"").unwrap(),
+      vec!["imports", "./utils.ts"]
+    );
+    assert!(parse_key("fmt..lineWidth").is_err());
+    assert!(parse_key("imports.\"./utils.ts").is_err());
+  }
+
+  #[track_caller]
+  fn assert_set(text: &str, key: &str, value: Value, expected: &str) {
+    let path = parse_key(key).unwrap();
+    assert_eq!(set_value(text, &path, &value).unwrap(), expected);
+  }
+
+  #[test]
+  fn set_values() {
+    // replaces the value, keeping the comments
+    assert_set(
+      "{\n  // the tasks\n  \"tasks\": {\n    \"dev\": \"deno run main.ts\" // dev\n  }\n}\n",
+      "tasks.dev",
+      json!("deno run --watch main.ts"),
+      "{\n  // the tasks\n  \"tasks\": {\n    \"dev\": \"deno run --watch main.ts\" // dev\n  }\n}\n",
+    );
+    // adds the missing objects at the indentation of the file
+    assert_set(
+      "{\n    \"tasks\": {}\n}\n",
+      "compilerOptions.strict",
+      json!(true),
+      "{\n    \"tasks\": {},\n    \"compilerOptions\": {\n        \"strict\": true\n    }\n}\n",
+    );
+    assert_set(
+      "{\n  \"tasks\": {}\n}\n",
+      "tasks.dev",
+      json!("deno run main.ts"),
+      "{\n  \"tasks\": {\n    \"dev\": \"deno run main.ts\"\n  }\n}\n",
+    );
+    assert_set(
+      "{ \"imports\": { \"a\": \"./a.ts\" } }\n",
+      "imports.b",
+      json!("./b.ts"),
+      "{ \"imports\": { \"a\": \"./a.ts\", \"b\": \"./b.ts\" } }\n",
+    );
+    assert_set("", "lock", json!(false), "{\n  \"lock\": false\n}\n");
+    let path = parse_key("lock.frozen").unwrap();
+    assert!(set_value("{ \"lock\": false }", &path, &json!(true)).is_err());
+  }
+
+  #[test]
+  fn enum_values() {
+    let problems = validate_config(&json!({ "nodeModulesDir": "autoo" }));
+    assert_eq!(problems.len(), 1);
+    assert_eq!(problems[0].code, "invalid-value");
+    assert!(problems[0].message.contains("did you mean \"auto\"?"));
+  }
+}
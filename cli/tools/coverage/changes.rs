@@ -0,0 +1,233 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::util::fs::canonicalize_path;
+
+/// The lines added or modified in the working tree since a git ref.
+#[derive(Debug, Default)]
+pub struct ChangedLines {
+  /// Zero based line indexes per file. `None` means the whole file is new
+  /// because it is not tracked by git yet.
+  files: HashMap<PathBuf, Option<HashSet<usize>>>,
+}
+
+impl ChangedLines {
+  pub fn since(git_ref: &str, cwd: &Path) -> Result<Self, AnyError> {
+    let root =
+      PathBuf::from(git(cwd, &["rev-parse", "--show-toplevel"])?.trim());
+    let root = canonicalize_path(&root).unwrap_or(root);
+    let diff = git(
+      &root,
+      &[
+        "diff",
+        "--unified=0",
+        "--no-color",
+        "--no-ext-diff",
+        "--no-prefix",
+        git_ref,
+        "--",
+      ],
+    )?;
+    let mut changed_lines = Self {
+      files: parse_diff(&diff)
+        .into_iter()
+        .map(|(path, lines)| (root.join(path), Some(lines)))
+        .collect(),
+    };
+    let untracked =
+      git(&root, &["ls-files", "-z", "--others", "--exclude-standard"])?;
+    for path in untracked.split('\0').filter(|path| !path.is_empty()) {
+      changed_lines.files.insert(root.join(path), None);
+    }
+    Ok(changed_lines)
+  }
+
+  /// Keeps only the items which are on a changed line of the file, which
+  /// removes all of them when the file has not changed.
+  pub fn retain<T>(
+    &self,
+    path: &Path,
+    items: &mut Vec<T>,
+    line_index: impl Fn(&T) -> usize,
+  ) {
+    match self.files.get(&normalize(path)) {
+      Some(Some(lines)) => {
+        items.retain(|item| lines.contains(&line_index(item)))
+      }
+      Some(None) => {}
+      None => items.clear(),
+    }
+  }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+  canonicalize_path(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn git(cwd: &Path, args: &[&str]) -> Result<String, AnyError> {
+  let output = Command::new("git")
+    .args(args)
+    .current_dir(cwd)
+    .output()
+    .context("Failed to run git, which is required by --changed-since")?;
+  if !output.status.success() {
+    bail!(
+      "git {} failed: {}",
+      args[0],
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+  }
+  Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Unquotes a path in the header of a diff, which git writes in double quotes
+/// with C-style escapes when it contains special or non-ASCII characters,
+/// and followed by a tab when it contains spaces.
+fn unquote_diff_path(path: &str) -> String {
+  let path = path.strip_suffix('\t').unwrap_or(path);
+  let Some(quoted) = path
+    .strip_prefix('"')
+    .and_then(|path| path.strip_suffix('"'))
+  else {
+    return path.to_string();
+  };
+  let mut bytes = Vec::with_capacity(quoted.len());
+  let mut input = quoted.bytes().peekable();
+  while let Some(byte) = input.next() {
+    if byte != b'\\' {
+      bytes.push(byte);
+      continue;
+    }
+    let Some(escaped) = input.next() else {
+      bytes.push(byte);
+      break;
+    };
+    match escaped {
+      b'a' => bytes.push(0x07),
+      b'b' => bytes.push(0x08),
+      b'f' => bytes.push(0x0c),
+      b'n' => bytes.push(b'\n'),
+      b'r' => bytes.push(b'\r'),
+      b't' => bytes.push(b'\t'),
+      b'v' => bytes.push(0x0b),
+      // an octal escape of a byte of a UTF-8 sequence, e.g. \303\251
+      b'0'..=b'7' => {
+        let mut value = u32::from(escaped - b'0');
+        for _ in 0..2 {
+          match input.peek() {
+            Some(digit @ b'0'..=b'7') => {
+              value = value * 8 + u32::from(digit - b'0');
+              input.next();
+            }
+            _ => break,
+          }
+        }
+        bytes.push(value as u8);
+      }
+      other => bytes.push(other),
+    }
+  }
+  String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Parses the output of `git diff --unified=0 --no-prefix` into the zero
+/// based indexes of the added lines per file path relative to the
+/// repository root. The paths are taken from the `+++` lines, which
+/// are unquoted.
+fn parse_diff(diff: &str) -> HashMap<String, HashSet<usize>> {
+  let mut files: HashMap<String, HashSet<usize>> = HashMap::new();
+  let mut current: Option<String> = None;
+  for line in diff.lines() {
+    if let Some(path) = line.strip_prefix("+++ ") {
+      current = (path != "/dev/null").then(|| unquote_diff_path(path));
+      continue;
+    }
+    let (Some(path), Some(hunk)) = (&current, line.strip_prefix("@@ -")) else {
+      continue;
+    };
+    // @@ -start[,count] +start[,count] @@
+    let Some(added) = hunk
+      .split(' ')
+      .nth(1)
+      .and_then(|range| range.strip_prefix('+'))
+    else {
+      continue;
+    };
+    let (start, count) = match added.split_once(',') {
+      Some((start, count)) => (start.parse::<usize>(), count.parse()),
+      None => (added.parse(), Ok(1)),
+    };
+    let (Ok(start), Ok(count)) = (start, count) else {
+      continue;
+    };
+    let lines = files.entry(path.clone()).or_default();
+    if count > 0 {
+      lines.extend((start - 1)..(start - 1 + count));
+    }
+  }
+  files
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_parse_diff() {
+    let diff = r#"diff --git foo.ts foo.ts
+index 3b18e51..a3f0d5c 100644
+--- foo.ts
++++ foo.ts
+@@ -1 +1 @@
+-export const a = 1;
++export const a = 2;
+@@ -4,0 +5,2 @@ export function b() {
++  console.log("b");
++  return 1;
+@@ -10,2 +12,0 @@
+-// removed
+-// removed
+diff --git old.ts old.ts
+deleted file mode 100644
+--- old.ts
++++ /dev/null
+@@ -1 +0,0 @@
+-export {};
+"#;
+    let files = parse_diff(diff);
+    assert_eq!(files.len(), 1);
+    assert_eq!(files["foo.ts"], HashSet::from([0, 4, 5]));
+  }
+
+  #[test]
+  fn test_parse_diff_quoted_paths() {
+    let diff = concat!(
+      "diff --git a b.ts a b.ts\n",
+      "--- a b.ts\t\n",
+      "+++ a b.ts\t\n",
+      "@@ -1 +1 @@\n",
+      "+export {};\n",
+      "diff --git \"caf\\303\\251.ts\" \"caf\\303\\251.ts\"\n",
+      "--- \"caf\\303\\251.ts\"\n",
+      "+++ \"caf\\303\\251.ts\"\n",
+      "@@ -2,0 +3 @@\n",
+      "+export {};\n",
+      "+++ \"with \\\"quote\\\".ts\"\n",
+      "@@ -0,0 +1 @@\n",
+      "+export {};\n",
+    );
+    let files = parse_diff(diff);
+    assert_eq!(files.len(), 3);
+    assert_eq!(files["a b.ts"], HashSet::from([0]));
+    assert_eq!(files["café.ts"], HashSet::from([2]));
+    assert_eq!(files["with \"quote\".ts"], HashSet::from([0]));
+  }
+}
@@ -0,0 +1,238 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! The coverage of previous runs, which is stored as a JSON line per run, so
+//! that the changes in coverage can be shown without a dashboard.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::colors;
+
+/// The characters of a sparkline, from 0% to 100%.
+const SPARKLINE_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(
+  Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct LineCounts {
+  pub found: usize,
+  pub hit: usize,
+}
+
+impl LineCounts {
+  fn percent(&self) -> Option<f64> {
+    (self.found > 0).then(|| self.hit as f64 / self.found as f64 * 100.0)
+  }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverageHistoryEntry {
+  pub timestamp: String,
+  pub lines: LineCounts,
+  /// The line counts of the files directly in each directory, relative to
+  /// the current directory.
+  pub directories: BTreeMap<String, LineCounts>,
+}
+
+impl CoverageHistoryEntry {
+  pub fn new() -> Self {
+    Self {
+      timestamp: chrono::Utc::now().to_rfc3339(),
+      ..Default::default()
+    }
+  }
+
+  pub fn add_file(&mut self, url: &Url, cwd: &Path, counts: LineCounts) {
+    let directory = directory_name(url, cwd);
+    let directory_counts = self.directories.entry(directory).or_default();
+    directory_counts.found += counts.found;
+    directory_counts.hit += counts.hit;
+    self.lines.found += counts.found;
+    self.lines.hit += counts.hit;
+  }
+}
+
+fn directory_name(url: &Url, cwd: &Path) -> String {
+  let Ok(path) = url.to_file_path() else {
+    let url = url.as_str();
+    return url[..url.rfind('/').unwrap_or(url.len())].to_string();
+  };
+  let directory = path.parent().unwrap_or(&path);
+  match directory.strip_prefix(cwd) {
+    Ok(relative) if relative.as_os_str().is_empty() => ".".to_string(),
+    Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+    Err(_) => directory.to_string_lossy().replace('\\', "/"),
+  }
+}
+
+/// Reads the entries of the history file, skipping the lines which can't be
+/// parsed. A missing file is an empty history.
+pub fn read_history(
+  path: &Path,
+) -> Result<Vec<CoverageHistoryEntry>, AnyError> {
+  let text = match std::fs::read_to_string(path) {
+    Ok(text) => text,
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+      return Ok(Vec::new())
+    }
+    Err(err) => {
+      return Err(err)
+        .with_context(|| format!("Failed reading {}", path.display()))
+    }
+  };
+  Ok(
+    text
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .filter_map(|line| match serde_json::from_str(line) {
+        Ok(entry) => Some(entry),
+        Err(err) => {
+          log::debug!("Skipping coverage history entry: {:#}", err);
+          None
+        }
+      })
+      .collect(),
+  )
+}
+
+pub fn append_history(
+  path: &Path,
+  entry: &CoverageHistoryEntry,
+) -> Result<(), AnyError> {
+  let mut line = serde_json::to_string(entry)?;
+  line.push('\n');
+  File::options()
+    .create(true)
+    .append(true)
+    .open(path)
+    .and_then(|mut file| file.write_all(line.as_bytes()))
+    .with_context(|| format!("Failed writing {}", path.display()))
+}
+
+/// Prints the coverage of each directory with the change since the previous
+/// run and a sparkline of the last runs.
+#[allow(clippy::print_stdout)]
+pub fn print_trend(
+  current: &CoverageHistoryEntry,
+  previous: &[CoverageHistoryEntry],
+) {
+  let rows = current
+    .directories
+    .iter()
+    .map(|(directory, counts)| {
+      (
+        directory.as_str(),
+        *counts,
+        previous
+          .iter()
+          .map(|entry| entry.directories.get(directory).copied())
+          .collect::<Vec<_>>(),
+      )
+    })
+    .chain(std::iter::once((
+      "All files",
+      current.lines,
+      previous.iter().map(|entry| Some(entry.lines)).collect(),
+    )))
+    .collect::<Vec<_>>();
+  let name_max = rows
+    .iter()
+    .map(|(name, ..)| name.len())
+    .chain(std::iter::once("Directory".len()))
+    .max()
+    .unwrap();
+
+  println!();
+  println!(
+    "{}",
+    colors::bold(format!(
+      "Coverage trend of the last {} runs",
+      previous.len() + 1
+    ))
+  );
+  println!(" {:name_max$} | Line % | Change | History", "Directory");
+  for (name, counts, history) in rows {
+    let percent = counts.percent().unwrap_or(100.0);
+    let change = match history.last().copied().flatten() {
+      Some(previous) => {
+        format_change(percent - previous.percent().unwrap_or(100.0))
+      }
+      None => colors::gray("   new").to_string(),
+    };
+    let sparkline = history
+      .iter()
+      .map(|counts| counts.and_then(|counts| counts.percent()))
+      .chain(std::iter::once(Some(percent)))
+      .map(sparkline_char)
+      .collect::<String>();
+    println!(" {name:name_max$} | {percent:>6.1} | {change} | {sparkline}");
+  }
+}
+
+fn format_change(change: f64) -> String {
+  let text = format!("{change:>+6.1}");
+  if change > 0.05 {
+    colors::green(text).to_string()
+  } else if change < -0.05 {
+    colors::red(text).to_string()
+  } else {
+    text
+  }
+}
+
+/// A space stands for a run in which the directory had no covered files.
+fn sparkline_char(percent: Option<f64>) -> char {
+  match percent {
+    Some(percent) => {
+      let last = SPARKLINE_CHARS.len() - 1;
+      let index = (percent / 100.0 * last as f64).round() as usize;
+      SPARKLINE_CHARS[index.min(last)]
+    }
+    None => ' ',
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_sparkline_char() {
+    assert_eq!(sparkline_char(Some(0.0)), '▁');
+    assert_eq!(sparkline_char(Some(50.0)), '▅');
+    assert_eq!(sparkline_char(Some(100.0)), '█');
+    assert_eq!(sparkline_char(None), ' ');
+  }
+
+  #[test]
+  fn test_add_file() {
+    let cwd = if cfg!(windows) {
+      Path::new("C:\\project")
+    } else {
+      Path::new("/project")
+    };
+    let url = |path: &str| Url::from_file_path(cwd.join(path)).unwrap();
+    let mut entry = CoverageHistoryEntry::new();
+    entry.add_file(&url("main.ts"), cwd, LineCounts { found: 10, hit: 5 });
+    entry.add_file(&url("src/a.ts"), cwd, LineCounts { found: 4, hit: 4 });
+    entry.add_file(&url("src/b.ts"), cwd, LineCounts { found: 6, hit: 3 });
+    assert_eq!(entry.lines, LineCounts { found: 20, hit: 12 });
+    assert_eq!(
+      entry.directories.into_iter().collect::<Vec<_>>(),
+      vec![
+        (".".to_string(), LineCounts { found: 10, hit: 5 }),
+        ("src".to_string(), LineCounts { found: 10, hit: 7 }),
+      ]
+    );
+  }
+}
@@ -2,6 +2,7 @@
 
 use crate::args::CliOptions;
 use crate::args::CoverageFlags;
+use crate::args::CoverageType;
 use crate::args::FileFlags;
 use crate::args::Flags;
 use crate::cdp;
@@ -9,6 +10,8 @@ use crate::factory::CliFactory;
 use crate::npm::CliNpmResolver;
 use crate::tools::fmt::format_json;
 use crate::tools::test::is_supported_test_path;
+use crate::util::fs::collect_specifiers;
+use crate::util::path::is_script_ext;
 use crate::util::text_encoding::source_map_from_code;
 
 use deno_ast::MediaType;
@@ -26,6 +29,7 @@ use deno_core::sourcemap::SourceMap;
 use deno_core::url::Url;
 use deno_core::LocalInspectorSession;
 use regex::Regex;
+use std::collections::HashSet;
 use std::fs;
 use std::fs::File;
 use std::io::BufWriter;
@@ -36,10 +40,15 @@ use std::sync::Arc;
 use text_lines::TextLines;
 use uuid::Uuid;
 
+mod changes;
+mod history;
 mod merge;
 mod range_tree;
 mod reporter;
 mod util;
+use changes::ChangedLines;
+use history::CoverageHistoryEntry;
+use history::LineCounts;
 use merge::ProcessCoverage;
 
 pub struct CoverageCollector {
@@ -440,6 +449,66 @@ fn collect_coverages(
   Ok(coverages)
 }
 
+/// Creates empty coverages for the modules of the project which no test
+/// loaded, so they show up as uncovered instead of being left out.
+fn collect_untested_coverages(
+  cli_options: &CliOptions,
+  coverages: &[cdp::ScriptCoverage],
+  ignore: &[String],
+) -> Result<Vec<cdp::ScriptCoverage>, AnyError> {
+  let mut exclude = cli_options
+    .workspace()
+    .resolve_config_excludes()?
+    .into_path_or_patterns();
+  exclude.extend(
+    PathOrPatternSet::from_exclude_relative_path_or_patterns(
+      cli_options.initial_cwd(),
+      ignore,
+    )
+    .context("Invalid ignore pattern.")?
+    .into_path_or_patterns(),
+  );
+  let specifiers = collect_specifiers(
+    FilePatterns {
+      base: cli_options.workspace().root_dir_path(),
+      include: None,
+      exclude: PathOrPatternSet::new(exclude),
+    },
+    cli_options.vendor_dir_path().map(ToOwned::to_owned),
+    |e| {
+      is_script_ext(e.path)
+        && !matches!(
+          MediaType::from_path(e.path),
+          MediaType::Dts | MediaType::Dmts | MediaType::Dcts
+        )
+    },
+  )?;
+  let covered = coverages
+    .iter()
+    .map(|coverage| coverage.url.as_str())
+    .collect::<HashSet<_>>();
+  Ok(
+    specifiers
+      .into_iter()
+      .filter(|specifier| !covered.contains(specifier.as_str()))
+      .map(|specifier| cdp::ScriptCoverage {
+        script_id: String::new(),
+        url: specifier.to_string(),
+        // a single block spanning the whole module which never ran
+        functions: vec![cdp::FunctionCoverage {
+          function_name: String::new(),
+          ranges: vec![cdp::CoverageRange {
+            start_char_offset: 0,
+            end_char_offset: usize::MAX,
+            count: 0,
+          }],
+          is_block_coverage: true,
+        }],
+      })
+      .collect(),
+  )
+}
+
 fn filter_coverages(
   coverages: Vec<cdp::ScriptCoverage>,
   include: Vec<String>,
@@ -482,12 +551,23 @@ fn filter_coverages(
 pub async fn cover_files(
   flags: Arc<Flags>,
   coverage_flags: CoverageFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags);
+  let r#type = coverage_flags.r#type.clone();
+  report_coverages(&factory, coverage_flags, &[r#type]).await
+}
+
+/// Reports the coverage profiles of `coverage_flags` once with each of the
+/// given report types, so they only have to be collected and mapped once.
+pub async fn report_coverages(
+  factory: &CliFactory,
+  coverage_flags: CoverageFlags,
+  types: &[CoverageType],
 ) -> Result<(), AnyError> {
   if coverage_flags.files.include.is_empty() {
     return Err(generic_error("No matching coverage profiles found"));
   }
 
-  let factory = CliFactory::from_flags(flags);
   let cli_options = factory.cli_options()?;
   let npm_resolver = factory.npm_resolver().await?;
   let file_fetcher = factory.file_fetcher()?;
@@ -499,7 +579,8 @@ pub async fn cover_files(
   let coverage_root = cli_options
     .initial_cwd()
     .join(&coverage_flags.files.include[0]);
-  let script_coverages = collect_coverages(
+  let ignore = coverage_flags.files.ignore.clone();
+  let mut script_coverages = collect_coverages(
     cli_options,
     coverage_flags.files,
     cli_options.initial_cwd(),
@@ -507,6 +588,18 @@ pub async fn cover_files(
   if script_coverages.is_empty() {
     return Err(generic_error("No coverage files found"));
   }
+  let mut untested_urls = HashSet::new();
+  if coverage_flags.include_untested {
+    let untested_coverages =
+      collect_untested_coverages(cli_options, &script_coverages, &ignore)?;
+    untested_urls.extend(
+      untested_coverages
+        .iter()
+        .map(|coverage| coverage.url.clone()),
+    );
+    script_coverages.extend(untested_coverages);
+    script_coverages.sort_by_key(|k| k.url.clone());
+  }
   let script_coverages = filter_coverages(
     script_coverages,
     coverage_flags.include,
@@ -529,7 +622,11 @@ pub async fn cover_files(
     vec![]
   };
 
-  let mut reporter = reporter::create(coverage_flags.r#type);
+  let mut reporters = types
+    .iter()
+    .cloned()
+    .map(reporter::create)
+    .collect::<Vec<_>>();
 
   let out_mode = match coverage_flags.output {
     Some(ref path) => match File::create(path) {
@@ -541,6 +638,14 @@ pub async fn cover_files(
     None => None,
   };
 
+  let changed_lines = match &coverage_flags.changed_since {
+    Some(git_ref) => {
+      Some(ChangedLines::since(git_ref, cli_options.initial_cwd())?)
+    }
+    None => None,
+  };
+  let mut history_entry = CoverageHistoryEntry::new();
+
   for script_coverage in script_coverages {
     let module_specifier = deno_core::resolve_url_or_path(
       &script_coverage.url,
@@ -579,6 +684,12 @@ pub async fn cover_files(
       | MediaType::Tsx => {
         Some(match emitter.maybe_cached_emit(&file.specifier, &file.source) {
           Some(code) => code,
+          // modules which were never loaded weren't emitted either
+          None if untested_urls.contains(&script_coverage.url) => emitter
+            .emit_parsed_source(&file.specifier, file.media_type, &file.source)
+            .await?
+            .as_bytes()
+            .to_vec(),
           None => {
             return Err(anyhow!(
               "Missing transpiled source code for: \"{}\".
@@ -599,19 +710,71 @@ pub async fn cover_files(
     };
 
     let source_map = source_map_from_code(runtime_code.as_bytes());
-    let coverage_report = generate_coverage_report(
+    let mut coverage_report = generate_coverage_report(
       &script_coverage,
       runtime_code.as_str().to_owned(),
       &source_map,
       &out_mode,
     );
 
+    if let Some(changed_lines) = &changed_lines {
+      let path = module_specifier.to_file_path().unwrap_or_default();
+      changed_lines.retain(&path, &mut coverage_report.found_lines, |l| l.0);
+      changed_lines
+        .retain(&path, &mut coverage_report.branches, |b| b.line_index);
+      changed_lines.retain(&path, &mut coverage_report.named_functions, |f| {
+        f.line_index
+      });
+    }
+
     if !coverage_report.found_lines.is_empty() {
-      reporter.report(&coverage_report, &original_source)?;
+      history_entry.add_file(
+        &module_specifier,
+        cli_options.initial_cwd(),
+        LineCounts {
+          found: coverage_report.found_lines.len(),
+          hit: coverage_report
+            .found_lines
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .count(),
+        },
+      );
+      for reporter in &mut reporters {
+        reporter.report(&coverage_report, &original_source)?;
+      }
     }
   }
 
-  reporter.done(&coverage_root);
+  for reporter in &mut reporters {
+    reporter.done(&coverage_root);
+  }
+
+  if let Some(history_path) = &coverage_flags.history {
+    let history_path = cli_options.initial_cwd().join(history_path);
+    if let Some(runs) = coverage_flags.trend {
+      let previous = history::read_history(&history_path)?;
+      let previous = &previous[previous.len().saturating_sub(runs)..];
+      history::print_trend(&history_entry, previous);
+    }
+    history::append_history(&history_path, &history_entry)?;
+  }
+
+  if let Some(threshold) = coverage_flags.threshold {
+    let LineCounts {
+      found: lines_found,
+      hit: lines_hit,
+    } = history_entry.lines;
+    if lines_found > 0 {
+      let percent = lines_hit as f32 / lines_found as f32 * 100.0;
+      if percent < threshold as f32 {
+        return Err(generic_error(format!(
+          "Line coverage {:.1}% is below the threshold of {}%",
+          percent, threshold
+        )));
+      }
+    }
+  }
 
   Ok(())
 }
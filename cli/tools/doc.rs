@@ -18,6 +18,7 @@ use deno_config::glob::PathOrPatternSet;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::resolve_url_or_path;
 use deno_core::serde_json;
 use deno_doc as doc;
 use deno_doc::html::UrlResolveKind;
@@ -33,6 +34,8 @@ use std::collections::BTreeMap;
 use std::rc::Rc;
 use std::sync::Arc;
 
+mod openapi;
+
 const JSON_SCHEMA_VERSION: u8 = 1;
 
 async fn generate_doc_nodes_for_builtin_types(
@@ -93,12 +96,49 @@ pub async fn doc(
 ) -> Result<(), AnyError> {
   let factory = CliFactory::from_flags(flags);
   let cli_options = factory.cli_options()?;
+  if doc_flags.openapi {
+    let entrypoint = match &doc_flags.source_files {
+      DocSourceFileFlag::Paths(paths) if paths.len() == 1 => {
+        resolve_url_or_path(&paths[0], cli_options.initial_cwd())?
+      }
+      _ => {
+        bail!("--openapi requires the deno serve entrypoint as the only file")
+      }
+    };
+    if entrypoint.scheme() != "file" {
+      bail!("--openapi requires a local entrypoint, but got {entrypoint}");
+    }
+    let deno_json = cli_options.start_dir.maybe_deno_json();
+    let info = openapi::OpenApiInfo {
+      title: deno_json
+        .and_then(|config| config.json.name.clone())
+        .unwrap_or_else(|| {
+          entrypoint
+            .path_segments()
+            .unwrap()
+            .last()
+            .unwrap()
+            .to_string()
+        }),
+      version: deno_json
+        .and_then(|config| config.json.version.clone())
+        .unwrap_or_else(|| "0.0.0".to_string()),
+    };
+    let graph = factory
+      .module_graph_creator()
+      .await?
+      .create_graph(GraphKind::CodeOnly, vec![entrypoint.clone()])
+      .await?;
+    let document =
+      openapi::generate_openapi_document(&graph, &entrypoint, info)?;
+    return display::write_json_to_stdout(&document);
+  }
   let module_info_cache = factory.module_info_cache()?;
   let parsed_source_cache = factory.parsed_source_cache();
   let capturing_parser = parsed_source_cache.as_capturing_parser();
   let analyzer = module_info_cache.as_module_analyzer(parsed_source_cache);
 
-  let doc_nodes_by_url = match doc_flags.source_files {
+  let mut doc_nodes_by_url = match doc_flags.source_files {
     DocSourceFileFlag::Builtin => {
       generate_doc_nodes_for_builtin_types(
         doc_flags.clone(),
@@ -169,7 +209,29 @@ pub async fn doc(
     }
   };
 
+  if doc_flags.only_deprecated || doc_flags.min_since.is_some() {
+    let min_since = doc_flags
+      .min_since
+      .as_deref()
+      .map(|version| {
+        parse_since_version(version).with_context(|| {
+          format!("Invalid version \"{version}\" passed to --min-since")
+        })
+      })
+      .transpose()?;
+    for nodes in doc_nodes_by_url.values_mut() {
+      nodes.retain(|node| {
+        (!doc_flags.only_deprecated || is_deprecated(&node.js_doc))
+          && min_since.as_ref().map_or(true, |min_since| {
+            since_version(&node.js_doc)
+              .is_some_and(|since| compare_versions(&since, min_since).is_ge())
+          })
+      });
+    }
+  }
+
   if let Some(html_options) = &doc_flags.html {
+    add_since_badges(&mut doc_nodes_by_url);
     let deno_ns = if doc_flags.source_files != DocSourceFileFlag::Builtin {
       let deno_ns = generate_doc_nodes_for_builtin_types(
         doc_flags.clone(),
@@ -575,6 +637,66 @@ fn print_docs_to_stdout(
     .map_err(AnyError::from)
 }
 
+fn is_deprecated(js_doc: &doc::js_doc::JsDoc) -> bool {
+  js_doc
+    .tags
+    .iter()
+    .any(|tag| matches!(tag, doc::js_doc::JsDocTag::Deprecated { .. }))
+}
+
+/// Returns the text of the `@since` tag of the JSDoc.
+fn since_text(js_doc: &doc::js_doc::JsDoc) -> Option<&str> {
+  js_doc.tags.iter().find_map(|tag| match tag {
+    doc::js_doc::JsDocTag::Since { doc } => Some(&**doc),
+    _ => None,
+  })
+}
+
+fn since_version(js_doc: &doc::js_doc::JsDoc) -> Option<Vec<u64>> {
+  since_text(js_doc).and_then(parse_since_version)
+}
+
+/// Parses the version at the start of a `@since` tag, like `v1.2` in
+/// `v1.2 added the options`, into its numeric components. Pre-release and
+/// build metadata suffixes are ignored.
+fn parse_since_version(text: &str) -> Option<Vec<u64>> {
+  let version = text.split_whitespace().next()?;
+  let version = version.strip_prefix('v').unwrap_or(version);
+  let version = version.split(['-', '+']).next()?;
+  version.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Compares the versions, treating missing components as zero so that `1.2`
+/// equals `1.2.0`.
+fn compare_versions(a: &[u64], b: &[u64]) -> std::cmp::Ordering {
+  (0..a.len().max(b.len()))
+    .map(|i| {
+      let a = a.get(i).copied().unwrap_or(0);
+      let b = b.get(i).copied().unwrap_or(0);
+      a.cmp(&b)
+    })
+    .find(|ordering| ordering.is_ne())
+    .unwrap_or(std::cmp::Ordering::Equal)
+}
+
+/// The HTML output renders `@deprecated` tags as a banner, but doesn't show
+/// `@since` tags, so they're surfaced as a badge of the symbol.
+fn add_since_badges(
+  doc_nodes_by_url: &mut IndexMap<ModuleSpecifier, Vec<doc::DocNode>>,
+) {
+  for node in doc_nodes_by_url.values_mut().flatten() {
+    let Some(since) = since_text(&node.js_doc) else {
+      continue;
+    };
+    let badge = format!("since {}", since.trim());
+    let mut tags = node.js_doc.tags.to_vec();
+    tags.push(doc::js_doc::JsDocTag::Tags {
+      tags: vec![badge.into()].into(),
+    });
+    node.js_doc.tags = tags.into();
+  }
+}
+
 fn check_diagnostics(diagnostics: &[DocDiagnostic]) -> Result<(), AnyError> {
   if diagnostics.is_empty() {
     return Ok(());
@@ -608,3 +730,25 @@ fn check_diagnostics(diagnostics: &[DocDiagnostic]) -> Result<(), AnyError> {
     if diagnostics.len() == 1 { "" } else { "s" }
   );
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_parse_since_version() {
+    assert_eq!(parse_since_version("1.2.3"), Some(vec![1, 2, 3]));
+    assert_eq!(parse_since_version("v2.0 added options"), Some(vec![2, 0]));
+    assert_eq!(parse_since_version("1.40.0-rc.1"), Some(vec![1, 40, 0]));
+    assert_eq!(parse_since_version("next"), None);
+    assert_eq!(parse_since_version(""), None);
+  }
+
+  #[test]
+  fn test_compare_versions() {
+    use std::cmp::Ordering;
+    assert_eq!(compare_versions(&[1, 2], &[1, 2, 0]), Ordering::Equal);
+    assert_eq!(compare_versions(&[1, 10], &[1, 9, 5]), Ordering::Greater);
+    assert_eq!(compare_versions(&[0, 9], &[1]), Ordering::Less);
+  }
+}
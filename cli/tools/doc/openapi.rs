@@ -0,0 +1,542 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Statically extracts the route table of a `deno serve` entrypoint, as
+//! defined with the `route()` helper of `@std/http`, into an OpenAPI 3.1
+//! document.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::common::comments::CommentKind;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_ast::SourcePos;
+use deno_ast::SourceRangedForSpanned;
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_graph::Module;
+use deno_graph::ModuleGraph;
+use deno_graph::Resolution;
+
+use crate::colors;
+
+const OPENAPI_VERSION: &str = "3.1.0";
+
+/// The HTTP methods which have an operation in an OpenAPI path item.
+const METHODS: &[&str] = &[
+  "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+pub struct OpenApiInfo {
+  pub title: String,
+  pub version: String,
+}
+
+/// Creates the OpenAPI document of the routes declared by the entrypoint and
+/// the local modules it statically imports, as resolved in the module graph.
+pub fn generate_openapi_document(
+  graph: &ModuleGraph,
+  entrypoint: &ModuleSpecifier,
+  info: OpenApiInfo,
+) -> Result<serde_json::Value, AnyError> {
+  let mut routes = Vec::new();
+  let mut visited = HashSet::new();
+  let mut pending = vec![entrypoint.clone()];
+  while let Some(specifier) = pending.pop() {
+    let specifier = graph.resolve(&specifier);
+    if !visited.insert(specifier.clone()) {
+      continue;
+    }
+    let module = match graph.try_get(specifier) {
+      Ok(Some(Module::Js(module))) => module,
+      Ok(_) => continue,
+      Err(err) if specifier == entrypoint => bail!("{err}"),
+      Err(err) => {
+        log::warn!(
+          "{} Skipping the routes of {}: {}",
+          colors::yellow("Warning"),
+          specifier,
+          err
+        );
+        continue;
+      }
+    };
+    routes.extend(analyze_module(
+      &module.specifier,
+      module.source.clone(),
+      module.media_type,
+    )?);
+    // routes are commonly declared next to their handlers
+    for (import, dependency) in module.dependencies.iter().rev() {
+      if dependency.is_dynamic {
+        continue;
+      }
+      match &dependency.maybe_code {
+        Resolution::Ok(resolved) if resolved.specifier.scheme() == "file" => {
+          pending.push(resolved.specifier.clone());
+        }
+        Resolution::Err(err) => {
+          log::warn!(
+            "{} Skipping the routes of \"{}\" imported by {}: {}",
+            colors::yellow("Warning"),
+            import,
+            specifier,
+            err
+          );
+        }
+        _ => {}
+      }
+    }
+  }
+
+  let mut paths = BTreeMap::<String, serde_json::Map<_, _>>::new();
+  for route in routes {
+    let (path, params) = to_openapi_path(&route.pathname);
+    let path_item = paths.entry(path).or_default();
+    for method in &route.methods {
+      // the first matching route handles the request
+      if path_item.contains_key(method) {
+        continue;
+      }
+      let operation = operation(&route, &params);
+      path_item.insert(method.clone(), operation);
+    }
+  }
+
+  Ok(json!({
+    "openapi": OPENAPI_VERSION,
+    "info": {
+      "title": info.title,
+      "version": info.version,
+    },
+    "paths": paths,
+  }))
+}
+
+fn operation(route: &Route, params: &[PathParam]) -> serde_json::Value {
+  let js_doc = route
+    .js_doc
+    .as_deref()
+    .map(parse_js_doc)
+    .unwrap_or_default();
+  let mut operation = serde_json::Map::new();
+  if let (Some(handler), [_]) = (&route.handler, route.methods.as_slice()) {
+    operation.insert("operationId".to_string(), json!(handler));
+  }
+  if let Some(summary) = js_doc.summary {
+    operation.insert("summary".to_string(), json!(summary));
+  }
+  if let Some(description) = js_doc.description {
+    operation.insert("description".to_string(), json!(description));
+  }
+  if !js_doc.tags.is_empty() {
+    operation.insert("tags".to_string(), json!(js_doc.tags));
+  }
+  if js_doc.deprecated {
+    operation.insert("deprecated".to_string(), json!(true));
+  }
+  if !params.is_empty() {
+    let parameters = params
+      .iter()
+      .map(|param| {
+        let mut parameter = json!({
+          "name": param.name,
+          "in": "path",
+          "required": true,
+          "schema": { "type": if param.is_integer { "integer" } else { "string" } },
+        });
+        if let Some(description) = js_doc.params.get(&param.name) {
+          parameter["description"] = json!(description);
+        }
+        parameter
+      })
+      .collect::<Vec<_>>();
+    operation.insert("parameters".to_string(), json!(parameters));
+  }
+  operation.insert(
+    "responses".to_string(),
+    json!({ "default": { "description": "The response of the handler" } }),
+  );
+  serde_json::Value::Object(operation)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Route {
+  pathname: String,
+  /// The lowercase methods, defaulting to `get` like `route()` does.
+  methods: Vec<String>,
+  /// The name of the handler, when it's a reference to a function.
+  handler: Option<String>,
+  js_doc: Option<String>,
+}
+
+fn analyze_module(
+  specifier: &ModuleSpecifier,
+  text: Arc<str>,
+  media_type: MediaType,
+) -> Result<Vec<Route>, AnyError> {
+  let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier: specifier.clone(),
+    text,
+    media_type,
+    capture_tokens: false,
+    scope_analysis: false,
+    maybe_syntax: None,
+  })?;
+  let mut collector = RouteCollector::default();
+  parsed_source.program_ref().visit_with(&mut collector);
+  let routes = collector
+    .routes
+    .into_iter()
+    .map(|(start, mut route)| {
+      route.js_doc = parsed_source
+        .comments()
+        .get_leading(start)
+        .and_then(|comments| {
+          comments.iter().rev().find(|comment| {
+            comment.kind == CommentKind::Block && comment.text.starts_with('*')
+          })
+        })
+        .map(|comment| comment.text.to_string());
+      route
+    })
+    .collect();
+  Ok(routes)
+}
+
+#[derive(Default)]
+struct RouteCollector {
+  routes: Vec<(SourcePos, Route)>,
+}
+
+impl Visit for RouteCollector {
+  fn visit_object_lit(&mut self, object: &ast::ObjectLit) {
+    if let Some(route) = route_from_object(object) {
+      self.routes.push((object.start(), route));
+    }
+    object.visit_children_with(self);
+  }
+}
+
+/// Reads an object like
+/// `{ pattern: new URLPattern({ pathname: "/users/:id" }), method: "GET", handler }`.
+fn route_from_object(object: &ast::ObjectLit) -> Option<Route> {
+  let mut pathname = None;
+  let mut methods = vec!["get".to_string()];
+  let mut handler = None;
+  let mut has_handler = false;
+  for prop in &object.props {
+    let ast::PropOrSpread::Prop(prop) = prop else {
+      continue;
+    };
+    match &**prop {
+      ast::Prop::KeyValue(key_value) => {
+        let Some(name) = prop_name(&key_value.key) else {
+          continue;
+        };
+        match name.as_str() {
+          "pattern" => pathname = pattern_pathname(&key_value.value),
+          "method" => methods = route_methods(&key_value.value)?,
+          "handler" => {
+            has_handler = true;
+            if let ast::Expr::Ident(ident) = unwrap_expr(&key_value.value) {
+              handler = Some(ident.sym.to_string());
+            }
+          }
+          _ => {}
+        }
+      }
+      ast::Prop::Shorthand(ident) if &*ident.sym == "handler" => {
+        has_handler = true;
+      }
+      ast::Prop::Method(method) => {
+        if prop_name(&method.key).as_deref() == Some("handler") {
+          has_handler = true;
+        }
+      }
+      _ => {}
+    }
+  }
+  if !has_handler {
+    return None;
+  }
+  Some(Route {
+    pathname: pathname?,
+    methods,
+    handler,
+    js_doc: None,
+  })
+}
+
+fn prop_name(name: &ast::PropName) -> Option<String> {
+  match name {
+    ast::PropName::Ident(ident) => Some(ident.sym.to_string()),
+    ast::PropName::Str(str) => Some(str.value.to_string()),
+    _ => None,
+  }
+}
+
+fn unwrap_expr(expr: &ast::Expr) -> &ast::Expr {
+  match expr {
+    ast::Expr::Paren(paren) => unwrap_expr(&paren.expr),
+    ast::Expr::TsAs(ts_as) => unwrap_expr(&ts_as.expr),
+    ast::Expr::TsConstAssertion(assertion) => unwrap_expr(&assertion.expr),
+    ast::Expr::TsSatisfies(satisfies) => unwrap_expr(&satisfies.expr),
+    expr => expr,
+  }
+}
+
+fn string_value(expr: &ast::Expr) -> Option<String> {
+  match unwrap_expr(expr) {
+    ast::Expr::Lit(ast::Lit::Str(str)) => Some(str.value.to_string()),
+    ast::Expr::Tpl(tpl) if tpl.exprs.is_empty() => tpl
+      .quasis
+      .first()?
+      .cooked
+      .as_ref()
+      .map(|text| text.to_string()),
+    _ => None,
+  }
+}
+
+/// The pathname of `new URLPattern({ pathname })` or `new URLPattern("/path")`.
+fn pattern_pathname(expr: &ast::Expr) -> Option<String> {
+  let ast::Expr::New(new_expr) = unwrap_expr(expr) else {
+    return None;
+  };
+  match unwrap_expr(&new_expr.callee) {
+    ast::Expr::Ident(ident) if &*ident.sym == "URLPattern" => {}
+    _ => return None,
+  }
+  let arg = new_expr.args.as_ref()?.first()?;
+  if arg.spread.is_some() {
+    return None;
+  }
+  match unwrap_expr(&arg.expr) {
+    ast::Expr::Object(init) => init.props.iter().find_map(|prop| {
+      let ast::PropOrSpread::Prop(prop) = prop else {
+        return None;
+      };
+      let ast::Prop::KeyValue(key_value) = &**prop else {
+        return None;
+      };
+      (prop_name(&key_value.key)? == "pathname")
+        .then(|| string_value(&key_value.value))
+        .flatten()
+    }),
+    expr => string_value(expr).filter(|pathname| pathname.starts_with('/')),
+  }
+}
+
+fn route_methods(expr: &ast::Expr) -> Option<Vec<String>> {
+  let values = match unwrap_expr(expr) {
+    ast::Expr::Array(array) => array
+      .elems
+      .iter()
+      .map(|elem| {
+        elem
+          .as_ref()
+          .filter(|elem| elem.spread.is_none())
+          .and_then(|elem| string_value(&elem.expr))
+      })
+      .collect::<Option<Vec<_>>>()?,
+    expr => vec![string_value(expr)?],
+  };
+  Some(
+    values
+      .into_iter()
+      .map(|method| method.to_lowercase())
+      .filter(|method| METHODS.contains(&method.as_str()))
+      .collect(),
+  )
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PathParam {
+  name: String,
+  /// Whether the group of the parameter only matches digits, like `:id(\d+)`.
+  is_integer: bool,
+}
+
+/// Converts a URLPattern pathname like `/users/:id(\d+)` to an OpenAPI path
+/// like `/users/{id}`.
+fn to_openapi_path(pathname: &str) -> (String, Vec<PathParam>) {
+  let mut path = String::with_capacity(pathname.len());
+  let mut params = Vec::new();
+  let mut chars = pathname.chars().peekable();
+  while let Some(c) = chars.next() {
+    if c != ':' {
+      path.push(c);
+      continue;
+    }
+    let mut name = String::new();
+    while let Some(c) =
+      chars.next_if(|c| c.is_ascii_alphanumeric() || *c == '_')
+    {
+      name.push(c);
+    }
+    let mut group = String::new();
+    if chars.next_if_eq(&'(').is_some() {
+      let mut depth = 1;
+      for c in chars.by_ref() {
+        match c {
+          '(' => depth += 1,
+          ')' => depth -= 1,
+          _ => {}
+        }
+        if depth == 0 {
+          break;
+        }
+        group.push(c);
+      }
+    }
+    // the modifiers have no equivalent in OpenAPI
+    chars.next_if(|c| matches!(c, '?' | '*' | '+'));
+    path.push_str(&format!("{{{name}}}"));
+    params.push(PathParam {
+      is_integer: group == "\\d+" || group == "[0-9]+",
+      name,
+    });
+  }
+  (path, params)
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct JsDocInfo {
+  summary: Option<String>,
+  description: Option<String>,
+  tags: Vec<String>,
+  deprecated: bool,
+  params: BTreeMap<String, String>,
+}
+
+/// Reads the text of a `/** */` comment: the first line is the summary and
+/// the following ones the description, along with the `@tag`, `@deprecated`
+/// and `@param` tags.
+fn parse_js_doc(text: &str) -> JsDocInfo {
+  let mut info = JsDocInfo::default();
+  let mut lines = Vec::new();
+  for line in text.lines() {
+    let line = line.trim_start();
+    let line = line.strip_prefix('*').unwrap_or(line);
+    let line = line.strip_prefix(' ').unwrap_or(line).trim_end();
+    if let Some(tag) = line.strip_prefix('@') {
+      let (name, value) = tag.split_once(' ').unwrap_or((tag, ""));
+      let value = value.trim();
+      match name {
+        "tag" if !value.is_empty() => info.tags.push(value.to_string()),
+        "deprecated" => info.deprecated = true,
+        "param" => {
+          if let Some((param, description)) = value.split_once(' ') {
+            let description =
+              description.trim().trim_start_matches("- ").to_string();
+            info.params.insert(param.to_string(), description);
+          }
+        }
+        _ => {}
+      }
+    } else if !line.is_empty() || !lines.is_empty() {
+      lines.push(line);
+    }
+  }
+  let mut lines = lines.into_iter();
+  info.summary = lines.next().map(|line| line.to_string());
+  let description = lines.collect::<Vec<_>>().join("\n");
+  let description = description.trim();
+  if !description.is_empty() {
+    info.description = Some(description.to_string());
+  }
+  info
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn routes(text: &str) -> Vec<Route> {
+    let specifier = ModuleSpecifier::parse("file:///main.ts").unwrap();
+    analyze_module(&specifier, text.into(), MediaType::TypeScript).unwrap()
+  }
+
+  #[test]
+  fn collects_routes() {
+    let routes = routes(
+      r#"
+import { route } from "jsr:@std/http/unstable-route";
+import { getUser } from "./users.ts";
+
+const routes = [
+  /**
+   * Get a user
+   *
+   * Returns the user of the id.
+   * @tag users
+   * @param id The id of the user
+   */
+  {
+    pattern: new URLPattern({ pathname: "/users/:id(\\d+)" }),
+    method: "GET",
+    handler: getUser,
+  },
+  {
+    pattern: new URLPattern("/users"),
+    method: ["POST", "PUT"],
+    handler: (req) => new Response("created"),
+  },
+];
+
+export default {
+  fetch: route(routes, () => new Response("Not found", { status: 404 })),
+} satisfies Deno.ServeDefaultExport;
+"#,
+    );
+    assert_eq!(routes.len(), 2);
+    assert_eq!(routes[0].pathname, "/users/:id(\\d+)");
+    assert_eq!(routes[0].methods, vec!["get"]);
+    assert_eq!(routes[0].handler.as_deref(), Some("getUser"));
+    assert_eq!(
+      parse_js_doc(routes[0].js_doc.as_deref().unwrap()),
+      JsDocInfo {
+        summary: Some("Get a user".to_string()),
+        description: Some("Returns the user of the id.".to_string()),
+        tags: vec!["users".to_string()],
+        deprecated: false,
+        params: BTreeMap::from([(
+          "id".to_string(),
+          "The id of the user".to_string()
+        )]),
+      }
+    );
+    assert_eq!(routes[1].pathname, "/users");
+    assert_eq!(routes[1].methods, vec!["post", "put"]);
+    assert_eq!(routes[1].handler, None);
+    assert_eq!(routes[1].js_doc, None);
+  }
+
+  #[test]
+  fn converts_paths() {
+    assert_eq!(
+      to_openapi_path("/users/:id(\\d+)/posts/:slug?"),
+      (
+        "/users/{id}/posts/{slug}".to_string(),
+        vec![
+          PathParam {
+            name: "id".to_string(),
+            is_integer: true,
+          },
+          PathParam {
+            name: "slug".to_string(),
+            is_integer: false,
+          },
+        ]
+      )
+    );
+    assert_eq!(to_openapi_path("/health"), ("/health".to_string(), vec![]));
+  }
+}
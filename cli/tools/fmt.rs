@@ -9,7 +9,10 @@
 
 use crate::args::CliOptions;
 use crate::args::Flags;
+use crate::args::FmtBom;
+use crate::args::FmtFileOptions;
 use crate::args::FmtFlags;
+use crate::args::FmtLineEnding;
 use crate::args::FmtOptions;
 use crate::args::FmtOptionsConfig;
 use crate::args::ProseWrap;
@@ -19,9 +22,11 @@ use crate::colors;
 use crate::factory::CliFactory;
 use crate::util::diff::diff;
 use crate::util::file_watcher;
+use crate::util::fs::atomic_write_file_with_retries;
 use crate::util::fs::canonicalize_path;
 use crate::util::path::get_extension;
 use async_trait::async_trait;
+use console_static_text::ansi::strip_ansi_codes;
 use deno_ast::ParsedSource;
 use deno_config::glob::FileCollector;
 use deno_config::glob::FilePatterns;
@@ -32,11 +37,13 @@ use deno_core::error::generic_error;
 use deno_core::error::AnyError;
 use deno_core::futures;
 use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
 use deno_core::unsync::spawn_blocking;
 use deno_core::url::Url;
 use log::debug;
 use log::info;
 use log::warn;
+use serde::Serialize;
 use std::borrow::Cow;
 use std::fs;
 use std::io::stdin;
@@ -64,6 +71,7 @@ pub async fn format(
     let fmt_options = FmtOptions::resolve(
       fmt_config,
       cli_options.resolve_config_unstable_fmt_options(),
+      cli_options.resolve_config_fmt_file_options()?,
       &fmt_flags,
     );
     return format_stdin(
@@ -185,7 +193,13 @@ async fn format_files(
   paths_with_options_batches: Vec<PathsWithOptions>,
 ) -> Result<(), AnyError> {
   let formatter: Box<dyn Formatter> = if fmt_flags.check {
-    Box::new(CheckFormatter::default())
+    Box::new(CheckFormatter {
+      output_file: fmt_flags
+        .output_file
+        .as_ref()
+        .map(|path| cli_options.initial_cwd().join(path)),
+      ..Default::default()
+    })
   } else {
     Box::new(RealFormatter::default())
   };
@@ -199,7 +213,11 @@ async fn format_files(
     let paths = paths_with_options.paths;
     let incremental_cache = Arc::new(IncrementalCache::new(
       caches.fmt_incremental_cache_db(),
-      &(&fmt_options.options, &fmt_options.unstable), // cache key
+      &(
+        &fmt_options.options,
+        &fmt_options.unstable,
+        &fmt_options.file,
+      ), // cache key
       &paths,
     ));
     formatter
@@ -207,6 +225,7 @@ async fn format_files(
         paths,
         fmt_options.options,
         fmt_options.unstable,
+        fmt_options.file,
         incremental_cache.clone(),
         cli_options.ext_flag().clone(),
       )
@@ -237,6 +256,7 @@ fn format_markdown(
   file_text: &str,
   fmt_options: &FmtOptionsConfig,
   unstable_options: &UnstableFmtOptions,
+  skip_code_blocks: &[String],
 ) -> Result<Option<String>, AnyError> {
   let markdown_config = get_resolved_markdown_config(fmt_options);
   dprint_plugin_markdown::format_text(
@@ -244,6 +264,9 @@ fn format_markdown(
     &markdown_config,
     move |tag, text, line_width| {
       let tag = tag.to_lowercase();
+      if is_code_block_skipped(&tag, skip_code_blocks) {
+        return Ok(None);
+      }
       if matches!(
         tag.as_str(),
         "ts"
@@ -273,11 +296,7 @@ fn format_markdown(
       ) {
         // It's important to tell dprint proper file extension, otherwise
         // it might parse the file twice.
-        let extension = match tag.as_str() {
-          "javascript" => "js",
-          "typescript" => "ts",
-          rest => rest,
-        };
+        let extension = code_block_extension(&tag);
 
         let fake_filename =
           PathBuf::from(format!("deno_fmt_stdin.{extension}"));
@@ -318,6 +337,25 @@ fn format_markdown(
   )
 }
 
+fn code_block_extension(tag: &str) -> &str {
+  match tag {
+    "javascript" => "js",
+    "typescript" => "ts",
+    "yml" => "yaml",
+    rest => rest,
+  }
+}
+
+/// Whether the code blocks of the language were excluded from formatting
+/// with `--skip-code-blocks`, where aliases like `typescript` and `ts` are
+/// the same language.
+fn is_code_block_skipped(tag: &str, skip_code_blocks: &[String]) -> bool {
+  let extension = code_block_extension(tag);
+  skip_code_blocks
+    .iter()
+    .any(|language| code_block_extension(&language.to_lowercase()) == extension)
+}
+
 /// Formats JSON and JSONC using the rules provided by .deno()
 /// of configuration builder of <https://github.com/dprint/dprint-plugin-json>.
 /// See <https://github.com/dprint/dprint-plugin-json/blob/cfa1052dbfa0b54eb3d814318034cdc514c813d7/src/configuration/builder.rs#L87> for configuration.
@@ -492,6 +530,7 @@ pub fn format_file(
   file_text: &str,
   fmt_options: &FmtOptionsConfig,
   unstable_options: &UnstableFmtOptions,
+  skip_code_blocks: &[String],
   ext: Option<String>,
 ) -> Result<Option<String>, AnyError> {
   let ext = ext
@@ -499,9 +538,12 @@ pub fn format_file(
     .unwrap_or("ts".to_string());
 
   match ext.as_str() {
-    "md" | "mkd" | "mkdn" | "mdwn" | "mdown" | "markdown" => {
-      format_markdown(file_text, fmt_options, unstable_options)
-    }
+    "md" | "mkd" | "mkdn" | "mdwn" | "mdown" | "markdown" => format_markdown(
+      file_text,
+      fmt_options,
+      unstable_options,
+      skip_code_blocks,
+    ),
     "json" | "jsonc" => format_json(file_path, file_text, fmt_options),
     "css" | "scss" | "sass" | "less" => {
       format_css(file_path, file_text, fmt_options)
@@ -518,7 +560,14 @@ pub fn format_file(
     "ipynb" => dprint_plugin_jupyter::format_text(
       file_text,
       |file_path: &Path, file_text: String| {
-        format_file(file_path, &file_text, fmt_options, unstable_options, None)
+        format_file(
+          file_path,
+          &file_text,
+          fmt_options,
+          unstable_options,
+          skip_code_blocks,
+          None,
+        )
       },
     ),
     _ => {
@@ -533,6 +582,59 @@ pub fn format_file(
   }
 }
 
+/// Formats a file and then applies the options which are the same for all
+/// file types, like the line ending.
+fn format_file_with_options(
+  file_path: &Path,
+  file_text: &str,
+  fmt_options: &FmtOptionsConfig,
+  unstable_options: &UnstableFmtOptions,
+  file_options: &FmtFileOptions,
+  ext: Option<String>,
+) -> Result<Option<String>, AnyError> {
+  let formatted_text = format_file(
+    file_path,
+    file_text,
+    fmt_options,
+    unstable_options,
+    &file_options.skip_code_blocks,
+    ext,
+  )?;
+  let Some(line_ending) = file_options.line_ending else {
+    return Ok(formatted_text);
+  };
+  let text = apply_line_ending(
+    file_text,
+    formatted_text.unwrap_or_else(|| file_text.to_string()),
+    line_ending,
+  );
+  Ok(if text == file_text { None } else { Some(text) })
+}
+
+fn apply_line_ending(
+  original_text: &str,
+  text: String,
+  line_ending: FmtLineEnding,
+) -> String {
+  let use_crlf = match line_ending {
+    FmtLineEnding::Lf => false,
+    FmtLineEnding::Crlf => true,
+    FmtLineEnding::Auto => original_text
+      .find('\n')
+      .is_some_and(|index| original_text[..index].ends_with('\r')),
+  };
+  let text = if text.contains('\r') {
+    text.replace("\r\n", "\n")
+  } else {
+    text
+  };
+  if use_crlf {
+    text.replace('\n', "\r\n")
+  } else {
+    text
+  }
+}
+
 pub fn format_parsed_source(
   parsed_source: &ParsedSource,
   fmt_options: &FmtOptionsConfig,
@@ -550,6 +652,7 @@ trait Formatter {
     paths: Vec<PathBuf>,
     fmt_options: FmtOptionsConfig,
     unstable_options: UnstableFmtOptions,
+    file_options: FmtFileOptions,
     incremental_cache: Arc<IncrementalCache>,
     ext: Option<String>,
   ) -> Result<(), AnyError>;
@@ -557,10 +660,60 @@ trait Formatter {
   fn finish(&self) -> Result<(), AnyError>;
 }
 
+/// A file reported by `deno fmt --check --output-file`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase", tag = "reason")]
+enum NotFormattedFile {
+  Diff { file: String, diff: String },
+  ByteOrderMark { file: String },
+  Error { file: String, message: String },
+}
+
+impl NotFormattedFile {
+  fn file(&self) -> &str {
+    match self {
+      Self::Diff { file, .. }
+      | Self::ByteOrderMark { file }
+      | Self::Error { file, .. } => file,
+    }
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckReport<'a> {
+  version: u8,
+  checked_files: usize,
+  not_formatted: &'a [NotFormattedFile],
+}
+
 #[derive(Default)]
 struct CheckFormatter {
   not_formatted_files_count: Arc<AtomicUsize>,
   checked_files_count: Arc<AtomicUsize>,
+  /// Where the result is written as JSON, in which case the files which
+  /// aren't formatted are collected.
+  output_file: Option<PathBuf>,
+  not_formatted_files: Arc<Mutex<Vec<NotFormattedFile>>>,
+}
+
+impl CheckFormatter {
+  fn write_output_file(
+    &self,
+    output_file: &Path,
+    checked_files: usize,
+  ) -> Result<(), AnyError> {
+    let mut not_formatted =
+      std::mem::take(&mut *self.not_formatted_files.lock());
+    not_formatted.sort_by(|a, b| a.file().cmp(b.file()));
+    let json = serde_json::to_string_pretty(&CheckReport {
+      version: 1,
+      checked_files,
+      not_formatted: &not_formatted,
+    })?;
+    atomic_write_file_with_retries(output_file, format!("{json}\n"), 0o644)
+      .with_context(|| format!("Failed writing {}", output_file.display()))
+  }
 }
 
 #[async_trait]
@@ -570,6 +723,7 @@ impl Formatter for CheckFormatter {
     paths: Vec<PathBuf>,
     fmt_options: FmtOptionsConfig,
     unstable_options: UnstableFmtOptions,
+    file_options: FmtFileOptions,
     incremental_cache: Arc<IncrementalCache>,
     ext: Option<String>,
   ) -> Result<(), AnyError> {
@@ -579,20 +733,46 @@ impl Formatter for CheckFormatter {
     run_parallelized(paths, {
       let not_formatted_files_count = self.not_formatted_files_count.clone();
       let checked_files_count = self.checked_files_count.clone();
+      let not_formatted_files = self
+        .output_file
+        .is_some()
+        .then(|| self.not_formatted_files.clone());
       move |file_path| {
+        let report = |not_formatted_file| {
+          if let Some(not_formatted_files) = &not_formatted_files {
+            not_formatted_files.lock().push(not_formatted_file);
+          }
+        };
         checked_files_count.fetch_add(1, Ordering::Relaxed);
-        let file_text = read_file_contents(&file_path)?.text;
+        let file_contents = read_file_contents(&file_path)?;
+        let file_text = file_contents.text;
+
+        if file_contents.had_bom && file_options.bom == FmtBom::Strip {
+          not_formatted_files_count.fetch_add(1, Ordering::Relaxed);
+          let _g = output_lock.lock();
+          info!("");
+          info!(
+            "{} {}: has a byte order mark",
+            colors::bold("from"),
+            file_path.display()
+          );
+          report(NotFormattedFile::ByteOrderMark {
+            file: file_path.display().to_string(),
+          });
+          return Ok(());
+        }
 
         // skip checking the file if we know it's formatted
         if incremental_cache.is_file_same(&file_path, &file_text) {
           return Ok(());
         }
 
-        match format_file(
+        match format_file_with_options(
           &file_path,
           &file_text,
           &fmt_options,
           &unstable_options,
+          &file_options,
           ext.clone(),
         ) {
           Ok(Some(formatted_text)) => {
@@ -602,6 +782,10 @@ impl Formatter for CheckFormatter {
             info!("");
             info!("{} {}:", colors::bold("from"), file_path.display());
             info!("{}", diff);
+            report(NotFormattedFile::Diff {
+              file: file_path.display().to_string(),
+              diff: strip_ansi_codes(&diff).into_owned(),
+            });
           }
           Ok(None) => {
             // When checking formatting, only update the incremental cache when
@@ -629,6 +813,10 @@ impl Formatter for CheckFormatter {
                 .collect::<Vec<_>>()
                 .join("\n")
             );
+            report(NotFormattedFile::Error {
+              file: file_path.display().to_string(),
+              message: e.to_string(),
+            });
           }
         }
         Ok(())
@@ -645,6 +833,9 @@ impl Formatter for CheckFormatter {
     let checked_files_count = self.checked_files_count.load(Ordering::Relaxed);
     let checked_files_str =
       format!("{} {}", checked_files_count, files_str(checked_files_count));
+    if let Some(output_file) = &self.output_file {
+      self.write_output_file(output_file, checked_files_count)?;
+    }
     if not_formatted_files_count == 0 {
       info!("Checked {}", checked_files_str);
       Ok(())
@@ -670,6 +861,7 @@ impl Formatter for RealFormatter {
     paths: Vec<PathBuf>,
     fmt_options: FmtOptionsConfig,
     unstable_options: UnstableFmtOptions,
+    file_options: FmtFileOptions,
     incremental_cache: Arc<IncrementalCache>,
     ext: Option<String>,
   ) -> Result<(), AnyError> {
@@ -682,30 +874,43 @@ impl Formatter for RealFormatter {
         checked_files_count.fetch_add(1, Ordering::Relaxed);
         let file_contents = read_file_contents(&file_path)?;
 
+        let strip_bom =
+          file_contents.had_bom && file_options.bom == FmtBom::Strip;
+
         // skip formatting the file if we know it's formatted
-        if incremental_cache.is_file_same(&file_path, &file_contents.text) {
+        if !strip_bom
+          && incremental_cache.is_file_same(&file_path, &file_contents.text)
+        {
           return Ok(());
         }
 
-        match format_ensure_stable(
+        let format_result = format_ensure_stable(
           &file_path,
           &file_contents.text,
           |file_path, file_text| {
-            format_file(
+            format_file_with_options(
               file_path,
               file_text,
               &fmt_options,
               &unstable_options,
+              &file_options,
               ext.clone(),
             )
           },
-        ) {
+        )
+        .map(|formatted_text| {
+          // removing the byte order mark requires writing the file even
+          // when the text is already formatted
+          formatted_text
+            .or_else(|| strip_bom.then(|| file_contents.text.clone()))
+        });
+        match format_result {
           Ok(Some(formatted_text)) => {
             incremental_cache.update_file(&file_path, &formatted_text);
             write_file_contents(
               &file_path,
               FileContents {
-                had_bom: file_contents.had_bom,
+                had_bom: file_contents.had_bom && !strip_bom,
                 text: formatted_text,
               },
             )?;
@@ -818,11 +1023,17 @@ fn format_stdin(
     bail!("Failed to read from stdin");
   }
   let file_path = PathBuf::from(format!("_stdin.{ext}"));
-  let formatted_text = format_file(
+  if fmt_options.file.bom == FmtBom::Strip {
+    if let Some(text) = source.strip_prefix('\u{FEFF}') {
+      source = text.to_string();
+    }
+  }
+  let formatted_text = format_file_with_options(
     &file_path,
     &source,
     &fmt_options.options,
     &fmt_options.unstable,
+    &fmt_options.file,
     None,
   )?;
   if fmt_flags.check {
@@ -1252,6 +1463,76 @@ mod test {
     assert!(is_supported_ext_fmt(Path::new("foo.ipynb")));
   }
 
+  #[test]
+  fn test_apply_line_ending() {
+    let text = "a\nb\n".to_string();
+    assert_eq!(apply_line_ending("", text.clone(), FmtLineEnding::Lf), text);
+    assert_eq!(
+      apply_line_ending("", text.clone(), FmtLineEnding::Crlf),
+      "a\r\nb\r\n"
+    );
+    assert_eq!(
+      apply_line_ending("x\r\ny\n", text.clone(), FmtLineEnding::Auto),
+      "a\r\nb\r\n"
+    );
+    assert_eq!(
+      apply_line_ending(
+        "x\ny\r\n",
+        "a\r\nb\n".to_string(),
+        FmtLineEnding::Auto
+      ),
+      text
+    );
+    assert_eq!(
+      apply_line_ending("x", text.clone(), FmtLineEnding::Auto),
+      text
+    );
+  }
+
+  #[test]
+  fn test_format_file_with_line_ending() {
+    let file_options = FmtFileOptions {
+      line_ending: Some(FmtLineEnding::Crlf),
+      ..Default::default()
+    };
+    let format = |file_text: &str| {
+      format_file_with_options(
+        &PathBuf::from("mod.ts"),
+        file_text,
+        &Default::default(),
+        &Default::default(),
+        &file_options,
+        None,
+      )
+      .unwrap()
+    };
+    assert_eq!(format("1;\n").unwrap(), "1;\r\n");
+    assert_eq!(format("1;\r\n"), None);
+  }
+
+  #[test]
+  fn test_format_markdown_skip_code_blocks() {
+    let file_text = "```ts\nconst a=1\n```\n\n```js\nlet b=2\n```\n";
+    let format = |skip_code_blocks: &[String]| {
+      format_markdown(
+        file_text,
+        &Default::default(),
+        &Default::default(),
+        skip_code_blocks,
+      )
+      .unwrap()
+      .unwrap()
+    };
+    assert_eq!(
+      format(&[]),
+      "```ts\nconst a = 1;\n```\n\n```js\nlet b = 2;\n```\n"
+    );
+    assert_eq!(
+      format(&["typescript".to_string()]),
+      "```ts\nconst a=1\n```\n\n```js\nlet b = 2;\n```\n"
+    );
+  }
+
   #[test]
   #[should_panic(expected = "Formatting not stable. Bailed after 5 tries.")]
   fn test_format_ensure_stable_unstable_format() {
@@ -1311,6 +1592,7 @@ mod test {
         ..Default::default()
       },
       &UnstableFmtOptions::default(),
+      &[],
       None,
     )
     .unwrap()
@@ -1,16 +1,22 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::fmt;
 use std::fmt::Write;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::SystemTime;
 
 use deno_ast::ModuleSpecifier;
 use deno_core::anyhow::bail;
 use deno_core::error::AnyError;
 use deno_core::resolve_url_or_path;
 use deno_core::serde_json;
+use deno_core::url::Url;
 use deno_graph::Dependency;
 use deno_graph::GraphKind;
 use deno_graph::Module;
@@ -24,14 +30,24 @@ use deno_semver::npm::NpmPackageNvReference;
 use deno_semver::npm::NpmPackageReqReference;
 use deno_semver::package::PackageNv;
 use deno_terminal::colors;
+use indexmap::map::Entry;
+use indexmap::IndexMap;
+use indexmap::IndexSet;
+use serde::Deserialize;
+use serde::Serialize;
 
 use crate::args::Flags;
 use crate::args::InfoFlags;
+use crate::args::InfoGraphFormat;
+use crate::cache::npm_cache_package_dirs;
+use crate::cache::read_http_cache_entries;
+use crate::cache::DiskUsage;
 use crate::display;
 use crate::factory::CliFactory;
 use crate::graph_util::graph_exit_integrity_errors;
 use crate::npm::CliNpmResolver;
 use crate::npm::ManagedCliNpmResolver;
+use crate::tools::test::fmt::to_relative_path_or_remote_url;
 use crate::util::checksum;
 
 const JSON_SCHEMA_VERSION: u8 = 1;
@@ -40,6 +56,9 @@ pub async fn info(
   flags: Arc<Flags>,
   info_flags: InfoFlags,
 ) -> Result<(), AnyError> {
+  if info_flags.unused {
+    return crate::tools::registry::unused(flags, info_flags).await;
+  }
   let factory = CliFactory::from_flags(flags);
   let cli_options = factory.cli_options()?;
   if let Some(specifier) = info_flags.file {
@@ -79,7 +98,22 @@ pub async fn info(
       lockfile.write_if_changed()?;
     }
 
-    if info_flags.json {
+    if let Some(format) = info_flags.graph {
+      let cwd = Url::from_directory_path(cli_options.initial_cwd()).unwrap();
+      let mut output = String::new();
+      GraphDiagramContext::write(
+        &graph,
+        npm_resolver.as_ref(),
+        GraphDiagramOptions {
+          format,
+          cwd,
+          max_depth: info_flags.graph_depth,
+          collapse_npm: info_flags.collapse_npm,
+        },
+        &mut output,
+      )?;
+      display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+    } else if info_flags.json {
       let mut json_graph = serde_json::json!(graph);
       if let Some(output) = json_graph.as_object_mut() {
         output.shift_insert(
@@ -95,6 +129,10 @@ pub async fn info(
       GraphDisplayContext::write(&graph, npm_resolver.as_ref(), &mut output)?;
       display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
     }
+  } else if info_flags.http_cache {
+    print_http_cache_entries(&factory, info_flags.json)?;
+  } else if info_flags.cache_usage {
+    print_cache_usage(&factory, info_flags.json, cli_options.initial_cwd())?;
   } else {
     // If it was just "deno info" print location of caches and exit
     print_cache_info(
@@ -106,6 +144,370 @@ pub async fn info(
   Ok(())
 }
 
+fn print_http_cache_entries(
+  factory: &CliFactory,
+  json: bool,
+) -> Result<(), AnyError> {
+  let remote_dir = factory.deno_dir()?.remote_folder_path();
+  let entries = read_http_cache_entries(&remote_dir)?;
+  if json {
+    let entries = entries
+      .iter()
+      .map(|entry| {
+        serde_json::json!({
+          "url": entry.url,
+          "path": entry.path,
+          "size": entry.size,
+          "downloadTime": entry.download_time.and_then(|time| {
+            time.duration_since(SystemTime::UNIX_EPOCH).ok()
+          }).map(|duration| duration.as_secs()),
+          "headers": entry.headers,
+        })
+      })
+      .collect::<Vec<_>>();
+    return display::write_json_to_stdout(&serde_json::json!({
+      "version": JSON_SCHEMA_VERSION,
+      "entries": entries,
+    }));
+  }
+
+  let now = SystemTime::now();
+  let mut output = String::new();
+  for entry in &entries {
+    let age = entry
+      .download_time
+      .and_then(|time| now.duration_since(time).ok())
+      .map(|age| format!(", {} old", human_age(age)))
+      .unwrap_or_default();
+    writeln!(
+      output,
+      "{} {}",
+      entry.url,
+      colors::gray(format!(
+        "({}{})",
+        display::human_size(entry.size as f64),
+        age
+      ))
+    )?;
+    let mut headers = entry.headers.iter().collect::<Vec<_>>();
+    headers.sort();
+    for (name, value) in headers {
+      writeln!(output, "  {}: {}", colors::gray(name), value)?;
+    }
+  }
+  let total_size = entries.iter().map(|entry| entry.size).sum::<u64>();
+  writeln!(
+    output,
+    "{} {}",
+    colors::bold(format!("{} cached remote modules", entries.len())),
+    colors::gray(format!("({})", display::human_size(total_size as f64)))
+  )?;
+  display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+  Ok(())
+}
+
+/// The number of largest npm packages listed by `deno info --cache-usage`.
+const LARGEST_NPM_PACKAGES_COUNT: usize = 10;
+
+/// The sizes recorded by the previous `deno info --cache-usage`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheUsageSnapshot {
+  /// Seconds since the unix epoch.
+  time: u64,
+  total: u64,
+  caches: BTreeMap<String, u64>,
+}
+
+impl CacheUsageSnapshot {
+  fn read(path: &Path) -> Option<Self> {
+    let text = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&text).ok()
+  }
+
+  fn write(&self, path: &Path) {
+    let result = serde_json::to_string(self)
+      .map_err(AnyError::from)
+      .and_then(|text| Ok(std::fs::write(path, text)?));
+    if let Err(err) = result {
+      log::debug!("Failed writing {}: {:#}", path.display(), err);
+    }
+  }
+
+  fn growth_since(&self, previous: &CacheUsageSnapshot) -> CacheUsageGrowth {
+    let delta = |now: u64, before: u64| now as i64 - before as i64;
+    CacheUsageGrowth {
+      since: Duration::from_secs(self.time.saturating_sub(previous.time)),
+      total: delta(self.total, previous.total),
+      caches: self
+        .caches
+        .iter()
+        .map(|(key, bytes)| {
+          let before = previous.caches.get(key).copied().unwrap_or(0);
+          (key.clone(), delta(*bytes, before))
+        })
+        .collect(),
+    }
+  }
+}
+
+/// The growth of the caches since the previous report.
+#[derive(Debug, PartialEq, Eq)]
+struct CacheUsageGrowth {
+  since: Duration,
+  total: i64,
+  caches: BTreeMap<String, i64>,
+}
+
+fn print_cache_usage(
+  factory: &CliFactory,
+  json: bool,
+  cwd: &Path,
+) -> Result<(), AnyError> {
+  let dir = factory.deno_dir()?;
+  let code_cache_file_name = dir
+    .code_cache_db_file_path()
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  // the code cache is a sqlite database, which may have -wal and -shm files
+  let code_cache = std::fs::read_dir(&dir.root)
+    .into_iter()
+    .flatten()
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| {
+      entry
+        .file_name()
+        .to_string_lossy()
+        .starts_with(&code_cache_file_name)
+    })
+    .fold(DiskUsage::default(), |usage, entry| {
+      usage.add(DiskUsage::of_path(&entry.path()))
+    });
+  let categories = [
+    (
+      "remoteModules",
+      "Remote modules",
+      DiskUsage::of_path(&dir.remote_folder_path()),
+    ),
+    (
+      "npmPackages",
+      "npm packages",
+      DiskUsage::of_path(&dir.npm_folder_path()),
+    ),
+    (
+      "emittedModules",
+      "Emitted modules",
+      DiskUsage::of_path(&dir.gen_cache.location),
+    ),
+    ("codeCache", "V8 code cache", code_cache),
+  ];
+  // the snapshot of the previous report isn't a cache, so leave it out
+  let snapshot_path = dir.cache_usage_file_path();
+  let total = DiskUsage::of_path(&dir.root)
+    .saturating_sub(DiskUsage::of_path(&snapshot_path));
+  let known = categories
+    .iter()
+    .fold(DiskUsage::default(), |usage, (_, _, c)| usage.add(*c));
+  let other = total.saturating_sub(known);
+
+  let mut largest_npm_packages =
+    npm_cache_package_dirs(&dir.npm_folder_path())?
+      .into_iter()
+      .map(|package| {
+        let usage = DiskUsage::of_path(&package.path);
+        (package, usage)
+      })
+      .collect::<Vec<_>>();
+  largest_npm_packages.sort_by(|(a, a_usage), (b, b_usage)| {
+    b_usage
+      .bytes
+      .cmp(&a_usage.bytes)
+      .then_with(|| a.name.cmp(&b.name))
+  });
+  largest_npm_packages.truncate(LARGEST_NPM_PACKAGES_COUNT);
+
+  // coverage data isn't stored in DENO_DIR, but in the directory
+  // `deno test --coverage` writes to by default
+  let coverage_dir = cwd.join("coverage");
+  let coverage = coverage_dir
+    .is_dir()
+    .then(|| DiskUsage::of_path(&coverage_dir));
+  // likewise for the directory `deno doc --html` writes to by default
+  let docs_dir = cwd.join("docs");
+  let docs = docs_dir
+    .join("all_symbols.html")
+    .is_file()
+    .then(|| DiskUsage::of_path(&docs_dir));
+
+  let now = SystemTime::now()
+    .duration_since(SystemTime::UNIX_EPOCH)
+    .unwrap_or_default();
+  let snapshot = CacheUsageSnapshot {
+    time: now.as_secs(),
+    total: total.bytes,
+    caches: categories
+      .iter()
+      .map(|(key, _, usage)| (key.to_string(), usage.bytes))
+      .collect(),
+  };
+  let growth = CacheUsageSnapshot::read(&snapshot_path)
+    .map(|previous| snapshot.growth_since(&previous));
+  snapshot.write(&snapshot_path);
+
+  if json {
+    let usage_json = |usage: &DiskUsage| serde_json::json!({ "files": usage.files, "bytes": usage.bytes });
+    let mut caches = serde_json::Map::new();
+    for (key, _, usage) in &categories {
+      caches.insert(key.to_string(), usage_json(usage));
+    }
+    caches.insert("other".to_string(), usage_json(&other));
+    return display::write_json_to_stdout(&serde_json::json!({
+      "version": JSON_SCHEMA_VERSION,
+      "denoDir": dir.root,
+      "total": usage_json(&total),
+      "caches": caches,
+      "largestNpmPackages": largest_npm_packages
+        .iter()
+        .map(|(package, usage)| serde_json::json!({
+          "name": package.name,
+          "version": package.version,
+          "bytes": usage.bytes,
+        }))
+        .collect::<Vec<_>>(),
+      "coverage": coverage.map(|usage| serde_json::json!({
+        "path": coverage_dir,
+        "files": usage.files,
+        "bytes": usage.bytes,
+      })),
+      "docs": docs.map(|usage| serde_json::json!({
+        "path": docs_dir,
+        "files": usage.files,
+        "bytes": usage.bytes,
+      })),
+      "growth": growth.as_ref().map(|growth| serde_json::json!({
+        "sinceSeconds": growth.since.as_secs(),
+        "bytes": growth.total,
+        "caches": growth.caches,
+      })),
+    }));
+  }
+
+  let mut output = String::new();
+  writeln!(
+    output,
+    "{} {}",
+    colors::bold("DENO_DIR location:"),
+    dir.root_path_for_display()
+  )?;
+  writeln!(output)?;
+  let write_usage = |output: &mut String,
+                     label: &str,
+                     usage: &DiskUsage,
+                     growth: Option<i64>|
+   -> fmt::Result {
+    write!(
+      output,
+      "{} {:>10} {}",
+      colors::bold(format!("{label:<18}")),
+      display::human_size(usage.bytes as f64),
+      colors::gray(format!("({} files)", usage.files))
+    )?;
+    if let Some(growth) = growth.filter(|growth| *growth != 0) {
+      let sign = if growth > 0 { "+" } else { "" };
+      write!(
+        output,
+        " {}",
+        colors::yellow(format!("{sign}{}", display::human_size(growth as f64)))
+      )?;
+    }
+    writeln!(output)
+  };
+  for (key, label, usage) in &categories {
+    let cache_growth = growth
+      .as_ref()
+      .and_then(|growth| growth.caches.get(*key).copied());
+    write_usage(&mut output, label, usage, cache_growth)?;
+  }
+  write_usage(&mut output, "Other", &other, None)?;
+  write_usage(
+    &mut output,
+    "Total",
+    &total,
+    growth.as_ref().map(|growth| growth.total),
+  )?;
+  if let Some(growth) = &growth {
+    writeln!(
+      output,
+      "{}",
+      colors::gray(format!(
+        "Growth since the last report {} ago",
+        human_age(growth.since)
+      ))
+    )?;
+  }
+
+  if !largest_npm_packages.is_empty() {
+    writeln!(output)?;
+    writeln!(output, "{}", colors::bold("Largest npm packages:"))?;
+    for (package, usage) in &largest_npm_packages {
+      writeln!(
+        output,
+        "  {:<40} {:>10}",
+        format!("{}@{}", package.name, package.version),
+        display::human_size(usage.bytes as f64)
+      )?;
+    }
+  }
+
+  if let Some(coverage) = coverage {
+    writeln!(output)?;
+    writeln!(
+      output,
+      "{} {} {}",
+      colors::bold(format!("Coverage data ({}):", coverage_dir.display())),
+      display::human_size(coverage.bytes as f64),
+      colors::gray(format!("({} files)", coverage.files))
+    )?;
+  }
+
+  if let Some(docs) = docs {
+    if coverage.is_none() {
+      writeln!(output)?;
+    }
+    writeln!(
+      output,
+      "{} {} {}",
+      colors::bold(format!("Generated docs ({}):", docs_dir.display())),
+      display::human_size(docs.bytes as f64),
+      colors::gray(format!("({} files)", docs.files))
+    )?;
+  }
+
+  writeln!(output)?;
+  writeln!(
+    output,
+    "{}",
+    colors::gray(
+      "Run `deno clean --npm`, `--remote` or `--codegen` to remove a cache, or add `--older-than` to only remove old entries."
+    )
+  )?;
+  display::write_to_stdout_ignore_sigpipe(output.as_bytes())?;
+  Ok(())
+}
+
+fn human_age(age: Duration) -> String {
+  let secs = age.as_secs();
+  if secs >= 86400 {
+    format!("{}d", secs / 86400)
+  } else if secs >= 3600 {
+    format!("{}h", secs / 3600)
+  } else if secs >= 60 {
+    format!("{}m", secs / 60)
+  } else {
+    format!("{secs}s")
+  }
+}
+
 #[allow(clippy::print_stdout)]
 fn print_cache_info(
   factory: &CliFactory,
@@ -720,6 +1122,188 @@ impl<'a> GraphDisplayContext<'a> {
   }
 }
 
+struct GraphDiagramOptions {
+  format: InfoGraphFormat,
+  cwd: Url,
+  max_depth: Option<usize>,
+  collapse_npm: bool,
+}
+
+enum DiagramNode<'a> {
+  Module(&'a Module),
+  Package(NpmPackageId),
+}
+
+/// Renders the module graph as a diagram with a node per module or npm
+/// package. Type dependencies are drawn with dashed edges.
+struct GraphDiagramContext<'a> {
+  graph: &'a ModuleGraph,
+  npm_info: NpmInfo,
+  options: GraphDiagramOptions,
+  /// Labels of the nodes by the module specifier or npm package id.
+  nodes: IndexMap<String, String>,
+  /// The indexes of the nodes of each edge and if it's a type dependency.
+  edges: IndexSet<(usize, usize, bool)>,
+}
+
+impl<'a> GraphDiagramContext<'a> {
+  pub fn write<TWrite: Write>(
+    graph: &'a ModuleGraph,
+    npm_resolver: &'a dyn CliNpmResolver,
+    options: GraphDiagramOptions,
+    writer: &mut TWrite,
+  ) -> Result<(), AnyError> {
+    if graph.roots.len() != 1 {
+      bail!("displaying graphs that have multiple roots is not supported.");
+    }
+    let Some(root) = graph.get(graph.resolve(&graph.roots[0])) else {
+      bail!("module could not be found");
+    };
+    let npm_info = match npm_resolver.as_managed() {
+      Some(npm_resolver) => {
+        let npm_snapshot = npm_resolver.snapshot();
+        NpmInfo::build(graph, npm_resolver, &npm_snapshot)
+      }
+      None => NpmInfo::default(),
+    };
+    let mut context = Self {
+      graph,
+      npm_info,
+      options,
+      nodes: Default::default(),
+      edges: Default::default(),
+    };
+    context.build(root);
+    match context.options.format {
+      InfoGraphFormat::Dot => context.write_dot(writer)?,
+      InfoGraphFormat::Mermaid => context.write_mermaid(writer)?,
+    }
+    Ok(())
+  }
+
+  fn build(&mut self, root: &'a Module) {
+    let root = self.diagram_node(root);
+    self.add_node(&root);
+    let mut pending = VecDeque::from([(root, 0)]);
+    while let Some((node, depth)) = pending.pop_front() {
+      if self.options.max_depth.is_some_and(|max| depth >= max) {
+        continue;
+      }
+      let (from, _) = self.add_node(&node);
+      for (child, type_dep) in self.children(&node) {
+        let (to, is_new) = self.add_node(&child);
+        self.edges.insert((from, to, type_dep));
+        if is_new {
+          pending.push_back((child, depth + 1));
+        }
+      }
+    }
+  }
+
+  fn diagram_node(&self, module: &'a Module) -> DiagramNode<'a> {
+    module
+      .npm()
+      .and_then(|npm| self.npm_info.resolve_package(npm.nv_reference.nv()))
+      .map(|package| DiagramNode::Package(package.id.clone()))
+      .unwrap_or(DiagramNode::Module(module))
+  }
+
+  /// Adds the node if it's new, returning its index and if it was added.
+  fn add_node(&mut self, node: &DiagramNode) -> (usize, bool) {
+    let (key, label) = match node {
+      DiagramNode::Module(module) => (
+        module.specifier().to_string(),
+        to_relative_path_or_remote_url(
+          &self.options.cwd,
+          module.specifier().as_str(),
+        ),
+      ),
+      DiagramNode::Package(id) => {
+        (id.as_serialized(), format!("npm:{}", id.nv))
+      }
+    };
+    match self.nodes.entry(key) {
+      Entry::Occupied(entry) => (entry.index(), false),
+      Entry::Vacant(entry) => {
+        let index = entry.index();
+        entry.insert(label);
+        (index, true)
+      }
+    }
+  }
+
+  /// The dependencies of the node and if they're type dependencies.
+  fn children(&self, node: &DiagramNode<'a>) -> Vec<(DiagramNode<'a>, bool)> {
+    let mut children = Vec::new();
+    match node {
+      DiagramNode::Module(module) => {
+        let Some(module) = module.js() else {
+          return children;
+        };
+        let mut add_resolution = |resolution: &Resolution, type_dep: bool| {
+          if let Resolution::Ok(resolved) = resolution {
+            let specifier = self.graph.resolve(&resolved.specifier);
+            if let Some(module) = self.graph.get(specifier) {
+              children.push((self.diagram_node(module), type_dep));
+            }
+          }
+        };
+        if let Some(types_dep) = &module.maybe_types_dependency {
+          add_resolution(&types_dep.dependency, true);
+        }
+        for dep in module.dependencies.values() {
+          add_resolution(&dep.maybe_code, false);
+          add_resolution(&dep.maybe_type, true);
+        }
+      }
+      DiagramNode::Package(id) => {
+        if self.options.collapse_npm {
+          return children;
+        }
+        if let Some(package) = self.npm_info.packages.get(id) {
+          let mut ids = package.dependencies.values().collect::<Vec<_>>();
+          ids.sort();
+          children.extend(
+            ids
+              .into_iter()
+              .map(|id| (DiagramNode::Package(id.clone()), false)),
+          );
+        }
+      }
+    }
+    children
+  }
+
+  fn write_dot<TWrite: Write>(&self, writer: &mut TWrite) -> fmt::Result {
+    writeln!(writer, "digraph {{")?;
+    writeln!(writer, "  node [shape=box];")?;
+    for (index, label) in self.nodes.values().enumerate() {
+      let label = label.replace('\\', "\\\\").replace('"', "\\\"");
+      writeln!(writer, "  n{} [label=\"{}\"];", index, label)?;
+    }
+    for (from, to, type_dep) in &self.edges {
+      if *type_dep {
+        writeln!(writer, "  n{} -> n{} [style=dashed];", from, to)?;
+      } else {
+        writeln!(writer, "  n{} -> n{};", from, to)?;
+      }
+    }
+    writeln!(writer, "}}")
+  }
+
+  fn write_mermaid<TWrite: Write>(&self, writer: &mut TWrite) -> fmt::Result {
+    writeln!(writer, "graph LR")?;
+    for (index, label) in self.nodes.values().enumerate() {
+      writeln!(writer, "  n{}[\"{}\"]", index, label.replace('"', "#quot;"))?;
+    }
+    for (from, to, type_dep) in &self.edges {
+      let arrow = if *type_dep { "-.->" } else { "-->" };
+      writeln!(writer, "  n{} {} n{}", from, arrow, to)?;
+    }
+    Ok(())
+  }
+}
+
 fn maybe_size_to_text(maybe_size: Option<u64>) -> String {
   colors::gray(format!(
     "({})",
@@ -730,3 +1314,70 @@ fn maybe_size_to_text(maybe_size: Option<u64>) -> String {
   ))
   .to_string()
 }
+
+#[cfg(test)]
+mod test {
+  use test_util::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn cache_usage_snapshot_roundtrip() {
+    let temp_dir = TempDir::new();
+    let path = temp_dir.path().join("cache_usage.json");
+    assert!(CacheUsageSnapshot::read(path.as_path()).is_none());
+
+    let snapshot = CacheUsageSnapshot {
+      time: 100,
+      total: 2048,
+      caches: BTreeMap::from([("npmPackages".to_string(), 1024)]),
+    };
+    snapshot.write(path.as_path());
+    let read = CacheUsageSnapshot::read(path.as_path()).unwrap();
+    assert_eq!(read.time, 100);
+    assert_eq!(read.total, 2048);
+    assert_eq!(read.caches, snapshot.caches);
+
+    path.write("not json");
+    assert!(CacheUsageSnapshot::read(path.as_path()).is_none());
+  }
+
+  #[test]
+  fn cache_usage_growth_since() {
+    let previous = CacheUsageSnapshot {
+      time: 1_000,
+      total: 5_000,
+      caches: BTreeMap::from([
+        ("npmPackages".to_string(), 3_000),
+        ("remoteModules".to_string(), 2_000),
+      ]),
+    };
+    let current = CacheUsageSnapshot {
+      time: 4_600,
+      total: 5_500,
+      caches: BTreeMap::from([
+        ("npmPackages".to_string(), 4_000),
+        ("remoteModules".to_string(), 1_000),
+        ("codeCache".to_string(), 500),
+      ]),
+    };
+    assert_eq!(
+      current.growth_since(&previous),
+      CacheUsageGrowth {
+        since: Duration::from_secs(3_600),
+        total: 500,
+        caches: BTreeMap::from([
+          ("codeCache".to_string(), 500),
+          ("npmPackages".to_string(), 1_000),
+          ("remoteModules".to_string(), -1_000),
+        ]),
+      }
+    );
+
+    // a clock that went backwards doesn't underflow
+    assert_eq!(
+      previous.growth_since(&current).since,
+      Duration::from_secs(0)
+    );
+  }
+}
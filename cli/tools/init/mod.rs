@@ -9,6 +9,8 @@ use log::info;
 use std::io::Write;
 use std::path::Path;
 
+mod template;
+
 pub fn init_project(init_flags: InitFlags) -> Result<(), AnyError> {
   let cwd =
     std::env::current_dir().context("Can't read current working directory.")?;
@@ -17,9 +19,18 @@ pub fn init_project(init_flags: InitFlags) -> Result<(), AnyError> {
     std::fs::create_dir_all(&dir)?;
     dir
   } else {
-    cwd
+    cwd.clone()
   };
 
+  if let Some(template) = &init_flags.template {
+    return template::init_from_template(
+      template,
+      &cwd,
+      &dir,
+      init_flags.dir.as_deref(),
+    );
+  }
+
   if init_flags.serve {
     create_file(
       &dir,
@@ -109,6 +120,86 @@ Deno.test(async function serverFetchStatic() {
         }
       }),
     )?;
+  } else if init_flags.npm_compat {
+    let project_name = dir
+      .file_name()
+      .unwrap_or_else(|| dir.as_os_str())
+      .to_str()
+      .unwrap();
+
+    create_file(
+      &dir,
+      "main.ts",
+      r#"import { readFile } from "node:fs/promises";
+import chalk from "chalk";
+
+export function add(a: number, b: number): number {
+  return a + b;
+}
+
+if (import.meta.main) {
+  const pkg = JSON.parse(await readFile("package.json", "utf8"));
+  console.log(chalk.green(`Hello from ${pkg.name}!`));
+  console.log("Add 2 + 3 =", add(2, 3));
+}
+"#,
+    )?;
+    create_file(
+      &dir,
+      "main_test.ts",
+      r#"import assert from "node:assert";
+import { add } from "./main.ts";
+
+Deno.test(function addTest() {
+  assert.strictEqual(add(2, 3), 5);
+});
+"#,
+    )?;
+
+    create_json_file(
+      &dir,
+      "package.json",
+      &json!({
+        "name": project_name,
+        "version": "0.1.0",
+        "type": "module",
+        "scripts": {
+          "dev": "deno run --watch -R main.ts",
+          "start": "deno run -R main.ts",
+          "test": "deno test -R"
+        },
+        "dependencies": {
+          "chalk": "^5.3.0"
+        },
+        "devDependencies": {
+          "@types/node": "^22.0.0"
+        }
+      }),
+    )?;
+    // for editors and Node.js tools which read the tsconfig.json
+    create_json_file(
+      &dir,
+      "tsconfig.json",
+      &json!({
+        "compilerOptions": {
+          "target": "ES2022",
+          "module": "NodeNext",
+          "moduleResolution": "NodeNext",
+          "allowImportingTsExtensions": true,
+          "noEmit": true,
+          "strict": true,
+          "types": ["node"]
+        }
+      }),
+    )?;
+    create_json_file(
+      &dir,
+      "deno.json",
+      &json!({
+        // use the node_modules directory created by the package manager
+        "nodeModulesDir": "manual"
+      }),
+    )?;
   } else if init_flags.lib {
     // Extract the directory name to use as the project name
     let project_name = dir
@@ -213,6 +304,21 @@ Deno.test(function addTest() {
     info!("");
     info!("  {}", colors::gray("# Run the tests"));
     info!("  deno test -R");
+  } else if init_flags.npm_compat {
+    info!("  {}", colors::gray("# Install the dependencies"));
+    info!("  npm install");
+    info!("");
+    info!("  {}", colors::gray("# Run the program"));
+    info!("  deno run -R main.ts");
+    info!("");
+    info!(
+      "  {}",
+      colors::gray("# Run the program and watch for file changes")
+    );
+    info!("  deno task dev");
+    info!("");
+    info!("  {}", colors::gray("# Run the tests"));
+    info!("  deno test -R");
   } else if init_flags.lib {
     info!("  {}", colors::gray("# Run the tests"));
     info!("  deno test");
@@ -256,7 +362,14 @@ fn create_file(
   filename: &str,
   content: &str,
 ) -> Result<(), AnyError> {
-  let path = dir.join(filename);
+  create_file_at(&dir.join(filename), filename, content.as_bytes())
+}
+
+fn create_file_at(
+  path: &Path,
+  filename: &str,
+  content: &[u8],
+) -> Result<(), AnyError> {
   if path.exists() {
     info!(
       "ℹ️ {}",
@@ -269,7 +382,7 @@ fn create_file(
       .create_new(true)
       .open(path)
       .with_context(|| format!("Failed to create {filename} file"))?;
-    file.write_all(content.as_bytes())?;
+    file.write_all(content)?;
     Ok(())
   }
 }
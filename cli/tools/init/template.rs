@@ -0,0 +1,270 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use serde::Deserialize;
+
+use crate::colors;
+
+/// The name of the file in the root of a template which describes the
+/// variables to substitute. It's not copied to the project.
+const TEMPLATE_MANIFEST_FILE_NAME: &str = "deno-template.json";
+
+/// Built-in templates which are scaffolded by running a script rather than
+/// by copying files.
+const SCRIPT_TEMPLATES: &[(&str, &str)] = &[("fresh", "jsr:@fresh/init")];
+
+enum TemplateSource {
+  Script(&'static str),
+  Git(String),
+  Local(PathBuf),
+}
+
+fn resolve_template_source(
+  template: &str,
+  cwd: &Path,
+) -> Result<TemplateSource, AnyError> {
+  if let Some((_, script)) =
+    SCRIPT_TEMPLATES.iter().find(|(name, _)| *name == template)
+  {
+    return Ok(TemplateSource::Script(script));
+  }
+  if template.starts_with("https://")
+    || template.starts_with("http://")
+    || template.starts_with("git@")
+    || template.ends_with(".git")
+  {
+    return Ok(TemplateSource::Git(template.to_string()));
+  }
+  let path = cwd.join(template);
+  if path.is_dir() {
+    return Ok(TemplateSource::Local(path));
+  }
+  bail!(
+    "Unknown template \"{}\". Use one of script, lib, serve, {}, a git repository url or a path to a local directory.",
+    template,
+    SCRIPT_TEMPLATES
+      .iter()
+      .map(|(name, _)| *name)
+      .collect::<Vec<_>>()
+      .join(", ")
+  )
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TemplateManifest {
+  #[serde(default)]
+  variables: BTreeMap<String, TemplateVariable>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TemplateVariable {
+  prompt: Option<String>,
+  default: Option<String>,
+}
+
+/// Scaffolds the project in the directory from a template which isn't one of
+/// the hardcoded layouts.
+pub fn init_from_template(
+  template: &str,
+  cwd: &Path,
+  dir: &Path,
+  dir_arg: Option<&str>,
+) -> Result<(), AnyError> {
+  match resolve_template_source(template, cwd)? {
+    // the script reports the next steps itself
+    TemplateSource::Script(script) => {
+      let status = std::process::Command::new(std::env::current_exe()?)
+        .args(["run", "-A", "-r", script])
+        .arg(dir)
+        .status()
+        .with_context(|| format!("Failed running {script}"))?;
+      if !status.success() {
+        bail!("Failed scaffolding the {} template.", template);
+      }
+      return Ok(());
+    }
+    TemplateSource::Git(url) => {
+      let temp_dir = tempfile::tempdir()?;
+      let checkout_dir = temp_dir.path().join("template");
+      log::info!("{} {}", colors::green("Cloning"), url);
+      let output = std::process::Command::new("git")
+        .args(["clone", "--depth", "1", "--quiet", "--", &url])
+        .arg(&checkout_dir)
+        .output()
+        .context("Failed running git. Is it installed?")?;
+      if !output.status.success() {
+        bail!(
+          "Failed cloning {}:\n{}",
+          url,
+          String::from_utf8_lossy(&output.stderr).trim()
+        );
+      }
+      copy_template(&checkout_dir, dir)?;
+    }
+    TemplateSource::Local(template_dir) => copy_template(&template_dir, dir)?,
+  }
+
+  log::info!("✅ {}", colors::green("Project initialized"));
+  if let Some(dir) = dir_arg {
+    log::info!("");
+    log::info!("{}", colors::gray("Run this command to get started"));
+    log::info!("");
+    log::info!("  cd {}", dir);
+  }
+  Ok(())
+}
+
+fn copy_template(template_dir: &Path, dir: &Path) -> Result<(), AnyError> {
+  let manifest_path = template_dir.join(TEMPLATE_MANIFEST_FILE_NAME);
+  let manifest = match std::fs::read_to_string(&manifest_path) {
+    Ok(text) => serde_json::from_str::<TemplateManifest>(&text)
+      .with_context(|| format!("Failed parsing {}", manifest_path.display()))?,
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+      TemplateManifest::default()
+    }
+    Err(err) => return Err(err.into()),
+  };
+  let project_name = dir
+    .file_name()
+    .map(|name| name.to_string_lossy().into_owned())
+    .unwrap_or_default();
+  let variables = resolve_variables(&manifest, &project_name)?;
+
+  for entry in walkdir::WalkDir::new(template_dir)
+    .into_iter()
+    .filter_entry(|entry| entry.file_name() != ".git")
+  {
+    let entry = entry?;
+    if !entry.file_type().is_file() {
+      continue;
+    }
+    let relative_path = entry.path().strip_prefix(template_dir)?;
+    if relative_path == Path::new(TEMPLATE_MANIFEST_FILE_NAME) {
+      continue;
+    }
+    let target_path = dir.join(relative_path);
+    if let Some(parent) = target_path.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+    let bytes = std::fs::read(entry.path())?;
+    let content = match String::from_utf8(bytes) {
+      Ok(text) => substitute_variables(&text, &variables).into_bytes(),
+      Err(err) => err.into_bytes(), // binary files are copied as is
+    };
+    let display_path = relative_path.to_string_lossy().replace('\\', "/");
+    super::create_file_at(&target_path, &display_path, &content)?;
+  }
+  Ok(())
+}
+
+/// Resolves the values of the variables of the template by prompting for
+/// them, or by using their default values when not running interactively.
+/// The `name` variable is always available and defaults to the name of the
+/// project directory.
+fn resolve_variables(
+  manifest: &TemplateManifest,
+  project_name: &str,
+) -> Result<BTreeMap<String, String>, AnyError> {
+  let mut values = BTreeMap::new();
+  values.insert("name".to_string(), project_name.to_string());
+  let is_interactive = std::io::stdin().is_terminal();
+  for (name, variable) in &manifest.variables {
+    let default = variable
+      .default
+      .as_ref()
+      .map(|default| substitute_variables(default, &values))
+      .or_else(|| values.get(name).cloned());
+    let value = if is_interactive {
+      prompt_variable(
+        variable.prompt.as_deref().unwrap_or(name),
+        default.as_deref(),
+      )?
+      .or(default)
+    } else {
+      default
+    };
+    let Some(value) = value else {
+      bail!("Missing a value for the template variable \"{}\".", name);
+    };
+    values.insert(name.clone(), value);
+  }
+  Ok(values)
+}
+
+#[allow(clippy::print_stderr)]
+fn prompt_variable(
+  prompt: &str,
+  default: Option<&str>,
+) -> Result<Option<String>, AnyError> {
+  match default {
+    Some(default) => eprint!("{} {}: ", prompt, colors::gray(default)),
+    None => eprint!("{}: ", prompt),
+  }
+  std::io::stderr().flush()?;
+  let mut answer = String::new();
+  std::io::stdin()
+    .read_line(&mut answer)
+    .context("Failed reading from standard input")?;
+  let answer = answer.trim();
+  Ok((!answer.is_empty()).then(|| answer.to_string()))
+}
+
+/// Replaces the `{{ variable }}` placeholders of the known variables.
+fn substitute_variables(
+  text: &str,
+  variables: &BTreeMap<String, String>,
+) -> String {
+  let mut output = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find("{{") {
+    output.push_str(&rest[..start]);
+    let after_start = &rest[start + 2..];
+    let replacement = after_start.find("}}").and_then(|end| {
+      let value = variables.get(after_start[..end].trim())?;
+      Some((value, end))
+    });
+    match replacement {
+      Some((value, end)) => {
+        output.push_str(value);
+        rest = &after_start[end + 2..];
+      }
+      None => {
+        output.push_str("{{");
+        rest = after_start;
+      }
+    }
+  }
+  output.push_str(rest);
+  output
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_substitute_variables() {
+    let variables = BTreeMap::from([
+      ("name".to_string(), "my-app".to_string()),
+      ("port".to_string(), "8000".to_string()),
+    ]);
+    assert_eq!(
+      substitute_variables(
+        "# {{name}}\nListening on {{ port }} ({{ unknown }}) {{",
+        &variables
+      ),
+      "# my-app\nListening on 8000 ({{ unknown }}) {{"
+    );
+  }
+}
@@ -12,6 +12,7 @@ use crate::args::InstallKind;
 use crate::args::TypeCheckMode;
 use crate::args::UninstallFlags;
 use crate::args::UninstallKind;
+use crate::colors;
 use crate::factory::CliFactory;
 use crate::graph_container::ModuleGraphContainer;
 use crate::http_util::HttpClientProvider;
@@ -40,6 +41,13 @@ use std::path::PathBuf;
 use std::os::unix::fs::PermissionsExt;
 use std::sync::Arc;
 
+mod service;
+
+use service::display_command;
+use service::ServiceDefinition;
+use service::ServiceDirs;
+use service::ServiceManager;
+
 static EXEC_NAME_RE: Lazy<Regex> = Lazy::new(|| {
   RegexBuilder::new(r"^[a-z0-9][\w-]*$")
     .case_insensitive(true)
@@ -57,6 +65,57 @@ fn validate_name(exec_name: &str) -> Result<(), AnyError> {
   }
 }
 
+/// Environment variables which are kept when a launcher scrubs the
+/// environment, as Deno needs them to locate its cache and executables.
+const LAUNCHER_ENV_ESSENTIALS: &[&str] = &[
+  "PATH",
+  "HOME",
+  "DENO_DIR",
+  "DENO_INSTALL_ROOT",
+  "TMPDIR",
+  "USERPROFILE",
+  "APPDATA",
+  "LOCALAPPDATA",
+  "SYSTEMROOT",
+  "TEMP",
+  "TMP",
+];
+
+fn launcher_env_vars(env_allowlist: &[String]) -> Vec<&str> {
+  let mut vars = LAUNCHER_ENV_ESSENTIALS.to_vec();
+  for var in env_allowlist {
+    if !vars.contains(&var.as_str()) {
+      vars.push(var);
+    }
+  }
+  vars
+}
+
+/// Generates a launcher for `sh` running deno with the already escaped
+/// arguments.
+fn sh_launcher(shim_data: &ShimData, args: &str, exec: bool) -> String {
+  let mut script = "#!/bin/sh\n# generated by deno install\n".to_string();
+  if let Some(cwd) = &shim_data.cwd {
+    script.push_str(&format!(
+      "cd {} || exit 1\n",
+      shell_escape::unix::escape(cwd.to_string_lossy())
+    ));
+  }
+  if exec {
+    script.push_str("exec ");
+  }
+  if let Some(env_allowlist) = &shim_data.env_allowlist {
+    // only pass through the variables which are set
+    script.push_str("env -i");
+    for var in launcher_env_vars(env_allowlist) {
+      script.push_str(&format!(" ${{{var}+\"{var}=${var}\"}}"));
+    }
+    script.push(' ');
+  }
+  script.push_str(&format!("deno {args} \"$@\"\n"));
+  script
+}
+
 #[cfg(windows)]
 /// On Windows, 2 files are generated.
 /// One compatible with cmd & powershell with a .cmd extension
@@ -65,26 +124,37 @@ fn validate_name(exec_name: &str) -> Result<(), AnyError> {
 fn generate_executable_file(shim_data: &ShimData) -> Result<(), AnyError> {
   let args: Vec<String> =
     shim_data.args.iter().map(|c| format!("\"{c}\"")).collect();
-  let template = format!(
-    "% generated by deno install %\n@deno {} %*\n",
+  let mut template = "% generated by deno install %\n".to_string();
+  if shim_data.cwd.is_some() || shim_data.env_allowlist.is_some() {
+    template.push_str("@setlocal\n");
+  }
+  if let Some(cwd) = &shim_data.cwd {
+    template.push_str(&format!(
+      "@cd /d \"{}\" || exit /b 1\n",
+      cwd.to_string_lossy().replace('%', "%%")
+    ));
+  }
+  if let Some(env_allowlist) = &shim_data.env_allowlist {
+    // clear every variable which is not in the allowlist
+    template.push_str(&format!(
+      "@for /f \"delims==\" %%v in ('set') do @(echo ;{}; | findstr /i /c:\";%%v;\" >nul || set \"%%v=\")\n",
+      launcher_env_vars(env_allowlist).join(";")
+    ));
+  }
+  template.push_str(&format!(
+    "@deno {} %*\n",
     args
       .iter()
       .map(|arg| arg.replace('%', "%%"))
       .collect::<Vec<_>>()
       .join(" ")
-  );
+  ));
   let mut file = File::create(&shim_data.file_path)?;
   file.write_all(template.as_bytes())?;
 
   // write file for bash
   // create filepath without extensions
-  let template = format!(
-    r#"#!/bin/sh
-# generated by deno install
-deno {} "$@"
-"#,
-    args.join(" "),
-  );
+  let template = sh_launcher(shim_data, &args.join(" "), false);
   let mut file = File::create(shim_data.file_path.with_extension(""))?;
   file.write_all(template.as_bytes())?;
   Ok(())
@@ -98,13 +168,7 @@ fn generate_executable_file(shim_data: &ShimData) -> Result<(), AnyError> {
     .iter()
     .map(|c| escape(c.into()).into_owned())
     .collect();
-  let template = format!(
-    r#"#!/bin/sh
-# generated by deno install
-exec deno {} "$@"
-"#,
-    args.join(" "),
-  );
+  let template = sh_launcher(shim_data, &args.join(" "), true);
   let mut file = File::create(&shim_data.file_path)?;
   file.write_all(template.as_bytes())?;
   let _metadata = fs::metadata(&shim_data.file_path)?;
@@ -120,20 +184,126 @@ fn get_installer_root() -> Result<PathBuf, io::Error> {
       return canonicalize_path_maybe_not_exists(&PathBuf::from(env_dir));
     }
   }
+  let mut home_path = get_home_dir()?;
+  home_path.push(".deno");
+  Ok(home_path)
+}
+
+fn get_home_dir() -> Result<PathBuf, io::Error> {
   // Note: on Windows, the $HOME environment variable may be set by users or by
   // third party software, but it is non-standard and should not be relied upon.
   let home_env_var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
-  let mut home_path =
-    env::var_os(home_env_var)
-      .map(PathBuf::from)
-      .ok_or_else(|| {
-        io::Error::new(
-          io::ErrorKind::NotFound,
-          format!("${home_env_var} is not defined"),
-        )
-      })?;
-  home_path.push(".deno");
-  Ok(home_path)
+  env::var_os(home_env_var).map(PathBuf::from).ok_or_else(|| {
+    io::Error::new(
+      io::ErrorKind::NotFound,
+      format!("${home_env_var} is not defined"),
+    )
+  })
+}
+
+fn service_definition(
+  root: &Path,
+  name: &str,
+) -> Result<ServiceDefinition, AnyError> {
+  let mut launcher = root.join("bin").join(name);
+  if cfg!(windows) {
+    launcher = launcher.with_extension("cmd");
+  }
+  // the launcher runs `deno` from the PATH, which service managers don't
+  // take from the shell of the user
+  let deno_exe = env::current_exe()?;
+  Ok(ServiceDefinition {
+    manager: ServiceManager::current(),
+    name: name.to_string(),
+    launcher,
+    deno_dir: deno_exe.parent().unwrap().to_path_buf(),
+    log_file: root.join("logs").join(format!("{name}.log")),
+  })
+}
+
+/// Runs a command of the service manager, warning when it fails.
+fn run_service_command(args: &[String]) {
+  let status = std::process::Command::new(&args[0])
+    .args(&args[1..])
+    .stdin(std::process::Stdio::null())
+    .status();
+  if !status.map(|status| status.success()).unwrap_or(false) {
+    log::warn!(
+      "{} Failed running: {}",
+      colors::yellow("Warning"),
+      display_command(args)
+    );
+  }
+}
+
+/// Writes the definition of a service running the installed executable.
+fn install_service(
+  dirs: &ServiceDirs,
+  root: &Path,
+  name: &str,
+  force: bool,
+) -> Result<(), AnyError> {
+  let definition = service_definition(root, name)?;
+  let file_path = definition.file_path(dirs, root);
+  if file_path.exists() && !force {
+    return Err(generic_error(format!(
+      "Existing service definition found at {}. Aborting (Use -f to overwrite).",
+      file_path.display()
+    )));
+  }
+  fs::create_dir_all(definition.log_file.parent().unwrap())?;
+  fs::create_dir_all(file_path.parent().unwrap())?;
+  fs::write(&file_path, definition.contents()).with_context(|| {
+    format!(
+      "Failed writing the service definition {}",
+      file_path.display()
+    )
+  })?;
+
+  log::info!("✅ Successfully created the service {}", definition.id());
+  log::info!("{}", file_path.display());
+  log::info!(
+    "ℹ️  The output is written to {}",
+    definition.log_file.display()
+  );
+  log::info!("ℹ️  Start the service with:");
+  for command in definition.start_commands(&file_path) {
+    log::info!("    {}", display_command(&command));
+  }
+  if let Some(note) = definition.note() {
+    log::info!("ℹ️  {}", note);
+  }
+  Ok(())
+}
+
+/// Stops the service running the installed executable and removes its
+/// definition, returning whether it existed. The commands of the service
+/// manager are run with `run_command`.
+fn uninstall_service(
+  dirs: &ServiceDirs,
+  root: &Path,
+  name: &str,
+  run_command: &mut dyn FnMut(&[String]),
+) -> Result<bool, AnyError> {
+  let definition = service_definition(root, name)?;
+  let file_path = definition.file_path(dirs, root);
+  if !file_path.exists() {
+    log::warn!(
+      "{} No service definition found for {}",
+      colors::yellow("Warning"),
+      name
+    );
+    return Ok(false);
+  }
+  for command in definition.stop_commands(&file_path) {
+    run_command(&command);
+  }
+  fs::remove_file(&file_path)?;
+  log::info!("deleted {}", file_path.display());
+  for command in definition.reload_commands() {
+    run_command(&command);
+  }
+  Ok(true)
 }
 
 pub async fn infer_name_from_url(
@@ -228,6 +398,15 @@ pub async fn uninstall(
 
   let mut removed = false;
 
+  if uninstall_flags.service {
+    removed = uninstall_service(
+      &ServiceDirs::from_home(get_home_dir()?),
+      &root,
+      &uninstall_flags.name,
+      &mut run_service_command,
+    )?;
+  }
+
   if file_path.exists() {
     fs::remove_file(&file_path)?;
     log::info!("deleted {}", file_path.to_string_lossy());
@@ -287,14 +466,16 @@ async fn install_local(
   match install_flags {
     InstallFlagsLocal::Add(add_flags) => {
       super::registry::add(
-        flags,
+        flags.clone(),
         add_flags,
         super::registry::AddCommandName::Install,
       )
-      .await
+      .await?;
+      report_skipped_lifecycle_scripts(&CliFactory::from_flags(flags)).await
     }
     InstallFlagsLocal::Entrypoints(entrypoints) => {
-      install_from_entrypoints(flags, &entrypoints).await
+      install_from_entrypoints(flags.clone(), &entrypoints).await?;
+      report_skipped_lifecycle_scripts(&CliFactory::from_flags(flags)).await
     }
     InstallFlagsLocal::TopLevel => {
       let factory = CliFactory::from_flags(flags);
@@ -308,9 +489,59 @@ async fn install_local(
         lockfile.write_if_changed()?;
       }
 
-      Ok(())
+      report_skipped_lifecycle_scripts(&factory).await
+    }
+  }
+}
+
+/// Lists all the packages in the local node_modules directory whose
+/// lifecycle scripts were not run, including the ones that were skipped by
+/// previous installs and already warned about.
+async fn report_skipped_lifecycle_scripts(
+  factory: &CliFactory,
+) -> Result<(), AnyError> {
+  let Some(npm_resolver) = factory.npm_resolver().await?.as_managed() else {
+    return Ok(());
+  };
+  let mut packages = npm_resolver.packages_with_skipped_lifecycle_scripts();
+  if packages.is_empty() {
+    return Ok(());
+  }
+  packages.sort_by(|a, b| a.id.nv.cmp(&b.id.nv));
+  log::info!(
+    "{} Lifecycle scripts were not run for {} {}:",
+    colors::yellow("Summary"),
+    packages.len(),
+    if packages.len() == 1 {
+      "package"
+    } else {
+      "packages"
     }
+  );
+  for package in &packages {
+    let scripts = ["preinstall", "install", "postinstall"]
+      .into_iter()
+      .filter(|name| package.scripts.contains_key(*name))
+      .collect::<Vec<_>>();
+    log::info!(
+      "  npm:{} {}",
+      package.id.nv,
+      colors::gray(format!("({})", scripts.join(", ")))
+    );
   }
+  log::info!("To run them, reinstall with:");
+  log::info!(
+    "  {}",
+    colors::bold(format!(
+      "deno install --allow-scripts={}",
+      packages
+        .iter()
+        .map(|p| format!("npm:{}", p.id.nv))
+        .collect::<Vec<_>>()
+        .join(",")
+    ))
+  );
+  Ok(())
 }
 
 fn check_if_installs_a_single_package_globally(
@@ -393,6 +624,16 @@ async fn create_install_shim(
   for (path, contents) in shim_data.extra_files {
     fs::write(path, contents)?;
   }
+  if install_flags_global.service {
+    // the executable is installed in the bin directory of the root
+    let root = shim_data.installation_dir.parent().unwrap();
+    install_service(
+      &ServiceDirs::from_home(get_home_dir()?),
+      root,
+      &shim_data.name,
+      install_flags_global.force,
+    )?;
+  }
 
   log::info!("✅ Successfully installed {}", shim_data.name);
   log::info!("{}", shim_data.file_path.display());
@@ -420,6 +661,8 @@ struct ShimData {
   file_path: PathBuf,
   args: Vec<String>,
   extra_files: Vec<(PathBuf, String)>,
+  env_allowlist: Option<Vec<String>>,
+  cwd: Option<PathBuf>,
 }
 
 async fn resolve_shim_data(
@@ -539,6 +782,11 @@ async fn resolve_shim_data(
     let import_map_url = resolve_url_or_path(import_map_path, &cwd)?;
     executable_args.push("--import-map".to_string());
     executable_args.push(import_map_url.to_string());
+    for import_map_path in &flags.import_map_overrides {
+      let import_map_url = resolve_url_or_path(import_map_path, &cwd)?;
+      executable_args.push("--import-map".to_string());
+      executable_args.push(import_map_url.to_string());
+    }
   }
 
   if let ConfigFlag::Path(config_path) = &flags.config_flag {
@@ -580,12 +828,19 @@ async fn resolve_shim_data(
   executable_args.push(module_url.to_string());
   executable_args.extend_from_slice(&install_flags_global.args);
 
+  let launcher_cwd = match &install_flags_global.cwd {
+    Some(dir) => Some(canonicalize_path_maybe_not_exists(&cwd.join(dir))?),
+    None => None,
+  };
+
   Ok(ShimData {
     name,
     installation_dir,
     file_path,
     args: executable_args,
     extra_files,
+    env_allowlist: install_flags_global.env_allowlist.clone(),
+    cwd: launcher_cwd,
   })
 }
 
@@ -829,6 +1084,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -852,6 +1110,58 @@ mod tests {
     }
   }
 
+  #[tokio::test]
+  async fn install_env_allowlist_and_cwd() {
+    let temp_dir = TempDir::new();
+    let bin_dir = temp_dir.path().join("bin");
+    std::fs::create_dir(&bin_dir).unwrap();
+
+    create_install_shim(
+      &HttpClientProvider::new(None, None),
+      &Flags::default(),
+      InstallFlagsGlobal {
+        module_url: "http://localhost:4545/echo_server.ts".to_string(),
+        args: vec![],
+        name: Some("echo_test".to_string()),
+        root: Some(temp_dir.path().to_string()),
+        force: false,
+        env_allowlist: Some(vec!["GITHUB_TOKEN".to_string()]),
+        cwd: Some(temp_dir.path().to_string()),
+        service: false,
+      },
+    )
+    .await
+    .unwrap();
+
+    let mut file_path = bin_dir.join("echo_test");
+    if cfg!(windows) {
+      file_path = file_path.with_extension("cmd");
+    }
+
+    let content = fs::read_to_string(file_path).unwrap();
+    if cfg!(windows) {
+      assert!(content.contains("@cd /d"));
+      assert!(content.contains(";PATH;HOME;"));
+      assert!(content.contains(";GITHUB_TOKEN;"));
+    } else {
+      assert!(content.contains(&format!(
+        "cd {} || exit 1",
+        shell_escape::unix::escape(
+          temp_dir
+            .path()
+            .canonicalize()
+            .to_string_lossy()
+            .to_string()
+            .into()
+        )
+      )));
+      assert!(content
+        .contains(r#"exec env -i ${PATH+"PATH=$PATH"} ${HOME+"HOME=$HOME"}"#));
+      assert!(content
+        .contains(r#"${GITHUB_TOKEN+"GITHUB_TOKEN=$GITHUB_TOKEN"} deno run"#));
+    }
+  }
+
   #[tokio::test]
   async fn install_inferred_name() {
     let shim_data = resolve_shim_data(
@@ -863,6 +1173,9 @@ mod tests {
         name: None,
         root: Some(env::temp_dir().to_string_lossy().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -886,6 +1199,9 @@ mod tests {
         name: None,
         root: Some(env::temp_dir().to_string_lossy().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -915,6 +1231,9 @@ mod tests {
         name: None,
         root: Some(env::temp_dir().to_string_lossy().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -944,6 +1263,9 @@ mod tests {
         name: None,
         root: Some(env::temp_dir().to_string_lossy().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -969,6 +1291,9 @@ mod tests {
         name: None,
         root: Some(env::temp_dir().to_string_lossy().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -996,6 +1321,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(env::temp_dir().to_string_lossy().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -1028,6 +1356,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(env::temp_dir().to_string_lossy().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -1065,6 +1396,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(env::temp_dir().to_string_lossy().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -1098,6 +1432,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(env::temp_dir().to_string_lossy().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -1132,6 +1469,9 @@ mod tests {
         name: None,
         root: Some(temp_dir.to_string_lossy().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -1170,6 +1510,9 @@ mod tests {
         name: None,
         root: Some(env::temp_dir().to_string_lossy().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -1206,6 +1549,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -1236,6 +1582,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -1257,6 +1606,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await;
@@ -1279,6 +1631,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_string()),
         force: true,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await;
@@ -1310,6 +1665,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_string()),
         force: true,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await;
@@ -1340,6 +1698,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -1381,6 +1742,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_string()),
         force: false,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await
@@ -1426,6 +1790,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_string()),
         force: true,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await;
@@ -1469,6 +1836,9 @@ mod tests {
         name: Some("echo_test".to_string()),
         root: Some(temp_dir.path().to_string()),
         force: true,
+        env_allowlist: None,
+        cwd: None,
+        service: false,
       },
     )
     .await;
@@ -1525,6 +1895,7 @@ mod tests {
         kind: UninstallKind::Global(UninstallFlagsGlobal {
           name: "echo_test".to_string(),
           root: Some(temp_dir.path().to_string()),
+          service: false,
         }),
       },
     )
@@ -1541,4 +1912,72 @@ mod tests {
       assert!(!file_path.exists());
     }
   }
+
+  #[cfg(target_os = "linux")]
+  #[test]
+  fn install_and_uninstall_service() {
+    let temp_dir = TempDir::new();
+    let dirs = ServiceDirs {
+      home: temp_dir.path().join("home").to_path_buf(),
+      config: temp_dir.path().join("config").to_path_buf(),
+    };
+    let root = temp_dir.path().join("root");
+
+    install_service(&dirs, root.as_path(), "serve", false).unwrap();
+    let unit = dirs.config.join("systemd/user/deno-serve.service");
+    let deno_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    assert_eq!(
+      std::fs::read_to_string(&unit).unwrap(),
+      format!(
+        "# generated by deno install --service
+[Unit]
+Description=serve (installed by deno)
+
+[Service]
+Environment=\"PATH={}:/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin\"
+ExecStart=\"{}\"
+Restart=on-failure
+StandardOutput=append:{log_file}
+StandardError=append:{log_file}
+
+[Install]
+WantedBy=default.target
+",
+        deno_dir.display(),
+        root.join("bin/serve"),
+        log_file = root.join("logs/serve.log"),
+      )
+    );
+    assert!(root.join("logs").is_dir());
+    assert!(install_service(&dirs, root.as_path(), "serve", false).is_err());
+    install_service(&dirs, root.as_path(), "serve", true).unwrap();
+
+    let mut commands = Vec::new();
+    let mut run_command = |command: &[String]| commands.push(command.join(" "));
+    assert!(uninstall_service(
+      &dirs,
+      root.as_path(),
+      "serve",
+      &mut run_command
+    )
+    .unwrap());
+    assert!(!unit.exists());
+    assert_eq!(
+      commands,
+      vec![
+        "systemctl --user disable --now deno-serve.service",
+        "systemctl --user daemon-reload",
+      ]
+    );
+    commands.clear();
+    let mut run_command = |command: &[String]| commands.push(command.join(" "));
+    assert!(!uninstall_service(
+      &dirs,
+      root.as_path(),
+      "serve",
+      &mut run_command
+    )
+    .unwrap());
+    assert!(commands.is_empty());
+  }
 }
@@ -0,0 +1,398 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+/// The service manager which runs the user services of the platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceManager {
+  Systemd,
+  Launchd,
+  /// Windows services have to implement the service control protocol, so the
+  /// executable is run by a Task Scheduler task at logon instead.
+  TaskScheduler,
+}
+
+impl ServiceManager {
+  pub fn current() -> Self {
+    if cfg!(windows) {
+      Self::TaskScheduler
+    } else if cfg!(target_os = "macos") {
+      Self::Launchd
+    } else {
+      Self::Systemd
+    }
+  }
+}
+
+/// The directories the definitions of the services are written to.
+#[derive(Debug, Clone)]
+pub struct ServiceDirs {
+  pub home: PathBuf,
+  /// The config directory of the user, which has the units of systemd.
+  pub config: PathBuf,
+}
+
+impl ServiceDirs {
+  /// Uses `$XDG_CONFIG_HOME` as the config directory, falling back to
+  /// `~/.config`.
+  pub fn from_home(home: PathBuf) -> Self {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+      .filter(|dir| !dir.is_empty())
+      .map(PathBuf::from)
+      .unwrap_or_else(|| home.join(".config"));
+    Self { home, config }
+  }
+}
+
+/// The definition of a service running an installed executable.
+#[derive(Debug, Clone)]
+pub struct ServiceDefinition {
+  pub manager: ServiceManager,
+  pub name: String,
+  /// The launcher generated for the executable.
+  pub launcher: PathBuf,
+  /// The directory of the deno executable, which is added to the `PATH` of
+  /// systemd and launchd services so the launcher finds it. Task Scheduler
+  /// tasks get the `PATH` of the user.
+  pub deno_dir: PathBuf,
+  /// The file the output of the executable is appended to.
+  pub log_file: PathBuf,
+}
+
+impl ServiceDefinition {
+  /// The name the service is registered with.
+  pub fn id(&self) -> String {
+    match self.manager {
+      ServiceManager::Systemd => format!("deno-{}.service", self.name),
+      ServiceManager::Launchd => format!("land.deno.{}", self.name),
+      ServiceManager::TaskScheduler => format!("deno-{}", self.name),
+    }
+  }
+
+  /// The path of the definition, relative to the config directory for
+  /// systemd, the home directory for launchd, and to the installation root
+  /// for the Task Scheduler.
+  pub fn file_path(&self, dirs: &ServiceDirs, root: &Path) -> PathBuf {
+    match self.manager {
+      ServiceManager::Systemd => {
+        dirs.config.join("systemd").join("user").join(self.id())
+      }
+      ServiceManager::Launchd => dirs
+        .home
+        .join("Library")
+        .join("LaunchAgents")
+        .join(format!("{}.plist", self.id())),
+      ServiceManager::TaskScheduler => {
+        root.join("services").join(format!("{}.xml", self.id()))
+      }
+    }
+  }
+
+  pub fn contents(&self) -> String {
+    let launcher = self.launcher.to_string_lossy();
+    let log_file = self.log_file.to_string_lossy();
+    let path = format!(
+      "{}:/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin",
+      self.deno_dir.to_string_lossy()
+    );
+    match self.manager {
+      ServiceManager::Systemd => format!(
+        "# generated by deno install --service
+[Unit]
+Description={name} (installed by deno)
+
+[Service]
+Environment=\"PATH={path}\"
+ExecStart=\"{launcher}\"
+Restart=on-failure
+StandardOutput=append:{log_file}
+StandardError=append:{log_file}
+
+[Install]
+WantedBy=default.target
+",
+        name = self.name,
+        path = escape_systemd(&path),
+        launcher = escape_systemd(&launcher),
+        log_file = escape_systemd_specifiers(&log_file),
+      ),
+      ServiceManager::Launchd => format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- generated by deno install --service -->
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>Label</key>
+  <string>{id}</string>
+  <key>ProgramArguments</key>
+  <array>
+    <string>{launcher}</string>
+  </array>
+  <key>EnvironmentVariables</key>
+  <dict>
+    <key>PATH</key>
+    <string>{path}</string>
+  </dict>
+  <key>RunAtLoad</key>
+  <true/>
+  <key>KeepAlive</key>
+  <dict>
+    <key>SuccessfulExit</key>
+    <false/>
+  </dict>
+  <key>StandardOutPath</key>
+  <string>{log_file}</string>
+  <key>StandardErrorPath</key>
+  <string>{log_file}</string>
+</dict>
+</plist>
+"#,
+        id = escape_xml(&self.id()),
+        path = escape_xml(&path),
+        launcher = escape_xml(&launcher),
+        log_file = escape_xml(&log_file),
+      ),
+      ServiceManager::TaskScheduler => format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- generated by deno install --service -->
+<Task version="1.2" xmlns="http://schemas.microsoft.com/windows/2004/02/mit/task">
+  <RegistrationInfo>
+    <Description>{name} (installed by deno)</Description>
+  </RegistrationInfo>
+  <Triggers>
+    <LogonTrigger>
+      <Enabled>true</Enabled>
+    </LogonTrigger>
+  </Triggers>
+  <Settings>
+    <MultipleInstancesPolicy>IgnoreNew</MultipleInstancesPolicy>
+    <DisallowStartIfOnBatteries>false</DisallowStartIfOnBatteries>
+    <StopIfGoingOnBatteries>false</StopIfGoingOnBatteries>
+    <ExecutionTimeLimit>PT0S</ExecutionTimeLimit>
+    <RestartOnFailure>
+      <Interval>PT1M</Interval>
+      <Count>999</Count>
+    </RestartOnFailure>
+  </Settings>
+  <Actions>
+    <Exec>
+      <Command>cmd.exe</Command>
+      <Arguments>/c ""{launcher}" &gt;&gt; "{log_file}" 2&gt;&amp;1"</Arguments>
+    </Exec>
+  </Actions>
+</Task>
+"#,
+        name = escape_xml(&self.name),
+        launcher = escape_xml(&launcher),
+        log_file = escape_xml(&log_file),
+      ),
+    }
+  }
+
+  /// What the user has to know about the service beyond the commands which
+  /// start it.
+  pub fn note(&self) -> Option<&'static str> {
+    match self.manager {
+      ServiceManager::Systemd => Some(
+        "User services are stopped when you log out. Keep the service running by enabling lingering with: loginctl enable-linger",
+      ),
+      ServiceManager::Launchd => None,
+      ServiceManager::TaskScheduler => Some(
+        "The service is a Task Scheduler task which starts when you log on, rather than a Windows service.",
+      ),
+    }
+  }
+
+  /// The commands which start the service once the definition was written.
+  pub fn start_commands(&self, file_path: &Path) -> Vec<Vec<String>> {
+    let id = self.id();
+    let file_path = file_path.to_string_lossy().to_string();
+    match self.manager {
+      ServiceManager::Systemd => vec![
+        command(&["systemctl", "--user", "daemon-reload"]),
+        command(&["systemctl", "--user", "enable", "--now", &id]),
+      ],
+      ServiceManager::Launchd => {
+        vec![command(&["launchctl", "load", "-w", &file_path])]
+      }
+      ServiceManager::TaskScheduler => vec![
+        command(&["schtasks", "/Create", "/TN", &id, "/XML", &file_path]),
+        command(&["schtasks", "/Run", "/TN", &id]),
+      ],
+    }
+  }
+
+  /// The commands which stop and unregister the service. They have to be run
+  /// before the definition is removed, as the service manager reads it.
+  pub fn stop_commands(&self, file_path: &Path) -> Vec<Vec<String>> {
+    let id = self.id();
+    let file_path = file_path.to_string_lossy().to_string();
+    match self.manager {
+      ServiceManager::Systemd => {
+        vec![command(&["systemctl", "--user", "disable", "--now", &id])]
+      }
+      ServiceManager::Launchd => {
+        vec![command(&["launchctl", "unload", "-w", &file_path])]
+      }
+      ServiceManager::TaskScheduler => vec![
+        command(&["schtasks", "/End", "/TN", &id]),
+        command(&["schtasks", "/Delete", "/TN", &id, "/F"]),
+      ],
+    }
+  }
+
+  /// The commands which make the service manager forget the service once its
+  /// definition was removed.
+  pub fn reload_commands(&self) -> Vec<Vec<String>> {
+    match self.manager {
+      ServiceManager::Systemd => {
+        vec![command(&["systemctl", "--user", "daemon-reload"])]
+      }
+      ServiceManager::Launchd | ServiceManager::TaskScheduler => vec![],
+    }
+  }
+}
+
+fn command(args: &[&str]) -> Vec<String> {
+  args.iter().map(|arg| arg.to_string()).collect()
+}
+
+/// Formats the arguments of a command to be printed, quoting the ones with
+/// whitespace.
+pub fn display_command(args: &[String]) -> String {
+  args
+    .iter()
+    .map(|arg| {
+      if arg.contains(char::is_whitespace) {
+        format!("\"{arg}\"")
+      } else {
+        arg.to_string()
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Escapes a value of a systemd unit which is quoted.
+fn escape_systemd(text: &str) -> String {
+  escape_systemd_specifiers(&text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Escapes the `%` of a value of a systemd unit which can't be quoted, like
+/// the path of `StandardOutput=append:`, so it's not read as a specifier.
+fn escape_systemd_specifiers(text: &str) -> String {
+  text.replace('%', "%%")
+}
+
+fn escape_xml(text: &str) -> String {
+  text
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn definition(manager: ServiceManager) -> ServiceDefinition {
+    ServiceDefinition {
+      manager,
+      name: "serve".to_string(),
+      launcher: PathBuf::from("/home/user/.deno/bin/serve"),
+      deno_dir: PathBuf::from("/home/user/.deno/bin"),
+      log_file: PathBuf::from("/home/user/.deno/logs/serve.log"),
+    }
+  }
+
+  #[test]
+  fn systemd_unit() {
+    let definition = definition(ServiceManager::Systemd);
+    assert_eq!(definition.id(), "deno-serve.service");
+    let contents = definition.contents();
+    assert!(contents.contains("ExecStart=\"/home/user/.deno/bin/serve\"\n"));
+    assert!(contents.contains(
+      "Environment=\"PATH=/home/user/.deno/bin:/usr/local/bin:/usr/bin:/bin:/usr/sbin:/sbin\"\n"
+    ));
+    assert!(contents.contains("Restart=on-failure\n"));
+    assert!(contents
+      .contains("StandardOutput=append:/home/user/.deno/logs/serve.log\n"));
+    let definition = ServiceDefinition {
+      log_file: PathBuf::from("/home/100%/logs/serve.log"),
+      ..definition
+    };
+    assert!(definition
+      .contents()
+      .contains("StandardOutput=append:/home/100%%/logs/serve.log\n"));
+    assert_eq!(
+      definition
+        .start_commands(Path::new("/unused"))
+        .iter()
+        .map(|command| display_command(command))
+        .collect::<Vec<_>>(),
+      vec![
+        "systemctl --user daemon-reload",
+        "systemctl --user enable --now deno-serve.service"
+      ]
+    );
+    assert_eq!(
+      definition.stop_commands(Path::new("/unused")),
+      vec![command(&[
+        "systemctl",
+        "--user",
+        "disable",
+        "--now",
+        "deno-serve.service"
+      ])]
+    );
+    assert_eq!(
+      definition.reload_commands(),
+      vec![command(&["systemctl", "--user", "daemon-reload"])]
+    );
+  }
+
+  #[test]
+  fn launchd_agent() {
+    let definition = definition(ServiceManager::Launchd);
+    let file_path = definition.file_path(
+      &ServiceDirs::from_home(PathBuf::from("/Users/user")),
+      Path::new("/unused"),
+    );
+    assert_eq!(
+      file_path,
+      PathBuf::from("/Users/user/Library/LaunchAgents/land.deno.serve.plist")
+    );
+    let contents = definition.contents();
+    assert!(contents.contains("<string>land.deno.serve</string>"));
+    assert!(contents.contains("<string>/home/user/.deno/bin/serve</string>"));
+    assert!(
+      contents.contains("<key>PATH</key>\n    <string>/home/user/.deno/bin:")
+    );
+    assert_eq!(
+      definition
+        .stop_commands(&file_path)
+        .iter()
+        .map(|command| display_command(command))
+        .collect::<Vec<_>>(),
+      vec!["launchctl unload -w /Users/user/Library/LaunchAgents/land.deno.serve.plist"]
+    );
+  }
+
+  #[test]
+  fn task_scheduler_task() {
+    let definition = ServiceDefinition {
+      launcher: PathBuf::from("C:\\Users\\R&D\\.deno\\bin\\serve.cmd"),
+      ..definition(ServiceManager::TaskScheduler)
+    };
+    let file_path = definition.file_path(
+      &ServiceDirs::from_home(PathBuf::from("/unused")),
+      Path::new("/root"),
+    );
+    assert_eq!(file_path, PathBuf::from("/root/services/deno-serve.xml"));
+    let contents = definition.contents();
+    assert!(contents.contains("C:\\Users\\R&amp;D\\.deno\\bin\\serve.cmd"));
+    assert!(contents.contains("<LogonTrigger>"));
+  }
+}
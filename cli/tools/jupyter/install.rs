@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
 use deno_core::serde_json::json;
@@ -9,14 +10,21 @@ use std::path::Path;
 
 use jupyter_runtime::dirs::user_data_dir;
 
+use crate::args::flags_from_vec;
+use crate::args::JupyterFlags;
+
 const DENO_ICON_32: &[u8] = include_bytes!("./resources/deno-logo-32x32.png");
 const DENO_ICON_64: &[u8] = include_bytes!("./resources/deno-logo-64x64.png");
 const DENO_ICON_SVG: &[u8] = include_bytes!("./resources/deno-logo-svg.svg");
 
+const DEFAULT_KERNEL_NAME: &str = "deno";
+const DEFAULT_DISPLAY_NAME: &str = "Deno";
+
 pub fn status() -> Result<(), AnyError> {
   let user_data_dir = user_data_dir()?;
 
-  let kernel_spec_dir_path = user_data_dir.join("kernels").join("deno");
+  let kernel_spec_dir_path =
+    user_data_dir.join("kernels").join(DEFAULT_KERNEL_NAME);
   let kernel_spec_path = kernel_spec_dir_path.join("kernel.json");
 
   if kernel_spec_path.exists() {
@@ -39,29 +47,70 @@ fn install_icon(
   Ok(())
 }
 
-pub fn install() -> Result<(), AnyError> {
+pub fn install(jupyter_flags: &JupyterFlags) -> Result<(), AnyError> {
+  let name = jupyter_flags.name.as_deref().unwrap_or(DEFAULT_KERNEL_NAME);
+  let display_name = jupyter_flags
+    .display_name
+    .as_deref()
+    .unwrap_or(DEFAULT_DISPLAY_NAME);
+  validate_kernel_args(&jupyter_flags.kernel_args)?;
+
   let user_data_dir = user_data_dir()?;
-  let kernel_dir = user_data_dir.join("kernels").join("deno");
+  let kernel_dir = user_data_dir.join("kernels").join(name);
 
   std::fs::create_dir_all(&kernel_dir)?;
 
   let kernel_json_path = kernel_dir.join("kernel.json");
 
+  let mut argv = vec![
+    current_exe().unwrap().to_string_lossy().to_string(),
+    "jupyter".to_string(),
+  ];
+  argv.extend(jupyter_flags.kernel_args.iter().cloned());
+  argv.extend([
+    "--kernel".to_string(),
+    "--conn".to_string(),
+    "{connection_file}".to_string(),
+  ]);
+
   // TODO(bartlomieju): add remaining fields as per
   // https://jupyter-client.readthedocs.io/en/stable/kernels.html#kernel-specs
   // FIXME(bartlomieju): replace `current_exe` before landing?
   let json_data = json!({
-      "argv": [current_exe().unwrap().to_string_lossy(), "jupyter", "--kernel", "--conn", "{connection_file}"],
-      "display_name": "Deno",
+      "argv": argv,
+      "display_name": display_name,
       "language": "typescript",
   });
 
   let f = std::fs::File::create(kernel_json_path)?;
   serde_json::to_writer_pretty(f, &json_data)?;
-  install_icon(&user_data_dir, "logo-32x32.png", DENO_ICON_32)?;
-  install_icon(&user_data_dir, "logo-64x64.png", DENO_ICON_64)?;
-  install_icon(&user_data_dir, "logo-svg.svg", DENO_ICON_SVG)?;
+  install_icon(&kernel_dir, "logo-32x32.png", DENO_ICON_32)?;
+  install_icon(&kernel_dir, "logo-64x64.png", DENO_ICON_64)?;
+  install_icon(&kernel_dir, "logo-svg.svg", DENO_ICON_SVG)?;
 
-  log::info!("✅ Deno kernelspec installed successfully.");
+  log::info!(
+    "✅ Deno kernelspec \"{}\" installed successfully at {}.",
+    name,
+    kernel_dir.display()
+  );
+  Ok(())
+}
+
+/// Makes sure the kernel will be able to start with the provided arguments,
+/// instead of failing later on when Jupyter launches it.
+fn validate_kernel_args(kernel_args: &[String]) -> Result<(), AnyError> {
+  if kernel_args.is_empty() {
+    return Ok(());
+  }
+  let args = ["deno", "jupyter"]
+    .into_iter()
+    .map(String::from)
+    .chain(kernel_args.iter().cloned())
+    .chain(["--kernel", "--conn", "{connection_file}"].map(String::from))
+    .map(Into::into)
+    .collect();
+  flags_from_vec(args).with_context(|| {
+    format!("Invalid kernel arguments: {}", kernel_args.join(" "))
+  })?;
   Ok(())
 }
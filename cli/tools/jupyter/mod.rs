@@ -1,5 +1,6 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::args::Flags;
@@ -35,6 +36,7 @@ use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot;
 
 mod install;
+pub mod outputs;
 pub mod server;
 
 pub async fn kernel(
@@ -52,7 +54,7 @@ pub async fn kernel(
   }
 
   if jupyter_flags.install {
-    install::install()?;
+    install::install(&jupyter_flags)?;
     return Ok(());
   }
 
@@ -64,8 +66,13 @@ pub async fn kernel(
     resolve_url_or_path("./$deno$jupyter.ts", cli_options.initial_cwd())
       .unwrap();
   // TODO(bartlomieju): should we run with all permissions?
-  let permissions =
-    PermissionsContainer::allow_all(factory.permission_desc_parser()?.clone());
+  // Kernels installed with explicit permission flags (via `--kernel-arg`) run
+  // sandboxed, all other kernels keep running with all permissions.
+  let permissions = if cli_options.permission_flags().has_permission() {
+    factory.root_permissions_container()?.clone()
+  } else {
+    PermissionsContainer::allow_all(factory.permission_desc_parser()?.clone())
+  };
   let npm_resolver = factory.npm_resolver().await?.clone();
   let resolver = factory.resolver().await?.clone();
   let worker_factory = factory.create_cli_main_worker_factory().await?;
@@ -95,8 +102,13 @@ pub async fn kernel(
       main_module.clone(),
       permissions,
       vec![
-        ops::jupyter::deno_jupyter::init_ops(stdio_tx.clone()),
-        ops::testing::deno_test::init_ops(test_event_sender),
+        ops::jupyter::deno_jupyter::init_ops(
+          stdio_tx.clone(),
+          // kept relative, so the notebook references the files relative
+          // to its directory
+          outputs::OutputSink::new(jupyter_flags.assets_dir.map(PathBuf::from)),
+        ),
+        ops::testing::deno_test::init_ops(test_event_sender, false, None),
       ],
       // FIXME(nayeemrmn): Test output capturing currently doesn't work.
       Stdio {
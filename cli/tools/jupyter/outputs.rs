@@ -0,0 +1,213 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Limits the size of the outputs sent to the Jupyter frontend. Outputs which
+//! are too large to send inline are saved to the assets directory given with
+//! `--assets-dir` and referenced from the notebook instead.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::Map;
+use deno_core::serde_json::Value;
+use sha2::Digest;
+
+use crate::util::display::human_size;
+
+/// Outputs larger than this are saved to the assets directory, when there
+/// is one.
+const MAX_INLINE_OUTPUT_SIZE: usize = 1024 * 1024;
+/// Outputs larger than this are rejected when there's no assets directory,
+/// because frontends become unresponsive with them.
+const MAX_OUTPUT_SIZE: usize = 16 * 1024 * 1024;
+
+/// Media types whose data is base64 encoded in the bundle.
+const BASE64_MEDIA_TYPES: [&str; 5] = [
+  "image/png",
+  "image/jpeg",
+  "image/gif",
+  "image/webp",
+  "application/pdf",
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct OutputSink {
+  assets_dir: Option<PathBuf>,
+}
+
+impl OutputSink {
+  pub fn new(assets_dir: Option<PathBuf>) -> Self {
+    Self { assets_dir }
+  }
+
+  /// Replaces the entries of the MIME bundle which are too large to send
+  /// inline with references to the files they are saved to.
+  pub fn process_bundle(
+    &self,
+    bundle: &mut Map<String, Value>,
+  ) -> Result<(), AnyError> {
+    let mut references = Vec::new();
+    let media_types = bundle.keys().cloned().collect::<Vec<_>>();
+    for media_type in media_types {
+      let size = output_size(&bundle[&media_type]);
+      let Some(assets_dir) = &self.assets_dir else {
+        if size > MAX_OUTPUT_SIZE {
+          bail!(
+            "The {} output is {}, which is more than the limit of {}. Pass --assets-dir to the kernel to save large outputs to files.",
+            media_type,
+            human_size(size as f64),
+            human_size(MAX_OUTPUT_SIZE as f64),
+          );
+        }
+        continue;
+      };
+      if size <= MAX_INLINE_OUTPUT_SIZE {
+        continue;
+      }
+      let value = bundle.remove(&media_type).unwrap();
+      let path = save_asset(assets_dir, &media_type, &value)?;
+      references.push((media_type, path, size));
+    }
+    if references.is_empty() {
+      return Ok(());
+    }
+
+    if !bundle.contains_key("text/html") {
+      let html = references
+        .iter()
+        .map(|(media_type, path, size)| {
+          if media_type.starts_with("image/") {
+            format!("<img src=\"{path}\">")
+          } else {
+            format!(
+              "<a href=\"{}\" target=\"_blank\">{} output ({})</a>",
+              path,
+              media_type,
+              human_size(*size as f64)
+            )
+          }
+        })
+        .collect::<String>();
+      bundle.insert("text/html".to_string(), Value::String(html));
+    }
+    if !bundle.contains_key("text/plain") {
+      let text = references
+        .iter()
+        .map(|(media_type, path, _)| {
+          format!("{media_type} output saved to {path}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+      bundle.insert("text/plain".to_string(), Value::String(text));
+    }
+    Ok(())
+  }
+}
+
+fn output_size(value: &Value) -> usize {
+  match value {
+    Value::String(text) => text.len(),
+    value => serde_json::to_string(value).map(|s| s.len()).unwrap_or(0),
+  }
+}
+
+/// Saves the output to a file named by the hash of its contents and returns
+/// the path of the file for referencing it from the notebook.
+fn save_asset(
+  assets_dir: &Path,
+  media_type: &str,
+  value: &Value,
+) -> Result<String, AnyError> {
+  let bytes = match value {
+    Value::String(text) if BASE64_MEDIA_TYPES.contains(&media_type) => {
+      BASE64_STANDARD.decode(text).with_context(|| {
+        format!("Invalid base64 data of {media_type} output")
+      })?
+    }
+    Value::String(text) => text.as_bytes().to_vec(),
+    value => serde_json::to_vec(value)?,
+  };
+  let hash = faster_hex::hex_string(&sha2::Sha256::digest(&bytes));
+  let file_name = format!("{}.{}", &hash[..16], asset_extension(media_type));
+  std::fs::create_dir_all(assets_dir).with_context(|| {
+    format!("Failed creating assets directory {}", assets_dir.display())
+  })?;
+  let path = assets_dir.join(file_name);
+  std::fs::write(&path, bytes)
+    .with_context(|| format!("Failed writing {}", path.display()))?;
+  Ok(path.to_string_lossy().replace('\\', "/"))
+}
+
+fn asset_extension(media_type: &str) -> &'static str {
+  match media_type {
+    "image/png" => "png",
+    "image/jpeg" => "jpg",
+    "image/gif" => "gif",
+    "image/webp" => "webp",
+    "image/svg+xml" => "svg",
+    "application/pdf" => "pdf",
+    "text/html" => "html",
+    "text/markdown" => "md",
+    "text/plain" => "txt",
+    _ => "json",
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use deno_core::serde_json::json;
+
+  fn bundle(value: Value) -> Map<String, Value> {
+    value.as_object().unwrap().clone()
+  }
+
+  #[test]
+  fn test_process_bundle_small_outputs() {
+    let temp_dir = test_util::TempDir::new();
+    let sink = OutputSink::new(Some(temp_dir.path().to_path_buf()));
+    let original = bundle(json!({
+      "text/plain": "hello",
+      "image/png": BASE64_STANDARD.encode([1, 2, 3]),
+    }));
+    let mut processed = original.clone();
+    sink.process_bundle(&mut processed).unwrap();
+    assert_eq!(processed, original);
+  }
+
+  #[test]
+  fn test_process_bundle_saves_large_outputs() {
+    let temp_dir = test_util::TempDir::new();
+    let assets_dir = temp_dir.path().join("assets").to_path_buf();
+    let sink = OutputSink::new(Some(assets_dir.clone()));
+    let image = vec![7; MAX_INLINE_OUTPUT_SIZE];
+    let mut processed = bundle(json!({
+      "image/png": BASE64_STANDARD.encode(&image),
+    }));
+    sink.process_bundle(&mut processed).unwrap();
+    assert!(!processed.contains_key("image/png"));
+    let text = processed["text/plain"].as_str().unwrap();
+    let path = text.strip_prefix("image/png output saved to ").unwrap();
+    assert!(path.ends_with(".png"));
+    assert_eq!(std::fs::read(path).unwrap(), image);
+    assert_eq!(
+      processed["text/html"].as_str().unwrap(),
+      format!("<img src=\"{path}\">")
+    );
+  }
+
+  #[test]
+  fn test_process_bundle_rejects_too_large_outputs() {
+    let sink = OutputSink::default();
+    let mut processed = bundle(json!({
+      "text/html": "a".repeat(MAX_OUTPUT_SIZE + 1),
+    }));
+    let err = sink.process_bundle(&mut processed).unwrap_err();
+    assert!(err.to_string().contains("Pass --assets-dir"));
+  }
+}
@@ -19,6 +19,7 @@ use deno_lint::linter::LinterOptions;
 use crate::util::fs::atomic_write_file_with_retries;
 use crate::util::fs::specifier_from_file_path;
 
+use super::rules::find_conflict_markers;
 use super::rules::FileOrPackageLintRule;
 use super::rules::PackageLintRule;
 use super::ConfiguredRules;
@@ -105,7 +106,12 @@ impl CliLinter {
       MediaType::from_specifier(&specifier)
     };
 
-    if self.fix {
+    // conflict markers are syntax errors, so point them out instead of only
+    // failing to parse
+    let conflict_marker_line = find_conflict_markers(&source_code)
+      .first()
+      .map(|(offset, _)| source_code[..*offset].lines().count() + 1);
+    let result = if self.fix {
       self.lint_file_and_fix(&specifier, media_type, source_code, file_path)
     } else {
       self
@@ -117,6 +123,12 @@ impl CliLinter {
           config: self.deno_lint_config.clone(),
         })
         .map_err(AnyError::from)
+    };
+    match conflict_marker_line {
+      Some(line) => result.with_context(|| {
+        format!("Unresolved merge conflict marker at line {line}")
+      }),
+      None => result,
     }
   }
 
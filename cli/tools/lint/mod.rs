@@ -126,7 +126,7 @@ pub async fn lint(
               .await?;
           }
 
-          linter.finish();
+          linter.finish()?;
 
           Ok(())
         })
@@ -144,6 +144,7 @@ pub async fn lint(
       let start_dir = &cli_options.start_dir;
       let reporter_lock = Arc::new(Mutex::new(create_reporter(
         workspace_lint_options.reporter_kind,
+        workspace_lint_options.output_file.clone(),
       )));
       let lint_config = start_dir
         .to_lint_config(FilePatterns::new_with_base(start_dir.dir_path()))?;
@@ -165,7 +166,7 @@ pub async fn lint(
         r,
         reporter_lock.clone(),
       );
-      reporter_lock.lock().close(1);
+      reporter_lock.lock().close(1)?;
       success
     } else {
       let mut linter = WorkspaceLinter::new(
@@ -188,7 +189,7 @@ pub async fn lint(
           )
           .await?;
       }
-      linter.finish()
+      linter.finish()?
     };
     if !success {
       std::process::exit(1);
@@ -250,8 +251,10 @@ impl WorkspaceLinter {
     workspace_dir: Arc<WorkspaceDirectory>,
     workspace_options: &WorkspaceLintOptions,
   ) -> Self {
-    let reporter_lock =
-      Arc::new(Mutex::new(create_reporter(workspace_options.reporter_kind)));
+    let reporter_lock = Arc::new(Mutex::new(create_reporter(
+      workspace_options.reporter_kind,
+      workspace_options.output_file.clone(),
+    )));
     Self {
       caches,
       lint_rule_provider,
@@ -418,10 +421,10 @@ impl WorkspaceLinter {
     Ok(())
   }
 
-  pub fn finish(self) -> bool {
+  pub fn finish(self) -> Result<bool, AnyError> {
     debug!("Found {} files", self.file_count);
-    self.reporter_lock.lock().close(self.file_count);
-    !self.has_error.is_raised() // success
+    self.reporter_lock.lock().close(self.file_count)?;
+    Ok(!self.has_error.is_raised()) // success
   }
 }
 
@@ -463,6 +466,12 @@ pub fn print_rules_list(json: bool, maybe_rules_tags: Option<Vec<String>>) {
             "code": rule.code(),
             "tags": rule.tags(),
             "docs": rule.docs(),
+            "docsUrl": rule.help_docs_url(),
+            "fixable": rule.fixable(),
+            "options": rule.options().map(|options| serde_json::json!({
+              "configKey": options.config_key,
+              "schema": options.schema,
+            })),
           })
         })
         .collect::<Vec<serde_json::Value>>(),
@@ -1,6 +1,9 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::path::PathBuf;
+
 use deno_ast::diagnostics::Diagnostic;
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::serde_json;
 use deno_lint::diagnostic::LintDiagnostic;
@@ -9,23 +12,35 @@ use log::info;
 use serde::Serialize;
 
 use crate::args::LintReporterKind;
+use crate::util::fs::atomic_write_file_with_retries;
 
 use super::LintError;
 
 const JSON_SCHEMA_VERSION: u8 = 1;
 
-pub fn create_reporter(kind: LintReporterKind) -> Box<dyn LintReporter + Send> {
-  match kind {
+pub fn create_reporter(
+  kind: LintReporterKind,
+  output_file: Option<PathBuf>,
+) -> Box<dyn LintReporter + Send> {
+  let reporter: Box<dyn LintReporter + Send> = match kind {
     LintReporterKind::Pretty => Box::new(PrettyLintReporter::new()),
     LintReporterKind::Json => Box::new(JsonLintReporter::new()),
     LintReporterKind::Compact => Box::new(CompactLintReporter::new()),
+  };
+  match output_file {
+    Some(output_file) => Box::new(OutputFileLintReporter {
+      reporter,
+      json_reporter: JsonLintReporter::new(),
+      output_file,
+    }),
+    None => reporter,
   }
 }
 
 pub trait LintReporter {
   fn visit_diagnostic(&mut self, d: &LintDiagnostic);
   fn visit_error(&mut self, file_path: &str, err: &AnyError);
-  fn close(&mut self, check_count: usize);
+  fn close(&mut self, check_count: usize) -> Result<(), AnyError>;
 }
 
 struct PrettyLintReporter {
@@ -57,7 +72,7 @@ impl LintReporter for PrettyLintReporter {
     log::error!("   {err}");
   }
 
-  fn close(&mut self, check_count: usize) {
+  fn close(&mut self, check_count: usize) -> Result<(), AnyError> {
     let fixable_suffix = if self.fixable_diagnostics > 0 {
       colors::gray(format!(" ({} fixable via --fix)", self.fixable_diagnostics))
         .to_string()
@@ -76,6 +91,7 @@ impl LintReporter for PrettyLintReporter {
       1 => info!("Checked 1 file"),
       n => info!("Checked {} files", n),
     }
+    Ok(())
   }
 }
 
@@ -118,7 +134,7 @@ impl LintReporter for CompactLintReporter {
     log::error!("   {err}");
   }
 
-  fn close(&mut self, check_count: usize) {
+  fn close(&mut self, check_count: usize) -> Result<(), AnyError> {
     match self.lint_count {
       1 => info!("Found 1 problem"),
       n if n > 1 => info!("Found {} problems", self.lint_count),
@@ -129,6 +145,7 @@ impl LintReporter for CompactLintReporter {
       1 => info!("Checked 1 file"),
       n => info!("Checked {} files", n),
     }
+    Ok(())
   }
 }
 
@@ -218,13 +235,51 @@ impl LintReporter for JsonLintReporter {
     });
   }
 
-  fn close(&mut self, _check_count: usize) {
+  fn close(&mut self, _check_count: usize) -> Result<(), AnyError> {
     sort_diagnostics(&mut self.diagnostics);
     let json = serde_json::to_string_pretty(&self);
     #[allow(clippy::print_stdout)]
     {
       println!("{}", json.unwrap());
     }
+    Ok(())
+  }
+}
+
+/// Writes the diagnostics to `--output-file` in the format of `--json`, next
+/// to the selected reporter.
+struct OutputFileLintReporter {
+  reporter: Box<dyn LintReporter + Send>,
+  json_reporter: JsonLintReporter,
+  output_file: PathBuf,
+}
+
+impl LintReporter for OutputFileLintReporter {
+  fn visit_diagnostic(&mut self, d: &LintDiagnostic) {
+    self.reporter.visit_diagnostic(d);
+    self.json_reporter.visit_diagnostic(d);
+  }
+
+  fn visit_error(&mut self, file_path: &str, err: &AnyError) {
+    self.reporter.visit_error(file_path, err);
+    self.json_reporter.visit_error(file_path, err);
+  }
+
+  fn close(&mut self, check_count: usize) -> Result<(), AnyError> {
+    self.reporter.close(check_count)?;
+    sort_diagnostics(&mut self.json_reporter.diagnostics);
+    let json = serde_json::to_string_pretty(&self.json_reporter).unwrap();
+    atomic_write_file_with_retries(
+      &self.output_file,
+      format!("{json}\n"),
+      0o644,
+    )
+    .with_context(|| {
+      format!(
+        "Failed writing lint results to {}",
+        self.output_file.display()
+      )
+    })
   }
 }
 
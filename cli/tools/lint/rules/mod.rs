@@ -10,17 +10,49 @@ use deno_config::deno_json::LintRulesConfig;
 use deno_config::workspace::WorkspaceResolver;
 use deno_core::anyhow::bail;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
 use deno_graph::ModuleGraph;
 use deno_lint::diagnostic::LintDiagnostic;
 use deno_lint::rules::LintRule;
+use once_cell::sync::Lazy;
 
+use crate::args::resolve_config_lint_spelling_options;
+use crate::args::resolve_config_lint_todo_options;
+use crate::colors;
 use crate::resolver::CliSloppyImportsResolver;
 
+mod no_conflict_markers;
 mod no_sloppy_imports;
 mod no_slow_types;
+mod spelling;
+mod todo_format;
 
 // used for publishing
 pub use no_slow_types::collect_no_slow_type_diagnostics;
+// used for the syntax errors of files with conflict markers
+pub use no_conflict_markers::find_conflict_markers;
+
+static CONFIG_FILE_SCHEMA: Lazy<serde_json::Value> = Lazy::new(|| {
+  serde_json::from_str(include_str!("../../../schemas/config-file.v1.json"))
+    .unwrap()
+});
+
+/// Options of a rule, which are configured by a top level field of the
+/// config file.
+pub struct LintRuleOptions {
+  pub config_key: &'static str,
+  /// JSON schema of the field.
+  pub schema: serde_json::Value,
+}
+
+impl LintRuleOptions {
+  fn from_config_file_schema(config_key: &'static str) -> Self {
+    Self {
+      config_key,
+      schema: CONFIG_FILE_SCHEMA["properties"][config_key].clone(),
+    }
+  }
+}
 
 pub trait PackageLintRule: std::fmt::Debug + Send + Sync {
   fn code(&self) -> &'static str;
@@ -33,6 +65,10 @@ pub trait PackageLintRule: std::fmt::Debug + Send + Sync {
 
   fn help_docs_url(&self) -> Cow<'static, str>;
 
+  fn fixable(&self) -> bool {
+    false
+  }
+
   fn lint_package(
     &self,
     graph: &ModuleGraph,
@@ -46,6 +82,13 @@ pub(super) trait ExtendedLintRule: LintRule {
 
   fn help_docs_url(&self) -> Cow<'static, str>;
 
+  /// If the diagnostics of the rule have fixes which are applied by `--fix`.
+  fn fixable(&self) -> bool;
+
+  fn options(&self) -> Option<LintRuleOptions> {
+    None
+  }
+
   fn into_base(self: Box<Self>) -> Box<dyn LintRule>;
 }
 
@@ -103,6 +146,25 @@ impl CliLintRule {
     }
   }
 
+  /// If the diagnostics of the rule have fixes, which isn't known for the
+  /// rules of deno_lint.
+  pub fn fixable(&self) -> Option<bool> {
+    use CliLintRuleKind::*;
+    match &self.0 {
+      DenoLint(_) => None,
+      Extended(rule) => Some(rule.fixable()),
+      Package(rule) => Some(rule.fixable()),
+    }
+  }
+
+  pub fn options(&self) -> Option<LintRuleOptions> {
+    use CliLintRuleKind::*;
+    match &self.0 {
+      DenoLint(_) | Package(_) => None,
+      Extended(rule) => rule.options(),
+    }
+  }
+
   pub fn supports_incremental_cache(&self) -> bool {
     use CliLintRuleKind::*;
     match &self.0 {
@@ -177,12 +239,41 @@ impl LintRuleProvider {
     maybe_config_file: Option<&ConfigFile>,
   ) -> ConfiguredRules {
     let deno_lint_rules = deno_lint::rules::get_all_rules();
-    let cli_lint_rules = vec![CliLintRule(CliLintRuleKind::Extended(
-      Box::new(no_sloppy_imports::NoSloppyImportsRule::new(
-        self.sloppy_imports_resolver.clone(),
-        self.workspace_resolver.clone(),
-      )),
-    ))];
+    let spelling_options = maybe_config_file
+      .map(|config_file| {
+        resolve_config_lint_spelling_options(config_file).unwrap_or_else(
+          |err| {
+            log::warn!("{} {:#}", colors::yellow("Warning"), err);
+            Default::default()
+          },
+        )
+      })
+      .unwrap_or_default();
+    let todo_options = maybe_config_file
+      .map(|config_file| {
+        resolve_config_lint_todo_options(config_file).unwrap_or_else(|err| {
+          log::warn!("{} {:#}", colors::yellow("Warning"), err);
+          Default::default()
+        })
+      })
+      .unwrap_or_default();
+    let cli_lint_rules = vec![
+      CliLintRule(CliLintRuleKind::Extended(Box::new(
+        no_conflict_markers::NoConflictMarkersRule,
+      ))),
+      CliLintRule(CliLintRuleKind::Extended(Box::new(
+        no_sloppy_imports::NoSloppyImportsRule::new(
+          self.sloppy_imports_resolver.clone(),
+          self.workspace_resolver.clone(),
+        ),
+      ))),
+      CliLintRule(CliLintRuleKind::Extended(Box::new(
+        spelling::SpellingRule::new(spelling_options),
+      ))),
+      CliLintRule(CliLintRuleKind::Extended(Box::new(
+        todo_format::TodoFormatRule::new(todo_options),
+      ))),
+    ];
     let cli_graph_rules = vec![CliLintRule(CliLintRuleKind::Package(
       Box::new(no_slow_types::NoSlowTypesRule),
     ))];
@@ -293,4 +384,11 @@ mod test {
     recommended_rule_names.sort();
     assert_eq!(rule_names, recommended_rule_names);
   }
+
+  #[test]
+  fn rule_options_from_config_file_schema() {
+    let options = LintRuleOptions::from_config_file_schema("lintSpelling");
+    assert_eq!(options.schema["type"], "object");
+    assert_eq!(options.schema["properties"]["words"]["type"], "array");
+  }
 }
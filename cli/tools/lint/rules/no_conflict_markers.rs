@@ -0,0 +1,120 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::borrow::Cow;
+
+use deno_ast::SourceRange;
+use deno_lint::diagnostic::LintDiagnosticDetails;
+use deno_lint::diagnostic::LintDiagnosticRange;
+use deno_lint::rules::LintRule;
+
+use super::ExtendedLintRule;
+
+const CODE: &str = "no-conflict-markers";
+const DOCS_URL: &str = "https://docs.deno.com/go/lint";
+
+#[derive(Debug)]
+pub struct NoConflictMarkersRule;
+
+impl ExtendedLintRule for NoConflictMarkersRule {
+  fn supports_incremental_cache(&self) -> bool {
+    true
+  }
+
+  fn help_docs_url(&self) -> Cow<'static, str> {
+    Cow::Borrowed(DOCS_URL)
+  }
+
+  fn fixable(&self) -> bool {
+    false
+  }
+
+  fn into_base(self: Box<Self>) -> Box<dyn LintRule> {
+    self
+  }
+}
+
+impl LintRule for NoConflictMarkersRule {
+  fn lint_program_with_ast_view<'view>(
+    &self,
+    context: &mut deno_lint::context::Context<'view>,
+    _program: deno_lint::Program<'view>,
+  ) {
+    // markers outside of strings and comments are syntax errors, so only the
+    // ones in template literals and comments get here
+    let text_info = context.text_info().clone();
+    let file_start = text_info.range().start;
+    for (offset, marker) in find_conflict_markers(text_info.text_str()) {
+      let start = file_start + offset;
+      context.add_diagnostic_details(
+        Some(LintDiagnosticRange {
+          range: SourceRange::new(start, start + marker.len()),
+          description: None,
+          text_info: text_info.clone(),
+        }),
+        LintDiagnosticDetails {
+          message: "Unresolved merge conflict marker".to_string(),
+          code: CODE.to_string(),
+          custom_docs_url: Some(DOCS_URL.to_string()),
+          fixes: vec![],
+          hint: Some(
+            "Resolve the merge conflict and remove the marker".to_string(),
+          ),
+          info: vec![],
+        },
+      );
+    }
+  }
+
+  fn code(&self) -> &'static str {
+    CODE
+  }
+
+  fn docs(&self) -> &'static str {
+    include_str!("no_conflict_markers.md")
+  }
+
+  fn tags(&self) -> &'static [&'static str] {
+    &["recommended"]
+  }
+}
+
+/// Finds the lines which are merge conflict markers, returning the byte
+/// offset and the text of each line.
+pub fn find_conflict_markers(text: &str) -> Vec<(usize, &str)> {
+  let mut markers = Vec::new();
+  let mut offset = 0;
+  for line in text.split_inclusive('\n') {
+    let marker = line.trim_end_matches(['\r', '\n']);
+    if is_conflict_marker(marker) {
+      markers.push((offset, marker));
+    }
+    offset += line.len();
+  }
+  markers
+}
+
+fn is_conflict_marker(line: &str) -> bool {
+  if line == "=======" {
+    return true;
+  }
+  ["<<<<<<<", "|||||||", ">>>>>>>"].iter().any(|marker| {
+    line.strip_prefix(marker).is_some_and(|rest| {
+      rest.is_empty() || rest.starts_with(|c: char| c.is_whitespace())
+    })
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_find_conflict_markers() {
+    let text = "const a = `\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> main\r\n`;\n// ======== not a marker\n<<<<<<<<\n";
+    assert_eq!(
+      find_conflict_markers(text),
+      vec![(12, "<<<<<<< HEAD"), (30, "======="), (45, ">>>>>>> main")]
+    );
+    assert_eq!(find_conflict_markers(""), vec![]);
+  }
+}
@@ -60,6 +60,10 @@ impl ExtendedLintRule for NoSloppyImportsRule {
     Cow::Borrowed(DOCS_URL)
   }
 
+  fn fixable(&self) -> bool {
+    true
+  }
+
   fn into_base(self: Box<Self>) -> Box<dyn LintRule> {
     self
   }
@@ -0,0 +1,294 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::SourceRange;
+use deno_ast::SourceRangedForSpanned;
+use deno_lint::diagnostic::LintDiagnosticDetails;
+use deno_lint::diagnostic::LintDiagnosticRange;
+use deno_lint::diagnostic::LintFix;
+use deno_lint::diagnostic::LintFixChange;
+use deno_lint::rules::LintRule;
+use once_cell::sync::Lazy;
+
+use crate::args::LintSpellingOptions;
+
+use super::ExtendedLintRule;
+use super::LintRuleOptions;
+
+const CODE: &str = "spelling";
+const DOCS_URL: &str = "https://docs.deno.com/go/lint";
+
+/// Commonly misspelled words with their correction, which are listed as
+/// `misspelling->correction` lines.
+static DICTIONARY: Lazy<HashMap<&'static str, &'static str>> =
+  Lazy::new(|| {
+    include_str!("spelling_dictionary.txt")
+      .lines()
+      .filter_map(|line| line.split_once("->"))
+      .collect()
+  });
+
+#[derive(Debug)]
+pub struct SpellingRule {
+  /// Lowercased words of the project which are never reported.
+  words: HashSet<String>,
+  check_strings: bool,
+}
+
+impl SpellingRule {
+  pub fn new(options: LintSpellingOptions) -> Self {
+    SpellingRule {
+      words: options
+        .words
+        .iter()
+        .map(|word| word.to_lowercase())
+        .collect(),
+      check_strings: options.check_strings,
+    }
+  }
+
+  fn correction(&self, word: &str) -> Option<String> {
+    // short words are mostly abbreviations
+    if word.len() < 3 {
+      return None;
+    }
+    let lowercase = word.to_lowercase();
+    if self.words.contains(&lowercase) {
+      return None;
+    }
+    let correction = DICTIONARY.get(lowercase.as_str())?;
+    Some(match_case(word, correction))
+  }
+}
+
+impl ExtendedLintRule for SpellingRule {
+  fn supports_incremental_cache(&self) -> bool {
+    // the results depend on the project words of the config file, which
+    // aren't part of the cache key
+    false
+  }
+
+  fn help_docs_url(&self) -> Cow<'static, str> {
+    Cow::Borrowed(DOCS_URL)
+  }
+
+  fn fixable(&self) -> bool {
+    true
+  }
+
+  fn options(&self) -> Option<LintRuleOptions> {
+    Some(LintRuleOptions::from_config_file_schema("lintSpelling"))
+  }
+
+  fn into_base(self: Box<Self>) -> Box<dyn LintRule> {
+    self
+  }
+}
+
+impl LintRule for SpellingRule {
+  fn lint_program_with_ast_view<'view>(
+    &self,
+    context: &mut deno_lint::context::Context<'view>,
+    _program: deno_lint::Program<'view>,
+  ) {
+    let mut collector = TextRangeCollector {
+      ranges: Vec::new(),
+      check_strings: self.check_strings,
+    };
+    context
+      .parsed_source()
+      .program_ref()
+      .visit_with(&mut collector);
+    let mut ranges = collector.ranges;
+    ranges.extend(
+      context
+        .parsed_source()
+        .comments()
+        .get_vec()
+        .iter()
+        .map(|comment| comment.range()),
+    );
+
+    for range in ranges {
+      let text = context.text_info().range_text(&range).to_string();
+      for (offset, word) in split_words(&text) {
+        let Some(correction) = self.correction(word) else {
+          continue;
+        };
+        let start = range.start + offset;
+        let word_range = SourceRange::new(start, start + word.len());
+        context.add_diagnostic_details(
+          Some(LintDiagnosticRange {
+            range: word_range,
+            description: None,
+            text_info: context.text_info().clone(),
+          }),
+          LintDiagnosticDetails {
+            message: format!(
+              "\"{}\" is misspelled, did you mean \"{}\"?",
+              word, correction
+            ),
+            code: CODE.to_string(),
+            custom_docs_url: Some(DOCS_URL.to_string()),
+            fixes: vec![LintFix {
+              description: Cow::Owned(format!("Replace with \"{correction}\"")),
+              changes: vec![LintFixChange {
+                new_text: Cow::Owned(correction),
+                range: word_range,
+              }],
+            }],
+            hint: Some(
+              "If the word is intended, add it to \"lintSpelling.words\" in the config file"
+                .to_string(),
+            ),
+            info: vec![],
+          },
+        );
+      }
+    }
+  }
+
+  fn code(&self) -> &'static str {
+    CODE
+  }
+
+  fn docs(&self) -> &'static str {
+    include_str!("spelling.md")
+  }
+
+  fn tags(&self) -> &'static [&'static str] {
+    // opt-in through `lint.rules.include`
+    &[]
+  }
+}
+
+/// Collects the ranges of the identifiers, and optionally of the string
+/// literals, to check.
+struct TextRangeCollector {
+  ranges: Vec<SourceRange>,
+  check_strings: bool,
+}
+
+impl Visit for TextRangeCollector {
+  fn visit_ident(&mut self, ident: &ast::Ident) {
+    self.ranges.push(ident.range());
+  }
+
+  fn visit_str(&mut self, str_lit: &ast::Str) {
+    if self.check_strings {
+      self.ranges.push(str_lit.range());
+    }
+  }
+
+  // module specifiers are paths and package names, not prose
+  fn visit_import_decl(&mut self, import_decl: &ast::ImportDecl) {
+    import_decl.specifiers.visit_with(self);
+  }
+
+  fn visit_named_export(&mut self, named_export: &ast::NamedExport) {
+    named_export.specifiers.visit_with(self);
+  }
+
+  fn visit_export_all(&mut self, _export_all: &ast::ExportAll) {}
+}
+
+/// Splits the text into words at the characters which aren't letters and at
+/// the case changes of camelCase and PascalCase identifiers, returning the
+/// byte offset of each word.
+fn split_words(text: &str) -> Vec<(usize, &str)> {
+  let chars = text.char_indices().collect::<Vec<_>>();
+  let byte_offset =
+    |index: usize| chars.get(index).map(|(i, _)| *i).unwrap_or(text.len());
+  let mut words = Vec::new();
+  let mut word_start: Option<usize> = None;
+  for (index, (_, c)) in chars.iter().enumerate() {
+    if !c.is_alphabetic() {
+      if let Some(start) = word_start.take() {
+        words.push((start, index));
+      }
+      continue;
+    }
+    let Some(start) = word_start else {
+      word_start = Some(index);
+      continue;
+    };
+    let prev = chars[index - 1].1;
+    let next = chars.get(index + 1).map(|(_, c)| *c);
+    // `fooBar` or the `R` of `JSONResponse`
+    let is_boundary = (prev.is_lowercase() && c.is_uppercase())
+      || (prev.is_uppercase()
+        && c.is_uppercase()
+        && next.is_some_and(|next| next.is_lowercase()));
+    if is_boundary {
+      words.push((start, index));
+      word_start = Some(index);
+    }
+  }
+  if let Some(start) = word_start {
+    words.push((start, chars.len()));
+  }
+  words
+    .into_iter()
+    .map(|(start, end)| {
+      let (start, end) = (byte_offset(start), byte_offset(end));
+      (start, &text[start..end])
+    })
+    .collect()
+}
+
+/// Applies the casing of the misspelled word to the correction.
+fn match_case(word: &str, correction: &str) -> String {
+  let mut chars = word.chars();
+  let is_first_uppercase = chars.next().is_some_and(|c| c.is_uppercase());
+  if is_first_uppercase && chars.all(|c| c.is_uppercase()) {
+    correction.to_uppercase()
+  } else if is_first_uppercase {
+    let mut chars = correction.chars();
+    chars
+      .next()
+      .map(|c| c.to_uppercase().chain(chars).collect())
+      .unwrap_or_default()
+  } else {
+    correction.to_string()
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_split_words() {
+    assert_eq!(
+      split_words("parseJSONRespnse(snake_case, 42) // über"),
+      vec![
+        (0, "parse"),
+        (5, "JSON"),
+        (9, "Respnse"),
+        (17, "snake"),
+        (23, "case"),
+        (36, "über"),
+      ]
+    );
+    assert_eq!(split_words(""), vec![]);
+  }
+
+  #[test]
+  fn test_correction() {
+    let rule = SpellingRule::new(LintSpellingOptions {
+      words: vec!["Recieve".to_string()],
+      check_strings: false,
+    });
+    assert_eq!(rule.correction("respnse"), Some("response".to_string()));
+    assert_eq!(rule.correction("Respnse"), Some("Response".to_string()));
+    assert_eq!(rule.correction("RESPNSE"), Some("RESPONSE".to_string()));
+    assert_eq!(rule.correction("response"), None);
+    assert_eq!(rule.correction("recieve"), None);
+  }
+}
@@ -0,0 +1,281 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::borrow::Cow;
+
+use deno_ast::SourceRange;
+use deno_ast::SourceRangedForSpanned;
+use deno_lint::diagnostic::LintDiagnosticDetails;
+use deno_lint::diagnostic::LintDiagnosticRange;
+use deno_lint::diagnostic::LintFix;
+use deno_lint::diagnostic::LintFixChange;
+use deno_lint::rules::LintRule;
+use regex::Regex;
+
+use crate::args::LintTodoOptions;
+
+use super::ExtendedLintRule;
+use super::LintRuleOptions;
+
+const CODE: &str = "todo-format";
+const DOCS_URL: &str = "https://docs.deno.com/go/lint";
+
+#[derive(Debug)]
+pub struct TodoFormatRule {
+  keywords: Vec<String>,
+  /// Matches the keyword at the start of a comment line. Uppercase keywords
+  /// always match, other casings only when followed by `(` or `:`.
+  keyword_re: Regex,
+  require_owner: bool,
+  issue_re: Option<Regex>,
+}
+
+impl TodoFormatRule {
+  pub fn new(options: LintTodoOptions) -> Self {
+    let keywords = options
+      .keywords
+      .iter()
+      .map(|keyword| regex::escape(keyword))
+      .collect::<Vec<_>>()
+      .join("|");
+    let keyword_re =
+      Regex::new(&format!(r"^(?:({keywords})\b|(?i:({keywords}))\s*[(:])"))
+        .unwrap();
+    Self {
+      keywords: options.keywords,
+      keyword_re,
+      require_owner: options.require_owner,
+      // the pattern is validated when reading the config file
+      issue_re: options
+        .issue_pattern
+        .and_then(|pattern| Regex::new(&pattern).ok()),
+    }
+  }
+
+  fn check_line(&self, line: &str) -> Option<TodoProblems> {
+    let captures = self.keyword_re.captures(line)?;
+    let matched = captures.get(1).or_else(|| captures.get(2))?;
+    let keyword = self
+      .keywords
+      .iter()
+      .find(|keyword| keyword.eq_ignore_ascii_case(matched.as_str()))?;
+    let rest = &line[matched.end()..];
+    let todo = lazy_regex::regex_captures!(
+      r"^\s*(?:\(\s*([^)]*?)\s*\))?\s*:?\s*(.*?)\s*$",
+      rest
+    );
+    let (owner, description) = match todo {
+      Some((_, owner, description)) => (owner, description),
+      None => ("", rest.trim()),
+    };
+    let mut normalized = keyword.clone();
+    if !owner.is_empty() {
+      normalized.push_str(&format!("({owner})"));
+    }
+    normalized.push(':');
+    if !description.is_empty() {
+      normalized.push(' ');
+      normalized.push_str(description);
+    }
+    let text = line.trim_end();
+    Some(TodoProblems {
+      keyword_len: matched.len(),
+      len: text.len(),
+      normalized: (normalized != text).then_some(normalized),
+      missing_owner: self.require_owner && owner.is_empty(),
+      missing_issue: self
+        .issue_re
+        .as_ref()
+        .is_some_and(|issue_re| !issue_re.is_match(rest)),
+      keyword,
+    })
+  }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct TodoProblems<'a> {
+  keyword: &'a str,
+  keyword_len: usize,
+  /// The length of the comment line from the keyword.
+  len: usize,
+  /// The line in the `KEYWORD(owner): description` format, when it's not.
+  normalized: Option<String>,
+  missing_owner: bool,
+  missing_issue: bool,
+}
+
+impl ExtendedLintRule for TodoFormatRule {
+  fn supports_incremental_cache(&self) -> bool {
+    // the results depend on the options of the config file, which aren't
+    // part of the cache key
+    false
+  }
+
+  fn help_docs_url(&self) -> Cow<'static, str> {
+    Cow::Borrowed(DOCS_URL)
+  }
+
+  fn fixable(&self) -> bool {
+    true
+  }
+
+  fn options(&self) -> Option<LintRuleOptions> {
+    Some(LintRuleOptions::from_config_file_schema("lintTodo"))
+  }
+
+  fn into_base(self: Box<Self>) -> Box<dyn LintRule> {
+    self
+  }
+}
+
+impl LintRule for TodoFormatRule {
+  fn lint_program_with_ast_view<'view>(
+    &self,
+    context: &mut deno_lint::context::Context<'view>,
+    _program: deno_lint::Program<'view>,
+  ) {
+    let text_info = context.text_info().clone();
+    let comments = context
+      .parsed_source()
+      .comments()
+      .get_vec()
+      .iter()
+      .map(|comment| (comment.range(), comment.text.to_string()))
+      .collect::<Vec<_>>();
+    for (range, text) in comments {
+      // skip the `//` or `/*` of the comment
+      let mut offset = 2;
+      for line in text.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let content =
+          line.trim_start_matches(|c: char| c.is_whitespace() || c == '*');
+        let Some(problems) = self.check_line(content) else {
+          continue;
+        };
+        let start = range.start + line_start + (line.len() - content.len());
+        let keyword_range =
+          SourceRange::new(start, start + problems.keyword_len);
+        let mut add_diagnostic =
+          |message: String, hint: String, fixes: Vec<LintFix>| {
+            context.add_diagnostic_details(
+              Some(LintDiagnosticRange {
+                range: keyword_range,
+                description: None,
+                text_info: text_info.clone(),
+              }),
+              LintDiagnosticDetails {
+                message,
+                code: CODE.to_string(),
+                custom_docs_url: Some(DOCS_URL.to_string()),
+                fixes,
+                hint: Some(hint),
+                info: vec![],
+              },
+            );
+          };
+        let keyword = problems.keyword;
+        if let Some(normalized) = problems.normalized {
+          add_diagnostic(
+            format!("{keyword} comment isn't formatted as \"{keyword}(owner): description\""),
+            format!("Replace with \"{normalized}\""),
+            vec![LintFix {
+              description: Cow::Owned(format!("Replace with \"{normalized}\"")),
+              changes: vec![LintFixChange {
+                new_text: Cow::Owned(normalized),
+                range: SourceRange::new(start, start + problems.len),
+              }],
+            }],
+          );
+        }
+        if problems.missing_owner {
+          add_diagnostic(
+            format!("{keyword} comment has no owner"),
+            format!("Add the owner as in \"{keyword}(owner): description\""),
+            vec![],
+          );
+        }
+        if problems.missing_issue {
+          add_diagnostic(
+            format!("{keyword} comment doesn't reference an issue"),
+            "Add a link to the issue tracking it".to_string(),
+            vec![],
+          );
+        }
+      }
+    }
+  }
+
+  fn code(&self) -> &'static str {
+    CODE
+  }
+
+  fn docs(&self) -> &'static str {
+    include_str!("todo_format.md")
+  }
+
+  fn tags(&self) -> &'static [&'static str] {
+    // opt-in through `lint.rules.include`
+    &[]
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_check_line() {
+    let rule = TodoFormatRule::new(LintTodoOptions::default());
+    let problems = rule
+      .check_line("TODO(alice): fix it https://github.com/acme/app/issues/1")
+      .unwrap();
+    assert_eq!(problems.normalized, None);
+    assert!(!problems.missing_owner);
+    assert!(!problems.missing_issue);
+
+    let problems = rule.check_line("todo (bob) fix it, see #12  ").unwrap();
+    assert_eq!(problems.keyword, "TODO");
+    assert_eq!(
+      problems.normalized,
+      Some("TODO(bob): fix it, see #12".to_string())
+    );
+    assert_eq!(problems.len, "todo (bob) fix it, see #12".len());
+    assert!(!problems.missing_owner);
+    assert!(!problems.missing_issue);
+
+    let problems = rule.check_line("FIXME this is broken").unwrap();
+    assert_eq!(
+      problems.normalized,
+      Some("FIXME: this is broken".to_string())
+    );
+    assert!(problems.missing_owner);
+    assert!(problems.missing_issue);
+
+    // prose isn't a TODO comment
+    assert_eq!(rule.check_line("todo items are listed"), None);
+    assert_eq!(rule.check_line("TODOS are listed"), None);
+    assert_eq!(rule.check_line("see the TODO below"), None);
+  }
+
+  #[test]
+  fn test_check_line_options() {
+    let rule = TodoFormatRule::new(LintTodoOptions {
+      keywords: vec!["HACK".to_string()],
+      require_owner: false,
+      issue_pattern: None,
+    });
+    assert_eq!(rule.check_line("TODO: not checked"), None);
+    let problems = rule.check_line("HACK: works around it").unwrap();
+    assert_eq!(
+      problems,
+      TodoProblems {
+        keyword: "HACK",
+        keyword_len: 4,
+        len: "HACK: works around it".len(),
+        normalized: None,
+        missing_owner: false,
+        missing_issue: false,
+      }
+    );
+  }
+}
@@ -1,9 +1,15 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+pub mod auth;
 pub mod bench;
+pub mod bundle;
+pub mod cache;
 pub mod check;
+pub mod check_daemon;
 pub mod clean;
+pub mod compat;
 pub mod compile;
+pub mod config;
 pub mod coverage;
 pub mod doc;
 pub mod fmt;
@@ -19,3 +25,4 @@ pub mod serve;
 pub mod task;
 pub mod test;
 pub mod upgrade;
+pub mod version_manager;
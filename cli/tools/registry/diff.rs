@@ -0,0 +1,465 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Compares a package with its previously published version for
+//! `deno publish --dry-run --diff-previous`, to catch accidentally included
+//! files and removed exports before they are uploaded.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+
+use deno_ast::swc::ast;
+use deno_ast::swc::utils::find_pat_ids;
+use deno_ast::MediaType;
+use deno_ast::ModuleSpecifier;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::url::Url;
+use deno_semver::Version;
+use deno_terminal::colors;
+use serde::Deserialize;
+
+use crate::http_util;
+use crate::http_util::HttpClient;
+use crate::util::display::human_size;
+
+use super::tar::PublishableTarballFile;
+use super::PreparedPublishPackage;
+
+#[derive(Debug, Deserialize)]
+struct PackageMeta {
+  versions: HashMap<String, PackageMetaVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageMetaVersion {
+  #[serde(default)]
+  yanked: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct VersionMeta {
+  manifest: HashMap<String, ManifestEntry>,
+  #[serde(default)]
+  exports: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ManifestEntry {
+  size: usize,
+  checksum: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum FileChange {
+  Added { size: usize },
+  Removed { size: usize },
+  Modified { old_size: usize, new_size: usize },
+}
+
+/// Prints the changes of the files, the exports and the names exported by
+/// each entrypoint since the previous version published to the registry.
+pub async fn print_diff_previous(
+  http_client: &HttpClient,
+  registry_url: &Url,
+  package: &PreparedPublishPackage,
+) -> Result<(), AnyError> {
+  let package_url =
+    registry_url.join(&format!("@{}/{}/", package.scope, package.package))?;
+  let Some(previous) =
+    find_previous_version(http_client, &package_url, &package.version).await?
+  else {
+    log::info!(
+      "{} {} has no previously published version to compare with",
+      colors::yellow("Note"),
+      package.display_name()
+    );
+    return Ok(());
+  };
+  let previous_meta: VersionMeta = fetch_json(
+    http_client,
+    &package_url.join(&format!("{previous}_meta.json"))?,
+  )
+  .await?
+  .with_context(|| format!("Version {} was not found", previous))?;
+
+  log::info!(
+    "{} of {} since {}:",
+    colors::green_bold("Changes"),
+    colors::gray(package.display_name()),
+    previous
+  );
+  let file_changes =
+    diff_files(&previous_meta.manifest, &package.tarball.files);
+  if file_changes.is_empty() {
+    log::info!("  No file changes");
+  }
+  for (path, change) in &file_changes {
+    match change {
+      FileChange::Added { size } => log::info!(
+        "  {} {} ({})",
+        colors::green("+"),
+        path,
+        human_size(*size as f64)
+      ),
+      FileChange::Removed { size } => log::info!(
+        "  {} {} ({})",
+        colors::red("-"),
+        path,
+        human_size(*size as f64)
+      ),
+      FileChange::Modified { old_size, new_size } => log::info!(
+        "  {} {} ({} -> {})",
+        colors::yellow("~"),
+        path,
+        human_size(*old_size as f64),
+        human_size(*new_size as f64)
+      ),
+    }
+  }
+
+  let export_names = previous_meta
+    .exports
+    .keys()
+    .chain(package.exports.keys())
+    .collect::<BTreeSet<_>>();
+  for export_name in export_names {
+    let previous_path = previous_meta.exports.get(export_name);
+    let current_path = package.exports.get(export_name);
+    let (previous_path, current_path) = match (previous_path, current_path) {
+      (None, Some(_)) => {
+        log::info!("  {} export {}", colors::green("+"), export_name);
+        continue;
+      }
+      (Some(_), None) => {
+        log::warn!(
+          "  {} export {} {}",
+          colors::red("-"),
+          export_name,
+          colors::red("(breaking)")
+        );
+        continue;
+      }
+      (Some(previous_path), Some(current_path)) => {
+        (previous_path, current_path)
+      }
+      (None, None) => unreachable!(),
+    };
+    let previous_names = {
+      let url = package_url.join(&format!(
+        "{}/{}",
+        previous,
+        previous_path.trim_start_matches("./")
+      ))?;
+      match fetch_text(http_client, &url).await? {
+        Some(text) => exported_names(&url, text)?,
+        None => continue,
+      }
+    };
+    let current_names = match package
+      .tarball
+      .files
+      .iter()
+      .find(|file| file.path_str == current_path.trim_start_matches('.'))
+    {
+      Some(file) => {
+        let path = file.specifier.to_file_path().unwrap();
+        let text = std::fs::read_to_string(&path)
+          .with_context(|| format!("Failed reading '{}'", path.display()))?;
+        exported_names(&file.specifier, text)?
+      }
+      None => continue,
+    };
+    for name in previous_names.difference(&current_names) {
+      log::warn!(
+        "  {} {} from export {} {}",
+        colors::red("-"),
+        name,
+        export_name,
+        colors::red("(breaking)")
+      );
+    }
+    for name in current_names.difference(&previous_names) {
+      log::info!(
+        "  {} {} from export {}",
+        colors::green("+"),
+        name,
+        export_name
+      );
+    }
+  }
+  Ok(())
+}
+
+/// The highest version published before the new one, which isn't yanked.
+async fn find_previous_version(
+  http_client: &HttpClient,
+  package_url: &Url,
+  version: &str,
+) -> Result<Option<Version>, AnyError> {
+  let new_version = Version::parse_standard(version)?;
+  let Some(meta) =
+    fetch_json::<PackageMeta>(http_client, &package_url.join("meta.json")?)
+      .await?
+  else {
+    return Ok(None);
+  };
+  Ok(
+    meta
+      .versions
+      .into_iter()
+      .filter(|(_, info)| !info.yanked)
+      .filter_map(|(version, _)| Version::parse_standard(&version).ok())
+      .filter(|version| *version < new_version)
+      .max(),
+  )
+}
+
+async fn fetch_text(
+  http_client: &HttpClient,
+  url: &Url,
+) -> Result<Option<String>, AnyError> {
+  let response = http_client.get(url.clone())?.send().await?;
+  let status = response.status();
+  if status == 404 {
+    return Ok(None);
+  }
+  if !status.is_success() {
+    bail!("Failed fetching {} ({})", url, status);
+  }
+  Ok(Some(http_util::body_to_string(response).await?))
+}
+
+async fn fetch_json<T: serde::de::DeserializeOwned>(
+  http_client: &HttpClient,
+  url: &Url,
+) -> Result<Option<T>, AnyError> {
+  let Some(text) = fetch_text(http_client, url).await? else {
+    return Ok(None);
+  };
+  let value = serde_json::from_str(&text)
+    .with_context(|| format!("Failed parsing {}", url))?;
+  Ok(Some(value))
+}
+
+fn diff_files(
+  previous: &HashMap<String, ManifestEntry>,
+  current: &[PublishableTarballFile],
+) -> BTreeMap<String, FileChange> {
+  let mut changes = BTreeMap::new();
+  for file in current {
+    match previous.get(&file.path_str) {
+      None => {
+        changes
+          .insert(file.path_str.clone(), FileChange::Added { size: file.size });
+      }
+      Some(entry) if entry.checksum != file.hash => {
+        changes.insert(
+          file.path_str.clone(),
+          FileChange::Modified {
+            old_size: entry.size,
+            new_size: file.size,
+          },
+        );
+      }
+      Some(_) => {}
+    }
+  }
+  for (path, entry) in previous {
+    if !current.iter().any(|file| &file.path_str == path) {
+      changes.insert(path.clone(), FileChange::Removed { size: entry.size });
+    }
+  }
+  changes
+}
+
+/// The names exported by the module. Re-exports of all the names of another
+/// module are listed as `* from "<specifier>"`, without following them.
+fn exported_names(
+  specifier: &ModuleSpecifier,
+  text: String,
+) -> Result<BTreeSet<String>, AnyError> {
+  let parsed_source = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier: specifier.clone(),
+    text: text.into(),
+    media_type: MediaType::from_specifier(specifier),
+    capture_tokens: false,
+    scope_analysis: false,
+    maybe_syntax: None,
+  })?;
+  let mut names = BTreeSet::new();
+  let ast::Program::Module(module) = parsed_source.program_ref() else {
+    return Ok(names);
+  };
+  for item in &module.body {
+    let ast::ModuleItem::ModuleDecl(decl) = item else {
+      continue;
+    };
+    match decl {
+      ast::ModuleDecl::ExportDecl(export_decl) => match &export_decl.decl {
+        ast::Decl::Class(decl) => {
+          names.insert(decl.ident.sym.to_string());
+        }
+        ast::Decl::Fn(decl) => {
+          names.insert(decl.ident.sym.to_string());
+        }
+        ast::Decl::Var(decl) => {
+          for declarator in &decl.decls {
+            names.extend(
+              find_pat_ids::<_, ast::Ident>(&declarator.name)
+                .into_iter()
+                .map(|ident| ident.sym.to_string()),
+            );
+          }
+        }
+        ast::Decl::TsInterface(decl) => {
+          names.insert(decl.id.sym.to_string());
+        }
+        ast::Decl::TsTypeAlias(decl) => {
+          names.insert(decl.id.sym.to_string());
+        }
+        ast::Decl::TsEnum(decl) => {
+          names.insert(decl.id.sym.to_string());
+        }
+        ast::Decl::TsModule(decl) => {
+          if let ast::TsModuleName::Ident(ident) = &decl.id {
+            names.insert(ident.sym.to_string());
+          }
+        }
+        ast::Decl::Using(_) => {}
+      },
+      ast::ModuleDecl::ExportNamed(named) => {
+        for specifier in &named.specifiers {
+          let name = match specifier {
+            ast::ExportSpecifier::Named(named) => {
+              named.exported.as_ref().unwrap_or(&named.orig)
+            }
+            ast::ExportSpecifier::Namespace(namespace) => &namespace.name,
+            ast::ExportSpecifier::Default(default) => {
+              names.insert(default.exported.sym.to_string());
+              continue;
+            }
+          };
+          names.insert(module_export_name(name));
+        }
+      }
+      ast::ModuleDecl::ExportDefaultDecl(_)
+      | ast::ModuleDecl::ExportDefaultExpr(_) => {
+        names.insert("default".to_string());
+      }
+      ast::ModuleDecl::ExportAll(export_all) => {
+        names.insert(format!("* from \"{}\"", export_all.src.value));
+      }
+      _ => {}
+    }
+  }
+  Ok(names)
+}
+
+fn module_export_name(name: &ast::ModuleExportName) -> String {
+  match name {
+    ast::ModuleExportName::Ident(ident) => ident.sym.to_string(),
+    ast::ModuleExportName::Str(str) => str.value.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_diff_files() {
+    let previous = HashMap::from([
+      (
+        "/mod.ts".to_string(),
+        ManifestEntry {
+          size: 10,
+          checksum: "sha256-a".to_string(),
+        },
+      ),
+      (
+        "/removed.ts".to_string(),
+        ManifestEntry {
+          size: 5,
+          checksum: "sha256-b".to_string(),
+        },
+      ),
+      (
+        "/same.ts".to_string(),
+        ManifestEntry {
+          size: 3,
+          checksum: "sha256-c".to_string(),
+        },
+      ),
+    ]);
+    let file = |path: &str, hash: &str, size: usize| PublishableTarballFile {
+      path_str: path.to_string(),
+      specifier: Url::parse(&format!("file:///pkg{path}")).unwrap(),
+      hash: hash.to_string(),
+      size,
+    };
+    let current = vec![
+      file("/mod.ts", "sha256-d", 12),
+      file("/same.ts", "sha256-c", 3),
+      file("/.env", "sha256-e", 7),
+    ];
+    assert_eq!(
+      diff_files(&previous, &current)
+        .into_iter()
+        .collect::<Vec<_>>(),
+      vec![
+        ("/.env".to_string(), FileChange::Added { size: 7 }),
+        (
+          "/mod.ts".to_string(),
+          FileChange::Modified {
+            old_size: 10,
+            new_size: 12
+          }
+        ),
+        ("/removed.ts".to_string(), FileChange::Removed { size: 5 }),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_exported_names() {
+    let specifier = ModuleSpecifier::parse("file:///mod.ts").unwrap();
+    let names = exported_names(
+      &specifier,
+      r#"
+export function a() {}
+export class B {}
+export const { c, d: [e] } = f;
+export interface G {}
+export type H = string;
+export enum I {}
+export { j, k as l } from "./other.ts";
+export * as m from "./m.ts";
+export * from "./all.ts";
+export default 1;
+const n = 1;
+"#
+      .to_string(),
+    )
+    .unwrap();
+    assert_eq!(
+      names.into_iter().collect::<Vec<_>>(),
+      vec![
+        "* from \"./all.ts\"",
+        "B",
+        "G",
+        "H",
+        "I",
+        "a",
+        "c",
+        "default",
+        "e",
+        "j",
+        "l",
+        "m",
+      ]
+    );
+  }
+}
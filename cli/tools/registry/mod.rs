@@ -33,7 +33,6 @@ use serde::Serialize;
 use sha2::Digest;
 use tokio::process::Command;
 
-use crate::args::jsr_api_url;
 use crate::args::jsr_url;
 use crate::args::CliOptions;
 use crate::args::Flags;
@@ -42,6 +41,7 @@ use crate::cache::LazyGraphSourceParser;
 use crate::cache::ParsedSourceCache;
 use crate::factory::CliFactory;
 use crate::graph_util::ModuleGraphCreator;
+use crate::http_util;
 use crate::http_util::HttpClient;
 use crate::resolver::CliSloppyImportsResolver;
 use crate::resolver::SloppyImportsCachedFs;
@@ -55,6 +55,7 @@ mod api;
 mod auth;
 
 mod diagnostics;
+mod diff;
 mod graph;
 mod paths;
 mod pm;
@@ -62,15 +63,22 @@ mod provenance;
 mod publish_order;
 mod tar;
 mod unfurl;
+mod version_bump;
 
 use auth::get_auth_method;
 use auth::AuthMethod;
 pub use pm::add;
+pub use pm::audit;
 pub use pm::cache_top_level_deps;
+pub use pm::lock;
+pub use pm::outdated;
 pub use pm::remove;
+pub use pm::unused;
+pub use pm::why;
 pub use pm::AddCommandName;
 use publish_order::PublishOrderGraph;
 use unfurl::SpecifierUnfurler;
+use version_bump::VersionBump;
 
 use super::check::TypeChecker;
 
@@ -82,10 +90,35 @@ pub async fn publish(
   flags: Arc<Flags>,
   publish_flags: PublishFlags,
 ) -> Result<(), AnyError> {
+  let check_git_dirty = std::env::var("DENO_TESTING_DISABLE_GIT_CHECK")
+    .ok()
+    .is_none()
+    && !publish_flags.allow_dirty;
+  if let Some(set_version) = &publish_flags.set_version {
+    // the versions are updated before loading the config files that are
+    // published, so the changes of the update aren't seen as dirty
+    set_versions(
+      flags.clone(),
+      &VersionBump::parse(set_version)?,
+      &publish_flags,
+      check_git_dirty,
+    )
+    .await?;
+  }
+
   let cli_factory = CliFactory::from_flags(flags);
 
   let auth_method =
     get_auth_method(publish_flags.token, publish_flags.dry_run)?;
+  let provenance = match &publish_flags.provenance_file {
+    Some(provenance_file) => {
+      Provenance::Bundle(read_provenance_bundle(Path::new(provenance_file))?)
+    }
+    None if publish_flags.no_provenance => Provenance::Disabled,
+    None => Provenance::Automatic,
+  };
+  let registry_url =
+    publish_flags.registry.unwrap_or_else(|| jsr_url().clone());
 
   let cli_options = cli_factory.cli_options()?;
   let directory_path = cli_options.initial_cwd();
@@ -134,7 +167,11 @@ pub async fn publish(
     specifier_unfurler,
   );
 
-  let prepared_data = publish_preparer
+  let package_dirs = publish_configs
+    .iter()
+    .map(|config| (config.name.clone(), config.config_file.dir_path()))
+    .collect::<HashMap<_, _>>();
+  let mut prepared_data = publish_preparer
     .prepare_packages_for_publishing(
       publish_flags.allow_slow_types,
       &diagnostics_collector,
@@ -148,17 +185,51 @@ pub async fn publish(
     bail!("No packages to publish");
   }
 
-  if std::env::var("DENO_TESTING_DISABLE_GIT_CHECK")
-    .ok()
-    .is_none()
-    && !publish_flags.allow_dirty
-  {
-    if let Some(dirty_text) =
-      check_if_git_repo_dirty(cli_options.initial_cwd()).await
-    {
-      log::error!("\nUncommitted changes:\n\n{}\n", dirty_text);
-      bail!("Aborting due to uncommitted changes. Check in source code or run with --allow-dirty");
+  if let Some(git_ref) = &publish_flags.changed_since {
+    let changed_files =
+      git_changed_files(cli_options.initial_cwd(), git_ref).await?;
+    let changed_packages = package_dirs
+      .iter()
+      .filter(|(_, dir)| changed_files.iter().any(|file| file.starts_with(dir)))
+      .map(|(name, _)| name.clone());
+    let mut names = prepared_data
+      .publish_order_graph
+      .with_dependents(changed_packages);
+    if !publish_flags.dry_run {
+      let http_client = cli_factory.http_client_provider().get_or_create()?;
+      let registry_api_url = registry_api_url(&registry_url)?;
+      for name in names.clone() {
+        let package = &prepared_data.package_by_name[&name];
+        if is_version_published(&http_client, &registry_api_url, package)
+          .await?
+        {
+          log::info!(
+            "{} {}",
+            colors::yellow("Skipping, already published"),
+            package.display_name()
+          );
+          names.remove(&name);
+        }
+      }
     }
+    if names.is_empty() {
+      log::info!("No packages to publish with changes since {}", git_ref);
+      return Ok(());
+    }
+    prepared_data = prepared_data.retain(&names);
+  }
+
+  if matches!(provenance, Provenance::Bundle(_))
+    && prepared_data.package_by_name.len() > 1
+  {
+    bail!(
+      "--provenance-file can only be used when publishing a single package, but the workspace has {} packages",
+      prepared_data.package_by_name.len()
+    );
+  }
+
+  if check_git_dirty && publish_flags.set_version.is_none() {
+    ensure_git_repo_not_dirty(cli_options.initial_cwd()).await?;
   }
 
   if publish_flags.dry_run {
@@ -171,6 +242,14 @@ pub async fn publish(
       for file in &package.tarball.files {
         log::info!("   {} ({})", file.specifier, human_size(file.size as f64),);
       }
+      if publish_flags.diff_previous {
+        diff::print_diff_previous(
+          &cli_factory.http_client_provider().get_or_create()?,
+          &registry_url,
+          &package,
+        )
+        .await?;
+      }
     }
     log::warn!("{} Dry run complete", colors::green("Success"));
     return Ok(());
@@ -181,13 +260,75 @@ pub async fn publish(
     prepared_data.publish_order_graph,
     prepared_data.package_by_name,
     auth_method,
-    !publish_flags.no_provenance,
+    &registry_url,
+    &provenance,
   )
   .await?;
 
   Ok(())
 }
 
+async fn set_versions(
+  flags: Arc<Flags>,
+  bump: &VersionBump,
+  publish_flags: &PublishFlags,
+  check_git_dirty: bool,
+) -> Result<(), AnyError> {
+  let cli_factory = CliFactory::from_flags(flags);
+  let cli_options = cli_factory.cli_options()?;
+  if check_git_dirty {
+    ensure_git_repo_not_dirty(cli_options.initial_cwd()).await?;
+  }
+  let packages = cli_options.start_dir.jsr_packages_for_publish();
+  if packages.is_empty() {
+    bail!("No packages to set the version of");
+  }
+  let changes =
+    version_bump::set_package_versions(&packages, bump, publish_flags.dry_run)?;
+  if publish_flags.tag && !publish_flags.dry_run {
+    version_bump::commit_and_tag(cli_options.initial_cwd(), &changes).await?;
+  }
+  Ok(())
+}
+
+async fn ensure_git_repo_not_dirty(cwd: &Path) -> Result<(), AnyError> {
+  if let Some(dirty_text) = check_if_git_repo_dirty(cwd).await {
+    log::error!("\nUncommitted changes:\n\n{}\n", dirty_text);
+    bail!("Aborting due to uncommitted changes. Check in source code or run with --allow-dirty");
+  }
+  Ok(())
+}
+
+/// How the provenance attestations of the published packages are provided.
+enum Provenance {
+  Disabled,
+  /// Generated with the OIDC token when running on Github actions.
+  Automatic,
+  /// A Sigstore bundle which was generated outside of Github actions.
+  Bundle(Value),
+}
+
+fn read_provenance_bundle(path: &Path) -> Result<Value, AnyError> {
+  let text = std::fs::read_to_string(path).with_context(|| {
+    format!("Failed reading provenance file {}", path.display())
+  })?;
+  let bundle: Value = serde_json::from_str(&text).with_context(|| {
+    format!("Failed parsing provenance file {}", path.display())
+  })?;
+  if !bundle.is_object() {
+    bail!(
+      "Expected the provenance file {} to contain a Sigstore bundle object",
+      path.display()
+    );
+  }
+  Ok(bundle)
+}
+
+/// The API of a JSR compatible registry is served at `api/` of its URL.
+fn registry_api_url(registry_url: &Url) -> Result<Url, AnyError> {
+  Ok(registry_url.join("api/")?)
+}
+
 struct PreparedPublishPackage {
   scope: String,
   package: String,
@@ -208,6 +349,16 @@ struct PreparePackagesData {
   package_by_name: HashMap<String, Rc<PreparedPublishPackage>>,
 }
 
+impl PreparePackagesData {
+  fn retain(mut self, names: &HashSet<String>) -> Self {
+    self.package_by_name.retain(|name, _| names.contains(name));
+    Self {
+      publish_order_graph: self.publish_order_graph.retain(names),
+      package_by_name: self.package_by_name,
+    }
+  }
+}
+
 struct PublishPreparer {
   graph_diagnostics_collector: GraphDiagnosticsCollector,
   module_graph_creator: Arc<ModuleGraphCreator>,
@@ -831,10 +982,10 @@ async fn perform_publish(
   mut publish_order_graph: PublishOrderGraph,
   mut prepared_package_by_name: HashMap<String, Rc<PreparedPublishPackage>>,
   auth_method: AuthMethod,
-  provenance: bool,
+  registry_url: &Url,
+  provenance: &Provenance,
 ) -> Result<(), AnyError> {
-  let registry_api_url = jsr_api_url();
-  let registry_url = jsr_url();
+  let registry_api_url = &registry_api_url(registry_url)?;
 
   let packages = prepared_package_by_name
     .values()
@@ -919,7 +1070,7 @@ async fn publish_package(
   registry_api_url: &Url,
   registry_url: &Url,
   authorization: &str,
-  provenance: bool,
+  provenance: &Provenance,
 ) -> Result<(), AnyError> {
   log::info!(
     "{} @{}/{}@{} ...",
@@ -1026,12 +1177,40 @@ async fn publish_package(
   }
 
   let enable_provenance = std::env::var("DISABLE_JSR_PROVENANCE").is_err()
-    && (auth::is_gha() && auth::gha_oidc_token().is_some() && provenance);
+    && (auth::is_gha()
+      && auth::gha_oidc_token().is_some()
+      && matches!(provenance, Provenance::Automatic));
+
+  let provenance_url = format!(
+    "{}scopes/{}/packages/{}/versions/{}/provenance",
+    registry_api_url, package.scope, package.package, package.version
+  );
+
+  if let Provenance::Bundle(bundle) = provenance {
+    let response = http_client
+      .post_json(provenance_url.parse()?, &json!({ "bundle": bundle }))?
+      .header(http::header::AUTHORIZATION, authorization.parse()?)
+      .send()
+      .await?;
+    let status = response.status();
+    if !status.is_success() {
+      let text = http_util::body_to_string(response).await?;
+      bail!(
+        "Failed to submit the provenance of @{}/{} at {}: {}: {}",
+        package.scope,
+        package.package,
+        package.version,
+        status,
+        text
+      );
+    }
+    log::info!("{}", colors::green("Provenance attestation submitted"));
+  }
 
   // Enable provenance by default on Github actions with OIDC token
   if enable_provenance {
     // Get the version manifest from the registry
-    let meta_url = jsr_url().join(&format!(
+    let meta_url = registry_url.join(&format!(
       "@{}/{}/{}_meta.json",
       package.scope, package.package, package.version
     ))?;
@@ -1064,10 +1243,6 @@ async fn publish_package(
      );
 
     // Submit bundle to JSR
-    let provenance_url = format!(
-      "{}scopes/{}/packages/{}/versions/{}/provenance",
-      registry_api_url, package.scope, package.package, package.version
-    );
     http_client
       .post_json(provenance_url.parse()?, &json!({ "bundle": bundle }))?
       .header(http::header::AUTHORIZATION, authorization.parse()?)
@@ -1223,6 +1398,58 @@ async fn check_if_git_repo_dirty(cwd: &Path) -> Option<String> {
   }
 }
 
+/// The files which changed since the git ref, including the uncommitted
+/// changes.
+async fn git_changed_files(
+  cwd: &Path,
+  git_ref: &str,
+) -> Result<Vec<PathBuf>, AnyError> {
+  let root_dir = run_git(cwd, &["rev-parse", "--show-toplevel"]).await?;
+  let root_dir = PathBuf::from(root_dir.trim());
+  let changed_files =
+    run_git(cwd, &["diff", "--name-only", git_ref, "--"]).await?;
+  Ok(
+    changed_files
+      .lines()
+      .filter(|line| !line.is_empty())
+      .map(|line| root_dir.join(line))
+      .collect(),
+  )
+}
+
+/// Runs git and returns its stdout, erroring when it fails.
+async fn run_git(cwd: &Path, args: &[&str]) -> Result<String, AnyError> {
+  let bin_name = if cfg!(windows) { "git.exe" } else { "git" };
+  let output = Command::new(bin_name)
+    .current_dir(cwd)
+    .args(args)
+    .stdin(Stdio::null())
+    .output()
+    .await
+    .context("Failed to run git")?;
+  if !output.status.success() {
+    bail!(
+      "Failed to run 'git {}':\n{}",
+      args.join(" "),
+      String::from_utf8_lossy(&output.stderr).trim()
+    );
+  }
+  Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+async fn is_version_published(
+  http_client: &HttpClient,
+  registry_api_url: &Url,
+  package: &PreparedPublishPackage,
+) -> Result<bool, AnyError> {
+  let url = format!(
+    "{}scopes/{}/packages/{}/versions/{}",
+    registry_api_url, package.scope, package.package, package.version
+  );
+  let response = http_client.get(url.parse()?)?.send().await?;
+  Ok(response.status() == 200)
+}
+
 static SUPPORTED_LICENSE_FILE_NAMES: [&str; 6] = [
   "LICENSE",
   "LICENSE.md",
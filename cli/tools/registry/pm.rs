@@ -1,18 +1,31 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+mod audit;
 mod cache_deps;
+mod lock;
+mod outdated;
+mod unused;
+mod why;
 
+pub use audit::audit;
 pub use cache_deps::cache_top_level_deps;
 use deno_semver::jsr::JsrPackageReqReference;
 use deno_semver::npm::NpmPackageReqReference;
 use deno_semver::VersionReq;
+pub use lock::lock;
+pub use outdated::outdated;
+pub use unused::unused;
+pub use why::why;
 
 use std::borrow::Cow;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use deno_ast::TextChange;
+use deno_config::deno_json::ConfigFile;
 use deno_config::deno_json::FmtOptionsConfig;
+use deno_config::workspace::FolderConfigs;
+use deno_config::workspace::Workspace;
 use deno_core::anyhow::anyhow;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
@@ -33,6 +46,7 @@ use crate::args::CacheSetting;
 use crate::args::CliOptions;
 use crate::args::Flags;
 use crate::args::RemoveFlags;
+use crate::args::WorkspaceMemberTarget;
 use crate::factory::CliFactory;
 use crate::file_fetcher::FileFetcher;
 use crate::jsr::JsrFetchResolver;
@@ -56,12 +70,20 @@ impl DenoConfigFormat {
       _ => bail!("Unsupported deno config file: {file_name}"),
     }
   }
+
+  fn file_name(&self) -> &'static str {
+    match self {
+      Self::Json => "deno.json",
+      Self::Jsonc => "deno.jsonc",
+    }
+  }
 }
 
 struct DenoConfig {
   config: Arc<deno_config::deno_json::ConfigFile>,
   format: DenoConfigFormat,
   imports: IndexMap<String, String>,
+  dev_imports: IndexMap<String, String>,
 }
 
 fn deno_json_imports(
@@ -80,50 +102,96 @@ fn deno_json_imports(
       .unwrap_or_default(),
   )
 }
+
+fn deno_json_dev_imports(
+  config: &deno_config::deno_json::ConfigFile,
+) -> Result<IndexMap<String, String>, AnyError> {
+  Ok(
+    crate::args::deno_json_dev_imports(config)?
+      .map(|dev_imports| {
+        serde_json::from_value(serde_json::Value::Object(dev_imports)).map_err(
+          |err| anyhow!("Malformed \"devImports\" configuration: {err}"),
+        )
+      })
+      .transpose()?
+      .unwrap_or_default(),
+  )
+}
+
 impl DenoConfig {
   fn from_options(options: &CliOptions) -> Result<Option<Self>, AnyError> {
-    let start_dir = &options.start_dir;
-    if let Some(config) = start_dir.maybe_deno_json() {
-      Ok(Some(Self {
-        imports: deno_json_imports(config)?,
-        config: config.clone(),
-        format: DenoConfigFormat::from_specifier(&config.specifier)?,
-      }))
-    } else {
-      Ok(None)
-    }
+    options
+      .start_dir
+      .maybe_deno_json()
+      .map(Self::from_config_file)
+      .transpose()
+  }
+
+  fn from_config_file(config: &Arc<ConfigFile>) -> Result<Self, AnyError> {
+    Ok(Self {
+      imports: deno_json_imports(config)?,
+      dev_imports: deno_json_dev_imports(config)?,
+      config: config.clone(),
+      format: DenoConfigFormat::from_specifier(&config.specifier)?,
+    })
   }
 
-  fn add(&mut self, selected: SelectedPackage) {
-    self.imports.insert(
+  fn add(&mut self, selected: SelectedPackage, dev: bool) {
+    let (imports, other) = if dev {
+      (&mut self.dev_imports, &mut self.imports)
+    } else {
+      (&mut self.imports, &mut self.dev_imports)
+    };
+    // a package is either a dev dependency or not
+    other.shift_remove(&selected.import_name);
+    imports.insert(
       selected.import_name,
       format!("{}@{}", selected.package_name, selected.version_req),
     );
   }
 
   fn remove(&mut self, package: &str) -> bool {
-    self.imports.shift_remove(package).is_some()
+    let in_imports = self.imports.shift_remove(package).is_some();
+    let in_dev_imports = self.dev_imports.shift_remove(package).is_some();
+    in_imports || in_dev_imports
+  }
+
+  fn update(&mut self, key: &str, value: String) -> bool {
+    let imports = if self.imports.contains_key(key) {
+      &mut self.imports
+    } else {
+      &mut self.dev_imports
+    };
+    match imports.get_mut(key) {
+      Some(existing) => {
+        *existing = value;
+        true
+      }
+      None => false,
+    }
   }
 
   fn take_import_fields(
     &mut self,
   ) -> Vec<(&'static str, IndexMap<String, String>)> {
-    vec![("imports", std::mem::take(&mut self.imports))]
+    vec![
+      ("imports", std::mem::take(&mut self.imports)),
+      ("devImports", std::mem::take(&mut self.dev_imports)),
+    ]
   }
 }
 
 impl NpmConfig {
   fn from_options(options: &CliOptions) -> Result<Option<Self>, AnyError> {
-    let start_dir = &options.start_dir;
-    if let Some(pkg_json) = start_dir.maybe_pkg_json() {
-      Ok(Some(Self {
-        dependencies: pkg_json.dependencies.clone().unwrap_or_default(),
-        dev_dependencies: pkg_json.dev_dependencies.clone().unwrap_or_default(),
-        config: pkg_json.clone(),
-        fmt_options: None,
-      }))
-    } else {
-      Ok(None)
+    Ok(options.start_dir.maybe_pkg_json().map(Self::from_pkg_json))
+  }
+
+  fn from_pkg_json(pkg_json: &Arc<deno_node::PackageJson>) -> Self {
+    Self {
+      dependencies: pkg_json.dependencies.clone().unwrap_or_default(),
+      dev_dependencies: pkg_json.dev_dependencies.clone().unwrap_or_default(),
+      config: pkg_json.clone(),
+      fmt_options: None,
     }
   }
 
@@ -142,6 +210,21 @@ impl NpmConfig {
     in_deps || in_dev_deps
   }
 
+  fn update(&mut self, key: &str, value: String) -> bool {
+    let deps = if self.dependencies.contains_key(key) {
+      &mut self.dependencies
+    } else {
+      &mut self.dev_dependencies
+    };
+    match deps.get_mut(key) {
+      Some(existing) => {
+        *existing = value;
+        true
+      }
+      None => false,
+    }
+  }
+
   fn take_import_fields(
     &mut self,
   ) -> Vec<(&'static str, IndexMap<String, String>)> {
@@ -256,7 +339,7 @@ impl ConfigUpdater {
 
   fn add(&mut self, selected: SelectedPackage, dev: bool) {
     match &mut self.config {
-      DenoOrPackageJson::Deno(deno) => deno.add(selected),
+      DenoOrPackageJson::Deno(deno) => deno.add(selected, dev),
       DenoOrPackageJson::Npm(npm) => npm.add(selected, dev),
     }
     self.modified = true;
@@ -273,6 +356,18 @@ impl ConfigUpdater {
     removed
   }
 
+  /// Replaces the value of an existing dependency entry.
+  fn update(&mut self, key: &str, value: String) -> bool {
+    let updated = match &mut self.config {
+      DenoOrPackageJson::Deno(deno) => deno.update(key, value),
+      DenoOrPackageJson::Npm(npm) => npm.update(key, value),
+    };
+    if updated {
+      self.modified = true;
+    }
+    updated
+  }
+
   async fn commit(mut self) -> Result<(), AnyError> {
     if !self.modified {
       return Ok(());
@@ -337,10 +432,7 @@ impl DenoOrPackageJson {
 
   fn file_name(&self) -> &'static str {
     match self {
-      DenoOrPackageJson::Deno(config) => match config.format {
-        DenoConfigFormat::Json => "deno.json",
-        DenoConfigFormat::Jsonc => "deno.jsonc",
-      },
+      DenoOrPackageJson::Deno(config) => config.format.file_name(),
       DenoOrPackageJson::Npm(..) => "package.json",
     }
   }
@@ -389,11 +481,55 @@ impl std::fmt::Display for AddCommandName {
   }
 }
 
-fn load_configs(
+/// The configuration files of a workspace folder which are updated.
+struct FolderConfigUpdaters {
+  npm: Option<ConfigUpdater>,
+  deno: Option<ConfigUpdater>,
+}
+
+impl FolderConfigUpdaters {
+  async fn new(
+    npm_config: Option<NpmConfig>,
+    deno_config: Option<DenoConfig>,
+  ) -> Result<Self, AnyError> {
+    Ok(Self {
+      npm: ConfigUpdater::maybe_new(npm_config).await?,
+      deno: ConfigUpdater::maybe_new(deno_config).await?,
+    })
+  }
+
+  fn configs_mut(&mut self) -> impl Iterator<Item = &mut ConfigUpdater> {
+    self.npm.iter_mut().chain(self.deno.iter_mut())
+  }
+
+  fn into_configs(self) -> impl Iterator<Item = ConfigUpdater> {
+    self.npm.into_iter().chain(self.deno)
+  }
+}
+
+async fn load_configs(
   flags: &Arc<Flags>,
-) -> Result<(CliFactory, Option<NpmConfig>, Option<DenoConfig>), AnyError> {
+  target: &WorkspaceMemberTarget,
+) -> Result<(CliFactory, Vec<FolderConfigUpdaters>), AnyError> {
   let cli_factory = CliFactory::from_flags(flags.clone());
   let options = cli_factory.cli_options()?;
+  if *target != WorkspaceMemberTarget::Current {
+    let mut folders = Vec::new();
+    for folder in select_workspace_members(options.workspace(), target)? {
+      folders.push(
+        FolderConfigUpdaters::new(
+          folder.pkg_json.as_ref().map(NpmConfig::from_pkg_json),
+          folder
+            .deno_json
+            .as_ref()
+            .map(DenoConfig::from_config_file)
+            .transpose()?,
+        )
+        .await?,
+      );
+    }
+    return Ok((cli_factory, folders));
+  }
   let npm_config = NpmConfig::from_options(options)?;
   let (cli_factory, deno_config) = match DenoConfig::from_options(options)? {
     Some(config) => (cli_factory, Some(config)),
@@ -410,7 +546,94 @@ fn load_configs(
     }
   };
   assert!(deno_config.is_some() || npm_config.is_some());
-  Ok((cli_factory, npm_config, deno_config))
+  let folder = FolderConfigUpdaters::new(npm_config, deno_config).await?;
+  Ok((cli_factory, vec![folder]))
+}
+
+/// The configuration files of every folder of the workspace, including the
+/// root and the members.
+fn workspace_configs(
+  options: &CliOptions,
+) -> Result<Vec<(Option<DenoConfig>, Option<NpmConfig>)>, AnyError> {
+  options
+    .workspace()
+    .config_folders()
+    .iter()
+    .map(|(_, folder)| {
+      Ok((
+        folder
+          .deno_json
+          .as_ref()
+          .map(DenoConfig::from_config_file)
+          .transpose()?,
+        folder.pkg_json.as_ref().map(NpmConfig::from_pkg_json),
+      ))
+    })
+    .collect()
+}
+
+/// Selects the folders of the workspace members targeted by `--member` or
+/// `--all-members`. The lockfile is scoped by the members' configuration
+/// files, so it's updated for them when installing after the modification.
+fn select_workspace_members<'a>(
+  workspace: &'a Workspace,
+  target: &WorkspaceMemberTarget,
+) -> Result<Vec<&'a FolderConfigs>, AnyError> {
+  let root_url = workspace.root_dir();
+  let mut members = workspace
+    .config_folders()
+    .iter()
+    .filter(|(folder_url, _)| *folder_url != root_url);
+  match target {
+    WorkspaceMemberTarget::Current => unreachable!(),
+    WorkspaceMemberTarget::Member(name) => members
+      .find(|(folder_url, folder)| {
+        is_workspace_member(root_url, folder_url, folder, name)
+      })
+      .map(|(_, folder)| vec![folder])
+      .ok_or_else(|| {
+        anyhow!(
+          "Could not find a workspace member named \"{}\" in {}",
+          name,
+          root_url
+        )
+      }),
+    WorkspaceMemberTarget::AllMembers => {
+      let members = members.map(|(_, folder)| folder).collect::<Vec<_>>();
+      if members.is_empty() {
+        bail!("The workspace at {} has no members", root_url);
+      }
+      Ok(members)
+    }
+  }
+}
+
+/// Checks if the member has the package name or is at the path relative to
+/// the workspace root.
+fn is_workspace_member(
+  root_url: &ModuleSpecifier,
+  folder_url: &ModuleSpecifier,
+  folder: &FolderConfigs,
+  name: &str,
+) -> bool {
+  let deno_json_name = folder
+    .deno_json
+    .as_ref()
+    .and_then(|config| config.json.name.as_deref());
+  let pkg_json_name = folder
+    .pkg_json
+    .as_ref()
+    .and_then(|pkg_json| pkg_json.name.as_deref());
+  if deno_json_name == Some(name) || pkg_json_name == Some(name) {
+    return true;
+  }
+  let path = name
+    .strip_prefix("./")
+    .unwrap_or(name)
+    .trim_end_matches('/');
+  root_url
+    .make_relative(folder_url)
+    .is_some_and(|relative_path| relative_path.trim_end_matches('/') == path)
 }
 
 pub async fn add(
@@ -418,11 +641,10 @@ pub async fn add(
   add_flags: AddFlags,
   cmd_name: AddCommandName,
 ) -> Result<(), AnyError> {
-  let (cli_factory, npm_config, deno_config) = load_configs(&flags)?;
-  let mut npm_config = ConfigUpdater::maybe_new(npm_config).await?;
-  let mut deno_config = ConfigUpdater::maybe_new(deno_config).await?;
+  let (cli_factory, mut folders) =
+    load_configs(&flags, &add_flags.target).await?;
 
-  if let Some(deno) = &deno_config {
+  for deno in folders.iter().filter_map(|folder| folder.deno.as_ref()) {
     let specifier = deno.config.specifier();
     if deno.obj().get_string("importMap").is_some() {
       bail!(
@@ -448,9 +670,12 @@ pub async fn add(
     None,
   );
   deps_file_fetcher.set_download_log_level(log::Level::Trace);
+  let npmrc = cli_factory.cli_options()?.npmrc();
+  deps_file_fetcher.add_npmrc_auth_tokens(npmrc);
   let deps_file_fetcher = Arc::new(deps_file_fetcher);
   let jsr_resolver = Arc::new(JsrFetchResolver::new(deps_file_fetcher.clone()));
-  let npm_resolver = Arc::new(NpmFetchResolver::new(deps_file_fetcher));
+  let npm_resolver =
+    Arc::new(NpmFetchResolver::new(deps_file_fetcher, npmrc.clone()));
 
   let mut selected_packages = Vec::with_capacity(add_flags.packages.len());
   let mut package_reqs = Vec::with_capacity(add_flags.packages.len());
@@ -531,26 +756,45 @@ pub async fn add(
       selected_package.selected_version
     );
 
-    if selected_package.package_name.starts_with("npm:") {
-      if let Some(npm) = &mut npm_config {
-        npm.add(selected_package, dev);
+    for folder in &mut folders {
+      let selected_package = selected_package.clone();
+      if selected_package.package_name.starts_with("npm:") {
+        if let Some(npm) = &mut folder.npm {
+          npm.add(selected_package, dev);
+        } else {
+          folder.deno.as_mut().unwrap().add(selected_package, dev);
+        }
+      } else if let Some(deno) = &mut folder.deno {
+        deno.add(selected_package, dev);
       } else {
-        deno_config.as_mut().unwrap().add(selected_package, dev);
+        folder.npm.as_mut().unwrap().add(selected_package, dev);
       }
-    } else if let Some(deno) = &mut deno_config {
-      deno.add(selected_package, dev);
-    } else {
-      npm_config.as_mut().unwrap().add(selected_package, dev);
     }
   }
 
-  let mut commit_futures = vec![];
-  if let Some(npm) = npm_config {
-    commit_futures.push(npm.commit());
-  }
-  if let Some(deno) = deno_config {
-    commit_futures.push(deno.commit());
-  }
+  let configs = folders
+    .into_iter()
+    .flat_map(|folder| folder.into_configs())
+    .collect::<Vec<_>>();
+  // with a frozen lockfile the configuration files are restored when the
+  // added packages would change the lockfile
+  let original_contents = if cli_factory
+    .cli_options()?
+    .maybe_lockfile()
+    .is_some_and(|lockfile| lockfile.frozen())
+  {
+    configs
+      .iter()
+      .filter(|config| config.modified)
+      .map(|config| (config.path.clone(), config.contents().to_string()))
+      .collect()
+  } else {
+    Vec::new()
+  };
+  let commit_futures = configs
+    .into_iter()
+    .map(|config| config.commit())
+    .collect::<Vec<_>>();
   let commit_futures =
     deno_core::futures::future::join_all(commit_futures).await;
 
@@ -558,11 +802,18 @@ pub async fn add(
     result.context("Failed to update configuration file")?;
   }
 
-  npm_install_after_modification(flags, Some(jsr_resolver)).await?;
-
-  Ok(())
+  let result = npm_install_after_modification(flags, Some(jsr_resolver)).await;
+  if result.is_err() {
+    for (path, contents) in original_contents {
+      tokio::fs::write(&path, contents).await.with_context(|| {
+        format!("Failed to restore configuration file '{}'", path.display())
+      })?;
+    }
+  }
+  result
 }
 
+#[derive(Clone)]
 struct SelectedPackage {
   import_name: String,
   package_name: String,
@@ -752,18 +1003,13 @@ pub async fn remove(
   flags: Arc<Flags>,
   remove_flags: RemoveFlags,
 ) -> Result<(), AnyError> {
-  let (_, npm_config, deno_config) = load_configs(&flags)?;
-
-  let mut configs = [
-    ConfigUpdater::maybe_new(npm_config).await?,
-    ConfigUpdater::maybe_new(deno_config).await?,
-  ];
+  let (_, mut folders) = load_configs(&flags, &remove_flags.target).await?;
 
   let mut removed_packages = vec![];
 
   for package in &remove_flags.packages {
     let mut removed = false;
-    for config in configs.iter_mut().flatten() {
+    for config in folders.iter_mut().flat_map(|folder| folder.configs_mut()) {
       removed |= config.remove(package);
     }
     if removed {
@@ -777,7 +1023,7 @@ pub async fn remove(
     for package in &removed_packages {
       log::info!("Removed {}", crate::colors::green(package));
     }
-    for config in configs.into_iter().flatten() {
+    for config in folders.into_iter().flat_map(|folder| folder.into_configs()) {
       config.commit().await?;
     }
 
@@ -804,6 +1050,9 @@ async fn npm_install_after_modification(
   }
   // npm install
   cache_deps::cache_top_level_deps(&cli_factory, jsr_resolver).await?;
+  if let Some(lockfile) = cli_factory.cli_options()?.maybe_lockfile() {
+    lockfile.error_if_changed()?;
+  }
 
   Ok(())
 }
@@ -0,0 +1,531 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::futures::stream;
+use deno_core::futures::StreamExt;
+use deno_core::futures::TryStreamExt;
+use deno_core::serde_json::json;
+use deno_core::url::Url;
+use deno_lockfile::Lockfile;
+use deno_semver::package::PackageKind;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::why::split_package_id;
+use crate::args::AuditFlags;
+use crate::args::AuditSeverity;
+use crate::args::Flags;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::http_util;
+use crate::http_util::HttpClient;
+use crate::util::display;
+
+const OSV_API_URL: &str = "https://api.osv.dev/v1/";
+
+/// The maximum number of requests to the OSV API which are in flight at once.
+const OSV_MAX_CONCURRENT_REQUESTS: usize = 10;
+
+/// The maximum number of queries the OSV batch endpoint accepts at once.
+const OSV_MAX_BATCH_SIZE: usize = 1000;
+
+/// The OSV ecosystem of npm packages. OSV has no ecosystem for JSR packages,
+/// so those can't be audited.
+const OSV_NPM_ECOSYSTEM: &str = "npm";
+
+/// A package version resolved in the lockfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct AuditPackage {
+  kind: PackageKind,
+  name: String,
+  version: String,
+}
+
+impl Ord for AuditPackage {
+  fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    let key = |p: &Self| (p.kind == PackageKind::Npm, &p.name, &p.version);
+    key(self).cmp(&key(other))
+  }
+}
+
+impl PartialOrd for AuditPackage {
+  fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl AuditPackage {
+  fn display(&self) -> String {
+    let prefix = match self.kind {
+      PackageKind::Jsr => "jsr",
+      PackageKind::Npm => "npm",
+    };
+    format!("{}:{}@{}", prefix, self.name, self.version)
+  }
+}
+
+/// The unique package versions in the lockfile. Npm packages which only
+/// differ in their peer dependencies are audited once.
+fn collect_packages(lockfile: &Lockfile) -> Vec<AuditPackage> {
+  let packages = &lockfile.content.packages;
+  let jsr = packages
+    .jsr
+    .keys()
+    .map(|nv| (PackageKind::Jsr, nv.to_string()));
+  let npm = packages.npm.keys().map(|id| (PackageKind::Npm, id.clone()));
+  jsr
+    .chain(npm)
+    .filter_map(|(kind, id)| {
+      let (name, version) = split_package_id(&id)?;
+      Some(AuditPackage {
+        kind,
+        name: name.to_string(),
+        version: version.to_string(),
+      })
+    })
+    .collect::<BTreeSet<_>>()
+    .into_iter()
+    .collect()
+}
+
+#[derive(Serialize)]
+struct OsvQuery<'a> {
+  package: OsvPackage<'a>,
+  version: &'a str,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  page_token: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct OsvPackage<'a> {
+  name: &'a str,
+  ecosystem: &'a str,
+}
+
+#[derive(Deserialize)]
+struct OsvBatchResponse {
+  results: Vec<OsvBatchResult>,
+}
+
+#[derive(Deserialize)]
+struct OsvBatchResult {
+  #[serde(default)]
+  vulns: Vec<OsvVulnerabilityId>,
+  /// Set when there are more vulnerabilities than fit in one response.
+  #[serde(default)]
+  next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct OsvVulnerabilityId {
+  id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvVulnerability {
+  id: String,
+  #[serde(default)]
+  summary: Option<String>,
+  #[serde(default)]
+  aliases: Vec<String>,
+  #[serde(default)]
+  database_specific: Option<OsvDatabaseSpecific>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OsvDatabaseSpecific {
+  #[serde(default)]
+  severity: Option<String>,
+}
+
+impl OsvVulnerability {
+  fn severity(&self) -> Option<AuditSeverity> {
+    let severity = self.database_specific.as_ref()?.severity.as_deref()?;
+    parse_severity(severity)
+  }
+}
+
+fn parse_severity(text: &str) -> Option<AuditSeverity> {
+  match text.to_ascii_lowercase().as_str() {
+    "low" => Some(AuditSeverity::Low),
+    "moderate" | "medium" => Some(AuditSeverity::Moderate),
+    "high" => Some(AuditSeverity::High),
+    "critical" => Some(AuditSeverity::Critical),
+    _ => None,
+  }
+}
+
+/// A vulnerability affecting a package in the lockfile.
+#[derive(Debug)]
+struct Finding<'a> {
+  package: &'a AuditPackage,
+  vulnerability: Arc<OsvVulnerability>,
+}
+
+impl Finding<'_> {
+  fn severity(&self) -> Option<AuditSeverity> {
+    self.vulnerability.severity()
+  }
+
+  fn url(&self) -> String {
+    format!("https://osv.dev/vulnerability/{}", self.vulnerability.id)
+  }
+}
+
+/// The url of the OSV API, which can be changed with `DENO_OSV_API_URL`, like
+/// for a mirror.
+fn osv_api_url() -> Result<Url, AnyError> {
+  let mut url = match std::env::var("DENO_OSV_API_URL") {
+    Ok(url) => Url::parse(&url)
+      .with_context(|| format!("Invalid DENO_OSV_API_URL \"{url}\""))?,
+    Err(_) => Url::parse(OSV_API_URL)?,
+  };
+  // the endpoints are joined to the url
+  if !url.path().ends_with('/') {
+    url.set_path(&format!("{}/", url.path()));
+  }
+  Ok(url)
+}
+
+/// Queries the ids of the vulnerabilities affecting each of the npm
+/// packages, following the pages of the packages with many of them.
+async fn query_vulnerability_ids(
+  client: &HttpClient,
+  api_url: &Url,
+  packages: &[&AuditPackage],
+) -> Result<Vec<Vec<String>>, AnyError> {
+  let url = api_url.join("querybatch")?;
+  let mut ids = vec![Vec::new(); packages.len()];
+  // the index of each package left to query with the page to query
+  let mut pending = (0..packages.len())
+    .map(|index| (index, None))
+    .collect::<Vec<(usize, Option<String>)>>();
+  while !pending.is_empty() {
+    let mut next_pending = Vec::new();
+    for chunk in pending.chunks(OSV_MAX_BATCH_SIZE) {
+      let queries = chunk
+        .iter()
+        .map(|(index, page_token)| OsvQuery {
+          package: OsvPackage {
+            name: &packages[*index].name,
+            ecosystem: OSV_NPM_ECOSYSTEM,
+          },
+          version: &packages[*index].version,
+          page_token: page_token.as_deref(),
+        })
+        .collect::<Vec<_>>();
+      let response = client
+        .post_json(url.clone(), &json!({ "queries": queries }))?
+        .send()
+        .await?;
+      if !response.status().is_success() {
+        bail!(
+          "Failed querying vulnerabilities from {}: {}",
+          url,
+          response.status()
+        );
+      }
+      let body: OsvBatchResponse = http_util::body_to_json(response).await?;
+      if body.results.len() != chunk.len() {
+        bail!("Unexpected number of results from {}", url);
+      }
+      for ((index, _), result) in chunk.iter().zip(body.results) {
+        ids[*index].extend(result.vulns.into_iter().map(|vuln| vuln.id));
+        if let Some(page_token) = result.next_page_token {
+          next_pending.push((*index, Some(page_token)));
+        }
+      }
+    }
+    pending = next_pending;
+  }
+  Ok(ids)
+}
+
+async fn fetch_vulnerability(
+  client: &HttpClient,
+  api_url: &Url,
+  id: &str,
+) -> Result<OsvVulnerability, AnyError> {
+  let url = api_url.join(&format!("vulns/{id}"))?;
+  let response = client.get(url.clone())?.send().await?;
+  if !response.status().is_success() {
+    bail!(
+      "Failed fetching vulnerability {} from {}: {}",
+      id,
+      url,
+      response.status()
+    );
+  }
+  http_util::body_to_json(response).await
+}
+
+fn severity_label(severity: Option<AuditSeverity>) -> String {
+  match severity {
+    Some(AuditSeverity::Critical) => colors::red_bold("critical").to_string(),
+    Some(AuditSeverity::High) => colors::red("high").to_string(),
+    Some(AuditSeverity::Moderate) => colors::yellow("moderate").to_string(),
+    Some(AuditSeverity::Low) => colors::gray("low").to_string(),
+    None => colors::gray("unknown").to_string(),
+  }
+}
+
+fn print_findings(findings: &[Finding], package_count: usize) {
+  if findings.is_empty() {
+    log::info!(
+      "{} No known vulnerabilities found in {} packages",
+      colors::green("✓"),
+      package_count
+    );
+    return;
+  }
+
+  let mut severity_counts = Vec::new();
+  let mut index = 0;
+  while index < findings.len() {
+    let severity = findings[index].severity();
+    let count = findings[index..]
+      .iter()
+      .take_while(|finding| finding.severity() == severity)
+      .count();
+    log::info!("{} ({})", colors::bold(severity_label(severity)), count);
+    for finding in &findings[index..index + count] {
+      log::info!(
+        "  {} {} {}",
+        finding.package.display(),
+        colors::gray(&finding.vulnerability.id),
+        finding.vulnerability.summary.as_deref().unwrap_or_default()
+      );
+      log::info!("    {}", colors::gray(finding.url()));
+    }
+    log::info!("");
+    severity_counts.push(format!(
+      "{} {}",
+      count,
+      severity.map(|s| s.as_str()).unwrap_or("unknown")
+    ));
+    index += count;
+  }
+  let affected_packages = findings
+    .iter()
+    .map(|finding| finding.package)
+    .collect::<BTreeSet<_>>()
+    .len();
+  log::info!(
+    "Found {} vulnerabilities in {} of {} packages ({})",
+    findings.len(),
+    affected_packages,
+    package_count,
+    severity_counts.join(", ")
+  );
+}
+
+fn print_findings_json(findings: &[Finding]) -> Result<(), AnyError> {
+  let vulnerabilities = findings
+    .iter()
+    .map(|finding| {
+      json!({
+        "package": finding.package.display(),
+        "id": finding.vulnerability.id,
+        "aliases": finding.vulnerability.aliases,
+        "severity": finding.severity().map(|s| s.as_str()),
+        "summary": finding.vulnerability.summary,
+        "url": finding.url(),
+      })
+    })
+    .collect::<Vec<_>>();
+  display::write_json_to_stdout(&json!({
+    "version": 1,
+    "vulnerabilities": vulnerabilities,
+  }))
+}
+
+/// Whether the finding should make the command exit with an error. The
+/// vulnerabilities without a severity are treated as high ones, so that they
+/// aren't missed.
+fn is_failure(finding: &Finding, fail_on: Option<AuditSeverity>) -> bool {
+  match fail_on {
+    Some(min) => finding.severity().unwrap_or(AuditSeverity::High) >= min,
+    None => true,
+  }
+}
+
+pub async fn audit(
+  flags: Arc<Flags>,
+  audit_flags: AuditFlags,
+) -> Result<(), AnyError> {
+  let cli_factory = CliFactory::from_flags(flags);
+  let cli_options = cli_factory.cli_options()?;
+  let Some(lockfile) = cli_options.maybe_lockfile() else {
+    bail!(
+      "`deno audit` requires a lockfile. Run `deno install` to create one."
+    );
+  };
+  let packages = collect_packages(&lockfile.lock());
+  let client = cli_factory.http_client_provider().get_or_create()?;
+  let api_url = osv_api_url()?;
+
+  let npm_packages = packages
+    .iter()
+    .filter(|package| package.kind == PackageKind::Npm)
+    .collect::<Vec<_>>();
+  let jsr_package_count = packages.len() - npm_packages.len();
+  if jsr_package_count > 0 {
+    log::info!(
+      "{} Skipped {} JSR {}, OSV has no advisories for JSR packages.",
+      colors::gray("Note"),
+      jsr_package_count,
+      if jsr_package_count == 1 {
+        "package"
+      } else {
+        "packages"
+      }
+    );
+  }
+  let npm_ids =
+    query_vulnerability_ids(&client, &api_url, &npm_packages).await?;
+  let affected = npm_packages.into_iter().zip(npm_ids).collect::<Vec<_>>();
+
+  let ids = affected
+    .iter()
+    .flat_map(|(_, ids)| ids.iter().map(|id| id.as_str()))
+    .collect::<BTreeSet<_>>();
+  let vulnerabilities = stream::iter(ids.iter().map(|id| {
+    let client = &client;
+    let api_url = &api_url;
+    async move {
+      let vulnerability = fetch_vulnerability(client, api_url, id).await?;
+      Ok::<_, AnyError>((*id, Arc::new(vulnerability)))
+    }
+  }))
+  .buffer_unordered(OSV_MAX_CONCURRENT_REQUESTS)
+  .try_collect::<HashMap<_, _>>()
+  .await?;
+
+  let mut findings = affected
+    .iter()
+    .flat_map(|(package, ids)| {
+      ids.iter().map(|id| Finding {
+        package,
+        vulnerability: vulnerabilities[id.as_str()].clone(),
+      })
+    })
+    .collect::<Vec<_>>();
+  // most severe first, vulnerabilities of unknown severity last
+  findings.sort_by(|a, b| {
+    b.severity()
+      .cmp(&a.severity())
+      .then_with(|| a.package.cmp(b.package))
+      .then_with(|| a.vulnerability.id.cmp(&b.vulnerability.id))
+  });
+
+  if audit_flags.json {
+    print_findings_json(&findings)?;
+  } else {
+    print_findings(&findings, packages.len() - jsr_package_count);
+  }
+
+  let failures = findings
+    .iter()
+    .filter(|finding| is_failure(finding, audit_flags.fail_on))
+    .count();
+  if failures > 0 {
+    match audit_flags.fail_on {
+      Some(min) => bail!(
+        "Found {} vulnerabilities of {} severity or higher.",
+        failures,
+        min.as_str()
+      ),
+      None => bail!("Found {} vulnerabilities.", failures),
+    }
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use super::*;
+
+  fn vulnerability(id: &str, severity: Option<&str>) -> Arc<OsvVulnerability> {
+    Arc::new(OsvVulnerability {
+      id: id.to_string(),
+      summary: None,
+      aliases: Vec::new(),
+      database_specific: Some(OsvDatabaseSpecific {
+        severity: severity.map(|s| s.to_string()),
+      }),
+    })
+  }
+
+  #[test]
+  fn test_collect_packages() {
+    let lockfile = Lockfile::new(deno_lockfile::NewLockfileOptions {
+      file_path: PathBuf::from("/deno.lock"),
+      content: r#"{
+        "version": "4",
+        "jsr": {
+          "@std/fmt@1.0.0": { "integrity": "abc" }
+        },
+        "npm": {
+          "preact@10.0.0_react@18.2.0": { "integrity": "def" },
+          "preact@10.0.0_react@17.0.0": { "integrity": "def" },
+          "@types/node@20.1.0": { "integrity": "ghi" }
+        }
+      }"#,
+      overwrite: false,
+    })
+    .unwrap();
+    let packages = collect_packages(&lockfile)
+      .iter()
+      .map(|package| package.display())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      packages,
+      vec![
+        "jsr:@std/fmt@1.0.0",
+        "npm:@types/node@20.1.0",
+        "npm:preact@10.0.0"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_is_failure() {
+    let package = AuditPackage {
+      kind: PackageKind::Npm,
+      name: "chalk".to_string(),
+      version: "1.0.0".to_string(),
+    };
+    let finding = |severity| Finding {
+      package: &package,
+      vulnerability: vulnerability("GHSA-1", severity),
+    };
+    assert_eq!(
+      finding(Some("MEDIUM")).severity(),
+      Some(AuditSeverity::Moderate)
+    );
+    assert!(is_failure(&finding(None), None));
+    assert!(is_failure(&finding(None), Some(AuditSeverity::Low)));
+    assert!(is_failure(&finding(None), Some(AuditSeverity::High)));
+    assert!(!is_failure(&finding(None), Some(AuditSeverity::Critical)));
+    assert!(is_failure(
+      &finding(Some("CRITICAL")),
+      Some(AuditSeverity::High)
+    ));
+    assert!(is_failure(
+      &finding(Some("HIGH")),
+      Some(AuditSeverity::High)
+    ));
+    assert!(!is_failure(
+      &finding(Some("MODERATE")),
+      Some(AuditSeverity::High)
+    ));
+  }
+}
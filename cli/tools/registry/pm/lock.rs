@@ -0,0 +1,505 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_graph::GraphKind;
+use deno_graph::ModuleGraph;
+use deno_lockfile::Lockfile;
+use deno_semver::jsr::JsrDepPackageReq;
+use deno_semver::package::PackageKind;
+use deno_semver::package::PackageNv;
+use deno_semver::Version;
+
+use super::outdated::collect_dependencies;
+use super::outdated::Dependency;
+use super::DenoConfig;
+use super::NpmConfig;
+use crate::args::jsr_url;
+use crate::args::Flags;
+use crate::args::LockFlags;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::graph_util::collect_graph_package_reqs;
+use crate::util::display;
+use crate::util::fs::atomic_write_file_with_retries;
+
+pub async fn lock(
+  flags: Arc<Flags>,
+  lock_flags: LockFlags,
+) -> Result<(), AnyError> {
+  let flags = match &lock_flags {
+    LockFlags::Verify { .. } => {
+      // never update the lockfile while verifying it
+      let mut flags = (*flags).clone();
+      flags.frozen_lockfile = Some(true);
+      Arc::new(flags)
+    }
+    _ => flags,
+  };
+  let factory = CliFactory::from_flags(flags);
+  let cli_options = factory.cli_options()?;
+  let Some(lockfile) = cli_options.maybe_lockfile().cloned() else {
+    bail!("`deno lock` requires a lockfile. Run `deno install` to create one.");
+  };
+  let deno_config = DenoConfig::from_options(cli_options)?;
+  let npm_config = NpmConfig::from_options(cli_options)?;
+  let root_deps =
+    collect_dependencies(deno_config.as_ref(), npm_config.as_ref());
+
+  match lock_flags {
+    LockFlags::Export { output } => {
+      let import_map = lockfile_import_map(&lockfile.lock(), &root_deps);
+      match output {
+        Some(output) => {
+          let path = cli_options.initial_cwd().join(output);
+          let mut text = serde_json::to_string_pretty(&import_map)?;
+          text.push('\n');
+          atomic_write_file_with_retries(&path, text, 0o644)
+            .with_context(|| format!("Failed writing {}", path.display()))?;
+          log::info!("{} {}", colors::green("Exported"), path.display());
+          Ok(())
+        }
+        None => display::write_json_to_stdout(&import_map),
+      }
+    }
+    LockFlags::Prune { files } => {
+      let graph = create_graph(&factory, &files).await?;
+      let unused = {
+        let lockfile = lockfile.lock();
+        let used = UsedEntries::from_graph(&graph, &root_deps);
+        UnusedEntries::collect(&lockfile, &used)
+      };
+      if unused.is_empty() {
+        lockfile.write_if_changed()?;
+        log::info!("The lockfile has no unused entries.");
+        return Ok(());
+      }
+      {
+        let mut lockfile = lockfile.lock();
+        unused.remove_from(&mut lockfile);
+        lockfile.has_content_changed = true;
+      }
+      lockfile.write_if_changed()?;
+      log::info!(
+        "{} {} unused {} from the lockfile.",
+        colors::green("Removed"),
+        unused.len(),
+        if unused.len() == 1 {
+          "entry"
+        } else {
+          "entries"
+        }
+      );
+      Ok(())
+    }
+    LockFlags::Verify { files } => {
+      let graph = create_graph(&factory, &files).await?;
+      lockfile.error_if_changed()?;
+      let unused = {
+        let lockfile = lockfile.lock();
+        let used = UsedEntries::from_graph(&graph, &root_deps);
+        UnusedEntries::collect(&lockfile, &used)
+      };
+      if !unused.is_empty() {
+        log::warn!(
+          "{} The lockfile has {} unused {}. Run `deno lock prune` to remove them.",
+          colors::yellow("Warning"),
+          unused.len(),
+          if unused.len() == 1 { "entry" } else { "entries" }
+        );
+      }
+      log::info!("{} The lockfile is up to date.", colors::green("Verified"));
+      Ok(())
+    }
+  }
+}
+
+async fn create_graph(
+  factory: &CliFactory,
+  files: &[String],
+) -> Result<ModuleGraph, AnyError> {
+  let roots = factory
+    .main_module_graph_container()
+    .await?
+    .collect_specifiers(files)?;
+  let module_graph_creator = factory.module_graph_creator().await?;
+  let graph = module_graph_creator
+    .create_graph(GraphKind::All, roots)
+    .await?;
+  // don't prune the entries of modules which failed to load
+  module_graph_creator.graph_valid(&graph)?;
+  Ok(graph)
+}
+
+/// An import map which maps the package requirements of the lockfile and of
+/// the configuration files to the locked versions, with a scope for the
+/// dependencies of each jsr package, and the remote redirects.
+fn lockfile_import_map(
+  lockfile: &Lockfile,
+  root_deps: &[Dependency],
+) -> serde_json::Value {
+  let specifiers = &lockfile.content.packages.specifiers;
+  let pinned = |dep_req: &JsrDepPackageReq| {
+    let version = specifiers.get(dep_req)?;
+    // drop the peer dependency suffix of npm packages
+    let version = version.split('_').next().unwrap_or(version);
+    Some(format!(
+      "{}:{}@{}",
+      kind_prefix(dep_req.kind),
+      dep_req.req.name,
+      version
+    ))
+  };
+  let insert_package =
+    |map: &mut serde_json::Map<String, serde_json::Value>,
+     key: String,
+     pinned: String| {
+      if !key.ends_with('/') {
+        map.insert(format!("{key}/"), format!("{pinned}/").into());
+      }
+      map.insert(key, pinned.into());
+    };
+
+  let mut imports = serde_json::Map::new();
+  for dep in root_deps {
+    let dep_req = JsrDepPackageReq {
+      kind: dep.kind,
+      req: dep.req.clone(),
+    };
+    let Some(pinned) = pinned(&dep_req) else {
+      continue;
+    };
+    match &dep.sub_path {
+      Some(sub_path) => {
+        imports.insert(dep.key.clone(), format!("{pinned}/{sub_path}").into());
+      }
+      None => insert_package(&mut imports, dep.key.clone(), pinned),
+    }
+  }
+  for dep_req in specifiers.keys() {
+    if let Some(pinned) = pinned(dep_req) {
+      let key = format!("{}:{}", kind_prefix(dep_req.kind), dep_req.req);
+      insert_package(&mut imports, key, pinned);
+    }
+  }
+  for (from, to) in &lockfile.content.redirects {
+    imports.insert(from.clone(), to.clone().into());
+  }
+
+  let mut scopes = serde_json::Map::new();
+  for (nv, info) in &lockfile.content.packages.jsr {
+    let mut scope = serde_json::Map::new();
+    for dep_req in &info.dependencies {
+      if let Some(pinned) = pinned(dep_req) {
+        let key = format!("{}:{}", kind_prefix(dep_req.kind), dep_req.req);
+        insert_package(&mut scope, key, pinned);
+      }
+    }
+    if !scope.is_empty() {
+      let url = format!("{}{}/{}/", jsr_url(), nv.name, nv.version);
+      scopes.insert(url, scope.into());
+    }
+  }
+
+  let mut import_map = serde_json::Map::new();
+  import_map.insert("imports".to_string(), imports.into());
+  if !scopes.is_empty() {
+    import_map.insert("scopes".to_string(), scopes.into());
+  }
+  import_map.into()
+}
+
+fn kind_prefix(kind: PackageKind) -> &'static str {
+  match kind {
+    PackageKind::Jsr => "jsr",
+    PackageKind::Npm => "npm",
+  }
+}
+
+/// The entries of the lockfile which are used directly by a module graph or
+/// the configuration files.
+#[derive(Debug, Default)]
+struct UsedEntries {
+  reqs: HashSet<JsrDepPackageReq>,
+  remote: HashSet<String>,
+  redirects: HashSet<String>,
+}
+
+impl UsedEntries {
+  fn from_graph(graph: &ModuleGraph, root_deps: &[Dependency]) -> Self {
+    let mut used = UsedEntries::default();
+    used
+      .reqs
+      .extend(root_deps.iter().map(|dep| JsrDepPackageReq {
+        kind: dep.kind,
+        req: dep.req.clone(),
+      }));
+    let (npm_reqs, jsr_reqs) = collect_graph_package_reqs(graph);
+    used
+      .reqs
+      .extend(npm_reqs.into_iter().map(JsrDepPackageReq::npm));
+    used
+      .reqs
+      .extend(jsr_reqs.into_iter().map(JsrDepPackageReq::jsr));
+    // includes the requirements of the dependencies of jsr packages
+    used.reqs.extend(
+      graph
+        .packages
+        .mappings()
+        .keys()
+        .map(|req| JsrDepPackageReq::jsr(req.clone())),
+    );
+    used.remote.extend(
+      graph
+        .modules()
+        .map(|module| module.specifier())
+        .filter(|specifier| matches!(specifier.scheme(), "http" | "https"))
+        .map(|specifier| specifier.to_string()),
+    );
+    used
+      .redirects
+      .extend(graph.redirects.keys().map(|from| from.to_string()));
+    used
+  }
+}
+
+/// The entries of the lockfile which can't be reached from the used entries.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct UnusedEntries {
+  specifiers: Vec<JsrDepPackageReq>,
+  jsr: Vec<PackageNv>,
+  npm: Vec<String>,
+  remote: Vec<String>,
+  redirects: Vec<String>,
+}
+
+impl UnusedEntries {
+  fn collect(lockfile: &Lockfile, used: &UsedEntries) -> Self {
+    let packages = &lockfile.content.packages;
+    let mut reached_reqs = HashSet::new();
+    let mut reached_jsr = HashSet::new();
+    let mut reached_npm = HashSet::new();
+    let mut pending_reqs = used
+      .reqs
+      .iter()
+      .filter(|req| packages.specifiers.contains_key(*req))
+      .collect::<Vec<_>>();
+    let mut pending_npm = Vec::new();
+    while let Some(dep_req) = pending_reqs.pop() {
+      if !reached_reqs.insert(dep_req) {
+        continue;
+      }
+      let Some(version) = packages.specifiers.get(dep_req) else {
+        continue;
+      };
+      match dep_req.kind {
+        PackageKind::Jsr => {
+          let Ok(version) = Version::parse_standard(version) else {
+            continue;
+          };
+          let nv = PackageNv {
+            name: dep_req.req.name.clone(),
+            version,
+          };
+          if let Some(info) = packages.jsr.get(&nv) {
+            pending_reqs.extend(info.dependencies.iter());
+          }
+          reached_jsr.insert(nv);
+        }
+        PackageKind::Npm => {
+          pending_npm.push(format!("{}@{}", dep_req.req.name, version));
+        }
+      }
+    }
+    while let Some(id) = pending_npm.pop() {
+      if let Some(info) = packages.npm.get(&id) {
+        pending_npm.extend(
+          info
+            .dependencies
+            .values()
+            .filter(|dep_id| !reached_npm.contains(*dep_id))
+            .cloned(),
+        );
+      }
+      reached_npm.insert(id);
+    }
+
+    UnusedEntries {
+      specifiers: packages
+        .specifiers
+        .keys()
+        .filter(|req| !reached_reqs.contains(req))
+        .cloned()
+        .collect(),
+      jsr: packages
+        .jsr
+        .keys()
+        .filter(|nv| !reached_jsr.contains(*nv))
+        .cloned()
+        .collect(),
+      npm: packages
+        .npm
+        .keys()
+        .filter(|id| !reached_npm.contains(*id))
+        .cloned()
+        .collect(),
+      remote: lockfile
+        .content
+        .remote
+        .keys()
+        .filter(|specifier| !used.remote.contains(*specifier))
+        .cloned()
+        .collect(),
+      redirects: lockfile
+        .content
+        .redirects
+        .keys()
+        .filter(|from| !used.redirects.contains(*from))
+        .cloned()
+        .collect(),
+    }
+  }
+
+  fn len(&self) -> usize {
+    self.specifiers.len()
+      + self.jsr.len()
+      + self.npm.len()
+      + self.remote.len()
+      + self.redirects.len()
+  }
+
+  fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  fn remove_from(&self, lockfile: &mut Lockfile) {
+    let content = &mut lockfile.content;
+    for req in &self.specifiers {
+      content.packages.specifiers.remove(req);
+    }
+    for nv in &self.jsr {
+      content.packages.jsr.remove(nv);
+    }
+    for id in &self.npm {
+      content.packages.npm.remove(id);
+    }
+    for specifier in &self.remote {
+      content.remote.remove(specifier);
+    }
+    for from in &self.redirects {
+      content.redirects.remove(from);
+    }
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use std::path::PathBuf;
+
+  use deno_core::serde_json::json;
+  use deno_semver::package::PackageReq;
+
+  use super::*;
+
+  fn lockfile(value: serde_json::Value) -> Lockfile {
+    Lockfile::new(deno_lockfile::NewLockfileOptions {
+      file_path: PathBuf::from("/deno.lock"),
+      content: &value.to_string(),
+      overwrite: false,
+    })
+    .unwrap()
+  }
+
+  fn test_lockfile() -> Lockfile {
+    lockfile(json!({
+      "version": "4",
+      "specifiers": {
+        "jsr:@std/fs@1": "1.0.4",
+        "jsr:@std/path@^1.0.6": "1.0.6",
+        "npm:chalk@5": "5.3.0",
+        "npm:left-pad@1": "1.3.0",
+      },
+      "jsr": {
+        "@std/fs@1.0.4": {
+          "integrity": "abc",
+          "dependencies": ["jsr:@std/path@^1.0.6"],
+        },
+        "@std/path@1.0.6": { "integrity": "def" },
+      },
+      "npm": {
+        "chalk@5.3.0": { "integrity": "ghi" },
+        "left-pad@1.3.0": { "integrity": "jkl" },
+      },
+      "redirects": {
+        "https://deno.land/x/mod/mod.ts": "https://deno.land/x/mod@1.0.0/mod.ts",
+      },
+      "remote": {
+        "https://deno.land/x/mod@1.0.0/mod.ts": "mno",
+        "https://deno.land/x/old@1.0.0/mod.ts": "pqr",
+      },
+    }))
+  }
+
+  #[test]
+  fn test_lockfile_import_map() {
+    let lockfile = test_lockfile();
+    let import_map = lockfile_import_map(&lockfile, &[]);
+    assert_eq!(
+      import_map,
+      json!({
+        "imports": {
+          "jsr:@std/fs@1": "jsr:@std/fs@1.0.4",
+          "jsr:@std/fs@1/": "jsr:@std/fs@1.0.4/",
+          "jsr:@std/path@^1.0.6": "jsr:@std/path@1.0.6",
+          "jsr:@std/path@^1.0.6/": "jsr:@std/path@1.0.6/",
+          "npm:chalk@5": "npm:chalk@5.3.0",
+          "npm:chalk@5/": "npm:chalk@5.3.0/",
+          "npm:left-pad@1": "npm:left-pad@1.3.0",
+          "npm:left-pad@1/": "npm:left-pad@1.3.0/",
+          "https://deno.land/x/mod/mod.ts": "https://deno.land/x/mod@1.0.0/mod.ts",
+        },
+        "scopes": {
+          "https://jsr.io/@std/fs/1.0.4/": {
+            "jsr:@std/path@^1.0.6": "jsr:@std/path@1.0.6",
+            "jsr:@std/path@^1.0.6/": "jsr:@std/path@1.0.6/",
+          },
+        },
+      })
+    );
+  }
+
+  #[test]
+  fn test_unused_entries() {
+    let mut lockfile = test_lockfile();
+    let used = UsedEntries {
+      reqs: HashSet::from([
+        JsrDepPackageReq::jsr(PackageReq::from_str("@std/fs@1").unwrap()),
+        JsrDepPackageReq::npm(PackageReq::from_str("chalk@5").unwrap()),
+      ]),
+      remote: HashSet::from([
+        "https://deno.land/x/mod@1.0.0/mod.ts".to_string()
+      ]),
+      redirects: HashSet::from(["https://deno.land/x/mod/mod.ts".to_string()]),
+    };
+    let unused = UnusedEntries::collect(&lockfile, &used);
+    assert_eq!(
+      unused,
+      UnusedEntries {
+        specifiers: vec![JsrDepPackageReq::npm(
+          PackageReq::from_str("left-pad@1").unwrap()
+        )],
+        jsr: vec![],
+        npm: vec!["left-pad@1.3.0".to_string()],
+        remote: vec!["https://deno.land/x/old@1.0.0/mod.ts".to_string()],
+        redirects: vec![],
+      }
+    );
+    unused.remove_from(&mut lockfile);
+    assert!(UnusedEntries::collect(&lockfile, &used).is_empty());
+  }
+}
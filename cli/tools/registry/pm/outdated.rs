@@ -0,0 +1,452 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+
+use deno_core::error::AnyError;
+use deno_core::futures::future::join_all;
+use deno_semver::jsr::JsrPackageReqReference;
+use deno_semver::npm::NpmPackageReqReference;
+use deno_semver::package::PackageKind;
+use deno_semver::package::PackageReq;
+use deno_semver::Version;
+use serde::Serialize;
+
+use super::FolderConfigUpdaters;
+use crate::args::CacheSetting;
+use crate::args::Flags;
+use crate::args::OutdatedFlags;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::file_fetcher::FileFetcher;
+use crate::jsr::JsrFetchResolver;
+use crate::npm::NpmFetchResolver;
+use crate::util::display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum DependencySource {
+  DenoJson,
+  PackageJson,
+}
+
+/// A registry dependency found in a configuration file.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Dependency {
+  /// The key of the entry in "imports", "dependencies" or "devDependencies".
+  pub key: String,
+  pub kind: PackageKind,
+  pub req: PackageReq,
+  pub sub_path: Option<String>,
+  pub source: DependencySource,
+  /// Whether the value is written with a `jsr:`/`npm:` prefix.
+  prefixed: bool,
+}
+
+impl Dependency {
+  fn from_deno_json_entry(key: &str, value: &str) -> Option<Self> {
+    let (kind, req, sub_path) =
+      if let Ok(req_ref) = JsrPackageReqReference::from_str(value) {
+        let sub_path = req_ref.sub_path().map(|s| s.to_string());
+        (PackageKind::Jsr, req_ref.req().clone(), sub_path)
+      } else if let Ok(req_ref) = NpmPackageReqReference::from_str(value) {
+        let sub_path = req_ref.sub_path().map(|s| s.to_string());
+        (PackageKind::Npm, req_ref.req().clone(), sub_path)
+      } else {
+        return None;
+      };
+    Some(Self {
+      key: key.to_string(),
+      kind,
+      req,
+      sub_path,
+      source: DependencySource::DenoJson,
+      prefixed: true,
+    })
+  }
+
+  fn from_package_json_entry(key: &str, value: &str) -> Option<Self> {
+    let (req, prefixed) = if value.starts_with("npm:") {
+      let req_ref = NpmPackageReqReference::from_str(value).ok()?;
+      if req_ref.sub_path().is_some() {
+        return None;
+      }
+      (req_ref.req().clone(), true)
+    } else {
+      // skips "workspace:", "file:", git urls and the like
+      (PackageReq::from_str(&format!("{key}@{value}")).ok()?, false)
+    };
+    Some(Self {
+      key: key.to_string(),
+      kind: PackageKind::Npm,
+      req,
+      sub_path: None,
+      source: DependencySource::PackageJson,
+      prefixed,
+    })
+  }
+
+  /// The name of the package including the registry prefix.
+  pub fn display_name(&self) -> String {
+    let prefix = match self.kind {
+      PackageKind::Jsr => "jsr",
+      PackageKind::Npm => "npm",
+    };
+    format!("{}:{}", prefix, self.req.name)
+  }
+
+  fn matches_filter(&self, filter: &str) -> bool {
+    filter == self.key
+      || filter == self.req.name
+      || filter == self.display_name()
+  }
+
+  /// The config file value with the version requirement swapped out.
+  fn with_version_req(&self, version_req: &str) -> String {
+    if !self.prefixed {
+      return version_req.to_string();
+    }
+    let sub_path = match &self.sub_path {
+      Some(sub_path) => format!("/{sub_path}"),
+      None => String::new(),
+    };
+    format!("{}@{}{}", self.display_name(), version_req, sub_path)
+  }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct OutdatedEntry {
+  package: String,
+  current: Option<String>,
+  wanted: Option<String>,
+  latest: Option<String>,
+  /// The indexes of the dependencies with this requirement, which can be in
+  /// several configuration files of the workspace.
+  #[serde(skip)]
+  dependency_indexes: Vec<usize>,
+}
+
+pub(super) fn collect_dependencies(
+  deno_config: Option<&DenoConfig>,
+  npm_config: Option<&NpmConfig>,
+) -> Vec<Dependency> {
+  let mut deps = Vec::new();
+  if let Some(deno_config) = deno_config {
+    deps.extend(
+      deno_config
+        .imports
+        .iter()
+        .chain(deno_config.dev_imports.iter())
+        .filter_map(|(key, value)| {
+          Dependency::from_deno_json_entry(key, value)
+        }),
+    );
+  }
+  if let Some(npm_config) = npm_config {
+    deps.extend(
+      npm_config
+        .dependencies
+        .iter()
+        .chain(npm_config.dev_dependencies.iter())
+        .filter_map(|(key, value)| {
+          Dependency::from_package_json_entry(key, value)
+        }),
+    );
+  }
+  deps
+}
+
+fn latest_jsr_version(
+  info: &deno_graph::packages::JsrPackageInfo,
+) -> Option<Version> {
+  info
+    .versions
+    .iter()
+    .filter(|(v, i)| !i.yanked && v.pre.is_empty())
+    .map(|(v, _)| v)
+    .max()
+    .cloned()
+}
+
+fn latest_npm_version(
+  info: &deno_npm::registry::NpmPackageInfo,
+) -> Option<Version> {
+  info.dist_tags.get("latest").cloned().or_else(|| {
+    info
+      .versions
+      .keys()
+      .filter(|v| v.pre.is_empty())
+      .max()
+      .cloned()
+  })
+}
+
+pub async fn outdated(
+  flags: Arc<Flags>,
+  outdated_flags: OutdatedFlags,
+) -> Result<(), AnyError> {
+  let cli_factory = CliFactory::from_flags(flags.clone());
+  let cli_options = cli_factory.cli_options()?;
+  let folders = super::workspace_configs(cli_options)?;
+
+  // the folder index and the dependency
+  let deps = folders
+    .iter()
+    .enumerate()
+    .flat_map(|(folder_index, (deno_config, npm_config))| {
+      collect_dependencies(deno_config.as_ref(), npm_config.as_ref())
+        .into_iter()
+        .map(move |dep| (folder_index, dep))
+    })
+    .filter(|(_, dep)| {
+      outdated_flags.filters.is_empty()
+        || outdated_flags
+          .filters
+          .iter()
+          .any(|filter| dep.matches_filter(filter))
+    })
+    .collect::<Vec<_>>();
+  // the same requirement is only resolved and reported once
+  let mut reqs: IndexMap<(PackageKind, &PackageReq), Vec<usize>> =
+    IndexMap::new();
+  for (index, (_, dep)) in deps.iter().enumerate() {
+    reqs.entry((dep.kind, &dep.req)).or_default().push(index);
+  }
+
+  let locked_versions = match cli_options.maybe_lockfile() {
+    Some(lockfile) => {
+      let lockfile = lockfile.lock();
+      reqs
+        .keys()
+        .map(|(kind, req)| {
+          lockfile.content.packages.specifiers.iter().find_map(
+            |(dep_req, version)| {
+              (dep_req.kind == *kind && dep_req.req == **req).then(|| {
+                // strip the peer dependency suffix of npm packages
+                version.split('_').next().unwrap_or(version).to_string()
+              })
+            },
+          )
+        })
+        .collect::<Vec<_>>()
+    }
+    None => vec![None; reqs.len()],
+  };
+
+  let http_client = cli_factory.http_client_provider();
+  let deps_http_cache = cli_factory.global_http_cache()?;
+  let mut deps_file_fetcher = FileFetcher::new(
+    deps_http_cache.clone(),
+    CacheSetting::ReloadAll,
+    true,
+    http_client.clone(),
+    Default::default(),
+    None,
+  );
+  deps_file_fetcher.set_download_log_level(log::Level::Trace);
+  let npmrc = cli_options.npmrc();
+  deps_file_fetcher.add_npmrc_auth_tokens(npmrc);
+  let deps_file_fetcher = Arc::new(deps_file_fetcher);
+  let jsr_resolver = Arc::new(JsrFetchResolver::new(deps_file_fetcher.clone()));
+  let npm_resolver =
+    Arc::new(NpmFetchResolver::new(deps_file_fetcher, npmrc.clone()));
+
+  let resolved = join_all(reqs.keys().map(|(kind, req)| {
+    let jsr_resolver = jsr_resolver.clone();
+    let npm_resolver = npm_resolver.clone();
+    async move {
+      match kind {
+        PackageKind::Jsr => {
+          let wanted = jsr_resolver.req_to_nv(req).await;
+          let latest = jsr_resolver
+            .package_info(&req.name)
+            .await
+            .and_then(|info| latest_jsr_version(&info));
+          (wanted.map(|nv| nv.version), latest)
+        }
+        PackageKind::Npm => {
+          let wanted = npm_resolver.req_to_nv(req).await;
+          let latest = npm_resolver
+            .package_info(&req.name)
+            .await
+            .and_then(|info| latest_npm_version(&info));
+          (wanted.map(|nv| nv.version), latest)
+        }
+      }
+    }
+  }))
+  .await;
+
+  let mut entries = Vec::new();
+  for (((wanted, latest), current), dependency_indexes) in resolved
+    .into_iter()
+    .zip(locked_versions)
+    .zip(reqs.into_values())
+  {
+    // a prerelease requirement can be ahead of the latest stable version
+    let latest = match (&wanted, latest) {
+      (Some(wanted), Some(latest)) if *wanted > latest => Some(wanted.clone()),
+      (_, latest) => latest,
+    };
+    let current_version = current
+      .as_deref()
+      .and_then(|v| Version::parse_standard(v).ok());
+    let is_outdated = match (&current_version, &latest) {
+      (Some(current), Some(latest)) => current < latest,
+      (None, Some(_)) => true,
+      (_, None) => false,
+    };
+    if !is_outdated {
+      continue;
+    }
+    entries.push(OutdatedEntry {
+      package: deps[dependency_indexes[0]].1.display_name(),
+      current,
+      wanted: wanted.map(|v| v.to_string()),
+      latest: latest.map(|v| v.to_string()),
+      dependency_indexes,
+    });
+  }
+
+  if outdated_flags.json {
+    display::write_json_to_stdout(&entries)?;
+  } else if entries.is_empty() {
+    log::info!("All dependencies are up to date.");
+  } else {
+    print_table(&entries);
+  }
+
+  if outdated_flags.update && !entries.is_empty() {
+    let mut updaters = Vec::with_capacity(folders.len());
+    for (deno_config, npm_config) in folders {
+      updaters.push(FolderConfigUpdaters::new(npm_config, deno_config).await?);
+    }
+    for entry in &entries {
+      let Some(latest) = &entry.latest else {
+        continue;
+      };
+      let mut updated = false;
+      for &index in &entry.dependency_indexes {
+        let (folder_index, dep) = &deps[index];
+        let range_symbol =
+          if dep.req.version_req.version_text().starts_with('~') {
+            '~'
+          } else {
+            '^'
+          };
+        let value = dep.with_version_req(&format!("{range_symbol}{latest}"));
+        let folder = &mut updaters[*folder_index];
+        let config = match dep.source {
+          DependencySource::DenoJson => folder.deno.as_mut(),
+          DependencySource::PackageJson => folder.npm.as_mut(),
+        };
+        if let Some(config) = config {
+          updated |= config.update(&dep.key, value);
+        }
+      }
+      if updated {
+        log::info!(
+          "Updated {} to {}",
+          colors::green(&entry.package),
+          colors::gray(latest)
+        );
+      }
+    }
+    for config in updaters.into_iter().flat_map(|f| f.into_configs()) {
+      config.commit().await?;
+    }
+
+    super::npm_install_after_modification(flags, Some(jsr_resolver)).await?;
+  }
+
+  Ok(())
+}
+
+fn print_table(entries: &[OutdatedEntry]) {
+  const HEADERS: [&str; 4] = ["Package", "Current", "Wanted", "Latest"];
+  let rows = entries
+    .iter()
+    .map(|entry| {
+      [
+        entry.package.clone(),
+        entry.current.clone().unwrap_or_else(|| "-".to_string()),
+        entry.wanted.clone().unwrap_or_else(|| "-".to_string()),
+        entry.latest.clone().unwrap_or_else(|| "-".to_string()),
+      ]
+    })
+    .collect::<Vec<_>>();
+  let mut widths = HEADERS.map(|h| h.len());
+  for row in &rows {
+    for (width, cell) in widths.iter_mut().zip(row.iter()) {
+      *width = (*width).max(cell.len());
+    }
+  }
+  let header = HEADERS
+    .iter()
+    .zip(widths)
+    .map(|(h, w)| format!("{h:<w$}"))
+    .collect::<Vec<_>>()
+    .join("  ");
+  log::info!("{}", colors::bold(header.trim_end()));
+  for row in rows {
+    let line = row
+      .iter()
+      .zip(widths)
+      .enumerate()
+      .map(|(i, (cell, w))| {
+        let cell = format!("{cell:<w$}");
+        match i {
+          2 => colors::yellow(cell).to_string(),
+          3 => colors::green(cell).to_string(),
+          _ => cell,
+        }
+      })
+      .collect::<Vec<_>>()
+      .join("  ");
+    log::info!("{}", line.trim_end());
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_dependency_from_entries() {
+    let dep = Dependency::from_deno_json_entry(
+      "@std/path/posix",
+      "jsr:@std/path@~1.0.1/posix",
+    )
+    .unwrap();
+    assert_eq!(dep.kind, PackageKind::Jsr);
+    assert_eq!(dep.req, PackageReq::from_str("@std/path@~1.0.1").unwrap());
+    assert_eq!(dep.sub_path.as_deref(), Some("posix"));
+    assert_eq!(dep.display_name(), "jsr:@std/path");
+    assert!(dep.matches_filter("@std/path"));
+    assert!(dep.matches_filter("jsr:@std/path"));
+    assert!(!dep.matches_filter("npm:@std/path"));
+    assert_eq!(dep.with_version_req("~1.1.0"), "jsr:@std/path@~1.1.0/posix");
+
+    assert_eq!(
+      Dependency::from_deno_json_entry("foo", "https://deno.land/x/foo.ts"),
+      None
+    );
+
+    let dep = Dependency::from_package_json_entry("chalk", "^4.0.0").unwrap();
+    assert_eq!(dep.kind, PackageKind::Npm);
+    assert_eq!(dep.req, PackageReq::from_str("chalk@^4.0.0").unwrap());
+    assert_eq!(dep.with_version_req("^5.3.0"), "^5.3.0");
+
+    let dep =
+      Dependency::from_package_json_entry("kleur", "npm:chalk@^4").unwrap();
+    assert_eq!(dep.req.name, "chalk");
+    assert!(dep.matches_filter("kleur"));
+    assert_eq!(dep.with_version_req("^5.3.0"), "npm:chalk@^5.3.0");
+
+    assert_eq!(
+      Dependency::from_package_json_entry("local", "workspace:*"),
+      None
+    );
+  }
+}
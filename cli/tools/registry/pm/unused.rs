@@ -0,0 +1,294 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use deno_config::glob::FilePatterns;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_graph::GraphKind;
+use deno_graph::Module;
+use deno_semver::npm::NpmPackageReqReference;
+use serde::Serialize;
+
+use super::npm_install_after_modification;
+use super::ConfigUpdater;
+use super::DenoConfig;
+use super::NpmConfig;
+use crate::args::Flags;
+use crate::args::InfoFlags;
+use crate::colors;
+use crate::factory::CliFactory;
+use crate::util::display;
+use crate::util::fs::collect_specifiers;
+use crate::util::path::is_script_ext;
+
+/// An entry of a configuration file which isn't imported by any module of
+/// the workspace.
+#[derive(Debug, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+struct UnusedEntry {
+  /// The file name of the configuration file.
+  config_file: &'static str,
+  /// "imports", "devImports", "dependencies" or "devDependencies".
+  field: &'static str,
+  key: String,
+}
+
+/// Reports the entries of "imports" in the deno.json and of the dependencies
+/// in the package.json which no module of the workspace imports, optionally
+/// removing them with `--fix`.
+pub async fn unused(
+  flags: Arc<Flags>,
+  info_flags: InfoFlags,
+) -> Result<(), AnyError> {
+  let factory = CliFactory::from_flags(flags.clone());
+  let cli_options = factory.cli_options()?;
+  let deno_config = DenoConfig::from_options(cli_options)?;
+  let npm_config = NpmConfig::from_options(cli_options)?;
+
+  let roots = collect_specifiers(
+    FilePatterns {
+      base: cli_options.workspace().root_dir_path(),
+      include: None,
+      exclude: cli_options.workspace().resolve_config_excludes()?,
+    },
+    cli_options.vendor_dir_path().map(ToOwned::to_owned),
+    |e| is_script_ext(e.path),
+  )?;
+  let graph = factory
+    .module_graph_creator()
+    .await?
+    .create_graph(GraphKind::All, roots)
+    .await?;
+  let mut imported = BTreeSet::new();
+  for module in graph.modules() {
+    if let Module::Js(module) = module {
+      if module.specifier.scheme() == "file" {
+        imported.extend(module.dependencies.keys().cloned());
+      }
+    }
+  }
+  let scripts = collect_scripts(deno_config.as_ref(), npm_config.as_ref());
+
+  let mut unused = BTreeSet::new();
+  if let Some(deno_config) = &deno_config {
+    let fields = [
+      ("imports", &deno_config.imports),
+      ("devImports", &deno_config.dev_imports),
+    ];
+    for (field, imports) in fields {
+      for key in imports.keys() {
+        if !imported.iter().any(|s| is_import_map_key_used(key, s)) {
+          unused.insert(UnusedEntry {
+            config_file: deno_config.format.file_name(),
+            field,
+            key: key.clone(),
+          });
+        }
+      }
+    }
+  }
+  if let Some(npm_config) = &npm_config {
+    let fields = [
+      ("dependencies", &npm_config.dependencies),
+      ("devDependencies", &npm_config.dev_dependencies),
+    ];
+    for (field, deps) in fields {
+      for name in deps.keys() {
+        let used = imported.iter().any(|s| is_package_used(name, s))
+          || scripts
+            .iter()
+            .any(|script| is_package_used_in_script(name, script));
+        if !used {
+          unused.insert(UnusedEntry {
+            config_file: "package.json",
+            field,
+            key: name.clone(),
+          });
+        }
+      }
+    }
+  }
+
+  if info_flags.json {
+    display::write_json_to_stdout(&serde_json::json!({ "unused": unused }))?;
+  } else if unused.is_empty() {
+    log::info!("No unused dependencies found.");
+  } else {
+    log::info!(
+      "Found {} unused {}:",
+      unused.len(),
+      if unused.len() == 1 {
+        "dependency"
+      } else {
+        "dependencies"
+      }
+    );
+    for entry in &unused {
+      log::info!(
+        "  {} {}",
+        entry.key,
+        colors::gray(format!("({} {})", entry.config_file, entry.field))
+      );
+    }
+  }
+
+  if !info_flags.fix || unused.is_empty() {
+    return Ok(());
+  }
+  let mut deno_updater = ConfigUpdater::maybe_new(deno_config).await?;
+  let mut npm_updater = ConfigUpdater::maybe_new(npm_config).await?;
+  for entry in &unused {
+    let updater = if entry.config_file == "package.json" {
+      npm_updater.as_mut()
+    } else {
+      deno_updater.as_mut()
+    };
+    if let Some(updater) = updater {
+      updater.remove(&entry.key);
+    }
+  }
+  for updater in deno_updater.into_iter().chain(npm_updater) {
+    updater.commit().await?;
+  }
+  log::info!(
+    "{} {} unused {}",
+    colors::green("Removed"),
+    unused.len(),
+    if unused.len() == 1 {
+      "dependency"
+    } else {
+      "dependencies"
+    }
+  );
+  npm_install_after_modification(flags, None).await
+}
+
+/// The commands of the tasks of the deno.json and the scripts of the
+/// package.json, which can use the binaries of dependencies.
+fn collect_scripts(
+  deno_config: Option<&DenoConfig>,
+  npm_config: Option<&NpmConfig>,
+) -> Vec<String> {
+  let mut scripts = Vec::new();
+  if let Some(tasks) = deno_config
+    .and_then(|config| config.config.json.tasks.as_ref())
+    .and_then(|tasks| tasks.as_object())
+  {
+    for task in tasks.values() {
+      let command = match task {
+        serde_json::Value::String(command) => Some(command.as_str()),
+        serde_json::Value::Object(task) => {
+          task.get("command").and_then(|command| command.as_str())
+        }
+        _ => None,
+      };
+      scripts.extend(command.map(|command| command.to_string()));
+    }
+  }
+  if let Some(pkg_scripts) =
+    npm_config.and_then(|config| config.config.scripts.as_ref())
+  {
+    scripts.extend(pkg_scripts.values().cloned());
+  }
+  scripts
+}
+
+/// Whether an import specifier is mapped by a key of an import map, where a
+/// key for a package like `"@std/path"` also maps its sub paths.
+fn is_import_map_key_used(key: &str, specifier: &str) -> bool {
+  if key.ends_with('/') {
+    specifier.starts_with(key)
+  } else {
+    specifier == key
+      || specifier
+        .strip_prefix(key)
+        .is_some_and(|rest| rest.starts_with('/'))
+  }
+}
+
+/// Whether an import specifier imports the npm package of a package.json
+/// dependency, or the package which an `@types/` dependency has types for.
+fn is_package_used(name: &str, specifier: &str) -> bool {
+  let imported_name = match NpmPackageReqReference::from_str(specifier) {
+    Ok(req_ref) => req_ref.req().name.clone(),
+    Err(_) => {
+      let mut parts = specifier.split('/');
+      match parts.next() {
+        Some(scope) if scope.starts_with('@') => match parts.next() {
+          Some(name) => format!("{scope}/{name}"),
+          None => return false,
+        },
+        Some(name) => name.to_string(),
+        None => return false,
+      }
+    }
+  };
+  if imported_name == name {
+    return true;
+  }
+  // `@types/node` for `node:` imports, `@types/scope__name` for `@scope/name`
+  match name.strip_prefix("@types/") {
+    Some("node") => specifier.starts_with("node:"),
+    Some(types_name) => {
+      imported_name == types_name
+        || imported_name
+          .strip_prefix('@')
+          .is_some_and(|scoped| scoped.replacen('/', "__", 1) == types_name)
+    }
+    None => false,
+  }
+}
+
+/// Whether a script might run a binary of the package, which is assumed when
+/// it mentions the name of the package.
+fn is_package_used_in_script(name: &str, script: &str) -> bool {
+  let bin_name = name.rsplit('/').next().unwrap_or(name);
+  script
+    .split(|c: char| c.is_whitespace() || matches!(c, ';' | '&' | '|' | '('))
+    .any(|word| {
+      word == bin_name || word == name || word.ends_with(&format!("npm:{name}"))
+    })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_is_import_map_key_used() {
+    assert!(is_import_map_key_used("@std/path", "@std/path"));
+    assert!(is_import_map_key_used("@std/path", "@std/path/join"));
+    assert!(!is_import_map_key_used("@std/path", "@std/path-extra"));
+    assert!(is_import_map_key_used("utils/", "utils/strings.ts"));
+    assert!(!is_import_map_key_used("utils/", "utils"));
+  }
+
+  #[test]
+  fn test_is_package_used() {
+    assert!(is_package_used("chalk", "chalk"));
+    assert!(is_package_used("chalk", "npm:chalk@5"));
+    assert!(is_package_used("preact", "preact/hooks"));
+    assert!(is_package_used("@scope/pkg", "@scope/pkg/sub"));
+    assert!(!is_package_used("@scope/pkg", "@scope/other"));
+    assert!(is_package_used("@types/express", "express"));
+    assert!(is_package_used("@types/scope__pkg", "@scope/pkg"));
+    assert!(is_package_used("@types/node", "node:fs"));
+    assert!(!is_package_used("chalk", "./chalk.ts"));
+  }
+
+  #[test]
+  fn test_is_package_used_in_script() {
+    assert!(is_package_used_in_script("vite", "vite build"));
+    assert!(is_package_used_in_script(
+      "@acme/cli",
+      "deno fmt && cli run"
+    ));
+    assert!(is_package_used_in_script(
+      "eslint",
+      "deno run -A npm:eslint ."
+    ));
+    assert!(!is_package_used_in_script("vite", "deno run main.ts"));
+  }
+}
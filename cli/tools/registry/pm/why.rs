@@ -0,0 +1,350 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_lockfile::Lockfile;
+use deno_semver::package::PackageKind;
+use deno_semver::package::PackageReq;
+use deno_semver::Version;
+
+use super::outdated::collect_dependencies;
+use super::outdated::DependencySource;
+use crate::args::Flags;
+use crate::args::WhyFlags;
+use crate::colors;
+use crate::factory::CliFactory;
+
+/// Stop listing chains after this many to keep the output readable for
+/// packages that are depended on from all over the graph.
+const MAX_CHAINS: usize = 50;
+
+/// A resolved package in the lockfile.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct PackageNode {
+  kind: PackageKind,
+  /// The key of the package in the lockfile, which for npm packages may
+  /// include a peer dependency suffix.
+  id: String,
+}
+
+/// Splits the key of a package in the lockfile into its name and version,
+/// dropping the peer dependency suffix of npm packages.
+pub(super) fn split_package_id(id: &str) -> Option<(&str, &str)> {
+  // skip the first character to not split on the scope of a package
+  let index = id.get(1..)?.find('@')? + 1;
+  let version = &id[index + 1..];
+  let version = version.split('_').next().unwrap_or(version);
+  Some((&id[..index], version))
+}
+
+impl PackageNode {
+  fn name_and_version(&self) -> Option<(&str, &str)> {
+    split_package_id(&self.id)
+  }
+
+  fn display(&self) -> String {
+    let prefix = match self.kind {
+      PackageKind::Jsr => "jsr",
+      PackageKind::Npm => "npm",
+    };
+    match self.name_and_version() {
+      Some((name, version)) => format!("{prefix}:{name}@{version}"),
+      None => format!("{prefix}:{}", self.id),
+    }
+  }
+
+  fn matches(&self, kind: Option<PackageKind>, req: &PackageReq) -> bool {
+    if kind.is_some_and(|kind| kind != self.kind) {
+      return false;
+    }
+    let Some((name, version)) = self.name_and_version() else {
+      return false;
+    };
+    name == req.name
+      && Version::parse_standard(version)
+        .map(|version| req.version_req.matches(&version))
+        .unwrap_or(false)
+  }
+}
+
+/// Parses `jsr:@std/fmt`, `npm:chalk@^4` or `chalk` into the kind of the
+/// package, if specified, and a requirement for it.
+fn parse_package_arg(
+  text: &str,
+) -> Result<(Option<PackageKind>, PackageReq), AnyError> {
+  let (kind, text) = if let Some(text) = text.strip_prefix("jsr:") {
+    (Some(PackageKind::Jsr), text)
+  } else if let Some(text) = text.strip_prefix("npm:") {
+    (Some(PackageKind::Npm), text)
+  } else {
+    (None, text)
+  };
+  let req = PackageReq::from_str(text)
+    .with_context(|| format!("Invalid package: {text}"))?;
+  Ok((kind, req))
+}
+
+/// The packages each package in the lockfile depends on.
+fn dependency_graph(
+  lockfile: &Lockfile,
+) -> HashMap<PackageNode, Vec<PackageNode>> {
+  let content = &lockfile.content;
+  let mut graph = HashMap::new();
+  for (nv, info) in &content.packages.jsr {
+    let deps = info
+      .dependencies
+      .iter()
+      .filter_map(|dep| {
+        let version = content.packages.specifiers.get(dep)?;
+        Some(PackageNode {
+          kind: dep.kind,
+          id: format!("{}@{}", dep.req.name, version),
+        })
+      })
+      .collect();
+    graph.insert(
+      PackageNode {
+        kind: PackageKind::Jsr,
+        id: nv.to_string(),
+      },
+      deps,
+    );
+  }
+  for (id, info) in &content.packages.npm {
+    let deps = info
+      .dependencies
+      .values()
+      .map(|dep_id| PackageNode {
+        kind: PackageKind::Npm,
+        id: dep_id.clone(),
+      })
+      .collect();
+    graph.insert(
+      PackageNode {
+        kind: PackageKind::Npm,
+        id: id.clone(),
+      },
+      deps,
+    );
+  }
+  graph
+}
+
+/// The packages from which one of the targets can be reached, including the
+/// targets themselves.
+fn nodes_reaching_targets(
+  graph: &HashMap<PackageNode, Vec<PackageNode>>,
+  targets: &HashSet<PackageNode>,
+) -> HashSet<PackageNode> {
+  let mut dependents: HashMap<&PackageNode, Vec<&PackageNode>> = HashMap::new();
+  for (node, deps) in graph {
+    for dep in deps {
+      dependents.entry(dep).or_default().push(node);
+    }
+  }
+  let mut reaching = targets.clone();
+  let mut pending = targets.iter().collect::<Vec<_>>();
+  while let Some(node) = pending.pop() {
+    for dependent in dependents.get(node).into_iter().flatten() {
+      if reaching.insert((*dependent).clone()) {
+        pending.push(dependent);
+      }
+    }
+  }
+  reaching
+}
+
+/// Collects the chains of packages leading from `node` to one of the targets.
+fn collect_chains(
+  graph: &HashMap<PackageNode, Vec<PackageNode>>,
+  reaching: &HashSet<PackageNode>,
+  targets: &HashSet<PackageNode>,
+  node: &PackageNode,
+  path: &mut Vec<PackageNode>,
+  chains: &mut Vec<Vec<PackageNode>>,
+) {
+  if chains.len() >= MAX_CHAINS || path.contains(node) {
+    return;
+  }
+  path.push(node.clone());
+  if targets.contains(node) {
+    chains.push(path.clone());
+  } else {
+    for dep in graph.get(node).into_iter().flatten() {
+      if reaching.contains(dep) {
+        collect_chains(graph, reaching, targets, dep, path, chains);
+      }
+    }
+  }
+  path.pop();
+}
+
+pub async fn why(
+  flags: Arc<Flags>,
+  why_flags: WhyFlags,
+) -> Result<(), AnyError> {
+  let (kind, req) = parse_package_arg(&why_flags.package)?;
+  let cli_factory = CliFactory::from_flags(flags);
+  let cli_options = cli_factory.cli_options()?;
+  let Some(lockfile) = cli_options.maybe_lockfile() else {
+    bail!("`deno why` requires a lockfile. Run `deno install` to create one.");
+  };
+  // the dependencies of the configuration files of the root and the members,
+  // along with the directory of the file relative to the workspace root
+  let workspace = cli_options.workspace();
+  let root_dir = workspace.root_dir();
+  let root_deps = super::workspace_configs(cli_options)?
+    .into_iter()
+    .zip(
+      workspace
+        .config_folders()
+        .iter()
+        .map(|(folder_url, _)| folder_url),
+    )
+    .flat_map(|((deno_config, npm_config), folder_url)| {
+      let dir = root_dir.make_relative(folder_url).unwrap_or_default();
+      collect_dependencies(deno_config.as_ref(), npm_config.as_ref())
+        .into_iter()
+        .map(move |dep| (dir.clone(), dep))
+    })
+    .collect::<Vec<_>>();
+
+  let lockfile = lockfile.lock();
+  let graph = dependency_graph(&lockfile);
+  let targets = graph
+    .keys()
+    .filter(|node| node.matches(kind, &req))
+    .cloned()
+    .collect::<HashSet<_>>();
+  if targets.is_empty() {
+    bail!(
+      "{} is not in the dependency graph.",
+      colors::bold(&why_flags.package)
+    );
+  }
+  let mut sorted_targets = targets.iter().collect::<Vec<_>>();
+  sorted_targets.sort_by_key(|node| node.display());
+  for target in sorted_targets {
+    log::info!("{}", colors::bold(target.display()));
+    let target = HashSet::from([target.clone()]);
+    let reaching = nodes_reaching_targets(&graph, &target);
+    let mut chains = Vec::new();
+    for (dir, dep) in &root_deps {
+      let Some(version) = lockfile.content.packages.specifiers.iter().find_map(
+        |(dep_req, version)| {
+          (dep_req.kind == dep.kind && dep_req.req == dep.req)
+            .then_some(version)
+        },
+      ) else {
+        continue;
+      };
+      let root = PackageNode {
+        kind: dep.kind,
+        id: format!("{}@{}", dep.req.name, version),
+      };
+      if !reaching.contains(&root) {
+        continue;
+      }
+      let start = chains.len();
+      collect_chains(
+        &graph,
+        &reaching,
+        &target,
+        &root,
+        &mut Vec::new(),
+        &mut chains,
+      );
+      let file_name = match dep.source {
+        DependencySource::DenoJson => format!("{dir}deno.json"),
+        DependencySource::PackageJson => format!("{dir}package.json"),
+      };
+      for chain in &chains[start..] {
+        let chain = chain
+          .iter()
+          .map(|node| node.display())
+          .collect::<Vec<_>>()
+          .join(" > ");
+        log::info!(
+          "  {} {} {}",
+          colors::gray(format!("{} ({})", dep.key, file_name)),
+          colors::gray(">"),
+          chain
+        );
+      }
+    }
+    if chains.is_empty() {
+      log::info!(
+        "  {}",
+        colors::gray(
+          "Not reachable from the configuration files, run `deno install` to prune the lockfile."
+        )
+      );
+    } else if chains.len() >= MAX_CHAINS {
+      log::info!("  {}", colors::gray("..."));
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn node(kind: PackageKind, id: &str) -> PackageNode {
+    PackageNode {
+      kind,
+      id: id.to_string(),
+    }
+  }
+
+  #[test]
+  fn test_package_node_matches() {
+    let (kind, req) = parse_package_arg("npm:@types/node@^20").unwrap();
+    assert!(node(PackageKind::Npm, "@types/node@20.1.0").matches(kind, &req));
+    assert!(!node(PackageKind::Npm, "@types/node@18.0.0").matches(kind, &req));
+    assert!(!node(PackageKind::Jsr, "@types/node@20.1.0").matches(kind, &req));
+
+    let (kind, req) = parse_package_arg("preact").unwrap();
+    assert_eq!(kind, None);
+    let preact = node(PackageKind::Npm, "preact@10.0.0_react@18.2.0");
+    assert!(preact.matches(kind, &req));
+    assert_eq!(preact.display(), "npm:preact@10.0.0");
+  }
+
+  #[test]
+  fn test_collect_chains() {
+    let a = node(PackageKind::Jsr, "@scope/a@1.0.0");
+    let b = node(PackageKind::Jsr, "@scope/b@1.0.0");
+    let c = node(PackageKind::Npm, "c@1.0.0");
+    let d = node(PackageKind::Npm, "d@1.0.0");
+    let graph = HashMap::from([
+      (a.clone(), vec![b.clone(), c.clone()]),
+      (b.clone(), vec![c.clone(), a.clone()]),
+      (c.clone(), vec![]),
+      (d.clone(), vec![]),
+    ]);
+    let targets = HashSet::from([c.clone()]);
+    let reaching = nodes_reaching_targets(&graph, &targets);
+    assert!(!reaching.contains(&d));
+
+    let mut chains = Vec::new();
+    collect_chains(
+      &graph,
+      &reaching,
+      &targets,
+      &a,
+      &mut Vec::new(),
+      &mut chains,
+    );
+    assert_eq!(
+      chains,
+      vec![vec![a.clone(), b.clone(), c.clone()], vec![a, c]]
+    );
+  }
+}
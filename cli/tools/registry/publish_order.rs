@@ -49,6 +49,38 @@ impl PublishOrderGraph {
     }
   }
 
+  /// The packages and the packages which depend on them, directly or through
+  /// other packages.
+  pub fn with_dependents(
+    &self,
+    names: impl IntoIterator<Item = String>,
+  ) -> HashSet<String> {
+    let mut result = HashSet::new();
+    let mut pending = names.into_iter().collect::<VecDeque<_>>();
+    while let Some(name) = pending.pop_front() {
+      if let Some(dependents) = self.reverse_map.get(&name) {
+        pending.extend(dependents.iter().cloned());
+      }
+      result.insert(name);
+    }
+    result
+  }
+
+  /// Only keeps the packages with the names. The dependencies on the other
+  /// packages are considered published.
+  pub fn retain(self, names: &HashSet<String>) -> Self {
+    let packages = self
+      .packages
+      .into_iter()
+      .filter(|(name, _)| names.contains(name))
+      .map(|(name, deps)| {
+        let deps = deps.into_iter().filter(|dep| names.contains(dep));
+        (name, deps.collect())
+      })
+      .collect();
+    build_publish_order_graph_from_pkgs_deps(packages)
+  }
+
   /// There could be pending packages if there's a circular dependency.
   pub fn ensure_no_pending(&self) -> Result<(), AnyError> {
     // this is inefficient, but that's ok because it's simple and will
@@ -280,6 +312,26 @@ mod test {
     graph.ensure_no_pending().unwrap();
   }
 
+  #[test]
+  fn test_graph_retain_with_dependents() {
+    let graph = build_publish_order_graph_from_pkgs_deps(HashMap::from([
+      ("a".to_string(), HashSet::from(["b".to_string()])),
+      ("b".to_string(), HashSet::from(["c".to_string()])),
+      ("c".to_string(), HashSet::new()),
+      ("d".to_string(), HashSet::from(["c".to_string()])),
+      ("e".to_string(), HashSet::new()),
+    ]));
+    let names = graph.with_dependents(["b".to_string()]);
+    assert_eq!(names, HashSet::from(["a".to_string(), "b".to_string()]));
+    let mut graph = graph.retain(&names);
+    assert_eq!(graph.next(), vec!["b".to_string()]);
+    graph.finish_package("b");
+    assert_eq!(graph.next(), vec!["a".to_string()]);
+    graph.finish_package("a");
+    assert!(graph.next().is_empty());
+    graph.ensure_no_pending().unwrap();
+  }
+
   #[test]
   fn test_graph_circular_dep() {
     let mut graph = build_publish_order_graph_from_pkgs_deps(HashMap::from([
@@ -0,0 +1,241 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Sets the versions of the packages in their config files before they are
+//! published with `deno publish --set-version`.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use deno_config::workspace::JsrPackageConfig;
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_semver::Version;
+use deno_terminal::colors;
+use jsonc_parser::ast::Value;
+
+use super::run_git;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VersionBump {
+  Patch,
+  Minor,
+  Major,
+  Exact(Version),
+}
+
+impl VersionBump {
+  pub fn parse(text: &str) -> Result<Self, AnyError> {
+    match text {
+      "patch" => Ok(Self::Patch),
+      "minor" => Ok(Self::Minor),
+      "major" => Ok(Self::Major),
+      _ => Version::parse_standard(text)
+        .map(Self::Exact)
+        .map_err(|_| anyhow!("Invalid version '{}'", text)),
+    }
+  }
+
+  /// The version after the bump. The pre-release of a version is released
+  /// by bumping the part which was incremented for it, so `1.1.0-rc.1`
+  /// becomes `1.1.0` with `minor`.
+  pub fn apply(&self, version: &Version) -> Version {
+    let is_pre = !version.pre.is_empty();
+    let (major, minor, patch) = match self {
+      Self::Exact(version) => return version.clone(),
+      Self::Patch if is_pre => (version.major, version.minor, version.patch),
+      Self::Patch => (version.major, version.minor, version.patch + 1),
+      Self::Minor if is_pre && version.patch == 0 => {
+        (version.major, version.minor, 0)
+      }
+      Self::Minor => (version.major, version.minor + 1, 0),
+      Self::Major if is_pre && version.minor == 0 && version.patch == 0 => {
+        (version.major, 0, 0)
+      }
+      Self::Major => (version.major + 1, 0, 0),
+    };
+    Version {
+      major,
+      minor,
+      patch,
+      pre: Default::default(),
+      build: Default::default(),
+    }
+  }
+}
+
+pub struct PackageVersionChange {
+  pub name: String,
+  pub version: Version,
+  pub config_path: PathBuf,
+}
+
+impl PackageVersionChange {
+  /// The name of the git tag of the release, where the tags of the packages
+  /// of a workspace are prefixed with their name.
+  fn tag_name(&self, is_workspace: bool) -> String {
+    if is_workspace {
+      format!("{}@{}", self.name, self.version)
+    } else {
+      format!("v{}", self.version)
+    }
+  }
+}
+
+/// Updates the version of each package in its config file. The files are
+/// left as is on a dry run.
+pub fn set_package_versions(
+  packages: &[JsrPackageConfig],
+  bump: &VersionBump,
+  dry_run: bool,
+) -> Result<Vec<PackageVersionChange>, AnyError> {
+  let mut changes = Vec::with_capacity(packages.len());
+  for package in packages {
+    let config_file = &package.config_file;
+    let Some(current) = &config_file.json.version else {
+      bail!(
+        "Missing 'version' field in '{}' to update.",
+        config_file.specifier
+      );
+    };
+    let current = Version::parse_standard(current).with_context(|| {
+      format!(
+        "Invalid version '{}' in '{}'.",
+        current, config_file.specifier
+      )
+    })?;
+    let version = bump.apply(&current);
+    let config_path = config_file.specifier.to_file_path().map_err(|_| {
+      anyhow!(
+        "Can't update the remote config file {}",
+        config_file.specifier
+      )
+    })?;
+    if !dry_run {
+      let text = std::fs::read_to_string(&config_path).with_context(|| {
+        format!("Failed reading '{}'", config_path.display())
+      })?;
+      let text = set_config_version(&text, &version).with_context(|| {
+        format!("Failed updating '{}'", config_path.display())
+      })?;
+      std::fs::write(&config_path, text).with_context(|| {
+        format!("Failed writing '{}'", config_path.display())
+      })?;
+    }
+    log::info!(
+      "{} {} from {} to {}",
+      colors::green(if dry_run {
+        "Simulating version update"
+      } else {
+        "Updated version"
+      }),
+      package.name,
+      current,
+      colors::bold(version.to_string()),
+    );
+    changes.push(PackageVersionChange {
+      name: package.name.clone(),
+      version,
+      config_path,
+    });
+  }
+  Ok(changes)
+}
+
+/// Replaces the value of the top level `"version"` field, keeping the rest
+/// of the text including the comments as is.
+fn set_config_version(
+  text: &str,
+  version: &Version,
+) -> Result<String, AnyError> {
+  let ast =
+    jsonc_parser::parse_to_ast(text, &Default::default(), &Default::default())?;
+  let Some(Value::Object(obj)) = ast.value else {
+    bail!("Expected an object");
+  };
+  let Some(prop) = obj.get("version") else {
+    bail!("Missing 'version' field");
+  };
+  let Value::StringLit(value) = &prop.value else {
+    bail!("Expected the 'version' field to be a string");
+  };
+  let new_value = serde_json::to_string(&version.to_string())?;
+  Ok(format!(
+    "{}{}{}",
+    &text[..value.range.start],
+    new_value,
+    &text[value.range.end..]
+  ))
+}
+
+/// Commits the updated config files and tags the commit with the versions
+/// of the packages.
+pub async fn commit_and_tag(
+  cwd: &Path,
+  changes: &[PackageVersionChange],
+) -> Result<(), AnyError> {
+  let is_workspace = changes.len() > 1;
+  let tags = changes
+    .iter()
+    .map(|change| change.tag_name(is_workspace))
+    .collect::<Vec<_>>();
+  let message = format!("Release {}", tags.join(", "));
+  let config_paths = changes
+    .iter()
+    .map(|change| change.config_path.to_string_lossy())
+    .collect::<Vec<_>>();
+  let mut commit_args = vec!["commit", "--message", &message, "--"];
+  commit_args.extend(config_paths.iter().map(|path| path.as_ref()));
+  run_git(cwd, &commit_args).await?;
+  for tag in &tags {
+    run_git(cwd, &["tag", tag]).await?;
+    log::info!("{} {}", colors::green("Created git tag"), tag);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn version(text: &str) -> Version {
+    Version::parse_standard(text).unwrap()
+  }
+
+  #[test]
+  fn test_version_bump_apply() {
+    let cases = [
+      ("1.2.3", "patch", "1.2.4"),
+      ("1.2.3", "minor", "1.3.0"),
+      ("1.2.3", "major", "2.0.0"),
+      ("1.2.3-rc.1", "patch", "1.2.3"),
+      ("1.3.0-rc.1", "minor", "1.3.0"),
+      ("1.3.1-rc.1", "minor", "1.4.0"),
+      ("2.0.0-rc.1", "major", "2.0.0"),
+      ("1.2.3", "0.1.0", "0.1.0"),
+    ];
+    for (current, bump, expected) in cases {
+      let bump = VersionBump::parse(bump).unwrap();
+      assert_eq!(bump.apply(&version(current)), version(expected));
+    }
+    assert!(VersionBump::parse("next").is_err());
+  }
+
+  #[test]
+  fn test_set_config_version() {
+    let text = r#"{
+  // the package
+  "name": "@scope/pkg",
+  "version": "1.0.0",
+  "exports": "./mod.ts"
+}
+"#;
+    assert_eq!(
+      set_config_version(text, &version("1.1.0")).unwrap(),
+      text.replace("1.0.0", "1.1.0")
+    );
+    assert!(set_config_version("{}", &version("1.1.0")).is_err());
+  }
+}
@@ -0,0 +1,222 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use deno_ast::swc::ast as swc_ast;
+use deno_ast::swc::visit::noop_visit_type;
+use deno_ast::swc::visit::Visit;
+use deno_ast::swc::visit::VisitWith;
+use deno_ast::ModuleSpecifier;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static UNDEFINED_IDENTIFIER_RE: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r"ReferenceError: ([A-Za-z_$][A-Za-z0-9_$]*) is not defined")
+    .unwrap()
+});
+
+/// Returns the identifier of a `ReferenceError` caused by referencing a
+/// variable which is not defined.
+pub fn undefined_identifier(error_text: &str) -> Option<&str> {
+  UNDEFINED_IDENTIFIER_RE
+    .captures(error_text)
+    .and_then(|c| c.get(1))
+    .map(|m| m.as_str())
+}
+
+/// Guesses the npm package an identifier refers to, converting camel case
+/// identifiers to kebab case package names (ex. `fooBar` -> `npm:foo-bar`).
+pub fn suggest_specifier(identifier: &str) -> Option<String> {
+  let mut name = String::with_capacity(identifier.len());
+  for (i, c) in identifier.trim_matches(['_', '$']).chars().enumerate() {
+    if c.is_ascii_uppercase() {
+      if i > 0 {
+        name.push('-');
+      }
+      name.push(c.to_ascii_lowercase());
+    } else if c == '_' || c == '$' {
+      name.push('-');
+    } else {
+      name.push(c);
+    }
+  }
+  if name.is_empty() {
+    return None;
+  }
+  Some(format!("npm:{name}"))
+}
+
+/// Parses the answer to the auto-import prompt, which is either a
+/// confirmation of the suggested specifier or a different npm or JSR
+/// specifier to import.
+pub fn parse_answer(answer: &str, suggested: &str) -> Option<String> {
+  let answer = answer.trim();
+  match answer {
+    "y" | "Y" | "yes" => Some(suggested.to_string()),
+    _ if answer.starts_with("npm:") || answer.starts_with("jsr:") => {
+      Some(answer.to_string())
+    }
+    _ => None,
+  }
+}
+
+/// Code which imports the module into the provided identifier, preferring
+/// the default export of the module when there is one.
+pub fn import_code(identifier: &str, specifier: &str) -> String {
+  format!(
+    "const {identifier} = await import({}).then((m) => m.default ?? m);",
+    deno_core::serde_json::to_string(specifier).unwrap()
+  )
+}
+
+/// Whether the code can be evaluated again once the import is defined. The
+/// parts of the code evaluated before the `ReferenceError` would otherwise
+/// run twice, so this is only the case for a single expression without
+/// calls, assignments or other side effects.
+pub fn can_evaluate_again(code: &str) -> bool {
+  let Ok(parsed) = deno_ast::parse_module(deno_ast::ParseParams {
+    specifier: ModuleSpecifier::parse("file:///repl.ts").unwrap(),
+    text: code.into(),
+    media_type: deno_ast::MediaType::TypeScript,
+    capture_tokens: false,
+    maybe_syntax: None,
+    scope_analysis: false,
+  }) else {
+    return false;
+  };
+  let Some(module) = parsed.program_ref().as_module() else {
+    return false;
+  };
+  let [swc_ast::ModuleItem::Stmt(swc_ast::Stmt::Expr(stmt))] =
+    module.body.as_slice()
+  else {
+    return false;
+  };
+  let mut visitor = SideEffectVisitor::default();
+  stmt.expr.visit_with(&mut visitor);
+  !visitor.has_side_effects
+}
+
+#[derive(Default)]
+struct SideEffectVisitor {
+  has_side_effects: bool,
+}
+
+impl Visit for SideEffectVisitor {
+  noop_visit_type!();
+
+  fn visit_call_expr(&mut self, _: &swc_ast::CallExpr) {
+    self.has_side_effects = true;
+  }
+
+  fn visit_opt_call(&mut self, _: &swc_ast::OptCall) {
+    self.has_side_effects = true;
+  }
+
+  fn visit_new_expr(&mut self, _: &swc_ast::NewExpr) {
+    self.has_side_effects = true;
+  }
+
+  fn visit_tagged_tpl(&mut self, _: &swc_ast::TaggedTpl) {
+    self.has_side_effects = true;
+  }
+
+  fn visit_assign_expr(&mut self, _: &swc_ast::AssignExpr) {
+    self.has_side_effects = true;
+  }
+
+  fn visit_update_expr(&mut self, _: &swc_ast::UpdateExpr) {
+    self.has_side_effects = true;
+  }
+
+  fn visit_await_expr(&mut self, _: &swc_ast::AwaitExpr) {
+    self.has_side_effects = true;
+  }
+
+  fn visit_yield_expr(&mut self, _: &swc_ast::YieldExpr) {
+    self.has_side_effects = true;
+  }
+
+  // computed keys and static blocks run when the class is defined
+  fn visit_class(&mut self, _: &swc_ast::Class) {
+    self.has_side_effects = true;
+  }
+
+  fn visit_unary_expr(&mut self, unary_expr: &swc_ast::UnaryExpr) {
+    if unary_expr.op == swc_ast::UnaryOp::Delete {
+      self.has_side_effects = true;
+    } else {
+      unary_expr.visit_children_with(self);
+    }
+  }
+
+  // the bodies of functions don't run when they are defined
+  fn visit_function(&mut self, _: &swc_ast::Function) {}
+
+  fn visit_arrow_expr(&mut self, _: &swc_ast::ArrowExpr) {}
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_undefined_identifier() {
+    assert_eq!(
+      undefined_identifier(
+        "Uncaught ReferenceError: lodash is not defined\n    at <anonymous>:1:1"
+      ),
+      Some("lodash")
+    );
+    assert_eq!(
+      undefined_identifier("Uncaught TypeError: lodash is not a function"),
+      None
+    );
+  }
+
+  #[test]
+  fn test_suggest_specifier() {
+    assert_eq!(suggest_specifier("lodash").unwrap(), "npm:lodash");
+    assert_eq!(suggest_specifier("dateFns").unwrap(), "npm:date-fns");
+    assert_eq!(suggest_specifier("left_pad").unwrap(), "npm:left-pad");
+    assert_eq!(suggest_specifier("$"), None);
+  }
+
+  #[test]
+  fn test_parse_answer() {
+    assert_eq!(
+      parse_answer("y\n", "npm:lodash"),
+      Some("npm:lodash".to_string())
+    );
+    assert_eq!(
+      parse_answer("jsr:@std/path", "npm:path"),
+      Some("jsr:@std/path".to_string())
+    );
+    assert_eq!(parse_answer("", "npm:lodash"), None);
+    assert_eq!(parse_answer("n", "npm:lodash"), None);
+  }
+
+  #[test]
+  fn test_import_code() {
+    assert_eq!(
+      import_code("lodash", "npm:lodash"),
+      r#"const lodash = await import("npm:lodash").then((m) => m.default ?? m);"#
+    );
+  }
+
+  #[test]
+  fn test_can_evaluate_again() {
+    assert!(can_evaluate_again("lodash"));
+    assert!(can_evaluate_again("lodash.VERSION + 1"));
+    assert!(can_evaluate_again("[lodash, { a: () => lodash.x() }]"));
+    assert!(can_evaluate_again("typeof lodash"));
+    assert!(!can_evaluate_again("lodash.chunk([1, 2], 1)"));
+    assert!(!can_evaluate_again("counter++; lodash"));
+    assert!(!can_evaluate_again("counter++, lodash"));
+    assert!(!can_evaluate_again("x = lodash"));
+    assert!(!can_evaluate_again("const x = lodash;"));
+    assert!(!can_evaluate_again("delete lodash.x"));
+    assert!(!can_evaluate_again("await lodash"));
+    assert!(!can_evaluate_again("new lodash()"));
+    assert!(!can_evaluate_again("lodash`x`"));
+    assert!(!can_evaluate_again("lodash."));
+  }
+}
@@ -1,7 +1,10 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::collections::HashMap;
 use std::io;
 use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
 
 use std::sync::Arc;
 
@@ -12,6 +15,8 @@ use crate::cdp;
 use crate::colors;
 use crate::factory::CliFactory;
 use crate::file_fetcher::FileFetcher;
+use deno_core::anyhow::anyhow;
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
 use deno_core::futures::StreamExt;
 use deno_core::serde_json;
@@ -19,8 +24,10 @@ use deno_core::unsync::spawn_blocking;
 use deno_runtime::WorkerExecutionMode;
 use rustyline::error::ReadlineError;
 
+mod auto_import;
 mod channel;
 mod editor;
+mod server;
 mod session;
 
 use channel::rustyline_channel;
@@ -36,14 +43,160 @@ pub use session::REPL_INTERNALS_NAME;
 
 use super::test::create_single_test_event_channel;
 
+/// Meta-commands handled by the REPL itself instead of being evaluated.
+#[derive(Debug, PartialEq, Eq)]
+enum ReplCommand<'a> {
+  /// `.save <file>` writes the code evaluated so far to a file.
+  Save(&'a str),
+  /// `.load <file>` evaluates the code of a file in the current session.
+  Load(&'a str),
+}
+
+impl<'a> ReplCommand<'a> {
+  /// Parses a command, whose file is empty when it's missing.
+  fn parse(line: &'a str) -> Option<Self> {
+    let line = line.trim();
+    let (command, arg) =
+      line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let arg = arg.trim();
+    match command {
+      ".save" => Some(Self::Save(arg)),
+      ".load" => Some(Self::Load(arg)),
+      _ => None,
+    }
+  }
+}
+
 struct Repl {
   session: ReplSession,
   editor: ReplEditor,
   message_handler: RustylineSyncMessageHandler,
+  cwd: PathBuf,
+  /// The code which was successfully evaluated during the session, in order.
+  session_log: Vec<String>,
+  auto_import: bool,
+  /// The specifier that was imported for an undefined identifier, or `None`
+  /// when the user declined to import it.
+  auto_imports: HashMap<String, Option<String>>,
 }
 
 #[allow(clippy::print_stdout)]
 impl Repl {
+  async fn evaluate_and_log(&mut self, code: &str) -> EvaluationOutput {
+    let output = self.session.evaluate_line_and_get_output(code).await;
+    if let EvaluationOutput::Value(_) = output {
+      self.session_log.push(code.to_string());
+    }
+    output
+  }
+
+  fn save_session(&self, path: &Path) -> Result<(), AnyError> {
+    let mut text = self.session_log.join("\n");
+    text.push('\n');
+    std::fs::write(path, text)
+      .with_context(|| format!("Failed writing '{}'", path.display()))
+  }
+
+  /// Replays the code of a file in the session, which restores the state of
+  /// a saved session including the results of top-level awaits.
+  async fn load_session(&mut self, path: &Path) -> Result<(), AnyError> {
+    let code = std::fs::read_to_string(path)
+      .with_context(|| format!("Failed reading '{}'", path.display()))?;
+    if let EvaluationOutput::Error(error_text) =
+      self.evaluate_and_log(&code).await
+    {
+      println!("{}", error_text);
+    }
+    Ok(())
+  }
+
+  /// Offers to import a package for an identifier which isn't defined,
+  /// returning `true` if it was imported and the code should be evaluated
+  /// again, which is only the case for code without side effects. The
+  /// decision is remembered for the rest of the session.
+  async fn maybe_auto_import(
+    &mut self,
+    line: &str,
+    output: &EvaluationOutput,
+  ) -> bool {
+    let EvaluationOutput::Error(error_text) = output else {
+      return false;
+    };
+    let Some(identifier) = auto_import::undefined_identifier(error_text) else {
+      return false;
+    };
+    if self.auto_imports.contains_key(identifier) {
+      return false;
+    }
+    let Some(suggested) = auto_import::suggest_specifier(identifier) else {
+      return false;
+    };
+    print!(
+      "{} is not defined. Import it from {}? [y/N or specifier] ",
+      colors::bold(identifier),
+      colors::green(&suggested)
+    );
+    let _ = io::stdout().flush();
+    let answer = spawn_blocking(|| {
+      let mut answer = String::new();
+      io::stdin().read_line(&mut answer).map(|_| answer)
+    })
+    .await;
+    let specifier = match answer {
+      Ok(Ok(answer)) => auto_import::parse_answer(&answer, &suggested),
+      _ => None,
+    };
+    self
+      .auto_imports
+      .insert(identifier.to_string(), specifier.clone());
+    let Some(specifier) = specifier else {
+      return false;
+    };
+    let code = auto_import::import_code(identifier, &specifier);
+    match self.evaluate_and_log(&code).await {
+      EvaluationOutput::Value(_) if auto_import::can_evaluate_again(line) => {
+        true
+      }
+      EvaluationOutput::Value(_) => {
+        println!(
+          "{}",
+          colors::gray(format!(
+            "Imported {identifier} from {specifier}. The line may have side effects, so it is not run again."
+          ))
+        );
+        false
+      }
+      EvaluationOutput::Error(error_text) => {
+        println!("{}", error_text);
+        false
+      }
+    }
+  }
+
+  async fn run_command(&mut self, command: ReplCommand<'_>) {
+    let result = match command {
+      ReplCommand::Save("") => Err(anyhow!("Usage: .save <file>")),
+      ReplCommand::Load("") => Err(anyhow!("Usage: .load <file>")),
+      ReplCommand::Save(file) => {
+        let path = self.cwd.join(file);
+        self
+          .save_session(&path)
+          .map(|()| format!("Session saved to {}", path.display()))
+      }
+      ReplCommand::Load(file) => {
+        let path = self.cwd.join(file);
+        self
+          .load_session(&path)
+          .await
+          .map(|()| format!("Session loaded from {}", path.display()))
+      }
+    };
+    match result {
+      Ok(message) => println!("{}", colors::gray(message)),
+      Err(err) => println!("{} {:#}", colors::red("error:"), err),
+    }
+  }
+
   async fn run(&mut self) -> Result<(), AnyError> {
     loop {
       let line = read_line_and_poll(
@@ -56,7 +209,14 @@ impl Repl {
         Ok(line) => {
           self.editor.set_should_exit_on_interrupt(false);
           self.editor.update_history(line.clone());
-          let output = self.session.evaluate_line_and_get_output(&line).await;
+          if let Some(command) = ReplCommand::parse(&line) {
+            self.run_command(command).await;
+            continue;
+          }
+          let mut output = self.evaluate_and_log(&line).await;
+          if self.auto_import && self.maybe_auto_import(&line, &output).await {
+            output = self.evaluate_and_log(&line).await;
+          }
 
           // We check for close and break here instead of making it a loop condition to get
           // consistent behavior in when the user evaluates a call to close().
@@ -178,7 +338,11 @@ pub async fn run(
       WorkerExecutionMode::Repl,
       main_module.clone(),
       permissions.clone(),
-      vec![crate::ops::testing::deno_test::init_ops(test_event_sender)],
+      vec![crate::ops::testing::deno_test::init_ops(
+        test_event_sender,
+        false,
+        None,
+      )],
       Default::default(),
     )
     .await?;
@@ -206,6 +370,10 @@ pub async fn run(
     session,
     editor,
     message_handler: rustyline_channel.1,
+    cwd: cli_options.initial_cwd().to_path_buf(),
+    session_log: Vec::new(),
+    auto_import: repl_flags.auto_import,
+    auto_imports: HashMap::new(),
   };
 
   if let Some(eval_files) = repl_flags.eval_files {
@@ -236,6 +404,11 @@ pub async fn run(
     }
   }
 
+  if let Some(addr) = repl_flags.repl_server {
+    server::serve(&mut repl.session, addr).await?;
+    return Ok(repl.session.worker.exit_code());
+  }
+
   // Doing this manually, instead of using `log::info!` because these messages
   // are supposed to go to stdout, not stderr.
   // Using writeln, because println panics in certain cases
@@ -261,5 +434,30 @@ pub async fn run(
 
   repl.run().await?;
 
+  if let Some(save_session) = &repl_flags.save_session {
+    repl.save_session(&cli_options.initial_cwd().join(save_session))?;
+  }
+
   Ok(repl.session.worker.exit_code())
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_parse_repl_command() {
+    assert_eq!(
+      ReplCommand::parse(".save session.ts"),
+      Some(ReplCommand::Save("session.ts"))
+    );
+    assert_eq!(
+      ReplCommand::parse("  .load  ./session.ts "),
+      Some(ReplCommand::Load("./session.ts"))
+    );
+    assert_eq!(ReplCommand::parse(".save"), Some(ReplCommand::Save("")));
+    assert_eq!(ReplCommand::parse(".load  "), Some(ReplCommand::Load("")));
+    assert_eq!(ReplCommand::parse(".5 + 1"), None);
+    assert_eq!(ReplCommand::parse("save(file)"), None);
+  }
+}
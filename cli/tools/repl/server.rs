@@ -0,0 +1,489 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! A JSON-RPC 2.0 server which lets GUI frontends (notebooks, REPL windows)
+//! drive a REPL session. Requests and responses are newline delimited JSON
+//! objects sent over a TCP connection on a loopback address. Connections are
+//! served one at a time and share the state of the session.
+//!
+//! The first request of a connection has to be `authenticate { token }` with
+//! the token printed on startup, or the one of `DENO_REPL_SERVER_TOKEN`.
+//! Connections which send anything else first, like the HTTP request of a
+//! browser, are closed without evaluating anything.
+//!
+//! Methods:
+//! - `evaluate { code }` evaluates the code in the global scope.
+//! - `complete { code, cursor }` returns the completions at the cursor.
+//! - `inspect { objectId, offset?, limit? }` returns a page of the own
+//!   properties of an object which was returned by another method.
+
+use std::net::SocketAddr;
+
+use deno_core::anyhow::bail;
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_core::serde_json::Value;
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+
+use super::EvaluationOutput;
+use super::ReplSession;
+use crate::cdp;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+/// Returned for an `authenticate` request with the wrong token.
+const UNAUTHORIZED: i64 = -32001;
+
+/// The environment variable with the token for frontends which start the
+/// server themselves.
+const TOKEN_ENV_VAR_NAME: &str = "DENO_REPL_SERVER_TOKEN";
+
+/// The number of properties returned by `inspect` when no limit is given.
+const DEFAULT_INSPECT_LIMIT: usize = 100;
+
+#[derive(Deserialize)]
+struct Request {
+  #[serde(default)]
+  jsonrpc: String,
+  /// Notifications don't have an id and don't get a response.
+  id: Option<Value>,
+  method: String,
+  #[serde(default)]
+  params: Value,
+}
+
+#[derive(Deserialize)]
+struct AuthenticateParams {
+  token: String,
+}
+
+#[derive(Deserialize)]
+struct EvaluateParams {
+  code: String,
+}
+
+#[derive(Deserialize)]
+struct CompleteParams {
+  code: String,
+  /// Byte offset of the cursor in `code`.
+  cursor: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InspectParams {
+  object_id: cdp::RemoteObjectId,
+  #[serde(default)]
+  offset: usize,
+  limit: Option<usize>,
+}
+
+#[derive(Debug)]
+struct RpcError {
+  code: i64,
+  message: String,
+}
+
+impl RpcError {
+  fn new(code: i64, message: impl Into<String>) -> Self {
+    Self {
+      code,
+      message: message.into(),
+    }
+  }
+
+  fn to_json(&self) -> Value {
+    json!({
+      "code": self.code,
+      "message": self.message,
+    })
+  }
+}
+
+impl From<AnyError> for RpcError {
+  fn from(err: AnyError) -> Self {
+    Self::new(INTERNAL_ERROR, format!("{err:#}"))
+  }
+}
+
+/// Serves the session on the address until the code evaluated by a client
+/// calls `close()`.
+pub async fn serve(
+  session: &mut ReplSession,
+  addr: SocketAddr,
+) -> Result<(), AnyError> {
+  if !addr.ip().is_loopback() {
+    bail!(
+      "The REPL server can only listen on a loopback address, got {}",
+      addr.ip()
+    );
+  }
+  let token = match std::env::var(TOKEN_ENV_VAR_NAME) {
+    Ok(token) if !token.is_empty() => token,
+    _ => faster_hex::hex_string(&rand::thread_rng().gen::<[u8; 32]>()),
+  };
+  let listener = TcpListener::bind(addr).await?;
+  log::info!("REPL server listening on {}", listener.local_addr()?);
+  if std::env::var_os(TOKEN_ENV_VAR_NAME).is_none() {
+    log::info!("REPL server token: {}", token);
+  }
+  let mut poll_worker = true;
+  loop {
+    let stream = tokio::select! {
+      result = listener.accept() => result?.0,
+      _ = session.run_event_loop(), if poll_worker => {
+        poll_worker = false;
+        continue;
+      }
+    };
+    if serve_connection(session, stream, &token).await? {
+      return Ok(());
+    }
+    poll_worker = true;
+  }
+}
+
+/// Handles the requests of a client until it disconnects, returning `true`
+/// if the session was closed.
+async fn serve_connection(
+  session: &mut ReplSession,
+  stream: TcpStream,
+  token: &str,
+) -> Result<bool, AnyError> {
+  let (reader, mut writer) = stream.into_split();
+  let mut lines = BufReader::new(reader).lines();
+  let Ok(Some(line)) = lines.next_line().await else {
+    return Ok(false);
+  };
+  let response = match authenticate(&line, token) {
+    Ok(response) => response,
+    // closes the connection, without responding to what may not be a
+    // JSON-RPC client at all
+    Err(None) => return Ok(false),
+    Err(Some(response)) => {
+      let _ = write_response(&mut writer, &response).await;
+      return Ok(false);
+    }
+  };
+  if write_response(&mut writer, &response).await.is_err() {
+    return Ok(false);
+  }
+  let mut poll_worker = true;
+  loop {
+    let line = tokio::select! {
+      result = lines.next_line() => result,
+      _ = session.run_event_loop(), if poll_worker => {
+        poll_worker = false;
+        continue;
+      }
+    };
+    // treat read errors like a disconnect, the session stays usable
+    let Ok(Some(line)) = line else {
+      return Ok(false);
+    };
+    if line.trim().is_empty() {
+      continue;
+    }
+    poll_worker = true;
+    if let Some(response) = handle_message(session, &line).await {
+      if write_response(&mut writer, &response).await.is_err() {
+        return Ok(false);
+      }
+    }
+    if session.closing().await? {
+      return Ok(true);
+    }
+  }
+}
+
+async fn write_response(
+  writer: &mut (impl AsyncWriteExt + Unpin),
+  response: &Value,
+) -> Result<(), AnyError> {
+  let mut text = serde_json::to_string(response)?;
+  text.push('\n');
+  writer.write_all(text.as_bytes()).await?;
+  Ok(())
+}
+
+/// Checks the first message of a connection, which has to be an
+/// `authenticate` request with the token. Returns the response to send, or
+/// the error response when the connection is refused, which is `None` when
+/// the message isn't a JSON-RPC request.
+fn authenticate(text: &str, token: &str) -> Result<Value, Option<Value>> {
+  let request = serde_json::from_str::<Request>(text).map_err(|_| None)?;
+  if request.jsonrpc != "2.0" {
+    return Err(None);
+  }
+  let id = request.id.unwrap_or(Value::Null);
+  let error = if request.method != "authenticate" {
+    RpcError::new(UNAUTHORIZED, "Expected an \"authenticate\" request.")
+  } else {
+    match parse_params::<AuthenticateParams>(request.params) {
+      Ok(params) if tokens_equal(&params.token, token) => {
+        return Ok(json!({
+          "jsonrpc": "2.0",
+          "id": id,
+          "result": null,
+        }));
+      }
+      Ok(_) => RpcError::new(UNAUTHORIZED, "Invalid token."),
+      Err(error) => error,
+    }
+  };
+  Err(Some(error_response(id, &error)))
+}
+
+/// Compares the tokens in a time which doesn't depend on where they differ.
+fn tokens_equal(a: &str, b: &str) -> bool {
+  a.len() == b.len()
+    && a
+      .bytes()
+      .zip(b.bytes())
+      .fold(0, |diff, (a, b)| diff | (a ^ b))
+      == 0
+}
+
+async fn handle_message(
+  session: &mut ReplSession,
+  text: &str,
+) -> Option<Value> {
+  let request = match serde_json::from_str::<Request>(text) {
+    Ok(request) => request,
+    Err(err) => {
+      let error = RpcError::new(PARSE_ERROR, err.to_string());
+      return Some(error_response(Value::Null, &error));
+    }
+  };
+  let result = if request.jsonrpc == "2.0" {
+    handle_request(session, &request.method, request.params).await
+  } else {
+    Err(RpcError::new(
+      INVALID_REQUEST,
+      "Expected \"jsonrpc\": \"2.0\".",
+    ))
+  };
+  let id = request.id?;
+  Some(match result {
+    Ok(result) => json!({
+      "jsonrpc": "2.0",
+      "id": id,
+      "result": result,
+    }),
+    Err(error) => error_response(id, &error),
+  })
+}
+
+fn error_response(id: Value, error: &RpcError) -> Value {
+  json!({
+    "jsonrpc": "2.0",
+    "id": id,
+    "error": error.to_json(),
+  })
+}
+
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+  serde_json::from_value(params)
+    .map_err(|err| RpcError::new(INVALID_PARAMS, err.to_string()))
+}
+
+async fn handle_request(
+  session: &mut ReplSession,
+  method: &str,
+  params: Value,
+) -> Result<Value, RpcError> {
+  match method {
+    "evaluate" => {
+      let params: EvaluateParams = parse_params(params)?;
+      Ok(evaluate(session, &params.code).await)
+    }
+    "complete" => {
+      let params: CompleteParams = parse_params(params)?;
+      complete(session, params).await
+    }
+    "inspect" => {
+      let params: InspectParams = parse_params(params)?;
+      inspect(session, params).await
+    }
+    _ => Err(RpcError::new(
+      METHOD_NOT_FOUND,
+      format!("Unknown method \"{method}\"."),
+    )),
+  }
+}
+
+async fn evaluate(session: &mut ReplSession, code: &str) -> Value {
+  let (output, remote_object) =
+    session.evaluate_line_and_get_result(code).await;
+  let (text, is_exception) = match &output {
+    EvaluationOutput::Value(text) => (text, false),
+    EvaluationOutput::Error(text) => (text, true),
+  };
+  json!({
+    "text": console_static_text::ansi::strip_ansi_codes(text),
+    "exception": is_exception,
+    "value": remote_object.as_ref().map(remote_object_to_json),
+  })
+}
+
+async fn complete(
+  session: &mut ReplSession,
+  params: CompleteParams,
+) -> Result<Value, RpcError> {
+  if !params.code.is_char_boundary(params.cursor) {
+    return Err(RpcError::new(
+      INVALID_PARAMS,
+      "The cursor is not at a character boundary of the code.",
+    ));
+  }
+  let items = session
+    .language_server
+    .completions(&params.code, params.cursor)
+    .await
+    .into_iter()
+    .map(|item| {
+      json!({
+        "text": item.new_text,
+        "start": item.range.start,
+        "end": item.range.end,
+      })
+    })
+    .collect::<Vec<_>>();
+  Ok(json!({ "items": items }))
+}
+
+async fn inspect(
+  session: &mut ReplSession,
+  params: InspectParams,
+) -> Result<Value, RpcError> {
+  let response = session
+    .post_message_with_event_loop(
+      "Runtime.getProperties",
+      Some(cdp::GetPropertiesArgs {
+        object_id: params.object_id,
+        own_properties: Some(true),
+        accessor_properties_only: None,
+        generate_preview: None,
+        non_indexed_properties_only: None,
+      }),
+    )
+    .await
+    .map_err(|err| RpcError::new(INVALID_PARAMS, format!("{err:#}")))?;
+  let response: cdp::GetPropertiesResponse =
+    serde_json::from_value(response).map_err(AnyError::from)?;
+  let limit = params.limit.unwrap_or(DEFAULT_INSPECT_LIMIT);
+  let total = response.result.len();
+  let properties = response
+    .result
+    .iter()
+    .skip(params.offset)
+    .take(limit)
+    .map(|property| {
+      json!({
+        "name": property.name,
+        "value": property.value.as_ref().map(remote_object_to_json),
+      })
+    })
+    .collect::<Vec<_>>();
+  Ok(json!({
+    "properties": properties,
+    "total": total,
+  }))
+}
+
+/// Converts the remote object to the shape sent to clients. Objects have an
+/// `objectId` which can be passed to `inspect`.
+fn remote_object_to_json(object: &cdp::RemoteObject) -> Value {
+  json!({
+    "type": object.kind,
+    "description": object.description,
+    "value": object.value,
+    "objectId": object.object_id,
+  })
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_rpc_error_response() {
+    let error = RpcError::new(METHOD_NOT_FOUND, "Unknown method \"foo\".");
+    assert_eq!(
+      error_response(json!(1), &error),
+      json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "error": {
+          "code": -32601,
+          "message": "Unknown method \"foo\".",
+        },
+      })
+    );
+  }
+
+  #[test]
+  fn test_authenticate() {
+    let token = "secret";
+    assert_eq!(
+      authenticate(
+        r#"{"jsonrpc":"2.0","id":1,"method":"authenticate","params":{"token":"secret"}}"#,
+        token
+      ),
+      Ok(json!({ "jsonrpc": "2.0", "id": 1, "result": null }))
+    );
+
+    // unauthenticated requests are refused
+    let response = authenticate(
+      r#"{"jsonrpc":"2.0","id":1,"method":"evaluate","params":{"code":"1"}}"#,
+      token,
+    )
+    .unwrap_err()
+    .unwrap();
+    assert_eq!(response["error"]["code"], UNAUTHORIZED);
+    let response = authenticate(
+      r#"{"jsonrpc":"2.0","id":1,"method":"authenticate","params":{"token":"secreT"}}"#,
+      token,
+    )
+    .unwrap_err()
+    .unwrap();
+    assert_eq!(response["error"]["message"], "Invalid token.");
+
+    // the request line of a cross-site HTTP request closes the connection
+    // before its body is read
+    assert_eq!(authenticate("POST / HTTP/1.1", token), Err(None));
+    assert_eq!(
+      authenticate(r#"{"id":1,"method":"authenticate"}"#, token),
+      Err(None)
+    );
+  }
+
+  #[test]
+  fn test_tokens_equal() {
+    assert!(tokens_equal("abc", "abc"));
+    assert!(!tokens_equal("abc", "abd"));
+    assert!(!tokens_equal("abc", "abcd"));
+  }
+
+  #[test]
+  fn test_parse_params() {
+    let params: InspectParams =
+      parse_params(json!({ "objectId": "1.2", "limit": 10 })).unwrap();
+    assert_eq!(params.object_id, "1.2");
+    assert_eq!(params.offset, 0);
+    assert_eq!(params.limit, Some(10));
+
+    let err = parse_params::<EvaluateParams>(json!({})).unwrap_err();
+    assert_eq!(err.code, INVALID_PARAMS);
+  }
+}
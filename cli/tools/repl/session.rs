@@ -346,6 +346,16 @@ impl ReplSession {
     &mut self,
     line: &str,
   ) -> EvaluationOutput {
+    self.evaluate_line_and_get_result(line).await.0
+  }
+
+  /// Evaluates the line like `evaluate_line_and_get_output`, but also returns
+  /// the remote object of the value or of the thrown exception, so that it
+  /// can be inspected further.
+  pub async fn evaluate_line_and_get_result(
+    &mut self,
+    line: &str,
+  ) -> (EvaluationOutput, Option<cdp::RemoteObject>) {
     fn format_diagnostic(diagnostic: &deno_ast::ParseDiagnostic) -> String {
       let display_position = diagnostic.display_position();
       format!(
@@ -360,7 +370,7 @@ impl ReplSession {
     async fn inner(
       session: &mut ReplSession,
       line: &str,
-    ) -> Result<EvaluationOutput, AnyError> {
+    ) -> Result<(EvaluationOutput, Option<cdp::RemoteObject>), AnyError> {
       match session.evaluate_line_with_object_wrapping(line).await {
         Ok(evaluate_response) => {
           let cdp::EvaluateResponse {
@@ -382,10 +392,11 @@ impl ReplSession {
               }
               None => "Unknown exception".to_string(),
             };
-            EvaluationOutput::Error(format!(
+            let output = EvaluationOutput::Error(format!(
               "{} {}",
               exception_details.text, description
-            ))
+            ));
+            (output, Some(result))
           } else {
             session
               .language_server
@@ -394,23 +405,26 @@ impl ReplSession {
 
             session.set_last_eval_result(&result).await?;
             let value = session.get_eval_value(&result).await?;
-            EvaluationOutput::Value(value)
+            (EvaluationOutput::Value(value), Some(result))
           })
         }
         Err(err) => {
           // handle a parsing diagnostic
           match err.downcast_ref::<deno_ast::ParseDiagnostic>() {
             Some(diagnostic) => {
-              Ok(EvaluationOutput::Error(format_diagnostic(diagnostic)))
+              Ok((EvaluationOutput::Error(format_diagnostic(diagnostic)), None))
             }
             None => match err.downcast_ref::<ParseDiagnosticsError>() {
-              Some(diagnostics) => Ok(EvaluationOutput::Error(
-                diagnostics
-                  .0
-                  .iter()
-                  .map(format_diagnostic)
-                  .collect::<Vec<_>>()
-                  .join("\n\n"),
+              Some(diagnostics) => Ok((
+                EvaluationOutput::Error(
+                  diagnostics
+                    .0
+                    .iter()
+                    .map(format_diagnostic)
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
+                ),
+                None,
               )),
               None => Err(err),
             },
@@ -419,8 +433,10 @@ impl ReplSession {
       }
     }
 
-    let result = inner(self, line).await;
-    result_to_evaluation_output(result)
+    match inner(self, line).await {
+      Ok(result) => result,
+      Err(err) => (result_to_evaluation_output(Err(err)), None),
+    }
   }
 
   pub async fn evaluate_line_with_object_wrapping(
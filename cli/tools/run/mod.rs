@@ -1,14 +1,23 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use std::io::Read;
+use std::io::Write;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
 use deno_config::deno_json::NodeModulesDirMode;
+use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
 use deno_runtime::WorkerExecutionMode;
+use serde::Deserialize;
+use serde::Serialize;
 
 use crate::args::EvalFlags;
+use crate::args::EvalInputMode;
 use crate::args::Flags;
+use crate::args::RestartOnFailureFlags;
 use crate::args::WatchFlagsWithPaths;
 use crate::factory::CliFactory;
 use crate::file_fetcher::File;
@@ -16,6 +25,9 @@ use crate::util;
 use crate::util::file_watcher::WatcherRestartMode;
 
 pub mod hmr;
+mod siblings;
+
+pub use siblings::run_siblings;
 
 pub fn check_permission_before_script(flags: &Flags) {
   if !flags.has_permission() && flags.has_permission_in_argv() {
@@ -30,6 +42,268 @@ To grant permissions, set them before the script argument. For example:
   }
 }
 
+/// Hidden environment variable used by the `--ephemeral-tmp` supervisor
+/// process to pass the temporary directory to the process running the script.
+pub(crate) const EPHEMERAL_TMP_HIDDEN_ENV_VAR_NAME: &str =
+  "DENO_INTERNAL_EPHEMERAL_TMP";
+
+/// The signals asking a supervisor to terminate which, unlike Ctrl+C in a
+/// terminal, aren't delivered to its child as well.
+struct TerminationSignals {
+  #[cfg(unix)]
+  terminate: tokio::signal::unix::Signal,
+  #[cfg(unix)]
+  hangup: tokio::signal::unix::Signal,
+}
+
+#[cfg(unix)]
+impl TerminationSignals {
+  fn new() -> Result<Self, AnyError> {
+    use tokio::signal::unix::signal;
+    use tokio::signal::unix::SignalKind;
+    Ok(Self {
+      terminate: signal(SignalKind::terminate())?,
+      hangup: signal(SignalKind::hangup())?,
+    })
+  }
+
+  /// Waits for one of the signals, returning its number.
+  async fn recv(&mut self) -> i32 {
+    tokio::select! {
+      _ = self.terminate.recv() => libc::SIGTERM,
+      _ = self.hangup.recv() => libc::SIGHUP,
+    }
+  }
+}
+
+#[cfg(not(unix))]
+impl TerminationSignals {
+  fn new() -> Result<Self, AnyError> {
+    Ok(Self {})
+  }
+
+  async fn recv(&mut self) -> i32 {
+    deno_core::futures::future::pending().await
+  }
+}
+
+/// Runs the current command again in a child process which gets its own
+/// temporary directory. This process supervises the child and removes the
+/// directory once the child exits, even when it crashed or this process was
+/// asked to terminate.
+pub async fn supervise_ephemeral_tmp() -> Result<i32, AnyError> {
+  let tmp_dir = tempfile::Builder::new()
+    .prefix("deno_ephemeral_")
+    .tempdir()
+    .context("Failed to create the temporary directory")?;
+  let mut child = tokio::process::Command::new(std::env::current_exe()?)
+    .args(std::env::args_os().skip(1))
+    .env(EPHEMERAL_TMP_HIDDEN_ENV_VAR_NAME, tmp_dir.path())
+    .env("TMPDIR", tmp_dir.path())
+    .env("TMP", tmp_dir.path())
+    .env("TEMP", tmp_dir.path())
+    .spawn()
+    .context("Failed to spawn the script process")?;
+  let mut termination_signals = TerminationSignals::new()?;
+  let status = loop {
+    tokio::select! {
+      status = child.wait() => break status?,
+      // the signal is delivered to the child as well, so keep waiting for it
+      // to exit before cleaning up
+      _ = tokio::signal::ctrl_c() => {}
+      signo = termination_signals.recv() => {
+        // pass it on and clean up once the child exits
+        #[cfg(unix)]
+        if let Some(pid) = child.id() {
+          // SAFETY: libc call with a pid of our own child process
+          unsafe {
+            libc::kill(pid as i32, signo);
+          }
+        }
+        #[cfg(not(unix))]
+        let _ = signo;
+      }
+    }
+  };
+  let tmp_dir_path = tmp_dir.path().to_path_buf();
+  if let Err(err) = tmp_dir.close() {
+    log::warn!(
+      "Failed to remove the temporary directory '{}': {:#}",
+      tmp_dir_path.display(),
+      err
+    );
+  }
+  Ok(status.code().unwrap_or(1))
+}
+
+/// Hidden environment variable set for the process running the script under
+/// a `--restart-on-failure` supervisor.
+pub(crate) const RESTART_SUPERVISED_HIDDEN_ENV_VAR_NAME: &str =
+  "DENO_INTERNAL_RESTART_SUPERVISED";
+
+/// Hidden environment variable with the loopback address to which the
+/// `--restart-on-failure` supervisor sends its events as JSON lines, so the
+/// `--ui` panel of sibling processes doesn't have to parse its log output.
+pub(crate) const SUPERVISOR_EVENTS_HIDDEN_ENV_VAR_NAME: &str =
+  "DENO_INTERNAL_SUPERVISOR_EVENTS";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub(crate) enum SupervisorEvent {
+  /// The process running the script was spawned.
+  Started,
+  /// The process failed and is going to be restarted after a backoff.
+  Restarting,
+}
+
+/// The connection to the sibling process panel receiving the events of the
+/// supervisor, when it's run by one.
+pub(crate) struct SupervisorEvents(Option<std::net::TcpStream>);
+
+impl SupervisorEvents {
+  fn from_env() -> Self {
+    match std::env::var(SUPERVISOR_EVENTS_HIDDEN_ENV_VAR_NAME) {
+      Ok(addr) => Self::connect(&addr),
+      Err(_) => Self(None),
+    }
+  }
+
+  pub(crate) fn connect(addr: &str) -> Self {
+    Self(std::net::TcpStream::connect(addr).ok())
+  }
+
+  pub(crate) fn emit(&mut self, event: SupervisorEvent) {
+    if let Some(stream) = &mut self.0 {
+      let mut line = serde_json::to_string(&event).unwrap();
+      line.push('\n');
+      if stream.write_all(line.as_bytes()).is_err() {
+        // the panel went away, so there's no one to report to anymore
+        self.0 = None;
+      }
+    }
+  }
+}
+
+/// The consecutive failures are counted again after a run which lasted at
+/// least this long.
+const RESTART_RESET_AFTER: Duration = Duration::from_secs(60);
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Whether this process runs the script under a `--restart-on-failure`
+/// supervisor.
+pub fn is_restart_supervised() -> bool {
+  let is_supervised =
+    std::env::var_os(RESTART_SUPERVISED_HIDDEN_ENV_VAR_NAME).is_some();
+  // remove the env var so that child sub processes won't pick this up
+  std::env::remove_var(RESTART_SUPERVISED_HIDDEN_ENV_VAR_NAME);
+  is_supervised
+}
+
+/// Runs the current command again in a child process, which is restarted
+/// with a backoff when it exits with a non-zero code or crashes.
+pub async fn supervise_restarts(
+  options: &RestartOnFailureFlags,
+) -> Result<i32, AnyError> {
+  let mut events = SupervisorEvents::from_env();
+  let mut failures = 0;
+  loop {
+    let started = Instant::now();
+    let mut child = tokio::process::Command::new(std::env::current_exe()?)
+      .args(std::env::args_os().skip(1))
+      .env(RESTART_SUPERVISED_HIDDEN_ENV_VAR_NAME, "1")
+      // only the supervisor reports to the panel, not the script or the
+      // processes it spawns
+      .env_remove(SUPERVISOR_EVENTS_HIDDEN_ENV_VAR_NAME)
+      .spawn()
+      .context("Failed to spawn the script process")?;
+    events.emit(SupervisorEvent::Started);
+    let mut interrupted = false;
+    let status = loop {
+      tokio::select! {
+        status = child.wait() => break status?,
+        // the signal is delivered to the child as well, which isn't a
+        // failure to restart from
+        _ = tokio::signal::ctrl_c() => interrupted = true,
+      }
+    };
+    let exit_code = status.code().unwrap_or(1);
+    if status.success() || interrupted {
+      return Ok(exit_code);
+    }
+
+    if started.elapsed() >= RESTART_RESET_AFTER {
+      failures = 0;
+    }
+    failures += 1;
+    let attempts = match options.max_retries {
+      Some(max_retries) if failures > max_retries => {
+        log::error!(
+          "{} {} restarts={}",
+          crate::colors::red("Giving up restarting"),
+          describe_exit_status(&status),
+          max_retries
+        );
+        return Ok(exit_code);
+      }
+      Some(max_retries) => format!("{failures}/{max_retries}"),
+      None => failures.to_string(),
+    };
+    let delay = restart_delay(options.backoff, failures);
+    log::warn!(
+      "{} {} attempt={} delay_ms={}",
+      crate::colors::yellow("Restarting"),
+      describe_exit_status(&status),
+      attempts,
+      delay.as_millis()
+    );
+    events.emit(SupervisorEvent::Restarting);
+    tokio::select! {
+      _ = tokio::time::sleep(delay) => {}
+      _ = tokio::signal::ctrl_c() => return Ok(exit_code),
+    }
+  }
+}
+
+fn describe_exit_status(status: &std::process::ExitStatus) -> String {
+  #[cfg(unix)]
+  if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(status) {
+    return format!("signal={signal}");
+  }
+  format!("exit_code={}", status.code().unwrap_or(1))
+}
+
+/// The backoff doubled for each consecutive failure, up to 30 seconds or the
+/// backoff itself when it's longer.
+fn restart_delay(backoff: Duration, failures: u32) -> Duration {
+  let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+  backoff
+    .saturating_mul(factor)
+    .min(MAX_RESTART_BACKOFF.max(backoff))
+}
+
+/// When running under an `--ephemeral-tmp` supervisor, returns the flags with
+/// read and write access to its temporary directory.
+pub fn ephemeral_tmp_flags(flags: &Flags) -> Option<Arc<Flags>> {
+  let tmp_dir = std::env::var(EPHEMERAL_TMP_HIDDEN_ENV_VAR_NAME).ok()?;
+  // remove the env var so that child sub processes won't pick this up
+  std::env::remove_var(EPHEMERAL_TMP_HIDDEN_ENV_VAR_NAME);
+  let mut flags = flags.clone();
+  if !flags.permissions.allow_all {
+    for allowlist in [
+      &mut flags.permissions.allow_read,
+      &mut flags.permissions.allow_write,
+    ] {
+      match allowlist {
+        // already allowed everywhere
+        Some(paths) if paths.is_empty() => {}
+        Some(paths) => paths.push(tmp_dir.clone()),
+        None => *allowlist = Some(vec![tmp_dir.clone()]),
+      }
+    }
+  }
+  Some(Arc::new(flags))
+}
+
 pub async fn run_script(
   mode: WorkerExecutionMode,
   flags: Arc<Flags>,
@@ -156,11 +430,19 @@ pub async fn eval_command(
   maybe_npm_install(&factory).await?;
 
   // Create a dummy source file.
-  let source_code = if eval_flags.print {
-    format!("console.log({})", eval_flags.code)
-  } else {
-    eval_flags.code
-  };
+  let mut snippets = eval_flags.code;
+  if eval_flags.print {
+    if let Some(last) = snippets.last_mut() {
+      *last = format!("console.log({})", last);
+    }
+  }
+  let mut source_code = String::new();
+  if let Some(input) = eval_flags.input {
+    let mut stdin = String::new();
+    std::io::stdin().read_to_string(&mut stdin)?;
+    source_code.push_str(&stdin_declaration(input, stdin)?);
+  }
+  source_code.push_str(&snippets.join(";\n"));
 
   // Save a fake file into file fetcher cache
   // to allow module access by TS compiler.
@@ -178,6 +460,20 @@ pub async fn eval_command(
   Ok(exit_code)
 }
 
+/// Declares `$stdin` for `deno eval --input`. JSON is a subset of
+/// JavaScript, so the value can be inlined as a literal.
+fn stdin_declaration(
+  input: EvalInputMode,
+  stdin: String,
+) -> Result<String, AnyError> {
+  let value = match input {
+    EvalInputMode::Json => serde_json::from_str::<serde_json::Value>(&stdin)
+      .context("Failed parsing stdin as JSON")?,
+    EvalInputMode::Text => serde_json::Value::String(stdin),
+  };
+  Ok(format!("const $stdin = {};\n", value))
+}
+
 pub async fn maybe_npm_install(factory: &CliFactory) -> Result<(), AnyError> {
   // ensure an "npm install" is done if the user has explicitly
   // opted into using a managed node_modules directory
@@ -190,3 +486,20 @@ pub async fn maybe_npm_install(factory: &CliFactory) -> Result<(), AnyError> {
   }
   Ok(())
 }
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_restart_delay() {
+    let backoff = Duration::from_secs(1);
+    assert_eq!(restart_delay(backoff, 1), Duration::from_secs(1));
+    assert_eq!(restart_delay(backoff, 2), Duration::from_secs(2));
+    assert_eq!(restart_delay(backoff, 4), Duration::from_secs(8));
+    assert_eq!(restart_delay(backoff, 10), MAX_RESTART_BACKOFF);
+    assert_eq!(restart_delay(backoff, u32::MAX), MAX_RESTART_BACKOFF);
+    let backoff = Duration::from_secs(60);
+    assert_eq!(restart_delay(backoff, 3), backoff);
+  }
+}
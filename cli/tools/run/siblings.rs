@@ -0,0 +1,604 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Instant;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_core::futures::stream::FuturesUnordered;
+use deno_core::futures::StreamExt;
+use deno_core::parking_lot::Mutex;
+use deno_core::serde_json;
+use deno_runtime::ops::tty::ConsoleSize;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncRead;
+use tokio::io::BufReader;
+use tokio::net::TcpListener;
+use tokio::process::Child;
+
+use super::SupervisorEvent;
+use super::SUPERVISOR_EVENTS_HIDDEN_ENV_VAR_NAME;
+use crate::args::Flags;
+use crate::args::RunFlags;
+use crate::colors;
+use crate::util::display::human_elapsed;
+use crate::util::draw_thread::DrawThread;
+use crate::util::draw_thread::DrawThreadRenderer;
+use crate::util::sync::AsyncFlag;
+
+/// A program run as one of the sibling processes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SiblingProgram {
+  /// The name which prefixes the output of the process.
+  name: String,
+  script: String,
+  args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SiblingStatus {
+  Running,
+  /// Waiting to be restarted by its `--restart-on-failure` supervisor.
+  Restarting,
+  Exited(i32),
+}
+
+#[derive(Debug)]
+struct SiblingState {
+  name: String,
+  status: SiblingStatus,
+  restarts: usize,
+  last_line: String,
+  started: Instant,
+}
+
+/// The status of the sibling processes, which is rendered in a panel below
+/// their output with `--ui`.
+#[derive(Debug, Default)]
+struct SiblingsPanel {
+  states: Mutex<Vec<SiblingState>>,
+}
+
+impl SiblingsPanel {
+  fn add(&self, name: String) {
+    self.states.lock().push(SiblingState {
+      name,
+      status: SiblingStatus::Running,
+      restarts: 0,
+      last_line: String::new(),
+      started: Instant::now(),
+    });
+  }
+
+  fn record_line(&self, index: usize, line: &str) {
+    let line = console_static_text::ansi::strip_ansi_codes(line);
+    let line = line.trim();
+    if line.is_empty() {
+      return;
+    }
+    self.states.lock()[index].last_line = line.to_string();
+  }
+
+  fn record_event(&self, index: usize, event: SupervisorEvent) {
+    let mut states = self.states.lock();
+    let state = &mut states[index];
+    if matches!(state.status, SiblingStatus::Exited(_)) {
+      return;
+    }
+    match event {
+      SupervisorEvent::Started => {
+        if state.status == SiblingStatus::Restarting {
+          state.status = SiblingStatus::Running;
+          state.started = Instant::now();
+        }
+      }
+      SupervisorEvent::Restarting => {
+        state.status = SiblingStatus::Restarting;
+        state.restarts += 1;
+      }
+    }
+  }
+
+  fn set_exited(&self, index: usize, code: i32) {
+    self.states.lock()[index].status = SiblingStatus::Exited(code);
+  }
+}
+
+impl DrawThreadRenderer for SiblingsPanel {
+  fn render(&self, size: &ConsoleSize) -> String {
+    let states = self.states.lock();
+    let name_width = states
+      .iter()
+      .map(|state| state.name.len())
+      .max()
+      .unwrap_or(0);
+    let mut text = String::new();
+    for (index, state) in states.iter().enumerate() {
+      let status = match state.status {
+        SiblingStatus::Running => format!(
+          "running {}",
+          human_elapsed(state.started.elapsed().as_millis())
+        ),
+        SiblingStatus::Restarting => "restarting".to_string(),
+        SiblingStatus::Exited(code) => format!("exited {code}"),
+      };
+      let status_text = format!(
+        "{:name_width$}  {:16} restarts={}  ",
+        state.name, status, state.restarts
+      );
+      let remaining = (size.cols as usize).saturating_sub(status_text.len());
+      let last_line = state.last_line.chars().take(remaining);
+      text.push_str(&format!(
+        "{}  {} restarts={}  {}\n",
+        colored(index, &format!("{:name_width$}", state.name)),
+        match state.status {
+          SiblingStatus::Running => colors::green(format!("{status:16}")),
+          SiblingStatus::Restarting => colors::yellow(format!("{status:16}")),
+          SiblingStatus::Exited(_) => colors::red(format!("{status:16}")),
+        },
+        state.restarts,
+        colors::gray(last_line.collect::<String>()),
+      ));
+    }
+    text
+  }
+}
+
+/// Runs the programs of the procfile or the script along with the ones given
+/// with `--sibling` as sibling processes, which share the flags given before
+/// the script.
+///
+/// The output of the processes is interleaved line by line, prefixed with
+/// their names. Once one of them exits, the others are terminated and its exit
+/// code is returned.
+pub async fn run_siblings(
+  flags: &Flags,
+  run_flags: &RunFlags,
+) -> Result<i32, AnyError> {
+  let os_args = std::env::args_os().skip(1).collect::<Vec<_>>();
+  let (programs, common_args) = match &run_flags.procfile {
+    Some(procfile) => {
+      let text = std::fs::read_to_string(procfile)
+        .with_context(|| format!("Failed reading procfile '{procfile}'"))?;
+      let programs = parse_procfile(&text)
+        .with_context(|| format!("Failed parsing procfile '{procfile}'"))?;
+      (programs, common_args(&os_args, &[]))
+    }
+    None => {
+      let programs = programs_from_args(run_flags, &flags.argv);
+      let script_and_argv = std::iter::once(&run_flags.script)
+        .chain(&flags.argv)
+        .map(|arg| arg.as_str())
+        .collect::<Vec<_>>();
+      (programs, common_args(&os_args, &script_and_argv))
+    }
+  };
+  if programs.is_empty() {
+    bail!("No programs to run");
+  }
+  if programs.iter().any(|program| program.script == "-") {
+    bail!("Programs read from stdin can't be run as sibling processes");
+  }
+
+  let name_width = programs
+    .iter()
+    .map(|program| program.name.len())
+    .max()
+    .unwrap_or(0);
+  let shutdown = AsyncFlag::default();
+  let panel = Arc::new(SiblingsPanel::default());
+  let ui = run_flags.ui && DrawThread::is_supported();
+  // the `--restart-on-failure` supervisors of the processes report their
+  // restarts to the panel through a loopback connection each
+  let follow_events = ui && run_flags.restart_on_failure.is_some();
+  let mut processes = FuturesUnordered::new();
+  for (index, program) in programs.into_iter().enumerate() {
+    let prefix = format!(
+      "{} ",
+      colored(index, &format!("{:name_width$} |", program.name))
+    );
+    let events_listener = if follow_events {
+      Some(
+        TcpListener::bind("127.0.0.1:0")
+          .await
+          .context("Failed to listen for the events of the supervisors")?,
+      )
+    } else {
+      None
+    };
+    let mut command = tokio::process::Command::new(std::env::current_exe()?);
+    command
+      .args(&common_args)
+      .arg(&program.script)
+      .args(&program.args)
+      .stdin(Stdio::null())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped());
+    if let Some(listener) = &events_listener {
+      command.env(
+        SUPERVISOR_EVENTS_HIDDEN_ENV_VAR_NAME,
+        listener.local_addr()?.to_string(),
+      );
+    }
+    let child = command
+      .spawn()
+      .with_context(|| format!("Failed to spawn '{}'", program.name))?;
+    panel.add(program.name.clone());
+    processes.push(supervise_sibling(
+      Sibling {
+        index,
+        name: program.name,
+        prefix,
+        panel: panel.clone(),
+        ui,
+      },
+      events_listener,
+      child,
+      shutdown.clone(),
+    ));
+  }
+  let _draw_thread_guard = ui.then(|| DrawThread::add_entry(panel.clone()));
+
+  let mut exit_code = None;
+  while let Some(result) = processes.next().await {
+    let (name, status) = result?;
+    let code = status.code().unwrap_or(1);
+    if exit_code.is_none() {
+      exit_code = Some(code);
+      if !shutdown.is_raised() {
+        log::info!(
+          "{} {} exited with code {}, stopping the other processes",
+          colors::yellow("Sibling"),
+          name,
+          code
+        );
+        shutdown.raise();
+      }
+    }
+  }
+  Ok(exit_code.unwrap_or(0))
+}
+
+struct Sibling {
+  index: usize,
+  name: String,
+  /// Prefixes each line of the output of the process.
+  prefix: String,
+  panel: Arc<SiblingsPanel>,
+  ui: bool,
+}
+
+async fn supervise_sibling(
+  sibling: Sibling,
+  mut child: Child,
+  events_listener: Option<TcpListener>,
+  shutdown: AsyncFlag,
+) -> Result<(String, std::process::ExitStatus), AnyError> {
+  let stdout = forward_lines(child.stdout.take().unwrap(), &sibling, false);
+  let stderr = forward_lines(child.stderr.take().unwrap(), &sibling, true);
+  let output = deno_core::futures::future::join(stdout, stderr);
+  let wait = async {
+    loop {
+      tokio::select! {
+        status = child.wait() => break status,
+        _ = shutdown.wait_raised() => terminate(&mut child),
+        // the signal is delivered to the children as well, which stop
+        // on their own
+        _ = tokio::signal::ctrl_c() => {}
+      }
+    }
+  };
+  let follow_events = async {
+    match events_listener {
+      Some(listener) => follow_supervisor_events(listener, &sibling).await,
+      None => deno_core::futures::future::pending().await,
+    }
+  };
+  let (status, _) = tokio::select! {
+    result = deno_core::futures::future::join(wait, output) => result,
+    _ = follow_events => unreachable!(),
+  };
+  let status = status?;
+  sibling
+    .panel
+    .set_exited(sibling.index, status.code().unwrap_or(1));
+  Ok((sibling.name, status))
+}
+
+async fn forward_lines(
+  reader: impl AsyncRead + Unpin,
+  sibling: &Sibling,
+  is_stderr: bool,
+) {
+  let prefix = &sibling.prefix;
+  let mut lines = BufReader::new(reader).lines();
+  while let Ok(Some(line)) = lines.next_line().await {
+    sibling.panel.record_line(sibling.index, &line);
+    if sibling.ui {
+      DrawThread::hide();
+    }
+    // write whole lines so the output of the processes doesn't get mixed up
+    let _ = if is_stderr {
+      writeln!(std::io::stderr().lock(), "{prefix}{line}")
+    } else {
+      writeln!(std::io::stdout().lock(), "{prefix}{line}")
+    };
+    if sibling.ui {
+      DrawThread::show();
+    }
+  }
+}
+
+/// Applies the events sent by the `--restart-on-failure` supervisor of the
+/// process to the panel. This never returns, so it should be dropped once the
+/// process exits.
+async fn follow_supervisor_events(listener: TcpListener, sibling: &Sibling) {
+  loop {
+    let Ok((stream, _)) = listener.accept().await else {
+      continue;
+    };
+    let mut lines = BufReader::new(stream).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+      if let Ok(event) = serde_json::from_str(&line) {
+        sibling.panel.record_event(sibling.index, event);
+      }
+    }
+  }
+}
+
+fn terminate(child: &mut Child) {
+  #[cfg(unix)]
+  if let Some(pid) = child.id() {
+    // SAFETY: libc call with a pid of our own child process
+    unsafe {
+      libc::kill(pid as i32, libc::SIGTERM);
+    }
+    return;
+  }
+  let _ = child.start_kill();
+}
+
+fn colored(index: usize, text: &str) -> String {
+  match index % 4 {
+    0 => colors::cyan(text).to_string(),
+    1 => colors::yellow(text).to_string(),
+    2 => colors::magenta(text).to_string(),
+    _ => colors::green(text).to_string(),
+  }
+}
+
+/// The arguments of this process which are passed to every program, which
+/// are the ones before the script without `--procfile` and `--sibling`. The
+/// script and its arguments are always the last arguments, as the script
+/// takes all the arguments that follow it.
+fn common_args(
+  os_args: &[OsString],
+  script_and_argv: &[&str],
+) -> Vec<OsString> {
+  let mut args = os_args;
+  for tail_arg in script_and_argv.iter().rev() {
+    match args.split_last() {
+      Some((last, rest)) if last.to_string_lossy() == *tail_arg => args = rest,
+      _ => break,
+    }
+  }
+  args
+    .iter()
+    .filter(|arg| {
+      let arg = arg.to_string_lossy();
+      !arg.starts_with("--procfile=") && !arg.starts_with("--sibling=")
+    })
+    .cloned()
+    .collect()
+}
+
+fn programs_from_args(
+  run_flags: &RunFlags,
+  argv: &[String],
+) -> Vec<SiblingProgram> {
+  let mut programs = vec![SiblingProgram {
+    name: String::new(),
+    script: run_flags.script.clone(),
+    args: argv.to_vec(),
+  }];
+  programs.extend(run_flags.siblings.iter().map(|sibling| SiblingProgram {
+    name: String::new(),
+    script: sibling[0].clone(),
+    args: sibling[1..].to_vec(),
+  }));
+  for index in 0..programs.len() {
+    let name = Path::new(&programs[index].script)
+      .file_stem()
+      .map(|stem| stem.to_string_lossy().to_string())
+      .unwrap_or_else(|| programs[index].script.clone());
+    let is_duplicate =
+      programs.iter().enumerate().any(|(other, program)| {
+        other != index && program.script == programs[index].script
+      }) || programs[..index].iter().any(|program| program.name == name);
+    programs[index].name = if is_duplicate {
+      format!("{name}.{}", index + 1)
+    } else {
+      name
+    };
+  }
+  programs
+}
+
+/// Parses the `name: script args...` lines of a procfile, skipping empty
+/// lines and comments. The arguments are separated by whitespace.
+fn parse_procfile(text: &str) -> Result<Vec<SiblingProgram>, AnyError> {
+  let mut programs: Vec<SiblingProgram> = Vec::new();
+  for (index, line) in text.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+    let Some((name, command)) = line.split_once(':') else {
+      bail!("Expected 'name: script' on line {}", index + 1);
+    };
+    let name = name.trim();
+    let mut parts = command.split_whitespace().map(|part| part.to_string());
+    let Some(script) = parts.next() else {
+      bail!("Missing the script of '{}' on line {}", name, index + 1);
+    };
+    if name.is_empty() {
+      bail!("Missing the name on line {}", index + 1);
+    }
+    if programs.iter().any(|program| program.name == name) {
+      bail!("Duplicate name '{}' on line {}", name, index + 1);
+    }
+    programs.push(SiblingProgram {
+      name: name.to_string(),
+      script,
+      args: parts.collect(),
+    });
+  }
+  Ok(programs)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+  use crate::tools::run::SupervisorEvents;
+
+  #[test]
+  fn test_parse_procfile() {
+    let programs = parse_procfile(
+      "# services\nweb: server.ts --port=8000\n\nworker:worker.ts\n",
+    )
+    .unwrap();
+    assert_eq!(
+      programs,
+      vec![
+        SiblingProgram {
+          name: "web".to_string(),
+          script: "server.ts".to_string(),
+          args: vec!["--port=8000".to_string()],
+        },
+        SiblingProgram {
+          name: "worker".to_string(),
+          script: "worker.ts".to_string(),
+          args: vec![],
+        },
+      ]
+    );
+    assert!(parse_procfile("web server.ts").is_err());
+    assert!(parse_procfile("web:").is_err());
+    assert!(parse_procfile("web: a.ts\nweb: b.ts").is_err());
+  }
+
+  #[test]
+  fn test_programs_from_args() {
+    let run_flags = RunFlags {
+      siblings: vec![
+        vec!["jobs/worker.ts".to_string()],
+        vec!["jobs/worker.ts".to_string(), "--fast".to_string()],
+      ],
+      ..RunFlags::new_default("server.ts".to_string())
+    };
+    let programs = programs_from_args(&run_flags, &["--port=8000".to_string()]);
+    assert_eq!(
+      programs
+        .iter()
+        .map(|program| program.name.as_str())
+        .collect::<Vec<_>>(),
+      vec!["server", "worker.2", "worker.3"]
+    );
+    assert_eq!(programs[0].args, vec!["--port=8000".to_string()]);
+    assert_eq!(programs[2].args, vec!["--fast".to_string()]);
+  }
+
+  #[test]
+  fn test_common_args() {
+    let os_args =
+      |args: &[&str]| args.iter().map(OsString::from).collect::<Vec<_>>();
+    assert_eq!(
+      common_args(
+        &os_args(&[
+          "run",
+          "-A",
+          "--sibling=worker.ts --queue=jobs",
+          "--sibling=jobs.ts",
+          "server.ts",
+          "--port=8000",
+          "server.ts",
+        ]),
+        &["server.ts", "--port=8000", "server.ts"],
+      ),
+      os_args(&["run", "-A"])
+    );
+    // a script argument equal to a flag isn't taken for the flag
+    assert_eq!(
+      common_args(
+        &os_args(&["run", "--reload", "--sibling=a.ts", "b.ts", "--reload"]),
+        &["b.ts", "--reload"],
+      ),
+      os_args(&["run", "--reload"])
+    );
+    assert_eq!(
+      common_args(&os_args(&["run", "-A", "--procfile=Procfile"]), &[]),
+      os_args(&["run", "-A"])
+    );
+  }
+
+  #[tokio::test]
+  async fn test_follow_supervisor_events() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let panel = Arc::new(SiblingsPanel::default());
+    panel.add("server".to_string());
+    let sibling = Sibling {
+      index: 0,
+      name: "server".to_string(),
+      prefix: String::new(),
+      panel: panel.clone(),
+      ui: true,
+    };
+    let mut events = SupervisorEvents::connect(&addr);
+    events.emit(SupervisorEvent::Started);
+    events.emit(SupervisorEvent::Restarting);
+    events.emit(SupervisorEvent::Started);
+    drop(events);
+    // the events are applied until the connection is closed
+    let _ = tokio::time::timeout(
+      std::time::Duration::from_millis(500),
+      follow_supervisor_events(listener, &sibling),
+    )
+    .await;
+    let states = panel.states.lock();
+    assert_eq!(states[0].status, SiblingStatus::Running);
+    assert_eq!(states[0].restarts, 1);
+  }
+
+  #[test]
+  fn test_panel_render() {
+    let panel = SiblingsPanel::default();
+    panel.add("server".to_string());
+    panel.add("db".to_string());
+    panel.record_line(0, "  listening on :8000  ");
+    panel.set_exited(0, 3);
+    panel.record_event(1, SupervisorEvent::Restarting);
+    let text = panel.render(&ConsoleSize { cols: 45, rows: 10 });
+    assert_eq!(
+      console_static_text::ansi::strip_ansi_codes(&text),
+      concat!(
+        "server  exited 3         restarts=0  listenin\n",
+        "db      restarting       restarts=1  \n",
+      )
+    );
+
+    // the status of exited processes isn't changed by late events
+    panel.record_event(0, SupervisorEvent::Restarting);
+    panel.record_event(1, SupervisorEvent::Started);
+    let states = panel.states.lock();
+    assert_eq!(states[0].status, SiblingStatus::Exited(3));
+    assert_eq!(states[0].restarts, 0);
+    assert_eq!(states[1].status, SiblingStatus::Running);
+    assert_eq!(states[1].restarts, 1);
+  }
+}
@@ -16,14 +16,20 @@ use deno_core::anyhow::anyhow;
 use deno_core::anyhow::bail;
 use deno_core::anyhow::Context;
 use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::url::Url;
 use deno_path_util::normalize_path;
+use deno_semver::jsr::JsrPackageReqReference;
 use deno_task_shell::ShellCommand;
 
+use crate::args::jsr_url;
 use crate::args::CliOptions;
 use crate::args::Flags;
 use crate::args::TaskFlags;
 use crate::colors;
 use crate::factory::CliFactory;
+use crate::file_fetcher::FileFetcher;
+use crate::jsr::JsrFetchResolver;
 use crate::npm::CliNpmResolver;
 use crate::task_runner;
 use crate::util::fs::canonicalize_path;
@@ -48,6 +54,15 @@ pub async fn execute_script(
         v == "1"
       })
       .unwrap_or(false);
+  let raw_tasks = raw_deno_json_tasks(start_dir);
+  if !force_use_pkg_json
+    && raw_tasks
+      .iter()
+      .any(|task| matches!(task.definition, RawTaskDefinition::Extends(_)))
+  {
+    // deno_config only understands string definitions
+    return execute_raw_task(&factory, task_flags, &raw_tasks).await;
+  }
   let tasks_config = start_dir.to_tasks_config()?;
   let tasks_config = if force_use_pkg_json {
     tasks_config.with_only_pkg_json()
@@ -92,6 +107,7 @@ pub async fn execute_script(
           custom_commands,
           npm_resolver: npm_resolver.as_ref(),
           cli_options,
+          expand_env_vars: cli_options.allow_env_in_config(),
         })
         .await
       }
@@ -128,6 +144,7 @@ pub async fn execute_script(
               custom_commands: custom_commands.clone(),
               npm_resolver: npm_resolver.as_ref(),
               cli_options,
+              expand_env_vars: false,
             })
             .await?;
             if exit_code > 0 {
@@ -156,6 +173,232 @@ pub async fn execute_script(
   }
 }
 
+/// A task of a deno.json as it was written, which may extend a task shared
+/// by a remote module or another workspace member.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RawTaskDefinition {
+  Command(String),
+  Extends(String),
+}
+
+impl RawTaskDefinition {
+  fn from_value(value: &serde_json::Value) -> Option<Self> {
+    match value {
+      serde_json::Value::String(command) => {
+        Some(Self::Command(command.clone()))
+      }
+      serde_json::Value::Object(obj) => {
+        if let Some(specifier) = obj.get("extends").and_then(|v| v.as_str()) {
+          Some(Self::Extends(specifier.to_string()))
+        } else {
+          // the commented form
+          let definition = obj.get("definition")?.as_str()?;
+          Some(Self::Command(definition.to_string()))
+        }
+      }
+      _ => None,
+    }
+  }
+}
+
+struct RawTask {
+  name: String,
+  definition: RawTaskDefinition,
+  folder_url: Url,
+  is_root: bool,
+}
+
+/// Collects the tasks of the deno.json of the directory followed by the ones
+/// of the workspace root.
+fn raw_deno_json_tasks(start_dir: &WorkspaceDirectory) -> Vec<RawTask> {
+  let root_deno_json = start_dir.workspace.root_deno_json();
+  let member_deno_json = start_dir.maybe_deno_json().filter(|config| {
+    root_deno_json.map_or(true, |root| root.specifier != config.specifier)
+  });
+  let mut tasks = Vec::new();
+  for (config, is_root) in [(member_deno_json, false), (root_deno_json, true)] {
+    let Some(config) = config else {
+      continue;
+    };
+    let Ok(folder_url) = config.specifier.join(".") else {
+      continue;
+    };
+    let Some(obj) = config.json.tasks.as_ref().and_then(|t| t.as_object())
+    else {
+      continue;
+    };
+    for (name, value) in obj {
+      if let Some(definition) = RawTaskDefinition::from_value(value) {
+        tasks.push(RawTask {
+          name: name.clone(),
+          definition,
+          folder_url: folder_url.clone(),
+          is_root: is_root && member_deno_json.is_some(),
+        });
+      }
+    }
+  }
+  tasks
+}
+
+async fn execute_raw_task(
+  factory: &CliFactory,
+  task_flags: TaskFlags,
+  raw_tasks: &[RawTask],
+) -> Result<i32, AnyError> {
+  let cli_options = factory.cli_options()?;
+  let Some(task_name) = &task_flags.task else {
+    print_available_raw_tasks(&mut std::io::stdout(), raw_tasks)?;
+    return Ok(0);
+  };
+  // tasks of the member take precedence over the ones of the root
+  let Some(task) = raw_tasks.iter().find(|task| task.name == *task_name) else {
+    if task_flags.is_run {
+      return Err(anyhow!("Task not found: {}", task_name));
+    }
+    log::error!("Task not found: {}", task_name);
+    if log::log_enabled!(log::Level::Error) {
+      print_available_raw_tasks(&mut std::io::stderr(), raw_tasks)?;
+    }
+    return Ok(1);
+  };
+  let script = match &task.definition {
+    RawTaskDefinition::Command(command) => command.clone(),
+    RawTaskDefinition::Extends(specifier) => {
+      let file_fetcher = factory.file_fetcher()?;
+      let jsr_resolver = JsrFetchResolver::new(file_fetcher.clone());
+      resolve_extends_task(
+        task_name,
+        specifier,
+        &task.folder_url,
+        file_fetcher,
+        &jsr_resolver,
+      )
+      .await?
+    }
+  };
+
+  let cwd = match task_flags.cwd {
+    Some(path) => canonicalize_path(&PathBuf::from(path))
+      .context("failed canonicalizing --cwd")?,
+    None => normalize_path(task.folder_url.to_file_path().unwrap()),
+  };
+  let npm_resolver = factory.npm_resolver().await?;
+  let node_resolver = factory.node_resolver().await?;
+  let custom_commands =
+    task_runner::resolve_custom_commands(npm_resolver.as_ref(), node_resolver)?;
+  run_task(RunTaskOptions {
+    task_name,
+    script: &script,
+    cwd: &cwd,
+    env_vars: task_runner::real_env_vars(),
+    custom_commands,
+    npm_resolver: npm_resolver.as_ref(),
+    cli_options,
+    expand_env_vars: cli_options.allow_env_in_config(),
+  })
+  .await
+}
+
+/// Resolves the command of a task which extends a shared task definition.
+///
+/// The specifier points to a JSON(C) file, which is either the command, a
+/// single task definition, a map of task names to definitions or a config
+/// file with a `"tasks"` field. The task is looked up by the fragment of the
+/// specifier (ex. `./shared/deno.json#build`), falling back to the name of
+/// the local task. Shared tasks may themselves extend other tasks.
+async fn resolve_extends_task(
+  task_name: &str,
+  specifier: &str,
+  referrer: &Url,
+  file_fetcher: &FileFetcher,
+  jsr_resolver: &JsrFetchResolver,
+) -> Result<String, AnyError> {
+  let mut task_name = task_name.to_string();
+  let mut specifier = specifier.to_string();
+  let mut referrer = referrer.clone();
+  let mut visited = HashSet::new();
+  loop {
+    let (specifier_without_fragment, fragment) = match specifier.split_once('#')
+    {
+      Some((specifier, fragment)) => (specifier, Some(fragment)),
+      None => (specifier.as_str(), None),
+    };
+    if let Some(fragment) = fragment {
+      task_name = fragment.to_string();
+    }
+    let url = resolve_extends_specifier(
+      specifier_without_fragment,
+      &referrer,
+      jsr_resolver,
+    )
+    .await?;
+    if !visited.insert((url.clone(), task_name.clone())) {
+      bail!(
+        "Task \"{}\" in {} extends itself in a cycle.",
+        task_name,
+        url
+      );
+    }
+    let file = file_fetcher
+      .fetch_bypass_permissions(&url)
+      .await
+      .with_context(|| format!("Failed fetching shared task from {url}"))?;
+    let text = String::from_utf8_lossy(&file.source);
+    let value = jsonc_parser::parse_to_serde_value(&text, &Default::default())
+      .with_context(|| format!("Failed parsing shared task from {url}"))?
+      .unwrap_or(serde_json::Value::Null);
+    match lookup_shared_task(&value, &task_name) {
+      Some(RawTaskDefinition::Command(command)) => return Ok(command),
+      Some(RawTaskDefinition::Extends(next)) => {
+        specifier = next;
+        referrer = file.specifier;
+      }
+      None => bail!("Task \"{}\" was not found in {}", task_name, url),
+    }
+  }
+}
+
+async fn resolve_extends_specifier(
+  specifier: &str,
+  referrer: &Url,
+  jsr_resolver: &JsrFetchResolver,
+) -> Result<Url, AnyError> {
+  if !specifier.starts_with("jsr:") {
+    return Ok(deno_core::resolve_import(specifier, referrer.as_str())?);
+  }
+  let req_ref = JsrPackageReqReference::from_str(specifier)?;
+  let nv = jsr_resolver.req_to_nv(req_ref.req()).await.ok_or_else(|| {
+    anyhow!("Could not find a version matching {}", specifier)
+  })?;
+  let export_name = req_ref.export_name();
+  let path = jsr_resolver
+    .package_version_info(&nv)
+    .await
+    .and_then(|info| info.export(&export_name).map(|path| path.to_string()))
+    .ok_or_else(|| {
+      anyhow!("Package {} does not export \"{}\"", nv, export_name)
+    })?;
+  Ok(jsr_url().join(&format!("{}/{}/{}", nv.name, nv.version, path))?)
+}
+
+fn lookup_shared_task(
+  value: &serde_json::Value,
+  task_name: &str,
+) -> Option<RawTaskDefinition> {
+  let serde_json::Value::Object(obj) = value else {
+    return RawTaskDefinition::from_value(value);
+  };
+  if obj.contains_key("extends") || obj.contains_key("definition") {
+    return RawTaskDefinition::from_value(value);
+  }
+  let tasks = match obj.get("tasks") {
+    Some(serde_json::Value::Object(tasks)) => tasks,
+    _ => obj,
+  };
+  RawTaskDefinition::from_value(tasks.get(task_name)?)
+}
+
 struct RunTaskOptions<'a> {
   task_name: &'a str,
   script: &'a str,
@@ -164,6 +407,9 @@ struct RunTaskOptions<'a> {
   custom_commands: HashMap<String, Rc<dyn ShellCommand>>,
   npm_resolver: &'a dyn CliNpmResolver,
   cli_options: &'a CliOptions,
+  /// Expand the environment variables referenced by the command of a
+  /// deno.json task, allowed with `--allow-env-in-config`.
+  expand_env_vars: bool,
 }
 
 async fn run_task(opts: RunTaskOptions<'_>) -> Result<i32, AnyError> {
@@ -171,20 +417,33 @@ async fn run_task(opts: RunTaskOptions<'_>) -> Result<i32, AnyError> {
     task_name,
     script,
     cwd,
-    env_vars,
+    mut env_vars,
     custom_commands,
     npm_resolver,
     cli_options,
+    expand_env_vars,
   } = opts;
 
+  // print the command as it's written in the config file
   output_task(
     opts.task_name,
     &task_runner::get_script_with_args(script, cli_options.argv()),
   );
 
+  let script = if expand_env_vars {
+    let (script, expanded_env_vars) =
+      crate::args::expand_task_env_vars(script, |name| {
+        std::env::var(name).ok()
+      })?;
+    env_vars.extend(expanded_env_vars);
+    Cow::Owned(script)
+  } else {
+    Cow::Borrowed(script)
+  };
+
   task_runner::run_task(task_runner::RunTaskOptions {
     task_name,
-    script,
+    script: &script,
     cwd,
     env_vars,
     custom_commands,
@@ -204,6 +463,36 @@ fn output_task(task_name: &str, script: &str) {
   );
 }
 
+fn print_available_raw_tasks(
+  writer: &mut dyn std::io::Write,
+  raw_tasks: &[RawTask],
+) -> Result<(), std::io::Error> {
+  writeln!(writer, "{}", colors::green("Available tasks:"))?;
+  let mut seen_task_names = HashSet::with_capacity(raw_tasks.len());
+  for task in raw_tasks {
+    if !seen_task_names.insert(&task.name) {
+      continue; // already seen
+    }
+    writeln!(
+      writer,
+      "- {}{}",
+      colors::cyan(&task.name),
+      if task.is_root {
+        format!(" {}", colors::italic_gray("(workspace)"))
+      } else {
+        "".to_string()
+      }
+    )?;
+    match &task.definition {
+      RawTaskDefinition::Command(command) => writeln!(writer, "    {command}")?,
+      RawTaskDefinition::Extends(specifier) => {
+        writeln!(writer, "    {} {specifier}", colors::italic_gray("extends"))?
+      }
+    }
+  }
+  Ok(())
+}
+
 fn print_available_tasks(
   writer: &mut dyn std::io::Write,
   workspace_dir: &Arc<WorkspaceDirectory>,
@@ -295,3 +584,39 @@ fn print_available_tasks(
 
   Ok(())
 }
+
+#[cfg(test)]
+mod test {
+  use deno_core::serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_lookup_shared_task() {
+    let command =
+      RawTaskDefinition::Command("deno run -A build.ts".to_string());
+    assert_eq!(
+      lookup_shared_task(&json!("deno run -A build.ts"), "build"),
+      Some(command.clone())
+    );
+    assert_eq!(
+      lookup_shared_task(
+        &json!({ "tasks": { "build": "deno run -A build.ts" } }),
+        "build"
+      ),
+      Some(command.clone())
+    );
+    assert_eq!(
+      lookup_shared_task(&json!({ "build": "deno run -A build.ts" }), "build"),
+      Some(command)
+    );
+    assert_eq!(
+      lookup_shared_task(&json!({ "extends": "./other.json" }), "build"),
+      Some(RawTaskDefinition::Extends("./other.json".to_string()))
+    );
+    assert_eq!(
+      lookup_shared_task(&json!({ "tasks": { "lint": "deno lint" } }), "build"),
+      None
+    );
+  }
+}
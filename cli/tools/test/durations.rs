@@ -0,0 +1,133 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use deno_core::error::AnyError;
+use deno_core::serde_json;
+use deno_core::ModuleSpecifier;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::cache::CACHE_PERM;
+use crate::util::fs::atomic_write_file_with_retries;
+
+/// Durations in milliseconds of the test modules of previous runs of a
+/// workspace, which are used to run the slowest modules first when running
+/// in parallel, so that a worker doesn't end up running a slow module alone
+/// at the end.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TestDurations(HashMap<String, u64>);
+
+impl TestDurations {
+  pub fn read(path: &Path) -> Self {
+    std::fs::read_to_string(path)
+      .ok()
+      .and_then(|text| serde_json::from_str(&text).ok())
+      .unwrap_or_default()
+  }
+
+  /// Writes the durations atomically, so that concurrent runs don't corrupt
+  /// the file, after dropping the ones of modules which no longer exist.
+  pub fn write(&mut self, path: &Path) {
+    self.prune();
+    let result = serde_json::to_string(self)
+      .map_err(AnyError::from)
+      .and_then(|text| {
+        if let Some(parent) = path.parent() {
+          std::fs::create_dir_all(parent)?;
+        }
+        Ok(atomic_write_file_with_retries(path, text, CACHE_PERM)?)
+      });
+    if let Err(err) = result {
+      log::debug!("Failed writing {}: {:#}", path.display(), err);
+    }
+  }
+
+  pub fn record(&mut self, specifier: &ModuleSpecifier, duration: Duration) {
+    self
+      .0
+      .insert(specifier.to_string(), duration.as_millis() as u64);
+  }
+
+  /// Drops the durations of local modules which were removed or renamed.
+  fn prune(&mut self) {
+    self.0.retain(|specifier, _| {
+      match ModuleSpecifier::parse(specifier)
+        .ok()
+        .filter(|specifier| specifier.scheme() == "file")
+        .and_then(|specifier| specifier.to_file_path().ok())
+      {
+        Some(path) => path.exists(),
+        None => true,
+      }
+    });
+  }
+
+  /// Sorts the specifiers from the slowest to the fastest module. Modules
+  /// without a recorded duration go first, because they might be slow.
+  pub fn sort_longest_first(&self, specifiers: &mut [ModuleSpecifier]) {
+    specifiers.sort_by_key(|specifier| {
+      Reverse(self.0.get(specifier.as_str()).copied().unwrap_or(u64::MAX))
+    });
+  }
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_sort_longest_first() {
+    let mut durations = TestDurations::default();
+    let specifier = |name: &str| {
+      ModuleSpecifier::parse(&format!("file:///{name}_test.ts")).unwrap()
+    };
+    durations.record(&specifier("fast"), Duration::from_millis(10));
+    durations.record(&specifier("slow"), Duration::from_secs(3));
+    durations.record(&specifier("medium"), Duration::from_millis(500));
+    let mut specifiers = vec![
+      specifier("fast"),
+      specifier("medium"),
+      specifier("new"),
+      specifier("slow"),
+    ];
+    durations.sort_longest_first(&mut specifiers);
+    assert_eq!(
+      specifiers,
+      vec![
+        specifier("new"),
+        specifier("slow"),
+        specifier("medium"),
+        specifier("fast"),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_write_prunes_missing_modules() {
+    let temp_dir = test_util::TempDir::new();
+    let existing = temp_dir.path().join("existing_test.ts");
+    existing.write("");
+    let existing = ModuleSpecifier::from_file_path(existing).unwrap();
+    let removed =
+      ModuleSpecifier::from_file_path(temp_dir.path().join("removed_test.ts"))
+        .unwrap();
+    let remote =
+      ModuleSpecifier::parse("https://deno.land/x/a_test.ts").unwrap();
+    let mut durations = TestDurations::default();
+    durations.record(&existing, Duration::from_millis(10));
+    durations.record(&removed, Duration::from_millis(10));
+    durations.record(&remote, Duration::from_millis(10));
+    let path = temp_dir.path().join("durations").join("workspace.json");
+    durations.write(path.as_path());
+    let mut names = TestDurations::read(path.as_path())
+      .0
+      .into_keys()
+      .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec![existing.to_string(), remote.to_string()]);
+  }
+}
@@ -4,6 +4,7 @@ use deno_core::stats::RuntimeActivity;
 use deno_core::stats::RuntimeActivityDiff;
 use deno_core::stats::RuntimeActivityTrace;
 use deno_core::stats::RuntimeActivityType;
+use deno_runtime::deno_io::redact;
 use phf::phf_map;
 use std::borrow::Cow;
 use std::ops::AddAssign;
@@ -88,9 +89,9 @@ pub fn format_test_error(
     .trim_start_matches("Uncaught ")
     .to_string();
   if options.hide_stacktraces {
-    return js_error.exception_message;
+    return redact(&js_error.exception_message).into_owned();
   }
-  format_js_error(&js_error)
+  redact(&format_js_error(&js_error)).into_owned()
 }
 
 pub fn format_sanitizer_diff(
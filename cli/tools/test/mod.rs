@@ -1,15 +1,24 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
 use crate::args::CliOptions;
+use crate::args::CoverageFlags;
+use crate::args::CoverageType;
+use crate::args::FileFlags;
 use crate::args::Flags;
+use crate::args::TestFixtures;
 use crate::args::TestFlags;
+use crate::args::TestOtelOptions;
 use crate::args::TestReporterConfig;
+use crate::args::TestReporterOutput;
+use crate::args::DEFAULT_COVERAGE_EXCLUDE;
+use crate::args::DEFAULT_COVERAGE_INCLUDE;
 use crate::colors;
 use crate::display;
 use crate::factory::CliFactory;
 use crate::file_fetcher::File;
 use crate::file_fetcher::FileFetcher;
 use crate::graph_util::has_graph_root_local_dependent_changed;
+use crate::http_util::HttpClientProvider;
 use crate::ops;
 use crate::util::extract::extract_doc_tests;
 use crate::util::file_watcher;
@@ -34,6 +43,7 @@ use deno_core::futures::stream;
 use deno_core::futures::FutureExt;
 use deno_core::futures::StreamExt;
 use deno_core::located_script_name;
+use deno_core::serde_json;
 use deno_core::serde_v8;
 use deno_core::stats::RuntimeActivity;
 use deno_core::stats::RuntimeActivityDiff;
@@ -48,6 +58,7 @@ use deno_core::v8;
 use deno_core::ModuleSpecifier;
 use deno_core::OpState;
 use deno_core::PollEventLoopOptions;
+use deno_path_util::url_to_file_path;
 use deno_runtime::deno_io::Stdio;
 use deno_runtime::deno_io::StdioPipe;
 use deno_runtime::deno_permissions::Permissions;
@@ -77,6 +88,7 @@ use std::future::poll_fn;
 use std::io::Write;
 use std::num::NonZeroUsize;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
@@ -85,8 +97,10 @@ use std::task::Poll;
 use std::time::Duration;
 use std::time::Instant;
 use tokio::signal;
+use tokio::sync::Notify;
 
 mod channel;
+mod durations;
 pub mod fmt;
 pub mod reporters;
 
@@ -95,11 +109,14 @@ pub use channel::create_test_event_channel;
 pub use channel::TestEventReceiver;
 pub use channel::TestEventSender;
 pub use channel::TestEventWorkerSender;
+use durations::TestDurations;
 use fmt::format_sanitizer_diff;
 pub use fmt::format_test_error;
 use reporters::CompoundTestReporter;
 use reporters::DotTestReporter;
+use reporters::JsonTestReporter;
 use reporters::JunitTestReporter;
+use reporters::OtelTestReporter;
 use reporters::PrettyTestReporter;
 use reporters::TapTestReporter;
 use reporters::TestReporter;
@@ -168,6 +185,9 @@ pub struct TestFilter {
   pub regex: Option<Regex>,
   pub include: Option<Vec<String>>,
   pub exclude: Vec<String>,
+  /// When not empty, only the tests which have one of these tags run.
+  pub tags: Vec<String>,
+  pub skip_tags: Vec<String>,
 }
 
 impl TestFilter {
@@ -193,6 +213,27 @@ impl TestFilter {
     true
   }
 
+  pub fn includes_tags(&self, tags: &[String]) -> bool {
+    if !self.tags.is_empty() && !tags.iter().any(|t| self.tags.contains(t)) {
+      return false;
+    }
+    !tags.iter().any(|t| self.skip_tags.contains(t))
+  }
+
+  pub fn with_tags(
+    mut self,
+    tags: Vec<String>,
+    skip_tags: Vec<String>,
+  ) -> Self {
+    self.tags = tags;
+    self.skip_tags = skip_tags;
+    self
+  }
+
+  pub fn has_tags(&self) -> bool {
+    !self.tags.is_empty() || !self.skip_tags.is_empty()
+  }
+
   pub fn from_flag(flag: &Option<String>) -> Self {
     let mut substring = None;
     let mut regex = None;
@@ -277,6 +318,7 @@ pub struct TestDescription {
   pub location: TestLocation,
   pub sanitize_ops: bool,
   pub sanitize_resources: bool,
+  pub tags: Vec<String>,
 }
 
 /// May represent a failure of a test or test step.
@@ -313,6 +355,7 @@ pub enum TestFailure {
   FailedSteps(usize),
   IncompleteSteps,
   Leaked(Vec<String>, Vec<String>), // Details, trailer notes
+  TimedOut(u64, Vec<String>, Vec<String>), // Timeout in ms, pending activity details, trailer notes
   // The rest are for steps only.
   Incomplete,
   OverlapsWithSanitizers(IndexSet<String>), // Long names of overlapped tests
@@ -349,6 +392,20 @@ impl TestFailure {
         }
         Cow::Owned(f)
       }
+      TestFailure::TimedOut(timeout, details, trailer_notes) => {
+        let mut f = String::new();
+        write!(f, "Test timed out after {}ms.", timeout).unwrap();
+        if !details.is_empty() {
+          write!(f, " Pending async activity:").unwrap();
+        }
+        for detail in details {
+          write!(f, "\n  - {}", detail).unwrap();
+        }
+        for trailer in trailer_notes {
+          write!(f, "\n{}", trailer).unwrap();
+        }
+        Cow::Owned(f)
+      }
       TestFailure::OverlapsWithSanitizers(long_names) => {
         let mut f = String::new();
         write!(f, "Started test step while another test step with sanitizers was running:").unwrap();
@@ -378,6 +435,9 @@ impl TestFailure {
       }
       TestFailure::Incomplete => "Didn't complete before parent".to_string(),
       TestFailure::Leaked(_, _) => "Leaks detected".to_string(),
+      TestFailure::TimedOut(timeout, _, _) => {
+        format!("Test timed out after {timeout}ms")
+      }
       TestFailure::OverlapsWithSanitizers(_) => {
         "Started test step while another test step with sanitizers was running"
           .to_string()
@@ -425,6 +485,8 @@ pub enum TestResult {
   Ignored,
   Failed(TestFailure),
   Cancelled,
+  /// The test wasn't started because `--fail-fast` stopped the run.
+  Skipped,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Deserialize)]
@@ -471,7 +533,11 @@ pub enum TestEvent {
   Wait(usize),
   Output(Vec<u8>),
   Slow(usize, u64),
+  /// The artifacts directory of a test or step, once it's created.
+  Artifacts(usize, PathBuf),
   Result(usize, TestResult, u64),
+  /// A test file which wasn't started because `--fail-fast` stopped the run.
+  SkippedFile(ModuleSpecifier),
   UncaughtError(String, Box<JsError>),
   StepRegister(TestStepDescription),
   StepWait(usize),
@@ -509,6 +575,10 @@ pub struct TestSummary {
   pub passed: usize,
   pub failed: usize,
   pub ignored: usize,
+  #[serde(default)]
+  pub skipped: usize,
+  #[serde(default)]
+  pub skipped_files: usize,
   pub passed_steps: usize,
   pub failed_steps: usize,
   pub ignored_steps: usize,
@@ -516,6 +586,9 @@ pub struct TestSummary {
   pub measured: usize,
   pub failures: Vec<(TestFailureDescription, TestFailure)>,
   pub uncaught_errors: Vec<(String, Box<JsError>)>,
+  /// The artifacts directories of the tests and steps, by their ids.
+  #[serde(default)]
+  pub artifacts: HashMap<usize, PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -528,7 +601,13 @@ struct TestSpecifiersOptions {
   specifier: TestSpecifierOptions,
   reporter: TestReporterConfig,
   junit_path: Option<String>,
+  reporter_outputs: Vec<TestReporterOutput>,
   hide_stacktraces: bool,
+  otel: Option<TestOtelOptions>,
+  http_client_provider: Arc<HttpClientProvider>,
+  slow_threshold: Option<Duration>,
+  /// File with the durations of the test modules of previous runs.
+  durations_path: Option<PathBuf>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -536,6 +615,68 @@ pub struct TestSpecifierOptions {
   pub shuffle: Option<u64>,
   pub filter: TestFilter,
   pub trace_leaks: bool,
+  /// The `--setup` and `--teardown` modules run in the worker of the test
+  /// module.
+  pub fixtures: TestFixtures,
+  pub update_snapshots: bool,
+  pub timeout: Option<Duration>,
+  pub artifacts_dir: Option<TestArtifactsDir>,
+}
+
+/// The `--artifacts-dir`, in which each test gets a directory for its
+/// screenshots, logs and other files as `t.artifactsDir`.
+#[derive(Debug, Clone)]
+pub struct TestArtifactsDir {
+  root: PathBuf,
+  cwd: PathBuf,
+}
+
+impl TestArtifactsDir {
+  pub fn new(root: &Path, cwd: &Path) -> Self {
+    Self {
+      root: cwd.join(root),
+      cwd: cwd.to_path_buf(),
+    }
+  }
+
+  /// Returns the directory of a test, which is namespaced by the path of its
+  /// module and the names of the test and the steps leading to it.
+  pub fn path(&self, origin: &ModuleSpecifier, names: &[String]) -> PathBuf {
+    let module = match url_to_file_path(origin) {
+      Ok(path) => path
+        .strip_prefix(&self.cwd)
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| path.to_string_lossy().into_owned()),
+      Err(_) => origin.to_string(),
+    };
+    let mut path = self.root.join(artifacts_path_segment(&module));
+    for name in names {
+      path.push(artifacts_path_segment(name));
+    }
+    path
+  }
+}
+
+/// Replaces the characters of a name which aren't safe in a file name on all
+/// platforms.
+fn artifacts_path_segment(name: &str) -> String {
+  const MAX_LEN: usize = 100;
+  let segment = name
+    .chars()
+    .map(|c| {
+      if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+        c
+      } else {
+        '_'
+      }
+    })
+    .take(MAX_LEN)
+    .collect::<String>();
+  if segment.is_empty() || segment.chars().all(|c| c == '.') {
+    "_".to_string()
+  } else {
+    segment
+  }
 }
 
 impl TestSummary {
@@ -545,6 +686,8 @@ impl TestSummary {
       passed: 0,
       failed: 0,
       ignored: 0,
+      skipped: 0,
+      skipped_files: 0,
       passed_steps: 0,
       failed_steps: 0,
       ignored_steps: 0,
@@ -552,6 +695,7 @@ impl TestSummary {
       measured: 0,
       failures: Vec::new(),
       uncaught_errors: Vec::new(),
+      artifacts: HashMap::new(),
     }
   }
 
@@ -570,14 +714,17 @@ fn get_test_reporter(options: &TestSpecifiersOptions) -> Box<dyn TestReporter> {
       options.cwd.clone(),
       failure_format_options,
     )),
-    TestReporterConfig::Pretty => Box::new(PrettyTestReporter::new(
-      parallel,
-      options.log_level != Some(Level::Error),
-      options.filter,
-      false,
-      options.cwd.clone(),
-      failure_format_options,
-    )),
+    TestReporterConfig::Pretty => Box::new(
+      PrettyTestReporter::new(
+        parallel,
+        options.log_level != Some(Level::Error),
+        options.filter,
+        false,
+        options.cwd.clone(),
+        failure_format_options,
+      )
+      .with_slow_threshold(options.slow_threshold),
+    ),
     TestReporterConfig::Junit => Box::new(JunitTestReporter::new(
       options.cwd.clone(),
       "-".to_string(),
@@ -588,20 +735,60 @@ fn get_test_reporter(options: &TestSpecifiersOptions) -> Box<dyn TestReporter> {
       options.concurrent_jobs > NonZeroUsize::new(1).unwrap(),
       failure_format_options,
     )),
+    TestReporterConfig::Json => Box::new(JsonTestReporter::new(
+      options.cwd.clone(),
+      "-".to_string(),
+      failure_format_options,
+    )),
   };
 
+  let mut reporters = vec![reporter];
+
   if let Some(junit_path) = &options.junit_path {
-    let junit = Box::new(JunitTestReporter::new(
+    reporters.push(Box::new(JunitTestReporter::new(
       options.cwd.clone(),
       junit_path.to_string(),
       TestFailureFormatOptions {
         hide_stacktraces: options.hide_stacktraces,
       },
-    ));
-    return Box::new(CompoundTestReporter::new(vec![reporter, junit]));
+    )));
   }
 
-  reporter
+  for output in &options.reporter_outputs {
+    let failure_format_options = TestFailureFormatOptions {
+      hide_stacktraces: options.hide_stacktraces,
+    };
+    reporters.push(match output.reporter {
+      TestReporterConfig::Junit => Box::new(JunitTestReporter::new(
+        options.cwd.clone(),
+        output.path.clone(),
+        failure_format_options,
+      )),
+      TestReporterConfig::Json => Box::new(JsonTestReporter::new(
+        options.cwd.clone(),
+        output.path.clone(),
+        failure_format_options,
+      )),
+      // only these can write to files, which is checked when parsing
+      TestReporterConfig::Pretty
+      | TestReporterConfig::Dot
+      | TestReporterConfig::Tap => unreachable!(),
+    });
+  }
+
+  if let Some(otel) = &options.otel {
+    reporters.push(Box::new(OtelTestReporter::new(
+      options.cwd.clone(),
+      otel.traces_endpoint.clone(),
+      otel.service_name.clone(),
+      options.http_client_provider.clone(),
+    )));
+  }
+
+  if reporters.len() == 1 {
+    return reporters.pop().unwrap();
+  }
+  Box::new(CompoundTestReporter::new(reporters))
 }
 
 async fn configure_main_worker(
@@ -616,7 +803,11 @@ async fn configure_main_worker(
       WorkerExecutionMode::Test,
       specifier.clone(),
       permissions_container,
-      vec![ops::testing::deno_test::init_ops(worker_sender.sender)],
+      vec![ops::testing::deno_test::init_ops(
+        worker_sender.sender,
+        options.update_snapshots,
+        options.artifacts_dir.clone(),
+      )],
       Stdio {
         stdin: StdioPipe::inherit(),
         stdout: StdioPipe::file(worker_sender.stdout),
@@ -665,6 +856,8 @@ pub async fn test_specifier(
   options: TestSpecifierOptions,
 ) -> Result<(), AnyError> {
   if fail_fast_tracker.should_stop() {
+    let mut sender = worker_sender.sender;
+    sender.send(TestEvent::SkippedFile(specifier))?;
     return Ok(());
   }
   let (coverage_collector, mut worker) = configure_main_worker(
@@ -675,6 +868,11 @@ pub async fn test_specifier(
     &options,
   )
   .await?;
+  worker
+    .js_runtime
+    .op_state()
+    .borrow_mut()
+    .put(fail_fast_tracker.clone());
 
   match test_specifier_inner(
     &mut worker,
@@ -719,8 +917,29 @@ async fn test_specifier_inner(
 
   worker.dispatch_load_event()?;
 
-  run_tests_for_worker(worker, &specifier, &options, &fail_fast_tracker)
-    .await?;
+  if let Some(setup) = &options.fixtures.setup {
+    run_test_fixture(worker, setup, true).await?;
+  }
+  let result =
+    run_tests_for_worker(worker, &specifier, &options, &fail_fast_tracker)
+      .await;
+  // tear down regardless of the test results, so that resources of the setup
+  // aren't left behind, and report a failure next to the results
+  if let Some(teardown) = &options.fixtures.teardown {
+    if let Err(error) = run_test_fixture(worker, teardown, false).await {
+      if !error.is::<JsError>() {
+        return Err(error);
+      }
+      send_test_event(
+        &worker.js_runtime.op_state(),
+        TestEvent::UncaughtError(
+          specifier.to_string(),
+          Box::new(error.downcast::<JsError>().unwrap()),
+        ),
+      )?;
+    }
+  }
+  result?;
 
   // Ignore `defaultPrevented` of the `beforeunload` event. We don't allow the
   // event loop to continue beyond what's needed to await results.
@@ -751,6 +970,30 @@ async fn test_specifier_inner(
   Ok(())
 }
 
+/// Runs the default export of a `--setup` or `--teardown` module in the
+/// worker of a test module. The value the setup function resolves to is
+/// returned by `Deno.test.getSetupData()` and passed to the teardown function.
+async fn run_test_fixture(
+  worker: &mut MainWorker,
+  specifier: &ModuleSpecifier,
+  is_setup: bool,
+) -> Result<(), AnyError> {
+  let script = format!(
+    "import({}).then((mod) => Deno[Deno.internal].runTestFixture(mod, {}))",
+    serde_json::to_string(specifier.as_str())?,
+    is_setup,
+  );
+  let promise = worker
+    .js_runtime
+    .execute_script(located_script_name!(), script)?;
+  let resolve = worker.js_runtime.resolve(promise);
+  worker
+    .js_runtime
+    .with_event_loop_promise(resolve, PollEventLoopOptions::default())
+    .await?;
+  Ok(())
+}
+
 pub fn worker_has_tests(worker: &mut MainWorker) -> bool {
   let state_rc = worker.js_runtime.op_state();
   let state = state_rc.borrow();
@@ -830,7 +1073,9 @@ async fn run_tests_for_worker_inner(
   let mut tests_to_run = Vec::with_capacity(tests.len());
   let mut used_only = false;
   for ((_, d), f) in tests.tests.iter().zip(test_functions) {
-    if !options.filter.includes(&d.name) {
+    if !options.filter.includes(&d.name)
+      || !options.filter.includes_tags(&d.tags)
+    {
       continue;
     }
 
@@ -876,6 +1121,10 @@ async fn run_tests_for_worker_inner(
     .iter()
     .position(|op| *op == "op_host_recv_ctrl")
     .unwrap();
+  let op_id_test_wait_for_fail_fast = ops
+    .iter()
+    .position(|op| *op == "op_test_wait_for_fail_fast")
+    .unwrap();
 
   // For consistency between tests with and without sanitizers, we _always_ include
   // the actual sanitizer capture before and after a test, but a test that ignores resource
@@ -886,6 +1135,7 @@ async fn run_tests_for_worker_inner(
   filter = filter.with_timers();
   filter = filter.omit_op(op_id_host_recv_ctrl as _);
   filter = filter.omit_op(op_id_host_recv_message as _);
+  filter = filter.omit_op(op_id_test_wait_for_fail_fast as _);
 
   // Count the top-level stats so we can filter them out if they complete and restart within
   // a test.
@@ -900,8 +1150,14 @@ async fn run_tests_for_worker_inner(
   }
 
   for (desc, function) in tests_to_run.into_iter() {
+    // report the tests which won't run, instead of leaving them out of the
+    // reports
     if fail_fast_tracker.should_stop() {
-      break;
+      send_test_event(
+        &state_rc,
+        TestEvent::Result(desc.id, TestResult::Skipped, 0),
+      )?;
+      continue;
     }
 
     // Each test needs a fresh reqwest connection pool to avoid inter-test weirdness with connections
@@ -975,8 +1231,38 @@ async fn run_tests_for_worker_inner(
 
     let result = worker
       .js_runtime
-      .with_event_loop_promise(call, PollEventLoopOptions::default())
-      .await;
+      .with_event_loop_promise(call, PollEventLoopOptions::default());
+    let result = match options.timeout {
+      Some(timeout) => match tokio::time::timeout(timeout, result).await {
+        Ok(result) => result,
+        Err(_) => {
+          slow_test_warning.abort();
+          // report the async activity the test is still waiting on
+          let after = stats.clone().capture(&filter);
+          let diff = RuntimeActivityStats::diff(&before, &after);
+          let (formatted, trailer_notes) = format_sanitizer_diff(diff);
+          let timeout = timeout.as_millis() as u64;
+          fail_fast_tracker.add_failure();
+          send_test_event(
+            &state_rc,
+            TestEvent::Result(
+              desc.id,
+              TestResult::Failed(TestFailure::TimedOut(
+                timeout,
+                formatted,
+                trailer_notes,
+              )),
+              timeout,
+            ),
+          )?;
+          // the test keeps running in the background, so the rest of the
+          // module can't be run reliably
+          had_uncaught_error = true;
+          continue;
+        }
+      },
+      None => result.await,
+    };
     slow_test_warning.abort();
     let result = match result {
       Ok(r) => r,
@@ -1205,6 +1491,21 @@ async fn test_specifiers(
     specifiers
   };
 
+  let durations_path = options.durations_path.clone();
+  let mut durations = durations_path
+    .as_deref()
+    .map(TestDurations::read)
+    .unwrap_or_default();
+  let specifiers = if options.concurrent_jobs.get() > 1
+    && options.specifier.shuffle.is_none()
+  {
+    let mut specifiers = specifiers;
+    durations.sort_longest_first(&mut specifiers);
+    specifiers
+  } else {
+    specifiers
+  };
+
   let (test_event_sender_factory, receiver) = create_test_event_channel();
   let concurrent_jobs = options.concurrent_jobs;
 
@@ -1227,26 +1528,39 @@ async fn test_specifiers(
     let fail_fast_tracker = fail_fast_tracker.clone();
     let specifier_options = options.specifier.clone();
     spawn_blocking(move || {
+      let start = Instant::now();
       create_and_run_current_thread(test_specifier(
         worker_factory,
         permissions_container,
-        specifier,
+        specifier.clone(),
         worker_sender,
         fail_fast_tracker,
         specifier_options,
       ))
+      .map(|()| (specifier, start.elapsed()))
     })
   });
 
   let join_stream = stream::iter(join_handles)
     .buffer_unordered(concurrent_jobs.get())
-    .collect::<Vec<Result<Result<(), AnyError>, tokio::task::JoinError>>>();
+    .collect::<Vec<
+      Result<
+        Result<(ModuleSpecifier, Duration), AnyError>,
+        tokio::task::JoinError,
+      >,
+    >>();
 
   let handler = spawn(async move { report_tests(receiver, reporter).await.0 });
 
   let (join_results, result) = future::join(join_stream, handler).await;
   sigint_handler_handle.abort();
   HAS_TEST_RUN_SIGINT_HANDLER.store(false, Ordering::Relaxed);
+  if let Some(durations_path) = &durations_path {
+    for (specifier, duration) in join_results.iter().flatten().flatten() {
+      durations.record(specifier, *duration);
+    }
+    durations.write(durations_path);
+  }
   for join_result in join_results {
     join_result??;
   }
@@ -1299,6 +1613,9 @@ pub async fn report_tests(
       TestEvent::Slow(id, elapsed) => {
         reporter.report_slow(tests.get(&id).unwrap(), elapsed);
       }
+      TestEvent::Artifacts(id, path) => {
+        reporter.report_artifacts(id, &path);
+      }
       TestEvent::Result(id, result, elapsed) => {
         if tests_with_result.insert(id) {
           match result {
@@ -1310,6 +1627,9 @@ pub async fn report_tests(
           reporter.report_result(tests.get(&id).unwrap(), &result, elapsed);
         }
       }
+      TestEvent::SkippedFile(specifier) => {
+        reporter.report_skipped_file(&specifier);
+      }
       TestEvent::UncaughtError(origin, error) => {
         failed = true;
         reporter.report_uncaught_error(&origin, error);
@@ -1546,6 +1866,7 @@ pub async fn run_tests(
   let cli_options = factory.cli_options()?;
   let workspace_test_options =
     cli_options.resolve_workspace_test_options(&test_flags);
+  let fixtures = cli_options.resolve_test_fixtures(&test_flags)?;
   let file_fetcher = factory.file_fetcher()?;
   // Various test files should not share the same permissions in terms of
   // `PermissionsContainer` - otherwise granting/revoking permissions in one
@@ -1595,9 +1916,14 @@ pub async fn run_tests(
 
   let worker_factory =
     Arc::new(factory.create_cli_main_worker_factory().await?);
+  let test_filter = TestFilter::from_flag(&workspace_test_options.filter)
+    .with_tags(
+      workspace_test_options.tags.clone(),
+      workspace_test_options.skip_tags.clone(),
+    );
 
   // Run tests
-  test_specifiers(
+  let result = test_specifiers(
     worker_factory,
     &permissions,
     permission_desc_parser,
@@ -1614,20 +1940,69 @@ pub async fn run_tests(
       concurrent_jobs: workspace_test_options.concurrent_jobs,
       fail_fast: workspace_test_options.fail_fast,
       log_level,
-      filter: workspace_test_options.filter.is_some(),
+      filter: workspace_test_options.filter.is_some() || test_filter.has_tags(),
       reporter: workspace_test_options.reporter,
       junit_path: workspace_test_options.junit_path,
+      reporter_outputs: workspace_test_options.reporter_outputs,
+      otel: workspace_test_options.otel,
+      http_client_provider: factory.http_client_provider().clone(),
       hide_stacktraces: workspace_test_options.hide_stacktraces,
+      slow_threshold: workspace_test_options.slow_threshold,
+      durations_path: Some(
+        factory
+          .deno_dir()?
+          .test_durations_file_path(cli_options.workspace().root_dir()),
+      ),
       specifier: TestSpecifierOptions {
-        filter: TestFilter::from_flag(&workspace_test_options.filter),
+        filter: test_filter,
         shuffle: workspace_test_options.shuffle,
         trace_leaks: workspace_test_options.trace_leaks,
+        fixtures,
+        update_snapshots: workspace_test_options.update_snapshots,
+        timeout: workspace_test_options.timeout,
+        artifacts_dir: workspace_test_options
+          .artifacts_dir
+          .as_ref()
+          .map(|dir| TestArtifactsDir::new(dir, cli_options.initial_cwd())),
       },
     },
   )
-  .await?;
+  .await;
 
-  Ok(())
+  // Report the coverage of the run, even when some tests failed, but return
+  // the failure of the tests rather than the one of the report.
+  if !test_flags.coverage_report.is_empty() {
+    if let Some(coverage_dir) = cli_options.coverage_dir() {
+      let report_result =
+        report_coverage(&factory, coverage_dir, &test_flags.coverage_report)
+          .await;
+      result?;
+      return report_result;
+    }
+  }
+
+  result
+}
+
+async fn report_coverage(
+  factory: &CliFactory,
+  coverage_dir: String,
+  types: &[CoverageType],
+) -> Result<(), AnyError> {
+  let output = types
+    .contains(&CoverageType::Lcov)
+    .then(|| format!("{coverage_dir}/lcov.info"));
+  let coverage_flags = CoverageFlags {
+    files: FileFlags {
+      include: vec![coverage_dir],
+      ignore: vec![],
+    },
+    output,
+    include: vec![DEFAULT_COVERAGE_INCLUDE.to_string()],
+    exclude: vec![DEFAULT_COVERAGE_EXCLUDE.to_string()],
+    ..Default::default()
+  };
+  crate::tools::coverage::report_coverages(factory, coverage_flags, types).await
 }
 
 pub async fn run_tests_with_watch(
@@ -1667,6 +2042,7 @@ pub async fn run_tests_with_watch(
         let cli_options = factory.cli_options()?;
         let workspace_test_options =
           cli_options.resolve_workspace_test_options(&test_flags);
+        let fixtures = cli_options.resolve_test_fixtures(&test_flags)?;
 
         let _ = watcher_communicator.watch_paths(cli_options.watch_paths());
         let graph_kind = cli_options.type_check_mode().as_graph_kind();
@@ -1718,7 +2094,10 @@ pub async fn run_tests_with_watch(
         module_graph_creator.graph_valid(&graph)?;
         let test_modules = &graph.roots;
 
-        let test_modules_to_reload = if let Some(changed_paths) = changed_paths
+        // only re-run the test modules which depend on a changed file,
+        // unless all of them are requested with `--watch-all`
+        let test_modules_to_reload = if let Some(changed_paths) =
+          changed_paths.filter(|_| !test_flags.watch_all)
         {
           let mut result = IndexSet::with_capacity(test_modules.len());
           let changed_paths = changed_paths.into_iter().collect::<HashSet<_>>();
@@ -1772,6 +2151,11 @@ pub async fn run_tests_with_watch(
 
         let worker_factory =
           Arc::new(factory.create_cli_main_worker_factory().await?);
+        let test_filter = TestFilter::from_flag(&workspace_test_options.filter)
+          .with_tags(
+            workspace_test_options.tags.clone(),
+            workspace_test_options.skip_tags.clone(),
+          );
 
         test_specifiers(
           worker_factory,
@@ -1790,14 +2174,30 @@ pub async fn run_tests_with_watch(
             concurrent_jobs: workspace_test_options.concurrent_jobs,
             fail_fast: workspace_test_options.fail_fast,
             log_level,
-            filter: workspace_test_options.filter.is_some(),
+            filter: workspace_test_options.filter.is_some()
+              || test_filter.has_tags(),
             reporter: workspace_test_options.reporter,
             junit_path: workspace_test_options.junit_path,
+            reporter_outputs: workspace_test_options.reporter_outputs,
+            otel: workspace_test_options.otel,
+            http_client_provider: factory.http_client_provider().clone(),
             hide_stacktraces: workspace_test_options.hide_stacktraces,
+            slow_threshold: workspace_test_options.slow_threshold,
+            durations_path: Some(
+              factory
+                .deno_dir()?
+                .test_durations_file_path(cli_options.workspace().root_dir()),
+            ),
             specifier: TestSpecifierOptions {
-              filter: TestFilter::from_flag(&workspace_test_options.filter),
+              filter: test_filter,
               shuffle: workspace_test_options.shuffle,
               trace_leaks: workspace_test_options.trace_leaks,
+              fixtures,
+              update_snapshots: workspace_test_options.update_snapshots,
+              timeout: workspace_test_options.timeout,
+              artifacts_dir: workspace_test_options.artifacts_dir.as_ref().map(
+                |dir| TestArtifactsDir::new(dir, cli_options.initial_cwd()),
+              ),
             },
           },
         )
@@ -1850,6 +2250,7 @@ fn get_target_specifiers(
 pub struct FailFastTracker {
   max_count: Option<usize>,
   failure_count: Arc<AtomicUsize>,
+  stop_notify: Arc<Notify>,
 }
 
 impl FailFastTracker {
@@ -1857,20 +2258,42 @@ impl FailFastTracker {
     Self {
       max_count: fail_fast.map(|v| v.into()),
       failure_count: Default::default(),
+      stop_notify: Default::default(),
     }
   }
 
   pub fn add_failure(&self) -> bool {
     if let Some(max_count) = &self.max_count {
-      self
+      let count = self
         .failure_count
-        .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
-        >= *max_count
+        .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+      if count + 1 == *max_count {
+        self.stop_notify.notify_waiters();
+      }
+      count >= *max_count
     } else {
       false
     }
   }
 
+  /// Resolves once enough tests failed for the run to stop, so the tests
+  /// which are still running can be cancelled. Never resolves without
+  /// `--fail-fast`.
+  pub async fn stopped(&self) {
+    if self.max_count.is_none() {
+      return std::future::pending().await;
+    }
+    let notified = self.stop_notify.notified();
+    tokio::pin!(notified);
+    // register for the notification before checking, so a failure which is
+    // added in between isn't missed
+    notified.as_mut().enable();
+    if self.should_stop() {
+      return;
+    }
+    notified.await;
+  }
+
   pub fn should_stop(&self) -> bool {
     if let Some(max_count) = &self.max_count {
       self.failure_count.load(std::sync::atomic::Ordering::SeqCst) >= *max_count
@@ -1886,6 +2309,32 @@ mod inner_test {
 
   use super::*;
 
+  #[test]
+  fn test_artifacts_dir_path() {
+    let cwd = if cfg!(windows) {
+      PathBuf::from("C:\\project")
+    } else {
+      PathBuf::from("/project")
+    };
+    let artifacts_dir = TestArtifactsDir::new(Path::new("artifacts"), &cwd);
+    let origin =
+      ModuleSpecifier::from_file_path(cwd.join("e2e/login.ts")).unwrap();
+    assert_eq!(
+      artifacts_dir.path(
+        &origin,
+        &["logs in".to_string(), "shows the home page?".to_string()]
+      ),
+      cwd
+        .join("artifacts")
+        .join("e2e_login.ts")
+        .join("logs_in")
+        .join("shows_the_home_page_")
+    );
+    assert_eq!(artifacts_path_segment(".."), "_");
+    assert_eq!(artifacts_path_segment(""), "_");
+    assert_eq!(artifacts_path_segment(&"a".repeat(200)).len(), 100);
+  }
+
   #[test]
   fn test_is_supported_test_ext() {
     assert!(!is_supported_test_ext(Path::new("tests/subdir/redirects")));
@@ -1954,4 +2403,23 @@ mod inner_test {
     assert!(!is_supported_test_path(Path::new("notatest.js")));
     assert!(!is_supported_test_path(Path::new("NotAtest.ts")));
   }
+
+  #[tokio::test]
+  async fn fail_fast_tracker_stopped() {
+    let tracker = FailFastTracker::new(NonZeroUsize::new(2));
+    let stopped = tokio::spawn({
+      let tracker = tracker.clone();
+      async move { tracker.stopped().await }
+    });
+    tracker.add_failure();
+    tokio::task::yield_now().await;
+    assert!(!stopped.is_finished());
+    tracker.add_failure();
+    tokio::time::timeout(Duration::from_secs(5), stopped)
+      .await
+      .unwrap()
+      .unwrap();
+    // resolves immediately once the run is stopped
+    tracker.stopped().await;
+  }
 }
@@ -144,6 +144,15 @@ pub(super) fn report_summary(
             failure.format(options)
           )
           .unwrap();
+          if let Some(path) = summary.artifacts.get(&description.id) {
+            writeln!(
+              writer,
+              "{} {}",
+              colors::gray("artifacts:"),
+              path.display()
+            )
+            .unwrap();
+          }
           writeln!(writer).unwrap();
           failure_titles.push(failure_title);
         }
@@ -228,4 +237,32 @@ pub(super) fn report_summary(
     colors::gray(format!("({})", display::human_elapsed(elapsed.as_millis()))),
   )
   .unwrap();
+
+  if let Some(skipped) = skipped_text(summary) {
+    writeln!(writer, "{}", colors::gray(skipped)).unwrap();
+  }
+}
+
+/// Describes the tests and files which weren't run because `--fail-fast`
+/// stopped the run, e.g. "9 tests and 1 file skipped due to --fail-fast".
+fn skipped_text(summary: &TestSummary) -> Option<String> {
+  let count = |count: usize, noun: &str| match count {
+    0 => None,
+    1 => Some(format!("1 {noun}")),
+    count => Some(format!("{count} {noun}s")),
+  };
+  let counts = [
+    count(summary.skipped, "test"),
+    count(summary.skipped_files, "file"),
+  ]
+  .into_iter()
+  .flatten()
+  .collect::<Vec<_>>();
+  if counts.is_empty() {
+    return None;
+  }
+  Some(format!(
+    "{} skipped due to --fail-fast",
+    counts.join(" and ")
+  ))
 }
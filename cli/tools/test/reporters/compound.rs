@@ -37,6 +37,12 @@ impl TestReporter for CompoundTestReporter {
     }
   }
 
+  fn report_artifacts(&mut self, id: usize, path: &Path) {
+    for reporter in &mut self.test_reporters {
+      reporter.report_artifacts(id, path);
+    }
+  }
+
   fn report_output(&mut self, output: &[u8]) {
     for reporter in &mut self.test_reporters {
       reporter.report_output(output);
@@ -54,6 +60,12 @@ impl TestReporter for CompoundTestReporter {
     }
   }
 
+  fn report_skipped_file(&mut self, specifier: &ModuleSpecifier) {
+    for reporter in &mut self.test_reporters {
+      reporter.report_skipped_file(specifier);
+    }
+  }
+
   fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>) {
     for reporter in &mut self.test_reporters {
       reporter.report_uncaught_error(origin, error.clone());
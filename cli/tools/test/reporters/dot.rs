@@ -64,6 +64,7 @@ impl DotTestReporter {
       TestResult::Ignored => fmt_ignored(),
       TestResult::Failed(_failure) => fmt_failed(),
       TestResult::Cancelled => fmt_cancelled(),
+      TestResult::Skipped => fmt_ignored(),
     };
 
     self.print_status(status);
@@ -101,6 +102,9 @@ impl TestReporter for DotTestReporter {
   }
 
   fn report_slow(&mut self, _description: &TestDescription, _elapsed: u64) {}
+  fn report_artifacts(&mut self, id: usize, path: &Path) {
+    self.summary.artifacts.insert(id, path.to_path_buf());
+  }
   fn report_output(&mut self, _output: &[u8]) {}
 
   fn report_result(
@@ -126,11 +130,18 @@ impl TestReporter for DotTestReporter {
       TestResult::Cancelled => {
         self.summary.failed += 1;
       }
+      TestResult::Skipped => {
+        self.summary.skipped += 1;
+      }
     }
 
     self.print_test_result(result);
   }
 
+  fn report_skipped_file(&mut self, _specifier: &ModuleSpecifier) {
+    self.summary.skipped_files += 1;
+  }
+
   fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>) {
     self.summary.failed += 1;
     self
@@ -0,0 +1,373 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::fs::File;
+use std::io::BufWriter;
+
+use console_static_text::ansi::strip_ansi_codes;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+
+use super::fmt::format_test_error;
+use super::fmt::to_relative_path_or_remote_url;
+use super::*;
+
+/// Writes the test events as newline delimited JSON, for tools which follow
+/// a run while it's in progress.
+pub struct JsonTestReporter {
+  cwd: Url,
+  output_path: String,
+  writer: Option<Box<dyn Write>>,
+  /// The first error writing an event, which is returned when flushing.
+  write_error: Option<std::io::Error>,
+  summary: TestSummary,
+  failure_format_options: TestFailureFormatOptions,
+}
+
+impl JsonTestReporter {
+  pub fn new(
+    cwd: Url,
+    output_path: String,
+    failure_format_options: TestFailureFormatOptions,
+  ) -> Self {
+    Self {
+      cwd,
+      output_path,
+      writer: None,
+      write_error: None,
+      summary: TestSummary::new(),
+      failure_format_options,
+    }
+  }
+
+  fn write_event(&mut self, event: serde_json::Value) {
+    if self.write_error.is_some() {
+      return;
+    }
+    if let Err(err) = self.try_write_event(&event) {
+      self.write_error = Some(err);
+    }
+  }
+
+  fn try_write_event(
+    &mut self,
+    event: &serde_json::Value,
+  ) -> Result<(), std::io::Error> {
+    let writer = match &mut self.writer {
+      Some(writer) => writer,
+      None => {
+        let writer: Box<dyn Write> = if self.output_path == "-" {
+          Box::new(std::io::stdout())
+        } else {
+          let path = PathBuf::from(&self.output_path);
+          if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+          }
+          Box::new(BufWriter::new(File::create(path)?))
+        };
+        self.writer.insert(writer)
+      }
+    };
+    serde_json::to_writer(&mut *writer, event)?;
+    writer.write_all(b"\n")?;
+    if self.output_path == "-" {
+      writer.flush()?;
+    }
+    Ok(())
+  }
+
+  fn origin(&self, origin: &str) -> String {
+    to_relative_path_or_remote_url(&self.cwd, origin)
+  }
+
+  fn failure_message(&self, failure: &TestFailure) -> String {
+    strip_ansi_codes(&failure.format(&self.failure_format_options)).to_string()
+  }
+}
+
+fn test_outcome(result: &TestResult) -> &'static str {
+  match result {
+    TestResult::Ok => "ok",
+    TestResult::Ignored => "ignored",
+    TestResult::Failed(_) => "failed",
+    TestResult::Cancelled => "cancelled",
+    TestResult::Skipped => "skipped",
+  }
+}
+
+fn test_step_outcome(result: &TestStepResult) -> &'static str {
+  match result {
+    TestStepResult::Ok => "ok",
+    TestStepResult::Ignored => "ignored",
+    TestStepResult::Failed(_) => "failed",
+  }
+}
+
+impl TestReporter for JsonTestReporter {
+  fn report_register(&mut self, _description: &TestDescription) {}
+
+  fn report_plan(&mut self, plan: &TestPlan) {
+    self.summary.total += plan.total;
+    self.summary.filtered_out += plan.filtered_out;
+    self.write_event(json!({
+      "type": "plan",
+      "origin": self.origin(&plan.origin),
+      "total": plan.total,
+      "filteredOut": plan.filtered_out,
+      "usedOnly": plan.used_only,
+    }));
+  }
+
+  fn report_wait(&mut self, description: &TestDescription) {
+    self.write_event(json!({
+      "type": "wait",
+      "id": description.id,
+      "name": description.name,
+      "origin": self.origin(&description.origin),
+    }));
+  }
+
+  fn report_slow(&mut self, description: &TestDescription, elapsed: u64) {
+    self.write_event(json!({
+      "type": "slow",
+      "id": description.id,
+      "name": description.name,
+      "elapsed": elapsed,
+    }));
+  }
+
+  fn report_artifacts(&mut self, id: usize, path: &Path) {
+    self.write_event(json!({
+      "type": "artifacts",
+      "id": id,
+      "path": path.to_string_lossy(),
+    }));
+  }
+
+  fn report_output(&mut self, output: &[u8]) {
+    self.write_event(json!({
+      "type": "output",
+      "text": String::from_utf8_lossy(output),
+    }));
+  }
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    let error = match result {
+      TestResult::Ok => {
+        self.summary.passed += 1;
+        None
+      }
+      TestResult::Ignored => {
+        self.summary.ignored += 1;
+        None
+      }
+      TestResult::Failed(failure) => {
+        self.summary.failed += 1;
+        Some(self.failure_message(failure))
+      }
+      TestResult::Cancelled => {
+        self.summary.failed += 1;
+        None
+      }
+      TestResult::Skipped => {
+        self.summary.skipped += 1;
+        None
+      }
+    };
+    self.write_event(json!({
+      "type": "result",
+      "id": description.id,
+      "name": description.name,
+      "origin": self.origin(&description.origin),
+      "line": description.location.line_number,
+      "outcome": test_outcome(result),
+      "elapsed": elapsed,
+      "error": error,
+    }));
+  }
+
+  fn report_skipped_file(&mut self, _specifier: &ModuleSpecifier) {
+    self.summary.skipped_files += 1;
+  }
+
+  fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>) {
+    self.summary.failed += 1;
+    let error = format_test_error(&error, &self.failure_format_options);
+    self.write_event(json!({
+      "type": "uncaughtError",
+      "origin": self.origin(origin),
+      "error": strip_ansi_codes(&error),
+    }));
+  }
+
+  fn report_step_register(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_wait(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let error = match result {
+      TestStepResult::Ok => {
+        self.summary.passed_steps += 1;
+        None
+      }
+      TestStepResult::Ignored => {
+        self.summary.ignored_steps += 1;
+        None
+      }
+      TestStepResult::Failed(failure) => {
+        self.summary.failed_steps += 1;
+        Some(self.failure_message(failure))
+      }
+    };
+    self.write_event(json!({
+      "type": "stepResult",
+      "id": description.id,
+      "parentId": description.parent_id,
+      "level": description.level,
+      "name": description.name,
+      "origin": self.origin(&description.origin),
+      "line": description.location.line_number,
+      "outcome": test_step_outcome(result),
+      "elapsed": elapsed,
+      "error": error,
+    }));
+  }
+
+  fn report_summary(
+    &mut self,
+    elapsed: &Duration,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    self.write_event(json!({
+      "type": "summary",
+      "passed": self.summary.passed,
+      "failed": self.summary.failed,
+      "ignored": self.summary.ignored,
+      "skipped": self.summary.skipped,
+      "skippedFiles": self.summary.skipped_files,
+      "passedSteps": self.summary.passed_steps,
+      "failedSteps": self.summary.failed_steps,
+      "ignoredSteps": self.summary.ignored_steps,
+      "filteredOut": self.summary.filtered_out,
+      "elapsed": elapsed.as_millis() as u64,
+    }));
+  }
+
+  fn report_sigint(
+    &mut self,
+    tests_pending: &HashSet<usize>,
+    tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    for id in tests_pending {
+      if let Some(description) = tests.get(id) {
+        self.report_result(description, &TestResult::Cancelled, 0)
+      }
+    }
+  }
+
+  fn report_completed(&mut self) {}
+
+  fn flush_report(
+    &mut self,
+    _elapsed: &Duration,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) -> anyhow::Result<()> {
+    if let Some(err) = self.write_error.take() {
+      return Err(err).with_context(|| {
+        format!("Failed to write JSON test events to {}", self.output_path)
+      });
+    }
+    if let Some(writer) = &mut self.writer {
+      writer.flush().with_context(|| {
+        format!("Failed to write JSON test events to {}", self.output_path)
+      })?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use test_util::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn writes_events_as_ndjson() {
+    let temp_dir = TempDir::new();
+    let output_path = temp_dir.path().join("events.ndjson");
+    let mut reporter = JsonTestReporter::new(
+      Url::parse("file:///dev/").unwrap(),
+      output_path.to_string(),
+      TestFailureFormatOptions::default(),
+    );
+    let test = TestDescription {
+      id: 1,
+      name: "test".to_string(),
+      ignore: false,
+      only: false,
+      origin: "file:///dev/a_test.ts".to_string(),
+      location: TestLocation {
+        file_name: "file:///dev/a_test.ts".to_string(),
+        line_number: 3,
+        column_number: 1,
+      },
+      sanitize_ops: true,
+      sanitize_resources: true,
+      tags: vec![],
+    };
+    reporter.report_plan(&TestPlan {
+      origin: test.origin.clone(),
+      total: 1,
+      filtered_out: 0,
+      used_only: false,
+    });
+    reporter.report_result(&test, &TestResult::Ok, 5);
+    reporter.report_summary(
+      &Duration::from_millis(7),
+      &IndexMap::new(),
+      &IndexMap::new(),
+    );
+    reporter
+      .flush_report(&Duration::ZERO, &IndexMap::new(), &IndexMap::new())
+      .unwrap();
+
+    let events = output_path
+      .read_to_string()
+      .lines()
+      .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0]["type"], "plan");
+    assert_eq!(events[0]["origin"], "./a_test.ts");
+    assert_eq!(
+      events[1],
+      json!({
+        "type": "result",
+        "id": 1,
+        "name": "test",
+        "origin": "./a_test.ts",
+        "line": 3,
+        "outcome": "ok",
+        "elapsed": 5,
+        "error": null,
+      })
+    );
+    assert_eq!(events[2]["passed"], 1);
+    assert_eq!(events[2]["elapsed"], 7);
+  }
+}
@@ -54,6 +54,11 @@ impl JunitTestReporter {
         description: None,
         reruns: vec![],
       },
+      TestResult::Skipped => quick_junit::TestCaseStatus::Skipped {
+        message: Some("Skipped due to --fail-fast".to_string()),
+        ty: None,
+        description: None,
+      },
     }
   }
 
@@ -105,6 +110,13 @@ impl TestReporter for JunitTestReporter {
   fn report_plan(&mut self, _plan: &TestPlan) {}
 
   fn report_slow(&mut self, _description: &TestDescription, _elapsed: u64) {}
+
+  fn report_artifacts(&mut self, id: usize, path: &Path) {
+    // the attachment syntax which CI systems like Jenkins and GitLab pick up
+    if let Some(case) = self.cases.get_mut(&id) {
+      case.set_system_out(format!("[[ATTACHMENT|{}]]", path.display()));
+    }
+  }
   fn report_wait(&mut self, _description: &TestDescription) {}
 
   fn report_output(&mut self, _output: &[u8]) {
@@ -128,6 +140,8 @@ impl TestReporter for JunitTestReporter {
     }
   }
 
+  fn report_skipped_file(&mut self, _specifier: &ModuleSpecifier) {}
+
   fn report_uncaught_error(&mut self, _origin: &str, _error: Box<JsError>) {}
 
   fn report_step_register(&mut self, description: &TestStepDescription) {
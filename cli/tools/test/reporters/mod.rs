@@ -5,13 +5,17 @@ use super::*;
 mod common;
 mod compound;
 mod dot;
+mod json;
 mod junit;
+mod otel;
 mod pretty;
 mod tap;
 
 pub use compound::CompoundTestReporter;
 pub use dot::DotTestReporter;
+pub use json::JsonTestReporter;
 pub use junit::JunitTestReporter;
+pub use otel::OtelTestReporter;
 pub use pretty::PrettyTestReporter;
 pub use tap::TapTestReporter;
 
@@ -20,6 +24,7 @@ pub trait TestReporter {
   fn report_plan(&mut self, plan: &TestPlan);
   fn report_wait(&mut self, description: &TestDescription);
   fn report_slow(&mut self, description: &TestDescription, elapsed: u64);
+  fn report_artifacts(&mut self, id: usize, path: &Path);
   fn report_output(&mut self, output: &[u8]);
   fn report_result(
     &mut self,
@@ -27,6 +32,7 @@ pub trait TestReporter {
     result: &TestResult,
     elapsed: u64,
   );
+  fn report_skipped_file(&mut self, specifier: &ModuleSpecifier);
   fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>);
   fn report_step_register(&mut self, description: &TestStepDescription);
   fn report_step_wait(&mut self, description: &TestStepDescription);
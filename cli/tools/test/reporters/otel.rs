@@ -0,0 +1,478 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use deno_core::anyhow::anyhow;
+use deno_core::serde_json;
+use deno_core::serde_json::json;
+use deno_runtime::tokio_util::create_basic_runtime;
+
+use super::fmt::to_relative_path_or_remote_url;
+use super::*;
+use crate::colors;
+use crate::http_util::HttpClientProvider;
+
+const SPAN_KIND_INTERNAL: u8 = 1;
+const STATUS_CODE_UNSET: u8 = 0;
+const STATUS_CODE_OK: u8 = 1;
+const STATUS_CODE_ERROR: u8 = 2;
+
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Exports a span per test file, test and test step to an OTLP/HTTP
+/// collector using the JSON encoding.
+pub struct OtelTestReporter {
+  cwd: Url,
+  traces_endpoint: String,
+  service_name: String,
+  http_client_provider: Arc<HttpClientProvider>,
+  trace_id: String,
+  // Span ids by test or step id, assigned on registration so that steps can
+  // reference the span of their parent before it has finished.
+  span_ids: HashMap<usize, String>,
+  file_spans: IndexMap<String, FileSpan>,
+  spans: Vec<serde_json::Value>,
+}
+
+struct FileSpan {
+  span_id: String,
+  start_time: u128,
+  end_time: u128,
+  failed: bool,
+}
+
+fn now_unix_nanos() -> u128 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_nanos())
+    .unwrap_or_default()
+}
+
+fn new_span_id() -> String {
+  format!("{:016x}", rand::random::<u64>())
+}
+
+impl OtelTestReporter {
+  pub fn new(
+    cwd: Url,
+    traces_endpoint: String,
+    service_name: String,
+    http_client_provider: Arc<HttpClientProvider>,
+  ) -> Self {
+    Self {
+      cwd,
+      traces_endpoint,
+      service_name,
+      http_client_provider,
+      trace_id: format!("{:032x}", rand::random::<u128>()),
+      span_ids: HashMap::new(),
+      file_spans: IndexMap::new(),
+      spans: Vec::new(),
+    }
+  }
+
+  fn span_id(&mut self, id: usize) -> String {
+    self.span_ids.entry(id).or_insert_with(new_span_id).clone()
+  }
+
+  /// Returns the span id of the file span, widening its time range to cover
+  /// the given child span.
+  fn file_span_id(
+    &mut self,
+    origin: &str,
+    start_time: u128,
+    end_time: u128,
+    failed: bool,
+  ) -> String {
+    let file_span =
+      self
+        .file_spans
+        .entry(origin.to_string())
+        .or_insert_with(|| FileSpan {
+          span_id: new_span_id(),
+          start_time,
+          end_time,
+          failed: false,
+        });
+    file_span.start_time = file_span.start_time.min(start_time);
+    file_span.end_time = file_span.end_time.max(end_time);
+    file_span.failed |= failed;
+    file_span.span_id.clone()
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  fn push_span(
+    &mut self,
+    span_id: String,
+    parent_span_id: String,
+    name: &str,
+    start_time: u128,
+    end_time: u128,
+    status: (u8, &str),
+    attributes: Vec<(&str, serde_json::Value)>,
+  ) {
+    self.spans.push(span_json(
+      &self.trace_id,
+      span_id,
+      Some(parent_span_id),
+      name,
+      start_time,
+      end_time,
+      status,
+      attributes,
+    ));
+  }
+
+  fn export_payload(&self) -> serde_json::Value {
+    let mut spans = self
+      .file_spans
+      .iter()
+      .map(|(origin, file_span)| {
+        let file_name = to_relative_path_or_remote_url(&self.cwd, origin);
+        span_json(
+          &self.trace_id,
+          file_span.span_id.clone(),
+          None,
+          &file_name,
+          file_span.start_time,
+          file_span.end_time,
+          if file_span.failed {
+            (STATUS_CODE_ERROR, "")
+          } else {
+            (STATUS_CODE_OK, "")
+          },
+          vec![("deno.test.file", json!({ "stringValue": file_name }))],
+        )
+      })
+      .collect::<Vec<_>>();
+    spans.extend(self.spans.iter().cloned());
+    json!({
+      "resourceSpans": [{
+        "resource": {
+          "attributes": [{
+            "key": "service.name",
+            "value": { "stringValue": self.service_name },
+          }],
+        },
+        "scopeSpans": [{
+          "scope": { "name": "deno test" },
+          "spans": spans,
+        }],
+      }],
+    })
+  }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn span_json(
+  trace_id: &str,
+  span_id: String,
+  parent_span_id: Option<String>,
+  name: &str,
+  start_time: u128,
+  end_time: u128,
+  (status_code, status_message): (u8, &str),
+  attributes: Vec<(&str, serde_json::Value)>,
+) -> serde_json::Value {
+  json!({
+    "traceId": trace_id,
+    "spanId": span_id,
+    "parentSpanId": parent_span_id.unwrap_or_default(),
+    "name": name,
+    "kind": SPAN_KIND_INTERNAL,
+    // 64-bit integers are encoded as strings in OTLP/JSON
+    "startTimeUnixNano": start_time.to_string(),
+    "endTimeUnixNano": end_time.to_string(),
+    "attributes": attributes
+      .into_iter()
+      .map(|(key, value)| json!({ "key": key, "value": value }))
+      .collect::<Vec<_>>(),
+    "status": { "code": status_code, "message": status_message },
+  })
+}
+
+fn test_status(result: &TestResult) -> (u8, Cow<'static, str>, &'static str) {
+  match result {
+    TestResult::Ok => (STATUS_CODE_OK, Cow::Borrowed(""), "ok"),
+    TestResult::Ignored => (STATUS_CODE_UNSET, Cow::Borrowed(""), "ignored"),
+    TestResult::Failed(failure) => {
+      (STATUS_CODE_ERROR, Cow::Owned(failure.overview()), "failed")
+    }
+    TestResult::Cancelled => {
+      (STATUS_CODE_ERROR, Cow::Borrowed("Cancelled"), "cancelled")
+    }
+    TestResult::Skipped => (STATUS_CODE_UNSET, Cow::Borrowed(""), "skipped"),
+  }
+}
+
+fn test_step_status(
+  result: &TestStepResult,
+) -> (u8, Cow<'static, str>, &'static str) {
+  match result {
+    TestStepResult::Ok => (STATUS_CODE_OK, Cow::Borrowed(""), "ok"),
+    TestStepResult::Ignored => {
+      (STATUS_CODE_UNSET, Cow::Borrowed(""), "ignored")
+    }
+    TestStepResult::Failed(failure) => {
+      (STATUS_CODE_ERROR, Cow::Owned(failure.overview()), "failed")
+    }
+  }
+}
+
+/// Sends the payload with a plain HTTP/1.1 request. The collector is expected
+/// to run alongside the tests, so TLS is not supported.
+fn post_json(url: &str, body: &[u8]) -> anyhow::Result<()> {
+  let url = Url::parse(url)
+    .with_context(|| format!("Invalid OTLP traces endpoint: {url}"))?;
+  if url.scheme() != "http" {
+    bail!(
+      "Unsupported OTLP traces endpoint scheme \"{}\", only http is supported.",
+      url.scheme()
+    );
+  }
+  let host = url
+    .host_str()
+    .with_context(|| format!("Missing host in OTLP traces endpoint: {url}"))?;
+  let port = url.port_or_known_default().unwrap_or(80);
+  let mut stream = TcpStream::connect((host, port))
+    .with_context(|| format!("Failed to connect to {url}"))?;
+  let path = match url.query() {
+    Some(query) => format!("{}?{}", url.path(), query),
+    None => url.path().to_string(),
+  };
+  write!(
+    stream,
+    "POST {path} HTTP/1.1\r\nHost: {host}:{port}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+    body.len()
+  )?;
+  stream.write_all(body)?;
+  let mut response = String::new();
+  stream.read_to_string(&mut response)?;
+  let status_line = response.lines().next().unwrap_or_default();
+  let status = status_line.split(' ').nth(1).unwrap_or_default();
+  if !status.starts_with('2') {
+    bail!("OTLP collector at {url} responded with: {status_line}");
+  }
+  Ok(())
+}
+
+impl TestReporter for OtelTestReporter {
+  fn report_register(&mut self, description: &TestDescription) {
+    self.span_id(description.id);
+  }
+
+  fn report_plan(&mut self, _plan: &TestPlan) {}
+  fn report_wait(&mut self, _description: &TestDescription) {}
+  fn report_slow(&mut self, _description: &TestDescription, _elapsed: u64) {}
+  fn report_artifacts(&mut self, _id: usize, _path: &Path) {}
+  fn report_output(&mut self, _output: &[u8]) {}
+
+  fn report_result(
+    &mut self,
+    description: &TestDescription,
+    result: &TestResult,
+    elapsed: u64,
+  ) {
+    let end_time = now_unix_nanos();
+    let start_time = end_time.saturating_sub(elapsed as u128 * 1_000_000);
+    let (status_code, message, outcome) = test_status(result);
+    let parent_span_id = self.file_span_id(
+      &description.origin,
+      start_time,
+      end_time,
+      status_code == STATUS_CODE_ERROR,
+    );
+    let span_id = self.span_id(description.id);
+    self.push_span(
+      span_id,
+      parent_span_id,
+      &description.name,
+      start_time,
+      end_time,
+      (status_code, &message),
+      vec![
+        ("deno.test.outcome", json!({ "stringValue": outcome })),
+        (
+          "code.lineno",
+          json!({ "intValue": description.location.line_number.to_string() }),
+        ),
+      ],
+    );
+  }
+
+  fn report_skipped_file(&mut self, _specifier: &ModuleSpecifier) {}
+
+  fn report_uncaught_error(&mut self, _origin: &str, _error: Box<JsError>) {}
+
+  fn report_step_register(&mut self, description: &TestStepDescription) {
+    self.span_id(description.id);
+  }
+
+  fn report_step_wait(&mut self, _description: &TestStepDescription) {}
+
+  fn report_step_result(
+    &mut self,
+    description: &TestStepDescription,
+    result: &TestStepResult,
+    elapsed: u64,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    let end_time = now_unix_nanos();
+    let start_time = end_time.saturating_sub(elapsed as u128 * 1_000_000);
+    let (status_code, message, outcome) = test_step_status(result);
+    let parent_span_id = self.span_id(description.parent_id);
+    let span_id = self.span_id(description.id);
+    self.push_span(
+      span_id,
+      parent_span_id,
+      &description.name,
+      start_time,
+      end_time,
+      (status_code, &message),
+      vec![
+        ("deno.test.outcome", json!({ "stringValue": outcome })),
+        (
+          "deno.test.step.level",
+          json!({ "intValue": description.level.to_string() }),
+        ),
+        (
+          "code.lineno",
+          json!({ "intValue": description.location.line_number.to_string() }),
+        ),
+      ],
+    );
+  }
+
+  fn report_summary(
+    &mut self,
+    _elapsed: &Duration,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+  }
+
+  fn report_sigint(
+    &mut self,
+    tests_pending: &HashSet<usize>,
+    tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) {
+    for id in tests_pending {
+      if let Some(description) = tests.get(id) {
+        self.report_result(description, &TestResult::Cancelled, 0)
+      }
+    }
+  }
+
+  fn report_completed(&mut self) {}
+
+  fn flush_report(
+    &mut self,
+    _elapsed: &Duration,
+    _tests: &IndexMap<usize, TestDescription>,
+    _test_steps: &IndexMap<usize, TestStepDescription>,
+  ) -> anyhow::Result<()> {
+    if self.file_spans.is_empty() {
+      return Ok(());
+    }
+    // The spans are a side channel, so a missing or failing collector must
+    // not fail the test run.
+    if self.http_client_provider.is_offline() {
+      log::warn!(
+        "{} Not exporting OpenTelemetry spans to {} in --offline mode.",
+        colors::yellow("Warning"),
+        self.traces_endpoint,
+      );
+      return Ok(());
+    }
+    let result = Url::parse(&self.traces_endpoint)
+      .with_context(|| {
+        format!("Invalid OTLP traces endpoint: {}", self.traces_endpoint)
+      })
+      .and_then(|url| {
+        export_spans(
+          self.http_client_provider.clone(),
+          url,
+          self.export_payload(),
+        )
+      });
+    if let Err(err) = result {
+      log::warn!(
+        "{} Failed exporting OpenTelemetry spans to {}: {:#}",
+        colors::yellow("Warning"),
+        self.traces_endpoint,
+        err,
+      );
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn export_payload_nests_spans() {
+    let mut reporter = OtelTestReporter::new(
+      Url::parse("file:///dev/").unwrap(),
+      "http://localhost:4318/v1/traces".to_string(),
+      "deno".to_string(),
+      Arc::new(HttpClientProvider::new(None, None)),
+    );
+    let location = TestLocation {
+      file_name: "file:///dev/a_test.ts".to_string(),
+      line_number: 1,
+      column_number: 1,
+    };
+    let test = TestDescription {
+      id: 1,
+      name: "test".to_string(),
+      ignore: false,
+      only: false,
+      origin: "file:///dev/a_test.ts".to_string(),
+      location: location.clone(),
+      sanitize_ops: true,
+      sanitize_resources: true,
+      tags: vec![],
+    };
+    let step = TestStepDescription {
+      id: 2,
+      name: "step".to_string(),
+      origin: test.origin.clone(),
+      location,
+      level: 1,
+      parent_id: 1,
+      root_id: 1,
+      root_name: "test".to_string(),
+    };
+    reporter.report_register(&test);
+    reporter.report_step_register(&step);
+    reporter.report_step_result(
+      &step,
+      &TestStepResult::Ok,
+      1,
+      &IndexMap::new(),
+      &IndexMap::new(),
+    );
+    reporter.report_result(&test, &TestResult::Cancelled, 2);
+
+    let payload = reporter.export_payload();
+    let spans = payload["resourceSpans"][0]["scopeSpans"][0]["spans"]
+      .as_array()
+      .unwrap();
+    assert_eq!(spans.len(), 3);
+    let (file, step, test) = (&spans[0], &spans[1], &spans[2]);
+    assert_eq!(file["name"], "./a_test.ts");
+    assert_eq!(file["parentSpanId"], "");
+    assert_eq!(file["status"]["code"], STATUS_CODE_ERROR);
+    assert_eq!(test["parentSpanId"], file["spanId"]);
+    assert_eq!(test["status"]["message"], "Cancelled");
+    assert_eq!(step["parentSpanId"], test["spanId"]);
+    assert_eq!(step["status"]["code"], STATUS_CODE_OK);
+    assert_eq!(step["traceId"], file["traceId"]);
+  }
+}
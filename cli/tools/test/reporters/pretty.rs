@@ -21,6 +21,7 @@ pub struct PrettyTestReporter {
   summary: TestSummary,
   writer: Box<dyn std::io::Write>,
   failure_format_options: TestFailureFormatOptions,
+  slow_threshold: Option<Duration>,
 }
 
 impl PrettyTestReporter {
@@ -48,6 +49,7 @@ impl PrettyTestReporter {
       summary: TestSummary::new(),
       writer: Box::new(std::io::stdout()),
       failure_format_options,
+      slow_threshold: None,
     }
   }
 
@@ -55,6 +57,14 @@ impl PrettyTestReporter {
     Self { writer, ..self }
   }
 
+  /// Marks the tests which took longer than the threshold as slow.
+  pub fn with_slow_threshold(self, slow_threshold: Option<Duration>) -> Self {
+    Self {
+      slow_threshold,
+      ..self
+    }
+  }
+
   fn force_report_wait(&mut self, description: &TestDescription) {
     if !self.in_new_line {
       writeln!(&mut self.writer).unwrap();
@@ -212,6 +222,9 @@ impl TestReporter for PrettyTestReporter {
     )
     .unwrap();
   }
+  fn report_artifacts(&mut self, id: usize, path: &Path) {
+    self.summary.artifacts.insert(id, path.to_path_buf());
+  }
   fn report_output(&mut self, output: &[u8]) {
     if !self.echo_output {
       return;
@@ -266,6 +279,11 @@ impl TestReporter for PrettyTestReporter {
       TestResult::Cancelled => {
         self.summary.failed += 1;
       }
+      TestResult::Skipped => {
+        // these are summarized in one line instead
+        self.summary.skipped += 1;
+        return;
+      }
     }
 
     if self.parallel {
@@ -282,6 +300,7 @@ impl TestReporter for PrettyTestReporter {
       TestResult::Ignored => colors::yellow("ignored").to_string(),
       TestResult::Failed(failure) => failure.format_label(),
       TestResult::Cancelled => colors::gray("cancelled").to_string(),
+      TestResult::Skipped => unreachable!(),
     };
     write!(&mut self.writer, " {}", status).unwrap();
     if let TestResult::Failed(failure) = result {
@@ -289,16 +308,27 @@ impl TestReporter for PrettyTestReporter {
         write!(&mut self.writer, " ({})", inline_summary).unwrap();
       }
     }
-    writeln!(
+    write!(
       &mut self.writer,
       " {}",
       colors::gray(format!("({})", display::human_elapsed(elapsed.into())))
     )
     .unwrap();
+    let is_slow = self
+      .slow_threshold
+      .is_some_and(|threshold| elapsed >= threshold.as_millis() as u64);
+    if is_slow && !matches!(result, TestResult::Ignored | TestResult::Skipped) {
+      write!(&mut self.writer, " {}", colors::yellow("slow")).unwrap();
+    }
+    writeln!(&mut self.writer).unwrap();
     self.in_new_line = true;
     self.scope_test_id = None;
   }
 
+  fn report_skipped_file(&mut self, _specifier: &ModuleSpecifier) {
+    self.summary.skipped_files += 1;
+  }
+
   fn report_uncaught_error(&mut self, origin: &str, error: Box<JsError>) {
     self.summary.failed += 1;
     self
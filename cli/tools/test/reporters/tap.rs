@@ -148,6 +148,7 @@ impl TestReporter for TapTestReporter {
   }
 
   fn report_slow(&mut self, _description: &TestDescription, _elapsed: u64) {}
+  fn report_artifacts(&mut self, _id: usize, _path: &Path) {}
   fn report_output(&mut self, _output: &[u8]) {}
 
   fn report_result(
@@ -173,6 +174,7 @@ impl TestReporter for TapTestReporter {
       TestResult::Ignored => ("ok", " # SKIP"),
       TestResult::Failed(_failure) => ("not ok", ""),
       TestResult::Cancelled => ("not ok", ""),
+      TestResult::Skipped => ("ok", " # SKIP fail-fast"),
     };
     self.n += 1;
     Self::print_line(0, status, self.n, &description.name, directive);
@@ -189,6 +191,8 @@ impl TestReporter for TapTestReporter {
     }
   }
 
+  fn report_skipped_file(&mut self, _specifier: &ModuleSpecifier) {}
+
   fn report_uncaught_error(&mut self, _origin: &str, _errorr: Box<JsError>) {}
 
   fn report_step_register(&mut self, _description: &TestStepDescription) {}
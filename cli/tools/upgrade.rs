@@ -11,6 +11,7 @@ use crate::http_util::HttpClient;
 use crate::http_util::HttpClientProvider;
 use crate::shared::ReleaseChannel;
 use crate::util::archive;
+use crate::util::checksum;
 use crate::util::progress_bar::ProgressBar;
 use crate::util::progress_bar::ProgressBarStyle;
 use crate::version;
@@ -41,6 +42,14 @@ const DL_RELEASE_URL: &str = "https://dl.deno.land/release";
 pub static ARCHIVE_NAME: Lazy<String> =
   Lazy::new(|| format!("deno-{}.zip", env!("TARGET")));
 
+/// The checksum of the executable in the archive, published next to it.
+static CHECKSUM_FILE_NAME: Lazy<String> =
+  Lazy::new(|| format!("deno-{}.sha256sum", env!("TARGET")));
+
+/// The first release published with checksum files. Only older releases may
+/// be installed without verifying their checksum.
+const FIRST_VERSION_WITH_CHECKSUMS: &str = "2.0.0";
+
 // How often query server for new version. In hours.
 const UPGRADE_CHECK_INTERVAL: i64 = 24;
 
@@ -320,7 +329,7 @@ pub fn check_for_upgrades(
   http_client_provider: Arc<HttpClientProvider>,
   cache_file_path: PathBuf,
 ) {
-  if !upgrade_check_enabled() {
+  if !upgrade_check_enabled() || http_client_provider.is_offline() {
     return;
   }
 
@@ -506,6 +515,7 @@ pub async fn upgrade(
 
   let requested_version =
     RequestedVersion::from_upgrade_flags(upgrade_flags.clone())?;
+  let source = UpgradeSource::from_upgrade_flags(&upgrade_flags);
 
   log::info!("Current Deno version: v{}", version::DENO_VERSION_INFO.deno);
 
@@ -513,6 +523,7 @@ pub async fn upgrade(
     RequestedVersion::Latest(channel) => {
       find_latest_version_to_upgrade(
         http_client_provider.clone(),
+        &source,
         *channel,
         force_selection_of_new_version,
       )
@@ -532,17 +543,17 @@ pub async fn upgrade(
     return Ok(());
   };
 
-  let download_url = get_download_url(
+  let download_url = source.download_url(
     &selected_version_to_upgrade.version_or_hash,
     requested_version.release_channel(),
   )?;
   log::info!("{}", colors::gray(format!("Downloading {}", &download_url)));
-  let Some(archive_data) = download_package(&client, download_url).await?
+  let Some(archive_data) =
+    download_package(&client, download_url.clone()).await?
   else {
     log::error!("Download could not be found, aborting");
     std::process::exit(1)
   };
-
   log::info!(
     "{}",
     colors::gray(format!(
@@ -559,13 +570,25 @@ pub async fn upgrade(
     is_windows: cfg!(windows),
     dest_path: temp_dir.path(),
   })?;
+  verify_checksum(
+    &client,
+    &download_url,
+    &new_exe_path,
+    source.allows_missing_checksum(
+      &selected_version_to_upgrade.version_or_hash,
+      selected_version_to_upgrade.release_channel,
+    ),
+  )
+  .await?;
   fs::set_permissions(&new_exe_path, permissions)?;
   check_exe(&new_exe_path)?;
 
   if upgrade_flags.dry_run {
     fs::remove_file(&new_exe_path)?;
     log::info!("Upgraded successfully (dry run)");
-    if requested_version.release_channel() == ReleaseChannel::Stable {
+    if requested_version.release_channel() == ReleaseChannel::Stable
+      && source == UpgradeSource::Official
+    {
       print_release_notes(
         version::DENO_VERSION_INFO.deno,
         &selected_version_to_upgrade.version_or_hash,
@@ -595,7 +618,9 @@ pub async fn upgrade(
       selected_version_to_upgrade.release_channel.name()
     ))
   );
-  if requested_version.release_channel() == ReleaseChannel::Stable {
+  if requested_version.release_channel() == ReleaseChannel::Stable
+    && source == UpgradeSource::Official
+  {
     print_release_notes(
       version::DENO_VERSION_INFO.deno,
       &selected_version_to_upgrade.version_or_hash,
@@ -718,6 +743,7 @@ fn select_specific_version_for_upgrade(
 
 async fn find_latest_version_to_upgrade(
   http_client_provider: Arc<HttpClientProvider>,
+  source: &UpgradeSource,
   release_channel: ReleaseChannel,
   force: bool,
 ) -> Result<Option<AvailableVersion>, AnyError> {
@@ -728,25 +754,20 @@ async fn find_latest_version_to_upgrade(
 
   let client = http_client_provider.get_or_create()?;
 
-  let latest_version_found = match fetch_latest_version(
-    &client,
-    release_channel,
-    UpgradeCheckKind::Execution,
-  )
-  .await
-  {
-    Ok(v) => v,
-    Err(err) => {
-      if err.to_string().contains("Not found") {
-        bail!(
-          "No {} release available at the moment.",
-          release_channel.name()
-        );
-      } else {
-        return Err(err);
+  let latest_version_found =
+    match source.fetch_latest_version(&client, release_channel).await {
+      Ok(v) => v,
+      Err(err) => {
+        if err.to_string().contains("Not found") {
+          bail!(
+            "No {} release available at the moment.",
+            release_channel.name()
+          );
+        } else {
+          return Err(err);
+        }
       }
-    }
-  };
+    };
 
   let (maybe_newer_latest_version, current_version) = match release_channel {
     ReleaseChannel::Canary => {
@@ -800,6 +821,124 @@ async fn find_latest_version_to_upgrade(
   Ok(maybe_newer_latest_version)
 }
 
+/// Where the releases are downloaded from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum UpgradeSource {
+  /// The official releases on dl.deno.land and GitHub.
+  Official,
+  /// The releases of a fork on GitHub, as `owner/name`.
+  GithubRepo(String),
+  /// A mirror with the same layout as dl.deno.land.
+  Mirror(Url),
+}
+
+impl UpgradeSource {
+  fn from_upgrade_flags(upgrade_flags: &UpgradeFlags) -> Self {
+    if let Some(repo) = &upgrade_flags.repo {
+      Self::GithubRepo(repo.clone())
+    } else if let Some(url) = &upgrade_flags.url {
+      Self::Mirror(url.clone())
+    } else {
+      Self::Official
+    }
+  }
+
+  /// Whether the release may be installed without a checksum file. The
+  /// releases of custom sources are only trusted with a checksum.
+  fn allows_missing_checksum(
+    &self,
+    version: &str,
+    release_channel: ReleaseChannel,
+  ) -> bool {
+    *self == Self::Official && predates_checksums(version, release_channel)
+  }
+
+  async fn fetch_latest_version(
+    &self,
+    client: &HttpClient,
+    release_channel: ReleaseChannel,
+  ) -> Result<AvailableVersion, AnyError> {
+    match self {
+      Self::Official => {
+        fetch_latest_version(
+          client,
+          release_channel,
+          UpgradeCheckKind::Execution,
+        )
+        .await
+      }
+      Self::GithubRepo(repo) => {
+        if release_channel != ReleaseChannel::Stable {
+          bail!(
+            "Only stable releases can be upgraded to from {}, not {} releases.",
+            repo,
+            release_channel.name()
+          );
+        }
+        // the latest release redirects to the page of its tag
+        let latest_url =
+          Url::parse(&format!("https://github.com/{repo}/releases/latest"))?;
+        let url = client.get_redirected_url(latest_url, None).await?;
+        let Some(tag) = url
+          .path_segments()
+          .and_then(|mut segments| segments.next_back())
+          .filter(|_| url.path().contains("/releases/tag/"))
+        else {
+          bail!("No release found in {}", repo);
+        };
+        normalize_version_from_server(release_channel, tag)
+      }
+      Self::Mirror(base_url) => {
+        let url = latest_version_url(
+          base_url.as_str().trim_end_matches('/'),
+          release_channel,
+          env!("TARGET"),
+          UpgradeCheckKind::Execution,
+        );
+        let text = client.download_text(url.parse()?).await?;
+        normalize_version_from_server(release_channel, &text)
+      }
+    }
+  }
+
+  fn download_url(
+    &self,
+    version: &str,
+    release_channel: ReleaseChannel,
+  ) -> Result<Url, AnyError> {
+    let download_url = match (self, release_channel) {
+      (Self::Official, _) => {
+        return get_download_url(version, release_channel);
+      }
+      (Self::GithubRepo(repo), ReleaseChannel::Canary) => {
+        bail!("Canary builds can't be upgraded to from {}.", repo)
+      }
+      (Self::GithubRepo(repo), _) => format!(
+        "https://github.com/{}/releases/download/v{}/{}",
+        repo, version, *ARCHIVE_NAME
+      ),
+      (Self::Mirror(base_url), ReleaseChannel::Canary) => format!(
+        "{}/canary/{}/{}",
+        base_url.as_str().trim_end_matches('/'),
+        version,
+        *ARCHIVE_NAME
+      ),
+      (Self::Mirror(base_url), _) => format!(
+        "{}/release/v{}/{}",
+        base_url.as_str().trim_end_matches('/'),
+        version,
+        *ARCHIVE_NAME
+      ),
+    };
+    Url::parse(&download_url).with_context(|| {
+      format!(
+        "Failed to parse URL to download new release: {}",
+        download_url
+      )
+    })
+  }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 struct AvailableVersion {
   version_or_hash: String,
@@ -852,6 +991,20 @@ fn get_latest_version_url(
   release_channel: ReleaseChannel,
   target_tuple: &str,
   check_kind: UpgradeCheckKind,
+) -> String {
+  latest_version_url(
+    &base_upgrade_url(),
+    release_channel,
+    target_tuple,
+    check_kind,
+  )
+}
+
+fn latest_version_url(
+  base_url: &str,
+  release_channel: ReleaseChannel,
+  target_tuple: &str,
+  check_kind: UpgradeCheckKind,
 ) -> String {
   let file_name = match release_channel {
     ReleaseChannel::Stable => Cow::Borrowed("release-latest.txt"),
@@ -865,7 +1018,7 @@ fn get_latest_version_url(
     UpgradeCheckKind::Execution => "",
     UpgradeCheckKind::Lsp => "?lsp",
   };
-  format!("{}/{}{}", base_upgrade_url(), file_name, query_param)
+  format!("{}/{}{}", base_url, file_name, query_param)
 }
 
 fn base_upgrade_url() -> Cow<'static, str> {
@@ -877,7 +1030,7 @@ fn base_upgrade_url() -> Cow<'static, str> {
   }
 }
 
-fn get_download_url(
+pub fn get_download_url(
   version: &str,
   release_channel: ReleaseChannel,
 ) -> Result<Url, AnyError> {
@@ -904,7 +1057,7 @@ fn get_download_url(
   })
 }
 
-async fn download_package(
+pub async fn download_package(
   client: &HttpClient,
   download_url: Url,
 ) -> Result<Option<Vec<u8>>, AnyError> {
@@ -919,6 +1072,81 @@ async fn download_package(
   Ok(maybe_bytes)
 }
 
+/// Whether an official release was published before checksum files were.
+/// Canary builds are identified by a commit hash, which can't be ordered, so
+/// they always need a checksum.
+pub fn predates_checksums(
+  version: &str,
+  release_channel: ReleaseChannel,
+) -> bool {
+  if release_channel == ReleaseChannel::Canary {
+    return false;
+  }
+  let first = Version::parse_standard(FIRST_VERSION_WITH_CHECKSUMS).unwrap();
+  Version::parse_standard(version).is_ok_and(|version| version < first)
+}
+
+/// Compares the checksum of the unpacked executable with the one published
+/// next to the archive. A missing checksum file is only skipped when
+/// `allow_missing` is set, for releases published before checksum files were.
+pub async fn verify_checksum(
+  client: &HttpClient,
+  download_url: &Url,
+  exe_path: &Path,
+  allow_missing: bool,
+) -> Result<(), AnyError> {
+  let checksum_url = download_url.join(&CHECKSUM_FILE_NAME)?;
+  let maybe_text = client
+    .download_text_if_exists(checksum_url.clone())
+    .await
+    .with_context(|| {
+    format!("Failed downloading the checksum {checksum_url}")
+  })?;
+  let Some(text) = maybe_text else {
+    if allow_missing {
+      log::debug!(
+        "Skipping checksum verification, {} predates checksum files",
+        download_url
+      );
+      return Ok(());
+    }
+    bail!(
+      "The checksum {} could not be found, so the download can't be verified.",
+      checksum_url
+    );
+  };
+  let Some(expected) = parse_checksum_file(&text) else {
+    bail!("Invalid checksum file {}", checksum_url);
+  };
+  let exe_data = fs::read(exe_path)?;
+  let actual = checksum::gen(&[&exe_data]);
+  if actual != expected {
+    bail!(
+      "Checksum mismatch for {}\n  Expected: {}\n  Actual: {}",
+      download_url,
+      expected,
+      actual
+    );
+  }
+  log::info!("{}", colors::gray("Verified checksum"));
+  Ok(())
+}
+
+/// Gets the checksum of a file in the format of `sha256sum`, which is the
+/// checksum optionally followed by the file name, or in the `Format-List`
+/// format of PowerShell's `Get-FileHash` used for the Windows releases.
+fn parse_checksum_file(text: &str) -> Option<String> {
+  let checksum = text
+    .lines()
+    .find_map(|line| {
+      let (key, value) = line.split_once(':')?;
+      (key.trim() == "Hash").then(|| value.trim())
+    })
+    .or_else(|| text.split_whitespace().next())?;
+  (checksum.len() == 64 && checksum.chars().all(|c| c.is_ascii_hexdigit()))
+    .then(|| checksum.to_ascii_lowercase())
+}
+
 fn replace_exe(from: &Path, to: &Path) -> Result<(), std::io::Error> {
   if cfg!(windows) {
     // On windows you cannot replace the currently running executable.
@@ -995,7 +1223,7 @@ fn set_exe_permissions(
   Ok(permissions)
 }
 
-fn check_exe(exe_path: &Path) -> Result<(), AnyError> {
+pub fn check_exe(exe_path: &Path) -> Result<(), AnyError> {
   let output = Command::new(exe_path)
     .arg("-V")
     .stderr(std::process::Stdio::inherit())
@@ -1098,6 +1326,8 @@ mod test {
       version: None,
       output: None,
       version_or_hash_or_channel: None,
+      repo: None,
+      url: None,
     };
 
     let req_ver =
@@ -1654,6 +1884,89 @@ mod test {
     );
   }
 
+  #[test]
+  fn test_upgrade_source_download_url() {
+    let archive_name = &*ARCHIVE_NAME;
+    let repo = UpgradeSource::GithubRepo("acme/deno".to_string());
+    assert_eq!(
+      repo.download_url("2.0.0", ReleaseChannel::Stable).unwrap(),
+      Url::parse(&format!(
+        "https://github.com/acme/deno/releases/download/v2.0.0/{archive_name}"
+      ))
+      .unwrap()
+    );
+    assert!(repo
+      .download_url(
+        "aaf2bf4bfbf90bed0b6e9812f337f057d1d24f93",
+        ReleaseChannel::Canary
+      )
+      .is_err());
+
+    let mirror = UpgradeSource::Mirror(
+      Url::parse("https://mirror.example.com/deno/").unwrap(),
+    );
+    assert_eq!(
+      mirror
+        .download_url("2.0.0", ReleaseChannel::Stable)
+        .unwrap(),
+      Url::parse(&format!(
+        "https://mirror.example.com/deno/release/v2.0.0/{archive_name}"
+      ))
+      .unwrap()
+    );
+    assert_eq!(
+      mirror
+        .download_url(
+          "aaf2bf4bfbf90bed0b6e9812f337f057d1d24f93",
+          ReleaseChannel::Canary
+        )
+        .unwrap(),
+      Url::parse(&format!(
+        "https://mirror.example.com/deno/canary/aaf2bf4bfbf90bed0b6e9812f337f057d1d24f93/{archive_name}"
+      ))
+      .unwrap()
+    );
+    assert!(UpgradeSource::Official
+      .allows_missing_checksum("1.46.3", ReleaseChannel::Stable));
+    assert!(!UpgradeSource::Official
+      .allows_missing_checksum("2.0.0", ReleaseChannel::Stable));
+    assert!(!mirror.allows_missing_checksum("1.46.3", ReleaseChannel::Stable));
+  }
+
+  #[test]
+  fn test_predates_checksums() {
+    assert!(predates_checksums("1.46.3", ReleaseChannel::Stable));
+    assert!(predates_checksums("2.0.0-rc.10", ReleaseChannel::Rc));
+    assert!(!predates_checksums("2.0.0", ReleaseChannel::Stable));
+    assert!(!predates_checksums("2.1.0", ReleaseChannel::Lts));
+    assert!(!predates_checksums(
+      "aaf2bf4bfbf90bed0b6e9812f337f057d1d24f93",
+      ReleaseChannel::Canary
+    ));
+  }
+
+  #[test]
+  fn test_parse_checksum_file() {
+    let checksum =
+      "E3B0C44298FC1C149AFBF4C8996FB92427AE41E4649B934CA495991B7852B855";
+    assert_eq!(
+      parse_checksum_file(&format!("{checksum}  deno.zip\n")),
+      Some(checksum.to_ascii_lowercase())
+    );
+    assert_eq!(
+      parse_checksum_file(&checksum.to_ascii_lowercase()),
+      Some(checksum.to_ascii_lowercase())
+    );
+    assert_eq!(
+      parse_checksum_file(&format!(
+        "\r\nAlgorithm : SHA256\r\nHash      : {checksum}\r\nPath      : D:\\deno.exe\r\n"
+      )),
+      Some(checksum.to_ascii_lowercase())
+    );
+    assert_eq!(parse_checksum_file("abc deno.zip"), None);
+    assert_eq!(parse_checksum_file(""), None);
+  }
+
   #[test]
   fn test_normalize_version_server() {
     // should strip v for stable
@@ -0,0 +1,419 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+//! Runs the Deno version pinned by the project with a `.deno-version` file or
+//! the `"deno.version"` field of the config file. The pinned versions are
+//! downloaded to the `DENO_DIR`. This is opt-in with `DENO_VERSION_MANAGER=1`.
+//!
+//! The pin is resolved before the arguments are parsed, since the current
+//! version may not know the flags of the pinned one.
+
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::Arc;
+
+use deno_core::anyhow::bail;
+use deno_core::anyhow::Context;
+use deno_core::error::AnyError;
+use deno_semver::Version;
+
+use crate::args::CaData;
+use crate::cache::DenoDir;
+use crate::colors;
+use crate::factory::CliRootCertStoreProvider;
+use crate::http_util::HttpClientProvider;
+use crate::shared::ReleaseChannel;
+use crate::util::archive;
+use crate::version;
+
+use super::upgrade::check_exe;
+use super::upgrade::download_package;
+use super::upgrade::get_download_url;
+use super::upgrade::predates_checksums;
+use super::upgrade::verify_checksum;
+use super::upgrade::ARCHIVE_NAME;
+
+const VERSION_FILE_NAME: &str = ".deno-version";
+const CONFIG_FILE_NAMES: [&str; 2] = ["deno.json", "deno.jsonc"];
+
+#[derive(Debug, PartialEq, Eq)]
+struct PinnedVersion {
+  version: String,
+  release_channel: ReleaseChannel,
+  /// The file which pins the version.
+  source: PathBuf,
+}
+
+impl PinnedVersion {
+  fn parse(text: &str, source: PathBuf) -> Result<Self, AnyError> {
+    let version = text.strip_prefix('v').unwrap_or(text).to_string();
+    if lazy_regex::regex_is_match!("^[0-9a-f]{40}$", &version) {
+      return Ok(Self {
+        version,
+        release_channel: ReleaseChannel::Canary,
+        source,
+      });
+    }
+    let semver = Version::parse_standard(&version).with_context(|| {
+      format!(
+        "Invalid Deno version '{}' pinned in {}",
+        text,
+        source.display()
+      )
+    })?;
+    let release_channel = if semver.pre.contains(&"rc".to_string()) {
+      ReleaseChannel::Rc
+    } else {
+      ReleaseChannel::Stable
+    };
+    Ok(Self {
+      version,
+      release_channel,
+      source,
+    })
+  }
+
+  fn is_current(&self) -> bool {
+    match self.release_channel {
+      ReleaseChannel::Canary => {
+        version::DENO_VERSION_INFO.git_hash == self.version
+      }
+      _ => version::DENO_VERSION_INFO.deno == self.version,
+    }
+  }
+}
+
+fn is_enabled() -> bool {
+  std::env::var("DENO_VERSION_MANAGER").is_ok_and(|value| value == "1")
+}
+
+/// The flags which apply to downloading the pinned version. They are picked
+/// from the arguments before those are parsed. The arguments after the
+/// script, or after `--`, are left to the program.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct DownloadArgs {
+  is_upgrade: bool,
+  cert: Option<String>,
+  unsafely_ignore_certificate_errors: Option<Vec<String>>,
+  offline: bool,
+  log_level: Option<log::Level>,
+}
+
+impl DownloadArgs {
+  fn parse(args: &[OsString]) -> Self {
+    let mut download_args = Self::default();
+    let mut subcommand = None;
+    let mut args = args.iter().skip(1).map(|arg| arg.to_string_lossy());
+    while let Some(arg) = args.next() {
+      if arg == "--" {
+        break;
+      }
+      let (name, value) = match arg.split_once('=') {
+        Some((name, value)) => (name, Some(value.to_string())),
+        None => (arg.as_ref(), None),
+      };
+      match name {
+        "--cert" => {
+          download_args.cert =
+            value.or_else(|| args.next().map(|arg| arg.into_owned()));
+        }
+        "--unsafely-ignore-certificate-errors" => {
+          download_args.unsafely_ignore_certificate_errors = Some(
+            value
+              .map(|hosts| hosts.split(',').map(str::to_string).collect())
+              .unwrap_or_default(),
+          );
+        }
+        "--offline" => download_args.offline = true,
+        "-q" | "--quiet" => download_args.log_level = Some(log::Level::Error),
+        "--log-level" => {
+          download_args.log_level = value
+            .or_else(|| args.next().map(|arg| arg.into_owned()))
+            .and_then(|level| level.parse().ok());
+        }
+        _ if !name.starts_with('-') => {
+          if subcommand.is_some() {
+            break;
+          }
+          subcommand = Some(name.to_string());
+        }
+        _ => {}
+      }
+    }
+    download_args.is_upgrade = subcommand.as_deref() == Some("upgrade");
+    download_args
+  }
+}
+
+/// Runs the pinned version of Deno with the arguments of this process when
+/// it isn't the current version, returning its exit code.
+pub async fn maybe_run_pinned_version(
+  args: &[OsString],
+) -> Result<Option<i32>, AnyError> {
+  if !is_enabled() {
+    return Ok(None);
+  }
+  let download_args = DownloadArgs::parse(args);
+  // upgrading must keep working for the current executable
+  if download_args.is_upgrade {
+    return Ok(None);
+  }
+  let cwd = std::env::current_dir().context("Failed getting cwd")?;
+  let Some(pinned) = find_pinned_version(&cwd)? else {
+    return Ok(None);
+  };
+  if pinned.is_current() {
+    return Ok(None);
+  }
+  // the process exits or is replaced below, so the logger of this run isn't
+  // initialized again
+  crate::util::logger::init(download_args.log_level);
+  let exe_path = ensure_version_installed(&download_args, &pinned).await?;
+  log::debug!(
+    "Running Deno {} pinned by {}",
+    pinned.version,
+    pinned.source.display()
+  );
+  let mut command = Command::new(&exe_path);
+  command.args(args.iter().skip(1));
+  #[cfg(unix)]
+  {
+    let err = std::os::unix::process::CommandExt::exec(&mut command);
+    Err(err).with_context(|| format!("Failed running {}", exe_path.display()))
+  }
+  #[cfg(not(unix))]
+  {
+    let status = command
+      .status()
+      .with_context(|| format!("Failed running {}", exe_path.display()))?;
+    Ok(Some(status.code().unwrap_or(1)))
+  }
+}
+
+/// Finds the version pinned by the closest directory, where a `.deno-version`
+/// file takes precedence over the config file.
+fn find_pinned_version(
+  start_dir: &Path,
+) -> Result<Option<PinnedVersion>, AnyError> {
+  for dir in start_dir.ancestors() {
+    let version_file = dir.join(VERSION_FILE_NAME);
+    if let Some(text) = read_file_if_exists(&version_file)? {
+      return PinnedVersion::parse(text.trim(), version_file).map(Some);
+    }
+    for name in CONFIG_FILE_NAMES {
+      let config_path = dir.join(name);
+      let Some(text) = read_file_if_exists(&config_path)? else {
+        continue;
+      };
+      let value =
+        jsonc_parser::parse_to_serde_value(&text, &Default::default())
+          .with_context(|| {
+            format!("Failed parsing {}", config_path.display())
+          })?;
+      let Some(version) = value
+        .as_ref()
+        .and_then(|value| value.get("deno"))
+        .and_then(|deno| deno.get("version"))
+      else {
+        continue;
+      };
+      let Some(version) = version.as_str() else {
+        bail!(
+          "Expected \"deno.version\" in {} to be a string",
+          config_path.display()
+        );
+      };
+      return PinnedVersion::parse(version, config_path).map(Some);
+    }
+  }
+  Ok(None)
+}
+
+fn read_file_if_exists(path: &Path) -> Result<Option<String>, AnyError> {
+  match fs::read_to_string(path) {
+    Ok(text) => Ok(Some(text)),
+    Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+    Err(err) => {
+      Err(err).with_context(|| format!("Failed reading {}", path.display()))
+    }
+  }
+}
+
+/// Downloads the pinned version to the `DENO_DIR` unless it's already there
+/// and returns the path of its executable.
+async fn ensure_version_installed(
+  download_args: &DownloadArgs,
+  pinned: &PinnedVersion,
+) -> Result<PathBuf, AnyError> {
+  let deno_dir = DenoDir::new(None)?;
+  let versions_dir = deno_dir.versions_folder_path();
+  let version_dir = versions_dir.join(&pinned.version);
+  let exe_path =
+    version_dir.join(if cfg!(windows) { "deno.exe" } else { "deno" });
+  if exe_path.exists() {
+    return Ok(exe_path);
+  }
+
+  if download_args.offline {
+    bail!(
+      "Deno {} pinned by {} is not installed and can't be downloaded with --offline. Run once without --offline to download it.",
+      pinned.version,
+      pinned.source.display()
+    );
+  }
+  log::info!(
+    "{} Deno {} pinned by {}",
+    colors::green("Download"),
+    pinned.version,
+    pinned.source.display()
+  );
+  // like the http client of the other subcommands, this respects `--cert`,
+  // `DENO_CERT` and `DENO_TLS_CA_STORE`
+  let root_cert_store_provider = CliRootCertStoreProvider::new(
+    None,
+    None,
+    download_args.cert.clone().map(CaData::File),
+  );
+  let http_client_provider = HttpClientProvider::new(
+    Some(Arc::new(root_cert_store_provider)),
+    download_args.unsafely_ignore_certificate_errors.clone(),
+  );
+  let client = http_client_provider.get_or_create()?;
+  let download_url = get_download_url(&pinned.version, pinned.release_channel)?;
+  let Some(archive_data) =
+    download_package(&client, download_url.clone()).await?
+  else {
+    bail!("Deno {} could not be found", pinned.version);
+  };
+
+  fs::create_dir_all(&versions_dir)
+    .with_context(|| format!("Failed creating {}", versions_dir.display()))?;
+  // unpack next to the final directory, so that concurrent runs never see a
+  // partially written executable
+  let temp_dir = tempfile::tempdir_in(&versions_dir)?;
+  let new_exe_path = archive::unpack_into_dir(archive::UnpackArgs {
+    exe_name: "deno",
+    archive_name: &ARCHIVE_NAME,
+    archive_data: &archive_data,
+    is_windows: cfg!(windows),
+    dest_path: temp_dir.path(),
+  })?;
+  verify_checksum(
+    &client,
+    &download_url,
+    &new_exe_path,
+    predates_checksums(&pinned.version, pinned.release_channel),
+  )
+  .await?;
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(&new_exe_path, fs::Permissions::from_mode(0o755))?;
+  }
+  check_exe(&new_exe_path)?;
+  if let Err(err) = fs::rename(temp_dir.path(), &version_dir) {
+    // another process installed the version in the meantime
+    if !exe_path.exists() {
+      return Err(err)
+        .with_context(|| format!("Failed installing Deno {}", pinned.version));
+    }
+  }
+  Ok(exe_path)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  #[test]
+  fn test_pinned_version_parse() {
+    let source = PathBuf::from(".deno-version");
+    let pinned = PinnedVersion::parse("v2.1.0", source.clone()).unwrap();
+    assert_eq!(pinned.version, "2.1.0");
+    assert_eq!(pinned.release_channel, ReleaseChannel::Stable);
+    let pinned = PinnedVersion::parse("2.1.0-rc.1", source.clone()).unwrap();
+    assert_eq!(pinned.release_channel, ReleaseChannel::Rc);
+    let pinned = PinnedVersion::parse(
+      "aaf2bf4bfbf90bed0b6e9812f337f057d1d24f93",
+      source.clone(),
+    )
+    .unwrap();
+    assert_eq!(pinned.release_channel, ReleaseChannel::Canary);
+    assert!(PinnedVersion::parse("latest", source).is_err());
+  }
+
+  #[test]
+  fn test_find_pinned_version() {
+    let temp_dir = test_util::TempDir::new();
+    let root = temp_dir.path();
+    let member = root.join("member");
+    member.create_dir_all();
+    assert_eq!(find_pinned_version(member.as_path()).unwrap(), None);
+
+    root
+      .join("deno.jsonc")
+      .write("{\n  // pinned\n  \"deno\": { \"version\": \"2.0.0\" }\n}");
+    member
+      .join("deno.json")
+      .write(r#"{ "name": "@scope/member" }"#);
+    let pinned = find_pinned_version(member.as_path()).unwrap().unwrap();
+    assert_eq!(pinned.version, "2.0.0");
+    assert_eq!(pinned.source, root.join("deno.jsonc").to_path_buf());
+
+    member.join(".deno-version").write("2.1.0\n");
+    let pinned = find_pinned_version(member.as_path()).unwrap().unwrap();
+    assert_eq!(pinned.version, "2.1.0");
+  }
+
+  #[test]
+  fn test_download_args_parse() {
+    let parse = |args: &[&str]| {
+      let args = std::iter::once("deno")
+        .chain(args.iter().copied())
+        .map(OsString::from)
+        .collect::<Vec<_>>();
+      DownloadArgs::parse(&args)
+    };
+    assert_eq!(parse(&["run", "main.ts"]), DownloadArgs::default());
+    assert_eq!(
+      parse(&[
+        "run",
+        "--cert",
+        "ca.pem",
+        "--offline",
+        "--unsafely-ignore-certificate-errors=a.com,b.com",
+        "--log-level=debug",
+        "--flag-of-a-newer-version",
+        "main.ts",
+      ]),
+      DownloadArgs {
+        is_upgrade: false,
+        cert: Some("ca.pem".to_string()),
+        unsafely_ignore_certificate_errors: Some(vec![
+          "a.com".to_string(),
+          "b.com".to_string()
+        ]),
+        offline: true,
+        log_level: Some(log::Level::Debug),
+      }
+    );
+    assert_eq!(
+      parse(&["-q", "--cert=ca.pem", "upgrade"]),
+      DownloadArgs {
+        is_upgrade: true,
+        cert: Some("ca.pem".to_string()),
+        log_level: Some(log::Level::Error),
+        ..Default::default()
+      }
+    );
+    // the arguments of the program are left alone
+    assert_eq!(
+      parse(&["run", "main.ts", "--offline"]),
+      DownloadArgs::default()
+    );
+    assert_eq!(parse(&["task", "--", "--offline"]), DownloadArgs::default());
+    assert!(!parse(&["run", "upgrade.ts"]).is_upgrade);
+  }
+}
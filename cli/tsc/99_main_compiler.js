@@ -1288,6 +1288,9 @@ delete Object.prototype.__proto__;
       case "$getAssets": {
         return respond(id, getAssets());
       }
+      case "$getHeapUsed": {
+        return respond(id, ops.op_heap_used());
+      }
       case "$getDiagnostics": {
         const projectVersion = args[1];
         // there's a possibility that we receive a change notification
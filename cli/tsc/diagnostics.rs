@@ -273,7 +273,7 @@ impl fmt::Display for Diagnostic {
   }
 }
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
 pub struct Diagnostics(Vec<Diagnostic>);
 
 impl Diagnostics {
@@ -307,6 +307,16 @@ impl Diagnostics {
     self.0.is_empty()
   }
 
+  /// Adds the diagnostics that aren't already included, which happens when
+  /// separately checked parts of a graph share a module.
+  pub fn extend(&mut self, other: Diagnostics) {
+    for diagnostic in other.0 {
+      if !self.0.contains(&diagnostic) {
+        self.0.push(diagnostic);
+      }
+    }
+  }
+
   /// Modifies all the diagnostics to have their display positions
   /// modified to point at the original source.
   pub fn apply_fast_check_source_maps(&mut self, graph: &ModuleGraph) {
@@ -693,6 +693,16 @@ declare namespace Deno {
     /** If the current test is a step of another test, the parent test context
      * will be set here. */
     parent?: TestContext;
+    /** The directory in which the test can write screenshots, logs and other
+     * files, namespaced by the module and the names of the test and its
+     * steps. It's created on first access and is `undefined` unless
+     * `--artifacts-dir` is passed. */
+    readonly artifactsDir?: string;
+    /** A signal which is aborted when `--fail-fast` stops the test run while
+     * the test is still running, so that it can stop cooperatively. The
+     * sanitizers still run once the test settles. Steps share the signal of
+     * their test. */
+    readonly signal: AbortSignal;
 
     /** Run a sub step of the parent test or step. Returns a promise
      * that resolves to a boolean signifying if the step completed successfully.
@@ -843,6 +853,11 @@ declare namespace Deno {
      *
      * @default {"inherit"} */
     permissions?: PermissionOptions;
+    /** Categories of the test, like `"integration"` or `"slow"`, which can be
+     * selected with `--tags` and excluded with `--skip-tags`.
+     *
+     * @default {[]} */
+    tags?: string[];
   }
 
   /** Register a test which will be run when `deno test` is used on the command
@@ -1157,6 +1172,34 @@ declare namespace Deno {
       options: Omit<TestDefinition, "fn" | "only">,
       fn: (t: TestContext) => void | Promise<void>,
     ): void;
+
+    /** Returns the value which the default export of the setup module
+     * resolved to, or `undefined` when there is no setup module. The setup
+     * module is set with `deno test --setup`, and is run in the worker of
+     * every test module before its tests.
+     *
+     * ```ts
+     * // setup.ts
+     * export default async function () {
+     *   const db = await startDatabase();
+     *   return { url: db.url };
+     * }
+     *
+     * // db_test.ts
+     * const { url } = Deno.test.getSetupData<{ url: string }>();
+     * ```
+     *
+     * @category Testing
+     */
+    getSetupData<T = unknown>(): T;
+
+    /** Whether `deno test` was run with `--update-snapshots`. Snapshot
+     * assertion libraries read it to write the snapshots instead of
+     * comparing against them.
+     *
+     * @category Testing
+     */
+    readonly updateSnapshots: boolean;
   }
 
   /**
@@ -126,6 +126,17 @@ declare namespace Deno {
     reusePort?: boolean;
   }
 
+  /**
+   * @category HTTP Server
+   * @experimental
+   */
+  export interface ServeHandlerInfo<Addr extends Deno.Addr = Deno.Addr> {
+    /** The PEM encoded certificate which the client presented, when the
+     * server verifies client certificates, like `deno serve --tls-client-ca`
+     * does. `null` otherwise. */
+    clientCertificate: string | null;
+  }
+
   /** **UNSTABLE**: New API, yet to be vetted.
    *
    * Unstable options which can be set when opening a datagram listener via
@@ -1180,6 +1191,43 @@ declare namespace Deno {
       ...values: unknown[]
     ): Displayable;
 
+    /**
+     * @category Jupyter
+     * @experimental
+     */
+    export interface ImageOptions {
+      /** The media type of the image, detected from its contents when not
+       * provided. */
+      mediaType?: string;
+      /** The width the image is shown with, in pixels. */
+      width?: number;
+      /** The height the image is shown with, in pixels. */
+      height?: number;
+    }
+
+    /**
+     * Show an image in Jupyter frontends, like a plot rendered by a plotting
+     * library. PNG, JPEG, GIF, WebP and SVG images are supported.
+     *
+     * Images that are too large to send to the notebook are saved to the
+     * directory passed to the kernel with `--assets-dir`.
+     *
+     * @example
+     * ```typescript
+     * const { display, image } = Deno.jupyter;
+     * await display(image("./plot.png", { width: 640 }));
+     * ```
+     *
+     * @param data - The path of the image file or its bytes
+     * @param options - The media type and the size of the image
+     * @category Jupyter
+     * @experimental
+     */
+    export function image(
+      data: string | ArrayBuffer | ArrayBufferView,
+      options?: ImageOptions,
+    ): Displayable;
+
     /**
      * Format an object for displaying in Deno
      *
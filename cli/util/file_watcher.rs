@@ -15,6 +15,7 @@ use log::info;
 use notify::event::Event as NotifyEvent;
 use notify::event::EventKind;
 use notify::Error as NotifyError;
+use notify::PollWatcher;
 use notify::RecommendedWatcher;
 use notify::RecursiveMode;
 use notify::Watcher;
@@ -39,16 +40,20 @@ struct DebouncedReceiver {
   // lose items if a `recv()` never completes
   received_items: HashSet<PathBuf>,
   receiver: UnboundedReceiver<Vec<PathBuf>>,
+  debounce: Duration,
 }
 
 impl DebouncedReceiver {
-  fn new_with_sender() -> (Arc<mpsc::UnboundedSender<Vec<PathBuf>>>, Self) {
+  fn new_with_sender(
+    debounce: Duration,
+  ) -> (Arc<mpsc::UnboundedSender<Vec<PathBuf>>>, Self) {
     let (sender, receiver) = mpsc::unbounded_channel();
     (
       Arc::new(sender),
       Self {
         receiver,
         received_items: HashSet::new(),
+        debounce,
       },
     )
   }
@@ -65,7 +70,7 @@ impl DebouncedReceiver {
         items = self.receiver.recv() => {
           self.received_items.extend(items?);
         }
-        _ = sleep(DEBOUNCE_INTERVAL) => {
+        _ = sleep(self.debounce) => {
           return Some(self.received_items.drain().collect());
         }
       }
@@ -251,12 +256,15 @@ where
   F: Future<Output = Result<(), AnyError>>,
 {
   let exclude_set = flags.resolve_watch_exclude_set()?;
+  let watch_options = flags.resolve_watch_options();
   let (paths_to_watch_tx, mut paths_to_watch_rx) =
     tokio::sync::mpsc::unbounded_channel();
   let (restart_tx, mut restart_rx) = tokio::sync::mpsc::unbounded_channel();
   let (changed_paths_tx, changed_paths_rx) = tokio::sync::broadcast::channel(4);
   let (watcher_sender, mut watcher_receiver) =
-    DebouncedReceiver::new_with_sender();
+    DebouncedReceiver::new_with_sender(
+      watch_options.debounce.unwrap_or(DEBOUNCE_INTERVAL),
+    );
 
   let PrintConfig {
     banner,
@@ -305,13 +313,18 @@ where
       tokio::task::yield_now().await;
     }
 
-    let mut watcher = new_watcher(watcher_sender.clone())?;
-    consume_paths_to_watch(&mut watcher, &mut paths_to_watch_rx, &exclude_set);
+    let mut watcher =
+      new_watcher(watcher_sender.clone(), watch_options.poll_interval)?;
+    consume_paths_to_watch(&mut *watcher, &mut paths_to_watch_rx, &exclude_set);
 
     let receiver_future = async {
       loop {
         let maybe_paths = paths_to_watch_rx.recv().await;
-        add_paths_to_watcher(&mut watcher, &maybe_paths.unwrap(), &exclude_set);
+        add_paths_to_watcher(
+          &mut *watcher,
+          &maybe_paths.unwrap(),
+          &exclude_set,
+        );
       }
     };
     let operation_future = error_handler(operation(
@@ -332,10 +345,13 @@ where
       _ = receiver_future => {},
       _ = restart_rx.recv() => {
         print_after_restart();
+        if let Some(command) = &watch_options.exec {
+          run_watch_exec(command).await;
+        }
         continue;
       },
       success = operation_future => {
-        consume_paths_to_watch(&mut watcher, &mut paths_to_watch_rx, &exclude_set);
+        consume_paths_to_watch(&mut *watcher, &mut paths_to_watch_rx, &exclude_set);
         // TODO(bartlomieju): print exit code here?
         info!(
           "{} {} {}. Restarting on file change...",
@@ -352,7 +368,11 @@ where
     let receiver_future = async {
       loop {
         let maybe_paths = paths_to_watch_rx.recv().await;
-        add_paths_to_watcher(&mut watcher, &maybe_paths.unwrap(), &exclude_set);
+        add_paths_to_watcher(
+          &mut *watcher,
+          &maybe_paths.unwrap(),
+          &exclude_set,
+        );
       }
     };
 
@@ -363,42 +383,88 @@ where
       _ = receiver_future => {},
       _ = restart_rx.recv() => {
         print_after_restart();
+        if let Some(command) = &watch_options.exec {
+          run_watch_exec(command).await;
+        }
         continue;
       },
     }
   }
 }
 
+/// Runs the `--watch-exec` command before restarting. Failures are reported,
+/// but don't prevent the restart.
+async fn run_watch_exec(command: &str) {
+  let result = async {
+    let cwd = std::env::current_dir()?;
+    crate::task_runner::run_task(crate::task_runner::RunTaskOptions {
+      task_name: "--watch-exec",
+      script: command,
+      cwd: &cwd,
+      init_cwd: &cwd,
+      env_vars: std::env::vars().collect(),
+      argv: &[],
+      custom_commands: Default::default(),
+      root_node_modules_dir: None,
+    })
+    .await
+  }
+  .await;
+  match result {
+    Ok(0) => {}
+    Ok(exit_code) => log::warn!(
+      "{} --watch-exec command exited with code {}.",
+      colors::yellow("Warning"),
+      exit_code
+    ),
+    Err(err) => log::warn!(
+      "{} Failed running --watch-exec command: {:#}",
+      colors::yellow("Warning"),
+      err
+    ),
+  }
+}
+
 fn new_watcher(
   sender: Arc<mpsc::UnboundedSender<Vec<PathBuf>>>,
-) -> Result<RecommendedWatcher, AnyError> {
-  Ok(Watcher::new(
-    move |res: Result<NotifyEvent, NotifyError>| {
-      let Ok(event) = res else {
-        return;
-      };
-
-      if !matches!(
-        event.kind,
-        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
-      ) {
-        return;
-      }
+  poll_interval: Option<Duration>,
+) -> Result<Box<dyn Watcher>, AnyError> {
+  let event_handler = move |res: Result<NotifyEvent, NotifyError>| {
+    let Ok(event) = res else {
+      return;
+    };
 
-      let paths = event
-        .paths
-        .iter()
-        .filter_map(|path| canonicalize_path(path).ok())
-        .collect();
+    if !matches!(
+      event.kind,
+      EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    ) {
+      return;
+    }
 
-      sender.send(paths).unwrap();
-    },
-    Default::default(),
-  )?)
+    let paths = event
+      .paths
+      .iter()
+      .filter_map(|path| canonicalize_path(path).ok())
+      .collect();
+
+    sender.send(paths).unwrap();
+  };
+
+  // polling is slower, but works on file systems which don't report
+  // native events, like network shares
+  Ok(match poll_interval {
+    Some(interval) => Box::new(PollWatcher::new(
+      event_handler,
+      notify::Config::default().with_poll_interval(interval),
+    )?),
+    None => {
+      Box::new(RecommendedWatcher::new(event_handler, Default::default())?)
+    }
+  })
 }
 
 fn add_paths_to_watcher(
-  watcher: &mut RecommendedWatcher,
+  watcher: &mut dyn Watcher,
   paths: &[PathBuf],
   paths_to_exclude: &PathOrPatternSet,
 ) {
@@ -417,7 +483,7 @@ fn add_paths_to_watcher(
 }
 
 fn consume_paths_to_watch(
-  watcher: &mut RecommendedWatcher,
+  watcher: &mut dyn Watcher,
   receiver: &mut UnboundedReceiver<Vec<PathBuf>>,
   exclude_set: &PathOrPatternSet,
 ) {
@@ -1,9 +1,27 @@
 // Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
 
+use std::fmt;
 use std::io::Write;
 
+use deno_runtime::deno_io::is_redacting;
+use deno_runtime::deno_io::redact;
+
 use super::draw_thread::DrawThread;
 
+/// Displays the arguments of a log record with the values of `--redact-env`
+/// masked, which are only formatted into a string when there are any.
+struct Redacted<'a>(&'a fmt::Arguments<'a>);
+
+impl fmt::Display for Redacted<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if is_redacting() {
+      f.write_str(&redact(&self.0.to_string()))
+    } else {
+      self.0.fmt(f)
+    }
+  }
+}
+
 struct CliLogger(env_logger::Logger);
 
 impl CliLogger {
@@ -76,7 +94,7 @@ pub fn init(maybe_level: Option<log::Level>) {
         && record.level() == log::Level::Debug)
     {
       // Print ERROR, WARN, INFO and lsp_debug logs as they are
-      writeln!(buf, "{}", record.args())
+      writeln!(buf, "{}", Redacted(record.args()))
     } else {
       // Add prefix to DEBUG or TRACE logs
       writeln!(
@@ -84,7 +102,7 @@ pub fn init(maybe_level: Option<log::Level>) {
         "{} RS - {} - {}",
         record.level(),
         target,
-        record.args()
+        Redacted(record.args())
       )
     }
   })
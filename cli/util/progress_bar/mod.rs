@@ -8,6 +8,7 @@ use std::time::Instant;
 use deno_core::parking_lot::Mutex;
 use deno_runtime::ops::tty::ConsoleSize;
 
+use crate::args::ProgressMode;
 use crate::colors;
 
 use self::renderer::ProgressBarRenderer;
@@ -81,6 +82,10 @@ pub enum ProgressBarStyle {
 
   /// Shows a list of currently downloaded files.
   TextOnly,
+
+  /// Shows a single progress bar with the count of downloaded files, the
+  /// downloaded bytes and the host currently downloaded from.
+  DownloadSummary,
 }
 
 #[derive(Debug)]
@@ -135,6 +140,8 @@ struct InternalState {
   start_time: Instant,
   keep_alive_count: usize,
   total_entries: usize,
+  /// The bytes of the entries which have finished.
+  finished_size: u64,
   entries: Vec<Arc<ProgressBarEntry>>,
 }
 
@@ -152,6 +159,7 @@ impl ProgressBarInner {
         start_time: Instant::now(),
         keep_alive_count: 0,
         total_entries: 0,
+        finished_size: 0,
         entries: Vec::new(),
       })),
       renderer,
@@ -189,7 +197,8 @@ impl ProgressBarInner {
       .entries
       .binary_search_by(|e| e.id.cmp(&entry_id))
     {
-      internal_state.entries.remove(index);
+      let entry = internal_state.entries.remove(index);
+      internal_state.finished_size += entry.position();
       self.decrement_keep_alive(&mut internal_state);
     }
   }
@@ -247,6 +256,8 @@ impl DrawThreadRenderer for ProgressBarInner {
         terminal_width: size.cols,
         pending_entries: state.entries.len(),
         total_entries: state.total_entries,
+        downloaded_size: state.finished_size
+          + display_entries.iter().map(|e| e.position).sum::<u64>(),
         display_entries,
         percent_done: {
           let mut total_percent_sum = 0f64;
@@ -266,6 +277,7 @@ impl DrawThreadRenderer for ProgressBarInner {
 #[derive(Clone, Debug)]
 pub struct ProgressBar {
   inner: ProgressBarInner,
+  mode: ProgressMode,
 }
 
 impl ProgressBar {
@@ -275,7 +287,13 @@ impl ProgressBar {
   }
 
   pub fn new(style: ProgressBarStyle) -> Self {
+    Self::new_with_mode(style, ProgressMode::Auto)
+  }
+
+  /// Creates a progress bar which is displayed according to `--progress`.
+  pub fn new_with_mode(style: ProgressBarStyle, mode: ProgressMode) -> Self {
     Self {
+      mode,
       inner: ProgressBarInner::new(match style {
         ProgressBarStyle::DownloadBars => {
           Arc::new(renderer::BarProgressBarRenderer {
@@ -290,6 +308,9 @@ impl ProgressBar {
         ProgressBarStyle::TextOnly => {
           Arc::new(renderer::TextOnlyProgressBarRenderer::default())
         }
+        ProgressBarStyle::DownloadSummary => {
+          Arc::new(renderer::SummaryProgressBarRenderer)
+        }
       }),
     }
   }
@@ -303,9 +324,12 @@ impl ProgressBar {
     kind: ProgressMessagePrompt,
     msg: &str,
   ) -> UpdateGuard {
+    if self.mode == ProgressMode::None {
+      return UpdateGuard { maybe_entry: None };
+    }
     // only check if progress bars are supported once we go
     // to update so that we lazily initialize the progress bar
-    if ProgressBar::are_supported() {
+    if self.mode == ProgressMode::Auto && ProgressBar::are_supported() {
       let entry = self.inner.add_entry(kind, msg.to_string());
       UpdateGuard {
         maybe_entry: Some(entry),
@@ -4,6 +4,7 @@ use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
 
+use deno_core::url::Url;
 use deno_terminal::colors;
 
 use crate::util::display::human_download_size;
@@ -25,6 +26,8 @@ pub struct ProgressData {
   pub pending_entries: usize,
   pub percent_done: f64,
   pub total_entries: usize,
+  /// The bytes of the finished entries and the positions of the pending ones.
+  pub downloaded_size: u64,
   pub duration: Duration,
 }
 
@@ -97,24 +100,8 @@ impl ProgressBarRenderer for BarProgressBarRenderer {
     } else {
       max_width - same_line_text_width
     };
-    let completed_bars =
-      (total_bars as f64 * data.percent_done).floor() as usize;
-    text.push_str(" [");
-    if completed_bars != total_bars {
-      if completed_bars > 0 {
-        text.push_str(&format!(
-          "{}",
-          colors::cyan(format!("{}{}", "#".repeat(completed_bars - 1), ">"))
-        ))
-      }
-      text.push_str(&format!(
-        "{}",
-        colors::intense_blue("-".repeat(total_bars - completed_bars))
-      ))
-    } else {
-      text.push_str(&format!("{}", colors::cyan("#".repeat(completed_bars))))
-    }
-    text.push(']');
+    text.push(' ');
+    text.push_str(&get_bar_text(total_bars, data.percent_done));
 
     // suffix
     if display_entry.message.is_empty() {
@@ -126,6 +113,50 @@ impl ProgressBarRenderer for BarProgressBarRenderer {
   }
 }
 
+/// Aggregates all the entries in a single progress bar with the count of the
+/// finished entries, the downloaded bytes and the host of the latest entry,
+/// which stays readable when hundreds of modules are downloaded at once.
+#[derive(Debug)]
+pub struct SummaryProgressBarRenderer;
+
+impl ProgressBarRenderer for SummaryProgressBarRenderer {
+  fn render(&self, data: ProgressData) -> String {
+    let Some(display_entry) = data.display_entries.last() else {
+      return String::new();
+    };
+    let count_text = format!(
+      "{}/{}",
+      data.total_entries - data.pending_entries,
+      data.total_entries
+    );
+    let bytes_text =
+      human_download_size(data.downloaded_size, data.downloaded_size);
+    let elapsed_text = get_elapsed_text(data.duration);
+    let max_width = (data.terminal_width as i32 - 5).clamp(10, 75) as usize;
+    // space, open and close brace
+    let same_line_text_width = elapsed_text.len() + 3;
+    let total_bars = max_width.saturating_sub(same_line_text_width).max(1);
+
+    format!(
+      "{} {} {}{}\n{} {}",
+      display_entry.prompt.as_text(),
+      count_text,
+      bytes_text,
+      colors::gray(format!(" {}", get_host_text(&display_entry.message))),
+      elapsed_text,
+      get_bar_text(total_bars, data.percent_done),
+    )
+  }
+}
+
+/// The host of a url, or the message itself when it isn't a url.
+fn get_host_text(message: &str) -> String {
+  Url::parse(message)
+    .ok()
+    .and_then(|url| url.host_str().map(|host| host.to_string()))
+    .unwrap_or_else(|| message.to_string())
+}
+
 #[derive(Debug)]
 pub struct TextOnlyProgressBarRenderer {
   last_tick: AtomicUsize,
@@ -206,6 +237,27 @@ impl ProgressBarRenderer for TextOnlyProgressBarRenderer {
   }
 }
 
+fn get_bar_text(total_bars: usize, percent_done: f64) -> String {
+  let completed_bars = (total_bars as f64 * percent_done).floor() as usize;
+  let mut text = String::from("[");
+  if completed_bars != total_bars {
+    if completed_bars > 0 {
+      text.push_str(&format!(
+        "{}",
+        colors::cyan(format!("{}{}", "#".repeat(completed_bars - 1), ">"))
+      ))
+    }
+    text.push_str(&format!(
+      "{}",
+      colors::intense_blue("-".repeat(total_bars - completed_bars))
+    ))
+  } else {
+    text.push_str(&format!("{}", colors::cyan("#".repeat(completed_bars))))
+  }
+  text.push(']');
+  text
+}
+
 fn get_elapsed_text(elapsed: Duration) -> String {
   let elapsed_secs = elapsed.as_secs();
   let seconds = elapsed_secs % 60;
@@ -262,6 +314,7 @@ mod test {
       duration: Duration::from_secs(1),
       pending_entries: 1,
       total_entries: 1,
+      downloaded_size: 0,
       percent_done: 0f64,
       terminal_width: 50,
     };
@@ -306,6 +359,52 @@ mod test {
     assert_eq!(text, "[00:01] [###################################]",);
   }
 
+  #[test]
+  fn should_render_summary_progress() {
+    let renderer = SummaryProgressBarRenderer;
+    let mut data = ProgressData {
+      display_entries: vec![
+        ProgressDataDisplayEntry {
+          prompt: ProgressMessagePrompt::Download,
+          message: "https://deno.land/std/path/mod.ts".to_string(),
+          position: 0,
+          total_size: 0,
+        },
+        ProgressDataDisplayEntry {
+          prompt: ProgressMessagePrompt::Download,
+          message: "https://jsr.io/@std/path/meta.json".to_string(),
+          position: 0,
+          total_size: 0,
+        },
+      ],
+      duration: Duration::from_secs(1),
+      pending_entries: 2,
+      total_entries: 4,
+      downloaded_size: 5 * BYTES_TO_KIB,
+      percent_done: 0.5f64,
+      terminal_width: 50,
+    };
+    let text = renderer.render(data.clone());
+    let text = test_util::strip_ansi_codes(&text);
+    assert_eq!(
+      text,
+      concat!(
+        "Download 2/4 5.00KiB jsr.io\n",
+        "[00:01] [################>------------------]",
+      ),
+    );
+
+    data.display_entries.pop();
+    data.display_entries[0].message = "@denotest/esm-basic".to_string();
+    data.terminal_width = 0;
+    let text = renderer.render(data);
+    let text = test_util::strip_ansi_codes(&text);
+    assert_eq!(
+      text,
+      "Download 2/4 5.00KiB @denotest/esm-basic\n[00:01] [-]"
+    );
+  }
+
   #[test]
   fn should_render_text_only_progress() {
     let renderer = TextOnlyProgressBarRenderer::default();
@@ -319,6 +418,7 @@ mod test {
       duration: Duration::from_secs(1),
       pending_entries: 1,
       total_entries: 3,
+      downloaded_size: 0,
       percent_done: 0f64,
       terminal_width: 50,
     };
@@ -38,6 +38,7 @@ use deno_runtime::worker::MainWorker;
 use deno_runtime::worker::WorkerOptions;
 use deno_runtime::worker::WorkerServiceOptions;
 use deno_runtime::BootstrapOptions;
+use deno_runtime::ServeTlsOptions;
 use deno_runtime::WorkerExecutionMode;
 use deno_runtime::WorkerLogLevel;
 use deno_semver::npm::NpmPackageReqReference;
@@ -120,6 +121,13 @@ pub struct CliMainWorkerOptions {
   pub node_ipc: Option<i64>,
   pub serve_port: Option<u16>,
   pub serve_host: Option<String>,
+  pub serve_mirror: Option<String>,
+  pub serve_mirror_percent: Option<u8>,
+  pub serve_max_concurrent_uploads: Option<usize>,
+  pub serve_upload_high_water_mark: Option<usize>,
+  pub serve_upload_metrics_path: Option<String>,
+  pub serve_idle_shutdown: Option<u64>,
+  pub serve_tls: Option<ServeTlsOptions>,
 }
 
 struct SharedWorkerState {
@@ -610,6 +618,20 @@ impl CliMainWorkerFactory {
         mode,
         serve_port: shared.options.serve_port,
         serve_host: shared.options.serve_host.clone(),
+        serve_mirror: shared.options.serve_mirror.clone(),
+        serve_mirror_percent: shared.options.serve_mirror_percent,
+        serve_max_concurrent_uploads: shared
+          .options
+          .serve_max_concurrent_uploads,
+        serve_upload_high_water_mark: shared
+          .options
+          .serve_upload_high_water_mark,
+        serve_upload_metrics_path: shared
+          .options
+          .serve_upload_metrics_path
+          .clone(),
+        serve_idle_shutdown: shared.options.serve_idle_shutdown,
+        serve_tls: shared.options.serve_tls.clone(),
       },
       extensions: custom_extensions,
       startup_snapshot: crate::js::deno_isolate_init(),
@@ -811,6 +833,20 @@ fn create_web_worker_callback(
         mode: WorkerExecutionMode::Worker,
         serve_port: shared.options.serve_port,
         serve_host: shared.options.serve_host.clone(),
+        serve_mirror: shared.options.serve_mirror.clone(),
+        serve_mirror_percent: shared.options.serve_mirror_percent,
+        serve_max_concurrent_uploads: shared
+          .options
+          .serve_max_concurrent_uploads,
+        serve_upload_high_water_mark: shared
+          .options
+          .serve_upload_high_water_mark,
+        serve_upload_metrics_path: shared
+          .options
+          .serve_upload_metrics_path
+          .clone(),
+        serve_idle_shutdown: shared.options.serve_idle_shutdown,
+        serve_tls: shared.options.serve_tls.clone(),
       },
       extensions: vec![],
       startup_snapshot: crate::js::deno_isolate_init(),
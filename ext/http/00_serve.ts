@@ -13,6 +13,7 @@ import {
   op_http_close_after_finish,
   op_http_get_request_headers,
   op_http_get_request_method_and_url,
+  op_http_get_request_peer_certificate,
   op_http_read_request_body,
   op_http_serve,
   op_http_serve_on,
@@ -30,31 +31,40 @@ import {
 } from "ext:core/ops";
 const {
   ArrayPrototypePush,
+  MathRandom,
   ObjectHasOwn,
   ObjectPrototypeIsPrototypeOf,
   PromisePrototypeCatch,
+  PromisePrototypeFinally,
   PromisePrototypeThen,
   StringPrototypeIncludes,
   Symbol,
   TypeError,
+  TypedArrayPrototypeGetByteLength,
   TypedArrayPrototypeGetSymbolToStringTag,
   Uint8Array,
   Promise,
 } = primordials;
 
 import { InnerBody } from "ext:deno_fetch/22_body.js";
+import { Headers } from "ext:deno_fetch/20_headers.js";
+import { fetch } from "ext:deno_fetch/26_fetch.js";
+import { URL } from "ext:deno_url/00_url.js";
 import { Event } from "ext:deno_web/02_event.js";
 import {
   fromInnerResponse,
   newInnerResponse,
+  Response,
   ResponsePrototype,
   toInnerResponse,
 } from "ext:deno_fetch/23_response.js";
 import {
   abortRequest,
   fromInnerRequest,
+  Request,
   toInnerRequest,
 } from "ext:deno_fetch/23_request.js";
+import { ReadableStream } from "ext:deno_web/06_streams.js";
 import { AbortController } from "ext:deno_web/03_abort_signal.js";
 import {
   _eventLoop,
@@ -77,7 +87,11 @@ import {
   resourceForReadableStream,
 } from "ext:deno_web/06_streams.js";
 import { listen, listenOptionApiName, TcpConn } from "ext:deno_net/01_net.js";
-import { hasTlsKeyPairOptions, listenTls } from "ext:deno_net/02_tls.js";
+import {
+  clientAuthSymbol,
+  hasTlsKeyPairOptions,
+  listenTls,
+} from "ext:deno_net/02_tls.js";
 import { SymbolAsyncDispose } from "ext:deno_web/00_infra.js";
 
 const _upgraded = Symbol("_upgraded");
@@ -326,6 +340,13 @@ class InnerRequest {
     };
   }
 
+  get peerCertificate() {
+    if (this.#external === null) {
+      throw new TypeError("Request closed");
+    }
+    return op_http_get_request_peer_certificate(this.#external) ?? null;
+  }
+
   get method() {
     if (this.#methodAndUri === undefined) {
       if (this.#external === null) {
@@ -416,6 +437,10 @@ class ServeHandlerInfo {
   get remoteAddr() {
     return this.#inner.remoteAddr;
   }
+  /** The PEM encoded certificate of the client, when it was verified. */
+  get clientCertificate() {
+    return this.#inner.peerCertificate;
+  }
   get completed() {
     return this.#inner.completed;
   }
@@ -687,6 +712,7 @@ function serve(arg1, arg2) {
     listenOpts.cert = options.cert;
     listenOpts.key = options.key;
     listenOpts.alpnProtocols = ["h2", "http/1.1"];
+    listenOpts[clientAuthSymbol] = options[clientAuthSymbol];
     listener = listenTls(listenOpts);
     listenOpts.port = listener.addr.port;
   } else {
@@ -858,6 +884,135 @@ internals.upgradeHttpRaw = upgradeHttpRaw;
 internals.serveHttpOnListener = serveHttpOnListener;
 internals.serveHttpOnConnection = serveHttpOnConnection;
 
+/**
+ * Sends a copy of the request to the `mirror` backend without waiting for it,
+ * the response and any errors are ignored.
+ */
+function mirrorRequest(req, mirror) {
+  const clone = req.clone();
+  const url = new URL(clone.url);
+  const headers = new Headers(clone.headers);
+  headers.delete("host");
+  PromisePrototypeCatch(
+    PromisePrototypeThen(
+      fetch(new URL(url.pathname + url.search, mirror), {
+        method: clone.method,
+        headers,
+        body: clone.body,
+        redirect: "manual",
+      }),
+      (res) => res.body?.cancel(),
+    ),
+    () => {},
+  );
+}
+
+/**
+ * Counters for the request bodies streamed to the `fetch` handler of
+ * `deno serve`, served as JSON on `--upload-metrics-path`.
+ */
+const uploadMetrics = {
+  activeUploads: 0,
+  totalUploads: 0,
+  rejectedUploads: 0,
+  backpressureEvents: 0,
+};
+
+/**
+ * Counts the request as an active upload until its body is fully read or
+ * cancelled. With a `highWaterMark`, the body is re-wrapped in a stream that
+ * buffers at most that many bytes ahead of the handler, and every time the
+ * buffer fills up a backpressure event is recorded.
+ */
+function trackUpload(req, highWaterMark) {
+  uploadMetrics.activeUploads++;
+  uploadMetrics.totalUploads++;
+  let started = false;
+  let finished = false;
+  const finish = () => {
+    if (!finished) {
+      finished = true;
+      uploadMetrics.activeUploads--;
+    }
+  };
+
+  const reader = req.body.getReader();
+  const body = new ReadableStream({
+    async pull(controller) {
+      started = true;
+      try {
+        const { done, value } = await reader.read();
+        if (done) {
+          finish();
+          controller.close();
+          return;
+        }
+        controller.enqueue(value);
+        if (highWaterMark !== null && controller.desiredSize <= 0) {
+          uploadMetrics.backpressureEvents++;
+        }
+      } catch (error) {
+        finish();
+        controller.error(error);
+      }
+    },
+    cancel(reason) {
+      finish();
+      return reader.cancel(reason);
+    },
+  }, {
+    highWaterMark: highWaterMark ?? 0,
+    size: (chunk) => TypedArrayPrototypeGetByteLength(chunk),
+  });
+  // a body that the handler never started reading ends with the response
+  const finishUnlessStarted = () => {
+    if (!started) {
+      finish();
+    }
+  };
+  return { request: new Request(req, { body }), finishUnlessStarted };
+}
+
+/** Exit code of `deno serve --idle-shutdown` when the server was idle. */
+const IDLE_SHUTDOWN_EXIT_CODE = 75;
+
+/**
+ * Exits the process after no requests were handled for `seconds`. Returns
+ * functions to call when a request starts and when its handler settles, the
+ * timer only runs while no request is in flight.
+ */
+function createIdleShutdown(seconds) {
+  let inFlight = 0;
+  let timer = null;
+  const arm = () => {
+    timer = setTimeout(() => {
+      // deno-lint-ignore no-console
+      console.error(
+        `%cdeno serve%c: No requests for ${seconds}s, shutting down`,
+        "color: green",
+        "color: inherit",
+      );
+      Deno.exit(IDLE_SHUTDOWN_EXIT_CODE);
+    }, seconds * 1000);
+  };
+  return {
+    arm,
+    start() {
+      inFlight++;
+      if (timer !== null) {
+        clearTimeout(timer);
+        timer = null;
+      }
+    },
+    finish() {
+      inFlight--;
+      if (inFlight === 0) {
+        arm();
+      }
+    },
+  };
+}
+
 function registerDeclarativeServer(exports) {
   if (ObjectHasOwn(exports, "fetch")) {
     if (typeof exports.fetch !== "function") {
@@ -865,7 +1020,69 @@ function registerDeclarativeServer(exports) {
         "Invalid type for fetch: must be a function with a single or no parameter",
       );
     }
-    return ({ servePort, serveHost, serveIsMain, serveWorkerCount }) => {
+    return ({
+      servePort,
+      serveHost,
+      serveIsMain,
+      serveWorkerCount,
+      serveMirror,
+      serveMirrorPercent,
+      serveMaxConcurrentUploads,
+      serveUploadHighWaterMark,
+      serveUploadMetricsPath,
+      serveIdleShutdown,
+      serveTls,
+    }) => {
+      const idleShutdown = serveIdleShutdown !== null
+        ? createIdleShutdown(serveIdleShutdown)
+        : null;
+      // wrapping the body costs a stream and a request per upload, so it is
+      // only done when one of the upload options needs it
+      const trackUploads = serveMaxConcurrentUploads !== null ||
+        serveUploadHighWaterMark !== null || !!serveUploadMetricsPath;
+      const handler = (req, connInfo) => {
+        if (idleShutdown === null) {
+          return handleRequest(req, connInfo);
+        }
+        idleShutdown.start();
+        return PromisePrototypeFinally(
+          (async () => await handleRequest(req, connInfo))(),
+          idleShutdown.finish,
+        );
+      };
+      const handleRequest = (req, connInfo) => {
+        if (
+          serveUploadMetricsPath &&
+          new URL(req.url).pathname === serveUploadMetricsPath
+        ) {
+          return Response.json(uploadMetrics);
+        }
+        if (serveMirror && MathRandom() * 100 < serveMirrorPercent) {
+          mirrorRequest(req, serveMirror);
+        }
+        if (req.body === null || !trackUploads) {
+          return exports.fetch(req, connInfo);
+        }
+        if (
+          serveMaxConcurrentUploads !== null &&
+          uploadMetrics.activeUploads >= serveMaxConcurrentUploads
+        ) {
+          uploadMetrics.rejectedUploads++;
+          return new Response("Too many concurrent uploads", {
+            status: 503,
+            headers: { "retry-after": "1" },
+          });
+        }
+        const { request, finishUnlessStarted } = trackUpload(
+          req,
+          serveUploadHighWaterMark,
+        );
+        return PromisePrototypeFinally(
+          (async () => await exports.fetch(request, connInfo))(),
+          finishUnlessStarted,
+        );
+      };
+      const scheme = serveTls !== null ? "https://" : "http://";
       Deno.serve({
         port: servePort,
         hostname: serveHost,
@@ -880,17 +1097,23 @@ function registerDeclarativeServer(exports) {
 
             // deno-lint-ignore no-console
             console.error(
-              `%cdeno serve%c: Listening on %chttp://${host}:${port}/%c${nThreads}`,
+              `%cdeno serve%c: Listening on %c${scheme}${host}:${port}/%c${nThreads}`,
               "color: green",
               "color: inherit",
               "color: yellow",
               "color: inherit",
             );
           }
+          idleShutdown?.arm();
         },
-        handler: (req, connInfo) => {
-          return exports.fetch(req, connInfo);
-        },
+        handler,
+        ...(serveTls !== null && {
+          cert: serveTls.cert,
+          key: serveTls.key,
+          [clientAuthSymbol]: serveTls.clientCa !== null
+            ? { ca: serveTls.clientCa, optional: serveTls.clientAuthOptional }
+            : undefined,
+        }),
       });
     };
   }
@@ -18,6 +18,8 @@ use crate::service::HttpServerState;
 use crate::service::SignallingRc;
 use crate::websocket_upgrade::WebSocketUpgrade;
 use crate::LocalExecutor;
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
 use cache_control::CacheControl;
 use deno_core::error::AnyError;
 use deno_core::external;
@@ -332,6 +334,27 @@ where
   v8::Array::new_with_elements(scope, vec.as_slice())
 }
 
+/// Returns the PEM encoded certificate which the client presented, when the
+/// server verifies client certificates.
+#[op2]
+#[string]
+pub fn op_http_get_request_peer_certificate(
+  external: *const c_void,
+) -> Option<String> {
+  let http =
+    // SAFETY: op is called with external.
+    unsafe { clone_external!(external, "op_http_get_request_peer_certificate") };
+  let der = http.request_info().peer_certificate.as_ref()?.der()?;
+  let mut pem = String::from("-----BEGIN CERTIFICATE-----\n");
+  for chunk in BASE64_STANDARD.encode(der).as_bytes().chunks(64) {
+    // base64 is ASCII
+    pem.push_str(std::str::from_utf8(chunk).unwrap());
+    pem.push('\n');
+  }
+  pem.push_str("-----END CERTIFICATE-----\n");
+  Some(pem)
+}
+
 #[op2]
 #[serde]
 pub fn op_http_get_request_header(
@@ -2,6 +2,8 @@
 use deno_core::error::AnyError;
 use deno_core::OpState;
 use deno_core::ResourceId;
+use deno_net::ops_tls::PeerCertificate;
+use deno_net::ops_tls::TlsStreamResource;
 use deno_net::raw::take_network_stream_listener_resource;
 use deno_net::raw::take_network_stream_resource;
 use deno_net::raw::NetworkStream;
@@ -32,12 +34,20 @@ pub struct HttpConnectionProperties {
   pub peer_port: Option<u16>,
   pub local_port: Option<u16>,
   pub stream_type: NetworkStreamType,
+  pub peer_certificate: Option<PeerCertificate>,
 }
 
 pub struct HttpRequestProperties {
   pub authority: Option<String>,
 }
 
+/// A connection of the [`DefaultHttpPropertyExtractor`], which keeps the
+/// certificate of the client when the listener verifies client certificates.
+pub struct HttpNetworkConnection {
+  pub stream: NetworkStream,
+  pub peer_certificate: Option<PeerCertificate>,
+}
+
 /// Pluggable trait to determine listen, connection and request properties
 /// for embedders that wish to provide alternative routes for incoming HTTP.
 #[async_trait::async_trait(?Send)]
@@ -97,7 +107,7 @@ pub struct DefaultHttpPropertyExtractor {}
 impl HttpPropertyExtractor for DefaultHttpPropertyExtractor {
   type Listener = NetworkStreamListener;
 
-  type Connection = NetworkStream;
+  type Connection = HttpNetworkConnection;
 
   fn get_listener_for_rid(
     state: &mut OpState,
@@ -112,18 +122,36 @@ impl HttpPropertyExtractor for DefaultHttpPropertyExtractor {
   fn get_connection_for_rid(
     state: &mut OpState,
     stream_rid: ResourceId,
-  ) -> Result<NetworkStream, AnyError> {
-    take_network_stream_resource(&mut state.resource_table, stream_rid)
+  ) -> Result<HttpNetworkConnection, AnyError> {
+    let peer_certificate = state
+      .resource_table
+      .get::<TlsStreamResource>(stream_rid)
+      .ok()
+      .and_then(|resource| resource.peer_certificate().cloned());
+    let stream =
+      take_network_stream_resource(&mut state.resource_table, stream_rid)?;
+    Ok(HttpNetworkConnection {
+      stream,
+      peer_certificate,
+    })
   }
 
   async fn accept_connection_from_listener(
     listener: &NetworkStreamListener,
-  ) -> Result<NetworkStream, AnyError> {
-    listener
-      .accept()
-      .await
-      .map_err(Into::into)
-      .map(|(stm, _)| stm)
+  ) -> Result<HttpNetworkConnection, AnyError> {
+    if let NetworkStreamListener::Tls(listener) = listener {
+      let (stream, _, peer_certificate) =
+        listener.accept_with_peer_certificate().await?;
+      return Ok(HttpNetworkConnection {
+        stream: NetworkStream::Tls(stream),
+        peer_certificate,
+      });
+    }
+    let (stream, _) = listener.accept().await?;
+    Ok(HttpNetworkConnection {
+      stream,
+      peer_certificate: None,
+    })
   }
 
   fn listen_properties_from_listener(
@@ -137,23 +165,23 @@ impl HttpPropertyExtractor for DefaultHttpPropertyExtractor {
   fn listen_properties_from_connection(
     connection: &Self::Connection,
   ) -> Result<HttpListenProperties, std::io::Error> {
-    let stream_type = connection.stream();
-    let local_address = connection.local_address()?;
+    let stream_type = connection.stream.stream();
+    let local_address = connection.stream.local_address()?;
     listener_properties(stream_type, local_address)
   }
 
   fn to_network_stream_from_connection(
     connection: Self::Connection,
   ) -> NetworkStream {
-    connection
+    connection.stream
   }
 
   fn connection_properties(
     listen_properties: &HttpListenProperties,
-    connection: &NetworkStream,
+    connection: &HttpNetworkConnection,
   ) -> HttpConnectionProperties {
     // We always want some sort of peer address. If we can't get one, just make up one.
-    let peer_address = connection.peer_address().unwrap_or_else(|_| {
+    let peer_address = connection.stream.peer_address().unwrap_or_else(|_| {
       NetworkStreamAddress::Ip(SocketAddr::V4(SocketAddrV4::new(
         Ipv4Addr::new(0, 0, 0, 0),
         0,
@@ -177,6 +205,7 @@ impl HttpPropertyExtractor for DefaultHttpPropertyExtractor {
       peer_port,
       local_port,
       stream_type,
+      peer_certificate: connection.peer_certificate.clone(),
     }
   }
 
@@ -649,6 +649,7 @@ mod tests {
       peer_port: None,
       local_port: None,
       stream_type: NetworkStreamType::Tcp,
+      peer_certificate: None,
     };
     let svc = service_fn(move |req: hyper::Request<Incoming>| {
       handle_request(
@@ -57,6 +57,7 @@ use std::sync::Arc;
 
 pub mod fs;
 mod pipe;
+mod redact;
 #[cfg(windows)]
 mod winpipe;
 
@@ -69,6 +70,10 @@ pub use pipe::PipeRead;
 pub use pipe::PipeWrite;
 pub use pipe::RawPipeHandle;
 
+pub use redact::is_redacting;
+pub use redact::redact;
+pub use redact::set_redacted_values;
+
 pub use bi_pipe::bi_pipe_pair_raw;
 pub use bi_pipe::BiPipe;
 pub use bi_pipe::BiPipeRead;
@@ -1012,6 +1017,7 @@ pub fn op_print(
   is_err: bool,
 ) -> Result<(), AnyError> {
   let rid = if is_err { 2 } else { 1 };
+  let msg = redact(msg);
   FileResource::with_file(state, rid, move |file| {
     Ok(file.write_all_sync(msg.as_bytes())?)
   })
@@ -0,0 +1,67 @@
+// Copyright 2018-2024 the Deno authors. All rights reserved. MIT license.
+
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// The text which replaces a redacted value.
+pub const REDACTED_TEXT: &str = "[REDACTED]";
+
+static REDACTED_VALUES: OnceLock<Vec<String>> = OnceLock::new();
+
+/// Sets the secret values, like the values of the environment variables of
+/// `--redact-env`, which are masked in the output. Only the first call of a
+/// process has an effect.
+pub fn set_redacted_values(values: impl IntoIterator<Item = String>) {
+  let mut values = values
+    .into_iter()
+    .filter(|value| !value.is_empty())
+    .collect::<Vec<_>>();
+  // a value can contain another one, so the longest is replaced first
+  values.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+  values.dedup();
+  if !values.is_empty() {
+    _ = REDACTED_VALUES.set(values);
+  }
+}
+
+/// Whether there are secret values to mask, so text which is only formatted
+/// to be redacted doesn't have to be formatted otherwise.
+pub fn is_redacting() -> bool {
+  REDACTED_VALUES.get().is_some()
+}
+
+/// Masks the secret values in text which is about to be output.
+pub fn redact(text: &str) -> Cow<str> {
+  let Some(values) = REDACTED_VALUES.get() else {
+    return Cow::Borrowed(text);
+  };
+  redact_values(text, values)
+}
+
+fn redact_values<'a>(text: &'a str, values: &[String]) -> Cow<'a, str> {
+  let mut text = Cow::Borrowed(text);
+  for value in values {
+    if text.contains(value.as_str()) {
+      text = Cow::Owned(text.replace(value.as_str(), REDACTED_TEXT));
+    }
+  }
+  text
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn redacts_values() {
+    let values = vec!["secret-token-123".to_string(), "secret".to_string()];
+    assert_eq!(
+      redact_values("token: secret-token-123, key: secret", &values),
+      "token: [REDACTED], key: [REDACTED]"
+    );
+    assert!(matches!(
+      redact_values("nothing to hide", &values),
+      Cow::Borrowed(_)
+    ));
+  }
+}
@@ -165,9 +165,10 @@ function listenTls({
     );
   }
   const keyPair = loadTlsKeyPair("Deno.listenTls", arguments[0]);
+  const clientAuth = arguments[0][clientAuthSymbol];
   const { 0: rid, 1: localAddr } = op_net_listen_tls(
     { hostname, port },
-    { alpnProtocols, reusePort },
+    { alpnProtocols, reusePort, clientAuth },
     keyPair,
   );
   return new TlsListener(rid, localAddr);
@@ -193,6 +194,9 @@ async function startTls(
 
 const resolverSymbol = SymbolFor("unstableSniResolver");
 const serverNameSymbol = SymbolFor("unstableServerName");
+// `{ ca, optional }` to verify the certificates of the clients against the
+// PEM encoded CA certificates
+const clientAuthSymbol = SymbolFor("unstableClientAuth");
 
 function createTlsKeyResolver(callback) {
   const { 0: resolver, 1: lookup } = op_tls_cert_resolver_create();
@@ -223,6 +227,7 @@ internals.serverNameSymbol = serverNameSymbol;
 internals.createTlsKeyResolver = createTlsKeyResolver;
 
 export {
+  clientAuthSymbol,
   connectTls,
   hasTlsKeyPairOptions,
   listenTls,
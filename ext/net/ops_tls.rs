@@ -31,9 +31,24 @@ use deno_tls::create_client_config;
 use deno_tls::load_certs;
 use deno_tls::load_private_keys;
 use deno_tls::new_resolver;
+use deno_tls::rustls::client::danger::HandshakeSignatureValid;
+use deno_tls::rustls::crypto::ring::sign::any_supported_type;
+use deno_tls::rustls::pki_types::CertificateDer;
+use deno_tls::rustls::pki_types::PrivateKeyDer;
 use deno_tls::rustls::pki_types::ServerName;
+use deno_tls::rustls::pki_types::UnixTime;
+use deno_tls::rustls::server::danger::ClientCertVerified;
+use deno_tls::rustls::server::danger::ClientCertVerifier;
+use deno_tls::rustls::server::ClientHello;
+use deno_tls::rustls::server::ResolvesServerCert;
+use deno_tls::rustls::server::WebPkiClientVerifier;
+use deno_tls::rustls::sign::CertifiedKey;
 use deno_tls::rustls::ClientConnection;
+use deno_tls::rustls::DigitallySignedStruct;
+use deno_tls::rustls::DistinguishedName;
+use deno_tls::rustls::RootCertStore;
 use deno_tls::rustls::ServerConfig;
+use deno_tls::rustls::SignatureScheme;
 use deno_tls::ServerConfigProvider;
 use deno_tls::SocketUse;
 use deno_tls::TlsKey;
@@ -54,6 +69,7 @@ use std::net::SocketAddr;
 use std::num::NonZeroUsize;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::sync::Mutex;
 use tokio::io::AsyncReadExt;
 use tokio::io::AsyncWriteExt;
 use tokio::net::TcpStream;
@@ -67,12 +83,147 @@ pub struct TlsListener {
   pub(crate) tcp_listener: TcpListener,
   pub(crate) tls_config: Option<Arc<ServerConfig>>,
   pub(crate) server_config_provider: Option<ServerConfigProvider>,
+  pub(crate) client_auth: Option<TlsClientAuth>,
+}
+
+/// Verifies the certificates of the clients. The verifier is the only place
+/// where rustls hands out the certificate of the peer, so the server config is
+/// assembled per connection from the parts which are loaded once per listener.
+pub(crate) struct TlsClientAuth {
+  verifier: Arc<dyn ClientCertVerifier>,
+  cert_resolver: Arc<SingleCertResolver>,
+  alpn_protocols: Vec<Vec<u8>>,
+}
+
+impl TlsClientAuth {
+  fn new(
+    verifier: Arc<dyn ClientCertVerifier>,
+    cert_chain: Vec<CertificateDer<'static>>,
+    private_key: PrivateKeyDer<'static>,
+    alpn_protocols: Vec<Vec<u8>>,
+  ) -> Result<Self, deno_tls::rustls::Error> {
+    let signing_key = any_supported_type(&private_key)?;
+    Ok(Self {
+      verifier,
+      cert_resolver: Arc::new(SingleCertResolver(Arc::new(CertifiedKey::new(
+        cert_chain,
+        signing_key,
+      )))),
+      alpn_protocols,
+    })
+  }
+
+  fn server_config(&self, peer_certificate: PeerCertificate) -> ServerConfig {
+    let verifier = Arc::new(RecordingClientCertVerifier {
+      inner: self.verifier.clone(),
+      peer_certificate,
+    });
+    let mut tls_config = ServerConfig::builder()
+      .with_client_cert_verifier(verifier)
+      .with_cert_resolver(self.cert_resolver.clone());
+    tls_config.alpn_protocols = self.alpn_protocols.clone();
+    tls_config
+  }
+}
+
+#[derive(Debug)]
+struct SingleCertResolver(Arc<CertifiedKey>);
+
+impl ResolvesServerCert for SingleCertResolver {
+  fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+    Some(self.0.clone())
+  }
+}
+
+/// The certificate which a client presented in the TLS handshake, which is
+/// set once the certificate has been verified.
+#[derive(Clone, Debug, Default)]
+pub struct PeerCertificate(Arc<Mutex<Option<CertificateDer<'static>>>>);
+
+impl PeerCertificate {
+  /// Returns the DER encoded certificate of the peer, if it presented one.
+  pub fn der(&self) -> Option<Vec<u8>> {
+    self.0.lock().unwrap().as_ref().map(|cert| cert.to_vec())
+  }
+}
+
+#[derive(Debug)]
+struct RecordingClientCertVerifier {
+  inner: Arc<dyn ClientCertVerifier>,
+  peer_certificate: PeerCertificate,
+}
+
+impl ClientCertVerifier for RecordingClientCertVerifier {
+  fn offer_client_auth(&self) -> bool {
+    self.inner.offer_client_auth()
+  }
+
+  fn client_auth_mandatory(&self) -> bool {
+    self.inner.client_auth_mandatory()
+  }
+
+  fn root_hint_subjects(&self) -> &[DistinguishedName] {
+    self.inner.root_hint_subjects()
+  }
+
+  fn verify_client_cert(
+    &self,
+    end_entity: &CertificateDer<'_>,
+    intermediates: &[CertificateDer<'_>],
+    now: UnixTime,
+  ) -> Result<ClientCertVerified, deno_tls::rustls::Error> {
+    let verified =
+      self
+        .inner
+        .verify_client_cert(end_entity, intermediates, now)?;
+    *self.peer_certificate.0.lock().unwrap() =
+      Some(end_entity.clone().into_owned());
+    Ok(verified)
+  }
+
+  fn verify_tls12_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, deno_tls::rustls::Error> {
+    self.inner.verify_tls12_signature(message, cert, dss)
+  }
+
+  fn verify_tls13_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, deno_tls::rustls::Error> {
+    self.inner.verify_tls13_signature(message, cert, dss)
+  }
+
+  fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+    self.inner.supported_verify_schemes()
+  }
 }
 
 impl TlsListener {
   pub async fn accept(&self) -> std::io::Result<(TlsStream, SocketAddr)> {
+    let (tls, addr, _) = self.accept_with_peer_certificate().await?;
+    Ok((tls, addr))
+  }
+
+  /// Accepts a connection along with the certificate which the client
+  /// presents in the handshake, when the listener verifies client
+  /// certificates.
+  pub async fn accept_with_peer_certificate(
+    &self,
+  ) -> std::io::Result<(TlsStream, SocketAddr, Option<PeerCertificate>)> {
     let (tcp, addr) = self.tcp_listener.accept().await?;
-    let tls = if let Some(provider) = &self.server_config_provider {
+    let mut maybe_peer_certificate = None;
+    let tls = if let Some(client_auth) = &self.client_auth {
+      let peer_certificate = PeerCertificate::default();
+      let tls_config = client_auth.server_config(peer_certificate.clone());
+      maybe_peer_certificate = Some(peer_certificate);
+      TlsStream::new_server_side(tcp, tls_config.into(), TLS_BUFFER_SIZE)
+    } else if let Some(provider) = &self.server_config_provider {
       TlsStream::new_server_side_acceptor(
         tcp,
         provider.clone(),
@@ -85,8 +236,9 @@ impl TlsListener {
         TLS_BUFFER_SIZE,
       )
     };
-    Ok((tls, addr))
+    Ok((tls, addr, maybe_peer_certificate))
   }
+
   pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
     self.tcp_listener.local_addr()
   }
@@ -99,6 +251,9 @@ pub struct TlsStreamResource {
   // `None` when a TLS handshake hasn't been done.
   handshake_info: RefCell<Option<TlsHandshakeInfo>>,
   cancel_handle: CancelHandle, // Only read and handshake ops get canceled.
+  /// Set for the server side of connections whose listener verifies client
+  /// certificates.
+  peer_certificate: Option<PeerCertificate>,
 }
 
 impl TlsStreamResource {
@@ -108,9 +263,22 @@ impl TlsStreamResource {
       wr: wr.into(),
       handshake_info: RefCell::new(None),
       cancel_handle: Default::default(),
+      peer_certificate: None,
     }
   }
 
+  pub fn with_peer_certificate(
+    mut self,
+    peer_certificate: Option<PeerCertificate>,
+  ) -> Self {
+    self.peer_certificate = peer_certificate;
+    self
+  }
+
+  pub fn peer_certificate(&self) -> Option<&PeerCertificate> {
+    self.peer_certificate.as_ref()
+  }
+
   pub fn into_inner(self) -> (TlsStreamRead, TlsStreamWrite) {
     (self.rd.into_inner(), self.wr.into_inner())
   }
@@ -435,6 +603,34 @@ pub struct ListenTlsArgs {
   reuse_port: bool,
   #[serde(default)]
   load_balanced: bool,
+  #[serde(default)]
+  client_auth: Option<ListenTlsClientAuthArgs>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenTlsClientAuthArgs {
+  /// PEM encoded CA certificates which client certificates are verified
+  /// against.
+  ca: String,
+  /// Whether clients without a certificate are accepted.
+  optional: bool,
+}
+
+fn client_cert_verifier(
+  args: ListenTlsClientAuthArgs,
+) -> Result<Arc<dyn ClientCertVerifier>, AnyError> {
+  let mut roots = RootCertStore::empty();
+  for cert in load_certs(&mut BufReader::new(args.ca.as_bytes()))? {
+    roots.add(cert)?;
+  }
+  let builder = WebPkiClientVerifier::builder(Arc::new(roots));
+  let builder = if args.optional {
+    builder.allow_unauthenticated()
+  } else {
+    builder
+  };
+  Ok(builder.build()?)
 }
 
 #[op2]
@@ -474,8 +670,26 @@ where
     .into_iter()
     .map(|s| s.into_bytes())
     .collect();
+  let client_verifier = args
+    .client_auth
+    .map(client_cert_verifier)
+    .transpose()
+    .map_err(|e| {
+      custom_error("InvalidData", "Error creating TLS client verifier")
+        .context(e)
+    })?;
   let listener = match keys.take() {
     TlsKeys::Null => Err(anyhow!("Deno.listenTls requires a key")),
+    TlsKeys::Static(TlsKey(cert, key)) if client_verifier.is_some() => {
+      TlsClientAuth::new(client_verifier.unwrap(), cert, key, alpn)
+        .map(|client_auth| TlsListener {
+          tcp_listener,
+          tls_config: None,
+          server_config_provider: None,
+          client_auth: Some(client_auth),
+        })
+        .map_err(|e| anyhow!(e))
+    }
     TlsKeys::Static(TlsKey(cert, key)) => {
       let mut tls_config = ServerConfig::builder()
         .with_no_client_auth()
@@ -486,12 +700,17 @@ where
         tcp_listener,
         tls_config: Some(tls_config.into()),
         server_config_provider: None,
+        client_auth: None,
       })
     }
+    TlsKeys::Resolver(_) if client_verifier.is_some() => Err(anyhow!(
+      "Client certificates can't be verified with a certificate resolver"
+    )),
     TlsKeys::Resolver(resolver) => Ok(TlsListener {
       tcp_listener,
       tls_config: None,
       server_config_provider: Some(resolver.into_server_config_provider(alpn)),
+      client_auth: None,
     }),
   }
   .map_err(|e| {
@@ -522,21 +741,25 @@ pub async fn op_net_accept_tls(
     .try_borrow_mut()
     .ok_or_else(|| custom_error("Busy", "Another accept task is ongoing"))?;
 
-  let (tls_stream, remote_addr) =
-    match listener.accept().try_or_cancel(&cancel_handle).await {
-      Ok(tuple) => tuple,
-      Err(err) if err.kind() == ErrorKind::Interrupted => {
-        return Err(bad_resource("Listener has been closed"));
-      }
-      Err(err) => return Err(err.into()),
-    };
+  let (tls_stream, remote_addr, peer_certificate) = match listener
+    .accept_with_peer_certificate()
+    .try_or_cancel(&cancel_handle)
+    .await
+  {
+    Ok(tuple) => tuple,
+    Err(err) if err.kind() == ErrorKind::Interrupted => {
+      return Err(bad_resource("Listener has been closed"));
+    }
+    Err(err) => return Err(err.into()),
+  };
 
   let local_addr = tls_stream.local_addr()?;
   let rid = {
     let mut state_ = state.borrow_mut();
-    state_
-      .resource_table
-      .add(TlsStreamResource::new(tls_stream.into_split()))
+    state_.resource_table.add(
+      TlsStreamResource::new(tls_stream.into_split())
+        .with_peer_certificate(peer_certificate),
+    )
   };
 
   Ok((rid, IpAddr::from(local_addr), IpAddr::from(remote_addr)))
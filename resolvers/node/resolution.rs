@@ -137,11 +137,23 @@ pub type NodeResolverRc<TEnv> = crate::sync::MaybeArc<NodeResolver<TEnv>>;
 pub struct NodeResolver<TEnv: NodeResolverEnv> {
   env: TEnv,
   npm_resolver: NpmResolverRc,
+  /// Conditions which are matched in the "exports" and "imports" of
+  /// package.json files in addition to the default ones, like with the
+  /// `--conditions` flag of Node.
+  user_conditions: Vec<String>,
 }
 
 impl<TEnv: NodeResolverEnv> NodeResolver<TEnv> {
-  pub fn new(env: TEnv, npm_resolver: NpmResolverRc) -> Self {
-    Self { env, npm_resolver }
+  pub fn new(
+    env: TEnv,
+    npm_resolver: NpmResolverRc,
+    user_conditions: Vec<String>,
+  ) -> Self {
+    Self {
+      env,
+      npm_resolver,
+      user_conditions,
+    }
   }
 
   pub fn in_npm_package(&self, specifier: &Url) -> bool {
@@ -934,6 +946,7 @@ impl<TEnv: NodeResolverEnv> NodeResolver<TEnv> {
 
         if key == "default"
           || conditions.contains(&key.as_str())
+          || self.user_conditions.iter().any(|c| c == key)
           || mode.is_types() && key.as_str() == "types"
         {
           let condition_target = target_obj.get(key).unwrap();
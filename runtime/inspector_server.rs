@@ -29,16 +29,19 @@ use hyper_util::rt::TokioIo;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::net::ToSocketAddrs;
 use std::pin::pin;
 use std::process;
 use std::rc::Rc;
 use std::thread;
 use tokio::net::TcpListener;
+use tokio::net::TcpStream;
 use tokio::sync::broadcast;
 use uuid::Uuid;
 
 /// Websocket server that is used to proxy connections from
-/// devtools to the inspector.
+/// devtools to the inspector. With [`InspectorServer::connect`] it instead
+/// dials out to a debugger that is listening for connections.
 pub struct InspectorServer {
   pub host: SocketAddr,
   register_inspector_tx: UnboundedSender<InspectorInfo>,
@@ -48,37 +51,69 @@ pub struct InspectorServer {
 
 impl InspectorServer {
   pub fn new(host: SocketAddr, name: &'static str) -> Result<Self, AnyError> {
-    let (register_inspector_tx, register_inspector_rx) =
-      mpsc::unbounded::<InspectorInfo>();
-
-    let (shutdown_server_tx, shutdown_server_rx) = broadcast::channel(1);
-
     let tcp_listener =
       std::net::TcpListener::bind(host).with_context(|| {
         format!("Failed to start inspector server at \"{}\"", host)
       })?;
     tcp_listener.set_nonblocking(true)?;
 
-    let thread_handle = thread::spawn(move || {
-      let rt = crate::tokio_util::create_basic_runtime();
-      let local = tokio::task::LocalSet::new();
-      local.block_on(
-        &rt,
+    Ok(Self::spawn(
+      host,
+      move |register_inspector_rx, shutdown_server_rx| {
         server(
           tcp_listener,
           register_inspector_rx,
           shutdown_server_rx,
           name,
-        ),
-      )
+        )
+      },
+    ))
+  }
+
+  /// Creates an inspector that connects every registered isolate to the
+  /// debugger listening on `target` (`host:port`), for environments where
+  /// the debugger can't reach a port opened by Deno, like containers.
+  pub fn connect(target: &str, name: &'static str) -> Result<Self, AnyError> {
+    let host = target
+      .to_socket_addrs()
+      .ok()
+      .and_then(|mut addrs| addrs.next())
+      .with_context(|| {
+        format!("Failed to resolve the debugger address \"{}\"", target)
+      })?;
+
+    Ok(Self::spawn(
+      host,
+      move |register_inspector_rx, shutdown_server_rx| {
+        connect_server(host, register_inspector_rx, shutdown_server_rx, name)
+      },
+    ))
+  }
+
+  fn spawn<F, Fut>(host: SocketAddr, f: F) -> Self
+  where
+    F: FnOnce(UnboundedReceiver<InspectorInfo>, broadcast::Receiver<()>) -> Fut
+      + Send
+      + 'static,
+    Fut: Future<Output = ()>,
+  {
+    let (register_inspector_tx, register_inspector_rx) =
+      mpsc::unbounded::<InspectorInfo>();
+
+    let (shutdown_server_tx, shutdown_server_rx) = broadcast::channel(1);
+
+    let thread_handle = thread::spawn(move || {
+      let rt = crate::tokio_util::create_basic_runtime();
+      let local = tokio::task::LocalSet::new();
+      local.block_on(&rt, f(register_inspector_rx, shutdown_server_rx))
     });
 
-    Ok(Self {
+    Self {
       host,
       register_inspector_tx,
       shutdown_server_tx: Some(shutdown_server_tx),
       thread_handle: Some(thread_handle),
-    })
+    }
   }
 
   pub fn register_inspector(
@@ -376,6 +411,95 @@ async fn server(
   }
 }
 
+async fn connect_server(
+  target: SocketAddr,
+  register_inspector_rx: UnboundedReceiver<InspectorInfo>,
+  mut shutdown_server_rx: broadcast::Receiver<()>,
+  name: &'static str,
+) {
+  let mut register_inspector_handler = pin!(register_inspector_rx
+    .for_each(|info| {
+      spawn(connect_inspector(target, info, name));
+      future::ready(())
+    })
+    .fuse());
+  let mut shutdown_rx = pin!(shutdown_server_rx.recv().fuse());
+
+  select! {
+    _ = register_inspector_handler => {},
+    _ = shutdown_rx => {},
+  }
+}
+
+/// Opens a websocket connection to the debugger listening on `target` and
+/// starts a session for the inspector described by `info` over it.
+async fn connect_inspector(
+  target: SocketAddr,
+  info: InspectorInfo,
+  name: &'static str,
+) {
+  let url = info.get_websocket_debugger_url(&target.to_string());
+  log::info!("Debugger connecting to {}", url);
+  if let Err(err) = connect_websocket(target, &info, name).await {
+    log::error!("Failed to connect to the debugger at {}: {:#}", url, err);
+    if info.wait_for_session {
+      // The isolate would otherwise wait forever for a session.
+      process::exit(1);
+    }
+  }
+}
+
+async fn connect_websocket(
+  target: SocketAddr,
+  info: &InspectorInfo,
+  name: &'static str,
+) -> Result<(), AnyError> {
+  let stream = TcpStream::connect(target).await?;
+  let request = http::Request::builder()
+    .method(http::Method::GET)
+    .uri(format!("/ws/{}", info.uuid))
+    .header(http::header::HOST, target.to_string())
+    .header(http::header::USER_AGENT, name)
+    .header(http::header::UPGRADE, "websocket")
+    .header(http::header::CONNECTION, "Upgrade")
+    .header(
+      http::header::SEC_WEBSOCKET_KEY,
+      fastwebsockets::handshake::generate_key(),
+    )
+    .header(http::header::SEC_WEBSOCKET_VERSION, "13")
+    .body(http_body_util::Empty::<Bytes>::new())?;
+  let (websocket, _) =
+    fastwebsockets::handshake::client(&LocalExecutor, request, stream).await?;
+
+  // The 'outbound' channel carries messages sent to the websocket.
+  let (outbound_tx, outbound_rx) = mpsc::unbounded();
+  // The 'inbound' channel carries messages received from the websocket.
+  let (inbound_tx, inbound_rx) = mpsc::unbounded();
+
+  let inspector_session_proxy = InspectorSessionProxy {
+    tx: outbound_tx,
+    rx: inbound_rx,
+  };
+
+  log::info!("Debugger session started.");
+  let _ = info.new_session_tx.unbounded_send(inspector_session_proxy);
+  pump_websocket_messages(websocket, inbound_tx, outbound_rx).await;
+  Ok(())
+}
+
+// Runs the futures of the websocket handshake on the inspector thread.
+struct LocalExecutor;
+
+impl<Fut> hyper::rt::Executor<Fut> for LocalExecutor
+where
+  Fut: Future + 'static,
+  Fut::Output: 'static,
+{
+  fn execute(&self, fut: Fut) {
+    spawn(fut);
+  }
+}
+
 /// The pump future takes care of forwarding messages between the websocket
 /// and channels. It resolves when either side disconnects, ignoring any
 /// errors.
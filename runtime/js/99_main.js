@@ -574,6 +574,13 @@ function bootstrapMainRuntime(runtimeOptions, warmup = false) {
       10: serveHost,
       11: serveIsMain,
       12: serveWorkerCount,
+      13: serveMirror,
+      14: serveMirrorPercent,
+      15: serveMaxConcurrentUploads,
+      16: serveUploadHighWaterMark,
+      17: serveUploadMetricsPath,
+      18: serveIdleShutdown,
+      19: serveTls,
     } = runtimeOptions;
 
     if (mode === executionModes.serve) {
@@ -634,7 +641,19 @@ function bootstrapMainRuntime(runtimeOptions, warmup = false) {
             );
           }
           if (mode === executionModes.serve) {
-            serve({ servePort, serveHost, serveIsMain, serveWorkerCount });
+            serve({
+              servePort,
+              serveHost,
+              serveIsMain,
+              serveWorkerCount,
+              serveMirror,
+              serveMirrorPercent,
+              serveMaxConcurrentUploads,
+              serveUploadHighWaterMark,
+              serveUploadMetricsPath,
+              serveIdleShutdown,
+              serveTls,
+            });
           }
         }
       });
@@ -338,7 +338,7 @@ fn op_runtime_memory_usage(scope: &mut v8::HandleScope) -> MemoryUsage {
 }
 
 #[cfg(any(target_os = "android", target_os = "linux"))]
-fn rss() -> usize {
+pub fn rss() -> usize {
   // Inspired by https://github.com/Arc-blroth/memory-stats/blob/5364d0d09143de2a470d33161b2330914228fde9/src/linux.rs
 
   // Extracts a positive integer from a string that
@@ -389,7 +389,7 @@ fn rss() -> usize {
 }
 
 #[cfg(target_os = "macos")]
-fn rss() -> usize {
+pub fn rss() -> usize {
   // Inspired by https://github.com/Arc-blroth/memory-stats/blob/5364d0d09143de2a470d33161b2330914228fde9/src/darwin.rs
 
   let mut task_info =
@@ -412,7 +412,7 @@ fn rss() -> usize {
 }
 
 #[cfg(target_os = "openbsd")]
-fn rss() -> usize {
+pub fn rss() -> usize {
   // Uses OpenBSD's KERN_PROC_PID sysctl(2)
   // to retrieve information about the current
   // process, part of which is the RSS (p_vm_rssize)
@@ -459,7 +459,7 @@ fn rss() -> usize {
 }
 
 #[cfg(windows)]
-fn rss() -> usize {
+pub fn rss() -> usize {
   use winapi::shared::minwindef::DWORD;
   use winapi::shared::minwindef::FALSE;
   use winapi::um::processthreadsapi::GetCurrentProcess;
@@ -118,6 +118,28 @@ pub struct BootstrapOptions {
   // Used by `deno serve`
   pub serve_port: Option<u16>,
   pub serve_host: Option<String>,
+  pub serve_mirror: Option<String>,
+  pub serve_mirror_percent: Option<u8>,
+  pub serve_max_concurrent_uploads: Option<usize>,
+  pub serve_upload_high_water_mark: Option<usize>,
+  pub serve_upload_metrics_path: Option<String>,
+  pub serve_idle_shutdown: Option<u64>,
+  pub serve_tls: Option<ServeTlsOptions>,
+}
+
+/// The TLS configuration of the server started by `deno serve`.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServeTlsOptions {
+  /// PEM encoded certificate chain.
+  pub cert: String,
+  /// PEM encoded private key.
+  pub key: String,
+  /// PEM encoded CA certificates which the certificates of the clients are
+  /// verified against.
+  pub client_ca: Option<String>,
+  /// Whether clients without a certificate are accepted.
+  pub client_auth_optional: bool,
 }
 
 impl Default for BootstrapOptions {
@@ -152,6 +174,13 @@ impl Default for BootstrapOptions {
       mode: WorkerExecutionMode::None,
       serve_port: Default::default(),
       serve_host: Default::default(),
+      serve_mirror: Default::default(),
+      serve_mirror_percent: Default::default(),
+      serve_max_concurrent_uploads: Default::default(),
+      serve_upload_high_water_mark: Default::default(),
+      serve_upload_metrics_path: Default::default(),
+      serve_idle_shutdown: Default::default(),
+      serve_tls: Default::default(),
     }
   }
 }
@@ -193,6 +222,20 @@ struct BootstrapV8<'a>(
   Option<bool>,
   // serve worker count
   Option<usize>,
+  // serve mirror
+  Option<&'a str>,
+  // serve mirror percent
+  u8,
+  // serve max concurrent uploads
+  Option<usize>,
+  // serve upload high water mark
+  Option<usize>,
+  // serve upload metrics path
+  Option<&'a str>,
+  // serve idle shutdown
+  Option<u64>,
+  // serve tls
+  Option<&'a ServeTlsOptions>,
 );
 
 impl BootstrapOptions {
@@ -219,6 +262,13 @@ impl BootstrapOptions {
       self.serve_host.as_deref(),
       serve_is_main,
       serve_worker_count,
+      self.serve_mirror.as_deref(),
+      self.serve_mirror_percent.unwrap_or_default(),
+      self.serve_max_concurrent_uploads,
+      self.serve_upload_high_water_mark,
+      self.serve_upload_metrics_path.as_deref(),
+      self.serve_idle_shutdown,
+      self.serve_tls.as_ref(),
     );
 
     bootstrap.serialize(ser).unwrap()
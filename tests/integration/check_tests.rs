@@ -259,3 +259,49 @@ fn npm_module_check_then_error() {
     .assert_matches_text("Check [WILDCARD]main.ts\nerror: TS2305[WILDCARD]has no exported member 'oldName'[WILDCARD]")
     .assert_exit_code(1);
 }
+
+#[cfg(unix)]
+#[test]
+fn check_daemon_per_environment() {
+  let test_context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = test_context.temp_dir();
+  let runtime_dir = temp_dir.path().join("runtime");
+  runtime_dir.create_dir_all();
+  let sockets_dir = runtime_dir.join("deno");
+  let socket_paths = || {
+    let mut paths = sockets_dir
+      .read_dir()
+      .map(|entry| entry.unwrap().path())
+      .collect::<Vec<_>>();
+    paths.sort();
+    paths
+  };
+  let check = |envs: &[(&str, &str)], expected: &str| {
+    test_context
+      .new_command()
+      .args_vec(["check", "main.ts"])
+      .env("DENO_CHECK_DAEMON", "1")
+      .env("XDG_RUNTIME_DIR", runtime_dir.as_path())
+      .envs(envs.iter().copied())
+      .with_pty(|mut console| console.expect(expected));
+  };
+
+  temp_dir.write("main.ts", "const value: string = 1;\n");
+  check(&[], "TS2322");
+  assert_eq!(socket_paths().len(), 1);
+
+  // the daemon checks the changed module again
+  temp_dir.write("main.ts", "Math.max('1');\n");
+  check(&[], "TS2345");
+  assert_eq!(socket_paths().len(), 1);
+
+  // another value of a variable which affects resolution gets its own daemon
+  // instead of changing the environment of the running one
+  check(&[("DENO_NO_PACKAGE_JSON", "1")], "TS2345");
+  assert_eq!(socket_paths().len(), 2);
+
+  // the daemons exit when their sockets are removed
+  for path in socket_paths() {
+    std::fs::remove_file(path).unwrap();
+  }
+}
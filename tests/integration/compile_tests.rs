@@ -891,6 +891,46 @@ fn compile_npm_bin_cjs() {
   });
 }
 
+#[test]
+fn compile_npm_licenses() {
+  let context = TestContextBuilder::for_npm().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  let binary_path = if cfg!(windows) {
+    temp_dir.path().join("cli-esm.exe")
+  } else {
+    temp_dir.path().join("cli-esm")
+  };
+
+  context
+    .new_command()
+    .args("compile npm:@denotest/bin/cli-esm")
+    .run()
+    .assert_exit_code(0)
+    .skip_output_check();
+  context
+    .new_command()
+    .name(&binary_path)
+    .arg("--licenses")
+    .run()
+    .assert_matches_text("[WILDCARD]@denotest/bin@1.0.0[WILDCARD]")
+    .assert_exit_code(0);
+
+  // the argument is passed through to the program without embedded licenses
+  context
+    .new_command()
+    .args("compile --no-licenses npm:@denotest/bin/cli-esm")
+    .run()
+    .assert_exit_code(0)
+    .skip_output_check();
+  context
+    .new_command()
+    .name(&binary_path)
+    .arg("--licenses")
+    .run()
+    .assert_matches_text("--licenses\n")
+    .assert_exit_code(0);
+}
+
 #[test]
 fn compile_npm_cowsay_main() {
   run_npm_bin_compile_test(RunNpmBinCompileOptions {
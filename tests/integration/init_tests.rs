@@ -171,6 +171,90 @@ Run these commands to get started
   output.assert_matches_text("Log from main.ts that already exists\n");
 }
 
+#[test]
+fn init_subcommand_npm_compat() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let cwd = context.temp_dir().path();
+
+  let output = context
+    .new_command()
+    .args("init --npm-compat")
+    .split_output()
+    .run();
+
+  output.assert_exit_code(0);
+  let stderr = output.stderr();
+  assert_contains!(stderr, "Project initialized");
+  assert_contains!(stderr, "npm install");
+  assert_contains!(stderr, "deno task dev");
+
+  assert!(cwd.join("main.ts").exists());
+  assert!(cwd.join("main_test.ts").exists());
+  assert!(cwd.join("tsconfig.json").exists());
+  assert_contains!(cwd.join("package.json").read_to_string(), "\"chalk\"");
+  assert_contains!(
+    cwd.join("deno.json").read_to_string(),
+    "\"nodeModulesDir\": \"manual\""
+  );
+}
+
+#[test]
+fn init_subcommand_with_local_template() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let cwd = context.temp_dir().path();
+
+  let template_dir = cwd.join("template");
+  template_dir.join("src").create_dir_all();
+  template_dir.join("deno-template.json").write(
+    r#"{ "variables": { "greeting": { "prompt": "Greeting", "default": "Hello" } } }"#,
+  );
+  template_dir
+    .join("deno.json")
+    .write(r#"{ "name": "@scope/{{ name }}" }"#);
+  template_dir
+    .join("src/main.ts")
+    .write("console.log('{{ greeting }} from {{name}}');");
+
+  let output = context
+    .new_command()
+    .args("init my-app --template ./template")
+    .split_output()
+    .run();
+
+  output.assert_exit_code(0);
+  output.assert_stderr_matches_text(
+    "✅ Project initialized
+
+Run this command to get started
+
+  cd my-app
+",
+  );
+
+  let project_dir = cwd.join("my-app");
+  assert!(!project_dir.join("deno-template.json").exists());
+  project_dir
+    .join("deno.json")
+    .assert_matches_text(r#"{ "name": "@scope/my-app" }"#);
+  project_dir
+    .join("src/main.ts")
+    .assert_matches_text("console.log('Hello from my-app');");
+}
+
+#[test]
+fn init_subcommand_with_unknown_template() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+
+  let output = context
+    .new_command()
+    .args("init --template unknown")
+    .split_output()
+    .run();
+
+  output.assert_exit_code(1);
+  assert_contains!(output.stderr(), "Unknown template \"unknown\"");
+}
+
 #[tokio::test]
 async fn init_subcommand_serve() {
   let context = TestContextBuilder::for_jsr().use_temp_cwd().build();
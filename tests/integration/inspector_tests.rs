@@ -316,6 +316,99 @@ async fn inspector_connect() {
   child.wait().unwrap();
 }
 
+#[tokio::test]
+async fn inspector_connect_to_debugger() {
+  let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+  let addr = listener.local_addr().unwrap();
+  let script = util::testdata_path().join("inspector/inspector1.js");
+  let mut child = util::deno_cmd()
+    .arg("run")
+    .arg(format!("--inspect-connect={addr}"))
+    .arg(script)
+    .stderr_piped()
+    .spawn()
+    .unwrap();
+
+  let stderr = child.stderr.take().unwrap();
+  let mut stderr_lines = StdErrLines::new(stderr);
+  assert_starts_with!(
+    stderr_lines.next().unwrap(),
+    &format!("Debugger connecting to ws://{addr}/ws/")
+  );
+
+  // Act as the debugger, accepting the websocket connection from Deno.
+  let (stream, _) = listener.accept().await.unwrap();
+  let (upgrade_tx, mut upgrade_rx) = tokio::sync::mpsc::unbounded_channel();
+  let service =
+    hyper::service::service_fn(move |mut req: Request<Incoming>| {
+      let upgrade_tx = upgrade_tx.clone();
+      async move {
+        assert!(req.uri().path().starts_with("/ws/"));
+        let (response, fut) = fastwebsockets::upgrade::upgrade(&mut req)?;
+        upgrade_tx.send(fut).unwrap();
+        Ok::<_, fastwebsockets::WebSocketError>(response)
+      }
+    });
+  deno_core::unsync::spawn(async move {
+    hyper::server::conn::http1::Builder::new()
+      .serve_connection(TokioIo::new(stream), service)
+      .with_upgrades()
+      .await
+      .unwrap();
+  });
+  let mut socket = upgrade_rx.recv().await.unwrap().await.unwrap();
+  assert_eq!(stderr_lines.next().unwrap(), "Debugger session started.");
+
+  socket
+    .write_frame(Frame::text(
+      json!({"id":1,"method":"Runtime.enable"})
+        .to_string()
+        .into_bytes()
+        .into(),
+    ))
+    .await
+    .unwrap();
+  loop {
+    let frame = timeout(Duration::from_secs(10), socket.read_frame())
+      .await
+      .unwrap()
+      .unwrap();
+    let msg = String::from_utf8(frame.payload.to_vec()).unwrap();
+    if msg.starts_with(r#"{"id":1,"#) {
+      assert_eq!(msg, r#"{"id":1,"result":{}}"#);
+      break;
+    }
+  }
+
+  child.kill().unwrap();
+  child.wait().unwrap();
+}
+
+#[tokio::test]
+async fn inspector_connect_to_debugger_refused() {
+  // Reserve a port and close it again, so nothing is listening on it.
+  let addr = std::net::TcpListener::bind("127.0.0.1:0")
+    .unwrap()
+    .local_addr()
+    .unwrap();
+  let script = util::testdata_path().join("inspector/inspector1.js");
+  let output = util::deno_cmd()
+    .arg("run")
+    .arg(format!("--inspect-connect={addr}"))
+    .arg(script)
+    .stderr_piped()
+    .spawn()
+    .unwrap()
+    .wait_with_output()
+    .unwrap();
+
+  assert!(!output.status.success());
+  let stderr =
+    util::strip_ansi_codes(std::str::from_utf8(&output.stderr).unwrap())
+      .to_string();
+  assert_contains!(stderr, "Failed to connect to the debugger at");
+}
+
 #[tokio::test]
 async fn inspector_break_on_first_line() {
   let script = util::testdata_path().join("inspector/inspector2.js");
@@ -1202,6 +1202,70 @@ fn lsp_deno_task() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_deno_task_run_command() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write(
+    "deno.json",
+    r#"{
+    "tasks": {
+      "hello": "echo hello"
+    }
+  }"#,
+  );
+
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+
+  let res = client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.task.list",
+    }),
+  );
+  assert_eq!(
+    res,
+    json!([
+      {
+        "name": "hello",
+        "detail": "echo hello",
+        "sourceUri": temp_dir.url().join("deno.json").unwrap(),
+      }
+    ])
+  );
+
+  let res = client.write_request(
+    "workspace/executeCommand",
+    json!({
+      "command": "deno.task.run",
+      "arguments": [{
+        "name": "hello",
+        "sourceUri": temp_dir.url().join("deno.json").unwrap(),
+      }],
+    }),
+  );
+  assert_eq!(res, json!({ "id": 1, "inspect": null }));
+
+  let mut stdout = String::new();
+  loop {
+    let params = client
+      .read_notification_with_method::<Value>("deno/taskRunProgress")
+      .unwrap();
+    assert_eq!(params["id"], 1);
+    let message = &params["message"];
+    if message["type"] == "end" {
+      assert_eq!(message["exitCode"], 0);
+      break;
+    }
+    if message["stream"] == "stdout" {
+      stdout.push_str(message["text"].as_str().unwrap());
+    }
+  }
+  assert_eq!(stdout.trim(), "hello");
+  client.shutdown();
+}
+
 #[test]
 fn lsp_reload_import_registries_command() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
@@ -2725,6 +2789,54 @@ fn lsp_document_preload_limit_zero_deno_json_detection() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_diagnostics_scope_workspace() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let temp_dir = context.temp_dir();
+  temp_dir.write("deno.json", json!({}).to_string());
+  temp_dir.write("main.ts", "console.log(1);\n");
+  temp_dir.write("closed.ts", "const a: number = \"\";\nconsole.log(a);\n");
+  let mut client = context.new_lsp_command().build();
+  client.initialize(|builder| {
+    builder.set_diagnostics_scope("workspace");
+  });
+  let diagnostics = client.did_open(json!({
+    "textDocument": {
+      "uri": temp_dir.url().join("main.ts").unwrap(),
+      "languageId": "typescript",
+      "version": 1,
+      "text": "console.log(1);\n",
+    },
+  }));
+  assert_eq!(diagnostics.all().len(), 0);
+  // the file which isn't open is checked in the background
+  let closed_uri = temp_dir.url().join("closed.ts").unwrap();
+  let diagnostics = loop {
+    let params = client
+      .read_notification_with_method::<lsp::PublishDiagnosticsParams>(
+        "textDocument/publishDiagnostics",
+      )
+      .unwrap();
+    if params.uri.as_str() == closed_uri.as_str() {
+      break params.diagnostics;
+    }
+  };
+  assert_eq!(
+    json!(diagnostics),
+    json!([{
+      "range": {
+        "start": { "line": 0, "character": 6 },
+        "end": { "line": 0, "character": 7 },
+      },
+      "severity": 1,
+      "code": 2322,
+      "source": "deno-ts",
+      "message": "Type 'string' is not assignable to type 'number'.",
+    }]),
+  );
+  client.shutdown();
+}
+
 // Regression test for https://github.com/denoland/deno/issues/23141.
 #[test]
 fn lsp_import_map_setting_with_deno_json() {
@@ -7061,6 +7173,50 @@ fn lsp_code_actions_refactor_no_disabled_support() {
   client.shutdown();
 }
 
+#[test]
+fn lsp_code_actions_refactor_convert_to_async() {
+  let context = TestContextBuilder::new().use_temp_cwd().build();
+  let mut client = context.new_lsp_command().build();
+  client.initialize_default();
+  client.did_open(json!({
+    "textDocument": {
+      "uri": "file:///a/file.ts",
+      "languageId": "typescript",
+      "version": 1,
+      "text": "export function f() {\n  return fetch(\"https://deno.land\").then((r) => r.text());\n}\n",
+    },
+  }));
+  let res = client.write_request(
+    "textDocument/codeAction",
+    json!({
+      "textDocument": { "uri": "file:///a/file.ts" },
+      "range": {
+        "start": { "line": 0, "character": 16 },
+        "end": { "line": 0, "character": 16 },
+      },
+      "context": {
+        "diagnostics": [],
+        "only": ["refactor"],
+      },
+    }),
+  );
+  let action = res
+    .as_array()
+    .unwrap()
+    .iter()
+    .find(|a| a["kind"] == "refactor.rewrite.function.async")
+    .unwrap();
+  assert_eq!(action["title"], "Convert to async function");
+  let new_text = action["edit"]["documentChanges"][0]["edits"]
+    .as_array()
+    .unwrap()
+    .iter()
+    .map(|e| e["newText"].as_str().unwrap())
+    .collect::<String>();
+  assert!(new_text.contains("async"), "{new_text}");
+  client.shutdown();
+}
+
 #[test]
 fn lsp_code_actions_deadlock() {
   let context = TestContextBuilder::new().use_temp_cwd().build();
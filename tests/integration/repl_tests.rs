@@ -1136,3 +1136,66 @@ fn eval_file_promise_error() {
   assert_contains!(out, "Uncaught undefined");
   assert!(err.is_empty());
 }
+
+#[test]
+fn auto_import_runs_side_effects_once() {
+  let test_context = TestContextBuilder::for_npm()
+    .use_temp_cwd()
+    .env("NO_COLOR", "1")
+    .build();
+  test_context
+    .new_command()
+    .args_vec(["repl", "-A", "--auto-import"])
+    .with_pty(|mut console| {
+      console.write_line("let counter = 0;");
+      console.expect("undefined");
+      console.write_line("counter++; chalk.red('hello');");
+      console.expect("chalk is not defined. Import it from npm:chalk?");
+      console.write_line("y");
+      console.expect("Imported chalk from npm:chalk.");
+      console.write_line("counter");
+      console.expect("1");
+
+      // code without side effects is evaluated again
+      console.write_line("typeof chalkTemplate.red");
+      console.expect("chalkTemplate is not defined. Import it from");
+      console.write_line("npm:chalk");
+      console.expect("\"function\"");
+    });
+}
+
+#[test]
+fn save_and_load_session() {
+  let test_context = TestContextBuilder::new()
+    .use_temp_cwd()
+    .env("NO_COLOR", "1")
+    .build();
+  test_context
+    .new_command()
+    .args_vec(["repl"])
+    .with_pty(|mut console| {
+      console.write_line("const value = await Promise.resolve(41);");
+      console.expect("undefined");
+      console.write_line(".save");
+      console.expect("error: Usage: .save <file>");
+      console.write_line(".save session.ts");
+      console.expect("Session saved to");
+    });
+  test_context
+    .temp_dir()
+    .path()
+    .join("session.ts")
+    .assert_matches_text("const value = await Promise.resolve(41);\n");
+
+  test_context
+    .new_command()
+    .args_vec(["repl"])
+    .with_pty(|mut console| {
+      console.write_line(".load");
+      console.expect("error: Usage: .load <file>");
+      console.write_line(".load session.ts");
+      console.expect("Session loaded from");
+      console.write_line("value + 1");
+      console.expect("42");
+    });
+}
@@ -5175,3 +5175,40 @@ fn handle_invalid_path_error() {
   let output = deno_cmd.arg("run").arg("///a/b").output().unwrap();
   assert_contains!(String::from_utf8_lossy(&output.stderr), "Module not found");
 }
+
+#[test]
+#[cfg(unix)]
+fn ephemeral_tmp_removed_on_sigterm() {
+  use std::io::BufRead;
+
+  use nix::sys::signal;
+  use nix::sys::signal::Signal;
+  use nix::unistd::Pid;
+
+  let context = TestContext::default();
+  let main = context.temp_dir().path().join("main.ts");
+  main.write(
+    "console.log(Deno.env.get('TMPDIR'));\nsetInterval(() => {}, 1000);",
+  );
+  let mut child = context
+    .new_command()
+    .args_vec([
+      "run",
+      "--allow-env=TMPDIR",
+      "--ephemeral-tmp",
+      &main.to_string_lossy(),
+    ])
+    .spawn_with_piped_output();
+  let mut tmp_dir = String::new();
+  std::io::BufReader::new(child.stdout.take().unwrap())
+    .read_line(&mut tmp_dir)
+    .unwrap();
+  let tmp_dir = std::path::PathBuf::from(tmp_dir.trim());
+  assert!(tmp_dir.is_dir());
+
+  // the supervisor passes the signal on and cleans up once the script exits
+  signal::kill(Pid::from_raw(child.id() as i32), Signal::SIGTERM).unwrap();
+  let status = child.wait().unwrap();
+  assert!(!status.success());
+  assert!(!tmp_dir.exists());
+}
@@ -1880,3 +1880,64 @@ setInterval(() => {
 
   check_alive_then_kill(child);
 }
+
+#[flaky_test(tokio)]
+async fn compile_watch_run() {
+  let t = TempDir::new();
+  let file_to_watch = t.path().join("file_to_watch.ts");
+  file_to_watch.write("console.log('compiled 1');");
+  let exe = t
+    .path()
+    .join(if cfg!(windows) { "main.exe" } else { "main" });
+
+  let mut child = util::deno_cmd()
+    .current_dir(t.path())
+    .arg("compile")
+    .arg("--watch")
+    .arg("--watch-run")
+    .arg("-L")
+    .arg("debug")
+    .arg("--output")
+    .arg(&exe)
+    .arg(&file_to_watch)
+    .env("NO_COLOR", "1")
+    .piped_output()
+    .spawn()
+    .unwrap();
+  let (mut stdout_lines, mut stderr_lines) = child_lines(&mut child);
+
+  wait_contains("compiled 1", &mut stdout_lines).await;
+  wait_for_watcher("file_to_watch.ts", &mut stderr_lines).await;
+
+  // the executable is rebuilt with a new dependency
+  let another_file = t.path().join("another_file.ts");
+  another_file.write("export const value = 2;");
+  file_to_watch.write(
+    "import { value } from './another_file.ts'; console.log(`compiled ${value}`);",
+  );
+
+  wait_contains("Restarting", &mut stderr_lines).await;
+  wait_contains("compiled 2", &mut stdout_lines).await;
+  wait_for_watcher("another_file.ts", &mut stderr_lines).await;
+
+  // and again when the dependency changes
+  another_file.write("export const value = 3;");
+
+  wait_contains("Restarting", &mut stderr_lines).await;
+  wait_contains("compiled 3", &mut stdout_lines).await;
+  wait_for_watcher("file_to_watch.ts", &mut stderr_lines).await;
+
+  // a build error doesn't stop the watcher
+  file_to_watch.write("syntax error ^^");
+
+  wait_contains("Restarting", &mut stderr_lines).await;
+  wait_contains("error:", &mut stderr_lines).await;
+  wait_for_watcher("file_to_watch.ts", &mut stderr_lines).await;
+
+  file_to_watch.write("console.log('compiled 4');");
+
+  wait_contains("Restarting", &mut stderr_lines).await;
+  wait_contains("compiled 4", &mut stdout_lines).await;
+  assert!(exe.exists());
+  check_alive_then_kill(child);
+}
@@ -0,0 +1 @@
+export const condition = "default";
@@ -0,0 +1,3 @@
+Deno.bench("add", () => {
+  1 + 1;
+});
@@ -0,0 +1,7 @@
+Deno.bench("add", () => {
+  1 + 1;
+});
+
+Deno.bench("concat", { group: "strings", baseline: true }, () => {
+  "a" + "b";
+});
@@ -0,0 +1,2 @@
+const { add } = await import("./util.ts");
+console.log(add(1, 2));
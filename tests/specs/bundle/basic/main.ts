@@ -0,0 +1,5 @@
+import { add } from "./util.ts";
+import data from "./data.json" with { type: "json" };
+
+console.log(add(1, 2));
+console.log(data.name);
@@ -0,0 +1,4 @@
+import { getValue, setValue } from "npm:@denotest/esm-basic";
+
+setValue(5);
+console.log(getValue());
@@ -0,0 +1,4 @@
+import "http://localhost:4545/echo.ts";
+import { getValue } from "npm:@denotest/esm-basic@1";
+
+console.log(getValue());
@@ -0,0 +1,2 @@
+const message: string = "Hello";
+console.log(message);
@@ -0,0 +1,3 @@
+import { value } from "./shared.ts";
+
+export const doubled: number = value * 2;
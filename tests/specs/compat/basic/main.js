@@ -0,0 +1,5 @@
+import fs from "node:fs";
+import { getHeapSnapshot } from "node:v8";
+const trace = require("trace_events");
+
+fs.writeFileSync("heap", getHeapSnapshot());
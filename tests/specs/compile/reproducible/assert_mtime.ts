@@ -0,0 +1,2 @@
+const { mtime } = Deno.statSync(Deno.args[0]);
+console.log(mtime?.getTime());
@@ -0,0 +1,5 @@
+const actual = Deno.readTextFileSync("deno.jsonc");
+const expected = Deno.readTextFileSync("expected.jsonc");
+if (actual !== expected) {
+  throw new Error(`Unexpected deno.jsonc:\n${actual}`);
+}
@@ -0,0 +1,3 @@
+export function subtract(a: number, b: number): number {
+  return a - b;
+}
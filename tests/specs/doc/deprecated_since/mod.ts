@@ -0,0 +1,20 @@
+/**
+ * Reads the config.
+ *
+ * @since 1.0.0
+ * @deprecated Use {@linkcode loadConfig} instead.
+ */
+export function readConfig() {
+}
+
+/**
+ * Loads the config.
+ *
+ * @since 1.2.0
+ */
+export function loadConfig() {
+}
+
+/** Writes the config. */
+export function writeConfig() {
+}
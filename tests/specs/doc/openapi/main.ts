@@ -0,0 +1,15 @@
+import { route } from "jsr:@std/http/unstable-route";
+import { userRoutes } from "./users.ts";
+
+const routes = [
+  ...userRoutes,
+  /** Check the health of the service */
+  {
+    pattern: new URLPattern({ pathname: "/health" }),
+    handler: () => new Response("ok"),
+  },
+];
+
+export default {
+  fetch: route(routes, () => new Response("Not found", { status: 404 })),
+} satisfies Deno.ServeDefaultExport;
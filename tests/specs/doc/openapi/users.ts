@@ -0,0 +1,28 @@
+export const userRoutes = [
+  /**
+   * Get a user
+   *
+   * Returns the user of the id.
+   * @tag users
+   * @param id The id of the user
+   */
+  {
+    pattern: new URLPattern({ pathname: "/users/:id(\\d+)" }),
+    method: "GET",
+    handler: getUser,
+  },
+  /**
+   * Create or replace users
+   * @tag users
+   * @deprecated
+   */
+  {
+    pattern: new URLPattern({ pathname: "/users" }),
+    method: ["POST", "PUT"],
+    handler: (_req: Request) => new Response("created", { status: 201 }),
+  },
+];
+
+function getUser(_req: Request, _info: unknown, params?: URLPatternResult) {
+  return Response.json({ id: params?.pathname.groups.id });
+}
@@ -0,0 +1,4 @@
+import { healthRoutes } from "@/health.ts";
+import { userRoutes } from "./missing.ts";
+
+export const routes = [...healthRoutes, ...userRoutes];
@@ -0,0 +1,7 @@
+export const healthRoutes = [
+  /** Check the health of the service */
+  {
+    pattern: new URLPattern({ pathname: "/health" }),
+    handler: () => new Response("ok"),
+  },
+];
@@ -0,0 +1 @@
+const b = 2;
@@ -0,0 +1,5 @@
+const output = JSON.parse(Deno.readTextFileSync("fmt.json"));
+console.log(output.checkedFiles);
+for (const file of output.notFormatted) {
+  console.log(file.reason, file.file.endsWith("unformatted.ts"));
+}
@@ -0,0 +1,6 @@
+import { format } from "./format.ts";
+
+export function add(a, b) {
+  console.log(format(a));
+  return a + b;
+}
@@ -0,0 +1,5 @@
+// @deno-types="./lib.d.ts"
+import { add } from "./lib.js";
+import { format } from "./format.ts";
+
+console.log(format(add(1, 2)));
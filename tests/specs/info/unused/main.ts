@@ -0,0 +1,4 @@
+import { add } from "@denotest/add";
+import { greeting } from "utils/strings.ts";
+
+console.log(add(1, 2), greeting);
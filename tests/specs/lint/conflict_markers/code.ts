@@ -0,0 +1,7 @@
+export function greet(): string {
+<<<<<<< HEAD
+  return "Hello";
+=======
+  return "Hi";
+>>>>>>> feature
+}
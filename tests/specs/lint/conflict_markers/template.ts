@@ -0,0 +1,7 @@
+export const message = `
+<<<<<<< HEAD
+Hello
+=======
+Hi
+>>>>>>> feature
+`;
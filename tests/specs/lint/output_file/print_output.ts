@@ -0,0 +1,2 @@
+const output = JSON.parse(Deno.readTextFileSync("lint.json"));
+console.log(output.diagnostics.map((d: { code: string }) => d.code));
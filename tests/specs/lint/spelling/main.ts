@@ -0,0 +1,7 @@
+// the reponse is passed through as is
+export function parseRespnse(text: string): string {
+  return text;
+}
+
+// recieve is a project word
+export const message = "it was recieved";
@@ -0,0 +1,5 @@
+// TODO(alice): retry the request, see #12
+// todo (bob) handle the errors, see #13
+// TODO: cache the responses
+// FIXME is not a configured keyword
+export function request(): void {}
@@ -0,0 +1,3 @@
+import { subtract } from "npm:@denotest/subtract@1";
+
+console.log(subtract(3, 2));
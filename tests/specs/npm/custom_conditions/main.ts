@@ -0,0 +1,3 @@
+import { condition } from "npm:@denotest/custom-conditions";
+
+console.log(condition);
@@ -0,0 +1,5 @@
+const packageJson = Deno.statSync(
+  "./node_modules/.deno/@denotest+esm-basic@1.0.0/node_modules/@denotest/esm-basic/package.json",
+);
+// hard linked from the npm cache and the content addressed store
+console.log(packageJson.nlink! >= 3);
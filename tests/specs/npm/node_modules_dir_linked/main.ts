@@ -0,0 +1,3 @@
+import { getValue } from "npm:@denotest/esm-basic";
+
+console.log(getValue());
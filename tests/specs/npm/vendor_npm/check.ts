@@ -0,0 +1,4 @@
+const packageDir = "./vendor/npm/localhost_4260/@denotest/esm-basic/1.0.0";
+const integrity = Deno.readTextFileSync(`${packageDir}.integrity`);
+console.log(integrity.split("-")[0]);
+console.log(Deno.statSync(`${packageDir}/package.json`).isFile);
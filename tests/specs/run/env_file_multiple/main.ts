@@ -0,0 +1,3 @@
+for (const name of ["API_URL", "BASE_URL", "USERS_URL", "MODE"]) {
+  console.log(`${name}=${Deno.env.get(name)}`);
+}
@@ -0,0 +1,7 @@
+const tmpDir = Deno.readTextFileSync("tmp_dir.txt");
+try {
+  Deno.statSync(tmpDir);
+  console.log("exists");
+} catch (err) {
+  console.log(err instanceof Deno.errors.NotFound ? "removed" : err);
+}
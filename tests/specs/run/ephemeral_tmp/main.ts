@@ -0,0 +1,4 @@
+const tmpDir = Deno.env.get("TMPDIR")!;
+Deno.writeTextFileSync(`${tmpDir}/file.txt`, "data");
+console.log(Deno.readTextFileSync(`${tmpDir}/file.txt`));
+Deno.writeTextFileSync("tmp_dir.txt", tmpDir);
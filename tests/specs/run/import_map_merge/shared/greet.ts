@@ -0,0 +1,5 @@
+import { name } from "name";
+
+export function greet() {
+  console.log(`Hello from the ${name}`);
+}
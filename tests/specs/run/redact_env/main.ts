@@ -0,0 +1,4 @@
+const token = Deno.env.get("API_TOKEN");
+console.log(`token: ${token}`);
+console.error(`authorization: Bearer ${token}`);
+throw new Error(`request failed with ${token}`);
@@ -0,0 +1,13 @@
+// fails until it ran the given number of times
+const [countFile, failures] = Deno.args;
+let count = 0;
+try {
+  count = Number(Deno.readTextFileSync(countFile));
+} catch {
+  // first run
+}
+Deno.writeTextFileSync(countFile, String(count + 1));
+console.log(`run ${count}`);
+if (count < Number(failures)) {
+  Deno.exit(1);
+}
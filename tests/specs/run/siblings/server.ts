@@ -0,0 +1,4 @@
+// runs until it's stopped
+console.log("listening");
+Deno.writeTextFileSync("ready.txt", "");
+setInterval(() => {}, 1000);
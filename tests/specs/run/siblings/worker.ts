@@ -0,0 +1,11 @@
+// exits once the server is ready
+while (true) {
+  try {
+    Deno.statSync("ready.txt");
+    break;
+  } catch {
+    await new Promise((resolve) => setTimeout(resolve, 10));
+  }
+}
+console.log("done");
+Deno.exit(3);
@@ -0,0 +1,3 @@
+import { authorization } from "http://localhost:4545/echo_authorization.ts";
+
+console.log(authorization);
@@ -0,0 +1,3 @@
+import { authorization } from "https://localhost:5545/echo_authorization.ts";
+
+console.log(authorization);
@@ -0,0 +1,6 @@
+import { name as rootDep } from "root-dep";
+import { name as rootDev } from "root-dev";
+import { name as memberDep } from "member-dep";
+import { name as memberDev } from "member-dev";
+
+console.log(rootDep, rootDev, memberDep, memberDev);
@@ -0,0 +1,19 @@
+(async () => {
+  for (let i = 0; i < 1000; i++) {
+    try {
+      const resp = await fetch("http://localhost:12469/");
+      console.log(await resp.text());
+      return;
+    } catch {
+      await new Promise((r) => setTimeout(r, 10));
+    }
+  }
+
+  Deno.exit(2);
+})();
+
+export default {
+  fetch(_request) {
+    return new Response("Hello world!");
+  },
+} satisfies Deno.ServeDefaultExport;
@@ -0,0 +1,3 @@
+import { env } from "config";
+
+console.log(env);
@@ -0,0 +1,11 @@
+Deno.test("writes a log", async (t) => {
+  const dir = t.artifactsDir;
+  console.log(dir);
+  if (dir !== undefined) {
+    Deno.writeTextFileSync(`${dir}/log.txt`, "log");
+    await t.step("nested step", (t) => {
+      console.log(t.artifactsDir);
+    });
+  }
+  throw new Error("fail");
+});
@@ -0,0 +1,14 @@
+Deno.test("stops when the run is cancelled", async (t) => {
+  let timeoutId: number | undefined;
+  await new Promise<void>((resolve, reject) => {
+    t.signal.addEventListener("abort", () => resolve());
+    timeoutId = setTimeout(() => reject(new Error("not aborted")), 10_000);
+  });
+  clearTimeout(timeoutId);
+  if (!(t.signal.reason instanceof DOMException)) {
+    throw new Error("unexpected abort reason");
+  }
+  if (t.signal.reason.name !== "AbortError") {
+    throw new Error("unexpected abort reason");
+  }
+});
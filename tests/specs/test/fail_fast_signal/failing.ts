@@ -0,0 +1,7 @@
+Deno.test("fails", async () => {
+  // let the other test start before failing
+  await new Promise((resolve) => setTimeout(resolve, 100));
+  throw new Error("boom");
+});
+
+Deno.test("never runs", () => {});
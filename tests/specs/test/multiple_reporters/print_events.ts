@@ -0,0 +1,5 @@
+const events = Deno.readTextFileSync("events.ndjson").trim().split("\n")
+  .map((line) => JSON.parse(line));
+console.log(events.map((event) => event.type).join(","));
+const report = Deno.readTextFileSync("report.xml");
+console.log(report.includes('<testcase name="passes"'));
@@ -0,0 +1,7 @@
+Deno.test("passes", async (t) => {
+  await t.step("step", () => {});
+});
+
+Deno.test("fails", () => {
+  throw new Error("failed");
+});
@@ -0,0 +1,16 @@
+const name = Deno.args[0];
+const res = await fetch(
+  `http://localhost:4545/otel_collector/${name}/v1/traces`,
+);
+if (!res.ok) {
+  console.log(res.status);
+  Deno.exit(0);
+}
+const { resourceSpans: [{ resource, scopeSpans: [{ spans }] }] } = await res
+  .json();
+console.log(resource.attributes[0].value.stringValue);
+const names = new Map(spans.map((span) => [span.spanId, span.name]));
+for (const span of spans) {
+  const parent = names.get(span.parentSpanId) ?? "-";
+  console.log(`${span.name} < ${parent} (status ${span.status.code})`);
+}
@@ -0,0 +1,6 @@
+Deno.test("reads the setup data", () => {
+  const data = Deno.test.getSetupData<{ url: () => string }>();
+  if (data.url() !== "postgres://localhost:5432") {
+    throw new Error("Unexpected setup data");
+  }
+});
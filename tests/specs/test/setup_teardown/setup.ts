@@ -0,0 +1,5 @@
+export default function () {
+  console.log("setup");
+  // the value isn't serialized, so it can hold functions
+  return { url: () => "postgres://localhost:5432" };
+}
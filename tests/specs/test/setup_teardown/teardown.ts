@@ -0,0 +1,3 @@
+export default function (data: { url: () => string }) {
+  console.log("teardown", data.url());
+}
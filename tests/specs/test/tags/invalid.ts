@@ -0,0 +1,6 @@
+Deno.test({
+  name: "invalid",
+  // @ts-expect-error tags must be strings
+  tags: "integration",
+  fn() {},
+});
@@ -0,0 +1,19 @@
+Deno.test("unit", () => {});
+
+Deno.test({
+  name: "database",
+  tags: ["integration"],
+  fn() {},
+});
+
+Deno.test({
+  name: "api",
+  tags: ["integration", "slow"],
+  fn() {},
+});
+
+Deno.test({
+  name: "browser",
+  tags: ["e2e", "slow"],
+  fn() {},
+});
@@ -0,0 +1,9 @@
+Deno.test("slow", async () => {
+  await new Promise((resolve) => setTimeout(resolve, 50));
+});
+
+Deno.test("hangs", async () => {
+  await new Promise((resolve) => setTimeout(resolve, 100_000));
+});
+
+Deno.test("after", () => {});
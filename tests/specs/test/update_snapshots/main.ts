@@ -0,0 +1,3 @@
+Deno.test("update snapshots", () => {
+  console.log("updateSnapshots:", Deno.test.updateSnapshots);
+});
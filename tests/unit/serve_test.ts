@@ -3179,6 +3179,106 @@ Deno.test(
   },
 );
 
+function serveWithClientAuth(
+  ac: AbortController,
+  listen: ReturnType<typeof onListen>,
+  optional = false,
+) {
+  const ca = Deno.readTextFileSync("tests/testdata/tls/RootCA.pem");
+  return Deno.serve({
+    handler: (_req, info) => new Response(info.clientCertificate ?? "none"),
+    hostname: "127.0.0.1",
+    port: servePort,
+    signal: ac.signal,
+    onListen: listen,
+    onError: createOnErrorCb(ac),
+    cert: Deno.readTextFileSync("tests/testdata/tls/localhost.crt"),
+    key: Deno.readTextFileSync("tests/testdata/tls/localhost.key"),
+    // @ts-ignore the option is internal to `deno serve --tls-client-ca`
+    [Symbol.for("unstableClientAuth")]: { ca, optional },
+  });
+}
+
+Deno.test(
+  { permissions: { read: true, net: true } },
+  async function httpServerWithTlsClientAuthAccepted() {
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+    const server = serveWithClientAuth(ac, onListen(resolve));
+
+    await promise;
+    const caCert = Deno.readTextFileSync("tests/testdata/tls/RootCA.pem");
+    const cert = Deno.readTextFileSync("tests/testdata/tls/localhost.crt");
+    const client = Deno.createHttpClient({
+      caCerts: [caCert],
+      cert,
+      key: Deno.readTextFileSync("tests/testdata/tls/localhost.key"),
+    });
+    const resp = await fetch(`https://localhost:${servePort}/`, {
+      client,
+      headers: { "connection": "close" },
+    });
+
+    // the certificate is the one of the connection, re-encoded as PEM
+    const respBody = await resp.text();
+    assertEquals(
+      respBody.replace(/\s/g, ""),
+      cert.replace(/\s/g, ""),
+    );
+
+    client.close();
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { read: true, net: true } },
+  async function httpServerWithTlsClientAuthRejected() {
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+    const server = serveWithClientAuth(ac, onListen(resolve));
+
+    await promise;
+    const caCert = Deno.readTextFileSync("tests/testdata/tls/RootCA.pem");
+    const client = Deno.createHttpClient({ caCerts: [caCert] });
+    await assertRejects(
+      () =>
+        fetch(`https://localhost:${servePort}/`, {
+          client,
+          headers: { "connection": "close" },
+        }),
+      TypeError,
+    );
+
+    client.close();
+    ac.abort();
+    await server.finished;
+  },
+);
+
+Deno.test(
+  { permissions: { read: true, net: true } },
+  async function httpServerWithOptionalTlsClientAuth() {
+    const ac = new AbortController();
+    const { promise, resolve } = Promise.withResolvers<void>();
+    const server = serveWithClientAuth(ac, onListen(resolve), true);
+
+    await promise;
+    const caCert = Deno.readTextFileSync("tests/testdata/tls/RootCA.pem");
+    const client = Deno.createHttpClient({ caCerts: [caCert] });
+    const resp = await fetch(`https://localhost:${servePort}/`, {
+      client,
+      headers: { "connection": "close" },
+    });
+    assertEquals(await resp.text(), "none");
+
+    client.close();
+    ac.abort();
+    await server.finished;
+  },
+);
+
 Deno.test(
   { permissions: { net: true, write: true, read: true } },
   async function httpServerRequestCLTE() {
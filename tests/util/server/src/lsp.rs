@@ -406,6 +406,15 @@ impl InitializeParamsBuilder {
     self
   }
 
+  pub fn set_diagnostics_scope(&mut self, value: impl AsRef<str>) -> &mut Self {
+    let options = self.initialization_options_mut();
+    options.insert(
+      "diagnosticsScope".to_string(),
+      value.as_ref().to_string().into(),
+    );
+    self
+  }
+
   pub fn set_tls_certificate(&mut self, value: impl AsRef<str>) -> &mut Self {
     let options = self.initialization_options_mut();
     options.insert(
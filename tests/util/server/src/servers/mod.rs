@@ -25,6 +25,8 @@ use http_body_util::combinators::UnsyncBoxBody;
 use http_body_util::BodyExt;
 use http_body_util::Empty;
 use http_body_util::Full;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use pretty_assertions::assert_eq;
 use prost::Message;
 use std::collections::HashMap;
@@ -58,6 +60,16 @@ pub(crate) const PORT: u16 = 4545;
 const TEST_AUTH_TOKEN: &str = "abcdef123456789";
 const TEST_BASIC_AUTH_USERNAME: &str = "testuser123";
 const TEST_BASIC_AUTH_PASSWORD: &str = "testpassabc";
+
+/// The files of the `DENO_CACHE_BACKEND` store under `/cache_store/`.
+static CACHE_STORE: Lazy<Mutex<HashMap<String, Bytes>>> =
+  Lazy::new(Default::default);
+/// The requests to the cache store, as `<method> <path> <status>` lines.
+static CACHE_STORE_LOG: Lazy<Mutex<Vec<String>>> = Lazy::new(Default::default);
+/// The last OTLP/JSON traces export posted to each collector under
+/// `/otel_collector/<name>/v1/traces`.
+static OTEL_COLLECTOR: Lazy<Mutex<HashMap<String, Bytes>>> =
+  Lazy::new(Default::default);
 const KV_DATABASE_ID: &str = "11111111-1111-1111-1111-111111111111";
 const KV_ACCESS_TOKEN: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
 const KV_DATABASE_TOKEN: &str = "MOCKMOCKMOCKMOCKMOCKMOCKMOCK";
@@ -881,6 +893,56 @@ async fn main_server(
       );
       Ok(res)
     }
+    (_, "/echo_authorization.ts") => {
+      let authorization = req
+        .headers()
+        .get("authorization")
+        .map(|v| v.to_str().unwrap());
+      let mut res = Response::new(string_body(&format!(
+        "export const authorization = {};",
+        serde_json::json!(authorization)
+      )));
+      res.headers_mut().insert(
+        "Content-type",
+        HeaderValue::from_static("application/typescript"),
+      );
+      Ok(res)
+    }
+    (&Method::POST, "/osv/v1/querybatch") => {
+      // a mock of the OSV API, where only @denotest/vulnerable is affected
+      let body = req.into_body().collect().await?.to_bytes();
+      let body: serde_json::Value = serde_json::from_slice(&body)?;
+      let results = body["queries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|query| {
+          if query["package"]["name"] == "@denotest/vulnerable" {
+            serde_json::json!({
+              "vulns": [{ "id": "GHSA-test-high" }, { "id": "GHSA-test-unknown" }]
+            })
+          } else {
+            serde_json::json!({})
+          }
+        })
+        .collect::<Vec<_>>();
+      Ok(Response::new(json_body(
+        serde_json::json!({ "results": results }),
+      )))
+    }
+    (&Method::GET, "/osv/v1/vulns/GHSA-test-high") => {
+      Ok(Response::new(json_body(serde_json::json!({
+        "id": "GHSA-test-high",
+        "summary": "Prototype pollution",
+        "database_specific": { "severity": "HIGH" },
+      }))))
+    }
+    (&Method::GET, "/osv/v1/vulns/GHSA-test-unknown") => {
+      Ok(Response::new(json_body(serde_json::json!({
+        "id": "GHSA-test-unknown",
+        "summary": "Advisory without a severity",
+      }))))
+    }
     (_, "/echo_accept") => {
       let accept = req.headers().get("accept").map(|v| v.to_str().unwrap());
       let res =
@@ -1105,6 +1167,85 @@ async fn main_server(
           .unwrap(),
       );
     }
+    (&Method::GET, path) if path.starts_with("/cache_store_log/") => {
+      let prefix = path.strip_prefix("/cache_store_log").unwrap();
+      let lines = CACHE_STORE_LOG
+        .lock()
+        .iter()
+        .filter(|line| {
+          line
+            .split(' ')
+            .nth(1)
+            .is_some_and(|path| path.starts_with(prefix))
+        })
+        .map(|line| format!("{line}\n"))
+        .collect::<String>();
+      Ok(Response::new(string_body(&lines)))
+    }
+    (method, path) if path.starts_with("/cache_store/") => {
+      let method = method.clone();
+      let key = path.strip_prefix("/cache_store").unwrap().to_string();
+      let response = match method {
+        Method::PUT => {
+          let (_, body) = req.into_parts();
+          let data = body.collect().await?.to_bytes();
+          CACHE_STORE.lock().insert(key.clone(), data);
+          Response::new(empty_body())
+        }
+        Method::GET => match CACHE_STORE.lock().get(&key) {
+          Some(data) => {
+            Response::new(UnsyncBoxBody::new(Full::new(data.clone())))
+          }
+          None => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(empty_body())?,
+        },
+        _ => Response::builder()
+          .status(StatusCode::METHOD_NOT_ALLOWED)
+          .body(empty_body())?,
+      };
+      CACHE_STORE_LOG.lock().push(format!(
+        "{} {} {}",
+        method,
+        key,
+        response.status().as_u16()
+      ));
+      Ok(response)
+    }
+    (method, path)
+      if path.starts_with("/otel_collector/")
+        && path.ends_with("/v1/traces") =>
+    {
+      let name = path
+        .strip_prefix("/otel_collector/")
+        .unwrap()
+        .strip_suffix("/v1/traces")
+        .unwrap()
+        .to_string();
+      match *method {
+        Method::POST => {
+          let (_, body) = req.into_parts();
+          let data = body.collect().await?.to_bytes();
+          OTEL_COLLECTOR.lock().insert(name, data);
+          Ok(Response::new(string_body("{}")))
+        }
+        Method::GET => match OTEL_COLLECTOR.lock().get(&name) {
+          Some(data) => {
+            Ok(Response::new(UnsyncBoxBody::new(Full::new(data.clone()))))
+          }
+          None => Ok(
+            Response::builder()
+              .status(StatusCode::NOT_FOUND)
+              .body(empty_body())?,
+          ),
+        },
+        _ => Ok(
+          Response::builder()
+            .status(StatusCode::METHOD_NOT_ALLOWED)
+            .body(empty_body())?,
+        ),
+      }
+    }
     (&Method::GET, "/release-latest.txt") => {
       return Ok(
         Response::builder()